@@ -0,0 +1,76 @@
+//! SIMD kernels against their scalar baselines
+//!
+//! Run with `cargo bench --bench simd_voxel_ops`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use hearth_engine::simd_data::{AabbColumns, SimdLevel};
+use hearth_engine::simd_operations::{
+    matching_run_length_scalar, matching_run_length_with, push_aabb_column, simd_level,
+    sweep_aabb_scalar, sweep_aabb_with,
+};
+use hearth_engine::BlockId;
+
+/// Levels worth measuring on this machine
+fn levels() -> Vec<SimdLevel> {
+    [SimdLevel::Sse2, SimdLevel::Avx2]
+        .into_iter()
+        .filter(|&level| level <= simd_level())
+        .collect()
+}
+
+fn bench_voxel_rows(c: &mut Criterion) {
+    // One 32^3 chunk of stone with a single dirt voxel at the end of each row
+    let mut blocks = vec![BlockId::STONE; 32 * 32 * 32];
+    for row in blocks.chunks_mut(32) {
+        row[31] = BlockId::DIRT;
+    }
+
+    let mut group = c.benchmark_group("greedy_row_runs");
+    group.bench_function("scalar", |b| {
+        b.iter(|| {
+            blocks
+                .chunks(32)
+                .map(|row| matching_run_length_scalar(black_box(row), BlockId::STONE))
+                .sum::<usize>()
+        })
+    });
+    for level in levels() {
+        group.bench_function(BenchmarkId::new("simd", format!("{:?}", level)), |b| {
+            b.iter(|| {
+                blocks
+                    .chunks(32)
+                    .map(|row| matching_run_length_with(level, black_box(row), BlockId::STONE))
+                    .sum::<usize>()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_aabb_sweeps(c: &mut Criterion) {
+    // A 16x16 floor of unit blocks under a falling player-sized box
+    let mut columns = AabbColumns::default();
+    for x in 0..16 {
+        for z in 0..16 {
+            let (x, z) = (x as f32, z as f32);
+            push_aabb_column(&mut columns, [x, -1.0, z], [x + 1.0, 0.0, z + 1.0]);
+        }
+    }
+    let min = [7.6, 2.0, 7.6];
+    let max = [8.4, 3.8, 8.4];
+    let displacement = [0.5, -4.0, 0.25];
+
+    let mut group = c.benchmark_group("aabb_sweep");
+    group.bench_function("scalar", |b| {
+        b.iter(|| sweep_aabb_scalar(min, max, displacement, black_box(&columns)))
+    });
+    for level in levels() {
+        group.bench_function(BenchmarkId::new("simd", format!("{:?}", level)), |b| {
+            b.iter(|| sweep_aabb_with(level, min, max, displacement, black_box(&columns)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_voxel_rows, bench_aabb_sweeps);
+criterion_main!(benches);
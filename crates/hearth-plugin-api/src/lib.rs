@@ -0,0 +1,338 @@
+//! Hearth Plugin API - The stable C interface between the engine and native
+//! plugins
+//!
+//! Everything here is `#[repr(C)]` and uses only fixed-size integers, floats
+//! and raw pointers, so plugins may be built with a different compiler
+//! version (or in another language) than the engine. Any layout change bumps
+//! `PLUGIN_ABI_VERSION`.
+//!
+//! A plugin is a dynamic library exporting `hearth_plugin_entry`, which
+//! returns a `PluginDescriptor` that stays valid while the library is
+//! loaded (see `declare_plugin!`). Hooks receive a `PluginHostApi` whose
+//! functions are only valid for the duration of the hook call.
+//!
+//! Hooks are plain `extern "C"`: a panic must never cross into the engine,
+//! which may have been built with another compiler and could not catch it.
+//! `declare_plugin!` and `plugin_hook!` wrap each hook in `guard_hook`, so a
+//! panicking Rust hook returns `PLUGIN_ERROR` and the engine disables the
+//! plugin instead of aborting.
+//!
+//! This crate only depends on `std` and is released with the engine, so
+//! plugin crates can depend on it without pulling in the engine.
+
+use std::ffi::c_void;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Layout version of every type in this file
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Name of the symbol every plugin exports
+pub const PLUGIN_ENTRY_SYMBOL: &[u8] = b"hearth_plugin_entry\0";
+
+/// Hook and host function result: success
+pub const PLUGIN_OK: i32 = 0;
+/// Hook and host function result: failure
+pub const PLUGIN_ERROR: i32 = 1;
+
+/// `player_id` of events not caused by a player
+pub const PLUGIN_NO_PLAYER: u32 = u32::MAX;
+
+/// `PluginEvent::kind` values
+pub const PLUGIN_EVENT_BLOCK_BREAK: u32 = 0;
+pub const PLUGIN_EVENT_BLOCK_PLACE: u32 = 1;
+pub const PLUGIN_EVENT_SPAWN_PARTICLE: u32 = 2;
+pub const PLUGIN_EVENT_CUSTOM: u32 = 3;
+
+/// `PluginCommand::kind` values
+pub const PLUGIN_COMMAND_SAVE_WORLD: u32 = 0;
+pub const PLUGIN_COMMAND_LOAD_WORLD: u32 = 1;
+pub const PLUGIN_COMMAND_UPDATE_SETTING: u32 = 2;
+pub const PLUGIN_COMMAND_SHUTDOWN: u32 = 3;
+
+/// `PluginHostApi::log` levels
+pub const PLUGIN_LOG_DEBUG: u32 = 0;
+pub const PLUGIN_LOG_INFO: u32 = 1;
+pub const PLUGIN_LOG_WARN: u32 = 2;
+pub const PLUGIN_LOG_ERROR: u32 = 3;
+
+/// Borrowed UTF-8 string or byte slice (not NUL terminated)
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginStr {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+// Only ever points at immutable data: string literals of a loaded plugin,
+// or arguments borrowed for the duration of one call
+unsafe impl Send for PluginStr {}
+unsafe impl Sync for PluginStr {}
+
+/// Engine or plugin version
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PluginVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+}
+
+/// Gateway event queued by a plugin
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginEvent {
+    /// One of the `PLUGIN_EVENT_*` values
+    pub kind: u32,
+    /// `PLUGIN_NO_PLAYER` when not caused by a player
+    pub player_id: u32,
+    /// Block position (block events)
+    pub position: [i32; 3],
+    /// World-space position (particle events)
+    pub world_position: [f32; 3],
+    /// Block id (block events) or particle type
+    pub id: u16,
+    /// Packed block metadata (place events)
+    pub metadata: u8,
+    /// Particle count
+    pub count: u32,
+    /// Event type (custom events)
+    pub name: PluginStr,
+    /// Payload (custom events)
+    pub data: PluginStr,
+}
+
+/// Block registered by a plugin; booleans are 0 or 1
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginBlock {
+    pub id: u16,
+    pub name: PluginStr,
+    pub is_solid: u8,
+    pub is_transparent: u8,
+    pub is_liquid: u8,
+    pub can_interact: u8,
+    pub light_emission: u8,
+    pub hardness: f32,
+    pub friction: f32,
+    pub restitution: f32,
+    pub speed_multiplier: f32,
+}
+
+/// Engine command issued by a plugin
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginCommand {
+    /// One of the `PLUGIN_COMMAND_*` values
+    pub kind: u32,
+    /// World path, or setting name
+    pub name: PluginStr,
+    /// Setting value
+    pub value: PluginStr,
+}
+
+/// Functions a plugin may call during a hook. `context` must be passed
+/// back unchanged.
+#[repr(C)]
+pub struct PluginHostApi {
+    pub abi_version: u32,
+    pub engine_version: PluginVersion,
+    pub context: *mut c_void,
+    pub queue_event: extern "C" fn(context: *mut c_void, event: *const PluginEvent) -> i32,
+    pub register_block: extern "C" fn(context: *mut c_void, block: *const PluginBlock) -> i32,
+    pub queue_command: extern "C" fn(context: *mut c_void, command: *const PluginCommand) -> i32,
+    pub log: extern "C" fn(context: *mut c_void, level: u32, message: PluginStr),
+}
+
+/// `on_load` and `on_unload`
+pub type PluginHook = extern "C" fn(host: *const PluginHostApi) -> i32;
+
+/// `on_world_start`, with the world seed
+pub type PluginWorldStartHook = extern "C" fn(host: *const PluginHostApi, seed: u32) -> i32;
+
+/// `on_tick`, with the world tick and the frame time (seconds)
+pub type PluginTickHook =
+    extern "C" fn(host: *const PluginHostApi, tick: u64, delta_seconds: f32) -> i32;
+
+/// Signature of `hearth_plugin_entry`
+pub type PluginEntryFn = unsafe extern "C" fn() -> *const PluginDescriptor;
+
+/// What a plugin exports. Missing hooks are null.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginDescriptor {
+    /// Must equal the engine's `PLUGIN_ABI_VERSION`
+    pub abi_version: u32,
+    pub name: PluginStr,
+    pub version: PluginVersion,
+    /// `ENGINE_PLUGIN_VERSION` the plugin was built against
+    pub engine_version: PluginVersion,
+    pub on_load: Option<PluginHook>,
+    pub on_world_start: Option<PluginWorldStartHook>,
+    pub on_tick: Option<PluginTickHook>,
+    pub on_unload: Option<PluginHook>,
+}
+
+const fn parse_version_part(text: &str) -> u16 {
+    let bytes = text.as_bytes();
+    let mut value = 0u16;
+    let mut index = 0;
+    while index < bytes.len() {
+        value = value * 10 + (bytes[index] - b'0') as u16;
+        index += 1;
+    }
+    value
+}
+
+/// Engine version this API belongs to; the crate is versioned with the
+/// engine
+pub const ENGINE_PLUGIN_VERSION: PluginVersion = PluginVersion {
+    major: parse_version_part(env!("CARGO_PKG_VERSION_MAJOR")),
+    minor: parse_version_part(env!("CARGO_PKG_VERSION_MINOR")),
+    patch: parse_version_part(env!("CARGO_PKG_VERSION_PATCH")),
+};
+
+/// Borrow a string for the ABI
+pub const fn plugin_str(text: &str) -> PluginStr {
+    PluginStr {
+        ptr: text.as_ptr(),
+        len: text.len(),
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+/// Run a hook body on the plugin side, turning a panic into `PLUGIN_ERROR`
+/// (logged through the host) so it never unwinds into the engine. A null
+/// `host` fails without running `call`.
+///
+/// # Safety
+/// `host` must be null or the host API the engine passed to the running
+/// hook.
+pub unsafe fn guard_hook(
+    host: *const PluginHostApi,
+    call: impl FnOnce(&PluginHostApi) -> i32,
+) -> i32 {
+    let Some(host) = host.as_ref() else {
+        return PLUGIN_ERROR;
+    };
+    match catch_unwind(AssertUnwindSafe(|| call(host))) {
+        Ok(code) => code,
+        Err(payload) => {
+            let message = format!("hook panicked: {}", panic_message(payload.as_ref()));
+            (host.log)(host.context, PLUGIN_LOG_ERROR, plugin_str(&message));
+            PLUGIN_ERROR
+        }
+    }
+}
+
+/// `extern "C"` hook calling a Rust hook function under `guard_hook`. The
+/// function takes `&PluginHostApi` followed by the hook's other arguments.
+///
+/// ```ignore
+/// fn on_tick(host: &PluginHostApi, tick: u64, delta_seconds: f32) -> i32 { PLUGIN_OK }
+/// let hook: PluginTickHook = plugin_hook!(on_tick, on_tick);
+/// ```
+#[macro_export]
+macro_rules! plugin_hook {
+    (on_load, $hook:path) => {
+        $crate::plugin_hook!(@lifecycle $hook)
+    };
+    (on_unload, $hook:path) => {
+        $crate::plugin_hook!(@lifecycle $hook)
+    };
+    (on_world_start, $hook:path) => {{
+        extern "C" fn guarded(host: *const $crate::PluginHostApi, seed: u32) -> i32 {
+            // SAFETY: the engine passes its host API for this call
+            unsafe { $crate::guard_hook(host, |host| $hook(host, seed)) }
+        }
+        guarded as $crate::PluginWorldStartHook
+    }};
+    (on_tick, $hook:path) => {{
+        extern "C" fn guarded(
+            host: *const $crate::PluginHostApi,
+            tick: u64,
+            delta_seconds: f32,
+        ) -> i32 {
+            // SAFETY: the engine passes its host API for this call
+            unsafe { $crate::guard_hook(host, |host| $hook(host, tick, delta_seconds)) }
+        }
+        guarded as $crate::PluginTickHook
+    }};
+    (@lifecycle $hook:path) => {{
+        extern "C" fn guarded(host: *const $crate::PluginHostApi) -> i32 {
+            // SAFETY: the engine passes its host API for this call
+            unsafe { $crate::guard_hook(host, |host| $hook(host)) }
+        }
+        guarded as $crate::PluginHook
+    }};
+}
+
+/// Descriptor for this ABI and engine version with guarded hooks; hooks
+/// left out are null
+///
+/// ```ignore
+/// plugin_descriptor! {
+///     name: "glow",
+///     version: PluginVersion { major: 1, minor: 0, patch: 0 },
+///     on_load: register_blocks,
+///     on_tick: tick,
+/// }
+/// ```
+#[macro_export]
+macro_rules! plugin_descriptor {
+    (
+        name: $name:expr,
+        version: $version:expr
+        $(, on_load: $on_load:path)?
+        $(, on_world_start: $on_world_start:path)?
+        $(, on_tick: $on_tick:path)?
+        $(, on_unload: $on_unload:path)?
+        $(,)?
+    ) => {
+        $crate::PluginDescriptor {
+            abi_version: $crate::PLUGIN_ABI_VERSION,
+            name: $crate::plugin_str($name),
+            version: $version,
+            engine_version: $crate::ENGINE_PLUGIN_VERSION,
+            on_load: $crate::plugin_descriptor!(@hook on_load $($on_load)?),
+            on_world_start: $crate::plugin_descriptor!(@hook on_world_start $($on_world_start)?),
+            on_tick: $crate::plugin_descriptor!(@hook on_tick $($on_tick)?),
+            on_unload: $crate::plugin_descriptor!(@hook on_unload $($on_unload)?),
+        }
+    };
+    (@hook $kind:ident $hook:path) => {
+        Some($crate::plugin_hook!($kind, $hook))
+    };
+    (@hook $kind:ident) => {
+        None
+    };
+}
+
+/// Export `hearth_plugin_entry` returning a static `plugin_descriptor!`
+///
+/// ```ignore
+/// declare_plugin! {
+///     name: "glow",
+///     version: PluginVersion { major: 1, minor: 0, patch: 0 },
+///     on_tick: tick,
+/// }
+/// ```
+#[macro_export]
+macro_rules! declare_plugin {
+    ($($descriptor:tt)*) => {
+        #[no_mangle]
+        pub extern "C" fn hearth_plugin_entry() -> *const $crate::PluginDescriptor {
+            static DESCRIPTOR: $crate::PluginDescriptor =
+                $crate::plugin_descriptor!($($descriptor)*);
+            &DESCRIPTOR
+        }
+    };
+}
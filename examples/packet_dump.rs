@@ -0,0 +1,40 @@
+//! Summarize a network packet capture
+//!
+//! Prints packet type frequencies and sizes for a file recorded with
+//! `network::start_packet_capture`. Pass `--ticks` to also list the number
+//! of packets captured on every tick.
+//!
+//! Usage: cargo run --example packet_dump -- <capture.hpcap> [--ticks]
+
+use hearth_engine::network::{
+    format_capture_summary, load_packet_capture, packets_per_tick, summarize_capture,
+};
+use std::path::PathBuf;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(path) = args.iter().find(|arg| !arg.starts_with("--")) else {
+        eprintln!("Usage: packet_dump <capture.hpcap> [--ticks]");
+        std::process::exit(2);
+    };
+    let show_ticks = args.iter().any(|arg| arg == "--ticks");
+
+    let capture = match load_packet_capture(&PathBuf::from(path)) {
+        Ok(capture) => capture,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    println!("Capture: {}", path);
+    print!("{}", format_capture_summary(&summarize_capture(&capture)));
+
+    if show_ticks {
+        println!();
+        println!("{:>10} {:>8}", "tick", "packets");
+        for (tick, count) in packets_per_tick(&capture) {
+            println!("{:>10} {:>8}", tick, count);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Activation Range Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Range checks, hysteresis and stats live in activation_range_operations.rs
+
+use crate::world::ChunkPos;
+use std::collections::HashMap;
+
+/// Distances in chunks (Chebyshev, to the nearest player) within which each
+/// system runs. Independent of render distance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActivationRangeConfig {
+    /// Block random ticks (crop growth, grass spread, ...)
+    pub random_tick_distance: u32,
+    /// Entity AI and pathfinding
+    pub entity_ai_distance: u32,
+    /// Physics simulation of entities and chunks
+    pub physics_distance: u32,
+    /// Extra chunks something already active stays active for, so a player
+    /// walking along a range boundary does not toggle it every tick
+    pub hysteresis: u32,
+}
+
+/// Which systems are active for one chunk or entity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActivationFlags {
+    pub random_ticks: bool,
+    pub entity_ai: bool,
+    pub physics: bool,
+}
+
+/// Counters for one range
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RangeStats {
+    pub active_chunks: u32,
+    pub active_entities: u32,
+    /// Chunks and entities that became active since creation
+    pub activations: u64,
+    /// Chunks and entities that became inactive since creation
+    pub deactivations: u64,
+}
+
+/// Per-range counters for tuning the distances
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActivationRangeStats {
+    pub random_ticks: RangeStats,
+    pub entity_ai: RangeStats,
+    pub physics: RangeStats,
+    /// Entities with neither AI nor physics this tick
+    pub frozen_entities: u32,
+}
+
+/// Activation state of every loaded chunk and entity
+#[derive(Debug, Clone)]
+pub struct ActivationRangeData {
+    pub config: ActivationRangeConfig,
+    pub chunks: HashMap<ChunkPos, ActivationFlags>,
+    /// Indexed like `PhysicsBuffers`
+    pub entities: Vec<ActivationFlags>,
+    pub stats: ActivationRangeStats,
+}
@@ -0,0 +1,303 @@
+//! Activation Range Operations - Pure DOP functions
+//!
+//! Per world tick: `update_chunk_activation` and `update_entity_activation`
+//! decide by distance to the nearest player whether block random ticks,
+//! entity AI and physics run for each loaded chunk and entity. Something
+//! becomes active inside a range and only goes inactive once it is
+//! `hysteresis` chunks past it. Entities outside both the AI and physics
+//! ranges are frozen: systems skip them without touching their state, so
+//! they pick up where they left off when a player returns.
+
+use crate::activation_range_data::{
+    ActivationFlags, ActivationRangeConfig, ActivationRangeData, ActivationRangeStats, RangeStats,
+};
+use crate::constants::activation_range::*;
+use crate::engine_buffers::PhysicsBuffers;
+use crate::simulation_scaling_operations::chunk_distance;
+use crate::world::ChunkPos;
+use std::collections::{HashMap, HashSet};
+
+/// Default distances from `constants::activation_range`
+pub fn default_activation_range_config() -> ActivationRangeConfig {
+    ActivationRangeConfig {
+        random_tick_distance: RANDOM_TICK_DISTANCE,
+        entity_ai_distance: ENTITY_AI_DISTANCE,
+        physics_distance: PHYSICS_DISTANCE,
+        hysteresis: HYSTERESIS,
+    }
+}
+
+/// Create activation state with nothing loaded
+pub fn create_activation_ranges(config: ActivationRangeConfig) -> ActivationRangeData {
+    ActivationRangeData {
+        config,
+        chunks: HashMap::new(),
+        entities: Vec::new(),
+        stats: ActivationRangeStats::default(),
+    }
+}
+
+/// Distance in chunks to the nearest player, `None` with no players
+pub fn nearest_player_distance(chunk_pos: ChunkPos, player_chunks: &[ChunkPos]) -> Option<u32> {
+    player_chunks
+        .iter()
+        .map(|&player| chunk_distance(chunk_pos, player))
+        .min()
+}
+
+fn range_active(distance: Option<u32>, range: u32, hysteresis: u32, was_active: bool) -> bool {
+    match distance {
+        Some(distance) if was_active => distance <= range.saturating_add(hysteresis),
+        Some(distance) => distance <= range,
+        None => false,
+    }
+}
+
+/// Flags at `distance` from the nearest player, given the previous flags
+pub fn activation_flags(
+    config: &ActivationRangeConfig,
+    distance: Option<u32>,
+    previous: ActivationFlags,
+) -> ActivationFlags {
+    let hysteresis = config.hysteresis;
+    ActivationFlags {
+        random_ticks: range_active(
+            distance,
+            config.random_tick_distance,
+            hysteresis,
+            previous.random_ticks,
+        ),
+        entity_ai: range_active(
+            distance,
+            config.entity_ai_distance,
+            hysteresis,
+            previous.entity_ai,
+        ),
+        physics: range_active(
+            distance,
+            config.physics_distance,
+            hysteresis,
+            previous.physics,
+        ),
+    }
+}
+
+/// Count one range's state and transition for a chunk or entity
+fn record_range(stats: &mut RangeStats, before: bool, after: bool, is_chunk: bool) {
+    if after {
+        if is_chunk {
+            stats.active_chunks += 1;
+        } else {
+            stats.active_entities += 1;
+        }
+    }
+    match (before, after) {
+        (false, true) => stats.activations += 1,
+        (true, false) => stats.deactivations += 1,
+        _ => {}
+    }
+}
+
+fn record_flags(
+    stats: &mut ActivationRangeStats,
+    before: ActivationFlags,
+    after: ActivationFlags,
+    is_chunk: bool,
+) {
+    record_range(
+        &mut stats.random_ticks,
+        before.random_ticks,
+        after.random_ticks,
+        is_chunk,
+    );
+    record_range(
+        &mut stats.entity_ai,
+        before.entity_ai,
+        after.entity_ai,
+        is_chunk,
+    );
+    record_range(&mut stats.physics, before.physics, after.physics, is_chunk);
+}
+
+/// Re-evaluate every loaded chunk. Chunks missing from `loaded_chunks` are
+/// forgotten.
+pub fn update_chunk_activation(
+    data: &mut ActivationRangeData,
+    loaded_chunks: &[ChunkPos],
+    player_chunks: &[ChunkPos],
+) {
+    let loaded: HashSet<ChunkPos> = loaded_chunks.iter().copied().collect();
+    data.chunks.retain(|pos, _| loaded.contains(pos));
+
+    let config = data.config;
+    let stats = &mut data.stats;
+    for range in [
+        &mut stats.random_ticks,
+        &mut stats.entity_ai,
+        &mut stats.physics,
+    ] {
+        range.active_chunks = 0;
+    }
+
+    for &chunk_pos in loaded_chunks {
+        let flags = data.chunks.entry(chunk_pos).or_default();
+        let distance = nearest_player_distance(chunk_pos, player_chunks);
+        let next = activation_flags(&config, distance, *flags);
+        record_flags(stats, *flags, next, true);
+        *flags = next;
+    }
+}
+
+/// Re-evaluate every entity. `entity_chunks[i]` is the chunk entity `i`
+/// (indexed like `PhysicsBuffers`) is in.
+pub fn update_entity_activation(
+    data: &mut ActivationRangeData,
+    entity_chunks: &[ChunkPos],
+    player_chunks: &[ChunkPos],
+) {
+    data.entities
+        .resize(entity_chunks.len(), ActivationFlags::default());
+
+    let config = data.config;
+    let stats = &mut data.stats;
+    for range in [
+        &mut stats.random_ticks,
+        &mut stats.entity_ai,
+        &mut stats.physics,
+    ] {
+        range.active_entities = 0;
+    }
+    stats.frozen_entities = 0;
+
+    for (flags, &chunk_pos) in data.entities.iter_mut().zip(entity_chunks) {
+        let distance = nearest_player_distance(chunk_pos, player_chunks);
+        let next = activation_flags(&config, distance, *flags);
+        record_flags(stats, *flags, next, false);
+        if !next.entity_ai && !next.physics {
+            stats.frozen_entities += 1;
+        }
+        *flags = next;
+    }
+}
+
+/// Chunk of each physics entity, for `update_entity_activation`
+pub fn entity_chunks_from_physics(physics: &PhysicsBuffers, chunk_size: u32) -> Vec<ChunkPos> {
+    let size = chunk_size.max(1) as f32;
+    physics
+        .positions
+        .iter()
+        .map(|position| {
+            ChunkPos::new(
+                (position[0] / size).floor() as i32,
+                (position[1] / size).floor() as i32,
+                (position[2] / size).floor() as i32,
+            )
+        })
+        .collect()
+}
+
+/// Flags of a chunk; unknown chunks are inactive
+pub fn chunk_activation(data: &ActivationRangeData, chunk_pos: ChunkPos) -> ActivationFlags {
+    data.chunks.get(&chunk_pos).copied().unwrap_or_default()
+}
+
+/// Flags of an entity; unknown entities are inactive
+pub fn entity_activation(data: &ActivationRangeData, index: usize) -> ActivationFlags {
+    data.entities.get(index).copied().unwrap_or_default()
+}
+
+/// True when neither AI nor physics should touch the entity
+pub fn is_entity_frozen(data: &ActivationRangeData, index: usize) -> bool {
+    let flags = entity_activation(data, index);
+    !flags.entity_ai && !flags.physics
+}
+
+/// Chunks that receive block random ticks this tick
+pub fn random_tick_chunks(data: &ActivationRangeData) -> Vec<ChunkPos> {
+    data.chunks
+        .iter()
+        .filter(|(_, flags)| flags.random_ticks)
+        .map(|(&chunk_pos, _)| chunk_pos)
+        .collect()
+}
+
+/// Indices of entities whose AI runs this tick
+pub fn ai_active_entities(data: &ActivationRangeData) -> Vec<usize> {
+    data.entities
+        .iter()
+        .enumerate()
+        .filter(|(_, flags)| flags.entity_ai)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Indices of entities that simulate physics this tick
+pub fn physics_active_entities(data: &ActivationRangeData) -> Vec<usize> {
+    data.entities
+        .iter()
+        .enumerate()
+        .filter(|(_, flags)| flags.physics)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_buffers::create_engine_buffers;
+
+    fn config() -> ActivationRangeConfig {
+        ActivationRangeConfig {
+            random_tick_distance: 3,
+            entity_ai_distance: 2,
+            physics_distance: 4,
+            hysteresis: 1,
+        }
+    }
+
+    #[test]
+    fn test_ranges_use_hysteresis_at_boundaries() {
+        let mut data = create_activation_ranges(config());
+        let chunk = ChunkPos::new(0, 0, 0);
+        let player_at = |x| [ChunkPos::new(x, 0, 0)];
+
+        update_chunk_activation(&mut data, &[chunk], &player_at(3));
+        assert!(chunk_activation(&data, chunk).random_ticks);
+
+        // One chunk past the range stays active, two chunks past turns off
+        update_chunk_activation(&mut data, &[chunk], &player_at(4));
+        assert!(chunk_activation(&data, chunk).random_ticks);
+        update_chunk_activation(&mut data, &[chunk], &player_at(3));
+        update_chunk_activation(&mut data, &[chunk], &player_at(4));
+        assert_eq!(data.stats.random_ticks.activations, 1);
+        update_chunk_activation(&mut data, &[chunk], &player_at(5));
+        assert!(!chunk_activation(&data, chunk).random_ticks);
+        assert_eq!(data.stats.random_ticks.deactivations, 1);
+
+        // Coming back does not activate until inside the range again
+        update_chunk_activation(&mut data, &[chunk], &player_at(4));
+        assert!(!chunk_activation(&data, chunk).random_ticks);
+        assert!(random_tick_chunks(&data).is_empty());
+    }
+
+    #[test]
+    fn test_far_entities_freeze() {
+        let mut data = create_activation_ranges(config());
+        let mut buffers = create_engine_buffers();
+        buffers.physics.positions = vec![[10.0, 0.0, 0.0], [95.0, 0.0, 0.0], [400.0, 0.0, 0.0]];
+        let chunks = entity_chunks_from_physics(&buffers.physics, 32);
+        assert_eq!(chunks[1], ChunkPos::new(2, 0, 0));
+
+        update_entity_activation(&mut data, &chunks, &[ChunkPos::new(0, 0, 0)]);
+        assert_eq!(ai_active_entities(&data), vec![0, 1]);
+        assert_eq!(physics_active_entities(&data), vec![0, 1]);
+        assert!(is_entity_frozen(&data, 2));
+        assert_eq!(data.stats.frozen_entities, 1);
+        assert_eq!(data.stats.entity_ai.active_entities, 2);
+
+        // No players: everything freezes
+        update_entity_activation(&mut data, &chunks, &[]);
+        assert_eq!(data.stats.frozen_entities, 3);
+        assert_eq!(data.stats.physics.deactivations, 2);
+    }
+}
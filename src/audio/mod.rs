@@ -0,0 +1,25 @@
+/// Audio Module - Data-Oriented Programming (DOP) style
+///
+/// This module follows pure DOP principles:
+/// - propagation_data.rs: Pure data structures with NO methods
+/// - propagation_operations.rs: Pure functions that operate on data
+///
+/// The engine does not play sound itself; the game's mixer applies the
+/// gain and low-pass cutoff computed here for each source.
+pub mod propagation_data;
+pub mod propagation_operations;
+
+// Re-export data structures
+pub use propagation_data::{
+    AudioBlockSampler, AudioOcclusion, AudioPosition, AudioPropagationConfig, AudioPropagationData,
+    AudioPropagationStats, AudioSourceId, CachedAudioOcclusion,
+};
+
+// Re-export all operations
+pub use propagation_operations::{
+    create_audio_propagation, default_audio_propagation_config,
+    invalidate_audio_occlusion_for_events, invalidate_audio_occlusion_in_chunks,
+    invalidate_audio_occlusion_near, occlusion_response, query_audio_occlusion,
+    remove_audio_source, set_audio_source, set_block_absorption, trace_audio_path,
+    update_audio_propagation,
+};
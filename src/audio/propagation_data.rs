@@ -0,0 +1,88 @@
+//! Audio Propagation Data - Pure DOP
+//!
+//! Occlusion between sound sources and the listener. A voxel ray is traced
+//! from each source to the listener; the blocks it passes through, weighted
+//! by how much sound each absorbs, give the blocked volume, which maps to
+//! a gain and a low-pass cutoff the game's mixer applies. Results are
+//! cached per source and dropped when a block near the path changes.
+//!
+//! NO METHODS - just data.
+
+use crate::world::core::{BlockId, VoxelPos};
+use std::collections::HashMap;
+
+/// Game-assigned id of a playing sound source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AudioSourceId(pub u32);
+
+/// World position of a source or the listener (voxels)
+pub type AudioPosition = [f32; 3];
+
+/// Block lookup used while tracing (e.g. `world_operations::get_block`)
+pub type AudioBlockSampler<'a> = dyn Fn(VoxelPos) -> BlockId + 'a;
+
+/// Occlusion response tuning
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioPropagationConfig {
+    /// Sources farther than this are not traced (voxels)
+    pub max_trace_distance: f32,
+    /// Attenuation per fully absorbing voxel (decibels)
+    pub attenuation_db_per_voxel: f32,
+    /// Gain never drops below this, so sounds behind thick walls stay faintly audible
+    pub min_gain: f32,
+    /// Cutoff of an unobstructed path (Hz)
+    pub open_cutoff_hz: f32,
+    /// Lowest cutoff, however much is in the way (Hz)
+    pub min_cutoff_hz: f32,
+    /// Cutoff multiplier per fully absorbing voxel
+    pub cutoff_falloff_per_voxel: f32,
+    /// Source or listener movement that invalidates a cached result (voxels)
+    pub cache_move_tolerance: f32,
+    /// Block changes within this distance of a path invalidate it (voxels)
+    pub invalidation_radius: f32,
+    /// Absorption of blocks without an entry (0 = transparent, 1 = solid)
+    pub default_absorption: f32,
+}
+
+/// Occlusion of one source as heard from the listener
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioOcclusion {
+    /// Non-air voxels between source and listener
+    pub blocked_voxels: u32,
+    /// Blocked voxels weighted by absorption
+    pub blocked_volume: f32,
+    /// Linear gain to apply (0.0 - 1.0)
+    pub gain: f32,
+    /// Low-pass cutoff to apply (Hz)
+    pub low_pass_cutoff_hz: f32,
+}
+
+/// Cached result and the path it was traced along
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CachedAudioOcclusion {
+    pub source_position: [f32; 3],
+    pub listener_position: [f32; 3],
+    pub occlusion: AudioOcclusion,
+}
+
+/// Cache counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AudioPropagationStats {
+    pub cache_hits: u64,
+    pub traces: u64,
+    pub invalidations: u64,
+}
+
+/// Occlusion state of all sources
+#[derive(Debug, Clone)]
+pub struct AudioPropagationData {
+    pub config: AudioPropagationConfig,
+    /// Sound absorption per block (0.0 - 1.0); air is always 0
+    pub block_absorption: HashMap<BlockId, f32>,
+    pub cache: HashMap<AudioSourceId, CachedAudioOcclusion>,
+    /// Positions of the playing sources the audio update traces
+    pub sources: HashMap<AudioSourceId, AudioPosition>,
+    /// Gain and cutoff of each source from the last audio update
+    pub occlusion: HashMap<AudioSourceId, AudioOcclusion>,
+    pub stats: AudioPropagationStats,
+}
@@ -0,0 +1,426 @@
+//! Audio Propagation Operations - Pure DOP functions
+//!
+//! Register playing sources with `set_audio_source`; once per frame
+//! `update_audio_propagation` queries each one's occlusion from the
+//! listener and the mixer applies the gain and cutoff left in
+//! `occlusion`. Feed block changes to `invalidate_audio_occlusion_near`
+//! (or the frame's events to `invalidate_audio_occlusion_for_events`) and
+//! call `remove_audio_source` when a sound stops.
+
+use super::propagation_data::{
+    AudioBlockSampler, AudioOcclusion, AudioPosition, AudioPropagationConfig, AudioPropagationData,
+    AudioPropagationStats, AudioSourceId, CachedAudioOcclusion,
+};
+use crate::constants::audio::*;
+use crate::game::GameEvent;
+use crate::world::core::{BlockId, ChunkPos, VoxelPos};
+use std::collections::HashMap;
+
+pub fn default_audio_propagation_config() -> AudioPropagationConfig {
+    AudioPropagationConfig {
+        max_trace_distance: MAX_OCCLUSION_TRACE_DISTANCE,
+        attenuation_db_per_voxel: OCCLUSION_DB_PER_VOXEL,
+        min_gain: MIN_OCCLUDED_GAIN,
+        open_cutoff_hz: OPEN_LOW_PASS_CUTOFF_HZ,
+        min_cutoff_hz: MIN_LOW_PASS_CUTOFF_HZ,
+        cutoff_falloff_per_voxel: LOW_PASS_FALLOFF_PER_VOXEL,
+        cache_move_tolerance: OCCLUSION_CACHE_MOVE_TOLERANCE,
+        invalidation_radius: OCCLUSION_INVALIDATION_RADIUS,
+        default_absorption: 1.0,
+    }
+}
+
+pub fn create_audio_propagation(config: AudioPropagationConfig) -> AudioPropagationData {
+    AudioPropagationData {
+        config,
+        block_absorption: HashMap::new(),
+        cache: HashMap::new(),
+        sources: HashMap::new(),
+        occlusion: HashMap::new(),
+        stats: AudioPropagationStats::default(),
+    }
+}
+
+/// How much sound `block` absorbs (0 = none, e.g. leaves; 1 = solid
+/// stone). Cached results are dropped since they used the old value.
+pub fn set_block_absorption(data: &mut AudioPropagationData, block: BlockId, absorption: f32) {
+    data.block_absorption
+        .insert(block, absorption.clamp(0.0, 1.0));
+    data.stats.invalidations += data.cache.len() as u64;
+    data.cache.clear();
+}
+
+fn block_absorption(data: &AudioPropagationData, block: BlockId) -> f32 {
+    if block == BlockId::AIR {
+        return 0.0;
+    }
+    data.block_absorption
+        .get(&block)
+        .copied()
+        .unwrap_or(data.config.default_absorption)
+}
+
+fn voxel_of(point: [f32; 3]) -> VoxelPos {
+    VoxelPos::new(
+        point[0].floor() as i32,
+        point[1].floor() as i32,
+        point[2].floor() as i32,
+    )
+}
+
+/// Blocked voxels and absorption-weighted volume on the straight path
+/// between two points. The voxels holding the endpoints are skipped, so
+/// a source embedded in a block (a jukebox, a furnace) is not muffled by
+/// itself.
+pub fn trace_audio_path(
+    data: &AudioPropagationData,
+    from: [f32; 3],
+    to: [f32; 3],
+    block_at: &AudioBlockSampler,
+) -> (u32, f32) {
+    let start = voxel_of(from);
+    let end = voxel_of(to);
+    let delta = [to[0] - from[0], to[1] - from[1], to[2] - from[2]];
+
+    // Amanatides-Woo voxel traversal
+    let mut voxel = [start.x, start.y, start.z];
+    let target = [end.x, end.y, end.z];
+    let mut step = [0i32; 3];
+    let mut t_max = [f32::INFINITY; 3];
+    let mut t_delta = [f32::INFINITY; 3];
+    for axis in 0..3 {
+        if delta[axis] > 0.0 {
+            step[axis] = 1;
+            t_max[axis] = ((voxel[axis] + 1) as f32 - from[axis]) / delta[axis];
+            t_delta[axis] = 1.0 / delta[axis];
+        } else if delta[axis] < 0.0 {
+            step[axis] = -1;
+            t_max[axis] = (voxel[axis] as f32 - from[axis]) / delta[axis];
+            t_delta[axis] = -1.0 / delta[axis];
+        }
+    }
+
+    let mut blocked_voxels = 0;
+    let mut blocked_volume = 0.0;
+    let max_steps = target
+        .iter()
+        .zip(voxel.iter())
+        .map(|(t, v)| t.abs_diff(*v))
+        .sum::<u32>();
+    for _ in 0..max_steps {
+        let axis = if t_max[0] < t_max[1] {
+            if t_max[0] < t_max[2] {
+                0
+            } else {
+                2
+            }
+        } else if t_max[1] < t_max[2] {
+            1
+        } else {
+            2
+        };
+        voxel[axis] += step[axis];
+        t_max[axis] += t_delta[axis];
+        if voxel == target {
+            break;
+        }
+
+        let absorption =
+            block_absorption(data, block_at(VoxelPos::new(voxel[0], voxel[1], voxel[2])));
+        if absorption > 0.0 {
+            blocked_voxels += 1;
+            blocked_volume += absorption;
+        }
+    }
+    (blocked_voxels, blocked_volume)
+}
+
+/// Gain and low-pass cutoff for an absorption-weighted blocked volume
+pub fn occlusion_response(
+    config: &AudioPropagationConfig,
+    blocked_voxels: u32,
+    blocked_volume: f32,
+) -> AudioOcclusion {
+    let gain = 10f32
+        .powf(-config.attenuation_db_per_voxel * blocked_volume / 20.0)
+        .max(config.min_gain);
+    let cutoff = (config.open_cutoff_hz * config.cutoff_falloff_per_voxel.powf(blocked_volume))
+        .max(config.min_cutoff_hz);
+    AudioOcclusion {
+        blocked_voxels,
+        blocked_volume,
+        gain,
+        low_pass_cutoff_hz: cutoff,
+    }
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+/// Occlusion of `source` heard at `listener`; reuses the cached result
+/// while neither end has moved more than `cache_move_tolerance`. Sources
+/// beyond `max_trace_distance` are treated as unobstructed.
+pub fn query_audio_occlusion(
+    data: &mut AudioPropagationData,
+    source: AudioSourceId,
+    source_position: [f32; 3],
+    listener_position: [f32; 3],
+    block_at: &AudioBlockSampler,
+) -> AudioOcclusion {
+    let tolerance = data.config.cache_move_tolerance;
+    if let Some(cached) = data.cache.get(&source) {
+        if distance(cached.source_position, source_position) <= tolerance
+            && distance(cached.listener_position, listener_position) <= tolerance
+        {
+            data.stats.cache_hits += 1;
+            return cached.occlusion;
+        }
+    }
+
+    let (blocked_voxels, blocked_volume) =
+        if distance(source_position, listener_position) > data.config.max_trace_distance {
+            (0, 0.0)
+        } else {
+            data.stats.traces += 1;
+            trace_audio_path(data, source_position, listener_position, block_at)
+        };
+    let occlusion = occlusion_response(&data.config, blocked_voxels, blocked_volume);
+    data.cache.insert(
+        source,
+        CachedAudioOcclusion {
+            source_position,
+            listener_position,
+            occlusion,
+        },
+    );
+    occlusion
+}
+
+/// Distance from `point` to the segment `a`-`b`
+fn distance_to_segment(point: [f32; 3], a: [f32; 3], b: [f32; 3]) -> f32 {
+    let ab = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let ap = [point[0] - a[0], point[1] - a[1], point[2] - a[2]];
+    let length_sq = ab[0] * ab[0] + ab[1] * ab[1] + ab[2] * ab[2];
+    let t = if length_sq > 0.0 {
+        ((ap[0] * ab[0] + ap[1] * ab[1] + ap[2] * ab[2]) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    distance(
+        point,
+        [a[0] + ab[0] * t, a[1] + ab[1] * t, a[2] + ab[2] * t],
+    )
+}
+
+/// Drop cached results whose path passes near a changed block; returns
+/// how many were dropped
+pub fn invalidate_audio_occlusion_near(data: &mut AudioPropagationData, block: VoxelPos) -> usize {
+    let center = [
+        block.x as f32 + 0.5,
+        block.y as f32 + 0.5,
+        block.z as f32 + 0.5,
+    ];
+    let radius = data.config.invalidation_radius;
+    let before = data.cache.len();
+    data.cache.retain(|_, cached| {
+        distance_to_segment(center, cached.source_position, cached.listener_position) > radius
+    });
+    let dropped = before - data.cache.len();
+    data.stats.invalidations += dropped as u64;
+    dropped
+}
+
+/// Drop cached results whose path crosses a chunk that just loaded; it was
+/// traced as open air before. Returns how many were dropped.
+pub fn invalidate_audio_occlusion_in_chunks(
+    data: &mut AudioPropagationData,
+    chunks: &[ChunkPos],
+    chunk_size: u32,
+) -> usize {
+    let half = chunk_size as f32 * 0.5;
+    // Radius of the sphere around a chunk
+    let radius = half * 3.0f32.sqrt();
+    let before = data.cache.len();
+    data.cache.retain(|_, cached| {
+        chunks.iter().all(|chunk| {
+            let center = [
+                chunk.x as f32 * chunk_size as f32 + half,
+                chunk.y as f32 * chunk_size as f32 + half,
+                chunk.z as f32 * chunk_size as f32 + half,
+            ];
+            distance_to_segment(center, cached.source_position, cached.listener_position) > radius
+        })
+    });
+    let dropped = before - data.cache.len();
+    data.stats.invalidations += dropped as u64;
+    dropped
+}
+
+/// Invalidate around every block broken or placed in `events`
+pub fn invalidate_audio_occlusion_for_events(
+    data: &mut AudioPropagationData,
+    events: &[GameEvent],
+) -> usize {
+    events
+        .iter()
+        .map(|event| match event {
+            GameEvent::BlockBreak { position, .. } | GameEvent::BlockPlace { position, .. } => {
+                invalidate_audio_occlusion_near(data, *position)
+            }
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Start or move a playing source
+pub fn set_audio_source(
+    data: &mut AudioPropagationData,
+    source: AudioSourceId,
+    position: AudioPosition,
+) {
+    data.sources.insert(source, position);
+}
+
+/// Forget a stopped source
+pub fn remove_audio_source(data: &mut AudioPropagationData, source: AudioSourceId) {
+    data.cache.remove(&source);
+    data.sources.remove(&source);
+    data.occlusion.remove(&source);
+}
+
+/// Occlusion of every playing source heard at `listener_position`, kept
+/// in `data.occlusion` for the mixer
+pub fn update_audio_propagation(
+    data: &mut AudioPropagationData,
+    listener_position: [f32; 3],
+    block_at: &AudioBlockSampler,
+) {
+    let sources: Vec<AudioSourceId> = data.sources.keys().copied().collect();
+    for source in sources {
+        let position = data.sources[&source];
+        let occlusion = query_audio_occlusion(data, source, position, listener_position, block_at);
+        data.occlusion.insert(source, occlusion);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STONE: BlockId = BlockId(1);
+    const GLASS: BlockId = BlockId(2);
+
+    /// A wall of stone at x = 5 and a pane of glass at x = 8
+    fn block_at(pos: VoxelPos) -> BlockId {
+        match pos.x {
+            5 => STONE,
+            8 => GLASS,
+            _ => BlockId::AIR,
+        }
+    }
+
+    #[test]
+    fn test_walls_muffle_and_cache_invalidates() {
+        let mut data = create_audio_propagation(default_audio_propagation_config());
+        set_block_absorption(&mut data, GLASS, 0.25);
+        let source = AudioSourceId(1);
+        let listener = [0.5, 0.5, 0.5];
+
+        let open = query_audio_occlusion(&mut data, source, [3.5, 0.5, 0.5], listener, &block_at);
+        assert_eq!(open.blocked_voxels, 0);
+        assert_eq!(open.gain, 1.0);
+        assert_eq!(open.low_pass_cutoff_hz, data.config.open_cutoff_hz);
+
+        let behind_wall =
+            query_audio_occlusion(&mut data, source, [6.5, 0.5, 0.5], listener, &block_at);
+        assert_eq!(behind_wall.blocked_voxels, 1);
+        assert!(behind_wall.gain < 1.0);
+        assert!(behind_wall.low_pass_cutoff_hz < open.low_pass_cutoff_hz);
+
+        let behind_glass = query_audio_occlusion(
+            &mut data,
+            AudioSourceId(2),
+            [9.5, 0.5, 0.5],
+            [7.5, 0.5, 0.5],
+            &block_at,
+        );
+        assert_eq!(behind_glass.blocked_voxels, 1);
+        assert!(behind_glass.gain > behind_wall.gain);
+
+        // Embedded in the wall itself: not muffled by its own block
+        let embedded = query_audio_occlusion(
+            &mut data,
+            AudioSourceId(3),
+            [5.5, 0.5, 0.5],
+            listener,
+            &block_at,
+        );
+        assert_eq!(embedded.blocked_voxels, 0);
+
+        // Small moves hit the cache, block changes near the path drop it
+        let traces = data.stats.traces;
+        query_audio_occlusion(&mut data, source, [6.55, 0.5, 0.5], listener, &block_at);
+        assert_eq!(data.stats.traces, traces);
+        assert_eq!(
+            invalidate_audio_occlusion_near(&mut data, VoxelPos::new(0, 40, 0)),
+            0
+        );
+        let events = [GameEvent::BlockBreak {
+            position: VoxelPos::new(5, 0, 0),
+            block_id: STONE,
+            player_id: None,
+        }];
+        assert_eq!(invalidate_audio_occlusion_for_events(&mut data, &events), 2);
+        assert!(!data.cache.contains_key(&source));
+
+        // Thick walls bottom out at the configured floor
+        let floor = occlusion_response(&data.config, 100, 100.0);
+        assert_eq!(floor.gain, data.config.min_gain);
+        assert_eq!(floor.low_pass_cutoff_hz, data.config.min_cutoff_hz);
+    }
+
+    #[test]
+    fn test_update_occludes_playing_sources() {
+        let mut data = create_audio_propagation(default_audio_propagation_config());
+        set_audio_source(&mut data, AudioSourceId(1), [3.5, 0.5, 0.5]);
+        set_audio_source(&mut data, AudioSourceId(2), [6.5, 0.5, 0.5]);
+        update_audio_propagation(&mut data, [0.5, 0.5, 0.5], &block_at);
+        assert_eq!(data.occlusion[&AudioSourceId(1)].gain, 1.0);
+        assert!(data.occlusion[&AudioSourceId(2)].gain < 1.0);
+
+        remove_audio_source(&mut data, AudioSourceId(2));
+        update_audio_propagation(&mut data, [0.5, 0.5, 0.5], &block_at);
+        assert_eq!(data.occlusion.len(), 1);
+        assert_eq!(data.stats.cache_hits, 1);
+    }
+
+    #[test]
+    fn test_loaded_chunks_drop_the_paths_through_them() {
+        let mut data = create_audio_propagation(default_audio_propagation_config());
+        let listener = [0.5, 0.5, 0.5];
+        query_audio_occlusion(
+            &mut data,
+            AudioSourceId(1),
+            [3.5, 0.5, 0.5],
+            listener,
+            &block_at,
+        );
+        query_audio_occlusion(
+            &mut data,
+            AudioSourceId(2),
+            [0.5, 0.5, 3.5],
+            listener,
+            &block_at,
+        );
+
+        let far = [ChunkPos::new(4, 0, 0)];
+        assert_eq!(invalidate_audio_occlusion_in_chunks(&mut data, &far, 32), 0);
+        let home = [ChunkPos::new(0, 0, 0)];
+        assert_eq!(
+            invalidate_audio_occlusion_in_chunks(&mut data, &home, 32),
+            2
+        );
+        assert!(data.cache.is_empty());
+    }
+}
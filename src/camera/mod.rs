@@ -7,9 +7,15 @@
 
 pub mod camera_data;
 pub mod camera_operations;
+pub mod spectator_data;
+pub mod spectator_operations;
 
 // Re-export data structures
 pub use camera_data::{CameraData, CameraTransformBatch, CameraUniform};
+pub use spectator_data::{
+    CinematicKeyframe, CinematicPath, CinematicPlayback, FollowTarget, SpectatorConfig,
+    SpectatorError, SpectatorInput, SpectatorMode, SpectatorResult, SpectatorState,
+};
 
 // Re-export all operations
 pub use camera_operations::{
@@ -48,6 +54,14 @@ pub use camera_operations::{
     log_performance_context,
 };
 
+// Spectator camera
+pub use spectator_operations::{
+    catmull_rom, cinematic_path_duration, create_spectator_state, default_spectator_config,
+    enter_free_fly, execute_spectator_command, insert_keyframe, keyframe_from_camera,
+    play_cinematic, sample_cinematic_path, start_follow, update_cinematic, update_follow,
+    update_free_fly, update_spectator,
+};
+
 // Compatibility aliases for easier migration
 pub use camera_operations::{
     move_forward as camera_move_forward,
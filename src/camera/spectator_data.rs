@@ -0,0 +1,125 @@
+//! Spectator camera data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Free flight, entity following and cinematic path playback are in
+//! spectator_operations.rs
+
+use crate::physics::EntityId;
+use cgmath::{Point3, Vector3};
+use std::collections::HashMap;
+
+/// What drives the spectator camera
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpectatorMode {
+    /// Noclip flight from player input
+    Free,
+    /// Orbit around an entity
+    Follow,
+    /// Play back a cinematic path
+    Cinematic,
+}
+
+/// Spectator tuning
+#[derive(Debug, Clone, Copy)]
+pub struct SpectatorConfig {
+    /// Free-fly thrust (voxels/s²)
+    pub acceleration: f32,
+    /// Velocity decay rate (1/s)
+    pub damping: f32,
+    /// Free-fly speed cap (voxels/s)
+    pub max_speed: f32,
+    /// Thrust and speed cap multiplier while boosting
+    pub boost_multiplier: f32,
+    /// Orbit rotation per pixel (radians)
+    pub orbit_sensitivity: f32,
+    /// Orbit distance change per scroll step (voxels)
+    pub zoom_step: f32,
+    pub min_orbit_distance: f32,
+    pub max_orbit_distance: f32,
+    /// Follow catch-up rate (1/s)
+    pub follow_smoothing: f32,
+}
+
+/// One frame of spectator input
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpectatorInput {
+    /// Forward, right and up thrust, each -1.0 to 1.0
+    pub movement: [f32; 3],
+    /// Mouse movement (pixels)
+    pub look_delta: [f32; 2],
+    /// Scroll steps; positive zooms in
+    pub zoom_delta: f32,
+    pub boost: bool,
+}
+
+/// Orbit around a followed entity
+#[derive(Debug, Clone, Copy)]
+pub struct FollowTarget {
+    pub entity: EntityId,
+    /// Orbit radius (voxels)
+    pub distance: f32,
+    /// Orbit angles around the target (radians)
+    pub orbit_yaw: f32,
+    pub orbit_pitch: f32,
+    /// Point the orbit centers on, relative to the entity position (voxels)
+    pub focus_offset: Vector3<f32>,
+}
+
+/// Camera pose at a point in time along a cinematic path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CinematicKeyframe {
+    /// Seconds from the start of the path
+    pub time: f32,
+    pub position: Point3<f32>,
+    pub yaw_radians: f32,
+    pub pitch_radians: f32,
+}
+
+/// Keyframes sorted by time, interpolated with Catmull-Rom splines
+#[derive(Debug, Clone, Default)]
+pub struct CinematicPath {
+    pub keyframes: Vec<CinematicKeyframe>,
+}
+
+/// A path being played back
+#[derive(Debug, Clone)]
+pub struct CinematicPlayback {
+    pub path: String,
+    /// Seconds into the path
+    pub elapsed: f32,
+    /// Playback rate (1.0 = recorded speed)
+    pub speed: f32,
+    /// Restart from the beginning at the end instead of stopping
+    pub looping: bool,
+}
+
+/// Everything the spectator camera keeps between frames
+#[derive(Debug, Clone)]
+pub struct SpectatorState {
+    pub mode: SpectatorMode,
+    pub config: SpectatorConfig,
+    /// Free-fly velocity (voxels/s)
+    pub velocity: Vector3<f32>,
+    pub follow: Option<FollowTarget>,
+    pub playback: Option<CinematicPlayback>,
+    /// Named paths, editable and playable from the console
+    pub paths: HashMap<String, CinematicPath>,
+}
+
+/// Spectator console command failures
+#[derive(Debug, thiserror::Error)]
+pub enum SpectatorError {
+    #[error("Unknown spectator command: {command}")]
+    UnknownCommand { command: String },
+
+    #[error("Invalid value '{value}' for {argument}")]
+    InvalidArgument { argument: String, value: String },
+
+    #[error("No cinematic path named '{name}'")]
+    UnknownPath { name: String },
+
+    #[error("Cinematic path '{name}' needs at least two keyframes")]
+    PathTooShort { name: String },
+}
+
+pub type SpectatorResult<T> = Result<T, SpectatorError>;
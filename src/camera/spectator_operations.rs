@@ -0,0 +1,635 @@
+//! Spectator camera operations - Pure functions
+//!
+//! Call `update_spectator` once per frame with the input and, while
+//! following, the followed entity's position. Console commands go through
+//! `execute_spectator_command`.
+
+use super::camera_data::CameraData;
+use super::camera_operations::{calculate_forward_vector, calculate_right_vector, rotate};
+use super::spectator_data::{
+    CinematicKeyframe, CinematicPath, CinematicPlayback, FollowTarget, SpectatorConfig,
+    SpectatorError, SpectatorInput, SpectatorMode, SpectatorResult, SpectatorState,
+};
+use crate::constants::spectator::{
+    ACCELERATION, BOOST_MULTIPLIER, DAMPING, DEFAULT_FOLLOW_DISTANCE, DEFAULT_KEYFRAME_SPACING,
+    FOLLOW_SMOOTHING, MAX_ORBIT_DISTANCE, MAX_SPEED, MIN_ORBIT_DISTANCE, ORBIT_SENSITIVITY,
+    ZOOM_STEP,
+};
+use crate::physics::EntityId;
+use cgmath::{InnerSpace, Point3, Vector3, Zero};
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+/// Keeps the orbit off the poles, like `rotate`
+const PITCH_LIMIT: f32 = FRAC_PI_2 - 0.01;
+
+// ============================================================================
+// INITIALIZATION
+// ============================================================================
+
+/// Default spectator tuning
+pub fn default_spectator_config() -> SpectatorConfig {
+    SpectatorConfig {
+        acceleration: ACCELERATION,
+        damping: DAMPING,
+        max_speed: MAX_SPEED,
+        boost_multiplier: BOOST_MULTIPLIER,
+        orbit_sensitivity: ORBIT_SENSITIVITY,
+        zoom_step: ZOOM_STEP,
+        min_orbit_distance: MIN_ORBIT_DISTANCE,
+        max_orbit_distance: MAX_ORBIT_DISTANCE,
+        follow_smoothing: FOLLOW_SMOOTHING,
+    }
+}
+
+/// Spectator at rest in free-fly mode
+pub fn create_spectator_state(config: SpectatorConfig) -> SpectatorState {
+    SpectatorState {
+        mode: SpectatorMode::Free,
+        config,
+        velocity: Vector3::zero(),
+        follow: None,
+        playback: None,
+        paths: HashMap::new(),
+    }
+}
+
+// ============================================================================
+// MODES
+// ============================================================================
+
+/// Switch to free flight from wherever the camera is
+pub fn enter_free_fly(state: &mut SpectatorState) {
+    state.mode = SpectatorMode::Free;
+    state.velocity = Vector3::zero();
+    state.follow = None;
+    state.playback = None;
+}
+
+/// Start orbiting `entity`, keeping the camera's current view direction
+pub fn start_follow(
+    state: &mut SpectatorState,
+    camera: &CameraData,
+    entity: EntityId,
+    distance: f32,
+) {
+    state.mode = SpectatorMode::Follow;
+    state.velocity = Vector3::zero();
+    state.playback = None;
+    state.follow = Some(FollowTarget {
+        entity,
+        distance: distance.clamp(
+            state.config.min_orbit_distance,
+            state.config.max_orbit_distance,
+        ),
+        orbit_yaw: camera.yaw_radians,
+        orbit_pitch: camera.pitch_radians.clamp(-PITCH_LIMIT, PITCH_LIMIT),
+        focus_offset: Vector3::zero(),
+    });
+}
+
+/// Start playing a named path from its first keyframe
+pub fn play_cinematic(
+    state: &mut SpectatorState,
+    name: &str,
+    speed: f32,
+    looping: bool,
+) -> SpectatorResult<()> {
+    let path = state
+        .paths
+        .get(name)
+        .ok_or_else(|| SpectatorError::UnknownPath {
+            name: name.to_string(),
+        })?;
+    if path.keyframes.len() < 2 {
+        return Err(SpectatorError::PathTooShort {
+            name: name.to_string(),
+        });
+    }
+    state.mode = SpectatorMode::Cinematic;
+    state.velocity = Vector3::zero();
+    state.follow = None;
+    state.playback = Some(CinematicPlayback {
+        path: name.to_string(),
+        elapsed: 0.0,
+        speed: speed.max(0.0),
+        looping,
+    });
+    Ok(())
+}
+
+// ============================================================================
+// PER-FRAME UPDATE
+// ============================================================================
+
+/// Advance the spectator camera by one frame.
+///
+/// `target_position` is the followed entity's position; when it is `None`
+/// in follow mode (entity despawned) the spectator drops to free flight.
+pub fn update_spectator(
+    state: &mut SpectatorState,
+    camera: &CameraData,
+    input: &SpectatorInput,
+    target_position: Option<Point3<f32>>,
+    delta_time: f32,
+) -> CameraData {
+    match state.mode {
+        SpectatorMode::Free => update_free_fly(state, camera, input, delta_time),
+        SpectatorMode::Follow => match target_position {
+            Some(target) => update_follow(state, camera, input, target, delta_time),
+            None => {
+                enter_free_fly(state);
+                update_free_fly(state, camera, input, delta_time)
+            }
+        },
+        SpectatorMode::Cinematic => update_cinematic(state, camera, delta_time),
+    }
+}
+
+/// Noclip flight: thrust along the view direction, exponential damping
+pub fn update_free_fly(
+    state: &mut SpectatorState,
+    camera: &CameraData,
+    input: &SpectatorInput,
+    delta_time: f32,
+) -> CameraData {
+    let config = state.config;
+    let mut result = rotate(
+        camera,
+        input.look_delta[0] * camera.rotation_sensitivity,
+        -input.look_delta[1] * camera.rotation_sensitivity,
+    );
+
+    let forward = calculate_forward_vector(result.yaw_radians, result.pitch_radians);
+    let right = calculate_right_vector(result.yaw_radians);
+    let [thrust_forward, thrust_right, thrust_up] = input.movement;
+    let mut wish = forward * thrust_forward + right * thrust_right + Vector3::unit_y() * thrust_up;
+    if wish.magnitude2() > 1.0 {
+        wish = wish.normalize();
+    }
+
+    let boost = if input.boost {
+        config.boost_multiplier
+    } else {
+        1.0
+    };
+    state.velocity += wish * config.acceleration * boost * delta_time;
+    state.velocity *= (-config.damping * delta_time).exp();
+
+    let max_speed = config.max_speed * boost;
+    if state.velocity.magnitude2() > max_speed * max_speed {
+        state.velocity = state.velocity.normalize_to(max_speed);
+    }
+
+    result.position += state.velocity * delta_time;
+    result
+}
+
+/// Orbit the target, easing toward the orbit position and always looking
+/// at the focus point
+pub fn update_follow(
+    state: &mut SpectatorState,
+    camera: &CameraData,
+    input: &SpectatorInput,
+    target_position: Point3<f32>,
+    delta_time: f32,
+) -> CameraData {
+    let config = state.config;
+    let Some(follow) = state.follow.as_mut() else {
+        return *camera;
+    };
+
+    follow.orbit_yaw += input.look_delta[0] * config.orbit_sensitivity;
+    follow.orbit_pitch = (follow.orbit_pitch - input.look_delta[1] * config.orbit_sensitivity)
+        .clamp(-PITCH_LIMIT, PITCH_LIMIT);
+    follow.distance = (follow.distance - input.zoom_delta * config.zoom_step)
+        .clamp(config.min_orbit_distance, config.max_orbit_distance);
+
+    let focus = target_position + follow.focus_offset;
+    let desired =
+        focus - calculate_forward_vector(follow.orbit_yaw, follow.orbit_pitch) * follow.distance;
+    let blend = 1.0 - (-config.follow_smoothing * delta_time).exp();
+
+    let mut result = *camera;
+    result.position = camera.position + (desired - camera.position) * blend;
+    look_at(&mut result, focus);
+    result
+}
+
+/// Move along the playing path; returns to free flight when a
+/// non-looping path ends
+pub fn update_cinematic(
+    state: &mut SpectatorState,
+    camera: &CameraData,
+    delta_time: f32,
+) -> CameraData {
+    let Some(playback) = state.playback.as_mut() else {
+        return *camera;
+    };
+    let Some(path) = state.paths.get(&playback.path) else {
+        enter_free_fly(state);
+        return *camera;
+    };
+
+    let duration = cinematic_path_duration(path);
+    playback.elapsed += delta_time * playback.speed;
+    let finished = playback.elapsed >= duration;
+    if finished && playback.looping && duration > 0.0 {
+        playback.elapsed %= duration;
+    }
+
+    let time = path.keyframes.first().map_or(0.0, |first| first.time) + playback.elapsed;
+    let mut result = *camera;
+    if let Some(pose) = sample_cinematic_path(path, time) {
+        result.position = pose.position;
+        result.yaw_radians = pose.yaw_radians;
+        result.pitch_radians = pose.pitch_radians;
+    }
+
+    if finished && !playback.looping {
+        enter_free_fly(state);
+    }
+    result
+}
+
+/// Point the camera at `target`
+fn look_at(camera: &mut CameraData, target: Point3<f32>) {
+    let direction = target - camera.position;
+    let length = direction.magnitude();
+    if length <= f32::EPSILON {
+        return;
+    }
+    camera.yaw_radians = direction.z.atan2(direction.x);
+    camera.pitch_radians = (direction.y / length)
+        .clamp(-1.0, 1.0)
+        .asin()
+        .clamp(-PITCH_LIMIT, PITCH_LIMIT);
+}
+
+// ============================================================================
+// CINEMATIC PATHS
+// ============================================================================
+
+/// Uniform Catmull-Rom interpolation between `p1` and `p2`
+pub fn catmull_rom(p0: f32, p1: f32, p2: f32, p3: f32, t: f32) -> f32 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+/// Seconds from the first keyframe to the last
+pub fn cinematic_path_duration(path: &CinematicPath) -> f32 {
+    match (path.keyframes.first(), path.keyframes.last()) {
+        (Some(first), Some(last)) => (last.time - first.time).max(0.0),
+        _ => 0.0,
+    }
+}
+
+/// Add a keyframe, keeping the path sorted by time
+pub fn insert_keyframe(path: &mut CinematicPath, keyframe: CinematicKeyframe) {
+    let index = path
+        .keyframes
+        .partition_point(|existing| existing.time <= keyframe.time);
+    path.keyframes.insert(index, keyframe);
+}
+
+/// Keyframe of the camera's current pose at `time`
+pub fn keyframe_from_camera(camera: &CameraData, time: f32) -> CinematicKeyframe {
+    CinematicKeyframe {
+        time,
+        position: camera.position,
+        yaw_radians: camera.yaw_radians,
+        pitch_radians: camera.pitch_radians,
+    }
+}
+
+/// `angle` shifted by whole turns to within half a turn of `reference`
+fn unwrap_angle(angle: f32, reference: f32) -> f32 {
+    reference + (angle - reference + PI).rem_euclid(TAU) - PI
+}
+
+/// Camera pose at `time` along the path, clamped to its ends. Yaw turns
+/// the short way round between keyframes.
+pub fn sample_cinematic_path(path: &CinematicPath, time: f32) -> Option<CinematicKeyframe> {
+    let keys = &path.keyframes;
+    let first = keys.first()?;
+    let last = keys.last()?;
+    if keys.len() == 1 || time <= first.time {
+        return Some(CinematicKeyframe { time, ..*first });
+    }
+    if time >= last.time {
+        return Some(CinematicKeyframe { time, ..*last });
+    }
+
+    let segment = keys
+        .partition_point(|key| key.time <= time)
+        .saturating_sub(1);
+    let at = |index: isize| keys[index.clamp(0, keys.len() as isize - 1) as usize];
+    let index = segment as isize;
+    let [k0, k1, k2, k3] = [at(index - 1), at(index), at(index + 1), at(index + 2)];
+
+    let span = k2.time - k1.time;
+    let t = if span > f32::EPSILON {
+        (time - k1.time) / span
+    } else {
+        0.0
+    };
+
+    let yaw1 = k1.yaw_radians;
+    let yaw0 = unwrap_angle(k0.yaw_radians, yaw1);
+    let yaw2 = unwrap_angle(k2.yaw_radians, yaw1);
+    let yaw3 = unwrap_angle(k3.yaw_radians, yaw2);
+
+    let axis = |i: usize| {
+        catmull_rom(
+            k0.position[i],
+            k1.position[i],
+            k2.position[i],
+            k3.position[i],
+            t,
+        )
+    };
+    Some(CinematicKeyframe {
+        time,
+        position: Point3::new(axis(0), axis(1), axis(2)),
+        yaw_radians: catmull_rom(yaw0, yaw1, yaw2, yaw3, t),
+        pitch_radians: catmull_rom(
+            k0.pitch_radians,
+            k1.pitch_radians,
+            k2.pitch_radians,
+            k3.pitch_radians,
+            t,
+        )
+        .clamp(-PITCH_LIMIT, PITCH_LIMIT),
+    })
+}
+
+// ============================================================================
+// CONSOLE COMMAND
+// ============================================================================
+
+fn parse_value<T: std::str::FromStr>(argument: &str, value: &str) -> SpectatorResult<T> {
+    value.parse().map_err(|_| SpectatorError::InvalidArgument {
+        argument: argument.to_string(),
+        value: value.to_string(),
+    })
+}
+
+fn positive(argument: &str, value: &str) -> SpectatorResult<f32> {
+    let parsed: f32 = parse_value(argument, value)?;
+    if parsed.is_finite() && parsed > 0.0 {
+        Ok(parsed)
+    } else {
+        Err(SpectatorError::InvalidArgument {
+            argument: argument.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+fn format_status(state: &SpectatorState) -> String {
+    match state.mode {
+        SpectatorMode::Free => crate::tr!(
+            "console.spectator.free_status",
+            accel = format!("{:.0}", state.config.acceleration),
+            damping = format!("{:.1}", state.config.damping),
+            max_speed = format!("{:.0}", state.config.max_speed),
+        ),
+        SpectatorMode::Follow => match &state.follow {
+            Some(follow) => crate::tr!(
+                "console.spectator.following",
+                entity = follow.entity,
+                distance = format!("{:.0}", follow.distance),
+            ),
+            None => crate::tr!("console.spectator.following_nothing"),
+        },
+        SpectatorMode::Cinematic => match &state.playback {
+            Some(playback) => {
+                let key = if playback.looping {
+                    "console.spectator.playing_looped"
+                } else {
+                    "console.spectator.playing"
+                };
+                crate::tr!(
+                    key,
+                    path = playback.path,
+                    elapsed = format!("{:.1}", playback.elapsed),
+                    speed = playback.speed,
+                )
+            }
+            None => crate::tr!("console.spectator.no_path"),
+        },
+    }
+}
+
+/// Run a `spectator` console command and return the text to print:
+///
+/// - `spectator` / `spectator status` - current mode
+/// - `spectator free` - noclip flight from the current view
+/// - `spectator follow <entity> [distance]` - orbit an entity
+/// - `spectator accel|damping|maxspeed <value>` - free-fly tuning
+/// - `spectator path new <name>` / `path delete <name>` / `path list`
+/// - `spectator path key <name> [seconds]` - add the current view as a
+///   keyframe, by default two seconds after the last one
+/// - `spectator path play <name> [speed] [loop]` / `path stop`
+pub fn execute_spectator_command(
+    state: &mut SpectatorState,
+    camera: &CameraData,
+    command: &str,
+) -> SpectatorResult<String> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let args = match words.first() {
+        Some(&"spectator") => &words[1..],
+        _ => &words[..],
+    };
+    let unknown = || SpectatorError::UnknownCommand {
+        command: command.trim().to_string(),
+    };
+    let unknown_path = |name: &str| SpectatorError::UnknownPath {
+        name: name.to_string(),
+    };
+
+    match args {
+        [] | ["status"] => Ok(format_status(state)),
+        ["free"] => {
+            enter_free_fly(state);
+            Ok(crate::tr!("console.spectator.free"))
+        }
+        ["follow", entity, rest @ ..] => {
+            let entity: EntityId = parse_value("entity", entity)?;
+            let distance = match rest {
+                [] => DEFAULT_FOLLOW_DISTANCE,
+                [distance] => positive("distance", distance)?,
+                _ => return Err(unknown()),
+            };
+            start_follow(state, camera, entity, distance);
+            Ok(format_status(state))
+        }
+        ["accel", value] => {
+            state.config.acceleration = positive("accel", value)?;
+            Ok(format_status(state))
+        }
+        ["damping", value] => {
+            let damping: f32 = parse_value("damping", value)?;
+            if !damping.is_finite() || damping < 0.0 {
+                return Err(SpectatorError::InvalidArgument {
+                    argument: "damping".to_string(),
+                    value: value.to_string(),
+                });
+            }
+            state.config.damping = damping;
+            Ok(format_status(state))
+        }
+        ["maxspeed", value] => {
+            state.config.max_speed = positive("maxspeed", value)?;
+            Ok(format_status(state))
+        }
+        ["path", "new", name] => {
+            state
+                .paths
+                .insert(name.to_string(), CinematicPath::default());
+            Ok(crate::tr!("console.spectator.path_created", name = name))
+        }
+        ["path", "delete", name] => {
+            state
+                .paths
+                .remove(*name)
+                .ok_or_else(|| unknown_path(name))?;
+            if state
+                .playback
+                .as_ref()
+                .is_some_and(|playback| playback.path == *name)
+            {
+                enter_free_fly(state);
+            }
+            Ok(crate::tr!("console.spectator.path_deleted", name = name))
+        }
+        ["path", "list"] => {
+            let mut names: Vec<_> = state.paths.iter().collect();
+            names.sort_by(|a, b| a.0.cmp(b.0));
+            Ok(names
+                .into_iter()
+                .map(|(name, path)| {
+                    crate::tr!(
+                        "console.spectator.path_entry",
+                        name = name,
+                        count = path.keyframes.len(),
+                        seconds = format!("{:.1}", cinematic_path_duration(path)),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        ["path", "key", name, rest @ ..] => {
+            let path = state
+                .paths
+                .get_mut(*name)
+                .ok_or_else(|| unknown_path(name))?;
+            let time = match rest {
+                [] => path
+                    .keyframes
+                    .last()
+                    .map_or(0.0, |last| last.time + DEFAULT_KEYFRAME_SPACING),
+                [value] => {
+                    let seconds: f32 = parse_value("seconds", value)?;
+                    if !seconds.is_finite() || seconds < 0.0 {
+                        return Err(SpectatorError::InvalidArgument {
+                            argument: "seconds".to_string(),
+                            value: value.to_string(),
+                        });
+                    }
+                    seconds
+                }
+                _ => return Err(unknown()),
+            };
+            insert_keyframe(path, keyframe_from_camera(camera, time));
+            Ok(crate::tr!(
+                "console.spectator.keyframe_added",
+                name = name,
+                seconds = format!("{:.1}", time),
+                count = path.keyframes.len(),
+            ))
+        }
+        ["path", "play", name, rest @ ..] => {
+            let (speed, looping) = match rest {
+                [] => (1.0, false),
+                ["loop"] => (1.0, true),
+                [speed] => (positive("speed", speed)?, false),
+                [speed, "loop"] => (positive("speed", speed)?, true),
+                _ => return Err(unknown()),
+            };
+            play_cinematic(state, name, speed, looping)?;
+            Ok(format_status(state))
+        }
+        ["path", "stop"] => {
+            enter_free_fly(state);
+            Ok(crate::tr!("console.spectator.stopped"))
+        }
+        _ => Err(unknown()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_fly_accelerates_then_coasts_to_rest() {
+        let mut state = create_spectator_state(default_spectator_config());
+        let mut camera = CameraData::default();
+        let thrust = SpectatorInput {
+            movement: [1.0, 0.0, 0.0],
+            ..Default::default()
+        };
+        for _ in 0..60 {
+            camera = update_spectator(&mut state, &camera, &thrust, None, 1.0 / 60.0);
+        }
+        assert!(camera.position.x > 10.0);
+        assert!(state.velocity.magnitude() <= state.config.max_speed + 1e-3);
+
+        for _ in 0..600 {
+            camera = update_spectator(
+                &mut state,
+                &camera,
+                &SpectatorInput::default(),
+                None,
+                1.0 / 60.0,
+            );
+        }
+        assert!(state.velocity.magnitude() < 0.1);
+    }
+
+    #[test]
+    fn test_cinematic_path_from_console() {
+        let mut state = create_spectator_state(default_spectator_config());
+        let mut camera = CameraData::default();
+        execute_spectator_command(&mut state, &camera, "spectator path new intro").expect("new");
+        for (x, yaw) in [(0.0, 3.0), (10.0, -3.0), (20.0, -2.5)] {
+            camera.position = Point3::new(x, 50.0, 0.0);
+            camera.yaw_radians = yaw;
+            execute_spectator_command(&mut state, &camera, "path key intro").expect("key");
+        }
+        assert!(matches!(
+            execute_spectator_command(&mut state, &camera, "path play outro"),
+            Err(SpectatorError::UnknownPath { .. })
+        ));
+
+        // Passes through keyframes, and yaw crosses +-PI the short way
+        let path = &state.paths["intro"];
+        let at_key = sample_cinematic_path(path, 2.0).expect("pose");
+        assert!((at_key.position.x - 10.0).abs() < 1e-4);
+        let between = sample_cinematic_path(path, 1.0).expect("pose");
+        assert!(between.yaw_radians > 3.0 && between.yaw_radians < 3.3);
+
+        execute_spectator_command(&mut state, &camera, "path play intro 2").expect("play");
+        let idle = SpectatorInput::default();
+        camera = update_spectator(&mut state, &camera, &idle, None, 1.0);
+        assert!((camera.position.x - 10.0).abs() < 1e-4);
+        camera = update_spectator(&mut state, &camera, &idle, None, 1.0);
+        assert!((camera.position.x - 20.0).abs() < 1e-4);
+        assert_eq!(state.mode, SpectatorMode::Free);
+    }
+}
@@ -11,6 +11,9 @@ pub mod core {
     pub const CHUNK_SIZE: u32 = 50;
     pub const CHUNK_SIZE_F32: f32 = 50.0;
     pub const VOXELS_PER_CHUNK: u32 = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
+
+    /// Chunk sizes a world may be created with (CHUNK_SIZE is the default)
+    pub const SUPPORTED_CHUNK_SIZES: [u32; 3] = [32, 50, 64];
     
     /// World limits
     pub const MAX_WORLD_SIZE: u32 = 512; // 512³ chunks
@@ -114,6 +117,57 @@ pub mod physics_constants {
     /// Block collision box half-extents (voxels)
     /// 1 voxel = 10cm, so half-extents = 5cm = 0.5 voxels
     pub const BLOCK_HALF_EXTENTS: [f32; 3] = [0.5, 0.5, 0.5];
+
+    /// Maximum entities per leaf of the picking BVH
+    pub const PICK_BVH_LEAF_SIZE: usize = 4;
+
+    /// Surface grip of ordinary blocks (1.0 = full control)
+    pub const DEFAULT_BLOCK_FRICTION: f32 = 1.0;
+
+    /// Ordinary blocks do not bounce
+    pub const DEFAULT_BLOCK_RESTITUTION: f32 = 0.0;
+
+    /// Ordinary blocks do not change walking speed
+    pub const DEFAULT_SPEED_MULTIPLIER: f32 = 1.0;
+
+    /// Horizontal acceleration of a character on full-grip ground (voxels/s²)
+    pub const CHARACTER_GROUND_ACCELERATION: f32 = 400.0;
+
+    /// Horizontal acceleration of a character in the air (voxels/s²)
+    pub const CHARACTER_AIR_ACCELERATION: f32 = 80.0;
+
+    /// Landing speeds below this do not bounce (voxels/s)
+    pub const MIN_BOUNCE_SPEED: f32 = 20.0;
+
+    /// How far below the feet the ground is sampled (voxels)
+    pub const SURFACE_PROBE_DEPTH: f32 = 0.05;
+
+    /// Player mass for pushing against dynamic entities (kg)
+    pub const PLAYER_MASS: f32 = 80.0;
+
+    /// Gap kept between the player and entities it slides along (voxels)
+    pub const ENTITY_CONTACT_SKIN: f32 = 0.01;
+
+    /// Slide iterations per tick when sweeping against entities
+    pub const ENTITY_MAX_SLIDES: usize = 4;
+
+    /// Overlap an entity can force into the player against solid voxels
+    /// before the player counts as crushed (voxels)
+    pub const CRUSH_DEPTH: f32 = 0.5;
+
+    /// Mass of the dynamic entities in the physics buffers when the player
+    /// pushes them (kg)
+    pub const DYNAMIC_ENTITY_MASS: f32 = 40.0;
+
+    /// Density of entities without their own (kg/m³); below water's, so
+    /// they float
+    pub const DEFAULT_ENTITY_DENSITY: f32 = 900.0;
+
+    /// Fluid density the drag coefficient is given for (kg/m³, water)
+    pub const REFERENCE_FLUID_DENSITY: f32 = 1000.0;
+
+    /// Velocity damping of a fully submerged entity in water (1/s)
+    pub const FLUID_LINEAR_DRAG: f32 = 3.0;
 }
 
 /// Camera and rendering constants - ALL IN VOXEL UNITS
@@ -136,6 +190,53 @@ pub mod camera_constants {
     pub const FLY_SPEED: f32 = 100.0;      // ~10.0 m/s flying
 }
 
+/// Spectator camera tuning - ALL IN VOXEL UNITS
+pub mod spectator {
+    /// Free-fly thrust (voxels/s²)
+    pub const ACCELERATION: f32 = 600.0;
+
+    /// Velocity decay rate; higher stops sooner (1/s)
+    pub const DAMPING: f32 = 4.0;
+
+    /// Free-fly speed cap (voxels/s)
+    pub const MAX_SPEED: f32 = 300.0;
+
+    /// Thrust and speed cap multiplier while boosting
+    pub const BOOST_MULTIPLIER: f32 = 3.0;
+
+    /// Orbit rotation per pixel of mouse movement (radians)
+    pub const ORBIT_SENSITIVITY: f32 = 0.005;
+
+    /// Orbit distance change per scroll step (voxels)
+    pub const ZOOM_STEP: f32 = 5.0;
+
+    /// Closest and farthest follow orbit (voxels)
+    pub const MIN_ORBIT_DISTANCE: f32 = 10.0;
+    pub const MAX_ORBIT_DISTANCE: f32 = 500.0;
+
+    /// Orbit distance when following starts (voxels)
+    pub const DEFAULT_FOLLOW_DISTANCE: f32 = 60.0;
+
+    /// How fast the follow camera catches up with its target (1/s)
+    pub const FOLLOW_SMOOTHING: f32 = 8.0;
+
+    /// Gap after the previous keyframe when a key is added without a time (s)
+    pub const DEFAULT_KEYFRAME_SPACING: f32 = 2.0;
+
+    /// Trackpad scroll (pixels) counted as one zoom step
+    pub const SCROLL_PIXELS_PER_STEP: f32 = 40.0;
+}
+
+/// Biome color blending - ALL IN VOXEL UNITS
+pub mod biome_tint {
+    /// Columns averaged on each side of a vertex when blending biome
+    /// colors; the width of a border transition is twice this (voxels)
+    pub const BLEND_RADIUS: u32 = 6;
+
+    /// Tint kinds per chunk tint map (grass, foliage, water)
+    pub const TINT_KIND_COUNT: usize = 3;
+}
+
 /// Terrain generation constants - ALL IN VOXEL UNITS
 pub mod terrain {
     /// Terrain height variations (voxels)
@@ -157,11 +258,144 @@ pub mod terrain {
     /// Terrain generation height threshold (voxels)
     /// Base terrain generation height: 6.4m × 10 voxels/m = 64 voxels
     pub const TERRAIN_THRESHOLD: i32 = 64;
+
+    /// Sea level of worlds without water: below any surface
+    pub const DRY_SEA_LEVEL: f32 = f32::MIN;
     
     /// Terrain height limits (voxels)
     /// Range: 10m-200m × 10 voxels/m = 100-2000 voxels
     pub const MIN_HEIGHT: i32 = 100;
     pub const MAX_HEIGHT: i32 = 2000;
+
+    /// Height above which chunks are guaranteed empty (voxels)
+    /// Terrain limit plus headroom for trees and structures; chunks entirely
+    /// above it are stored as sparse air without running generation
+    pub const SPARSE_AIR_HEIGHT: i32 = MAX_HEIGHT + 256;
+
+    /// Heightmap value of a column with no loaded non-air block
+    pub const NO_SURFACE_HEIGHT: i32 = i32::MIN;
+}
+
+/// Debug and testing world presets - ALL IN VOXEL UNITS
+pub mod world_presets {
+    /// Stone layer of the default superflat stack (voxels)
+    /// 4m × 10 voxels/m = 40 voxels
+    pub const SUPERFLAT_STONE_DEPTH: u32 = 40;
+
+    /// Dirt layer of the default superflat stack (voxels)
+    /// 0.3m × 10 voxels/m = 3 voxels
+    pub const SUPERFLAT_DIRT_DEPTH: u32 = 3;
+
+    /// Height of the checkerboard and void debug floors (voxels)
+    pub const DEBUG_FLOOR_Y: i32 = 64;
+
+    /// Checkerboard square edge (voxels)
+    /// 1m × 10 voxels/m = 10 voxels
+    pub const CHECKERBOARD_CELL_SIZE: u32 = 10;
+
+    /// Biome reported by presets that do not name one
+    pub const DEFAULT_PRESET_BIOME: &str = "temperate";
+}
+
+/// Far terrain heightfield ring constants - ALL IN VOXEL UNITS
+pub mod far_terrain {
+    /// Heightmap texels per side
+    pub const HEIGHTMAP_RESOLUTION: u32 = 512;
+
+    /// Ring outer radius (voxels)
+    /// 800m × 10 voxels/m = 8,000 voxels, inside camera ZFAR
+    pub const OUTER_RADIUS: f32 = 8000.0;
+
+    /// Width of the fade into the voxel world (voxels)
+    pub const BLEND_WIDTH: f32 = 100.0;
+
+    /// Camera travel before the heightmap is regenerated (voxels)
+    pub const RECENTER_DISTANCE: f32 = 250.0;
+
+    /// Depth the ring sinks below the terrain at the voxel boundary (voxels)
+    pub const BOUNDARY_SINK: f32 = 4.0;
+
+    /// `FarTerrainUniform::surface_mode`: the reference hills
+    pub const SURFACE_REFERENCE: u32 = 0;
+
+    /// `FarTerrainUniform::surface_mode`: level ground at `flat_height`
+    pub const SURFACE_FLAT: u32 = 1;
+}
+
+/// Light placement preview
+pub mod light_preview {
+    /// Cells around the target covered by the preview; a level-15 source
+    /// reaches 14 voxels before falling to zero
+    pub const PREVIEW_RADIUS: u32 = super::lighting::MAX_LIGHT_LEVEL as u32 - 1;
+
+    /// Cells per side of the preview volume
+    pub const PREVIEW_EXTENT: u32 = PREVIEW_RADIUS * 2 + 1;
+
+    /// Propagation passes per update (even, so the result lands in the first buffer)
+    pub const PROPAGATION_PASSES: u32 = 16;
+
+    /// Overlay tint (rgb) and alpha at full brightness
+    pub const OVERLAY_COLOR: [f32; 4] = [1.0, 0.78, 0.35, 0.45];
+}
+
+/// Ghost of the block about to be placed
+pub mod placement_preview {
+    /// Farthest a block can be placed from the camera (voxels)
+    /// 5 m × 10 voxels/m
+    pub const MAX_REACH: f32 = 50.0;
+
+    /// Ghost tint (rgb) and alpha where the block can be placed
+    pub const VALID_COLOR: [f32; 4] = [0.85, 0.92, 1.0, 0.35];
+
+    /// Ghost tint (rgb) and alpha where placement would fail
+    pub const INVALID_COLOR: [f32; 4] = [1.0, 0.22, 0.18, 0.45];
+
+    /// Ghost growth past the voxel bounds so it does not z-fight
+    /// neighbouring faces (voxels)
+    pub const GHOST_INFLATE: f32 = 0.004;
+
+    /// Brightness of the model front face relative to the other faces, so
+    /// the ghost shows which way a directional block will face
+    pub const FRONT_FACE_EMPHASIS: f32 = 1.6;
+}
+
+/// Voxel inspector (debug readback of world buffer contents)
+pub mod voxel_inspector {
+    /// Radius inspected when the command gives none
+    pub const DEFAULT_RADIUS: u32 = 1;
+
+    /// Largest radius accepted (9³ voxels)
+    pub const MAX_RADIUS: u32 = 4;
+}
+
+/// Sub-chunk readback of voxel regions
+pub mod region_readback {
+    /// Voxels the shadow cache keeps from region readbacks before dropping them all
+    pub const SHADOW_PARTIAL_VOXEL_LIMIT: usize = 65_536;
+
+    /// Regions recorded into one shadow cache readback batch
+    pub const SHADOW_REGION_BATCH: usize = 64;
+}
+
+/// Thread pool priority lanes
+pub mod thread_pool_constants {
+    /// Default worker thread count when the host reports none
+    pub const DEFAULT_WORKER_COUNT: usize = 4;
+
+    /// Workers that pull from the background lane before any other
+    pub const RESERVED_BACKGROUND_WORKERS: usize = 1;
+
+    /// Queued tasks older than this are run ahead of higher lanes (ms)
+    pub const STARVATION_THRESHOLD_MS: u64 = 250;
+
+    /// Maximum tasks waiting in a single lane
+    pub const MAX_LANE_DEPTH: usize = 4096;
+
+    /// How often idle workers re-check lanes for starved tasks (ms)
+    pub const IDLE_POLL_INTERVAL_MS: u64 = 50;
+
+    /// Chunks within this distance of the camera (in chunks) generate in the high lane
+    pub const NEAR_CHUNK_GENERATION_RADIUS: i32 = 2;
 }
 
 /// GPU buffer alignment requirements
@@ -209,6 +443,36 @@ pub mod buffer_sizes {
     pub const TEMP_BUFFER_SIZE: usize = 4096; // 4KB
 }
 
+/// Chunk upload batching through the staging ring
+pub mod chunk_upload {
+    /// Staging ring size; a frame's batch never exceeds it
+    pub const RING_SIZE_BYTES: u64 = 32 * 1024 * 1024; // 32MB
+
+    /// Voxel data uploaded per frame before the rest waits for the next one
+    /// 8MB = 16 chunks of 50³ voxels
+    pub const BYTES_PER_FRAME: u64 = 8 * 1024 * 1024;
+
+    /// Weight of the newest frame in the smoothed upload bandwidth
+    pub const BANDWIDTH_SMOOTHING: f64 = 0.1;
+}
+
+/// Palettized (8-bit index) voxel storage tier
+pub mod voxel_palette {
+    /// Distinct voxel values a palettized chunk can hold; chunks with more
+    /// fall back to the 32-bit format
+    pub const PALETTE_MAX_ENTRIES: usize = 256;
+
+    /// 8-bit palette indices packed per u32 word
+    pub const PALETTE_INDICES_PER_WORD: usize = 4;
+
+    /// With the palette tier, full 32-bit slots only hold palette overflow
+    /// and chunks being generated or edited: 1/DIVISOR of the view volume
+    pub const DENSE_FALLBACK_DIVISOR: u32 = 4;
+
+    /// Palette chunks decoded into full slots per `promote_palette_chunks`
+    pub const PALETTE_DECODE_MAX_BATCH: usize = 64;
+}
+
 /// Gameplay constants
 pub mod gameplay {
     /// Target frames per second for physics simulation
@@ -249,6 +513,146 @@ pub mod network_constants {
     
     /// P2P buffer size (16KB)
     pub const P2P_BUFFER_SIZE: usize = 1024 * 16;
+
+    /// Default per-connection send cap (256 KB/s)
+    pub const DEFAULT_CONNECTION_BYTES_PER_SEC: u32 = 256 * 1024;
+
+    /// Default send rate for bulk streams such as chunk data (192 KB/s)
+    pub const DEFAULT_BULK_BYTES_PER_SEC: u32 = 192 * 1024;
+
+    /// Burst allowance for bulk streams (64KB)
+    pub const DEFAULT_BULK_BURST_BYTES: u32 = 64 * 1024;
+
+    /// Congestion backoff when RTT exceeds this multiple of the minimum RTT
+    pub const CONGESTION_RTT_RATIO: f32 = 2.0;
+
+    /// Congestion backoff when ack loss exceeds this fraction
+    pub const CONGESTION_LOSS_THRESHOLD: f32 = 0.05;
+
+    /// Multiplicative decrease applied to the send rate on congestion
+    pub const CONGESTION_BACKOFF_FACTOR: f32 = 0.7;
+
+    /// Additive increase of the send rate per second without congestion
+    pub const CONGESTION_RECOVERY_PER_SEC: f32 = 0.1;
+
+    /// Lowest fraction of the configured rate congestion control can drop to
+    pub const CONGESTION_MIN_RATE_FACTOR: f32 = 0.1;
+
+    /// Minimum time between two backoffs (one per round trip at most)
+    pub const CONGESTION_BACKOFF_INTERVAL_MS: f32 = 250.0;
+
+    /// Largest record accepted when reading a packet capture (16MB)
+    pub const MAX_CAPTURE_RECORD_BYTES: usize = 16 * 1024 * 1024;
+
+    /// Capture records buffered before the file is flushed
+    pub const CAPTURE_FLUSH_INTERVAL_RECORDS: u64 = 256;
+
+    /// Packet types of the engine's own messages, named in every capture
+    pub const CHUNK_REQUEST_PACKET_TYPE: u16 = 1;
+    pub const CHUNK_SECTION_PACKET_TYPE: u16 = 2;
+    pub const CHUNK_DIFF_PACKET_TYPE: u16 = 3;
+    pub const LOCKSTEP_PACKET_TYPE: u16 = 4;
+    pub const CUSTOM_PACKET_TYPE: u16 = 5;
+    pub const SCOREBOARD_PACKET_TYPE: u16 = 6;
+
+    /// Games number their own packet types from here
+    pub const FIRST_GAME_PACKET_TYPE: u16 = 64;
+
+    /// Network protocol version sent in beacons and handshakes
+    /// (2: block registry sync after the hello, 3: chunks sent in sections,
+    /// 4: versioned chunks and diffs for cached chunks, 5: custom packet
+    /// tables after the hellos)
+    pub const PROTOCOL_VERSION: u32 = 5;
+
+    /// Oldest protocol version this build can talk to
+    pub const MIN_COMPATIBLE_PROTOCOL_VERSION: u32 = 5;
+
+    /// UDP port servers broadcast LAN beacons to
+    pub const LAN_DISCOVERY_PORT: u16 = 47_800;
+
+    /// Time between two LAN beacons
+    pub const LAN_BEACON_INTERVAL_MS: u64 = 1500;
+
+    /// A server is dropped from the list after this long without a beacon
+    pub const LAN_SERVER_TIMEOUT_MS: u64 = 5000;
+
+    /// Largest beacon accepted (bytes)
+    pub const MAX_BEACON_BYTES: usize = 512;
+
+    /// Largest payload a custom packet channel can allow (32KB)
+    pub const MAX_CUSTOM_PACKET_BYTES: u32 = 32 * 1024;
+
+    /// Custom packet channels one game can register
+    pub const MAX_CUSTOM_PACKET_CHANNELS: usize = 256;
+
+    /// Reliable custom packets are resent after this long without an ack
+    pub const CUSTOM_PACKET_RESEND_MS: f32 = 250.0;
+
+    /// Out-of-order packets held per ordered channel while waiting for a gap
+    pub const MAX_HELD_CUSTOM_PACKETS: usize = 256;
+
+    /// Block shown for server blocks the client does not know (stone)
+    pub const REGISTRY_PLACEHOLDER_BLOCK: u16 = 3;
+
+    /// Height of one chunk section sent as a separate packet (voxels)
+    pub const CHUNK_SECTION_HEIGHT: u32 = 8;
+
+    /// Extra distance factor for chunks directly behind the view direction
+    /// (chunks behind count as 1 + weight times farther)
+    pub const CHUNK_VIEW_DIRECTION_WEIGHT: f32 = 1.0;
+
+    /// Most chunks a client keeps requested at once
+    pub const MAX_CHUNK_REQUESTS: usize = 512;
+
+    /// Chunk sections queued on a connection per tick
+    pub const MAX_CHUNK_SECTIONS_PER_TICK: usize = 32;
+
+    /// Chunk data kept queued on a connection before the server stops
+    /// encoding more sections, so cancels and new priorities apply quickly
+    pub const MAX_QUEUED_CHUNK_BYTES: usize = 128 * 1024;
+
+    /// Block edits logged per chunk for diffs to late joiners; clients
+    /// whose copy is older than the log get the whole chunk
+    pub const MAX_CHUNK_EDIT_LOG_ENTRIES: usize = 1024;
+
+    /// Distinct block edits above which a diff is sent as the full chunk
+    pub const MAX_CHUNK_DIFF_EDITS: usize = 4096;
+
+    /// Server ticks of entity positions and voxel edits kept for rewinding
+    pub const LAG_COMPENSATION_HISTORY_TICKS: u32 = 64;
+
+    /// Farthest back a client's perceived tick may be rewound (ticks)
+    pub const MAX_LAG_REWIND_TICKS: u32 = 20;
+
+    /// Most players in a lockstep session
+    pub const LOCKSTEP_MAX_PLAYERS: usize = 4;
+
+    /// Ticks between sampling a lockstep input and simulating it, so it
+    /// reaches every peer in time
+    pub const LOCKSTEP_INPUT_DELAY_TICKS: u64 = 3;
+
+    /// Ticks between lockstep state hashes
+    pub const LOCKSTEP_HASH_INTERVAL_TICKS: u64 = 60;
+
+    /// Simulated ticks of lockstep inputs kept, so a peer that ran ahead of
+    /// the host can replay from a resync
+    pub const LOCKSTEP_INPUT_HISTORY_TICKS: u64 = 120;
+
+    /// Largest encoded input of one player for one tick
+    pub const LOCKSTEP_MAX_INPUT_BYTES: usize = 256;
+
+    /// Longest hit a client may claim (voxels)
+    pub const MAX_HIT_DISTANCE: f32 = 6.0;
+
+    /// Slack added to target bounds when validating a rewound hit (voxels)
+    pub const HIT_BOUNDS_TOLERANCE: f32 = 0.3;
+
+    /// Longest block interaction reach (voxels, eye to block center)
+    pub const MAX_BLOCK_REACH: f32 = 6.0;
+
+    /// Distance a claimed eye position may be from the rewound player
+    /// position (voxels)
+    pub const MAX_CLAIM_ORIGIN_ERROR: f32 = 2.5;
 }
 
 
@@ -262,6 +666,55 @@ pub mod lighting {
     
     /// Light falloff per block
     pub const LIGHT_FALLOFF: u8 = 1;
+
+    /// Maximum light level stored in the dedicated lighting buffer (8 bits per channel)
+    pub const DEDICATED_MAX_LIGHT_LEVEL: u8 = 255;
+
+    /// Stamp saved with baked chunk light. Bump whenever propagation rules or
+    /// block light properties change so old saves are relit on load.
+    pub const BAKED_LIGHT_VERSION: u32 = 1;
+
+    /// Brightest block light the default spawn rule accepts (darkness only)
+    pub const DEFAULT_SPAWN_MAX_BLOCK_LIGHT: u8 = 0;
+
+    /// Brightest sky light the default spawn rule accepts
+    pub const DEFAULT_SPAWN_MAX_SKY_LIGHT: u8 = 7;
+
+    /// Voxels around an edit whose AO can change: one for the AO kernel
+    /// plus one per smoothing pass of an incremental relight
+    pub const LIGHT_DIRTY_HALO: u32 = 2;
+
+    /// Smoothing passes run over dirty sub-volumes
+    pub const LIGHT_DIRTY_SMOOTH_PASSES: u32 = 1;
+
+    /// Two dirty regions are merged when their bounding box holds at most
+    /// this many times the voxels of both
+    pub const LIGHT_DIRTY_MERGE_WASTE: f32 = 1.5;
+
+    /// Threads per workgroup of the region AO kernels
+    pub const LIGHT_REGION_WORKGROUP_SIZE: u32 = 64;
+
+    /// Threads per axis of a workgroup of the fixture propagation kernel
+    pub const LIGHT_FIXTURE_WORKGROUP_SIZE: u32 = 4;
+
+    /// Threads per axis of a workgroup of the chunk light propagation kernel
+    pub const CHUNK_LIGHT_WORKGROUP_SIZE: u32 = 4;
+
+    /// Block ids covered by the chunk light kernel's block table; higher
+    /// ids are treated as opaque and dark
+    pub const CHUNK_LIGHT_BLOCK_TABLE_SIZE: usize = 256;
+
+    /// Edge of the chunk-sized lighting fixtures (voxels)
+    pub const LIGHT_FIXTURE_CHUNK_SIZE: u32 = 32;
+
+    /// Differing voxels listed per lighting fixture
+    pub const MAX_REPORTED_LIGHT_MISMATCHES: usize = 8;
+
+    /// Set to a light level difference the lighting harness accepts per voxel
+    pub const LIGHT_TOLERANCE_ENV_VAR: &str = "HEARTH_LIGHT_TOLERANCE";
+
+    /// Set to a voxel count the lighting harness accepts outside the tolerance
+    pub const LIGHT_MAX_MISMATCHES_ENV_VAR: &str = "HEARTH_LIGHT_MAX_MISMATCHES";
 }
 
 /// Weather system constants
@@ -369,6 +822,82 @@ pub mod persistence_constants {
     /// Compression magic bytes/headers
     pub const COMPRESSED_DATA_MAGIC: &[u8] = b"HCMP"; // Hearth Compressed
     pub const COMPRESSED_DATA_VERSION: u8 = 1;
+
+    /// Largest region a schematic may hold (voxels); 256³
+    pub const SCHEMATIC_MAX_VOLUME: u64 = 256 * 256 * 256;
+
+    /// Chunks per side of one modification journal region
+    pub const JOURNAL_REGION_SIZE_CHUNKS: i32 = 8;
+
+    /// How often buffered block edits are appended to disk (milliseconds)
+    pub const JOURNAL_FLUSH_INTERVAL_MS: u64 = 250;
+
+    /// Journal records in a region before it is compacted into full chunk saves
+    pub const JOURNAL_COMPACT_RECORDS: u64 = 8192;
+
+    /// Metadata file inside each world slot directory
+    pub const WORLD_METADATA_FILE: &str = "world.json";
+
+    /// Menu thumbnail inside each world slot directory
+    pub const WORLD_THUMBNAIL_FILE: &str = "thumbnail.png";
+
+    /// Version of the world slot metadata
+    pub const WORLD_SLOT_FORMAT_VERSION: u32 = 1;
+
+    /// Longest display name a world may have (characters)
+    pub const MAX_WORLD_NAME_LEN: usize = 64;
+
+    /// Longest edge of a stored world thumbnail (pixels)
+    pub const WORLD_THUMBNAIL_SIZE: u32 = 256;
+
+    /// Scoreboard file inside each world slot directory
+    pub const SCOREBOARD_FILE: &str = "scoreboard.bin";
+
+    /// Version of the saved scoreboard
+    pub const SCOREBOARD_FORMAT_VERSION: u32 = 1;
+
+    /// Random stream states inside each world slot directory
+    pub const WORLD_RANDOM_FILE: &str = "random.bin";
+
+    /// Version of the saved random stream states
+    pub const WORLD_RANDOM_FORMAT_VERSION: u32 = 1;
+
+    /// Trigger volumes inside each world slot directory
+    pub const TRIGGER_VOLUMES_FILE: &str = "triggers.bin";
+
+    /// Version of the saved trigger volumes
+    pub const TRIGGER_VOLUMES_FORMAT_VERSION: u32 = 1;
+
+    /// Region claims inside each world slot directory
+    pub const REGION_CLAIMS_FILE: &str = "claims.bin";
+
+    /// Version of the saved region claims
+    pub const REGION_CLAIMS_FORMAT_VERSION: u32 = 1;
+
+    /// Player statistics and achievements inside each world slot directory
+    pub const PLAYER_STATS_FILE: &str = "player_stats.bin";
+
+    /// Version of the saved player statistics
+    pub const PLAYER_STATS_FORMAT_VERSION: u32 = 1;
+
+    /// Directory the engine keeps its pipeline caches in, next to the
+    /// executable's working directory
+    pub const PIPELINE_CACHE_DIR: &str = "pipeline_cache";
+
+    /// Start of each adapter's pipeline cache file name in the cache directory
+    pub const PIPELINE_CACHE_FILE_PREFIX: &str = "pipelines";
+
+    /// Version of the saved pipeline cache
+    pub const PIPELINE_CACHE_FORMAT_VERSION: u32 = 1;
+
+    /// Extension of saved chunk files
+    pub const CHUNK_FILE_EXTENSION: &str = "chunk";
+
+    /// Entity records a world diff compares, inside each world slot directory
+    pub const WORLD_ENTITIES_FILE: &str = "entities.json";
+
+    /// Block deltas a world diff lists per chunk by default
+    pub const DIFF_MAX_BLOCK_DELTAS_PER_CHUNK: usize = 1024;
 }
 
 /// Event system constants
@@ -410,6 +939,10 @@ pub mod buffer_layouts {
     /// Size of a single voxel data element (u32)
     pub const VOXEL_DATA_SIZE: u64 = 4;
     
+    /// Size of a single voxel light element in the dedicated lighting buffer
+    /// (8-bit block light + 8-bit sky light)
+    pub const LIGHT_DATA_SIZE: u64 = 2;
+
     /// Size of chunk metadata structure (8 u32 fields = 32 bytes)
     pub const CHUNK_METADATA_SIZE: u64 = 32;
     
@@ -438,6 +971,9 @@ pub mod buffer_layouts {
     
     /// Size of a single chunk slot in world buffer
     pub const CHUNK_BUFFER_SLOT_SIZE: u64 = VOXELS_PER_CHUNK as u64 * VOXEL_DATA_SIZE;
+
+    /// Size of a single chunk slot in the dedicated lighting buffer
+    pub const CHUNK_LIGHT_SLOT_SIZE: u64 = VOXELS_PER_CHUNK as u64 * LIGHT_DATA_SIZE;
     
     /// Maximum chunks based on view distance
     pub const MAX_CHUNKS_VIEW_DISTANCE_3: u32 = 343; // (2*3+1)³ = 7³
@@ -500,17 +1036,19 @@ pub mod buffer_layouts {
     // ===== Helper Functions =====
     
     /// Calculate the number of chunks that fit in a given memory budget
+    /// (voxel slot plus dedicated lighting slot per chunk)
     pub fn chunks_per_memory_budget(budget_mb: u32) -> u32 {
         let budget_bytes = (budget_mb as u64) * 1024 * 1024;
-        let chunks = budget_bytes / CHUNK_BUFFER_SLOT_SIZE;
+        let chunks = budget_bytes / (CHUNK_BUFFER_SLOT_SIZE + CHUNK_LIGHT_SLOT_SIZE);
         chunks.min(u32::MAX as u64) as u32
     }
     
-    /// Calculate memory requirement for a given view distance
+    /// Calculate memory requirement for a given view distance, counting the
+    /// dedicated lighting buffer next to the voxel buffer
     pub fn memory_for_view_distance(view_distance: u32) -> u64 {
         let diameter = 2 * view_distance + 1;
         let max_chunks = diameter * diameter * diameter;
-        max_chunks as u64 * CHUNK_BUFFER_SLOT_SIZE
+        max_chunks as u64 * (CHUNK_BUFFER_SLOT_SIZE + CHUNK_LIGHT_SLOT_SIZE)
     }
     
     /// Get recommended view distance for available GPU memory
@@ -571,12 +1109,750 @@ pub mod monitoring {
     pub const DEFAULT_CPU_CRITICAL_PERCENT: f64 = 95.0;
     pub const DEFAULT_ERROR_RATE_WARNING: f64 = 1.0;      // 1 error per minute
     pub const DEFAULT_ERROR_RATE_CRITICAL: f64 = 5.0;     // 5 errors per minute
+    pub const DEFAULT_CHUNK_QUEUE_WARNING: f64 = 256.0;   // Pending chunk generations
+    pub const DEFAULT_GPU_LEAK_WARNING_MB: f64 = 0.0;     // Any leaked GPU allocation
+
+    /// Alert rule timing (in frames)
+    pub const DEFAULT_ALERT_SUSTAIN_FRAMES: u32 = 30;      // 0.5s at 60fps
+    pub const DEFAULT_ALERT_COOLDOWN_FRAMES: u32 = 120;    // 2s between mitigations
+
+    /// Built-in mitigation limits
+    pub const MITIGATION_PARTICLE_BUDGET_FACTOR: f32 = 0.75;
+    pub const MIN_PARTICLE_BUDGET: u32 = 10_000;
+    pub const MITIGATION_RENDER_SCALE_STEP: f32 = 0.1;
+    pub const MIN_RENDER_SCALE: f32 = 0.5;
     
     /// Memory layout optimization
     pub const CACHE_LINE_SIZE: usize = 64;                // CPU cache line size
     pub const METRIC_BUFFER_ALIGNMENT: usize = 64;        // Align to cache lines
 }
 
+/// Player death/respawn lifecycle
+pub mod player_lifecycle {
+    /// Health a player spawns with
+    pub const DEFAULT_MAX_HEALTH: f32 = 20.0;
+
+    /// Seconds of damage immunity after respawning
+    pub const DEFAULT_RESPAWN_INVULNERABILITY_SECS: f32 = 3.0;
+
+    /// Offset from an anchor block to the respawn position (on top, centered)
+    pub const ANCHOR_SPAWN_OFFSET: [f32; 3] = [0.5, 1.0, 0.5];
+}
+
+/// Entity health and environmental damage (voxels, seconds, health points)
+pub mod health {
+    /// Attribute holding an entity's current health
+    pub const HEALTH_ATTRIBUTE: &str = "health";
+
+    /// Attribute holding an entity's maximum health (buffs raise it)
+    pub const MAX_HEALTH_ATTRIBUTE: &str = "max_health";
+
+    /// Maximum health of an entity without modifiers
+    pub const DEFAULT_MAX_HEALTH: f32 = super::player_lifecycle::DEFAULT_MAX_HEALTH;
+
+    /// Damage immunity after each hit, so touching lava is not lethal in a frame
+    pub const DEFAULT_INVULNERABILITY_SECS: f32 = 0.5;
+
+    /// Falls up to this far do no damage
+    pub const SAFE_FALL_DISTANCE: f32 = 3.0;
+
+    /// Damage per voxel fallen beyond the safe distance
+    pub const FALL_DAMAGE_PER_VOXEL: f32 = 1.0;
+
+    /// Damage of each lava hit
+    pub const LAVA_CONTACT_DAMAGE: f32 = 4.0;
+
+    /// Breath held with the head in fluid before drowning starts
+    pub const MAX_AIR_SECS: f32 = 15.0;
+
+    /// Damage of each drowning hit
+    pub const DROWNING_DAMAGE: f32 = 2.0;
+
+    /// Time between drowning hits once out of air
+    pub const DROWNING_INTERVAL_SECS: f32 = 1.0;
+
+    /// Height below which entities take void damage
+    pub const VOID_Y: f32 = -64.0;
+
+    /// Damage of each void hit
+    pub const VOID_DAMAGE: f32 = 4.0;
+}
+
+/// Entity attributes (stats and modifiers)
+pub mod attributes {
+    /// Undrained effective value changes kept before the oldest are dropped
+    pub const MAX_PENDING_ATTRIBUTE_CHANGES: usize = 4096;
+}
+
+/// Gateway request/response calls
+pub mod gateway_rpc {
+    /// Requests queued or answered but not yet polled before new ones are refused
+    pub const MAX_IN_FLIGHT_REQUESTS: usize = 256;
+
+    /// Milliseconds a request may wait for an answer
+    pub const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 5000;
+
+    /// Requests answered per engine frame
+    pub const MAX_SERVED_PER_FRAME: usize = 64;
+
+    /// Blocks above and below the requested height searched for a spawn
+    pub const SPAWN_SEARCH_HEIGHT: i32 = 64;
+}
+
+/// Block drop tables and item entities
+pub mod loot {
+    /// Most rolls a single pool makes, whatever its table asks for
+    pub const MAX_LOOT_POOL_ROLLS: u32 = 64;
+
+    /// Half the edge length of a dropped item's bounding box (voxels)
+    pub const ITEM_ENTITY_HALF_EXTENT: f32 = 0.125;
+
+    /// Largest horizontal speed a dropped item pops out with (voxels/s)
+    pub const ITEM_POP_HORIZONTAL_SPEED: f32 = 1.5;
+
+    /// Upward speed a dropped item pops out with (voxels/s)
+    pub const ITEM_POP_VERTICAL_SPEED: f32 = 3.0;
+}
+
+/// Processes bound to blocks in the world
+pub mod process_anchor {
+    /// Gap between the top of an anchor block and its process indicator
+    /// (voxels)
+    pub const INDICATOR_HEIGHT: f32 = 3.0;
+}
+
+/// Objective scoreboards
+pub mod scoreboard {
+    /// Longest objective or player name (bytes)
+    pub const MAX_SCOREBOARD_NAME_LEN: usize = 64;
+
+    /// Unpublished deltas kept before the next update becomes a snapshot
+    pub const MAX_PENDING_SCORE_DELTAS: usize = 4096;
+}
+
+/// Block action cooldowns and rate limits
+pub mod block_action_limits {
+    /// Time to regain one block placement (milliseconds)
+    pub const PLACE_COOLDOWN_MS: u32 = 200;
+
+    /// Time to regain one block break (milliseconds)
+    pub const BREAK_COOLDOWN_MS: u32 = 150;
+
+    /// Time to regain one block interaction (milliseconds)
+    pub const INTERACT_COOLDOWN_MS: u32 = 250;
+
+    /// Actions a player can fire back to back after waiting
+    pub const ACTION_BURST: u32 = 2;
+}
+
+/// Persistent player statistics and achievements
+pub mod player_stats {
+    /// Longest stat, achievement or player name (bytes)
+    pub const MAX_STAT_NAME_LEN: usize = 64;
+
+    /// Seconds between automatic saves of changed statistics
+    pub const STATS_SAVE_INTERVAL_SECS: f32 = 60.0;
+
+    /// Blocks the player broke
+    pub const STAT_BLOCKS_BROKEN: &str = "blocks_broken";
+
+    /// Distance the player walked (voxels)
+    pub const STAT_DISTANCE_WALKED: &str = "distance_walked";
+
+    /// Time the player spent in the world (seconds)
+    pub const STAT_PLAY_TIME: &str = "play_time";
+}
+
+/// Data-driven entity behavior trees
+pub mod behavior_trees {
+    /// Most nodes a single tree may have
+    pub const MAX_BEHAVIOR_TREE_NODES: usize = 256;
+
+    /// Agents evaluated per update before the rest wait for the next one
+    pub const BEHAVIOR_TICKS_PER_UPDATE: u32 = 256;
+}
+
+/// World loading progress
+pub mod load_progress {
+    /// Share of the loading bar each phase fills, in `LoadPhase` order
+    /// (GPU init, world buffer, spawn chunks, lighting, entities)
+    pub const LOAD_PHASE_WEIGHTS: [f32; 5] = [0.1, 0.1, 0.5, 0.2, 0.1];
+
+    /// Spawn chunks that must be generated before the game gets control
+    pub const MIN_PLAYABLE_CHUNKS: u32 = 9;
+}
+
+/// Enter/exit trigger volumes
+pub mod trigger_volumes {
+    /// Longest trigger volume name (bytes)
+    pub const MAX_TRIGGER_NAME_LEN: usize = 64;
+
+    /// Spatial hash cells a volume may span before it is tested against
+    /// every position instead of being bucketed
+    pub const MAX_TRIGGER_VOLUME_CELLS: usize = 4096;
+}
+
+/// Region protection claims
+pub mod region_claims {
+    /// Longest claim or group name (bytes)
+    pub const MAX_CLAIM_NAME_LEN: usize = 64;
+
+    /// Largest claim a command may create (blocks)
+    pub const MAX_CLAIM_VOLUME: u64 = 1 << 30;
+}
+
+/// Ore vein placement
+pub mod ore_generation {
+    /// Edge of the cubic cells veins are seeded in (voxels)
+    pub const ORE_CELL_SIZE: i32 = 16;
+
+    /// Upper bound on veins of one ore seeded in a single cell
+    pub const MAX_VEINS_PER_CELL: u32 = 64;
+
+    /// Radius of streak veins (voxels)
+    pub const STREAK_RADIUS: f32 = 0.9;
+}
+
+/// World generation golden data
+pub mod golden_worldgen {
+    /// Bumped whenever the golden file layout changes; older files are rejected
+    pub const GOLDEN_FORMAT_VERSION: u32 = 1;
+
+    /// Edge of the fixture chunks (voxels)
+    pub const GOLDEN_CHUNK_SIZE: u32 = 32;
+
+    /// Mismatching voxels listed per drifted fixture
+    pub const MAX_REPORTED_MISMATCHES: usize = 8;
+
+    /// Set to rewrite the golden files from the current generator
+    pub const BLESS_ENV_VAR: &str = "HEARTH_BLESS_GOLDEN";
+
+    /// Set to a voxel count to accept that much drift per fixture
+    pub const TOLERANCE_ENV_VAR: &str = "HEARTH_GOLDEN_TOLERANCE";
+}
+
+/// Heightmap halos shared by neighbouring chunks during generation
+pub mod generation_halo {
+    /// Columns evaluated around each batch beyond its chunks; also the number
+    /// of erosion passes, since each pass reads one column further out
+    pub const HALO_WIDTH: u32 = 4;
+
+    /// Edge of the square chunk tiles batched under one heightmap (chunks)
+    pub const TILE_CHUNKS: i32 = 4;
+
+    /// Height difference between neighbouring columns that erosion leaves
+    /// alone (voxels)
+    pub const EROSION_TALUS: f32 = 1.0;
+
+    /// Share of the excess slope moved per erosion pass
+    pub const EROSION_RATE: f32 = 0.25;
+}
+
+/// Adaptive view distance controller
+pub mod view_distance {
+    /// Lowest distance the controller shrinks to (chunks)
+    pub const MIN_VIEW_DISTANCE: u32 = 2;
+
+    /// Frame time budget (milliseconds, 60 FPS)
+    pub const DEFAULT_FRAME_BUDGET_MS: f64 = 16.7;
+
+    /// Consecutive over-budget frames before shrinking (0.5s at 60fps)
+    pub const SHRINK_AFTER_FRAMES: u32 = 30;
+
+    /// Consecutive frames with headroom before growing (5s at 60fps)
+    pub const GROW_AFTER_FRAMES: u32 = 300;
+
+    /// Frame time below this fraction of the budget counts as headroom
+    pub const FRAME_HEADROOM_RATIO: f64 = 0.7;
+
+    /// GPU memory use that forces a shrink (percent of budget)
+    pub const MEMORY_PRESSURE_PERCENT: f64 = 90.0;
+
+    /// GPU memory use below which growing is allowed (percent of budget)
+    pub const MEMORY_HEADROOM_PERCENT: f64 = 70.0;
+
+    /// Frames between two distance changes
+    pub const CHANGE_COOLDOWN_FRAMES: u32 = 120;
+
+    /// Frames a failed allocation caps the distance below the failed value (1 min at 60fps)
+    pub const ALLOCATION_CEILING_FRAMES: u32 = 3600;
+
+    /// Frame times kept in `MetricsBuffers::frame_times`
+    pub const FRAME_TIME_HISTORY: usize = 100;
+}
+
+/// World simulation scaling (sleep mode for chunks without nearby players)
+pub mod simulation_scaling {
+    /// World ticks per second while any player is online
+    pub const ACTIVE_TICK_RATE: u32 = 20;
+
+    /// World ticks per second while every chunk is suspended
+    pub const SLEEP_TICK_RATE: u32 = 1;
+
+    /// Chunks within this distance of a player tick every tick
+    /// 16 chunks × 5m = 80m
+    pub const FULL_RATE_DISTANCE: u32 = 16;
+
+    /// Chunks within this distance tick at the reduced rate; beyond it they suspend
+    /// 48 chunks × 5m = 240m
+    pub const REDUCED_RATE_DISTANCE: u32 = 48;
+
+    /// Game ticks between two simulation steps of a reduced-rate chunk (2 Hz)
+    pub const REDUCED_TICK_INTERVAL: u64 = 10;
+
+    /// Most ticks a suspended chunk catches up on when it wakes (10 minutes)
+    pub const MAX_CATCH_UP_TICKS: u64 = 20 * 60 * 10;
+}
+
+/// Activation ranges (which systems run how far from players)
+pub mod activation_range {
+    /// Block random ticks run within this many chunks of a player
+    /// 8 chunks × 5m = 40m
+    pub const RANDOM_TICK_DISTANCE: u32 = 8;
+
+    /// Entity AI runs within this many chunks of a player
+    /// 6 chunks × 5m = 30m
+    pub const ENTITY_AI_DISTANCE: u32 = 6;
+
+    /// Physics simulates within this many chunks of a player
+    /// 10 chunks × 5m = 50m
+    pub const PHYSICS_DISTANCE: u32 = 10;
+
+    /// Chunks past a range an active chunk or entity stays active for
+    pub const HYSTERESIS: u32 = 1;
+
+    /// Voxels picked for a random tick in each active chunk per tick
+    pub const RANDOM_TICKS_PER_CHUNK: u32 = 3;
+}
+
+/// Frame limiter
+pub mod frame_pacing {
+    /// Frame rate while the window is unfocused
+    pub const UNFOCUSED_FPS: u32 = 10;
+
+    /// Final stretch before a deadline spent spinning instead of sleeping
+    /// (OS sleep granularity is ~1-2ms on most platforms)
+    pub const SPIN_THRESHOLD_MICROS: u64 = 2000;
+
+    /// A frame is counted as missed when it ends this late past its deadline
+    pub const MISSED_DEADLINE_TOLERANCE_MICROS: u64 = 500;
+
+    /// Highest accepted FPS cap
+    pub const MAX_FPS_CAP: u32 = 1000;
+
+    /// Fixed simulation ticks per second the render interpolation assumes
+    pub const FIXED_TICK_RATE: u32 = 60;
+
+    /// Most fixed ticks run in one frame; time beyond that is dropped so a
+    /// long stall does not turn into a burst of catch-up ticks
+    pub const MAX_TICKS_PER_FRAME: u32 = 8;
+}
+
+/// Texture streaming and mip residency
+pub mod texture_streaming {
+    /// Mip bytes uploaded per frame; a single larger mip still goes through
+    /// alone so big textures are not starved
+    pub const MAX_TEXTURE_UPLOAD_BYTES_PER_FRAME: u64 = 4 * 1024 * 1024;
+
+    /// GPU memory use (percent of budget) at which streamed mips are evicted,
+    /// below the monitor's critical level so eviction acts first
+    pub const TEXTURE_EVICT_MEMORY_PERCENT: f64 = 85.0;
+
+    /// Eviction frees memory down to this level, and finer mips stream back
+    /// in only once use is below it again
+    pub const TEXTURE_RESTORE_MEMORY_PERCENT: f64 = 70.0;
+
+    /// Frames without a reported use before a texture drops to its coarsest
+    /// streamed mip (5s at 60fps)
+    pub const TEXTURE_UNUSED_FRAMES: u64 = 300;
+
+    /// Camera distance (voxels) within which mip 0 is wanted; each doubling
+    /// of the distance wants one mip coarser
+    pub const DEFAULT_FULL_DETAIL_DISTANCE: f32 = 16.0;
+
+    /// Coarsest mips a streamed texture always keeps resident
+    pub const DEFAULT_MIN_RESIDENT_MIPS: u32 = 4;
+
+    /// Edge (pixels) of one block texture tile in the block atlas
+    pub const BLOCK_ATLAS_TILE_SIZE: u32 = 64;
+
+    /// Tiles per row of the block atlas
+    pub const BLOCK_ATLAS_COLUMNS: u32 = 16;
+
+    /// Color of atlas tiles no registered block uses
+    pub const BLOCK_ATLAS_EMPTY_TILE: [u8; 4] = [128, 128, 128, 255];
+}
+
+/// Per-frame bump arena for temporary slices
+pub mod frame_arena {
+    /// Initial arena size; it grows to the largest frame seen and keeps
+    /// that size afterwards
+    pub const DEFAULT_FRAME_ARENA_BYTES: usize = 1024 * 1024;
+}
+
+/// Procedural sky
+pub mod sky {
+    /// Angular radius of the sun disc (radians)
+    pub const SUN_ANGULAR_RADIUS: f32 = 0.035;
+
+    /// Angular radius of the moon disc (radians)
+    pub const MOON_ANGULAR_RADIUS: f32 = 0.025;
+
+    /// Star grid cells per unit of view direction
+    pub const STAR_GRID_SCALE: f32 = 180.0;
+
+    /// Fraction of star grid cells holding a star
+    pub const STAR_DENSITY: f32 = 0.02;
+
+    /// Sun elevation (sine) below which stars start to appear
+    pub const STAR_FADE_ELEVATION: f32 = 0.05;
+
+    /// Sun elevation (sine) at which the sky reaches full daylight
+    pub const DAYLIGHT_ELEVATION: f32 = 0.25;
+
+    /// Sun elevation (sine) at which the sky reaches full night
+    pub const NIGHT_ELEVATION: f32 = -0.15;
+
+    /// Cloud coverage of clear weather at 100% humidity
+    pub const CLEAR_HUMIDITY_COVERAGE: f32 = 0.3;
+
+    /// Strongest pull of the sky toward cloud grey at full coverage
+    pub const MAX_CLOUD_TINT: f32 = 0.8;
+}
+
+/// Cloud layer
+pub mod clouds {
+    /// Cloud plane altitude (voxels)
+    /// 210m × 10 voxels/m = 2,100 voxels, just above the highest terrain
+    pub const DEFAULT_ALTITUDE: f32 = 2100.0;
+
+    /// Apparent layer thickness used for shading (voxels)
+    pub const DEFAULT_THICKNESS: f32 = 120.0;
+
+    /// Noise frequency (cycles per voxel); ~80m between cloud cells
+    pub const NOISE_SCALE: f32 = 0.00125;
+
+    /// Half-width of the cloud plane around the camera (voxels)
+    pub const PLANE_HALF_EXTENT: f32 = 8000.0;
+
+    /// Density ramp width above the coverage threshold
+    pub const EDGE_SOFTNESS: f32 = 0.25;
+
+    /// Light removed under the densest clouds
+    pub const SHADOW_STRENGTH: f32 = 0.45;
+
+    /// Cloud opacity at full density
+    pub const OPACITY: f32 = 0.9;
+
+    /// Cloud drift relative to the surface wind speed
+    pub const WIND_DRIFT_SCALE: f32 = 1.5;
+}
+
+/// Sound occlusion (muffling through walls)
+pub mod audio {
+    /// Sources farther than this from the listener are not traced (voxels)
+    pub const MAX_OCCLUSION_TRACE_DISTANCE: f32 = 64.0;
+
+    /// Attenuation per fully absorbing voxel between source and listener
+    pub const OCCLUSION_DB_PER_VOXEL: f32 = 6.0;
+
+    /// Quietest an occluded source gets (linear gain)
+    pub const MIN_OCCLUDED_GAIN: f32 = 0.05;
+
+    /// Low-pass cutoff of an unobstructed source (Hz)
+    pub const OPEN_LOW_PASS_CUTOFF_HZ: f32 = 20_000.0;
+
+    /// Lowest low-pass cutoff of an occluded source (Hz)
+    pub const MIN_LOW_PASS_CUTOFF_HZ: f32 = 300.0;
+
+    /// Cutoff multiplier per fully absorbing voxel
+    pub const LOW_PASS_FALLOFF_PER_VOXEL: f32 = 0.35;
+
+    /// Movement of source or listener that forces a new trace (voxels)
+    pub const OCCLUSION_CACHE_MOVE_TOLERANCE: f32 = 0.25;
+
+    /// Block changes this close to a traced path invalidate it (voxels;
+    /// a path touches voxels whose centers lie up to ~0.87 away)
+    pub const OCCLUSION_INVALIDATION_RADIUS: f32 = 1.0;
+}
+
+/// Seeded per-world random streams
+pub mod world_random {
+    /// Terrain and structure placement
+    pub const TERRAIN_STREAM: &str = "terrain";
+
+    /// Loot rolls
+    pub const LOOT_STREAM: &str = "loot";
+
+    /// Weather changes and precipitation
+    pub const WEATHER_STREAM: &str = "weather";
+
+    /// Mob and NPC decisions
+    pub const AI_STREAM: &str = "ai";
+
+    /// Particle emission
+    pub const PARTICLE_STREAM: &str = "particles";
+
+    /// Voxels picked for block random ticks
+    pub const RANDOM_TICK_STREAM: &str = "random_ticks";
+}
+
+/// Entity tags
+pub mod entity_tags {
+    /// Distinct tag names per world; membership is one u64 bitset per entity
+    pub const MAX_ENTITY_TAGS: usize = 64;
+}
+
+/// GPU device and surface loss recovery
+pub mod device_recovery {
+    /// Consecutive frames a surface may report Lost/Outdated after being
+    /// reconfigured before the whole device is recreated
+    pub const SURFACE_LOSS_RECOVERY_FRAMES: u32 = 3;
+}
+
+/// Trace capture and export
+pub mod profiling {
+    /// Events kept per capture; a long capture drops the rest
+    pub const MAX_TRACE_EVENTS: usize = 1 << 20;
+
+    /// GPU passes timed per submission
+    pub const MAX_TIMED_GPU_PASSES: u32 = 32;
+
+    /// File written by `trace stop` without a path, per format
+    pub const DEFAULT_CHROME_TRACE_PATH: &str = "trace.json";
+    pub const DEFAULT_PERFETTO_TRACE_PATH: &str = "trace.perfetto-trace";
+}
+
+/// Structured logging
+pub mod logging {
+    /// Log records kept in memory for the console and crash reports
+    pub const DEFAULT_HISTORY_CAPACITY: usize = 4096;
+
+    /// Most recent records appended to a crash report
+    pub const CRASH_REPORT_ENTRIES: usize = 200;
+
+    /// Records shown by the console's `log show` without a count
+    pub const DEFAULT_VIEW_ENTRIES: usize = 50;
+
+    /// Minimum time between two rate-limited messages from one call site (ms)
+    pub const DEFAULT_RATE_LIMIT_MS: u64 = 1000;
+
+    /// Level used when neither RUST_LOG nor a filter spec is given
+    pub const DEFAULT_FILTER_SPEC: &str = "info";
+}
+
+/// Engine feature flags
+pub mod feature_flags {
+    /// Override file read at startup, next to the executable's working directory
+    pub const CONFIG_FILE: &str = "features.toml";
+
+    /// Table in the override file holding `flag = true/false` entries
+    pub const CONFIG_TABLE: &str = "features";
+}
+
+/// Anti-aliasing modes for the main pass
+pub mod anti_aliasing {
+    /// MSAA sample count requested when no count is given
+    pub const DEFAULT_MSAA_SAMPLES: u32 = 4;
+
+    /// Highest MSAA sample count the renderer will request
+    pub const MAX_MSAA_SAMPLES: u32 = 8;
+
+    /// Weight of the current frame when blending with the TAA history
+    pub const TAA_HISTORY_BLEND: f32 = 0.1;
+
+    /// Frames in the Halton jitter sequence before it repeats
+    pub const TAA_JITTER_SEQUENCE_LENGTH: u64 = 8;
+
+    /// Local contrast (relative to the brightest neighbour) FXAA treats as an edge
+    pub const FXAA_EDGE_THRESHOLD: f32 = 0.125;
+
+    /// Contrast below which FXAA skips dark areas entirely
+    pub const FXAA_EDGE_THRESHOLD_MIN: f32 = 0.0312;
+
+    /// Longest FXAA search along an edge (pixels)
+    pub const FXAA_SPAN_MAX: f32 = 8.0;
+
+    /// Lowest internal resolution scale the scene is drawn at
+    pub const MIN_RENDER_SCALE: f32 = 0.25;
+}
+
+/// Edge highlight post pass (accessibility outlines, mesh topology view)
+pub mod edge_highlight {
+    /// Outline color: high-contrast yellow, RGBA
+    pub const DEFAULT_COLOR: [f32; 4] = [1.0, 0.85, 0.0, 1.0];
+
+    /// Outline width in pixels
+    pub const DEFAULT_THICKNESS: f32 = 1.5;
+
+    /// Thickest outline allowed (pixels)
+    pub const MAX_THICKNESS: f32 = 4.0;
+
+    /// Depth step, relative to the nearer depth, treated as an edge
+    pub const DEFAULT_DEPTH_THRESHOLD: f32 = 0.05;
+
+    /// Normal change (1 - cosine of the angle) treated as an edge
+    pub const DEFAULT_NORMAL_THRESHOLD: f32 = 0.4;
+
+    /// How far the topology view darkens the scene behind its edges
+    pub const TOPOLOGY_BACKGROUND_DIM: f32 = 0.75;
+}
+
+/// Chunk mesh LOD levels and the skirts stitching them together
+pub mod chunk_lod {
+    /// Coarsest chunk LOD level; each level doubles the cell size
+    pub const MAX_CHUNK_LOD: u32 = 4;
+
+    /// Skirt depth in cells of the chunk's own level
+    pub const SKIRT_DEPTH_CELLS: f32 = 1.0;
+
+    /// Distance from a chunk face (voxels) within which a vertex lies on it
+    pub const BOUNDARY_EPSILON: f32 = 1e-3;
+}
+
+/// Input contexts stacked over gameplay (console, menus, chat)
+pub mod input_contexts {
+    /// Context at the bottom of every stack; never popped
+    pub const GAMEPLAY_CONTEXT: &str = "gameplay";
+
+    /// Menus and other UI screens
+    pub const UI_CONTEXT: &str = "ui";
+
+    /// Debug console and chat: captures typed text
+    pub const CONSOLE_CONTEXT: &str = "console";
+
+    /// Contexts a stack may hold, including gameplay
+    pub const MAX_CONTEXT_DEPTH: usize = 16;
+}
+
+/// Grass tufts, flowers and pebbles added after chunks first render
+pub mod decoration {
+    /// Seconds a chunk is on screen before it is decorated
+    pub const DECORATION_DELAY_SECS: f32 = 1.0;
+
+    /// Time decoration passes may use per frame (ms)
+    pub const DECORATION_FRAME_BUDGET_MS: f32 = 1.0;
+
+    /// Decoration passes run per frame at most, however cheap
+    pub const DECORATION_PASSES_PER_FRAME: u32 = 4;
+
+    /// Share of exposed grass that grows a tuft
+    pub const TALL_GRASS_CHANCE: f32 = 0.3;
+
+    /// Share of the remaining exposed grass that grows a flower
+    pub const FLOWER_CHANCE: f32 = 0.04;
+
+    /// Share of exposed stone, dirt and sand topped with a pebble
+    pub const PEBBLE_CHANCE: f32 = 0.01;
+}
+
+/// Gamepad rumble patterns for gameplay events
+pub mod haptics {
+    /// Rumbles playing on one gamepad at once; the oldest is dropped
+    pub const MAX_ACTIVE_RUMBLES: usize = 8;
+
+    /// Motor change smaller than this is not sent to the device again
+    pub const MOTOR_EPSILON: f32 = 0.01;
+
+    /// Short high-frequency tick when a block breaks
+    pub const BLOCK_BREAK_WEAK: f32 = 0.3;
+    pub const BLOCK_BREAK_SECS: f32 = 0.08;
+
+    /// Damage at or above which the damage rumble is at full strength
+    pub const DAMAGE_FULL_RUMBLE: f32 = 10.0;
+    pub const DAMAGE_SECS: f32 = 0.3;
+    pub const DAMAGE_RELEASE_SECS: f32 = 0.15;
+
+    /// Explosion rumble at intensity 1.0
+    pub const EXPLOSION_SECS: f32 = 0.7;
+    pub const EXPLOSION_RELEASE_SECS: f32 = 0.5;
+    /// High-frequency motor share of an explosion's strength
+    pub const EXPLOSION_WEAK_SHARE: f32 = 0.6;
+}
+
+/// Secondary viewpoints rendered to textures (portals, cameras, map items)
+pub mod secondary_views {
+    /// Views a renderer may hold at once
+    pub const MAX_SECONDARY_VIEWS: usize = 16;
+
+    /// Views rendered in one frame when the budget allows
+    pub const DEFAULT_MAX_VIEWS_PER_FRAME: u32 = 2;
+
+    /// Pixels rendered into secondary views per frame at full load factor
+    pub const DEFAULT_PIXEL_BUDGET: u64 = 1024 * 1024;
+
+    /// Frame time above which the pixel budget starts shrinking (ms)
+    pub const DEFAULT_TARGET_FRAME_MS: f32 = 16.7;
+
+    /// Smallest fraction of the pixel budget kept under heavy load
+    pub const MIN_LOAD_FACTOR: f32 = 0.125;
+
+    /// Load factor regained per frame that meets the target frame time
+    pub const LOAD_RECOVERY_PER_FRAME: f32 = 0.05;
+
+    /// Highest degradation level; each level doubles a view's interval
+    pub const MAX_DEGRADATION_LEVEL: u32 = 3;
+
+    /// Culling radius of a view created with the default config (chunks)
+    pub const DEFAULT_VIEW_DISTANCE_CHUNKS: u32 = 4;
+}
+
+/// Parallel command recording of independent render passes
+pub mod parallel_encoding {
+    /// Fewer independent passes than this are recorded on the main thread,
+    /// where a worker hand-off would cost more than it saves
+    pub const MIN_PARALLEL_PASSES: usize = 2;
+
+    /// Passes one worker records before another worker is used
+    pub const PASSES_PER_WORKER: usize = 1;
+}
+
+/// Entity level of detail: distance bands, update intervals (voxels, frames)
+pub mod entity_lod {
+    /// Full meshes and animation up to this distance (16 m)
+    pub const FULL_DETAIL_DISTANCE: f32 = 160.0;
+
+    /// Simplified meshes up to this distance (48 m)
+    pub const SIMPLIFIED_DISTANCE: f32 = 480.0;
+
+    /// Billboard impostors up to this distance; entities beyond are culled (128 m)
+    pub const IMPOSTOR_DISTANCE: f32 = 1280.0;
+
+    /// Distance past a band edge before an entity changes band
+    pub const BAND_HYSTERESIS: f32 = 8.0;
+
+    /// Animation runs every N frames in the simplified band
+    pub const SIMPLIFIED_ANIMATION_INTERVAL: u32 = 2;
+
+    /// AI runs every N ticks in the simplified band
+    pub const SIMPLIFIED_AI_INTERVAL: u32 = 2;
+
+    /// AI runs every N ticks for impostors
+    pub const IMPOSTOR_AI_INTERVAL: u32 = 4;
+
+    /// AI runs every N ticks for entities beyond the impostor band
+    pub const CULLED_AI_INTERVAL: u32 = 8;
+
+    /// Bounding sphere of entities without their own (a player-sized box)
+    pub const DEFAULT_ENTITY_RADIUS: f32 = 10.0;
+}
+
+/// Seasons layered on the day/night cycle
+pub mod seasons {
+    /// In-game days each season lasts
+    pub const DAYS_PER_SEASON: u32 = 7;
+
+    /// Days at the start of a season over which its parameters fade in
+    /// from the previous season's
+    pub const SEASON_BLEND_DAYS: f32 = 2.0;
+
+    /// Weather types a season weighs (`weather::WEATHER_CLEAR` ..= `WEATHER_BLIZZARD`)
+    pub const WEATHER_TYPE_COUNT: usize = 8;
+
+    /// Lowest snow cover that places snow on the surface (0.0 - 1.0)
+    pub const MIN_SNOW_COVER: f32 = 0.05;
+}
+
 /// Block ID constants - Wrapped in BlockId type for type safety
 /// This requires importing BlockId from the crate when used
 pub mod typed_blocks {
@@ -621,6 +1897,11 @@ pub mod typed_blocks {
 
 /// Generate WGSL constants file content
 pub fn generate_wgsl_constants() -> String {
+    generate_wgsl_constants_for_chunk_size(core::CHUNK_SIZE)
+}
+
+/// Generate WGSL constants for a world using `chunk_size` voxels per chunk edge
+pub fn generate_wgsl_constants_for_chunk_size(chunk_size: u32) -> String {
     format!(r#"// AUTO-GENERATED GPU CONSTANTS - DO NOT EDIT
 // Generated from constants.rs
 
@@ -687,6 +1968,10 @@ const SNOW_HEIGHT_TYPICAL_HIGH: i32 = {}i;
 const TERRAIN_THRESHOLD: i32 = {}i;
 const SEA_LEVEL: i32 = {}i;
 
+// Far terrain surface modes
+const FAR_SURFACE_REFERENCE: u32 = {}u;
+const FAR_SURFACE_FLAT: u32 = {}u;
+
 // World dimensions
 const WORLD_SIZE: u32 = {}u;
 const WORLD_HEIGHT: u32 = {}u;
@@ -703,9 +1988,9 @@ const MAX_RENDER_DISTANCE: f32 = 500.0;
 const MAX_VERTICES_PER_CHUNK: u32 = {}u;
 const MAX_INDICES_PER_CHUNK: u32 = {}u;
 "#, 
-        core::CHUNK_SIZE,
-        core::CHUNK_SIZE_F32,
-        core::VOXELS_PER_CHUNK,
+        chunk_size,
+        chunk_size as f32,
+        chunk_size * chunk_size * chunk_size,
         core::MAX_WORLD_SIZE,
         core::MAX_BLOCK_DISTRIBUTIONS as u32,
         blocks::AIR as u32,
@@ -750,9 +2035,122 @@ const MAX_INDICES_PER_CHUNK: u32 = {}u;
         weather::SNOW_HEIGHT_TYPICAL_HIGH,
         terrain::TERRAIN_THRESHOLD,
         terrain::SEA_LEVEL,
+        far_terrain::SURFACE_REFERENCE,
+        far_terrain::SURFACE_FLAT,
         core::MAX_WORLD_SIZE,
         256u32, // WORLD_HEIGHT (hardcoded for now)
         buffer_layouts::MAX_VERTICES_PER_MESH,
         buffer_layouts::MAX_INDICES_PER_MESH,
     )
-}
\ No newline at end of file
+}
+/// Grass, flower and rock instances streamed around the camera
+pub mod prop_instances {
+    /// Width of a streaming cell (voxels)
+    pub const PROP_CELL_SIZE: u32 = 32;
+
+    /// Cells kept loaded on each side of the camera's cell
+    pub const PROP_RING_RADIUS: u32 = 6;
+
+    /// Extra cells past the ring before a cell unloads (avoids churn at
+    /// the edge)
+    pub const PROP_UNLOAD_MARGIN: u32 = 1;
+
+    /// Cells generated per streaming update at most
+    pub const PROP_CELLS_PER_UPDATE: usize = 4;
+
+    /// Share of grass columns with a grass instance
+    pub const GRASS_PROP_CHANCE: f32 = 0.15;
+
+    /// Share of grass columns with a flower instance
+    pub const FLOWER_PROP_CHANCE: f32 = 0.02;
+
+    /// Share of grass, dirt and stone columns with a rock instance
+    pub const ROCK_PROP_CHANCE: f32 = 0.004;
+
+    /// Whether rocks get colliders; grass and flowers never do
+    pub const PROPS_COLLIDE: bool = false;
+
+    /// `ChunkInstance::flags` bit marking a prop cell in the culling buffer
+    pub const PROP_CELL_CULL_FLAG: u32 = 1 << 8;
+}
+
+/// Batched process timer updates (`process::submit_process_batch_to_gpu`)
+pub mod process_batch {
+    /// Threads per workgroup of the process timer shader
+    pub const PROCESS_WORKGROUP_SIZE: u32 = 64;
+
+    /// Fewer processes than this run on the CPU even with a GPU attached;
+    /// the upload and readback cost more than they save
+    pub const MIN_GPU_PROCESS_BATCH: usize = 4096;
+
+    /// Processes the GPU buffers hold before they first grow
+    pub const INITIAL_PROCESS_GPU_CAPACITY: u32 = 4096;
+}
+
+/// Smooth terrain mode (`renderer::smooth_terrain_operations`)
+pub mod smooth_terrain {
+    /// Voxels sampled beyond each chunk face, so the distance fields of
+    /// neighboring chunks agree along their shared faces
+    pub const SDF_PADDING: u32 = 2;
+
+    /// Signed distances are clamped to this many voxels
+    pub const MAX_SDF_DISTANCE: f32 = 64.0;
+
+    /// Distance (voxels) within which a projected vertex counts as lying
+    /// on the surface; vertices farther off are dropped
+    pub const SURFACE_TOLERANCE: f32 = 0.1;
+
+    /// Chunks the engine tessellates per frame; the rest wait in the queue
+    pub const SMOOTH_CHUNKS_PER_FRAME: usize = 8;
+
+    /// Viewport height (pixels) the engine's tessellation error targets
+    pub const TESSELLATION_VIEWPORT_HEIGHT: f32 = 1080.0;
+}
+
+/// World the engine streams around the camera (`engine_world_operations`)
+pub mod engine_world {
+    /// Seed of the engine's world generator
+    pub const DEFAULT_WORLD_SEED: u32 = 12345;
+
+    /// Chunks generated and loaded per frame at most
+    pub const CHUNKS_LOADED_PER_FRAME: usize = 8;
+
+    /// Radius (chunks) of the voxel data kept on the CPU and in the GPU world
+    /// buffer for edits, physics, queries and meshing. The buffer holds
+    /// (2r+1)³ = 125 full 50³ slots, under the 128 MB binding limit, which
+    /// covers the sphere of r + `UNLOAD_MARGIN_CHUNKS` (123 chunks).
+    pub const SIMULATION_RADIUS_CHUNKS: u32 = 2;
+
+    /// Chunks beyond the simulation radius kept loaded before they are dropped
+    pub const UNLOAD_MARGIN_CHUNKS: u32 = 1;
+
+    /// Chunk columns the engine's CPU shadow cache of the GPU world keeps
+    pub const SHADOW_CACHE_COLUMNS: usize = 64;
+
+    /// Shadow cache chunk readbacks recorded per frame at most
+    pub const SHADOW_READBACKS_PER_FRAME: usize = 4;
+
+    /// Chunks relit per frame at most; each takes chunk size + 16
+    /// relaxation dispatches over its voxels
+    pub const LIGHT_CHUNKS_PER_FRAME: usize = 2;
+
+    /// Indirect draws of chunk meshes in the mesh arenas; covers the loaded
+    /// sphere of the simulation radius plus its unload margin
+    pub const MESH_ARENA_DRAW_SLOTS: u32 = 256;
+
+    /// Time an idle frame may spend compacting the mesh arenas
+    pub const MESH_DEFRAG_BUDGET_MS: u64 = 1;
+
+    /// Bytes an idle frame may move while compacting each mesh arena
+    pub const MESH_DEFRAG_BUDGET_BYTES: u64 = 8 * 1024 * 1024;
+
+    /// Width of a chunk LOD band in chunks: chunks this far from the camera
+    /// chunk (on their farthest axis) are meshed one level coarser per band
+    pub const CHUNK_LOD_BAND_CHUNKS: i32 = 1;
+
+    /// Subdirectory of a world save holding the chunk files
+    pub const SAVE_CHUNK_DIRECTORY: &str = "chunks";
+
+    /// Subdirectory of a world save holding the block edit journals
+    pub const SAVE_JOURNAL_DIRECTORY: &str = "journal";
+}
@@ -6,6 +6,7 @@
 //! - Functions operate on this data
 //! - No methods, just pure data structures
 
+use crate::entity_tag_data::{EntityTagId, EntityTagMask};
 use crate::world::core::{BlockId, ChunkPos, VoxelPos, PhysicsProperties, RenderData};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
@@ -28,6 +29,12 @@ pub struct EngineBuffers {
     /// Physics data buffer
     pub physics: PhysicsBuffers,
 
+    /// Entity transforms of the last two ticks, for render interpolation
+    pub transforms: TransformBuffers,
+
+    /// Entity tags and per-tag member lists
+    pub tags: EntityTagBuffers,
+
     /// Input state buffer
     pub input: InputBuffers,
 
@@ -155,6 +162,9 @@ pub struct RenderBuffers {
 
     /// Render statistics
     pub stats: RenderStats,
+
+    /// Internal resolution scale (1.0 = native), lowered by adaptive quality
+    pub render_scale: f32,
 }
 
 /// Mesh data for a chunk
@@ -219,6 +229,54 @@ pub struct PhysicsBuffers {
     pub time_accumulator: f32,
 }
 
+/// Entity transforms at the last two fixed ticks
+///
+/// Simulation writes `current_*` every tick after copying it to
+/// `previous_*`; each frame the renderer reads the `interpolated_*` arrays,
+/// blended by `alpha` from the frame pacer. Indices match `PhysicsBuffers`.
+#[derive(Clone, Default)]
+pub struct TransformBuffers {
+    /// Positions at the previous tick (SOA)
+    pub previous_positions: Vec<[f32; 3]>,
+
+    /// Positions at the latest tick (SOA)
+    pub current_positions: Vec<[f32; 3]>,
+
+    /// Rotations at the previous tick, quaternion x, y, z, w (SOA)
+    pub previous_rotations: Vec<[f32; 4]>,
+
+    /// Rotations at the latest tick, quaternion x, y, z, w (SOA)
+    pub current_rotations: Vec<[f32; 4]>,
+
+    /// Blend factor used for the interpolated arrays (0 = previous, 1 = current)
+    pub alpha: f32,
+
+    /// Positions to render this frame (SOA)
+    pub interpolated_positions: Vec<[f32; 3]>,
+
+    /// Rotations to render this frame (SOA)
+    pub interpolated_rotations: Vec<[f32; 4]>,
+}
+
+/// Entity tags (see `entity_tag_operations`)
+///
+/// Indices of `masks` match `PhysicsBuffers`; entities past its end have
+/// no tags.
+#[derive(Clone, Default)]
+pub struct EntityTagBuffers {
+    /// Interned tag names, indexed by tag id
+    pub names: Vec<String>,
+
+    /// Tag ids by name
+    pub ids: HashMap<String, EntityTagId>,
+
+    /// Tags carried by each entity (SOA)
+    pub masks: Vec<EntityTagMask>,
+
+    /// Entities carrying each tag, indexed by tag id (unordered)
+    pub members: Vec<Vec<u32>>,
+}
+
 /// Axis-Aligned Bounding Box
 #[derive(Clone, Copy, Debug)]
 pub struct AABB {
@@ -306,11 +364,14 @@ pub struct NetworkStats {
 }
 
 /// Particle system buffers
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct ParticleBuffers {
     /// Active particle count
     pub particle_count: u32,
 
+    /// Maximum live particles, lowered by adaptive quality
+    pub particle_budget: u32,
+
     /// Particle positions (SOA)
     pub positions: Vec<[f32; 3]>,
 
@@ -344,6 +405,33 @@ pub struct MetricsBuffers {
 
     /// Active threads
     pub thread_count: u32,
+
+    /// Mesh vertex arena fragmentation (0.0 = compact, 1.0 = fully scattered)
+    pub mesh_arena_fragmentation: f32,
+
+    /// Frame limiter statistics (missed deadlines, throttling)
+    pub frame_pacing: crate::renderer::FramePacingStats,
+
+    /// Batched chunk upload counters and bandwidth (`WorldBuffer::chunk_upload_stats`)
+    pub chunk_uploads: crate::world::storage::ChunkUploadStats,
+
+    /// Enabled feature flags and toggle counters (`record_feature_flag_metrics`)
+    pub feature_flags: crate::feature_flags::FeatureFlagMetrics,
+
+    /// Texture residency and upload counters (`record_texture_streaming_metrics`)
+    pub texture_streaming: crate::renderer::TextureStreamingStats,
+
+    /// Frame arena allocation counters of the last frame (`record_frame_arena_metrics`)
+    pub frame_arena: crate::memory::FrameArenaStats,
+
+    /// Tracked GPU allocations and leaks (`record_gpu_allocation_metrics`)
+    pub gpu_allocations: crate::memory::GpuAllocationStats,
+
+    /// Entities per LOD band and throttled updates (`record_entity_lod_metrics`)
+    pub entity_lod: crate::renderer::EntityLodStats,
+    /// Startup pipeline cache status and creation time
+    /// (`record_pipeline_cache_metrics`)
+    pub pipeline_cache: crate::renderer::PipelineCacheStats,
 }
 
 /// Thread-safe shared buffers (Arc<RwLock<>>)
@@ -373,6 +461,7 @@ pub fn create_engine_buffers() -> EngineBuffers {
             frame_count: 0,
             delta_time: 0.0,
             stats: RenderStats::default(),
+            render_scale: 1.0,
         },
         physics: PhysicsBuffers {
             entity_count: 0,
@@ -385,6 +474,8 @@ pub fn create_engine_buffers() -> EngineBuffers {
             physics_tick: 0,
             time_accumulator: 0.0,
         },
+        transforms: TransformBuffers::default(),
+        tags: EntityTagBuffers::default(),
         input: InputBuffers::default(),
         network: NetworkBuffers::default(),
         particles: ParticleBuffers::default(),
@@ -403,6 +494,20 @@ impl Default for EngineBuffers {
     }
 }
 
+impl Default for ParticleBuffers {
+    fn default() -> Self {
+        Self {
+            particle_count: 0,
+            particle_budget: crate::particles::particle_data::MAX_PARTICLES as u32,
+            positions: Vec::new(),
+            velocities: Vec::new(),
+            lifetimes: Vec::new(),
+            ages: Vec::new(),
+            types: Vec::new(),
+        }
+    }
+}
+
 impl Default for ChunkFlags {
     fn default() -> Self {
         Self {
@@ -0,0 +1,133 @@
+//! Engine GPU World Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! GPU copy of the engine world: the voxel buffer the loaded chunks are
+//! uploaded into, the mesher that turns them into geometry (with LOD skirts
+//! between bands), the props streamed around the camera and the culling
+//! that picks the chunk meshes and prop cells drawn from the camera. With
+//! smooth terrain on, ground blocks are left out of the cubes and drawn
+//! from distance fields kept in step with the chunks. Exists while a
+//! renderer is attached; the operations live in
+//! engine_gpu_world_operations.rs
+
+use crate::gpu::automation::CustomPassRegistryData;
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use crate::renderer::chunk_lod_stitch_data::ChunkLodStitchData;
+use crate::renderer::gpu_culling::{ChunkCulling, InstanceStreamer, VisibilityGraphData};
+use crate::renderer::gpu_meshing::{GpuMeshingState, MeshArena};
+use crate::renderer::prop_instance_data::PropInstanceData;
+use crate::renderer::smooth_terrain_data::SmoothTerrainData;
+use crate::world::compute::{ChunkConnectivityCompute, ChunkModifier, GpuChunkLight};
+use crate::world::core::ChunkPos;
+use crate::world::storage::{SharedShadowCache, VoxelInspectorData, WorldBuffer};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Upload and meshing counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngineGpuWorldStats {
+    pub chunks_uploaded: u64,
+    pub chunks_meshed: u64,
+    pub chunks_released: u64,
+    /// Block edits applied on the GPU instead of re-uploading their chunk
+    pub blocks_modified: u64,
+    pub chunks_lit: u64,
+    /// Chunks uploaded with the light baked into their save, unlit
+    pub chunks_light_restored: u64,
+    /// Game compute passes encoded, over all stages
+    pub custom_passes_encoded: u64,
+    /// Meshed chunks the last cave culling traversal did not reach
+    pub chunks_cave_culled: u32,
+    /// Meshes relocated by mesh arena defragmentation
+    pub meshes_defragmented: u64,
+    /// Meshed chunks with skirts toward a finer neighbor
+    pub chunks_stitched: u32,
+    /// Prop instances streamed around the camera
+    pub prop_instances: u32,
+    /// Chunks with a smooth surface uploaded
+    pub smooth_chunks: u32,
+    /// Smooth surfaces tessellated, including rebuilds after edits
+    pub smooth_chunks_tessellated: u64,
+}
+
+/// Smooth surface of a chunk on the GPU
+pub struct SmoothChunkDraw {
+    /// `Vertex` per surface vertex, in the chunk's space
+    pub vertices: wgpu::Buffer,
+    pub indices: wgpu::Buffer,
+    /// Per vertex: its block id with its blend neighbor's id in the high
+    /// half, then the blend weight's bits
+    pub materials: wgpu::Buffer,
+    pub index_count: u32,
+    /// Released when the chunk's surface is dropped or rebuilt
+    pub gpu_allocations: Vec<GpuAllocationGuard>,
+}
+
+/// GPU world state owned by the engine
+pub struct EngineGpuWorldData {
+    pub world_buffer: WorldBuffer,
+    /// Mesher fed from `world_buffer`; allocates from the engine frame arena
+    pub meshing: GpuMeshingState,
+    /// Applies edits of resident chunks to `world_buffer` and mirrors them
+    /// into `shadow`
+    pub modifier: ChunkModifier,
+    /// Lights chunks into the lighting storage of `world_buffer`; None when
+    /// its shader failed to build, in which case chunks are meshed unlit
+    pub chunk_light: Option<GpuChunkLight>,
+    /// Game compute passes, encoded after uploads (PostGeneration), before
+    /// relighting (PreLighting) and once per fixed tick (PerTick)
+    pub custom_passes: CustomPassRegistryData,
+    /// Face connectivity of resident chunks, shared with generators
+    /// through `Engine::chunk_connectivity`
+    pub connectivity: Arc<ChunkConnectivityCompute>,
+    /// Connectivity read back so far; cave culling floods it from the
+    /// camera chunk every frame
+    pub visibility: VisibilityGraphData,
+    /// Frustum culls the draws cave culling kept; None when its shader
+    /// failed to build
+    pub chunk_culling: Option<ChunkCulling>,
+    /// Grass, flower and rock cells streamed in a ring around the camera
+    pub props: PropInstanceData,
+    /// GPU copy of the streamed props
+    pub prop_streamer: InstanceStreamer,
+    /// Frustum culls the prop cell draws; None when its shader failed to
+    /// build
+    pub prop_culling: Option<ChunkCulling>,
+    /// Distance fields of the resident chunks with smooth blocks, rebuilt
+    /// on upload and edit; empty while smooth terrain is off
+    pub smooth: SmoothTerrainData,
+    /// Tessellated smooth surfaces
+    pub smooth_draws: HashMap<ChunkPos, SmoothChunkDraw>,
+    /// CPU view of `world_buffer` for block and light queries, shared with
+    /// the game gateway
+    pub shadow: SharedShadowCache,
+    /// Readbacks of `world_buffer` requested by the `inspect` command
+    pub inspector: VoxelInspectorData,
+    /// Chunks uploaded (or queued for upload) to `world_buffer`
+    pub resident: HashSet<ChunkPos>,
+    /// Resident chunks whose queued upload has not been flushed yet
+    pub uploading: HashSet<ChunkPos>,
+    /// Chunks whose voxels changed on the GPU and wait to be relit
+    pub light_queue: Vec<ChunkPos>,
+    /// Chunks whose voxels and light reached the GPU and wait to be meshed
+    pub mesh_queue: Vec<ChunkPos>,
+    /// Mesh buffer index of every meshed chunk
+    pub meshed: HashMap<ChunkPos, u32>,
+    /// LOD level every chunk was meshed at, from its band around the camera
+    /// chunk, and the skirts it needs toward finer neighbors
+    pub lod: ChunkLodStitchData,
+    /// Vertices of the meshed chunks, copied out of the mesher's buffer
+    pub vertex_arena: MeshArena,
+    /// Indices of the meshed chunks, relative to their first vertex
+    pub index_arena: MeshArena,
+    /// One indexed indirect draw per draw slot, patched when the arenas
+    /// are compacted; unused slots draw nothing
+    pub mesh_draws: wgpu::Buffer,
+    pub mesh_draws_allocation: GpuAllocationGuard,
+    /// Draw slot of every chunk with a mesh in the arenas
+    pub draw_slots: HashMap<ChunkPos, u32>,
+    pub free_draw_slots: Vec<u32>,
+    pub stats: EngineGpuWorldStats,
+    /// Owner of the engine's own draw buffers in the GPU allocation tracker
+    pub gpu_owner: GpuOwnerGuard,
+}
@@ -0,0 +1,1053 @@
+//! Engine GPU World Operations - Pure DOP
+//!
+//! Mirrors the engine's loaded chunks into the GPU world buffer each frame
+//! and lights and meshes the chunks whose voxels arrived. Edits of resident
+//! chunks go through the chunk modifier; the shadow cache is kept in step
+//! with every upload, edit, relight and release and its readbacks are
+//! serviced here. Game compute passes are encoded at their frame stage.
+//! Meshes are copied into the vertex and index arenas, which idle frames
+//! compact under a time budget. Chunks are meshed at the LOD of their band
+//! around the camera chunk; the LOD tracker hands the mesher the faces that
+//! border a finer chunk, which get skirts, and queues neighbors whose
+//! skirts changed to be meshed again. After the sync, cave culling floods the
+//! chunk visibility graph from the camera chunk and the draws it reached
+//! are frustum culled on the GPU. Props stream in cells around the camera
+//! on every camera update; cells over chunks that loaded or changed are
+//! regenerated, and their instanced draws are culled with the chunks'.
+//! With smooth terrain on, the distance fields of uploaded chunks, their
+//! face neighbors and every chunk an edit's padding reaches are rebuilt
+//! from the world; queued fields are tessellated from the camera a few
+//! chunks per frame and uploaded with their material blends.
+
+use crate::constants::engine_world::{
+    CHUNK_LOD_BAND_CHUNKS, LIGHT_CHUNKS_PER_FRAME, MESH_ARENA_DRAW_SLOTS, MESH_DEFRAG_BUDGET_BYTES,
+    MESH_DEFRAG_BUDGET_MS, SHADOW_CACHE_COLUMNS, SHADOW_READBACKS_PER_FRAME,
+    SIMULATION_RADIUS_CHUNKS,
+};
+use crate::constants::smooth_terrain::{
+    SDF_PADDING, SMOOTH_CHUNKS_PER_FRAME, TESSELLATION_VIEWPORT_HEIGHT,
+};
+use crate::engine_buffers::MetricsBuffers;
+use crate::engine_gpu_world_data::{EngineGpuWorldData, EngineGpuWorldStats, SmoothChunkDraw};
+use crate::engine_world_data::EngineWorldData;
+use crate::feature_flags::{feature_enabled, FLAG_HZB_OCCLUSION};
+use crate::gpu::automation::{
+    create_custom_pass_registry, encode_custom_passes, CustomPassRegistryData, CustomPassResources,
+    CustomPassStage,
+};
+use crate::gpu::buffer_layouts::{DrawMetadata, IndirectDrawIndexedCommand};
+use crate::memory::{
+    create_tracked_buffer, create_tracked_buffer_init, register_global_gpu_owner, SharedFrameArena,
+};
+use crate::renderer::adaptive_tessellation::TessellationView;
+use crate::renderer::chunk_lod_stitch_operations::{
+    chunk_lod_level, chunk_stitch_mask, create_chunk_lod_stitch, default_chunk_lod_stitch_config,
+    remove_chunk_lod, set_chunk_lod, take_restitch_chunks,
+};
+use crate::renderer::gpu_culling::{
+    apply_cave_culling, cave_culling_traversal, remove_chunk_connectivity, set_chunk_connectivity,
+    ChunkCulling, GpuCamera, InstanceStreamer, VisibilityGraphData,
+};
+use crate::renderer::gpu_meshing::{
+    allocate_mesh, create_gpu_meshing_state, create_mesh_arena, create_mesh_index_arena,
+    defragment_mesh_arena, free_mesh, free_mesh_buffer, generate_chunk_meshes, mesh_base_vertex,
+    needs_defragmentation, record_mesh_arena_metrics, remove_chunk_tint_map, set_chunk_stitch_mask,
+    update_mesh_statistics, DefragBudget, MeshGenerationResult, DEFAULT_MESH_ARENA_SIZE,
+    DEFAULT_MESH_INDEX_ARENA_SIZE, MAX_INDICES_PER_CHUNK, MAX_MESH_REQUESTS_PER_DISPATCH,
+    MAX_VERTICES_PER_CHUNK, MESH_VERTEX_STRIDE,
+};
+use crate::renderer::prop_instance_data::{PropCell, PropInstanceData, PropSurface};
+use crate::renderer::prop_instance_operations::{
+    create_prop_instances, default_prop_instance_config, invalidate_prop_cell, pack_prop_instances,
+    prop_cell_at, update_prop_streaming,
+};
+use crate::renderer::smooth_terrain_data::{SmoothTerrainConfig, SmoothTerrainData};
+use crate::renderer::smooth_terrain_operations::{
+    create_smooth_terrain, default_smooth_terrain_config, remove_chunk_sdf, smooth_block_mask,
+    take_smooth_remesh_chunks, tessellate_smooth_chunks, update_chunk_sdf,
+};
+use crate::world::compute::{
+    is_connectivity_passable, ChunkConnectivityCompute, ChunkModifier, GpuChunkLight,
+    ModificationCommand, ALL_FACES_CONNECTED,
+};
+use crate::world::core::voxel_to_chunk_pos;
+use crate::world::core::{BlockId, ChunkLayout, ChunkPos, VoxelPos};
+use crate::world::data_types::{ChunkData, WorldData};
+use crate::world::storage::{
+    create_shadow_cache, create_voxel_inspector, inspection_lines, invalidate_shadow_chunk,
+    poll_shadow_readbacks, poll_voxel_inspections, request_shadow_readbacks,
+    request_shadow_region_readbacks, request_voxel_inspection, ShadowCacheData, SharedShadowCache,
+    VoxelData, VoxelInspectRequest, WorldBuffer, WorldBufferDescriptor,
+};
+use crate::world::world_operations::{get_block, get_surface_height, WorldModification};
+use cgmath::EuclideanSpace;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Bytes of one indexed indirect draw
+const DRAW_COMMAND_SIZE: u64 = std::mem::size_of::<IndirectDrawIndexedCommand>() as u64;
+
+/// World buffer sized for the simulation radius and a mesher that takes its
+/// per-frame allocations from `frame_arena`, leaving smooth blocks out of
+/// the cubes when `smooth_terrain` is on; props are placed from
+/// `world_seed`. None when the device lacks `VERTEX_WRITABLE_STORAGE`,
+/// which the world buffer layout needs.
+pub fn create_engine_gpu_world(
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+    chunk_layout: ChunkLayout,
+    frame_arena: SharedFrameArena,
+    smooth_terrain: bool,
+    world_seed: u32,
+) -> Option<EngineGpuWorldData> {
+    if !device
+        .features()
+        .contains(wgpu::Features::VERTEX_WRITABLE_STORAGE)
+    {
+        log::warn!("[EngineGpuWorld] Device lacks VERTEX_WRITABLE_STORAGE; chunks stay CPU-only");
+        return None;
+    }
+    let world_buffer = WorldBuffer::new(
+        device.clone(),
+        &WorldBufferDescriptor {
+            view_distance: SIMULATION_RADIUS_CHUNKS,
+            // The voxel inspector copies out of it
+            enable_readback: true,
+            chunk_layout,
+            ..WorldBufferDescriptor::default()
+        },
+    );
+    let inspector = create_voxel_inspector(&world_buffer);
+    let shadow: SharedShadowCache = Arc::new(Mutex::new(create_shadow_cache(
+        SHADOW_CACHE_COLUMNS,
+        chunk_layout,
+    )));
+    crate::game::set_gateway_light_cache(shadow.clone());
+    let chunk_light = match GpuChunkLight::new(device.clone()) {
+        Ok(chunk_light) => Some(chunk_light),
+        Err(e) => {
+            log::error!("[EngineGpuWorld] {}; chunks are meshed unlit", e);
+            None
+        }
+    };
+    let chunk_culling = match ChunkCulling::new(&device) {
+        Ok(chunk_culling) => Some(chunk_culling),
+        Err(e) => {
+            log::error!("[EngineGpuWorld] {}; chunk draws are not frustum culled", e);
+            None
+        }
+    };
+    let prop_culling = ChunkCulling::new(&device).ok();
+    let smooth = create_smooth_terrain(SmoothTerrainConfig {
+        enabled: smooth_terrain,
+        ..default_smooth_terrain_config()
+    });
+    let mut meshing =
+        create_gpu_meshing_state(device.clone(), queue, chunk_layout, Some(frame_arena));
+    meshing.smooth_blocks = smooth_block_mask(&smooth.config);
+    let gpu_owner = register_global_gpu_owner("engine_world");
+    let (mesh_draws, mesh_draws_allocation) = create_tracked_buffer(
+        &device,
+        &gpu_owner,
+        &wgpu::BufferDescriptor {
+            label: Some("Engine Mesh Draws"),
+            size: MESH_ARENA_DRAW_SLOTS as u64 * DRAW_COMMAND_SIZE,
+            usage: wgpu::BufferUsages::INDIRECT
+                | wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
+    Some(EngineGpuWorldData {
+        world_buffer,
+        modifier: ChunkModifier::new(device.clone()).with_shadow_cache(shadow.clone()),
+        chunk_light,
+        custom_passes: create_custom_pass_registry(),
+        connectivity: Arc::new(ChunkConnectivityCompute::new(device.clone())),
+        visibility: VisibilityGraphData::default(),
+        chunk_culling,
+        props: create_prop_instances(default_prop_instance_config(world_seed as u64)),
+        prop_streamer: InstanceStreamer::new(&device),
+        prop_culling,
+        smooth,
+        smooth_draws: HashMap::new(),
+        meshing,
+        shadow,
+        inspector,
+        resident: HashSet::new(),
+        uploading: HashSet::new(),
+        light_queue: Vec::new(),
+        mesh_queue: Vec::new(),
+        meshed: HashMap::new(),
+        lod: create_chunk_lod_stitch(default_chunk_lod_stitch_config()),
+        vertex_arena: create_mesh_arena(&device, DEFAULT_MESH_ARENA_SIZE, MESH_VERTEX_STRIDE),
+        index_arena: create_mesh_index_arena(&device, DEFAULT_MESH_INDEX_ARENA_SIZE),
+        mesh_draws,
+        mesh_draws_allocation,
+        draw_slots: HashMap::new(),
+        free_draw_slots: (0..MESH_ARENA_DRAW_SLOTS).rev().collect(),
+        stats: EngineGpuWorldStats::default(),
+        gpu_owner,
+    })
+}
+
+/// Lock the shadow cache, recovering it if a holder panicked
+pub fn lock_engine_shadow_cache(shadow: &SharedShadowCache) -> MutexGuard<'_, ShadowCacheData> {
+    match shadow.lock() {
+        Ok(cache) => cache,
+        Err(poisoned) => {
+            log::warn!("[EngineGpuWorld] Shadow cache mutex was poisoned, recovering");
+            poisoned.into_inner()
+        }
+    }
+}
+
+/// Encode the game passes of `stage` over the world buffer; `chunk_count`
+/// is the workgroup count of per-chunk passes. Returns the passes encoded.
+pub fn encode_engine_custom_passes(
+    passes: &CustomPassRegistryData,
+    world_buffer: &WorldBuffer,
+    device: &wgpu::Device,
+    encoder: &mut wgpu::CommandEncoder,
+    stage: CustomPassStage,
+    chunk_count: u32,
+) -> u32 {
+    if passes.passes.is_empty() {
+        return 0;
+    }
+    let resources = CustomPassResources {
+        world_voxels: world_buffer.voxel_buffer(),
+        chunk_metadata: world_buffer.metadata_buffer(),
+        world_light: world_buffer.light_buffer(),
+        chunk_count,
+    };
+    match encode_custom_passes(passes, stage, device, encoder, &resources) {
+        Ok(encoded) => encoded,
+        Err(e) => {
+            log::warn!("[EngineGpuWorld] {:?} custom passes skipped: {}", stage, e);
+            0
+        }
+    }
+}
+
+/// Run the PerTick game passes once for each fixed tick of the frame
+pub fn tick_engine_custom_passes(gpu: &mut EngineGpuWorldData, ticks: u32) {
+    if ticks == 0 || gpu.custom_passes.passes.is_empty() {
+        return;
+    }
+    let device = gpu.meshing.device.clone();
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Engine Tick Pass Encoder"),
+    });
+    let chunk_count = gpu.resident.len() as u32;
+    for _ in 0..ticks {
+        gpu.stats.custom_passes_encoded += encode_engine_custom_passes(
+            &gpu.custom_passes,
+            &gpu.world_buffer,
+            &device,
+            &mut encoder,
+            CustomPassStage::PerTick,
+            chunk_count,
+        ) as u64;
+    }
+    gpu.meshing.queue.submit(std::iter::once(encoder.finish()));
+}
+
+/// Chunk modifier command of an engine block edit
+pub fn edit_command(edit: &WorldModification) -> ModificationCommand {
+    let pos = edit.position;
+    if edit.new_block == BlockId::AIR {
+        ModificationCommand::break_block(pos.x, pos.y, pos.z)
+    } else {
+        ModificationCommand::set_block(pos.x, pos.y, pos.z, edit.new_block.0)
+    }
+}
+
+/// GPU voxels of a CPU chunk (both x-major)
+pub fn chunk_voxels(chunk: &ChunkData) -> Vec<VoxelData> {
+    chunk
+        .blocks
+        .iter()
+        .map(|block| VoxelData::new(block.0, 0, 0, 0))
+        .collect()
+}
+
+/// Release chunks the world unloaded, upload the ones it loaded, apply
+/// edits, relight the chunks whose voxels changed on the GPU and mesh them
+pub fn sync_engine_gpu_world(gpu: &mut EngineGpuWorldData, engine_world: &mut EngineWorldData) {
+    let edits = std::mem::take(&mut engine_world.pending_edits);
+    let world = &engine_world.world;
+    let _span = crate::trace_span!(Gpu, "sync_engine_gpu_world");
+    let loaded: HashSet<ChunkPos> = world.chunks.iter().map(|chunk| chunk.position).collect();
+    let released: Vec<ChunkPos> = gpu.resident.difference(&loaded).copied().collect();
+    for pos in &released {
+        release_engine_mesh(gpu, *pos);
+    }
+    let mut shadow = lock_engine_shadow_cache(&gpu.shadow);
+
+    for pos in &released {
+        gpu.resident.remove(pos);
+        gpu.uploading.remove(pos);
+        gpu.world_buffer.release_chunk_slot(*pos);
+        invalidate_shadow_chunk(&mut shadow, *pos);
+        free_mesh_buffer(&gpu.meshing, pos);
+        remove_chunk_tint_map(&gpu.meshing, pos);
+        remove_chunk_connectivity(&mut gpu.visibility, *pos);
+        remove_chunk_lod(&mut gpu.lod, *pos);
+        set_chunk_stitch_mask(&gpu.meshing, *pos, 0);
+        remove_chunk_sdf(&mut gpu.smooth, *pos);
+        gpu.smooth_draws.remove(pos);
+        let size = world.chunk_layout.size as i32;
+        invalidate_engine_prop_columns(&mut gpu.props, pos.x * size, pos.z * size, size);
+        gpu.meshed.remove(pos);
+    }
+    gpu.light_queue.retain(|pos| loaded.contains(pos));
+    gpu.mesh_queue.retain(|pos| loaded.contains(pos));
+    gpu.stats.chunks_released += released.len() as u64;
+
+    // Edits of chunks already on the GPU are applied in place; chunks still
+    // waiting for their upload are queued again with the edited voxels
+    let mut commands = Vec::new();
+    let mut modified = Vec::new();
+    let mut edited = HashSet::new();
+    for edit in &edits {
+        let chunk_pos = voxel_to_chunk_pos(world.chunk_layout, edit.position);
+        if gpu.resident.contains(&chunk_pos) && !gpu.uploading.contains(&chunk_pos) {
+            commands.push(edit_command(edit));
+            if !modified.contains(&chunk_pos) {
+                modified.push(chunk_pos);
+            }
+            if !gpu.light_queue.contains(&chunk_pos) {
+                gpu.light_queue.push(chunk_pos);
+            }
+        } else {
+            edited.insert(chunk_pos);
+        }
+    }
+    // Fields reach SDF_PADDING voxels into their neighbors
+    let mut smooth_changed = HashSet::new();
+    for edit in &edits {
+        invalidate_engine_prop_columns(&mut gpu.props, edit.position.x, edit.position.z, 1);
+        smooth_changed.extend(smooth_field_chunks(world.chunk_layout, edit.position));
+    }
+    let device = gpu.meshing.device.clone();
+    let queue = gpu.meshing.queue.clone();
+    let mut restored = Vec::new();
+    for chunk in &world.chunks {
+        let newly_resident = gpu.resident.insert(chunk.position);
+        if newly_resident || edited.contains(&chunk.position) {
+            smooth_changed.insert(chunk.position);
+            smooth_changed.extend(chunk_face_neighbors(chunk.position));
+            let size = world.chunk_layout.size as i32;
+            invalidate_engine_prop_columns(
+                &mut gpu.props,
+                chunk.position.x * size,
+                chunk.position.z * size,
+                size,
+            );
+            remove_chunk_connectivity(&mut gpu.visibility, chunk.position);
+            // Valid light saved with the chunk skips the lighting pass
+            let baked = engine_world.baked_light.remove(&chunk.position);
+            if let Some(baked) = baked.filter(|_| newly_resident) {
+                match gpu.world_buffer.upload_chunk_with_light(
+                    &queue,
+                    chunk.position,
+                    &chunk_voxels(chunk),
+                    &baked.light,
+                ) {
+                    Ok(()) => {
+                        gpu.uploading.remove(&chunk.position);
+                        invalidate_shadow_chunk(&mut shadow, chunk.position);
+                        restored.push(chunk.position);
+                        continue;
+                    }
+                    Err(e) => log::warn!(
+                        "[EngineGpuWorld] Relighting chunk {:?}, saved light rejected: {}",
+                        chunk.position,
+                        e
+                    ),
+                }
+            }
+            gpu.world_buffer
+                .queue_chunk_upload(chunk.position, &chunk_voxels(chunk));
+            // Uniform chunks become sparse descriptors without a flush; their
+            // faces are all open or all closed
+            if let Some(sparse) = gpu.world_buffer.sparse_chunk(chunk.position) {
+                gpu.uploading.remove(&chunk.position);
+                invalidate_shadow_chunk(&mut shadow, chunk.position);
+                let connectivity = match is_connectivity_passable(BlockId(sparse.voxel.block_id()))
+                {
+                    true => ALL_FACES_CONNECTED,
+                    false => 0,
+                };
+                set_chunk_connectivity(&mut gpu.visibility, chunk.position, connectivity);
+            } else {
+                gpu.uploading.insert(chunk.position);
+            }
+        }
+    }
+
+    gpu.stats.chunks_uploaded += restored.len() as u64;
+    gpu.stats.chunks_light_restored += restored.len() as u64;
+    gpu.connectivity.queue_chunks(&restored);
+    for pos in restored {
+        if !gpu.mesh_queue.contains(&pos) {
+            gpu.mesh_queue.push(pos);
+        }
+    }
+
+    if gpu.world_buffer.has_pending_chunk_uploads() || !commands.is_empty() {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Engine World Upload Encoder"),
+        });
+        let uploaded = gpu.world_buffer.flush_chunk_uploads(&queue, &mut encoder);
+        // The modifier locks the shadow cache itself to mirror the edits
+        drop(shadow);
+        gpu.modifier
+            .apply_modifications(&mut encoder, &queue, &gpu.world_buffer, &commands);
+        if !uploaded.is_empty() {
+            gpu.stats.custom_passes_encoded += encode_engine_custom_passes(
+                &gpu.custom_passes,
+                &gpu.world_buffer,
+                &device,
+                &mut encoder,
+                CustomPassStage::PostGeneration,
+                uploaded.len() as u32,
+            ) as u64;
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+        shadow = lock_engine_shadow_cache(&gpu.shadow);
+
+        for pos in &uploaded {
+            gpu.uploading.remove(pos);
+            invalidate_shadow_chunk(&mut shadow, *pos);
+        }
+        gpu.stats.chunks_uploaded += uploaded.len() as u64;
+        gpu.stats.blocks_modified += commands.len() as u64;
+        // Connectivity is recomputed from the voxels now on the GPU
+        for pos in &modified {
+            remove_chunk_connectivity(&mut gpu.visibility, *pos);
+        }
+        gpu.connectivity.queue_chunks(&modified);
+        gpu.connectivity.queue_chunks(&uploaded);
+        gpu.light_queue.extend(uploaded);
+    }
+
+    // Light reaches the lighting storage before the chunk is meshed, a few
+    // chunks per frame since every relight runs the full propagation
+    if !gpu.light_queue.is_empty() {
+        let count = match gpu.chunk_light {
+            Some(_) => gpu.light_queue.len().min(LIGHT_CHUNKS_PER_FRAME),
+            None => gpu.light_queue.len(),
+        };
+        let batch: Vec<ChunkPos> = gpu.light_queue.drain(..count).collect();
+        if let Some(chunk_light) = &gpu.chunk_light {
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Engine World Light Encoder"),
+            });
+            gpu.stats.custom_passes_encoded += encode_engine_custom_passes(
+                &gpu.custom_passes,
+                &gpu.world_buffer,
+                &device,
+                &mut encoder,
+                CustomPassStage::PreLighting,
+                batch.len() as u32,
+            ) as u64;
+            let lit = chunk_light.encode_chunk_light(&mut encoder, &gpu.world_buffer, &batch);
+            queue.submit(std::iter::once(encoder.finish()));
+            for pos in &lit {
+                invalidate_shadow_chunk(&mut shadow, *pos);
+            }
+            gpu.stats.chunks_lit += lit.len() as u64;
+        }
+        for pos in batch {
+            if !gpu.mesh_queue.contains(&pos) {
+                gpu.mesh_queue.push(pos);
+            }
+        }
+    }
+
+    if gpu.smooth.config.enabled {
+        update_engine_smooth_fields(&mut gpu.smooth, world, &loaded, &smooth_changed);
+    }
+
+    request_shadow_readbacks(
+        &mut shadow,
+        &gpu.world_buffer,
+        &device,
+        &queue,
+        SHADOW_READBACKS_PER_FRAME,
+    );
+    request_shadow_region_readbacks(&mut shadow, &gpu.world_buffer, &device, &queue);
+    poll_shadow_readbacks(&mut shadow, &device);
+    drop(shadow);
+
+    queue_engine_lod_changes(gpu, engine_world.center);
+    if !gpu.mesh_queue.is_empty() {
+        let count = gpu.mesh_queue.len().min(MAX_MESH_REQUESTS_PER_DISPATCH);
+        let batch: Vec<ChunkPos> = gpu.mesh_queue.drain(..count).collect();
+        // Levels are recorded first so the batch stitches against itself
+        for pos in &batch {
+            set_chunk_lod(
+                &mut gpu.lod,
+                *pos,
+                chunk_lod_band(engine_world.center, *pos),
+            );
+        }
+        queue_engine_restitch(gpu, &batch);
+        // One dispatch per level; each one's meshes leave the mesher's
+        // buffer before the next overwrites it
+        for level in 0..=gpu.lod.config.max_level {
+            let chunks: Vec<ChunkPos> = batch
+                .iter()
+                .filter(|pos| chunk_lod_level(&gpu.lod, **pos) == Some(level))
+                .copied()
+                .collect();
+            if chunks.is_empty() {
+                continue;
+            }
+            for pos in &chunks {
+                set_chunk_stitch_mask(&gpu.meshing, *pos, chunk_stitch_mask(&gpu.lod, *pos));
+            }
+            let meshed = generate_chunk_meshes(&gpu.meshing, &gpu.world_buffer, &chunks, level);
+            update_mesh_statistics(&mut gpu.meshing, meshed.len() as u32);
+            gpu.stats.chunks_meshed += meshed.len() as u64;
+            store_engine_meshes(gpu, &meshed);
+            for mesh in meshed {
+                gpu.meshed.insert(mesh.chunk_pos, mesh.buffer_index);
+            }
+        }
+    }
+    gpu.stats.chunks_stitched = gpu.lod.masks.len() as u32;
+    mesh_engine_smooth_terrain(gpu, engine_world);
+    gpu.stats.smooth_chunks = gpu.smooth_draws.len() as u32;
+}
+
+/// Chunks whose distance field samples the voxel at `pos`: its own and
+/// any within `SDF_PADDING` of it
+fn smooth_field_chunks(layout: ChunkLayout, pos: VoxelPos) -> Vec<ChunkPos> {
+    let pad = SDF_PADDING as i32;
+    let low = voxel_to_chunk_pos(layout, VoxelPos::new(pos.x - pad, pos.y - pad, pos.z - pad));
+    let high = voxel_to_chunk_pos(layout, VoxelPos::new(pos.x + pad, pos.y + pad, pos.z + pad));
+    let mut chunks = Vec::new();
+    for z in low.z..=high.z {
+        for y in low.y..=high.y {
+            for x in low.x..=high.x {
+                chunks.push(ChunkPos::new(x, y, z));
+            }
+        }
+    }
+    chunks
+}
+
+fn chunk_face_neighbors(pos: ChunkPos) -> [ChunkPos; 6] {
+    [
+        ChunkPos::new(pos.x + 1, pos.y, pos.z),
+        ChunkPos::new(pos.x - 1, pos.y, pos.z),
+        ChunkPos::new(pos.x, pos.y + 1, pos.z),
+        ChunkPos::new(pos.x, pos.y - 1, pos.z),
+        ChunkPos::new(pos.x, pos.y, pos.z + 1),
+        ChunkPos::new(pos.x, pos.y, pos.z - 1),
+    ]
+}
+
+/// Rebuild the distance fields of the `changed` chunks that are loaded;
+/// fields that changed are queued for tessellation
+fn update_engine_smooth_fields(
+    smooth: &mut SmoothTerrainData,
+    world: &WorldData,
+    loaded: &HashSet<ChunkPos>,
+    changed: &HashSet<ChunkPos>,
+) {
+    let size = world.chunk_layout.size;
+    for pos in changed.iter().filter(|pos| loaded.contains(pos)) {
+        update_chunk_sdf(smooth, *pos, size, |x, y, z| {
+            get_block(world, VoxelPos::new(x, y, z), size)
+        });
+    }
+}
+
+/// Tessellate the queued smooth chunks nearest the camera chunk, up to
+/// `SMOOTH_CHUNKS_PER_FRAME`, and upload their surfaces; chunks whose
+/// field went away lose theirs. The queue waits for a camera.
+fn mesh_engine_smooth_terrain(gpu: &mut EngineGpuWorldData, engine_world: &EngineWorldData) {
+    let Some(camera) = &engine_world.camera else {
+        return;
+    };
+    if gpu.smooth.remesh.is_empty() {
+        return;
+    }
+    let _span = crate::trace_span!(Gpu, "mesh_engine_smooth_terrain");
+    let mut chunks = take_smooth_remesh_chunks(&mut gpu.smooth);
+    chunks.sort_by_key(|pos| chunk_lod_band(engine_world.center, *pos));
+    let later = chunks.split_off(chunks.len().min(SMOOTH_CHUNKS_PER_FRAME));
+    gpu.smooth.remesh.extend(later);
+
+    let view = TessellationView {
+        view_pos: camera.position.to_vec(),
+        viewport_height: TESSELLATION_VIEWPORT_HEIGHT,
+        fov: camera.fov_radians,
+    };
+    let device = gpu.meshing.device.clone();
+    gpu.stats.smooth_chunks_tessellated += chunks.len() as u64;
+    for (pos, mesh) in tessellate_smooth_chunks(&gpu.smooth, &chunks, &view) {
+        if mesh.indices.is_empty() {
+            gpu.smooth_draws.remove(&pos);
+            continue;
+        }
+        let materials: Vec<[u32; 2]> = mesh
+            .materials
+            .iter()
+            .zip(&mesh.blends)
+            .map(|(block, blend)| {
+                [
+                    block.0 as u32 | (blend.neighbor.0 as u32) << 16,
+                    blend.weight.to_bits(),
+                ]
+            })
+            .collect();
+        let mut gpu_allocations = Vec::new();
+        let mut buffer = |label: &str, contents: &[u8], usage: wgpu::BufferUsages| {
+            let (buffer, allocation) = create_tracked_buffer_init(
+                &device,
+                &gpu.gpu_owner,
+                &wgpu::util::BufferInitDescriptor {
+                    label: Some(label),
+                    contents,
+                    usage,
+                },
+            );
+            gpu_allocations.push(allocation);
+            buffer
+        };
+        let vertices = buffer(
+            "Smooth Terrain Vertices",
+            bytemuck::cast_slice(&mesh.vertices),
+            wgpu::BufferUsages::VERTEX,
+        );
+        let indices = buffer(
+            "Smooth Terrain Indices",
+            bytemuck::cast_slice(&mesh.indices),
+            wgpu::BufferUsages::INDEX,
+        );
+        let materials = buffer(
+            "Smooth Terrain Materials",
+            bytemuck::cast_slice(&materials),
+            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::STORAGE,
+        );
+        let draw = SmoothChunkDraw {
+            vertices,
+            indices,
+            materials,
+            index_count: mesh.indices.len() as u32,
+            gpu_allocations,
+        };
+        gpu.smooth_draws.insert(pos, draw);
+    }
+}
+
+/// Stream the prop ring around the engine camera and upload it when cells
+/// changed; the surface comes from the loaded chunks
+pub fn stream_engine_props(gpu: &mut EngineGpuWorldData, engine_world: &EngineWorldData) {
+    let Some(camera) = &engine_world.camera else {
+        return;
+    };
+    let world = &engine_world.world;
+    let size = world.chunk_layout.size;
+    update_prop_streaming(
+        &mut gpu.props,
+        [camera.position.x, camera.position.y, camera.position.z],
+        |x, z| {
+            let height = get_surface_height(world, x, z)?;
+            let block = get_block(world, VoxelPos::new(x, height, z), size);
+            Some(PropSurface { height, block })
+        },
+    );
+    if gpu.props.dirty {
+        let packed = pack_prop_instances(&mut gpu.props);
+        let metrics =
+            gpu.prop_streamer
+                .upload_props(&gpu.meshing.device, &gpu.meshing.queue, &packed);
+        gpu.stats.prop_instances = metrics.instance_count;
+    }
+}
+
+/// Regenerate the loaded prop cells over the `width` wide square of
+/// columns starting at (`x`, `z`)
+fn invalidate_engine_prop_columns(props: &mut PropInstanceData, x: i32, z: i32, width: i32) {
+    let first = prop_cell_at(&props.config, [x as f32, 0.0, z as f32]);
+    let last = prop_cell_at(
+        &props.config,
+        [(x + width - 1) as f32, 0.0, (z + width - 1) as f32],
+    );
+    for cell_z in first.z..=last.z {
+        for cell_x in first.x..=last.x {
+            invalidate_prop_cell(
+                props,
+                PropCell {
+                    x: cell_x,
+                    z: cell_z,
+                },
+            );
+        }
+    }
+}
+
+/// LOD level of a chunk from its band around the camera chunk
+pub fn chunk_lod_band(center: ChunkPos, pos: ChunkPos) -> u32 {
+    let distance = (pos.x - center.x)
+        .abs()
+        .max((pos.y - center.y).abs())
+        .max((pos.z - center.z).abs());
+    (distance / CHUNK_LOD_BAND_CHUNKS) as u32
+}
+
+/// Queue the meshed chunks whose band moved with the camera, and those
+/// whose skirts changed when a neighbor was released
+fn queue_engine_lod_changes(gpu: &mut EngineGpuWorldData, center: ChunkPos) {
+    let moved: Vec<ChunkPos> = gpu
+        .lod
+        .levels
+        .iter()
+        .filter(|(pos, level)| {
+            chunk_lod_band(center, **pos).min(gpu.lod.config.max_level) != **level
+        })
+        .map(|(pos, _)| *pos)
+        .collect();
+    for pos in moved {
+        if !gpu.mesh_queue.contains(&pos) {
+            gpu.mesh_queue.push(pos);
+        }
+    }
+    queue_engine_restitch(gpu, &[]);
+}
+
+/// Queue the meshed chunks whose stitch mask changed, apart from `meshing`
+fn queue_engine_restitch(gpu: &mut EngineGpuWorldData, meshing: &[ChunkPos]) {
+    for pos in take_restitch_chunks(&mut gpu.lod) {
+        if gpu.meshed.contains_key(&pos)
+            && !meshing.contains(&pos)
+            && !gpu.mesh_queue.contains(&pos)
+        {
+            gpu.mesh_queue.push(pos);
+        }
+    }
+}
+
+/// Copy fresh meshes out of the mesher's buffer into the arenas, before the
+/// next dispatch overwrites it, and point their draw slots at them
+pub fn store_engine_meshes(gpu: &mut EngineGpuWorldData, meshes: &[MeshGenerationResult]) {
+    let mut encoder = gpu
+        .meshing
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Engine Mesh Arena Encoder"),
+        });
+    let index_stride = std::mem::size_of::<u32>() as u64;
+    for mesh in meshes {
+        release_engine_mesh(gpu, mesh.chunk_pos);
+        if mesh.index_count == 0 {
+            continue;
+        }
+        let Some(slot) = gpu.free_draw_slots.pop() else {
+            log::warn!(
+                "[EngineGpuWorld] No mesh draw slot left for chunk {:?}",
+                mesh.chunk_pos
+            );
+            continue;
+        };
+        let vertex_bytes = mesh.vertex_count as u64 * MESH_VERTEX_STRIDE;
+        let index_bytes = mesh.index_count as u64 * index_stride;
+        let placed = allocate_mesh(&mut gpu.vertex_arena, mesh.chunk_pos, vertex_bytes, slot).zip(
+            allocate_mesh(&mut gpu.index_arena, mesh.chunk_pos, index_bytes, slot),
+        );
+        let Some((vertex_offset, index_offset)) = placed else {
+            log::warn!(
+                "[EngineGpuWorld] Mesh arenas full, chunk {:?} is not drawn",
+                mesh.chunk_pos
+            );
+            free_mesh(&mut gpu.vertex_arena, mesh.chunk_pos);
+            gpu.free_draw_slots.push(slot);
+            continue;
+        };
+
+        let request = mesh.request_index as u64;
+        let source = &gpu.meshing.mesh_buffers[0];
+        encoder.copy_buffer_to_buffer(
+            &source.vertices,
+            request * MAX_VERTICES_PER_CHUNK as u64 * MESH_VERTEX_STRIDE,
+            &gpu.vertex_arena.buffer,
+            vertex_offset,
+            vertex_bytes,
+        );
+        encoder.copy_buffer_to_buffer(
+            &source.indices,
+            request * MAX_INDICES_PER_CHUNK as u64 * index_stride,
+            &gpu.index_arena.buffer,
+            index_offset,
+            index_bytes,
+        );
+        let command = IndirectDrawIndexedCommand::with_offsets(
+            mesh.index_count,
+            1,
+            (index_offset / index_stride) as u32,
+            (vertex_offset / MESH_VERTEX_STRIDE) as i32,
+            0,
+        );
+        gpu.meshing.queue.write_buffer(
+            &gpu.mesh_draws,
+            slot as u64 * DRAW_COMMAND_SIZE,
+            bytemuck::bytes_of(&command),
+        );
+        gpu.draw_slots.insert(mesh.chunk_pos, slot);
+    }
+    gpu.meshing.queue.submit(std::iter::once(encoder.finish()));
+}
+
+/// Free a chunk's arena extents and clear its draw
+pub fn release_engine_mesh(gpu: &mut EngineGpuWorldData, chunk_pos: ChunkPos) {
+    free_mesh(&mut gpu.vertex_arena, chunk_pos);
+    free_mesh(&mut gpu.index_arena, chunk_pos);
+    if let Some(slot) = gpu.draw_slots.remove(&chunk_pos) {
+        gpu.meshing.queue.write_buffer(
+            &gpu.mesh_draws,
+            slot as u64 * DRAW_COMMAND_SIZE,
+            bytemuck::bytes_of(&IndirectDrawIndexedCommand::default()),
+        );
+        gpu.free_draw_slots.push(slot);
+    }
+}
+
+/// Compact the mesh arenas on frames with no lighting or meshing queued,
+/// within `MESH_DEFRAG_BUDGET_MS` and `MESH_DEFRAG_BUDGET_BYTES` per arena.
+/// Moved meshes have their draws patched in the same submission. Returns
+/// the meshes moved.
+pub fn defragment_engine_mesh_arenas(gpu: &mut EngineGpuWorldData) -> u32 {
+    let idle = gpu.light_queue.is_empty() && gpu.mesh_queue.is_empty();
+    if !idle
+        || !(needs_defragmentation(&gpu.vertex_arena) || needs_defragmentation(&gpu.index_arena))
+    {
+        return 0;
+    }
+    let _span = crate::trace_span!(Gpu, "defragment_engine_mesh_arenas");
+    let budget = DefragBudget {
+        max_bytes: MESH_DEFRAG_BUDGET_BYTES,
+        max_time: std::time::Duration::from_millis(MESH_DEFRAG_BUDGET_MS),
+    };
+    let queue = gpu.meshing.queue.clone();
+    let mut encoder = gpu
+        .meshing
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Engine Mesh Defrag Encoder"),
+        });
+    let mut moved = 0;
+    for arena in [&mut gpu.vertex_arena, &mut gpu.index_arena] {
+        if needs_defragmentation(arena) {
+            moved += defragment_mesh_arena(arena, &mut encoder, &queue, &gpu.mesh_draws, budget)
+                .meshes_moved;
+        }
+    }
+    queue.submit(std::iter::once(encoder.finish()));
+    gpu.stats.meshes_defragmented += moved as u64;
+    moved
+}
+
+/// Read back the voxels around `request.center`; the decoded result is
+/// logged and kept in `gpu.inspector.latest` once the copy completes
+pub fn inspect_engine_voxels(gpu: &mut EngineGpuWorldData, request: VoxelInspectRequest) -> u64 {
+    request_voxel_inspection(
+        &mut gpu.inspector,
+        &gpu.world_buffer,
+        &gpu.meshing.device,
+        &gpu.meshing.queue,
+        request,
+    )
+}
+
+/// Decode the inspections whose readback finished and log them; call once
+/// per frame. Returns how many completed.
+pub fn poll_engine_voxel_inspections(gpu: &mut EngineGpuWorldData) -> usize {
+    let completed = poll_voxel_inspections(&mut gpu.inspector, &gpu.meshing.device);
+    if completed > 0 {
+        if let Some(inspection) = &gpu.inspector.latest {
+            for line in inspection_lines(inspection) {
+                log::info!("[VoxelInspector] {}", line);
+            }
+        }
+    }
+    completed
+}
+
+/// Read back a resident chunk's light for baking into its save; `None`
+/// while lighting is off or the chunk's light is not computed yet. Blocks
+/// until the copy completes.
+pub fn read_engine_chunk_light(
+    gpu: &mut EngineGpuWorldData,
+    chunk_pos: ChunkPos,
+) -> Option<Vec<u16>> {
+    let settled = gpu.resident.contains(&chunk_pos)
+        && !gpu.uploading.contains(&chunk_pos)
+        && !gpu.light_queue.contains(&chunk_pos);
+    if gpu.chunk_light.is_none() || !settled {
+        return None;
+    }
+    let device = gpu.meshing.device.clone();
+    let queue = gpu.meshing.queue.clone();
+    match gpu
+        .world_buffer
+        .read_chunk_light(&device, &queue, chunk_pos)
+    {
+        Ok(light) => Some(light),
+        Err(e) => {
+            log::warn!(
+                "[EngineGpuWorld] Light of chunk {:?} not read back: {}",
+                chunk_pos,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Publish the mesh arena fragmentation and chunk upload counters to the
+/// metrics the system monitor samples
+pub fn record_engine_gpu_world_metrics(gpu: &EngineGpuWorldData, metrics: &mut MetricsBuffers) {
+    record_mesh_arena_metrics(&[&gpu.vertex_arena, &gpu.index_arena], metrics);
+    metrics.chunk_uploads = gpu.world_buffer.chunk_upload_stats();
+}
+
+/// Distance (voxels) from `position` to the closest chunk with a mesh in
+/// the arenas, where the block atlas is drawn; infinite with none
+pub fn nearest_drawn_chunk_distance(gpu: &EngineGpuWorldData, position: [f32; 3]) -> f32 {
+    let size = gpu.world_buffer.chunk_layout().size as f32;
+    gpu.draw_slots
+        .keys()
+        .map(|chunk_pos| {
+            let min = [chunk_pos.x, chunk_pos.y, chunk_pos.z].map(|axis| axis as f32 * size);
+            let outside: f32 = (0..3)
+                .map(|axis| {
+                    let gap = (min[axis] - position[axis]).max(position[axis] - min[axis] - size);
+                    gap.max(0.0).powi(2)
+                })
+                .sum();
+            outside.sqrt()
+        })
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// Draw of a meshed chunk: its bounding sphere and mesh buffer
+pub fn chunk_draw_metadata(
+    layout: ChunkLayout,
+    chunk_pos: ChunkPos,
+    buffer_index: u32,
+) -> DrawMetadata {
+    let size = layout.size as f32;
+    let half = size * 0.5;
+    let center = [
+        chunk_pos.x as f32 * size + half,
+        chunk_pos.y as f32 * size + half,
+        chunk_pos.z as f32 * size + half,
+    ];
+    // gpu_culling.wgsl reads bits 1 and 2 as skip-frustum and
+    // always-visible, so only the visible bit is kept
+    DrawMetadata {
+        flags: DrawMetadata::FLAG_VISIBLE,
+        ..DrawMetadata::new(center, half * 3.0f32.sqrt(), 0, buffer_index)
+    }
+}
+
+/// Free the visibility graph when occlusion culling is turned off, and
+/// queue every resident chunk to rebuild it when it is turned back on
+pub fn set_engine_occlusion_culling(gpu: &mut EngineGpuWorldData, enabled: bool) {
+    if enabled {
+        let resident: Vec<ChunkPos> = gpu.resident.iter().copied().collect();
+        gpu.connectivity.queue_chunks(&resident);
+    } else {
+        gpu.visibility = VisibilityGraphData::default();
+        gpu.stats.chunks_cave_culled = 0;
+    }
+}
+
+/// Collect the connectivity read back since last frame, encode the next
+/// batch, flood the visibility graph from the camera chunk out to
+/// `view_distance` and frustum cull the meshed chunks it reached. With the
+/// `hzb_occlusion` flag off the flood is skipped and only the frustum
+/// culls.
+pub fn cull_engine_gpu_world(
+    gpu: &mut EngineGpuWorldData,
+    engine_world: &EngineWorldData,
+    view_distance: u32,
+) {
+    let _span = crate::trace_span!(Culling, "cull_engine_gpu_world");
+    let occlusion = feature_enabled(FLAG_HZB_OCCLUSION);
+    for (chunk_pos, connectivity) in gpu.connectivity.poll_results() {
+        if occlusion && gpu.resident.contains(&chunk_pos) {
+            set_chunk_connectivity(&mut gpu.visibility, chunk_pos, connectivity);
+        }
+    }
+
+    let device = gpu.meshing.device.clone();
+    let queue = gpu.meshing.queue.clone();
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Engine World Culling Encoder"),
+    });
+    if gpu.connectivity.has_pending_work() {
+        gpu.connectivity
+            .encode_batch(&mut encoder, &queue, &gpu.world_buffer);
+    }
+
+    let chunks: Vec<ChunkPos> = gpu.meshed.keys().copied().collect();
+    let mut draws: Vec<DrawMetadata> = chunks
+        .iter()
+        .map(|pos| chunk_draw_metadata(gpu.world_buffer.chunk_layout(), *pos, gpu.meshed[pos]))
+        .collect();
+    gpu.stats.chunks_cave_culled = if occlusion {
+        let traversal = cave_culling_traversal(
+            &gpu.visibility,
+            engine_world.center,
+            view_distance as i32,
+            |_| true,
+        );
+        apply_cave_culling(&mut draws, &chunks, &traversal)
+    } else {
+        0
+    };
+
+    if let (Some(chunk_culling), Some(camera)) = (gpu.chunk_culling.as_mut(), &engine_world.camera)
+    {
+        let gpu_camera = GpuCamera::from_matrices(
+            &crate::camera::build_view_matrix(camera),
+            &crate::camera::build_projection_matrix(camera),
+            camera.position.to_vec(),
+        );
+        chunk_culling.encode(&device, &mut encoder, &gpu_camera, &draws);
+        if let Some(prop_culling) = gpu.prop_culling.as_mut() {
+            let prop_draws = gpu.prop_streamer.culling_draws();
+            prop_culling.encode(&device, &mut encoder, &gpu_camera, prop_draws);
+        }
+    }
+    queue.submit(std::iter::once(encoder.finish()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::core::BlockId;
+
+    #[test]
+    fn test_chunk_voxels_keep_block_order() {
+        let chunk = ChunkData {
+            position: ChunkPos::new(0, 0, 0),
+            blocks: vec![BlockId::STONE, BlockId::AIR, BlockId::GRASS],
+            block_metadata: Default::default(),
+            flags: Default::default(),
+            last_modified: 0,
+        };
+        let ids: Vec<u16> = chunk_voxels(&chunk).iter().map(|v| v.block_id()).collect();
+        assert_eq!(
+            ids,
+            vec![BlockId::STONE.0, BlockId::AIR.0, BlockId::GRASS.0]
+        );
+    }
+}
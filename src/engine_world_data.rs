@@ -0,0 +1,109 @@
+//! Engine World Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! The world the engine owns and advances from `Engine::frame`: the
+//! generator chosen by the config and the voxel data streamed in around
+//! the camera, decorated once they have drawn. The operations live in
+//! engine_world_operations.rs
+
+use crate::activation_range_data::ActivationRangeData;
+use crate::camera::CameraData;
+use crate::persistence::{BakedChunkLight, ModificationLogData, SaveCipherData};
+use crate::process::{CancelOutcome, ProcessManager};
+use crate::simulation_scaling_data::SimulationScalingData;
+use crate::world::core::{BlockId, ChunkPos, VoxelPos};
+use crate::world::data_types::{ChunkData, WorldData};
+use crate::world::generation::{DecorationQueueData, WorldGenerator};
+use crate::world::storage::TempChunk;
+use crate::world::world_operations::WorldModification;
+use crate::world_random_data::WorldRandomData;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Arc;
+
+/// Generator the engine streams chunks from; shared with the pool jobs
+/// generating them
+pub type EngineWorldGenerator = Arc<dyn WorldGenerator>;
+
+/// Streaming counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngineWorldStats {
+    pub chunks_generated: u64,
+    pub chunks_unloaded: u64,
+    /// Chunks inside the radius still waiting to be generated, including
+    /// the ones generating on the thread pool
+    pub chunks_pending: usize,
+}
+
+/// Save the world streams from: chunk files plus the journal of edits made
+/// since they were written
+pub struct EngineWorldSave {
+    pub chunk_directory: PathBuf,
+    pub log: ModificationLogData,
+    /// Chunks loaded from a file rather than generated
+    pub chunks_loaded: u64,
+    /// Loaded chunks edited since they were last written out
+    pub unsaved_chunks: HashSet<ChunkPos>,
+    /// Edited chunks that unloaded, written out with their light by
+    /// `write_out_engine_world_chunks`
+    pub leaving: Vec<ChunkData>,
+}
+
+/// Chunks generating on the thread pool. A new channel is opened whenever
+/// the generator or the save changes, so results already queued are dropped.
+pub struct EngineWorldGeneration {
+    pub sender: Sender<TempChunk>,
+    pub results: Receiver<TempChunk>,
+    /// Chunks queued and not received yet
+    pub in_flight: HashSet<ChunkPos>,
+}
+
+/// Voxel picked for a block random tick (crop growth, grass spread, ...)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RandomTick {
+    pub pos: VoxelPos,
+    pub block: BlockId,
+}
+
+/// World state owned by the engine
+pub struct EngineWorldData {
+    pub generator: EngineWorldGenerator,
+    pub generation: EngineWorldGeneration,
+    pub world: WorldData,
+    /// Camera last handed to `Engine::set_camera`
+    pub camera: Option<CameraData>,
+    /// Chunk the streaming radius is centred on (the camera chunk)
+    pub center: ChunkPos,
+    pub stats: EngineWorldStats,
+    /// Present once `Engine::open_world_save` attached a save directory
+    pub save: Option<EngineWorldSave>,
+    /// Cipher for `EngineConfig::save_encryption_key`; the save seals its
+    /// chunks and journals with it
+    pub save_cipher: Option<SaveCipherData>,
+    /// Edits since the GPU world last synced
+    pub pending_edits: Vec<WorldModification>,
+    /// Valid light saved with chunks loaded from the save, until the GPU
+    /// world uploads them with it instead of relighting them
+    pub baked_light: HashMap<ChunkPos, BakedChunkLight>,
+    /// Grass, flowers and pebbles added to chunks after they first draw
+    pub decoration: DecorationQueueData,
+    /// Crafting, smelting and crop growth running in the world
+    pub processes: ProcessManager,
+    /// Processes cancelled because the block they were bound to was
+    /// broken, until `Engine::take_cancelled_processes` hands them over
+    pub cancelled_processes: Vec<CancelOutcome>,
+    /// How often each loaded chunk's processes tick, by distance to the
+    /// nearest player
+    pub simulation: SimulationScalingData,
+    /// Which chunks get random ticks and which physics entities simulate,
+    /// by distance to the nearest player
+    pub activation: ActivationRangeData,
+    /// Seeded streams the world's systems draw from
+    pub random: WorldRandomData,
+    /// Voxels picked for a random tick during the last frame's fixed ticks
+    pub random_ticks: Vec<RandomTick>,
+    /// The config's generator factory waits for a device (windowed engines
+    /// create theirs in `Engine::run`); the reference terrain stands in
+    pub factory_pending: bool,
+}
@@ -0,0 +1,981 @@
+//! Engine World Operations - Pure DOP
+//!
+//! Builds the engine's world from an `EngineConfig` and streams chunks in
+//! and out around the camera once per frame. Chunks that have drawn are
+//! decorated a few passes per frame under the decoration budget.
+
+use crate::activation_range_operations::{
+    create_activation_ranges, default_activation_range_config, entity_chunks_from_physics,
+    random_tick_chunks, update_chunk_activation, update_entity_activation,
+};
+use crate::camera::CameraData;
+use crate::constants::activation_range::RANDOM_TICKS_PER_CHUNK;
+use crate::constants::engine_world::{
+    CHUNKS_LOADED_PER_FRAME, SAVE_CHUNK_DIRECTORY, SAVE_JOURNAL_DIRECTORY,
+    SIMULATION_RADIUS_CHUNKS, UNLOAD_MARGIN_CHUNKS,
+};
+use crate::constants::world_random::RANDOM_TICK_STREAM;
+use crate::engine_buffers::PhysicsBuffers;
+use crate::engine_world_data::{
+    EngineWorldData, EngineWorldGeneration, EngineWorldGenerator, EngineWorldSave,
+    EngineWorldStats, RandomTick,
+};
+use crate::gpu::TerrainParamsSOA;
+use crate::persistence::{
+    bake_chunk_light, chunk_save_path, compact_region, create_save_cipher,
+    default_modification_log_config, flush_modification_log, load_chunk_with_modifications,
+    log_block_edit, modification_log_flush_due, open_modification_log, regions_needing_compaction,
+    replay_chunk_modifications, submit_chunk_save_with_light, PersistenceResult, SavePriority,
+};
+use crate::process::{anchor_chunk, ProcessId, ProcessManager};
+use crate::simulation_scaling_operations::{
+    advance_simulation, apply_scaled_process_ticks, create_simulation_scaling,
+    default_simulation_scaling_config,
+};
+use crate::thread_pool::global_thread_pool;
+use crate::world::core::{
+    layout_voxel_index, voxel_to_chunk_pos, world_point_to_voxel, BlockId, ChunkPos, VoxelPos,
+};
+use crate::world::data_types::WorldData;
+use crate::world::error::WorldError;
+use crate::world::generation::{
+    advance_decoration_clock, create_decoration_queue, create_preset_generator,
+    decorate_chunk_pass, default_decoration_config, default_generation_stages,
+    forget_chunk_decoration, note_chunk_rendered, preset_for_generator_type, run_decoration_budget,
+    schedule_chunk_generation, DecorationStep, ReferenceGenerator, WorldGenerator,
+};
+use crate::world::season_data::SeasonParams;
+use crate::world::season_operations::{apply_season_to_processes, apply_season_to_terrain_params};
+use crate::world::storage::TempChunk;
+use crate::world::world_operations::{
+    apply_chunk_column_tops, get_block, get_chunks_in_radius, insert_chunk, insert_generated_chunk,
+    set_block_with_metadata, unload_chunk, WorldModification,
+};
+use crate::world_random_operations::{
+    advance_world_random_tick, create_world_random, fork_world_stream, next_world_rng_u64,
+    position_fork_key,
+};
+use crate::EngineConfig;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{mpsc, Arc};
+use std::time::Instant;
+
+/// Device and queue a world generator factory builds on
+pub type WorldGeneratorDevice = (Arc<wgpu::Device>, Arc<wgpu::Queue>);
+
+/// Generator for a config: a `WorldGeneratorType::Preset` seeded with
+/// `world_seed`, else the game's
+/// generator, else its factory (when a device is available), else the
+/// reference terrain. The game's generator is moved out of the config.
+pub fn create_world_generator(
+    config: &mut EngineConfig,
+    device: Option<WorldGeneratorDevice>,
+) -> Result<EngineWorldGenerator> {
+    if let Some(preset) = preset_for_generator_type(&config.world_generator_type) {
+        let generator = create_preset_generator(
+            preset.clone(),
+            config.world_seed,
+            default_generation_stages(preset),
+        )
+        .map_err(|e| anyhow::anyhow!("EngineConfig: {}", e))?;
+        log::info!("[EngineWorld] Using the {:?} preset", generator.preset);
+        return Ok(Arc::new(generator));
+    }
+    if let Some(generator) = config.world_generator.take() {
+        return Ok(share_generator(generator));
+    }
+    if let (Some(factory), Some((device, queue))) =
+        (config.world_generator_factory.as_ref(), device)
+    {
+        return Ok(share_generator(factory(device, queue, config)));
+    }
+    Ok(Arc::new(ReferenceGenerator))
+}
+
+fn share_generator(generator: Box<dyn WorldGenerator + Send + Sync>) -> EngineWorldGenerator {
+    let shared: Arc<dyn WorldGenerator + Send + Sync> = Arc::from(generator);
+    shared
+}
+
+fn create_engine_world_generation() -> EngineWorldGeneration {
+    let (sender, results) = mpsc::channel();
+    EngineWorldGeneration {
+        sender,
+        results,
+        in_flight: HashSet::new(),
+    }
+}
+
+/// Empty world for a config, generated by `create_world_generator`
+pub fn create_engine_world(
+    config: &mut EngineConfig,
+    device: Option<WorldGeneratorDevice>,
+) -> Result<EngineWorldData> {
+    let layout = config.chunk_layout()?;
+    let factory_pending = device.is_none()
+        && config.world_generator_factory.is_some()
+        && config.world_generator.is_none()
+        && preset_for_generator_type(&config.world_generator_type).is_none();
+    let generator = create_world_generator(config, device)?;
+    let diameter = SIMULATION_RADIUS_CHUNKS * 2 + 1;
+    Ok(EngineWorldData {
+        generator,
+        generation: create_engine_world_generation(),
+        world: WorldData::with_chunk_layout(
+            config.world_seed,
+            diameter,
+            diameter,
+            diameter,
+            layout,
+        ),
+        camera: None,
+        center: ChunkPos::new(0, 0, 0),
+        stats: EngineWorldStats::default(),
+        save: None,
+        save_cipher: config.save_encryption_key.as_ref().map(create_save_cipher),
+        pending_edits: Vec::new(),
+        baked_light: HashMap::new(),
+        decoration: create_decoration_queue(default_decoration_config(config.world_seed as u64)),
+        processes: ProcessManager::new()?,
+        cancelled_processes: Vec::new(),
+        simulation: create_simulation_scaling(default_simulation_scaling_config()),
+        activation: create_activation_ranges(default_activation_range_config()),
+        random: create_world_random(config.world_seed as u64),
+        random_ticks: Vec::new(),
+        factory_pending,
+    })
+}
+
+/// Build the config's factory generator once a device exists; chunks
+/// generated by the stand-in are dropped and stream in again
+pub fn attach_world_generator_device(
+    world: &mut EngineWorldData,
+    config: &EngineConfig,
+    device: WorldGeneratorDevice,
+) {
+    if !world.factory_pending {
+        return;
+    }
+    let Some(factory) = config.world_generator_factory.as_ref() else {
+        return;
+    };
+    world.generator = share_generator(factory(device.0, device.1, config));
+    world.generation = create_engine_world_generation();
+    world.factory_pending = false;
+    // Stand-in terrain must not reach the chunk files; the journal keeps
+    // the edits for the chunks the new generator builds
+    if let Some(save) = world.save.as_mut() {
+        save.unsaved_chunks.clear();
+    }
+    let loaded: Vec<ChunkPos> = world.world.chunks.iter().map(|c| c.position).collect();
+    drop_chunks(world, &loaded);
+}
+
+fn drop_chunks(world: &mut EngineWorldData, positions: &[ChunkPos]) {
+    let size = world.world.chunk_layout.size;
+    let columns = (size * size) as usize;
+    if let Some(save) = world.save.as_mut() {
+        for chunk in &world.world.chunks {
+            if positions.contains(&chunk.position) && save.unsaved_chunks.remove(&chunk.position) {
+                save.leaving.push(chunk.clone());
+            }
+        }
+    }
+    world
+        .world
+        .chunks
+        .retain(|chunk| !positions.contains(&chunk.position));
+    for pos in positions {
+        world.baked_light.remove(pos);
+        let _ = unload_chunk(&mut world.world, *pos);
+        let _ = apply_chunk_column_tops(&mut world.world, *pos, vec![0; columns], size);
+        forget_chunk_decoration(&mut world.decoration, *pos);
+        world.processes.on_chunk_unloaded(*pos, size);
+    }
+    world.stats.chunks_unloaded += positions.len() as u64;
+}
+
+/// Terrain params the far terrain ring is drawn from: the generator's,
+/// else the defaults with the world seed
+pub fn engine_world_terrain_params(world: &EngineWorldData) -> TerrainParamsSOA {
+    world
+        .generator
+        .terrain_params()
+        .unwrap_or(TerrainParamsSOA {
+            seed: world.world.seed,
+            ..Default::default()
+        })
+}
+
+/// Take a season's params: its snow cover and temperature reach the chunks
+/// generated from now on, its growth rate the world's crops. Applied over
+/// the generator's own params, so seasons do not add up.
+pub fn apply_engine_world_season(world: &mut EngineWorldData, season: &SeasonParams) {
+    let mut params = engine_world_terrain_params(world).to_aos();
+    apply_season_to_terrain_params(&mut params, season);
+    world
+        .generator
+        .set_terrain_params(&TerrainParamsSOA::from_aos(&params));
+    apply_season_to_processes(&mut world.processes, season);
+}
+
+/// Follow the camera: chunks stream in around the chunk it is in
+pub fn set_engine_world_camera(world: &mut EngineWorldData, camera: &CameraData) {
+    let position = camera.position;
+    let voxel = world_point_to_voxel([position.x, position.y, position.z]);
+    world.center = voxel_to_chunk_pos(world.world.chunk_layout, voxel);
+    world.camera = Some(*camera);
+}
+
+/// Radius (chunks) of the CPU voxel data for a view distance
+pub fn simulation_radius(view_distance: u32) -> u32 {
+    view_distance.clamp(1, SIMULATION_RADIUS_CHUNKS)
+}
+
+fn chunk_distance_sq(a: ChunkPos, b: ChunkPos) -> i64 {
+    let (dx, dy, dz) = ((a.x - b.x) as i64, (a.y - b.y) as i64, (a.z - b.z) as i64);
+    dx * dx + dy * dy + dz * dz
+}
+
+/// Drop chunks that left the radius, take in the chunks the thread pool
+/// finished and start the nearest missing ones, at most
+/// `CHUNKS_LOADED_PER_FRAME`; returns the chunks loaded this frame
+pub fn stream_engine_world(world: &mut EngineWorldData, view_distance: u32) -> Vec<ChunkPos> {
+    let _span = crate::trace_span!(Generation, "stream_engine_world");
+    let radius = simulation_radius(view_distance);
+    let center = world.center;
+    let size = world.world.chunk_layout.size;
+
+    let keep_sq = ((radius + UNLOAD_MARGIN_CHUNKS) as i64).pow(2);
+    let leaving: Vec<ChunkPos> = world
+        .world
+        .chunks
+        .iter()
+        .map(|chunk| chunk.position)
+        .filter(|pos| chunk_distance_sq(*pos, center) > keep_sq)
+        .collect();
+    if !leaving.is_empty() {
+        drop_chunks(world, &leaving);
+    }
+
+    let mut loaded = receive_generated_chunks(world, keep_sq);
+
+    let mut missing: Vec<ChunkPos> = get_chunks_in_radius(center, radius)
+        .into_iter()
+        .filter(|pos| !world.world.active_chunks.contains(pos))
+        .collect();
+    missing.sort_by_key(|pos| chunk_distance_sq(*pos, center));
+
+    let starting: Vec<ChunkPos> = missing
+        .iter()
+        .filter(|pos| !world.generation.in_flight.contains(pos))
+        .take(CHUNKS_LOADED_PER_FRAME)
+        .copied()
+        .collect();
+    let mut generate = Vec::new();
+    for pos in starting {
+        match load_saved_engine_world_chunk(world, pos) {
+            Ok(true) => loaded.push(pos),
+            Ok(false) => generate.push(pos),
+            Err(e) => log::warn!("[EngineWorld] Chunk {:?} not loaded: {}", pos, e),
+        }
+    }
+
+    match global_thread_pool() {
+        Some(pool) => {
+            let queued = schedule_chunk_generation(
+                pool,
+                &world.generator,
+                &generate,
+                center,
+                size,
+                &world.generation.sender,
+            );
+            world.generation.in_flight.extend(queued);
+        }
+        None => {
+            for pos in generate {
+                let chunk = world.generator.generate_chunk(pos, size);
+                match insert_engine_world_chunk(world, &chunk) {
+                    Ok(()) => loaded.push(pos),
+                    Err(e) => log::warn!("[EngineWorld] Chunk {:?} not loaded: {}", pos, e),
+                }
+            }
+        }
+    }
+
+    world.stats.chunks_pending = missing
+        .iter()
+        .filter(|pos| !world.world.active_chunks.contains(pos))
+        .count();
+
+    // Processes bound to blocks in these chunks pick up again, unless the
+    // block changed while they were away
+    let voxels = &world.world;
+    for pos in &loaded {
+        let cancelled = world
+            .processes
+            .on_chunk_loaded(*pos, size, |voxel| get_block(voxels, voxel, size));
+        world.cancelled_processes.extend(cancelled);
+    }
+    loaded
+}
+
+/// Insert the chunks the thread pool finished that are still in range
+fn receive_generated_chunks(world: &mut EngineWorldData, keep_sq: i64) -> Vec<ChunkPos> {
+    let mut loaded = Vec::new();
+    while let Ok(chunk) = world.generation.results.try_recv() {
+        let pos = chunk.position;
+        world.generation.in_flight.remove(&pos);
+        if chunk_distance_sq(pos, world.center) > keep_sq
+            || world.world.active_chunks.contains(&pos)
+        {
+            continue;
+        }
+        match insert_engine_world_chunk(world, &chunk) {
+            Ok(()) => loaded.push(pos),
+            Err(e) => log::warn!("[EngineWorld] Chunk {:?} not loaded: {}", pos, e),
+        }
+    }
+    loaded
+}
+
+/// Read a chunk from the save with its journal replayed; false when the
+/// save has no readable file for it and it has to be generated
+fn load_saved_engine_world_chunk(
+    world: &mut EngineWorldData,
+    pos: ChunkPos,
+) -> Result<bool, WorldError> {
+    let Some(save) = world.save.as_mut() else {
+        return Ok(false);
+    };
+    let path = chunk_save_path(&save.chunk_directory, pos);
+    if !path.exists() {
+        return Ok(false);
+    }
+    match load_chunk_with_modifications(&save.log, &path) {
+        Ok(loaded) => {
+            save.chunks_loaded += 1;
+            if let Some(baked) = loaded.baked_light {
+                world.baked_light.insert(pos, baked);
+            }
+            insert_chunk(&mut world.world, loaded.chunk).map(|()| true)
+        }
+        Err(e) => {
+            log::error!(
+                "[EngineWorld] Saved chunk {:?} unreadable, regenerating: {}",
+                pos,
+                e
+            );
+            Ok(false)
+        }
+    }
+}
+
+/// Insert a generated chunk and replay the journaled edits of a chunk that
+/// was never written out
+fn insert_engine_world_chunk(
+    world: &mut EngineWorldData,
+    chunk: &TempChunk,
+) -> Result<(), WorldError> {
+    let pos = chunk.position;
+    let size = world.world.chunk_layout.size;
+    insert_generated_chunk(&mut world.world, chunk)?;
+    world.stats.chunks_generated += 1;
+    let Some(save) = world.save.as_ref() else {
+        return Ok(());
+    };
+    let Some(stored) = world.world.chunks.iter_mut().find(|c| c.position == pos) else {
+        return Ok(());
+    };
+    match replay_chunk_modifications(&save.log, stored) {
+        Ok(0) => Ok(()),
+        Ok(_) => {
+            crate::world::world_operations::rebuild_chunk_heightmap(&mut world.world, pos, size)
+        }
+        Err(e) => {
+            log::error!("[EngineWorld] Journal replay for {:?} failed: {}", pos, e);
+            Ok(())
+        }
+    }
+}
+
+/// Stream chunks from (and journal edits into) the save in `directory`.
+/// Chunks already loaded are dropped so they come back from the save.
+pub fn open_engine_world_save(
+    world: &mut EngineWorldData,
+    directory: &Path,
+) -> PersistenceResult<()> {
+    let log = open_modification_log(
+        default_modification_log_config(directory.join(SAVE_JOURNAL_DIRECTORY)),
+        world.save_cipher.clone(),
+    )?;
+    world.save = Some(EngineWorldSave {
+        chunk_directory: directory.join(SAVE_CHUNK_DIRECTORY),
+        log,
+        chunks_loaded: 0,
+        unsaved_chunks: HashSet::new(),
+        leaving: Vec::new(),
+    });
+    // Chunks generating now would skip the save's files
+    world.generation = create_engine_world_generation();
+    let loaded: Vec<ChunkPos> = world.world.chunks.iter().map(|c| c.position).collect();
+    drop_chunks(world, &loaded);
+    Ok(())
+}
+
+/// Chunks players are in: the camera's, once one was set
+pub fn engine_world_player_chunks(world: &EngineWorldData) -> Vec<ChunkPos> {
+    world.camera.map(|_| world.center).into_iter().collect()
+}
+
+/// Run the world's processes for the fixed ticks that elapsed. Processes
+/// bound to a block tick with its chunk: every tick near a player, every
+/// few ticks farther out, and not at all beyond that, replaying the banked
+/// ticks once a player comes back.
+pub fn tick_engine_world_processes(world: &mut EngineWorldData, elapsed_ticks: u64) {
+    if elapsed_ticks == 0 {
+        return;
+    }
+    let loaded: Vec<ChunkPos> = world
+        .world
+        .chunks
+        .iter()
+        .map(|chunk| chunk.position)
+        .collect();
+    let players = engine_world_player_chunks(world);
+    let work = advance_simulation(&mut world.simulation, &loaded, &players, elapsed_ticks);
+    let size = world.world.chunk_layout.size;
+    let processes = &world.processes;
+    let process_chunks: HashMap<ProcessId, ChunkPos> = processes
+        .processes
+        .ids
+        .iter()
+        .zip(&processes.anchors)
+        .filter_map(|(id, anchor)| Some((*id, anchor_chunk(anchor.as_ref()?, size))))
+        .collect();
+    apply_scaled_process_ticks(&mut world.processes, &process_chunks, &work, elapsed_ticks);
+}
+
+/// Re-evaluate which loaded chunks and physics entities are in range of
+/// a player
+pub fn update_engine_world_activation(world: &mut EngineWorldData, physics: &PhysicsBuffers) {
+    let loaded: Vec<ChunkPos> = world
+        .world
+        .chunks
+        .iter()
+        .map(|chunk| chunk.position)
+        .collect();
+    let players = engine_world_player_chunks(world);
+    update_chunk_activation(&mut world.activation, &loaded, &players);
+    let entity_chunks = entity_chunks_from_physics(physics, world.world.chunk_layout.size);
+    update_entity_activation(&mut world.activation, &entity_chunks, &players);
+}
+
+/// Pick the voxels that get a block random tick in the fixed ticks that
+/// elapsed: `RANDOM_TICKS_PER_CHUNK` per tick in every chunk within the
+/// random tick range. Air is skipped; the rest replace `random_ticks`.
+pub fn tick_engine_world_random_ticks(world: &mut EngineWorldData, elapsed_ticks: u64) {
+    world.random_ticks.clear();
+    let size = world.world.chunk_layout.size;
+    let mut chunks = random_tick_chunks(&world.activation);
+    chunks.sort_by_key(|pos| (pos.x, pos.y, pos.z));
+    let first_tick = world.world.tick.saturating_sub(elapsed_ticks) + 1;
+    for tick in first_tick..=world.world.tick {
+        advance_world_random_tick(&mut world.random, tick);
+        for &chunk_pos in &chunks {
+            let key = position_fork_key(chunk_pos.x, chunk_pos.y, chunk_pos.z);
+            let mut rng = fork_world_stream(&world.random, RANDOM_TICK_STREAM, key);
+            for _ in 0..RANDOM_TICKS_PER_CHUNK {
+                let bits = next_world_rng_u64(&mut rng);
+                let offset = [0, 1, 2].map(|axis| ((bits >> (axis * 16)) % size as u64) as i32);
+                let pos = VoxelPos::new(
+                    chunk_pos.x * size as i32 + offset[0],
+                    chunk_pos.y * size as i32 + offset[1],
+                    chunk_pos.z * size as i32 + offset[2],
+                );
+                let block = get_block(&world.world, pos, size);
+                if block != BlockId::AIR {
+                    world.random_ticks.push(RandomTick { pos, block });
+                }
+            }
+        }
+    }
+}
+
+/// Edit a block of the loaded world: journaled when a save is open,
+/// handed to the GPU world at the next sync and checked against the
+/// processes bound to the block
+pub fn set_engine_world_block(
+    world: &mut EngineWorldData,
+    pos: VoxelPos,
+    block: BlockId,
+    metadata: u8,
+) -> Result<WorldModification, WorldError> {
+    let size = world.world.chunk_layout.size;
+    let modification = set_block_with_metadata(&mut world.world, pos, block, metadata, size)?;
+    if let Some(save) = world.save.as_mut() {
+        log_block_edit(&mut save.log, pos, block, metadata, world.world.tick, size);
+        save.unsaved_chunks
+            .insert(voxel_to_chunk_pos(world.world.chunk_layout, pos));
+    }
+    world.pending_edits.push(modification);
+    let cancelled = world.processes.on_block_changed(pos, block);
+    world.cancelled_processes.extend(cancelled);
+    Ok(modification)
+}
+
+/// Queue the chunks that drew this frame for decoration, advance the queue
+/// by `dt` and run its ready passes within the frame budget. Placed blocks
+/// are edits like any other: journaled and remeshed at the next sync.
+pub fn decorate_engine_world(
+    world: &mut EngineWorldData,
+    rendered: &[ChunkPos],
+    dt: f32,
+) -> Vec<DecorationStep> {
+    for chunk in rendered {
+        note_chunk_rendered(&mut world.decoration, *chunk);
+    }
+    advance_decoration_clock(&mut world.decoration, dt);
+    let config = world.decoration.config;
+    let layout = world.world.chunk_layout;
+    let size = layout.size;
+    let chunks = &world.world.chunks;
+    let mut placed = Vec::new();
+    let steps = run_decoration_budget(&mut world.decoration, world.center, |pos, pass| {
+        let chunk = chunks.iter().find(|chunk| chunk.position == pos)?;
+        // Passes work on y-major blocks; the world stores them x-major
+        let index = |x, y, z| layout_voxel_index(layout, x, y, z) as usize;
+        let mut blocks = Vec::with_capacity(chunk.blocks.len());
+        for y in 0..size {
+            for z in 0..size {
+                for x in 0..size {
+                    blocks.push(chunk.blocks[index(x, y, z)]);
+                }
+            }
+        }
+        let count = decorate_chunk_pass(&config, pos, size, &mut blocks, pass);
+        for (i, block) in blocks.iter().enumerate() {
+            let i = i as u32;
+            let (x, z, y) = (i % size, (i / size) % size, i / (size * size));
+            if *block != chunk.blocks[index(x, y, z)] {
+                let voxel = VoxelPos::new(
+                    pos.x * size as i32 + x as i32,
+                    pos.y * size as i32 + y as i32,
+                    pos.z * size as i32 + z as i32,
+                );
+                placed.push((voxel, *block));
+            }
+        }
+        Some(count)
+    });
+    for (pos, block) in placed {
+        if let Err(e) = set_engine_world_block(world, pos, block, 0) {
+            log::warn!("[EngineWorld] Decoration at {:?} not placed: {}", pos, e);
+        }
+    }
+    steps
+}
+
+/// Persistence tick: append buffered edits once the flush interval passed
+/// and fold long journals into chunk files
+pub fn tick_engine_world_save(world: &mut EngineWorldData, now: Instant) {
+    let Some(save) = world.save.as_mut() else {
+        return;
+    };
+    if modification_log_flush_due(&save.log, now) {
+        if let Err(e) = flush_modification_log(&mut save.log) {
+            log::error!("[EngineWorld] Journal flush failed: {}", e);
+        }
+    }
+
+    let size = world.world.chunk_layout.size;
+    for region in regions_needing_compaction(&save.log) {
+        let chunks = &world.world.chunks;
+        let directory = &save.chunk_directory;
+        if let Err(e) = compact_region(
+            &mut save.log,
+            region,
+            size,
+            |pos| chunk_save_path(directory, pos),
+            |pos| chunks.iter().find(|c| c.position == pos).cloned(),
+        ) {
+            log::error!(
+                "[EngineWorld] Compacting journal region {:?} failed: {}",
+                region,
+                e
+            );
+        }
+    }
+}
+
+/// Write the edited chunks that unloaded to their files on the thread
+/// pool, baking in the light `chunk_light` reads back for them. Without a
+/// pool the journal keeps their edits until compaction.
+pub fn write_out_engine_world_chunks(
+    world: &mut EngineWorldData,
+    mut chunk_light: impl FnMut(ChunkPos) -> Option<Vec<u16>>,
+) {
+    let Some(save) = world.save.as_mut() else {
+        return;
+    };
+    let leaving = std::mem::take(&mut save.leaving);
+    let Some(pool) = global_thread_pool() else {
+        return;
+    };
+    let layout = world.world.chunk_layout;
+    for chunk in leaving {
+        // Light on the GPU predates edits it has not received yet
+        let stale = world
+            .pending_edits
+            .iter()
+            .any(|edit| voxel_to_chunk_pos(layout, edit.position) == chunk.position);
+        let light = match stale {
+            true => None,
+            false => chunk_light(chunk.position).map(|light| bake_chunk_light(&chunk, light)),
+        };
+        let path = chunk_save_path(&save.chunk_directory, chunk.position);
+        let pos = chunk.position;
+        if let Err(e) = submit_chunk_save_with_light(
+            pool,
+            world.save_cipher.clone(),
+            chunk,
+            light,
+            layout.size,
+            path,
+            SavePriority::Normal,
+        ) {
+            log::warn!("[EngineWorld] Chunk {:?} left to the journal: {}", pos, e);
+        }
+    }
+}
+
+/// Append every buffered edit now (before exit or a world switch)
+pub fn flush_engine_world_save(world: &mut EngineWorldData) -> PersistenceResult<usize> {
+    match world.save.as_mut() {
+        Some(save) => flush_modification_log(&mut save.log),
+        None => Ok(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation_range_operations::physics_active_entities;
+    use crate::world::generation::{default_superflat_config, WorldPreset, DECORATION_PASSES};
+    use crate::world::world_operations::get_block;
+    use crate::WorldGeneratorType;
+
+    /// Stream until every chunk in the radius arrived from the thread pool
+    fn stream_until_loaded(world: &mut EngineWorldData, view_distance: u32) -> Vec<ChunkPos> {
+        let mut loaded = stream_engine_world(world, view_distance);
+        while world.stats.chunks_pending > 0 {
+            std::thread::yield_now();
+            loaded.extend(stream_engine_world(world, view_distance));
+        }
+        loaded
+    }
+
+    #[test]
+    fn test_preset_config_builds_a_superflat_world() {
+        let mut config = EngineConfig {
+            world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+                default_superflat_config(),
+            )),
+            ..EngineConfig::default()
+        };
+        let mut world = create_engine_world(&mut config, None).expect("world");
+        assert!(!world.generator.is_gpu());
+
+        let loaded = stream_until_loaded(&mut world, 1);
+        assert!(loaded.contains(&ChunkPos::new(0, 0, 0)));
+        let size = world.world.chunk_layout.size;
+        let block = |y| get_block(&world.world, VoxelPos::new(3, y, 7), size);
+        assert_eq!(block(0), BlockId::BEDROCK);
+        assert_eq!(block(1), BlockId::STONE);
+        assert_eq!(block(44), BlockId::GRASS);
+        assert_eq!(block(45), BlockId::AIR);
+    }
+
+    #[test]
+    fn test_world_seed_reaches_the_world_data() {
+        let mut config = EngineConfig {
+            world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+                default_superflat_config(),
+            )),
+            world_seed: 987,
+            ..EngineConfig::default()
+        };
+        let world = create_engine_world(&mut config, None).expect("world");
+        assert_eq!(world.world.seed, 987);
+        assert_eq!(world.decoration.config.seed, 987);
+    }
+
+    #[test]
+    fn test_streaming_follows_the_center() {
+        let mut config = EngineConfig::default();
+        let mut world = create_engine_world(&mut config, None).expect("world");
+        stream_until_loaded(&mut world, 1);
+        // Radius 1: the center and its six face neighbours
+        assert_eq!(world.world.active_chunks.len(), 7);
+
+        world.center = ChunkPos::new(5, 0, 0);
+        stream_until_loaded(&mut world, 1);
+        assert!(world.world.chunks.iter().all(|c| c.position.x >= 3));
+        assert_eq!(world.stats.chunks_unloaded, 7);
+    }
+
+    #[test]
+    fn test_journaled_edits_survive_a_reload() {
+        let dir = tempfile::TempDir::new().expect("temp dir");
+        let edit = VoxelPos::new(4, 60, 4);
+
+        let mut config = EngineConfig::default();
+        let mut world = create_engine_world(&mut config, None).expect("world");
+        open_engine_world_save(&mut world, dir.path()).expect("save");
+        stream_until_loaded(&mut world, 1);
+        set_engine_world_block(&mut world, edit, BlockId::STONE, 0).expect("edit");
+        assert_eq!(world.pending_edits.len(), 1);
+        assert_eq!(flush_engine_world_save(&mut world).expect("flush"), 1);
+
+        let mut config = EngineConfig::default();
+        let mut reopened = create_engine_world(&mut config, None).expect("world");
+        open_engine_world_save(&mut reopened, dir.path()).expect("save");
+        stream_until_loaded(&mut reopened, 1);
+        let size = reopened.world.chunk_layout.size;
+        assert_eq!(get_block(&reopened.world, edit, size), BlockId::STONE);
+
+        // Compaction folds the journal into a chunk file the next load reads
+        let save = reopened.save.as_mut().expect("save");
+        save.log.config.compact_after_records = 1;
+        tick_engine_world_save(&mut reopened, Instant::now());
+        let mut config = EngineConfig::default();
+        let mut compacted = create_engine_world(&mut config, None).expect("world");
+        open_engine_world_save(&mut compacted, dir.path()).expect("save");
+        stream_until_loaded(&mut compacted, 1);
+        assert!(compacted.save.as_ref().expect("save").chunks_loaded > 0);
+        assert_eq!(get_block(&compacted.world, edit, size), BlockId::STONE);
+    }
+
+    #[test]
+    fn test_edited_chunks_are_written_out_with_their_light() {
+        let dir = tempfile::TempDir::new().expect("temp dir");
+        let mut config = EngineConfig::default();
+        let mut world = create_engine_world(&mut config, None).expect("world");
+        open_engine_world_save(&mut world, dir.path()).expect("save");
+        stream_until_loaded(&mut world, 1);
+        set_engine_world_block(&mut world, VoxelPos::new(4, 60, 4), BlockId::STONE, 0)
+            .expect("edit");
+        // The GPU world takes the edit before the chunk leaves
+        world.pending_edits.clear();
+        let chunk = voxel_to_chunk_pos(world.world.chunk_layout, VoxelPos::new(4, 60, 4));
+        let path = chunk_save_path(&world.save.as_ref().expect("save").chunk_directory, chunk);
+        assert!(!path.exists());
+
+        world.center = ChunkPos::new(5, 0, 0);
+        stream_engine_world(&mut world, 1);
+        let voxels = world.world.chunk_layout.voxels_per_chunk as usize;
+        write_out_engine_world_chunks(&mut world, |_| Some(vec![0x0f; voxels]));
+        let deadline = Instant::now() + std::time::Duration::from_secs(5);
+        while !path.exists() && Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(path.exists());
+
+        world.center = ChunkPos::new(0, 0, 0);
+        stream_until_loaded(&mut world, 1);
+        let baked = world.baked_light.get(&chunk).expect("saved light");
+        assert_eq!(baked.light.len(), voxels);
+    }
+
+    #[test]
+    fn test_processes_follow_the_block_they_are_bound_to() {
+        use crate::instance::InstanceId;
+        use crate::process::{
+            create_anchor, AnchorBreakPolicy, ProcessStatus, ProcessType, TimeUnit,
+        };
+
+        let mut config = EngineConfig::default();
+        let mut world = create_engine_world(&mut config, None).expect("world");
+        stream_until_loaded(&mut world, 1);
+        let furnace = VoxelPos::new(4, 60, 4);
+        set_engine_world_block(&mut world, furnace, BlockId::STONE, 0).expect("place");
+        let processes = &mut world.processes;
+        let id = processes.start_process(
+            ProcessType::default(),
+            InstanceId::new(),
+            vec![],
+            TimeUnit::Ticks(1600),
+        );
+        let index = processes.processes.find_index(id).expect("index");
+        processes.processes.status[index] = ProcessStatus::Active;
+        let anchor = create_anchor(furnace, BlockId::STONE, AnchorBreakPolicy::Cancel);
+        processes.bind_to_block(id, anchor).expect("bind");
+
+        // Unloading the chunk pauses the process
+        world.center = ChunkPos::new(5, 0, 0);
+        stream_engine_world(&mut world, 1);
+        assert!(world.processes.anchored_indicators()[0].paused);
+
+        // Without a save the placed block is generated away meanwhile
+        world.center = ChunkPos::new(0, 0, 0);
+        stream_until_loaded(&mut world, 1);
+        let size = world.world.chunk_layout.size;
+        assert_ne!(get_block(&world.world, furnace, size), BlockId::STONE);
+        let cancelled: Vec<_> = world
+            .cancelled_processes
+            .iter()
+            .map(|c| c.process)
+            .collect();
+        assert_eq!(cancelled, vec![id]);
+    }
+
+    #[test]
+    fn test_bound_processes_tick_only_near_a_player() {
+        use crate::camera::CameraData;
+        use crate::instance::InstanceId;
+        use crate::process::{
+            create_anchor, AnchorBreakPolicy, ProcessStatus, ProcessType, TimeUnit,
+        };
+
+        let mut config = EngineConfig::default();
+        let mut world = create_engine_world(&mut config, None).expect("world");
+        stream_until_loaded(&mut world, 1);
+        let furnace = VoxelPos::new(4, 60, 4);
+        set_engine_world_block(&mut world, furnace, BlockId::STONE, 0).expect("place");
+        let processes = &mut world.processes;
+        let free = processes.start_process(
+            ProcessType::default(),
+            InstanceId::new(),
+            vec![],
+            TimeUnit::Ticks(1600),
+        );
+        let bound = processes.start_process(
+            ProcessType::default(),
+            InstanceId::new(),
+            vec![],
+            TimeUnit::Ticks(1600),
+        );
+        let anchor = create_anchor(furnace, BlockId::STONE, AnchorBreakPolicy::Cancel);
+        processes.bind_to_block(bound, anchor).expect("bind");
+        for id in [free, bound] {
+            let index = processes.processes.find_index(id).expect("index");
+            processes.processes.status[index] = ProcessStatus::Active;
+        }
+        let elapsed = |world: &EngineWorldData, id| {
+            let index = world.processes.processes.find_index(id).expect("index");
+            world.processes.processes.elapsed[index]
+        };
+
+        // No player yet: only the process without a block runs
+        tick_engine_world_processes(&mut world, 10);
+        assert!(elapsed(&world, free) > 0);
+        assert_eq!(elapsed(&world, bound), 0);
+
+        // A camera over the chunk wakes it and replays the banked ticks
+        set_engine_world_camera(&mut world, &CameraData::default());
+        tick_engine_world_processes(&mut world, 10);
+        assert_eq!(elapsed(&world, bound), elapsed(&world, free));
+    }
+
+    #[test]
+    fn test_random_ticks_and_physics_stay_in_range_of_a_player() {
+        use crate::camera::CameraData;
+        use crate::engine_buffers::create_engine_buffers;
+
+        let mut config = EngineConfig::default();
+        let mut world = create_engine_world(&mut config, None).expect("world");
+        stream_until_loaded(&mut world, 1);
+        let size = world.world.chunk_layout.size as f32;
+        let mut buffers = create_engine_buffers();
+        let far = size * (world.activation.config.physics_distance + 2) as f32;
+        buffers.physics.positions = vec![[1.0; 3], [far, 1.0, 1.0]];
+
+        // Nothing is in range without a player
+        world.world.tick += 4;
+        update_engine_world_activation(&mut world, &buffers.physics);
+        tick_engine_world_random_ticks(&mut world, 4);
+        assert!(world.random_ticks.is_empty());
+        assert!(physics_active_entities(&world.activation).is_empty());
+
+        set_engine_world_camera(&mut world, &CameraData::default());
+        world.world.tick += 4;
+        update_engine_world_activation(&mut world, &buffers.physics);
+        tick_engine_world_random_ticks(&mut world, 4);
+        assert!(!world.random_ticks.is_empty());
+        let layout = world.world.chunk_layout;
+        for tick in &world.random_ticks {
+            let chunk_pos = voxel_to_chunk_pos(layout, tick.pos);
+            assert!(world.world.chunks.iter().any(|c| c.position == chunk_pos));
+            assert_ne!(tick.block, BlockId::AIR);
+        }
+        assert_eq!(physics_active_entities(&world.activation), vec![0]);
+    }
+
+    #[test]
+    fn test_configured_key_seals_the_world_save() {
+        use crate::persistence::{is_sealed_save, PersistenceError, SaveEncryptionKey};
+
+        let dir = tempfile::TempDir::new().expect("temp dir");
+        let mut config = EngineConfig {
+            save_encryption_key: Some(SaveEncryptionKey([7; 32])),
+            ..EngineConfig::default()
+        };
+        let mut world = create_engine_world(&mut config, None).expect("world");
+        open_engine_world_save(&mut world, dir.path()).expect("save");
+        stream_until_loaded(&mut world, 1);
+        set_engine_world_block(&mut world, VoxelPos::new(4, 60, 4), BlockId::STONE, 0)
+            .expect("edit");
+        flush_engine_world_save(&mut world).expect("flush");
+
+        let journals = dir.path().join(SAVE_JOURNAL_DIRECTORY);
+        let journal = std::fs::read_dir(&journals)
+            .expect("journal dir")
+            .next()
+            .expect("journal")
+            .expect("entry");
+        let bytes = std::fs::read(journal.path()).expect("journal bytes");
+        assert!(is_sealed_save(&bytes));
+
+        // Without the key the journal cannot be replayed
+        let mut config = EngineConfig::default();
+        let mut plain = create_engine_world(&mut config, None).expect("world");
+        assert!(matches!(
+            open_engine_world_save(&mut plain, dir.path()),
+            Err(PersistenceError::EncryptionKeyMissing(_))
+        ));
+    }
+
+    #[test]
+    fn test_rendered_chunks_are_decorated_after_the_delay() {
+        let mut config = EngineConfig {
+            world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+                default_superflat_config(),
+            )),
+            ..EngineConfig::default()
+        };
+        let mut world = create_engine_world(&mut config, None).expect("world");
+        stream_until_loaded(&mut world, 1);
+        let chunk = ChunkPos::new(0, 0, 0);
+
+        assert!(decorate_engine_world(&mut world, &[chunk], 0.0).is_empty());
+        let mut steps = Vec::new();
+        for _ in 0..3 {
+            steps.extend(decorate_engine_world(&mut world, &[chunk], 1.0));
+        }
+        assert_eq!(steps.len(), DECORATION_PASSES.len());
+        assert!(world.decoration.decorated.contains(&chunk));
+
+        // Tufts grow on the grass at y = 44 and reach the GPU as edits
+        assert!(!world.pending_edits.is_empty());
+        assert!(world
+            .pending_edits
+            .iter()
+            .all(|edit| edit.position.y == 45 && edit.new_block != BlockId::AIR));
+        let size = world.world.chunk_layout.size;
+        let edit = world.pending_edits[0];
+        assert_eq!(get_block(&world.world, edit.position, size), edit.new_block);
+    }
+}
@@ -0,0 +1,40 @@
+//! Entity Tag Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Interning, membership and queries live in entity_tag_operations.rs
+//!
+//! Game logic labels entities with string tags ("hostile", "boss",
+//! "flammable"). Names are interned to small ids once; each entity carries
+//! one bitset of the tags it has, and every tag keeps a list of its members
+//! so "all hostile entities within 16 blocks" only visits hostile entities.
+//! The tag buffers live in `EngineBuffers::tags`, indexed like
+//! `PhysicsBuffers`.
+
+/// Small-int id of an interned tag (0 .. MAX_ENTITY_TAGS)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntityTagId(pub u8);
+
+/// Set of tags, bit `n` for `EntityTagId(n)`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct EntityTagMask(pub u64);
+
+/// Which tags an entity must and must not carry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EntityTagFilter {
+    /// Every one of these
+    pub all: EntityTagMask,
+    /// None of these
+    pub none: EntityTagMask,
+}
+
+/// Entity positions queries test against (`PhysicsBuffers::positions`)
+pub type EntityPositions = [[f32; 3]];
+
+/// Entity tag errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EntityTagError {
+    #[error("Entity tag limit of {limit} reached, cannot intern '{name}'")]
+    TooManyTags { name: String, limit: usize },
+}
+
+pub type EntityTagResult<T> = Result<T, EntityTagError>;
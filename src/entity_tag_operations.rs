@@ -0,0 +1,230 @@
+//! Entity Tag Operations - Pure DOP functions
+//!
+//! Intern names once with `intern_entity_tag` and keep the ids; tagging
+//! and queries then never touch strings. Radius queries write into a
+//! caller-owned `Vec` that is cleared and reused, so a query run every tick
+//! stops allocating once the vector has grown to the largest result.
+//! Call `clear_entity_tags` when an entity is despawned.
+
+use crate::constants::entity_tags::MAX_ENTITY_TAGS;
+use crate::engine_buffers::EntityTagBuffers;
+use crate::entity_tag_data::{
+    EntityPositions, EntityTagError, EntityTagFilter, EntityTagId, EntityTagMask, EntityTagResult,
+};
+
+/// Id of `name`, interning it on first use
+pub fn intern_entity_tag(tags: &mut EntityTagBuffers, name: &str) -> EntityTagResult<EntityTagId> {
+    if let Some(id) = tags.ids.get(name) {
+        return Ok(*id);
+    }
+    if tags.names.len() >= MAX_ENTITY_TAGS {
+        return Err(EntityTagError::TooManyTags {
+            name: name.to_string(),
+            limit: MAX_ENTITY_TAGS,
+        });
+    }
+    let id = EntityTagId(tags.names.len() as u8);
+    tags.names.push(name.to_string());
+    tags.ids.insert(name.to_string(), id);
+    tags.members.push(Vec::new());
+    Ok(id)
+}
+
+/// Id of an already interned tag
+pub fn entity_tag_id(tags: &EntityTagBuffers, name: &str) -> Option<EntityTagId> {
+    tags.ids.get(name).copied()
+}
+
+/// Name of an interned tag
+pub fn entity_tag_name(tags: &EntityTagBuffers, tag: EntityTagId) -> Option<&str> {
+    tags.names.get(tag.0 as usize).map(String::as_str)
+}
+
+/// Mask holding `ids`
+pub fn entity_tag_mask(ids: &[EntityTagId]) -> EntityTagMask {
+    EntityTagMask(ids.iter().fold(0, |bits, id| bits | (1u64 << id.0)))
+}
+
+/// Whether `mask` holds `tag`
+pub fn mask_has_tag(mask: EntityTagMask, tag: EntityTagId) -> bool {
+    mask.0 & (1u64 << tag.0) != 0
+}
+
+/// Whether a tag set passes `filter`
+pub fn filter_matches(filter: &EntityTagFilter, mask: EntityTagMask) -> bool {
+    mask.0 & filter.all.0 == filter.all.0 && mask.0 & filter.none.0 == 0
+}
+
+/// Tags carried by `entity`
+pub fn entity_tags(tags: &EntityTagBuffers, entity: u32) -> EntityTagMask {
+    tags.masks.get(entity as usize).copied().unwrap_or_default()
+}
+
+/// Whether `entity` carries `tag`
+pub fn has_entity_tag(tags: &EntityTagBuffers, entity: u32, tag: EntityTagId) -> bool {
+    mask_has_tag(entity_tags(tags, entity), tag)
+}
+
+/// Give `entity` a tag; returns false if it already had it
+pub fn add_entity_tag(tags: &mut EntityTagBuffers, entity: u32, tag: EntityTagId) -> bool {
+    let Some(members) = tags.members.get_mut(tag.0 as usize) else {
+        return false;
+    };
+    let index = entity as usize;
+    if tags.masks.len() <= index {
+        tags.masks.resize(index + 1, EntityTagMask::default());
+    }
+    if mask_has_tag(tags.masks[index], tag) {
+        return false;
+    }
+    tags.masks[index].0 |= 1u64 << tag.0;
+    members.push(entity);
+    true
+}
+
+/// Take a tag from `entity`; returns false if it did not have it
+pub fn remove_entity_tag(tags: &mut EntityTagBuffers, entity: u32, tag: EntityTagId) -> bool {
+    if !has_entity_tag(tags, entity, tag) {
+        return false;
+    }
+    tags.masks[entity as usize].0 &= !(1u64 << tag.0);
+    if let Some(members) = tags.members.get_mut(tag.0 as usize) {
+        if let Some(position) = members.iter().position(|member| *member == entity) {
+            members.swap_remove(position);
+        }
+    }
+    true
+}
+
+/// Drop every tag of `entity` (on despawn, before its index is reused)
+pub fn clear_entity_tags(tags: &mut EntityTagBuffers, entity: u32) {
+    let mask = entity_tags(tags, entity);
+    for bit in 0..MAX_ENTITY_TAGS as u8 {
+        if mask_has_tag(mask, EntityTagId(bit)) {
+            remove_entity_tag(tags, entity, EntityTagId(bit));
+        }
+    }
+}
+
+/// Every entity carrying `tag`, in no particular order
+pub fn tagged_entities(tags: &EntityTagBuffers, tag: EntityTagId) -> &[u32] {
+    tags.members
+        .get(tag.0 as usize)
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+/// Entities within `radius` of `center` whose tags pass `filter`, written
+/// to `out` (cleared first). Only members of the rarest required tag are
+/// visited; a filter without required tags scans every entity.
+pub fn query_entities_in_radius<'a>(
+    tags: &EntityTagBuffers,
+    positions: &EntityPositions,
+    filter: &EntityTagFilter,
+    center: [f32; 3],
+    radius: f32,
+    out: &'a mut Vec<u32>,
+) -> &'a [u32] {
+    out.clear();
+    let radius_sq = radius * radius;
+    let mut visit = |entity: u32| {
+        let Some(position) = positions.get(entity as usize) else {
+            return;
+        };
+        let dx = position[0] - center[0];
+        let dy = position[1] - center[1];
+        let dz = position[2] - center[2];
+        if dx * dx + dy * dy + dz * dz <= radius_sq
+            && filter_matches(filter, entity_tags(tags, entity))
+        {
+            out.push(entity);
+        }
+    };
+
+    let rarest = (0..tags.members.len() as u8)
+        .map(EntityTagId)
+        .filter(|tag| mask_has_tag(filter.all, *tag))
+        .min_by_key(|tag| tags.members[tag.0 as usize].len());
+    match rarest {
+        Some(tag) => tagged_entities(tags, tag).iter().for_each(|e| visit(*e)),
+        None if filter.all.0 != 0 => {} // Requires a tag nobody has interned
+        None => (0..positions.len() as u32).for_each(visit),
+    }
+    out
+}
+
+/// Entities carrying `tag` within `radius` of `center`, written to `out`
+pub fn query_tagged_in_radius<'a>(
+    tags: &EntityTagBuffers,
+    positions: &EntityPositions,
+    tag: EntityTagId,
+    center: [f32; 3],
+    radius: f32,
+    out: &'a mut Vec<u32>,
+) -> &'a [u32] {
+    let filter = EntityTagFilter {
+        all: entity_tag_mask(&[tag]),
+        none: EntityTagMask::default(),
+    };
+    query_entities_in_radius(tags, positions, &filter, center, radius, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_membership_and_radius_queries() {
+        let mut tags = EntityTagBuffers::default();
+        let hostile = intern_entity_tag(&mut tags, "hostile").expect("intern");
+        let boss = intern_entity_tag(&mut tags, "boss").expect("intern");
+        assert_eq!(intern_entity_tag(&mut tags, "hostile"), Ok(hostile));
+        assert_eq!(entity_tag_name(&tags, boss), Some("boss"));
+
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [3.0, 0.0, 0.0],
+            [30.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+        ];
+        for entity in [0, 1, 2] {
+            assert!(add_entity_tag(&mut tags, entity, hostile));
+        }
+        assert!(!add_entity_tag(&mut tags, 1, hostile));
+        add_entity_tag(&mut tags, 1, boss);
+
+        let mut out = Vec::new();
+        let mut near =
+            query_tagged_in_radius(&tags, &positions, hostile, [0.0; 3], 5.0, &mut out).to_vec();
+        near.sort_unstable();
+        assert_eq!(near, vec![0, 1]);
+
+        // Hostile but not a boss; untagged entity 3 is never returned
+        let filter = EntityTagFilter {
+            all: entity_tag_mask(&[hostile]),
+            none: entity_tag_mask(&[boss]),
+        };
+        let minions =
+            query_entities_in_radius(&tags, &positions, &filter, [0.0; 3], 50.0, &mut out);
+        assert_eq!(minions.len(), 2);
+        assert!(!minions.contains(&1) && !minions.contains(&3));
+
+        // The results vector is reused without growing
+        let capacity = out.capacity();
+        query_tagged_in_radius(&tags, &positions, hostile, [0.0; 3], 5.0, &mut out);
+        assert_eq!(out.capacity(), capacity);
+
+        clear_entity_tags(&mut tags, 1);
+        assert_eq!(entity_tags(&tags, 1), EntityTagMask::default());
+        assert!(tagged_entities(&tags, boss).is_empty());
+        assert_eq!(tagged_entities(&tags, hostile).len(), 2);
+
+        for i in 2..MAX_ENTITY_TAGS {
+            intern_entity_tag(&mut tags, &format!("tag{}", i)).expect("intern");
+        }
+        assert!(matches!(
+            intern_entity_tag(&mut tags, "one_too_many"),
+            Err(EntityTagError::TooManyTags { .. })
+        ));
+    }
+}
@@ -435,6 +435,19 @@ impl From<crate::persistence::PersistenceError> for EngineError {
             PersistenceError::CapacityExceeded(e) => EngineError::Internal {
                 message: format!("Capacity exceeded: {}", e),
             },
+            PersistenceError::WorldNotFound(e) => EngineError::Internal {
+                message: format!("World not found: {}", e),
+            },
+            PersistenceError::InvalidWorldName(e) => EngineError::Internal {
+                message: format!("Invalid world name: {}", e),
+            },
+            PersistenceError::EncryptionKeyMissing(_) => EngineError::MissingConfig {
+                field: "save_encryption_key".to_string(),
+            },
+            PersistenceError::WrongEncryptionKey(e) => EngineError::LoadFailed {
+                path: String::new(),
+                error: format!("Wrong save encryption key: {}", e),
+            },
         }
     }
 }
@@ -0,0 +1,165 @@
+//! Feature flag data structures - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Registration, overrides, toggling and reporting happen in
+//! feature_flags_operations.rs
+
+/// Name of the HZB occlusion culling flag
+pub const FLAG_HZB_OCCLUSION: &str = "hzb_occlusion";
+/// Name of the vectorized terrain generation flag
+pub const FLAG_VECTORIZED_TERRAIN: &str = "vectorized_terrain";
+/// Name of the hydraulic erosion flag
+pub const FLAG_EROSION: &str = "erosion";
+/// Name of the shadows flag
+pub const FLAG_SHADOWS: &str = "shadows";
+
+/// A flag known at compile time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureFlagSpec {
+    pub name: &'static str,
+    /// Value before any config file or console override
+    pub default_enabled: bool,
+    pub description: &'static str,
+}
+
+/// Flags every registry starts with
+pub const BUILTIN_FEATURE_FLAGS: [FeatureFlagSpec; 4] = [
+    FeatureFlagSpec {
+        name: FLAG_HZB_OCCLUSION,
+        default_enabled: true,
+        description: "Hierarchical-Z occlusion culling of chunks",
+    },
+    FeatureFlagSpec {
+        name: FLAG_VECTORIZED_TERRAIN,
+        default_enabled: cfg!(any(target_arch = "x86_64", target_arch = "aarch64")),
+        description: "SIMD terrain generation and meshing kernels",
+    },
+    FeatureFlagSpec {
+        name: FLAG_EROSION,
+        default_enabled: true,
+        description: "Hydraulic erosion pass during world generation",
+    },
+    FeatureFlagSpec {
+        name: FLAG_SHADOWS,
+        default_enabled: true,
+        description: "Sun shadows and cloud shadows",
+    },
+];
+
+/// Where a flag's current value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureFlagSource {
+    Default,
+    Config,
+    Runtime,
+}
+
+/// Current value of one flag
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureFlagState {
+    pub name: String,
+    pub description: String,
+    pub enabled: bool,
+    pub default_enabled: bool,
+    pub source: FeatureFlagSource,
+    /// Successful changes since startup
+    pub toggle_count: u32,
+    /// Why the last change was rolled back, if it was
+    pub last_error: Option<String>,
+}
+
+/// Called with the new value before a flag changes, so the feature can
+/// rebuild pipelines or allocate and free buffers. An error cancels the
+/// change.
+pub type FeatureFlagHook = Box<dyn FnMut(bool) -> Result<(), String> + Send>;
+
+/// Handle of a registered hook, for removing it again
+pub type FeatureFlagHookId = u64;
+
+/// Hook registered for one flag
+pub struct FeatureFlagHookEntry {
+    pub id: FeatureFlagHookId,
+    pub flag: String,
+    pub hook: FeatureFlagHook,
+}
+
+/// Change waiting for the next safe point
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingFlagChange {
+    pub flag: String,
+    pub enabled: bool,
+    pub source: FeatureFlagSource,
+}
+
+/// What happened to a pending change when it was applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlagChangeOutcome {
+    Applied,
+    /// The flag already had the requested value
+    Unchanged,
+    /// A hook failed; the flag and the hooks that already ran were reverted
+    RolledBack {
+        error: String,
+    },
+}
+
+/// One applied change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagChangeResult {
+    pub flag: String,
+    pub enabled: bool,
+    pub outcome: FlagChangeOutcome,
+}
+
+/// One entry of a config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureFlagOverride {
+    pub name: String,
+    pub enabled: bool,
+}
+
+/// Flag totals for the metrics buffers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureFlagMetrics {
+    /// Names of the enabled flags
+    pub enabled: Vec<String>,
+    /// Flags that differ from their compile-time default
+    pub overridden: u32,
+    pub changes_applied: u64,
+    pub changes_rolled_back: u64,
+}
+
+/// All flags, their hooks and the changes waiting to be applied
+#[derive(Default)]
+pub struct FeatureFlagRegistry {
+    pub flags: Vec<FeatureFlagState>,
+    pub hooks: Vec<FeatureFlagHookEntry>,
+    /// Id handed to the next hook
+    pub next_hook_id: FeatureFlagHookId,
+    /// Applied in order by `apply_feature_flag_changes`
+    pub pending: Vec<PendingFlagChange>,
+    pub changes_applied: u64,
+    pub changes_rolled_back: u64,
+}
+
+/// Feature flag errors
+#[derive(Debug, thiserror::Error)]
+pub enum FeatureFlagError {
+    #[error("Unknown feature flag: {name}")]
+    UnknownFlag { name: String },
+
+    #[error("Feature flag already registered: {name}")]
+    AlreadyRegistered { name: String },
+
+    #[error("Unknown feature command: {command}")]
+    UnknownCommand { command: String },
+
+    #[error("Invalid feature flag config: {message}")]
+    InvalidConfig { message: String },
+
+    #[error("Failed to read feature flag config {path}: {message}")]
+    Io { path: String, message: String },
+}
+
+/// Result type for feature flag operations
+pub type FeatureFlagResult<T> = Result<T, FeatureFlagError>;
@@ -0,0 +1,510 @@
+//! Feature flag operations - Pure DOP
+//!
+//! Functions that register flags, read config overrides, queue and apply
+//! runtime toggles and report flag state. Toggles never take effect where
+//! they are requested: they wait for `apply_feature_flag_changes`, which
+//! the engine calls at a frame boundary where hooks can safely rebuild
+//! pipelines and buffers.
+
+use super::feature_flags_data::{
+    FeatureFlagError, FeatureFlagHook, FeatureFlagHookEntry, FeatureFlagHookId, FeatureFlagMetrics,
+    FeatureFlagOverride, FeatureFlagRegistry, FeatureFlagResult, FeatureFlagSource,
+    FeatureFlagState, FlagChangeOutcome, FlagChangeResult, PendingFlagChange,
+    BUILTIN_FEATURE_FLAGS,
+};
+use crate::constants::feature_flags::CONFIG_TABLE;
+use std::path::Path;
+
+// ============================================================================
+// REGISTRATION
+// ============================================================================
+
+/// Registry holding the built-in flags at their compile-time defaults
+pub fn create_feature_flag_registry() -> FeatureFlagRegistry {
+    let mut registry = FeatureFlagRegistry::default();
+    for spec in BUILTIN_FEATURE_FLAGS {
+        registry.flags.push(FeatureFlagState {
+            name: spec.name.to_string(),
+            description: spec.description.to_string(),
+            enabled: spec.default_enabled,
+            default_enabled: spec.default_enabled,
+            source: FeatureFlagSource::Default,
+            toggle_count: 0,
+            last_error: None,
+        });
+    }
+    registry
+}
+
+/// Add a flag owned by a game or plugin
+pub fn register_feature_flag(
+    registry: &mut FeatureFlagRegistry,
+    name: &str,
+    default_enabled: bool,
+    description: &str,
+) -> FeatureFlagResult<()> {
+    if find_feature_flag(registry, name).is_some() {
+        return Err(FeatureFlagError::AlreadyRegistered {
+            name: name.to_string(),
+        });
+    }
+    registry.flags.push(FeatureFlagState {
+        name: name.to_string(),
+        description: description.to_string(),
+        enabled: default_enabled,
+        default_enabled,
+        source: FeatureFlagSource::Default,
+        toggle_count: 0,
+        last_error: None,
+    });
+    Ok(())
+}
+
+/// Run `hook` before every change of `flag`. Hooks of one flag run in the
+/// order they were added.
+pub fn add_feature_flag_hook(
+    registry: &mut FeatureFlagRegistry,
+    flag: &str,
+    hook: FeatureFlagHook,
+) -> FeatureFlagResult<FeatureFlagHookId> {
+    if find_feature_flag(registry, flag).is_none() {
+        return Err(FeatureFlagError::UnknownFlag {
+            name: flag.to_string(),
+        });
+    }
+    let id = registry.next_hook_id;
+    registry.next_hook_id += 1;
+    registry.hooks.push(FeatureFlagHookEntry {
+        id,
+        flag: flag.to_string(),
+        hook,
+    });
+    Ok(id)
+}
+
+/// Stop running a hook; false if it was already removed
+pub fn remove_feature_flag_hook(registry: &mut FeatureFlagRegistry, id: FeatureFlagHookId) -> bool {
+    let before = registry.hooks.len();
+    registry.hooks.retain(|entry| entry.id != id);
+    registry.hooks.len() != before
+}
+
+// ============================================================================
+// QUERIES
+// ============================================================================
+
+pub fn find_feature_flag<'a>(
+    registry: &'a FeatureFlagRegistry,
+    name: &str,
+) -> Option<&'a FeatureFlagState> {
+    registry.flags.iter().find(|flag| flag.name == name)
+}
+
+/// Current value of a flag (false for unknown flags)
+pub fn is_feature_enabled(registry: &FeatureFlagRegistry, name: &str) -> bool {
+    find_feature_flag(registry, name).is_some_and(|flag| flag.enabled)
+}
+
+/// Value a flag will have once pending changes are applied
+pub fn requested_feature_state(registry: &FeatureFlagRegistry, name: &str) -> Option<bool> {
+    registry
+        .pending
+        .iter()
+        .rev()
+        .find(|change| change.flag == name)
+        .map(|change| change.enabled)
+        .or_else(|| find_feature_flag(registry, name).map(|flag| flag.enabled))
+}
+
+// ============================================================================
+// CHANGES
+// ============================================================================
+
+/// Queue a change for the next `apply_feature_flag_changes`
+pub fn request_feature_flag(
+    registry: &mut FeatureFlagRegistry,
+    name: &str,
+    enabled: bool,
+    source: FeatureFlagSource,
+) -> FeatureFlagResult<()> {
+    if find_feature_flag(registry, name).is_none() {
+        return Err(FeatureFlagError::UnknownFlag {
+            name: name.to_string(),
+        });
+    }
+    registry.pending.push(PendingFlagChange {
+        flag: name.to_string(),
+        enabled,
+        source,
+    });
+    Ok(())
+}
+
+/// Apply queued changes in order. Each change runs the flag's hooks with
+/// the new value; if one fails, the hooks that already ran are called
+/// again with the old value and the flag keeps its old value.
+pub fn apply_feature_flag_changes(registry: &mut FeatureFlagRegistry) -> Vec<FlagChangeResult> {
+    let pending = std::mem::take(&mut registry.pending);
+    let mut results = Vec::with_capacity(pending.len());
+
+    for change in pending {
+        let Some(index) = registry.flags.iter().position(|f| f.name == change.flag) else {
+            continue;
+        };
+        let old = registry.flags[index].enabled;
+        if old == change.enabled {
+            // Still record where the value now comes from
+            registry.flags[index].source = change.source;
+            results.push(FlagChangeResult {
+                flag: change.flag,
+                enabled: change.enabled,
+                outcome: FlagChangeOutcome::Unchanged,
+            });
+            continue;
+        }
+
+        let mut ran = 0;
+        let mut failure = None;
+        for entry in registry
+            .hooks
+            .iter_mut()
+            .filter(|entry| entry.flag == change.flag)
+        {
+            if let Err(error) = (entry.hook)(change.enabled) {
+                failure = Some(error);
+                break;
+            }
+            ran += 1;
+        }
+
+        let flag = &mut registry.flags[index];
+        let outcome = match failure {
+            None => {
+                flag.enabled = change.enabled;
+                flag.source = change.source;
+                flag.toggle_count += 1;
+                flag.last_error = None;
+                registry.changes_applied += 1;
+                log::info!(
+                    "[FeatureFlags] {} {}",
+                    change.flag,
+                    if change.enabled {
+                        "enabled"
+                    } else {
+                        "disabled"
+                    }
+                );
+                FlagChangeOutcome::Applied
+            }
+            Some(error) => {
+                for entry in registry
+                    .hooks
+                    .iter_mut()
+                    .filter(|entry| entry.flag == change.flag)
+                    .take(ran)
+                {
+                    if let Err(revert) = (entry.hook)(old) {
+                        log::error!(
+                            "[FeatureFlags] Reverting {} failed: {}",
+                            change.flag,
+                            revert
+                        );
+                    }
+                }
+                log::warn!(
+                    "[FeatureFlags] Change of {} rolled back: {}",
+                    change.flag,
+                    error
+                );
+                flag.last_error = Some(error.clone());
+                registry.changes_rolled_back += 1;
+                FlagChangeOutcome::RolledBack { error }
+            }
+        };
+        results.push(FlagChangeResult {
+            flag: change.flag,
+            enabled: change.enabled,
+            outcome,
+        });
+    }
+    results
+}
+
+// ============================================================================
+// CONFIG FILE
+// ============================================================================
+
+/// Parse a TOML override file: `flag = true/false` entries, either in a
+/// `[features]` table or at the top level
+pub fn parse_feature_flag_config(text: &str) -> FeatureFlagResult<Vec<FeatureFlagOverride>> {
+    let root: toml::Table =
+        text.parse()
+            .map_err(|e: toml::de::Error| FeatureFlagError::InvalidConfig {
+                message: e.to_string(),
+            })?;
+    let table = match root.get(CONFIG_TABLE) {
+        Some(toml::Value::Table(table)) => table,
+        Some(_) => {
+            return Err(FeatureFlagError::InvalidConfig {
+                message: format!("'{}' must be a table", CONFIG_TABLE),
+            })
+        }
+        None => &root,
+    };
+
+    table
+        .iter()
+        .map(|(name, value)| match value {
+            toml::Value::Boolean(enabled) => Ok(FeatureFlagOverride {
+                name: name.clone(),
+                enabled: *enabled,
+            }),
+            other => Err(FeatureFlagError::InvalidConfig {
+                message: format!("'{}' must be true or false, found {}", name, other),
+            }),
+        })
+        .collect()
+}
+
+/// Queue config overrides and apply them. Unknown flags are skipped with a
+/// warning so an old config file does not stop the engine.
+pub fn apply_feature_flag_overrides(
+    registry: &mut FeatureFlagRegistry,
+    overrides: &[FeatureFlagOverride],
+) -> Vec<FlagChangeResult> {
+    for entry in overrides {
+        if let Err(e) = request_feature_flag(
+            registry,
+            &entry.name,
+            entry.enabled,
+            FeatureFlagSource::Config,
+        ) {
+            log::warn!("[FeatureFlags] Ignoring config entry: {}", e);
+        }
+    }
+    apply_feature_flag_changes(registry)
+}
+
+/// Read and apply an override file; a missing file is not an error
+pub fn load_feature_flag_config(
+    registry: &mut FeatureFlagRegistry,
+    path: &Path,
+) -> FeatureFlagResult<Vec<FlagChangeResult>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(FeatureFlagError::Io {
+                path: path.display().to_string(),
+                message: e.to_string(),
+            })
+        }
+    };
+    let overrides = parse_feature_flag_config(&text)?;
+    Ok(apply_feature_flag_overrides(registry, &overrides))
+}
+
+// ============================================================================
+// REPORTING
+// ============================================================================
+
+fn source_name(source: FeatureFlagSource) -> &'static str {
+    match source {
+        FeatureFlagSource::Default => "default",
+        FeatureFlagSource::Config => "config",
+        FeatureFlagSource::Runtime => "runtime",
+    }
+}
+
+/// One line per flag: `name=on (config)`, plus the last rollback error
+pub fn format_feature_flag(flag: &FeatureFlagState) -> String {
+    let mut line = format!(
+        "{}={} ({})",
+        flag.name,
+        if flag.enabled { "on" } else { "off" },
+        source_name(flag.source)
+    );
+    if let Some(error) = &flag.last_error {
+        line.push_str(&format!(" last change failed: {}", error));
+    }
+    line
+}
+
+/// Flag lines for crash reports and the console
+pub fn feature_flag_report_lines(registry: &FeatureFlagRegistry) -> Vec<String> {
+    registry.flags.iter().map(format_feature_flag).collect()
+}
+
+/// Flag totals for `MetricsBuffers::feature_flags`
+pub fn feature_flag_metrics(registry: &FeatureFlagRegistry) -> FeatureFlagMetrics {
+    FeatureFlagMetrics {
+        enabled: registry
+            .flags
+            .iter()
+            .filter(|flag| flag.enabled)
+            .map(|flag| flag.name.clone())
+            .collect(),
+        overridden: registry
+            .flags
+            .iter()
+            .filter(|flag| flag.enabled != flag.default_enabled)
+            .count() as u32,
+        changes_applied: registry.changes_applied,
+        changes_rolled_back: registry.changes_rolled_back,
+    }
+}
+
+// ============================================================================
+// CONSOLE
+// ============================================================================
+
+fn queue_console_change(
+    registry: &mut FeatureFlagRegistry,
+    name: &str,
+    enabled: bool,
+    source: FeatureFlagSource,
+) -> FeatureFlagResult<String> {
+    request_feature_flag(registry, name, enabled, source)?;
+    let key = if enabled {
+        "console.feature.queued_on"
+    } else {
+        "console.feature.queued_off"
+    };
+    Ok(crate::tr!(key, name = name))
+}
+
+/// Run a `feature` console command and return the text to print:
+///
+/// - `feature list`
+/// - `feature on <flag>` / `feature off <flag>` / `feature toggle <flag>`
+/// - `feature reset <flag>` / `feature reset all` - back to compile-time defaults
+/// - `feature info <flag>`
+///
+/// Changes are queued and applied at the next frame boundary.
+pub fn execute_feature_command(
+    registry: &mut FeatureFlagRegistry,
+    command: &str,
+) -> FeatureFlagResult<String> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let args = match words.first() {
+        Some(&"feature") => &words[1..],
+        _ => &words[..],
+    };
+    let unknown_flag = |name: &str| FeatureFlagError::UnknownFlag {
+        name: name.to_string(),
+    };
+
+    match args {
+        ["list"] | [] => Ok(feature_flag_report_lines(registry).join("\n")),
+        ["on", name] => queue_console_change(registry, name, true, FeatureFlagSource::Runtime),
+        ["off", name] => queue_console_change(registry, name, false, FeatureFlagSource::Runtime),
+        ["toggle", name] => {
+            let current =
+                requested_feature_state(registry, name).ok_or_else(|| unknown_flag(name))?;
+            queue_console_change(registry, name, !current, FeatureFlagSource::Runtime)
+        }
+        ["reset", "all"] => {
+            let defaults: Vec<_> = registry
+                .flags
+                .iter()
+                .map(|flag| (flag.name.clone(), flag.default_enabled))
+                .collect();
+            for (name, enabled) in &defaults {
+                request_feature_flag(registry, name, *enabled, FeatureFlagSource::Default)?;
+            }
+            Ok(crate::tr!(
+                "console.feature.reset_all",
+                count = defaults.len()
+            ))
+        }
+        ["reset", name] => {
+            let default = find_feature_flag(registry, name)
+                .map(|flag| flag.default_enabled)
+                .ok_or_else(|| unknown_flag(name))?;
+            queue_console_change(registry, name, default, FeatureFlagSource::Default)
+        }
+        ["info", name] => {
+            let flag = find_feature_flag(registry, name).ok_or_else(|| unknown_flag(name))?;
+            let default = if flag.default_enabled {
+                crate::tr!("console.feature.on")
+            } else {
+                crate::tr!("console.feature.off")
+            };
+            Ok(crate::tr!(
+                "console.feature.info",
+                flag = format_feature_flag(flag),
+                description = flag.description,
+                default = default,
+                count = flag.toggle_count,
+            ))
+        }
+        _ => Err(FeatureFlagError::UnknownCommand {
+            command: command.trim().to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feature_flags::feature_flags_data::{FLAG_HZB_OCCLUSION, FLAG_SHADOWS};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_failed_hook_rolls_back_toggle() {
+        let mut registry = create_feature_flag_registry();
+        let rebuilds = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&rebuilds);
+        assert!(add_feature_flag_hook(
+            &mut registry,
+            FLAG_SHADOWS,
+            Box::new(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }),
+        )
+        .is_ok());
+        assert!(add_feature_flag_hook(
+            &mut registry,
+            FLAG_SHADOWS,
+            Box::new(|enabled| {
+                if enabled {
+                    Ok(())
+                } else {
+                    Err("shadow atlas is in use".to_string())
+                }
+            }),
+        )
+        .is_ok());
+
+        // Queued, not applied, until the frame boundary
+        assert!(execute_feature_command(&mut registry, "feature off shadows").is_ok());
+        assert!(is_feature_enabled(&registry, FLAG_SHADOWS));
+
+        let results = apply_feature_flag_changes(&mut registry);
+        assert!(matches!(
+            results[0].outcome,
+            FlagChangeOutcome::RolledBack { .. }
+        ));
+        assert!(is_feature_enabled(&registry, FLAG_SHADOWS));
+        // The first hook ran for the change and again for the revert
+        assert_eq!(rebuilds.load(Ordering::SeqCst), 2);
+        assert_eq!(feature_flag_metrics(&registry).changes_rolled_back, 1);
+    }
+
+    #[test]
+    fn test_config_overrides_and_report() {
+        let mut registry = create_feature_flag_registry();
+        let overrides =
+            parse_feature_flag_config("[features]\nhzb_occlusion = false\nold_flag = true\n")
+                .expect("config");
+        let results = apply_feature_flag_overrides(&mut registry, &overrides);
+        assert_eq!(results.len(), 1);
+        assert!(!is_feature_enabled(&registry, FLAG_HZB_OCCLUSION));
+
+        let report = feature_flag_report_lines(&registry);
+        assert!(report.contains(&"hzb_occlusion=off (config)".to_string()));
+        assert!(parse_feature_flag_config("shadows = 1").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+/// Feature Flags Module - Data-Oriented Programming (DOP) style
+///
+/// This module follows pure DOP principles:
+/// - feature_flags_data.rs: Pure data structures with NO methods
+/// - feature_flags_operations.rs: Pure functions that operate on data
+///
+/// Expensive features (HZB occlusion, vectorized terrain, erosion,
+/// shadows) are gated by named flags. Flags start at compile-time defaults,
+/// can be overridden by `features.toml` and toggled at runtime through the
+/// `feature` console command. Runtime changes run per-feature hooks at a
+/// frame boundary and are rolled back if a hook fails. Flag state is
+/// written into crash reports and the metrics buffers.
+pub mod feature_flags_data;
+pub mod feature_flags_operations;
+
+use parking_lot::Mutex;
+
+// Re-export data structures
+pub use feature_flags_data::{
+    FeatureFlagError, FeatureFlagHook, FeatureFlagHookEntry, FeatureFlagHookId, FeatureFlagMetrics,
+    FeatureFlagOverride, FeatureFlagRegistry, FeatureFlagResult, FeatureFlagSource,
+    FeatureFlagSpec, FeatureFlagState, FlagChangeOutcome, FlagChangeResult, PendingFlagChange,
+    BUILTIN_FEATURE_FLAGS, FLAG_EROSION, FLAG_HZB_OCCLUSION, FLAG_SHADOWS, FLAG_VECTORIZED_TERRAIN,
+};
+
+// Re-export all operations
+pub use feature_flags_operations::{
+    add_feature_flag_hook, apply_feature_flag_changes, apply_feature_flag_overrides,
+    create_feature_flag_registry, execute_feature_command, feature_flag_metrics,
+    feature_flag_report_lines, find_feature_flag, format_feature_flag, is_feature_enabled,
+    load_feature_flag_config, parse_feature_flag_config, register_feature_flag,
+    remove_feature_flag_hook, request_feature_flag, requested_feature_state,
+};
+
+lazy_static::lazy_static! {
+    /// Flags shared by every engine system
+    pub static ref GLOBAL_FEATURE_FLAGS: Mutex<FeatureFlagRegistry> =
+        Mutex::new(create_feature_flag_registry());
+}
+
+/// Whether a global flag is on
+pub fn feature_enabled(name: &str) -> bool {
+    is_feature_enabled(&GLOBAL_FEATURE_FLAGS.lock(), name)
+}
+
+/// Apply `constants::feature_flags::CONFIG_FILE` (or another path) to the
+/// global flags; call once at startup before systems register hooks
+pub fn load_global_feature_flags(
+    path: &std::path::Path,
+) -> FeatureFlagResult<Vec<FlagChangeResult>> {
+    load_feature_flag_config(&mut GLOBAL_FEATURE_FLAGS.lock(), path)
+}
+
+/// Run `hook` before every change of a global flag. Hooks run while the
+/// flags are locked, so they must not query the flags themselves.
+pub fn add_global_feature_flag_hook(
+    flag: &str,
+    hook: FeatureFlagHook,
+) -> FeatureFlagResult<FeatureFlagHookId> {
+    add_feature_flag_hook(&mut GLOBAL_FEATURE_FLAGS.lock(), flag, hook)
+}
+
+/// Remove a hook added by `add_global_feature_flag_hook`
+pub fn remove_global_feature_flag_hook(id: FeatureFlagHookId) -> bool {
+    remove_feature_flag_hook(&mut GLOBAL_FEATURE_FLAGS.lock(), id)
+}
+
+/// Run a `feature ...` console command against the global flags
+pub fn run_feature_command(command: &str) -> FeatureFlagResult<String> {
+    execute_feature_command(&mut GLOBAL_FEATURE_FLAGS.lock(), command)
+}
+
+/// Apply queued global flag changes; call once per frame, between frames
+pub fn apply_global_feature_flag_changes() -> Vec<FlagChangeResult> {
+    apply_feature_flag_changes(&mut GLOBAL_FEATURE_FLAGS.lock())
+}
+
+/// Copy global flag totals into the metrics buffers
+pub fn record_feature_flag_metrics(metrics: &mut crate::MetricsBuffers) {
+    metrics.feature_flags = feature_flag_metrics(&GLOBAL_FEATURE_FLAGS.lock());
+}
+
+/// Flag state for a crash report. Never blocks: returns nothing if the
+/// flags are locked (e.g. a panic inside a hook).
+pub fn crash_report_feature_flags() -> Vec<String> {
+    match GLOBAL_FEATURE_FLAGS.try_lock() {
+        Some(registry) => feature_flag_report_lines(&registry),
+        None => Vec::new(),
+    }
+}
@@ -0,0 +1,80 @@
+//! Action Limit Data - Cooldowns for block place, break and interact
+//!
+//! Each player has a small budget per action that refills at the action's
+//! cooldown, so a player can act a few times in quick succession but not
+//! faster than the cooldown over time. The server enforces the budgets and
+//! turns refused actions into `GameEvent::BlockActionRejected`; the client
+//! runs the same budgets on its own actions to grey out input before the
+//! server answers, and resynchronizes from the rejections it receives.
+//!
+//! Pure DOP: No methods, just data structures.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Rate-limited block action
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BlockAction {
+    Place = 0,
+    Break = 1,
+    Interact = 2,
+}
+
+/// Number of `BlockAction` variants
+pub const BLOCK_ACTION_COUNT: usize = 3;
+
+/// Player id and the action they attempted
+pub type PlayerBlockAction = (u32, BlockAction);
+
+/// Limit of one action
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActionCooldown {
+    /// Time to regain one use (milliseconds, 0 = unlimited)
+    pub cooldown_ms: u32,
+    /// Uses available back to back after waiting
+    pub burst: u32,
+}
+
+/// Limits of every action, indexed by `BlockAction as usize`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActionLimitConfig {
+    pub actions: [ActionCooldown; BLOCK_ACTION_COUNT],
+}
+
+/// Remaining uses of one action for one player
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ActionBudget {
+    /// Uses available, fractional while refilling
+    pub tokens: f32,
+    /// Time the tokens were last brought up to date (milliseconds)
+    pub updated_ms: u64,
+}
+
+/// Why an action was refused
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ActionRejection {
+    pub action: BlockAction,
+    /// Time until the next use is available (milliseconds)
+    pub retry_in_ms: u32,
+}
+
+/// Budgets of one player, indexed by `BlockAction as usize`
+pub type ActionBudgets = [ActionBudget; BLOCK_ACTION_COUNT];
+
+/// Refusal counters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ActionLimitStats {
+    /// Actions allowed, indexed by `BlockAction as usize`
+    pub allowed: [u64; BLOCK_ACTION_COUNT],
+    /// Actions refused, indexed by `BlockAction as usize`
+    pub rejected: [u64; BLOCK_ACTION_COUNT],
+}
+
+/// Budgets of every player, on the server or predicted on a client
+#[derive(Clone, Debug)]
+pub struct ActionLimitData {
+    pub config: ActionLimitConfig,
+    /// Budgets by player id
+    pub players: HashMap<u32, ActionBudgets>,
+    pub stats: ActionLimitStats,
+}
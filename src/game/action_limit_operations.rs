@@ -0,0 +1,315 @@
+//! Action Limit Operations - Pure functions for block action cooldowns
+//!
+//! Server: pass player block events through `limit_block_events` before
+//! applying them; the gateway does this for every player block event it
+//! queues, on the clock `update_gateway` advances. Refused ones come back
+//! as `BlockActionRejected` for the client. Client: call
+//! `try_block_action` before sending an action, show
+//! `action_cooldown_remaining` in the UI, and `apply_action_rejection` for
+//! every rejection the server sends back.
+
+use super::action_limit_data::{
+    ActionBudget, ActionBudgets, ActionCooldown, ActionLimitConfig, ActionLimitData,
+    ActionLimitStats, ActionRejection, BlockAction, PlayerBlockAction, BLOCK_ACTION_COUNT,
+};
+use super::gateway_data::GameEvent;
+use crate::constants::block_action_limits::{
+    ACTION_BURST, BREAK_COOLDOWN_MS, INTERACT_COOLDOWN_MS, PLACE_COOLDOWN_MS,
+};
+use std::collections::HashMap;
+
+/// Limits from the engine constants
+pub fn default_action_limit_config() -> ActionLimitConfig {
+    let limit = |cooldown_ms| ActionCooldown {
+        cooldown_ms,
+        burst: ACTION_BURST,
+    };
+    ActionLimitConfig {
+        actions: [
+            limit(PLACE_COOLDOWN_MS),
+            limit(BREAK_COOLDOWN_MS),
+            limit(INTERACT_COOLDOWN_MS),
+        ],
+    }
+}
+
+/// No players yet
+pub fn create_action_limits(config: ActionLimitConfig) -> ActionLimitData {
+    ActionLimitData {
+        config,
+        players: HashMap::new(),
+        stats: ActionLimitStats::default(),
+    }
+}
+
+/// Change one action's limit; budgets refill at the new rate from now on
+pub fn set_action_cooldown(data: &mut ActionLimitData, action: BlockAction, limit: ActionCooldown) {
+    data.config.actions[action as usize] = limit;
+}
+
+/// Forget a player who left
+pub fn remove_action_limit_player(data: &mut ActionLimitData, player_id: u32) {
+    data.players.remove(&player_id);
+}
+
+/// Player and action of a rate-limited event (`None` for other events and
+/// actions without a player)
+pub fn block_action_of(event: &GameEvent) -> Option<PlayerBlockAction> {
+    match event {
+        GameEvent::BlockPlace {
+            player_id: Some(player),
+            ..
+        } => Some((*player, BlockAction::Place)),
+        GameEvent::BlockBreak {
+            player_id: Some(player),
+            ..
+        } => Some((*player, BlockAction::Break)),
+        GameEvent::BlockInteract {
+            player_id: Some(player),
+            ..
+        } => Some((*player, BlockAction::Interact)),
+        _ => None,
+    }
+}
+
+fn burst(limit: &ActionCooldown) -> f32 {
+    limit.burst.max(1) as f32
+}
+
+/// Budget brought up to `now_ms`
+fn refilled(budget: ActionBudget, limit: &ActionCooldown, now_ms: u64) -> ActionBudget {
+    let tokens = if limit.cooldown_ms == 0 {
+        burst(limit)
+    } else {
+        let elapsed = now_ms.saturating_sub(budget.updated_ms) as f32;
+        (budget.tokens + elapsed / limit.cooldown_ms as f32).min(burst(limit))
+    };
+    ActionBudget {
+        tokens,
+        updated_ms: now_ms.max(budget.updated_ms),
+    }
+}
+
+fn player_budgets(data: &mut ActionLimitData, player_id: u32, now_ms: u64) -> &mut ActionBudgets {
+    let config = data.config;
+    data.players.entry(player_id).or_insert_with(|| {
+        [0, 1, 2].map(|index| ActionBudget {
+            tokens: burst(&config.actions[index]),
+            updated_ms: now_ms,
+        })
+    })
+}
+
+fn retry_in_ms(budget: &ActionBudget, limit: &ActionCooldown) -> u32 {
+    ((1.0 - budget.tokens).max(0.0) * limit.cooldown_ms as f32).ceil() as u32
+}
+
+/// Spend one use of an action, or say how long until the next one
+pub fn try_block_action(
+    data: &mut ActionLimitData,
+    player_id: u32,
+    action: BlockAction,
+    now_ms: u64,
+) -> Result<(), ActionRejection> {
+    let limit = data.config.actions[action as usize];
+    let budgets = player_budgets(data, player_id, now_ms);
+    let budget = refilled(budgets[action as usize], &limit, now_ms);
+
+    if budget.tokens >= 1.0 {
+        budgets[action as usize] = ActionBudget {
+            tokens: budget.tokens - 1.0,
+            ..budget
+        };
+        data.stats.allowed[action as usize] += 1;
+        return Ok(());
+    }
+    budgets[action as usize] = budget;
+    data.stats.rejected[action as usize] += 1;
+    Err(ActionRejection {
+        action,
+        retry_in_ms: retry_in_ms(&budget, &limit),
+    })
+}
+
+/// Time until a player can use an action again (0 when ready), for
+/// cooldown indicators
+pub fn action_cooldown_remaining(
+    data: &ActionLimitData,
+    player_id: u32,
+    action: BlockAction,
+    now_ms: u64,
+) -> u32 {
+    let limit = &data.config.actions[action as usize];
+    data.players
+        .get(&player_id)
+        .map(|budgets| retry_in_ms(&refilled(budgets[action as usize], limit, now_ms), limit))
+        .unwrap_or(0)
+}
+
+/// Whether a player can use an action now
+pub fn action_ready(
+    data: &ActionLimitData,
+    player_id: u32,
+    action: BlockAction,
+    now_ms: u64,
+) -> bool {
+    action_cooldown_remaining(data, player_id, action, now_ms) == 0
+}
+
+/// Client: take the server's word on a refused action so the predicted
+/// cooldown ends when the server's does
+pub fn apply_action_rejection(
+    data: &mut ActionLimitData,
+    player_id: u32,
+    rejection: &ActionRejection,
+    now_ms: u64,
+) {
+    let limit = data.config.actions[rejection.action as usize];
+    if limit.cooldown_ms == 0 {
+        return;
+    }
+    let budgets = player_budgets(data, player_id, now_ms);
+    budgets[rejection.action as usize] = ActionBudget {
+        tokens: 1.0 - rejection.retry_in_ms as f32 / limit.cooldown_ms as f32,
+        updated_ms: now_ms,
+    };
+}
+
+/// Rejection event for a refused block event
+fn rejection_event(event: &GameEvent, rejection: ActionRejection) -> Option<GameEvent> {
+    let (player_id, position) = match event {
+        GameEvent::BlockPlace {
+            player_id: Some(player),
+            position,
+            ..
+        }
+        | GameEvent::BlockBreak {
+            player_id: Some(player),
+            position,
+            ..
+        }
+        | GameEvent::BlockInteract {
+            player_id: Some(player),
+            position,
+            ..
+        } => (*player, *position),
+        _ => return None,
+    };
+    Some(GameEvent::BlockActionRejected {
+        player_id,
+        action: rejection.action,
+        position,
+        retry_in_ms: rejection.retry_in_ms,
+    })
+}
+
+/// Check one event against its player's budget: the event itself when
+/// allowed or not rate-limited, a `BlockActionRejected` when refused
+pub fn limit_block_event(data: &mut ActionLimitData, event: GameEvent, now_ms: u64) -> GameEvent {
+    let Some((player_id, action)) = block_action_of(&event) else {
+        return event;
+    };
+    match try_block_action(data, player_id, action, now_ms) {
+        Ok(()) => event,
+        Err(rejection) => rejection_event(&event, rejection).unwrap_or(event),
+    }
+}
+
+/// `limit_block_event` for a batch, in order
+pub fn limit_block_events(
+    data: &mut ActionLimitData,
+    events: Vec<GameEvent>,
+    now_ms: u64,
+) -> Vec<GameEvent> {
+    events
+        .into_iter()
+        .map(|event| limit_block_event(data, event, now_ms))
+        .collect()
+}
+
+/// Actions refused so far, indexed by `BlockAction as usize`
+pub fn rejected_action_counts(data: &ActionLimitData) -> [u64; BLOCK_ACTION_COUNT] {
+    data.stats.rejected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::core::{BlockId, VoxelPos};
+
+    fn place(player: u32) -> GameEvent {
+        GameEvent::BlockPlace {
+            position: VoxelPos { x: 1, y: 2, z: 3 },
+            block_id: BlockId(1),
+            metadata: 0,
+            player_id: Some(player),
+        }
+    }
+
+    #[test]
+    fn test_burst_then_cooldown_per_player() {
+        let mut data = create_action_limits(default_action_limit_config());
+        let place_ms = PLACE_COOLDOWN_MS as u64;
+
+        for _ in 0..ACTION_BURST {
+            assert!(try_block_action(&mut data, 1, BlockAction::Place, 0).is_ok());
+        }
+        let rejection =
+            try_block_action(&mut data, 1, BlockAction::Place, 0).expect_err("budget spent");
+        assert_eq!(rejection.retry_in_ms, PLACE_COOLDOWN_MS);
+
+        // Other players and other actions have their own budgets
+        assert!(try_block_action(&mut data, 2, BlockAction::Place, 0).is_ok());
+        assert!(try_block_action(&mut data, 1, BlockAction::Break, 0).is_ok());
+
+        assert_eq!(
+            action_cooldown_remaining(&data, 1, BlockAction::Place, place_ms / 2),
+            PLACE_COOLDOWN_MS / 2
+        );
+        assert!(action_ready(&data, 1, BlockAction::Place, place_ms));
+        assert!(try_block_action(&mut data, 1, BlockAction::Place, place_ms).is_ok());
+        assert_eq!(
+            rejected_action_counts(&data)[BlockAction::Place as usize],
+            1
+        );
+    }
+
+    #[test]
+    fn test_refused_events_become_rejections_and_sync_the_client() {
+        let mut server = create_action_limits(default_action_limit_config());
+        let events = vec![
+            place(1),
+            place(1),
+            place(1),
+            GameEvent::PlayerLeave { player_id: 1 },
+        ];
+        let limited = limit_block_events(&mut server, events, 0);
+        assert!(matches!(limited[1], GameEvent::BlockPlace { .. }));
+        assert!(matches!(limited[3], GameEvent::PlayerLeave { .. }));
+        let GameEvent::BlockActionRejected {
+            player_id,
+            action,
+            retry_in_ms,
+            ..
+        } = limited[2]
+        else {
+            panic!("third placement should be refused");
+        };
+        assert_eq!((player_id, action), (1, BlockAction::Place));
+
+        // A client that missed the burst learns the cooldown from the server
+        let mut client = create_action_limits(default_action_limit_config());
+        let rejection = ActionRejection {
+            action,
+            retry_in_ms,
+        };
+        apply_action_rejection(&mut client, 1, &rejection, 50);
+        assert!(!action_ready(&client, 1, BlockAction::Place, 50));
+        assert!(action_ready(
+            &client,
+            1,
+            BlockAction::Place,
+            50 + retry_in_ms as u64
+        ));
+    }
+}
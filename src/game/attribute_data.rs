@@ -0,0 +1,133 @@
+//! Entity Attribute Data - Stats with buffs and debuffs
+//!
+//! Attributes (health, speed, damage, ...) are registered once and stored as
+//! SoA columns: one column per attribute, one row per entity. Each entity
+//! also keeps a list of modifiers. The effective value is
+//! `(base + sum(additive)) * product(multiplicative)`, clamped to the
+//! attribute's range, and is cached so reads never recompute.
+//!
+//! Pure DOP: No methods, just data structures.
+
+use crate::instance::InstanceId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Index of a registered attribute
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AttributeId(pub u16);
+
+/// Game-defined origin of a modifier (item, spell, aura, ...)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ModifierSource(pub u64);
+
+/// Registered attribute
+#[derive(Clone, Debug)]
+pub struct AttributeDefinition {
+    pub id: AttributeId,
+    pub name: String,
+    /// Base value for entities that never set one
+    pub default_base: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+/// How a modifier combines with the base value
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModifierKind {
+    /// Added to the base value
+    Additive,
+    /// Multiplies the sum of base and additive modifiers
+    Multiplicative,
+}
+
+/// Buff or debuff on one attribute of one entity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AttributeModifier {
+    pub attribute: AttributeId,
+    pub kind: ModifierKind,
+    pub value: f32,
+    pub source: ModifierSource,
+    /// Tick at which the modifier is removed (None = permanent)
+    pub expires_at_tick: Option<u64>,
+}
+
+/// Modifiers of one entity
+pub type ModifierList = Vec<AttributeModifier>;
+
+/// Values of one attribute for every entity (indexed by entity row)
+#[derive(Clone, Debug, Default)]
+pub struct AttributeColumn {
+    pub base: Vec<f32>,
+    pub effective: Vec<f32>,
+}
+
+/// Effective value change, for UI and game logic to react to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AttributeChange {
+    pub entity: InstanceId,
+    pub attribute: AttributeId,
+    pub old_value: f32,
+    pub new_value: f32,
+}
+
+/// Attribute store for all entities
+#[derive(Clone, Debug, Default)]
+pub struct AttributeStoreData {
+    pub definitions: Vec<AttributeDefinition>,
+    pub name_lookup: HashMap<String, AttributeId>,
+
+    /// Entity in each row
+    pub entities: Vec<InstanceId>,
+    pub entity_rows: HashMap<InstanceId, usize>,
+    /// One column per attribute, indexed by AttributeId
+    pub columns: Vec<AttributeColumn>,
+    /// Modifiers per entity row
+    pub modifiers: Vec<ModifierList>,
+
+    /// Tick of the last `update_attributes` call
+    pub current_tick: u64,
+    /// Earliest expiry among all modifiers, so most ticks skip the scan
+    pub next_expiry_tick: Option<u64>,
+
+    /// Effective value changes since the last drain
+    pub changes: Vec<AttributeChange>,
+}
+
+/// Attribute persistence errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AttributeError {
+    #[error("Attribute serialization failed: {0}")]
+    Serialization(String),
+
+    #[error("Attribute deserialization failed: {0}")]
+    Deserialization(String),
+}
+
+pub type AttributeResult<T> = Result<T, AttributeError>;
+
+/// Saved modifier, keyed by attribute name so ids may change between runs
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedModifier {
+    pub attribute: String,
+    pub kind: ModifierKind,
+    pub value: f32,
+    pub source: ModifierSource,
+    /// Ticks left at save time (None = permanent)
+    pub remaining_ticks: Option<u64>,
+}
+
+/// Saved base value, keyed by attribute name
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedBaseValue {
+    pub attribute: String,
+    pub value: f32,
+}
+
+/// Saved attributes of one entity
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EntityAttributeSnapshot {
+    pub entity: InstanceId,
+    /// Only bases that differ from the attribute default
+    pub base_values: Vec<SavedBaseValue>,
+    pub modifiers: Vec<SavedModifier>,
+}
@@ -0,0 +1,522 @@
+//! Entity Attribute Operations - Pure DOP Functions
+//!
+//! Effective values are recomputed only when a base value or modifier of
+//! that attribute changes, so reads are a row lookup plus a column index.
+//! Modifier expiry is checked in `update_attributes`, which skips the scan
+//! entirely until the earliest expiry tick is reached.
+
+use super::attribute_data::{
+    AttributeChange, AttributeColumn, AttributeDefinition, AttributeError, AttributeId,
+    AttributeModifier, AttributeResult, AttributeStoreData, EntityAttributeSnapshot, ModifierKind,
+    ModifierSource, SavedBaseValue, SavedModifier,
+};
+use crate::constants::attributes::MAX_PENDING_ATTRIBUTE_CHANGES;
+use crate::instance::InstanceId;
+
+// ============================================================================
+// REGISTRATION
+// ============================================================================
+
+/// Create an empty attribute store
+pub fn create_attribute_store() -> AttributeStoreData {
+    AttributeStoreData::default()
+}
+
+/// Register an attribute, or return the id of an existing one with this name
+pub fn register_attribute(
+    store: &mut AttributeStoreData,
+    name: &str,
+    default_base: f32,
+    min: f32,
+    max: f32,
+) -> AttributeId {
+    if let Some(id) = store.name_lookup.get(name) {
+        return *id;
+    }
+
+    let id = AttributeId(store.definitions.len() as u16);
+    let (min, max) = if min <= max { (min, max) } else { (max, min) };
+    let row_count = store.entities.len();
+
+    store.definitions.push(AttributeDefinition {
+        id,
+        name: name.to_string(),
+        default_base,
+        min,
+        max,
+    });
+    store.name_lookup.insert(name.to_string(), id);
+    store.columns.push(AttributeColumn {
+        base: vec![default_base; row_count],
+        effective: vec![default_base.clamp(min, max); row_count],
+    });
+
+    id
+}
+
+/// Look up an attribute by name
+pub fn find_attribute(store: &AttributeStoreData, name: &str) -> Option<AttributeId> {
+    store.name_lookup.get(name).copied()
+}
+
+/// Start tracking an entity, with every attribute at its default base
+pub fn add_attribute_entity(store: &mut AttributeStoreData, entity: InstanceId) {
+    if store.entity_rows.contains_key(&entity) {
+        return;
+    }
+
+    store.entity_rows.insert(entity, store.entities.len());
+    store.entities.push(entity);
+    store.modifiers.push(Vec::new());
+    for (definition, column) in store.definitions.iter().zip(store.columns.iter_mut()) {
+        column.base.push(definition.default_base);
+        column.effective.push(
+            definition
+                .default_base
+                .clamp(definition.min, definition.max),
+        );
+    }
+}
+
+/// Stop tracking an entity and drop its modifiers
+pub fn remove_attribute_entity(store: &mut AttributeStoreData, entity: InstanceId) -> bool {
+    let Some(row) = store.entity_rows.remove(&entity) else {
+        return false;
+    };
+
+    store.entities.swap_remove(row);
+    store.modifiers.swap_remove(row);
+    for column in &mut store.columns {
+        column.base.swap_remove(row);
+        column.effective.swap_remove(row);
+    }
+
+    if let Some(moved) = store.entities.get(row) {
+        store.entity_rows.insert(*moved, row);
+    }
+    true
+}
+
+// ============================================================================
+// MUTATION
+// ============================================================================
+
+/// Set an entity's base value; returns false for unknown entities or attributes
+pub fn set_base_value(
+    store: &mut AttributeStoreData,
+    entity: InstanceId,
+    attribute: AttributeId,
+    value: f32,
+) -> bool {
+    let Some(row) = store.entity_rows.get(&entity).copied() else {
+        return false;
+    };
+    let Some(column) = store.columns.get_mut(attribute.0 as usize) else {
+        return false;
+    };
+
+    column.base[row] = value;
+    recompute_attribute(store, row, attribute);
+    true
+}
+
+/// Attach a modifier; returns false for unknown entities or attributes
+pub fn add_modifier(
+    store: &mut AttributeStoreData,
+    entity: InstanceId,
+    modifier: AttributeModifier,
+) -> bool {
+    let Some(row) = store.entity_rows.get(&entity).copied() else {
+        return false;
+    };
+    if modifier.attribute.0 as usize >= store.definitions.len() {
+        return false;
+    }
+
+    if let Some(expiry) = modifier.expires_at_tick {
+        store.next_expiry_tick = Some(store.next_expiry_tick.map_or(expiry, |t| t.min(expiry)));
+    }
+    store.modifiers[row].push(modifier);
+    recompute_attribute(store, row, modifier.attribute);
+    true
+}
+
+/// Remove every modifier an entity has from `source`; returns how many were removed
+pub fn remove_modifiers_from_source(
+    store: &mut AttributeStoreData,
+    entity: InstanceId,
+    source: ModifierSource,
+) -> usize {
+    let Some(row) = store.entity_rows.get(&entity).copied() else {
+        return 0;
+    };
+
+    let before = store.modifiers[row].len();
+    let mut affected = Vec::new();
+    store.modifiers[row].retain(|modifier| {
+        let keep = modifier.source != source;
+        if !keep && !affected.contains(&modifier.attribute) {
+            affected.push(modifier.attribute);
+        }
+        keep
+    });
+
+    let removed = before - store.modifiers[row].len();
+    for attribute in affected {
+        recompute_attribute(store, row, attribute);
+    }
+    removed
+}
+
+/// Advance to `tick` and drop expired modifiers; returns how many expired
+pub fn update_attributes(store: &mut AttributeStoreData, tick: u64) -> usize {
+    store.current_tick = tick;
+    match store.next_expiry_tick {
+        Some(next) if next <= tick => {}
+        _ => return 0,
+    }
+
+    let mut expired = 0;
+    let mut next_expiry: Option<u64> = None;
+    for row in 0..store.entities.len() {
+        let before = store.modifiers[row].len();
+        let mut affected = Vec::new();
+        store.modifiers[row].retain(|modifier| match modifier.expires_at_tick {
+            Some(expiry) if expiry <= tick => {
+                if !affected.contains(&modifier.attribute) {
+                    affected.push(modifier.attribute);
+                }
+                false
+            }
+            Some(expiry) => {
+                next_expiry = Some(next_expiry.map_or(expiry, |t| t.min(expiry)));
+                true
+            }
+            None => true,
+        });
+
+        expired += before - store.modifiers[row].len();
+        for attribute in affected {
+            recompute_attribute(store, row, attribute);
+        }
+    }
+
+    store.next_expiry_tick = next_expiry;
+    expired
+}
+
+/// Effective value from a base and the modifiers on that attribute
+pub fn compute_effective_value(
+    definition: &AttributeDefinition,
+    base: f32,
+    modifiers: &[AttributeModifier],
+) -> f32 {
+    let mut additive = 0.0;
+    let mut multiplier = 1.0;
+    for modifier in modifiers.iter().filter(|m| m.attribute == definition.id) {
+        match modifier.kind {
+            ModifierKind::Additive => additive += modifier.value,
+            ModifierKind::Multiplicative => multiplier *= modifier.value,
+        }
+    }
+
+    ((base + additive) * multiplier).clamp(definition.min, definition.max)
+}
+
+/// Recompute one cached effective value and record the change if it moved
+fn recompute_attribute(store: &mut AttributeStoreData, row: usize, attribute: AttributeId) {
+    let index = attribute.0 as usize;
+    let new_value = compute_effective_value(
+        &store.definitions[index],
+        store.columns[index].base[row],
+        &store.modifiers[row],
+    );
+
+    let old_value = store.columns[index].effective[row];
+    if old_value == new_value {
+        return;
+    }
+    store.columns[index].effective[row] = new_value;
+
+    if store.changes.len() >= MAX_PENDING_ATTRIBUTE_CHANGES {
+        store.changes.remove(0);
+    }
+    store.changes.push(AttributeChange {
+        entity: store.entities[row],
+        attribute,
+        old_value,
+        new_value,
+    });
+}
+
+// ============================================================================
+// QUERIES
+// ============================================================================
+
+/// Cached effective value
+pub fn effective_value(
+    store: &AttributeStoreData,
+    entity: InstanceId,
+    attribute: AttributeId,
+) -> Option<f32> {
+    let row = *store.entity_rows.get(&entity)?;
+    store
+        .columns
+        .get(attribute.0 as usize)
+        .map(|column| column.effective[row])
+}
+
+/// Cached effective value, looked up by attribute name
+pub fn effective_value_by_name(
+    store: &AttributeStoreData,
+    entity: InstanceId,
+    name: &str,
+) -> Option<f32> {
+    effective_value(store, entity, find_attribute(store, name)?)
+}
+
+/// Base value before modifiers
+pub fn base_value(
+    store: &AttributeStoreData,
+    entity: InstanceId,
+    attribute: AttributeId,
+) -> Option<f32> {
+    let row = *store.entity_rows.get(&entity)?;
+    store
+        .columns
+        .get(attribute.0 as usize)
+        .map(|column| column.base[row])
+}
+
+/// Active modifiers of an entity
+pub fn entity_modifiers(store: &AttributeStoreData, entity: InstanceId) -> &[AttributeModifier] {
+    store
+        .entity_rows
+        .get(&entity)
+        .map(|row| store.modifiers[*row].as_slice())
+        .unwrap_or(&[])
+}
+
+/// Take all effective value changes since the last drain
+pub fn drain_attribute_changes(store: &mut AttributeStoreData) -> Vec<AttributeChange> {
+    std::mem::take(&mut store.changes)
+}
+
+// ============================================================================
+// PERSISTENCE
+// ============================================================================
+
+/// Capture an entity's base values and modifiers
+pub fn snapshot_entity_attributes(
+    store: &AttributeStoreData,
+    entity: InstanceId,
+) -> Option<EntityAttributeSnapshot> {
+    let row = *store.entity_rows.get(&entity)?;
+
+    let base_values = store
+        .definitions
+        .iter()
+        .zip(&store.columns)
+        .filter(|(definition, column)| column.base[row] != definition.default_base)
+        .map(|(definition, column)| SavedBaseValue {
+            attribute: definition.name.clone(),
+            value: column.base[row],
+        })
+        .collect();
+
+    let modifiers = store.modifiers[row]
+        .iter()
+        .map(|modifier| SavedModifier {
+            attribute: store.definitions[modifier.attribute.0 as usize]
+                .name
+                .clone(),
+            kind: modifier.kind,
+            value: modifier.value,
+            source: modifier.source,
+            remaining_ticks: modifier
+                .expires_at_tick
+                .map(|expiry| expiry.saturating_sub(store.current_tick)),
+        })
+        .collect();
+
+    Some(EntityAttributeSnapshot {
+        entity,
+        base_values,
+        modifiers,
+    })
+}
+
+/// Restore a snapshot, replacing the entity's current modifiers.
+/// Attributes that are no longer registered are skipped; returns how many.
+pub fn restore_entity_attributes(
+    store: &mut AttributeStoreData,
+    snapshot: &EntityAttributeSnapshot,
+) -> usize {
+    let entity = snapshot.entity;
+    add_attribute_entity(store, entity);
+    remove_all_modifiers(store, entity);
+
+    let mut skipped = 0;
+    for saved in &snapshot.base_values {
+        match find_attribute(store, &saved.attribute) {
+            Some(attribute) => {
+                set_base_value(store, entity, attribute, saved.value);
+            }
+            None => skipped += 1,
+        }
+    }
+
+    for saved in &snapshot.modifiers {
+        let Some(attribute) = find_attribute(store, &saved.attribute) else {
+            skipped += 1;
+            continue;
+        };
+        add_modifier(
+            store,
+            entity,
+            AttributeModifier {
+                attribute,
+                kind: saved.kind,
+                value: saved.value,
+                source: saved.source,
+                expires_at_tick: saved
+                    .remaining_ticks
+                    .map(|remaining| store.current_tick + remaining),
+            },
+        );
+    }
+
+    if skipped > 0 {
+        log::warn!(
+            "[Attributes] Skipped {} unknown attribute entries restoring {}",
+            skipped,
+            entity
+        );
+    }
+    skipped
+}
+
+/// Remove every modifier of an entity
+fn remove_all_modifiers(store: &mut AttributeStoreData, entity: InstanceId) {
+    let Some(row) = store.entity_rows.get(&entity).copied() else {
+        return;
+    };
+
+    let removed = std::mem::take(&mut store.modifiers[row]);
+    for index in 0..store.definitions.len() {
+        if removed.iter().any(|m| m.attribute.0 as usize == index) {
+            recompute_attribute(store, row, AttributeId(index as u16));
+        }
+    }
+}
+
+/// Serialize every entity's attributes
+pub fn serialize_attribute_store(store: &AttributeStoreData) -> AttributeResult<Vec<u8>> {
+    let snapshots: Vec<EntityAttributeSnapshot> = store
+        .entities
+        .iter()
+        .filter_map(|entity| snapshot_entity_attributes(store, *entity))
+        .collect();
+
+    bincode::serialize(&snapshots).map_err(|e| AttributeError::Serialization(e.to_string()))
+}
+
+/// Restore attributes written by `serialize_attribute_store`; returns entities restored
+pub fn deserialize_attribute_store(
+    store: &mut AttributeStoreData,
+    bytes: &[u8],
+) -> AttributeResult<usize> {
+    let snapshots: Vec<EntityAttributeSnapshot> =
+        bincode::deserialize(bytes).map_err(|e| AttributeError::Deserialization(e.to_string()))?;
+
+    for snapshot in &snapshots {
+        restore_entity_attributes(store, snapshot);
+    }
+    Ok(snapshots.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(low: u64) -> InstanceId {
+        InstanceId { high: 0, low }
+    }
+
+    #[test]
+    fn test_modifiers_recompute_and_expire() {
+        let mut store = create_attribute_store();
+        let speed = register_attribute(&mut store, "speed", 4.0, 0.0, 20.0);
+        let player = entity(1);
+        add_attribute_entity(&mut store, player);
+
+        add_modifier(
+            &mut store,
+            player,
+            AttributeModifier {
+                attribute: speed,
+                kind: ModifierKind::Additive,
+                value: 2.0,
+                source: ModifierSource(1),
+                expires_at_tick: None,
+            },
+        );
+        add_modifier(
+            &mut store,
+            player,
+            AttributeModifier {
+                attribute: speed,
+                kind: ModifierKind::Multiplicative,
+                value: 1.5,
+                source: ModifierSource(2),
+                expires_at_tick: Some(10),
+            },
+        );
+        assert_eq!(effective_value(&store, player, speed), Some(9.0));
+
+        assert_eq!(update_attributes(&mut store, 9), 0);
+        assert_eq!(update_attributes(&mut store, 10), 1);
+        assert_eq!(effective_value(&store, player, speed), Some(6.0));
+
+        assert_eq!(
+            remove_modifiers_from_source(&mut store, player, ModifierSource(1)),
+            1
+        );
+        assert_eq!(effective_value(&store, player, speed), Some(4.0));
+        assert_eq!(drain_attribute_changes(&mut store).len(), 4);
+    }
+
+    #[test]
+    fn test_round_trip_by_name() {
+        let mut store = create_attribute_store();
+        let health = register_attribute(&mut store, "health", 20.0, 0.0, 100.0);
+        let player = entity(7);
+        add_attribute_entity(&mut store, player);
+        set_base_value(&mut store, player, health, 30.0);
+        update_attributes(&mut store, 100);
+        add_modifier(
+            &mut store,
+            player,
+            AttributeModifier {
+                attribute: health,
+                kind: ModifierKind::Additive,
+                value: 5.0,
+                source: ModifierSource(3),
+                expires_at_tick: Some(150),
+            },
+        );
+
+        let bytes = serialize_attribute_store(&store).unwrap_or_default();
+
+        // Different registration order in the loading run
+        let mut loaded = create_attribute_store();
+        register_attribute(&mut loaded, "speed", 4.0, 0.0, 20.0);
+        let loaded_health = register_attribute(&mut loaded, "health", 20.0, 0.0, 100.0);
+        assert_eq!(deserialize_attribute_store(&mut loaded, &bytes), Ok(1));
+        assert_eq!(effective_value(&loaded, player, loaded_health), Some(35.0));
+        assert_eq!(
+            entity_modifiers(&loaded, player)[0].expires_at_tick,
+            Some(50)
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! Behavior Tree Data - Pure DOP
+//!
+//! Data-driven AI for mobs. Trees of selector, sequence, condition and
+//! action nodes are loaded from JSON or TOML files (or built in code) and
+//! attached to entities; the game only registers the condition and action
+//! handlers the trees name. Every update evaluates each attached entity's
+//! tree from the root, within a per-update budget and only for entities
+//! whose AI is inside the activation range.
+//!
+//! Handlers run while the tree data is borrowed (under the gateway lock
+//! when ticked through the gateway), so they must not call back into it;
+//! they push the game events they want queued onto their context instead.
+//!
+//! NO METHODS - just data.
+
+use super::gateway_data::GameEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Index of a loaded tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BehaviorTreeId(pub u32);
+
+/// Result of evaluating a node
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BehaviorStatus {
+    Success,
+    #[default]
+    Failure,
+    /// Still working; evaluated again next tick
+    Running,
+}
+
+/// Node as written in a behavior file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BehaviorNodeSpec {
+    /// Children in order until one does not fail
+    Selector { children: Vec<BehaviorNodeSpec> },
+    /// Children in order until one does not succeed
+    Sequence { children: Vec<BehaviorNodeSpec> },
+    /// Registered condition: success when it holds, failure otherwise
+    Condition { name: String },
+    /// Registered action
+    Action { name: String },
+}
+
+/// Contents of a behavior file: trees keyed by name ("mobs/zombie")
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BehaviorFileSpec {
+    #[serde(default)]
+    pub trees: HashMap<String, BehaviorNodeSpec>,
+}
+
+/// Behavior file encodings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BehaviorFileFormat {
+    Json,
+    Toml,
+}
+
+/// What a compiled node does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BehaviorNodeKind {
+    Selector,
+    Sequence,
+    Condition { name: String },
+    Action { name: String },
+}
+
+/// Compiled node; children are indices into the tree's node list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BehaviorNode {
+    pub kind: BehaviorNodeKind,
+    pub children: Vec<u32>,
+}
+
+/// Compiled tree, root first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BehaviorTree {
+    pub name: String,
+    pub nodes: Vec<BehaviorNode>,
+}
+
+/// What a handler sees and can emit
+#[derive(Debug, Clone)]
+pub struct BehaviorContext {
+    /// Entity index, as in `PhysicsBuffers`
+    pub entity: u32,
+    pub tree: BehaviorTreeId,
+    /// Node being evaluated
+    pub node: u32,
+    /// The action was already running on the entity's previous tick
+    pub resumed: bool,
+    pub tick: u64,
+    /// Seconds since the entity was last evaluated
+    pub dt: f32,
+    /// Events queued once the update finishes
+    pub events: Vec<GameEvent>,
+}
+
+/// Game-provided condition, referenced from trees by name
+pub type BehaviorConditionHandler = Box<dyn Fn(&BehaviorContext) -> bool + Send + Sync>;
+
+/// Game-provided action, referenced from trees by name
+pub type BehaviorActionHandler = Box<dyn Fn(&mut BehaviorContext) -> BehaviorStatus + Send + Sync>;
+
+/// Execution state of every entity running a tree, one column per field
+/// and one row per entity
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BehaviorAgents {
+    pub entities: Vec<u32>,
+    pub trees: Vec<BehaviorTreeId>,
+    /// Action node left running on the last tick
+    pub running: Vec<Option<u32>>,
+    /// Root status of the last tick
+    pub status: Vec<BehaviorStatus>,
+    /// Tick of the last evaluation (None before the first)
+    pub last_tick: Vec<Option<u64>>,
+    /// Row of each entity
+    pub rows: HashMap<u32, usize>,
+}
+
+/// Per-update budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BehaviorTickConfig {
+    /// Agents evaluated per update; the rest continue next update
+    pub max_ticks_per_update: u32,
+}
+
+/// Counters of the last update
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BehaviorStats {
+    pub agents: u32,
+    /// Agents evaluated
+    pub ticked: u32,
+    /// Agents skipped because their AI is out of activation range
+    pub inactive: u32,
+    /// Agents left for the next update by the budget
+    pub deferred: u32,
+    /// Running actions abandoned because the tree took another branch
+    pub interrupted: u32,
+    /// Nodes naming a handler that is not registered (they fail)
+    pub missing_handlers: u32,
+}
+
+/// Loaded trees, registered handlers and attached entities
+pub struct BehaviorTreeData {
+    pub trees: Vec<BehaviorTree>,
+    pub tree_names: HashMap<String, BehaviorTreeId>,
+    pub conditions: HashMap<String, BehaviorConditionHandler>,
+    pub actions: HashMap<String, BehaviorActionHandler>,
+    pub agents: BehaviorAgents,
+    pub config: BehaviorTickConfig,
+    /// Row the next update starts from, so every agent gets its turn
+    pub cursor: usize,
+    pub stats: BehaviorStats,
+}
+
+/// Behavior tree errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BehaviorTreeError {
+    #[error("Failed to read behavior file {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("Failed to parse behavior trees: {message}")]
+    Parse { message: String },
+
+    #[error("Unsupported behavior file extension: {path}")]
+    UnsupportedFormat { path: String },
+
+    #[error("Invalid behavior tree {tree}: {reason}")]
+    InvalidTree { tree: String, reason: String },
+
+    #[error("Unknown behavior tree: {name}")]
+    UnknownTree { name: String },
+}
+
+pub type BehaviorTreeResult<T> = Result<T, BehaviorTreeError>;
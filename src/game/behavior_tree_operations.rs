@@ -0,0 +1,552 @@
+//! Behavior Tree Operations - Pure DOP functions
+//!
+//! Load trees with `load_behavior_trees` (or `add_behavior_tree` for trees
+//! built in code), register the handlers they name with
+//! `register_behavior_condition` / `register_behavior_action`, attach
+//! entities with `attach_behavior`, and call `tick_behavior_trees` once per
+//! tick with the current activation ranges.
+
+use super::behavior_tree_data::{
+    BehaviorActionHandler, BehaviorAgents, BehaviorConditionHandler, BehaviorContext,
+    BehaviorFileFormat, BehaviorFileSpec, BehaviorNode, BehaviorNodeKind, BehaviorNodeSpec,
+    BehaviorStats, BehaviorStatus, BehaviorTickConfig, BehaviorTree, BehaviorTreeData,
+    BehaviorTreeError, BehaviorTreeId, BehaviorTreeResult,
+};
+use super::gateway_data::GameEvent;
+use crate::activation_range_data::ActivationRangeData;
+use crate::activation_range_operations::entity_activation;
+use crate::constants::behavior_trees::{BEHAVIOR_TICKS_PER_UPDATE, MAX_BEHAVIOR_TREE_NODES};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Budget from the engine constants
+pub fn default_behavior_tick_config() -> BehaviorTickConfig {
+    BehaviorTickConfig {
+        max_ticks_per_update: BEHAVIOR_TICKS_PER_UPDATE,
+    }
+}
+
+/// No trees, handlers or agents yet
+pub fn create_behavior_trees(config: BehaviorTickConfig) -> BehaviorTreeData {
+    BehaviorTreeData {
+        trees: Vec::new(),
+        tree_names: HashMap::new(),
+        conditions: HashMap::new(),
+        actions: HashMap::new(),
+        agents: BehaviorAgents::default(),
+        config,
+        cursor: 0,
+        stats: BehaviorStats::default(),
+    }
+}
+
+/// Register the handler `{"type": "condition", "name": ...}` nodes call
+pub fn register_behavior_condition(
+    data: &mut BehaviorTreeData,
+    name: &str,
+    handler: BehaviorConditionHandler,
+) {
+    data.conditions.insert(name.to_string(), handler);
+}
+
+/// Register the handler `{"type": "action", "name": ...}` nodes call
+pub fn register_behavior_action(
+    data: &mut BehaviorTreeData,
+    name: &str,
+    handler: BehaviorActionHandler,
+) {
+    data.actions.insert(name.to_string(), handler);
+}
+
+/// Append `spec` and its subtree to `nodes`, returning its index
+fn compile_node(
+    spec: &BehaviorNodeSpec,
+    tree: &str,
+    nodes: &mut Vec<BehaviorNode>,
+) -> BehaviorTreeResult<u32> {
+    let invalid = |reason: &str| BehaviorTreeError::InvalidTree {
+        tree: tree.to_string(),
+        reason: reason.to_string(),
+    };
+    if nodes.len() >= MAX_BEHAVIOR_TREE_NODES {
+        return Err(invalid("too many nodes"));
+    }
+
+    let index = nodes.len() as u32;
+    let (kind, children) = match spec {
+        BehaviorNodeSpec::Selector { children } => (BehaviorNodeKind::Selector, children),
+        BehaviorNodeSpec::Sequence { children } => (BehaviorNodeKind::Sequence, children),
+        BehaviorNodeSpec::Condition { name } | BehaviorNodeSpec::Action { name }
+            if name.is_empty() =>
+        {
+            return Err(invalid("a condition or action needs a name"))
+        }
+        BehaviorNodeSpec::Condition { name } => {
+            nodes.push(BehaviorNode {
+                kind: BehaviorNodeKind::Condition { name: name.clone() },
+                children: Vec::new(),
+            });
+            return Ok(index);
+        }
+        BehaviorNodeSpec::Action { name } => {
+            nodes.push(BehaviorNode {
+                kind: BehaviorNodeKind::Action { name: name.clone() },
+                children: Vec::new(),
+            });
+            return Ok(index);
+        }
+    };
+    if children.is_empty() {
+        return Err(invalid("a selector or sequence needs children"));
+    }
+
+    nodes.push(BehaviorNode {
+        kind,
+        children: Vec::with_capacity(children.len()),
+    });
+    for child in children {
+        let child = compile_node(child, tree, nodes)?;
+        nodes[index as usize].children.push(child);
+    }
+    Ok(index)
+}
+
+/// Build a tree from its file form
+pub fn compile_behavior_tree(
+    name: &str,
+    spec: &BehaviorNodeSpec,
+) -> BehaviorTreeResult<BehaviorTree> {
+    let mut nodes = Vec::new();
+    compile_node(spec, name, &mut nodes)?;
+    Ok(BehaviorTree {
+        name: name.to_string(),
+        nodes,
+    })
+}
+
+/// Add a tree under its name. A tree replacing one of the same name keeps
+/// its id, and entities running it start over from the root.
+pub fn add_behavior_tree(data: &mut BehaviorTreeData, tree: BehaviorTree) -> BehaviorTreeId {
+    if let Some(&id) = data.tree_names.get(&tree.name) {
+        data.trees[id.0 as usize] = tree;
+        for (running, _) in data
+            .agents
+            .running
+            .iter_mut()
+            .zip(&data.agents.trees)
+            .filter(|(_, &agent_tree)| agent_tree == id)
+        {
+            *running = None;
+        }
+        return id;
+    }
+    let id = BehaviorTreeId(data.trees.len() as u32);
+    data.tree_names.insert(tree.name.clone(), id);
+    data.trees.push(tree);
+    id
+}
+
+/// Parse a behavior file and add its trees; returns the trees added.
+/// Nothing is added if any tree is invalid.
+pub fn parse_behavior_trees(
+    data: &mut BehaviorTreeData,
+    text: &str,
+    format: BehaviorFileFormat,
+) -> BehaviorTreeResult<usize> {
+    let file: BehaviorFileSpec = match format {
+        BehaviorFileFormat::Json => {
+            serde_json::from_str(text).map_err(|e| BehaviorTreeError::Parse {
+                message: e.to_string(),
+            })?
+        }
+        BehaviorFileFormat::Toml => toml::from_str(text).map_err(|e| BehaviorTreeError::Parse {
+            message: e.to_string(),
+        })?,
+    };
+
+    let mut trees = Vec::with_capacity(file.trees.len());
+    for (name, spec) in &file.trees {
+        trees.push(compile_behavior_tree(name, spec)?);
+    }
+    let added = trees.len();
+    for tree in trees {
+        add_behavior_tree(data, tree);
+    }
+    Ok(added)
+}
+
+/// Load a `.json` or `.toml` behavior file (see `parse_behavior_trees`)
+pub fn load_behavior_trees(data: &mut BehaviorTreeData, path: &Path) -> BehaviorTreeResult<usize> {
+    let format = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => BehaviorFileFormat::Json,
+        Some("toml") => BehaviorFileFormat::Toml,
+        _ => {
+            return Err(BehaviorTreeError::UnsupportedFormat {
+                path: path.display().to_string(),
+            })
+        }
+    };
+    let text = std::fs::read_to_string(path).map_err(|e| BehaviorTreeError::Io {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })?;
+    parse_behavior_trees(data, &text, format)
+}
+
+/// Id of a tree by name
+pub fn behavior_tree_id(data: &BehaviorTreeData, name: &str) -> Option<BehaviorTreeId> {
+    data.tree_names.get(name).copied()
+}
+
+/// Condition and action names used by the loaded trees without a
+/// registered handler, sorted; check once the game registered its handlers
+pub fn missing_behavior_handlers(data: &BehaviorTreeData) -> Vec<String> {
+    let missing: HashSet<&String> = data
+        .trees
+        .iter()
+        .flat_map(|tree| &tree.nodes)
+        .filter_map(|node| match &node.kind {
+            BehaviorNodeKind::Condition { name } if !data.conditions.contains_key(name) => {
+                Some(name)
+            }
+            BehaviorNodeKind::Action { name } if !data.actions.contains_key(name) => Some(name),
+            _ => None,
+        })
+        .collect();
+    let mut missing: Vec<String> = missing.into_iter().cloned().collect();
+    missing.sort();
+    missing
+}
+
+/// Run the named tree on an entity (replacing any tree it ran before)
+pub fn attach_behavior(
+    data: &mut BehaviorTreeData,
+    entity: u32,
+    tree: &str,
+) -> BehaviorTreeResult<BehaviorTreeId> {
+    let id = behavior_tree_id(data, tree).ok_or_else(|| BehaviorTreeError::UnknownTree {
+        name: tree.to_string(),
+    })?;
+    let agents = &mut data.agents;
+    match agents.rows.get(&entity) {
+        Some(&row) => {
+            agents.trees[row] = id;
+            agents.running[row] = None;
+            agents.status[row] = BehaviorStatus::default();
+        }
+        None => {
+            agents.rows.insert(entity, agents.entities.len());
+            agents.entities.push(entity);
+            agents.trees.push(id);
+            agents.running.push(None);
+            agents.status.push(BehaviorStatus::default());
+            agents.last_tick.push(None);
+        }
+    }
+    Ok(id)
+}
+
+/// Stop running a tree on an entity (despawned, tamed, ...)
+pub fn detach_behavior(data: &mut BehaviorTreeData, entity: u32) -> bool {
+    let agents = &mut data.agents;
+    let Some(row) = agents.rows.remove(&entity) else {
+        return false;
+    };
+    agents.entities.swap_remove(row);
+    agents.trees.swap_remove(row);
+    agents.running.swap_remove(row);
+    agents.status.swap_remove(row);
+    agents.last_tick.swap_remove(row);
+    if let Some(&moved) = agents.entities.get(row) {
+        agents.rows.insert(moved, row);
+    }
+    true
+}
+
+/// Root status of an entity's last tick
+pub fn behavior_status(data: &BehaviorTreeData, entity: u32) -> Option<BehaviorStatus> {
+    data.agents
+        .rows
+        .get(&entity)
+        .map(|&row| data.agents.status[row])
+}
+
+/// State of one evaluation from the root
+struct Evaluation<'a> {
+    tree: &'a BehaviorTree,
+    conditions: &'a HashMap<String, BehaviorConditionHandler>,
+    actions: &'a HashMap<String, BehaviorActionHandler>,
+    /// Action left running on the previous tick
+    previous: Option<u32>,
+    /// Action left running on this tick
+    running: Option<u32>,
+    /// The previously running action was evaluated again
+    reached_previous: bool,
+    missing_handlers: u32,
+}
+
+fn evaluate_node(
+    eval: &mut Evaluation,
+    node: u32,
+    context: &mut BehaviorContext,
+) -> BehaviorStatus {
+    let tree = eval.tree;
+    let Some(compiled) = tree.nodes.get(node as usize) else {
+        return BehaviorStatus::Failure;
+    };
+    match &compiled.kind {
+        BehaviorNodeKind::Selector => {
+            for &child in &compiled.children {
+                let status = evaluate_node(eval, child, context);
+                if status != BehaviorStatus::Failure {
+                    return status;
+                }
+            }
+            BehaviorStatus::Failure
+        }
+        BehaviorNodeKind::Sequence => {
+            for &child in &compiled.children {
+                let status = evaluate_node(eval, child, context);
+                if status != BehaviorStatus::Success {
+                    return status;
+                }
+            }
+            BehaviorStatus::Success
+        }
+        BehaviorNodeKind::Condition { name } => match eval.conditions.get(name) {
+            Some(condition) => {
+                context.node = node;
+                if condition(context) {
+                    BehaviorStatus::Success
+                } else {
+                    BehaviorStatus::Failure
+                }
+            }
+            None => {
+                eval.missing_handlers += 1;
+                BehaviorStatus::Failure
+            }
+        },
+        BehaviorNodeKind::Action { name } => match eval.actions.get(name) {
+            Some(action) => {
+                context.node = node;
+                context.resumed = eval.previous == Some(node);
+                eval.reached_previous |= context.resumed;
+                let status = action(context);
+                if status == BehaviorStatus::Running {
+                    eval.running = Some(node);
+                }
+                status
+            }
+            None => {
+                eval.missing_handlers += 1;
+                BehaviorStatus::Failure
+            }
+        },
+    }
+}
+
+/// Evaluate the trees of up to `max_ticks_per_update` entities whose AI is
+/// active, continuing where the previous update stopped, and return the
+/// events the handlers emitted. With `activation` None every entity counts
+/// as active. `tick_seconds` is the length of one tick, for the `dt`
+/// handlers see.
+pub fn tick_behavior_trees(
+    data: &mut BehaviorTreeData,
+    activation: Option<&ActivationRangeData>,
+    tick: u64,
+    tick_seconds: f32,
+) -> Vec<GameEvent> {
+    let count = data.agents.entities.len();
+    let budget = data.config.max_ticks_per_update as usize;
+    let mut stats = BehaviorStats {
+        agents: count as u32,
+        ..BehaviorStats::default()
+    };
+    let mut events = Vec::new();
+    let start = if count == 0 { 0 } else { data.cursor % count };
+    let mut next_cursor = None;
+
+    for step in 0..count {
+        let row = (start + step) % count;
+        let entity = data.agents.entities[row];
+        let active =
+            activation.is_none_or(|ranges| entity_activation(ranges, entity as usize).entity_ai);
+        if !active {
+            stats.inactive += 1;
+            continue;
+        }
+        if stats.ticked as usize >= budget {
+            stats.deferred += 1;
+            next_cursor.get_or_insert(row);
+            continue;
+        }
+
+        let id = data.agents.trees[row];
+        let Some(tree) = data.trees.get(id.0 as usize) else {
+            continue;
+        };
+        let mut eval = Evaluation {
+            tree,
+            conditions: &data.conditions,
+            actions: &data.actions,
+            previous: data.agents.running[row],
+            running: None,
+            reached_previous: false,
+            missing_handlers: 0,
+        };
+        let mut context = BehaviorContext {
+            entity,
+            tree: id,
+            node: 0,
+            resumed: false,
+            tick,
+            dt: data.agents.last_tick[row].map_or(tick_seconds, |last| {
+                tick.saturating_sub(last) as f32 * tick_seconds
+            }),
+            events: Vec::new(),
+        };
+        let status = evaluate_node(&mut eval, 0, &mut context);
+
+        // An action that finished was reached; one the tree took another
+        // branch around was abandoned
+        if eval.previous.is_some() && !eval.reached_previous {
+            stats.interrupted += 1;
+        }
+        stats.missing_handlers += eval.missing_handlers;
+        stats.ticked += 1;
+        data.agents.running[row] = eval.running;
+        data.agents.status[row] = status;
+        data.agents.last_tick[row] = Some(tick);
+        events.append(&mut context.events);
+    }
+
+    data.cursor = next_cursor.unwrap_or(start);
+    data.stats = stats;
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activation_range_data::ActivationFlags;
+    use crate::activation_range_operations::{
+        create_activation_ranges, default_activation_range_config,
+    };
+
+    const ZOMBIE: &str = r#"{
+        "trees": {
+            "mobs/zombie": {
+                "type": "selector",
+                "children": [
+                    {
+                        "type": "sequence",
+                        "children": [
+                            { "type": "condition", "name": "sees_player" },
+                            { "type": "action", "name": "chase" }
+                        ]
+                    },
+                    { "type": "action", "name": "wander" }
+                ]
+            }
+        }
+    }"#;
+
+    fn zombie_trees(config: BehaviorTickConfig) -> BehaviorTreeData {
+        let mut data = create_behavior_trees(config);
+        assert_eq!(
+            parse_behavior_trees(&mut data, ZOMBIE, BehaviorFileFormat::Json),
+            Ok(1)
+        );
+        assert_eq!(
+            missing_behavior_handlers(&data),
+            ["chase", "sees_player", "wander"]
+        );
+
+        // Entity 1 sees the player until tick 2
+        register_behavior_condition(
+            &mut data,
+            "sees_player",
+            Box::new(|context| context.entity == 1 && context.tick < 2),
+        );
+        register_behavior_action(
+            &mut data,
+            "chase",
+            Box::new(|context| {
+                if !context.resumed {
+                    context.events.push(GameEvent::Custom {
+                        event_type: "chase_started".to_string(),
+                        data: vec![context.entity as u8],
+                    });
+                }
+                BehaviorStatus::Running
+            }),
+        );
+        register_behavior_action(&mut data, "wander", Box::new(|_| BehaviorStatus::Success));
+        assert!(missing_behavior_handlers(&data).is_empty());
+        data
+    }
+
+    #[test]
+    fn test_trees_load_and_run_registered_handlers() {
+        let mut data = zombie_trees(default_behavior_tick_config());
+        attach_behavior(&mut data, 1, "mobs/zombie").expect("tree loaded");
+        attach_behavior(&mut data, 2, "mobs/zombie").expect("tree loaded");
+        assert!(matches!(
+            attach_behavior(&mut data, 3, "mobs/skeleton"),
+            Err(BehaviorTreeError::UnknownTree { .. })
+        ));
+
+        let events = tick_behavior_trees(&mut data, None, 0, 0.05);
+        assert_eq!(events.len(), 1);
+        assert_eq!(behavior_status(&data, 1), Some(BehaviorStatus::Running));
+        assert_eq!(behavior_status(&data, 2), Some(BehaviorStatus::Success));
+
+        // A running action is resumed without starting over
+        assert!(tick_behavior_trees(&mut data, None, 1, 0.05).is_empty());
+
+        // Losing sight of the player abandons the chase
+        tick_behavior_trees(&mut data, None, 2, 0.05);
+        assert_eq!(behavior_status(&data, 1), Some(BehaviorStatus::Success));
+        assert_eq!(data.stats.interrupted, 1);
+
+        assert!(detach_behavior(&mut data, 1));
+        assert_eq!(behavior_status(&data, 1), None);
+        assert_eq!(behavior_status(&data, 2), Some(BehaviorStatus::Success));
+
+        let empty = r#"{ "trees": { "broken": { "type": "sequence", "children": [] } } }"#;
+        assert!(matches!(
+            parse_behavior_trees(&mut data, empty, BehaviorFileFormat::Json),
+            Err(BehaviorTreeError::InvalidTree { .. })
+        ));
+    }
+
+    #[test]
+    fn test_budget_and_activation_range_limit_ticks() {
+        let mut data = zombie_trees(BehaviorTickConfig {
+            max_ticks_per_update: 2,
+        });
+        for entity in 0..4 {
+            attach_behavior(&mut data, entity, "mobs/zombie").expect("tree loaded");
+        }
+        let mut ranges = create_activation_ranges(default_activation_range_config());
+        let active = ActivationFlags {
+            entity_ai: true,
+            ..ActivationFlags::default()
+        };
+        // Entity 0 is out of AI range
+        ranges.entities = vec![ActivationFlags::default(), active, active, active];
+
+        tick_behavior_trees(&mut data, Some(&ranges), 0, 0.05);
+        assert_eq!(
+            (data.stats.ticked, data.stats.inactive, data.stats.deferred),
+            (2, 1, 1)
+        );
+        assert_eq!(behavior_status(&data, 3), Some(BehaviorStatus::Failure));
+
+        // The entity left over goes first next update
+        tick_behavior_trees(&mut data, Some(&ranges), 1, 0.05);
+        assert_eq!(behavior_status(&data, 3), Some(BehaviorStatus::Success));
+        assert_eq!(data.agents.last_tick[data.agents.rows[&0]], None);
+    }
+}
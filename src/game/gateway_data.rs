@@ -5,7 +5,33 @@
 //!
 //! Pure DOP: No methods, just data structures.
 
+use super::action_limit_data::{ActionLimitData, BlockAction};
+use super::action_limit_operations::{create_action_limits, default_action_limit_config};
+use super::attribute_data::AttributeStoreData;
+use super::behavior_tree_data::BehaviorTreeData;
+use super::behavior_tree_operations::{create_behavior_trees, default_behavior_tick_config};
+use super::health_data::{DamageSource, HealthData};
+use super::health_operations::{create_entity_health, default_health_config};
+use super::lifecycle_data::{DropDecision, SpawnAnchorId};
+use super::load_progress_data::{LoadPhase, LoadPhaseStatus, LoadProgressData};
+use super::load_progress_operations::{create_load_progress, default_load_progress_config};
+use super::loot_data::LootStack;
+use super::player_stats_data::PlayerStatsData;
+use super::region_claim_data::{ClaimId, RegionClaimData};
+use super::scoreboard_data::ScoreboardData;
+use super::trigger_volume_data::{TriggerOccupant, TriggerVolumeData, TriggerVolumeId};
+use crate::constants::physics_constants::{
+    DEFAULT_BLOCK_FRICTION, DEFAULT_BLOCK_RESTITUTION, DEFAULT_SPEED_MULTIPLIER,
+};
+use crate::engine_buffers::SharedEngineBuffers;
+use crate::input::haptics_data::{HapticCue, HapticsData};
+use crate::input::haptics_operations::create_haptics;
+use crate::instance::InstanceId;
+use crate::view_distance_data::ViewDistanceChangeReason;
 use crate::world::core::{BlockId, VoxelPos};
+use crate::world::season_data::{Season, SeasonData};
+use crate::world::season_operations::{create_season_data, default_season_config};
+use crate::world::storage::ShadowCacheData;
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 
@@ -23,6 +49,8 @@ pub enum GameEvent {
     BlockPlace {
         position: VoxelPos,
         block_id: BlockId,
+        /// Packed block metadata (orientation etc., see MetadataSchemaRegistry)
+        metadata: u8,
         player_id: Option<u32>,
     },
 
@@ -52,12 +80,160 @@ pub enum GameEvent {
         player_id: u32,
     },
 
+    /// Player took damage (after invulnerability checks)
+    PlayerDamaged {
+        player_id: u32,
+        amount: f32,
+        health: f32,
+        cause: String,
+    },
+
+    /// Player health reached zero
+    PlayerDied {
+        player_id: u32,
+        position: [f32; 3],
+        cause: String,
+        source_entity: Option<u32>,
+    },
+
+    /// Inventory policy resolved for a dead player; the game performs the drop
+    PlayerInventoryDrop {
+        player_id: u32,
+        position: [f32; 3],
+        decision: DropDecision,
+    },
+
+    /// Player's spawn anchor was missing or obstructed at respawn time
+    SpawnAnchorLost {
+        player_id: u32,
+        anchor_id: SpawnAnchorId,
+    },
+
+    /// Player respawned (anchor_id is None for the world spawn)
+    PlayerRespawned {
+        player_id: u32,
+        position: [f32; 3],
+        anchor_id: Option<SpawnAnchorId>,
+    },
+
+    /// Post-respawn damage immunity ran out
+    PlayerInvulnerabilityEnded {
+        player_id: u32,
+    },
+
+    /// Entity other than a player took damage (after immunity checks)
+    EntityDamaged {
+        entity: InstanceId,
+        amount: f32,
+        health: f32,
+        source: DamageSource,
+    },
+
+    /// Entity other than a player ran out of health
+    EntityDied {
+        entity: InstanceId,
+        source: DamageSource,
+    },
+
+    /// Effective view distance changed (chunks)
+    ViewDistanceChanged {
+        previous: u32,
+        current: u32,
+        reason: ViewDistanceChangeReason,
+    },
+
     /// Apply force to physics entity
     ApplyForce {
         entity_id: u32,
         force: [f32; 3],
     },
 
+    /// Item entity dropped into the world (see `spawn_item_entities`)
+    ItemSpawned {
+        entity_id: u32,
+        stack: LootStack,
+        position: [f32; 3],
+    },
+
+    /// Player or entity entered a trigger volume
+    TriggerEnter {
+        volume: TriggerVolumeId,
+        name: String,
+        occupant: TriggerOccupant,
+    },
+
+    /// Player or entity left a trigger volume (or it was disabled)
+    TriggerExit {
+        volume: TriggerVolumeId,
+        name: String,
+        occupant: TriggerOccupant,
+    },
+
+    /// Player or entity is still inside a volume with a stay interval
+    TriggerStay {
+        volume: TriggerVolumeId,
+        name: String,
+        occupant: TriggerOccupant,
+        ticks_inside: u64,
+    },
+
+    /// Player block action refused by its cooldown
+    /// (see `limit_block_event`)
+    BlockActionRejected {
+        player_id: u32,
+        action: BlockAction,
+        position: VoxelPos,
+        /// Time until the action is available again (milliseconds)
+        retry_in_ms: u32,
+    },
+
+    /// Player block action refused because the block is in someone
+    /// else's claim (see `protect_block_event`)
+    BlockEditDenied {
+        player_id: u32,
+        action: BlockAction,
+        position: VoxelPos,
+        claim: ClaimId,
+    },
+
+    /// Player stat crossed an achievement threshold
+    /// (see `add_player_stat`)
+    AchievementUnlocked {
+        player: String,
+        achievement: String,
+        stat: String,
+        value: f64,
+    },
+
+    /// World loading advanced (see `begin_gateway_load_phase`)
+    LoadProgress {
+        phase: LoadPhase,
+        status: LoadPhaseStatus,
+        /// Progress of the phase (0 - 100)
+        phase_percent: f32,
+        /// Weighted progress of the whole load (0 - 100)
+        overall_percent: f32,
+    },
+
+    /// Enough of the world is loaded to hand control to the game
+    WorldPlayable {
+        spawn_chunks: u32,
+    },
+
+    /// A new season started (see `update_gateway_seasons`)
+    SeasonChanged {
+        from: Season,
+        to: Season,
+        day: u64,
+    },
+
+    /// Rumble a player's gamepad (explosions and other game-defined
+    /// moments; block break and damage rumble on their own)
+    Rumble {
+        player_id: u32,
+        cue: HapticCue,
+    },
+
     /// Spawn particle effect
     SpawnParticle {
         position: [f32; 3],
@@ -65,6 +241,14 @@ pub enum GameEvent {
         count: u32,
     },
 
+    /// Game-defined packet received on a custom channel
+    /// (see `network::receive_custom_packets`)
+    CustomPacket {
+        connection_id: u32,
+        channel: String,
+        payload: Vec<u8>,
+    },
+
     /// Custom game event
     Custom {
         event_type: String,
@@ -141,7 +325,12 @@ pub struct BlockProperties {
     pub is_liquid: bool,
     pub light_emission: u8,
     pub hardness: f32,
+    /// Surface grip for walking characters (ice is low)
     pub friction: f32,
+    /// Fraction of landing speed bounced back (slime is high)
+    pub restitution: f32,
+    /// Scales walking speed on the block (soul sand is below 1)
+    pub speed_multiplier: f32,
     pub can_interact: bool,
 }
 
@@ -180,6 +369,16 @@ pub struct PlayerInfo {
     pub active_block: BlockId,
 }
 
+/// Player block edit accepted by the gateway, for the engine to apply
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayerBlockEdit {
+    pub player_id: u32,
+    pub position: VoxelPos,
+    /// `BlockId::AIR` for a break
+    pub block_id: BlockId,
+    pub metadata: u8,
+}
+
 /// Game gateway data - the event queue state
 pub struct GameGatewayData {
     /// Pending events to process
@@ -205,6 +404,58 @@ pub struct GameGatewayData {
 
     /// Registered blocks
     pub registered_blocks: Vec<BlockRegistration>,
+
+    /// Entity attributes readable by game logic and UI
+    pub attributes: AttributeStoreData,
+
+    /// Entity health (stored in `attributes`) and environmental damage
+    pub health: HealthData,
+
+    /// Objective scoreboard readable by game logic and UI
+    pub scoreboard: ScoreboardData,
+
+    /// Player statistics and achievements readable by game logic and UI
+    pub stats: PlayerStatsData,
+
+    /// Place, break and interact cooldowns per player, spent as player
+    /// block events are queued
+    pub action_limits: ActionLimitData,
+
+    /// Milliseconds of gateway updates so far; the cooldowns run on it
+    /// (advanced by `update_gateway`)
+    pub clock_ms: u64,
+
+    /// Mob behavior trees and the game's node handlers
+    /// (see `update_gateway_behavior`)
+    pub behavior: BehaviorTreeData,
+
+    /// World loading phases and loading screen hooks
+    /// (see `query_load_progress`)
+    pub load_progress: LoadProgressData,
+
+    /// World clock and season (see `update_gateway_seasons`)
+    pub seasons: SeasonData,
+
+    /// Gamepad rumble settings and local player gamepads
+    /// (see `update_gateway_haptics`)
+    pub haptics: HapticsData,
+
+    /// Shadow cache answering light queries (set by the engine)
+    pub light_cache: Option<LightCacheHandle>,
+
+    /// Engine buffers answering entity tag queries (set by the engine)
+    pub engine_buffers: Option<SharedEngineBuffers>,
+
+    /// Enter/exit zones defined by the map (saved with the world)
+    pub triggers: TriggerVolumeData,
+
+    /// Protected regions checked as player block events are queued
+    /// (saved with the world)
+    pub claims: RegionClaimData,
+
+    /// Player block edits processed since the engine last applied them
+    /// (see `take_gateway_block_edits`)
+    pub block_edits: Vec<PlayerBlockEdit>,
 }
 
 /// Gateway configuration
@@ -269,8 +520,13 @@ pub trait GameDataAccess {
 /// Handle to game data (type-erased for flexibility)
 pub type GameDataHandle = Arc<Mutex<dyn std::any::Any + Send + Sync>>;
 
+/// Shadow cache shared between the engine and gateway light queries
+pub type LightCacheHandle = Arc<Mutex<ShadowCacheData>>;
+
 impl Default for GameGatewayData {
     fn default() -> Self {
+        let mut attributes = AttributeStoreData::default();
+        let health = create_entity_health(&mut attributes, default_health_config());
         Self {
             pending_events: VecDeque::new(),
             pending_commands: VecDeque::new(),
@@ -280,6 +536,21 @@ impl Default for GameGatewayData {
             initialized: false,
             active_block: BlockId(1), // Default to first block
             registered_blocks: Vec::new(),
+            attributes,
+            health,
+            scoreboard: ScoreboardData::default(),
+            stats: PlayerStatsData::default(),
+            action_limits: create_action_limits(default_action_limit_config()),
+            clock_ms: 0,
+            behavior: create_behavior_trees(default_behavior_tick_config()),
+            load_progress: create_load_progress(default_load_progress_config()),
+            seasons: create_season_data(default_season_config()),
+            haptics: create_haptics(),
+            light_cache: None,
+            engine_buffers: None,
+            triggers: TriggerVolumeData::default(),
+            claims: RegionClaimData::default(),
+            block_edits: Vec::new(),
         }
     }
 }
@@ -303,7 +574,9 @@ impl Default for BlockProperties {
             is_liquid: false,
             light_emission: 0,
             hardness: 1.0,
-            friction: 0.5,
+            friction: DEFAULT_BLOCK_FRICTION,
+            restitution: DEFAULT_BLOCK_RESTITUTION,
+            speed_multiplier: DEFAULT_SPEED_MULTIPLIER,
             can_interact: false,
         }
     }
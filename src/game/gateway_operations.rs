@@ -3,11 +3,87 @@
 //! Functions that operate on GameGatewayData.
 //! This is the OPTIONAL event queue - games can bypass and call engine operations directly.
 
+use super::action_limit_data::{ActionLimitData, BlockAction};
+use super::action_limit_operations::{action_cooldown_remaining, limit_block_event};
+use super::attribute_data::{AttributeChange, AttributeId, AttributeStoreData};
+use super::attribute_operations::{
+    drain_attribute_changes, effective_value, effective_value_by_name, update_attributes,
+};
+use super::behavior_tree_data::{
+    BehaviorActionHandler, BehaviorConditionHandler, BehaviorStatus, BehaviorTreeData,
+    BehaviorTreeId, BehaviorTreeResult,
+};
+use super::behavior_tree_operations::{
+    attach_behavior, behavior_status, detach_behavior, load_behavior_trees,
+    register_behavior_action, register_behavior_condition, tick_behavior_trees,
+};
 use super::gateway_data::{
     GameEvent, GameCommand, GameGatewayData, GatewayConfig, GatewayMetrics,
-    MessageType, BlockRegistration,
+    MessageType, BlockRegistration, LightCacheHandle, PlayerBlockEdit,
+};
+use super::gateway_rpc_operations::serve_gateway_requests;
+use super::health_data::{DamageDealt, HealthData, HealthSample};
+use super::health_operations::{
+    entity_damage_events, entity_health, forward_player_damage, sync_player_health,
+    update_entity_health,
+};
+use super::lifecycle_data::PlayerLifecycleData;
+use super::load_progress_data::{
+    LoadPhase, LoadProgressData, LoadProgressHook, LoadProgressSnapshot,
+};
+use super::load_progress_operations::{
+    advance_load_phase, begin_load_phase, complete_load_phase, fail_load_phase,
+    is_world_playable, load_progress_snapshot, notify_load_progress_hooks,
+    register_load_progress_hook, skip_load_phase,
+};
+use super::player_stats_data::{
+    AchievementProgress, PlayerStatsData, PlayerStatsResult, StatEntry,
+};
+use super::player_stats_operations::{
+    achievement_progress, add_player_stat, player_achievements, player_stat,
+    player_stat_entries, player_stats_save_due, save_player_stats, tick_player_stats,
+};
+use super::region_claim_data::{RegionClaim, RegionClaimData, RegionClaimResult};
+use super::region_claim_operations::{
+    check_block_edit, execute_claim_command, protect_block_event, region_claim_at,
+};
+use super::scoreboard_data::{LeaderboardEntry, ScoreboardData};
+use super::scoreboard_operations::{get_score, leaderboard};
+use super::trigger_volume_data::{
+    TriggerEvent, TriggerEventKind, TriggerOccupant, TriggerSubject, TriggerVolumeData,
+    TriggerVolumeId,
+};
+use super::trigger_volume_operations::{
+    trigger_volume, trigger_volumes_at, update_trigger_volumes,
 };
-use crate::world::core::{BlockId, BlockRegistry};
+use crate::activation_range_data::ActivationRangeData;
+use crate::engine_buffers::{EntityTagBuffers, SharedEngineBuffers};
+use crate::entity_tag_data::{EntityPositions, EntityTagResult};
+use crate::entity_tag_operations::{
+    add_entity_tag, entity_tag_id, intern_entity_tag, query_tagged_in_radius, remove_entity_tag,
+};
+use crate::input::haptics_data::{GamepadId, HapticCue, HapticsData, RumbleCommand};
+use crate::input::haptics_operations::{
+    assign_gamepad, event_haptic_cue, set_rumble_enabled, set_rumble_strength, trigger_rumble,
+    update_haptics,
+};
+use crate::instance::InstanceId;
+use crate::persistence::SaveCipherData;
+use crate::physics::buoyancy_data::FluidMaterialTable;
+use crate::physics::surface_material_data::{SurfaceMaterial, SurfaceMaterialTable};
+use crate::physics::surface_material_operations::set_surface_material;
+use crate::world::core::{BlockId, BlockRegistry, VoxelPos};
+use crate::world::data_types::WorldData;
+use crate::world::lighting::{
+    filter_spawn_positions, get_light_at, is_sky_visible, sample_light_batch, LightLevel,
+    LightSample, SpawnLightRule, TimeOfDayData,
+};
+use crate::world::season_data::{SavedWorldTime, SeasonChange, SeasonData, SeasonSnapshot};
+use crate::world::season_operations::{
+    advance_world_days, load_world_time, save_world_time, season_snapshot, update_season_clock,
+};
+use crate::world::storage::ShadowCacheData;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
@@ -49,49 +125,89 @@ pub fn is_gateway_initialized() -> bool {
 // EVENT QUEUE OPERATIONS
 // ============================================================================
 
-/// Queue a single event for processing
-pub fn queue_event(event: GameEvent) {
-    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+/// Whether an event is a player block action the gateway refused
+fn is_refused_block_event(event: &GameEvent) -> bool {
+    matches!(
+        event,
+        GameEvent::BlockActionRejected { .. } | GameEvent::BlockEditDenied { .. }
+    )
+}
 
-    if let Some(gateway) = guard.as_mut() {
-        // Check queue size
-        if gateway.pending_events.len() >= gateway.config.max_queue_size {
-            gateway.metrics.events_dropped += 1;
-            if gateway.config.debug_logging {
-                log::warn!("[Gateway] Event queue full, dropping event: {:?}", event);
-            }
-            return;
+/// Queue an event; a player's block event is checked against the region
+/// claims, then its action cooldown at the gateway clock, and queued as
+/// `BlockEditDenied` or `BlockActionRejected` when refused. A denied edit
+/// does not use up the player's budget. Returns whether the event itself
+/// was queued.
+fn push_event(gateway: &mut GameGatewayData, event: GameEvent) -> bool {
+    if gateway.pending_events.len() >= gateway.config.max_queue_size {
+        gateway.metrics.events_dropped += 1;
+        if gateway.config.debug_logging {
+            log::warn!("[Gateway] Event queue full, dropping event: {:?}", event);
         }
+        return false;
+    }
 
-        gateway.pending_events.push_back(event);
+    let mut event = protect_block_event(&mut gateway.claims, event);
+    if !is_refused_block_event(&event) {
+        event = limit_block_event(&mut gateway.action_limits, event, gateway.clock_ms);
+    }
+    let allowed = !is_refused_block_event(&event);
+    gateway.pending_events.push_back(event);
 
-        // Update peak queue size
-        if gateway.pending_events.len() > gateway.metrics.peak_queue_size {
-            gateway.metrics.peak_queue_size = gateway.pending_events.len();
-        }
+    // Update peak queue size
+    if gateway.pending_events.len() > gateway.metrics.peak_queue_size {
+        gateway.metrics.peak_queue_size = gateway.pending_events.len();
     }
+    allowed
 }
 
-/// Queue multiple events at once
+/// Queue a single event for processing. Player block events go through
+/// the region claims and action cooldowns (see `push_event`).
+pub fn queue_event(event: GameEvent) {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    if let Some(gateway) = guard.as_mut() {
+        push_event(gateway, event);
+    }
+}
+
+/// Queue multiple events at once, each checked like `queue_event`
 pub fn queue_events(events: Vec<GameEvent>) {
     let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
 
     if let Some(gateway) = guard.as_mut() {
         for event in events {
-            if gateway.pending_events.len() >= gateway.config.max_queue_size {
-                gateway.metrics.events_dropped += 1;
-                continue;
-            }
-
-            gateway.pending_events.push_back(event);
+            push_event(gateway, event);
         }
+    }
+}
+
+/// Queue a command for the engine, executed after the pending events
+pub fn queue_command(command: GameCommand) {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
 
-        if gateway.pending_events.len() > gateway.metrics.peak_queue_size {
-            gateway.metrics.peak_queue_size = gateway.pending_events.len();
+    if let Some(gateway) = guard.as_mut() {
+        if gateway.pending_commands.len() >= gateway.config.max_queue_size {
+            gateway.metrics.events_dropped += 1;
+            return;
         }
+        gateway.pending_commands.push_back(command);
     }
 }
 
+/// Engine side, once per frame: advance the gateway clock by `delta_ms`,
+/// process pending events and commands, then
+/// time out stale gateway RPC requests and answer queued ones from `world`.
+/// Returns the requests answered. The player block edits processed are
+/// left for `take_gateway_block_edits`.
+pub fn update_gateway(world: &WorldData, chunk_size: u32, delta_ms: u64) -> usize {
+    if let Some(gateway) = GATEWAY.lock().expect("[Gateway] Failed to lock").as_mut() {
+        gateway.clock_ms += delta_ms;
+    }
+    process_update();
+    serve_gateway_requests(world, chunk_size, delta_ms)
+}
+
 /// Process all pending events (call this once per frame/tick)
 pub fn process_update() {
     let start = Instant::now();
@@ -134,17 +250,71 @@ fn process_single_event(gateway: &mut GameGatewayData, event: &GameEvent) {
         log::debug!("[Gateway] Processing event: {:?}", event);
     }
 
-    // In a real implementation, this would call engine operations
-    // For now, we just log
+    // Player edits passed the claims and cooldowns when queued; the engine
+    // applies them.
+    // Edits without a player report what the game already did.
     match event {
-        GameEvent::BlockBreak { position, block_id, .. } => {
+        GameEvent::BlockBreak {
+            position,
+            block_id,
+            player_id,
+        } => {
             log::debug!("[Gateway] Block break at {:?}: {:?}", position, block_id);
+            if let Some(player_id) = *player_id {
+                gateway.block_edits.push(PlayerBlockEdit {
+                    player_id,
+                    position: *position,
+                    block_id: BlockId::AIR,
+                    metadata: 0,
+                });
+            }
         }
-        GameEvent::BlockPlace { position, block_id, .. } => {
+        GameEvent::BlockPlace {
+            position,
+            block_id,
+            metadata,
+            player_id,
+        } => {
             log::debug!("[Gateway] Block place at {:?}: {:?}", position, block_id);
+            if let Some(player_id) = *player_id {
+                gateway.block_edits.push(PlayerBlockEdit {
+                    player_id,
+                    position: *position,
+                    block_id: *block_id,
+                    metadata: *metadata,
+                });
+            }
+        }
+        GameEvent::BlockEditDenied {
+            player_id,
+            position,
+            claim,
+            ..
+        } => {
+            log::debug!(
+                "[Gateway] Player {} denied an edit at {:?} in claim {:?}",
+                player_id,
+                position,
+                claim
+            );
         }
         _ => {}
     }
+
+    if let Some(cue) = event_haptic_cue(event) {
+        trigger_rumble(&mut gateway.haptics, cue.player_id, cue.cue);
+    }
+}
+
+/// Player block edits processed since the last call, for the engine to
+/// apply to the world
+pub fn take_gateway_block_edits() -> Vec<PlayerBlockEdit> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_mut()
+        .map(|gateway| std::mem::take(&mut gateway.block_edits))
+        .unwrap_or_default()
 }
 
 /// Execute a single command (internal)
@@ -187,6 +357,30 @@ pub fn add_block_registration(registration: BlockRegistration) {
     }
 }
 
+/// Surface feel of a game block, `None` if it cannot be stood on
+pub fn registration_surface_material(registration: &BlockRegistration) -> Option<SurfaceMaterial> {
+    let properties = &registration.properties;
+    if !properties.is_solid || properties.is_liquid {
+        return None;
+    }
+    Some(SurfaceMaterial {
+        friction: properties.friction.clamp(0.0, 1.0),
+        restitution: properties.restitution.clamp(0.0, 1.0),
+        speed_multiplier: properties.speed_multiplier.max(0.0),
+    })
+}
+
+/// Write the surface feel of every game-registered block into the table
+pub fn apply_registered_surface_materials(table: &mut SurfaceMaterialTable) {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    if let Some(gateway) = guard.as_ref() {
+        for block_reg in &gateway.registered_blocks {
+            set_surface_material(table, block_reg.id, registration_surface_material(block_reg));
+        }
+    }
+}
+
 /// Get active block for placement
 pub fn get_active_block() -> BlockId {
     let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
@@ -265,6 +459,729 @@ pub fn update_gateway_config(config: GatewayConfig) {
     }
 }
 
+// ============================================================================
+// ATTRIBUTES
+// ============================================================================
+
+/// Run `f` on the gateway attribute store (None if the gateway is not initialized)
+pub fn with_gateway_attributes<R>(f: impl FnOnce(&mut AttributeStoreData) -> R) -> Option<R> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard.as_mut().map(|gateway| f(&mut gateway.attributes))
+}
+
+/// Effective value of an entity attribute
+pub fn query_attribute(entity: InstanceId, attribute: AttributeId) -> Option<f32> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .and_then(|gateway| effective_value(&gateway.attributes, entity, attribute))
+}
+
+/// Effective value of an entity attribute, by attribute name
+pub fn query_attribute_by_name(entity: InstanceId, name: &str) -> Option<f32> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .and_then(|gateway| effective_value_by_name(&gateway.attributes, entity, name))
+}
+
+/// Expire attribute modifiers up to `tick`; returns how many expired
+pub fn update_gateway_attributes(tick: u64) -> usize {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    if let Some(gateway) = guard.as_mut() {
+        update_attributes(&mut gateway.attributes, tick)
+    } else {
+        0
+    }
+}
+
+/// Get and clear effective value changes (for UI refresh)
+pub fn drain_gateway_attribute_changes() -> Vec<AttributeChange> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    if let Some(gateway) = guard.as_mut() {
+        drain_attribute_changes(&mut gateway.attributes)
+    } else {
+        Vec::new()
+    }
+}
+
+// ============================================================================
+// HEALTH
+// ============================================================================
+
+/// Run `f` on the gateway health state and the attribute store holding the
+/// health values (None if the gateway is not initialized)
+pub fn with_gateway_health<R>(
+    f: impl FnOnce(&mut HealthData, &mut AttributeStoreData) -> R,
+) -> Option<R> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_mut()
+        .map(|gateway| f(&mut gateway.health, &mut gateway.attributes))
+}
+
+/// Current health of an entity
+pub fn query_entity_health(entity: InstanceId) -> Option<f32> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .and_then(|gateway| entity_health(&gateway.health, &gateway.attributes, entity))
+}
+
+/// Deal this tick's environmental damage to the gateway's entities; pass
+/// the result to `dispatch_gateway_health_damage`
+pub fn update_gateway_health(
+    samples: &[HealthSample],
+    delta_time: f32,
+    fluids: &FluidMaterialTable,
+    block_at: impl FnMut(VoxelPos) -> BlockId,
+) -> Vec<DamageDealt> {
+    with_gateway_health(|health, attributes| {
+        update_entity_health(health, attributes, samples, delta_time, fluids, block_at)
+    })
+    .unwrap_or_default()
+}
+
+/// Report damage from `update_gateway_health`: players through the
+/// lifecycle system, other entities as queued events
+pub fn dispatch_gateway_health_damage(
+    lifecycle: &mut PlayerLifecycleData,
+    dealt: &[DamageDealt],
+) -> Vec<GameEvent> {
+    // The lifecycle queues its own events, so it runs outside the lock
+    let mut events = forward_player_damage(lifecycle, dealt);
+    with_gateway_health(|health, attributes| sync_player_health(health, attributes, lifecycle));
+
+    let entity_events = entity_damage_events(dealt);
+    queue_events(entity_events.clone());
+    events.extend(entity_events);
+    events
+}
+
+// ============================================================================
+// SCOREBOARD
+// ============================================================================
+
+/// Run `f` on the gateway scoreboard (None if the gateway is not initialized)
+pub fn with_gateway_scoreboard<R>(f: impl FnOnce(&mut ScoreboardData) -> R) -> Option<R> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard.as_mut().map(|gateway| f(&mut gateway.scoreboard))
+}
+
+/// A player's score on an objective
+pub fn query_score(objective: &str, player: &str) -> Option<i64> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .and_then(|gateway| get_score(&gateway.scoreboard, objective, player))
+}
+
+/// Top `limit` rows of an objective, for leaderboard UIs
+pub fn query_leaderboard(objective: &str, limit: usize) -> Vec<LeaderboardEntry> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .map(|gateway| leaderboard(&gateway.scoreboard, objective, limit))
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// ACTION LIMITS
+// ============================================================================
+
+/// Run `f` on the gateway action cooldowns (None if the gateway is not initialized)
+pub fn with_gateway_action_limits<R>(f: impl FnOnce(&mut ActionLimitData) -> R) -> Option<R> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard.as_mut().map(|gateway| f(&mut gateway.action_limits))
+}
+
+/// `queue_event` that reports the outcome: a place, break or interact in
+/// someone else's claim is queued as `BlockEditDenied`, one refused by its
+/// cooldown as `BlockActionRejected`. Returns whether the event itself was
+/// queued.
+pub fn queue_limited_event(event: GameEvent) -> bool {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_mut()
+        .is_some_and(|gateway| push_event(gateway, event))
+}
+
+/// Time until a player can use an action again at the gateway clock
+/// (0 when ready or without gateway)
+pub fn query_action_cooldown(player_id: u32, action: BlockAction) -> u32 {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard.as_ref().map_or(0, |gateway| {
+        action_cooldown_remaining(&gateway.action_limits, player_id, action, gateway.clock_ms)
+    })
+}
+
+// ============================================================================
+// REGION CLAIMS
+// ============================================================================
+
+/// Run `f` on the gateway region claims (None if the gateway is not initialized)
+pub fn with_gateway_claims<R>(f: impl FnOnce(&mut RegionClaimData) -> R) -> Option<R> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard.as_mut().map(|gateway| f(&mut gateway.claims))
+}
+
+/// Run a `claim` admin command for `issuer` (None = server console)
+pub fn run_gateway_claim_command(
+    issuer: Option<u32>,
+    command: &str,
+) -> Option<RegionClaimResult<String>> {
+    with_gateway_claims(|claims| execute_claim_command(claims, issuer, command))
+}
+
+/// Claim covering a block, if any
+pub fn query_claim_at(position: VoxelPos) -> Option<RegionClaim> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .and_then(|gateway| region_claim_at(&gateway.claims, position).cloned())
+}
+
+/// Whether a player may take an action on a block under the claims
+pub fn query_block_edit_allowed(player_id: u32, action: BlockAction, position: VoxelPos) -> bool {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard.as_ref().is_none_or(|gateway| {
+        check_block_edit(&gateway.claims, player_id, action, position).is_ok()
+    })
+}
+
+// ============================================================================
+// PLAYER STATS
+// ============================================================================
+
+/// Run `f` on the gateway player stats (None if the gateway is not initialized)
+pub fn with_gateway_stats<R>(f: impl FnOnce(&mut PlayerStatsData) -> R) -> Option<R> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard.as_mut().map(|gateway| f(&mut gateway.stats))
+}
+
+/// Add to a player's stat, queueing any achievements it unlocks. Returns
+/// the new value.
+pub fn add_gateway_stat(player: &str, stat: &str, amount: f64) -> Option<PlayerStatsResult<f64>> {
+    let result = with_gateway_stats(|stats| {
+        add_player_stat(stats, player, stat, amount)
+            .map(|events| (events, player_stat(stats, player, stat)))
+    })?;
+    Some(result.map(|(events, value)| {
+        if !events.is_empty() {
+            queue_events(events);
+        }
+        value
+    }))
+}
+
+/// Count play time for the online players, queue unlocked achievements and
+/// save into `world_dir` once the save interval has passed. Returns whether
+/// it saved.
+pub fn update_gateway_stats(
+    online_players: &[&str],
+    delta_time: f32,
+    world_dir: &Path,
+    cipher: Option<&SaveCipherData>,
+) -> PlayerStatsResult<bool> {
+    let Some((events, saved)) = with_gateway_stats(|stats| {
+        let events = tick_player_stats(stats, online_players, delta_time);
+        let saved = if player_stats_save_due(stats) {
+            save_player_stats(stats, world_dir, cipher).map(|()| true)
+        } else {
+            Ok(false)
+        };
+        (events, saved)
+    }) else {
+        return Ok(false);
+    };
+    if !events.is_empty() {
+        queue_events(events);
+    }
+    saved
+}
+
+/// A player's stat (0 if never counted or without gateway)
+pub fn query_player_stat(player: &str, stat: &str) -> f64 {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .map_or(0.0, |gateway| player_stat(&gateway.stats, player, stat))
+}
+
+/// Every stat of a player, for stat screens
+pub fn query_player_stats(player: &str) -> Vec<StatEntry> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .map(|gateway| player_stat_entries(&gateway.stats, player))
+        .unwrap_or_default()
+}
+
+/// Achievement ids a player unlocked, oldest first
+pub fn query_player_achievements(player: &str) -> Vec<String> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .map(|gateway| player_achievements(&gateway.stats, player).to_vec())
+        .unwrap_or_default()
+}
+
+/// Progress of a player on every achievement, for achievement screens
+pub fn query_achievement_progress(player: &str) -> Vec<AchievementProgress> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .map(|gateway| achievement_progress(&gateway.stats, player))
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// BEHAVIOR TREES
+// ============================================================================
+
+/// Run `f` on the gateway behavior trees (None if the gateway is not initialized)
+pub fn with_gateway_behavior<R>(f: impl FnOnce(&mut BehaviorTreeData) -> R) -> Option<R> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard.as_mut().map(|gateway| f(&mut gateway.behavior))
+}
+
+/// Register a condition node handler. Handlers run under the gateway lock:
+/// push events onto the context instead of calling gateway functions.
+pub fn register_gateway_behavior_condition(name: &str, handler: BehaviorConditionHandler) {
+    with_gateway_behavior(|behavior| register_behavior_condition(behavior, name, handler));
+}
+
+/// Register an action node handler (same rules as conditions)
+pub fn register_gateway_behavior_action(name: &str, handler: BehaviorActionHandler) {
+    with_gateway_behavior(|behavior| register_behavior_action(behavior, name, handler));
+}
+
+/// Load a `.json` or `.toml` behavior file into the gateway
+pub fn load_gateway_behavior_trees(path: &Path) -> Option<BehaviorTreeResult<usize>> {
+    with_gateway_behavior(|behavior| load_behavior_trees(behavior, path))
+}
+
+/// Run the named tree on an entity
+pub fn attach_gateway_behavior(entity: u32, tree: &str) -> Option<BehaviorTreeResult<BehaviorTreeId>> {
+    with_gateway_behavior(|behavior| attach_behavior(behavior, entity, tree))
+}
+
+/// Stop running a tree on an entity
+pub fn detach_gateway_behavior(entity: u32) -> bool {
+    with_gateway_behavior(|behavior| detach_behavior(behavior, entity)).unwrap_or(false)
+}
+
+/// Tick the behavior trees of entities whose AI is in `activation` range
+/// (every entity with None) and queue the events their handlers emitted.
+/// Call once per tick; returns how many entities were evaluated.
+pub fn update_gateway_behavior(
+    activation: Option<&ActivationRangeData>,
+    tick: u64,
+    tick_seconds: f32,
+) -> usize {
+    let Some((events, ticked)) = with_gateway_behavior(|behavior| {
+        let events = tick_behavior_trees(behavior, activation, tick, tick_seconds);
+        (events, behavior.stats.ticked as usize)
+    }) else {
+        return 0;
+    };
+    if !events.is_empty() {
+        queue_events(events);
+    }
+    ticked
+}
+
+/// Root status of an entity's last behavior tick
+pub fn query_behavior_status(entity: u32) -> Option<BehaviorStatus> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .and_then(|gateway| behavior_status(&gateway.behavior, entity))
+}
+
+// ============================================================================
+// LOAD PROGRESS
+// ============================================================================
+
+/// Run `f` on the gateway load progress (None if the gateway is not initialized)
+pub fn with_gateway_load_progress<R>(f: impl FnOnce(&mut LoadProgressData) -> R) -> Option<R> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard.as_mut().map(|gateway| f(&mut gateway.load_progress))
+}
+
+/// Apply a phase report, then call the loading screen hooks and queue the
+/// events outside the gateway lock so hooks may query the gateway
+fn report_gateway_load_progress(report: impl FnOnce(&mut LoadProgressData) -> Vec<GameEvent>) {
+    let Some((events, snapshot, hooks)) = with_gateway_load_progress(|progress| {
+        let events = report(progress);
+        (events, load_progress_snapshot(progress), progress.hooks.clone())
+    }) else {
+        return;
+    };
+    notify_load_progress_hooks(&hooks, &snapshot);
+    if !events.is_empty() {
+        queue_events(events);
+    }
+}
+
+/// Start a load phase of `total` units of work (0 when unknown)
+pub fn begin_gateway_load_phase(phase: LoadPhase, total: u32, message: &str) {
+    report_gateway_load_progress(|progress| begin_load_phase(progress, phase, total, message));
+}
+
+/// Set how many units of a load phase are done
+pub fn advance_gateway_load_phase(phase: LoadPhase, completed: u32) {
+    report_gateway_load_progress(|progress| advance_load_phase(progress, phase, completed));
+}
+
+/// Finish a load phase
+pub fn complete_gateway_load_phase(phase: LoadPhase) {
+    report_gateway_load_progress(|progress| complete_load_phase(progress, phase));
+}
+
+/// Leave a load phase out of the overall progress
+pub fn skip_gateway_load_phase(phase: LoadPhase) {
+    report_gateway_load_progress(|progress| skip_load_phase(progress, phase));
+}
+
+/// Stop a load phase with the reason shown on the loading screen
+pub fn fail_gateway_load_phase(phase: LoadPhase, reason: &str) {
+    report_gateway_load_progress(|progress| fail_load_phase(progress, phase, reason));
+}
+
+/// What the loading screen should show
+pub fn query_load_progress() -> Option<LoadProgressSnapshot> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .map(|gateway| load_progress_snapshot(&gateway.load_progress))
+}
+
+/// Whether enough of the world is loaded to hand control to the game
+pub fn is_gateway_world_playable() -> bool {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .is_some_and(|gateway| is_world_playable(&gateway.load_progress))
+}
+
+/// Register a hook drawing the game's loading screen; it is called after
+/// every progress report, outside the gateway lock
+pub fn register_gateway_load_progress_hook(hook: LoadProgressHook) {
+    with_gateway_load_progress(|progress| register_load_progress_hook(progress, hook));
+}
+
+// ============================================================================
+// SEASONS
+// ============================================================================
+
+/// Run `f` on the gateway world clock (None if the gateway is not initialized)
+pub fn with_gateway_seasons<R>(f: impl FnOnce(&mut SeasonData) -> R) -> Option<R> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard.as_mut().map(|gateway| f(&mut gateway.seasons))
+}
+
+/// Queue `SeasonChanged` for a transition
+fn report_season_change(change: Option<SeasonChange>) -> Option<SeasonChange> {
+    if let Some(change) = change {
+        queue_event(GameEvent::SeasonChanged {
+            from: change.from,
+            to: change.to,
+            day: change.day,
+        });
+    }
+    change
+}
+
+/// Follow the game's day/night cycle; call once per frame after advancing
+/// it (`Engine::update_seasons` does, and applies the new season). Returns
+/// the transition if a new season started.
+pub fn update_gateway_seasons(time: &TimeOfDayData) -> Option<SeasonChange> {
+    report_season_change(with_gateway_seasons(|seasons| update_season_clock(seasons, time))?)
+}
+
+/// Skip whole days (sleeping, time commands)
+pub fn advance_gateway_days(days: u64) -> Option<SeasonChange> {
+    report_season_change(with_gateway_seasons(|seasons| advance_world_days(seasons, days))?)
+}
+
+/// Current season and the biome parameters in effect
+pub fn query_season() -> Option<SeasonSnapshot> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .map(|gateway| season_snapshot(&gateway.seasons))
+}
+
+/// World clock to write with the save
+pub fn save_gateway_world_time() -> Option<SavedWorldTime> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard
+        .as_ref()
+        .map(|gateway| save_world_time(&gateway.seasons))
+}
+
+/// Restore the world clock from a save; returns the time of day to put
+/// back into the game's day/night cycle
+pub fn load_gateway_world_time(saved: &SavedWorldTime) -> Option<TimeOfDayData> {
+    with_gateway_seasons(|seasons| load_world_time(seasons, saved))
+}
+
+// ============================================================================
+// HAPTICS
+// ============================================================================
+
+/// Run `f` on the gateway rumble state (None if the gateway is not initialized)
+pub fn with_gateway_haptics<R>(f: impl FnOnce(&mut HapticsData) -> R) -> Option<R> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard.as_mut().map(|gateway| f(&mut gateway.haptics))
+}
+
+/// Route a local player's rumble to a gamepad
+pub fn assign_gateway_gamepad(player_id: u32, device: GamepadId) -> Option<GamepadId> {
+    with_gateway_haptics(|haptics| assign_gamepad(haptics, player_id, device))?
+}
+
+/// Rumble a player's gamepad right away, without going through the event
+/// queue; returns whether a pattern started
+pub fn trigger_gateway_rumble(player_id: u32, cue: HapticCue) -> bool {
+    with_gateway_haptics(|haptics| trigger_rumble(haptics, player_id, cue)).unwrap_or(false)
+}
+
+/// Rumble settings toggle and strength
+pub fn set_gateway_rumble(enabled: bool, strength: f32) {
+    with_gateway_haptics(|haptics| {
+        set_rumble_enabled(haptics, enabled);
+        set_rumble_strength(haptics, strength);
+    });
+}
+
+/// Advance rumble playback; call once per frame and send the returned
+/// commands to the gamepads
+pub fn update_gateway_haptics(dt: f32) -> Vec<RumbleCommand> {
+    with_gateway_haptics(|haptics| update_haptics(haptics, dt)).unwrap_or_default()
+}
+
+// ============================================================================
+// LIGHT QUERIES
+// ============================================================================
+
+/// Share the engine's shadow cache so games can query light through the gateway
+pub fn set_gateway_light_cache(cache: LightCacheHandle) {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    if let Some(gateway) = guard.as_mut() {
+        gateway.light_cache = Some(cache);
+    }
+}
+
+/// Run `f` on the shared shadow cache (None without gateway or cache)
+pub fn with_gateway_light_cache<R>(f: impl FnOnce(&mut ShadowCacheData) -> R) -> Option<R> {
+    // Release the gateway before locking the cache so queries never hold both
+    let cache = {
+        let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+        guard.as_ref().and_then(|gateway| gateway.light_cache.clone())
+    }?;
+    let mut cache = cache.lock().expect("[Gateway] Failed to lock light cache");
+    Some(f(&mut cache))
+}
+
+/// Block and sky light at a voxel (None until its chunk is cached)
+pub fn query_light_at(pos: VoxelPos) -> Option<LightLevel> {
+    with_gateway_light_cache(|cache| get_light_at(cache, pos)).flatten()
+}
+
+/// Whether a voxel can see the sky (None while unknown)
+pub fn query_sky_visible(pos: VoxelPos) -> Option<bool> {
+    with_gateway_light_cache(|cache| is_sky_visible(cache, pos)).flatten()
+}
+
+/// Light and sky visibility of many voxels
+pub fn query_light_batch(positions: &[VoxelPos]) -> Vec<LightSample> {
+    with_gateway_light_cache(|cache| sample_light_batch(cache, positions)).unwrap_or_default()
+}
+
+/// Candidate positions that meet a spawn light rule
+pub fn query_spawn_positions(positions: &[VoxelPos], rule: &SpawnLightRule) -> Vec<VoxelPos> {
+    with_gateway_light_cache(|cache| filter_spawn_positions(cache, positions, rule))
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// ENTITY TAGS
+// ============================================================================
+
+/// Share the engine buffers so scripts and plugins can tag and query entities
+pub fn set_gateway_engine_buffers(buffers: SharedEngineBuffers) {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    if let Some(gateway) = guard.as_mut() {
+        gateway.engine_buffers = Some(buffers);
+    }
+}
+
+/// Run `f` on the entity tags and positions (None without gateway or buffers)
+pub fn with_gateway_entity_tags<R>(
+    f: impl FnOnce(&mut EntityTagBuffers, &EntityPositions) -> R,
+) -> Option<R> {
+    // Release the gateway before locking the buffers so queries never hold both
+    let buffers = {
+        let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+        guard.as_ref().and_then(|gateway| gateway.engine_buffers.clone())
+    }?;
+    let mut buffers = buffers.write();
+    let buffers = &mut *buffers;
+    Some(f(&mut buffers.tags, &buffers.physics.positions))
+}
+
+/// Tag an entity by name, interning the tag; Ok(false) if it already had it
+pub fn tag_entity(entity: u32, tag: &str) -> Option<EntityTagResult<bool>> {
+    with_gateway_entity_tags(|tags, _| {
+        intern_entity_tag(tags, tag).map(|id| add_entity_tag(tags, entity, id))
+    })
+}
+
+/// Remove a tag from an entity; false if it did not have it
+pub fn untag_entity(entity: u32, tag: &str) -> bool {
+    with_gateway_entity_tags(|tags, _| {
+        entity_tag_id(tags, tag).is_some_and(|id| remove_entity_tag(tags, entity, id))
+    })
+    .unwrap_or(false)
+}
+
+/// Entities carrying `tag` within `radius` of `center`
+pub fn query_tagged_entities(tag: &str, center: [f32; 3], radius: f32) -> Vec<u32> {
+    with_gateway_entity_tags(|tags, positions| {
+        let mut entities = Vec::new();
+        if let Some(id) = entity_tag_id(tags, tag) {
+            query_tagged_in_radius(tags, positions, id, center, radius, &mut entities);
+        }
+        entities
+    })
+    .unwrap_or_default()
+}
+
+// ============================================================================
+// TRIGGER VOLUMES
+// ============================================================================
+
+/// Run `f` on the gateway trigger volumes (None if the gateway is not initialized)
+pub fn with_gateway_triggers<R>(f: impl FnOnce(&mut TriggerVolumeData) -> R) -> Option<R> {
+    let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    guard.as_mut().map(|gateway| f(&mut gateway.triggers))
+}
+
+/// Game event for a trigger volume event
+fn trigger_game_event(data: &TriggerVolumeData, event: &TriggerEvent) -> Option<GameEvent> {
+    let name = trigger_volume(data, event.volume)?.name.clone();
+    Some(match event.kind {
+        TriggerEventKind::Enter => GameEvent::TriggerEnter {
+            volume: event.volume,
+            name,
+            occupant: event.occupant,
+        },
+        TriggerEventKind::Exit => GameEvent::TriggerExit {
+            volume: event.volume,
+            name,
+            occupant: event.occupant,
+        },
+        TriggerEventKind::Stay { ticks_inside } => GameEvent::TriggerStay {
+            volume: event.volume,
+            name,
+            occupant: event.occupant,
+            ticks_inside,
+        },
+    })
+}
+
+/// Test `players` (and every physics entity of the shared engine buffers)
+/// against the trigger volumes for `tick`, queueing the resulting events.
+/// Call once per tick; returns how many were queued.
+pub fn update_gateway_triggers(tick: u64, players: &[TriggerSubject]) -> usize {
+    let mut subjects = players.to_vec();
+
+    // Release the gateway before locking the buffers so updates never hold both
+    let buffers = {
+        let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+        guard.as_ref().and_then(|gateway| gateway.engine_buffers.clone())
+    };
+    if let Some(buffers) = buffers {
+        let buffers = buffers.read();
+        let physics = &buffers.physics;
+        subjects.extend(
+            physics
+                .positions
+                .iter()
+                .take(physics.entity_count as usize)
+                .enumerate()
+                .map(|(entity, &position)| TriggerSubject {
+                    occupant: TriggerOccupant::Entity(entity as u32),
+                    position,
+                }),
+        );
+    }
+
+    let events = {
+        let mut guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+        let Some(gateway) = guard.as_mut() else {
+            return 0;
+        };
+        let mut trigger_events = Vec::new();
+        update_trigger_volumes(&mut gateway.triggers, tick, &subjects, &mut trigger_events);
+        trigger_events
+            .iter()
+            .filter_map(|event| trigger_game_event(&gateway.triggers, event))
+            .collect::<Vec<_>>()
+    };
+    let count = events.len();
+    if count > 0 {
+        queue_events(events);
+    }
+    count
+}
+
+/// Enabled trigger volumes containing `position`
+pub fn query_trigger_volumes_at(position: [f32; 3]) -> Vec<TriggerVolumeId> {
+    let guard = GATEWAY.lock().expect("[Gateway] Failed to lock");
+
+    let mut volumes = Vec::new();
+    if let Some(gateway) = guard.as_ref() {
+        trigger_volumes_at(&gateway.triggers, position, &mut volumes);
+    }
+    volumes
+}
+
 // ============================================================================
 // MESSAGING
 // ============================================================================
@@ -0,0 +1,126 @@
+//! Game Gateway RPC Data - Request/Response Calls
+//!
+//! The event queue only carries fire-and-forget events. This layer lets game
+//! code ask the engine structured questions without touching its internals:
+//! every request gets a correlation id, the engine answers queued requests
+//! once per frame, and the game either polls the id or receives the answer
+//! in a completion callback. Requests that wait too long time out, and new
+//! requests are refused once too many are in flight.
+//!
+//! Pure DOP: No methods, just data structures.
+
+use crate::world::core::{BlockId, VoxelPos};
+use std::collections::{HashMap, VecDeque};
+
+/// Correlation id of one request
+pub type RpcId = u64;
+
+/// Answer to a request, or why there is none
+pub type RpcResult = Result<GatewayResponse, GatewayRpcError>;
+
+/// Called once with the request's answer, outside the gateway lock
+pub type RpcCallback = Box<dyn FnOnce(RpcId, RpcResult) + Send>;
+
+/// Question from game to engine
+#[derive(Clone, Debug, PartialEq)]
+pub enum GatewayRequest {
+    /// Block, metadata and load state at a position
+    QueryBlockInfo { position: VoxelPos },
+
+    /// Standing position nearest to `near`, searching `radius` columns out
+    FindSpawn { near: VoxelPos, radius: u32 },
+
+    /// Walkable route between two standing positions
+    PathTo {
+        from: VoxelPos,
+        to: VoxelPos,
+        /// Nodes expanded before giving up
+        max_nodes: u32,
+    },
+}
+
+/// Engine answer to a request
+#[derive(Clone, Debug, PartialEq)]
+pub enum GatewayResponse {
+    BlockInfo {
+        position: VoxelPos,
+        block_id: BlockId,
+        metadata: u8,
+        chunk_loaded: bool,
+    },
+
+    /// Feet position with solid ground below and two free blocks, if any
+    Spawn(Option<VoxelPos>),
+
+    /// Positions from start to goal inclusive, if a route was found
+    Path(Option<Vec<VoxelPos>>),
+}
+
+/// Why a request has no answer
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum GatewayRpcError {
+    #[error("Gateway RPC is not initialized")]
+    NotInitialized,
+
+    #[error("Too many gateway requests in flight ({in_flight})")]
+    Backpressure { in_flight: usize },
+
+    #[error("Gateway request timed out after {timeout_ms} ms")]
+    TimedOut { timeout_ms: u64 },
+
+    #[error("Gateway request was cancelled")]
+    Cancelled,
+}
+
+/// RPC settings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GatewayRpcConfig {
+    /// Queued plus unpolled requests allowed before submits are refused
+    pub max_in_flight: usize,
+    pub default_timeout_ms: u64,
+    pub max_served_per_frame: usize,
+}
+
+/// Request waiting for the engine
+pub struct PendingRpc {
+    pub id: RpcId,
+    pub request: GatewayRequest,
+    pub timeout_ms: u64,
+    /// Clock time the request times out at
+    pub deadline_ms: u64,
+    /// None when the game polls for the answer
+    pub callback: Option<RpcCallback>,
+}
+
+/// Answer whose callback has yet to run
+pub struct RpcCompletion {
+    pub id: RpcId,
+    pub result: RpcResult,
+    pub callback: RpcCallback,
+}
+
+/// RPC counters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GatewayRpcStats {
+    pub submitted: u64,
+    pub answered: u64,
+    pub timed_out: u64,
+    pub cancelled: u64,
+    /// Submits refused by backpressure
+    pub rejected: u64,
+}
+
+/// RPC state
+pub struct GatewayRpcData {
+    pub config: GatewayRpcConfig,
+    /// Milliseconds advanced by the engine
+    pub clock_ms: u64,
+    pub next_id: RpcId,
+    /// Requests in submit order
+    pub pending: VecDeque<PendingRpc>,
+    /// Answers waiting to be polled
+    pub results: HashMap<RpcId, RpcResult>,
+    /// Answers waiting for their callbacks to run
+    pub completions: Vec<RpcCompletion>,
+    pub stats: GatewayRpcStats,
+}
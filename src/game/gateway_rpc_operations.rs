@@ -0,0 +1,571 @@
+//! Game Gateway RPC Operations - Pure DOP Functions
+//!
+//! Functions that operate on GatewayRpcData, plus a global instance next to
+//! the gateway's. Game code submits requests and polls or waits for
+//! callbacks; the engine serves them from `update_gateway` once per frame.
+
+use super::gateway_rpc_data::{
+    GatewayRequest, GatewayResponse, GatewayRpcConfig, GatewayRpcData, GatewayRpcError,
+    GatewayRpcStats, PendingRpc, RpcCallback, RpcCompletion, RpcId, RpcResult,
+};
+use crate::constants::gateway_rpc::{
+    DEFAULT_REQUEST_TIMEOUT_MS, MAX_IN_FLIGHT_REQUESTS, MAX_SERVED_PER_FRAME, SPAWN_SEARCH_HEIGHT,
+};
+use crate::world::core::{BlockId, VoxelPos};
+use crate::world::data_types::WorldData;
+use crate::world::world_operations;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+
+/// Global RPC storage (optional - only used if game wants request/response calls)
+static GATEWAY_RPC: Mutex<Option<GatewayRpcData>> = Mutex::new(None);
+
+/// Feet position visited by the path search
+type PathNode = [i32; 3];
+
+/// Open set entry of the path search: (estimated cost, cost so far, position)
+type OpenEntry = Reverse<(u32, u32, PathNode)>;
+
+/// Horizontal step (x, z) of the path search
+type PathStep = (i32, i32);
+
+const PATH_STEPS: [PathStep; 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+// ============================================================================
+// STATE
+// ============================================================================
+
+/// Default RPC settings
+pub fn default_gateway_rpc_config() -> GatewayRpcConfig {
+    GatewayRpcConfig {
+        max_in_flight: MAX_IN_FLIGHT_REQUESTS,
+        default_timeout_ms: DEFAULT_REQUEST_TIMEOUT_MS,
+        max_served_per_frame: MAX_SERVED_PER_FRAME,
+    }
+}
+
+/// Create empty RPC state
+pub fn create_gateway_rpc(config: GatewayRpcConfig) -> GatewayRpcData {
+    GatewayRpcData {
+        config,
+        clock_ms: 0,
+        next_id: 1,
+        pending: VecDeque::new(),
+        results: HashMap::new(),
+        completions: Vec::new(),
+        stats: GatewayRpcStats::default(),
+    }
+}
+
+/// Requests queued or answered but not yet polled
+pub fn rpc_in_flight(data: &GatewayRpcData) -> usize {
+    data.pending.len() + data.results.len()
+}
+
+/// Queue a request; `timeout_ms` of None uses the configured default.
+/// Refused with `Backpressure` when too many requests are in flight.
+pub fn submit_rpc(
+    data: &mut GatewayRpcData,
+    request: GatewayRequest,
+    timeout_ms: Option<u64>,
+    callback: Option<RpcCallback>,
+) -> Result<RpcId, GatewayRpcError> {
+    let in_flight = rpc_in_flight(data);
+    if in_flight >= data.config.max_in_flight {
+        data.stats.rejected += 1;
+        return Err(GatewayRpcError::Backpressure { in_flight });
+    }
+
+    let id = data.next_id;
+    data.next_id += 1;
+    let timeout_ms = timeout_ms.unwrap_or(data.config.default_timeout_ms);
+    data.pending.push_back(PendingRpc {
+        id,
+        request,
+        timeout_ms,
+        deadline_ms: data.clock_ms.saturating_add(timeout_ms),
+        callback,
+    });
+    data.stats.submitted += 1;
+    Ok(id)
+}
+
+/// Take the answer to a polled request, if it has one
+pub fn poll_rpc(data: &mut GatewayRpcData, id: RpcId) -> Option<RpcResult> {
+    data.results.remove(&id)
+}
+
+/// Drop a request that has not been answered yet.
+/// Its callback still runs, with `Cancelled`.
+pub fn cancel_rpc(data: &mut GatewayRpcData, id: RpcId) -> bool {
+    let Some(index) = data.pending.iter().position(|pending| pending.id == id) else {
+        return false;
+    };
+    if let Some(pending) = data.pending.remove(index) {
+        data.stats.cancelled += 1;
+        complete_rpc(data, pending, Err(GatewayRpcError::Cancelled));
+    }
+    true
+}
+
+/// Advance the clock and time out requests past their deadline
+pub fn expire_rpcs(data: &mut GatewayRpcData, delta_ms: u64) -> usize {
+    data.clock_ms = data.clock_ms.saturating_add(delta_ms);
+    let clock_ms = data.clock_ms;
+    let (expired, waiting): (VecDeque<_>, VecDeque<_>) = std::mem::take(&mut data.pending)
+        .into_iter()
+        .partition(|pending| pending.deadline_ms <= clock_ms);
+    data.pending = waiting;
+
+    let count = expired.len();
+    for pending in expired {
+        data.stats.timed_out += 1;
+        let timeout_ms = pending.timeout_ms;
+        complete_rpc(data, pending, Err(GatewayRpcError::TimedOut { timeout_ms }));
+    }
+    count
+}
+
+/// Answer up to `max_served_per_frame` requests in submit order
+pub fn serve_rpcs(data: &mut GatewayRpcData, world: &WorldData, chunk_size: u32) -> usize {
+    let mut served = 0;
+    while served < data.config.max_served_per_frame {
+        let Some(pending) = data.pending.pop_front() else {
+            break;
+        };
+        let response = answer_gateway_request(world, &pending.request, chunk_size);
+        data.stats.answered += 1;
+        complete_rpc(data, pending, Ok(response));
+        served += 1;
+    }
+    served
+}
+
+/// Take answers whose callbacks have yet to run
+pub fn take_rpc_completions(data: &mut GatewayRpcData) -> Vec<RpcCompletion> {
+    std::mem::take(&mut data.completions)
+}
+
+/// Run completion callbacks in answer order
+pub fn run_rpc_completions(completions: Vec<RpcCompletion>) {
+    for completion in completions {
+        (completion.callback)(completion.id, completion.result);
+    }
+}
+
+/// Hand an answer to its callback, or keep it for polling
+fn complete_rpc(data: &mut GatewayRpcData, pending: PendingRpc, result: RpcResult) {
+    match pending.callback {
+        Some(callback) => data.completions.push(RpcCompletion {
+            id: pending.id,
+            result,
+            callback,
+        }),
+        None => {
+            data.results.insert(pending.id, result);
+        }
+    }
+}
+
+// ============================================================================
+// REQUEST HANDLERS
+// ============================================================================
+
+/// Answer one request from world data
+pub fn answer_gateway_request(
+    world: &WorldData,
+    request: &GatewayRequest,
+    chunk_size: u32,
+) -> GatewayResponse {
+    match *request {
+        GatewayRequest::QueryBlockInfo { position } => GatewayResponse::BlockInfo {
+            position,
+            block_id: world_operations::get_block(world, position, chunk_size),
+            metadata: world_operations::get_block_metadata(world, position, chunk_size),
+            chunk_loaded: world_operations::is_chunk_loaded(
+                world,
+                position.to_chunk_pos(chunk_size),
+            ),
+        },
+        GatewayRequest::FindSpawn { near, radius } => {
+            GatewayResponse::Spawn(find_spawn_position(world, near, radius, chunk_size))
+        }
+        GatewayRequest::PathTo {
+            from,
+            to,
+            max_nodes,
+        } => GatewayResponse::Path(find_walkable_path(world, from, to, max_nodes, chunk_size)),
+    }
+}
+
+/// Feet position with a solid block below and two free blocks, searching
+/// rings of columns outward from `near` and heights closest to it first
+pub fn find_spawn_position(
+    world: &WorldData,
+    near: VoxelPos,
+    radius: u32,
+    chunk_size: u32,
+) -> Option<VoxelPos> {
+    let radius = radius as i32;
+    for ring in 0..=radius {
+        for dx in -ring..=ring {
+            for dz in -ring..=ring {
+                if dx.abs().max(dz.abs()) != ring {
+                    continue;
+                }
+                for offset in 0..=SPAWN_SEARCH_HEIGHT {
+                    for dy in [offset, -offset] {
+                        let feet = VoxelPos::new(near.x + dx, near.y + dy, near.z + dz);
+                        if is_standable(world, feet, chunk_size) {
+                            return Some(feet);
+                        }
+                        if offset == 0 {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// A* route between standing positions, walking flat or one block up or
+/// down per step. None when the goal is unreachable within `max_nodes`.
+pub fn find_walkable_path(
+    world: &WorldData,
+    from: VoxelPos,
+    to: VoxelPos,
+    max_nodes: u32,
+    chunk_size: u32,
+) -> Option<Vec<VoxelPos>> {
+    if !is_standable(world, from, chunk_size) || !is_standable(world, to, chunk_size) {
+        return None;
+    }
+
+    let goal = [to.x, to.y, to.z];
+    let start = [from.x, from.y, from.z];
+    let mut open: BinaryHeap<OpenEntry> = BinaryHeap::new();
+    let mut came_from: HashMap<PathNode, PathNode> = HashMap::new();
+    let mut best_cost: HashMap<PathNode, u32> = HashMap::new();
+    let mut closed: HashSet<PathNode> = HashSet::new();
+    open.push(Reverse((path_estimate(start, goal), 0, start)));
+    best_cost.insert(start, 0);
+
+    let mut expanded = 0;
+    while let Some(Reverse((_, cost, node))) = open.pop() {
+        if node == goal {
+            let mut path = vec![to];
+            let mut current = node;
+            while let Some(&previous) = came_from.get(&current) {
+                path.push(VoxelPos::new(previous[0], previous[1], previous[2]));
+                current = previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        if !closed.insert(node) {
+            continue;
+        }
+        expanded += 1;
+        if expanded > max_nodes {
+            break;
+        }
+
+        for (dx, dz) in PATH_STEPS {
+            for dy in [0, 1, -1] {
+                let next = [node[0] + dx, node[1] + dy, node[2] + dz];
+                if closed.contains(&next) {
+                    continue;
+                }
+                let feet = VoxelPos::new(next[0], next[1], next[2]);
+                if !is_standable(world, feet, chunk_size) {
+                    continue;
+                }
+                // Stepping up needs headroom above the current position
+                if dy == 1
+                    && !is_free(
+                        world,
+                        VoxelPos::new(node[0], node[1] + 2, node[2]),
+                        chunk_size,
+                    )
+                {
+                    continue;
+                }
+                // Stepping down needs headroom above the next position
+                if dy == -1
+                    && !is_free(
+                        world,
+                        VoxelPos::new(next[0], next[1] + 2, next[2]),
+                        chunk_size,
+                    )
+                {
+                    continue;
+                }
+                let next_cost = cost + 1;
+                if best_cost
+                    .get(&next)
+                    .is_some_and(|&known| known <= next_cost)
+                {
+                    continue;
+                }
+                best_cost.insert(next, next_cost);
+                came_from.insert(next, node);
+                open.push(Reverse((
+                    next_cost + path_estimate(next, goal),
+                    next_cost,
+                    next,
+                )));
+            }
+        }
+    }
+    None
+}
+
+/// Manhattan distance, never more than the steps left
+fn path_estimate(a: PathNode, b: PathNode) -> u32 {
+    let horizontal = a[0].abs_diff(b[0]) + a[2].abs_diff(b[2]);
+    horizontal.max(a[1].abs_diff(b[1]))
+}
+
+fn is_free(world: &WorldData, pos: VoxelPos, chunk_size: u32) -> bool {
+    world_operations::get_block(world, pos, chunk_size) == BlockId::AIR
+}
+
+/// Solid block below and two free blocks at `feet`
+fn is_standable(world: &WorldData, feet: VoxelPos, chunk_size: u32) -> bool {
+    let below = VoxelPos::new(feet.x, feet.y - 1, feet.z);
+    let head = VoxelPos::new(feet.x, feet.y + 1, feet.z);
+    !is_free(world, below, chunk_size)
+        && is_free(world, feet, chunk_size)
+        && is_free(world, head, chunk_size)
+}
+
+// ============================================================================
+// GLOBAL INSTANCE
+// ============================================================================
+
+/// Initialize gateway RPC with custom config
+pub fn init_gateway_rpc(config: GatewayRpcConfig) {
+    let mut guard = GATEWAY_RPC.lock().expect("[Gateway] Failed to lock RPC");
+    *guard = Some(create_gateway_rpc(config));
+}
+
+/// Shutdown gateway RPC; unanswered requests are dropped without callbacks
+pub fn shutdown_gateway_rpc() {
+    let mut guard = GATEWAY_RPC.lock().expect("[Gateway] Failed to lock RPC");
+    *guard = None;
+}
+
+/// Submit a request to be polled with `poll_gateway_response`
+pub fn gateway_request(
+    request: GatewayRequest,
+    timeout_ms: Option<u64>,
+) -> Result<RpcId, GatewayRpcError> {
+    let mut guard = GATEWAY_RPC.lock().expect("[Gateway] Failed to lock RPC");
+    let data = guard.as_mut().ok_or(GatewayRpcError::NotInitialized)?;
+    submit_rpc(data, request, timeout_ms, None)
+}
+
+/// Submit a request whose answer is passed to `callback`
+pub fn gateway_request_with_callback(
+    request: GatewayRequest,
+    timeout_ms: Option<u64>,
+    callback: impl FnOnce(RpcId, RpcResult) + Send + 'static,
+) -> Result<RpcId, GatewayRpcError> {
+    let mut guard = GATEWAY_RPC.lock().expect("[Gateway] Failed to lock RPC");
+    let data = guard.as_mut().ok_or(GatewayRpcError::NotInitialized)?;
+    submit_rpc(data, request, timeout_ms, Some(Box::new(callback)))
+}
+
+/// Take the answer to a request, if it has one
+pub fn poll_gateway_response(id: RpcId) -> Option<RpcResult> {
+    let mut guard = GATEWAY_RPC.lock().expect("[Gateway] Failed to lock RPC");
+    guard.as_mut().and_then(|data| poll_rpc(data, id))
+}
+
+/// Cancel an unanswered request
+pub fn cancel_gateway_request(id: RpcId) -> bool {
+    let completions = {
+        let mut guard = GATEWAY_RPC.lock().expect("[Gateway] Failed to lock RPC");
+        let Some(data) = guard.as_mut() else {
+            return false;
+        };
+        if !cancel_rpc(data, id) {
+            return false;
+        }
+        take_rpc_completions(data)
+    };
+    run_rpc_completions(completions);
+    true
+}
+
+/// Engine side, once per frame: time out stale requests and answer queued
+/// ones. Callbacks run after the lock is released, so they may submit more.
+pub fn serve_gateway_requests(world: &WorldData, chunk_size: u32, delta_ms: u64) -> usize {
+    let (served, completions) = {
+        let mut guard = GATEWAY_RPC.lock().expect("[Gateway] Failed to lock RPC");
+        let Some(data) = guard.as_mut() else {
+            return 0;
+        };
+        expire_rpcs(data, delta_ms);
+        let served = serve_rpcs(data, world, chunk_size);
+        (served, take_rpc_completions(data))
+    };
+    run_rpc_completions(completions);
+    served
+}
+
+/// Get RPC counters
+pub fn get_gateway_rpc_stats() -> GatewayRpcStats {
+    let guard = GATEWAY_RPC.lock().expect("[Gateway] Failed to lock RPC");
+    guard.as_ref().map(|data| data.stats).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::core::ChunkPos;
+    use crate::world::data_types::ChunkData;
+    use crate::world::world_operations::{get_world_chunk_size, set_block};
+    use std::sync::{Arc, Mutex};
+
+    fn floor_world() -> WorldData {
+        let mut world = WorldData::new(0, 4, 4, 4);
+        let chunk_size = get_world_chunk_size(&world);
+        world
+            .chunks
+            .push(ChunkData::new(ChunkPos::new(0, 0, 0), chunk_size));
+        for x in 0..8 {
+            for z in 0..8 {
+                let _ = set_block(
+                    &mut world,
+                    VoxelPos::new(x, 0, z),
+                    BlockId::STONE,
+                    chunk_size,
+                );
+            }
+        }
+        world
+    }
+
+    #[test]
+    fn test_backpressure_timeout_and_poll() {
+        let config = GatewayRpcConfig {
+            max_in_flight: 2,
+            default_timeout_ms: 100,
+            max_served_per_frame: 1,
+        };
+        let mut data = create_gateway_rpc(config);
+        let query = GatewayRequest::QueryBlockInfo {
+            position: VoxelPos::new(1, 0, 1),
+        };
+
+        let first = submit_rpc(&mut data, query.clone(), None, None).expect("first");
+        let second = submit_rpc(&mut data, query.clone(), Some(50), None).expect("second");
+        assert_ne!(first, second);
+        assert_eq!(
+            submit_rpc(&mut data, query.clone(), None, None),
+            Err(GatewayRpcError::Backpressure { in_flight: 2 })
+        );
+
+        // One served per frame; the second times out before its turn
+        let world = floor_world();
+        let chunk_size = get_world_chunk_size(&world);
+        assert_eq!(serve_rpcs(&mut data, &world, chunk_size), 1);
+        assert_eq!(expire_rpcs(&mut data, 60), 1);
+        assert_eq!(
+            poll_rpc(&mut data, second),
+            Some(Err(GatewayRpcError::TimedOut { timeout_ms: 50 }))
+        );
+        match poll_rpc(&mut data, first) {
+            Some(Ok(GatewayResponse::BlockInfo { block_id, .. })) => {
+                assert_eq!(block_id, BlockId::STONE)
+            }
+            other => panic!("unexpected answer {:?}", other),
+        }
+        assert_eq!(poll_rpc(&mut data, first), None);
+
+        // Polling frees room for new requests
+        assert!(submit_rpc(&mut data, query, None, None).is_ok());
+        assert_eq!(data.stats.rejected, 1);
+        assert_eq!(data.stats.timed_out, 1);
+    }
+
+    #[test]
+    fn test_callbacks_answer_spawn_and_path() {
+        let mut world = floor_world();
+        let chunk_size = get_world_chunk_size(&world);
+        // Wall across x = 3 with a gap at z = 7, and a step up at the goal
+        for z in 0..7 {
+            for y in 1..3 {
+                let _ = set_block(
+                    &mut world,
+                    VoxelPos::new(3, y, z),
+                    BlockId::STONE,
+                    chunk_size,
+                );
+            }
+        }
+        let _ = set_block(
+            &mut world,
+            VoxelPos::new(6, 1, 0),
+            BlockId::STONE,
+            chunk_size,
+        );
+
+        let answers: Arc<Mutex<Vec<(RpcId, RpcResult)>>> = Arc::new(Mutex::new(Vec::new()));
+        let mut data = create_gateway_rpc(default_gateway_rpc_config());
+        let mut submit = |request| {
+            let answers = Arc::clone(&answers);
+            submit_rpc(
+                &mut data,
+                request,
+                None,
+                Some(Box::new(move |id, result| {
+                    answers.lock().expect("answers").push((id, result));
+                })),
+            )
+            .expect("submit")
+        };
+        let spawn = submit(GatewayRequest::FindSpawn {
+            near: VoxelPos::new(3, 5, 3),
+            radius: 2,
+        });
+        let path = submit(GatewayRequest::PathTo {
+            from: VoxelPos::new(0, 1, 0),
+            to: VoxelPos::new(6, 2, 0),
+            max_nodes: 256,
+        });
+        let cancelled = submit(GatewayRequest::FindSpawn {
+            near: VoxelPos::new(0, 1, 0),
+            radius: 0,
+        });
+        assert!(cancel_rpc(&mut data, cancelled));
+
+        assert_eq!(serve_rpcs(&mut data, &world, chunk_size), 2);
+        assert!(data.results.is_empty());
+        run_rpc_completions(take_rpc_completions(&mut data));
+
+        let answers = answers.lock().expect("answers");
+        assert_eq!(answers.len(), 3);
+        assert_eq!(answers[0], (cancelled, Err(GatewayRpcError::Cancelled)));
+        assert_eq!(
+            answers[1],
+            (
+                spawn,
+                Ok(GatewayResponse::Spawn(Some(VoxelPos::new(3, 3, 3))))
+            )
+        );
+        match &answers[2] {
+            (id, Ok(GatewayResponse::Path(Some(route)))) => {
+                assert_eq!(*id, path);
+                assert_eq!(route.first(), Some(&VoxelPos::new(0, 1, 0)));
+                assert_eq!(route.last(), Some(&VoxelPos::new(6, 2, 0)));
+                assert!(route.iter().any(|step| step.x == 3 && step.z == 7));
+            }
+            other => panic!("unexpected answer {:?}", other),
+        }
+    }
+}
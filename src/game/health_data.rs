@@ -0,0 +1,119 @@
+//! Entity Health Data - Health points and environmental damage
+//!
+//! Current and maximum health live in the attribute store as the `health`
+//! and `max_health` attributes, so buffs can raise maximum health and UI
+//! reads health like any other stat. This module adds what the attribute
+//! store does not know: damage immunity after each hit, fall tracking,
+//! breath under fluid, and which environmental damage sources (falls, lava
+//! contact, drowning, the void) a game wants. Damage to entities linked to
+//! a player is handed to the lifecycle system, which owns player death and
+//! respawn.
+//!
+//! Pure DOP: No methods, just data structures.
+
+use super::attribute_data::AttributeId;
+use crate::instance::InstanceId;
+use crate::world::core::BlockId;
+use std::collections::HashMap;
+
+/// What dealt damage
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DamageSource {
+    /// Landing after a fall longer than the safe distance
+    Fall,
+    /// Touching a hazard block (lava by default)
+    Contact(BlockId),
+    /// Out of air with the head in fluid
+    Drowning,
+    /// Below the bottom of the world
+    Void,
+    /// Applied by the game (attacks, spells, commands)
+    Game,
+}
+
+/// Environmental damage sources a game wants
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DamageSourceToggles {
+    pub fall: bool,
+    pub contact: bool,
+    pub drowning: bool,
+    pub void: bool,
+}
+
+/// Block that hurts entities touching it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactHazard {
+    pub block: BlockId,
+    /// Damage of each hit
+    pub damage: f32,
+}
+
+/// Health tuning (voxels, seconds, health points)
+#[derive(Clone, Debug, PartialEq)]
+pub struct HealthConfig {
+    pub sources: DamageSourceToggles,
+    /// Damage immunity after each hit
+    pub invulnerability_secs: f32,
+    pub safe_fall_distance: f32,
+    pub fall_damage_per_voxel: f32,
+    pub contact_hazards: Vec<ContactHazard>,
+    /// Breath held with the head in fluid
+    pub max_air_secs: f32,
+    pub drowning_damage: f32,
+    pub drowning_interval_secs: f32,
+    pub void_y: f32,
+    pub void_damage: f32,
+}
+
+/// Damage bookkeeping of one entity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EntityHealthState {
+    /// Player this entity is, whose death the lifecycle system handles
+    pub player_id: Option<u32>,
+    pub dead: bool,
+    /// Seconds of damage immunity left
+    pub invulnerable_secs: f32,
+    /// Highest feet height since leaving the ground (None = grounded or
+    /// swimming)
+    pub fall_peak_y: Option<f32>,
+    /// Breath left with the head in fluid
+    pub air_secs: f32,
+    /// Time out of air since the last drowning hit
+    pub drowning_secs: f32,
+}
+
+/// Where an entity is this tick, as its physics left it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HealthSample {
+    pub entity: InstanceId,
+    /// Bottom center of the entity's box
+    pub feet: [f32; 3],
+    /// Box width, height and depth
+    pub size: [f32; 3],
+    /// Eye height above the feet; the eyes decide drowning
+    pub eye_height: f32,
+    pub on_ground: bool,
+}
+
+/// Damage taken by one entity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DamageDealt {
+    pub entity: InstanceId,
+    pub player_id: Option<u32>,
+    pub source: DamageSource,
+    pub amount: f32,
+    /// Health left afterwards
+    pub health: f32,
+    pub killed: bool,
+}
+
+/// Health state of every tracked entity
+#[derive(Clone, Debug)]
+pub struct HealthData {
+    pub config: HealthConfig,
+    /// `health` attribute in the attribute store
+    pub health: AttributeId,
+    /// `max_health` attribute in the attribute store
+    pub max_health: AttributeId,
+    pub entities: HashMap<InstanceId, EntityHealthState>,
+}
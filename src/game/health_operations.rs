@@ -0,0 +1,564 @@
+//! Entity Health Operations - Pure DOP Functions
+//!
+//! `update_entity_health` samples the voxels around each entity once per
+//! tick and deals the environmental damage that applies: void, hazard
+//! contact, fall landing and drowning, in that order. Any hit starts the
+//! entity's damage immunity, so at most one lands per immunity window.
+//! `dispatch_health_damage` then reports the damage: players through the
+//! lifecycle system (damage, death, drops, respawn), other entities as
+//! `EntityDamaged`/`EntityDied` events. The gateway does the same in
+//! `dispatch_gateway_health_damage`.
+
+use super::attribute_data::AttributeStoreData;
+use super::attribute_operations::{
+    add_attribute_entity, base_value, effective_value, register_attribute, remove_attribute_entity,
+    set_base_value,
+};
+use super::gateway_data::GameEvent;
+use super::gateway_operations::queue_events;
+use super::health_data::{
+    ContactHazard, DamageDealt, DamageSource, DamageSourceToggles, EntityHealthState, HealthConfig,
+    HealthData, HealthSample,
+};
+use super::lifecycle_data::{DamageEvent, PlayerLifeState, PlayerLifecycleData};
+use super::lifecycle_operations::apply_damage;
+use crate::constants::health::*;
+use crate::instance::InstanceId;
+use crate::physics::buoyancy_data::FluidMaterialTable;
+use crate::physics::buoyancy_operations::fluid_material;
+use crate::world::core::{BlockId, VoxelPos};
+use std::collections::HashMap;
+
+// ============================================================================
+// INITIALIZATION
+// ============================================================================
+
+/// Every source on, lava as the only hazard
+pub fn default_health_config() -> HealthConfig {
+    HealthConfig {
+        sources: DamageSourceToggles {
+            fall: true,
+            contact: true,
+            drowning: true,
+            void: true,
+        },
+        invulnerability_secs: DEFAULT_INVULNERABILITY_SECS,
+        safe_fall_distance: SAFE_FALL_DISTANCE,
+        fall_damage_per_voxel: FALL_DAMAGE_PER_VOXEL,
+        contact_hazards: vec![ContactHazard {
+            block: BlockId::LAVA,
+            damage: LAVA_CONTACT_DAMAGE,
+        }],
+        max_air_secs: MAX_AIR_SECS,
+        drowning_damage: DROWNING_DAMAGE,
+        drowning_interval_secs: DROWNING_INTERVAL_SECS,
+        void_y: VOID_Y,
+        void_damage: VOID_DAMAGE,
+    }
+}
+
+/// Register the `health` and `max_health` attributes and track no entities
+pub fn create_entity_health(store: &mut AttributeStoreData, config: HealthConfig) -> HealthData {
+    HealthData {
+        config,
+        health: register_attribute(store, HEALTH_ATTRIBUTE, DEFAULT_MAX_HEALTH, 0.0, f32::MAX),
+        max_health: register_attribute(
+            store,
+            MAX_HEALTH_ATTRIBUTE,
+            DEFAULT_MAX_HEALTH,
+            1.0,
+            f32::MAX,
+        ),
+        entities: HashMap::new(),
+    }
+}
+
+/// Start tracking an entity at full health; `player_id` links it to the
+/// lifecycle system
+pub fn add_health_entity(
+    data: &mut HealthData,
+    store: &mut AttributeStoreData,
+    entity: InstanceId,
+    player_id: Option<u32>,
+) {
+    add_attribute_entity(store, entity);
+    let max = entity_max_health(data, store, entity).unwrap_or(DEFAULT_MAX_HEALTH);
+    set_base_value(store, entity, data.health, max);
+    data.entities.insert(
+        entity,
+        EntityHealthState {
+            player_id,
+            dead: false,
+            invulnerable_secs: 0.0,
+            fall_peak_y: None,
+            air_secs: data.config.max_air_secs,
+            drowning_secs: 0.0,
+        },
+    );
+}
+
+/// Stop tracking an entity and drop its attributes
+pub fn remove_health_entity(
+    data: &mut HealthData,
+    store: &mut AttributeStoreData,
+    entity: InstanceId,
+) -> bool {
+    remove_attribute_entity(store, entity);
+    data.entities.remove(&entity).is_some()
+}
+
+// ============================================================================
+// QUERIES
+// ============================================================================
+
+/// Current health of an entity
+pub fn entity_health(
+    data: &HealthData,
+    store: &AttributeStoreData,
+    entity: InstanceId,
+) -> Option<f32> {
+    base_value(store, entity, data.health)
+}
+
+/// Maximum health of an entity, modifiers included
+pub fn entity_max_health(
+    data: &HealthData,
+    store: &AttributeStoreData,
+    entity: InstanceId,
+) -> Option<f32> {
+    effective_value(store, entity, data.max_health)
+}
+
+/// Whether the game wants damage from `source`; game damage is always on
+pub fn damage_source_enabled(config: &HealthConfig, source: DamageSource) -> bool {
+    match source {
+        DamageSource::Fall => config.sources.fall,
+        DamageSource::Contact(_) => config.sources.contact,
+        DamageSource::Drowning => config.sources.drowning,
+        DamageSource::Void => config.sources.void,
+        DamageSource::Game => true,
+    }
+}
+
+/// Cause string used in lifecycle and gateway events
+pub fn damage_source_name(source: DamageSource) -> &'static str {
+    match source {
+        DamageSource::Fall => "fall",
+        DamageSource::Contact(BlockId::LAVA) => "lava",
+        DamageSource::Contact(_) => "contact",
+        DamageSource::Drowning => "drowning",
+        DamageSource::Void => "void",
+        DamageSource::Game => "game",
+    }
+}
+
+// ============================================================================
+// DAMAGE AND HEALING
+// ============================================================================
+
+/// Deal damage to an entity. Dead or immune entities and disabled sources
+/// are unaffected; a hit starts the entity's damage immunity.
+pub fn apply_entity_damage(
+    data: &mut HealthData,
+    store: &mut AttributeStoreData,
+    entity: InstanceId,
+    source: DamageSource,
+    amount: f32,
+) -> Option<DamageDealt> {
+    if amount <= 0.0 || !damage_source_enabled(&data.config, source) {
+        return None;
+    }
+    let health = entity_health(data, store, entity)?;
+    let state = data.entities.get_mut(&entity)?;
+    if state.dead || state.invulnerable_secs > 0.0 {
+        return None;
+    }
+
+    let remaining = (health - amount).max(0.0);
+    set_base_value(store, entity, data.health, remaining);
+    state.invulnerable_secs = data.config.invulnerability_secs;
+    state.dead = remaining <= 0.0;
+    Some(DamageDealt {
+        entity,
+        player_id: state.player_id,
+        source,
+        amount,
+        health: remaining,
+        killed: state.dead,
+    })
+}
+
+/// Restore health up to the maximum; the dead stay dead
+pub fn heal_entity(
+    data: &HealthData,
+    store: &mut AttributeStoreData,
+    entity: InstanceId,
+    amount: f32,
+) -> Option<f32> {
+    if data.entities.get(&entity)?.dead {
+        return None;
+    }
+    let max = entity_max_health(data, store, entity)?;
+    let health = (entity_health(data, store, entity)? + amount.max(0.0)).min(max);
+    set_base_value(store, entity, data.health, health);
+    Some(health)
+}
+
+/// Bring an entity back at full health with fresh breath
+pub fn revive_entity(data: &mut HealthData, store: &mut AttributeStoreData, entity: InstanceId) {
+    let Some(max) = entity_max_health(data, store, entity) else {
+        return;
+    };
+    let Some(state) = data.entities.get_mut(&entity) else {
+        return;
+    };
+    set_base_value(store, entity, data.health, max);
+    state.dead = false;
+    state.invulnerable_secs = 0.0;
+    state.fall_peak_y = None;
+    state.air_secs = data.config.max_air_secs;
+    state.drowning_secs = 0.0;
+}
+
+// ============================================================================
+// ENVIRONMENTAL DAMAGE
+// ============================================================================
+
+fn voxel_at(point: [f32; 3]) -> VoxelPos {
+    VoxelPos {
+        x: point[0].floor() as i32,
+        y: point[1].floor() as i32,
+        z: point[2].floor() as i32,
+    }
+}
+
+/// Strongest hazard among the voxels the entity's box overlaps
+fn touched_hazard(
+    hazards: &[ContactHazard],
+    sample: &HealthSample,
+    block_at: &mut impl FnMut(VoxelPos) -> BlockId,
+) -> Option<ContactHazard> {
+    if hazards.is_empty() {
+        return None;
+    }
+    let [x, y, z] = sample.feet;
+    let [width, height, depth] = sample.size;
+    let min = voxel_at([x - width / 2.0, y, z - depth / 2.0]);
+    let max = voxel_at([x + width / 2.0, y + height, z + depth / 2.0]);
+
+    let mut strongest: Option<ContactHazard> = None;
+    for vx in min.x..=max.x {
+        for vy in min.y..=max.y {
+            for vz in min.z..=max.z {
+                let block = block_at(VoxelPos::new(vx, vy, vz));
+                let hazard = hazards.iter().find(|hazard| hazard.block == block);
+                if let Some(hazard) = hazard {
+                    if strongest.is_none_or(|current| hazard.damage > current.damage) {
+                        strongest = Some(*hazard);
+                    }
+                }
+            }
+        }
+    }
+    strongest
+}
+
+/// Advance immunity, falls and breath for this tick's samples and deal the
+/// environmental damage they call for. `block_at` reads the world;
+/// `fluids` decides what counts as fluid for drowning and for breaking a
+/// fall.
+pub fn update_entity_health(
+    data: &mut HealthData,
+    store: &mut AttributeStoreData,
+    samples: &[HealthSample],
+    delta_time: f32,
+    fluids: &FluidMaterialTable,
+    mut block_at: impl FnMut(VoxelPos) -> BlockId,
+) -> Vec<DamageDealt> {
+    let mut dealt = Vec::new();
+    let config = data.config.clone();
+
+    for sample in samples {
+        let Some(state) = data.entities.get_mut(&sample.entity) else {
+            continue;
+        };
+        state.invulnerable_secs = (state.invulnerable_secs - delta_time).max(0.0);
+        if state.dead {
+            continue;
+        }
+        let [x, y, z] = sample.feet;
+        let mut hits = Vec::new();
+
+        if y < config.void_y {
+            hits.push((DamageSource::Void, config.void_damage));
+        }
+
+        if let Some(hazard) = touched_hazard(&config.contact_hazards, sample, &mut block_at) {
+            hits.push((DamageSource::Contact(hazard.block), hazard.damage));
+        }
+
+        // Falls end on landing; fluid breaks them
+        let in_fluid = fluid_material(fluids, block_at(voxel_at(sample.feet))).is_some();
+        if sample.on_ground {
+            if let Some(peak) = state.fall_peak_y.take() {
+                let distance = peak - y - config.safe_fall_distance;
+                if distance > 0.0 {
+                    hits.push((DamageSource::Fall, distance * config.fall_damage_per_voxel));
+                }
+            }
+        } else if in_fluid {
+            state.fall_peak_y = None;
+        } else {
+            state.fall_peak_y = Some(state.fall_peak_y.map_or(y, |peak| peak.max(y)));
+        }
+
+        let eyes = voxel_at([x, y + sample.eye_height, z]);
+        if fluid_material(fluids, block_at(eyes)).is_some() {
+            state.air_secs = (state.air_secs - delta_time).max(0.0);
+            if state.air_secs <= 0.0 {
+                state.drowning_secs += delta_time;
+                if state.drowning_secs >= config.drowning_interval_secs {
+                    state.drowning_secs -= config.drowning_interval_secs;
+                    hits.push((DamageSource::Drowning, config.drowning_damage));
+                }
+            }
+        } else {
+            state.air_secs = config.max_air_secs;
+            state.drowning_secs = 0.0;
+        }
+
+        for (source, amount) in hits {
+            if let Some(hit) = apply_entity_damage(data, store, sample.entity, source, amount) {
+                dealt.push(hit);
+                break;
+            }
+        }
+    }
+    dealt
+}
+
+// ============================================================================
+// REPORTING
+// ============================================================================
+
+/// Hand player damage to the lifecycle system, which emits (and queues) the
+/// player events and decides death
+pub fn forward_player_damage(
+    lifecycle: &mut PlayerLifecycleData,
+    dealt: &[DamageDealt],
+) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+    for hit in dealt {
+        if let Some(player_id) = hit.player_id {
+            events.extend(apply_damage(
+                lifecycle,
+                DamageEvent {
+                    player_id,
+                    amount: hit.amount,
+                    cause: damage_source_name(hit.source).to_string(),
+                    source_entity: None,
+                },
+            ));
+        }
+    }
+    events
+}
+
+/// `EntityDamaged` and `EntityDied` events for damage to entities that are
+/// not players
+pub fn entity_damage_events(dealt: &[DamageDealt]) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+    for hit in dealt.iter().filter(|hit| hit.player_id.is_none()) {
+        events.push(GameEvent::EntityDamaged {
+            entity: hit.entity,
+            amount: hit.amount,
+            health: hit.health,
+            source: hit.source,
+        });
+        if hit.killed {
+            events.push(GameEvent::EntityDied {
+                entity: hit.entity,
+                source: hit.source,
+            });
+        }
+    }
+    events
+}
+
+/// Report dealt damage: players through the lifecycle system, whose health
+/// the attribute store follows afterwards, other entities as events. Every
+/// event is also queued on the gateway.
+pub fn dispatch_health_damage(
+    data: &mut HealthData,
+    store: &mut AttributeStoreData,
+    lifecycle: &mut PlayerLifecycleData,
+    dealt: &[DamageDealt],
+) -> Vec<GameEvent> {
+    let mut events = forward_player_damage(lifecycle, dealt);
+    sync_player_health(data, store, lifecycle);
+
+    let entity_events = entity_damage_events(dealt);
+    queue_events(entity_events.clone());
+    events.extend(entity_events);
+    events
+}
+
+/// Copy player health and life state from the lifecycle system, reviving
+/// entities of respawned players; call after lifecycle updates
+pub fn sync_player_health(
+    data: &mut HealthData,
+    store: &mut AttributeStoreData,
+    lifecycle: &PlayerLifecycleData,
+) {
+    let linked: Vec<_> = data
+        .entities
+        .iter()
+        .filter_map(|(entity, state)| state.player_id.map(|player| (*entity, player)))
+        .collect();
+    for (entity, player_id) in linked {
+        let Some(player) = lifecycle.players.get(&player_id) else {
+            continue;
+        };
+        let alive = player.state == PlayerLifeState::Alive;
+        let was_dead = data.entities.get(&entity).is_some_and(|state| state.dead);
+        if alive && was_dead {
+            revive_entity(data, store, entity);
+        }
+        set_base_value(store, entity, data.health, player.health);
+        if let Some(state) = data.entities.get_mut(&entity) {
+            state.dead = !alive;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::lifecycle_data::LifecycleConfig;
+    use crate::game::lifecycle_operations::{add_lifecycle_player, create_player_lifecycle};
+    use crate::physics::buoyancy_data::FluidMaterial;
+    use crate::physics::buoyancy_operations::set_fluid_material;
+
+    fn entity(low: u64) -> InstanceId {
+        InstanceId { high: 0, low }
+    }
+
+    fn sample(entity: InstanceId, y: f32, on_ground: bool) -> HealthSample {
+        HealthSample {
+            entity,
+            feet: [0.5, y, 0.5],
+            size: [0.6, 1.8, 0.6],
+            eye_height: 1.6,
+            on_ground,
+        }
+    }
+
+    fn water_below(y: i32) -> impl FnMut(VoxelPos) -> BlockId {
+        move |pos| {
+            if pos.y < y {
+                BlockId::WATER
+            } else {
+                BlockId::AIR
+            }
+        }
+    }
+
+    #[test]
+    fn test_falls_drowning_and_disabled_sources() {
+        let mut store = AttributeStoreData::default();
+        let mut data = create_entity_health(&mut store, default_health_config());
+        let mut fluids = FluidMaterialTable::default();
+        set_fluid_material(
+            &mut fluids,
+            BlockId::WATER,
+            Some(FluidMaterial {
+                density: 1000.0,
+                drag: 2.0,
+            }),
+        );
+        let mob = entity(1);
+        add_health_entity(&mut data, &mut store, mob, None);
+
+        // A 10 voxel fall lands 7 voxels past the safe distance
+        for y in [80.0, 75.0, 70.0] {
+            let hits = update_entity_health(
+                &mut data,
+                &mut store,
+                &[sample(mob, y, y == 70.0)],
+                0.1,
+                &fluids,
+                water_below(0),
+            );
+            assert_eq!(hits.len(), usize::from(y == 70.0));
+        }
+        assert_eq!(entity_health(&data, &store, mob), Some(13.0));
+
+        // Submerged: breath runs out, then a hit every drowning interval
+        data.config.invulnerability_secs = 0.0;
+        let mut drowned = 0;
+        for _ in 0..16 {
+            let hits = update_entity_health(
+                &mut data,
+                &mut store,
+                &[sample(mob, 10.0, false)],
+                1.0,
+                &fluids,
+                water_below(64),
+            );
+            drowned += hits.len();
+        }
+        assert_eq!(drowned, 2);
+        assert_eq!(entity_health(&data, &store, mob), Some(9.0));
+
+        data.config.sources.drowning = false;
+        let hits = update_entity_health(
+            &mut data,
+            &mut store,
+            &[sample(mob, 10.0, false)],
+            1.0,
+            &fluids,
+            water_below(64),
+        );
+        assert!(hits.is_empty());
+    }
+
+    #[test]
+    fn test_lava_kills_players_through_lifecycle() {
+        let mut store = AttributeStoreData::default();
+        let mut data = create_entity_health(&mut store, default_health_config());
+        let mut lifecycle = create_player_lifecycle([0.0; 3], LifecycleConfig::default());
+        let fluids = FluidMaterialTable::default();
+        let player = entity(7);
+        add_health_entity(&mut data, &mut store, player, Some(7));
+        add_lifecycle_player(&mut lifecycle, 7, [0.5, 64.0, 0.5]);
+
+        let mut events = Vec::new();
+        let mut hits = 0;
+        for _ in 0..20 {
+            let dealt = update_entity_health(
+                &mut data,
+                &mut store,
+                &[sample(player, 64.0, true)],
+                0.25,
+                &fluids,
+                |_| BlockId::LAVA,
+            );
+            hits += dealt.len();
+            events.extend(dispatch_health_damage(
+                &mut data,
+                &mut store,
+                &mut lifecycle,
+                &dealt,
+            ));
+        }
+
+        // Immunity spaces 4 damage hits half a second apart: dead on the fifth
+        assert_eq!(hits, 5);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            GameEvent::PlayerDied { cause, .. } if cause == "lava"
+        )));
+        assert_eq!(entity_health(&data, &store, player), Some(0.0));
+        assert!(data.entities[&player].dead);
+    }
+}
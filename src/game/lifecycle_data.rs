@@ -0,0 +1,120 @@
+//! Player Lifecycle Data - Death and respawn state
+//!
+//! Health, death, spawn anchors and post-respawn invulnerability for each
+//! player. The engine does not own an inventory, so what happens to it on
+//! death is decided by the game through `DropPolicy`.
+//!
+//! Pure DOP: No methods, just data structures.
+
+use crate::constants::player_lifecycle::*;
+use crate::world::core::{BlockId, VoxelPos};
+use std::collections::HashMap;
+
+/// Identifier of a registered spawn anchor
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpawnAnchorId(pub u32);
+
+/// Where a player is in the death/respawn cycle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlayerLifeState {
+    Alive,
+    /// Dead and waiting for a respawn request
+    Dead {
+        time_dead: f32,
+    },
+}
+
+/// Damage reported by the game
+#[derive(Clone, Debug)]
+pub struct DamageEvent {
+    pub player_id: u32,
+    pub amount: f32,
+    /// Game-defined cause (e.g. "fall", "lava", "zombie")
+    pub cause: String,
+    /// Entity responsible, if any
+    pub source_entity: Option<u32>,
+}
+
+/// Block that players can respawn at (bed-like block entity)
+#[derive(Clone, Debug)]
+pub struct SpawnAnchor {
+    pub id: SpawnAnchorId,
+    pub owner: u32,
+    pub position: VoxelPos,
+    /// Block the anchor is bound to; the anchor is invalid once it is gone
+    pub block_id: BlockId,
+}
+
+/// What happens to a dead player's inventory
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DropDecision {
+    Keep,
+    DropAll,
+    /// Drop this fraction of stacks (0.0 - 1.0)
+    DropFraction(f32),
+}
+
+/// Context passed to a custom drop hook
+#[derive(Clone, Debug)]
+pub struct DeathRecord {
+    pub player_id: u32,
+    pub position: [f32; 3],
+    pub cause: String,
+    pub source_entity: Option<u32>,
+    pub death_count: u32,
+}
+
+/// Game-provided inventory drop decision
+pub type InventoryDropHook = Box<dyn Fn(&DeathRecord) -> DropDecision + Send + Sync>;
+
+/// Inventory policy applied on death
+pub enum DropPolicy {
+    KeepAll,
+    DropAll,
+    Custom(InventoryDropHook),
+}
+
+/// Lifecycle state of one player
+#[derive(Clone, Debug)]
+pub struct PlayerLifecycle {
+    pub player_id: u32,
+    pub state: PlayerLifeState,
+    pub health: f32,
+    pub max_health: f32,
+    pub position: [f32; 3],
+    pub spawn_anchor: Option<SpawnAnchorId>,
+    /// Seconds of damage immunity left
+    pub invulnerable_remaining: f32,
+    pub death_count: u32,
+}
+
+/// Lifecycle tuning
+#[derive(Clone, Debug)]
+pub struct LifecycleConfig {
+    pub max_health: f32,
+    pub respawn_invulnerability_secs: f32,
+    /// Respawn automatically after this many seconds (None = wait for the game)
+    pub auto_respawn_delay: Option<f32>,
+    pub anchor_spawn_offset: [f32; 3],
+}
+
+/// All player lifecycle state
+pub struct PlayerLifecycleData {
+    pub players: HashMap<u32, PlayerLifecycle>,
+    pub anchors: HashMap<SpawnAnchorId, SpawnAnchor>,
+    pub world_spawn: [f32; 3],
+    pub drop_policy: DropPolicy,
+    pub config: LifecycleConfig,
+    pub next_anchor_id: u32,
+}
+
+impl Default for LifecycleConfig {
+    fn default() -> Self {
+        Self {
+            max_health: DEFAULT_MAX_HEALTH,
+            respawn_invulnerability_secs: DEFAULT_RESPAWN_INVULNERABILITY_SECS,
+            auto_respawn_delay: None,
+            anchor_spawn_offset: ANCHOR_SPAWN_OFFSET,
+        }
+    }
+}
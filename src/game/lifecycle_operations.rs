@@ -0,0 +1,463 @@
+//! Player Lifecycle Operations - Pure DOP Functions
+//!
+//! Damage -> death -> inventory drop -> respawn -> invulnerability. Every
+//! stage produces a `GameEvent`; the events are returned to the caller and
+//! also forwarded to the gateway queue (a no-op if the gateway is not
+//! initialized), so games can drive their own death screens from either.
+
+use super::gateway_data::GameEvent;
+use super::gateway_operations::queue_events;
+use super::lifecycle_data::{
+    DamageEvent, DeathRecord, DropDecision, DropPolicy, LifecycleConfig, PlayerLifeState,
+    PlayerLifecycle, PlayerLifecycleData, SpawnAnchor, SpawnAnchorId,
+};
+use crate::world::core::{BlockId, VoxelPos};
+use crate::world::data_types::WorldData;
+use std::collections::HashMap;
+
+// ============================================================================
+// INITIALIZATION
+// ============================================================================
+
+/// Create lifecycle state with the given world spawn
+pub fn create_player_lifecycle(
+    world_spawn: [f32; 3],
+    config: LifecycleConfig,
+) -> PlayerLifecycleData {
+    PlayerLifecycleData {
+        players: HashMap::new(),
+        anchors: HashMap::new(),
+        world_spawn,
+        drop_policy: DropPolicy::DropAll,
+        config,
+        next_anchor_id: 0,
+    }
+}
+
+/// Start tracking a player, alive at the given position
+pub fn add_lifecycle_player(data: &mut PlayerLifecycleData, player_id: u32, position: [f32; 3]) {
+    data.players.insert(
+        player_id,
+        PlayerLifecycle {
+            player_id,
+            state: PlayerLifeState::Alive,
+            health: data.config.max_health,
+            max_health: data.config.max_health,
+            position,
+            spawn_anchor: None,
+            invulnerable_remaining: 0.0,
+            death_count: 0,
+        },
+    );
+}
+
+/// Stop tracking a player and drop their anchors
+pub fn remove_lifecycle_player(data: &mut PlayerLifecycleData, player_id: u32) {
+    data.players.remove(&player_id);
+    data.anchors.retain(|_, anchor| anchor.owner != player_id);
+}
+
+/// Set the inventory drop policy
+pub fn set_drop_policy(data: &mut PlayerLifecycleData, policy: DropPolicy) {
+    data.drop_policy = policy;
+}
+
+/// Move the world spawn
+pub fn set_world_spawn(data: &mut PlayerLifecycleData, position: [f32; 3]) {
+    data.world_spawn = position;
+}
+
+/// Keep the tracked position in sync with movement
+pub fn update_player_position(data: &mut PlayerLifecycleData, player_id: u32, position: [f32; 3]) {
+    if let Some(player) = data.players.get_mut(&player_id) {
+        player.position = position;
+    }
+}
+
+// ============================================================================
+// QUERIES
+// ============================================================================
+
+pub fn is_player_alive(data: &PlayerLifecycleData, player_id: u32) -> bool {
+    data.players
+        .get(&player_id)
+        .map(|player| player.state == PlayerLifeState::Alive)
+        .unwrap_or(false)
+}
+
+pub fn is_player_invulnerable(data: &PlayerLifecycleData, player_id: u32) -> bool {
+    data.players
+        .get(&player_id)
+        .map(|player| player.invulnerable_remaining > 0.0)
+        .unwrap_or(false)
+}
+
+/// Where the player would respawn if the anchor is still intact
+pub fn player_spawn_point(data: &PlayerLifecycleData, player_id: u32) -> [f32; 3] {
+    data.players
+        .get(&player_id)
+        .and_then(|player| player.spawn_anchor)
+        .and_then(|id| data.anchors.get(&id))
+        .map(|anchor| anchor_spawn_position(anchor, data.config.anchor_spawn_offset))
+        .unwrap_or(data.world_spawn)
+}
+
+fn anchor_spawn_position(anchor: &SpawnAnchor, offset: [f32; 3]) -> [f32; 3] {
+    [
+        anchor.position.x as f32 + offset[0],
+        anchor.position.y as f32 + offset[1],
+        anchor.position.z as f32 + offset[2],
+    ]
+}
+
+// ============================================================================
+// SPAWN ANCHORS
+// ============================================================================
+
+/// Register a block as a player's spawn anchor, replacing any previous one
+pub fn register_spawn_anchor(
+    data: &mut PlayerLifecycleData,
+    owner: u32,
+    position: VoxelPos,
+    block_id: BlockId,
+) -> SpawnAnchorId {
+    let id = SpawnAnchorId(data.next_anchor_id);
+    data.next_anchor_id += 1;
+
+    data.anchors.retain(|_, anchor| anchor.owner != owner);
+    data.anchors.insert(
+        id,
+        SpawnAnchor {
+            id,
+            owner,
+            position,
+            block_id,
+        },
+    );
+    if let Some(player) = data.players.get_mut(&owner) {
+        player.spawn_anchor = Some(id);
+    }
+    id
+}
+
+/// Remove an anchor; its owner falls back to the world spawn
+pub fn remove_spawn_anchor(data: &mut PlayerLifecycleData, id: SpawnAnchorId) {
+    if let Some(anchor) = data.anchors.remove(&id) {
+        if let Some(player) = data.players.get_mut(&anchor.owner) {
+            if player.spawn_anchor == Some(id) {
+                player.spawn_anchor = None;
+            }
+        }
+    }
+}
+
+/// Remove anchors bound to a block position (call when the block is broken)
+pub fn remove_spawn_anchors_at(data: &mut PlayerLifecycleData, position: VoxelPos) {
+    let ids: Vec<SpawnAnchorId> = data
+        .anchors
+        .values()
+        .filter(|anchor| anchor.position == position)
+        .map(|anchor| anchor.id)
+        .collect();
+    for id in ids {
+        remove_spawn_anchor(data, id);
+    }
+}
+
+/// Default anchor check: the anchor block is still in place in the world
+pub fn anchor_intact_in_world(world: &WorldData, anchor: &SpawnAnchor, chunk_size: u32) -> bool {
+    super::get_block_dop(world, anchor.position, chunk_size) == anchor.block_id
+}
+
+// ============================================================================
+// DAMAGE AND DEATH
+// ============================================================================
+
+/// Apply game-defined damage. Dead or invulnerable players are unaffected.
+pub fn apply_damage(data: &mut PlayerLifecycleData, damage: DamageEvent) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+    let Some(player) = data.players.get_mut(&damage.player_id) else {
+        return events;
+    };
+    if player.state != PlayerLifeState::Alive
+        || player.invulnerable_remaining > 0.0
+        || damage.amount <= 0.0
+    {
+        return events;
+    }
+
+    player.health = (player.health - damage.amount).max(0.0);
+    events.push(GameEvent::PlayerDamaged {
+        player_id: damage.player_id,
+        amount: damage.amount,
+        health: player.health,
+        cause: damage.cause.clone(),
+    });
+
+    if player.health <= 0.0 {
+        events.extend(kill_player_internal(
+            data,
+            damage.player_id,
+            damage.cause,
+            damage.source_entity,
+        ));
+    }
+
+    queue_events(events.clone());
+    events
+}
+
+/// Kill a player outright (e.g. void, /kill)
+pub fn kill_player(data: &mut PlayerLifecycleData, player_id: u32, cause: &str) -> Vec<GameEvent> {
+    let events = kill_player_internal(data, player_id, cause.to_string(), None);
+    queue_events(events.clone());
+    events
+}
+
+fn kill_player_internal(
+    data: &mut PlayerLifecycleData,
+    player_id: u32,
+    cause: String,
+    source_entity: Option<u32>,
+) -> Vec<GameEvent> {
+    let Some(player) = data.players.get_mut(&player_id) else {
+        return Vec::new();
+    };
+    if player.state != PlayerLifeState::Alive {
+        return Vec::new();
+    }
+
+    player.state = PlayerLifeState::Dead { time_dead: 0.0 };
+    player.health = 0.0;
+    player.invulnerable_remaining = 0.0;
+    player.death_count += 1;
+
+    let record = DeathRecord {
+        player_id,
+        position: player.position,
+        cause: cause.clone(),
+        source_entity,
+        death_count: player.death_count,
+    };
+    let decision = match &data.drop_policy {
+        DropPolicy::KeepAll => DropDecision::Keep,
+        DropPolicy::DropAll => DropDecision::DropAll,
+        DropPolicy::Custom(hook) => hook(&record),
+    };
+
+    log::info!(
+        "[Lifecycle] Player {} died ({}), inventory: {:?}",
+        player_id,
+        cause,
+        decision
+    );
+
+    vec![
+        GameEvent::PlayerDied {
+            player_id,
+            position: record.position,
+            cause,
+            source_entity,
+        },
+        GameEvent::PlayerInventoryDrop {
+            player_id,
+            position: record.position,
+            decision,
+        },
+    ]
+}
+
+/// Restore health without reviving
+pub fn heal_player(data: &mut PlayerLifecycleData, player_id: u32, amount: f32) {
+    if let Some(player) = data.players.get_mut(&player_id) {
+        if player.state == PlayerLifeState::Alive {
+            player.health = (player.health + amount).min(player.max_health);
+        }
+    }
+}
+
+// ============================================================================
+// RESPAWN
+// ============================================================================
+
+/// Respawn a dead player at their anchor, or at the world spawn if the anchor
+/// fails `anchor_valid` (see `anchor_intact_in_world`)
+pub fn respawn_player<F>(
+    data: &mut PlayerLifecycleData,
+    player_id: u32,
+    anchor_valid: F,
+) -> Vec<GameEvent>
+where
+    F: Fn(&SpawnAnchor) -> bool,
+{
+    let events = respawn_player_internal(data, player_id, &anchor_valid);
+    queue_events(events.clone());
+    events
+}
+
+fn respawn_player_internal<F>(
+    data: &mut PlayerLifecycleData,
+    player_id: u32,
+    anchor_valid: &F,
+) -> Vec<GameEvent>
+where
+    F: Fn(&SpawnAnchor) -> bool,
+{
+    let mut events = Vec::new();
+    let anchor_id = match data.players.get(&player_id) {
+        Some(player) if matches!(player.state, PlayerLifeState::Dead { .. }) => player.spawn_anchor,
+        _ => return events,
+    };
+
+    let mut spawn_anchor = anchor_id.and_then(|id| data.anchors.get(&id));
+    if let Some(anchor) = spawn_anchor {
+        if !anchor_valid(anchor) {
+            events.push(GameEvent::SpawnAnchorLost {
+                player_id,
+                anchor_id: anchor.id,
+            });
+            spawn_anchor = None;
+        }
+    }
+
+    let position = spawn_anchor
+        .map(|anchor| anchor_spawn_position(anchor, data.config.anchor_spawn_offset))
+        .unwrap_or(data.world_spawn);
+    let used_anchor = spawn_anchor.map(|anchor| anchor.id);
+
+    if used_anchor.is_none() {
+        if let Some(id) = anchor_id {
+            remove_spawn_anchor(data, id);
+        }
+    }
+
+    if let Some(player) = data.players.get_mut(&player_id) {
+        player.state = PlayerLifeState::Alive;
+        player.health = player.max_health;
+        player.position = position;
+        player.invulnerable_remaining = data.config.respawn_invulnerability_secs;
+    }
+
+    events.push(GameEvent::PlayerRespawned {
+        player_id,
+        position,
+        anchor_id: used_anchor,
+    });
+    events
+}
+
+/// Advance timers: invulnerability countdown and optional auto-respawn
+pub fn update_player_lifecycle<F>(
+    data: &mut PlayerLifecycleData,
+    delta_time: f32,
+    anchor_valid: F,
+) -> Vec<GameEvent>
+where
+    F: Fn(&SpawnAnchor) -> bool,
+{
+    let mut events = Vec::new();
+    let mut to_respawn = Vec::new();
+
+    for player in data.players.values_mut() {
+        match &mut player.state {
+            PlayerLifeState::Alive => {
+                if player.invulnerable_remaining > 0.0 {
+                    player.invulnerable_remaining -= delta_time;
+                    if player.invulnerable_remaining <= 0.0 {
+                        player.invulnerable_remaining = 0.0;
+                        events.push(GameEvent::PlayerInvulnerabilityEnded {
+                            player_id: player.player_id,
+                        });
+                    }
+                }
+            }
+            PlayerLifeState::Dead { time_dead } => {
+                *time_dead += delta_time;
+                if let Some(delay) = data.config.auto_respawn_delay {
+                    if *time_dead >= delay {
+                        to_respawn.push(player.player_id);
+                    }
+                }
+            }
+        }
+    }
+
+    for player_id in to_respawn {
+        events.extend(respawn_player_internal(data, player_id, &anchor_valid));
+    }
+
+    queue_events(events.clone());
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn damage(player_id: u32, amount: f32) -> DamageEvent {
+        DamageEvent {
+            player_id,
+            amount,
+            cause: "fall".to_string(),
+            source_entity: None,
+        }
+    }
+
+    #[test]
+    fn test_death_and_respawn_at_anchor() {
+        let mut data = create_player_lifecycle([0.0, 70.0, 0.0], LifecycleConfig::default());
+        add_lifecycle_player(&mut data, 1, [10.0, 70.0, 10.0]);
+        let anchor = register_spawn_anchor(&mut data, 1, VoxelPos::new(5, 64, 5), BlockId(100));
+
+        let events = apply_damage(&mut data, damage(1, 100.0));
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, GameEvent::PlayerDied { .. })));
+        assert!(!is_player_alive(&data, 1));
+
+        let events = respawn_player(&mut data, 1, |_| true);
+        assert!(matches!(
+            events.last(),
+            Some(GameEvent::PlayerRespawned { anchor_id: Some(id), position, .. })
+                if *id == anchor && *position == [5.5, 65.0, 5.5]
+        ));
+        assert!(is_player_invulnerable(&data, 1));
+
+        // Damage is ignored during the invulnerability window
+        assert!(apply_damage(&mut data, damage(1, 5.0)).is_empty());
+
+        let events = update_player_lifecycle(&mut data, 10.0, |_| true);
+        assert!(matches!(
+            events[0],
+            GameEvent::PlayerInvulnerabilityEnded { player_id: 1 }
+        ));
+    }
+
+    #[test]
+    fn test_invalid_anchor_falls_back_to_world_spawn() {
+        let mut data = create_player_lifecycle([0.0, 70.0, 0.0], LifecycleConfig::default());
+        set_drop_policy(
+            &mut data,
+            DropPolicy::Custom(Box::new(|_| DropDecision::Keep)),
+        );
+        add_lifecycle_player(&mut data, 1, [0.0; 3]);
+        register_spawn_anchor(&mut data, 1, VoxelPos::new(5, 64, 5), BlockId(100));
+
+        let events = kill_player(&mut data, 1, "void");
+        assert!(events.iter().any(|e| matches!(
+            e,
+            GameEvent::PlayerInventoryDrop {
+                decision: DropDecision::Keep,
+                ..
+            }
+        )));
+
+        let events = respawn_player(&mut data, 1, |_| false);
+        assert!(matches!(events[0], GameEvent::SpawnAnchorLost { .. }));
+        assert!(matches!(
+            events[1],
+            GameEvent::PlayerRespawned { anchor_id: None, position, .. } if position == [0.0, 70.0, 0.0]
+        ));
+        assert!(data.anchors.is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+//! Load Progress Data - World loading screen state
+//!
+//! World startup runs through a fixed list of phases. Each phase reports
+//! how much of its work is done; the phases are weighted into one overall
+//! percentage for a loading bar. The game gets control once the world is
+//! playable: GPU and world buffer are ready and at least the minimum number
+//! of spawn chunks has been generated, even if lighting and entities are
+//! still loading. Progress reaches the game as `GameEvent::LoadProgress` /
+//! `GameEvent::WorldPlayable` and through hooks that draw its own loading
+//! screen.
+//!
+//! Pure DOP: No methods, just data structures.
+
+use std::sync::Arc;
+
+/// Startup phase, in the order they normally run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LoadPhase {
+    GpuInit = 0,
+    WorldBufferAlloc = 1,
+    SpawnChunks = 2,
+    Lighting = 3,
+    EntityLoad = 4,
+}
+
+/// Number of `LoadPhase` variants
+pub const LOAD_PHASE_COUNT: usize = 5;
+
+/// Every phase, in order
+pub const LOAD_PHASES: [LoadPhase; LOAD_PHASE_COUNT] = [
+    LoadPhase::GpuInit,
+    LoadPhase::WorldBufferAlloc,
+    LoadPhase::SpawnChunks,
+    LoadPhase::Lighting,
+    LoadPhase::EntityLoad,
+];
+
+/// Where a phase is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoadPhaseStatus {
+    #[default]
+    Pending,
+    Running,
+    Complete,
+    /// Not used by this game or world; left out of the overall percentage
+    Skipped,
+    Failed,
+}
+
+/// Progress of one phase
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadPhaseState {
+    pub status: LoadPhaseStatus,
+    /// Units of work done (chunks, entities, ...)
+    pub completed: u32,
+    /// Units of work in the phase (0 = unknown)
+    pub total: u32,
+    /// Shown under the loading bar ("Generating spawn area")
+    pub message: String,
+}
+
+/// Weights and the playable gate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadProgressConfig {
+    /// Share of the overall percentage, indexed by `LoadPhase as usize`
+    pub weights: [f32; LOAD_PHASE_COUNT],
+    /// Spawn chunks needed before the world is playable
+    pub min_playable_chunks: u32,
+}
+
+/// What a loading screen draws
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadProgressSnapshot {
+    /// Phase being worked on (None once every phase is done)
+    pub phase: Option<LoadPhase>,
+    /// Progress of that phase (0 - 100)
+    pub phase_percent: f32,
+    /// Weighted progress of every phase (0 - 100)
+    pub overall_percent: f32,
+    pub message: String,
+    pub playable: bool,
+    /// Reason of the first failed phase
+    pub failure: Option<String>,
+}
+
+/// Game hook called whenever progress changes, to draw its loading screen
+pub type LoadProgressHook = Arc<dyn Fn(&LoadProgressSnapshot) + Send + Sync>;
+
+/// Loading state of the world being started
+pub struct LoadProgressData {
+    pub config: LoadProgressConfig,
+    /// Indexed by `LoadPhase as usize`
+    pub phases: [LoadPhaseState; LOAD_PHASE_COUNT],
+    /// Set once by the playable gate, never cleared
+    pub playable: bool,
+    /// Whole overall percent of the last `LoadProgress` event
+    pub last_reported_percent: Option<u32>,
+    pub hooks: Vec<LoadProgressHook>,
+}
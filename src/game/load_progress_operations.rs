@@ -0,0 +1,321 @@
+//! Load Progress Operations - Pure functions over LoadProgressData
+//!
+//! Startup code reports each phase as it runs; every report returns the
+//! game events it caused. `LoadProgress` is emitted when a phase changes
+//! status or the overall percentage reaches a new whole percent, and
+//! `WorldPlayable` exactly once, when the playable gate opens.
+
+use super::gateway_data::GameEvent;
+use super::load_progress_data::{
+    LoadPhase, LoadPhaseState, LoadPhaseStatus, LoadProgressConfig, LoadProgressData,
+    LoadProgressHook, LoadProgressSnapshot, LOAD_PHASES,
+};
+use crate::constants::load_progress::{LOAD_PHASE_WEIGHTS, MIN_PLAYABLE_CHUNKS};
+use crate::world::core::ChunkPos;
+
+/// Default weights and playable gate
+pub fn default_load_progress_config() -> LoadProgressConfig {
+    LoadProgressConfig {
+        weights: LOAD_PHASE_WEIGHTS,
+        min_playable_chunks: MIN_PLAYABLE_CHUNKS,
+    }
+}
+
+/// Create load progress with every phase pending
+pub fn create_load_progress(config: LoadProgressConfig) -> LoadProgressData {
+    LoadProgressData {
+        config,
+        phases: Default::default(),
+        playable: false,
+        last_reported_percent: None,
+        hooks: Vec::new(),
+    }
+}
+
+/// Forget all progress (before loading another world); hooks are kept
+pub fn reset_load_progress(data: &mut LoadProgressData) {
+    data.phases = Default::default();
+    data.playable = false;
+    data.last_reported_percent = None;
+}
+
+/// Start a phase of `total` units of work (0 when unknown)
+pub fn begin_load_phase(
+    data: &mut LoadProgressData,
+    phase: LoadPhase,
+    total: u32,
+    message: &str,
+) -> Vec<GameEvent> {
+    let state = &mut data.phases[phase as usize];
+    state.status = LoadPhaseStatus::Running;
+    state.completed = 0;
+    state.total = total;
+    state.message = message.to_string();
+    report_load_progress(data, phase, true)
+}
+
+/// Set how many units of a phase are done, starting it if still pending
+pub fn advance_load_phase(
+    data: &mut LoadProgressData,
+    phase: LoadPhase,
+    completed: u32,
+) -> Vec<GameEvent> {
+    let state = &mut data.phases[phase as usize];
+    let started = state.status == LoadPhaseStatus::Pending;
+    if started {
+        state.status = LoadPhaseStatus::Running;
+    }
+    state.completed = if state.total > 0 {
+        completed.min(state.total)
+    } else {
+        completed
+    };
+    report_load_progress(data, phase, started)
+}
+
+/// Finish a phase
+pub fn complete_load_phase(data: &mut LoadProgressData, phase: LoadPhase) -> Vec<GameEvent> {
+    let state = &mut data.phases[phase as usize];
+    let changed = state.status != LoadPhaseStatus::Complete;
+    state.status = LoadPhaseStatus::Complete;
+    state.completed = state.completed.max(state.total);
+    report_load_progress(data, phase, changed)
+}
+
+/// Leave a phase out (no entities to load, lighting disabled, ...)
+pub fn skip_load_phase(data: &mut LoadProgressData, phase: LoadPhase) -> Vec<GameEvent> {
+    let state = &mut data.phases[phase as usize];
+    let changed = state.status != LoadPhaseStatus::Skipped;
+    state.status = LoadPhaseStatus::Skipped;
+    report_load_progress(data, phase, changed)
+}
+
+/// Stop a phase with the reason shown on the loading screen; the world
+/// does not become playable while a phase it needs has failed
+pub fn fail_load_phase(
+    data: &mut LoadProgressData,
+    phase: LoadPhase,
+    reason: &str,
+) -> Vec<GameEvent> {
+    let state = &mut data.phases[phase as usize];
+    let changed = state.status != LoadPhaseStatus::Failed;
+    state.status = LoadPhaseStatus::Failed;
+    state.message = reason.to_string();
+    report_load_progress(data, phase, changed)
+}
+
+/// Progress of one phase (0 - 100)
+pub fn load_phase_percent(state: &LoadPhaseState) -> f32 {
+    match state.status {
+        LoadPhaseStatus::Pending => 0.0,
+        LoadPhaseStatus::Complete | LoadPhaseStatus::Skipped => 100.0,
+        LoadPhaseStatus::Running | LoadPhaseStatus::Failed if state.total > 0 => {
+            (state.completed as f32 / state.total as f32 * 100.0).min(100.0)
+        }
+        LoadPhaseStatus::Running | LoadPhaseStatus::Failed => 0.0,
+    }
+}
+
+/// Weighted progress of every phase that is not skipped (0 - 100)
+pub fn overall_load_percent(data: &LoadProgressData) -> f32 {
+    let mut weighted = 0.0;
+    let mut total_weight = 0.0;
+    for phase in LOAD_PHASES {
+        let state = &data.phases[phase as usize];
+        if state.status == LoadPhaseStatus::Skipped {
+            continue;
+        }
+        let weight = data.config.weights[phase as usize].max(0.0);
+        weighted += weight * load_phase_percent(state);
+        total_weight += weight;
+    }
+    if total_weight > 0.0 {
+        weighted / total_weight
+    } else {
+        100.0
+    }
+}
+
+/// Phase a loading screen should name: the first failed or running one,
+/// else the first pending one
+pub fn current_load_phase(data: &LoadProgressData) -> Option<LoadPhase> {
+    let with_status = |statuses: &[LoadPhaseStatus]| {
+        LOAD_PHASES
+            .into_iter()
+            .find(|phase| statuses.contains(&data.phases[*phase as usize].status))
+    };
+    with_status(&[LoadPhaseStatus::Failed])
+        .or_else(|| with_status(&[LoadPhaseStatus::Running]))
+        .or_else(|| with_status(&[LoadPhaseStatus::Pending]))
+}
+
+/// Everything a loading screen draws
+pub fn load_progress_snapshot(data: &LoadProgressData) -> LoadProgressSnapshot {
+    let phase = current_load_phase(data);
+    let failure = LOAD_PHASES
+        .into_iter()
+        .map(|phase| &data.phases[phase as usize])
+        .find(|state| state.status == LoadPhaseStatus::Failed)
+        .map(|state| state.message.clone());
+    let (phase_percent, message) = phase
+        .map(|phase| {
+            let state = &data.phases[phase as usize];
+            (load_phase_percent(state), state.message.clone())
+        })
+        .unwrap_or((100.0, String::new()));
+
+    LoadProgressSnapshot {
+        phase,
+        phase_percent,
+        overall_percent: overall_load_percent(data),
+        message,
+        playable: data.playable,
+        failure,
+    }
+}
+
+/// Whether control can be handed to the game
+pub fn is_world_playable(data: &LoadProgressData) -> bool {
+    data.playable
+}
+
+/// Register a hook drawing the game's loading screen
+pub fn register_load_progress_hook(data: &mut LoadProgressData, hook: LoadProgressHook) {
+    data.hooks.push(hook);
+}
+
+/// Call every hook with `snapshot`
+pub fn notify_load_progress_hooks(hooks: &[LoadProgressHook], snapshot: &LoadProgressSnapshot) {
+    for hook in hooks {
+        hook(snapshot);
+    }
+}
+
+/// Chunks of the spawn area within `radius` columns of `center`, nearest
+/// first, so the playable gate opens on the chunks around the player
+pub fn spawn_chunk_area(center: ChunkPos, radius: i32) -> Vec<ChunkPos> {
+    let radius = radius.max(0);
+    let mut chunks = Vec::new();
+    for dx in -radius..=radius {
+        for dz in -radius..=radius {
+            chunks.push(ChunkPos::new(center.x + dx, center.y, center.z + dz));
+        }
+    }
+    chunks.sort_by_key(|chunk| {
+        let (dx, dz) = (chunk.x - center.x, chunk.z - center.z);
+        (dx * dx + dz * dz, dx, dz)
+    });
+    chunks
+}
+
+/// Gate: GPU and world buffer ready, nothing needed failed, and enough
+/// spawn chunks generated (or the spawn phase finished with fewer)
+fn playable_gate_open(data: &LoadProgressData) -> bool {
+    let ready = |phase: LoadPhase| {
+        matches!(
+            data.phases[phase as usize].status,
+            LoadPhaseStatus::Complete | LoadPhaseStatus::Skipped
+        )
+    };
+    let spawn = &data.phases[LoadPhase::SpawnChunks as usize];
+    let spawn_ready = match spawn.status {
+        LoadPhaseStatus::Complete | LoadPhaseStatus::Skipped => true,
+        LoadPhaseStatus::Running => spawn.completed >= data.config.min_playable_chunks,
+        LoadPhaseStatus::Pending | LoadPhaseStatus::Failed => false,
+    };
+    ready(LoadPhase::GpuInit) && ready(LoadPhase::WorldBufferAlloc) && spawn_ready
+}
+
+/// Events after a report on `phase`
+fn report_load_progress(
+    data: &mut LoadProgressData,
+    phase: LoadPhase,
+    status_changed: bool,
+) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+    let overall_percent = overall_load_percent(data);
+    let whole_percent = overall_percent.floor() as u32;
+
+    if status_changed || data.last_reported_percent != Some(whole_percent) {
+        let state = &data.phases[phase as usize];
+        events.push(GameEvent::LoadProgress {
+            phase,
+            status: state.status,
+            phase_percent: load_phase_percent(state),
+            overall_percent,
+        });
+        data.last_reported_percent = Some(whole_percent);
+    }
+
+    if !data.playable && playable_gate_open(data) {
+        data.playable = true;
+        events.push(GameEvent::WorldPlayable {
+            spawn_chunks: data.phases[LoadPhase::SpawnChunks as usize].completed,
+        });
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playable_events(events: &[GameEvent]) -> usize {
+        events
+            .iter()
+            .filter(|event| matches!(event, GameEvent::WorldPlayable { .. }))
+            .count()
+    }
+
+    #[test]
+    fn playable_gate_opens_once_on_minimum_spawn_chunks() {
+        let mut data = create_load_progress(LoadProgressConfig {
+            weights: LOAD_PHASE_WEIGHTS,
+            min_playable_chunks: 4,
+        });
+        complete_load_phase(&mut data, LoadPhase::GpuInit);
+        complete_load_phase(&mut data, LoadPhase::WorldBufferAlloc);
+        begin_load_phase(
+            &mut data,
+            LoadPhase::SpawnChunks,
+            9,
+            "Generating spawn area",
+        );
+
+        assert_eq!(
+            playable_events(&advance_load_phase(&mut data, LoadPhase::SpawnChunks, 3)),
+            0
+        );
+        assert!(!is_world_playable(&data));
+
+        let events = advance_load_phase(&mut data, LoadPhase::SpawnChunks, 4);
+        assert_eq!(playable_events(&events), 1);
+        assert!(is_world_playable(&data));
+
+        let events = complete_load_phase(&mut data, LoadPhase::SpawnChunks);
+        assert_eq!(playable_events(&events), 0);
+        let snapshot = load_progress_snapshot(&data);
+        assert_eq!(snapshot.phase, Some(LoadPhase::Lighting));
+        assert!(snapshot.playable);
+    }
+
+    #[test]
+    fn skipped_phases_leave_the_overall_percent() {
+        let mut data = create_load_progress(default_load_progress_config());
+        skip_load_phase(&mut data, LoadPhase::Lighting);
+        skip_load_phase(&mut data, LoadPhase::EntityLoad);
+        complete_load_phase(&mut data, LoadPhase::GpuInit);
+        complete_load_phase(&mut data, LoadPhase::WorldBufferAlloc);
+        begin_load_phase(&mut data, LoadPhase::SpawnChunks, 10, "");
+        advance_load_phase(&mut data, LoadPhase::SpawnChunks, 5);
+
+        // 0.1 + 0.1 done, half of 0.5, out of 0.7
+        let expected = (20.0 + 25.0) / 0.7;
+        assert!((overall_load_percent(&data) - expected).abs() < 1e-3);
+
+        fail_load_phase(&mut data, LoadPhase::SpawnChunks, "Out of memory");
+        let snapshot = load_progress_snapshot(&data);
+        assert_eq!(snapshot.failure.as_deref(), Some("Out of memory"));
+        assert_eq!(snapshot.phase, Some(LoadPhase::SpawnChunks));
+    }
+}
@@ -0,0 +1,225 @@
+//! Loot Data - Pure DOP
+//!
+//! Drop tables for broken blocks and for anything else a game rolls loot
+//! for (chests, mobs, fishing). A table is a list of pools; each pool rolls
+//! a number of times and every roll picks one weighted entry among those
+//! whose conditions hold. Tables are registered in code or loaded from JSON
+//! or TOML files. Rolled stacks can be spawned as item entities in the
+//! physics buffers.
+//!
+//! NO METHODS - just data.
+
+use crate::world::core::{BlockId, VoxelPos};
+use crate::world::error::WorldError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Game-defined item kind; the engine only carries the number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ItemId(pub u32);
+
+/// What a loot entry drops
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LootItem {
+    Block(BlockId),
+    Item(ItemId),
+}
+
+/// Condition an entry or pool needs to be eligible
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LootCondition {
+    /// Held tool is of this kind ("pickaxe", "shovel", ...)
+    ToolKind { kind: String },
+    /// Held tool is at least this tier
+    MinToolTier { tier: u32 },
+    /// Silk touch is (`required = true`) or is not (`false`) in use
+    SilkTouch { required: bool },
+    /// Passes with this probability (0.0 - 1.0)
+    RandomChance { chance: f32 },
+    /// Passes with `chance + per_level * fortune`
+    RandomChanceWithFortune { chance: f32, per_level: f32 },
+    /// Game hook registered with `register_loot_condition`
+    Hook { name: String },
+}
+
+/// One weighted outcome of a pool roll
+#[derive(Debug, Clone, PartialEq)]
+pub struct LootEntry {
+    pub item: LootItem,
+    pub weight: u32,
+    pub min_count: u32,
+    pub max_count: u32,
+    /// Added to `max_count` per fortune level
+    pub fortune_bonus: u32,
+    pub conditions: Vec<LootCondition>,
+}
+
+/// Entries rolled `min_rolls..=max_rolls` times
+#[derive(Debug, Clone, PartialEq)]
+pub struct LootPool {
+    pub min_rolls: u32,
+    pub max_rolls: u32,
+    pub entries: Vec<LootEntry>,
+    /// The pool is skipped unless all of these hold
+    pub conditions: Vec<LootCondition>,
+}
+
+/// Pools rolled together; a block without a table drops itself
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LootTable {
+    pub pools: Vec<LootPool>,
+}
+
+/// Tool used to break a block or kill a mob
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LootTool {
+    pub kind: String,
+    pub tier: u32,
+}
+
+/// What the loot is being rolled for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LootSource {
+    Block {
+        block: BlockId,
+        position: VoxelPos,
+    },
+    /// Mob or other entity, by game-defined kind
+    Entity {
+        entity_id: u32,
+        kind: String,
+    },
+    /// Chest or other container, by game-defined name
+    Container {
+        name: String,
+    },
+    Other,
+}
+
+/// Inputs conditions and condition hooks see
+#[derive(Debug, Clone, PartialEq)]
+pub struct LootContext {
+    pub source: LootSource,
+    pub tool: Option<LootTool>,
+    /// Fortune (looting) level of the tool
+    pub fortune: u32,
+    pub silk_touch: bool,
+    pub player_id: Option<u32>,
+}
+
+/// Game-provided condition, referenced from tables by name
+pub type LootConditionHook = Box<dyn Fn(&LootContext) -> bool + Send + Sync>;
+
+/// Maps block names in loot files to ids (e.g. `BlockRegistry::get_id`)
+pub type LootBlockResolver = dyn Fn(&str) -> Option<BlockId>;
+
+/// Registered tables and condition hooks
+pub struct LootRegistryData {
+    pub block_tables: HashMap<BlockId, LootTable>,
+    /// Tables rolled by name (chests, mobs, ...)
+    pub named_tables: HashMap<String, LootTable>,
+    pub condition_hooks: HashMap<String, LootConditionHook>,
+    /// Blocks without a table drop one of themselves
+    pub drop_self_by_default: bool,
+}
+
+/// Items produced by a roll
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LootStack {
+    pub item: LootItem,
+    pub count: u32,
+}
+
+/// Item entity added to the physics buffers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ItemEntitySpawn {
+    /// Index in `PhysicsBuffers` and `TransformBuffers`
+    pub entity_id: u32,
+    pub stack: LootStack,
+    pub position: [f32; 3],
+    pub velocity: [f32; 3],
+}
+
+/// Entry as written in a loot file; exactly one of `block` (registry name)
+/// and `item` is set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootEntrySpec {
+    #[serde(default)]
+    pub block: Option<String>,
+    #[serde(default)]
+    pub item: Option<u32>,
+    #[serde(default)]
+    pub weight: Option<u32>,
+    #[serde(default)]
+    pub min_count: Option<u32>,
+    #[serde(default)]
+    pub max_count: Option<u32>,
+    #[serde(default)]
+    pub fortune_bonus: u32,
+    #[serde(default)]
+    pub conditions: Vec<LootCondition>,
+}
+
+/// Pool as written in a loot file; `rolls` defaults to 1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootPoolSpec {
+    #[serde(default)]
+    pub rolls: Option<u32>,
+    #[serde(default)]
+    pub max_rolls: Option<u32>,
+    #[serde(default)]
+    pub conditions: Vec<LootCondition>,
+    pub entries: Vec<LootEntrySpec>,
+}
+
+/// Table as written in a loot file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LootTableSpec {
+    #[serde(default)]
+    pub pools: Vec<LootPoolSpec>,
+}
+
+/// Contents of a loot file: block tables keyed by block name and named
+/// tables keyed by table name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LootFileSpec {
+    #[serde(default)]
+    pub blocks: HashMap<String, LootTableSpec>,
+    #[serde(default)]
+    pub tables: HashMap<String, LootTableSpec>,
+}
+
+/// Loot file encodings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LootFileFormat {
+    Json,
+    Toml,
+}
+
+/// Loot table errors
+#[derive(Debug, thiserror::Error)]
+pub enum LootError {
+    #[error("Failed to read loot file {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("Failed to parse loot tables: {message}")]
+    Parse { message: String },
+
+    #[error("Unsupported loot file extension: {path}")]
+    UnsupportedFormat { path: String },
+
+    #[error("Unknown block {name} in loot table {table}")]
+    UnknownBlock { table: String, name: String },
+
+    #[error("Invalid entry in loot table {table}: {reason}")]
+    InvalidEntry { table: String, reason: String },
+
+    #[error("Unknown loot table: {name}")]
+    UnknownTable { name: String },
+
+    #[error("World error: {0}")]
+    World(#[from] WorldError),
+}
+
+pub type LootResult<T> = Result<T, LootError>;
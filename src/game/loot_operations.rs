@@ -0,0 +1,554 @@
+//! Loot Operations - Pure DOP functions
+//!
+//! Register tables with `register_block_loot` / `register_loot_table` or
+//! load them with `load_loot_tables`, roll them with `roll_block_loot` or
+//! `roll_named_loot`, and drop the result into the world with
+//! `spawn_item_entities`. `break_block_with_loot` does all three for a
+//! block broken by a player.
+
+use super::gateway_data::GameEvent;
+use super::loot_data::{
+    ItemEntitySpawn, ItemId, LootBlockResolver, LootCondition, LootConditionHook, LootContext,
+    LootEntry, LootError, LootFileFormat, LootFileSpec, LootItem, LootPool, LootRegistryData,
+    LootResult, LootSource, LootStack, LootTable, LootTableSpec,
+};
+use crate::constants::loot::{
+    ITEM_ENTITY_HALF_EXTENT, ITEM_POP_HORIZONTAL_SPEED, ITEM_POP_VERTICAL_SPEED,
+    MAX_LOOT_POOL_ROLLS,
+};
+use crate::engine_buffers::{EngineBuffers, PhysicsFlags, AABB};
+use crate::world::core::{BlockId, VoxelPos};
+use crate::world::data_types::WorldData;
+use crate::world::world_operations;
+use rand::Rng;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Empty registry; blocks drop themselves until given a table
+pub fn create_loot_registry() -> LootRegistryData {
+    LootRegistryData {
+        block_tables: HashMap::new(),
+        named_tables: HashMap::new(),
+        condition_hooks: HashMap::new(),
+        drop_self_by_default: true,
+    }
+}
+
+/// Set the table rolled when `block` breaks (replaces any earlier one)
+pub fn register_block_loot(registry: &mut LootRegistryData, block: BlockId, table: LootTable) {
+    registry.block_tables.insert(block, table);
+}
+
+/// Set a table rolled by name, e.g. "chests/dungeon" or "mobs/zombie"
+pub fn register_loot_table(registry: &mut LootRegistryData, name: &str, table: LootTable) {
+    registry.named_tables.insert(name.to_string(), table);
+}
+
+/// Register the hook `LootCondition::Hook { name }` calls
+pub fn register_loot_condition(
+    registry: &mut LootRegistryData,
+    name: &str,
+    hook: LootConditionHook,
+) {
+    registry.condition_hooks.insert(name.to_string(), hook);
+}
+
+/// Build a table from its file form, naming blocks through `resolve_block`
+pub fn loot_table_from_spec(
+    spec: &LootTableSpec,
+    table: &str,
+    resolve_block: &LootBlockResolver,
+) -> LootResult<LootTable> {
+    let invalid = |reason: &str| LootError::InvalidEntry {
+        table: table.to_string(),
+        reason: reason.to_string(),
+    };
+
+    let mut pools = Vec::with_capacity(spec.pools.len());
+    for pool in &spec.pools {
+        let min_rolls = pool.rolls.unwrap_or(1);
+        let max_rolls = pool.max_rolls.unwrap_or(min_rolls);
+        if max_rolls < min_rolls {
+            return Err(invalid("max_rolls is below rolls"));
+        }
+
+        let mut entries = Vec::with_capacity(pool.entries.len());
+        for entry in &pool.entries {
+            let item = match (&entry.block, entry.item) {
+                (Some(name), None) => match resolve_block(name) {
+                    Some(block) => LootItem::Block(block),
+                    None => {
+                        return Err(LootError::UnknownBlock {
+                            table: table.to_string(),
+                            name: name.clone(),
+                        })
+                    }
+                },
+                (None, Some(item)) => LootItem::Item(ItemId(item)),
+                _ => return Err(invalid("an entry needs exactly one of block and item")),
+            };
+            let min_count = entry.min_count.unwrap_or(1);
+            let max_count = entry.max_count.unwrap_or(min_count);
+            if max_count < min_count {
+                return Err(invalid("max_count is below min_count"));
+            }
+            entries.push(LootEntry {
+                item,
+                weight: entry.weight.unwrap_or(1),
+                min_count,
+                max_count,
+                fortune_bonus: entry.fortune_bonus,
+                conditions: entry.conditions.clone(),
+            });
+        }
+
+        pools.push(LootPool {
+            min_rolls,
+            max_rolls,
+            entries,
+            conditions: pool.conditions.clone(),
+        });
+    }
+    Ok(LootTable { pools })
+}
+
+/// Parse a loot file and register its tables; returns the tables added.
+/// Nothing is registered if any table is invalid.
+pub fn parse_loot_tables(
+    registry: &mut LootRegistryData,
+    text: &str,
+    format: LootFileFormat,
+    resolve_block: &LootBlockResolver,
+) -> LootResult<usize> {
+    let file: LootFileSpec = match format {
+        LootFileFormat::Json => serde_json::from_str(text).map_err(|e| LootError::Parse {
+            message: e.to_string(),
+        })?,
+        LootFileFormat::Toml => toml::from_str(text).map_err(|e| LootError::Parse {
+            message: e.to_string(),
+        })?,
+    };
+
+    let mut blocks = Vec::with_capacity(file.blocks.len());
+    for (name, spec) in &file.blocks {
+        let block = resolve_block(name).ok_or_else(|| LootError::UnknownBlock {
+            table: name.clone(),
+            name: name.clone(),
+        })?;
+        blocks.push((block, loot_table_from_spec(spec, name, resolve_block)?));
+    }
+    let mut named = Vec::with_capacity(file.tables.len());
+    for (name, spec) in &file.tables {
+        named.push((
+            name.clone(),
+            loot_table_from_spec(spec, name, resolve_block)?,
+        ));
+    }
+
+    let added = blocks.len() + named.len();
+    registry.block_tables.extend(blocks);
+    registry.named_tables.extend(named);
+    Ok(added)
+}
+
+/// Load a `.json` or `.toml` loot file (see `parse_loot_tables`)
+pub fn load_loot_tables(
+    registry: &mut LootRegistryData,
+    path: &Path,
+    resolve_block: &LootBlockResolver,
+) -> LootResult<usize> {
+    let format = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => LootFileFormat::Json,
+        Some("toml") => LootFileFormat::Toml,
+        _ => {
+            return Err(LootError::UnsupportedFormat {
+                path: path.display().to_string(),
+            })
+        }
+    };
+    let text = std::fs::read_to_string(path).map_err(|e| LootError::Io {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })?;
+    parse_loot_tables(registry, &text, format, resolve_block)
+}
+
+/// Whether `condition` holds; random conditions draw from `rng` and
+/// unregistered hooks fail
+pub fn loot_condition_holds(
+    registry: &LootRegistryData,
+    condition: &LootCondition,
+    context: &LootContext,
+    rng: &mut impl Rng,
+) -> bool {
+    match condition {
+        LootCondition::ToolKind { kind } => context.tool.as_ref().is_some_and(|t| &t.kind == kind),
+        LootCondition::MinToolTier { tier } => {
+            context.tool.as_ref().is_some_and(|t| t.tier >= *tier)
+        }
+        LootCondition::SilkTouch { required } => context.silk_touch == *required,
+        LootCondition::RandomChance { chance } => rng.gen::<f32>() < *chance,
+        LootCondition::RandomChanceWithFortune { chance, per_level } => {
+            rng.gen::<f32>() < chance + per_level * context.fortune as f32
+        }
+        LootCondition::Hook { name } => match registry.condition_hooks.get(name) {
+            Some(hook) => hook(context),
+            None => {
+                log::warn!("[Loot] Unknown loot condition hook: {}", name);
+                false
+            }
+        },
+    }
+}
+
+fn conditions_hold(
+    registry: &LootRegistryData,
+    conditions: &[LootCondition],
+    context: &LootContext,
+    rng: &mut impl Rng,
+) -> bool {
+    conditions
+        .iter()
+        .all(|condition| loot_condition_holds(registry, condition, context, rng))
+}
+
+fn add_loot_stack(stacks: &mut Vec<LootStack>, item: LootItem, count: u32) {
+    match stacks.iter_mut().find(|stack| stack.item == item) {
+        Some(stack) => stack.count = stack.count.saturating_add(count),
+        None => stacks.push(LootStack { item, count }),
+    }
+}
+
+/// Roll every pool of `table`; stacks of the same item are merged
+pub fn roll_loot_table(
+    registry: &LootRegistryData,
+    table: &LootTable,
+    context: &LootContext,
+    rng: &mut impl Rng,
+) -> Vec<LootStack> {
+    let mut stacks = Vec::new();
+    let mut eligible: Vec<&LootEntry> = Vec::new();
+
+    for pool in &table.pools {
+        if !conditions_hold(registry, &pool.conditions, context, rng) {
+            continue;
+        }
+        let max_rolls = pool.max_rolls.min(MAX_LOOT_POOL_ROLLS);
+        let rolls = rng.gen_range(pool.min_rolls.min(max_rolls)..=max_rolls);
+
+        for _ in 0..rolls {
+            eligible.clear();
+            for entry in &pool.entries {
+                if entry.weight > 0 && conditions_hold(registry, &entry.conditions, context, rng) {
+                    eligible.push(entry);
+                }
+            }
+            let total: u64 = eligible.iter().map(|entry| entry.weight as u64).sum();
+            if total == 0 {
+                continue;
+            }
+
+            let mut pick = rng.gen_range(0..total);
+            for entry in &eligible {
+                if pick < entry.weight as u64 {
+                    let max_count = entry
+                        .max_count
+                        .saturating_add(entry.fortune_bonus.saturating_mul(context.fortune));
+                    let count = rng.gen_range(entry.min_count..=max_count);
+                    if count > 0 {
+                        add_loot_stack(&mut stacks, entry.item, count);
+                    }
+                    break;
+                }
+                pick -= entry.weight as u64;
+            }
+        }
+    }
+    stacks
+}
+
+/// Roll a table registered with `register_loot_table` (chests, mobs, ...)
+pub fn roll_named_loot(
+    registry: &LootRegistryData,
+    name: &str,
+    context: &LootContext,
+    rng: &mut impl Rng,
+) -> LootResult<Vec<LootStack>> {
+    let table = registry
+        .named_tables
+        .get(name)
+        .ok_or_else(|| LootError::UnknownTable {
+            name: name.to_string(),
+        })?;
+    Ok(roll_loot_table(registry, table, context, rng))
+}
+
+/// Drops of a broken block: its table, or one of itself without one
+pub fn roll_block_loot(
+    registry: &LootRegistryData,
+    block: BlockId,
+    context: &LootContext,
+    rng: &mut impl Rng,
+) -> Vec<LootStack> {
+    match registry.block_tables.get(&block) {
+        Some(table) => roll_loot_table(registry, table, context, rng),
+        None if registry.drop_self_by_default && block != BlockId::AIR => vec![LootStack {
+            item: LootItem::Block(block),
+            count: 1,
+        }],
+        None => Vec::new(),
+    }
+}
+
+/// Add one dynamic, gravity-affected entity per stack at `position`,
+/// popping out in a random horizontal direction
+pub fn spawn_item_entities(
+    buffers: &mut EngineBuffers,
+    stacks: &[LootStack],
+    position: [f32; 3],
+    rng: &mut impl Rng,
+) -> Vec<ItemEntitySpawn> {
+    let physics = &mut buffers.physics;
+    let transforms = &mut buffers.transforms;
+    let mut spawns = Vec::with_capacity(stacks.len());
+
+    for stack in stacks {
+        let entity_id = physics.positions.len() as u32;
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let speed = rng.gen_range(0.0..=ITEM_POP_HORIZONTAL_SPEED);
+        let velocity = [
+            angle.cos() * speed,
+            ITEM_POP_VERTICAL_SPEED,
+            angle.sin() * speed,
+        ];
+        let e = ITEM_ENTITY_HALF_EXTENT;
+
+        physics.positions.push(position);
+        physics.velocities.push(velocity);
+        physics.accelerations.push([0.0; 3]);
+        physics.aabbs.push(AABB {
+            min: [position[0] - e, position[1] - e, position[2] - e],
+            max: [position[0] + e, position[1] + e, position[2] + e],
+        });
+        physics.flags.push(PhysicsFlags {
+            is_static: false,
+            is_kinematic: false,
+            is_dynamic: true,
+            has_gravity: true,
+            is_grounded: false,
+        });
+        physics.entity_count = physics.positions.len() as u32;
+
+        let identity = [0.0, 0.0, 0.0, 1.0];
+        transforms.previous_positions.push(position);
+        transforms.current_positions.push(position);
+        transforms.interpolated_positions.push(position);
+        transforms.previous_rotations.push(identity);
+        transforms.current_rotations.push(identity);
+        transforms.interpolated_rotations.push(identity);
+
+        spawns.push(ItemEntitySpawn {
+            entity_id,
+            stack: *stack,
+            position,
+            velocity,
+        });
+    }
+    spawns
+}
+
+/// Gateway events announcing spawned item entities
+pub fn item_spawn_events(spawns: &[ItemEntitySpawn]) -> Vec<GameEvent> {
+    spawns
+        .iter()
+        .map(|spawn| GameEvent::ItemSpawned {
+            entity_id: spawn.entity_id,
+            stack: spawn.stack,
+            position: spawn.position,
+        })
+        .collect()
+}
+
+/// Break the block at `pos`, roll its loot with `context` (whose source is
+/// set to the block) and spawn the drops at the block's center. Returns no
+/// spawns when the position was already air.
+pub fn break_block_with_loot(
+    world: &mut WorldData,
+    buffers: &mut EngineBuffers,
+    registry: &LootRegistryData,
+    pos: VoxelPos,
+    chunk_size: u32,
+    mut context: LootContext,
+    rng: &mut impl Rng,
+) -> LootResult<Vec<ItemEntitySpawn>> {
+    let block = world_operations::get_block(world, pos, chunk_size);
+    if block == BlockId::AIR {
+        return Ok(Vec::new());
+    }
+    world_operations::set_block(world, pos, BlockId::AIR, chunk_size)?;
+
+    context.source = LootSource::Block {
+        block,
+        position: pos,
+    };
+    let stacks = roll_block_loot(registry, block, &context, rng);
+    let center = [pos.x as f32 + 0.5, pos.y as f32 + 0.5, pos.z as f32 + 0.5];
+    Ok(spawn_item_entities(buffers, &stacks, center, rng))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::loot_data::LootTool;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const STONE: BlockId = BlockId(1);
+    const COBBLE: BlockId = BlockId(2);
+
+    fn resolve(name: &str) -> Option<BlockId> {
+        match name {
+            "stone" => Some(STONE),
+            "cobblestone" => Some(COBBLE),
+            _ => None,
+        }
+    }
+
+    fn context(tool: Option<LootTool>, fortune: u32, silk_touch: bool) -> LootContext {
+        LootContext {
+            source: LootSource::Other,
+            tool,
+            fortune,
+            silk_touch,
+            player_id: Some(1),
+        }
+    }
+
+    #[test]
+    fn loaded_tables_respect_tools_fortune_and_hooks() {
+        let mut registry = create_loot_registry();
+        let json = r#"{
+            "blocks": {
+                "stone": { "pools": [{ "entries": [
+                    { "block": "stone", "conditions": [{ "type": "silk_touch", "required": true }] },
+                    { "block": "cobblestone", "fortune_bonus": 1, "conditions": [
+                        { "type": "silk_touch", "required": false },
+                        { "type": "tool_kind", "kind": "pickaxe" }
+                    ] }
+                ] }] }
+            },
+            "tables": {
+                "mobs/ghost": { "pools": [{ "rolls": 3, "entries": [
+                    { "item": 7, "min_count": 2, "conditions": [{ "type": "hook", "name": "night" }] }
+                ] }] }
+            }
+        }"#;
+        assert_eq!(
+            parse_loot_tables(&mut registry, json, LootFileFormat::Json, &resolve).unwrap(),
+            2
+        );
+        let mut rng = StdRng::seed_from_u64(3);
+        let pickaxe = Some(LootTool {
+            kind: "pickaxe".to_string(),
+            tier: 1,
+        });
+
+        assert_eq!(
+            roll_block_loot(
+                &registry,
+                STONE,
+                &context(pickaxe.clone(), 0, true),
+                &mut rng
+            ),
+            vec![LootStack {
+                item: LootItem::Block(STONE),
+                count: 1
+            }]
+        );
+        assert_eq!(
+            roll_block_loot(
+                &registry,
+                STONE,
+                &context(pickaxe.clone(), 0, false),
+                &mut rng
+            ),
+            vec![LootStack {
+                item: LootItem::Block(COBBLE),
+                count: 1
+            }]
+        );
+        assert!(roll_block_loot(&registry, STONE, &context(None, 0, false), &mut rng).is_empty());
+        for _ in 0..20 {
+            let stacks = roll_block_loot(
+                &registry,
+                STONE,
+                &context(pickaxe.clone(), 2, false),
+                &mut rng,
+            );
+            assert!((1..=3).contains(&stacks[0].count));
+        }
+        assert_eq!(
+            roll_block_loot(&registry, COBBLE, &context(None, 0, false), &mut rng),
+            vec![LootStack {
+                item: LootItem::Block(COBBLE),
+                count: 1
+            }]
+        );
+
+        assert!(
+            roll_named_loot(&registry, "mobs/ghost", &context(None, 0, false), &mut rng)
+                .unwrap()
+                .is_empty()
+        );
+        register_loot_condition(
+            &mut registry,
+            "night",
+            Box::new(|ctx| ctx.player_id == Some(1)),
+        );
+        assert_eq!(
+            roll_named_loot(&registry, "mobs/ghost", &context(None, 0, false), &mut rng).unwrap(),
+            vec![LootStack {
+                item: LootItem::Item(ItemId(7)),
+                count: 6
+            }]
+        );
+        assert!(matches!(
+            roll_named_loot(&registry, "chests/none", &context(None, 0, false), &mut rng),
+            Err(LootError::UnknownTable { .. })
+        ));
+
+        let bad =
+            r#"{ "blocks": { "stone": { "pools": [{ "entries": [{ "block": "dirt" }] }] } } }"#;
+        assert!(matches!(
+            parse_loot_tables(&mut registry, bad, LootFileFormat::Json, &resolve),
+            Err(LootError::UnknownBlock { .. })
+        ));
+
+        let toml = "[[tables.\"chests/small\".pools]]\nrolls = 2\nentries = [{ item = 1, weight = 3 }, { item = 2 }]\n";
+        assert_eq!(
+            parse_loot_tables(&mut registry, toml, LootFileFormat::Toml, &resolve).unwrap(),
+            1
+        );
+        let chest = roll_named_loot(
+            &registry,
+            "chests/small",
+            &context(None, 0, false),
+            &mut rng,
+        )
+        .unwrap();
+        assert_eq!(chest.iter().map(|s| s.count).sum::<u32>(), 2);
+
+        let mut buffers = crate::engine_buffers::create_engine_buffers();
+        let spawns = spawn_item_entities(&mut buffers, &chest, [1.5, 2.5, 3.5], &mut rng);
+        assert_eq!(spawns.len(), chest.len());
+        assert_eq!(
+            buffers.physics.entity_count as usize,
+            buffers.physics.positions.len()
+        );
+        assert_eq!(
+            buffers.transforms.current_positions.len(),
+            buffers.physics.positions.len()
+        );
+        assert_eq!(item_spawn_events(&spawns).len(), spawns.len());
+    }
+}
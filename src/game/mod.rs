@@ -6,6 +6,7 @@
 use crate::camera::{calculate_forward_vector, CameraData};
 use crate::input::InputState;
 use crate::{BlockId, BlockRegistry, Ray, RaycastHit, VoxelPos};
+use crate::world::blocks::{compute_placement_metadata, MetadataSchemaRegistry};
 use crate::world::{world_operations, data_types::WorldData};
 use cgmath::{Point3, InnerSpace};
 
@@ -13,19 +14,251 @@ use cgmath::{Point3, InnerSpace};
 pub mod gateway_data;
 pub mod gateway_operations;
 
+// Gateway request/response calls
+pub mod gateway_rpc_data;
+pub mod gateway_rpc_operations;
+
+// Place/break/interact cooldowns per player
+pub mod action_limit_data;
+pub mod action_limit_operations;
+
+// Data-driven mob behavior trees
+pub mod behavior_tree_data;
+pub mod behavior_tree_operations;
+
+// World loading phases and playable gate
+pub mod load_progress_data;
+pub mod load_progress_operations;
+
+// Entity attributes (stats and modifiers)
+pub mod attribute_data;
+pub mod attribute_operations;
+
+// Objective scoreboards replicated to clients
+pub mod scoreboard_data;
+pub mod scoreboard_operations;
+
+// Persistent player statistics and achievements
+pub mod player_stats_data;
+pub mod player_stats_operations;
+
+// Player death/respawn lifecycle
+pub mod lifecycle_data;
+pub mod lifecycle_operations;
+
+// Block drop tables and loot rolls
+pub mod loot_data;
+pub mod loot_operations;
+
+// Enter/exit trigger volumes defined by the map
+pub mod trigger_volume_data;
+pub mod trigger_volume_operations;
+
+// Entity health and environmental damage
+pub mod health_data;
+pub mod health_operations;
+
+// Region protection claims checked on the server
+pub mod region_claim_data;
+pub mod region_claim_operations;
+
 // Re-export gateway types
 pub use gateway_data::{
     GameEvent, GameCommand, GameOperations, GameDataAccess, GameDataHandle,
     InteractionType, MessageType, BlockRegistration, BlockProperties,
     EngineStateView, InputStateView, WorldInfoView, PlayerInfo,
-    GameGatewayData, GatewayConfig, GatewayMetrics,
+    GameGatewayData, GatewayConfig, GatewayMetrics, LightCacheHandle, PlayerBlockEdit,
 };
 
 pub use gateway_operations::{
-    init_gateway, shutdown_gateway, queue_event, queue_events,
-    process_update, register_blocks, get_active_block,
+    init_gateway, shutdown_gateway, queue_event, queue_events, queue_command,
+    add_block_registration,
+    process_update, update_gateway, take_gateway_block_edits, register_blocks, get_active_block,
     save_game_state, load_game_state, get_metrics, reset_metrics,
     is_gateway_initialized, get_gateway_config, update_gateway_config,
+    with_gateway_attributes, query_attribute, query_attribute_by_name,
+    update_gateway_attributes, drain_gateway_attribute_changes,
+    with_gateway_scoreboard, query_score, query_leaderboard,
+    with_gateway_action_limits, queue_limited_event, query_action_cooldown,
+    with_gateway_stats, add_gateway_stat, update_gateway_stats, query_player_stat,
+    query_player_stats, query_player_achievements, query_achievement_progress,
+    with_gateway_behavior, register_gateway_behavior_condition, register_gateway_behavior_action,
+    load_gateway_behavior_trees, attach_gateway_behavior, detach_gateway_behavior,
+    update_gateway_behavior, query_behavior_status,
+    with_gateway_load_progress, begin_gateway_load_phase, advance_gateway_load_phase,
+    complete_gateway_load_phase, skip_gateway_load_phase, fail_gateway_load_phase,
+    query_load_progress, is_gateway_world_playable, register_gateway_load_progress_hook,
+    with_gateway_seasons, update_gateway_seasons, advance_gateway_days, query_season,
+    save_gateway_world_time, load_gateway_world_time,
+    with_gateway_haptics, assign_gateway_gamepad, trigger_gateway_rumble, set_gateway_rumble,
+    update_gateway_haptics,
+    set_gateway_light_cache, with_gateway_light_cache, query_light_at, query_sky_visible,
+    query_light_batch, query_spawn_positions,
+    set_gateway_engine_buffers, with_gateway_entity_tags, tag_entity, untag_entity,
+    query_tagged_entities,
+    with_gateway_triggers, update_gateway_triggers, query_trigger_volumes_at,
+    with_gateway_claims, run_gateway_claim_command, query_claim_at, query_block_edit_allowed,
+    with_gateway_health, query_entity_health, update_gateway_health,
+    dispatch_gateway_health_damage,
+    registration_surface_material, apply_registered_surface_materials,
+};
+
+pub use gateway_rpc_data::{
+    GatewayRequest, GatewayResponse, GatewayRpcConfig, GatewayRpcData, GatewayRpcError,
+    GatewayRpcStats, PendingRpc, RpcCallback, RpcCompletion, RpcId, RpcResult,
+};
+
+pub use gateway_rpc_operations::{
+    answer_gateway_request, cancel_gateway_request, cancel_rpc, create_gateway_rpc,
+    default_gateway_rpc_config, expire_rpcs, find_spawn_position, find_walkable_path,
+    gateway_request, gateway_request_with_callback, get_gateway_rpc_stats, init_gateway_rpc,
+    poll_gateway_response, poll_rpc, rpc_in_flight, run_rpc_completions, serve_gateway_requests,
+    serve_rpcs, shutdown_gateway_rpc, submit_rpc, take_rpc_completions,
+};
+
+pub use action_limit_data::{
+    ActionBudget, ActionBudgets, ActionCooldown, ActionLimitConfig, ActionLimitData,
+    ActionLimitStats, ActionRejection, BlockAction, PlayerBlockAction, BLOCK_ACTION_COUNT,
+};
+
+pub use action_limit_operations::{
+    action_cooldown_remaining, action_ready, apply_action_rejection, block_action_of,
+    create_action_limits, default_action_limit_config, limit_block_event, limit_block_events,
+    rejected_action_counts, remove_action_limit_player, set_action_cooldown, try_block_action,
+};
+
+pub use attribute_data::{
+    AttributeChange, AttributeColumn, AttributeDefinition, AttributeError, AttributeId,
+    AttributeModifier, AttributeResult, AttributeStoreData, EntityAttributeSnapshot,
+    ModifierKind, ModifierSource, SavedBaseValue, SavedModifier,
+};
+
+pub use attribute_operations::{
+    add_attribute_entity, add_modifier, base_value, compute_effective_value,
+    create_attribute_store, deserialize_attribute_store, drain_attribute_changes,
+    effective_value, effective_value_by_name, entity_modifiers, find_attribute,
+    register_attribute, remove_attribute_entity, remove_modifiers_from_source,
+    restore_entity_attributes, serialize_attribute_store, set_base_value,
+    snapshot_entity_attributes, update_attributes,
+};
+
+pub use scoreboard_data::{
+    LeaderboardEntry, Objective, SavedScoreboard, ScoreOrder, ScoreboardData, ScoreboardDelta,
+    ScoreboardError, ScoreboardResult, ScoreboardUpdate, SCOREBOARD_MAGIC,
+};
+
+pub use scoreboard_operations::{
+    add_objective, add_score, apply_scoreboard_update, create_scoreboard,
+    decode_scoreboard_update, deserialize_scoreboard, encode_scoreboard_update, find_objective,
+    get_score, leaderboard, load_scoreboard, remove_objective, replicate_scoreboard,
+    reset_player_scores, reset_score, save_scoreboard, scoreboard_snapshot, serialize_scoreboard,
+    set_score, take_scoreboard_update,
+};
+
+pub use player_stats_data::{
+    AchievementDefinition, AchievementProgress, PlayerStatProfile, PlayerStatProfiles, PlayerStatsData,
+    PlayerStatsError, PlayerStatsResult, SavedPlayerStats, StatEntry,
+};
+
+pub use player_stats_operations::{
+    achievement_progress, add_player_stat, autosave_player_stats, create_player_stats,
+    deserialize_player_stats, has_achievement, load_player_stats, player_achievements,
+    player_stat, player_stat_entries, player_stats_save_due, register_achievement,
+    save_player_stats, serialize_player_stats, set_player_stat, tick_player_stats,
+};
+
+pub use behavior_tree_data::{
+    BehaviorActionHandler, BehaviorAgents, BehaviorConditionHandler, BehaviorContext,
+    BehaviorFileFormat, BehaviorFileSpec, BehaviorNode, BehaviorNodeKind, BehaviorNodeSpec,
+    BehaviorStats, BehaviorStatus, BehaviorTickConfig, BehaviorTree, BehaviorTreeData,
+    BehaviorTreeError, BehaviorTreeId, BehaviorTreeResult,
+};
+
+pub use behavior_tree_operations::{
+    add_behavior_tree, attach_behavior, behavior_status, behavior_tree_id, compile_behavior_tree,
+    create_behavior_trees, default_behavior_tick_config, detach_behavior, load_behavior_trees,
+    missing_behavior_handlers, parse_behavior_trees, register_behavior_action,
+    register_behavior_condition, tick_behavior_trees,
+};
+
+pub use load_progress_data::{
+    LoadPhase, LoadPhaseState, LoadPhaseStatus, LoadProgressConfig, LoadProgressData,
+    LoadProgressHook, LoadProgressSnapshot, LOAD_PHASES, LOAD_PHASE_COUNT,
+};
+
+pub use load_progress_operations::{
+    advance_load_phase, begin_load_phase, complete_load_phase, create_load_progress,
+    current_load_phase, default_load_progress_config, fail_load_phase, is_world_playable,
+    load_phase_percent, load_progress_snapshot, notify_load_progress_hooks,
+    overall_load_percent, register_load_progress_hook, reset_load_progress, skip_load_phase,
+    spawn_chunk_area,
+};
+
+pub use lifecycle_data::{
+    DamageEvent, DeathRecord, DropDecision, DropPolicy, InventoryDropHook, LifecycleConfig,
+    PlayerLifeState, PlayerLifecycle, PlayerLifecycleData, SpawnAnchor, SpawnAnchorId,
+};
+
+pub use lifecycle_operations::{
+    add_lifecycle_player, anchor_intact_in_world, apply_damage, create_player_lifecycle,
+    heal_player, is_player_alive, is_player_invulnerable, kill_player, player_spawn_point,
+    register_spawn_anchor, remove_lifecycle_player, remove_spawn_anchor, remove_spawn_anchors_at,
+    respawn_player, set_drop_policy, set_world_spawn, update_player_lifecycle,
+    update_player_position,
+};
+
+pub use loot_data::{
+    ItemEntitySpawn, ItemId, LootBlockResolver, LootCondition, LootConditionHook, LootContext,
+    LootEntry, LootEntrySpec, LootError, LootFileFormat, LootFileSpec, LootItem, LootPool,
+    LootPoolSpec, LootRegistryData, LootResult, LootSource, LootStack, LootTable, LootTableSpec,
+    LootTool,
+};
+
+pub use loot_operations::{
+    break_block_with_loot, create_loot_registry, item_spawn_events, load_loot_tables,
+    loot_condition_holds, loot_table_from_spec, parse_loot_tables, register_block_loot,
+    register_loot_condition, register_loot_table, roll_block_loot, roll_loot_table,
+    roll_named_loot, spawn_item_entities,
+};
+
+pub use trigger_volume_data::{
+    SavedTriggerVolumes, TriggerBounds, TriggerCell, TriggerCellMap, TriggerEvent, TriggerEventKind, TriggerOccupant,
+    TriggerOccupancy, TriggerSubject, TriggerVolume, TriggerVolumeData, TriggerVolumeError,
+    TriggerVolumeId, TriggerVolumeResult,
+};
+
+pub use trigger_volume_operations::{
+    add_trigger_volume, create_trigger_volumes, deserialize_trigger_volumes, find_trigger_volume,
+    load_trigger_volumes, remove_trigger_volume, save_trigger_volumes, serialize_trigger_volumes,
+    set_trigger_volume_bounds, set_trigger_volume_enabled, trigger_bounds_from_voxels,
+    trigger_cell, trigger_entered_tick, trigger_volume, trigger_volume_contains,
+    trigger_volumes_at, update_trigger_volumes,
+};
+
+pub use health_data::{
+    ContactHazard, DamageDealt, DamageSource, DamageSourceToggles, EntityHealthState,
+    HealthConfig, HealthData, HealthSample,
+};
+
+pub use health_operations::{
+    add_health_entity, apply_entity_damage, create_entity_health, damage_source_enabled,
+    damage_source_name, default_health_config, dispatch_health_damage, entity_damage_events,
+    entity_health, entity_max_health, forward_player_damage, heal_entity, remove_health_entity,
+    revive_entity, sync_player_health, update_entity_health,
+};
+
+pub use region_claim_data::{
+    ClaimDenial, ClaimGroups, ClaimHolder, ClaimId, ClaimStats, RegionClaim, RegionClaimData,
+    RegionClaimError, RegionClaimResult, SavedRegionClaims,
+};
+
+pub use region_claim_operations::{
+    add_claim_member, add_group_member, add_region_claim, check_block_edit, claim_bounds,
+    claim_contains, claim_volume, create_region_claims, deserialize_region_claims,
+    execute_claim_command, find_region_claim, format_region_claim, holder_includes,
+    is_claim_member, load_region_claims, protect_block_event, region_claim, region_claim_at,
+    remove_claim_member, remove_group_member, remove_region_claim, save_region_claims,
+    serialize_region_claims, set_claim_admin, set_claim_public_action, transfer_region_claim,
 };
 
 /// Game data structure (DOP - no methods)
@@ -84,6 +317,7 @@ pub fn handle_block_place<T: GameData + 'static>(_game: &mut T, pos: VoxelPos, b
         queue_event(GameEvent::BlockPlace {
             position: pos,
             block_id: block,
+            metadata: 0,
             player_id: None,
         });
     }
@@ -155,12 +389,80 @@ pub fn place_block_dop(
     }
 }
 
+/// Queue `player_id` placing a directional block against the face hit by
+/// their camera ray. Orientation comes from the camera forward vector and
+/// the hit face and travels in the edit's metadata; the gateway checks the
+/// edit against the region claims and cooldowns and the engine applies it.
+/// Returns whether the placement was queued.
+pub fn place_oriented_block_dop(
+    world: &WorldData,
+    schemas: &MetadataSchemaRegistry,
+    camera: &CameraData,
+    hit: &RaycastHit,
+    block_id: BlockId,
+    player_id: u32,
+    chunk_size: u32,
+) -> bool {
+    let offset = hit.face.offset();
+    let pos = VoxelPos::new(
+        hit.position.x + offset.x,
+        hit.position.y + offset.y,
+        hit.position.z + offset.z,
+    );
+    if world_operations::get_block(world, pos, chunk_size) != BlockId::AIR {
+        return false;
+    }
+
+    let forward = calculate_forward_vector(camera.yaw_radians, camera.pitch_radians);
+    let metadata = compute_placement_metadata(schemas, block_id, forward, hit.face);
+    queue_limited_event(GameEvent::BlockPlace {
+        position: pos,
+        block_id,
+        metadata,
+        player_id: Some(player_id),
+    })
+}
+
+/// Preview placing the gateway's active block against the face hit by the
+/// camera ray; feed the result to `update_renderer_placement_preview`
+pub fn preview_block_placement_dop(
+    world: &WorldData,
+    schemas: &MetadataSchemaRegistry,
+    camera: &CameraData,
+    hit: &RaycastHit,
+    player: &crate::physics::AABB,
+    chunk_size: u32,
+) -> Option<crate::renderer::PlacementPreview> {
+    let block = if is_gateway_initialized() {
+        get_active_block()
+    } else {
+        BlockId::GRASS
+    };
+    crate::renderer::compute_placement_preview(
+        world, schemas, camera, hit, block, player, chunk_size,
+    )
+}
+
 /// Get block at position
 /// Pure function - reads block from world data
 pub fn get_block_dop(world: &WorldData, pos: VoxelPos, chunk_size: u32) -> BlockId {
     world_operations::get_block(world, pos, chunk_size)
 }
 
+/// Get block at position via the CPU shadow cache
+/// Falls back to world data on a miss; the miss queues an async readback
+pub fn get_block_shadowed_dop(
+    shadow: &mut crate::world::ShadowCacheData,
+    world: &WorldData,
+    pos: VoxelPos,
+    chunk_size: u32,
+) -> BlockId {
+    match crate::world::storage::shadow_get_block(shadow, pos) {
+        crate::world::ShadowLookup::Hit(block) => block,
+        crate::world::ShadowLookup::Miss => world_operations::get_block(world, pos, chunk_size),
+    }
+}
+
 /// Check if chunk is loaded
 /// Pure function - reads chunk state from world data
 pub fn is_chunk_loaded_dop(world: &WorldData, chunk_pos: crate::ChunkPos) -> bool {
@@ -0,0 +1,93 @@
+//! Player Stats Data - Named counters and achievements per player
+//!
+//! Every game wants the same counters (blocks broken, distance walked, play
+//! time), so the engine keeps them: the game adds to named stats through
+//! the gateway, achievements unlock when a stat crosses their threshold,
+//! and the profiles are saved with the world at a fixed interval. Profiles
+//! are keyed by player name so they survive reconnects and world reloads.
+//!
+//! Pure DOP: No methods, just data structures.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Achievement unlocked once a stat reaches a threshold
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AchievementDefinition {
+    pub id: String,
+    /// Title shown by achievement UIs
+    pub display_name: String,
+    pub stat: String,
+    pub threshold: f64,
+}
+
+/// Counters and unlocked achievements of one player
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStatProfile {
+    pub stats: HashMap<String, f64>,
+    /// Achievement ids in unlock order
+    pub achievements: Vec<String>,
+}
+
+/// Profiles by player name
+pub type PlayerStatProfiles = HashMap<String, PlayerStatProfile>;
+
+/// One stat of a player, for stat screens
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatEntry {
+    pub stat: String,
+    pub value: f64,
+}
+
+/// Achievement state of a player, for achievement screens
+#[derive(Clone, Debug, PartialEq)]
+pub struct AchievementProgress {
+    pub id: String,
+    pub display_name: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub unlocked: bool,
+}
+
+/// Statistics service of one world
+#[derive(Clone, Debug, Default)]
+pub struct PlayerStatsData {
+    pub profiles: PlayerStatProfiles,
+    /// Achievements in registration order
+    pub achievements: Vec<AchievementDefinition>,
+    /// Changes since the last save
+    pub dirty: bool,
+    /// Seconds since the last save
+    pub since_save_secs: f32,
+}
+
+/// Player stats errors
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum PlayerStatsError {
+    #[error("Invalid name: {0:?}")]
+    InvalidName(String),
+
+    #[error("Invalid stat amount {amount} for {stat}")]
+    InvalidAmount { stat: String, amount: f64 },
+
+    #[error("Achievement already exists: {0}")]
+    AchievementExists(String),
+
+    #[error("Player stats serialization failed: {0}")]
+    Serialization(String),
+
+    #[error("Player stats deserialization failed: {0}")]
+    Deserialization(String),
+
+    #[error("Player stats I/O failed: {0}")]
+    Io(String),
+}
+
+pub type PlayerStatsResult<T> = Result<T, PlayerStatsError>;
+
+/// Player profiles as stored with the world
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedPlayerStats {
+    pub version: u32,
+    pub profiles: PlayerStatProfiles,
+}
@@ -0,0 +1,369 @@
+//! Player Stats Operations - Pure functions for stats and achievements
+//!
+//! At startup: `register_achievement` for every achievement, then
+//! `load_player_stats` from the world directory. While playing: add to
+//! stats with `add_player_stat` (`STAT_BLOCKS_BROKEN` on a break,
+//! `STAT_DISTANCE_WALKED` per move) and call `tick_player_stats` once per
+//! tick with the online players, which counts `STAT_PLAY_TIME`. Queue the
+//! returned `AchievementUnlocked` events and `autosave_player_stats` when
+//! convenient; it only writes once the save interval has passed.
+
+use super::gateway_data::GameEvent;
+use super::player_stats_data::{
+    AchievementDefinition, AchievementProgress, PlayerStatProfile, PlayerStatProfiles,
+    PlayerStatsData, PlayerStatsError, PlayerStatsResult, SavedPlayerStats, StatEntry,
+};
+use crate::constants::persistence_constants::{PLAYER_STATS_FILE, PLAYER_STATS_FORMAT_VERSION};
+use crate::constants::player_stats::{MAX_STAT_NAME_LEN, STATS_SAVE_INTERVAL_SECS, STAT_PLAY_TIME};
+use crate::persistence::{read_save_file, write_save_file, SaveCipherData};
+use std::path::Path;
+
+/// Create a stats service with no players or achievements
+pub fn create_player_stats() -> PlayerStatsData {
+    PlayerStatsData::default()
+}
+
+fn validate_name(name: &str) -> PlayerStatsResult<()> {
+    if name.is_empty() || name.len() > MAX_STAT_NAME_LEN || name.chars().any(char::is_control) {
+        return Err(PlayerStatsError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Unlock every achievement on `stat` the profile has reached
+fn unlock_reached(
+    achievements: &[AchievementDefinition],
+    player: &str,
+    profile: &mut PlayerStatProfile,
+    stat: &str,
+    events: &mut Vec<GameEvent>,
+) {
+    let value = profile.stats.get(stat).copied().unwrap_or(0.0);
+    for achievement in achievements.iter().filter(|a| a.stat == stat) {
+        if value < achievement.threshold || profile.achievements.contains(&achievement.id) {
+            continue;
+        }
+        profile.achievements.push(achievement.id.clone());
+        events.push(GameEvent::AchievementUnlocked {
+            player: player.to_string(),
+            achievement: achievement.id.clone(),
+            stat: stat.to_string(),
+            value,
+        });
+    }
+}
+
+/// Add an achievement that unlocks once `stat` reaches `threshold`.
+/// Players already past the threshold unlock it right away.
+pub fn register_achievement(
+    data: &mut PlayerStatsData,
+    id: &str,
+    display_name: &str,
+    stat: &str,
+    threshold: f64,
+) -> PlayerStatsResult<Vec<GameEvent>> {
+    validate_name(id)?;
+    validate_name(stat)?;
+    if !threshold.is_finite() {
+        return Err(PlayerStatsError::InvalidAmount {
+            stat: stat.to_string(),
+            amount: threshold,
+        });
+    }
+    if data.achievements.iter().any(|a| a.id == id) {
+        return Err(PlayerStatsError::AchievementExists(id.to_string()));
+    }
+    data.achievements.push(AchievementDefinition {
+        id: id.to_string(),
+        display_name: display_name.to_string(),
+        stat: stat.to_string(),
+        threshold,
+    });
+
+    let mut events = Vec::new();
+    let achievement = std::slice::from_ref(&data.achievements[data.achievements.len() - 1]);
+    for (player, profile) in &mut data.profiles {
+        unlock_reached(achievement, player, profile, stat, &mut events);
+    }
+    if !events.is_empty() {
+        data.dirty = true;
+    }
+    Ok(events)
+}
+
+/// Set a stat, returning the achievements it unlocked. Lowering a stat
+/// keeps achievements already unlocked.
+pub fn set_player_stat(
+    data: &mut PlayerStatsData,
+    player: &str,
+    stat: &str,
+    value: f64,
+) -> PlayerStatsResult<Vec<GameEvent>> {
+    validate_name(player)?;
+    validate_name(stat)?;
+    if !value.is_finite() {
+        return Err(PlayerStatsError::InvalidAmount {
+            stat: stat.to_string(),
+            amount: value,
+        });
+    }
+    let profile = data.profiles.entry(player.to_string()).or_default();
+    profile.stats.insert(stat.to_string(), value);
+    data.dirty = true;
+
+    let mut events = Vec::new();
+    unlock_reached(&data.achievements, player, profile, stat, &mut events);
+    Ok(events)
+}
+
+/// Add a non-negative amount to a stat, returning the achievements it
+/// unlocked
+pub fn add_player_stat(
+    data: &mut PlayerStatsData,
+    player: &str,
+    stat: &str,
+    amount: f64,
+) -> PlayerStatsResult<Vec<GameEvent>> {
+    if !amount.is_finite() || amount < 0.0 {
+        return Err(PlayerStatsError::InvalidAmount {
+            stat: stat.to_string(),
+            amount,
+        });
+    }
+    let value = player_stat(data, player, stat) + amount;
+    set_player_stat(data, player, stat, value)
+}
+
+/// A player's stat (0 if never counted)
+pub fn player_stat(data: &PlayerStatsData, player: &str, stat: &str) -> f64 {
+    data.profiles
+        .get(player)
+        .and_then(|profile| profile.stats.get(stat))
+        .copied()
+        .unwrap_or(0.0)
+}
+
+/// Every stat of a player, sorted by name
+pub fn player_stat_entries(data: &PlayerStatsData, player: &str) -> Vec<StatEntry> {
+    let mut entries: Vec<StatEntry> = data
+        .profiles
+        .get(player)
+        .map(|profile| {
+            profile
+                .stats
+                .iter()
+                .map(|(stat, &value)| StatEntry {
+                    stat: stat.clone(),
+                    value,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort_by(|a, b| a.stat.cmp(&b.stat));
+    entries
+}
+
+/// Achievement ids a player unlocked, oldest first
+pub fn player_achievements<'a>(data: &'a PlayerStatsData, player: &str) -> &'a [String] {
+    data.profiles
+        .get(player)
+        .map(|profile| profile.achievements.as_slice())
+        .unwrap_or_default()
+}
+
+/// Whether a player unlocked an achievement
+pub fn has_achievement(data: &PlayerStatsData, player: &str, achievement: &str) -> bool {
+    player_achievements(data, player)
+        .iter()
+        .any(|id| id == achievement)
+}
+
+/// Progress of a player on every achievement, in registration order
+pub fn achievement_progress(data: &PlayerStatsData, player: &str) -> Vec<AchievementProgress> {
+    data.achievements
+        .iter()
+        .map(|achievement| AchievementProgress {
+            id: achievement.id.clone(),
+            display_name: achievement.display_name.clone(),
+            value: player_stat(data, player, &achievement.stat),
+            threshold: achievement.threshold,
+            unlocked: has_achievement(data, player, &achievement.id),
+        })
+        .collect()
+}
+
+/// Count play time for the online players and advance the save timer.
+/// Returns the achievements unlocked by play time.
+pub fn tick_player_stats(
+    data: &mut PlayerStatsData,
+    online_players: &[&str],
+    delta_time: f32,
+) -> Vec<GameEvent> {
+    data.since_save_secs += delta_time;
+    let mut events = Vec::new();
+    for player in online_players {
+        match add_player_stat(data, player, STAT_PLAY_TIME, f64::from(delta_time)) {
+            Ok(unlocked) => events.extend(unlocked),
+            Err(e) => log::warn!("[PlayerStats] Play time not counted: {}", e),
+        }
+    }
+    events
+}
+
+/// Whether there are changes and the save interval has passed
+pub fn player_stats_save_due(data: &PlayerStatsData) -> bool {
+    data.dirty && data.since_save_secs >= STATS_SAVE_INTERVAL_SECS
+}
+
+/// Save into a world slot directory if a save is due. Returns whether it
+/// wrote.
+pub fn autosave_player_stats(
+    data: &mut PlayerStatsData,
+    world_dir: &Path,
+    cipher: Option<&SaveCipherData>,
+) -> PlayerStatsResult<bool> {
+    if !player_stats_save_due(data) {
+        return Ok(false);
+    }
+    save_player_stats(data, world_dir, cipher)?;
+    Ok(true)
+}
+
+/// Serialize the player profiles (achievement definitions belong to the
+/// game and are registered again at startup)
+pub fn serialize_player_stats(data: &PlayerStatsData) -> PlayerStatsResult<Vec<u8>> {
+    let saved = SavedPlayerStats {
+        version: PLAYER_STATS_FORMAT_VERSION,
+        profiles: data.profiles.clone(),
+    };
+    bincode::serialize(&saved).map_err(|e| PlayerStatsError::Serialization(e.to_string()))
+}
+
+/// Player profiles from saved bytes
+pub fn deserialize_player_stats(bytes: &[u8]) -> PlayerStatsResult<PlayerStatProfiles> {
+    let saved: SavedPlayerStats = bincode::deserialize(bytes)
+        .map_err(|e| PlayerStatsError::Deserialization(e.to_string()))?;
+    if saved.version > PLAYER_STATS_FORMAT_VERSION {
+        return Err(PlayerStatsError::Deserialization(format!(
+            "Unsupported player stats version {}",
+            saved.version
+        )));
+    }
+    Ok(saved.profiles)
+}
+
+/// Write the player profiles into a world slot directory
+pub fn save_player_stats(
+    data: &mut PlayerStatsData,
+    world_dir: &Path,
+    cipher: Option<&SaveCipherData>,
+) -> PlayerStatsResult<()> {
+    let bytes = serialize_player_stats(data)?;
+    write_save_file(cipher, &world_dir.join(PLAYER_STATS_FILE), &bytes)
+        .map_err(|e| PlayerStatsError::Io(e.to_string()))?;
+    data.dirty = false;
+    data.since_save_secs = 0.0;
+    Ok(())
+}
+
+/// Replace the player profiles with those of a world slot directory,
+/// keeping the registered achievements; worlds saved without stats start
+/// with none
+pub fn load_player_stats(
+    data: &mut PlayerStatsData,
+    world_dir: &Path,
+    cipher: Option<&SaveCipherData>,
+) -> PlayerStatsResult<()> {
+    let path = world_dir.join(PLAYER_STATS_FILE);
+    data.profiles = if path.exists() {
+        let bytes =
+            read_save_file(cipher, &path).map_err(|e| PlayerStatsError::Io(e.to_string()))?;
+        deserialize_player_stats(&bytes)?
+    } else {
+        PlayerStatProfiles::new()
+    };
+    data.dirty = false;
+    data.since_save_secs = 0.0;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::player_stats::STAT_BLOCKS_BROKEN;
+
+    fn unlocked(events: &[GameEvent]) -> Vec<&str> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::AchievementUnlocked { achievement, .. } => Some(achievement.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_thresholds_unlock_once() {
+        let mut data = create_player_stats();
+        register_achievement(&mut data, "miner", "Miner", STAT_BLOCKS_BROKEN, 10.0)
+            .expect("new achievement");
+        register_achievement(&mut data, "digger", "Digger", STAT_BLOCKS_BROKEN, 3.0)
+            .expect("new achievement");
+        assert!(
+            register_achievement(&mut data, "miner", "Miner", STAT_BLOCKS_BROKEN, 1.0).is_err()
+        );
+
+        let events = add_player_stat(&mut data, "alice", STAT_BLOCKS_BROKEN, 2.0).expect("valid");
+        assert!(events.is_empty());
+        let events = add_player_stat(&mut data, "alice", STAT_BLOCKS_BROKEN, 9.0).expect("valid");
+        assert_eq!(unlocked(&events), ["miner", "digger"]);
+        let events = add_player_stat(&mut data, "alice", STAT_BLOCKS_BROKEN, 5.0).expect("valid");
+        assert!(events.is_empty());
+        assert!(add_player_stat(&mut data, "alice", STAT_BLOCKS_BROKEN, -1.0).is_err());
+
+        assert_eq!(player_stat(&data, "alice", STAT_BLOCKS_BROKEN), 16.0);
+        assert_eq!(player_achievements(&data, "alice"), ["miner", "digger"]);
+        assert!(!has_achievement(&data, "bob", "miner"));
+
+        // Late achievements unlock for players already past them
+        let events =
+            register_achievement(&mut data, "veteran", "Veteran", STAT_BLOCKS_BROKEN, 15.0)
+                .expect("new achievement");
+        assert_eq!(unlocked(&events), ["veteran"]);
+
+        let progress = achievement_progress(&data, "bob");
+        assert_eq!(progress.len(), 3);
+        assert!(progress.iter().all(|p| !p.unlocked && p.value == 0.0));
+    }
+
+    #[test]
+    fn test_play_time_autosaves_and_reloads() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let mut data = create_player_stats();
+        register_achievement(&mut data, "regular", "Regular", STAT_PLAY_TIME, 90.0)
+            .expect("new achievement");
+
+        let events = tick_player_stats(&mut data, &["alice"], 30.0);
+        assert!(events.is_empty());
+        assert!(!autosave_player_stats(&mut data, dir.path(), None).expect("not due"));
+
+        let events = tick_player_stats(&mut data, &["alice", "bob"], 60.0);
+        assert_eq!(unlocked(&events), ["regular"]);
+        assert!(autosave_player_stats(&mut data, dir.path(), None).expect("saves"));
+        assert!(!player_stats_save_due(&data));
+
+        let mut loaded = create_player_stats();
+        register_achievement(&mut loaded, "regular", "Regular", STAT_PLAY_TIME, 90.0)
+            .expect("new achievement");
+        load_player_stats(&mut loaded, dir.path(), None).expect("loads");
+        assert_eq!(player_stat(&loaded, "alice", STAT_PLAY_TIME), 90.0);
+        assert_eq!(
+            player_stat_entries(&loaded, "bob"),
+            [StatEntry {
+                stat: STAT_PLAY_TIME.to_string(),
+                value: 60.0,
+            }]
+        );
+        assert!(has_achievement(&loaded, "alice", "regular"));
+    }
+}
@@ -0,0 +1,120 @@
+//! Region Claim Data - Protected regions owned by players or groups
+//!
+//! A claim is a box of blocks owned by a player or a named group of
+//! players. On the server, player place/break/interact events pass through
+//! the claims before they are queued: an edit inside a claim by someone
+//! who is not a member becomes `GameEvent::BlockEditDenied`. Admins bypass
+//! every claim and manage them with the `claim` console command. Claims,
+//! groups and admins are saved with the world.
+//!
+//! Pure DOP: No methods, just data structures.
+
+use super::action_limit_data::{BlockAction, BLOCK_ACTION_COUNT};
+use crate::world::core::VoxelPos;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Players in each named group
+pub type ClaimGroups = BTreeMap<String, BTreeSet<u32>>;
+
+/// Id of a claim, stable across saves
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ClaimId(pub u32);
+
+/// Who a claim belongs to or lets in
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ClaimHolder {
+    Player(u32),
+    /// Every member of the named group
+    Group(String),
+}
+
+/// Protected box of blocks, `min` and `max` both inclusive
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionClaim {
+    pub id: ClaimId,
+    pub name: String,
+    pub min: VoxelPos,
+    pub max: VoxelPos,
+    pub owner: ClaimHolder,
+    /// Also allowed to edit, besides the owner
+    pub members: Vec<ClaimHolder>,
+    /// Actions anyone may take inside (e.g. `Interact` for shared doors)
+    pub public_actions: Vec<BlockAction>,
+}
+
+/// Edit refused by a claim
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClaimDenial {
+    pub claim: ClaimId,
+    pub action: BlockAction,
+}
+
+/// Counters for the server console
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClaimStats {
+    /// Edits refused, indexed by `BlockAction as usize`
+    pub denied: [u64; BLOCK_ACTION_COUNT],
+}
+
+/// Claims, groups and admins of one world
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RegionClaimData {
+    /// Claims in creation order; claims never overlap
+    pub claims: Vec<RegionClaim>,
+    /// Next id handed out; ids are never reused
+    pub next_id: u32,
+    pub groups: ClaimGroups,
+    /// Players who bypass every claim
+    pub admins: BTreeSet<u32>,
+    pub stats: ClaimStats,
+}
+
+/// Region claims as stored with the world
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedRegionClaims {
+    pub version: u32,
+    pub next_id: u32,
+    pub claims: Vec<RegionClaim>,
+    pub groups: ClaimGroups,
+    pub admins: BTreeSet<u32>,
+}
+
+/// Region claim errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RegionClaimError {
+    #[error("Unknown claim: {0}")]
+    UnknownClaim(String),
+
+    #[error("Claim already exists: {0}")]
+    ClaimExists(String),
+
+    #[error("Invalid claim or group name: {0:?}")]
+    InvalidName(String),
+
+    #[error("Claim {name} overlaps claim {existing}")]
+    Overlaps { name: String, existing: String },
+
+    #[error("Claim {0} is larger than the claim size limit")]
+    TooLarge(String),
+
+    #[error("Player {0} is not a claim admin")]
+    NotAdmin(u32),
+
+    #[error("Unknown claim command: {0}")]
+    UnknownCommand(String),
+
+    #[error("Invalid claim command argument: {0}")]
+    InvalidArgument(String),
+
+    #[error("Region claim serialization failed: {0}")]
+    Serialization(String),
+
+    #[error("Region claim deserialization failed: {0}")]
+    Deserialization(String),
+
+    #[error("Region claim I/O failed: {0}")]
+    Io(String),
+}
+
+pub type RegionClaimResult<T> = Result<T, RegionClaimError>;
@@ -0,0 +1,663 @@
+//! Region Claim Operations - Pure functions for region protection
+//!
+//! Create claims with `add_region_claim` or the `claim` console command,
+//! then pass every player block event through `protect_block_event` on the
+//! server before it is applied. The gateway does this for every player
+//! block event it queues, and the engine frame applies only the edits that
+//! passed.
+
+use super::action_limit_data::BlockAction;
+use super::action_limit_operations::block_action_of;
+use super::gateway_data::GameEvent;
+use super::region_claim_data::{
+    ClaimDenial, ClaimHolder, ClaimId, RegionClaim, RegionClaimData, RegionClaimError,
+    RegionClaimResult, SavedRegionClaims,
+};
+use crate::constants::persistence_constants::{REGION_CLAIMS_FILE, REGION_CLAIMS_FORMAT_VERSION};
+use crate::constants::region_claims::{MAX_CLAIM_NAME_LEN, MAX_CLAIM_VOLUME};
+use crate::persistence::{read_save_file, write_save_file, SaveCipherData};
+use crate::world::core::VoxelPos;
+use std::path::Path;
+
+/// Create an empty set of claims
+pub fn create_region_claims() -> RegionClaimData {
+    RegionClaimData::default()
+}
+
+fn validate_name(name: &str) -> RegionClaimResult<()> {
+    if name.is_empty()
+        || name.len() > MAX_CLAIM_NAME_LEN
+        || name.chars().any(|c| c.is_control() || c.is_whitespace())
+    {
+        return Err(RegionClaimError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Min and max corner of the blocks between two corners (in any order)
+pub fn claim_bounds(a: VoxelPos, b: VoxelPos) -> (VoxelPos, VoxelPos) {
+    (
+        VoxelPos::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+        VoxelPos::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+    )
+}
+
+/// Blocks inside a claim
+pub fn claim_volume(claim: &RegionClaim) -> u64 {
+    let extent = |min: i32, max: i32| (max as i64 - min as i64 + 1) as u64;
+    extent(claim.min.x, claim.max.x)
+        .saturating_mul(extent(claim.min.y, claim.max.y))
+        .saturating_mul(extent(claim.min.z, claim.max.z))
+}
+
+/// Whether a block lies inside a claim
+pub fn claim_contains(claim: &RegionClaim, position: VoxelPos) -> bool {
+    (claim.min.x..=claim.max.x).contains(&position.x)
+        && (claim.min.y..=claim.max.y).contains(&position.y)
+        && (claim.min.z..=claim.max.z).contains(&position.z)
+}
+
+fn claims_overlap(a: &RegionClaim, b: &RegionClaim) -> bool {
+    a.min.x <= b.max.x
+        && b.min.x <= a.max.x
+        && a.min.y <= b.max.y
+        && b.min.y <= a.max.y
+        && a.min.z <= b.max.z
+        && b.min.z <= a.max.z
+}
+
+/// Claim by id
+pub fn region_claim(data: &RegionClaimData, id: ClaimId) -> Option<&RegionClaim> {
+    data.claims.iter().find(|claim| claim.id == id)
+}
+
+fn region_claim_mut(
+    data: &mut RegionClaimData,
+    id: ClaimId,
+) -> RegionClaimResult<&mut RegionClaim> {
+    data.claims
+        .iter_mut()
+        .find(|claim| claim.id == id)
+        .ok_or_else(|| RegionClaimError::UnknownClaim(id.0.to_string()))
+}
+
+/// Claim by name
+pub fn find_region_claim<'a>(data: &'a RegionClaimData, name: &str) -> Option<&'a RegionClaim> {
+    data.claims.iter().find(|claim| claim.name == name)
+}
+
+/// Claim covering a block, if any (claims never overlap)
+pub fn region_claim_at(data: &RegionClaimData, position: VoxelPos) -> Option<&RegionClaim> {
+    data.claims
+        .iter()
+        .find(|claim| claim_contains(claim, position))
+}
+
+/// Claim the blocks between two corners for `owner`
+pub fn add_region_claim(
+    data: &mut RegionClaimData,
+    name: &str,
+    a: VoxelPos,
+    b: VoxelPos,
+    owner: ClaimHolder,
+) -> RegionClaimResult<ClaimId> {
+    validate_name(name)?;
+    if let ClaimHolder::Group(group) = &owner {
+        validate_name(group)?;
+    }
+    if find_region_claim(data, name).is_some() {
+        return Err(RegionClaimError::ClaimExists(name.to_string()));
+    }
+
+    let (min, max) = claim_bounds(a, b);
+    let claim = RegionClaim {
+        id: ClaimId(data.next_id),
+        name: name.to_string(),
+        min,
+        max,
+        owner,
+        members: Vec::new(),
+        public_actions: Vec::new(),
+    };
+    if claim_volume(&claim) > MAX_CLAIM_VOLUME {
+        return Err(RegionClaimError::TooLarge(name.to_string()));
+    }
+    if let Some(existing) = data
+        .claims
+        .iter()
+        .find(|other| claims_overlap(other, &claim))
+    {
+        return Err(RegionClaimError::Overlaps {
+            name: name.to_string(),
+            existing: existing.name.clone(),
+        });
+    }
+
+    data.next_id += 1;
+    let id = claim.id;
+    data.claims.push(claim);
+    Ok(id)
+}
+
+/// Delete a claim, leaving its blocks unprotected
+pub fn remove_region_claim(data: &mut RegionClaimData, id: ClaimId) -> Option<RegionClaim> {
+    let index = data.claims.iter().position(|claim| claim.id == id)?;
+    Some(data.claims.remove(index))
+}
+
+/// Give a claim to a new owner; its members stay
+pub fn transfer_region_claim(
+    data: &mut RegionClaimData,
+    id: ClaimId,
+    owner: ClaimHolder,
+) -> RegionClaimResult<()> {
+    if let ClaimHolder::Group(group) = &owner {
+        validate_name(group)?;
+    }
+    region_claim_mut(data, id)?.owner = owner;
+    Ok(())
+}
+
+/// Let a player or group edit a claim; returns false if they already could
+pub fn add_claim_member(
+    data: &mut RegionClaimData,
+    id: ClaimId,
+    member: ClaimHolder,
+) -> RegionClaimResult<bool> {
+    if let ClaimHolder::Group(group) = &member {
+        validate_name(group)?;
+    }
+    let claim = region_claim_mut(data, id)?;
+    if claim.owner == member || claim.members.contains(&member) {
+        return Ok(false);
+    }
+    claim.members.push(member);
+    Ok(true)
+}
+
+/// Stop a player or group editing a claim; returns false if they were no member
+pub fn remove_claim_member(
+    data: &mut RegionClaimData,
+    id: ClaimId,
+    member: &ClaimHolder,
+) -> RegionClaimResult<bool> {
+    let claim = region_claim_mut(data, id)?;
+    let before = claim.members.len();
+    claim.members.retain(|existing| existing != member);
+    Ok(claim.members.len() != before)
+}
+
+/// Allow or refuse an action inside a claim to everyone
+pub fn set_claim_public_action(
+    data: &mut RegionClaimData,
+    id: ClaimId,
+    action: BlockAction,
+    public: bool,
+) -> RegionClaimResult<()> {
+    let claim = region_claim_mut(data, id)?;
+    claim.public_actions.retain(|existing| *existing != action);
+    if public {
+        claim.public_actions.push(action);
+    }
+    Ok(())
+}
+
+/// Add a player to a group, creating it; returns false if already in it
+pub fn add_group_member(
+    data: &mut RegionClaimData,
+    group: &str,
+    player: u32,
+) -> RegionClaimResult<bool> {
+    validate_name(group)?;
+    Ok(data
+        .groups
+        .entry(group.to_string())
+        .or_default()
+        .insert(player))
+}
+
+/// Remove a player from a group, dropping the group once empty
+pub fn remove_group_member(data: &mut RegionClaimData, group: &str, player: u32) -> bool {
+    let Some(members) = data.groups.get_mut(group) else {
+        return false;
+    };
+    let removed = members.remove(&player);
+    if members.is_empty() {
+        data.groups.remove(group);
+    }
+    removed
+}
+
+/// Grant or revoke claim admin rights
+pub fn set_claim_admin(data: &mut RegionClaimData, player: u32, admin: bool) {
+    if admin {
+        data.admins.insert(player);
+    } else {
+        data.admins.remove(&player);
+    }
+}
+
+/// Whether a player is the holder or, for a group, one of its members
+pub fn holder_includes(data: &RegionClaimData, holder: &ClaimHolder, player: u32) -> bool {
+    match holder {
+        ClaimHolder::Player(id) => *id == player,
+        ClaimHolder::Group(group) => data
+            .groups
+            .get(group)
+            .is_some_and(|members| members.contains(&player)),
+    }
+}
+
+/// Whether a player owns or is a member of a claim
+pub fn is_claim_member(data: &RegionClaimData, claim: &RegionClaim, player: u32) -> bool {
+    holder_includes(data, &claim.owner, player)
+        || claim
+            .members
+            .iter()
+            .any(|member| holder_includes(data, member, player))
+}
+
+/// Check a player's action on a block against the claims
+pub fn check_block_edit(
+    data: &RegionClaimData,
+    player: u32,
+    action: BlockAction,
+    position: VoxelPos,
+) -> Result<(), ClaimDenial> {
+    if data.admins.contains(&player) {
+        return Ok(());
+    }
+    match region_claim_at(data, position) {
+        Some(claim)
+            if !claim.public_actions.contains(&action) && !is_claim_member(data, claim, player) =>
+        {
+            Err(ClaimDenial {
+                claim: claim.id,
+                action,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn event_position(event: &GameEvent) -> Option<VoxelPos> {
+    match event {
+        GameEvent::BlockPlace { position, .. }
+        | GameEvent::BlockBreak { position, .. }
+        | GameEvent::BlockInteract { position, .. } => Some(*position),
+        _ => None,
+    }
+}
+
+/// Pass a player's block event through the claims: a refused edit comes
+/// back as `GameEvent::BlockEditDenied`, anything else unchanged
+pub fn protect_block_event(data: &mut RegionClaimData, event: GameEvent) -> GameEvent {
+    let (Some((player_id, action)), Some(position)) =
+        (block_action_of(&event), event_position(&event))
+    else {
+        return event;
+    };
+    match check_block_edit(data, player_id, action, position) {
+        Ok(()) => event,
+        Err(denial) => {
+            data.stats.denied[action as usize] += 1;
+            GameEvent::BlockEditDenied {
+                player_id,
+                action,
+                position,
+                claim: denial.claim,
+            }
+        }
+    }
+}
+
+fn format_holder(holder: &ClaimHolder) -> String {
+    match holder {
+        ClaimHolder::Player(id) => format!("player:{}", id),
+        ClaimHolder::Group(group) => format!("group:{}", group),
+    }
+}
+
+fn parse_holder(value: &str) -> RegionClaimResult<ClaimHolder> {
+    let invalid = || RegionClaimError::InvalidArgument(value.to_string());
+    match value.split_once(':') {
+        Some(("player", id)) => id.parse().map(ClaimHolder::Player).map_err(|_| invalid()),
+        Some(("group", group)) if !group.is_empty() => Ok(ClaimHolder::Group(group.to_string())),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> RegionClaimResult<T> {
+    value
+        .parse()
+        .map_err(|_| RegionClaimError::InvalidArgument(value.to_string()))
+}
+
+fn parse_action(value: &str) -> RegionClaimResult<BlockAction> {
+    match value {
+        "place" => Ok(BlockAction::Place),
+        "break" => Ok(BlockAction::Break),
+        "interact" => Ok(BlockAction::Interact),
+        _ => Err(RegionClaimError::InvalidArgument(value.to_string())),
+    }
+}
+
+fn claim_id_by_name(data: &RegionClaimData, name: &str) -> RegionClaimResult<ClaimId> {
+    find_region_claim(data, name)
+        .map(|claim| claim.id)
+        .ok_or_else(|| RegionClaimError::UnknownClaim(name.to_string()))
+}
+
+/// One-line description of a claim
+pub fn format_region_claim(claim: &RegionClaim) -> String {
+    let members: Vec<String> = claim.members.iter().map(format_holder).collect();
+    format!(
+        "{} ({}, {}, {}) to ({}, {}, {}) owner {} members [{}]",
+        claim.name,
+        claim.min.x,
+        claim.min.y,
+        claim.min.z,
+        claim.max.x,
+        claim.max.y,
+        claim.max.z,
+        format_holder(&claim.owner),
+        members.join(", ")
+    )
+}
+
+/// Run a `claim` admin command and return the text to print. `issuer` is
+/// the player running it, None for the server console; players must be
+/// claim admins.
+///
+/// - `claim list` / `claim info <name>`
+/// - `claim create <name> <x1> <y1> <z1> <x2> <y2> <z2> <owner>`
+/// - `claim transfer <name> <owner>` / `claim delete <name>`
+/// - `claim member add|remove <name> <holder>`
+/// - `claim public place|break|interact on|off <name>`
+/// - `claim group add|remove <group> <player>`
+/// - `claim admin add|remove <player>`
+///
+/// Owners and holders are written `player:<id>` or `group:<name>`.
+pub fn execute_claim_command(
+    data: &mut RegionClaimData,
+    issuer: Option<u32>,
+    command: &str,
+) -> RegionClaimResult<String> {
+    if let Some(player) = issuer {
+        if !data.admins.contains(&player) {
+            return Err(RegionClaimError::NotAdmin(player));
+        }
+    }
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let args = match words.first() {
+        Some(&"claim") => &words[1..],
+        _ => &words[..],
+    };
+
+    match args {
+        ["list"] | [] => Ok(if data.claims.is_empty() {
+            crate::tr!("console.claim.none")
+        } else {
+            data.claims
+                .iter()
+                .map(format_region_claim)
+                .collect::<Vec<_>>()
+                .join("\n")
+        }),
+        ["info", name] => find_region_claim(data, name)
+            .map(format_region_claim)
+            .ok_or_else(|| RegionClaimError::UnknownClaim(name.to_string())),
+        ["create", name, x1, y1, z1, x2, y2, z2, owner] => {
+            let a = VoxelPos::new(parse_number(x1)?, parse_number(y1)?, parse_number(z1)?);
+            let b = VoxelPos::new(parse_number(x2)?, parse_number(y2)?, parse_number(z2)?);
+            let owner = parse_holder(owner)?;
+            add_region_claim(data, name, a, b, owner)?;
+            Ok(crate::tr!("console.claim.created", name = name))
+        }
+        ["transfer", name, owner] => {
+            let id = claim_id_by_name(data, name)?;
+            let owner = parse_holder(owner)?;
+            let text = crate::tr!(
+                "console.claim.transferred",
+                name = name,
+                owner = format_holder(&owner),
+            );
+            transfer_region_claim(data, id, owner)?;
+            Ok(text)
+        }
+        ["delete", name] => {
+            let id = claim_id_by_name(data, name)?;
+            remove_region_claim(data, id);
+            Ok(crate::tr!("console.claim.deleted", name = name))
+        }
+        ["member", "add", name, holder] => {
+            let id = claim_id_by_name(data, name)?;
+            let holder = parse_holder(holder)?;
+            let text = format_holder(&holder);
+            let key = if add_claim_member(data, id, holder)? {
+                "console.claim.member_added"
+            } else {
+                "console.claim.member_existing"
+            };
+            Ok(crate::tr!(key, holder = text, name = name))
+        }
+        ["member", "remove", name, holder] => {
+            let id = claim_id_by_name(data, name)?;
+            let holder = parse_holder(holder)?;
+            let key = if remove_claim_member(data, id, &holder)? {
+                "console.claim.member_removed"
+            } else {
+                "console.claim.member_missing"
+            };
+            Ok(crate::tr!(
+                key,
+                holder = format_holder(&holder),
+                name = name
+            ))
+        }
+        ["public", action, state @ ("on" | "off"), name] => {
+            let id = claim_id_by_name(data, name)?;
+            set_claim_public_action(data, id, parse_action(action)?, *state == "on")?;
+            Ok(crate::tr!(
+                "console.claim.public",
+                action = action,
+                name = name,
+                state = state,
+            ))
+        }
+        ["group", "add", group, player] => {
+            add_group_member(data, group, parse_number(player)?)?;
+            Ok(crate::tr!(
+                "console.claim.group_joined",
+                player = player,
+                group = group
+            ))
+        }
+        ["group", "remove", group, player] => {
+            remove_group_member(data, group, parse_number(player)?);
+            Ok(crate::tr!(
+                "console.claim.group_left",
+                player = player,
+                group = group
+            ))
+        }
+        ["admin", change @ ("add" | "remove"), player] => {
+            set_claim_admin(data, parse_number(player)?, *change == "add");
+            let key = if *change == "add" {
+                "console.claim.admin_added"
+            } else {
+                "console.claim.admin_removed"
+            };
+            Ok(crate::tr!(key, player = player))
+        }
+        _ => Err(RegionClaimError::UnknownCommand(command.trim().to_string())),
+    }
+}
+
+// ============================================================================
+// PERSISTENCE
+// ============================================================================
+
+/// Serialize claims, groups and admins for saving with the world
+pub fn serialize_region_claims(data: &RegionClaimData) -> RegionClaimResult<Vec<u8>> {
+    let saved = SavedRegionClaims {
+        version: REGION_CLAIMS_FORMAT_VERSION,
+        next_id: data.next_id,
+        claims: data.claims.clone(),
+        groups: data.groups.clone(),
+        admins: data.admins.clone(),
+    };
+    bincode::serialize(&saved).map_err(|e| RegionClaimError::Serialization(e.to_string()))
+}
+
+/// Restore saved claims
+pub fn deserialize_region_claims(bytes: &[u8]) -> RegionClaimResult<RegionClaimData> {
+    let saved: SavedRegionClaims = bincode::deserialize(bytes)
+        .map_err(|e| RegionClaimError::Deserialization(e.to_string()))?;
+    if saved.version > REGION_CLAIMS_FORMAT_VERSION {
+        return Err(RegionClaimError::Deserialization(format!(
+            "Unsupported region claim version {}",
+            saved.version
+        )));
+    }
+    if let Some(claim) = saved
+        .claims
+        .iter()
+        .find(|claim| claim.id.0 >= saved.next_id)
+    {
+        return Err(RegionClaimError::Deserialization(format!(
+            "Claim id of {} out of range",
+            claim.name
+        )));
+    }
+    Ok(RegionClaimData {
+        claims: saved.claims,
+        next_id: saved.next_id,
+        groups: saved.groups,
+        admins: saved.admins,
+        ..RegionClaimData::default()
+    })
+}
+
+/// Write the claims into a world slot directory
+pub fn save_region_claims(
+    data: &RegionClaimData,
+    world_dir: &Path,
+    cipher: Option<&SaveCipherData>,
+) -> RegionClaimResult<()> {
+    let bytes = serialize_region_claims(data)?;
+    write_save_file(cipher, &world_dir.join(REGION_CLAIMS_FILE), &bytes)
+        .map_err(|e| RegionClaimError::Io(e.to_string()))
+}
+
+/// Read the claims of a world slot directory; worlds saved without any
+/// get an empty set
+pub fn load_region_claims(
+    world_dir: &Path,
+    cipher: Option<&SaveCipherData>,
+) -> RegionClaimResult<RegionClaimData> {
+    let path = world_dir.join(REGION_CLAIMS_FILE);
+    if !path.exists() {
+        return Ok(create_region_claims());
+    }
+    let bytes = read_save_file(cipher, &path).map_err(|e| RegionClaimError::Io(e.to_string()))?;
+    deserialize_region_claims(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::core::BlockId;
+
+    fn edit(player: u32, x: i32) -> GameEvent {
+        GameEvent::BlockBreak {
+            position: VoxelPos::new(x, 64, 0),
+            block_id: BlockId(1),
+            player_id: Some(player),
+        }
+    }
+
+    fn allowed(data: &mut RegionClaimData, event: GameEvent) -> bool {
+        !matches!(
+            protect_block_event(data, event),
+            GameEvent::BlockEditDenied { .. }
+        )
+    }
+
+    #[test]
+    fn test_claims_refuse_non_members() {
+        let mut data = create_region_claims();
+        execute_claim_command(
+            &mut data,
+            None,
+            "claim create base 0 0 0 15 127 15 player:1",
+        )
+        .expect("create");
+        assert!(matches!(
+            execute_claim_command(
+                &mut data,
+                None,
+                "claim create other 10 0 10 20 10 20 player:2"
+            ),
+            Err(RegionClaimError::Overlaps { .. })
+        ));
+        assert!(matches!(
+            execute_claim_command(&mut data, Some(2), "claim delete base"),
+            Err(RegionClaimError::NotAdmin(2))
+        ));
+
+        assert!(allowed(&mut data, edit(1, 4)));
+        assert!(allowed(&mut data, edit(2, 40)));
+        assert!(matches!(
+            protect_block_event(&mut data, edit(2, 4)),
+            GameEvent::BlockEditDenied {
+                player_id: 2,
+                action: BlockAction::Break,
+                ..
+            }
+        ));
+
+        // Members through a group, then the claim changes hands
+        execute_claim_command(&mut data, None, "claim group add builders 2").expect("group");
+        execute_claim_command(&mut data, None, "claim member add base group:builders")
+            .expect("member");
+        assert!(allowed(&mut data, edit(2, 4)));
+        execute_claim_command(&mut data, None, "claim transfer base player:3").expect("transfer");
+        assert!(!allowed(&mut data, edit(1, 4)));
+        assert_eq!(data.stats.denied[BlockAction::Break as usize], 2);
+
+        execute_claim_command(&mut data, None, "claim admin add 1").expect("admin");
+        assert!(allowed(&mut data, edit(1, 4)));
+        execute_claim_command(&mut data, Some(1), "claim delete base").expect("delete");
+        assert!(data.claims.is_empty());
+    }
+
+    #[test]
+    fn test_claims_round_trip() {
+        let mut data = create_region_claims();
+        let id = add_region_claim(
+            &mut data,
+            "spawn",
+            VoxelPos::new(10, 0, 10),
+            VoxelPos::new(-10, 50, -10),
+            ClaimHolder::Group("staff".to_string()),
+        )
+        .expect("claim");
+        set_claim_public_action(&mut data, id, BlockAction::Interact, true).expect("public");
+        add_group_member(&mut data, "staff", 9).expect("group");
+        set_claim_admin(&mut data, 9, true);
+
+        let bytes = serialize_region_claims(&data).expect("serialize");
+        let loaded = deserialize_region_claims(&bytes).expect("deserialize");
+        assert_eq!(loaded, data);
+        assert_eq!(
+            region_claim_at(&loaded, VoxelPos::new(-10, 0, 10)).map(|claim| claim.id),
+            Some(id)
+        );
+        assert_eq!(
+            check_block_edit(&loaded, 4, BlockAction::Interact, VoxelPos::new(0, 0, 0)),
+            Ok(())
+        );
+        assert!(check_block_edit(&loaded, 4, BlockAction::Place, VoxelPos::new(0, 0, 0)).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+//! Scoreboard Data - Named objectives with per-player scores
+//!
+//! The server owns the scoreboard and records every change as a delta.
+//! Deltas are published in numbered updates that clients apply in order; a
+//! client that misses one asks for a full snapshot instead. Scores are keyed
+//! by player name so they survive reconnects and world reloads.
+//!
+//! Pure DOP: No methods, just data structures.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Prefix of encoded scoreboard updates
+pub const SCOREBOARD_MAGIC: [u8; 4] = *b"SCBD";
+
+/// Which end of an objective ranks first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScoreOrder {
+    /// Highest score first (kills, points)
+    Descending,
+    /// Lowest score first (lap times, deaths)
+    Ascending,
+}
+
+/// Named objective and the scores of every player on it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Objective {
+    pub name: String,
+    /// Title shown by leaderboard UIs
+    pub display_name: String,
+    pub order: ScoreOrder,
+    pub scores: HashMap<String, i64>,
+}
+
+/// One scoreboard change
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ScoreboardDelta {
+    /// Objective created, or its display name or order changed
+    ObjectiveAdded {
+        name: String,
+        display_name: String,
+        order: ScoreOrder,
+    },
+    ObjectiveRemoved {
+        name: String,
+    },
+    ScoreSet {
+        objective: String,
+        player: String,
+        score: i64,
+    },
+    ScoreReset {
+        objective: String,
+        player: String,
+    },
+}
+
+/// Batch of deltas sent to clients
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScoreboardUpdate {
+    /// Revision the deltas apply on top of (ignored for snapshots)
+    pub base_revision: u64,
+    /// Revision after applying the deltas
+    pub revision: u64,
+    /// Replaces the whole scoreboard instead of patching it
+    pub snapshot: bool,
+    pub deltas: Vec<ScoreboardDelta>,
+}
+
+/// One row of a leaderboard
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LeaderboardEntry {
+    /// 1-based; tied scores share a rank
+    pub rank: u32,
+    pub player: String,
+    pub score: i64,
+}
+
+/// Scoreboard of one world, on the server or mirrored on a client
+#[derive(Clone, Debug, Default)]
+pub struct ScoreboardData {
+    /// Objectives in creation order
+    pub objectives: Vec<Objective>,
+    /// Last published (server) or applied (client) revision
+    pub revision: u64,
+    /// Server changes not yet published
+    pub pending: Vec<ScoreboardDelta>,
+    /// Too many changes piled up; the next update is a snapshot
+    pub needs_snapshot: bool,
+}
+
+/// Scoreboard errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ScoreboardError {
+    #[error("Unknown objective: {0}")]
+    UnknownObjective(String),
+
+    #[error("Objective already exists: {0}")]
+    ObjectiveExists(String),
+
+    #[error("Invalid name: {0:?}")]
+    InvalidName(String),
+
+    #[error("Scoreboard update skips revisions (at {current}, update starts at {base})")]
+    RevisionGap { current: u64, base: u64 },
+
+    #[error("Scoreboard serialization failed: {0}")]
+    Serialization(String),
+
+    #[error("Scoreboard deserialization failed: {0}")]
+    Deserialization(String),
+
+    #[error("Scoreboard I/O failed: {0}")]
+    Io(String),
+}
+
+pub type ScoreboardResult<T> = Result<T, ScoreboardError>;
+
+/// Scoreboard as stored with the world
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedScoreboard {
+    pub version: u32,
+    pub objectives: Vec<Objective>,
+}
@@ -0,0 +1,498 @@
+//! Scoreboard Operations - Pure functions for objective scoreboards
+//!
+//! Server: mutate with `add_objective`, `set_score`, ..., then once per tick
+//! publish with `replicate_scoreboard` (or `take_scoreboard_update` for
+//! custom transports). Send `scoreboard_snapshot` to joining clients.
+//! Client: feed received packets to `apply_scoreboard_update`; on
+//! `RevisionGap` ask the server for a new snapshot.
+
+use super::scoreboard_data::{
+    LeaderboardEntry, Objective, SavedScoreboard, ScoreOrder, ScoreboardData, ScoreboardDelta,
+    ScoreboardError, ScoreboardResult, ScoreboardUpdate, SCOREBOARD_MAGIC,
+};
+use crate::constants::network_constants::SCOREBOARD_PACKET_TYPE;
+use crate::constants::persistence_constants::{SCOREBOARD_FILE, SCOREBOARD_FORMAT_VERSION};
+use crate::constants::scoreboard::{MAX_PENDING_SCORE_DELTAS, MAX_SCOREBOARD_NAME_LEN};
+use crate::network::{queue_packet, Connection, SendPriority};
+use crate::persistence::{read_save_file, write_save_file, SaveCipherData};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Create an empty scoreboard
+pub fn create_scoreboard() -> ScoreboardData {
+    ScoreboardData::default()
+}
+
+fn validate_name(name: &str) -> ScoreboardResult<()> {
+    if name.is_empty() || name.len() > MAX_SCOREBOARD_NAME_LEN || name.chars().any(char::is_control)
+    {
+        return Err(ScoreboardError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Objective by name
+pub fn find_objective<'a>(board: &'a ScoreboardData, name: &str) -> Option<&'a Objective> {
+    board
+        .objectives
+        .iter()
+        .find(|objective| objective.name == name)
+}
+
+fn require_objective(board: &ScoreboardData, name: &str) -> ScoreboardResult<()> {
+    match find_objective(board, name) {
+        Some(_) => Ok(()),
+        None => Err(ScoreboardError::UnknownObjective(name.to_string())),
+    }
+}
+
+/// Apply one delta. Deltas for unknown objectives are skipped: a client
+/// that got a snapshot may replay changes to objectives removed since.
+fn apply_delta(objectives: &mut Vec<Objective>, delta: &ScoreboardDelta) {
+    let find = |objectives: &mut Vec<Objective>, name: &str| {
+        objectives
+            .iter_mut()
+            .position(|objective| objective.name == name)
+    };
+    match delta {
+        ScoreboardDelta::ObjectiveAdded {
+            name,
+            display_name,
+            order,
+        } => match find(objectives, name) {
+            Some(index) => {
+                objectives[index].display_name = display_name.clone();
+                objectives[index].order = *order;
+            }
+            None => objectives.push(Objective {
+                name: name.clone(),
+                display_name: display_name.clone(),
+                order: *order,
+                scores: HashMap::new(),
+            }),
+        },
+        ScoreboardDelta::ObjectiveRemoved { name } => {
+            objectives.retain(|objective| &objective.name != name);
+        }
+        ScoreboardDelta::ScoreSet {
+            objective,
+            player,
+            score,
+        } => {
+            if let Some(index) = find(objectives, objective) {
+                objectives[index].scores.insert(player.clone(), *score);
+            }
+        }
+        ScoreboardDelta::ScoreReset { objective, player } => {
+            if let Some(index) = find(objectives, objective) {
+                objectives[index].scores.remove(player);
+            }
+        }
+    }
+}
+
+/// Apply a server change and queue it for the next update
+fn record_delta(board: &mut ScoreboardData, delta: ScoreboardDelta) {
+    apply_delta(&mut board.objectives, &delta);
+    if board.needs_snapshot {
+        return;
+    }
+    if board.pending.len() >= MAX_PENDING_SCORE_DELTAS {
+        board.pending.clear();
+        board.needs_snapshot = true;
+        return;
+    }
+    board.pending.push(delta);
+}
+
+// ============================================================================
+// SERVER API
+// ============================================================================
+
+/// Create an objective
+pub fn add_objective(
+    board: &mut ScoreboardData,
+    name: &str,
+    display_name: &str,
+    order: ScoreOrder,
+) -> ScoreboardResult<()> {
+    validate_name(name)?;
+    if find_objective(board, name).is_some() {
+        return Err(ScoreboardError::ObjectiveExists(name.to_string()));
+    }
+    record_delta(
+        board,
+        ScoreboardDelta::ObjectiveAdded {
+            name: name.to_string(),
+            display_name: display_name.to_string(),
+            order,
+        },
+    );
+    Ok(())
+}
+
+/// Remove an objective and all its scores
+pub fn remove_objective(board: &mut ScoreboardData, name: &str) -> ScoreboardResult<()> {
+    require_objective(board, name)?;
+    record_delta(
+        board,
+        ScoreboardDelta::ObjectiveRemoved {
+            name: name.to_string(),
+        },
+    );
+    Ok(())
+}
+
+/// Set a player's score
+pub fn set_score(
+    board: &mut ScoreboardData,
+    objective: &str,
+    player: &str,
+    score: i64,
+) -> ScoreboardResult<()> {
+    require_objective(board, objective)?;
+    validate_name(player)?;
+    if get_score(board, objective, player) == Some(score) {
+        return Ok(());
+    }
+    record_delta(
+        board,
+        ScoreboardDelta::ScoreSet {
+            objective: objective.to_string(),
+            player: player.to_string(),
+            score,
+        },
+    );
+    Ok(())
+}
+
+/// Add to a player's score (missing scores start at 0), returning the new score
+pub fn add_score(
+    board: &mut ScoreboardData,
+    objective: &str,
+    player: &str,
+    amount: i64,
+) -> ScoreboardResult<i64> {
+    let score = get_score(board, objective, player)
+        .unwrap_or(0)
+        .saturating_add(amount);
+    set_score(board, objective, player, score)?;
+    Ok(score)
+}
+
+/// Remove a player's score from an objective. Returns whether it had one.
+pub fn reset_score(
+    board: &mut ScoreboardData,
+    objective: &str,
+    player: &str,
+) -> ScoreboardResult<bool> {
+    require_objective(board, objective)?;
+    if get_score(board, objective, player).is_none() {
+        return Ok(false);
+    }
+    record_delta(
+        board,
+        ScoreboardDelta::ScoreReset {
+            objective: objective.to_string(),
+            player: player.to_string(),
+        },
+    );
+    Ok(true)
+}
+
+/// Remove a player's scores from every objective, returning how many
+pub fn reset_player_scores(board: &mut ScoreboardData, player: &str) -> usize {
+    let objectives: Vec<String> = board
+        .objectives
+        .iter()
+        .filter(|objective| objective.scores.contains_key(player))
+        .map(|objective| objective.name.clone())
+        .collect();
+    for objective in &objectives {
+        record_delta(
+            board,
+            ScoreboardDelta::ScoreReset {
+                objective: objective.clone(),
+                player: player.to_string(),
+            },
+        );
+    }
+    objectives.len()
+}
+
+// ============================================================================
+// QUERIES
+// ============================================================================
+
+/// A player's score, `None` if unset or the objective does not exist
+pub fn get_score(board: &ScoreboardData, objective: &str, player: &str) -> Option<i64> {
+    find_objective(board, objective).and_then(|objective| objective.scores.get(player).copied())
+}
+
+/// Top `limit` scores of an objective in its order. Ties share a rank and
+/// are listed by player name.
+pub fn leaderboard(board: &ScoreboardData, objective: &str, limit: usize) -> Vec<LeaderboardEntry> {
+    let Some(objective) = find_objective(board, objective) else {
+        return Vec::new();
+    };
+
+    let mut players: Vec<&String> = objective.scores.keys().collect();
+    players.sort_by(|a, b| {
+        let (score_a, score_b) = (objective.scores[*a], objective.scores[*b]);
+        let by_score = match objective.order {
+            ScoreOrder::Descending => score_b.cmp(&score_a),
+            ScoreOrder::Ascending => score_a.cmp(&score_b),
+        };
+        by_score.then_with(|| a.cmp(b))
+    });
+
+    let mut entries: Vec<LeaderboardEntry> = Vec::with_capacity(limit.min(players.len()));
+    for (index, player) in players.into_iter().take(limit).enumerate() {
+        let score = objective.scores[player];
+        let rank = match entries.last() {
+            Some(previous) if previous.score == score => previous.rank,
+            _ => index as u32 + 1,
+        };
+        entries.push(LeaderboardEntry {
+            rank,
+            player: player.clone(),
+            score,
+        });
+    }
+    entries
+}
+
+// ============================================================================
+// REPLICATION
+// ============================================================================
+
+/// Full state as an update, for clients that just joined or fell behind
+pub fn scoreboard_snapshot(board: &ScoreboardData) -> ScoreboardUpdate {
+    let mut deltas = Vec::new();
+    for objective in &board.objectives {
+        deltas.push(ScoreboardDelta::ObjectiveAdded {
+            name: objective.name.clone(),
+            display_name: objective.display_name.clone(),
+            order: objective.order,
+        });
+        let mut players: Vec<&String> = objective.scores.keys().collect();
+        players.sort();
+        for player in players {
+            deltas.push(ScoreboardDelta::ScoreSet {
+                objective: objective.name.clone(),
+                player: player.clone(),
+                score: objective.scores[player],
+            });
+        }
+    }
+    ScoreboardUpdate {
+        base_revision: board.revision,
+        revision: board.revision,
+        snapshot: true,
+        deltas,
+    }
+}
+
+/// Publish changes since the last update as the next revision. `None` when
+/// nothing changed.
+pub fn take_scoreboard_update(board: &mut ScoreboardData) -> Option<ScoreboardUpdate> {
+    if board.needs_snapshot {
+        board.needs_snapshot = false;
+        board.pending.clear();
+        board.revision += 1;
+        return Some(scoreboard_snapshot(board));
+    }
+    if board.pending.is_empty() {
+        return None;
+    }
+
+    let base_revision = board.revision;
+    board.revision += 1;
+    Some(ScoreboardUpdate {
+        base_revision,
+        revision: board.revision,
+        snapshot: false,
+        deltas: std::mem::take(&mut board.pending),
+    })
+}
+
+/// Apply an update received from the server. Deltas must arrive in order;
+/// on `RevisionGap` the client keeps its state and needs a snapshot.
+pub fn apply_scoreboard_update(
+    board: &mut ScoreboardData,
+    update: &ScoreboardUpdate,
+) -> ScoreboardResult<()> {
+    if update.snapshot {
+        board.objectives.clear();
+    } else if update.base_revision != board.revision {
+        return Err(ScoreboardError::RevisionGap {
+            current: board.revision,
+            base: update.base_revision,
+        });
+    }
+
+    for delta in &update.deltas {
+        apply_delta(&mut board.objectives, delta);
+    }
+    board.revision = update.revision;
+    Ok(())
+}
+
+/// Encode an update for the network
+pub fn encode_scoreboard_update(update: &ScoreboardUpdate) -> ScoreboardResult<Vec<u8>> {
+    let body =
+        bincode::serialize(update).map_err(|e| ScoreboardError::Serialization(e.to_string()))?;
+    let mut bytes = Vec::with_capacity(SCOREBOARD_MAGIC.len() + body.len());
+    bytes.extend_from_slice(&SCOREBOARD_MAGIC);
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+}
+
+/// Decode an update received from the network
+pub fn decode_scoreboard_update(bytes: &[u8]) -> ScoreboardResult<ScoreboardUpdate> {
+    let body = bytes
+        .strip_prefix(&SCOREBOARD_MAGIC[..])
+        .ok_or_else(|| ScoreboardError::Deserialization("Missing message magic".to_string()))?;
+    bincode::deserialize(body).map_err(|e| ScoreboardError::Deserialization(e.to_string()))
+}
+
+/// Publish pending changes to every connection. Returns whether an update
+/// was sent.
+pub fn replicate_scoreboard(
+    board: &mut ScoreboardData,
+    connections: &mut [Connection],
+) -> ScoreboardResult<bool> {
+    let Some(update) = take_scoreboard_update(board) else {
+        return Ok(false);
+    };
+    let bytes = encode_scoreboard_update(&update)?;
+    for connection in connections {
+        queue_packet(
+            connection,
+            SendPriority::BlockUpdate,
+            SCOREBOARD_PACKET_TYPE,
+            bytes.clone(),
+        );
+    }
+    Ok(true)
+}
+
+// ============================================================================
+// PERSISTENCE
+// ============================================================================
+
+/// Serialize the scoreboard for saving with the world
+pub fn serialize_scoreboard(board: &ScoreboardData) -> ScoreboardResult<Vec<u8>> {
+    let saved = SavedScoreboard {
+        version: SCOREBOARD_FORMAT_VERSION,
+        objectives: board.objectives.clone(),
+    };
+    bincode::serialize(&saved).map_err(|e| ScoreboardError::Serialization(e.to_string()))
+}
+
+/// Restore a saved scoreboard. Revisions restart, so clients need snapshots.
+pub fn deserialize_scoreboard(bytes: &[u8]) -> ScoreboardResult<ScoreboardData> {
+    let saved: SavedScoreboard =
+        bincode::deserialize(bytes).map_err(|e| ScoreboardError::Deserialization(e.to_string()))?;
+    if saved.version > SCOREBOARD_FORMAT_VERSION {
+        return Err(ScoreboardError::Deserialization(format!(
+            "Unsupported scoreboard version {}",
+            saved.version
+        )));
+    }
+    Ok(ScoreboardData {
+        objectives: saved.objectives,
+        ..ScoreboardData::default()
+    })
+}
+
+/// Write the scoreboard into a world slot directory
+pub fn save_scoreboard(
+    board: &ScoreboardData,
+    world_dir: &Path,
+    cipher: Option<&SaveCipherData>,
+) -> ScoreboardResult<()> {
+    let bytes = serialize_scoreboard(board)?;
+    write_save_file(cipher, &world_dir.join(SCOREBOARD_FILE), &bytes)
+        .map_err(|e| ScoreboardError::Io(e.to_string()))
+}
+
+/// Read the scoreboard of a world slot directory; worlds saved without one
+/// get an empty scoreboard
+pub fn load_scoreboard(
+    world_dir: &Path,
+    cipher: Option<&SaveCipherData>,
+) -> ScoreboardResult<ScoreboardData> {
+    let path = world_dir.join(SCOREBOARD_FILE);
+    if !path.exists() {
+        return Ok(create_scoreboard());
+    }
+    let bytes = read_save_file(cipher, &path).map_err(|e| ScoreboardError::Io(e.to_string()))?;
+    deserialize_scoreboard(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_board() -> ScoreboardData {
+        let mut board = create_scoreboard();
+        add_objective(&mut board, "kills", "Kills", ScoreOrder::Descending).expect("new objective");
+        set_score(&mut board, "kills", "alice", 7).expect("known objective");
+        set_score(&mut board, "kills", "bob", 9).expect("known objective");
+        set_score(&mut board, "kills", "carol", 7).expect("known objective");
+        board
+    }
+
+    #[test]
+    fn test_deltas_replicate_in_order() {
+        let mut server = server_board();
+        let mut client = create_scoreboard();
+
+        let first = take_scoreboard_update(&mut server).expect("pending changes");
+        let bytes = encode_scoreboard_update(&first).expect("encodes");
+        let decoded = decode_scoreboard_update(&bytes).expect("decodes");
+        apply_scoreboard_update(&mut client, &decoded).expect("in order");
+        assert!(take_scoreboard_update(&mut server).is_none());
+
+        let board = leaderboard(&client, "kills", 10);
+        assert_eq!(board.len(), 3);
+        assert_eq!((board[0].rank, board[0].player.as_str()), (1, "bob"));
+        assert_eq!((board[1].rank, board[1].player.as_str()), (2, "alice"));
+        assert_eq!((board[2].rank, board[2].player.as_str()), (2, "carol"));
+
+        // A missed update is detected and a snapshot recovers
+        add_score(&mut server, "kills", "alice", 5).expect("known objective");
+        let _lost = take_scoreboard_update(&mut server);
+        reset_score(&mut server, "kills", "bob").expect("known objective");
+        let next = take_scoreboard_update(&mut server).expect("pending changes");
+        assert!(matches!(
+            apply_scoreboard_update(&mut client, &next),
+            Err(ScoreboardError::RevisionGap { .. })
+        ));
+        apply_scoreboard_update(&mut client, &scoreboard_snapshot(&server)).expect("snapshot");
+        assert_eq!(get_score(&client, "kills", "alice"), Some(12));
+        assert_eq!(get_score(&client, "kills", "bob"), None);
+        assert_eq!(client.revision, server.revision);
+    }
+
+    #[test]
+    fn test_scoreboard_persists_with_world() {
+        let board = server_board();
+        let dir = tempfile::tempdir().expect("temp dir");
+        assert!(load_scoreboard(dir.path(), None)
+            .expect("missing file is empty")
+            .objectives
+            .is_empty());
+
+        save_scoreboard(&board, dir.path(), None).expect("saves");
+        let loaded = load_scoreboard(dir.path(), None).expect("loads");
+        assert_eq!(loaded.objectives, board.objectives);
+        assert_eq!(loaded.revision, 0);
+        assert!(loaded.pending.is_empty());
+        assert!(matches!(
+            add_objective(&mut create_scoreboard(), "", "Empty", ScoreOrder::Ascending),
+            Err(ScoreboardError::InvalidName(_))
+        ));
+    }
+}
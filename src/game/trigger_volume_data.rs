@@ -0,0 +1,129 @@
+//! Trigger Volume Data - Axis-aligned zones that report who enters and leaves
+//!
+//! Map makers register named boxes (spawn protection, arena bounds, ...).
+//! Each tick the positions of players and entities are tested against them;
+//! occupants crossing a boundary produce enter/exit events, and volumes with
+//! a stay interval also report who is still inside. Volumes are bucketed in
+//! a spatial hash of `SPATIAL_HASH_CELL_SIZE` cells, so a position is only
+//! tested against the volumes overlapping its cell. Volumes are saved with
+//! the world; occupancy is not and rebuilds on the first tick after loading.
+//!
+//! Pure DOP: No methods, just data structures.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Id of a registered trigger volume, stable across saves
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TriggerVolumeId(pub u32);
+
+/// Registered zone, `min` inclusive and `max` exclusive (world units)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TriggerVolume {
+    pub id: TriggerVolumeId,
+    pub name: String,
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+    /// Ticks between stay events for each occupant; 0 sends none
+    pub stay_interval: u32,
+    /// Disabled volumes are skipped; their occupants exit on the next tick
+    pub enabled: bool,
+}
+
+/// What is inside a volume
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TriggerOccupant {
+    Player(u32),
+    /// Index into `PhysicsBuffers`
+    Entity(u32),
+}
+
+/// Position tested against the volumes this tick
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TriggerSubject {
+    pub occupant: TriggerOccupant,
+    pub position: [f32; 3],
+}
+
+/// Kind of boundary event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerEventKind {
+    Enter,
+    Exit,
+    /// Still inside; `ticks_inside` counts from the enter tick
+    Stay {
+        ticks_inside: u64,
+    },
+}
+
+/// One volume event of a tick
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TriggerEvent {
+    pub volume: TriggerVolumeId,
+    pub occupant: TriggerOccupant,
+    pub kind: TriggerEventKind,
+}
+
+/// Min (inclusive) and max (exclusive) corner of a volume
+pub type TriggerBounds = ([f32; 3], [f32; 3]);
+
+/// Spatial hash cell (cell coordinates, not world units)
+pub type TriggerCell = [i32; 3];
+
+/// Volumes overlapping each spatial hash cell
+pub type TriggerCellMap = HashMap<TriggerCell, Vec<TriggerVolumeId>>;
+
+/// Tick each occupant entered each volume
+pub type TriggerOccupancy = HashMap<(TriggerVolumeId, TriggerOccupant), u64>;
+
+/// Trigger volumes of one world and who is inside them
+#[derive(Clone, Debug, Default)]
+pub struct TriggerVolumeData {
+    /// Volumes in registration order
+    pub volumes: Vec<TriggerVolume>,
+    /// Next id handed out; ids are never reused
+    pub next_id: u32,
+    /// Volumes overlapping each cell
+    pub cells: TriggerCellMap,
+    /// Volumes spanning too many cells to bucket; tested for every subject
+    pub oversized: Vec<TriggerVolumeId>,
+    /// Tick each occupant entered each volume
+    pub occupancy: TriggerOccupancy,
+    /// Occupancy seen this tick (reused between ticks)
+    pub seen: TriggerOccupancy,
+}
+
+/// Trigger volume errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TriggerVolumeError {
+    #[error("Unknown trigger volume: {0}")]
+    UnknownVolume(u32),
+
+    #[error("Trigger volume already exists: {0}")]
+    VolumeExists(String),
+
+    #[error("Invalid trigger volume name: {0:?}")]
+    InvalidName(String),
+
+    #[error("Invalid trigger volume bounds for {0}: min must be finite and below max")]
+    InvalidBounds(String),
+
+    #[error("Trigger volume serialization failed: {0}")]
+    Serialization(String),
+
+    #[error("Trigger volume deserialization failed: {0}")]
+    Deserialization(String),
+
+    #[error("Trigger volume I/O failed: {0}")]
+    Io(String),
+}
+
+pub type TriggerVolumeResult<T> = Result<T, TriggerVolumeError>;
+
+/// Trigger volumes as stored with the world
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedTriggerVolumes {
+    pub version: u32,
+    pub next_id: u32,
+    pub volumes: Vec<TriggerVolume>,
+}
@@ -0,0 +1,459 @@
+//! Trigger Volume Operations - Pure functions for enter/exit zones
+//!
+//! Register zones with `add_trigger_volume`, then once per tick call
+//! `update_trigger_volumes` with the position of every player and entity
+//! that can trigger them. The subject list must be complete each tick: an
+//! occupant missing from it (despawned, disconnected) exits its volumes.
+//! Through the gateway, `update_gateway_triggers` does the same and queues
+//! the results as `GameEvent::TriggerEnter`/`TriggerExit`/`TriggerStay`.
+
+use super::trigger_volume_data::{
+    SavedTriggerVolumes, TriggerBounds, TriggerCell, TriggerEvent, TriggerEventKind,
+    TriggerOccupant, TriggerSubject, TriggerVolume, TriggerVolumeData, TriggerVolumeError,
+    TriggerVolumeId, TriggerVolumeResult,
+};
+use crate::constants::persistence_constants::{
+    TRIGGER_VOLUMES_FILE, TRIGGER_VOLUMES_FORMAT_VERSION,
+};
+use crate::constants::physics_constants::SPATIAL_HASH_CELL_SIZE;
+use crate::constants::trigger_volumes::{MAX_TRIGGER_NAME_LEN, MAX_TRIGGER_VOLUME_CELLS};
+use crate::persistence::{read_save_file, write_save_file, SaveCipherData};
+use crate::world::core::VoxelPos;
+use std::path::Path;
+
+/// Create an empty set of trigger volumes
+pub fn create_trigger_volumes() -> TriggerVolumeData {
+    TriggerVolumeData::default()
+}
+
+fn validate_name(name: &str) -> TriggerVolumeResult<()> {
+    if name.is_empty() || name.len() > MAX_TRIGGER_NAME_LEN || name.chars().any(char::is_control) {
+        return Err(TriggerVolumeError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+fn validate_bounds(name: &str, min: [f32; 3], max: [f32; 3]) -> TriggerVolumeResult<()> {
+    let valid =
+        (0..3).all(|axis| min[axis].is_finite() && max[axis].is_finite() && min[axis] < max[axis]);
+    if !valid {
+        return Err(TriggerVolumeError::InvalidBounds(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Bounds covering every block between two corner blocks (in any order)
+pub fn trigger_bounds_from_voxels(a: VoxelPos, b: VoxelPos) -> TriggerBounds {
+    (
+        [
+            a.x.min(b.x) as f32,
+            a.y.min(b.y) as f32,
+            a.z.min(b.z) as f32,
+        ],
+        [
+            (a.x.max(b.x) + 1) as f32,
+            (a.y.max(b.y) + 1) as f32,
+            (a.z.max(b.z) + 1) as f32,
+        ],
+    )
+}
+
+/// Whether a point lies inside a volume (`min` inclusive, `max` exclusive)
+pub fn trigger_volume_contains(volume: &TriggerVolume, point: [f32; 3]) -> bool {
+    (0..3).all(|axis| point[axis] >= volume.min[axis] && point[axis] < volume.max[axis])
+}
+
+/// Spatial hash cell holding a point
+pub fn trigger_cell(point: [f32; 3]) -> TriggerCell {
+    [
+        (point[0] / SPATIAL_HASH_CELL_SIZE).floor() as i32,
+        (point[1] / SPATIAL_HASH_CELL_SIZE).floor() as i32,
+        (point[2] / SPATIAL_HASH_CELL_SIZE).floor() as i32,
+    ]
+}
+
+/// Cells overlapped by a volume, or None if there are more than
+/// `MAX_TRIGGER_VOLUME_CELLS`
+fn volume_cells(volume: &TriggerVolume) -> Option<Vec<TriggerCell>> {
+    let low = trigger_cell(volume.min);
+    let high = trigger_cell(volume.max);
+    let count = (0..3).try_fold(1usize, |count, axis| {
+        count.checked_mul((high[axis] as i64 - low[axis] as i64 + 1) as usize)
+    })?;
+    if count > MAX_TRIGGER_VOLUME_CELLS {
+        return None;
+    }
+
+    let mut cells = Vec::with_capacity(count);
+    for x in low[0]..=high[0] {
+        for y in low[1]..=high[1] {
+            for z in low[2]..=high[2] {
+                cells.push([x, y, z]);
+            }
+        }
+    }
+    Some(cells)
+}
+
+fn index_volume(data: &mut TriggerVolumeData, volume: &TriggerVolume) {
+    match volume_cells(volume) {
+        Some(cells) => {
+            for cell in cells {
+                data.cells.entry(cell).or_default().push(volume.id);
+            }
+        }
+        None => data.oversized.push(volume.id),
+    }
+}
+
+fn unindex_volume(data: &mut TriggerVolumeData, volume: &TriggerVolume) {
+    match volume_cells(volume) {
+        Some(cells) => {
+            for cell in cells {
+                if let Some(bucket) = data.cells.get_mut(&cell) {
+                    bucket.retain(|id| *id != volume.id);
+                    if bucket.is_empty() {
+                        data.cells.remove(&cell);
+                    }
+                }
+            }
+        }
+        None => data.oversized.retain(|id| *id != volume.id),
+    }
+}
+
+/// Position of a volume in `volumes` (ids grow in registration order)
+fn volume_index(data: &TriggerVolumeData, id: TriggerVolumeId) -> Option<usize> {
+    data.volumes
+        .binary_search_by_key(&id, |volume| volume.id)
+        .ok()
+}
+
+/// Volume by id
+pub fn trigger_volume(data: &TriggerVolumeData, id: TriggerVolumeId) -> Option<&TriggerVolume> {
+    volume_index(data, id).map(|index| &data.volumes[index])
+}
+
+/// Volume by name
+pub fn find_trigger_volume<'a>(
+    data: &'a TriggerVolumeData,
+    name: &str,
+) -> Option<&'a TriggerVolume> {
+    data.volumes.iter().find(|volume| volume.name == name)
+}
+
+/// Register a named volume; `stay_interval` is the number of ticks between
+/// stay events for each occupant (0 for none)
+pub fn add_trigger_volume(
+    data: &mut TriggerVolumeData,
+    name: &str,
+    min: [f32; 3],
+    max: [f32; 3],
+    stay_interval: u32,
+) -> TriggerVolumeResult<TriggerVolumeId> {
+    validate_name(name)?;
+    validate_bounds(name, min, max)?;
+    if find_trigger_volume(data, name).is_some() {
+        return Err(TriggerVolumeError::VolumeExists(name.to_string()));
+    }
+
+    let volume = TriggerVolume {
+        id: TriggerVolumeId(data.next_id),
+        name: name.to_string(),
+        min,
+        max,
+        stay_interval,
+        enabled: true,
+    };
+    data.next_id += 1;
+    index_volume(data, &volume);
+    let id = volume.id;
+    data.volumes.push(volume);
+    Ok(id)
+}
+
+/// Remove a volume. Its occupants are forgotten without exit events.
+pub fn remove_trigger_volume(data: &mut TriggerVolumeData, id: TriggerVolumeId) -> bool {
+    let Some(index) = volume_index(data, id) else {
+        return false;
+    };
+    let volume = data.volumes.remove(index);
+    unindex_volume(data, &volume);
+    data.occupancy.retain(|(volume_id, _), _| *volume_id != id);
+    true
+}
+
+/// Move or resize a volume; occupants outside the new bounds exit next tick
+pub fn set_trigger_volume_bounds(
+    data: &mut TriggerVolumeData,
+    id: TriggerVolumeId,
+    min: [f32; 3],
+    max: [f32; 3],
+) -> TriggerVolumeResult<()> {
+    let index = volume_index(data, id).ok_or(TriggerVolumeError::UnknownVolume(id.0))?;
+    validate_bounds(&data.volumes[index].name, min, max)?;
+
+    let mut volume = data.volumes[index].clone();
+    unindex_volume(data, &volume);
+    volume.min = min;
+    volume.max = max;
+    index_volume(data, &volume);
+    data.volumes[index] = volume;
+    Ok(())
+}
+
+/// Enable or disable a volume; occupants of a disabled volume exit next tick
+pub fn set_trigger_volume_enabled(
+    data: &mut TriggerVolumeData,
+    id: TriggerVolumeId,
+    enabled: bool,
+) -> TriggerVolumeResult<()> {
+    let index = volume_index(data, id).ok_or(TriggerVolumeError::UnknownVolume(id.0))?;
+    data.volumes[index].enabled = enabled;
+    Ok(())
+}
+
+/// Enabled volumes containing `point`, written to `out` (cleared first)
+pub fn trigger_volumes_at(
+    data: &TriggerVolumeData,
+    point: [f32; 3],
+    out: &mut Vec<TriggerVolumeId>,
+) {
+    out.clear();
+    let bucket = data.cells.get(&trigger_cell(point));
+    for id in bucket.into_iter().flatten().chain(&data.oversized) {
+        let inside = trigger_volume(data, *id)
+            .is_some_and(|volume| volume.enabled && trigger_volume_contains(volume, point));
+        if inside {
+            out.push(*id);
+        }
+    }
+    out.sort_unstable();
+}
+
+/// Test every subject against the volumes for `tick` and append the enter,
+/// stay and exit events to `events`. Exits are reported last, ordered by
+/// volume and occupant.
+pub fn update_trigger_volumes(
+    data: &mut TriggerVolumeData,
+    tick: u64,
+    subjects: &[TriggerSubject],
+    events: &mut Vec<TriggerEvent>,
+) {
+    let mut seen = std::mem::take(&mut data.seen);
+    seen.clear();
+
+    for subject in subjects {
+        let bucket = data.cells.get(&trigger_cell(subject.position));
+        for id in bucket.into_iter().flatten().chain(&data.oversized) {
+            let Some(volume) = trigger_volume(data, *id) else {
+                continue;
+            };
+            if !volume.enabled || !trigger_volume_contains(volume, subject.position) {
+                continue;
+            }
+
+            let key = (volume.id, subject.occupant);
+            let entered = match data.occupancy.get(&key) {
+                Some(&entered) => {
+                    let ticks_inside = tick.saturating_sub(entered);
+                    let interval = volume.stay_interval as u64;
+                    if interval > 0 && ticks_inside > 0 && ticks_inside % interval == 0 {
+                        events.push(TriggerEvent {
+                            volume: volume.id,
+                            occupant: subject.occupant,
+                            kind: TriggerEventKind::Stay { ticks_inside },
+                        });
+                    }
+                    entered
+                }
+                None => {
+                    events.push(TriggerEvent {
+                        volume: volume.id,
+                        occupant: subject.occupant,
+                        kind: TriggerEventKind::Enter,
+                    });
+                    tick
+                }
+            };
+            seen.insert(key, entered);
+        }
+    }
+
+    let mut exits: Vec<_> = data
+        .occupancy
+        .keys()
+        .filter(|key| !seen.contains_key(key))
+        .copied()
+        .collect();
+    exits.sort_unstable();
+    events.extend(exits.into_iter().map(|(volume, occupant)| TriggerEvent {
+        volume,
+        occupant,
+        kind: TriggerEventKind::Exit,
+    }));
+
+    data.seen = std::mem::replace(&mut data.occupancy, seen);
+}
+
+/// Tick an occupant entered a volume, while it is inside
+pub fn trigger_entered_tick(
+    data: &TriggerVolumeData,
+    volume: TriggerVolumeId,
+    occupant: TriggerOccupant,
+) -> Option<u64> {
+    data.occupancy.get(&(volume, occupant)).copied()
+}
+
+// ============================================================================
+// PERSISTENCE
+// ============================================================================
+
+/// Serialize the volumes for saving with the world
+pub fn serialize_trigger_volumes(data: &TriggerVolumeData) -> TriggerVolumeResult<Vec<u8>> {
+    let saved = SavedTriggerVolumes {
+        version: TRIGGER_VOLUMES_FORMAT_VERSION,
+        next_id: data.next_id,
+        volumes: data.volumes.clone(),
+    };
+    bincode::serialize(&saved).map_err(|e| TriggerVolumeError::Serialization(e.to_string()))
+}
+
+/// Restore saved volumes; occupancy starts empty, so everyone inside gets
+/// an enter event on the first update
+pub fn deserialize_trigger_volumes(bytes: &[u8]) -> TriggerVolumeResult<TriggerVolumeData> {
+    let saved: SavedTriggerVolumes = bincode::deserialize(bytes)
+        .map_err(|e| TriggerVolumeError::Deserialization(e.to_string()))?;
+    if saved.version > TRIGGER_VOLUMES_FORMAT_VERSION {
+        return Err(TriggerVolumeError::Deserialization(format!(
+            "Unsupported trigger volume version {}",
+            saved.version
+        )));
+    }
+
+    let mut data = TriggerVolumeData {
+        next_id: saved.next_id,
+        ..TriggerVolumeData::default()
+    };
+    for volume in saved.volumes {
+        validate_bounds(&volume.name, volume.min, volume.max)
+            .map_err(|e| TriggerVolumeError::Deserialization(e.to_string()))?;
+        if data.volumes.last().is_some_and(|last| last.id >= volume.id)
+            || volume.id.0 >= data.next_id
+        {
+            return Err(TriggerVolumeError::Deserialization(format!(
+                "Trigger volume ids out of order at {}",
+                volume.name
+            )));
+        }
+        index_volume(&mut data, &volume);
+        data.volumes.push(volume);
+    }
+    Ok(data)
+}
+
+/// Write the volumes into a world slot directory
+pub fn save_trigger_volumes(
+    data: &TriggerVolumeData,
+    world_dir: &Path,
+    cipher: Option<&SaveCipherData>,
+) -> TriggerVolumeResult<()> {
+    let bytes = serialize_trigger_volumes(data)?;
+    write_save_file(cipher, &world_dir.join(TRIGGER_VOLUMES_FILE), &bytes)
+        .map_err(|e| TriggerVolumeError::Io(e.to_string()))
+}
+
+/// Read the volumes of a world slot directory; worlds saved without any
+/// get an empty set
+pub fn load_trigger_volumes(
+    world_dir: &Path,
+    cipher: Option<&SaveCipherData>,
+) -> TriggerVolumeResult<TriggerVolumeData> {
+    let path = world_dir.join(TRIGGER_VOLUMES_FILE);
+    if !path.exists() {
+        return Ok(create_trigger_volumes());
+    }
+    let bytes = read_save_file(cipher, &path).map_err(|e| TriggerVolumeError::Io(e.to_string()))?;
+    deserialize_trigger_volumes(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subject(occupant: TriggerOccupant, position: [f32; 3]) -> TriggerSubject {
+        TriggerSubject { occupant, position }
+    }
+
+    #[test]
+    fn test_enter_stay_exit_and_save() {
+        let mut data = create_trigger_volumes();
+        let arena =
+            add_trigger_volume(&mut data, "arena", [0.0; 3], [10.0; 3], 2).expect("valid volume");
+        // Larger than MAX_TRIGGER_VOLUME_CELLS cells; tested for every subject
+        let world = add_trigger_volume(&mut data, "world", [-1.0e6; 3], [1.0e6; 3], 0)
+            .expect("valid volume");
+        assert_eq!(data.oversized, vec![world]);
+        assert!(matches!(
+            add_trigger_volume(&mut data, "arena", [0.0; 3], [1.0; 3], 0),
+            Err(TriggerVolumeError::VolumeExists(_))
+        ));
+        assert!(matches!(
+            add_trigger_volume(&mut data, "flat", [0.0; 3], [1.0, 0.0, 1.0], 0),
+            Err(TriggerVolumeError::InvalidBounds(_))
+        ));
+
+        let player = TriggerOccupant::Player(7);
+        let mut events = Vec::new();
+        update_trigger_volumes(&mut data, 1, &[subject(player, [5.0; 3])], &mut events);
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .all(|event| event.kind == TriggerEventKind::Enter));
+
+        events.clear();
+        update_trigger_volumes(&mut data, 2, &[subject(player, [5.0; 3])], &mut events);
+        assert!(events.is_empty());
+        update_trigger_volumes(&mut data, 3, &[subject(player, [5.0; 3])], &mut events);
+        assert_eq!(
+            events,
+            vec![TriggerEvent {
+                volume: arena,
+                occupant: player,
+                kind: TriggerEventKind::Stay { ticks_inside: 2 },
+            }]
+        );
+
+        // The max face is exclusive
+        events.clear();
+        update_trigger_volumes(
+            &mut data,
+            4,
+            &[subject(player, [10.0, 5.0, 5.0])],
+            &mut events,
+        );
+        assert_eq!(
+            events,
+            vec![TriggerEvent {
+                volume: arena,
+                occupant: player,
+                kind: TriggerEventKind::Exit,
+            }]
+        );
+
+        // Missing from the subject list leaves every volume
+        events.clear();
+        update_trigger_volumes(&mut data, 5, &[], &mut events);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].volume, world);
+
+        let restored =
+            deserialize_trigger_volumes(&serialize_trigger_volumes(&data).expect("serializes"))
+                .expect("deserializes");
+        assert_eq!(restored.volumes, data.volumes);
+        assert_eq!(restored.oversized, data.oversized);
+        let mut inside = Vec::new();
+        trigger_volumes_at(&restored, [1.0; 3], &mut inside);
+        assert_eq!(inside, vec![arena, world]);
+    }
+}
@@ -217,12 +217,14 @@ macro_rules! create_compute_pipeline {
     }};
 }
 
-/// Simplified buffer creation
+/// Simplified buffer creation, recorded in the GPU allocation tracker
+/// under `owner`
 ///
 /// # Example
 /// ```rust
-/// let buffer = create_buffer!(
+/// let (buffer, allocation) = create_buffer!(
 ///     device,
+///     &owner,
 ///     "My Buffer",
 ///     size: 1024,
 ///     usage: STORAGE | COPY_DST
@@ -232,16 +234,21 @@ macro_rules! create_compute_pipeline {
 macro_rules! create_buffer {
     (
         $device:expr,
+        $owner:expr,
         $label:expr,
         size: $size:expr,
         usage: $($usage:ident)|+
     ) => {
-        $device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some($label),
-            size: $size,
-            usage: wgpu::BufferUsages::$($usage)|+,
-            mapped_at_creation: false,
-        })
+        $crate::memory::create_tracked_buffer(
+            $device,
+            $owner,
+            &wgpu::BufferDescriptor {
+                label: Some($label),
+                size: $size,
+                usage: wgpu::BufferUsages::$($usage)|+,
+                mapped_at_creation: false,
+            },
+        )
     };
 }
 
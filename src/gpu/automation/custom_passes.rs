@@ -0,0 +1,434 @@
+//! Game-registered GPU compute passes
+//!
+//! Games with custom world logic provide WGSL source plus a list of binding
+//! requirements. Registration runs the source through the unified GPU system
+//! (constants and registered types are injected, the result is validated),
+//! builds the pipeline once, and files the pass under a frame stage. The engine
+//! then encodes every pass of a stage at the matching point in the frame.
+
+use crate::gpu::automation::create_gpu_shader;
+use crate::gpu::automation::safe_pipeline::{PipelineError, ValidatedShader};
+use crate::gpu::automation::unified_system::BindingAccess;
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
+use std::collections::HashSet;
+
+/// Point in the frame at which a custom pass is encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CustomPassStage {
+    /// After terrain generation has written new chunks
+    PostGeneration,
+    /// Before the lighting passes run
+    PreLighting,
+    /// Once per simulation tick
+    PerTick,
+}
+
+/// Engine or game resource bound to a custom pass binding slot
+#[derive(Debug, Clone, PartialEq)]
+pub enum CustomPassResource {
+    /// WorldBuffer voxel storage
+    WorldVoxels,
+    /// WorldBuffer chunk metadata
+    ChunkMetadata,
+    /// WorldBuffer dedicated lighting storage (requires Dedicated lighting mode)
+    WorldLight,
+    /// Pass-owned buffer allocated at registration, persists across frames
+    Owned { size: u64 },
+}
+
+/// One binding requirement declared by the game (always group 0)
+#[derive(Debug, Clone)]
+pub struct CustomPassBinding {
+    pub binding: u32,
+    pub access: BindingAccess,
+    pub resource: CustomPassResource,
+}
+
+/// Workgroup count for a custom pass dispatch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CustomPassDispatch {
+    /// Fixed workgroup counts
+    Fixed { x: u32, y: u32, z: u32 },
+    /// One workgroup per chunk handed to the stage
+    PerChunk,
+}
+
+/// Everything a game provides to register a compute pass
+#[derive(Debug, Clone)]
+pub struct CustomPassDescriptor {
+    pub name: String,
+    pub wgsl_source: String,
+    pub entry_point: String,
+    pub stage: CustomPassStage,
+    pub bindings: Vec<CustomPassBinding>,
+    pub dispatch: CustomPassDispatch,
+}
+
+/// Stable handle for a registered pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CustomPassId(pub u32);
+
+/// A validated, ready-to-encode custom pass
+pub struct RegisteredCustomPass {
+    pub id: CustomPassId,
+    pub descriptor: CustomPassDescriptor,
+    pub shader: ValidatedShader,
+    pub pipeline: wgpu::ComputePipeline,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    /// Pass-owned buffers, indexed like descriptor.bindings (None for engine resources)
+    pub owned_buffers: Vec<Option<wgpu::Buffer>>,
+    /// One guard per `Some` in `owned_buffers`
+    pub owned_allocations: Vec<GpuAllocationGuard>,
+    pub enabled: bool,
+}
+
+/// All custom passes registered by the game
+pub struct CustomPassRegistryData {
+    pub passes: Vec<RegisteredCustomPass>,
+    pub next_id: u32,
+    pub gpu_owner: GpuOwnerGuard,
+}
+
+/// Engine buffers available to custom passes for the current frame
+pub struct CustomPassResources<'a> {
+    pub world_voxels: &'a wgpu::Buffer,
+    pub chunk_metadata: &'a wgpu::Buffer,
+    pub world_light: Option<&'a wgpu::Buffer>,
+    /// Number of chunks the stage is processing (used by PerChunk dispatch)
+    pub chunk_count: u32,
+}
+
+/// Create an empty custom pass registry
+pub fn create_custom_pass_registry() -> CustomPassRegistryData {
+    CustomPassRegistryData {
+        passes: Vec::new(),
+        next_id: 1,
+        gpu_owner: register_global_gpu_owner("custom_passes"),
+    }
+}
+
+/// Check a descriptor for problems that do not need the GPU
+pub fn validate_custom_pass_descriptor(
+    registry: &CustomPassRegistryData,
+    desc: &CustomPassDescriptor,
+) -> Result<(), PipelineError> {
+    if desc.name.is_empty() {
+        return Err(PipelineError::CreationFailed(
+            "Custom pass name cannot be empty".to_string(),
+        ));
+    }
+
+    if registry.passes.iter().any(|p| p.descriptor.name == desc.name) {
+        return Err(PipelineError::CreationFailed(format!(
+            "Custom pass '{}' is already registered",
+            desc.name
+        )));
+    }
+
+    let mut seen = HashSet::new();
+    for binding in &desc.bindings {
+        if !seen.insert(binding.binding) {
+            return Err(PipelineError::LayoutMismatch {
+                expected: "unique binding indices".to_string(),
+                found: format!("binding {} declared twice in '{}'", binding.binding, desc.name),
+            });
+        }
+
+        match (&binding.resource, binding.access) {
+            (CustomPassResource::Owned { size: 0 }, _) => {
+                return Err(PipelineError::CreationFailed(format!(
+                    "Owned buffer at binding {} in '{}' has zero size",
+                    binding.binding, desc.name
+                )));
+            }
+            (CustomPassResource::Owned { .. }, _) => {}
+            (_, BindingAccess::Uniform) => {
+                return Err(PipelineError::LayoutMismatch {
+                    expected: "storage access for world buffers".to_string(),
+                    found: format!("uniform access at binding {}", binding.binding),
+                });
+            }
+            _ => {}
+        }
+
+        // Every declared binding must appear in the source
+        let marker = format!("@binding({})", binding.binding);
+        if !desc.wgsl_source.contains(&marker) {
+            return Err(PipelineError::MissingBinding {
+                group: 0,
+                binding: binding.binding,
+                name: desc.name.clone(),
+            });
+        }
+    }
+
+    let entry_marker = format!("fn {}", desc.entry_point);
+    if !desc.wgsl_source.contains("@compute") || !desc.wgsl_source.contains(&entry_marker) {
+        return Err(PipelineError::CreationFailed(format!(
+            "Custom pass '{}' has no @compute entry point '{}'",
+            desc.name, desc.entry_point
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate, compile and register a game compute pass
+pub fn register_custom_pass(
+    registry: &mut CustomPassRegistryData,
+    device: &wgpu::Device,
+    desc: CustomPassDescriptor,
+) -> Result<CustomPassId, PipelineError> {
+    validate_custom_pass_descriptor(registry, &desc)?;
+
+    // Goes through the unified system so constants and registered GPU types are injected
+    let shader_name = format!("custom_pass_{}", desc.name);
+    let shader = create_gpu_shader(device, &shader_name, &desc.wgsl_source)?;
+
+    let layout_entries: Vec<wgpu::BindGroupLayoutEntry> = desc
+        .bindings
+        .iter()
+        .map(|b| wgpu::BindGroupLayoutEntry {
+            binding: b.binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: match b.access {
+                    BindingAccess::ReadOnly => wgpu::BufferBindingType::Storage { read_only: true },
+                    BindingAccess::ReadWrite => {
+                        wgpu::BufferBindingType::Storage { read_only: false }
+                    }
+                    BindingAccess::Uniform => wgpu::BufferBindingType::Uniform,
+                },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        })
+        .collect();
+
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some(&format!("Custom Pass {} Layout", desc.name)),
+        entries: &layout_entries,
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some(&format!("Custom Pass {} Pipeline Layout", desc.name)),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some(&format!("Custom Pass {}", desc.name)),
+        layout: Some(&pipeline_layout),
+        module: &shader.module,
+        entry_point: &desc.entry_point,
+    });
+
+    let mut owned_allocations = Vec::new();
+    let owned_buffers = desc
+        .bindings
+        .iter()
+        .map(|b| match (&b.resource, b.access) {
+            (CustomPassResource::Owned { size }, access) => {
+                let usage = match access {
+                    BindingAccess::Uniform => wgpu::BufferUsages::UNIFORM,
+                    _ => wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                };
+                let (buffer, allocation) = create_tracked_buffer(
+                    device,
+                    &registry.gpu_owner,
+                    &wgpu::BufferDescriptor {
+                        label: Some(&format!("Custom Pass {} Binding {}", desc.name, b.binding)),
+                        size: *size,
+                        usage: usage | wgpu::BufferUsages::COPY_DST,
+                        mapped_at_creation: false,
+                    },
+                );
+                owned_allocations.push(allocation);
+                Some(buffer)
+            }
+            _ => None,
+        })
+        .collect();
+
+    let id = CustomPassId(registry.next_id);
+    registry.next_id += 1;
+
+    log::info!(
+        "[CustomPasses] Registered '{}' ({:?}) at stage {:?} with {} bindings",
+        desc.name,
+        id,
+        desc.stage,
+        desc.bindings.len()
+    );
+
+    registry.passes.push(RegisteredCustomPass {
+        id,
+        descriptor: desc,
+        shader,
+        pipeline,
+        bind_group_layout,
+        owned_buffers,
+        owned_allocations,
+        enabled: true,
+    });
+
+    Ok(id)
+}
+
+/// Remove a registered pass, returning whether it existed
+pub fn unregister_custom_pass(registry: &mut CustomPassRegistryData, id: CustomPassId) -> bool {
+    let before = registry.passes.len();
+    registry.passes.retain(|p| p.id != id);
+    before != registry.passes.len()
+}
+
+/// Enable or disable a pass without unregistering it
+pub fn set_custom_pass_enabled(
+    registry: &mut CustomPassRegistryData,
+    id: CustomPassId,
+    enabled: bool,
+) -> bool {
+    match registry.passes.iter_mut().find(|p| p.id == id) {
+        Some(pass) => {
+            pass.enabled = enabled;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Get the pass-owned buffer bound at a binding index (for game uploads/readbacks)
+pub fn custom_pass_owned_buffer(
+    registry: &CustomPassRegistryData,
+    id: CustomPassId,
+    binding: u32,
+) -> Option<&wgpu::Buffer> {
+    let pass = registry.passes.iter().find(|p| p.id == id)?;
+    let index = pass
+        .descriptor
+        .bindings
+        .iter()
+        .position(|b| b.binding == binding)?;
+    pass.owned_buffers.get(index)?.as_ref()
+}
+
+/// Encode every enabled pass registered for a stage, in registration order
+/// Returns the number of passes encoded
+pub fn encode_custom_passes(
+    registry: &CustomPassRegistryData,
+    stage: CustomPassStage,
+    device: &wgpu::Device,
+    encoder: &mut wgpu::CommandEncoder,
+    resources: &CustomPassResources,
+) -> Result<u32, PipelineError> {
+    let mut encoded = 0;
+
+    for pass in registry
+        .passes
+        .iter()
+        .filter(|p| p.enabled && p.descriptor.stage == stage)
+    {
+        let mut entries = Vec::with_capacity(pass.descriptor.bindings.len());
+        for (index, binding) in pass.descriptor.bindings.iter().enumerate() {
+            let buffer = match &binding.resource {
+                CustomPassResource::WorldVoxels => resources.world_voxels,
+                CustomPassResource::ChunkMetadata => resources.chunk_metadata,
+                CustomPassResource::WorldLight => {
+                    resources.world_light.ok_or_else(|| PipelineError::MissingBinding {
+                        group: 0,
+                        binding: binding.binding,
+                        name: format!("{} (world light buffer not allocated)", pass.descriptor.name),
+                    })?
+                }
+                CustomPassResource::Owned { .. } => pass
+                    .owned_buffers
+                    .get(index)
+                    .and_then(|b| b.as_ref())
+                    .ok_or_else(|| PipelineError::MissingBinding {
+                        group: 0,
+                        binding: binding.binding,
+                        name: pass.descriptor.name.clone(),
+                    })?,
+            };
+            entries.push(wgpu::BindGroupEntry {
+                binding: binding.binding,
+                resource: buffer.as_entire_binding(),
+            });
+        }
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(&format!("Custom Pass {} Bind Group", pass.descriptor.name)),
+            layout: &pass.bind_group_layout,
+            entries: &entries,
+        });
+
+        let (x, y, z) = match pass.descriptor.dispatch {
+            CustomPassDispatch::Fixed { x, y, z } => (x, y, z),
+            CustomPassDispatch::PerChunk => (resources.chunk_count, 1, 1),
+        };
+        if x == 0 || y == 0 || z == 0 {
+            continue;
+        }
+
+        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some(&pass.descriptor.name),
+            timestamp_writes: None,
+        });
+        compute_pass.set_pipeline(&pass.pipeline);
+        compute_pass.set_bind_group(0, &bind_group, &[]);
+        compute_pass.dispatch_workgroups(x, y, z);
+        encoded += 1;
+    }
+
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(source: &str) -> CustomPassDescriptor {
+        CustomPassDescriptor {
+            name: "danger_money".to_string(),
+            wgsl_source: source.to_string(),
+            entry_point: "main".to_string(),
+            stage: CustomPassStage::PerTick,
+            bindings: vec![CustomPassBinding {
+                binding: 0,
+                access: BindingAccess::ReadWrite,
+                resource: CustomPassResource::WorldVoxels,
+            }],
+            dispatch: CustomPassDispatch::PerChunk,
+        }
+    }
+
+    const SOURCE: &str = "@group(0) @binding(0) var<storage, read_write> world: array<u32>;\n\
+                          @compute @workgroup_size(64) fn main() {}";
+
+    #[test]
+    fn test_valid_descriptor() {
+        let registry = create_custom_pass_registry();
+        assert!(validate_custom_pass_descriptor(&registry, &descriptor(SOURCE)).is_ok());
+    }
+
+    #[test]
+    fn test_missing_binding_rejected() {
+        let registry = create_custom_pass_registry();
+        let source = "@compute @workgroup_size(64) fn main() {}";
+        assert!(matches!(
+            validate_custom_pass_descriptor(&registry, &descriptor(source)),
+            Err(PipelineError::MissingBinding { binding: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_binding_rejected() {
+        let registry = create_custom_pass_registry();
+        let mut desc = descriptor(SOURCE);
+        desc.bindings.push(desc.bindings[0].clone());
+        assert!(validate_custom_pass_descriptor(&registry, &desc).is_err());
+    }
+}
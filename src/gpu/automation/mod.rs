@@ -7,6 +7,7 @@ pub mod auto_layout;
 pub mod auto_wgsl;
 pub mod bind_group_macros;
 pub mod binding_manager;
+pub mod custom_passes;
 pub mod layout_derive;
 pub mod registry;
 pub mod safe_pipeline;
@@ -16,9 +17,15 @@ pub mod unified_system;
 
 // Re-export main types
 pub use auto_bindings::BindingUsage;
+pub use custom_passes::{
+    create_custom_pass_registry, encode_custom_passes, register_custom_pass,
+    unregister_custom_pass, CustomPassBinding, CustomPassDescriptor, CustomPassDispatch,
+    CustomPassId, CustomPassRegistryData, CustomPassResource, CustomPassResources,
+    CustomPassStage,
+};
 pub use registry::{
-    create_gpu_shader, generate_all_gpu_types, generate_gpu_constants, generate_shader_bindings,
-    initialize_gpu_registry,
+    clear_gpu_device_resources, create_gpu_shader, generate_all_gpu_types, generate_gpu_constants,
+    generate_shader_bindings, gpu_chunk_layout, initialize_gpu_registry, set_gpu_chunk_layout,
 };
 pub use safe_pipeline::{
     create_validated_shader, TypedComputePipelineBuilder, TypedRenderPipelineBuilder,
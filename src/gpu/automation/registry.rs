@@ -102,3 +102,27 @@ pub fn create_gpu_shader(
         .expect("[GpuRegistry] Failed to acquire GPU registry lock");
     registry.create_shader(device, name, shader_code)
 }
+
+/// Set the chunk layout all subsequently created shaders are generated for
+pub fn set_gpu_chunk_layout(layout: crate::world::core::ChunkLayout) {
+    let mut registry = GPU_REGISTRY
+        .lock()
+        .expect("[GpuRegistry] Failed to acquire GPU registry lock");
+    registry.set_chunk_layout(layout);
+}
+
+/// Forget device-owned shader state after the GPU device was lost
+pub fn clear_gpu_device_resources() {
+    let mut registry = GPU_REGISTRY
+        .lock()
+        .expect("[GpuRegistry] Failed to acquire GPU registry lock");
+    registry.clear_device_resources();
+}
+
+/// Chunk layout shaders are currently generated for
+pub fn gpu_chunk_layout() -> crate::world::core::ChunkLayout {
+    let registry = GPU_REGISTRY
+        .lock()
+        .expect("[GpuRegistry] Failed to acquire GPU registry lock");
+    registry.chunk_layout()
+}
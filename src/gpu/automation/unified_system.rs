@@ -11,6 +11,7 @@ use crate::gpu::automation::{
     shader_validator::{ShaderValidator, ValidationResult},
     typed_bindings::BindingSlot,
 };
+use crate::world::core::{validate_wgsl_chunk_layout, ChunkLayout, ChunkLayoutError};
 use std::collections::HashMap;
 use wgpu::{BindGroupLayout, Device, PipelineLayout, ShaderModule};
 
@@ -24,6 +25,8 @@ pub struct UnifiedGpuSystem {
     binding_layouts: HashMap<String, AutoBindingLayout>,
     /// Pipeline layouts
     pipeline_layouts: HashMap<String, PipelineLayout>,
+    /// Chunk layout the generated CHUNK_SIZE / VOXELS_PER_CHUNK constants use
+    chunk_layout: ChunkLayout,
 }
 
 /// Complete information about a GPU type
@@ -71,9 +74,35 @@ impl UnifiedGpuSystem {
             shaders: HashMap::new(),
             binding_layouts: HashMap::new(),
             pipeline_layouts: HashMap::new(),
+            chunk_layout: ChunkLayout::default(),
         }
     }
 
+    /// Set the chunk layout shaders are generated for
+    ///
+    /// Shaders created before the change keep their old constants, so this
+    /// clears the shader cache.
+    pub fn set_chunk_layout(&mut self, layout: ChunkLayout) {
+        if self.chunk_layout != layout {
+            self.chunk_layout = layout;
+            self.shaders.clear();
+        }
+    }
+
+    /// Drop cached shader modules and pipeline layouts
+    ///
+    /// They belong to the device that created them; after a device loss the
+    /// next `create_shader` regenerates them for the replacement device.
+    pub fn clear_device_resources(&mut self) {
+        self.shaders.clear();
+        self.pipeline_layouts.clear();
+    }
+
+    /// Chunk layout shaders are currently generated for
+    pub fn chunk_layout(&self) -> ChunkLayout {
+        self.chunk_layout
+    }
+
     /// Register a GPU type - this is the ONLY place types are defined
     pub fn register_type<T>(&mut self)
     where
@@ -140,14 +169,14 @@ impl UnifiedGpuSystem {
                 wgsl.push_str(
                     "@group(0) @binding(2) var<storage, read> params: TerrainParamsSOA;\n",
                 );
-            }
-            "chunk_modification" => {
-                // Chunk modification shader bindings
+                // Batch heightmaps from generation_halo.wgsl
                 wgsl.push_str(
-                    "@group(0) @binding(0) var<storage, read_write> world_data: array<u32>;\n",
+                    "@group(0) @binding(6) var<storage, read> halo_heights: array<f32>;\n",
                 );
-                wgsl.push_str("@group(0) @binding(1) var<storage, read_write> metadata: array<ChunkMetadata>;\n");
-                wgsl.push_str("@group(0) @binding(2) var<storage, read> commands: array<ModificationCommand>;\n");
+            }
+            "chunk_modification" => {
+                // Chunk modification binds the atomic voxel view and its resolved
+                // commands itself - don't generate any here
             }
             "hierarchical_physics" => {
                 // Hierarchical physics uses custom bindings - don't generate any here
@@ -223,7 +252,10 @@ impl UnifiedGpuSystem {
         complete_wgsl.push_str(&format!("// Shader: {}\n\n", name));
 
         // Add GPU constants first - use the centralized generator
-        complete_wgsl.push_str(&crate::constants::generate_wgsl_constants());
+        let constants =
+            crate::constants::generate_wgsl_constants_for_chunk_size(self.chunk_layout.size);
+        chunk_layout_check(&constants, self.chunk_layout, false)?;
+        complete_wgsl.push_str(&constants);
         complete_wgsl.push_str("\n");
 
         // Add all type definitions
@@ -237,6 +269,9 @@ impl UnifiedGpuSystem {
         // Process includes in shader code
         let processed_shader = self.process_includes(shader_code);
 
+        // Shaders that still hardcode their own chunk constants must agree too
+        chunk_layout_check(&processed_shader, self.chunk_layout, true)?;
+
         // Add the actual shader code
         complete_wgsl.push_str(&processed_shader);
 
@@ -451,6 +486,24 @@ impl UnifiedGpuSystem {
     }
 }
 
+/// Validate chunk constants in `wgsl` against the world chunk layout
+///
+/// With `allow_missing`, source that does not declare the constants passes.
+fn chunk_layout_check(
+    wgsl: &str,
+    layout: ChunkLayout,
+    allow_missing: bool,
+) -> Result<(), PipelineError> {
+    match validate_wgsl_chunk_layout(wgsl, layout) {
+        Ok(()) => Ok(()),
+        Err(ChunkLayoutError::MissingShaderConstant(_)) if allow_missing => Ok(()),
+        Err(e) => Err(PipelineError::LayoutMismatch {
+            expected: format!("chunk size {}", layout.size),
+            found: e.to_string(),
+        }),
+    }
+}
+
 /// Extract entry points from shader code
 fn extract_entry_points(shader_code: &str) -> Vec<String> {
     let mut entry_points = Vec::new();
@@ -134,7 +134,8 @@ pub struct DrawMetadata {
     /// - bit 1: cast shadows
     /// - bit 2: receive shadows
     /// - bit 3: is transparent
-    /// - bit 4-31: reserved
+    /// - bit 4: instanced (`mesh_id` holds the instance count)
+    /// - bit 5-31: reserved
     pub flags: u32,
 }
 
@@ -144,6 +145,7 @@ impl DrawMetadata {
     pub const FLAG_CAST_SHADOWS: u32 = 1 << 1;
     pub const FLAG_RECEIVE_SHADOWS: u32 = 1 << 2;
     pub const FLAG_TRANSPARENT: u32 = 1 << 3;
+    pub const FLAG_INSTANCED: u32 = 1 << 4;
 
     /// Create new draw metadata
     pub fn new(center: [f32; 3], radius: f32, material_id: u32, mesh_id: u32) -> Self {
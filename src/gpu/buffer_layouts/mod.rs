@@ -31,6 +31,10 @@ pub mod bindings {
         pub const VOXEL_BUFFER: u32 = 0;
         pub const METADATA_BUFFER: u32 = 1;
         pub const PARAMS_BUFFER: u32 = 2;
+        pub const LIGHT_BUFFER: u32 = 3;
+        pub const PALETTE_INDEX_BUFFER: u32 = 4;
+        pub const PALETTE_BUFFER: u32 = 5;
+        pub const HALO_HEIGHTS_BUFFER: u32 = 6;
     }
 
     /// Rendering bindings
@@ -77,6 +81,12 @@ pub mod calculations {
         slot as u64 * CHUNK_BUFFER_SLOT_SIZE
     }
 
+    /// Calculate offset for a chunk slot in the dedicated lighting buffer
+    #[inline]
+    pub fn chunk_light_slot_offset(slot: u32) -> u64 {
+        slot as u64 * CHUNK_LIGHT_SLOT_SIZE
+    }
+
     /// Calculate total size for instance buffer
     #[inline]
     pub fn instance_buffer_size(capacity: u32) -> u64 {
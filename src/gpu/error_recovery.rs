@@ -13,8 +13,8 @@ pub struct GpuErrorRecovery {
     device: Arc<wgpu::Device>,
     /// Queue reference  
     queue: Arc<wgpu::Queue>,
-    /// Flag indicating if device is lost
-    device_lost: AtomicBool,
+    /// Flag indicating if device is lost, shared with the device callbacks
+    device_lost: Arc<AtomicBool>,
     /// Error count for rate limiting
     error_count: AtomicU32,
     /// Maximum errors before forcing recovery
@@ -43,10 +43,25 @@ impl GpuErrorRecovery {
             }
         }));
 
+        let device_lost_clone = device_lost.clone();
+        device.set_device_lost_callback(move |reason, message| {
+            if matches!(
+                reason,
+                wgpu::DeviceLostReason::Unknown | wgpu::DeviceLostReason::Destroyed
+            ) {
+                log::error!(
+                    "[GPU Error Recovery] GPU device lost ({:?}): {}",
+                    reason,
+                    message
+                );
+                device_lost_clone.store(true, Ordering::Relaxed);
+            }
+        });
+
         Self {
             device,
             queue,
-            device_lost: AtomicBool::new(false),
+            device_lost,
             error_count: AtomicU32::new(0),
             max_errors: 10,
         }
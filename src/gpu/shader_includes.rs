@@ -11,6 +11,15 @@ pub const PERLIN_NOISE_WGSL: &str = include_str!("../shaders/rendering/perlin_no
 /// Unified Morton encoding functions for GPU shaders
 pub const MORTON_WGSL: &str = include_str!("wgsl_includes/morton.wgsl");
 
+/// Dedicated lighting buffer sampling functions for GPU shaders
+pub const LIGHT_STORAGE_WGSL: &str = include_str!("wgsl_includes/light_storage.wgsl");
+
+/// Palettized voxel storage decoding functions for GPU shaders
+pub const VOXEL_PALETTE_WGSL: &str = include_str!("wgsl_includes/voxel_palette.wgsl");
+
+/// Cloud layer density and projected cloud shadow functions
+pub const CLOUD_SHADOW_WGSL: &str = include_str!("wgsl_includes/cloud_shadow.wgsl");
+
 /// Get shader include content by name
 pub fn get_shader_include(name: &str) -> Option<&'static str> {
     match name {
@@ -25,6 +34,9 @@ pub fn get_shader_include(name: &str) -> Option<&'static str> {
             Some(PERLIN_NOISE_WGSL)
         }
         "morton.wgsl" | "wgsl_includes/morton.wgsl" => Some(MORTON_WGSL),
+        "light_storage.wgsl" | "wgsl_includes/light_storage.wgsl" => Some(LIGHT_STORAGE_WGSL),
+        "cloud_shadow.wgsl" | "wgsl_includes/cloud_shadow.wgsl" => Some(CLOUD_SHADOW_WGSL),
+        "voxel_palette.wgsl" | "wgsl_includes/voxel_palette.wgsl" => Some(VOXEL_PALETTE_WGSL),
         _ => None,
     }
 }
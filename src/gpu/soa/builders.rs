@@ -4,8 +4,13 @@
 
 use crate::gpu::soa::types::SoaCompatible;
 use crate::gpu::types::TypedGpuBuffer;
+use crate::memory::{
+    create_tracked_buffer, create_tracked_buffer_init, GpuAllocationGuard, GpuOwnerGuard,
+};
 use std::marker::PhantomData;
-use wgpu::util::DeviceExt;
+
+/// A built SOA buffer with its record in the GPU allocation tracker
+pub type TrackedSoaBuffer<A> = (TypedGpuBuffer<A>, GpuAllocationGuard);
 
 /// Builder for creating SOA GPU buffers
 pub struct SoaBufferBuilder<T: SoaCompatible> {
@@ -61,8 +66,12 @@ impl<T: SoaCompatible> SoaBufferBuilder<T> {
         self.items.is_empty()
     }
 
-    /// Build the SOA GPU buffer
-    pub fn build(self, device: &wgpu::Device) -> TypedGpuBuffer<T::Arrays> {
+    /// Build the SOA GPU buffer, recorded in the GPU allocation tracker under `owner`
+    pub fn build(
+        self,
+        device: &wgpu::Device,
+        owner: &GpuOwnerGuard,
+    ) -> TrackedSoaBuffer<T::Arrays> {
         // Convert AOS to SOA
         let soa_data = T::to_soa(&self.items);
 
@@ -72,11 +81,15 @@ impl<T: SoaCompatible> SoaBufferBuilder<T> {
             .unwrap_or_else(|| format!("SOA<{}>", std::any::type_name::<T>()));
 
         // Create GPU buffer
-        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some(&label),
-            contents: bytemuck::bytes_of(&soa_data),
-            usage: self.usage,
-        });
+        let (buffer, allocation) = create_tracked_buffer_init(
+            device,
+            owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some(&label),
+                contents: bytemuck::bytes_of(&soa_data),
+                usage: self.usage,
+            },
+        );
 
         let size = std::mem::size_of_val(&soa_data) as wgpu::BufferAddress;
 
@@ -87,15 +100,17 @@ impl<T: SoaCompatible> SoaBufferBuilder<T> {
             size
         );
 
-        TypedGpuBuffer::new(buffer, size)
+        (TypedGpuBuffer::new(buffer, size), allocation)
     }
 
-    /// Build an empty SOA buffer with capacity
+    /// Build an empty SOA buffer with capacity, recorded in the GPU allocation
+    /// tracker under `owner`
     pub fn build_empty(
         device: &wgpu::Device,
+        owner: &GpuOwnerGuard,
         capacity: usize,
         label: Option<&str>,
-    ) -> TypedGpuBuffer<T::Arrays> {
+    ) -> TrackedSoaBuffer<T::Arrays> {
         // Create empty SOA data
         let soa_data = T::to_soa(&[]);
         let size = std::mem::size_of_val(&soa_data) as wgpu::BufferAddress;
@@ -105,14 +120,18 @@ impl<T: SoaCompatible> SoaBufferBuilder<T> {
         });
 
         // Create GPU buffer with full capacity
-        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some(label),
-            size,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let (buffer, allocation) = create_tracked_buffer(
+            device,
+            owner,
+            &wgpu::BufferDescriptor {
+                label: Some(label),
+                size,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
 
-        TypedGpuBuffer::new(buffer, size)
+        (TypedGpuBuffer::new(buffer, size), allocation)
     }
 }
 
@@ -0,0 +1,79 @@
+//! Cloud layer density and projected cloud shadows
+//!
+//! Shared by the cloud plane shader and the lighting pass so shadows line up
+//! with the clouds drawn overhead. The density is a hashed value-noise fbm in
+//! world XZ, scrolled by the accumulated wind offset. Must match
+//! cloud_density in cloud_operations.rs.
+
+struct CloudUniform {
+    view_proj: mat4x4<f32>,
+    camera_pos: vec4<f32>,
+    /// xyz = direction toward the sun, w = shadow strength (0 = no shadows)
+    sun_direction: vec4<f32>,
+    /// rgb = sun color, a = cloud opacity
+    sun_color: vec4<f32>,
+    /// rgb = ambient sky color, a = unused
+    ambient_color: vec4<f32>,
+    /// x = altitude, y = thickness, z = noise scale, w = coverage
+    layer: vec4<f32>,
+    /// xy = accumulated wind drift (voxels), z = plane half-extent, w = edge softness
+    wind: vec4<f32>,
+}
+
+fn cloud_hash(cell: vec2<i32>) -> f32 {
+    var h = (bitcast<u32>(cell.x) * 0x8da6b343u) ^ (bitcast<u32>(cell.y) * 0xd8163841u);
+    h = h ^ (h >> 16u);
+    h = h * 0x85ebca6bu;
+    h = h ^ (h >> 13u);
+    h = h * 0xc2b2ae35u;
+    h = h ^ (h >> 16u);
+    return f32(h) / 4294967295.0;
+}
+
+fn cloud_value_noise(p: vec2<f32>) -> f32 {
+    let cell = vec2<i32>(floor(p));
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+    let a = cloud_hash(cell);
+    let b = cloud_hash(cell + vec2<i32>(1, 0));
+    let c = cloud_hash(cell + vec2<i32>(0, 1));
+    let d = cloud_hash(cell + vec2<i32>(1, 1));
+    return mix(mix(a, b, u.x), mix(c, d, u.x), u.y);
+}
+
+/// Cloud density (0-1) above a world XZ position
+fn cloud_density(world_xz: vec2<f32>, clouds: CloudUniform) -> f32 {
+    let coverage = clouds.layer.w;
+    if (coverage <= 0.0) {
+        return 0.0;
+    }
+
+    // Clouds travel with the wind: sample where this point's cloud came from
+    var p = (world_xz - clouds.wind.xy) * clouds.layer.z;
+    var amplitude = 0.5;
+    var value = 0.0;
+    for (var octave = 0; octave < 4; octave++) {
+        value += cloud_value_noise(p) * amplitude;
+        p *= 2.0;
+        amplitude *= 0.5;
+    }
+    value /= 0.9375;
+
+    let threshold = 1.0 - coverage;
+    return smoothstep(threshold, threshold + clouds.wind.w, value);
+}
+
+/// Light multiplier (1 = unshadowed) for a world position below the clouds
+fn cloud_shadow_factor(world_pos: vec3<f32>, clouds: CloudUniform) -> f32 {
+    let strength = clouds.sun_direction.w;
+    let sun = clouds.sun_direction.xyz;
+    if (strength <= 0.0 || sun.y <= 0.05) {
+        return 1.0;
+    }
+    let distance_to_layer = (clouds.layer.x - world_pos.y) / sun.y;
+    if (distance_to_layer <= 0.0) {
+        return 1.0;
+    }
+    let shadow_xz = world_pos.xz + sun.xz * distance_to_layer;
+    return 1.0 - strength * cloud_density(shadow_xz, clouds);
+}
@@ -0,0 +1,52 @@
+//! Dedicated lighting buffer access for GPU shaders
+//!
+//! The dedicated lighting buffer stores 16 bits per voxel (8-bit block light,
+//! 8-bit sky light). WGSL has no 16-bit storage type, so two voxels share one
+//! u32 word: the even voxel in the low half, the odd voxel in the high half.
+//! This is the single source of truth for GPU light packing and must match
+//! pack_voxel_light in world_buffer.rs.
+
+/// Number of u32 words used by one chunk slot in the lighting buffer
+fn light_words_per_chunk(chunk_size: u32) -> u32 {
+    return (chunk_size * chunk_size * chunk_size + 1u) / 2u;
+}
+
+/// Index of the u32 word holding the light for a voxel within a chunk slot
+fn light_word_index(slot: u32, local_index: u32, chunk_size: u32) -> u32 {
+    return slot * light_words_per_chunk(chunk_size) + (local_index >> 1u);
+}
+
+/// Extract the packed 16-bit light value for a voxel from its containing word
+fn unpack_voxel_light(word: u32, local_index: u32) -> u32 {
+    let shift = (local_index & 1u) * 16u;
+    return (word >> shift) & 0xFFFFu;
+}
+
+/// Block light (0-255) from a packed 16-bit light value
+fn block_light_of(light: u32) -> u32 {
+    return light & 0xFFu;
+}
+
+/// Sky light (0-255) from a packed 16-bit light value
+fn sky_light_of(light: u32) -> u32 {
+    return (light >> 8u) & 0xFFu;
+}
+
+/// Replace the light value for a voxel inside its containing word
+fn store_voxel_light(word: u32, local_index: u32, block_light: u32, sky_light: u32) -> u32 {
+    let shift = (local_index & 1u) * 16u;
+    let light = (block_light & 0xFFu) | ((sky_light & 0xFFu) << 8u);
+    return (word & ~(0xFFFFu << shift)) | (light << shift);
+}
+
+/// Brightest light level propagation writes (MAX_LIGHT_LEVEL); the 8-bit
+/// channels leave headroom above it
+const LIGHT_STORAGE_MAX_LEVEL: f32 = 15.0;
+
+/// Normalized light intensity for meshing/shading (0.0 - 1.0)
+/// sky_factor scales sky light for the current time of day
+fn voxel_light_intensity(light: u32, sky_factor: f32) -> f32 {
+    let block = min(f32(block_light_of(light)) / LIGHT_STORAGE_MAX_LEVEL, 1.0);
+    let sky = min(f32(sky_light_of(light)) / LIGHT_STORAGE_MAX_LEVEL, 1.0) * sky_factor;
+    return max(block, sky);
+}
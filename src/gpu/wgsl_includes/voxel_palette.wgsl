@@ -0,0 +1,28 @@
+//! Palettized voxel storage access for GPU shaders
+//!
+//! Chunks in the palette tier store one 8-bit palette index per voxel, four
+//! indices per u32 word (voxel i in byte i % 4 of word i / 4), and a palette
+//! of up to 256 packed voxels per chunk. This is the single source of truth
+//! for GPU palette decoding and must match palettize_chunk in voxel_palette.rs.
+//!
+//! The including shader declares the two palette tier buffers:
+//!   palette_indices: array<u32>  (palette_index_words() per palette slot)
+//!   palettes: array<u32>         (PALETTE_ENTRIES per palette slot)
+
+/// Palette entries reserved per palette slot
+const PALETTE_ENTRIES: u32 = 256u;
+
+/// Chunk table entries with this bit set name a palette slot, not a full slot
+const PALETTE_SLOT_FLAG: u32 = 0x80000000u;
+
+/// Number of u32 index words used by one palette slot
+fn palette_index_words() -> u32 {
+    return (VOXELS_PER_CHUNK + 3u) / 4u;
+}
+
+/// Packed 32-bit voxel at a local index of a palettized chunk
+fn palette_voxel(palette_slot: u32, local_index: u32) -> u32 {
+    let word = palette_indices[palette_slot * palette_index_words() + (local_index >> 2u)];
+    let entry = (word >> ((local_index & 3u) * 8u)) & 0xFFu;
+    return palettes[palette_slot * PALETTE_ENTRIES + entry];
+}
@@ -0,0 +1,92 @@
+//! Haptics Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Pattern playback and device mapping live in haptics_operations.rs
+//!
+//! Gameplay raises haptic cues (block break, damage, explosion) for a
+//! player; the player's gamepad plays the cue's rumble pattern. Patterns
+//! playing on one gamepad are mixed by taking the strongest level of each
+//! motor. The engine does not talk to gamepads itself: every update yields
+//! `RumbleCommand`s for the devices whose motors changed, which the host
+//! hands to its gamepad library.
+
+use std::collections::HashMap;
+
+/// Host id of a gamepad (e.g. a gilrs `GamepadId` as an integer)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GamepadId(pub u32);
+
+/// Levels of the two rumble motors (0.0 - 1.0)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MotorLevels {
+    /// Low-frequency (heavy) motor
+    pub strong: f32,
+    /// High-frequency (light) motor
+    pub weak: f32,
+}
+
+/// Fade in and out of a pattern
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RumbleEnvelope {
+    pub attack_secs: f32,
+    pub release_secs: f32,
+}
+
+/// Rumble played for one cue
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RumblePattern {
+    /// Peak motor levels
+    pub levels: MotorLevels,
+    pub duration_secs: f32,
+    pub envelope: RumbleEnvelope,
+}
+
+/// Gameplay moment a player should feel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HapticCue {
+    BlockBreak,
+    /// Health lost
+    Damage(f32),
+    /// Strength at the player (0.0 - 1.0), falling off with distance
+    Explosion(f32),
+    Custom(RumblePattern),
+}
+
+/// Pattern playing on a gamepad
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActiveRumble {
+    pub pattern: RumblePattern,
+    pub elapsed_secs: f32,
+}
+
+/// Patterns playing on each gamepad
+pub type ActiveRumbles = HashMap<GamepadId, Vec<ActiveRumble>>;
+
+/// Cue raised by a gameplay event, with the player who should feel it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayerHapticCue {
+    pub player_id: u32,
+    pub cue: HapticCue,
+}
+
+/// Motor levels to send to a gamepad
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RumbleCommand {
+    pub device: GamepadId,
+    pub levels: MotorLevels,
+}
+
+/// Rumble settings, device mapping and playback
+#[derive(Debug, Clone, Default)]
+pub struct HapticsData {
+    /// Settings toggle; turning it off stops every gamepad
+    pub enabled: bool,
+    /// Scale applied to every pattern (0.0 - 1.0)
+    pub strength: f32,
+    /// Gamepad of each local player
+    pub devices: HashMap<u32, GamepadId>,
+    /// Patterns playing on each gamepad
+    pub active: ActiveRumbles,
+    /// Levels last sent to each gamepad
+    pub sent: HashMap<GamepadId, MotorLevels>,
+}
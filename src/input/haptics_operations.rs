@@ -0,0 +1,299 @@
+//! Haptics Operations - Pure functions over HapticsData
+//!
+//! Gameplay calls `trigger_rumble` with a player and a cue (the gateway
+//! does this for block break, damage and `GameEvent::Rumble`). Once per
+//! frame `update_haptics` advances every pattern and returns the commands
+//! for gamepads whose motors changed, including a zero command when a
+//! gamepad falls silent.
+
+use super::haptics_data::{
+    ActiveRumble, GamepadId, HapticCue, HapticsData, MotorLevels, PlayerHapticCue, RumbleCommand,
+    RumbleEnvelope, RumblePattern,
+};
+use crate::constants::haptics::{
+    BLOCK_BREAK_SECS, BLOCK_BREAK_WEAK, DAMAGE_FULL_RUMBLE, DAMAGE_RELEASE_SECS, DAMAGE_SECS,
+    EXPLOSION_RELEASE_SECS, EXPLOSION_SECS, EXPLOSION_WEAK_SHARE, MAX_ACTIVE_RUMBLES,
+    MOTOR_EPSILON,
+};
+use std::collections::HashMap;
+
+/// Rumble on at full strength with no gamepads assigned
+pub fn create_haptics() -> HapticsData {
+    HapticsData {
+        enabled: true,
+        strength: 1.0,
+        devices: HashMap::new(),
+        active: HashMap::new(),
+        sent: HashMap::new(),
+    }
+}
+
+/// Route a local player's cues to a gamepad; returns the gamepad the
+/// player had before
+pub fn assign_gamepad(
+    haptics: &mut HapticsData,
+    player_id: u32,
+    device: GamepadId,
+) -> Option<GamepadId> {
+    haptics.devices.insert(player_id, device)
+}
+
+/// Stop routing a player's cues; patterns already playing run out
+pub fn unassign_gamepad(haptics: &mut HapticsData, player_id: u32) -> Option<GamepadId> {
+    haptics.devices.remove(&player_id)
+}
+
+/// Gamepad a player's cues go to
+pub fn player_gamepad(haptics: &HapticsData, player_id: u32) -> Option<GamepadId> {
+    haptics.devices.get(&player_id).copied()
+}
+
+/// Settings toggle; disabling drops every playing pattern so the next
+/// update stops the motors
+pub fn set_rumble_enabled(haptics: &mut HapticsData, enabled: bool) {
+    haptics.enabled = enabled;
+    if !enabled {
+        haptics.active.clear();
+    }
+}
+
+/// Scale every pattern (clamped to 0.0 - 1.0)
+pub fn set_rumble_strength(haptics: &mut HapticsData, strength: f32) {
+    haptics.strength = strength.clamp(0.0, 1.0);
+}
+
+/// Rumble pattern of a cue
+pub fn cue_pattern(cue: HapticCue) -> RumblePattern {
+    match cue {
+        HapticCue::BlockBreak => RumblePattern {
+            levels: MotorLevels {
+                strong: 0.0,
+                weak: BLOCK_BREAK_WEAK,
+            },
+            duration_secs: BLOCK_BREAK_SECS,
+            envelope: RumbleEnvelope::default(),
+        },
+        HapticCue::Damage(amount) => {
+            let scale = (amount / DAMAGE_FULL_RUMBLE).clamp(0.0, 1.0);
+            RumblePattern {
+                levels: MotorLevels {
+                    strong: scale,
+                    weak: scale * 0.5,
+                },
+                duration_secs: DAMAGE_SECS,
+                envelope: RumbleEnvelope {
+                    attack_secs: 0.0,
+                    release_secs: DAMAGE_RELEASE_SECS,
+                },
+            }
+        }
+        HapticCue::Explosion(intensity) => {
+            let intensity = intensity.clamp(0.0, 1.0);
+            RumblePattern {
+                levels: MotorLevels {
+                    strong: intensity,
+                    weak: intensity * EXPLOSION_WEAK_SHARE,
+                },
+                duration_secs: EXPLOSION_SECS * intensity.max(0.25),
+                envelope: RumbleEnvelope {
+                    attack_secs: 0.0,
+                    release_secs: EXPLOSION_RELEASE_SECS * intensity.max(0.25),
+                },
+            }
+        }
+        HapticCue::Custom(pattern) => pattern,
+    }
+}
+
+/// Explosion intensity for a player `distance` away from a blast of
+/// `radius` (linear falloff, 0.0 outside the radius)
+pub fn explosion_rumble_intensity(distance: f32, radius: f32) -> f32 {
+    if radius <= 0.0 {
+        return 0.0;
+    }
+    (1.0 - distance / radius).clamp(0.0, 1.0)
+}
+
+/// Envelope gain of a pattern `elapsed` seconds in (0.0 once it is over)
+pub fn rumble_gain(pattern: &RumblePattern, elapsed: f32) -> f32 {
+    if elapsed < 0.0 || elapsed >= pattern.duration_secs {
+        return 0.0;
+    }
+    let RumbleEnvelope {
+        attack_secs,
+        release_secs,
+    } = pattern.envelope;
+    let attack = if attack_secs > 0.0 {
+        (elapsed / attack_secs).min(1.0)
+    } else {
+        1.0
+    };
+    let remaining = pattern.duration_secs - elapsed;
+    let release = if release_secs > 0.0 {
+        (remaining / release_secs).min(1.0)
+    } else {
+        1.0
+    };
+    attack.min(release)
+}
+
+/// Play a cue on a player's gamepad; returns false when rumble is off,
+/// the player has no gamepad or the pattern is silent
+pub fn trigger_rumble(haptics: &mut HapticsData, player_id: u32, cue: HapticCue) -> bool {
+    if !haptics.enabled {
+        return false;
+    }
+    let Some(device) = player_gamepad(haptics, player_id) else {
+        return false;
+    };
+    let pattern = cue_pattern(cue);
+    let silent = pattern.levels.strong <= 0.0 && pattern.levels.weak <= 0.0;
+    if silent || pattern.duration_secs <= 0.0 {
+        return false;
+    }
+    let playing = haptics.active.entry(device).or_default();
+    if playing.len() >= MAX_ACTIVE_RUMBLES {
+        playing.remove(0);
+    }
+    playing.push(ActiveRumble {
+        pattern,
+        elapsed_secs: 0.0,
+    });
+    true
+}
+
+/// Haptic cue for a gameplay event
+pub fn event_haptic_cue(event: &crate::game::GameEvent) -> Option<PlayerHapticCue> {
+    use crate::game::GameEvent;
+    match event {
+        GameEvent::BlockBreak {
+            player_id: Some(player_id),
+            ..
+        } => Some(PlayerHapticCue {
+            player_id: *player_id,
+            cue: HapticCue::BlockBreak,
+        }),
+        GameEvent::PlayerDamaged {
+            player_id, amount, ..
+        } => Some(PlayerHapticCue {
+            player_id: *player_id,
+            cue: HapticCue::Damage(*amount),
+        }),
+        GameEvent::Rumble { player_id, cue } => Some(PlayerHapticCue {
+            player_id: *player_id,
+            cue: *cue,
+        }),
+        _ => None,
+    }
+}
+
+/// Current mixed levels of a gamepad: the strongest of each motor across
+/// its playing patterns, scaled by the strength setting
+pub fn gamepad_motor_levels(haptics: &HapticsData, device: GamepadId) -> MotorLevels {
+    let mut levels = MotorLevels::default();
+    for rumble in haptics.active.get(&device).into_iter().flatten() {
+        let gain = rumble_gain(&rumble.pattern, rumble.elapsed_secs) * haptics.strength;
+        levels.strong = levels.strong.max(rumble.pattern.levels.strong * gain);
+        levels.weak = levels.weak.max(rumble.pattern.levels.weak * gain);
+    }
+    levels
+}
+
+/// Advance every pattern by `dt` and return the commands to send, ordered
+/// by gamepad
+pub fn update_haptics(haptics: &mut HapticsData, dt: f32) -> Vec<RumbleCommand> {
+    for playing in haptics.active.values_mut() {
+        for rumble in playing.iter_mut() {
+            rumble.elapsed_secs += dt.max(0.0);
+        }
+        playing.retain(|rumble| rumble.elapsed_secs < rumble.pattern.duration_secs);
+    }
+    haptics.active.retain(|_, playing| !playing.is_empty());
+
+    let mut devices: Vec<GamepadId> = haptics
+        .active
+        .keys()
+        .chain(haptics.sent.keys())
+        .copied()
+        .collect();
+    devices.sort();
+    devices.dedup();
+
+    let mut commands = Vec::new();
+    for device in devices {
+        let levels = gamepad_motor_levels(haptics, device);
+        let previous = haptics.sent.get(&device).copied().unwrap_or_default();
+        let changed = (levels.strong - previous.strong).abs() >= MOTOR_EPSILON
+            || (levels.weak - previous.weak).abs() >= MOTOR_EPSILON
+            || (levels == MotorLevels::default() && previous != levels);
+        if !changed {
+            continue;
+        }
+        if levels == MotorLevels::default() {
+            haptics.sent.remove(&device);
+        } else {
+            haptics.sent.insert(device, levels);
+        }
+        commands.push(RumbleCommand { device, levels });
+    }
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cues_play_on_the_players_gamepad_and_stop() {
+        let mut haptics = create_haptics();
+        assert!(!trigger_rumble(&mut haptics, 1, HapticCue::BlockBreak));
+        assign_gamepad(&mut haptics, 1, GamepadId(7));
+        assign_gamepad(&mut haptics, 2, GamepadId(3));
+
+        assert!(trigger_rumble(&mut haptics, 1, HapticCue::BlockBreak));
+        assert!(trigger_rumble(&mut haptics, 2, HapticCue::Damage(20.0)));
+        let commands = update_haptics(&mut haptics, 0.01);
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].device, GamepadId(3));
+        assert_eq!(commands[0].levels.strong, 1.0);
+        assert_eq!(commands[1].levels.strong, 0.0);
+
+        // Unchanged motors are not sent again
+        assert!(update_haptics(&mut haptics, 0.01).is_empty());
+
+        // The block break tick ends first and its gamepad is stopped
+        let commands = update_haptics(&mut haptics, 0.1);
+        assert!(commands.contains(&RumbleCommand {
+            device: GamepadId(7),
+            levels: MotorLevels::default(),
+        }));
+
+        set_rumble_enabled(&mut haptics, false);
+        let commands = update_haptics(&mut haptics, 0.01);
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].levels, MotorLevels::default());
+        assert!(!trigger_rumble(&mut haptics, 2, HapticCue::Explosion(1.0)));
+        assert!(update_haptics(&mut haptics, 0.01).is_empty());
+    }
+
+    #[test]
+    fn envelope_fades_in_and_out() {
+        let pattern = RumblePattern {
+            levels: MotorLevels {
+                strong: 1.0,
+                weak: 1.0,
+            },
+            duration_secs: 1.0,
+            envelope: RumbleEnvelope {
+                attack_secs: 0.2,
+                release_secs: 0.5,
+            },
+        };
+        assert!((rumble_gain(&pattern, 0.1) - 0.5).abs() < 1e-5);
+        assert_eq!(rumble_gain(&pattern, 0.3), 1.0);
+        assert!((rumble_gain(&pattern, 0.75) - 0.5).abs() < 1e-5);
+        assert_eq!(rumble_gain(&pattern, 1.0), 0.0);
+        assert_eq!(explosion_rumble_intensity(5.0, 10.0), 0.5);
+        assert_eq!(explosion_rumble_intensity(12.0, 10.0), 0.0);
+    }
+}
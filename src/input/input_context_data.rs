@@ -0,0 +1,83 @@
+//! Input Context Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Stack handling and action resolution live in input_context_operations.rs
+//!
+//! Named actions ("jump", "break_block") are bound to keys and mouse
+//! buttons. Contexts form a stack with gameplay at the bottom; the console
+//! and UI push their own when they open and pop it when they close. An
+//! action reaches the topmost context that claims it, unless a context
+//! above swallows it first: opaque contexts swallow everything they do not
+//! claim, and text contexts swallow every keyboard trigger so typing in
+//! chat never moves the player or breaks blocks. The topmost context with
+//! a mouse policy decides whether the cursor is captured.
+
+use super::KeyCode;
+use std::collections::{HashMap, HashSet};
+use winit::event::MouseButton;
+
+/// Input bound to an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputTrigger {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+/// Triggers of each action
+pub type ActionBindings = HashMap<String, Vec<InputTrigger>>;
+
+/// What a context does with actions it does not claim
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextFallthrough {
+    /// Pass them to the contexts below (HUD overlays)
+    PassUnclaimed,
+    /// Swallow them (menus, console)
+    Opaque,
+}
+
+/// How a context wants the mouse cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseCapturePolicy {
+    /// Grab and hide the cursor; mouse motion turns the camera
+    Capture,
+    /// Free cursor for pointing at UI
+    Release,
+    /// Keep whatever the context below wants
+    Inherit,
+}
+
+/// One layer of the input stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputContext {
+    pub name: String,
+    /// Actions this context handles
+    pub actions: HashSet<String>,
+    pub fallthrough: ContextFallthrough,
+    pub mouse: MouseCapturePolicy,
+    /// Keyboard goes to text entry: keyboard triggers of unclaimed actions
+    /// never reach the contexts below, even when passing unclaimed actions
+    pub text_input: bool,
+}
+
+/// Action bindings and the context stack
+#[derive(Debug, Clone)]
+pub struct InputContextData {
+    pub bindings: ActionBindings,
+    /// Bottom (gameplay) first
+    pub stack: Vec<InputContext>,
+}
+
+/// Input context errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InputContextError {
+    #[error("Input context stack is full ({0} contexts)")]
+    StackFull(usize),
+
+    #[error("Input context {0} is not on the stack")]
+    UnknownContext(String),
+
+    #[error("The gameplay input context cannot be popped")]
+    CannotPopBase,
+}
+
+pub type InputContextResult<T> = Result<T, InputContextError>;
@@ -0,0 +1,301 @@
+//! Input Context Operations - Pure functions over InputContextData
+//!
+//! The console and UI call `push_input_context` when they open and
+//! `pop_input_context` when they close. Games ask `action_pressed` instead
+//! of checking keys directly, so an action only fires while the context
+//! that claims it can hear it. The engine applies the mouse policy of the
+//! stack to `InputState::cursor_locked` every frame.
+
+use super::input_context_data::{
+    ActionBindings, ContextFallthrough, InputContext, InputContextData, InputContextError,
+    InputContextResult, InputTrigger, MouseCapturePolicy,
+};
+use super::{InputState, KeyCode};
+use crate::constants::input_contexts::{
+    CONSOLE_CONTEXT, GAMEPLAY_CONTEXT, MAX_CONTEXT_DEPTH, UI_CONTEXT,
+};
+use winit::event::MouseButton;
+
+/// Actions each built-in context claims
+const GAMEPLAY_ACTIONS: [&str; 10] = [
+    "move_forward",
+    "move_back",
+    "move_left",
+    "move_right",
+    "jump",
+    "sneak",
+    "break_block",
+    "place_block",
+    "open_console",
+    "open_menu",
+];
+const UI_ACTIONS: [&str; 3] = ["ui_back", "ui_confirm", "ui_click"];
+const CONSOLE_ACTIONS: [&str; 4] = [
+    "console_submit",
+    "console_close",
+    "console_history_up",
+    "console_history_down",
+];
+
+/// Default keyboard and mouse layout for the built-in contexts' actions
+pub fn default_action_bindings() -> ActionBindings {
+    use InputTrigger::{Key, Mouse};
+    [
+        ("move_forward", Key(KeyCode::KeyW)),
+        ("move_back", Key(KeyCode::KeyS)),
+        ("move_left", Key(KeyCode::KeyA)),
+        ("move_right", Key(KeyCode::KeyD)),
+        ("jump", Key(KeyCode::Space)),
+        ("sneak", Key(KeyCode::ShiftLeft)),
+        ("break_block", Mouse(MouseButton::Left)),
+        ("place_block", Mouse(MouseButton::Right)),
+        ("open_console", Key(KeyCode::Backquote)),
+        ("open_menu", Key(KeyCode::Escape)),
+        ("ui_back", Key(KeyCode::Escape)),
+        ("ui_confirm", Key(KeyCode::Enter)),
+        ("ui_click", Mouse(MouseButton::Left)),
+        ("console_submit", Key(KeyCode::Enter)),
+        ("console_close", Key(KeyCode::Escape)),
+        ("console_close", Key(KeyCode::Backquote)),
+        ("console_history_up", Key(KeyCode::ArrowUp)),
+        ("console_history_down", Key(KeyCode::ArrowDown)),
+    ]
+    .into_iter()
+    .fold(ActionBindings::new(), |mut bindings, (action, trigger)| {
+        bind_trigger(&mut bindings, action, trigger);
+        bindings
+    })
+}
+
+/// Context claiming `actions`
+pub fn create_input_context(
+    name: &str,
+    actions: &[&str],
+    fallthrough: ContextFallthrough,
+    mouse: MouseCapturePolicy,
+    text_input: bool,
+) -> InputContext {
+    InputContext {
+        name: name.to_string(),
+        actions: actions.iter().map(|action| action.to_string()).collect(),
+        fallthrough,
+        mouse,
+        text_input,
+    }
+}
+
+/// Movement, block editing and the keys that open the console and menus;
+/// captures the mouse for looking around
+pub fn gameplay_context() -> InputContext {
+    create_input_context(
+        GAMEPLAY_CONTEXT,
+        &GAMEPLAY_ACTIONS,
+        ContextFallthrough::Opaque,
+        MouseCapturePolicy::Capture,
+        false,
+    )
+}
+
+/// Menus: free cursor, gameplay swallowed
+pub fn ui_context() -> InputContext {
+    create_input_context(
+        UI_CONTEXT,
+        &UI_ACTIONS,
+        ContextFallthrough::Opaque,
+        MouseCapturePolicy::Release,
+        false,
+    )
+}
+
+/// Console and chat: free cursor, typed text, gameplay swallowed
+pub fn console_context() -> InputContext {
+    create_input_context(
+        CONSOLE_CONTEXT,
+        &CONSOLE_ACTIONS,
+        ContextFallthrough::Opaque,
+        MouseCapturePolicy::Release,
+        true,
+    )
+}
+
+/// Stack holding only the gameplay context
+pub fn create_input_contexts(bindings: ActionBindings) -> InputContextData {
+    InputContextData {
+        bindings,
+        stack: vec![gameplay_context()],
+    }
+}
+
+/// Add a trigger to an action
+pub fn bind_action(data: &mut InputContextData, action: &str, trigger: InputTrigger) {
+    bind_trigger(&mut data.bindings, action, trigger);
+}
+
+/// Remove every trigger of an action
+pub fn unbind_action(data: &mut InputContextData, action: &str) {
+    data.bindings.remove(action);
+}
+
+/// Put a context on top of the stack
+pub fn push_input_context(
+    data: &mut InputContextData,
+    context: InputContext,
+) -> InputContextResult<()> {
+    if data.stack.len() >= MAX_CONTEXT_DEPTH {
+        return Err(InputContextError::StackFull(MAX_CONTEXT_DEPTH));
+    }
+    data.stack.push(context);
+    Ok(())
+}
+
+/// Remove the topmost context named `name`, wherever it is in the stack,
+/// so a console closed under an open menu still leaves
+pub fn pop_input_context(
+    data: &mut InputContextData,
+    name: &str,
+) -> InputContextResult<InputContext> {
+    match data.stack.iter().rposition(|context| context.name == name) {
+        Some(0) => Err(InputContextError::CannotPopBase),
+        Some(index) => Ok(data.stack.remove(index)),
+        None => Err(InputContextError::UnknownContext(name.to_string())),
+    }
+}
+
+/// Whether a context named `name` is on the stack
+pub fn input_context_active(data: &InputContextData, name: &str) -> bool {
+    data.stack.iter().any(|context| context.name == name)
+}
+
+/// Name of the context on top of the stack
+pub fn top_input_context(data: &InputContextData) -> Option<&str> {
+    data.stack.last().map(|context| context.name.as_str())
+}
+
+/// Context that receives `action` when `trigger` fires, or `None` when a
+/// context above the claiming one swallows it
+pub fn action_context<'a>(
+    data: &'a InputContextData,
+    action: &str,
+    trigger: InputTrigger,
+) -> Option<&'a str> {
+    for context in data.stack.iter().rev() {
+        if context.actions.contains(action) {
+            return Some(&context.name);
+        }
+        let swallows_key = context.text_input && matches!(trigger, InputTrigger::Key(_));
+        if context.fallthrough == ContextFallthrough::Opaque || swallows_key {
+            return None;
+        }
+    }
+    None
+}
+
+/// Whether any trigger of `action` is held and reaches its context
+pub fn action_pressed(data: &InputContextData, input: &InputState, action: &str) -> bool {
+    data.bindings.get(action).is_some_and(|triggers| {
+        triggers.iter().any(|trigger| {
+            let held = match *trigger {
+                InputTrigger::Key(key) => input.is_key_pressed(key),
+                InputTrigger::Mouse(button) => input.is_mouse_button_pressed(button),
+            };
+            held && action_context(data, action, *trigger).is_some()
+        })
+    })
+}
+
+/// Whether the stack wants the cursor grabbed: the topmost context that
+/// does not inherit decides
+pub fn mouse_captured(data: &InputContextData) -> bool {
+    data.stack
+        .iter()
+        .rev()
+        .map(|context| context.mouse)
+        .find(|mouse| *mouse != MouseCapturePolicy::Inherit)
+        == Some(MouseCapturePolicy::Capture)
+}
+
+/// Set `cursor_locked` from the stack; returns the new value
+pub fn apply_mouse_capture(data: &InputContextData, input: &mut InputState) -> bool {
+    let captured = mouse_captured(data);
+    if captured != input.cursor_locked {
+        input.cursor_locked = captured;
+        // Motion from before the switch belongs to the old mode
+        input.reset_mouse_tracking();
+    }
+    captured
+}
+
+fn bind_trigger(bindings: &mut ActionBindings, action: &str, trigger: InputTrigger) {
+    let triggers = bindings.entry(action.to_string()).or_default();
+    if !triggers.contains(&trigger) {
+        triggers.push(trigger);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::event::ElementState;
+
+    #[test]
+    fn console_swallows_gameplay_until_popped() {
+        let mut data = create_input_contexts(default_action_bindings());
+        let mut input = InputState::new();
+        input.process_key(KeyCode::KeyW, ElementState::Pressed);
+        input.process_mouse_button(MouseButton::Left, ElementState::Pressed);
+        assert!(action_pressed(&data, &input, "move_forward"));
+        assert!(action_pressed(&data, &input, "break_block"));
+        assert!(apply_mouse_capture(&data, &mut input));
+
+        push_input_context(&mut data, console_context()).expect("push console");
+        assert!(!action_pressed(&data, &input, "move_forward"));
+        assert!(!action_pressed(&data, &input, "break_block"));
+        assert!(!apply_mouse_capture(&data, &mut input));
+        assert!(!input.cursor_locked);
+
+        input.process_key(KeyCode::Escape, ElementState::Pressed);
+        assert!(action_pressed(&data, &input, "console_close"));
+        assert_eq!(
+            action_context(&data, "open_menu", InputTrigger::Key(KeyCode::Escape)),
+            None
+        );
+
+        assert_eq!(
+            pop_input_context(&mut data, GAMEPLAY_CONTEXT),
+            Err(InputContextError::CannotPopBase)
+        );
+        pop_input_context(&mut data, CONSOLE_CONTEXT).expect("pop console");
+        assert!(action_pressed(&data, &input, "move_forward"));
+        assert!(apply_mouse_capture(&data, &mut input));
+    }
+
+    #[test]
+    fn text_overlay_passes_mouse_but_not_keys() {
+        let mut data = create_input_contexts(default_action_bindings());
+        let chat = create_input_context(
+            "chat",
+            &["console_submit"],
+            ContextFallthrough::PassUnclaimed,
+            MouseCapturePolicy::Inherit,
+            true,
+        );
+        push_input_context(&mut data, chat).expect("push chat");
+        assert_eq!(top_input_context(&data), Some("chat"));
+        assert!(mouse_captured(&data));
+
+        let key = InputTrigger::Key(KeyCode::KeyW);
+        let click = InputTrigger::Mouse(MouseButton::Left);
+        assert_eq!(action_context(&data, "move_forward", key), None);
+        assert_eq!(
+            action_context(&data, "break_block", click),
+            Some(GAMEPLAY_CONTEXT)
+        );
+
+        // A menu opened over the chat swallows the click too
+        push_input_context(&mut data, ui_context()).expect("push ui");
+        assert_eq!(action_context(&data, "break_block", click), None);
+        pop_input_context(&mut data, "chat").expect("pop chat under menu");
+        assert!(input_context_active(&data, UI_CONTEXT));
+        assert!(!input_context_active(&data, "chat"));
+    }
+}
@@ -1,3 +1,28 @@
+pub mod haptics_data;
+pub mod haptics_operations;
+pub mod input_context_data;
+pub mod input_context_operations;
+
+pub use haptics_data::{
+    ActiveRumble, ActiveRumbles, GamepadId, HapticCue, HapticsData, MotorLevels, PlayerHapticCue,
+    RumbleCommand, RumbleEnvelope, RumblePattern,
+};
+pub use haptics_operations::{
+    assign_gamepad, create_haptics, cue_pattern, event_haptic_cue, explosion_rumble_intensity,
+    gamepad_motor_levels, player_gamepad, rumble_gain, set_rumble_enabled, set_rumble_strength,
+    trigger_rumble, unassign_gamepad, update_haptics,
+};
+pub use input_context_data::{
+    ActionBindings, ContextFallthrough, InputContext, InputContextData, InputContextError,
+    InputContextResult, InputTrigger, MouseCapturePolicy,
+};
+pub use input_context_operations::{
+    action_context, action_pressed, apply_mouse_capture, bind_action, console_context,
+    create_input_context, create_input_contexts, default_action_bindings, gameplay_context,
+    input_context_active, mouse_captured, pop_input_context, push_input_context,
+    top_input_context, ui_context, unbind_action,
+};
+
 use std::collections::HashSet;
 use winit::event::{ElementState, MouseButton};
 pub use winit::keyboard::KeyCode;
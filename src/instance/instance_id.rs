@@ -4,6 +4,8 @@ use crate::instance::error::{timestamp_error, InstanceResult};
 /// Provides unique identifiers for every instance in the game.
 /// Uses 128-bit UUIDs for globally unique identification.
 /// Supports efficient serialization and network transmission.
+use rand::rngs::OsRng;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -18,11 +20,12 @@ pub struct InstanceId {
 }
 
 impl InstanceId {
-    /// Create a new random instance ID
+    /// Create a new random instance ID (from OS entropy; ids must be unique
+    /// across worlds, so they do not come from a world random stream)
     pub fn new() -> Self {
         Self {
-            high: rand::random(),
-            low: rand::random(),
+            high: OsRng.next_u64(),
+            low: OsRng.next_u64(),
         }
     }
 
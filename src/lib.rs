@@ -25,10 +25,11 @@ pub mod panic_handler;
 pub use engine_buffers::{
     EngineBuffers, SharedEngineBuffers, create_engine_buffers, create_shared_buffers,
     WorldBuffers, RenderBuffers, PhysicsBuffers, NetworkBuffers, InputBuffers,
-    ParticleBuffers, MetricsBuffers,
+    ParticleBuffers, MetricsBuffers, TransformBuffers, EntityTagBuffers,
 };
 
 // Essential systems
+pub mod audio;
 pub mod camera;
 pub mod game;
 pub mod input;
@@ -39,6 +40,7 @@ pub mod network;
 pub mod particles;
 pub mod persistence;
 pub mod physics;
+pub mod plugin;
 pub mod renderer;
 // World module - GPU-first unified architecture
 pub mod world;
@@ -47,17 +49,37 @@ pub mod world;
 pub mod gpu;
 
 // Utilities
+pub mod activation_range_data;
+pub mod activation_range_operations;
+pub mod entity_tag_data;
+pub mod entity_tag_operations;
+pub mod engine_gpu_world_data;
+pub mod engine_gpu_world_operations;
+pub mod engine_world_data;
+pub mod engine_world_operations;
 pub mod event_system;
 pub mod event_system_data;
 pub mod event_system_operations;
 pub mod event_streams;
+pub mod feature_flags;
 pub mod instance;
+pub mod localization;
+pub mod logging;
 pub mod process;
+pub mod profiling;
+pub mod simd_data;
+pub mod simd_operations;
+pub mod simulation_scaling_data;
+pub mod simulation_scaling_operations;
 pub mod system_monitor;
 pub mod system_monitor_data;
 pub mod system_monitor_operations;
 pub mod thread_pool;
 pub mod utils;
+pub mod view_distance_data;
+pub mod view_distance_operations;
+pub mod world_random_data;
+pub mod world_random_operations;
 pub mod world_state;
 
 use anyhow::Result;
@@ -111,6 +133,8 @@ pub enum WorldGeneratorType {
     Default,
     DangerMoney,
     Custom(String),
+    /// Built-in superflat, debug or single-biome world
+    Preset(world::generation::WorldPreset),
 }
 
 /// Factory function type for creating world generators when GPU resources are available
@@ -135,6 +159,23 @@ pub struct EngineConfig {
     pub world_generator: Option<Box<dyn WorldGenerator + Send + Sync>>,
     pub world_generator_type: WorldGeneratorType,
     pub world_generator_factory: Option<WorldGeneratorFactory>,
+    /// Seed of the world: presets, decoration and props draw from it, and
+    /// factories receive it with the config
+    pub world_seed: u32,
+    /// Frame rate cap while focused (None = uncapped)
+    pub fps_cap: Option<u32>,
+    /// Present with vsync
+    pub vsync: bool,
+    /// Encrypt chunk saves and journals with this key (None = plaintext);
+    /// `Engine::save_cipher` seals the game's own files such as scoreboards.
+    /// Existing worlds can be converted with
+    /// `persistence::migrate_save_encryption`.
+    pub save_encryption_key: Option<persistence::SaveEncryptionKey>,
+    /// Draw grass, dirt and sand as a smooth surface instead of cubes
+    pub smooth_terrain: bool,
+    /// Load every native plugin library in this directory at startup
+    /// (None = no plugins)
+    pub plugin_dir: Option<std::path::PathBuf>,
 }
 
 impl std::fmt::Debug for EngineConfig {
@@ -160,6 +201,15 @@ impl std::fmt::Debug for EngineConfig {
                     .as_ref()
                     .map(|_| "<WorldGenerator Factory>"),
             )
+            .field("world_seed", &self.world_seed)
+            .field("fps_cap", &self.fps_cap)
+            .field("vsync", &self.vsync)
+            .field(
+                "save_encryption_key",
+                &self.save_encryption_key.as_ref().map(|_| "<redacted>"),
+            )
+            .field("smooth_terrain", &self.smooth_terrain)
+            .field("plugin_dir", &self.plugin_dir)
             .finish()
     }
 }
@@ -168,16 +218,7 @@ impl EngineConfig {
     /// Validate configuration parameters
     pub fn validate(&self) -> Result<()> {
         // Validate chunk size
-        if self.chunk_size == 0 {
-            return Err(anyhow::anyhow!("EngineConfig: chunk_size cannot be 0"));
-        }
-
-        if self.chunk_size > 256 {
-            return Err(anyhow::anyhow!(
-                "EngineConfig: chunk_size {} exceeds maximum of 256",
-                self.chunk_size
-            ));
-        }
+        self.chunk_layout()?;
 
         // Validate render distance
         if self.render_distance == 0 {
@@ -199,15 +240,16 @@ impl EngineConfig {
             self.chunk_size, voxels_per_chunk, chunk_memory_bytes / 1024, max_safe_view_distance
         );
 
-        // Validate render distance against GPU memory limits
+        // Render distances above the GPU memory limit are capped at runtime by
+        // the view distance controller instead of being rejected
         if self.render_distance > max_safe_view_distance {
-            return Err(anyhow::anyhow!(
-                "EngineConfig: render_distance {} exceeds GPU memory limit. Maximum safe render_distance for chunk_size {} is {}. {}",
+            log::warn!(
+                "[EngineConfig] render_distance {} exceeds the GPU memory limit for chunk_size {}; \
+                 the effective view distance starts at {}",
                 self.render_distance,
                 self.chunk_size,
-                max_safe_view_distance,
-                self.suggest_safe_config()
-            ));
+                max_safe_view_distance
+            );
         }
 
         // Validate window dimensions
@@ -227,6 +269,12 @@ impl EngineConfig {
         Ok(())
     }
 
+    /// Runtime chunk layout for `chunk_size`
+    pub fn chunk_layout(&self) -> Result<world::ChunkLayout> {
+        world::create_chunk_layout(self.chunk_size)
+            .map_err(|e| anyhow::anyhow!("EngineConfig: {}", e))
+    }
+
     /// Calculate safe view distance for a given chunk size
     pub fn calculate_safe_view_distance(chunk_size: u32) -> u32 {
         let voxel_data_size = 4u64; // 4 bytes per voxel
@@ -275,20 +323,261 @@ impl Default for EngineConfig {
             world_generator: None, // Use engine's default generator when None
             world_generator_type: WorldGeneratorType::Default,
             world_generator_factory: None, // Use engine's default generator when None
+            world_seed: crate::constants::engine_world::DEFAULT_WORLD_SEED,
+            fps_cap: None,
+            vsync: true,
+            save_encryption_key: None,
+            smooth_terrain: false,
+            plugin_dir: None,
         }
     }
 }
 
+/// Result of one host-driven frame (see [`Engine::frame`])
+#[derive(Debug, Clone, Default)]
+pub struct FrameResult {
+    /// Index of this frame, starting at 0
+    pub frame_number: u64,
+    /// Seconds since the previous frame (0 on the first frame)
+    pub delta_time: f32,
+    /// Fixed simulation ticks the game should run before the next frame;
+    /// call `renderer::begin_transform_tick` before each and
+    /// `renderer::capture_physics_transforms` after it
+    pub fixed_ticks: u32,
+    /// Blend between the previous and the latest tick used for this frame
+    pub interpolation_alpha: f32,
+    /// Whether a frame was rendered into the attached target
+    pub rendered: bool,
+    /// The host window asked to close
+    pub exit_requested: bool,
+    /// A resize event was processed this frame
+    pub resized: bool,
+    /// Current render target size in pixels
+    pub target_size: (u32, u32),
+    /// Mouse movement accumulated since the previous frame; zero while
+    /// the input contexts leave the cursor free
+    pub mouse_delta: (f32, f32),
+    /// Whether the host should grab and hide the cursor (set by the input
+    /// context stack)
+    pub cursor_locked: bool,
+    /// Gamepad motor changes to send this frame (rumble from the gateway)
+    pub rumble: Vec<input::RumbleCommand>,
+    /// Render error, if any (the host decides whether to continue)
+    pub error: Option<String>,
+    /// Engine events for the game (e.g. `GameEvent::ViewDistanceChanged`)
+    pub events: Vec<game::GameEvent>,
+}
+
+/// Frame pacer for the cap and vsync settings in `config`
+fn create_config_pacer(config: &EngineConfig) -> renderer::FramePacerData {
+    renderer::create_frame_pacer(renderer::FramePacerConfig {
+        fps_cap: config.fps_cap,
+        vsync: config.vsync,
+        ..renderer::default_frame_pacer_config()
+    })
+}
+
+/// View distance controller starting at the configured render distance,
+/// capped at the largest world buffer a GPU binding can hold
+fn create_config_view_distance(
+    config: &EngineConfig,
+    events: &mut Vec<game::GameEvent>,
+) -> view_distance_data::ViewDistanceControllerData {
+    let mut controller = view_distance_operations::create_view_distance_controller(
+        view_distance_operations::default_view_distance_config(config.render_distance),
+    );
+    events.extend(view_distance_operations::apply_memory_limit(
+        &mut controller,
+        EngineConfig::calculate_safe_view_distance(config.chunk_size),
+    ));
+    controller
+}
+
+/// Load the pipeline cache of the renderer's adapter, so the pipelines
+/// `configure_engine_renderer` builds are timed against the previous run
+fn configure_engine_pipeline_cache(renderer: &mut Renderer) {
+    let Some(adapter_info) = renderer.adapter_info.clone() else {
+        log::info!("[Engine] Adapter unknown, pipeline cache disabled");
+        return;
+    };
+    let cache_dir = std::path::Path::new(constants::persistence_constants::PIPELINE_CACHE_DIR);
+    renderer::enable_renderer_pipeline_cache(renderer, &adapter_info, cache_dir);
+}
+
+/// Apply the config's presentation settings to a renderer and enable the
+/// default sky, clouds, far terrain ring, placement ghost and anti-aliasing
+fn configure_engine_renderer(
+    config: &EngineConfig,
+    renderer: &mut Renderer,
+    pacer: &mut renderer::FramePacerData,
+) {
+    renderer::set_renderer_vsync(renderer, config.vsync);
+    renderer::set_display_refresh_rate(pacer, renderer::renderer_refresh_rate(renderer));
+    let anti_aliasing = renderer::AntiAliasingMode::Msaa {
+        samples: crate::constants::anti_aliasing::DEFAULT_MSAA_SAMPLES,
+    };
+    if let Err(e) = renderer::set_renderer_anti_aliasing(renderer, anti_aliasing) {
+        log::warn!("[Engine] Anti-aliasing disabled: {}", e);
+    }
+    if let Err(e) = renderer::enable_renderer_sky(renderer, renderer::default_sky_config()) {
+        log::warn!("[Engine] Sky disabled: {}", e);
+    }
+    if let Err(e) = renderer::enable_renderer_clouds(renderer, renderer::default_cloud_config()) {
+        log::warn!("[Engine] Clouds disabled: {}", e);
+    }
+    renderer::set_renderer_cloud_shadows(
+        renderer,
+        feature_flags::feature_enabled(feature_flags::FLAG_SHADOWS),
+    );
+    // The ring starts where the voxel chunks end
+    let inner_radius = (config.render_distance * config.chunk_size) as f32;
+    if let Err(e) = renderer::enable_renderer_far_terrain(renderer, inner_radius) {
+        log::warn!("[Engine] Far terrain disabled: {}", e);
+    }
+    if let Err(e) = renderer::enable_renderer_placement_preview(renderer) {
+        log::warn!("[Engine] Placement preview disabled: {}", e);
+    }
+}
+
+/// Preview held lights against the voxels of the GPU world
+fn configure_engine_light_preview(
+    renderer: &mut Renderer,
+    gpu_world: Option<&engine_gpu_world_data::EngineGpuWorldData>,
+    blocks: &BlockRegistry,
+) {
+    let Some(gpu_world) = gpu_world else {
+        return;
+    };
+    if let Err(e) =
+        renderer::enable_renderer_light_preview(renderer, &gpu_world.world_buffer, blocks)
+    {
+        log::warn!("[Engine] Light preview disabled: {}", e);
+    }
+}
+
+/// Feature flag changes accepted by the engine's hooks, waiting for the
+/// frame that applied them to rebuild the GPU state they gate
+type FeatureRebuildQueue = Arc<parking_lot::Mutex<Vec<(&'static str, bool)>>>;
+
+/// Apply the feature flag override file, switch the voxel kernels to the
+/// vectorized flag and register the hooks that rebuild the gated features
+/// when a flag changes at runtime. Erosion needs no hook: it is read for
+/// every generation batch, so it applies to chunks generated afterwards.
+fn register_engine_features(rebuilds: &FeatureRebuildQueue) -> Vec<feature_flags::FeatureFlagHookId> {
+    let config = std::path::Path::new(constants::feature_flags::CONFIG_FILE);
+    if let Err(e) = feature_flags::load_global_feature_flags(config) {
+        log::warn!("[Engine] Feature flag overrides not applied: {}", e);
+    }
+    let vectorized = feature_flags::feature_enabled(feature_flags::FLAG_VECTORIZED_TERRAIN);
+    if let Err(e) = simd_operations::set_voxel_kernels_vectorized(vectorized) {
+        log::warn!("[Engine] Vectorized voxel kernels unavailable: {}", e);
+    }
+
+    let mut ids = Vec::new();
+    let mut register = |flag: &str, hook: feature_flags::FeatureFlagHook| {
+        match feature_flags::add_global_feature_flag_hook(flag, hook) {
+            Ok(id) => ids.push(id),
+            Err(e) => log::error!("[Engine] Feature hook not registered: {}", e),
+        }
+    };
+    register(
+        feature_flags::FLAG_VECTORIZED_TERRAIN,
+        Box::new(simd_operations::set_voxel_kernels_vectorized),
+    );
+    // Occlusion frees or refills the visibility graph, shadows rewrite the
+    // cloud uniform; both need the GPU world or renderer the frame owns
+    for flag in [feature_flags::FLAG_HZB_OCCLUSION, feature_flags::FLAG_SHADOWS] {
+        let rebuilds = rebuilds.clone();
+        register(
+            flag,
+            Box::new(move |enabled| {
+                rebuilds.lock().push((flag, enabled));
+                Ok(())
+            }),
+        );
+    }
+    ids
+}
+
+/// Load the plugins of the config's plugin directory and start them on
+/// the world
+fn create_engine_plugins(config: &EngineConfig, seed: u32) -> plugin::PluginHostData {
+    let mut host = plugin::create_plugin_host();
+    if let Some(dir) = &config.plugin_dir {
+        if !dir.is_dir() {
+            log::warn!("[Engine] Plugin directory {} does not exist", dir.display());
+        }
+        for result in plugin::load_plugins_from_dir(&mut host, dir) {
+            if let Err(e) = result {
+                log::error!("[Engine] Plugin not loaded: {}", e);
+            }
+        }
+    }
+    // Hooks that fail disable their plugin and are logged
+    plugin::plugins_world_start(&mut host, seed);
+    host
+}
+
 /// Main engine struct that runs the game loop
 pub struct Engine {
     config: EngineConfig,
     event_loop: Option<EventLoop<()>>,
     /// Centralized engine data buffers (DOP architecture)
     buffers: SharedEngineBuffers,
+    /// Renderer attached to a host window/texture (embedded mode only)
+    renderer: Option<Renderer>,
+    input: input::InputState,
+    /// Action bindings and the gameplay/UI/console context stack
+    input_contexts: input::InputContextData,
+    frame_number: u64,
+    last_frame: Option<std::time::Instant>,
+    /// Frame limiter applied at the start of every embedded frame
+    pacer: renderer::FramePacerData,
+    /// Effective view distance, adapted to frame time and GPU memory
+    view_distance: view_distance_data::ViewDistanceControllerData,
+    /// Events waiting for the next `FrameResult`
+    pending_events: Vec<game::GameEvent>,
+    /// Alert rules whose mitigations lower the particle budget and render scale
+    monitor: system_monitor_data::SystemMonitorData,
+    /// Host-attached GPU particles, capped by `ParticleBuffers::particle_budget`
+    particles: Option<particles::GpuParticleSystem>,
+    /// Seconds the particle system has been simulated
+    particle_time: f32,
+    /// Generator from `world_generator_type` and the voxel data streamed
+    /// around the camera
+    world: engine_world_data::EngineWorldData,
+    /// GPU copy of the loaded chunks and their meshes (needs a renderer)
+    gpu_world: Option<engine_gpu_world_data::EngineGpuWorldData>,
+    /// Scratch memory of one frame, reset when the frame starts; shared
+    /// with the mesher and handed to generators and kernels
+    frame_arena: memory::SharedFrameArena,
+    /// Block properties the light preview reads emission and transparency from
+    blocks: BlockRegistry,
+    /// Block the player holds (None = the gateway's active block)
+    held_block: Option<BlockId>,
+    /// Orientation and model of directional blocks, for the placement ghost
+    block_schemas: world::blocks::MetadataSchemaRegistry,
+    /// Spectator camera that moves the engine camera each frame (None =
+    /// the host's camera from `set_camera`)
+    spectator: Option<camera::SpectatorState>,
+    /// Player resolved against the physics entities by `move_player`
+    player: physics::PlayerEntityCollisionState,
+    player_collision: physics::PlayerEntityCollisionConfig,
+    /// Occlusion of the playing sound sources, heard from the camera
+    audio: audio::AudioPropagationData,
+    /// Connections and packet capture; flushed once per frame
+    network: network::NetworkData,
+    /// Lockstep session run over `network` on every fixed tick
+    lockstep: Option<network::LockstepSessionData>,
+    /// Native plugins, ticked once per frame
+    plugins: plugin::PluginHostData,
+    /// Feature flag hooks of this engine, removed when it is dropped
+    feature_hooks: Vec<feature_flags::FeatureFlagHookId>,
+    feature_rebuilds: FeatureRebuildQueue,
 }
 
 impl Engine {
-    pub fn new(config: EngineConfig) -> Self {
+    pub fn new(mut config: EngineConfig) -> Self {
         log::debug!("[Engine::new] Starting engine initialization");
 
         // Validate configuration before proceeding
@@ -304,6 +593,12 @@ impl Engine {
             );
         }
 
+        // Shaders must be generated for the configured chunk size before any
+        // GPU world system is created
+        crate::gpu::automation::set_gpu_chunk_layout(config.chunk_layout().unwrap_or_default());
+        let feature_rebuilds = FeatureRebuildQueue::default();
+        let feature_hooks = register_engine_features(&feature_rebuilds);
+
         // Force X11 backend for WSL compatibility
         #[cfg(target_os = "linux")]
         let event_loop = {
@@ -337,18 +632,12 @@ impl Engine {
             }
         };
 
-        // Initialize GPU thread pool with DOP architecture
-        let thread_pool_config = thread_pool::GpuThreadPoolConfig::default();
-        let _gpu_thread_pool = match thread_pool::create_gpu_thread_pool_data(thread_pool_config) {
-            Ok(pool) => {
-                log::info!("[Engine::new] GPU thread pool initialized successfully");
-                pool
-            }
-            Err(e) => {
-                log::error!("[Engine::new] Failed to create GPU thread pool: {}", e);
-                panic!("Failed to create GPU thread pool: {}", e);
-            }
-        };
+        // Initialize the shared GPU thread pool with DOP architecture
+        if thread_pool::global_thread_pool().is_none() {
+            log::error!("[Engine::new] Failed to create GPU thread pool");
+            panic!("Failed to create GPU thread pool");
+        }
+        log::info!("[Engine::new] GPU thread pool initialized successfully");
 
         // Initialize engine buffers (DOP architecture)
         let buffers = create_shared_buffers();
@@ -356,42 +645,1318 @@ impl Engine {
 
         log::info!("[Engine::new] Engine initialization complete");
 
+        let pacer = create_config_pacer(&config);
+        let mut pending_events = Vec::new();
+        let view_distance = create_config_view_distance(&config, &mut pending_events);
+        let world = match engine_world_operations::create_engine_world(&mut config, None) {
+            Ok(world) => world,
+            Err(e) => {
+                log::error!("[Engine::new] Failed to create the world generator: {}", e);
+                panic!("Invalid engine configuration: {}", e);
+            }
+        };
+        let plugins = create_engine_plugins(&config, world.world.seed);
         Self {
             config,
             event_loop: Some(event_loop),
             buffers,
+            renderer: None,
+            input: input::InputState::new(),
+            input_contexts: input::create_input_contexts(input::default_action_bindings()),
+            frame_number: 0,
+            last_frame: None,
+            pacer,
+            view_distance,
+            pending_events,
+            monitor: system_monitor_operations::create_default_system_monitor(0),
+            particles: None,
+            particle_time: 0.0,
+            world,
+            gpu_world: None,
+            frame_arena: memory::create_shared_frame_arena(
+                constants::frame_arena::DEFAULT_FRAME_ARENA_BYTES,
+            ),
+            blocks: BlockRegistry::new(),
+            held_block: None,
+            block_schemas: world::blocks::create_metadata_schema_registry(),
+            spectator: None,
+            player: physics::PlayerEntityCollisionState {
+                position: [0.0; 3],
+                velocity: [0.0; 3],
+                ground_entity: None,
+            },
+            player_collision: physics::default_player_entity_collision_config(),
+            audio: audio::create_audio_propagation(audio::default_audio_propagation_config()),
+            network: network::create_network(),
+            lockstep: None,
+            plugins,
+            feature_hooks,
+            feature_rebuilds,
         }
     }
 
-    pub fn run<G: GameData + 'static>(mut self, game: G) -> Result<()> {
-        log::info!("[Engine::run] Starting engine run method");
+    /// Create an engine driven by a host application's event loop.
+    ///
+    /// No event loop is created; the host calls [`Engine::frame`] once per
+    /// frame and the engine renders into the attached renderer's target.
+    pub fn new_embedded(mut config: EngineConfig, mut renderer: Renderer) -> Result<Self> {
+        config.validate()?;
+        crate::gpu::automation::set_gpu_chunk_layout(config.chunk_layout()?);
+        let feature_rebuilds = FeatureRebuildQueue::default();
+        let feature_hooks = register_engine_features(&feature_rebuilds);
 
-        let event_loop = match self.event_loop.take() {
-            Some(loop_) => {
-                log::debug!("[Engine::run] Event loop retrieved successfully");
-                loop_
+        let mut pacer = create_config_pacer(&config);
+        configure_engine_pipeline_cache(&mut renderer);
+        configure_engine_renderer(&config, &mut renderer, &mut pacer);
+        let mut pending_events = Vec::new();
+        let view_distance = create_config_view_distance(&config, &mut pending_events);
+        let world = engine_world_operations::create_engine_world(
+            &mut config,
+            Some((renderer.device.clone(), renderer.queue.clone())),
+        )?;
+
+        let frame_arena =
+            memory::create_shared_frame_arena(constants::frame_arena::DEFAULT_FRAME_ARENA_BYTES);
+        let gpu_world = engine_gpu_world_operations::create_engine_gpu_world(
+            renderer.device.clone(),
+            renderer.queue.clone(),
+            config.chunk_layout()?,
+            frame_arena.clone(),
+            config.smooth_terrain,
+            config.world_seed,
+        );
+        let blocks = BlockRegistry::new();
+        configure_engine_light_preview(&mut renderer, gpu_world.as_ref(), &blocks);
+        renderer::set_renderer_block_atlas(&mut renderer, &blocks);
+
+        let buffers = create_shared_buffers();
+        let plugins = create_engine_plugins(&config, world.world.seed);
+        game::complete_gateway_load_phase(game::LoadPhase::GpuInit);
+        log::info!("[Engine::new_embedded] Engine initialized in embedded mode");
+
+        Ok(Self {
+            config,
+            event_loop: None,
+            buffers,
+            renderer: Some(renderer),
+            input: input::InputState::new(),
+            input_contexts: input::create_input_contexts(input::default_action_bindings()),
+            frame_number: 0,
+            last_frame: None,
+            pacer,
+            view_distance,
+            pending_events,
+            monitor: system_monitor_operations::create_default_system_monitor(0),
+            particles: None,
+            particle_time: 0.0,
+            world,
+            gpu_world,
+            frame_arena,
+            blocks,
+            held_block: None,
+            block_schemas: world::blocks::create_metadata_schema_registry(),
+            spectator: None,
+            player: physics::PlayerEntityCollisionState {
+                position: [0.0; 3],
+                velocity: [0.0; 3],
+                ground_entity: None,
+            },
+            player_collision: physics::default_player_entity_collision_config(),
+            audio: audio::create_audio_propagation(audio::default_audio_propagation_config()),
+            network: network::create_network(),
+            lockstep: None,
+            plugins,
+            feature_hooks,
+            feature_rebuilds,
+        })
+    }
+
+    /// Advance one frame with the window events the host received since the
+    /// previous call, then render into the attached target.
+    pub fn frame(&mut self, input_events: &[winit::event::WindowEvent]) -> FrameResult {
+        use winit::event::{MouseScrollDelta, WindowEvent};
+        use winit::keyboard::PhysicalKey;
+
+        let _span = trace_span!(Frame, "Engine::frame");
+        renderer::wait_for_next_frame(&mut self.pacer);
+        {
+            let mut arena = self.frame_arena.lock();
+            memory::reset_frame_arena(&mut arena);
+            memory::record_frame_arena_metrics(&arena, &mut self.buffers.write().metrics);
+        }
+        memory::record_gpu_allocation_metrics(&mut self.buffers.write().metrics);
+        // Flag toggles queued since the last frame take effect here
+        feature_flags::apply_global_feature_flag_changes();
+        self.apply_feature_rebuilds();
+        feature_flags::record_feature_flag_metrics(&mut self.buffers.write().metrics);
+        self.update_view_distance();
+        let view_distance = self.view_distance();
+        let loaded = engine_world_operations::stream_engine_world(&mut self.world, view_distance);
+        // Paths traced before these chunks arrived went through open air
+        audio::invalidate_audio_occlusion_in_chunks(
+            &mut self.audio,
+            &loaded,
+            self.config.chunk_size,
+        );
+        // Unloaded chunks are still on the GPU until the sync below
+        let gpu_world = &mut self.gpu_world;
+        engine_world_operations::write_out_engine_world_chunks(&mut self.world, |pos| {
+            gpu_world
+                .as_mut()
+                .and_then(|gpu| engine_gpu_world_operations::read_engine_chunk_light(gpu, pos))
+        });
+        self.invalidate_edited_light_preview();
+        match self.gpu_world.as_mut() {
+            Some(gpu_world) => {
+                engine_gpu_world_operations::sync_engine_gpu_world(gpu_world, &mut self.world);
+                // Cells the sync invalidated regenerate around the last camera
+                engine_gpu_world_operations::stream_engine_props(gpu_world, &self.world);
+                engine_gpu_world_operations::cull_engine_gpu_world(
+                    gpu_world,
+                    &self.world,
+                    view_distance,
+                );
+                engine_gpu_world_operations::defragment_engine_mesh_arenas(gpu_world);
+                engine_gpu_world_operations::record_engine_gpu_world_metrics(
+                    gpu_world,
+                    &mut self.buffers.write().metrics,
+                );
+                engine_gpu_world_operations::poll_engine_voxel_inspections(gpu_world);
             }
             None => {
-                log::error!("[Engine::run] Event loop already taken!");
-                panic!("Event loop already taken");
+                self.world.pending_edits.clear();
+                self.world.baked_light.clear();
             }
+        }
+        system_monitor_operations::update_system_monitor(
+            &mut self.monitor,
+            &mut self.buffers.write(),
+        );
+
+        let now = std::time::Instant::now();
+        let mut result = FrameResult {
+            frame_number: self.frame_number,
+            delta_time: self
+                .last_frame
+                .map(|last| now.duration_since(last).as_secs_f32())
+                .unwrap_or(0.0),
+            ..Default::default()
         };
+        self.last_frame = Some(now);
+        self.frame_number += 1;
 
-        let config = self.config;
-        let buffers = self.buffers;
-        log::info!(
-            "[Engine::run] Calling renderer::run with config: {:?}",
-            config
+        // The ticks handed out last frame have run since, so the alpha left
+        // over from then is the one that matches the transforms
+        result.interpolation_alpha = renderer::interpolation_alpha(&self.pacer);
+        result.fixed_ticks = renderer::advance_fixed_ticks(
+            &mut self.pacer,
+            std::time::Duration::from_secs_f32(result.delta_time),
         );
+        self.world.world.tick += result.fixed_ticks as u64;
+        engine_world_operations::tick_engine_world_processes(
+            &mut self.world,
+            result.fixed_ticks as u64,
+        );
+        // Physics and block random ticks only run in range of a player
+        {
+            let mut buffers = self.buffers.write();
+            engine_world_operations::update_engine_world_activation(
+                &mut self.world,
+                &buffers.physics,
+            );
+            let active = activation_range_operations::physics_active_entities(
+                &self.world.activation,
+            );
+            let interval = self.pacer.config.fixed_tick_interval;
+            let tick_seconds = if interval.is_zero() {
+                result.delta_time
+            } else {
+                interval.as_secs_f32()
+            };
+            for _ in 0..result.fixed_ticks {
+                physics::integrate_physics_entities(&mut buffers.physics, &active, tick_seconds);
+            }
+        }
+        engine_world_operations::tick_engine_world_random_ticks(
+            &mut self.world,
+            result.fixed_ticks as u64,
+        );
+        if let Some(gpu_world) = self.gpu_world.as_mut() {
+            engine_gpu_world_operations::tick_engine_custom_passes(gpu_world, result.fixed_ticks);
+        }
+        engine_world_operations::tick_engine_world_save(&mut self.world, now);
+        // Chunks with a draw have rendered; their decorations remesh them
+        // at the next sync
+        let rendered: Vec<ChunkPos> = self
+            .gpu_world
+            .as_ref()
+            .map(|gpu_world| gpu_world.draw_slots.keys().copied().collect())
+            .unwrap_or_default();
+        engine_world_operations::decorate_engine_world(
+            &mut self.world,
+            &rendered,
+            result.delta_time,
+        );
+        // Plugin requests reach the gateway in the frame they were made;
+        // gateway requests are answered from the world as this frame's
+        // ticks left it
+        plugin::plugins_tick(&mut self.plugins, self.world.world.tick, result.delta_time);
+        plugin::submit_plugin_outbox(plugin::drain_plugin_outbox(&mut self.plugins));
+        game::update_gateway(
+            &self.world.world,
+            self.config.chunk_size,
+            (result.delta_time * 1000.0).round() as u64,
+        );
+        // Player edits the gateway accepted (claims already checked); like
+        // every edit they cancel or pause the processes bound to the block
+        for edit in game::take_gateway_block_edits() {
+            if let Err(e) = self.set_block(edit.position, edit.block_id, edit.metadata) {
+                log::warn!(
+                    "[Engine] Edit by player {} at {:?} not applied: {}",
+                    edit.player_id,
+                    edit.position,
+                    e
+                );
+            }
+        }
 
-        // Pass buffers to renderer for DOP architecture
-        let result = renderer::run_with_buffers(event_loop, config, game, buffers);
+        let mut scroll_steps = 0.0;
+        for event in input_events {
+            match event {
+                WindowEvent::CloseRequested => result.exit_requested = true,
+                WindowEvent::Resized(size) => {
+                    if let Some(renderer) = self.renderer.as_mut() {
+                        renderer::resize_renderer(renderer, size.width, size.height);
+                    }
+                    result.resized = true;
+                }
+                WindowEvent::KeyboardInput { event, .. } => {
+                    if let PhysicalKey::Code(key) = event.physical_key {
+                        self.input.process_key(key, event.state);
+                    }
+                }
+                WindowEvent::MouseInput { state, button, .. } => {
+                    self.input.process_mouse_button(*button, *state);
+                }
+                WindowEvent::MouseWheel { delta, .. } => {
+                    scroll_steps += match delta {
+                        MouseScrollDelta::LineDelta(_, y) => *y,
+                        MouseScrollDelta::PixelDelta(position) => {
+                            position.y as f32 / constants::spectator::SCROLL_PIXELS_PER_STEP
+                        }
+                    };
+                }
+                WindowEvent::Focused(focused) => {
+                    if !focused {
+                        self.input.reset_mouse_tracking();
+                    }
+                    renderer::set_window_focused(&mut self.pacer, *focused);
+                }
+                _ => {}
+            }
+        }
 
-        match &result {
-            Ok(_) => log::info!("[Engine::run] Renderer returned successfully"),
-            Err(e) => log::error!("[Engine::run] Renderer error: {}", e),
+        self.simulate_particles(result.delta_time);
+        self.update_spectator(result.delta_time, scroll_steps);
+        self.update_audio();
+        self.update_held_block_previews();
+        self.update_network(result.delta_time, result.fixed_ticks);
+
+        if let Some(renderer) = self.renderer.as_mut() {
+            // Entities are drawn between the last two ticks the game ran
+            {
+                let mut buffers = self.buffers.write();
+                renderer::interpolate_entity_transforms(
+                    &mut buffers.transforms,
+                    result.interpolation_alpha,
+                );
+                renderer::update_renderer_entities(renderer, &buffers.transforms);
+                if let Some(camera) = &self.world.camera {
+                    renderer::update_renderer_entity_lod(renderer, camera);
+                }
+                renderer::record_entity_lod_metrics(&renderer.entity_lod, &mut buffers.metrics);
+                renderer::set_renderer_render_scale(renderer, buffers.render.render_scale);
+            }
+            if let Some(camera) = &self.world.camera {
+                let terrain = engine_world_operations::engine_world_terrain_params(&self.world);
+                let surface = self.world.generator.far_surface();
+                renderer::update_renderer_far_terrain(renderer, camera, &terrain, surface);
+            }
+            let atlas_distance = match (&self.world.camera, &self.gpu_world) {
+                (Some(camera), Some(gpu_world)) => {
+                    engine_gpu_world_operations::nearest_drawn_chunk_distance(
+                        gpu_world,
+                        camera.position.into(),
+                    )
+                }
+                _ => f32::INFINITY,
+            };
+            {
+                let mut buffers = self.buffers.write();
+                let memory = renderer::memory_budget_view(&self.monitor, &buffers);
+                renderer::update_renderer_texture_streaming(renderer, atlas_distance, memory);
+                renderer::record_texture_streaming_metrics(
+                    &renderer.texture_streaming,
+                    &mut buffers.metrics,
+                );
+                if let Some(cache) = &renderer.pipeline_cache {
+                    renderer::record_pipeline_cache_metrics(cache, &mut buffers.metrics);
+                }
+            }
+            result.target_size = renderer::render_target_size(renderer);
+            match renderer::render_embedded_frame(renderer) {
+                Ok(rendered) => result.rendered = rendered,
+                Err(e) => {
+                    log::error!("[Engine::frame] Render failed: {}", e);
+                    result.error = Some(e);
+                }
+            }
         }
 
+        result.cursor_locked = input::apply_mouse_capture(&self.input_contexts, &mut self.input);
+        if result.cursor_locked {
+            result.mouse_delta = self.input.get_mouse_delta();
+        }
+        self.input.clear_mouse_delta();
+        result.rumble = game::update_gateway_haptics(result.delta_time);
+        renderer::record_frame_pacing(&mut self.buffers.write().metrics, &self.pacer);
+        result.events = std::mem::take(&mut self.pending_events);
         result
     }
+
+    /// Rebuild what the feature flag changes applied this frame gate
+    fn apply_feature_rebuilds(&mut self) {
+        let changes = std::mem::take(&mut *self.feature_rebuilds.lock());
+        for (flag, enabled) in changes {
+            match flag {
+                feature_flags::FLAG_HZB_OCCLUSION => {
+                    if let Some(gpu_world) = self.gpu_world.as_mut() {
+                        engine_gpu_world_operations::set_engine_occlusion_culling(
+                            gpu_world, enabled,
+                        );
+                    }
+                }
+                feature_flags::FLAG_SHADOWS => {
+                    if let Some(renderer) = self.renderer.as_mut() {
+                        renderer::set_renderer_cloud_shadows(renderer, enabled);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Record the previous frame's time (without the pacing wait) and let
+    /// the view distance controller react to it
+    fn update_view_distance(&mut self) {
+        let stats = self.pacer.stats;
+        let mut buffers = self.buffers.write();
+        if stats.frames > 1 {
+            let frame_times = &mut buffers.metrics.frame_times;
+            frame_times.push_back((stats.last_frame_ms - stats.last_wait_ms).max(0.0));
+            while frame_times.len() > crate::constants::view_distance::FRAME_TIME_HISTORY {
+                frame_times.pop_front();
+            }
+        }
+        let events = view_distance_operations::update_view_distance_from_buffers(
+            &mut self.view_distance,
+            &buffers,
+            0,
+        );
+        self.pending_events.extend(events);
+    }
+
+    /// Advance the attached particle system within the current budget
+    fn simulate_particles(&mut self, delta_time: f32) {
+        let Some(system) = self.particles.as_mut() else {
+            return;
+        };
+        let mut buffers = self.buffers.write();
+        system.set_particle_budget(buffers.particles.particle_budget);
+        self.particle_time += delta_time;
+        if let Err(e) = system.update(
+            std::time::Duration::from_secs_f32(delta_time),
+            self.particle_time,
+        ) {
+            log::warn!("[Engine::frame] Particle update failed: {}", e);
+        }
+        buffers.particles.particle_count = system.particle_count() as u32;
+    }
+
+    /// Move the engine camera with the spectator: flight from the movement
+    /// actions, orbit from the mouse and scroll, or cinematic playback
+    fn update_spectator(&mut self, delta_time: f32, scroll_steps: f32) {
+        if self.spectator.is_none() {
+            return;
+        }
+        let Some(current) = self.world.camera else {
+            return;
+        };
+        let axis = |positive: &str, negative: &str| {
+            self.action_pressed(positive) as i32 as f32
+                - self.action_pressed(negative) as i32 as f32
+        };
+        let (look_x, look_y) = self.input.get_mouse_delta();
+        let input = camera::SpectatorInput {
+            movement: [
+                axis("move_forward", "move_back"),
+                axis("move_right", "move_left"),
+                axis("jump", "sneak"),
+            ],
+            look_delta: [look_x, look_y],
+            zoom_delta: scroll_steps,
+            boost: self.input.is_key_pressed(KeyCode::ControlLeft),
+        };
+        let Some(state) = self.spectator.as_mut() else {
+            return;
+        };
+        // Followed entities are read at their latest tick; the follow
+        // smoothing hides the steps between ticks
+        let target = state.follow.and_then(|follow| {
+            let buffers = self.buffers.read();
+            let position = buffers
+                .transforms
+                .current_positions
+                .get(follow.entity as usize)?;
+            Some(cgmath::Point3::new(position[0], position[1], position[2]))
+        });
+        let next = camera::update_spectator(state, &current, &input, target, delta_time);
+        self.set_camera(&next);
+    }
+
+    /// Gain and low-pass cutoff of each playing source as heard from the
+    /// camera, traced through the loaded voxels
+    fn update_audio(&mut self) {
+        let Some(camera) = &self.world.camera else {
+            return;
+        };
+        let world = &self.world.world;
+        let chunk_size = self.config.chunk_size;
+        let listener = [camera.position.x, camera.position.y, camera.position.z];
+        audio::update_audio_propagation(&mut self.audio, listener, &|position| {
+            world::world_operations::get_block(world, position, chunk_size)
+        });
+    }
+
+    /// Run the lockstep session once per fixed tick, then release the
+    /// packets the connections' budgets allow, stamped (and captured) with
+    /// the current tick
+    fn update_network(&mut self, delta_time: f32, fixed_ticks: u32) {
+        network::set_network_tick(&mut self.network, self.world.world.tick);
+        if let Some(session) = self.lockstep.as_mut() {
+            for _ in 0..fixed_ticks {
+                if let Err(e) = network::tick_lockstep_session(session, &mut self.network) {
+                    log::error!("[Engine::frame] Lockstep tick failed: {}", e);
+                }
+            }
+        }
+        network::flush_network(&mut self.network, delta_time);
+    }
+
+    /// Recompute the light preview when this frame's edits reach into it;
+    /// runs before the GPU sync consumes them
+    fn invalidate_edited_light_preview(&mut self) {
+        let Some(preview) = self
+            .renderer
+            .as_mut()
+            .and_then(|renderer| renderer.light_preview.as_mut())
+        else {
+            return;
+        };
+        let Some(previewed) = preview.previewed else {
+            return;
+        };
+        let radius = constants::light_preview::PREVIEW_RADIUS as i32;
+        let reached = self.world.pending_edits.iter().any(|edit| {
+            (edit.position.x - previewed.target.x).abs() <= radius
+                && (edit.position.y - previewed.target.y).abs() <= radius
+                && (edit.position.z - previewed.target.z).abs() <= radius
+        });
+        if reached {
+            renderer::invalidate_light_preview(preview);
+        }
+    }
+
+    /// Preview the held block at the voxel it would be placed in: against
+    /// the face the camera looks at, within placement reach. The ghost
+    /// shows its model and orientation, the light preview its emission.
+    fn update_held_block_previews(&mut self) {
+        let (Some(renderer), Some(camera)) = (self.renderer.as_mut(), self.world.camera.as_ref())
+        else {
+            return;
+        };
+        let held = self
+            .held_block
+            .or_else(|| game::is_gateway_initialized().then(game::get_active_block));
+        let hit = held.and_then(|_| {
+            let forward =
+                camera::calculate_forward_vector(camera.yaw_radians, camera.pitch_radians);
+            world::world_operations::raycast(
+                &self.world.world,
+                Ray::new(camera.position, forward),
+                constants::placement_preview::MAX_REACH,
+                self.config.chunk_size,
+            )
+        });
+
+        let player =
+            physics::player_bounds(&self.player_collision.shape, self.player.position.into());
+        let placement = held.zip(hit.as_ref()).and_then(|(block, hit)| {
+            renderer::compute_placement_preview(
+                &self.world.world,
+                &self.block_schemas,
+                camera,
+                hit,
+                block,
+                &player,
+                self.config.chunk_size,
+            )
+        });
+        renderer::update_renderer_placement_preview(renderer, camera, placement);
+
+        let Some(gpu_world) = self.gpu_world.as_ref() else {
+            return;
+        };
+        let emission = held
+            .map(|block| renderer::held_block_emission(&self.blocks, block))
+            .unwrap_or(0);
+        let target = hit.filter(|_| emission > 0).map(|hit| {
+            let offset = hit.face.offset();
+            VoxelPos::new(
+                hit.position.x + offset.x,
+                hit.position.y + offset.y,
+                hit.position.z + offset.z,
+            )
+        });
+        renderer::update_renderer_light_preview(
+            renderer,
+            &gpu_world.world_buffer,
+            camera,
+            target,
+            emission,
+        );
+    }
+
+    /// Register a block; emissive blocks get a light preview when held, and
+    /// its color a tile in the streamed block atlas
+    pub fn register_block(
+        &mut self,
+        name: &str,
+        properties: engine_buffers::BlockProperties,
+    ) -> BlockId {
+        let id = self.blocks.register_block(name, properties);
+        if let Some(renderer) = self.renderer.as_mut() {
+            if let Some(preview) = renderer.light_preview.as_mut() {
+                renderer::refresh_light_preview_blocks(preview, &renderer.queue, &self.blocks);
+            }
+            renderer::set_renderer_block_atlas(renderer, &self.blocks);
+        }
+        id
+    }
+
+    /// Hold `block` for placement previews; None follows the gateway's
+    /// active block
+    pub fn set_held_block(&mut self, block: Option<BlockId>) {
+        self.held_block = block;
+    }
+
+    /// Metadata schemas of the engine's blocks; register directional
+    /// blocks and block models here so the placement ghost shows them
+    pub fn block_schemas_mut(&mut self) -> &mut world::blocks::MetadataSchemaRegistry {
+        &mut self.block_schemas
+    }
+
+    /// Crafting, smelting and crop growth running in the world; seasons
+    /// set the growth rate
+    pub fn processes_mut(&mut self) -> &mut process::ProcessManager {
+        &mut self.world.processes
+    }
+
+    /// Chunks and physics entities in range of a player; hand it to
+    /// `game::update_gateway_behavior` to limit entity AI the same way
+    pub fn activation_ranges(&self) -> &activation_range_data::ActivationRangeData {
+        &self.world.activation
+    }
+
+    /// Activation distances and per-range stats
+    pub fn activation_ranges_mut(&mut self) -> &mut activation_range_data::ActivationRangeData {
+        &mut self.world.activation
+    }
+
+    /// Voxels picked for a block random tick during this frame's fixed
+    /// ticks, in chunks within the random tick range of a player
+    pub fn random_ticks(&self) -> &[engine_world_data::RandomTick] {
+        &self.world.random_ticks
+    }
+
+    /// Chunk simulation tiers and the catch-up counters; tune the distances
+    /// through its config
+    pub fn simulation_scaling_mut(
+        &mut self,
+    ) -> &mut simulation_scaling_data::SimulationScalingData {
+        &mut self.world.simulation
+    }
+
+    /// Processes cancelled since the last call because the block they were
+    /// bound to was broken, with their partial outputs and refunds
+    pub fn take_cancelled_processes(&mut self) -> Vec<process::CancelOutcome> {
+        std::mem::take(&mut self.world.cancelled_processes)
+    }
+
+    /// Attach a GPU particle system for the engine to simulate each frame;
+    /// adaptive quality caps it through `ParticleBuffers::particle_budget`
+    pub fn attach_particle_system(&mut self, system: particles::GpuParticleSystem) {
+        self.particles = Some(system);
+    }
+
+    /// The attached particle system, to add emitters
+    pub fn particle_system_mut(&mut self) -> Option<&mut particles::GpuParticleSystem> {
+        self.particles.as_mut()
+    }
+
+    /// Run a console command and return the text to print:
+    ///
+    /// - `inspect <x> <y> <z> [radius]` - read back voxels from the GPU
+    ///   world; the result is logged and kept in [`Engine::voxel_inspection`]
+    /// - `spectator ...` - fly, follow an entity or play a cinematic path
+    ///   from the engine camera; `spectator off` hands the camera back
+    /// - `netcap start <path>` / `netcap stop` - record every packet the
+    ///   network session sends and receives; `netcap dump [path]` prints
+    ///   the packet type summary of a capture
+    /// - `trace ...`, `log ...`, `feature ...` and `gpumem ...`
+    pub fn run_console_command(&mut self, command: &str) -> String {
+        let output = match command.split_whitespace().next() {
+            Some("inspect") => self.run_inspect_command(command),
+            Some("spectator") => self.run_spectator_command(command),
+            Some("trace") => profiling::run_trace_command(command).map_err(|e| e.to_string()),
+            Some("log") => logging::run_log_command(command).map_err(|e| e.to_string()),
+            Some("feature") => {
+                feature_flags::run_feature_command(command).map_err(|e| e.to_string())
+            }
+            Some("gpumem") => memory::run_gpu_memory_command(command).map_err(|e| e.to_string()),
+            Some("netcap") => network::run_network_command(&mut self.network, command),
+            _ => Err(format!("Unknown command: {}", command.trim())),
+        };
+        output.unwrap_or_else(|e| e)
+    }
+
+    fn run_inspect_command(&mut self, command: &str) -> std::result::Result<String, String> {
+        let request = world::storage::parse_inspect_command(command)?;
+        let gpu_world = self
+            .gpu_world
+            .as_mut()
+            .ok_or_else(|| "Inspecting voxels needs the GPU world of a renderer".to_string())?;
+        let id = engine_gpu_world_operations::inspect_engine_voxels(gpu_world, request);
+        Ok(format!(
+            "Inspection {} of ({}, {}, {}) radius {} requested",
+            id, request.center.x, request.center.y, request.center.z, request.radius
+        ))
+    }
+
+    fn run_spectator_command(&mut self, command: &str) -> std::result::Result<String, String> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        if words[1..] == ["off"] {
+            self.spectator = None;
+            return Ok(tr!("console.spectator.off"));
+        }
+        let current = self
+            .world
+            .camera
+            .ok_or_else(|| tr!("console.spectator.needs_camera"))?;
+        let state = self.spectator.get_or_insert_with(|| {
+            camera::create_spectator_state(camera::default_spectator_config())
+        });
+        camera::execute_spectator_command(state, &current, command).map_err(|e| e.to_string())
+    }
+
+    /// Spectator driving the engine camera, if one was started
+    pub fn spectator(&self) -> Option<&camera::SpectatorState> {
+        self.spectator.as_ref()
+    }
+
+    /// Camera the world streams around and the engine renders from
+    pub fn camera(&self) -> Option<&CameraData> {
+        self.world.camera.as_ref()
+    }
+
+    /// Newest completed voxel inspection, for the debug UI
+    /// ([`world::storage::inspection_lines`] formats it)
+    pub fn voxel_inspection(&self) -> Option<&world::storage::VoxelInspection> {
+        self.gpu_world
+            .as_ref()
+            .and_then(|gpu_world| gpu_world.inspector.latest.as_ref())
+    }
+
+    /// Alert rules and pending alert events (mitigations run each frame)
+    pub fn system_monitor_mut(&mut self) -> &mut system_monitor_data::SystemMonitorData {
+        &mut self.monitor
+    }
+
+    /// Centre world and prop streaming on the camera; call when it moves
+    pub fn set_camera(&mut self, camera: &CameraData) {
+        engine_world_operations::set_engine_world_camera(&mut self.world, camera);
+        if let Some(gpu_world) = self.gpu_world.as_mut() {
+            engine_gpu_world_operations::stream_engine_props(gpu_world, &self.world);
+        }
+    }
+
+    /// Voxel data loaded around the camera
+    pub fn world(&self) -> &world::data_types::WorldData {
+        &self.world.world
+    }
+
+    /// Stream chunks from the world save in `directory` and journal edits
+    /// into it; chunks that were never saved are generated
+    pub fn open_world_save(&mut self, directory: impl AsRef<std::path::Path>) -> Result<()> {
+        engine_world_operations::open_engine_world_save(&mut self.world, directory.as_ref())
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Write journaled edits that are still buffered; the frame flushes them
+    /// every few hundred milliseconds
+    pub fn flush_world_save(&mut self) -> Result<usize> {
+        engine_world_operations::flush_engine_world_save(&mut self.world)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Cipher for `EngineConfig::save_encryption_key` (None = plaintext), for
+    /// game files saved next to the world: scoreboards, claims, stats
+    pub fn save_cipher(&self) -> Option<&persistence::SaveCipherData> {
+        self.world.save_cipher.as_ref()
+    }
+
+    /// Place a block (with metadata such as orientation, 0 for none) in the
+    /// loaded world; the edit is journaled and reaches the GPU next frame.
+    /// This is the server's own edit: player edits are queued on the gateway
+    /// as block events with their `player_id`, checked against the region
+    /// claims, and applied by the frame.
+    pub fn set_block(
+        &mut self,
+        position: world::VoxelPos,
+        block: world::BlockId,
+        metadata: u8,
+    ) -> Result<world::WorldModification> {
+        let modification = engine_world_operations::set_engine_world_block(
+            &mut self.world,
+            position,
+            block,
+            metadata,
+        )
+        .map_err(|e| anyhow::anyhow!(e))?;
+        // Sound paths through the changed block are traced again
+        audio::invalidate_audio_occlusion_near(&mut self.audio, position);
+        Ok(modification)
+    }
+
+    /// Chunks generated, unloaded and still pending
+    pub fn world_stats(&self) -> engine_world_data::EngineWorldStats {
+        self.world.stats
+    }
+
+    /// Decoration counters of the chunks that have drawn
+    pub fn decoration_stats(&self) -> world::generation::DecorationStats {
+        self.world.decoration.stats
+    }
+
+    /// Validate and compile a game compute pass; the frame encodes it at
+    /// its stage from then on. Needs the GPU world of an attached renderer.
+    pub fn register_custom_pass(
+        &mut self,
+        desc: gpu::automation::CustomPassDescriptor,
+    ) -> Result<gpu::automation::CustomPassId> {
+        let gpu_world = self
+            .gpu_world
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Custom passes need the GPU world of a renderer"))?;
+        let device = gpu_world.meshing.device.clone();
+        gpu::automation::register_custom_pass(&mut gpu_world.custom_passes, &device, desc)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Stop encoding a game compute pass; false if it was not registered
+    pub fn unregister_custom_pass(&mut self, id: gpu::automation::CustomPassId) -> bool {
+        self.gpu_world.as_mut().is_some_and(|gpu_world| {
+            gpu::automation::unregister_custom_pass(&mut gpu_world.custom_passes, id)
+        })
+    }
+
+    /// Chunks uploaded to the GPU and meshed (None without a renderer)
+    pub fn gpu_world_stats(&self) -> Option<engine_gpu_world_data::EngineGpuWorldStats> {
+        self.gpu_world.as_ref().map(|gpu_world| gpu_world.stats)
+    }
+
+    /// Face connectivity compute of the GPU world (None without a
+    /// renderer); pass it to `GeneratorConfig::connectivity` so generated
+    /// chunks feed the engine's cave culling
+    pub fn chunk_connectivity(&self) -> Option<Arc<world::compute::ChunkConnectivityCompute>> {
+        self.gpu_world
+            .as_ref()
+            .map(|gpu_world| gpu_world.connectivity.clone())
+    }
+
+    /// The per-frame arena; pass it to `GeneratorConfig::frame_arena` and
+    /// `UnifiedComputeConfig::frame_arena` so generators and kernels
+    /// allocate their per-frame data from it
+    pub fn frame_arena(&self) -> memory::SharedFrameArena {
+        self.frame_arena.clone()
+    }
+
+    /// Effective view distance in chunks
+    pub fn view_distance(&self) -> u32 {
+        view_distance_operations::current_view_distance(&self.view_distance)
+    }
+
+    /// Report a failed GPU allocation for the current view distance; the
+    /// distance shrinks and the change is reported in the next `FrameResult`
+    pub fn report_gpu_allocation_failure(&mut self) {
+        let attempted = self.view_distance();
+        let events =
+            view_distance_operations::report_allocation_failure(&mut self.view_distance, attempted);
+        self.pending_events.extend(events);
+    }
+
+    /// Change the focused frame rate cap (None = uncapped)
+    pub fn set_fps_cap(&mut self, fps_cap: Option<u32>) {
+        self.config.fps_cap = fps_cap;
+        renderer::set_fps_cap(&mut self.pacer, fps_cap);
+    }
+
+    /// Switch vsync on the attached window surface
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.config.vsync = vsync;
+        renderer::set_frame_pacer_vsync(&mut self.pacer, vsync);
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer::set_renderer_vsync(renderer, vsync);
+        }
+    }
+
+    /// Drive the sky from the game's time of day, weather and camera; call
+    /// before [`Engine::frame`]
+    pub fn update_sky(
+        &mut self,
+        time: &TimeOfDayData,
+        weather: &world::WeatherData,
+        camera: &CameraData,
+    ) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer::update_renderer_sky(renderer, time, weather, camera);
+        }
+    }
+
+    /// Drift the clouds with the weather's wind; call after
+    /// [`Engine::update_sky`] and before [`Engine::frame`]
+    pub fn update_clouds(
+        &mut self,
+        weather: &world::WeatherData,
+        camera: &CameraData,
+        delta_seconds: f32,
+    ) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer::update_renderer_clouds(renderer, weather, camera, delta_seconds);
+        }
+    }
+
+    /// Count days and seasons with the game's day/night cycle on the
+    /// gateway clock; call once per frame after advancing the cycle. A new
+    /// season's snow cover and temperature reach the terrain generated from
+    /// then on and its growth rate the world's crops.
+    pub fn update_seasons(&mut self, time: &TimeOfDayData) -> Option<world::SeasonChange> {
+        let change = game::update_gateway_seasons(time)?;
+        self.apply_season(change.to);
+        Some(change)
+    }
+
+    /// Skip whole days (sleeping, time commands), applying the season
+    /// reached like [`Engine::update_seasons`]
+    pub fn advance_days(&mut self, days: u64) -> Option<world::SeasonChange> {
+        let change = game::advance_gateway_days(days)?;
+        self.apply_season(change.to);
+        Some(change)
+    }
+
+    fn apply_season(&mut self, season: world::Season) {
+        let params =
+            game::with_gateway_seasons(|seasons| seasons.config.profiles[season as usize]);
+        if let Some(params) = params {
+            engine_world_operations::apply_engine_world_season(&mut self.world, &params);
+        }
+    }
+
+    /// Turn the cloud layer and its shadows on or off (off on low-end hardware)
+    pub fn set_clouds_enabled(&mut self, enabled: bool) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer::set_renderer_clouds_enabled(renderer, enabled);
+        }
+    }
+
+    /// Switch anti-aliasing; returns the mode in use, which falls back to
+    /// FXAA when the GPU cannot multisample the target
+    pub fn set_anti_aliasing(
+        &mut self,
+        mode: renderer::AntiAliasingMode,
+    ) -> Result<renderer::AntiAliasingMode> {
+        let renderer = self
+            .renderer
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("No renderer attached"))?;
+        renderer::set_renderer_anti_aliasing(renderer, mode).map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Switch the edge highlight pass (accessibility outlines or the mesh
+    /// topology view) and optionally its outline color
+    pub fn set_edge_highlight(
+        &mut self,
+        mode: renderer::EdgeHighlightMode,
+        color: Option<[f32; 4]>,
+    ) -> Result<()> {
+        let renderer = self
+            .renderer
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("No renderer attached"))?;
+        let config = renderer::EdgeHighlightConfig {
+            mode,
+            color: color.unwrap_or(renderer.edge_highlight.config.color),
+            ..renderer.edge_highlight.config
+        };
+        renderer::set_renderer_edge_highlight(renderer, config);
+        Ok(())
+    }
+
+    /// Apply a `GameCommand::UpdateSettings` change that belongs to the
+    /// engine: "anti_aliasing" (off/msaa/msaa2/msaa4/msaa8/fxaa/taa),
+    /// "vsync", "fps_cap" (a number or "off"), "clouds", "edge_highlight"
+    /// (off/outline/topology), "edge_highlight_color" (#rrggbb[aa]), "rumble"
+    /// and "rumble_strength" (0.0 - 1.0)
+    pub fn update_setting(&mut self, setting_name: &str, value: &str) -> Result<()> {
+        let invalid = || anyhow::anyhow!("Invalid value '{}' for setting '{}'", value, setting_name);
+        match setting_name {
+            "anti_aliasing" => {
+                let mode = renderer::parse_anti_aliasing_mode(value).ok_or_else(invalid)?;
+                self.set_anti_aliasing(mode)?;
+            }
+            "vsync" => self.set_vsync(value.trim().parse().map_err(|_| invalid())?),
+            "fps_cap" => {
+                let fps_cap = match value.trim() {
+                    "off" | "none" | "0" => None,
+                    cap => Some(cap.parse().map_err(|_| invalid())?),
+                };
+                self.set_fps_cap(fps_cap);
+            }
+            "clouds" => self.set_clouds_enabled(value.trim().parse().map_err(|_| invalid())?),
+            "edge_highlight" => {
+                let mode = renderer::parse_edge_highlight_mode(value).ok_or_else(invalid)?;
+                self.set_edge_highlight(mode, None)?;
+            }
+            "edge_highlight_color" => {
+                let color = renderer::parse_edge_highlight_color(value).ok_or_else(invalid)?;
+                let mode = self
+                    .renderer
+                    .as_ref()
+                    .map(|renderer| renderer.edge_highlight.config.mode)
+                    .ok_or_else(|| anyhow::anyhow!("No renderer attached"))?;
+                self.set_edge_highlight(mode, Some(color))?;
+            }
+            "rumble" => {
+                let enabled = value.trim().parse().map_err(|_| invalid())?;
+                game::with_gateway_haptics(|haptics| input::set_rumble_enabled(haptics, enabled))
+                    .ok_or_else(|| anyhow::anyhow!("Game gateway not initialized"))?;
+            }
+            "rumble_strength" => {
+                let strength = value.trim().parse().map_err(|_| invalid())?;
+                game::with_gateway_haptics(|haptics| input::set_rumble_strength(haptics, strength))
+                    .ok_or_else(|| anyhow::anyhow!("Game gateway not initialized"))?;
+            }
+            _ => return Err(anyhow::anyhow!("Unknown engine setting '{}'", setting_name)),
+        }
+        Ok(())
+    }
+
+    /// Frame limiter statistics (also mirrored into `MetricsBuffers`)
+    pub fn frame_pacing_stats(&self) -> renderer::FramePacingStats {
+        self.pacer.stats
+    }
+
+    /// Forward raw mouse motion (winit `DeviceEvent::MouseMotion`) in embedded mode
+    pub fn mouse_motion(&mut self, delta: (f64, f64)) {
+        self.input.process_mouse_motion(delta);
+    }
+
+    /// Input state accumulated from host events
+    pub fn input_state(&self) -> &input::InputState {
+        &self.input
+    }
+
+    /// Whether an action's key or button is held and the input context
+    /// stack lets it through
+    pub fn action_pressed(&self, action: &str) -> bool {
+        input::action_pressed(&self.input_contexts, &self.input, action)
+    }
+
+    /// Push an input context when the console or a menu opens
+    pub fn push_input_context(&mut self, context: input::InputContext) -> Result<()> {
+        input::push_input_context(&mut self.input_contexts, context)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Pop the context pushed when the console or a menu opened
+    pub fn pop_input_context(&mut self, name: &str) -> Result<input::InputContext> {
+        input::pop_input_context(&mut self.input_contexts, name)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Action bindings and context stack, for rebinding keys
+    pub fn input_contexts_mut(&mut self) -> &mut input::InputContextData {
+        &mut self.input_contexts
+    }
+
+    /// Capture the current view as a world's menu thumbnail; call when
+    /// the world is saved or the player exits
+    pub fn save_world_thumbnail(
+        &mut self,
+        slots: &mut persistence::WorldSlotManager,
+        id: &str,
+    ) -> Result<()> {
+        let renderer = self
+            .renderer
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("No renderer attached to capture a thumbnail"))?;
+        let frame = renderer::capture_renderer_frame(renderer).map_err(|e| anyhow::anyhow!(e))?;
+        persistence::save_world_thumbnail(slots, id, frame.width, frame.height, frame.rgba)?;
+        Ok(())
+    }
+
+    /// Renderer attached in embedded mode
+    pub fn renderer_mut(&mut self) -> Option<&mut Renderer> {
+        self.renderer.as_mut()
+    }
+
+    /// Start or move a playing sound; its occlusion is updated each frame
+    pub fn set_audio_source(&mut self, source: audio::AudioSourceId, position: [f32; 3]) {
+        audio::set_audio_source(&mut self.audio, source, position);
+    }
+
+    /// Forget a sound that stopped
+    pub fn remove_audio_source(&mut self, source: audio::AudioSourceId) {
+        audio::remove_audio_source(&mut self.audio, source);
+    }
+
+    /// Gain and low-pass cutoff for the mixer to apply to a source, from
+    /// the last frame
+    pub fn audio_occlusion(&self, source: audio::AudioSourceId) -> Option<audio::AudioOcclusion> {
+        self.audio.occlusion.get(&source).copied()
+    }
+
+    /// Block absorption and occlusion tuning
+    pub fn audio_propagation_mut(&mut self) -> &mut audio::AudioPropagationData {
+        &mut self.audio
+    }
+
+    /// Network session: transports feed it with `network::receive_packet`
+    /// and send what `network::take_sent_packets` returns after each frame
+    pub fn network_mut(&mut self) -> &mut network::NetworkData {
+        &mut self.network
+    }
+
+    /// Run a lockstep session over the network session from the next
+    /// fixed tick on, replacing any running one
+    pub fn start_lockstep(&mut self, session: network::LockstepSessionData) {
+        self.lockstep = Some(session);
+    }
+
+    /// Stop the lockstep session, handing it back
+    pub fn stop_lockstep(&mut self) -> Option<network::LockstepSessionData> {
+        self.lockstep.take()
+    }
+
+    /// Running lockstep session
+    pub fn lockstep(&self) -> Option<&network::LockstepSessionData> {
+        self.lockstep.as_ref()
+    }
+
+    /// Plugins loaded from the plugin directory
+    pub fn plugins(&self) -> &plugin::PluginHostData {
+        &self.plugins
+    }
+
+    /// Plugin host, to add plugins linked into the executable
+    pub fn plugins_mut(&mut self) -> &mut plugin::PluginHostData {
+        &mut self.plugins
+    }
+
+    /// Put the player (the center of its collision shape) at `position`,
+    /// on spawn or teleport
+    pub fn place_player(&mut self, position: [f32; 3]) {
+        self.player = physics::PlayerEntityCollisionState {
+            position,
+            velocity: [0.0; 3],
+            ground_entity: None,
+        };
+    }
+
+    /// Collision shape and mass of the player
+    pub fn set_player_collision(&mut self, config: physics::PlayerEntityCollisionConfig) {
+        self.player_collision = config;
+    }
+
+    /// Move the player by one tick's `displacement`, already clipped
+    /// against voxels by the character controller, resolving it against
+    /// the entities in the physics buffers: the player rides the entity it
+    /// stands on, is pushed out of entities that moved into it and stops at
+    /// the ones in its way. Entities it pushed are moved in the buffers.
+    pub fn move_player(
+        &mut self,
+        displacement: [f32; 3],
+        delta_time: f32,
+    ) -> physics::PlayerEntityCollisionStep {
+        if delta_time > 0.0 {
+            self.player.velocity = displacement.map(|d| d / delta_time);
+        }
+        let world = &self.world.world;
+        let blocks = &self.blocks;
+        let chunk_size = self.config.chunk_size;
+        let solid_at = |position: VoxelPos| {
+            let block = world::world_operations::get_block(world, position, chunk_size);
+            match blocks.get_properties(block) {
+                Some(properties) => properties.is_solid,
+                None => block != BlockId::AIR && block != BlockId::WATER,
+            }
+        };
+
+        let mut buffers = self.buffers.write();
+        let (bvh, bodies) = physics::physics_entity_collision_scene(&buffers.physics);
+        let step = physics::resolve_player_entity_collisions(
+            &mut self.player,
+            &self.player_collision,
+            displacement,
+            &bvh,
+            &bodies,
+            delta_time,
+            solid_at,
+        );
+        physics::apply_entity_pushes(&mut buffers.physics, &step.pushes);
+        step
+    }
+
+    /// Player position, velocity and supporting entity after the last move
+    pub fn player(&self) -> &physics::PlayerEntityCollisionState {
+        &self.player
+    }
+
+    /// Shared engine buffers
+    pub fn buffers(&self) -> &SharedEngineBuffers {
+        &self.buffers
+    }
+
+    /// Open a window and drive [`Engine::frame`] from the engine's own
+    /// event loop, so windowed games run the same update and render path as
+    /// embedded hosts
+    pub fn run<G: GameData + 'static>(mut self, game: G) -> Result<()> {
+        use winit::event::{DeviceEvent, Event, WindowEvent};
+        use winit::window::CursorGrabMode;
+
+        log::info!("[Engine::run] Starting engine run method");
+
+        let event_loop = match self.event_loop.take() {
+            Some(loop_) => loop_,
+            None if self.renderer.is_some() => {
+                return Err(anyhow::anyhow!(
+                    "Engine is in embedded mode; drive it with Engine::frame instead of run"
+                ));
+            }
+            None => return Err(anyhow::anyhow!("Event loop already taken")),
+        };
+
+        let window = Arc::new(
+            winit::window::WindowBuilder::new()
+                .with_title(self.config.window_title.clone())
+                .with_inner_size(winit::dpi::PhysicalSize::new(
+                    self.config.window_width,
+                    self.config.window_height,
+                ))
+                .build(&event_loop)?,
+        );
+        let mut renderer =
+            renderer::create_window_renderer(window.clone()).map_err(|e| anyhow::anyhow!(e))?;
+        configure_engine_pipeline_cache(&mut renderer);
+        configure_engine_renderer(&self.config, &mut renderer, &mut self.pacer);
+        engine_world_operations::attach_world_generator_device(
+            &mut self.world,
+            &self.config,
+            (renderer.device.clone(), renderer.queue.clone()),
+        );
+        self.gpu_world = engine_gpu_world_operations::create_engine_gpu_world(
+            renderer.device.clone(),
+            renderer.queue.clone(),
+            self.config.chunk_layout()?,
+            self.frame_arena.clone(),
+            self.config.smooth_terrain,
+            self.config.world_seed,
+        );
+        configure_engine_light_preview(&mut renderer, self.gpu_world.as_ref(), &self.blocks);
+        renderer::set_renderer_block_atlas(&mut renderer, &self.blocks);
+        self.renderer = Some(renderer);
+        game::complete_gateway_load_phase(game::LoadPhase::GpuInit);
+        log::info!("[Engine::run] Window renderer attached, entering the event loop");
+
+        // The game is owned by the loop for as long as the window is open
+        let _game = game;
+        let mut window_events = Vec::new();
+        let mut cursor_locked = false;
+        event_loop.run(move |event, target| match event {
+            Event::WindowEvent {
+                event: WindowEvent::RedrawRequested,
+                ..
+            } => {
+                let result = self.frame(&std::mem::take(&mut window_events));
+                if let Some(e) = &result.error {
+                    log::error!("[Engine::run] Frame {} failed: {}", result.frame_number, e);
+                }
+                if result.cursor_locked != cursor_locked {
+                    cursor_locked = result.cursor_locked;
+                    let grab = if cursor_locked {
+                        window
+                            .set_cursor_grab(CursorGrabMode::Confined)
+                            .or_else(|_| window.set_cursor_grab(CursorGrabMode::Locked))
+                    } else {
+                        window.set_cursor_grab(CursorGrabMode::None)
+                    };
+                    if let Err(e) = grab {
+                        log::warn!("[Engine::run] Cursor grab failed: {}", e);
+                    }
+                    window.set_cursor_visible(!cursor_locked);
+                }
+                if result.exit_requested {
+                    if let Err(e) = self.flush_world_save() {
+                        log::error!("[Engine::run] World edits not saved: {}", e);
+                    }
+                    target.exit();
+                }
+            }
+            Event::WindowEvent { event, .. } => window_events.push(event),
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta },
+                ..
+            } => self.mouse_motion(delta),
+            Event::AboutToWait => window.request_redraw(),
+            _ => {}
+        })?;
+
+        log::info!("[Engine::run] Event loop exited");
+        Ok(())
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        for id in self.feature_hooks.drain(..) {
+            feature_flags::remove_global_feature_flag_hook(id);
+        }
+        if let Some(renderer) = &self.renderer {
+            let cache_dir =
+                std::path::Path::new(constants::persistence_constants::PIPELINE_CACHE_DIR);
+            if let Err(e) = renderer::save_renderer_pipeline_cache(renderer, cache_dir) {
+                log::warn!("[Engine] Pipeline cache not saved: {}", e);
+            }
+        }
+    }
 }
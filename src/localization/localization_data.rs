@@ -0,0 +1,60 @@
+//! Localization data structures - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! All lookups and loading happen in localization_operations.rs
+
+use std::collections::{HashMap, HashSet};
+
+/// Locale used when nothing else resolves a key
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Built-in English table, always available as the last fallback
+pub const BUILTIN_EN_TOML: &str = include_str!("../../assets/locales/en.toml");
+
+/// Strings for a single locale, keyed by dotted path (e.g. "engine.panic.title")
+#[derive(Debug, Clone, Default)]
+pub struct LocaleTable {
+    pub locale: String,
+    pub strings: HashMap<String, String>,
+}
+
+/// All loaded locales plus the active resolution order
+#[derive(Debug, Clone, Default)]
+pub struct LocalizationData {
+    /// Loaded tables by locale id
+    pub tables: HashMap<String, LocaleTable>,
+
+    /// Currently selected locale
+    pub active_locale: String,
+
+    /// Extra locales tried after the active locale and its base language
+    pub fallback_locales: Vec<String>,
+
+    /// Resolved lookup order, rebuilt whenever the locale or fallbacks change
+    pub resolution_chain: Vec<String>,
+
+    /// Keys that failed to resolve in every locale (reported once each)
+    pub missing_keys: HashSet<String>,
+}
+
+/// Localization errors
+#[derive(Debug, thiserror::Error)]
+pub enum LocalizationError {
+    #[error("Failed to read locale file {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("Failed to parse locale '{locale}': {message}")]
+    Parse { locale: String, message: String },
+
+    #[error("Locale value for key '{key}' in '{locale}' is not a string")]
+    InvalidValue { locale: String, key: String },
+
+    #[error("Unknown locale: {locale}")]
+    UnknownLocale { locale: String },
+}
+
+/// Result type for localization operations
+pub type LocalizationResult<T> = Result<T, LocalizationError>;
+
+/// Named placeholder arguments, e.g. `[("path", "logs/panic.log".to_string())]`
+pub type LocaleArg<'a> = (&'a str, String);
@@ -0,0 +1,248 @@
+//! Localization operations - Pure DOP functions
+//!
+//! Locale tables are TOML files (one per locale) whose nested tables are
+//! flattened into dotted keys. Lookups walk the resolution chain:
+//! active locale -> base language -> configured fallbacks -> default locale.
+
+use super::localization_data::{
+    LocaleArg, LocaleTable, LocalizationData, LocalizationError, LocalizationResult,
+    BUILTIN_EN_TOML, DEFAULT_LOCALE,
+};
+use std::collections::HashMap;
+use std::path::Path;
+
+// ============================================================================
+// INITIALIZATION
+// ============================================================================
+
+/// Create localization data with the built-in English table loaded
+pub fn create_localization() -> LocalizationData {
+    let mut data = LocalizationData {
+        active_locale: DEFAULT_LOCALE.to_string(),
+        ..Default::default()
+    };
+
+    match parse_locale_table(DEFAULT_LOCALE, BUILTIN_EN_TOML) {
+        Ok(table) => add_locale_table(&mut data, table),
+        Err(e) => log::error!("[LOCALIZATION] Built-in locale is invalid: {}", e),
+    }
+
+    rebuild_resolution_chain(&mut data);
+    data
+}
+
+// ============================================================================
+// LOADING
+// ============================================================================
+
+/// Parse a TOML locale source into a flat table
+pub fn parse_locale_table(locale: &str, source: &str) -> LocalizationResult<LocaleTable> {
+    let root: toml::Table =
+        source
+            .parse()
+            .map_err(|e: toml::de::Error| LocalizationError::Parse {
+                locale: locale.to_string(),
+                message: e.to_string(),
+            })?;
+
+    let mut strings = HashMap::new();
+    flatten_toml_table(locale, "", &root, &mut strings)?;
+
+    Ok(LocaleTable {
+        locale: locale.to_string(),
+        strings,
+    })
+}
+
+fn flatten_toml_table(
+    locale: &str,
+    prefix: &str,
+    table: &toml::Table,
+    out: &mut HashMap<String, String>,
+) -> LocalizationResult<()> {
+    for (name, value) in table {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+
+        match value {
+            toml::Value::String(text) => {
+                out.insert(key, text.clone());
+            }
+            toml::Value::Table(nested) => flatten_toml_table(locale, &key, nested, out)?,
+            _ => {
+                return Err(LocalizationError::InvalidValue {
+                    locale: locale.to_string(),
+                    key,
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Merge a table into the loaded locales (later keys override earlier ones)
+pub fn add_locale_table(data: &mut LocalizationData, table: LocaleTable) {
+    let entry = data
+        .tables
+        .entry(table.locale.clone())
+        .or_insert_with(|| LocaleTable {
+            locale: table.locale.clone(),
+            strings: HashMap::new(),
+        });
+    entry.strings.extend(table.strings);
+    data.missing_keys.clear();
+}
+
+/// Load a single locale file; the locale id is the file stem (e.g. "pt-BR.toml")
+pub fn load_locale_file(data: &mut LocalizationData, path: &Path) -> LocalizationResult<String> {
+    let locale = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| LocalizationError::Io {
+            path: path.display().to_string(),
+            message: "file name is not a valid locale id".to_string(),
+        })?
+        .to_string();
+
+    let source = std::fs::read_to_string(path).map_err(|e| LocalizationError::Io {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })?;
+
+    let table = parse_locale_table(&locale, &source)?;
+    log::info!(
+        "[LOCALIZATION] Loaded {} strings for locale '{}'",
+        table.strings.len(),
+        locale
+    );
+    add_locale_table(data, table);
+    Ok(locale)
+}
+
+/// Load every `*.toml` file in a directory, returning the locales loaded
+pub fn load_locale_directory(
+    data: &mut LocalizationData,
+    dir: &Path,
+) -> LocalizationResult<Vec<String>> {
+    let entries = std::fs::read_dir(dir).map_err(|e| LocalizationError::Io {
+        path: dir.display().to_string(),
+        message: e.to_string(),
+    })?;
+
+    let mut loaded = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some("toml") {
+            loaded.push(load_locale_file(data, &path)?);
+        }
+    }
+
+    loaded.sort();
+    Ok(loaded)
+}
+
+// ============================================================================
+// LOCALE SELECTION
+// ============================================================================
+
+/// Switch the active locale at runtime
+pub fn set_active_locale(data: &mut LocalizationData, locale: &str) -> LocalizationResult<()> {
+    let base = base_language(locale);
+    if !data.tables.contains_key(locale) && !data.tables.contains_key(base) {
+        return Err(LocalizationError::UnknownLocale {
+            locale: locale.to_string(),
+        });
+    }
+
+    data.active_locale = locale.to_string();
+    rebuild_resolution_chain(data);
+    log::info!(
+        "[LOCALIZATION] Active locale set to '{}' (chain: {:?})",
+        locale,
+        data.resolution_chain
+    );
+    Ok(())
+}
+
+/// Set extra fallback locales tried after the active locale
+pub fn set_fallback_locales(data: &mut LocalizationData, fallbacks: &[&str]) {
+    data.fallback_locales = fallbacks.iter().map(|l| l.to_string()).collect();
+    rebuild_resolution_chain(data);
+}
+
+/// List loaded locale ids, sorted
+pub fn available_locales(data: &LocalizationData) -> Vec<String> {
+    let mut locales: Vec<String> = data.tables.keys().cloned().collect();
+    locales.sort();
+    locales
+}
+
+/// "pt-BR" -> "pt", "en_US" -> "en"
+fn base_language(locale: &str) -> &str {
+    locale.split(['-', '_']).next().unwrap_or(locale)
+}
+
+fn rebuild_resolution_chain(data: &mut LocalizationData) {
+    let mut chain: Vec<String> = Vec::new();
+    let active = data.active_locale.clone();
+    let candidates = std::iter::once(active.as_str())
+        .chain(std::iter::once(base_language(&active)))
+        .chain(data.fallback_locales.iter().map(|l| l.as_str()))
+        .chain(std::iter::once(DEFAULT_LOCALE));
+
+    for locale in candidates {
+        if !chain.iter().any(|l| l == locale) {
+            chain.push(locale.to_string());
+        }
+    }
+
+    data.resolution_chain = chain;
+    data.missing_keys.clear();
+}
+
+// ============================================================================
+// LOOKUP
+// ============================================================================
+
+/// Resolve a key through the fallback chain without recording misses
+pub fn lookup<'a>(data: &'a LocalizationData, key: &str) -> Option<&'a str> {
+    data.resolution_chain
+        .iter()
+        .filter_map(|locale| data.tables.get(locale))
+        .find_map(|table| table.strings.get(key))
+        .map(|s| s.as_str())
+}
+
+/// Translate a key; unresolved keys return the key itself
+pub fn translate(data: &mut LocalizationData, key: &str) -> String {
+    match lookup(data, key) {
+        Some(text) => text.to_string(),
+        None => {
+            if data.missing_keys.insert(key.to_string()) {
+                log::warn!(
+                    "[LOCALIZATION] Missing key '{}' for locale '{}'",
+                    key,
+                    data.active_locale
+                );
+            }
+            key.to_string()
+        }
+    }
+}
+
+/// Translate a key and substitute `{name}` placeholders
+pub fn translate_with_args(data: &mut LocalizationData, key: &str, args: &[LocaleArg]) -> String {
+    format_placeholders(&translate(data, key), args)
+}
+
+/// Replace `{name}` placeholders; unknown placeholders are left intact
+pub fn format_placeholders(template: &str, args: &[LocaleArg]) -> String {
+    let mut result = template.to_string();
+    for (name, value) in args {
+        result = result.replace(&format!("{{{}}}", name), value);
+    }
+    result
+}
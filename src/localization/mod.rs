@@ -0,0 +1,143 @@
+/// Localization Module - Data-Oriented Programming (DOP) style
+///
+/// This module follows pure DOP principles:
+/// - localization_data.rs: Pure data structures with NO methods
+/// - localization_operations.rs: Pure functions that operate on data
+///
+/// Engine-facing strings (panic reports, console command output) go
+/// through the global table via `tr!`:
+/// `tr!("engine.panic.title")` or `tr!("engine.panic.log_location", path = log_path.display())`.
+pub mod localization_data;
+pub mod localization_operations;
+
+use parking_lot::RwLock;
+
+// Re-export data structures
+pub use localization_data::{
+    LocaleArg, LocaleTable, LocalizationData, LocalizationError, LocalizationResult,
+    BUILTIN_EN_TOML, DEFAULT_LOCALE,
+};
+
+// Re-export all operations
+pub use localization_operations::{
+    // Loading
+    add_locale_table,
+    // Locale selection
+    available_locales,
+    // Initialization
+    create_localization,
+
+    // Lookup
+    format_placeholders,
+    load_locale_directory,
+    load_locale_file,
+    lookup,
+    parse_locale_table,
+
+    set_active_locale,
+    set_fallback_locales,
+
+    translate,
+    translate_with_args,
+};
+
+lazy_static::lazy_static! {
+    /// Engine-wide localization table used by `tr!`
+    pub static ref GLOBAL_LOCALIZATION: RwLock<LocalizationData> = RwLock::new(create_localization());
+}
+
+/// Translate a key using the global table
+pub fn tr_global(key: &str) -> String {
+    tr_global_with_args(key, &[])
+}
+
+/// Translate a key with `{name}` arguments using the global table
+///
+/// Never blocks: if the table is being written (e.g. a panic during a locale
+/// switch), the key itself is returned.
+pub fn tr_global_with_args(key: &str, args: &[LocaleArg]) -> String {
+    let resolved = match GLOBAL_LOCALIZATION.try_read() {
+        Some(data) => lookup(&data, key).map(|s| s.to_string()),
+        None => return format_placeholders(key, args),
+    };
+
+    match resolved {
+        Some(text) => format_placeholders(&text, args),
+        None => translate_with_args(&mut GLOBAL_LOCALIZATION.write(), key, args),
+    }
+}
+
+/// Switch the global locale at runtime
+pub fn set_global_locale(locale: &str) -> LocalizationResult<()> {
+    set_active_locale(&mut GLOBAL_LOCALIZATION.write(), locale)
+}
+
+/// Load all locale files in a directory into the global table
+pub fn load_global_locales(dir: &std::path::Path) -> LocalizationResult<Vec<String>> {
+    load_locale_directory(&mut GLOBAL_LOCALIZATION.write(), dir)
+}
+
+/// Look up a localized engine string
+///
+/// ```ignore
+/// let title = tr!("engine.panic.title");
+/// let line = tr!("engine.panic.log_location", path = log_path.display());
+/// ```
+#[macro_export]
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::localization::tr_global($key)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::localization::tr_global_with_args(
+            $key,
+            &[$((stringify!($name), ($value).to_string())),+],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_chain_and_placeholders() {
+        let mut data = create_localization();
+        let german =
+            parse_locale_table("de", "[engine.panic]\ntitle = \"Hearth Engine Absturz!\"\n");
+        add_locale_table(&mut data, german.expect("valid toml"));
+
+        assert!(set_active_locale(&mut data, "de-AT").is_ok());
+        assert_eq!(data.resolution_chain, vec!["de-AT", "de", "en"]);
+
+        // Resolved from "de" via base language, then from "en" via default fallback
+        assert_eq!(
+            translate(&mut data, "engine.panic.title"),
+            "Hearth Engine Absturz!"
+        );
+        assert_eq!(
+            translate_with_args(
+                &mut data,
+                "engine.panic.log_location",
+                &[("path", "logs/panic.log".to_string())]
+            ),
+            "Log location: logs/panic.log"
+        );
+
+        // Console output falls back to the built-in English strings
+        assert_eq!(
+            translate_with_args(
+                &mut data,
+                "console.claim.created",
+                &[("name", "spawn".to_string())]
+            ),
+            "Created claim spawn"
+        );
+
+        // Unknown keys return the key and are recorded once
+        assert_eq!(translate(&mut data, "engine.nope"), "engine.nope");
+        assert!(data.missing_keys.contains("engine.nope"));
+
+        assert!(set_active_locale(&mut data, "fr").is_err());
+    }
+}
@@ -0,0 +1,92 @@
+//! Logging data structures - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! All filtering, retention and export happen in logging_operations.rs
+
+use chrono::{DateTime, Local};
+use log::{Level, LevelFilter};
+use std::collections::VecDeque;
+use std::sync::atomic::AtomicU64;
+
+/// One captured log record
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub timestamp: DateTime<Local>,
+    pub level: Level,
+    /// Module path the record came from (e.g. "hearth_engine::world::storage")
+    pub target: String,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+}
+
+/// Level override for a module and everything below it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleLevel {
+    /// Module path prefix; the crate name may be omitted ("world::storage")
+    pub module: String,
+    pub level: LevelFilter,
+}
+
+/// Per-module level filters, changeable at runtime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFilterData {
+    /// Level for modules without an override
+    pub default_level: LevelFilter,
+    /// Overrides, longest module path first so the most specific one wins
+    pub module_levels: Vec<ModuleLevel>,
+}
+
+/// Ring buffer of recent records for the console log view and crash reports
+#[derive(Debug, Clone, Default)]
+pub struct LogHistoryData {
+    pub entries: VecDeque<LogEntry>,
+    pub capacity: usize,
+    /// Records captured since startup
+    pub total_recorded: u64,
+    /// Records dropped to make room
+    pub evicted: u64,
+}
+
+/// Which records the log view shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogViewQuery {
+    /// Most recent records to return
+    pub count: usize,
+    /// Least severe level to include
+    pub min_level: Level,
+    /// Only records whose target contains this text
+    pub target_contains: Option<String>,
+}
+
+/// Per-call-site state for the rate-limited logging macros
+#[derive(Debug)]
+pub struct RateLimitSite {
+    /// Milliseconds since logging started at the last emitted message
+    /// (u64::MAX = never emitted)
+    pub last_emit_ms: AtomicU64,
+    /// Messages dropped since the last emitted one
+    pub suppressed: AtomicU64,
+}
+
+/// Logging errors
+#[derive(Debug, thiserror::Error)]
+pub enum LoggingError {
+    #[error("Invalid log level: {level}")]
+    InvalidLevel { level: String },
+
+    #[error("Invalid log filter '{directive}'")]
+    InvalidFilter { directive: String },
+
+    #[error("Unknown log command: {command}")]
+    UnknownCommand { command: String },
+
+    #[error("Failed to write log export {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("A global logger is already installed")]
+    AlreadyInitialized,
+}
+
+/// Result type for logging operations
+pub type LoggingResult<T> = Result<T, LoggingError>;
@@ -0,0 +1,420 @@
+//! Logging operations - Pure DOP
+//!
+//! Filter resolution, ring buffer retention, log view queries, JSON export
+//! and the `log` console command. The global logger in mod.rs calls these.
+
+use super::logging_data::{
+    LogEntry, LogFilterData, LogHistoryData, LogViewQuery, LoggingError, LoggingResult,
+    ModuleLevel, RateLimitSite,
+};
+use crate::constants::logging;
+use log::{Level, LevelFilter};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Crate prefix that module filters may leave out
+const CRATE_PREFIX: &str = "hearth_engine::";
+
+// ===== Filters =====
+
+/// Filters with a single default level and no overrides
+pub fn create_log_filters(default_level: LevelFilter) -> LogFilterData {
+    LogFilterData {
+        default_level,
+        module_levels: Vec::new(),
+    }
+}
+
+/// Parse "off", "error", "warn", "info", "debug" or "trace"
+pub fn parse_level_filter(level: &str) -> LoggingResult<LevelFilter> {
+    level
+        .trim()
+        .parse()
+        .map_err(|_| LoggingError::InvalidLevel {
+            level: level.trim().to_string(),
+        })
+}
+
+/// Parse an env_logger style spec: "info,world=debug,hearth_engine::network=warn"
+pub fn parse_filter_spec(spec: &str) -> LoggingResult<LogFilterData> {
+    let mut filters = create_log_filters(LevelFilter::Info);
+    for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+        match directive.split_once('=') {
+            Some((module, level)) => {
+                if module.trim().is_empty() {
+                    return Err(LoggingError::InvalidFilter {
+                        directive: directive.to_string(),
+                    });
+                }
+                set_module_level(&mut filters, module.trim(), parse_level_filter(level)?);
+            }
+            None => match parse_level_filter(directive) {
+                Ok(level) => filters.default_level = level,
+                // A bare module name enables everything for it, like env_logger
+                Err(_) => set_module_level(&mut filters, directive, LevelFilter::Trace),
+            },
+        }
+    }
+    Ok(filters)
+}
+
+fn normalize_module(module: &str) -> &str {
+    module.strip_prefix(CRATE_PREFIX).unwrap_or(module)
+}
+
+/// Override the level for a module and its children
+pub fn set_module_level(filters: &mut LogFilterData, module: &str, level: LevelFilter) {
+    let module = normalize_module(module).to_string();
+    filters.module_levels.retain(|entry| entry.module != module);
+    filters.module_levels.push(ModuleLevel { module, level });
+    filters
+        .module_levels
+        .sort_by_key(|entry| std::cmp::Reverse(entry.module.len()));
+}
+
+/// Remove a module override; returns false if there was none
+pub fn clear_module_level(filters: &mut LogFilterData, module: &str) -> bool {
+    let module = normalize_module(module);
+    let before = filters.module_levels.len();
+    filters.module_levels.retain(|entry| entry.module != module);
+    filters.module_levels.len() != before
+}
+
+fn module_matches(target: &str, module: &str) -> bool {
+    target
+        .strip_prefix(module)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// Level enabled for a record target; the most specific override wins
+pub fn level_for_target(filters: &LogFilterData, target: &str) -> LevelFilter {
+    let target = normalize_module(target);
+    filters
+        .module_levels
+        .iter()
+        .find(|entry| module_matches(target, &entry.module))
+        .map_or(filters.default_level, |entry| entry.level)
+}
+
+/// Most verbose level any filter allows (for `log::set_max_level`)
+pub fn max_level(filters: &LogFilterData) -> LevelFilter {
+    filters
+        .module_levels
+        .iter()
+        .map(|entry| entry.level)
+        .fold(filters.default_level, Ord::max)
+}
+
+/// Filters as a spec string `parse_filter_spec` accepts
+pub fn format_filter_spec(filters: &LogFilterData) -> String {
+    let mut parts = vec![filters.default_level.to_string().to_lowercase()];
+    parts.extend(filters.module_levels.iter().map(|entry| {
+        format!(
+            "{}={}",
+            entry.module,
+            entry.level.to_string().to_lowercase()
+        )
+    }));
+    parts.join(",")
+}
+
+// ===== History =====
+
+/// Empty ring buffer holding up to `capacity` records
+pub fn create_log_history(capacity: usize) -> LogHistoryData {
+    LogHistoryData {
+        entries: VecDeque::with_capacity(capacity.min(logging::DEFAULT_HISTORY_CAPACITY)),
+        capacity,
+        total_recorded: 0,
+        evicted: 0,
+    }
+}
+
+/// Append a record, evicting the oldest when full
+pub fn record_log_entry(history: &mut LogHistoryData, entry: LogEntry) {
+    history.total_recorded += 1;
+    if history.capacity == 0 {
+        history.evicted += 1;
+        return;
+    }
+    while history.entries.len() >= history.capacity {
+        history.entries.pop_front();
+        history.evicted += 1;
+    }
+    history.entries.push_back(entry);
+}
+
+/// Drop all retained records
+pub fn clear_log_history(history: &mut LogHistoryData) {
+    history.entries.clear();
+}
+
+/// Records matching a query, oldest first
+pub fn query_log_entries<'a>(
+    history: &'a LogHistoryData,
+    query: &LogViewQuery,
+) -> Vec<&'a LogEntry> {
+    let mut matches: Vec<&LogEntry> = history
+        .entries
+        .iter()
+        .rev()
+        .filter(|entry| entry.level <= query.min_level)
+        .filter(|entry| {
+            query
+                .target_contains
+                .as_deref()
+                .is_none_or(|text| entry.target.contains(text))
+        })
+        .take(query.count)
+        .collect();
+    matches.reverse();
+    matches
+}
+
+/// One line for the console log view
+pub fn format_log_entry(entry: &LogEntry) -> String {
+    format!(
+        "{} {:<5} {}: {}",
+        entry.timestamp.format("%H:%M:%S%.3f"),
+        entry.level,
+        normalize_module(&entry.target),
+        entry.message
+    )
+}
+
+/// Record as a JSON object
+pub fn log_entry_to_json(entry: &LogEntry) -> serde_json::Value {
+    serde_json::json!({
+        "timestamp": entry.timestamp.to_rfc3339(),
+        "level": entry.level.as_str(),
+        "target": entry.target,
+        "message": entry.message,
+        "file": entry.file,
+        "line": entry.line,
+    })
+}
+
+/// Write the retained records as JSON Lines; returns the number written
+pub fn export_log_history_json(history: &LogHistoryData, path: &Path) -> LoggingResult<usize> {
+    let io_error = |e: std::io::Error| LoggingError::Io {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    };
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    let file = std::fs::File::create(path).map_err(io_error)?;
+    let mut writer = std::io::BufWriter::new(file);
+    for entry in &history.entries {
+        writeln!(writer, "{}", log_entry_to_json(entry)).map_err(io_error)?;
+    }
+    writer.flush().map_err(io_error)?;
+    Ok(history.entries.len())
+}
+
+// ===== Rate limiting =====
+
+/// Initial state for a call site's `RateLimitSite` static
+pub const fn create_rate_limit_site() -> RateLimitSite {
+    RateLimitSite {
+        last_emit_ms: AtomicU64::new(u64::MAX),
+        suppressed: AtomicU64::new(0),
+    }
+}
+
+/// Decide whether a rate-limited call site may log at `now_ms`.
+/// Returns the number of messages suppressed since its last message when
+/// it may, None when this one should be dropped.
+pub fn rate_limit_allows(site: &RateLimitSite, now_ms: u64, interval_ms: u64) -> Option<u64> {
+    let last = site.last_emit_ms.load(Ordering::Relaxed);
+    if last != u64::MAX && now_ms.saturating_sub(last) < interval_ms {
+        site.suppressed.fetch_add(1, Ordering::Relaxed);
+        return None;
+    }
+    // Another thread may win the same window
+    if site
+        .last_emit_ms
+        .compare_exchange(last, now_ms, Ordering::Relaxed, Ordering::Relaxed)
+        .is_err()
+    {
+        site.suppressed.fetch_add(1, Ordering::Relaxed);
+        return None;
+    }
+    Some(site.suppressed.swap(0, Ordering::Relaxed))
+}
+
+// ===== Console command =====
+
+fn parse_view_query(args: &[&str]) -> LogViewQuery {
+    let mut query = LogViewQuery {
+        count: logging::DEFAULT_VIEW_ENTRIES,
+        min_level: Level::Trace,
+        target_contains: None,
+    };
+    for arg in args {
+        if let Ok(count) = arg.parse() {
+            query.count = count;
+        } else if let Ok(level) = arg.parse::<Level>() {
+            query.min_level = level;
+        } else {
+            query.target_contains = Some(arg.to_string());
+        }
+    }
+    query
+}
+
+/// Run a `log` console command and return the text to print:
+///
+/// - `log level <level>` / `log level <module> <level>`
+/// - `log reset [module]` - drop one or all module overrides
+/// - `log filters` - show the active filter spec
+/// - `log show [count] [level] [target text]` - recent records
+/// - `log export <path>` - write retained records as JSON Lines
+/// - `log clear`
+pub fn execute_log_command(
+    filters: &mut LogFilterData,
+    history: &mut LogHistoryData,
+    command: &str,
+) -> LoggingResult<String> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let args = match words.first() {
+        Some(&"log") => &words[1..],
+        _ => &words[..],
+    };
+    let unknown = || LoggingError::UnknownCommand {
+        command: command.trim().to_string(),
+    };
+
+    match args {
+        ["level", level] => {
+            filters.default_level = parse_level_filter(level)?;
+            Ok(format!("Default log level: {}", filters.default_level))
+        }
+        ["level", module, level] => {
+            let level = parse_level_filter(level)?;
+            set_module_level(filters, module, level);
+            Ok(format!(
+                "Log level for {}: {}",
+                normalize_module(module),
+                level
+            ))
+        }
+        ["reset"] => {
+            filters.module_levels.clear();
+            Ok("Cleared all module log levels".to_string())
+        }
+        ["reset", module] => {
+            if clear_module_level(filters, module) {
+                Ok(format!(
+                    "Cleared log level for {}",
+                    normalize_module(module)
+                ))
+            } else {
+                Ok(format!("No log level set for {}", normalize_module(module)))
+            }
+        }
+        ["filters"] => Ok(format_filter_spec(filters)),
+        ["show", rest @ ..] => {
+            let query = parse_view_query(rest);
+            Ok(query_log_entries(history, &query)
+                .into_iter()
+                .map(format_log_entry)
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        ["export", path] => {
+            let written = export_log_history_json(history, Path::new(path))?;
+            Ok(format!("Exported {} log records to {}", written, path))
+        }
+        ["clear"] => {
+            clear_log_history(history);
+            Ok("Log history cleared".to_string())
+        }
+        _ => Err(unknown()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+
+    fn entry(level: Level, target: &str, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: Local::now(),
+            level,
+            target: target.to_string(),
+            message: message.to_string(),
+            file: None,
+            line: None,
+        }
+    }
+
+    #[test]
+    fn test_most_specific_module_wins() {
+        let filters = parse_filter_spec("warn,world=debug,hearth_engine::world::storage=trace,net")
+            .expect("valid spec");
+        assert_eq!(filters.default_level, LevelFilter::Warn);
+        assert_eq!(
+            level_for_target(&filters, "hearth_engine::world::storage::world_buffer"),
+            LevelFilter::Trace
+        );
+        assert_eq!(
+            level_for_target(&filters, "hearth_engine::world::generation"),
+            LevelFilter::Debug
+        );
+        // "world" must not match "worldgen"
+        assert_eq!(level_for_target(&filters, "worldgen"), LevelFilter::Warn);
+        assert_eq!(max_level(&filters), LevelFilter::Trace);
+        assert!(parse_filter_spec("world=loud").is_err());
+    }
+
+    #[test]
+    fn test_history_ring_buffer_and_query() {
+        let mut history = create_log_history(3);
+        for i in 0..5 {
+            let level = if i % 2 == 0 {
+                Level::Info
+            } else {
+                Level::Debug
+            };
+            record_log_entry(
+                &mut history,
+                entry(level, "hearth_engine::world", &i.to_string()),
+            );
+        }
+        assert_eq!(history.entries.len(), 3);
+        assert_eq!(history.evicted, 2);
+        assert_eq!(history.total_recorded, 5);
+
+        let query = LogViewQuery {
+            count: 10,
+            min_level: Level::Info,
+            target_contains: Some("world".to_string()),
+        };
+        let messages: Vec<&str> = query_log_entries(&history, &query)
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect();
+        assert_eq!(messages, vec!["2", "4"]);
+    }
+
+    #[test]
+    fn test_console_command_and_rate_limit() {
+        let mut filters = create_log_filters(LevelFilter::Info);
+        let mut history = create_log_history(8);
+        execute_log_command(&mut filters, &mut history, "log level renderer debug")
+            .expect("valid command");
+        assert_eq!(format_filter_spec(&filters), "info,renderer=debug");
+        assert!(execute_log_command(&mut filters, &mut history, "log dance").is_err());
+
+        let site = create_rate_limit_site();
+        assert_eq!(rate_limit_allows(&site, 0, 100), Some(0));
+        assert_eq!(rate_limit_allows(&site, 50, 100), None);
+        assert_eq!(rate_limit_allows(&site, 60, 100), None);
+        assert_eq!(rate_limit_allows(&site, 150, 100), Some(2));
+    }
+}
@@ -0,0 +1,237 @@
+/// Logging Module - Data-Oriented Programming (DOP) style
+///
+/// This module follows pure DOP principles:
+/// - logging_data.rs: Pure data structures with NO methods
+/// - logging_operations.rs: Pure functions that operate on data
+///
+/// `init_logging` installs a global `log` backend that filters per module
+/// (changeable at runtime through the `log` console command), keeps recent
+/// records in a ring buffer for the console and crash reports, and still
+/// prints to the terminal. Hot paths use `log_rate_limited!`.
+pub mod logging_data;
+pub mod logging_operations;
+
+use crate::constants::logging;
+use parking_lot::{Mutex, RwLock};
+use std::time::Instant;
+
+// Re-export data structures
+pub use logging_data::{
+    LogEntry, LogFilterData, LogHistoryData, LogViewQuery, LoggingError, LoggingResult,
+    ModuleLevel, RateLimitSite,
+};
+
+// Re-export all operations
+pub use logging_operations::{
+    // History
+    clear_log_history,
+    // Filters
+    clear_module_level,
+    create_log_filters,
+    create_log_history,
+    // Console and rate limiting
+    create_rate_limit_site,
+    execute_log_command,
+    export_log_history_json,
+    format_filter_spec,
+    format_log_entry,
+    level_for_target,
+    log_entry_to_json,
+    max_level,
+    parse_filter_spec,
+    parse_level_filter,
+    query_log_entries,
+    rate_limit_allows,
+    record_log_entry,
+
+    set_module_level,
+};
+
+#[doc(hidden)]
+pub use log as log_crate;
+
+lazy_static::lazy_static! {
+    /// Per-module filters used by the global logger
+    pub static ref GLOBAL_LOG_FILTERS: RwLock<LogFilterData> =
+        RwLock::new(create_log_filters(log::LevelFilter::Info));
+
+    /// Recent records for the console log view and crash reports
+    pub static ref GLOBAL_LOG_HISTORY: Mutex<LogHistoryData> =
+        Mutex::new(create_log_history(logging::DEFAULT_HISTORY_CAPACITY));
+
+    static ref LOGGING_EPOCH: Instant = Instant::now();
+}
+
+/// Global `log` backend: filters, records into the history and forwards to
+/// the terminal
+struct EngineLogger {
+    console: env_logger::Logger,
+}
+
+impl log::Log for EngineLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= level_for_target(&GLOBAL_LOG_FILTERS.read(), metadata.target())
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        record_log_entry(
+            &mut GLOBAL_LOG_HISTORY.lock(),
+            LogEntry {
+                timestamp: chrono::Local::now(),
+                level: record.level(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+                file: record.file().map(str::to_string),
+                line: record.line(),
+            },
+        );
+        self.console.log(record);
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+    }
+}
+
+/// Install the engine logger. Filters come from `filter_spec`, else
+/// RUST_LOG, else `constants::logging::DEFAULT_FILTER_SPEC`.
+pub fn init_logging(filter_spec: Option<&str>) -> LoggingResult<()> {
+    let env_spec = std::env::var("RUST_LOG").ok();
+    let spec = filter_spec
+        .or(env_spec.as_deref())
+        .unwrap_or(logging::DEFAULT_FILTER_SPEC);
+    let filters = parse_filter_spec(spec)?;
+
+    // The engine filters; the terminal prints whatever gets through
+    let console = env_logger::Builder::new()
+        .filter_level(log::LevelFilter::Trace)
+        .format_timestamp_millis()
+        .build();
+    log::set_boxed_logger(Box::new(EngineLogger { console }))
+        .map_err(|_| LoggingError::AlreadyInitialized)?;
+
+    set_global_log_filters(filters);
+    Ok(())
+}
+
+/// Replace the global filters
+pub fn set_global_log_filters(filters: LogFilterData) {
+    log::set_max_level(max_level(&filters));
+    *GLOBAL_LOG_FILTERS.write() = filters;
+}
+
+/// Override the level for one module at runtime
+pub fn set_global_module_level(module: &str, level: log::LevelFilter) {
+    let mut filters = GLOBAL_LOG_FILTERS.write();
+    set_module_level(&mut filters, module, level);
+    log::set_max_level(max_level(&filters));
+}
+
+/// Run a `log ...` console command against the global logger
+pub fn run_log_command(command: &str) -> LoggingResult<String> {
+    let mut filters = GLOBAL_LOG_FILTERS.read().clone();
+    let output = execute_log_command(&mut filters, &mut GLOBAL_LOG_HISTORY.lock(), command)?;
+    set_global_log_filters(filters);
+    Ok(output)
+}
+
+/// Formatted records for the in-engine log view, oldest first
+pub fn recent_log_lines(query: &LogViewQuery) -> Vec<String> {
+    query_log_entries(&GLOBAL_LOG_HISTORY.lock(), query)
+        .into_iter()
+        .map(format_log_entry)
+        .collect()
+}
+
+/// Last `count` records for a crash report. Never blocks: returns nothing
+/// if the history is locked (e.g. a panic while logging).
+pub fn crash_report_log_lines(count: usize) -> Vec<String> {
+    let Some(history) = GLOBAL_LOG_HISTORY.try_lock() else {
+        return Vec::new();
+    };
+    let query = LogViewQuery {
+        count,
+        min_level: log::Level::Trace,
+        target_contains: None,
+    };
+    query_log_entries(&history, &query)
+        .into_iter()
+        .map(format_log_entry)
+        .collect()
+}
+
+/// Write the global history as JSON Lines
+pub fn export_global_logs(path: &std::path::Path) -> LoggingResult<usize> {
+    export_log_history_json(&GLOBAL_LOG_HISTORY.lock(), path)
+}
+
+/// Rate limit check for `log_rate_limited!` against the logging clock
+pub fn rate_limit_check(site: &RateLimitSite, interval_ms: u64) -> Option<u64> {
+    let now_ms = LOGGING_EPOCH.elapsed().as_millis() as u64;
+    rate_limit_allows(site, now_ms, interval_ms)
+}
+
+/// Log at most once per interval from this call site; the next message
+/// reports how many were dropped in between
+///
+/// ```ignore
+/// log_rate_limited!(log::Level::Info, 1000, "Uploaded chunk {:?}", pos);
+/// ```
+#[macro_export]
+macro_rules! log_rate_limited {
+    ($level:expr, $interval_ms:expr, $($arg:tt)+) => {{
+        static SITE: $crate::logging::RateLimitSite = $crate::logging::create_rate_limit_site();
+        let level: $crate::logging::log_crate::Level = $level;
+        if $crate::logging::log_crate::log_enabled!(level) {
+            match $crate::logging::rate_limit_check(&SITE, $interval_ms) {
+                Some(0) => $crate::logging::log_crate::log!(level, $($arg)+),
+                Some(suppressed) => $crate::logging::log_crate::log!(
+                    level,
+                    "{} ({} similar messages suppressed)",
+                    format_args!($($arg)+),
+                    suppressed
+                ),
+                None => {}
+            }
+        }
+    }};
+}
+
+/// `log_rate_limited!` at debug level with the default interval
+#[macro_export]
+macro_rules! debug_rate_limited {
+    ($($arg:tt)+) => {
+        $crate::log_rate_limited!(
+            $crate::logging::log_crate::Level::Debug,
+            $crate::constants::logging::DEFAULT_RATE_LIMIT_MS,
+            $($arg)+
+        )
+    };
+}
+
+/// `log_rate_limited!` at info level with the default interval
+#[macro_export]
+macro_rules! info_rate_limited {
+    ($($arg:tt)+) => {
+        $crate::log_rate_limited!(
+            $crate::logging::log_crate::Level::Info,
+            $crate::constants::logging::DEFAULT_RATE_LIMIT_MS,
+            $($arg)+
+        )
+    };
+}
+
+/// `log_rate_limited!` at warn level with the default interval
+#[macro_export]
+macro_rules! warn_rate_limited {
+    ($($arg:tt)+) => {
+        $crate::log_rate_limited!(
+            $crate::logging::log_crate::Level::Warn,
+            $crate::constants::logging::DEFAULT_RATE_LIMIT_MS,
+            $($arg)+
+        )
+    };
+}
@@ -0,0 +1,59 @@
+//! Frame Arena Data - Pure DOP
+//!
+//! Bump arena for slices that only live for one frame (chunk metadata,
+//! work graph nodes, mesh requests). Allocating is an offset bump into
+//! storage that is reused every frame, so the hot paths stop hitting the
+//! heap once the arena has grown to the largest frame.
+//!
+//! NO METHODS - just data.
+
+use parking_lot::Mutex;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Handle to a slice allocated this frame; resolve it with `frame_slice`
+/// or `frame_slice_mut`. Handles of earlier frames resolve to empty slices.
+#[derive(Debug)]
+pub struct FrameSlice<T> {
+    pub frame: u64,
+    /// Start in storage words (8 bytes)
+    pub offset_words: usize,
+    pub len: usize,
+    pub _marker: PhantomData<T>,
+}
+
+impl<T> Clone for FrameSlice<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for FrameSlice<T> {}
+
+/// Allocation counters of one frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameArenaStats {
+    /// Slices served from the arena (each one a heap allocation before)
+    pub allocations: u64,
+    pub bytes: u64,
+    /// Times the arena itself had to grow (heap allocations now)
+    pub heap_allocations: u64,
+}
+
+/// Arena storage and counters
+#[derive(Debug)]
+pub struct FrameArenaData {
+    /// u64 words so every slice start is 8-byte aligned
+    pub storage: Vec<u64>,
+    pub used_words: usize,
+    pub frame: u64,
+    /// Counters of the frame in progress
+    pub current: FrameArenaStats,
+    /// Counters of the last finished frame
+    pub last_frame: FrameArenaStats,
+    /// Largest number of bytes used in one frame
+    pub high_water_bytes: u64,
+}
+
+/// Arena shared by the systems that allocate during a frame
+pub type SharedFrameArena = Arc<Mutex<FrameArenaData>>;
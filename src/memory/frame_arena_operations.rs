@@ -0,0 +1,186 @@
+//! Frame Arena Operations - Pure DOP functions
+//!
+//! Call `reset_frame_arena` once at the start of every frame. Systems lock
+//! the shared arena, allocate the slices they need with `alloc_frame_slice`,
+//! fill them through `frame_slice_mut` and hand them to the GPU with
+//! `frame_slice`; nothing is freed until the next reset.
+
+use super::frame_arena_data::{FrameArenaData, FrameArenaStats, FrameSlice, SharedFrameArena};
+use crate::engine_buffers::MetricsBuffers;
+use bytemuck::Pod;
+use parking_lot::Mutex;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+const WORD_BYTES: usize = std::mem::size_of::<u64>();
+
+/// Arena with `capacity_bytes` of storage reserved up front
+pub fn create_frame_arena(capacity_bytes: usize) -> FrameArenaData {
+    FrameArenaData {
+        storage: vec![0; capacity_bytes.div_ceil(WORD_BYTES)],
+        used_words: 0,
+        frame: 0,
+        current: FrameArenaStats::default(),
+        last_frame: FrameArenaStats::default(),
+        high_water_bytes: 0,
+    }
+}
+
+/// Arena behind a lock, for systems that allocate from several places
+pub fn create_shared_frame_arena(capacity_bytes: usize) -> SharedFrameArena {
+    Arc::new(Mutex::new(create_frame_arena(capacity_bytes)))
+}
+
+/// Start a new frame: every slice handed out so far is released
+pub fn reset_frame_arena(arena: &mut FrameArenaData) {
+    arena.high_water_bytes = arena
+        .high_water_bytes
+        .max((arena.used_words * WORD_BYTES) as u64);
+    arena.last_frame = arena.current;
+    arena.current = FrameArenaStats::default();
+    arena.used_words = 0;
+    arena.frame += 1;
+}
+
+/// Allocate `len` zeroed values for this frame. The arena grows when the
+/// frame needs more than it has. Slices are 8-byte aligned, so types aligned
+/// above that fail to compile.
+pub fn alloc_frame_slice<T: Pod>(arena: &mut FrameArenaData, len: usize) -> FrameSlice<T> {
+    const {
+        assert!(
+            std::mem::align_of::<T>() <= WORD_BYTES,
+            "frame arena slices are only 8-byte aligned"
+        )
+    };
+
+    let bytes = len * std::mem::size_of::<T>();
+    if bytes == 0 {
+        return FrameSlice {
+            frame: arena.frame,
+            offset_words: arena.used_words,
+            len: 0,
+            _marker: PhantomData,
+        };
+    }
+
+    let words = bytes.div_ceil(WORD_BYTES);
+    let offset_words = arena.used_words;
+    let end = offset_words + words;
+    if end > arena.storage.len() {
+        // Double so a frame that keeps growing settles after a few frames
+        // Only the new tail comes zeroed; the old part may hold earlier frames
+        let grown = end.max(arena.storage.len() * 2);
+        arena.storage[offset_words..].fill(0);
+        arena.storage.resize(grown, 0);
+        arena.current.heap_allocations += 1;
+    } else {
+        arena.storage[offset_words..end].fill(0);
+    }
+    arena.used_words = end;
+    arena.current.allocations += 1;
+    arena.current.bytes += bytes as u64;
+
+    FrameSlice {
+        frame: arena.frame,
+        offset_words,
+        len,
+        _marker: PhantomData,
+    }
+}
+
+/// Values of a slice allocated this frame
+pub fn frame_slice<T: Pod>(arena: &FrameArenaData, slice: FrameSlice<T>) -> &[T] {
+    if slice.frame != arena.frame || slice.len == 0 {
+        return &[];
+    }
+    let bytes: &[u8] = bytemuck::cast_slice(&arena.storage[slice.offset_words..]);
+    bytemuck::try_cast_slice(&bytes[..slice.len * std::mem::size_of::<T>()]).unwrap_or(&[])
+}
+
+/// Mutable values of a slice allocated this frame
+pub fn frame_slice_mut<T: Pod>(arena: &mut FrameArenaData, slice: FrameSlice<T>) -> &mut [T] {
+    if slice.frame != arena.frame || slice.len == 0 {
+        return &mut [];
+    }
+    let bytes: &mut [u8] = bytemuck::cast_slice_mut(&mut arena.storage[slice.offset_words..]);
+    match bytemuck::try_cast_slice_mut(&mut bytes[..slice.len * std::mem::size_of::<T>()]) {
+        Ok(values) => values,
+        Err(_) => &mut [],
+    }
+}
+
+/// Allocate a slice, fill it with `fill` and return the filled values
+pub fn fill_frame_slice<T: Pod>(
+    arena: &mut FrameArenaData,
+    len: usize,
+    fill: impl FnOnce(&mut [T]),
+) -> &[T] {
+    let slice = alloc_frame_slice(arena, len);
+    fill(frame_slice_mut(arena, slice));
+    frame_slice(arena, slice)
+}
+
+/// Bytes allocated so far this frame
+pub fn frame_arena_used_bytes(arena: &FrameArenaData) -> u64 {
+    (arena.used_words * WORD_BYTES) as u64
+}
+
+/// Publish the last finished frame's counters to the metrics buffers
+pub fn record_frame_arena_metrics(arena: &FrameArenaData, metrics: &mut MetricsBuffers) {
+    metrics.frame_arena = arena.last_frame;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slices_bump_grow_and_reset() {
+        let mut arena = create_frame_arena(64);
+        let words = alloc_frame_slice::<u32>(&mut arena, 5);
+        let nodes = alloc_frame_slice::<[u32; 4]>(&mut arena, 2);
+        frame_slice_mut(&mut arena, words).copy_from_slice(&[1, 2, 3, 4, 5]);
+        frame_slice_mut(&mut arena, nodes)[1] = [7, 8, 9, 10];
+
+        assert_eq!(frame_slice(&arena, words), &[1, 2, 3, 4, 5]);
+        assert_eq!(frame_slice(&arena, nodes), &[[0; 4], [7, 8, 9, 10]]);
+        // 5 u32s round up to 3 words, then 4 words of nodes
+        assert_eq!(frame_arena_used_bytes(&arena), 56);
+        assert_eq!(arena.current.heap_allocations, 0);
+
+        // Outgrowing the storage grows it once; earlier slices stay intact
+        let big = fill_frame_slice::<u16>(&mut arena, 100, |values| values.fill(3)).len();
+        assert_eq!(big, 100);
+        assert_eq!(frame_slice(&arena, words)[4], 5);
+        assert_eq!(arena.current.allocations, 3);
+        assert_eq!(arena.current.heap_allocations, 1);
+
+        reset_frame_arena(&mut arena);
+        assert!(frame_slice(&arena, words).is_empty());
+        assert_eq!(arena.last_frame.allocations, 3);
+        assert_eq!(arena.high_water_bytes, 256);
+
+        // The next frame reuses the storage: zeroed, no new heap allocation
+        let again = alloc_frame_slice::<u32>(&mut arena, 5);
+        assert_eq!(frame_slice(&arena, again), &[0; 5]);
+        reset_frame_arena(&mut arena);
+        assert_eq!(arena.last_frame.heap_allocations, 0);
+
+        let mut metrics = MetricsBuffers::default();
+        record_frame_arena_metrics(&arena, &mut metrics);
+        assert_eq!(metrics.frame_arena.allocations, 1);
+    }
+
+    #[test]
+    fn test_growth_after_reset_zeroes_the_reused_storage() {
+        let mut arena = create_frame_arena(100 * WORD_BYTES);
+        fill_frame_slice::<u64>(&mut arena, 100, |values| values.fill(u64::MAX));
+        reset_frame_arena(&mut arena);
+
+        // The second slice starts inside last frame's data and ends past it
+        alloc_frame_slice::<u64>(&mut arena, 50);
+        let grown = alloc_frame_slice::<u64>(&mut arena, 80);
+        assert_eq!(arena.current.heap_allocations, 1);
+        assert_eq!(frame_slice(&arena, grown), &[0; 80]);
+    }
+}
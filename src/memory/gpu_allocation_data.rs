@@ -0,0 +1,119 @@
+//! GPU Allocation Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Buffers and textures created through engine paths are recorded against
+//! the system that owns them, under that system's tag ("sky", "world").
+//! Each record lives as long as its `GpuAllocationGuard`, which the owner
+//! keeps next to the resource, so dropping the resource releases it. The
+//! owner itself is held through a `GpuOwnerGuard` that marks it torn down
+//! when the system is dropped; anything still recorded for a torn-down
+//! owner is a leak. Operations live in gpu_allocation_operations.rs, the
+//! global tracker in memory/mod.rs.
+
+use std::collections::{HashMap, HashSet};
+
+/// Handle of a registered owner system
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GpuOwnerId(pub u32);
+
+/// Handle of a recorded allocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GpuAllocationId(pub u64);
+
+/// What was allocated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuResourceKind {
+    Buffer,
+    Texture,
+}
+
+/// System owning allocations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuOwner {
+    /// Usage is grouped by tag; several owners may share one
+    pub tag: String,
+    /// Set by `teardown_gpu_owner`; the owner is forgotten once its last
+    /// allocation is released
+    pub torn_down: bool,
+}
+
+/// Live allocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuAllocation {
+    pub owner: GpuOwnerId,
+    pub kind: GpuResourceKind,
+    pub label: String,
+    pub bytes: u64,
+}
+
+/// Usage of one tag in a report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuTagUsage {
+    pub tag: String,
+    pub buffers: u32,
+    pub textures: u32,
+    pub bytes: u64,
+    /// Change since the last printed report
+    pub delta_bytes: i64,
+}
+
+/// Allocation still alive after its owner was torn down
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuLeak {
+    pub allocation: GpuAllocationId,
+    pub tag: String,
+    pub kind: GpuResourceKind,
+    pub label: String,
+    pub bytes: u64,
+}
+
+/// Totals published to `MetricsBuffers::gpu_allocations`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GpuAllocationStats {
+    pub allocations: u32,
+    pub allocated_bytes: u64,
+    pub peak_bytes: u64,
+    pub leaks: u32,
+    pub leaked_bytes: u64,
+}
+
+/// Every tracked allocation and owner
+#[derive(Debug, Clone, Default)]
+pub struct GpuAllocationTracker {
+    pub owners: HashMap<GpuOwnerId, GpuOwner>,
+    pub next_owner: u32,
+    pub allocations: HashMap<GpuAllocationId, GpuAllocation>,
+    pub next_allocation: u64,
+    pub allocated_bytes: u64,
+    pub peak_bytes: u64,
+    /// Bytes per tag when the usage report was last printed
+    pub printed_bytes: HashMap<String, u64>,
+    /// Leaks already logged
+    pub reported_leaks: HashSet<GpuAllocationId>,
+    /// `allocated_bytes` last added to `MetricsBuffers::gpu_memory_usage`
+    pub recorded_bytes: u64,
+}
+
+/// Keeps an owner registered in the global tracker; tears it down on drop
+#[derive(Debug)]
+pub struct GpuOwnerGuard {
+    pub owner: GpuOwnerId,
+}
+
+/// Keeps an allocation recorded in the global tracker; released on drop
+#[derive(Debug)]
+pub struct GpuAllocationGuard {
+    pub allocation: GpuAllocationId,
+}
+
+/// A tracked texture with its default view
+pub type TrackedTextureView = (wgpu::Texture, wgpu::TextureView, GpuAllocationGuard);
+
+/// GPU memory console errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum GpuMemoryError {
+    #[error("Unknown gpumem command: {command}")]
+    UnknownCommand { command: String },
+}
+
+pub type GpuMemoryResult<T> = Result<T, GpuMemoryError>;
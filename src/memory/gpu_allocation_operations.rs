@@ -0,0 +1,425 @@
+//! GPU Allocation Operations - Pure DOP functions
+//!
+//! Recording, teardown and leak queries on a `GpuAllocationTracker`, the
+//! `gpumem` console command and publishing totals to the metrics buffers,
+//! where the system monitor checks them against the memory budget.
+
+use super::gpu_allocation_data::{
+    GpuAllocation, GpuAllocationId, GpuAllocationStats, GpuAllocationTracker, GpuLeak,
+    GpuMemoryError, GpuMemoryResult, GpuOwner, GpuOwnerId, GpuResourceKind, GpuTagUsage,
+};
+use crate::engine_buffers::MetricsBuffers;
+
+/// Tag shown for allocations whose owner is not registered
+const UNKNOWN_TAG: &str = "unknown";
+
+/// Create an empty tracker
+pub fn create_gpu_allocation_tracker() -> GpuAllocationTracker {
+    GpuAllocationTracker::default()
+}
+
+/// Register a system that owns GPU resources; usage is reported by `tag`
+pub fn register_gpu_owner(tracker: &mut GpuAllocationTracker, tag: &str) -> GpuOwnerId {
+    let id = GpuOwnerId(tracker.next_owner);
+    tracker.next_owner += 1;
+    tracker.owners.insert(
+        id,
+        GpuOwner {
+            tag: tag.to_string(),
+            torn_down: false,
+        },
+    );
+    id
+}
+
+/// Mark a system as gone. Its resources should be dropped with it; any it
+/// still has recorded from now on are reported as leaks.
+pub fn teardown_gpu_owner(tracker: &mut GpuAllocationTracker, owner: GpuOwnerId) {
+    let owns_allocations = tracker
+        .allocations
+        .values()
+        .any(|allocation| allocation.owner == owner);
+    if !owns_allocations {
+        tracker.owners.remove(&owner);
+    } else if let Some(owner) = tracker.owners.get_mut(&owner) {
+        owner.torn_down = true;
+    }
+}
+
+/// Record a buffer or texture of `bytes` created for `owner`
+pub fn record_gpu_allocation(
+    tracker: &mut GpuAllocationTracker,
+    owner: GpuOwnerId,
+    kind: GpuResourceKind,
+    label: &str,
+    bytes: u64,
+) -> GpuAllocationId {
+    let id = GpuAllocationId(tracker.next_allocation);
+    tracker.next_allocation += 1;
+    tracker.allocations.insert(
+        id,
+        GpuAllocation {
+            owner,
+            kind,
+            label: label.to_string(),
+            bytes,
+        },
+    );
+    tracker.allocated_bytes += bytes;
+    tracker.peak_bytes = tracker.peak_bytes.max(tracker.allocated_bytes);
+    id
+}
+
+/// Forget a freed allocation. Returns false if it was not recorded.
+pub fn release_gpu_allocation(tracker: &mut GpuAllocationTracker, id: GpuAllocationId) -> bool {
+    let Some(allocation) = tracker.allocations.remove(&id) else {
+        return false;
+    };
+    tracker.allocated_bytes = tracker.allocated_bytes.saturating_sub(allocation.bytes);
+    tracker.reported_leaks.remove(&id);
+
+    let owner = allocation.owner;
+    let torn_down = tracker
+        .owners
+        .get(&owner)
+        .is_some_and(|owner| owner.torn_down);
+    if torn_down
+        && !tracker
+            .allocations
+            .values()
+            .any(|other| other.owner == owner)
+    {
+        tracker.owners.remove(&owner);
+    }
+    true
+}
+
+/// Bytes of every mip of a texture; combined depth/stencil formats without
+/// a copy size count 4 bytes per texel
+pub fn texture_descriptor_bytes(descriptor: &wgpu::TextureDescriptor) -> u64 {
+    let format = descriptor.format;
+    let block_bytes = format.block_copy_size(None).unwrap_or(4) as u64;
+    let (block_width, block_height) = format.block_dimensions();
+    let mut bytes = 0;
+    for level in 0..descriptor.mip_level_count {
+        let Some(size) = descriptor.mip_level_size(level) else {
+            break;
+        };
+        let blocks = size.width.div_ceil(block_width) as u64
+            * size.height.div_ceil(block_height) as u64
+            * size.depth_or_array_layers as u64;
+        bytes += blocks * block_bytes;
+    }
+    bytes * descriptor.sample_count.max(1) as u64
+}
+
+/// Tag of an owner
+fn owner_tag(tracker: &GpuAllocationTracker, owner: GpuOwnerId) -> &str {
+    tracker
+        .owners
+        .get(&owner)
+        .map(|owner| owner.tag.as_str())
+        .unwrap_or(UNKNOWN_TAG)
+}
+
+/// Usage per tag, largest first, with the change since the last printed
+/// report. Tags that dropped to nothing since then are listed with 0 bytes.
+pub fn gpu_usage_by_tag(tracker: &GpuAllocationTracker) -> Vec<GpuTagUsage> {
+    let mut usage: Vec<GpuTagUsage> = Vec::new();
+    for allocation in tracker.allocations.values() {
+        let tag = owner_tag(tracker, allocation.owner);
+        let row = match usage.iter().position(|row| row.tag == tag) {
+            Some(row) => row,
+            None => {
+                usage.push(GpuTagUsage {
+                    tag: tag.to_string(),
+                    buffers: 0,
+                    textures: 0,
+                    bytes: 0,
+                    delta_bytes: 0,
+                });
+                usage.len() - 1
+            }
+        };
+        let row = &mut usage[row];
+        match allocation.kind {
+            GpuResourceKind::Buffer => row.buffers += 1,
+            GpuResourceKind::Texture => row.textures += 1,
+        }
+        row.bytes += allocation.bytes;
+    }
+    for (tag, printed) in &tracker.printed_bytes {
+        if *printed > 0 && !usage.iter().any(|row| &row.tag == tag) {
+            usage.push(GpuTagUsage {
+                tag: tag.clone(),
+                buffers: 0,
+                textures: 0,
+                bytes: 0,
+                delta_bytes: 0,
+            });
+        }
+    }
+    for row in &mut usage {
+        let printed = tracker.printed_bytes.get(&row.tag).copied().unwrap_or(0);
+        row.delta_bytes = row.bytes as i64 - printed as i64;
+    }
+    usage.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.tag.cmp(&b.tag)));
+    usage
+}
+
+/// Allocations whose owner was torn down, largest first
+pub fn detect_gpu_leaks(tracker: &GpuAllocationTracker) -> Vec<GpuLeak> {
+    let mut leaks: Vec<GpuLeak> = tracker
+        .allocations
+        .iter()
+        .filter(|(_, allocation)| {
+            tracker
+                .owners
+                .get(&allocation.owner)
+                .is_some_and(|owner| owner.torn_down)
+        })
+        .map(|(id, allocation)| GpuLeak {
+            allocation: *id,
+            tag: owner_tag(tracker, allocation.owner).to_string(),
+            kind: allocation.kind,
+            label: allocation.label.clone(),
+            bytes: allocation.bytes,
+        })
+        .collect();
+    leaks.sort_by(|a, b| {
+        b.bytes
+            .cmp(&a.bytes)
+            .then_with(|| a.allocation.cmp(&b.allocation))
+    });
+    leaks
+}
+
+/// Leaks not returned by an earlier call, for logging each once
+pub fn take_new_gpu_leaks(tracker: &mut GpuAllocationTracker) -> Vec<GpuLeak> {
+    let leaks: Vec<GpuLeak> = detect_gpu_leaks(tracker)
+        .into_iter()
+        .filter(|leak| !tracker.reported_leaks.contains(&leak.allocation))
+        .collect();
+    tracker
+        .reported_leaks
+        .extend(leaks.iter().map(|leak| leak.allocation));
+    leaks
+}
+
+/// Current totals
+pub fn gpu_allocation_stats(tracker: &GpuAllocationTracker) -> GpuAllocationStats {
+    let leaks = detect_gpu_leaks(tracker);
+    GpuAllocationStats {
+        allocations: tracker.allocations.len() as u32,
+        allocated_bytes: tracker.allocated_bytes,
+        peak_bytes: tracker.peak_bytes,
+        leaks: leaks.len() as u32,
+        leaked_bytes: leaks.iter().map(|leak| leak.bytes).sum(),
+    }
+}
+
+/// Publish the totals to the metrics buffers and account tracked bytes in
+/// `gpu_memory_usage`, so the memory budget covers them
+pub fn record_gpu_allocation_stats(
+    tracker: &mut GpuAllocationTracker,
+    metrics: &mut MetricsBuffers,
+) {
+    metrics.gpu_memory_usage = metrics
+        .gpu_memory_usage
+        .saturating_sub(tracker.recorded_bytes)
+        + tracker.allocated_bytes;
+    tracker.recorded_bytes = tracker.allocated_bytes;
+    metrics.gpu_allocations = gpu_allocation_stats(tracker);
+}
+
+// ============================================================================
+// CONSOLE
+// ============================================================================
+
+fn format_gpu_bytes(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= MB {
+        format!("{:.1} MB", bytes as f64 / MB)
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
+fn format_gpu_delta(delta: i64) -> String {
+    if delta == 0 {
+        return "=".to_string();
+    }
+    let sign = if delta > 0 { "+" } else { "-" };
+    format!("{}{}", sign, format_gpu_bytes(delta.unsigned_abs()))
+}
+
+/// Usage report by tag; remembers the printed sizes for the next deltas
+pub fn gpu_usage_report(tracker: &mut GpuAllocationTracker) -> String {
+    let usage = gpu_usage_by_tag(tracker);
+    let mut lines = vec![format!(
+        "GPU memory: {} in {} allocations (peak {})",
+        format_gpu_bytes(tracker.allocated_bytes),
+        tracker.allocations.len(),
+        format_gpu_bytes(tracker.peak_bytes)
+    )];
+    for row in &usage {
+        lines.push(format!(
+            "  {:<20} {:>10} ({:>10})  {} buffers, {} textures",
+            row.tag,
+            format_gpu_bytes(row.bytes),
+            format_gpu_delta(row.delta_bytes),
+            row.buffers,
+            row.textures
+        ));
+    }
+    tracker.printed_bytes = usage.into_iter().map(|row| (row.tag, row.bytes)).collect();
+    lines.join("\n")
+}
+
+fn gpu_leak_report(tracker: &GpuAllocationTracker) -> String {
+    let leaks = detect_gpu_leaks(tracker);
+    if leaks.is_empty() {
+        return "No leaked GPU allocations".to_string();
+    }
+    let mut lines = vec![format!(
+        "{} leaked GPU allocations ({})",
+        leaks.len(),
+        format_gpu_bytes(leaks.iter().map(|leak| leak.bytes).sum())
+    )];
+    for leak in &leaks {
+        lines.push(format!(
+            "  {:<20} {:?} \"{}\" {}",
+            leak.tag,
+            leak.kind,
+            leak.label,
+            format_gpu_bytes(leak.bytes)
+        ));
+    }
+    lines.join("\n")
+}
+
+fn gpu_tag_report(tracker: &GpuAllocationTracker, tag: &str) -> String {
+    let mut allocations: Vec<&GpuAllocation> = tracker
+        .allocations
+        .values()
+        .filter(|allocation| owner_tag(tracker, allocation.owner) == tag)
+        .collect();
+    if allocations.is_empty() {
+        return format!("No GPU allocations tagged {}", tag);
+    }
+    allocations.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.label.cmp(&b.label)));
+    let mut lines = vec![format!("{} allocations tagged {}", allocations.len(), tag)];
+    for allocation in allocations {
+        lines.push(format!(
+            "  {:?} \"{}\" {}",
+            allocation.kind,
+            allocation.label,
+            format_gpu_bytes(allocation.bytes)
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Run a `gpumem` console command and return the text to print:
+///
+/// - `gpumem` / `gpumem usage` - usage by tag with changes since the last print
+/// - `gpumem leaks` - allocations whose owning system was torn down
+/// - `gpumem tag <tag>` - allocations of one tag, largest first
+pub fn execute_gpu_memory_command(
+    tracker: &mut GpuAllocationTracker,
+    command: &str,
+) -> GpuMemoryResult<String> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let args = match words.first() {
+        Some(&"gpumem") => &words[1..],
+        _ => &words[..],
+    };
+
+    match args {
+        [] | ["usage"] => Ok(gpu_usage_report(tracker)),
+        ["leaks"] => Ok(gpu_leak_report(tracker)),
+        ["tag", tag] => Ok(gpu_tag_report(tracker, tag)),
+        _ => Err(GpuMemoryError::UnknownCommand {
+            command: command.trim().to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_deltas_and_leaks_follow_owner_teardown() {
+        let mut tracker = create_gpu_allocation_tracker();
+        let sky = register_gpu_owner(&mut tracker, "sky");
+        let world = register_gpu_owner(&mut tracker, "world");
+        let uniform = record_gpu_allocation(
+            &mut tracker,
+            sky,
+            GpuResourceKind::Buffer,
+            "Sky Uniform",
+            256,
+        );
+        record_gpu_allocation(&mut tracker, world, GpuResourceKind::Buffer, "World", 4096);
+
+        let usage = gpu_usage_by_tag(&tracker);
+        assert_eq!(usage[0].tag, "world");
+        assert_eq!(usage[1].delta_bytes, 256);
+        execute_gpu_memory_command(&mut tracker, "gpumem").expect("usage");
+
+        // The old sky is torn down but keeps its buffer: a leak
+        teardown_gpu_owner(&mut tracker, sky);
+        let replacement = register_gpu_owner(&mut tracker, "sky");
+        record_gpu_allocation(
+            &mut tracker,
+            replacement,
+            GpuResourceKind::Buffer,
+            "Sky Uniform",
+            256,
+        );
+        let leaks = detect_gpu_leaks(&tracker);
+        assert_eq!(leaks.len(), 1);
+        assert_eq!(leaks[0].allocation, uniform);
+        assert_eq!(take_new_gpu_leaks(&mut tracker).len(), 1);
+        assert!(take_new_gpu_leaks(&mut tracker).is_empty());
+        assert_eq!(gpu_usage_by_tag(&tracker)[1].delta_bytes, 256);
+
+        assert!(release_gpu_allocation(&mut tracker, uniform));
+        assert!(!tracker.owners.contains_key(&sky));
+        let stats = gpu_allocation_stats(&tracker);
+        assert_eq!(
+            (stats.leaks, stats.allocated_bytes, stats.peak_bytes),
+            (0, 4352, 4608)
+        );
+
+        let mut metrics = MetricsBuffers::default();
+        metrics.gpu_memory_usage = 1000;
+        record_gpu_allocation_stats(&mut tracker, &mut metrics);
+        release_gpu_allocation(&mut tracker, GpuAllocationId(1));
+        record_gpu_allocation_stats(&mut tracker, &mut metrics);
+        assert_eq!(metrics.gpu_memory_usage, 1256);
+    }
+
+    #[test]
+    fn texture_bytes_cover_every_mip() {
+        let descriptor = wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 256,
+                height: 256,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 3,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        };
+        assert_eq!(
+            texture_descriptor_bytes(&descriptor),
+            (65536 + 16384 + 4096) * 4
+        );
+    }
+}
@@ -3,6 +3,10 @@
 //! This module will be properly implemented after DOP conversion is complete.
 
 pub mod bandwidth_profiler;
+pub mod frame_arena_data;
+pub mod frame_arena_operations;
+pub mod gpu_allocation_data;
+pub mod gpu_allocation_operations;
 pub mod memory_pool;
 pub mod performance_metrics;
 pub mod persistent_buffer;
@@ -10,6 +14,23 @@ pub mod sync_barrier;
 
 // Simple re-exports matching our stub implementations
 pub use bandwidth_profiler::BandwidthProfiler;
+pub use frame_arena_data::{FrameArenaData, FrameArenaStats, FrameSlice, SharedFrameArena};
+pub use frame_arena_operations::{
+    alloc_frame_slice, create_frame_arena, create_shared_frame_arena, fill_frame_slice,
+    frame_arena_used_bytes, frame_slice, frame_slice_mut, record_frame_arena_metrics,
+    reset_frame_arena,
+};
+pub use gpu_allocation_data::{
+    GpuAllocation, GpuAllocationGuard, GpuAllocationId, GpuAllocationStats, GpuAllocationTracker,
+    GpuLeak, GpuMemoryError, GpuMemoryResult, GpuOwner, GpuOwnerGuard, GpuOwnerId,
+    GpuResourceKind, GpuTagUsage, TrackedTextureView,
+};
+pub use gpu_allocation_operations::{
+    create_gpu_allocation_tracker, detect_gpu_leaks, execute_gpu_memory_command,
+    gpu_allocation_stats, gpu_usage_by_tag, gpu_usage_report, record_gpu_allocation,
+    record_gpu_allocation_stats, register_gpu_owner, release_gpu_allocation, take_new_gpu_leaks,
+    teardown_gpu_owner, texture_descriptor_bytes,
+};
 pub use memory_pool::MemoryPool;
 pub use performance_metrics::PerformanceMetrics;
 pub use persistent_buffer::PersistentBuffer;
@@ -22,7 +43,113 @@ pub mod error {
 
 pub use error::MemoryResult;
 
+use parking_lot::Mutex;
 use std::sync::Arc;
+use wgpu::util::DeviceExt;
+
+lazy_static::lazy_static! {
+    /// Buffers and textures created through engine paths, by owner system
+    pub static ref GLOBAL_GPU_ALLOCATIONS: Mutex<GpuAllocationTracker> =
+        Mutex::new(create_gpu_allocation_tracker());
+}
+
+/// Register a system owning GPU resources under `tag`; keep the guard in
+/// the system so dropping the system tears the owner down
+pub fn register_global_gpu_owner(tag: &str) -> GpuOwnerGuard {
+    GpuOwnerGuard {
+        owner: register_gpu_owner(&mut GLOBAL_GPU_ALLOCATIONS.lock(), tag),
+    }
+}
+
+fn track_global_gpu_allocation(
+    owner: &GpuOwnerGuard,
+    kind: GpuResourceKind,
+    label: Option<&str>,
+    bytes: u64,
+) -> GpuAllocationGuard {
+    GpuAllocationGuard {
+        allocation: record_gpu_allocation(
+            &mut GLOBAL_GPU_ALLOCATIONS.lock(),
+            owner.owner,
+            kind,
+            label.unwrap_or("unlabeled"),
+            bytes,
+        ),
+    }
+}
+
+/// Create a buffer recorded against `owner`; keep the guard with the buffer
+pub fn create_tracked_buffer(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    descriptor: &wgpu::BufferDescriptor,
+) -> (wgpu::Buffer, GpuAllocationGuard) {
+    let buffer = device.create_buffer(descriptor);
+    let guard =
+        track_global_gpu_allocation(owner, GpuResourceKind::Buffer, descriptor.label, buffer.size());
+    (buffer, guard)
+}
+
+/// `create_tracked_buffer` with initial contents
+pub fn create_tracked_buffer_init(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    descriptor: &wgpu::util::BufferInitDescriptor,
+) -> (wgpu::Buffer, GpuAllocationGuard) {
+    let buffer = device.create_buffer_init(descriptor);
+    let guard =
+        track_global_gpu_allocation(owner, GpuResourceKind::Buffer, descriptor.label, buffer.size());
+    (buffer, guard)
+}
+
+/// Create a texture recorded against `owner`; keep the guard with the texture
+pub fn create_tracked_texture(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    descriptor: &wgpu::TextureDescriptor,
+) -> (wgpu::Texture, GpuAllocationGuard) {
+    let texture = device.create_texture(descriptor);
+    let guard = track_global_gpu_allocation(
+        owner,
+        GpuResourceKind::Texture,
+        descriptor.label,
+        texture_descriptor_bytes(descriptor),
+    );
+    (texture, guard)
+}
+
+impl Drop for GpuOwnerGuard {
+    fn drop(&mut self) {
+        teardown_gpu_owner(&mut GLOBAL_GPU_ALLOCATIONS.lock(), self.owner);
+    }
+}
+
+impl Drop for GpuAllocationGuard {
+    fn drop(&mut self) {
+        release_gpu_allocation(&mut GLOBAL_GPU_ALLOCATIONS.lock(), self.allocation);
+    }
+}
+
+/// Run a `gpumem ...` console command against the global tracker
+pub fn run_gpu_memory_command(command: &str) -> GpuMemoryResult<String> {
+    execute_gpu_memory_command(&mut GLOBAL_GPU_ALLOCATIONS.lock(), command)
+}
+
+/// Copy global allocation totals into the metrics buffers, logging leaks
+/// the first time they are seen; call once per frame
+pub fn record_gpu_allocation_metrics(metrics: &mut crate::MetricsBuffers) {
+    let mut tracker = GLOBAL_GPU_ALLOCATIONS.lock();
+    for leak in take_new_gpu_leaks(&mut tracker) {
+        log::warn!(
+            "[GpuMemory] {:?} \"{}\" ({} bytes) outlived its owner {}",
+            leak.kind,
+            leak.label,
+            leak.bytes,
+            leak.tag
+        );
+    }
+    record_gpu_allocation_stats(&mut tracker, metrics);
+}
 
 // Buffer allocation wrapper
 pub struct ManagedBuffer {
@@ -1,3 +1,380 @@
-//! Anti-cheat - Stub
+//! Anti-cheat - Server-side validation of client actions
+//!
+//! Hits and block interactions are checked against the world the client
+//! saw: the lag compensation buffer is rewound to the client's perceived
+//! tick, the claimed eye position must lie near the player's rewound
+//! bounds, hits must reach and intersect the target's rewound bounds and a
+//! block interaction must name the block that was there at that tick.
+//! Rejections are counted per player so the server can kick repeat
+//! offenders.
 
-pub struct AntiCheat;
+use super::lag_compensation::{
+    rewind_block, rewind_entity, EntityHistorySample, LagCompensation, RewindError,
+};
+use crate::constants::network_constants::*;
+use crate::world::core::{BlockId, VoxelPos};
+use std::collections::HashMap;
+
+/// Validation limits
+#[derive(Debug, Clone)]
+pub struct AntiCheatConfig {
+    pub max_hit_distance: f32,
+    /// Slack added to rewound target bounds
+    pub hit_bounds_tolerance: f32,
+    /// Eye to block center
+    pub max_block_reach: f32,
+    /// Claimed eye position to the player's rewound bounds
+    pub max_origin_error: f32,
+}
+
+/// A client's claim that it hit an entity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HitClaim {
+    pub attacker_id: u32,
+    pub target_id: u32,
+    /// Eye position the ray was cast from
+    pub origin: [f32; 3],
+    /// Normalized ray direction
+    pub direction: [f32; 3],
+    /// Server tick the client was rendering (fractional between ticks)
+    pub perceived_tick: f64,
+}
+
+/// A client's claim that it broke or used a block
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockInteractionClaim {
+    pub player_id: u32,
+    pub origin: [f32; 3],
+    pub position: VoxelPos,
+    /// Block the client saw at `position`
+    pub expected_block: BlockId,
+    pub perceived_tick: u64,
+}
+
+/// Why a claimed action was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionRejection {
+    Rewind(RewindError),
+    /// The acting player is not in the rewound state
+    UnknownPlayer,
+    /// The target is not in the rewound state
+    UnknownTarget,
+    /// Claimed eye position is too far from the player
+    OriginMismatch,
+    OutOfRange,
+    /// The ray misses the target's rewound bounds
+    Missed,
+    /// The block differs from the one at the perceived tick
+    BlockMismatch,
+}
+
+/// Validation counters
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AntiCheatStats {
+    pub hits_accepted: u64,
+    pub hits_rejected: u64,
+    pub interactions_accepted: u64,
+    pub interactions_rejected: u64,
+}
+
+/// Anti-cheat state
+#[derive(Debug, Clone)]
+pub struct AntiCheat {
+    pub config: AntiCheatConfig,
+    /// Rejected actions per player
+    pub violations: HashMap<u32, u32>,
+    pub stats: AntiCheatStats,
+}
+
+impl Default for AntiCheatConfig {
+    fn default() -> Self {
+        Self {
+            max_hit_distance: MAX_HIT_DISTANCE,
+            hit_bounds_tolerance: HIT_BOUNDS_TOLERANCE,
+            max_block_reach: MAX_BLOCK_REACH,
+            max_origin_error: MAX_CLAIM_ORIGIN_ERROR,
+        }
+    }
+}
+
+pub fn create_anticheat(config: AntiCheatConfig) -> AntiCheat {
+    AntiCheat {
+        config,
+        violations: HashMap::new(),
+        stats: AntiCheatStats::default(),
+    }
+}
+
+/// Rejected actions recorded for `player_id`
+pub fn player_violations(anticheat: &AntiCheat, player_id: u32) -> u32 {
+    anticheat.violations.get(&player_id).copied().unwrap_or(0)
+}
+
+/// Forget a player's violations (e.g. on disconnect)
+pub fn clear_player_violations(anticheat: &mut AntiCheat, player_id: u32) {
+    anticheat.violations.remove(&player_id);
+}
+
+/// World-space min and max corners
+type Bounds = ([f32; 3], [f32; 3]);
+
+/// World-space bounds of a sample, grown by `margin`
+fn sample_bounds(sample: &EntityHistorySample, margin: f32) -> Bounds {
+    let mut min = [0.0; 3];
+    let mut max = [0.0; 3];
+    for axis in 0..3 {
+        min[axis] = sample.position[axis] + sample.aabb_min[axis] - margin;
+        max[axis] = sample.position[axis] + sample.aabb_max[axis] + margin;
+    }
+    (min, max)
+}
+
+fn distance_to_bounds(point: [f32; 3], min: [f32; 3], max: [f32; 3]) -> f32 {
+    let mut squared = 0.0;
+    for axis in 0..3 {
+        let d = (min[axis] - point[axis])
+            .max(point[axis] - max[axis])
+            .max(0.0);
+        squared += d * d;
+    }
+    squared.sqrt()
+}
+
+/// Distance along the ray to the bounds (slab test), if it enters them
+fn ray_bounds_distance(
+    origin: [f32; 3],
+    direction: [f32; 3],
+    min: [f32; 3],
+    max: [f32; 3],
+) -> Option<f32> {
+    let mut near = 0.0f32;
+    let mut far = f32::INFINITY;
+    for axis in 0..3 {
+        if direction[axis].abs() < f32::EPSILON {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let inv = 1.0 / direction[axis];
+        let a = (min[axis] - origin[axis]) * inv;
+        let b = (max[axis] - origin[axis]) * inv;
+        near = near.max(a.min(b));
+        far = far.min(a.max(b));
+        if near > far {
+            return None;
+        }
+    }
+    Some(near)
+}
+
+fn check_origin(
+    anticheat: &AntiCheat,
+    lag: &LagCompensation,
+    player_id: u32,
+    origin: [f32; 3],
+    tick: f64,
+) -> Result<(), ActionRejection> {
+    let player = rewind_entity(lag, player_id, tick)
+        .map_err(ActionRejection::Rewind)?
+        .ok_or(ActionRejection::UnknownPlayer)?;
+    let (min, max) = sample_bounds(&player, 0.0);
+    if distance_to_bounds(origin, min, max) > anticheat.config.max_origin_error {
+        return Err(ActionRejection::OriginMismatch);
+    }
+    Ok(())
+}
+
+fn record_verdict<T>(
+    anticheat: &mut AntiCheat,
+    player_id: u32,
+    verdict: &Result<T, ActionRejection>,
+    hit: bool,
+) {
+    let stats = &mut anticheat.stats;
+    match (verdict.is_ok(), hit) {
+        (true, true) => stats.hits_accepted += 1,
+        (true, false) => stats.interactions_accepted += 1,
+        (false, true) => stats.hits_rejected += 1,
+        (false, false) => stats.interactions_rejected += 1,
+    }
+    if let Err(rejection) = verdict {
+        *anticheat.violations.entry(player_id).or_insert(0) += 1;
+        log::debug!(
+            "[AntiCheat] Player {} action rejected: {:?}",
+            player_id,
+            rejection
+        );
+    }
+}
+
+fn check_hit(
+    anticheat: &AntiCheat,
+    lag: &LagCompensation,
+    claim: &HitClaim,
+) -> Result<EntityHistorySample, ActionRejection> {
+    check_origin(
+        anticheat,
+        lag,
+        claim.attacker_id,
+        claim.origin,
+        claim.perceived_tick,
+    )?;
+    let target = rewind_entity(lag, claim.target_id, claim.perceived_tick)
+        .map_err(ActionRejection::Rewind)?
+        .ok_or(ActionRejection::UnknownTarget)?;
+    let (min, max) = sample_bounds(&target, anticheat.config.hit_bounds_tolerance);
+    match ray_bounds_distance(claim.origin, claim.direction, min, max) {
+        Some(distance) if distance <= anticheat.config.max_hit_distance => Ok(target),
+        Some(_) => Err(ActionRejection::OutOfRange),
+        None => Err(ActionRejection::Missed),
+    }
+}
+
+/// Validate a hit at the client's perceived tick; returns the target as
+/// the client saw it
+pub fn validate_hit(
+    anticheat: &mut AntiCheat,
+    lag: &LagCompensation,
+    claim: &HitClaim,
+) -> Result<EntityHistorySample, ActionRejection> {
+    let verdict = check_hit(anticheat, lag, claim);
+    record_verdict(anticheat, claim.attacker_id, &verdict, true);
+    verdict
+}
+
+fn check_block_interaction(
+    anticheat: &AntiCheat,
+    lag: &LagCompensation,
+    claim: &BlockInteractionClaim,
+    current_block: BlockId,
+) -> Result<(), ActionRejection> {
+    let tick = claim.perceived_tick;
+    check_origin(anticheat, lag, claim.player_id, claim.origin, tick as f64)?;
+    let center = [
+        claim.position.x as f32 + 0.5,
+        claim.position.y as f32 + 0.5,
+        claim.position.z as f32 + 0.5,
+    ];
+    let reach = (0..3)
+        .map(|axis| (center[axis] - claim.origin[axis]).powi(2))
+        .sum::<f32>()
+        .sqrt();
+    if reach > anticheat.config.max_block_reach {
+        return Err(ActionRejection::OutOfRange);
+    }
+    let seen =
+        rewind_block(lag, claim.position, current_block, tick).map_err(ActionRejection::Rewind)?;
+    if seen != claim.expected_block {
+        return Err(ActionRejection::BlockMismatch);
+    }
+    Ok(())
+}
+
+/// Validate a block break or use at the client's perceived tick;
+/// `current_block` is the block at the position now
+pub fn validate_block_interaction(
+    anticheat: &mut AntiCheat,
+    lag: &LagCompensation,
+    claim: &BlockInteractionClaim,
+    current_block: BlockId,
+) -> Result<(), ActionRejection> {
+    let verdict = check_block_interaction(anticheat, lag, claim, current_block);
+    record_verdict(anticheat, claim.player_id, &verdict, false);
+    verdict
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::lag_compensation::{
+        create_lag_compensation, finish_lag_tick, record_lag_voxel_edit, LagCompensationConfig,
+    };
+
+    fn sample(entity_id: u32, x: f32) -> EntityHistorySample {
+        EntityHistorySample {
+            entity_id,
+            position: [x, 0.0, 0.0],
+            aabb_min: [-0.3, 0.0, -0.3],
+            aabb_max: [0.3, 1.8, 0.3],
+        }
+    }
+
+    #[test]
+    fn test_validation_uses_perceived_tick() {
+        let mut lag = create_lag_compensation(LagCompensationConfig::default());
+        let mut anticheat = create_anticheat(AntiCheatConfig::default());
+        let block = VoxelPos::new(2, 1, 0);
+        for tick in 0..10u64 {
+            if tick == 8 {
+                record_lag_voxel_edit(&mut lag, block, BlockId(3), BlockId::AIR);
+            }
+            // Target runs away along +x, one voxel per tick
+            finish_lag_tick(
+                &mut lag,
+                tick,
+                vec![sample(1, 0.0), sample(2, 2.0 + tick as f32)],
+            );
+        }
+
+        // At tick 2 the target was in reach; by tick 5 it had run out of it
+        let mut claim = HitClaim {
+            attacker_id: 1,
+            target_id: 2,
+            origin: [0.0, 1.6, 0.0],
+            direction: [1.0, 0.0, 0.0],
+            perceived_tick: 2.0,
+        };
+        assert_eq!(
+            validate_hit(&mut anticheat, &lag, &claim).unwrap().position[0],
+            4.0
+        );
+        claim.perceived_tick = 5.0;
+        assert_eq!(
+            validate_hit(&mut anticheat, &lag, &claim),
+            Err(ActionRejection::OutOfRange)
+        );
+        claim.direction = [0.0, 0.0, 1.0];
+        claim.perceived_tick = 2.0;
+        assert_eq!(
+            validate_hit(&mut anticheat, &lag, &claim),
+            Err(ActionRejection::Missed)
+        );
+        claim.origin = [5.0, 1.6, 0.0];
+        assert_eq!(
+            validate_hit(&mut anticheat, &lag, &claim),
+            Err(ActionRejection::OriginMismatch)
+        );
+        claim.perceived_tick = 12.0;
+        assert_eq!(
+            validate_hit(&mut anticheat, &lag, &claim),
+            Err(ActionRejection::Rewind(RewindError::InFuture))
+        );
+
+        // The block was broken at tick 8; a client at tick 7 still saw it
+        let mut dig = BlockInteractionClaim {
+            player_id: 1,
+            origin: [0.0, 1.6, 0.0],
+            position: block,
+            expected_block: BlockId(3),
+            perceived_tick: 7,
+        };
+        assert!(validate_block_interaction(&mut anticheat, &lag, &dig, BlockId::AIR).is_ok());
+        dig.perceived_tick = 9;
+        assert_eq!(
+            validate_block_interaction(&mut anticheat, &lag, &dig, BlockId::AIR),
+            Err(ActionRejection::BlockMismatch)
+        );
+        dig.position = VoxelPos::new(20, 1, 0);
+        assert_eq!(
+            validate_block_interaction(&mut anticheat, &lag, &dig, BlockId::AIR),
+            Err(ActionRejection::OutOfRange)
+        );
+
+        assert_eq!(anticheat.stats.hits_accepted, 1);
+        assert_eq!(anticheat.stats.interactions_accepted, 1);
+        assert_eq!(player_violations(&anticheat, 1), 6);
+        clear_player_violations(&mut anticheat, 1);
+        assert_eq!(player_violations(&anticheat, 1), 0);
+    }
+}
@@ -0,0 +1,94 @@
+//! Chunk Diff Data - Pure DOP
+//!
+//! The server keeps a bounded log of block edits per chunk. A client that
+//! still has a chunk from an earlier visit sends the version it has with
+//! its request and receives only the edits made since, instead of the whole
+//! chunk. When the log no longer reaches back to that version (trimmed, or
+//! a different epoch) the chunk is sent in full as before.
+//!
+//! NO METHODS - just data.
+
+use crate::world::core::{BlockId, ChunkPos};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Prefix of encoded `ChunkDiffPacket`s
+pub const CHUNK_DIFF_MAGIC: [u8; 4] = *b"CDIF";
+
+/// Version of a chunk's contents. Versions only compare within one epoch;
+/// the server starts a new epoch whenever chunks may have changed without
+/// being logged (e.g. on start-up when the logs were not persisted).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ChunkVersion {
+    pub epoch: u64,
+    /// Edits logged since the epoch began (0 = as loaded or generated)
+    pub version: u64,
+}
+
+/// One block change inside a chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkEdit {
+    /// Index into the chunk's blocks, y-major like `TempChunk::blocks`
+    pub index: u32,
+    pub block: BlockId,
+}
+
+/// Edit stamped with the chunk version it produced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionedEdit {
+    pub version: u64,
+    pub edit: ChunkEdit,
+}
+
+/// Edit history of one chunk
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChunkEditLog {
+    pub version: u64,
+    /// Oldest version a diff can start from
+    pub oldest_version: u64,
+    /// Edits after `oldest_version`, oldest first
+    pub edits: VecDeque<VersionedEdit>,
+}
+
+/// Edit log settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChunkEditLogConfig {
+    /// Edits kept per chunk before the oldest are dropped
+    pub max_edits_per_chunk: usize,
+    /// Diffs with more distinct edits than this are sent as full chunks
+    pub max_diff_edits: usize,
+}
+
+/// Server-wide edit logs, shared by every client's send queue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkEditLogData {
+    pub config: ChunkEditLogConfig,
+    pub epoch: u64,
+    /// Chunks never edited in this epoch have no entry (version 0)
+    pub logs: HashMap<ChunkPos, ChunkEditLog>,
+}
+
+/// Server -> client: edits that bring a cached chunk from `from` to `to`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkDiffPacket {
+    pub chunk: ChunkPos,
+    pub chunk_size: u32,
+    pub from: ChunkVersion,
+    pub to: ChunkVersion,
+    /// Last edit per block, empty when the client is already up to date
+    pub edits: Vec<ChunkEdit>,
+}
+
+/// What a received diff did
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffReceipt {
+    /// Chunk no longer requested, or the diff does not start at the
+    /// version the client has
+    Ignored,
+    /// Apply `edits` to the cached chunk (see `apply_chunk_edits`); it is
+    /// then at `version`
+    Apply {
+        edits: Vec<ChunkEdit>,
+        version: ChunkVersion,
+    },
+}
@@ -0,0 +1,197 @@
+//! Chunk Diff Operations - Pure DOP functions
+//!
+//! Server: `record_chunk_edit` for every block change of a loaded chunk,
+//! and pass the log to `apply_chunk_request`, which answers requests for
+//! cached chunks with diffs. Client: `remember_chunk_version` for chunks
+//! restored from its cache, then `receive_chunk_diff` and
+//! `apply_chunk_edits` for every diff packet.
+
+use super::chunk_diff_data::{
+    ChunkEdit, ChunkEditLogConfig, ChunkEditLogData, ChunkVersion, VersionedEdit,
+};
+use super::error::NetworkResult;
+use crate::constants::network_constants::{MAX_CHUNK_DIFF_EDITS, MAX_CHUNK_EDIT_LOG_ENTRIES};
+use crate::world::core::{BlockId, ChunkPos};
+use std::collections::HashMap;
+
+/// Defaults from `constants::network_constants`
+pub fn default_chunk_edit_log_config() -> ChunkEditLogConfig {
+    ChunkEditLogConfig {
+        max_edits_per_chunk: MAX_CHUNK_EDIT_LOG_ENTRIES,
+        max_diff_edits: MAX_CHUNK_DIFF_EDITS,
+    }
+}
+
+/// Empty logs for a new epoch
+pub fn create_chunk_edit_log(config: ChunkEditLogConfig, epoch: u64) -> ChunkEditLogData {
+    ChunkEditLogData {
+        config,
+        epoch,
+        logs: HashMap::new(),
+    }
+}
+
+/// Current version of a chunk
+pub fn current_chunk_version(data: &ChunkEditLogData, chunk: ChunkPos) -> ChunkVersion {
+    ChunkVersion {
+        epoch: data.epoch,
+        version: data.logs.get(&chunk).map_or(0, |log| log.version),
+    }
+}
+
+/// Log a block change at `index` of `chunk`. Returns the new version.
+pub fn record_chunk_edit(
+    data: &mut ChunkEditLogData,
+    chunk: ChunkPos,
+    index: u32,
+    block: BlockId,
+) -> ChunkVersion {
+    let max_edits = data.config.max_edits_per_chunk;
+    let log = data.logs.entry(chunk).or_default();
+    log.version += 1;
+    log.edits.push_back(VersionedEdit {
+        version: log.version,
+        edit: ChunkEdit { index, block },
+    });
+    while log.edits.len() > max_edits {
+        // Clients at the dropped edit's version or later can still diff
+        if let Some(dropped) = log.edits.pop_front() {
+            log.oldest_version = dropped.version;
+        }
+    }
+    ChunkVersion {
+        epoch: data.epoch,
+        version: log.version,
+    }
+}
+
+/// Forget a chunk's edits when its stored contents are replaced wholesale
+/// (regenerated, restored from a backup). The version still moves on, so
+/// clients holding an older copy get the chunk in full.
+pub fn reset_chunk_edit_log(data: &mut ChunkEditLogData, chunk: ChunkPos) -> ChunkVersion {
+    let log = data.logs.entry(chunk).or_default();
+    log.version += 1;
+    log.oldest_version = log.version;
+    log.edits.clear();
+    ChunkVersion {
+        epoch: data.epoch,
+        version: log.version,
+    }
+}
+
+/// Edits that bring a chunk from `known` to its current version, keeping
+/// only the last edit per block. `None` when the log cannot produce that
+/// diff, or it would be larger than `max_diff_edits`; send the chunk in
+/// full then.
+pub fn chunk_diff_since(
+    data: &ChunkEditLogData,
+    chunk: ChunkPos,
+    known: ChunkVersion,
+) -> Option<Vec<ChunkEdit>> {
+    if known.epoch != data.epoch {
+        return None;
+    }
+    let Some(log) = data.logs.get(&chunk) else {
+        // Never edited this epoch
+        return (known.version == 0).then(Vec::new);
+    };
+    if known.version < log.oldest_version || known.version > log.version {
+        return None;
+    }
+
+    let mut latest: HashMap<u32, usize> = HashMap::new();
+    let mut edits: Vec<ChunkEdit> = Vec::new();
+    for logged in log
+        .edits
+        .iter()
+        .filter(|logged| logged.version > known.version)
+    {
+        match latest.get(&logged.edit.index) {
+            Some(&slot) => edits[slot] = logged.edit,
+            None => {
+                latest.insert(logged.edit.index, edits.len());
+                edits.push(logged.edit);
+            }
+        }
+    }
+    (edits.len() <= data.config.max_diff_edits).then_some(edits)
+}
+
+/// Apply a diff to a cached chunk. Edits outside `blocks` are skipped;
+/// returns how many were applied.
+pub fn apply_chunk_edits(blocks: &mut [BlockId], edits: &[ChunkEdit]) -> usize {
+    let mut applied = 0;
+    for edit in edits {
+        if let Some(block) = blocks.get_mut(edit.index as usize) {
+            *block = edit.block;
+            applied += 1;
+        }
+    }
+    applied
+}
+
+/// Serialize the logs so a persistent server can keep its epoch across
+/// restarts (save them together with the world)
+pub fn encode_chunk_edit_log(data: &ChunkEditLogData) -> NetworkResult<Vec<u8>> {
+    bincode::serialize(data).map_err(|e| format!("Failed to encode edit log: {}", e))
+}
+
+/// Restore logs saved by `encode_chunk_edit_log`
+pub fn decode_chunk_edit_log(bytes: &[u8]) -> NetworkResult<ChunkEditLogData> {
+    bincode::deserialize(bytes).map_err(|e| format!("Failed to decode edit log: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_collapses_edits_and_falls_back_after_trim() {
+        let config = ChunkEditLogConfig {
+            max_edits_per_chunk: 4,
+            max_diff_edits: 8,
+        };
+        let mut log = create_chunk_edit_log(config, 7);
+        let chunk = ChunkPos::new(1, 0, -2);
+        let start = current_chunk_version(&log, chunk);
+        assert_eq!(chunk_diff_since(&log, chunk, start), Some(Vec::new()));
+
+        record_chunk_edit(&mut log, chunk, 10, BlockId::STONE);
+        record_chunk_edit(&mut log, chunk, 11, BlockId::DIRT);
+        let latest = record_chunk_edit(&mut log, chunk, 10, BlockId::AIR);
+        assert_eq!(latest.version, 3);
+        assert_eq!(
+            chunk_diff_since(&log, chunk, start),
+            Some(vec![
+                ChunkEdit {
+                    index: 10,
+                    block: BlockId::AIR
+                },
+                ChunkEdit {
+                    index: 11,
+                    block: BlockId::DIRT
+                },
+            ])
+        );
+        assert_eq!(chunk_diff_since(&log, chunk, latest), Some(Vec::new()));
+
+        // A different epoch never diffs
+        let other_epoch = ChunkVersion { epoch: 8, ..start };
+        assert_eq!(chunk_diff_since(&log, chunk, other_epoch), None);
+
+        // Trimming past version 0 forces a full send for the oldest copy
+        record_chunk_edit(&mut log, chunk, 12, BlockId::GRASS);
+        record_chunk_edit(&mut log, chunk, 13, BlockId::GRASS);
+        assert_eq!(chunk_diff_since(&log, chunk, start), None);
+        let diff = chunk_diff_since(&log, chunk, latest).expect("still logged");
+        assert_eq!(diff.len(), 2);
+
+        let mut blocks = vec![BlockId::AIR; 16];
+        assert_eq!(apply_chunk_edits(&mut blocks, &diff), 2);
+        assert_eq!(blocks[13], BlockId::GRASS);
+
+        let restored =
+            decode_chunk_edit_log(&encode_chunk_edit_log(&log).expect("encodes")).expect("decodes");
+        assert_eq!(restored, log);
+    }
+}
@@ -0,0 +1,135 @@
+//! Chunk Stream Data - Pure DOP
+//!
+//! Clients request the chunks they are missing, ordered by distance and
+//! view direction, and cancel the ones they stop needing. The server sends
+//! each chunk as horizontal sections, nearest the player's Y first, so the
+//! ground under a player who just teleported or respawned arrives before
+//! the rest of the column. Clients that still hold an older copy of a
+//! chunk get a diff instead (see `chunk_diff_data`).
+//!
+//! NO METHODS - just data.
+
+use super::chunk_diff_data::ChunkVersion;
+use crate::world::core::{BlockId, ChunkPos};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Prefix of encoded `ChunkRequestMessage`s
+pub const CHUNK_REQUEST_MAGIC: [u8; 4] = *b"CREQ";
+
+/// Prefix of encoded `ChunkSectionPacket`s
+pub const CHUNK_SECTION_MAGIC: [u8; 4] = *b"CSEC";
+
+/// Client request for one chunk
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChunkRequest {
+    pub chunk: ChunkPos,
+    /// Lower is sent sooner (roughly chunks of distance)
+    pub priority: f32,
+    /// Version of the copy the client already has, if any
+    pub known_version: Option<ChunkVersion>,
+}
+
+/// Client -> server: every chunk the client still wants, and the chunks
+/// it requested before but no longer needs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChunkRequestMessage {
+    pub requests: Vec<ChunkRequest>,
+    pub cancels: Vec<ChunkPos>,
+}
+
+/// Server -> client: one horizontal slab of a chunk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkSectionPacket {
+    pub chunk: ChunkPos,
+    pub chunk_size: u32,
+    /// Section index, 0 at the bottom of the chunk
+    pub section: u32,
+    pub section_count: u32,
+    /// Version of the chunk the section was cut from
+    pub version: ChunkVersion,
+    /// Blocks of the section, y-major like `TempChunk::blocks`
+    pub blocks: Vec<BlockId>,
+}
+
+/// Chunk streaming settings shared by client and server
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkStreamConfig {
+    /// Section height (voxels)
+    pub section_height: u32,
+    /// How much farther chunks behind the player count
+    pub view_direction_weight: f32,
+    pub max_requests: usize,
+    pub max_sections_per_tick: usize,
+    pub max_queued_bytes: usize,
+}
+
+/// Chunk being assembled from sections on the client
+#[derive(Debug, Clone)]
+pub struct PendingChunk {
+    pub chunk_size: u32,
+    pub version: ChunkVersion,
+    pub blocks: Vec<BlockId>,
+    pub received: Vec<bool>,
+    pub received_count: u32,
+}
+
+/// Client-side request bookkeeping
+#[derive(Debug, Clone)]
+pub struct ChunkRequestState {
+    pub config: ChunkStreamConfig,
+    /// Requested chunks in the order last sent
+    pub requested: Vec<ChunkPos>,
+    pub pending: HashMap<ChunkPos, PendingChunk>,
+    /// Versions of chunks the client holds a copy of, loaded or cached
+    pub known_versions: HashMap<ChunkPos, ChunkVersion>,
+}
+
+/// What a received section did
+#[derive(Debug, Clone, PartialEq)]
+pub enum SectionReceipt {
+    /// Chunk no longer requested, or malformed section
+    Ignored,
+    Partial {
+        received: u32,
+        total: u32,
+    },
+    /// Last section arrived; full chunk blocks
+    Complete(Vec<BlockId>),
+}
+
+/// Chunk the server is sending to one client
+#[derive(Debug, Clone)]
+pub struct ChunkSendJob {
+    pub chunk: ChunkPos,
+    pub priority: f32,
+    pub chunk_size: u32,
+    pub version: ChunkVersion,
+    pub blocks: Vec<BlockId>,
+    /// Sections not yet queued
+    pub remaining_sections: Vec<u32>,
+}
+
+/// Streaming counters of one client
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChunkStreamStats {
+    pub sections_sent: u64,
+    pub chunks_completed: u64,
+    pub chunks_cancelled: u64,
+    /// Already queued section packets removed by cancels
+    pub packets_dropped: u64,
+    /// Requests for cached chunks answered with a diff
+    pub diffs_sent: u64,
+    /// Requests for cached chunks the edit log could not diff
+    pub full_fallbacks: u64,
+    /// Chunk block bytes not sent thanks to diffs
+    pub bytes_saved: u64,
+}
+
+/// Server-side send state of one client
+#[derive(Debug, Clone)]
+pub struct ChunkSendQueue {
+    pub config: ChunkStreamConfig,
+    pub jobs: Vec<ChunkSendJob>,
+    pub stats: ChunkStreamStats,
+}
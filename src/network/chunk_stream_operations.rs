@@ -0,0 +1,846 @@
+//! Chunk Stream Operations - Pure DOP functions
+//!
+//! Client: `update_chunk_requests` whenever the player moves or turns, send
+//! the returned message, and feed section packets to `receive_chunk_section`
+//! and diff packets to `receive_chunk_diff`.
+//! Server: `apply_chunk_request` for every request message, then
+//! `pump_chunk_sends` once per tick before `flush_connection`.
+
+use super::chunk_diff_data::{
+    ChunkDiffPacket, ChunkEditLogData, ChunkVersion, DiffReceipt, CHUNK_DIFF_MAGIC,
+};
+use super::chunk_diff_operations::{chunk_diff_since, current_chunk_version};
+use super::chunk_stream_data::{
+    ChunkRequest, ChunkRequestMessage, ChunkRequestState, ChunkSectionPacket, ChunkSendJob,
+    ChunkSendQueue, ChunkStreamConfig, ChunkStreamStats, PendingChunk, SectionReceipt,
+    CHUNK_REQUEST_MAGIC, CHUNK_SECTION_MAGIC,
+};
+use super::connection::{queue_packet, queued_bytes, Connection, SendPriority};
+use super::error::NetworkResult;
+use super::registry_sync_data::BlockIdRemap;
+use super::registry_sync_operations::{remap_incoming_block, remap_incoming_blocks};
+use crate::constants::network_constants::{
+    CHUNK_DIFF_PACKET_TYPE, CHUNK_SECTION_HEIGHT, CHUNK_SECTION_PACKET_TYPE,
+    CHUNK_VIEW_DIRECTION_WEIGHT, MAX_CHUNK_REQUESTS, MAX_CHUNK_SECTIONS_PER_TICK,
+    MAX_QUEUED_CHUNK_BYTES,
+};
+use crate::world::core::{
+    chunk_layout_for_size, world_point_to_chunk_pos, BlockId, ChunkPos,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Defaults from `constants::network_constants`
+pub fn default_chunk_stream_config() -> ChunkStreamConfig {
+    ChunkStreamConfig {
+        section_height: CHUNK_SECTION_HEIGHT,
+        view_direction_weight: CHUNK_VIEW_DIRECTION_WEIGHT,
+        max_requests: MAX_CHUNK_REQUESTS,
+        max_sections_per_tick: MAX_CHUNK_SECTIONS_PER_TICK,
+        max_queued_bytes: MAX_QUEUED_CHUNK_BYTES,
+    }
+}
+
+/// Sections per chunk (the top one may be shorter)
+pub fn chunk_section_count(config: &ChunkStreamConfig, chunk_size: u32) -> u32 {
+    let height = config.section_height.clamp(1, chunk_size.max(1));
+    chunk_size.div_ceil(height)
+}
+
+/// Range of `TempChunk::blocks` indices holding a section
+fn section_block_range(
+    config: &ChunkStreamConfig,
+    chunk_size: u32,
+    section: u32,
+) -> std::ops::Range<usize> {
+    let height = config.section_height.clamp(1, chunk_size.max(1));
+    let layer = (chunk_size * chunk_size) as usize;
+    let bottom = (section * height).min(chunk_size) as usize;
+    let top = ((section + 1) * height).min(chunk_size) as usize;
+    bottom * layer..top * layer
+}
+
+/// Request priority of a chunk: distance in chunks from the player, up to
+/// `1 + view_direction_weight` times farther for chunks behind. Lower is
+/// sooner; the player's own chunk is 0.
+pub fn chunk_request_priority(
+    config: &ChunkStreamConfig,
+    chunk: ChunkPos,
+    player_position: [f32; 3],
+    view_direction: [f32; 3],
+    chunk_size: u32,
+) -> f32 {
+    let size = chunk_size.max(1) as f32;
+    let offset = [
+        (chunk.x as f32 + 0.5) * size - player_position[0],
+        (chunk.y as f32 + 0.5) * size - player_position[1],
+        (chunk.z as f32 + 0.5) * size - player_position[2],
+    ];
+    let distance = (offset[0] * offset[0] + offset[1] * offset[1] + offset[2] * offset[2]).sqrt();
+    let view_length = (view_direction[0] * view_direction[0]
+        + view_direction[1] * view_direction[1]
+        + view_direction[2] * view_direction[2])
+        .sqrt();
+    if distance < size * 0.5 || view_length <= f32::EPSILON {
+        return (distance / size).max(0.0);
+    }
+
+    let cosine = (offset[0] * view_direction[0]
+        + offset[1] * view_direction[1]
+        + offset[2] * view_direction[2])
+        / (distance * view_length);
+    let behind = (1.0 - cosine.clamp(-1.0, 1.0)) * 0.5;
+    distance / size * (1.0 + config.view_direction_weight * behind)
+}
+
+// ============================================================================
+// CLIENT
+// ============================================================================
+
+/// Client state with nothing requested
+pub fn create_chunk_request_state(config: ChunkStreamConfig) -> ChunkRequestState {
+    ChunkRequestState {
+        config,
+        requested: Vec::new(),
+        pending: HashMap::new(),
+        known_versions: HashMap::new(),
+    }
+}
+
+/// Note a chunk the client holds a copy of (e.g. restored from its disk
+/// cache), so requests for it ask for a diff
+pub fn remember_chunk_version(
+    state: &mut ChunkRequestState,
+    chunk: ChunkPos,
+    version: ChunkVersion,
+) {
+    state.known_versions.insert(chunk, version);
+}
+
+/// Forget a chunk whose copy the client dropped
+pub fn forget_chunk_version(state: &mut ChunkRequestState, chunk: ChunkPos) {
+    state.known_versions.remove(&chunk);
+}
+
+/// Recompute which chunks within `view_distance` (chunks) of the player are
+/// missing and in what order. Returns a message when the set or order
+/// changed; chunks dropped from the set are cancelled.
+pub fn update_chunk_requests(
+    state: &mut ChunkRequestState,
+    player_position: [f32; 3],
+    view_direction: [f32; 3],
+    chunk_size: u32,
+    view_distance: u32,
+    loaded: &HashSet<ChunkPos>,
+) -> Option<ChunkRequestMessage> {
+    let center = world_point_to_chunk_pos(
+        chunk_layout_for_size(chunk_size),
+        player_position.map(f64::from),
+    );
+    let radius = view_distance as i32;
+
+    let mut requests = Vec::new();
+    for x in -radius..=radius {
+        for y in -radius..=radius {
+            for z in -radius..=radius {
+                let chunk = ChunkPos::new(center.x + x, center.y + y, center.z + z);
+                if loaded.contains(&chunk) {
+                    continue;
+                }
+                let priority = chunk_request_priority(
+                    &state.config,
+                    chunk,
+                    player_position,
+                    view_direction,
+                    chunk_size,
+                );
+                requests.push(ChunkRequest {
+                    chunk,
+                    priority,
+                    known_version: state.known_versions.get(&chunk).copied(),
+                });
+            }
+        }
+    }
+    requests.sort_by(|a, b| a.priority.total_cmp(&b.priority));
+    requests.truncate(state.config.max_requests);
+
+    let order: Vec<ChunkPos> = requests.iter().map(|request| request.chunk).collect();
+    if order == state.requested {
+        return None;
+    }
+
+    let wanted: HashSet<ChunkPos> = order.iter().copied().collect();
+    let cancels: Vec<ChunkPos> = state
+        .requested
+        .iter()
+        .copied()
+        .filter(|chunk| !wanted.contains(chunk))
+        .collect();
+    state.pending.retain(|chunk, _| wanted.contains(chunk));
+    state.requested = order;
+    Some(ChunkRequestMessage { requests, cancels })
+}
+
+/// Store a received section. Sections of chunks no longer requested are
+/// ignored, since their cancel may still be on the way to the server.
+pub fn receive_chunk_section(
+    state: &mut ChunkRequestState,
+    packet: ChunkSectionPacket,
+    remap: Option<&BlockIdRemap>,
+) -> SectionReceipt {
+    let _span = crate::trace_span!(Network, "receive_chunk_section");
+    let ChunkSectionPacket {
+        chunk,
+        chunk_size,
+        section,
+        section_count,
+        version,
+        mut blocks,
+    } = packet;
+    if !state.requested.contains(&chunk)
+        || section >= section_count
+        || section_count != chunk_section_count(&state.config, chunk_size)
+    {
+        return SectionReceipt::Ignored;
+    }
+    let range = section_block_range(&state.config, chunk_size, section);
+    if blocks.len() != range.len() {
+        return SectionReceipt::Ignored;
+    }
+    if let Some(remap) = remap {
+        remap_incoming_blocks(remap, &mut blocks);
+    }
+
+    let fresh = || PendingChunk {
+        chunk_size,
+        version,
+        blocks: vec![BlockId::AIR; (chunk_size * chunk_size * chunk_size) as usize],
+        received: vec![false; section_count as usize],
+        received_count: 0,
+    };
+    let pending = state.pending.entry(chunk).or_insert_with(fresh);
+    if pending.chunk_size != chunk_size {
+        return SectionReceipt::Ignored;
+    }
+    if pending.version != version {
+        // The chunk changed between two sends; sections of the older send
+        // cannot be mixed with the newer ones
+        *pending = fresh();
+    }
+    pending.blocks[range].copy_from_slice(&blocks);
+    if !pending.received[section as usize] {
+        pending.received[section as usize] = true;
+        pending.received_count += 1;
+    }
+    if pending.received_count < section_count {
+        return SectionReceipt::Partial {
+            received: pending.received_count,
+            total: section_count,
+        };
+    }
+
+    state.requested.retain(|requested| *requested != chunk);
+    match state.pending.remove(&chunk) {
+        Some(complete) => {
+            state.known_versions.insert(chunk, complete.version);
+            SectionReceipt::Complete(complete.blocks)
+        }
+        None => SectionReceipt::Ignored,
+    }
+}
+
+/// Accept a diff for a cached chunk. Diffs that do not start at the version
+/// the client holds are ignored; the chunk stays requested and the server
+/// answers the next request.
+pub fn receive_chunk_diff(
+    state: &mut ChunkRequestState,
+    packet: ChunkDiffPacket,
+    remap: Option<&BlockIdRemap>,
+) -> DiffReceipt {
+    if !state.requested.contains(&packet.chunk)
+        || state.known_versions.get(&packet.chunk) != Some(&packet.from)
+    {
+        return DiffReceipt::Ignored;
+    }
+    let mut edits = packet.edits;
+    if let Some(remap) = remap {
+        for edit in &mut edits {
+            edit.block = remap_incoming_block(remap, edit.block);
+        }
+    }
+    state
+        .requested
+        .retain(|requested| *requested != packet.chunk);
+    state.pending.remove(&packet.chunk);
+    state.known_versions.insert(packet.chunk, packet.to);
+    DiffReceipt::Apply {
+        edits,
+        version: packet.to,
+    }
+}
+
+// ============================================================================
+// SERVER
+// ============================================================================
+
+/// Server queue with nothing to send
+pub fn create_chunk_send_queue(config: ChunkStreamConfig) -> ChunkSendQueue {
+    ChunkSendQueue {
+        config,
+        jobs: Vec::new(),
+        stats: ChunkStreamStats::default(),
+    }
+}
+
+/// Apply a client request: cancel what it no longer needs, reprioritize
+/// chunks already being sent and start the new ones. Chunks the client has
+/// an older copy of are answered right away with a diff when `edit_log`
+/// can produce one. `chunk_blocks` returns the blocks of a loaded chunk at
+/// its current `edit_log` version (`None` if it is not ready yet; the
+/// client asks again with its next request). Returns how many sends
+/// started, diffs included.
+pub fn apply_chunk_request(
+    queue: &mut ChunkSendQueue,
+    conn: &mut Connection,
+    message: &ChunkRequestMessage,
+    chunk_size: u32,
+    edit_log: &ChunkEditLogData,
+    mut chunk_blocks: impl FnMut(ChunkPos) -> Option<Vec<BlockId>>,
+) -> usize {
+    let _span = crate::trace_span!(Network, "apply_chunk_request");
+    cancel_chunk_sends(queue, conn, &message.cancels);
+
+    let section_count = chunk_section_count(&queue.config, chunk_size);
+    let expected_len = (chunk_size * chunk_size * chunk_size) as usize;
+    let mut started = 0;
+    for request in message.requests.iter().take(queue.config.max_requests) {
+        if let Some(job) = queue.jobs.iter_mut().find(|job| job.chunk == request.chunk) {
+            job.priority = request.priority;
+            continue;
+        }
+        if let Some(known) = request.known_version {
+            if send_chunk_diff(queue, conn, edit_log, request.chunk, chunk_size, known) {
+                started += 1;
+                continue;
+            }
+            queue.stats.full_fallbacks += 1;
+        }
+        let Some(blocks) = chunk_blocks(request.chunk) else {
+            continue;
+        };
+        if blocks.len() != expected_len {
+            continue;
+        }
+        queue.jobs.push(ChunkSendJob {
+            chunk: request.chunk,
+            priority: request.priority,
+            chunk_size,
+            version: current_chunk_version(edit_log, request.chunk),
+            blocks,
+            remaining_sections: (0..section_count).collect(),
+        });
+        started += 1;
+    }
+    started
+}
+
+/// Queue a diff from `known` to the current version. Returns false when
+/// the chunk has to be sent in full.
+fn send_chunk_diff(
+    queue: &mut ChunkSendQueue,
+    conn: &mut Connection,
+    edit_log: &ChunkEditLogData,
+    chunk: ChunkPos,
+    chunk_size: u32,
+    known: ChunkVersion,
+) -> bool {
+    let Some(edits) = chunk_diff_since(edit_log, chunk, known) else {
+        return false;
+    };
+    let packet = ChunkDiffPacket {
+        chunk,
+        chunk_size,
+        from: known,
+        to: current_chunk_version(edit_log, chunk),
+        edits,
+    };
+    let bytes = match encode_chunk_diff(&packet) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::warn!("[ChunkStream] Sending {:?} in full: {}", chunk, e);
+            return false;
+        }
+    };
+    let full_bytes =
+        (chunk_size * chunk_size * chunk_size) as usize * std::mem::size_of::<BlockId>();
+    queue.stats.diffs_sent += 1;
+    queue.stats.bytes_saved += full_bytes.saturating_sub(bytes.len()) as u64;
+    queue_packet(conn, SendPriority::ChunkData, CHUNK_DIFF_PACKET_TYPE, bytes);
+    true
+}
+
+/// Chunk a queued section or diff packet belongs to
+fn queued_packet_chunk(payload: &[u8]) -> Option<ChunkPos> {
+    if let Ok(section) = decode_chunk_section(payload) {
+        return Some(section.chunk);
+    }
+    decode_chunk_diff(payload).ok().map(|diff| diff.chunk)
+}
+
+/// Stop sending `chunks`, including section and diff packets still queued on the
+/// connection. Returns how many sends were cancelled.
+pub fn cancel_chunk_sends(
+    queue: &mut ChunkSendQueue,
+    conn: &mut Connection,
+    chunks: &[ChunkPos],
+) -> usize {
+    if chunks.is_empty() {
+        return 0;
+    }
+    let cancelled: HashSet<ChunkPos> = chunks.iter().copied().collect();
+
+    // Chunks whose last sections were already queued count as cancelled too
+    let mut stopped = HashSet::new();
+    queue.jobs.retain(|job| {
+        let keep = !cancelled.contains(&job.chunk);
+        if !keep {
+            stopped.insert(job.chunk);
+        }
+        keep
+    });
+
+    let packets = &mut conn.queues[SendPriority::ChunkData as usize];
+    let queued = packets.len();
+    packets.retain(|packet| match queued_packet_chunk(&packet.payload) {
+        Some(chunk) if cancelled.contains(&chunk) => {
+            stopped.insert(chunk);
+            false
+        }
+        _ => true,
+    });
+    queue.stats.packets_dropped += (queued - packets.len()) as u64;
+    queue.stats.chunks_cancelled += stopped.len() as u64;
+    stopped.len()
+}
+
+/// World Y (voxels) of the middle of a section
+fn section_center_y(config: &ChunkStreamConfig, job: &ChunkSendJob, section: u32) -> f32 {
+    let range = section_block_range(config, job.chunk_size, section);
+    let layer = (job.chunk_size * job.chunk_size) as f32;
+    let middle = (range.start + range.end) as f32 * 0.5 / layer;
+    (job.chunk.y * job.chunk_size as i32) as f32 + middle
+}
+
+/// Job holding the next section to send, with that section moved to the
+/// end of its `remaining_sections`. A section's score is the chunk's
+/// priority plus how far (in chunks) it is above or below the player.
+fn select_next_section(queue: &mut ChunkSendQueue, player_y: f32) -> Option<usize> {
+    let mut best_score = f32::INFINITY;
+    let mut best_job = None;
+    let mut best_slot = 0;
+    for (job_index, job) in queue.jobs.iter().enumerate() {
+        let size = job.chunk_size.max(1) as f32;
+        for (slot, &section) in job.remaining_sections.iter().enumerate() {
+            let vertical = (section_center_y(&queue.config, job, section) - player_y).abs() / size;
+            let score = job.priority + vertical;
+            if best_job.is_none() || score < best_score {
+                best_score = score;
+                best_job = Some(job_index);
+                best_slot = slot;
+            }
+        }
+    }
+
+    let job_index = best_job?;
+    let remaining = &mut queue.jobs[job_index].remaining_sections;
+    let last = remaining.len() - 1;
+    remaining.swap(best_slot, last);
+    Some(job_index)
+}
+
+/// Queue the most urgent sections on the connection, within the per-tick
+/// and queued-bytes limits. Returns how many sections were queued.
+/// Sections may be larger than the connection's send burst; `flush_connection`
+/// lets such a packet out whenever its buckets are full.
+pub fn pump_chunk_sends(queue: &mut ChunkSendQueue, conn: &mut Connection, player_y: f32) -> usize {
+    let _span = crate::trace_span!(Network, "pump_chunk_sends");
+    let mut sent = 0;
+    while sent < queue.config.max_sections_per_tick
+        && queued_bytes(conn, SendPriority::ChunkData) < queue.config.max_queued_bytes
+    {
+        let Some(job_index) = select_next_section(queue, player_y) else {
+            break;
+        };
+        let job = &mut queue.jobs[job_index];
+        let Some(section) = job.remaining_sections.pop() else {
+            break;
+        };
+        let range = section_block_range(&queue.config, job.chunk_size, section);
+        let packet = ChunkSectionPacket {
+            chunk: job.chunk,
+            chunk_size: job.chunk_size,
+            section,
+            section_count: chunk_section_count(&queue.config, job.chunk_size),
+            version: job.version,
+            blocks: job.blocks[range].to_vec(),
+        };
+        match encode_chunk_section(&packet) {
+            Ok(bytes) => queue_packet(
+                conn,
+                SendPriority::ChunkData,
+                CHUNK_SECTION_PACKET_TYPE,
+                bytes,
+            ),
+            Err(e) => log::warn!("[ChunkStream] Dropping section of {:?}: {}", job.chunk, e),
+        }
+        sent += 1;
+        queue.stats.sections_sent += 1;
+
+        if job.remaining_sections.is_empty() {
+            queue.jobs.swap_remove(job_index);
+            queue.stats.chunks_completed += 1;
+        }
+    }
+    sent
+}
+
+// ============================================================================
+// ENCODING
+// ============================================================================
+
+fn encode_with_magic<T: serde::Serialize>(magic: [u8; 4], value: &T) -> NetworkResult<Vec<u8>> {
+    let body = bincode::serialize(value).map_err(|e| format!("Failed to encode: {}", e))?;
+    let mut bytes = Vec::with_capacity(magic.len() + body.len());
+    bytes.extend_from_slice(&magic);
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+}
+
+fn decode_with_magic<T: serde::de::DeserializeOwned>(
+    magic: [u8; 4],
+    bytes: &[u8],
+) -> NetworkResult<T> {
+    let body = bytes
+        .strip_prefix(&magic[..])
+        .ok_or_else(|| "Missing message magic".to_string())?;
+    bincode::deserialize(body).map_err(|e| format!("Failed to decode: {}", e))
+}
+
+/// Encode a client request
+pub fn encode_chunk_request(message: &ChunkRequestMessage) -> NetworkResult<Vec<u8>> {
+    encode_with_magic(CHUNK_REQUEST_MAGIC, message)
+}
+
+/// Decode a client request
+pub fn decode_chunk_request(bytes: &[u8]) -> NetworkResult<ChunkRequestMessage> {
+    decode_with_magic(CHUNK_REQUEST_MAGIC, bytes)
+}
+
+/// Encode a chunk section
+pub fn encode_chunk_section(packet: &ChunkSectionPacket) -> NetworkResult<Vec<u8>> {
+    encode_with_magic(CHUNK_SECTION_MAGIC, packet)
+}
+
+/// Decode a chunk section
+pub fn decode_chunk_section(bytes: &[u8]) -> NetworkResult<ChunkSectionPacket> {
+    decode_with_magic(CHUNK_SECTION_MAGIC, bytes)
+}
+
+/// Encode a chunk diff
+pub fn encode_chunk_diff(packet: &ChunkDiffPacket) -> NetworkResult<Vec<u8>> {
+    encode_with_magic(CHUNK_DIFF_MAGIC, packet)
+}
+
+/// Decode a chunk diff
+pub fn decode_chunk_diff(bytes: &[u8]) -> NetworkResult<ChunkDiffPacket> {
+    decode_with_magic(CHUNK_DIFF_MAGIC, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::chunk_diff_operations::{
+        apply_chunk_edits, create_chunk_edit_log, default_chunk_edit_log_config, record_chunk_edit,
+    };
+    use crate::network::connection::{create_connection, flush_connection, BandwidthConfig};
+
+    const SIZE: u32 = 32;
+
+    fn column_blocks() -> Vec<BlockId> {
+        // Stone below y = 20 in the chunk, air above
+        let layer = (SIZE * SIZE) as usize;
+        (0..(SIZE * SIZE * SIZE) as usize)
+            .map(|i| {
+                if i / layer < 20 {
+                    BlockId::STONE
+                } else {
+                    BlockId::AIR
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_requests_follow_view_direction() {
+        let config = default_chunk_stream_config();
+        let player = [48.0, 16.0, 16.0];
+        let view = [1.0, 0.0, 0.0];
+        let ahead = chunk_request_priority(&config, ChunkPos::new(2, 0, 0), player, view, SIZE);
+        let behind = chunk_request_priority(&config, ChunkPos::new(0, 0, 0), player, view, SIZE);
+        assert!(ahead < behind);
+        assert_eq!(
+            chunk_request_priority(&config, ChunkPos::new(1, 0, 0), player, view, SIZE),
+            0.0
+        );
+
+        let mut state = create_chunk_request_state(config);
+        let loaded = HashSet::from([ChunkPos::new(1, 0, 0)]);
+        let first = update_chunk_requests(&mut state, player, view, SIZE, 1, &loaded)
+            .expect("initial requests");
+        assert_eq!(first.requests.len(), 26);
+        assert!(first.cancels.is_empty());
+        assert_eq!(first.requests[0].chunk, ChunkPos::new(2, 0, 0));
+        assert!(update_chunk_requests(&mut state, player, view, SIZE, 1, &loaded).is_none());
+
+        // Teleporting far away cancels everything requested before
+        let moved = update_chunk_requests(&mut state, [4000.0, 16.0, 16.0], view, SIZE, 1, &loaded)
+            .expect("new requests");
+        assert_eq!(moved.cancels.len(), 26);
+        let bytes = encode_chunk_request(&moved).expect("encodes");
+        assert_eq!(decode_chunk_request(&bytes).expect("decodes"), moved);
+    }
+
+    #[test]
+    fn test_sections_near_player_first_and_cancel() {
+        let config = ChunkStreamConfig {
+            max_sections_per_tick: 2,
+            ..default_chunk_stream_config()
+        };
+        let mut client = create_chunk_request_state(config);
+        let mut server = create_chunk_send_queue(config);
+        let mut conn = create_connection(1, BandwidthConfig::default());
+        let edit_log = create_chunk_edit_log(default_chunk_edit_log_config(), 1);
+        let player = [16.0, 52.0, 16.0];
+        let message = update_chunk_requests(
+            &mut client,
+            player,
+            [0.0, 0.0, 1.0],
+            SIZE,
+            0,
+            &HashSet::new(),
+        )
+        .expect("requests");
+        let chunk = ChunkPos::new(0, 1, 0);
+        assert_eq!(message.requests[0].chunk, chunk);
+
+        assert_eq!(
+            apply_chunk_request(&mut server, &mut conn, &message, SIZE, &edit_log, |_| {
+                Some(column_blocks())
+            }),
+            1
+        );
+        assert_eq!(pump_chunk_sends(&mut server, &mut conn, player[1]), 2);
+        // Player at y = 52 stands in section 2 (48..56) of the chunk at y = 32
+        let sections: Vec<u32> = conn.queues[SendPriority::ChunkData as usize]
+            .iter()
+            .map(|packet| {
+                decode_chunk_section(&packet.payload)
+                    .expect("section")
+                    .section
+            })
+            .collect();
+        assert_eq!(sections[0], 2);
+
+        for packet in conn.queues[SendPriority::ChunkData as usize].drain(..) {
+            let section = decode_chunk_section(&packet.payload).expect("section");
+            assert!(matches!(
+                receive_chunk_section(&mut client, section, None),
+                SectionReceipt::Partial { total: 4, .. }
+            ));
+        }
+        while pump_chunk_sends(&mut server, &mut conn, player[1]) > 0 {}
+        let mut receipts = Vec::new();
+        for packet in conn.queues[SendPriority::ChunkData as usize].drain(..) {
+            let section = decode_chunk_section(&packet.payload).expect("section");
+            receipts.push(receive_chunk_section(&mut client, section, None));
+        }
+        assert_eq!(
+            receipts.last(),
+            Some(&SectionReceipt::Complete(column_blocks()))
+        );
+        assert_eq!(server.stats.chunks_completed, 1);
+
+        // A cancel removes both the job and the sections already queued
+        let other = ChunkRequestMessage {
+            requests: vec![ChunkRequest {
+                chunk: ChunkPos::new(5, 0, 0),
+                priority: 5.0,
+                known_version: None,
+            }],
+            cancels: Vec::new(),
+        };
+        apply_chunk_request(&mut server, &mut conn, &other, SIZE, &edit_log, |_| {
+            Some(column_blocks())
+        });
+        pump_chunk_sends(&mut server, &mut conn, player[1]);
+        let cancel = ChunkRequestMessage {
+            requests: Vec::new(),
+            cancels: vec![ChunkPos::new(5, 0, 0)],
+        };
+        apply_chunk_request(&mut server, &mut conn, &cancel, SIZE, &edit_log, |_| None);
+        assert!(server.jobs.is_empty());
+        assert_eq!(queued_bytes(&conn, SendPriority::ChunkData), 0);
+        assert_eq!(server.stats.packets_dropped, 2);
+        assert_eq!(server.stats.chunks_cancelled, 1);
+    }
+
+    #[test]
+    fn test_cached_chunk_receives_diff_or_full_fallback() {
+        let config = default_chunk_stream_config();
+        let mut client = create_chunk_request_state(config);
+        let mut server = create_chunk_send_queue(config);
+        let mut conn = create_connection(1, BandwidthConfig::default());
+        let mut edit_log = create_chunk_edit_log(default_chunk_edit_log_config(), 1);
+        let chunk = ChunkPos::new(0, 0, 0);
+        let player = [16.0, 16.0, 16.0];
+        let mut world = column_blocks();
+
+        // First visit: full chunk at version 0
+        let message = update_chunk_requests(
+            &mut client,
+            player,
+            [1.0, 0.0, 0.0],
+            SIZE,
+            0,
+            &HashSet::new(),
+        )
+        .expect("requests");
+        assert_eq!(message.requests[0].known_version, None);
+        apply_chunk_request(&mut server, &mut conn, &message, SIZE, &edit_log, |_| {
+            Some(world.clone())
+        });
+        while pump_chunk_sends(&mut server, &mut conn, player[1]) > 0 {}
+        let mut cached = Vec::new();
+        for packet in conn.queues[SendPriority::ChunkData as usize].drain(..) {
+            let section = decode_chunk_section(&packet.payload).expect("section");
+            if let SectionReceipt::Complete(blocks) =
+                receive_chunk_section(&mut client, section, None)
+            {
+                cached = blocks;
+            }
+        }
+        assert_eq!(cached, world);
+
+        // Edits while the client is away, then a late rejoin with the cache
+        for (index, block) in [
+            (5, BlockId::GLASS),
+            (30_000, BlockId::WOOD),
+            (5, BlockId::SAND),
+        ] {
+            world[index as usize] = block;
+            record_chunk_edit(&mut edit_log, chunk, index, block);
+        }
+        let known = client.known_versions[&chunk];
+        let mut rejoined = create_chunk_request_state(config);
+        remember_chunk_version(&mut rejoined, chunk, known);
+        let message = update_chunk_requests(
+            &mut rejoined,
+            player,
+            [1.0, 0.0, 0.0],
+            SIZE,
+            0,
+            &HashSet::new(),
+        )
+        .expect("requests");
+        assert_eq!(message.requests[0].known_version, Some(known));
+        assert_eq!(
+            apply_chunk_request(&mut server, &mut conn, &message, SIZE, &edit_log, |_| None),
+            1
+        );
+        assert!(server.jobs.is_empty());
+        assert_eq!(server.stats.diffs_sent, 1);
+        assert!(server.stats.bytes_saved > 60_000);
+
+        let packet = conn.queues[SendPriority::ChunkData as usize]
+            .pop_front()
+            .expect("diff queued");
+        let diff = decode_chunk_diff(&packet.payload).expect("diff");
+        match receive_chunk_diff(&mut rejoined, diff, None) {
+            DiffReceipt::Apply { edits, version } => {
+                assert_eq!(edits.len(), 2);
+                assert_eq!(version.version, 3);
+                apply_chunk_edits(&mut cached, &edits);
+            }
+            DiffReceipt::Ignored => panic!("diff ignored"),
+        }
+        assert_eq!(cached, world);
+        assert!(rejoined.requested.is_empty());
+
+        // After a restart without saved logs the epoch changes: full send
+        let restarted = create_chunk_edit_log(default_chunk_edit_log_config(), 2);
+        let mut again = create_chunk_request_state(config);
+        remember_chunk_version(&mut again, chunk, rejoined.known_versions[&chunk]);
+        let message = update_chunk_requests(
+            &mut again,
+            player,
+            [1.0, 0.0, 0.0],
+            SIZE,
+            0,
+            &HashSet::new(),
+        )
+        .expect("requests");
+        apply_chunk_request(&mut server, &mut conn, &message, SIZE, &restarted, |_| {
+            Some(world.clone())
+        });
+        assert_eq!(server.stats.full_fallbacks, 1);
+        assert_eq!(server.jobs.len(), 1);
+        assert_eq!(server.jobs[0].version.epoch, 2);
+    }
+
+    #[test]
+    fn test_sections_larger_than_the_send_burst_stream_through_the_connection() {
+        for size in [32, 50] {
+            let config = default_chunk_stream_config();
+            let mut client = create_chunk_request_state(config);
+            let mut server = create_chunk_send_queue(config);
+            let mut conn = create_connection(1, BandwidthConfig::default());
+            let edit_log = create_chunk_edit_log(default_chunk_edit_log_config(), 1);
+            let blocks = vec![BlockId::STONE; (size * size * size) as usize];
+            let center = size as f32 * 0.5;
+            let player = [center, center, center];
+            let message = update_chunk_requests(
+                &mut client,
+                player,
+                [1.0, 0.0, 0.0],
+                size,
+                0,
+                &HashSet::new(),
+            )
+            .expect("requests");
+            apply_chunk_request(&mut server, &mut conn, &message, size, &edit_log, |_| {
+                Some(blocks.clone())
+            });
+
+            pump_chunk_sends(&mut server, &mut conn, player[1]);
+            let first = &conn.queues[SendPriority::ChunkData as usize][0];
+            assert!(first.payload.len() as f32 > conn.send_bucket.capacity);
+
+            let mut received = None;
+            for _ in 0..200 {
+                pump_chunk_sends(&mut server, &mut conn, player[1]);
+                for packet in flush_connection(&mut conn, 0.05) {
+                    let section = decode_chunk_section(&packet.payload).expect("section");
+                    if let SectionReceipt::Complete(chunk) =
+                        receive_chunk_section(&mut client, section, None)
+                    {
+                        received = Some(chunk);
+                    }
+                }
+                if received.is_some() {
+                    break;
+                }
+            }
+            assert_eq!(received, Some(blocks), "chunk size {}", size);
+        }
+    }
+}
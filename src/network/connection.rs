@@ -1,3 +1,396 @@
-//! Connection - Stub
+//! Connection - Per-connection send-rate management
+//!
+//! Outgoing packets are queued by priority (position > block updates > chunk
+//! data) and released each tick under a bandwidth cap. Chunk data is further
+//! limited by its own token bucket so streaming can never starve movement
+//! updates. The protocol feeds RTT samples and ack/loss counts back in, and the
+//! effective rate backs off multiplicatively on congestion and recovers
+//! additively once the link is healthy again.
 
-pub struct Connection;
+use crate::constants::network_constants::*;
+use std::collections::VecDeque;
+
+/// Send priority, highest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SendPriority {
+    Position = 0,
+    BlockUpdate = 1,
+    ChunkData = 2,
+}
+
+/// All priorities in send order
+pub const SEND_PRIORITIES: [SendPriority; 3] = [
+    SendPriority::Position,
+    SendPriority::BlockUpdate,
+    SendPriority::ChunkData,
+];
+
+/// Packet waiting to be sent
+#[derive(Debug, Clone)]
+pub struct OutgoingPacket {
+    pub priority: SendPriority,
+    /// Message type, as recorded by packet captures (see
+    /// `network_constants::FIRST_GAME_PACKET_TYPE`)
+    pub packet_type: u16,
+    pub payload: Vec<u8>,
+}
+
+/// Token bucket rate limiter (bytes)
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    pub capacity: f32,
+    pub tokens: f32,
+    pub refill_per_sec: f32,
+}
+
+/// Bandwidth configuration for one connection
+#[derive(Debug, Clone)]
+pub struct BandwidthConfig {
+    /// Hard cap for all traffic
+    pub max_bytes_per_sec: u32,
+    /// Sustained rate for bulk (chunk) streams
+    pub bulk_bytes_per_sec: u32,
+    /// Burst size for bulk streams
+    pub bulk_burst_bytes: u32,
+    pub congestion_rtt_ratio: f32,
+    pub congestion_loss_threshold: f32,
+    pub backoff_factor: f32,
+    pub recovery_per_sec: f32,
+    pub min_rate_factor: f32,
+}
+
+/// Congestion signals and the resulting rate factor
+#[derive(Debug, Clone)]
+pub struct CongestionState {
+    /// Smoothed RTT in milliseconds (0 until the first sample)
+    pub smoothed_rtt_ms: f32,
+    /// Lowest RTT observed, used as the uncongested baseline
+    pub min_rtt_ms: f32,
+    /// Smoothed fraction of packets reported lost
+    pub loss_rate: f32,
+    /// Multiplier applied to the configured rates (min_rate_factor..=1.0)
+    pub rate_factor: f32,
+    /// Milliseconds since the last backoff
+    pub since_backoff_ms: f32,
+    pub backoff_count: u64,
+}
+
+/// Send statistics
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConnectionStats {
+    pub bytes_sent: u64,
+    pub packets_sent: u64,
+    /// Bytes sent per priority, indexed by `SendPriority as usize`
+    pub bytes_by_priority: [u64; 3],
+    /// Ticks where data remained queued because of the cap
+    pub throttled_ticks: u64,
+}
+
+/// One network connection's send state
+#[derive(Debug, Clone)]
+pub struct Connection {
+    pub id: u32,
+    pub config: BandwidthConfig,
+    /// Queues indexed by `SendPriority as usize`
+    pub queues: [VecDeque<OutgoingPacket>; 3],
+    pub send_bucket: TokenBucket,
+    pub bulk_bucket: TokenBucket,
+    pub congestion: CongestionState,
+    pub stats: ConnectionStats,
+}
+
+impl Default for BandwidthConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes_per_sec: DEFAULT_CONNECTION_BYTES_PER_SEC,
+            bulk_bytes_per_sec: DEFAULT_BULK_BYTES_PER_SEC,
+            bulk_burst_bytes: DEFAULT_BULK_BURST_BYTES,
+            congestion_rtt_ratio: CONGESTION_RTT_RATIO,
+            congestion_loss_threshold: CONGESTION_LOSS_THRESHOLD,
+            backoff_factor: CONGESTION_BACKOFF_FACTOR,
+            recovery_per_sec: CONGESTION_RECOVERY_PER_SEC,
+            min_rate_factor: CONGESTION_MIN_RATE_FACTOR,
+        }
+    }
+}
+
+// ============================================================================
+// TOKEN BUCKET
+// ============================================================================
+
+pub fn create_token_bucket(capacity: f32, refill_per_sec: f32) -> TokenBucket {
+    TokenBucket {
+        capacity,
+        tokens: capacity,
+        refill_per_sec,
+    }
+}
+
+pub fn refill_token_bucket(bucket: &mut TokenBucket, delta_time: f32) {
+    bucket.tokens = (bucket.tokens + bucket.refill_per_sec * delta_time).min(bucket.capacity);
+}
+
+/// Take `amount` tokens if available
+pub fn try_consume_tokens(bucket: &mut TokenBucket, amount: f32) -> bool {
+    if bucket.tokens >= amount {
+        bucket.tokens -= amount;
+        true
+    } else {
+        false
+    }
+}
+
+// ============================================================================
+// CONNECTION
+// ============================================================================
+
+/// Create a connection with full buckets
+pub fn create_connection(id: u32, config: BandwidthConfig) -> Connection {
+    // One tick's worth of burst at 20Hz keeps the send cap smooth
+    let send_capacity = (config.max_bytes_per_sec as f32 / 20.0).max(1.0);
+    let send_bucket = create_token_bucket(send_capacity, config.max_bytes_per_sec as f32);
+    let bulk_bucket = create_token_bucket(
+        config.bulk_burst_bytes as f32,
+        config.bulk_bytes_per_sec as f32,
+    );
+
+    Connection {
+        id,
+        config,
+        queues: [VecDeque::new(), VecDeque::new(), VecDeque::new()],
+        send_bucket,
+        bulk_bucket,
+        congestion: CongestionState {
+            smoothed_rtt_ms: 0.0,
+            min_rtt_ms: f32::MAX,
+            loss_rate: 0.0,
+            rate_factor: 1.0,
+            since_backoff_ms: CONGESTION_BACKOFF_INTERVAL_MS,
+            backoff_count: 0,
+        },
+        stats: ConnectionStats::default(),
+    }
+}
+
+/// Change the bandwidth caps of a live connection
+pub fn set_bandwidth_limits(
+    conn: &mut Connection,
+    max_bytes_per_sec: u32,
+    bulk_bytes_per_sec: u32,
+) {
+    conn.config.max_bytes_per_sec = max_bytes_per_sec;
+    conn.config.bulk_bytes_per_sec = bulk_bytes_per_sec.min(max_bytes_per_sec);
+    conn.send_bucket.capacity = (max_bytes_per_sec as f32 / 20.0).max(1.0);
+    conn.send_bucket.tokens = conn.send_bucket.tokens.min(conn.send_bucket.capacity);
+}
+
+/// Queue a packet of `packet_type` for sending
+pub fn queue_packet(
+    conn: &mut Connection,
+    priority: SendPriority,
+    packet_type: u16,
+    payload: Vec<u8>,
+) {
+    conn.queues[priority as usize].push_back(OutgoingPacket {
+        priority,
+        packet_type,
+        payload,
+    });
+}
+
+/// Bytes waiting in a priority queue
+pub fn queued_bytes(conn: &Connection, priority: SendPriority) -> usize {
+    conn.queues[priority as usize]
+        .iter()
+        .map(|packet| packet.payload.len())
+        .sum()
+}
+
+/// Current send rate after congestion control (bytes/sec)
+pub fn effective_send_rate(conn: &Connection) -> f32 {
+    conn.config.max_bytes_per_sec as f32 * conn.congestion.rate_factor
+}
+
+/// Release the packets that fit in this tick's budget, highest priority first.
+///
+/// Position packets are only limited by the overall cap. Chunk data must also
+/// fit the bulk bucket, so a large chunk backlog never blocks the queues above it.
+/// A packet larger than a bucket's capacity goes out on a tick that starts with
+/// that bucket full, leaving it in debt until the refills pay it back.
+pub fn flush_connection(conn: &mut Connection, delta_time: f32) -> Vec<OutgoingPacket> {
+    let factor = conn.congestion.rate_factor;
+    conn.send_bucket.refill_per_sec = conn.config.max_bytes_per_sec as f32 * factor;
+    conn.bulk_bucket.refill_per_sec = conn.config.bulk_bytes_per_sec as f32 * factor;
+    refill_token_bucket(&mut conn.send_bucket, delta_time);
+    refill_token_bucket(&mut conn.bulk_bucket, delta_time);
+    update_congestion_recovery(conn, delta_time);
+
+    // Smaller packets sent earlier in the tick don't hold back an oversized one
+    let mut send_full = conn.send_bucket.tokens >= conn.send_bucket.capacity;
+    let mut bulk_full = conn.bulk_bucket.tokens >= conn.bulk_bucket.capacity;
+
+    let mut sent = Vec::new();
+    for priority in SEND_PRIORITIES {
+        let queue_index = priority as usize;
+        while let Some(packet) = conn.queues[queue_index].front() {
+            let size = packet.payload.len() as f32;
+
+            if !admits_packet(&conn.send_bucket, size, send_full) {
+                break;
+            }
+            if priority == SendPriority::ChunkData {
+                if !admits_packet(&conn.bulk_bucket, size, bulk_full) {
+                    break;
+                }
+                conn.bulk_bucket.tokens -= size;
+                bulk_full &= size <= conn.bulk_bucket.capacity;
+            }
+            conn.send_bucket.tokens -= size;
+            send_full &= size <= conn.send_bucket.capacity;
+
+            if let Some(packet) = conn.queues[queue_index].pop_front() {
+                conn.stats.bytes_sent += packet.payload.len() as u64;
+                conn.stats.bytes_by_priority[queue_index] += packet.payload.len() as u64;
+                conn.stats.packets_sent += 1;
+                sent.push(packet);
+            }
+        }
+    }
+
+    if conn.queues.iter().any(|queue| !queue.is_empty()) {
+        conn.stats.throttled_ticks += 1;
+    }
+    sent
+}
+
+/// A packet fits the tokens left, or is larger than the whole bucket and the
+/// bucket started the tick full
+fn admits_packet(bucket: &TokenBucket, size: f32, full: bool) -> bool {
+    bucket.tokens >= size || (full && size > bucket.capacity)
+}
+
+// ============================================================================
+// CONGESTION CONTROL
+// ============================================================================
+
+/// Feed a round-trip time sample from the protocol
+pub fn record_rtt_sample(conn: &mut Connection, rtt_ms: f32) {
+    let congestion = &mut conn.congestion;
+    congestion.min_rtt_ms = congestion.min_rtt_ms.min(rtt_ms);
+    congestion.smoothed_rtt_ms = if congestion.smoothed_rtt_ms == 0.0 {
+        rtt_ms
+    } else {
+        congestion.smoothed_rtt_ms * 0.875 + rtt_ms * 0.125
+    };
+
+    if congestion.smoothed_rtt_ms > congestion.min_rtt_ms * conn.config.congestion_rtt_ratio {
+        apply_congestion_backoff(conn);
+    }
+}
+
+/// Feed ack results from the protocol (packets acked and lost since the last report)
+pub fn record_ack_results(conn: &mut Connection, acked: u32, lost: u32) {
+    let total = acked + lost;
+    if total == 0 {
+        return;
+    }
+
+    let sample = lost as f32 / total as f32;
+    conn.congestion.loss_rate = conn.congestion.loss_rate * 0.75 + sample * 0.25;
+
+    if conn.congestion.loss_rate > conn.config.congestion_loss_threshold {
+        apply_congestion_backoff(conn);
+    }
+}
+
+/// Multiplicative decrease, at most once per backoff interval
+fn apply_congestion_backoff(conn: &mut Connection) {
+    let congestion = &mut conn.congestion;
+    if congestion.since_backoff_ms < CONGESTION_BACKOFF_INTERVAL_MS {
+        return;
+    }
+
+    congestion.rate_factor =
+        (congestion.rate_factor * conn.config.backoff_factor).max(conn.config.min_rate_factor);
+    congestion.since_backoff_ms = 0.0;
+    congestion.backoff_count += 1;
+
+    log::debug!(
+        "[Connection {}] Congestion backoff: rate {:.0} B/s (rtt {:.1}ms, loss {:.1}%)",
+        conn.id,
+        conn.config.max_bytes_per_sec as f32 * congestion.rate_factor,
+        congestion.smoothed_rtt_ms,
+        congestion.loss_rate * 100.0
+    );
+}
+
+/// Additive increase while no backoff happened in the last interval
+fn update_congestion_recovery(conn: &mut Connection, delta_time: f32) {
+    let congestion = &mut conn.congestion;
+    congestion.since_backoff_ms += delta_time * 1000.0;
+    if congestion.since_backoff_ms >= CONGESTION_BACKOFF_INTERVAL_MS {
+        congestion.rate_factor =
+            (congestion.rate_factor + conn.config.recovery_per_sec * delta_time).min(1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_stream_does_not_starve_positions() {
+        let mut conn = create_connection(1, BandwidthConfig::default());
+        for _ in 0..64 {
+            queue_packet(&mut conn, SendPriority::ChunkData, 2, vec![0; 4096]);
+        }
+        queue_packet(&mut conn, SendPriority::Position, 1, vec![0; 32]);
+        queue_packet(&mut conn, SendPriority::BlockUpdate, 3, vec![0; 64]);
+
+        let sent = flush_connection(&mut conn, 0.05);
+        assert_eq!(sent[0].priority, SendPriority::Position);
+        assert_eq!(sent[1].priority, SendPriority::BlockUpdate);
+        assert!(sent.len() < 66);
+        assert!(conn.stats.bytes_sent as f32 <= conn.send_bucket.capacity);
+    }
+
+    #[test]
+    fn test_congestion_backoff_and_recovery() {
+        let mut conn = create_connection(1, BandwidthConfig::default());
+        record_rtt_sample(&mut conn, 50.0);
+        record_ack_results(&mut conn, 50, 50);
+        assert!(conn.congestion.rate_factor < 1.0);
+
+        let reduced = conn.congestion.rate_factor;
+        for _ in 0..100 {
+            flush_connection(&mut conn, 0.05);
+        }
+        assert!(conn.congestion.rate_factor > reduced);
+    }
+
+    #[test]
+    fn test_packet_larger_than_the_buckets_is_sent_when_they_are_full() {
+        let mut conn = create_connection(1, BandwidthConfig::default());
+        let size = (conn.send_bucket.capacity.max(conn.bulk_bucket.capacity) * 1.5) as usize;
+        queue_packet(&mut conn, SendPriority::ChunkData, 2, vec![0; size]);
+        queue_packet(&mut conn, SendPriority::ChunkData, 2, vec![0; size]);
+        queue_packet(&mut conn, SendPriority::Position, 1, vec![0; 32]);
+
+        let sent = flush_connection(&mut conn, 0.05);
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].priority, SendPriority::Position);
+        assert_eq!(sent[1].payload.len(), size);
+        assert!(conn.send_bucket.tokens < 0.0);
+        assert!(conn.bulk_bucket.tokens < 0.0);
+
+        // The debt is paid back before the second one goes out
+        assert!(flush_connection(&mut conn, 0.05).is_empty());
+        let mut ticks = 1;
+        while conn.queues[SendPriority::ChunkData as usize].len() == 1 {
+            flush_connection(&mut conn, 0.05);
+            ticks += 1;
+            assert!(ticks < 200, "oversized packet never sent");
+        }
+        assert!(ticks > 2);
+    }
+}
@@ -0,0 +1,127 @@
+//! Custom Packet Data - Pure DOP
+//!
+//! A channel for game-defined messages that the engine protocol does not
+//! know about. The game registers namespaced channels ("mygame:chat") with
+//! a payload size limit and a delivery guarantee, then each side sends its
+//! table as `HandshakeMessage::CustomPackets` after the hellos. Ids on the
+//! wire are the sender's; the receiver resolves them through the peer's
+//! table and only accepts channels it registered itself. Received payloads
+//! reach the game as `GameEvent::CustomPacket`.
+//!
+//! Packet layout: magic "CPKT", then a bincode-encoded `CustomPacketMessage`.
+//!
+//! NO METHODS - just data.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Identifier at the start of every custom packet message
+pub const CUSTOM_PACKET_MAGIC: [u8; 4] = *b"CPKT";
+
+/// Game bytes carried by one packet
+pub type CustomPayload = Vec<u8>;
+
+/// What the channel promises about arrival and order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CustomPacketDelivery {
+    /// May be lost; packets older than the newest received are dropped
+    Unreliable,
+    /// Resent until acknowledged, delivered once in arrival order
+    Reliable,
+    /// Resent until acknowledged, delivered once in send order
+    ReliableOrdered,
+}
+
+/// One channel as registered by the game and announced to the peer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomPacketChannel {
+    /// "namespace:name"
+    pub name: String,
+    pub id: u16,
+    pub delivery: CustomPacketDelivery,
+    /// Largest payload accepted on the channel (bytes)
+    pub max_payload_bytes: u32,
+}
+
+/// Channels of one side, sent during the join handshake
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomPacketTable {
+    pub channels: Vec<CustomPacketChannel>,
+}
+
+/// Payload on a channel, numbered per channel by the sender
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomPacket {
+    pub channel: u16,
+    pub sequence: u32,
+    pub payload: Vec<u8>,
+}
+
+/// Everything sent on the custom packet channel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CustomPacketMessage {
+    Packet(CustomPacket),
+    /// Reliable packets received, by sender channel id
+    Ack {
+        channel: u16,
+        sequences: Vec<u32>,
+    },
+}
+
+/// Payload ready for the game, with the channel name resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedCustomPacket {
+    pub channel: String,
+    pub payload: Vec<u8>,
+}
+
+/// Reliable packet waiting for its ack
+#[derive(Debug, Clone)]
+pub struct UnackedCustomPacket {
+    pub packet: CustomPacket,
+    /// Milliseconds since the last (re)send
+    pub since_sent_ms: f32,
+    pub resends: u32,
+}
+
+/// Receive progress of one peer channel
+#[derive(Debug, Clone, Default)]
+pub struct CustomChannelReceive {
+    /// Unreliable: newest sequence seen; ordered: next one to deliver
+    pub next_sequence: u32,
+    /// Reliable: sequences already delivered, pruned below `next_sequence`
+    pub delivered: Vec<u32>,
+    /// Ordered: packets that arrived ahead of a gap
+    pub held: BTreeMap<u32, CustomPayload>,
+    /// Sequences to acknowledge in the next ack
+    pub pending_acks: Vec<u32>,
+}
+
+/// Custom packet counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CustomPacketStats {
+    pub sent: u64,
+    pub resent: u64,
+    pub delivered: u64,
+    /// Duplicates and stale unreliable packets
+    pub dropped_stale: u64,
+    /// Channels the peer did not announce or this side did not register
+    pub dropped_unknown: u64,
+    pub dropped_oversized: u64,
+}
+
+/// Custom packet state of one connection
+#[derive(Debug, Clone, Default)]
+pub struct CustomPacketChannels {
+    /// Channels registered by the local game, indexed by id
+    pub local: Vec<CustomPacketChannel>,
+    /// Peer channels by the peer's id, from its handshake table
+    pub remote: HashMap<u16, CustomPacketChannel>,
+    /// Next sequence per local channel id
+    pub send_sequences: Vec<u32>,
+    pub unacked: Vec<UnackedCustomPacket>,
+    /// Receive progress by peer channel id
+    pub receive: HashMap<u16, CustomChannelReceive>,
+    pub resend_after_ms: f32,
+    pub stats: CustomPacketStats,
+}
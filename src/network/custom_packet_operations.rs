@@ -0,0 +1,507 @@
+//! Custom Packet Operations - Pure DOP Functions
+//!
+//! Before connecting: `register_custom_packet_channel` for every channel
+//! the game uses. After the hellos each side sends
+//! `custom_packet_handshake(&channels)` and passes the peer's message to
+//! `accept_custom_packet_table`.
+//!
+//! Then `send_custom_packet` to queue a payload, every received custom
+//! packet message through `receive_custom_packets` (its result through
+//! `custom_packet_events` into the gateway) and `tick_custom_packets` once
+//! per tick to send acks and resend what the peer has not acknowledged.
+
+use super::connection::{queue_packet, Connection, SendPriority};
+use super::custom_packet_data::{
+    CustomChannelReceive, CustomPacket, CustomPacketChannel, CustomPacketChannels,
+    CustomPacketDelivery, CustomPacketMessage, CustomPacketTable, CustomPayload,
+    ReceivedCustomPacket, UnackedCustomPacket, CUSTOM_PACKET_MAGIC,
+};
+use super::error::NetworkResult;
+use super::lan_discovery_data::HandshakeMessage;
+use crate::constants::network_constants::{
+    CUSTOM_PACKET_RESEND_MS, CUSTOM_PACKET_TYPE, MAX_CUSTOM_PACKET_BYTES,
+    MAX_CUSTOM_PACKET_CHANNELS, MAX_HELD_CUSTOM_PACKETS,
+};
+use crate::game::GameEvent;
+
+/// No channels yet, default resend interval
+pub fn create_custom_packet_channels() -> CustomPacketChannels {
+    CustomPacketChannels {
+        resend_after_ms: CUSTOM_PACKET_RESEND_MS,
+        ..Default::default()
+    }
+}
+
+fn valid_name_part(part: &str) -> bool {
+    !part.is_empty()
+        && part.chars().all(|c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-' | '.' | '/')
+        })
+}
+
+/// Whether a channel name is "namespace:name" in lowercase ASCII
+pub fn is_valid_custom_channel_name(name: &str) -> bool {
+    name.split_once(':')
+        .is_some_and(|(namespace, path)| valid_name_part(namespace) && valid_name_part(path))
+}
+
+/// Register a channel; returns its local id. Must happen before the
+/// handshake table is sent.
+pub fn register_custom_packet_channel(
+    channels: &mut CustomPacketChannels,
+    name: &str,
+    delivery: CustomPacketDelivery,
+    max_payload_bytes: u32,
+) -> NetworkResult<u16> {
+    if !is_valid_custom_channel_name(name) {
+        return Err(format!(
+            "Custom channel name '{}' must look like 'namespace:name'",
+            name
+        ));
+    }
+    if channels.local.iter().any(|channel| channel.name == name) {
+        return Err(format!("Custom channel '{}' is already registered", name));
+    }
+    if channels.local.len() >= MAX_CUSTOM_PACKET_CHANNELS {
+        return Err(format!(
+            "Too many custom channels (limit {})",
+            MAX_CUSTOM_PACKET_CHANNELS
+        ));
+    }
+    if max_payload_bytes == 0 || max_payload_bytes > MAX_CUSTOM_PACKET_BYTES {
+        return Err(format!(
+            "Custom channel payload limit must be 1..={} bytes",
+            MAX_CUSTOM_PACKET_BYTES
+        ));
+    }
+
+    let id = channels.local.len() as u16;
+    channels.local.push(CustomPacketChannel {
+        name: name.to_string(),
+        id,
+        delivery,
+        max_payload_bytes,
+    });
+    channels.send_sequences.push(0);
+    Ok(id)
+}
+
+/// Local channel by name
+pub fn custom_packet_channel<'a>(
+    channels: &'a CustomPacketChannels,
+    name: &str,
+) -> Option<&'a CustomPacketChannel> {
+    channels.local.iter().find(|channel| channel.name == name)
+}
+
+/// Handshake message announcing the local channels
+pub fn custom_packet_handshake(channels: &CustomPacketChannels) -> HandshakeMessage {
+    HandshakeMessage::CustomPackets(CustomPacketTable {
+        channels: channels.local.clone(),
+    })
+}
+
+/// Store the peer's channel table from its handshake message
+pub fn accept_custom_packet_table(
+    channels: &mut CustomPacketChannels,
+    message: &HandshakeMessage,
+) -> NetworkResult<()> {
+    let HandshakeMessage::CustomPackets(table) = message else {
+        return Err("Expected custom packet table".to_string());
+    };
+
+    channels.remote = table
+        .channels
+        .iter()
+        .map(|channel| (channel.id, channel.clone()))
+        .collect();
+    channels.receive.clear();
+
+    let unknown: Vec<&str> = table
+        .channels
+        .iter()
+        .filter(|channel| custom_packet_channel(channels, &channel.name).is_none())
+        .map(|channel| channel.name.as_str())
+        .collect();
+    if !unknown.is_empty() {
+        log::warn!(
+            "[Network] Ignoring {} custom channels not registered locally: {:?}",
+            unknown.len(),
+            unknown
+        );
+    }
+    Ok(())
+}
+
+fn encode_with_magic<T: serde::Serialize>(magic: [u8; 4], value: &T) -> NetworkResult<Vec<u8>> {
+    let body = bincode::serialize(value).map_err(|e| format!("Failed to encode: {}", e))?;
+    let mut bytes = Vec::with_capacity(magic.len() + body.len());
+    bytes.extend_from_slice(&magic);
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+}
+
+/// Encode a custom packet message
+pub fn encode_custom_packet_message(message: &CustomPacketMessage) -> NetworkResult<Vec<u8>> {
+    encode_with_magic(CUSTOM_PACKET_MAGIC, message)
+}
+
+/// Decode a custom packet message
+pub fn decode_custom_packet_message(bytes: &[u8]) -> NetworkResult<CustomPacketMessage> {
+    let body = bytes
+        .strip_prefix(&CUSTOM_PACKET_MAGIC[..])
+        .ok_or_else(|| "Missing message magic".to_string())?;
+    bincode::deserialize(body).map_err(|e| format!("Failed to decode: {}", e))
+}
+
+fn queue_custom_packet(conn: &mut Connection, packet: &CustomPacket) -> NetworkResult<()> {
+    let bytes = encode_custom_packet_message(&CustomPacketMessage::Packet(packet.clone()))?;
+    queue_packet(conn, SendPriority::BlockUpdate, CUSTOM_PACKET_TYPE, bytes);
+    Ok(())
+}
+
+/// Queue a payload on a registered channel
+pub fn send_custom_packet(
+    channels: &mut CustomPacketChannels,
+    conn: &mut Connection,
+    name: &str,
+    payload: Vec<u8>,
+) -> NetworkResult<()> {
+    let Some(channel) = custom_packet_channel(channels, name) else {
+        return Err(format!("Custom channel '{}' is not registered", name));
+    };
+    if payload.len() > channel.max_payload_bytes as usize {
+        return Err(format!(
+            "Payload of {} bytes exceeds the {} byte limit of '{}'",
+            payload.len(),
+            channel.max_payload_bytes,
+            name
+        ));
+    }
+    let (id, delivery) = (channel.id, channel.delivery);
+
+    let sequence = &mut channels.send_sequences[id as usize];
+    let packet = CustomPacket {
+        channel: id,
+        sequence: *sequence,
+        payload,
+    };
+    *sequence = sequence.wrapping_add(1);
+
+    queue_custom_packet(conn, &packet)?;
+    channels.stats.sent += 1;
+    if delivery != CustomPacketDelivery::Unreliable {
+        channels.unacked.push(UnackedCustomPacket {
+            packet,
+            since_sent_ms: 0.0,
+            resends: 0,
+        });
+    }
+    Ok(())
+}
+
+/// Advance `next_sequence` past sequences delivered out of order
+fn advance_delivered(receive: &mut CustomChannelReceive) {
+    while let Some(index) = receive
+        .delivered
+        .iter()
+        .position(|&sequence| sequence == receive.next_sequence)
+    {
+        receive.delivered.swap_remove(index);
+        receive.next_sequence = receive.next_sequence.wrapping_add(1);
+    }
+}
+
+/// Payloads of `packet` that can be handed to the game now, in order
+fn receive_packet(
+    receive: &mut CustomChannelReceive,
+    delivery: CustomPacketDelivery,
+    packet: CustomPacket,
+) -> Option<Vec<CustomPayload>> {
+    let CustomPacket {
+        sequence, payload, ..
+    } = packet;
+    match delivery {
+        CustomPacketDelivery::Unreliable => {
+            if sequence < receive.next_sequence {
+                return None;
+            }
+            receive.next_sequence = sequence.wrapping_add(1);
+            Some(vec![payload])
+        }
+        CustomPacketDelivery::Reliable => {
+            receive.pending_acks.push(sequence);
+            if sequence < receive.next_sequence || receive.delivered.contains(&sequence) {
+                return None;
+            }
+            receive.delivered.push(sequence);
+            advance_delivered(receive);
+            Some(vec![payload])
+        }
+        CustomPacketDelivery::ReliableOrdered => {
+            if sequence < receive.next_sequence || receive.held.contains_key(&sequence) {
+                receive.pending_acks.push(sequence);
+                return None;
+            }
+            if sequence > receive.next_sequence {
+                // Unacked packets beyond the buffer are resent later
+                if receive.held.len() < MAX_HELD_CUSTOM_PACKETS {
+                    receive.held.insert(sequence, payload);
+                    receive.pending_acks.push(sequence);
+                }
+                return Some(Vec::new());
+            }
+            receive.pending_acks.push(sequence);
+            let mut ready = vec![payload];
+            receive.next_sequence = sequence.wrapping_add(1);
+            while let Some(next) = receive.held.remove(&receive.next_sequence) {
+                ready.push(next);
+                receive.next_sequence = receive.next_sequence.wrapping_add(1);
+            }
+            Some(ready)
+        }
+    }
+}
+
+/// Handle a received custom packet message; returns the payloads ready for
+/// the game. Acks clear resends, packets on unknown channels or over the
+/// local limit are dropped.
+pub fn receive_custom_packets(
+    channels: &mut CustomPacketChannels,
+    bytes: &[u8],
+) -> NetworkResult<Vec<ReceivedCustomPacket>> {
+    let packet = match decode_custom_packet_message(bytes)? {
+        CustomPacketMessage::Ack { channel, sequences } => {
+            channels.unacked.retain(|unacked| {
+                unacked.packet.channel != channel || !sequences.contains(&unacked.packet.sequence)
+            });
+            return Ok(Vec::new());
+        }
+        CustomPacketMessage::Packet(packet) => packet,
+    };
+
+    let local = channels
+        .remote
+        .get(&packet.channel)
+        .and_then(|remote| custom_packet_channel(channels, &remote.name))
+        .cloned();
+    let Some(local) = local else {
+        channels.stats.dropped_unknown += 1;
+        return Ok(Vec::new());
+    };
+    if packet.payload.len() > local.max_payload_bytes as usize {
+        channels.stats.dropped_oversized += 1;
+        return Ok(Vec::new());
+    }
+
+    let receive = channels.receive.entry(packet.channel).or_default();
+    let Some(payloads) = receive_packet(receive, local.delivery, packet) else {
+        channels.stats.dropped_stale += 1;
+        return Ok(Vec::new());
+    };
+    channels.stats.delivered += payloads.len() as u64;
+    Ok(payloads
+        .into_iter()
+        .map(|payload| ReceivedCustomPacket {
+            channel: local.name.clone(),
+            payload,
+        })
+        .collect())
+}
+
+/// Send pending acks and resend reliable packets the peer has not
+/// acknowledged within the resend interval
+pub fn tick_custom_packets(
+    channels: &mut CustomPacketChannels,
+    conn: &mut Connection,
+    delta_ms: f32,
+) -> NetworkResult<()> {
+    let mut acked: Vec<_> = channels
+        .receive
+        .iter_mut()
+        .filter(|(_, receive)| !receive.pending_acks.is_empty())
+        .map(|(&channel, receive)| (channel, std::mem::take(&mut receive.pending_acks)))
+        .collect();
+    acked.sort_by_key(|(channel, _)| *channel);
+    for (channel, sequences) in acked {
+        let ack = CustomPacketMessage::Ack { channel, sequences };
+        queue_packet(
+            conn,
+            SendPriority::BlockUpdate,
+            CUSTOM_PACKET_TYPE,
+            encode_custom_packet_message(&ack)?,
+        );
+    }
+
+    let resend_after_ms = channels.resend_after_ms;
+    for unacked in &mut channels.unacked {
+        unacked.since_sent_ms += delta_ms;
+        if unacked.since_sent_ms < resend_after_ms {
+            continue;
+        }
+        queue_custom_packet(conn, &unacked.packet)?;
+        unacked.since_sent_ms = 0.0;
+        unacked.resends += 1;
+        channels.stats.resent += 1;
+    }
+    Ok(())
+}
+
+/// Gateway events for payloads received from a connection
+pub fn custom_packet_events(
+    connection_id: u32,
+    packets: Vec<ReceivedCustomPacket>,
+) -> Vec<GameEvent> {
+    packets
+        .into_iter()
+        .map(|packet| GameEvent::CustomPacket {
+            connection_id,
+            channel: packet.channel,
+            payload: packet.payload,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::connection::{create_connection, flush_connection, BandwidthConfig};
+
+    const CHAT: &str = "mygame:chat";
+    const POSITION: &str = "mygame:marker_position";
+
+    /// Both sides with the same channels, handshake tables exchanged
+    fn connected_pair() -> (CustomPacketChannels, CustomPacketChannels) {
+        let mut client = create_custom_packet_channels();
+        let mut server = create_custom_packet_channels();
+        // Different registration order gives different ids on each side
+        register_custom_packet_channel(
+            &mut client,
+            CHAT,
+            CustomPacketDelivery::ReliableOrdered,
+            64,
+        )
+        .expect("chat");
+        register_custom_packet_channel(&mut client, POSITION, CustomPacketDelivery::Unreliable, 16)
+            .expect("position");
+        register_custom_packet_channel(&mut server, POSITION, CustomPacketDelivery::Unreliable, 16)
+            .expect("position");
+        register_custom_packet_channel(
+            &mut server,
+            CHAT,
+            CustomPacketDelivery::ReliableOrdered,
+            64,
+        )
+        .expect("chat");
+        let client_table = custom_packet_handshake(&client);
+        accept_custom_packet_table(&mut client, &custom_packet_handshake(&server)).expect("table");
+        accept_custom_packet_table(&mut server, &client_table).expect("table");
+        (client, server)
+    }
+
+    fn sent_bytes(conn: &mut Connection) -> Vec<Vec<u8>> {
+        flush_connection(conn, 1.0)
+            .into_iter()
+            .map(|packet| packet.payload)
+            .collect()
+    }
+
+    #[test]
+    fn test_channel_registration_is_validated() {
+        let mut channels = create_custom_packet_channels();
+        let reliable = CustomPacketDelivery::Reliable;
+        assert!(register_custom_packet_channel(&mut channels, "chat", reliable, 8).is_err());
+        assert!(register_custom_packet_channel(&mut channels, "My:Chat", reliable, 8).is_err());
+        assert!(register_custom_packet_channel(&mut channels, CHAT, reliable, 0).is_err());
+        assert_eq!(
+            register_custom_packet_channel(&mut channels, CHAT, reliable, 8),
+            Ok(0)
+        );
+        assert!(register_custom_packet_channel(&mut channels, CHAT, reliable, 8).is_err());
+
+        let mut conn = create_connection(1, BandwidthConfig::default());
+        assert!(send_custom_packet(&mut channels, &mut conn, CHAT, vec![0; 9]).is_err());
+        assert!(send_custom_packet(&mut channels, &mut conn, "mygame:other", vec![]).is_err());
+        assert!(send_custom_packet(&mut channels, &mut conn, CHAT, vec![0; 8]).is_ok());
+    }
+
+    #[test]
+    fn test_ordered_channel_survives_loss_and_reordering() {
+        let (mut client, mut server) = connected_pair();
+        let mut client_conn = create_connection(1, BandwidthConfig::default());
+        let mut server_conn = create_connection(1, BandwidthConfig::default());
+
+        for text in [b"a", b"b", b"c"] {
+            send_custom_packet(&mut client, &mut client_conn, CHAT, text.to_vec()).expect("send");
+        }
+        let mut wire = sent_bytes(&mut client_conn);
+        assert_eq!(wire.len(), 3);
+
+        // "a" is lost, "c" arrives before "b": nothing can be delivered yet
+        let lost = wire.remove(0);
+        wire.reverse();
+        for bytes in &wire {
+            assert!(receive_custom_packets(&mut server, bytes)
+                .expect("receive")
+                .is_empty());
+        }
+
+        // The server acks what it holds; the client resends only "a"
+        tick_custom_packets(&mut server, &mut server_conn, 16.0).expect("tick");
+        for bytes in sent_bytes(&mut server_conn) {
+            receive_custom_packets(&mut client, &bytes).expect("ack");
+        }
+        assert_eq!(client.unacked.len(), 1);
+        tick_custom_packets(&mut client, &mut client_conn, CUSTOM_PACKET_RESEND_MS).expect("tick");
+        let resent = sent_bytes(&mut client_conn);
+        assert_eq!(resent, vec![lost]);
+
+        let received = receive_custom_packets(&mut server, &resent[0]).expect("receive");
+        let events = custom_packet_events(7, received);
+        let texts: Vec<_> = events
+            .iter()
+            .map(|event| match event {
+                GameEvent::CustomPacket {
+                    connection_id: 7,
+                    channel,
+                    payload,
+                } if channel == CHAT => payload.clone(),
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect();
+        assert_eq!(texts, vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]);
+
+        // A duplicate is acked again but not delivered twice
+        assert!(receive_custom_packets(&mut server, &resent[0])
+            .expect("receive")
+            .is_empty());
+        assert_eq!(server.stats.delivered, 3);
+        assert_eq!(server.stats.dropped_stale, 1);
+    }
+
+    #[test]
+    fn test_unreliable_channel_drops_stale_packets() {
+        let (mut client, mut server) = connected_pair();
+        let mut conn = create_connection(1, BandwidthConfig::default());
+        for value in [1u8, 2] {
+            send_custom_packet(&mut client, &mut conn, POSITION, vec![value]).expect("send");
+        }
+        assert!(client.unacked.is_empty());
+        let wire = sent_bytes(&mut conn);
+
+        let newest = receive_custom_packets(&mut server, &wire[1]).expect("receive");
+        assert_eq!(newest[0].payload, vec![2]);
+        assert!(receive_custom_packets(&mut server, &wire[0])
+            .expect("receive")
+            .is_empty());
+
+        // Channels the receiver did not register never reach the game
+        let mut stranger = create_custom_packet_channels();
+        accept_custom_packet_table(&mut stranger, &custom_packet_handshake(&client))
+            .expect("table");
+        assert!(receive_custom_packets(&mut stranger, &wire[1])
+            .expect("receive")
+            .is_empty());
+        assert_eq!(stranger.stats.dropped_unknown, 1);
+    }
+}
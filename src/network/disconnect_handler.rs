@@ -13,6 +13,7 @@ use crate::persistence::{
     AtomicSaveData, SaveOperation, SavePriority,
     PersistenceError, PersistenceResult,
 };
+use crate::world::core::{world_point_to_chunk_pos, ChunkLayout};
 use crate::{ChunkPos, World};
 
 /// Player connection state
@@ -55,6 +56,9 @@ pub struct DisconnectConfig {
     pub emergency_save_enabled: bool,
     /// Grace period for reconnection before save
     pub reconnect_grace_period: Duration,
+    /// Chunk layout of the world, for finding the player's chunk; defaults
+    /// to the layout the engine installed with `set_gpu_chunk_layout`
+    pub chunk_layout: ChunkLayout,
 }
 
 impl Default for DisconnectConfig {
@@ -64,6 +68,7 @@ impl Default for DisconnectConfig {
             chunk_save_radius: 3,
             emergency_save_enabled: true,
             reconnect_grace_period: Duration::from_secs(5),
+            chunk_layout: crate::gpu::automation::gpu_chunk_layout(),
         }
     }
 }
@@ -292,9 +297,9 @@ impl DisconnectHandler {
 
     /// Get chunks around a player position that need saving
     fn get_chunks_around_player(&self, position: (f64, f64, f64)) -> HashSet<ChunkPos> {
-        let (x, _y, z) = position;
-        let chunk_x = (x as i32) >> 4; // Assuming 16x16 chunks
-        let chunk_z = (z as i32) >> 4;
+        let (x, y, z) = position;
+        let center = world_point_to_chunk_pos(self.config.chunk_layout, [x, y, z]);
+        let (chunk_x, chunk_z) = (center.x, center.z);
 
         let mut chunks = HashSet::new();
         let radius = self.config.chunk_save_radius;
@@ -4,6 +4,7 @@
 //! All transformations happen in disconnect_handler_operations.rs
 
 use crate::persistence::AtomicSaveData;
+use crate::world::core::ChunkLayout;
 use crate::ChunkPos;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
@@ -50,6 +51,9 @@ pub struct DisconnectConfig {
     pub emergency_save_enabled: bool,
     /// Grace period for reconnection before save
     pub reconnect_grace_period: Duration,
+    /// Chunk layout of the world, for finding the player's chunk; defaults
+    /// to the layout the engine installed with `set_gpu_chunk_layout`
+    pub chunk_layout: ChunkLayout,
 }
 
 impl Default for DisconnectConfig {
@@ -59,6 +63,7 @@ impl Default for DisconnectConfig {
             chunk_save_radius: 3,
             emergency_save_enabled: true,
             reconnect_grace_period: Duration::from_secs(5),
+            chunk_layout: crate::gpu::automation::gpu_chunk_layout(),
         }
     }
 }
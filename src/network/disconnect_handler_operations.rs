@@ -7,6 +7,7 @@ use super::disconnect_handler_data::{
     ConnectionState, DisconnectConfig, DisconnectHandlerData, DisconnectStats, DisconnectingPlayer,
 };
 use crate::persistence::{AtomicSaveData, PersistenceError, PersistenceResult, SaveOperation, SavePriority};
+use crate::world::core::world_point_to_chunk_pos;
 use crate::{ChunkPos, World};
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
@@ -216,9 +217,9 @@ pub fn force_disconnect(data: &DisconnectHandlerData, player_uuid: &str) -> Pers
 
 /// Get chunks around a player position that need saving
 fn get_chunks_around_player(config: &DisconnectConfig, position: (f64, f64, f64)) -> HashSet<ChunkPos> {
-    let (x, _y, z) = position;
-    let chunk_x = (x as i32) >> 4; // Assuming 16x16 chunks
-    let chunk_z = (z as i32) >> 4;
+    let (x, y, z) = position;
+    let center = world_point_to_chunk_pos(config.chunk_layout, [x, y, z]);
+    let (chunk_x, chunk_z) = (center.x, center.z);
 
     let mut chunks = HashSet::new();
     let radius = config.chunk_save_radius;
@@ -1,3 +1,294 @@
-//! Lag Compensation - Stub
+//! Lag Compensation - Server-side rewind buffer
+//!
+//! Every server tick the positions and bounds of tracked entities and the
+//! voxel edits applied that tick go into a ring buffer of the last
+//! `history_ticks` ticks. A client shoots or digs at the world it renders,
+//! which is behind the server by its latency plus interpolation delay; the
+//! server rewinds to the client's perceived tick and validates the action
+//! against that state (see `anticheat`). Perceived ticks may be fractional
+//! because clients render between ticks.
 
-pub struct LagCompensation;
+use crate::constants::network_constants::*;
+use crate::world::core::{BlockId, VoxelPos};
+use std::collections::VecDeque;
+
+/// Rewind buffer configuration
+#[derive(Debug, Clone)]
+pub struct LagCompensationConfig {
+    /// Ticks of history kept
+    pub history_ticks: u32,
+    /// Farthest back a perceived tick is accepted, relative to the latest
+    pub max_rewind_ticks: u32,
+}
+
+/// An entity's collision state at one tick
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityHistorySample {
+    pub entity_id: u32,
+    pub position: [f32; 3],
+    /// Bounds relative to `position`
+    pub aabb_min: [f32; 3],
+    pub aabb_max: [f32; 3],
+}
+
+/// A voxel edit applied during a tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoxelEditSample {
+    pub position: VoxelPos,
+    pub previous: BlockId,
+    pub current: BlockId,
+}
+
+/// Everything recorded for one tick
+#[derive(Debug, Clone, Default)]
+pub struct TickHistory {
+    pub tick: u64,
+    /// Sorted by entity id
+    pub entities: Vec<EntityHistorySample>,
+    pub voxel_edits: Vec<VoxelEditSample>,
+}
+
+/// Why a perceived tick cannot be rewound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewindError {
+    /// Nothing has been recorded yet
+    NoHistory,
+    /// Newer than the latest recorded tick
+    InFuture,
+    /// Older than `max_rewind_ticks` or the oldest kept tick
+    TooOld,
+}
+
+pub type RewindResult<T> = Result<T, RewindError>;
+
+/// Ring buffer of recent ticks
+#[derive(Debug, Clone)]
+pub struct LagCompensation {
+    pub config: LagCompensationConfig,
+    /// Oldest first
+    pub history: VecDeque<TickHistory>,
+    /// Tick being recorded; edits land here until `finish_lag_tick`
+    pub recording: TickHistory,
+}
+
+impl Default for LagCompensationConfig {
+    fn default() -> Self {
+        Self {
+            history_ticks: LAG_COMPENSATION_HISTORY_TICKS,
+            max_rewind_ticks: MAX_LAG_REWIND_TICKS,
+        }
+    }
+}
+
+pub fn create_lag_compensation(config: LagCompensationConfig) -> LagCompensation {
+    LagCompensation {
+        history: VecDeque::with_capacity(config.history_ticks as usize),
+        config,
+        recording: TickHistory::default(),
+    }
+}
+
+/// Change the history length; the oldest ticks are dropped if it shrank
+pub fn set_lag_history_ticks(lag: &mut LagCompensation, history_ticks: u32) {
+    lag.config.history_ticks = history_ticks.max(1);
+    while lag.history.len() > lag.config.history_ticks as usize {
+        lag.history.pop_front();
+    }
+}
+
+/// Record a voxel edit made during the tick being recorded
+pub fn record_lag_voxel_edit(
+    lag: &mut LagCompensation,
+    position: VoxelPos,
+    previous: BlockId,
+    current: BlockId,
+) {
+    lag.recording.voxel_edits.push(VoxelEditSample {
+        position,
+        previous,
+        current,
+    });
+}
+
+/// Close `tick` with the entity states at its end and push it into the
+/// ring buffer
+pub fn finish_lag_tick(
+    lag: &mut LagCompensation,
+    tick: u64,
+    mut entities: Vec<EntityHistorySample>,
+) {
+    entities.sort_unstable_by_key(|sample| sample.entity_id);
+    let mut finished = std::mem::take(&mut lag.recording);
+    finished.tick = tick;
+    finished.entities = entities;
+
+    // A tick recorded twice (e.g. after a rollback) replaces the newer ticks
+    while lag.history.back().is_some_and(|last| last.tick >= tick) {
+        lag.history.pop_back();
+    }
+    if lag.history.len() >= lag.config.history_ticks.max(1) as usize {
+        lag.history.pop_front();
+    }
+    lag.history.push_back(finished);
+}
+
+/// Latest recorded tick
+pub fn latest_lag_tick(lag: &LagCompensation) -> Option<u64> {
+    lag.history.back().map(|history| history.tick)
+}
+
+/// Check a perceived tick is inside the rewind window
+pub fn check_rewind_tick(lag: &LagCompensation, tick: f64) -> RewindResult<()> {
+    let (oldest, latest) = match (lag.history.front(), lag.history.back()) {
+        (Some(oldest), Some(latest)) => (oldest.tick, latest.tick),
+        _ => return Err(RewindError::NoHistory),
+    };
+    if tick > latest as f64 {
+        return Err(RewindError::InFuture);
+    }
+    let earliest = latest
+        .saturating_sub(lag.config.max_rewind_ticks as u64)
+        .max(oldest);
+    if tick < earliest as f64 {
+        return Err(RewindError::TooOld);
+    }
+    Ok(())
+}
+
+/// Recorded tick `tick`, if it is still in the buffer
+pub fn tick_history(lag: &LagCompensation, tick: u64) -> Option<&TickHistory> {
+    let oldest = lag.history.front()?.tick;
+    let index = tick.checked_sub(oldest)? as usize;
+    // Ticks are normally contiguous; fall back to a search if some were skipped
+    match lag.history.get(index) {
+        Some(history) if history.tick == tick => Some(history),
+        _ => lag.history.iter().find(|history| history.tick == tick),
+    }
+}
+
+fn entity_sample(history: &TickHistory, entity_id: u32) -> Option<&EntityHistorySample> {
+    history
+        .entities
+        .binary_search_by_key(&entity_id, |sample| sample.entity_id)
+        .ok()
+        .map(|index| &history.entities[index])
+}
+
+/// An entity as a client at perceived `tick` saw it, blending the two
+/// surrounding ticks
+pub fn rewind_entity(
+    lag: &LagCompensation,
+    entity_id: u32,
+    tick: f64,
+) -> RewindResult<Option<EntityHistorySample>> {
+    check_rewind_tick(lag, tick)?;
+    let before_tick = tick.floor() as u64;
+    let t = (tick - before_tick as f64) as f32;
+
+    let before = tick_history(lag, before_tick).and_then(|h| entity_sample(h, entity_id));
+    let after = if t > 0.0 {
+        tick_history(lag, before_tick + 1).and_then(|h| entity_sample(h, entity_id))
+    } else {
+        None
+    };
+
+    Ok(match (before, after) {
+        (Some(before), Some(after)) => {
+            let lerp = |a: [f32; 3], b: [f32; 3]| {
+                [
+                    a[0] + (b[0] - a[0]) * t,
+                    a[1] + (b[1] - a[1]) * t,
+                    a[2] + (b[2] - a[2]) * t,
+                ]
+            };
+            Some(EntityHistorySample {
+                entity_id,
+                position: lerp(before.position, after.position),
+                aabb_min: lerp(before.aabb_min, after.aabb_min),
+                aabb_max: lerp(before.aabb_max, after.aabb_max),
+            })
+        }
+        (Some(sample), None) | (None, Some(sample)) => Some(*sample),
+        (None, None) => None,
+    })
+}
+
+/// Every entity (and the voxel edits) of `tick`, inside the rewind window
+pub fn rewind_tick(lag: &LagCompensation, tick: u64) -> RewindResult<&TickHistory> {
+    check_rewind_tick(lag, tick as f64)?;
+    tick_history(lag, tick).ok_or(RewindError::TooOld)
+}
+
+/// Block at `position` as of the end of `tick`, given the block there now.
+/// Edits recorded after `tick` are undone newest first.
+pub fn rewind_block(
+    lag: &LagCompensation,
+    position: VoxelPos,
+    current: BlockId,
+    tick: u64,
+) -> RewindResult<BlockId> {
+    check_rewind_tick(lag, tick as f64)?;
+    let mut block = current;
+    let pending = lag.recording.voxel_edits.iter().rev();
+    let recorded = lag
+        .history
+        .iter()
+        .rev()
+        .take_while(|history| history.tick > tick)
+        .flat_map(|history| history.voxel_edits.iter().rev());
+    for edit in pending.chain(recorded) {
+        if edit.position == position {
+            block = edit.previous;
+        }
+    }
+    Ok(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(entity_id: u32, x: f32) -> EntityHistorySample {
+        EntityHistorySample {
+            entity_id,
+            position: [x, 0.0, 0.0],
+            aabb_min: [-0.3, 0.0, -0.3],
+            aabb_max: [0.3, 1.8, 0.3],
+        }
+    }
+
+    #[test]
+    fn test_rewind_entities_and_voxel_edits() {
+        let mut lag = create_lag_compensation(LagCompensationConfig {
+            history_ticks: 8,
+            max_rewind_ticks: 5,
+        });
+        assert_eq!(rewind_entity(&lag, 1, 0.0), Err(RewindError::NoHistory));
+
+        let pos = VoxelPos::new(4, 5, 6);
+        for tick in 0..10u64 {
+            if tick == 7 {
+                record_lag_voxel_edit(&mut lag, pos, BlockId(3), BlockId::AIR);
+            }
+            finish_lag_tick(&mut lag, tick, vec![sample(2, 0.0), sample(1, tick as f32)]);
+        }
+        assert_eq!(lag.history.len(), 8);
+        assert_eq!(latest_lag_tick(&lag), Some(9));
+
+        let blended = rewind_entity(&lag, 1, 6.5).unwrap().unwrap();
+        assert!((blended.position[0] - 6.5).abs() < 1e-5);
+        assert_eq!(rewind_entity(&lag, 3, 6.0), Ok(None));
+        assert_eq!(rewind_entity(&lag, 1, 9.5), Err(RewindError::InFuture));
+        assert_eq!(rewind_entity(&lag, 1, 3.0), Err(RewindError::TooOld));
+        assert_eq!(rewind_tick(&lag, 8).unwrap().entities[0].entity_id, 1);
+
+        assert_eq!(rewind_block(&lag, pos, BlockId::AIR, 6), Ok(BlockId(3)));
+        assert_eq!(rewind_block(&lag, pos, BlockId::AIR, 7), Ok(BlockId::AIR));
+        record_lag_voxel_edit(&mut lag, pos, BlockId::AIR, BlockId(5));
+        assert_eq!(rewind_block(&lag, pos, BlockId(5), 9), Ok(BlockId::AIR));
+        assert_eq!(rewind_block(&lag, pos, BlockId(5), 6), Ok(BlockId(3)));
+
+        set_lag_history_ticks(&mut lag, 2);
+        assert_eq!(lag.history.front().map(|h| h.tick), Some(8));
+    }
+}
@@ -0,0 +1,109 @@
+//! LAN Discovery Data - Pure DOP
+//!
+//! Servers broadcast a small UDP beacon every few seconds; clients listen on
+//! the discovery port and keep a list of the servers they heard from. The
+//! same version information is exchanged in the connection handshake so a
+//! client can refuse an incompatible server before loading anything. After
+//! the hellos the server sends its block registry table (registry_sync_data)
+//! and both sides send their custom packet channels (custom_packet_data).
+//!
+//! Beacon layout: magic "HLAN", then a bincode-encoded `ServerBeacon`.
+//! Handshake layout: magic "HSHK", then a bincode-encoded `HandshakeMessage`.
+//!
+//! NO METHODS - just data.
+
+use super::custom_packet_data::CustomPacketTable;
+use super::registry_sync_data::BlockRegistryTable;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::Instant;
+
+/// Identifier at the start of every beacon
+pub const BEACON_MAGIC: [u8; 4] = *b"HLAN";
+
+/// Identifier at the start of every handshake message
+pub const HANDSHAKE_MAGIC: [u8; 4] = *b"HSHK";
+
+/// What a build speaks, compared during discovery and handshakes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolVersionInfo {
+    pub protocol_version: u32,
+    /// Oldest peer protocol version accepted
+    pub min_compatible_version: u32,
+    /// Engine crate version, informational
+    pub engine_version: String,
+    /// Game identifier; servers of other games are never compatible
+    pub game_id: String,
+}
+
+/// Broadcast by a server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerBeacon {
+    pub server_name: String,
+    /// Port the game server accepts connections on
+    pub game_port: u16,
+    pub player_count: u32,
+    pub max_players: u32,
+    pub version: ProtocolVersionInfo,
+}
+
+/// Result of comparing two versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeCompatibility {
+    Compatible,
+    /// The remote side requires a newer protocol than the local one
+    LocalTooOld {
+        remote_minimum: u32,
+    },
+    /// The remote side is older than the local minimum
+    RemoteTooOld {
+        local_minimum: u32,
+    },
+    DifferentGame,
+}
+
+/// Join handshake messages; the hello is the first one on a new connection
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HandshakeMessage {
+    Hello(ProtocolVersionInfo),
+    /// Sent instead of the server's hello when the client cannot join
+    Rejected {
+        reason: String,
+    },
+    /// Sent by the server after its hello
+    BlockRegistry(BlockRegistryTable),
+    /// Sent by both sides after the hellos (custom_packet_data)
+    CustomPackets(CustomPacketTable),
+}
+
+/// Server side: periodic beacon broadcaster
+pub struct LanBeaconData {
+    pub socket: UdpSocket,
+    pub target: SocketAddr,
+    pub beacon: ServerBeacon,
+    pub interval_ms: u64,
+    pub last_sent: Option<Instant>,
+    pub beacons_sent: u64,
+}
+
+/// A server heard on the LAN
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredServer {
+    /// Address to connect to (beacon sender's IP with the beacon's game port)
+    pub address: SocketAddr,
+    pub beacon: ServerBeacon,
+    pub compatibility: HandshakeCompatibility,
+    pub first_seen: Instant,
+    pub last_seen: Instant,
+}
+
+/// Client side: beacon listener and server list
+pub struct LanDiscoveryData {
+    pub socket: UdpSocket,
+    pub local_version: ProtocolVersionInfo,
+    pub servers: HashMap<SocketAddr, DiscoveredServer>,
+    pub timeout_ms: u64,
+    /// Datagrams that were not valid beacons
+    pub invalid_beacons: u64,
+}
@@ -0,0 +1,427 @@
+//! LAN Discovery Operations - Pure DOP Functions
+//!
+//! Server: `create_lan_beacon` once, `update_lan_beacon` when the player
+//! count changes, `tick_lan_beacon` every frame (it only sends when the
+//! interval has passed).
+//!
+//! Client: `create_lan_discovery` binds the discovery port, then
+//! `poll_lan_discovery` every frame drains beacons without blocking and
+//! expires silent servers. `discovered_servers` returns the list to show.
+//!
+//! Handshake: each side sends `encode_handshake(&HandshakeMessage::Hello(..))`
+//! first and checks the peer's hello with `check_compatibility`. An accepted
+//! client then receives the server's block registry (registry_sync_operations).
+
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::time::Instant;
+
+use super::lan_discovery_data::{
+    DiscoveredServer, HandshakeCompatibility, HandshakeMessage, LanBeaconData, LanDiscoveryData,
+    ProtocolVersionInfo, ServerBeacon, BEACON_MAGIC, HANDSHAKE_MAGIC,
+};
+use super::NetworkResult;
+use crate::constants::network_constants::{
+    LAN_BEACON_INTERVAL_MS, LAN_DISCOVERY_PORT, LAN_SERVER_TIMEOUT_MS, MAX_BEACON_BYTES,
+    MIN_COMPATIBLE_PROTOCOL_VERSION, PROTOCOL_VERSION,
+};
+
+// ============================================================================
+// VERSIONS AND HANDSHAKE
+// ============================================================================
+
+/// Version info of this build for `game_id`
+pub fn local_version_info(game_id: &str) -> ProtocolVersionInfo {
+    ProtocolVersionInfo {
+        protocol_version: PROTOCOL_VERSION,
+        min_compatible_version: MIN_COMPATIBLE_PROTOCOL_VERSION,
+        engine_version: env!("CARGO_PKG_VERSION").to_string(),
+        game_id: game_id.to_string(),
+    }
+}
+
+/// Whether `local` can talk to `remote`. Both sides' minimums apply.
+pub fn check_compatibility(
+    local: &ProtocolVersionInfo,
+    remote: &ProtocolVersionInfo,
+) -> HandshakeCompatibility {
+    if local.game_id != remote.game_id {
+        HandshakeCompatibility::DifferentGame
+    } else if local.protocol_version < remote.min_compatible_version {
+        HandshakeCompatibility::LocalTooOld {
+            remote_minimum: remote.min_compatible_version,
+        }
+    } else if remote.protocol_version < local.min_compatible_version {
+        HandshakeCompatibility::RemoteTooOld {
+            local_minimum: local.min_compatible_version,
+        }
+    } else {
+        HandshakeCompatibility::Compatible
+    }
+}
+
+/// Message shown to a player for an incompatible server
+pub fn compatibility_message(compatibility: HandshakeCompatibility) -> Option<String> {
+    match compatibility {
+        HandshakeCompatibility::Compatible => None,
+        HandshakeCompatibility::LocalTooOld { remote_minimum } => Some(format!(
+            "Server requires protocol {} or newer; update the game",
+            remote_minimum
+        )),
+        HandshakeCompatibility::RemoteTooOld { local_minimum } => Some(format!(
+            "Server is outdated (protocol {} or newer required)",
+            local_minimum
+        )),
+        HandshakeCompatibility::DifferentGame => Some("Server runs a different game".to_string()),
+    }
+}
+
+fn encode_with_magic<T: serde::Serialize>(magic: [u8; 4], value: &T) -> NetworkResult<Vec<u8>> {
+    let body = bincode::serialize(value).map_err(|e| format!("Failed to encode: {}", e))?;
+    let mut bytes = Vec::with_capacity(magic.len() + body.len());
+    bytes.extend_from_slice(&magic);
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+}
+
+fn decode_with_magic<T: serde::de::DeserializeOwned>(
+    magic: [u8; 4],
+    bytes: &[u8],
+) -> NetworkResult<T> {
+    let body = bytes
+        .strip_prefix(&magic[..])
+        .ok_or_else(|| "Missing message magic".to_string())?;
+    bincode::deserialize(body).map_err(|e| format!("Failed to decode: {}", e))
+}
+
+/// Encode a handshake message
+pub fn encode_handshake(message: &HandshakeMessage) -> NetworkResult<Vec<u8>> {
+    encode_with_magic(HANDSHAKE_MAGIC, message)
+}
+
+/// Decode a handshake message
+pub fn decode_handshake(bytes: &[u8]) -> NetworkResult<HandshakeMessage> {
+    decode_with_magic(HANDSHAKE_MAGIC, bytes)
+}
+
+/// Server answer to a client hello: its own hello, or a rejection
+pub fn answer_handshake(
+    local: &ProtocolVersionInfo,
+    client: &HandshakeMessage,
+) -> HandshakeMessage {
+    let HandshakeMessage::Hello(remote) = client else {
+        return HandshakeMessage::Rejected {
+            reason: "Expected hello".to_string(),
+        };
+    };
+    match compatibility_message(check_compatibility(local, remote)) {
+        None => HandshakeMessage::Hello(local.clone()),
+        Some(reason) => HandshakeMessage::Rejected { reason },
+    }
+}
+
+// ============================================================================
+// BEACONS
+// ============================================================================
+
+/// Encode a beacon; fails if it would not fit in `MAX_BEACON_BYTES`
+pub fn encode_beacon(beacon: &ServerBeacon) -> NetworkResult<Vec<u8>> {
+    let bytes = encode_with_magic(BEACON_MAGIC, beacon)?;
+    if bytes.len() > MAX_BEACON_BYTES {
+        return Err(format!(
+            "Beacon is {} bytes (limit {}); shorten the server name",
+            bytes.len(),
+            MAX_BEACON_BYTES
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Decode a beacon datagram
+pub fn decode_beacon(bytes: &[u8]) -> NetworkResult<ServerBeacon> {
+    if bytes.len() > MAX_BEACON_BYTES {
+        return Err(format!("Beacon too large ({} bytes)", bytes.len()));
+    }
+    decode_with_magic(BEACON_MAGIC, bytes)
+}
+
+/// Broadcast beacons to the default discovery port
+pub fn create_lan_beacon(beacon: ServerBeacon) -> NetworkResult<LanBeaconData> {
+    create_lan_beacon_to(
+        beacon,
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::BROADCAST, LAN_DISCOVERY_PORT)),
+    )
+}
+
+/// Send beacons to `target` (a broadcast address, or a single host in tests)
+pub fn create_lan_beacon_to(
+    beacon: ServerBeacon,
+    target: SocketAddr,
+) -> NetworkResult<LanBeaconData> {
+    encode_beacon(&beacon)?;
+    let socket = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0))
+        .map_err(|e| format!("Failed to bind beacon socket: {}", e))?;
+    socket
+        .set_broadcast(true)
+        .map_err(|e| format!("Failed to enable broadcast: {}", e))?;
+
+    log::info!(
+        "[LanDiscovery] Advertising '{}' (port {}) to {}",
+        beacon.server_name,
+        beacon.game_port,
+        target
+    );
+    Ok(LanBeaconData {
+        socket,
+        target,
+        beacon,
+        interval_ms: LAN_BEACON_INTERVAL_MS,
+        last_sent: None,
+        beacons_sent: 0,
+    })
+}
+
+/// Update the advertised player counts; the next beacon carries them
+pub fn update_lan_beacon(data: &mut LanBeaconData, player_count: u32, max_players: u32) {
+    data.beacon.player_count = player_count;
+    data.beacon.max_players = max_players;
+}
+
+/// Send a beacon if the interval has passed. Returns whether one was sent.
+pub fn tick_lan_beacon(data: &mut LanBeaconData, now: Instant) -> NetworkResult<bool> {
+    let due = data
+        .last_sent
+        .is_none_or(|last| now.duration_since(last).as_millis() as u64 >= data.interval_ms);
+    if !due {
+        return Ok(false);
+    }
+
+    let bytes = encode_beacon(&data.beacon)?;
+    data.socket
+        .send_to(&bytes, data.target)
+        .map_err(|e| format!("Failed to send beacon to {}: {}", data.target, e))?;
+    data.last_sent = Some(now);
+    data.beacons_sent += 1;
+    Ok(true)
+}
+
+// ============================================================================
+// DISCOVERY
+// ============================================================================
+
+/// Listen for beacons on the default discovery port. Only one process per
+/// machine can listen at a time.
+pub fn create_lan_discovery(local_version: ProtocolVersionInfo) -> NetworkResult<LanDiscoveryData> {
+    create_lan_discovery_on(
+        local_version,
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, LAN_DISCOVERY_PORT)),
+    )
+}
+
+/// Listen for beacons on `address`
+pub fn create_lan_discovery_on(
+    local_version: ProtocolVersionInfo,
+    address: SocketAddr,
+) -> NetworkResult<LanDiscoveryData> {
+    let socket = UdpSocket::bind(address)
+        .map_err(|e| format!("Failed to bind discovery socket {}: {}", address, e))?;
+    socket
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to make discovery socket non-blocking: {}", e))?;
+
+    Ok(LanDiscoveryData {
+        socket,
+        local_version,
+        servers: std::collections::HashMap::new(),
+        timeout_ms: LAN_SERVER_TIMEOUT_MS,
+        invalid_beacons: 0,
+    })
+}
+
+/// Record a beacon heard from `sender`. Returns true for a new server.
+pub fn record_server_beacon(
+    data: &mut LanDiscoveryData,
+    sender: SocketAddr,
+    beacon: ServerBeacon,
+    now: Instant,
+) -> bool {
+    let address = SocketAddr::new(sender.ip(), beacon.game_port);
+    let compatibility = check_compatibility(&data.local_version, &beacon.version);
+
+    match data.servers.get_mut(&address) {
+        Some(server) => {
+            server.beacon = beacon;
+            server.compatibility = compatibility;
+            server.last_seen = now;
+            false
+        }
+        None => {
+            log::debug!(
+                "[LanDiscovery] Found '{}' at {} ({:?})",
+                beacon.server_name,
+                address,
+                compatibility
+            );
+            data.servers.insert(
+                address,
+                DiscoveredServer {
+                    address,
+                    beacon,
+                    compatibility,
+                    first_seen: now,
+                    last_seen: now,
+                },
+            );
+            true
+        }
+    }
+}
+
+/// Drop servers not heard from within the timeout. Returns how many.
+pub fn expire_lan_servers(data: &mut LanDiscoveryData, now: Instant) -> usize {
+    let before = data.servers.len();
+    let timeout_ms = data.timeout_ms;
+    data.servers
+        .retain(|_, server| (now.duration_since(server.last_seen).as_millis() as u64) < timeout_ms);
+    before - data.servers.len()
+}
+
+/// Drain pending beacons without blocking and expire silent servers.
+/// Returns the number of newly found servers.
+pub fn poll_lan_discovery(data: &mut LanDiscoveryData, now: Instant) -> NetworkResult<usize> {
+    let mut found = 0;
+    let mut buffer = [0u8; MAX_BEACON_BYTES + 1];
+
+    loop {
+        match data.socket.recv_from(&mut buffer) {
+            Ok((len, sender)) => match decode_beacon(&buffer[..len]) {
+                Ok(beacon) => {
+                    if record_server_beacon(data, sender, beacon, now) {
+                        found += 1;
+                    }
+                }
+                Err(_) => data.invalid_beacons += 1,
+            },
+            Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+            // ICMP port unreachable and similar surface here on some platforms
+            Err(e) if e.kind() == ErrorKind::ConnectionReset => continue,
+            Err(e) => return Err(format!("Discovery receive failed: {}", e)),
+        }
+    }
+
+    expire_lan_servers(data, now);
+    Ok(found)
+}
+
+/// Known servers, compatible ones first, then by name
+pub fn discovered_servers(data: &LanDiscoveryData) -> Vec<&DiscoveredServer> {
+    let mut servers: Vec<&DiscoveredServer> = data.servers.values().collect();
+    servers.sort_by(|a, b| {
+        let a_ok = a.compatibility == HandshakeCompatibility::Compatible;
+        let b_ok = b.compatibility == HandshakeCompatibility::Compatible;
+        b_ok.cmp(&a_ok)
+            .then_with(|| a.beacon.server_name.cmp(&b.beacon.server_name))
+            .then_with(|| a.address.cmp(&b.address))
+    });
+    servers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn beacon(name: &str, version: ProtocolVersionInfo) -> ServerBeacon {
+        ServerBeacon {
+            server_name: name.to_string(),
+            game_port: 25_000,
+            player_count: 3,
+            max_players: 16,
+            version,
+        }
+    }
+
+    #[test]
+    fn test_compatibility_checks_both_minimums() {
+        let local = local_version_info("hearth");
+        let newer = ProtocolVersionInfo {
+            protocol_version: PROTOCOL_VERSION + 2,
+            min_compatible_version: PROTOCOL_VERSION + 1,
+            ..local.clone()
+        };
+        assert_eq!(
+            check_compatibility(&local, &local),
+            HandshakeCompatibility::Compatible
+        );
+        assert_eq!(
+            check_compatibility(&local, &newer),
+            HandshakeCompatibility::LocalTooOld {
+                remote_minimum: PROTOCOL_VERSION + 1
+            }
+        );
+        assert_eq!(
+            check_compatibility(&newer, &local),
+            HandshakeCompatibility::RemoteTooOld {
+                local_minimum: PROTOCOL_VERSION + 1
+            }
+        );
+        let other_game = local_version_info("other");
+        assert_eq!(
+            check_compatibility(&local, &other_game),
+            HandshakeCompatibility::DifferentGame
+        );
+
+        let hello = encode_handshake(&HandshakeMessage::Hello(newer)).expect("encode");
+        let client = decode_handshake(&hello).expect("decode");
+        assert!(matches!(
+            answer_handshake(&local, &client),
+            HandshakeMessage::Rejected { .. }
+        ));
+    }
+
+    #[test]
+    fn test_beacon_round_trip_and_rejects_garbage() {
+        let original = beacon("Home server", local_version_info("hearth"));
+        let bytes = encode_beacon(&original).expect("encode");
+        assert_eq!(decode_beacon(&bytes).expect("decode"), original);
+        assert!(decode_beacon(b"HLANgarbage").is_err());
+        assert!(decode_beacon(b"nope").is_err());
+
+        let huge = beacon(&"x".repeat(MAX_BEACON_BYTES), local_version_info("hearth"));
+        assert!(encode_beacon(&huge).is_err());
+    }
+
+    #[test]
+    fn test_beacon_reaches_listener_over_loopback() {
+        let version = local_version_info("hearth");
+        let loopback = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0));
+        let Ok(mut discovery) = create_lan_discovery_on(version.clone(), loopback) else {
+            return;
+        };
+        let Ok(target) = discovery.socket.local_addr() else {
+            return;
+        };
+        let mut server =
+            create_lan_beacon_to(beacon("Loopback", version), target).expect("beacon socket");
+
+        let start = Instant::now();
+        assert!(tick_lan_beacon(&mut server, start).expect("send"));
+        assert!(!tick_lan_beacon(&mut server, start).expect("not due"));
+
+        let mut found = 0;
+        for _ in 0..100 {
+            found += poll_lan_discovery(&mut discovery, Instant::now()).expect("poll");
+            if found > 0 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert_eq!(found, 1);
+        let servers = discovered_servers(&discovery);
+        assert_eq!(servers[0].beacon.server_name, "Loopback");
+        assert_eq!(servers[0].address.port(), 25_000);
+        assert_eq!(servers[0].compatibility, HandshakeCompatibility::Compatible);
+
+        let later = Instant::now() + std::time::Duration::from_millis(LAN_SERVER_TIMEOUT_MS);
+        assert_eq!(expire_lan_servers(&mut discovery, later), 1);
+    }
+}
@@ -0,0 +1,181 @@
+//! Lockstep Data - Pure DOP
+//!
+//! Lockstep mode for small co-op sessions (2-4 players). Instead of
+//! replicating state, peers exchange only their inputs. Every peer runs
+//! the same simulation tick by tick, with the fixed timestep and the
+//! world's seeded random streams. An input sampled at tick T is simulated
+//! at T + input delay, and a tick runs once every player's input for it
+//! has arrived. Each input message repeats the player's recent inputs, so
+//! a lost packet is covered by the next one.
+//!
+//! Every few ticks each peer hashes its state and broadcasts the hash.
+//! The host's state is canonical. A peer whose hash differs stops
+//! simulating. The host sees the mismatch and sends its state and random
+//! streams, and the peer continues from there.
+//!
+//! The engine runs a `LockstepSessionData` over its network session once
+//! per fixed tick, driving the game through `LockstepSimulation`.
+//!
+//! Packet layout: magic "LSTP", then a bincode-encoded `LockstepMessage`.
+//!
+//! NO METHODS - just data.
+
+use crate::world_random_data::WorldRandomData;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Identifier at the start of every lockstep message
+pub const LOCKSTEP_MAGIC: [u8; 4] = *b"LSTP";
+
+/// Player number within a session
+pub type LockstepPlayer = u8;
+
+/// One player's input for one tick, encoded by the game
+pub type LockstepInput = Vec<u8>;
+
+/// Inputs of every player for one tick
+pub type TickInputs = BTreeMap<LockstepPlayer, LockstepInput>;
+
+/// Inputs by tick
+pub type InputsByTick = BTreeMap<u64, TickInputs>;
+
+/// State hashes of one tick, by player
+pub type PlayerHashes = BTreeMap<LockstepPlayer, u64>;
+
+/// Session settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LockstepConfig {
+    pub max_players: usize,
+    pub input_delay_ticks: u64,
+    /// States are hashed on ticks divisible by this
+    pub hash_interval_ticks: u64,
+    pub input_history_ticks: u64,
+    pub max_input_bytes: usize,
+    /// Seconds simulated per tick
+    pub timestep: f32,
+}
+
+/// Everything sent between lockstep peers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LockstepMessage {
+    /// A player's inputs for consecutive ticks starting at `first_tick`
+    Inputs {
+        player: LockstepPlayer,
+        first_tick: u64,
+        inputs: Vec<LockstepInput>,
+    },
+    /// Hash of a player's state after simulating `tick`
+    StateHash {
+        player: LockstepPlayer,
+        tick: u64,
+        hash: u64,
+    },
+    /// Host state to continue from; `tick` is the next tick to simulate
+    Resync {
+        tick: u64,
+        state: Vec<u8>,
+        /// `serialize_world_random` of the host's random streams
+        random: Vec<u8>,
+    },
+}
+
+/// Whether the session can simulate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockstepStatus {
+    Running,
+    /// Waiting for another player's input
+    Stalled,
+    /// This peer's hash differs from the host's at `tick`; waiting for a
+    /// resync
+    Desynced {
+        tick: u64,
+    },
+}
+
+/// A tick ready to simulate
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockstepTick {
+    pub tick: u64,
+    /// Seconds to simulate
+    pub timestep: f32,
+    pub inputs: TickInputs,
+}
+
+/// Something the game must act on
+#[derive(Debug, Clone, PartialEq)]
+pub enum LockstepEvent {
+    /// Host: a peer's hash differs from ours; send it a resync
+    ResyncNeeded { player: LockstepPlayer, tick: u64 },
+    /// Peer: our hash differs from the host's; simulation is paused
+    Desynced { tick: u64 },
+    /// Peer: replace the game state and random streams with these and
+    /// continue from `tick`
+    Resync {
+        tick: u64,
+        state: Vec<u8>,
+        random: WorldRandomData,
+    },
+}
+
+/// Session counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LockstepStats {
+    pub ticks: u64,
+    /// Times the session had to wait for an input
+    pub stalls: u64,
+    /// State hashes compared
+    pub hashes_checked: u64,
+    pub desyncs: u64,
+    pub resyncs: u64,
+}
+
+/// One peer's side of a lockstep session
+#[derive(Debug, Clone)]
+pub struct LockstepData {
+    pub config: LockstepConfig,
+    pub local_player: LockstepPlayer,
+    pub host: LockstepPlayer,
+    /// Players in the session, sorted
+    pub players: Vec<LockstepPlayer>,
+    /// Next tick to simulate
+    pub tick: u64,
+    pub status: LockstepStatus,
+    /// Inputs received or sampled, including simulated ticks kept for
+    /// replay
+    pub inputs: InputsByTick,
+    /// Our state hash by tick
+    pub local_hashes: BTreeMap<u64, u64>,
+    /// Other players' state hashes by tick
+    pub remote_hashes: BTreeMap<u64, PlayerHashes>,
+    /// Host: tick of the last resync sent; older mismatches are ignored
+    pub last_resync_tick: Option<u64>,
+    /// Events waiting for `take_lockstep_events`
+    pub events: Vec<LockstepEvent>,
+    pub stats: LockstepStats,
+}
+
+/// Game simulation driven by a lockstep session
+pub trait LockstepSimulation {
+    /// Local player's input, sampled once per fixed tick
+    fn sample_input(&mut self) -> LockstepInput;
+
+    /// Simulate one tick; `random` is already advanced to it
+    fn simulate(&mut self, tick: &LockstepTick, random: &mut WorldRandomData);
+
+    /// Game state after the last simulated tick, hashed and sent in resyncs
+    fn encode_state(&self) -> Vec<u8>;
+
+    /// Continue from the host's state after a resync
+    fn load_state(&mut self, state: &[u8]);
+}
+
+/// Lockstep session run over the network session, one call per fixed tick
+pub struct LockstepSessionData {
+    pub lockstep: LockstepData,
+    /// Network connection id of every remote player
+    pub peers: BTreeMap<LockstepPlayer, u32>,
+    /// Random streams the simulation draws from, hashed and resynced with
+    /// the state
+    pub random: WorldRandomData,
+    pub simulation: Box<dyn LockstepSimulation>,
+}
@@ -0,0 +1,701 @@
+//! Lockstep Operations - Pure DOP Functions
+//!
+//! Every peer creates the session with `create_lockstep` once the players
+//! are known. Then, once per fixed tick:
+//! 1. `queue_local_input` with the input sampled this tick, and send the
+//!    returned message to every other peer with `send_lockstep_message`.
+//! 2. Pass every received lockstep packet to `receive_lockstep_packet`.
+//! 3. If `next_lockstep_tick` returns a tick: `advance_world_random_tick`
+//!    to it, simulate it, and pass `lockstep_state_hash` of the new state
+//!    to `record_lockstep_hash`, sending the message it returns on hash
+//!    ticks.
+//! 4. Handle `take_lockstep_events`: the host answers `ResyncNeeded` with
+//!    `lockstep_resync_message`; a peer loads the state of a `Resync`.
+//!
+//! `tick_lockstep_session` does all of this over the network session for a
+//! `LockstepSimulation`; the engine calls it on every fixed tick.
+
+use super::connection::{queue_packet, Connection, SendPriority};
+use super::error::NetworkResult;
+use super::lockstep_data::{
+    LockstepConfig, LockstepData, LockstepEvent, LockstepInput, LockstepMessage, LockstepPlayer,
+    LockstepSessionData, LockstepSimulation, LockstepStats, LockstepStatus, LockstepTick,
+    TickInputs, LOCKSTEP_MAGIC,
+};
+use super::network_data::NetworkData;
+use super::network_operations::take_received_packets_with_prefix;
+use crate::constants::gameplay::FIXED_TIMESTEP;
+use crate::constants::network_constants::{
+    LOCKSTEP_HASH_INTERVAL_TICKS, LOCKSTEP_INPUT_DELAY_TICKS, LOCKSTEP_INPUT_HISTORY_TICKS,
+    LOCKSTEP_MAX_INPUT_BYTES, LOCKSTEP_MAX_PLAYERS, LOCKSTEP_PACKET_TYPE,
+};
+use crate::world_random_data::WorldRandomData;
+use crate::world_random_operations::{
+    advance_world_random_tick, deserialize_world_random, serialize_world_random,
+};
+use std::collections::BTreeMap;
+
+const FNV_OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+/// Engine fixed timestep, hashing once a second at 60 ticks
+pub fn default_lockstep_config() -> LockstepConfig {
+    LockstepConfig {
+        max_players: LOCKSTEP_MAX_PLAYERS,
+        input_delay_ticks: LOCKSTEP_INPUT_DELAY_TICKS,
+        hash_interval_ticks: LOCKSTEP_HASH_INTERVAL_TICKS,
+        input_history_ticks: LOCKSTEP_INPUT_HISTORY_TICKS,
+        max_input_bytes: LOCKSTEP_MAX_INPUT_BYTES,
+        timestep: FIXED_TIMESTEP,
+    }
+}
+
+/// Session at tick 0 between `players`, seen from `local_player`
+pub fn create_lockstep(
+    config: LockstepConfig,
+    local_player: LockstepPlayer,
+    host: LockstepPlayer,
+    players: &[LockstepPlayer],
+) -> NetworkResult<LockstepData> {
+    let mut sorted = players.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    if sorted.len() != players.len() {
+        return Err("Lockstep players must be distinct".to_string());
+    }
+    if sorted.is_empty() || sorted.len() > config.max_players {
+        return Err(format!(
+            "Lockstep sessions take 1 to {} players, not {}",
+            config.max_players,
+            sorted.len()
+        ));
+    }
+    if !sorted.contains(&local_player) || !sorted.contains(&host) {
+        return Err("Local player and host must be in the session".to_string());
+    }
+
+    Ok(LockstepData {
+        config,
+        local_player,
+        host,
+        players: sorted,
+        tick: 0,
+        status: LockstepStatus::Running,
+        inputs: BTreeMap::new(),
+        local_hashes: BTreeMap::new(),
+        remote_hashes: BTreeMap::new(),
+        last_resync_tick: None,
+        events: Vec::new(),
+        stats: LockstepStats::default(),
+    })
+}
+
+/// Whether this peer's state is canonical
+pub fn is_lockstep_host(data: &LockstepData) -> bool {
+    data.local_player == data.host
+}
+
+/// Schedule this tick's input for `tick + input_delay_ticks`, and return
+/// the message carrying our recent inputs for the other peers. Ticks up
+/// to then that have no input yet (session start, after a resync) get
+/// this one too. An input is never replaced once set: peers may already
+/// have it.
+pub fn queue_local_input(
+    data: &mut LockstepData,
+    input: LockstepInput,
+) -> NetworkResult<LockstepMessage> {
+    if input.len() > data.config.max_input_bytes {
+        return Err(format!(
+            "Lockstep input of {} bytes exceeds the {} byte limit",
+            input.len(),
+            data.config.max_input_bytes
+        ));
+    }
+    let target = data.tick + data.config.input_delay_ticks;
+    for tick in data.tick..=target {
+        data.inputs
+            .entry(tick)
+            .or_default()
+            .entry(data.local_player)
+            .or_insert_with(|| input.clone());
+    }
+    Ok(local_input_message(data))
+}
+
+/// Our inputs from the oldest tick a peer may still be waiting for. A
+/// peer is at most `input_delay_ticks + 1` behind: we could not have
+/// simulated further without its inputs.
+fn local_input_message(data: &LockstepData) -> LockstepMessage {
+    let oldest = data.tick.saturating_sub(data.config.input_delay_ticks + 1);
+    let mut first_tick = None;
+    let mut inputs = Vec::new();
+    for (&tick, by_player) in data.inputs.range(oldest..) {
+        let Some(input) = by_player.get(&data.local_player) else {
+            if first_tick.is_some() {
+                break;
+            }
+            continue;
+        };
+        let first = *first_tick.get_or_insert(tick);
+        if tick != first + inputs.len() as u64 {
+            break;
+        }
+        inputs.push(input.clone());
+    }
+    LockstepMessage::Inputs {
+        player: data.local_player,
+        first_tick: first_tick.unwrap_or(data.tick),
+        inputs,
+    }
+}
+
+/// Take the next tick if every player's input for it has arrived. Call
+/// at most once per fixed tick, so the session keeps real time.
+pub fn next_lockstep_tick(data: &mut LockstepData) -> Option<LockstepTick> {
+    if matches!(data.status, LockstepStatus::Desynced { .. }) {
+        return None;
+    }
+    let inputs: TickInputs = match data.inputs.get(&data.tick) {
+        Some(by_player) if data.players.iter().all(|p| by_player.contains_key(p)) => by_player
+            .iter()
+            .filter(|(player, _)| data.players.contains(player))
+            .map(|(&player, input)| (player, input.clone()))
+            .collect(),
+        _ => {
+            if data.status == LockstepStatus::Running {
+                data.status = LockstepStatus::Stalled;
+                data.stats.stalls += 1;
+            }
+            return None;
+        }
+    };
+
+    let tick = data.tick;
+    data.tick += 1;
+    data.status = LockstepStatus::Running;
+    data.stats.ticks += 1;
+    let oldest = data.tick.saturating_sub(data.config.input_history_ticks);
+    data.inputs = data.inputs.split_off(&oldest);
+    Some(LockstepTick {
+        tick,
+        timestep: data.config.timestep,
+        inputs,
+    })
+}
+
+/// Players whose input for the next tick has not arrived
+pub fn lockstep_waiting_for(data: &LockstepData) -> Vec<LockstepPlayer> {
+    let by_player = data.inputs.get(&data.tick);
+    data.players
+        .iter()
+        .copied()
+        .filter(|player| !by_player.is_some_and(|inputs| inputs.contains_key(player)))
+        .collect()
+}
+
+/// Stable hash of a game state and the random streams it was simulated
+/// with (FNV-1a); the game encodes its state the same way on every peer
+pub fn lockstep_state_hash(state: &[u8], random: &WorldRandomData) -> u64 {
+    let mut hash = FNV_OFFSET;
+    let mut feed = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash = (hash ^ byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    };
+    feed(state);
+    feed(&random.tick.to_le_bytes());
+    for (name, rng) in &random.streams {
+        feed(name.as_bytes());
+        for word in rng.state {
+            feed(&word.to_le_bytes());
+        }
+    }
+    hash
+}
+
+/// Record our state hash after simulating `tick` and compare it with
+/// what the others sent. Returns the message for the other peers on
+/// hash ticks.
+pub fn record_lockstep_hash(
+    data: &mut LockstepData,
+    tick: u64,
+    hash: u64,
+) -> Option<LockstepMessage> {
+    if !tick.is_multiple_of(data.config.hash_interval_ticks.max(1)) {
+        return None;
+    }
+    data.local_hashes.insert(tick, hash);
+    compare_hashes(data, tick);
+
+    let oldest = tick.saturating_sub(data.config.input_history_ticks);
+    data.local_hashes = data.local_hashes.split_off(&oldest);
+    data.remote_hashes = data.remote_hashes.split_off(&oldest);
+    Some(LockstepMessage::StateHash {
+        player: data.local_player,
+        tick,
+        hash,
+    })
+}
+
+/// Compare the remote hashes of `tick` with ours once both are known.
+/// The host checks every peer; a peer checks only the host.
+fn compare_hashes(data: &mut LockstepData, tick: u64) {
+    let Some(&local) = data.local_hashes.get(&tick) else {
+        return;
+    };
+    let Some(remote) = data.remote_hashes.remove(&tick) else {
+        return;
+    };
+
+    if is_lockstep_host(data) {
+        // Hashes from before the last resync describe the old state
+        if data.last_resync_tick.is_some_and(|resync| tick < resync) {
+            return;
+        }
+        for (player, hash) in remote {
+            data.stats.hashes_checked += 1;
+            if hash != local {
+                data.stats.desyncs += 1;
+                data.events
+                    .push(LockstepEvent::ResyncNeeded { player, tick });
+            }
+        }
+    } else if let Some(&host_hash) = remote.get(&data.host) {
+        data.stats.hashes_checked += 1;
+        if host_hash != local {
+            data.stats.desyncs += 1;
+            data.status = LockstepStatus::Desynced { tick };
+            data.events.push(LockstepEvent::Desynced { tick });
+        }
+    } else {
+        // Still waiting for the host's hash
+        data.remote_hashes.insert(tick, remote);
+    }
+}
+
+/// Host: message replacing the peers' state with ours. `state` is the
+/// game state after the last simulated tick, encoded as for
+/// `lockstep_state_hash`.
+pub fn lockstep_resync_message(
+    data: &mut LockstepData,
+    state: Vec<u8>,
+    random: &WorldRandomData,
+) -> NetworkResult<LockstepMessage> {
+    if !is_lockstep_host(data) {
+        return Err("Only the lockstep host sends resyncs".to_string());
+    }
+    let random = serialize_world_random(random).map_err(|e| e.to_string())?;
+    data.last_resync_tick = Some(data.tick);
+    data.stats.resyncs += 1;
+    Ok(LockstepMessage::Resync {
+        tick: data.tick,
+        state,
+        random,
+    })
+}
+
+/// Handle a message from another peer
+pub fn receive_lockstep_message(
+    data: &mut LockstepData,
+    message: LockstepMessage,
+) -> NetworkResult<()> {
+    match message {
+        LockstepMessage::Inputs {
+            player,
+            first_tick,
+            inputs,
+        } => {
+            check_remote_player(data, player)?;
+            let window = 2 * (data.config.input_delay_ticks + 1);
+            if inputs.len() as u64 > window {
+                return Err(format!(
+                    "Player {} sent {} lockstep inputs, more than the {} tick window",
+                    player,
+                    inputs.len(),
+                    window
+                ));
+            }
+            let oldest = data.tick.saturating_sub(data.config.input_history_ticks);
+            for (tick, input) in (first_tick..).zip(inputs) {
+                if tick < oldest || input.len() > data.config.max_input_bytes {
+                    continue;
+                }
+                data.inputs
+                    .entry(tick)
+                    .or_default()
+                    .entry(player)
+                    .or_insert(input);
+            }
+        }
+        LockstepMessage::StateHash { player, tick, hash } => {
+            check_remote_player(data, player)?;
+            data.remote_hashes
+                .entry(tick)
+                .or_default()
+                .insert(player, hash);
+            compare_hashes(data, tick);
+        }
+        LockstepMessage::Resync {
+            tick,
+            state,
+            random,
+        } => {
+            if is_lockstep_host(data) {
+                return Err("The lockstep host does not accept resyncs".to_string());
+            }
+            let random = deserialize_world_random(&random).map_err(|e| e.to_string())?;
+            data.tick = tick;
+            data.status = LockstepStatus::Running;
+            data.local_hashes.clear();
+            data.remote_hashes = data.remote_hashes.split_off(&tick);
+            data.stats.resyncs += 1;
+            data.events.push(LockstepEvent::Resync {
+                tick,
+                state,
+                random,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_remote_player(data: &LockstepData, player: LockstepPlayer) -> NetworkResult<()> {
+    if player == data.local_player || !data.players.contains(&player) {
+        return Err(format!("Player {} is not a remote lockstep player", player));
+    }
+    Ok(())
+}
+
+/// Decode and handle a lockstep packet
+pub fn receive_lockstep_packet(data: &mut LockstepData, bytes: &[u8]) -> NetworkResult<()> {
+    let message = decode_lockstep_message(bytes)?;
+    receive_lockstep_message(data, message)
+}
+
+/// Events raised since the last call
+pub fn take_lockstep_events(data: &mut LockstepData) -> Vec<LockstepEvent> {
+    std::mem::take(&mut data.events)
+}
+
+/// Drop a player who left; the session stops waiting for their inputs.
+/// The session ends when the host leaves.
+pub fn remove_lockstep_player(
+    data: &mut LockstepData,
+    player: LockstepPlayer,
+) -> NetworkResult<()> {
+    if player == data.host {
+        return Err("The lockstep host left; the session has ended".to_string());
+    }
+    check_remote_player(data, player)?;
+    data.players.retain(|&p| p != player);
+    for hashes in data.remote_hashes.values_mut() {
+        hashes.remove(&player);
+    }
+    Ok(())
+}
+
+/// Encode a lockstep message
+pub fn encode_lockstep_message(message: &LockstepMessage) -> NetworkResult<Vec<u8>> {
+    let body = bincode::serialize(message).map_err(|e| format!("Failed to encode: {}", e))?;
+    let mut bytes = Vec::with_capacity(LOCKSTEP_MAGIC.len() + body.len());
+    bytes.extend_from_slice(&LOCKSTEP_MAGIC);
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+}
+
+/// Decode a lockstep message
+pub fn decode_lockstep_message(bytes: &[u8]) -> NetworkResult<LockstepMessage> {
+    let body = bytes
+        .strip_prefix(&LOCKSTEP_MAGIC[..])
+        .ok_or_else(|| "Missing message magic".to_string())?;
+    bincode::deserialize(body).map_err(|e| format!("Failed to decode: {}", e))
+}
+
+/// Queue a lockstep message on a peer's connection; inputs and hashes go
+/// out first, resyncs with the bulk data
+pub fn send_lockstep_message(
+    conn: &mut Connection,
+    message: &LockstepMessage,
+) -> NetworkResult<()> {
+    let priority = match message {
+        LockstepMessage::Resync { .. } => SendPriority::ChunkData,
+        _ => SendPriority::Position,
+    };
+    queue_packet(
+        conn,
+        priority,
+        LOCKSTEP_PACKET_TYPE,
+        encode_lockstep_message(message)?,
+    );
+    Ok(())
+}
+
+// ============================================================================
+// SESSION
+// ============================================================================
+
+/// Session run by `tick_lockstep_session`; every remote player needs a
+/// connection in `peers`
+pub fn create_lockstep_session(
+    lockstep: LockstepData,
+    peers: BTreeMap<LockstepPlayer, u32>,
+    random: WorldRandomData,
+    simulation: Box<dyn LockstepSimulation>,
+) -> NetworkResult<LockstepSessionData> {
+    if let Some(player) = lockstep
+        .players
+        .iter()
+        .find(|&&player| player != lockstep.local_player && !peers.contains_key(&player))
+    {
+        return Err(format!("No connection for lockstep player {}", player));
+    }
+    Ok(LockstepSessionData {
+        lockstep,
+        peers,
+        random,
+        simulation,
+    })
+}
+
+/// Run one fixed tick: take the lockstep packets received since the last
+/// one, send this tick's input, and simulate the next tick once every
+/// input for it is in, exchanging its hash. The host answers divergent
+/// peers with a resync; a peer continues from the resync it receives.
+/// Returns the tick simulated, if any.
+pub fn tick_lockstep_session(
+    session: &mut LockstepSessionData,
+    network: &mut NetworkData,
+) -> NetworkResult<Option<u64>> {
+    for packet in take_received_packets_with_prefix(network, &LOCKSTEP_MAGIC) {
+        if !session.peers.values().any(|&id| id == packet.connection_id) {
+            log::warn!(
+                "[Lockstep] Dropped packet from connection {}, which is not a peer",
+                packet.connection_id
+            );
+            continue;
+        }
+        if let Err(e) = receive_lockstep_packet(&mut session.lockstep, &packet.payload) {
+            log::warn!(
+                "[Lockstep] Dropped packet from connection {}: {}",
+                packet.connection_id,
+                e
+            );
+        }
+    }
+    handle_lockstep_session_events(session, network)?;
+
+    let input = session.simulation.sample_input();
+    let message = queue_local_input(&mut session.lockstep, input)?;
+    broadcast_lockstep_message(session, network, &message)?;
+
+    let Some(tick) = next_lockstep_tick(&mut session.lockstep) else {
+        return Ok(None);
+    };
+    advance_world_random_tick(&mut session.random, tick.tick);
+    session.simulation.simulate(&tick, &mut session.random);
+    let hash = lockstep_state_hash(&session.simulation.encode_state(), &session.random);
+    if let Some(message) = record_lockstep_hash(&mut session.lockstep, tick.tick, hash) {
+        broadcast_lockstep_message(session, network, &message)?;
+    }
+    handle_lockstep_session_events(session, network)?;
+    Ok(Some(tick.tick))
+}
+
+fn send_to_lockstep_player(
+    session: &LockstepSessionData,
+    network: &mut NetworkData,
+    player: LockstepPlayer,
+    message: &LockstepMessage,
+) -> NetworkResult<()> {
+    let conn = session
+        .peers
+        .get(&player)
+        .and_then(|id| network.connections.get_mut(id))
+        .ok_or_else(|| format!("No connection for lockstep player {}", player))?;
+    send_lockstep_message(conn, message)
+}
+
+fn broadcast_lockstep_message(
+    session: &LockstepSessionData,
+    network: &mut NetworkData,
+    message: &LockstepMessage,
+) -> NetworkResult<()> {
+    for &player in &session.lockstep.players {
+        if player != session.lockstep.local_player {
+            send_to_lockstep_player(session, network, player, message)?;
+        }
+    }
+    Ok(())
+}
+
+fn handle_lockstep_session_events(
+    session: &mut LockstepSessionData,
+    network: &mut NetworkData,
+) -> NetworkResult<()> {
+    for event in take_lockstep_events(&mut session.lockstep) {
+        match event {
+            LockstepEvent::ResyncNeeded { player, tick } => {
+                log::warn!(
+                    "[Lockstep] Player {} diverged at tick {}; resyncing",
+                    player,
+                    tick
+                );
+                let state = session.simulation.encode_state();
+                let message =
+                    lockstep_resync_message(&mut session.lockstep, state, &session.random)?;
+                send_to_lockstep_player(session, network, player, &message)?;
+            }
+            LockstepEvent::Desynced { tick } => {
+                log::warn!(
+                    "[Lockstep] Diverged from the host at tick {}; waiting for a resync",
+                    tick
+                );
+            }
+            LockstepEvent::Resync {
+                tick,
+                state,
+                random,
+            } => {
+                session.simulation.load_state(&state);
+                session.random = random;
+                log::info!("[Lockstep] Resynced from the host at tick {}", tick);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::world_random::AI_STREAM;
+    use crate::world_random_operations::{
+        advance_world_random_tick, create_world_random, fork_world_stream, next_world_rng_u64,
+    };
+
+    fn session(local: LockstepPlayer) -> LockstepData {
+        create_lockstep(default_lockstep_config(), local, 0, &[0, 1]).expect("session")
+    }
+
+    fn relay(message: &LockstepMessage, to: &mut LockstepData) {
+        let bytes = encode_lockstep_message(message).expect("encode");
+        receive_lockstep_packet(to, &bytes).expect("receive");
+    }
+
+    /// Simulate a tick if ready: fold the inputs and a random draw into
+    /// the state
+    fn run_tick(
+        data: &mut LockstepData,
+        random: &mut WorldRandomData,
+        state: &mut u64,
+    ) -> Option<u64> {
+        let tick = next_lockstep_tick(data)?;
+        advance_world_random_tick(random, tick.tick);
+        let draw = next_world_rng_u64(&mut fork_world_stream(random, AI_STREAM, 0));
+        for (player, input) in &tick.inputs {
+            *state = state.rotate_left(5) ^ u64::from(*player) ^ input.len() as u64;
+        }
+        *state ^= draw;
+        Some(tick.tick)
+    }
+
+    #[test]
+    fn peers_simulate_the_same_ticks_from_inputs_alone() {
+        let (mut host, mut peer) = (session(0), session(1));
+        let (mut host_random, mut peer_random) = (create_world_random(7), create_world_random(7));
+        let (mut host_state, mut peer_state) = (0u64, 0u64);
+        let (mut host_states, mut peer_states) = (Vec::new(), Vec::new());
+
+        // Nothing runs before the other player's input arrives
+        let first = queue_local_input(&mut host, vec![1]).expect("input");
+        assert_eq!(next_lockstep_tick(&mut host), None);
+        assert_eq!(host.status, LockstepStatus::Stalled);
+        assert_eq!(lockstep_waiting_for(&host), vec![1]);
+        relay(&first, &mut peer);
+
+        for step in 0..300u32 {
+            let input = vec![0; (step % 5) as usize];
+            let to_peer = queue_local_input(&mut host, input.clone()).expect("input");
+            let to_host = queue_local_input(&mut peer, input).expect("input");
+            relay(&to_peer, &mut peer);
+            // Every third packet to the host is lost; the next one covers it
+            if step % 3 != 0 {
+                relay(&to_host, &mut host);
+            }
+
+            if let Some(tick) = run_tick(&mut host, &mut host_random, &mut host_state) {
+                host_states.push(host_state);
+                let hash = lockstep_state_hash(&host_state.to_le_bytes(), &host_random);
+                if let Some(message) = record_lockstep_hash(&mut host, tick, hash) {
+                    relay(&message, &mut peer);
+                }
+            }
+            if let Some(tick) = run_tick(&mut peer, &mut peer_random, &mut peer_state) {
+                peer_states.push(peer_state);
+                let hash = lockstep_state_hash(&peer_state.to_le_bytes(), &peer_random);
+                if let Some(message) = record_lockstep_hash(&mut peer, tick, hash) {
+                    relay(&message, &mut host);
+                }
+            }
+        }
+
+        let common = host_states.len().min(peer_states.len());
+        assert!(common > 250, "only {} ticks simulated", common);
+        assert_eq!(host_states[..common], peer_states[..common]);
+        assert!(take_lockstep_events(&mut host).is_empty());
+        assert!(take_lockstep_events(&mut peer).is_empty());
+        assert!(host.stats.hashes_checked >= 4);
+        assert!(peer.stats.hashes_checked >= 4);
+        assert!(host.inputs.len() as u64 <= LOCKSTEP_INPUT_HISTORY_TICKS + 2 * 4);
+    }
+
+    #[test]
+    fn diverged_peer_is_resynced_from_the_host() {
+        let (mut host, mut peer) = (session(0), session(1));
+        assert!(create_lockstep(default_lockstep_config(), 0, 0, &[0, 1, 2, 3, 4]).is_err());
+        assert!(remove_lockstep_player(&mut peer, 0).is_err());
+
+        let to_peer = queue_local_input(&mut host, vec![]).expect("input");
+        let to_host = queue_local_input(&mut peer, vec![]).expect("input");
+        relay(&to_peer, &mut peer);
+        relay(&to_host, &mut host);
+        assert_eq!(next_lockstep_tick(&mut host).map(|t| t.tick), Some(0));
+        assert_eq!(next_lockstep_tick(&mut peer).map(|t| t.tick), Some(0));
+
+        let host_hash = record_lockstep_hash(&mut host, 0, 111).expect("hash tick");
+        let peer_hash = record_lockstep_hash(&mut peer, 0, 222).expect("hash tick");
+        relay(&host_hash, &mut peer);
+        relay(&peer_hash, &mut host);
+
+        assert_eq!(peer.status, LockstepStatus::Desynced { tick: 0 });
+        assert_eq!(
+            take_lockstep_events(&mut peer),
+            vec![LockstepEvent::Desynced { tick: 0 }]
+        );
+        assert_eq!(next_lockstep_tick(&mut peer), None);
+        assert_eq!(
+            take_lockstep_events(&mut host),
+            vec![LockstepEvent::ResyncNeeded { player: 1, tick: 0 }]
+        );
+
+        let mut random = create_world_random(7);
+        advance_world_random_tick(&mut random, 1);
+        let resync = lockstep_resync_message(&mut host, vec![9, 9], &random).expect("resync");
+        assert!(lockstep_resync_message(&mut peer, vec![], &random).is_err());
+        relay(&resync, &mut peer);
+        assert_eq!(
+            take_lockstep_events(&mut peer),
+            vec![LockstepEvent::Resync {
+                tick: 1,
+                state: vec![9, 9],
+                random,
+            }]
+        );
+        assert_eq!(peer.status, LockstepStatus::Running);
+        assert_eq!(next_lockstep_tick(&mut peer).map(|t| t.tick), Some(1));
+
+        // A late hash from before the resync is ignored
+        relay(&peer_hash, &mut host);
+        assert!(take_lockstep_events(&mut host).is_empty());
+    }
+}
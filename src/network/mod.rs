@@ -3,28 +3,135 @@
 //! This module will be properly implemented after DOP conversion is complete.
 
 pub mod anticheat;
+pub mod chunk_diff_data;
+pub mod chunk_diff_operations;
+pub mod chunk_stream_data;
+pub mod chunk_stream_operations;
 pub mod connection;
+pub mod custom_packet_data;
+pub mod custom_packet_operations;
 pub mod disconnect_handler;
 pub mod interest;
 pub mod interpolation;
 pub mod lag_compensation;
+pub mod lan_discovery_data;
+pub mod lan_discovery_operations;
+pub mod lockstep_data;
+pub mod lockstep_operations;
 pub mod network_data;
 pub mod network_operations;
 pub mod packet;
+pub mod packet_capture_data;
+pub mod packet_capture_operations;
 pub mod prediction;
 pub mod protocol;
+pub mod registry_sync_data;
+pub mod registry_sync_operations;
 
 // Simple re-exports matching our stub implementations
-pub use anticheat::AntiCheat;
-pub use connection::Connection;
+pub use anticheat::{
+    clear_player_violations, create_anticheat, player_violations, validate_block_interaction,
+    validate_hit, ActionRejection, AntiCheat, AntiCheatConfig, AntiCheatStats,
+    BlockInteractionClaim, HitClaim,
+};
+pub use chunk_diff_data::{
+    ChunkDiffPacket, ChunkEdit, ChunkEditLog, ChunkEditLogConfig, ChunkEditLogData, ChunkVersion,
+    DiffReceipt, VersionedEdit,
+};
+pub use chunk_diff_operations::{
+    apply_chunk_edits, chunk_diff_since, create_chunk_edit_log, current_chunk_version,
+    decode_chunk_edit_log, default_chunk_edit_log_config, encode_chunk_edit_log,
+    record_chunk_edit, reset_chunk_edit_log,
+};
+pub use chunk_stream_data::{
+    ChunkRequest, ChunkRequestMessage, ChunkRequestState, ChunkSectionPacket, ChunkSendJob,
+    ChunkSendQueue, ChunkStreamConfig, ChunkStreamStats, PendingChunk, SectionReceipt,
+};
+pub use chunk_stream_operations::{
+    apply_chunk_request, cancel_chunk_sends, chunk_request_priority, chunk_section_count,
+    create_chunk_request_state, create_chunk_send_queue, decode_chunk_diff, decode_chunk_request,
+    decode_chunk_section, default_chunk_stream_config, encode_chunk_diff, encode_chunk_request,
+    encode_chunk_section, forget_chunk_version, pump_chunk_sends, receive_chunk_diff,
+    receive_chunk_section, remember_chunk_version, update_chunk_requests,
+};
+pub use connection::{
+    create_connection, effective_send_rate, flush_connection, queue_packet, queued_bytes,
+    record_ack_results, record_rtt_sample, set_bandwidth_limits, BandwidthConfig, Connection,
+    ConnectionStats, CongestionState, OutgoingPacket, SendPriority, TokenBucket,
+};
+pub use custom_packet_data::{
+    CustomChannelReceive, CustomPacket, CustomPacketChannel, CustomPacketChannels,
+    CustomPacketDelivery, CustomPacketMessage, CustomPacketStats, CustomPacketTable, CustomPayload,
+    ReceivedCustomPacket, UnackedCustomPacket,
+};
+pub use custom_packet_operations::{
+    accept_custom_packet_table, create_custom_packet_channels, custom_packet_channel,
+    custom_packet_events, custom_packet_handshake, decode_custom_packet_message,
+    encode_custom_packet_message, is_valid_custom_channel_name, receive_custom_packets,
+    register_custom_packet_channel, send_custom_packet, tick_custom_packets,
+};
 pub use disconnect_handler::{DisconnectHandler, DisconnectReason, ConnectionState};
 pub use interest::InterestManager;
 pub use interpolation::Interpolation;
-pub use lag_compensation::LagCompensation;
-pub use network_data::NetworkData;
+pub use lag_compensation::{
+    check_rewind_tick, create_lag_compensation, finish_lag_tick, latest_lag_tick,
+    record_lag_voxel_edit, rewind_block, rewind_entity, rewind_tick, set_lag_history_ticks,
+    tick_history, EntityHistorySample, LagCompensation, LagCompensationConfig, RewindError,
+    RewindResult, TickHistory, VoxelEditSample,
+};
+pub use lan_discovery_data::{
+    DiscoveredServer, HandshakeCompatibility, HandshakeMessage, LanBeaconData, LanDiscoveryData,
+    ProtocolVersionInfo, ServerBeacon,
+};
+pub use lan_discovery_operations::{
+    answer_handshake, check_compatibility, compatibility_message, create_lan_beacon,
+    create_lan_beacon_to, create_lan_discovery, create_lan_discovery_on, decode_beacon,
+    decode_handshake, discovered_servers, encode_beacon, encode_handshake, expire_lan_servers,
+    local_version_info, poll_lan_discovery, record_server_beacon, tick_lan_beacon,
+    update_lan_beacon,
+};
+pub use lockstep_data::{
+    InputsByTick, LockstepConfig, LockstepData, LockstepEvent, LockstepInput, LockstepMessage,
+    LockstepPlayer, LockstepSessionData, LockstepSimulation, LockstepStats, LockstepStatus,
+    LockstepTick, PlayerHashes, TickInputs, LOCKSTEP_MAGIC,
+};
+pub use lockstep_operations::{
+    create_lockstep, create_lockstep_session, decode_lockstep_message, default_lockstep_config,
+    encode_lockstep_message, is_lockstep_host, lockstep_resync_message, lockstep_state_hash,
+    lockstep_waiting_for, next_lockstep_tick, queue_local_input, receive_lockstep_message,
+    receive_lockstep_packet, record_lockstep_hash, remove_lockstep_player, send_lockstep_message,
+    take_lockstep_events, tick_lockstep_session,
+};
+pub use network_data::{NetworkCapture, NetworkData, ReceivedPacket, SentPacket};
+pub use network_operations::{
+    add_network_connection, create_network, dump_network_capture, flush_network, receive_packet,
+    remove_network_connection, run_network_command, send_packet, set_network_tick,
+    start_network_capture, stop_network_capture, take_received_packets,
+    take_received_packets_with_prefix, take_sent_packets,
+};
 pub use packet::Packet;
+pub use packet_capture_data::{
+    CaptureRecord, CaptureSummary, CapturedPacket, PacketCaptureData, PacketCaptureFile,
+    PacketDirection, PacketReplayData, PacketTypeName, PacketTypeSummary, ReplayTarget,
+    EnginePacketTypeName, ENGINE_PACKET_TYPE_NAMES,
+};
+pub use packet_capture_operations::{
+    capture_packet, capture_sent_packets, create_packet_replay, finish_packet_capture,
+    flush_packet_capture, format_capture_summary, is_replay_finished, load_packet_capture,
+    name_packet_type, packets_per_tick, read_packet_capture, replay_to_end, replay_until_tick,
+    rewind_packet_replay, set_packet_capture_enabled,
+    start_packet_capture, summarize_capture,
+};
 pub use prediction::Prediction;
 pub use protocol::Protocol;
+pub use registry_sync_data::{
+    BlockIdRemap, BlockRegistryTable, RegistrySyncConfig, RegistryTableEntry,
+};
+pub use registry_sync_operations::{
+    accept_registry_sync, build_block_id_remap, build_registry_table, default_registry_sync_config,
+    is_identity_remap, registry_sync_message, remap_incoming_block, remap_incoming_blocks,
+    remap_outgoing_block, remap_outgoing_blocks,
+};
 
 // Network module error (stub)
 pub mod error {
@@ -1,3 +1,48 @@
-//! Network Data - Stub
+//! Network Data - Pure data structures for the network session
+//!
+//! The session owns the send state of every connection and the packet
+//! capture. Transports hand it the packets they read off the wire and put
+//! the packets it releases onto the wire.
+//! NO METHODS - just data.
 
-pub struct NetworkData;
+use super::connection::{Connection, OutgoingPacket};
+use super::packet_capture_data::PacketCaptureData;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Packet a transport read off the wire
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedPacket {
+    pub connection_id: u32,
+    pub packet_type: u16,
+    pub payload: Vec<u8>,
+}
+
+/// Packet released by a connection, for a transport to put on the wire
+#[derive(Debug, Clone)]
+pub struct SentPacket {
+    pub connection_id: u32,
+    pub packet: OutgoingPacket,
+}
+
+/// Active capture and the file it writes
+pub struct NetworkCapture {
+    pub data: PacketCaptureData,
+    pub path: PathBuf,
+}
+
+/// Network session
+pub struct NetworkData {
+    /// Connections by id
+    pub connections: BTreeMap<u32, Connection>,
+    /// Tick stamped on captured packets
+    pub tick: u64,
+    /// Packets received since the last `take_received_packets`
+    pub received: Vec<ReceivedPacket>,
+    /// Packets released since the last `take_sent_packets`
+    pub sent: Vec<SentPacket>,
+    /// Every packet sent and received is recorded while set
+    pub capture: Option<NetworkCapture>,
+    /// File of the last capture, dumped when `netcap dump` has no path
+    pub last_capture_path: Option<PathBuf>,
+}
@@ -1,4 +1,296 @@
-//! Network Operations - Stub
+//! Network Operations - Pure DOP Functions
+//!
+//! Every packet goes through the session: `send_packet` queues it on its
+//! connection and `flush_network` releases what the bandwidth caps allow,
+//! while transports hand what they read to `receive_packet`. Both ends are
+//! recorded by the packet capture, driven from the console with `netcap`.
 
-pub fn send_packet() {}
-pub fn receive_packet() {}
+use super::connection::{flush_connection, queue_packet, Connection, SendPriority};
+use super::error::NetworkResult;
+use super::network_data::{NetworkCapture, NetworkData, ReceivedPacket, SentPacket};
+use super::packet_capture_data::PacketDirection;
+use super::packet_capture_operations::{
+    capture_packet, finish_packet_capture, flush_packet_capture, format_capture_summary,
+    load_packet_capture, start_packet_capture, summarize_capture,
+};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Create a session with no connections
+pub fn create_network() -> NetworkData {
+    NetworkData {
+        connections: BTreeMap::new(),
+        tick: 0,
+        received: Vec::new(),
+        sent: Vec::new(),
+        capture: None,
+        last_capture_path: None,
+    }
+}
+
+/// Add a connection, replacing any with the same id
+pub fn add_network_connection(network: &mut NetworkData, conn: Connection) {
+    network.connections.insert(conn.id, conn);
+}
+
+/// Remove a connection, dropping the packets still queued on it
+pub fn remove_network_connection(
+    network: &mut NetworkData,
+    connection_id: u32,
+) -> Option<Connection> {
+    network.connections.remove(&connection_id)
+}
+
+/// Set the tick stamped on captured packets
+pub fn set_network_tick(network: &mut NetworkData, tick: u64) {
+    network.tick = tick;
+}
+
+/// A failed capture write stops the capture rather than the session
+fn stop_capture_after_error(network: &mut NetworkData, error: String) {
+    log::error!("[Network] Packet capture stopped: {}", error);
+    if let Some(capture) = network.capture.take() {
+        network.last_capture_path = Some(capture.path);
+        let _ = finish_packet_capture(capture.data);
+    }
+}
+
+/// Queue a packet of `packet_type` on a connection
+pub fn send_packet(
+    network: &mut NetworkData,
+    connection_id: u32,
+    priority: SendPriority,
+    packet_type: u16,
+    payload: Vec<u8>,
+) -> NetworkResult<()> {
+    let conn = network
+        .connections
+        .get_mut(&connection_id)
+        .ok_or_else(|| format!("Unknown connection {}", connection_id))?;
+    queue_packet(conn, priority, packet_type, payload);
+    Ok(())
+}
+
+/// Release what every connection's budget allows into `sent`, capturing it
+pub fn flush_network(network: &mut NetworkData, delta_time: f32) -> usize {
+    let mut released = Vec::new();
+    for (&connection_id, conn) in network.connections.iter_mut() {
+        released.extend(
+            flush_connection(conn, delta_time)
+                .into_iter()
+                .map(|packet| SentPacket {
+                    connection_id,
+                    packet,
+                }),
+        );
+    }
+
+    if let Some(capture) = network.capture.as_mut() {
+        let captured = released.iter().try_for_each(|sent| {
+            capture_packet(
+                &mut capture.data,
+                network.tick,
+                PacketDirection::Outbound,
+                sent.connection_id,
+                sent.packet.packet_type,
+                &sent.packet.payload,
+            )
+        });
+        if let Err(e) = captured {
+            stop_capture_after_error(network, e);
+        }
+    }
+
+    let count = released.len();
+    network.sent.extend(released);
+    count
+}
+
+/// Hand the session a packet read off the wire
+pub fn receive_packet(
+    network: &mut NetworkData,
+    connection_id: u32,
+    packet_type: u16,
+    payload: Vec<u8>,
+) -> NetworkResult<()> {
+    if !network.connections.contains_key(&connection_id) {
+        return Err(format!("Unknown connection {}", connection_id));
+    }
+    if let Some(capture) = network.capture.as_mut() {
+        if let Err(e) = capture_packet(
+            &mut capture.data,
+            network.tick,
+            PacketDirection::Inbound,
+            connection_id,
+            packet_type,
+            &payload,
+        ) {
+            stop_capture_after_error(network, e);
+        }
+    }
+    network.received.push(ReceivedPacket {
+        connection_id,
+        packet_type,
+        payload,
+    });
+    Ok(())
+}
+
+/// Packets received since the last call
+pub fn take_received_packets(network: &mut NetworkData) -> Vec<ReceivedPacket> {
+    std::mem::take(&mut network.received)
+}
+
+/// Packets received since the last call whose payload starts with
+/// `prefix`, leaving the others for their own consumers
+pub fn take_received_packets_with_prefix(
+    network: &mut NetworkData,
+    prefix: &[u8],
+) -> Vec<ReceivedPacket> {
+    let (matching, others) = std::mem::take(&mut network.received)
+        .into_iter()
+        .partition(|packet| packet.payload.starts_with(prefix));
+    network.received = others;
+    matching
+}
+
+/// Packets released since the last call, for the transport to send
+pub fn take_sent_packets(network: &mut NetworkData) -> Vec<SentPacket> {
+    std::mem::take(&mut network.sent)
+}
+
+/// Start recording every packet sent and received to `path`
+pub fn start_network_capture(network: &mut NetworkData, path: &Path) -> NetworkResult<()> {
+    if let Some(capture) = &network.capture {
+        return Err(format!("Already capturing to {}", capture.path.display()));
+    }
+    let data = start_packet_capture(path)?;
+    network.capture = Some(NetworkCapture {
+        data,
+        path: path.to_path_buf(),
+    });
+    Ok(())
+}
+
+/// Close the capture, returning the number of packets recorded
+pub fn stop_network_capture(network: &mut NetworkData) -> NetworkResult<u64> {
+    let capture = network
+        .capture
+        .take()
+        .ok_or_else(|| "No packet capture is running".to_string())?;
+    network.last_capture_path = Some(capture.path);
+    finish_packet_capture(capture.data)
+}
+
+/// Packet type summary of a capture file; a capture still being written
+/// is flushed first
+pub fn dump_network_capture(network: &mut NetworkData, path: &Path) -> NetworkResult<String> {
+    if let Some(capture) = network.capture.as_mut() {
+        if capture.path == path {
+            flush_packet_capture(&mut capture.data)?;
+        }
+    }
+    let file = load_packet_capture(path)?;
+    Ok(format!(
+        "Capture: {}\n{}",
+        path.display(),
+        format_capture_summary(&summarize_capture(&file))
+    ))
+}
+
+/// Run a `netcap` console command:
+/// - `netcap start <path>`: record every packet to a file
+/// - `netcap stop`: close the file
+/// - `netcap dump [path]`: summarize a capture (default: the current or last one)
+pub fn run_network_command(network: &mut NetworkData, command: &str) -> NetworkResult<String> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let args = match words.first() {
+        Some(&"netcap") => &words[1..],
+        _ => &words[..],
+    };
+
+    match args {
+        ["start", path] => {
+            start_network_capture(network, Path::new(path))?;
+            Ok(format!("Capturing packets to {}", path))
+        }
+        ["stop"] => {
+            let packets = stop_network_capture(network)?;
+            Ok(format!("Captured {} packets", packets))
+        }
+        ["dump", path] => dump_network_capture(network, Path::new(path)),
+        ["dump"] => {
+            let path: PathBuf = network
+                .capture
+                .as_ref()
+                .map(|capture| capture.path.clone())
+                .or_else(|| network.last_capture_path.clone())
+                .ok_or_else(|| "No packet capture to dump".to_string())?;
+            dump_network_capture(network, &path)
+        }
+        _ => Err(format!("Unknown command: {}", command.trim())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::network_constants::{FIRST_GAME_PACKET_TYPE, LOCKSTEP_PACKET_TYPE};
+    use crate::network::connection::{create_connection, BandwidthConfig};
+
+    #[test]
+    fn test_session_captures_sent_and_received_packets() {
+        let dir = tempfile::tempdir();
+        assert!(dir.is_ok());
+        let Ok(dir) = dir else { return };
+        let path = dir.path().join("session.hpcap");
+
+        let mut network = create_network();
+        add_network_connection(
+            &mut network,
+            create_connection(7, BandwidthConfig::default()),
+        );
+        let command = format!("netcap start {}", path.display());
+        assert!(run_network_command(&mut network, &command).is_ok());
+
+        set_network_tick(&mut network, 5);
+        let chat = FIRST_GAME_PACKET_TYPE;
+        let lockstep = LOCKSTEP_PACKET_TYPE;
+        assert!(send_packet(
+            &mut network,
+            7,
+            SendPriority::Position,
+            lockstep,
+            vec![1; 12]
+        )
+        .is_ok());
+        assert!(send_packet(
+            &mut network,
+            8,
+            SendPriority::Position,
+            lockstep,
+            vec![1; 12]
+        )
+        .is_err());
+        assert!(send_packet(&mut network, 7, SendPriority::BlockUpdate, chat, vec![2; 8]).is_ok());
+        assert_eq!(flush_network(&mut network, 0.05), 2);
+        assert!(receive_packet(&mut network, 7, chat, b"hello".to_vec()).is_ok());
+        assert_eq!(take_sent_packets(&mut network).len(), 2);
+        assert_eq!(take_received_packets(&mut network)[0].payload, b"hello");
+
+        let dump = run_network_command(&mut network, "netcap dump");
+        assert!(dump.is_ok());
+        let Ok(dump) = dump else { return };
+        assert!(dump.contains("3 packets"), "{}", dump);
+        assert!(dump.contains("lockstep"), "{}", dump);
+        // The game's chat type went out and came in under the same type
+        let chat_rows = format!("type_{}", chat);
+        assert_eq!(dump.matches(chat_rows.as_str()).count(), 2, "{}", dump);
+
+        assert_eq!(
+            run_network_command(&mut network, "netcap stop").ok(),
+            Some("Captured 3 packets".to_string())
+        );
+        assert!(run_network_command(&mut network, "netcap stop").is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! Packet Capture Data - Pure DOP
+//!
+//! Recorded network traffic for debugging multiplayer issues.
+//!
+//! File layout: magic "HPCAP", format version (u32 LE), then records. Each
+//! record is a u32 LE length followed by a bincode-encoded `CaptureRecord`.
+//! Type names may be recorded at any point; a packet's name is the last
+//! one recorded for its type before the dump.
+//!
+//! NO METHODS - just data.
+
+use crate::constants::network_constants::{
+    CHUNK_DIFF_PACKET_TYPE, CHUNK_REQUEST_PACKET_TYPE, CHUNK_SECTION_PACKET_TYPE,
+    CUSTOM_PACKET_TYPE, LOCKSTEP_PACKET_TYPE, SCOREBOARD_PACKET_TYPE,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufWriter;
+
+/// Format identifier at the start of every capture file
+pub const CAPTURE_MAGIC: [u8; 5] = *b"HPCAP";
+
+/// Current capture format version
+pub const CAPTURE_VERSION: u32 = 1;
+
+/// Packet type and the name a capture records for it
+pub type EnginePacketTypeName = (u16, &'static str);
+
+/// Names of the engine's packet types, recorded at the start of every capture
+pub const ENGINE_PACKET_TYPE_NAMES: [EnginePacketTypeName; 6] = [
+    (CHUNK_REQUEST_PACKET_TYPE, "chunk_request"),
+    (CHUNK_SECTION_PACKET_TYPE, "chunk_section"),
+    (CHUNK_DIFF_PACKET_TYPE, "chunk_diff"),
+    (LOCKSTEP_PACKET_TYPE, "lockstep"),
+    (CUSTOM_PACKET_TYPE, "custom_packet"),
+    (SCOREBOARD_PACKET_TYPE, "scoreboard"),
+];
+
+/// Which way a packet travelled, from the capturing endpoint's view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PacketDirection {
+    Inbound,
+    Outbound,
+}
+
+/// One captured packet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapturedPacket {
+    /// Simulation tick the packet was sent or received on
+    pub tick: u64,
+    pub direction: PacketDirection,
+    pub connection_id: u32,
+    /// Message type: one of the engine's (`ENGINE_PACKET_TYPE_NAMES`) or
+    /// the game's, numbered from `FIRST_GAME_PACKET_TYPE`
+    pub packet_type: u16,
+    pub payload: Vec<u8>,
+}
+
+/// Human-readable name for a packet type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PacketTypeName {
+    pub packet_type: u16,
+    pub name: String,
+}
+
+/// One entry of a capture file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CaptureRecord {
+    TypeName(PacketTypeName),
+    Packet(CapturedPacket),
+}
+
+/// Active capture writing to a file
+pub struct PacketCaptureData {
+    pub writer: BufWriter<File>,
+    /// Capturing is paused while false
+    pub enabled: bool,
+    pub packets_written: u64,
+    pub bytes_written: u64,
+    /// Records written since the last flush
+    pub unflushed_records: u64,
+}
+
+/// Capture file loaded back into memory
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PacketCaptureFile {
+    pub type_names: BTreeMap<u16, String>,
+    /// Packets in capture order
+    pub packets: Vec<CapturedPacket>,
+}
+
+/// Endpoint fed by a replay, typically a headless client or server
+pub trait ReplayTarget {
+    /// Called once per tick before that tick's packets are delivered
+    fn begin_tick(&mut self, tick: u64);
+
+    /// Deliver one captured packet
+    fn deliver_packet(&mut self, packet: &CapturedPacket);
+}
+
+/// Replay cursor over a loaded capture
+#[derive(Debug, Clone)]
+pub struct PacketReplayData {
+    pub capture: PacketCaptureFile,
+    /// Direction fed to the target; `Inbound` reproduces what the capturing
+    /// endpoint received
+    pub direction: PacketDirection,
+    /// Only replay this connection (None = all)
+    pub connection_filter: Option<u32>,
+    /// Index of the next packet to consider
+    pub cursor: usize,
+    /// Last tick handed to `ReplayTarget::begin_tick`
+    pub current_tick: Option<u64>,
+    pub packets_delivered: u64,
+}
+
+/// Traffic statistics for one packet type and direction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacketTypeSummary {
+    pub packet_type: u16,
+    pub name: String,
+    pub direction: PacketDirection,
+    pub count: u64,
+    pub total_bytes: u64,
+    pub min_bytes: usize,
+    pub max_bytes: usize,
+}
+
+/// Summary of a whole capture
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CaptureSummary {
+    pub packet_count: u64,
+    pub total_bytes: u64,
+    pub first_tick: u64,
+    pub last_tick: u64,
+    pub connections: usize,
+    /// Sorted by total bytes, largest first
+    pub types: Vec<PacketTypeSummary>,
+}
@@ -0,0 +1,479 @@
+//! Packet Capture Operations - Pure DOP Functions
+//!
+//! Capture: `start_packet_capture` opens a file, `capture_packet` (or
+//! `capture_sent_packets` after `flush_connection`) appends records tagged
+//! with the current tick, `finish_packet_capture` flushes and closes it.
+//!
+//! Replay: `load_packet_capture` reads a file back, `create_packet_replay`
+//! selects which direction to feed, and `replay_until_tick` drives a
+//! headless endpoint through the captured ticks in order.
+//!
+//! Dump: `summarize_capture` + `format_capture_summary` report packet type
+//! frequencies and sizes (see `examples/packet_dump.rs`).
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use super::connection::OutgoingPacket;
+use super::packet_capture_data::{
+    CaptureRecord, CaptureSummary, CapturedPacket, PacketCaptureData, PacketCaptureFile,
+    PacketDirection, PacketReplayData, PacketTypeName, PacketTypeSummary, ReplayTarget,
+    CAPTURE_MAGIC, CAPTURE_VERSION, ENGINE_PACKET_TYPE_NAMES,
+};
+use super::NetworkResult;
+use crate::constants::network_constants::{
+    CAPTURE_FLUSH_INTERVAL_RECORDS, MAX_CAPTURE_RECORD_BYTES,
+};
+
+/// Summary rows are kept per (packet type, direction)
+type TypeKey = (u16, PacketDirection);
+
+// ============================================================================
+// CAPTURE
+// ============================================================================
+
+fn write_record(capture: &mut PacketCaptureData, record: &CaptureRecord) -> NetworkResult<()> {
+    let bytes = bincode::serialize(record)
+        .map_err(|e| format!("Failed to encode capture record: {}", e))?;
+    capture
+        .writer
+        .write_all(&(bytes.len() as u32).to_le_bytes())
+        .and_then(|_| capture.writer.write_all(&bytes))
+        .map_err(|e| format!("Failed to write capture record: {}", e))?;
+
+    capture.bytes_written += 4 + bytes.len() as u64;
+    capture.unflushed_records += 1;
+    if capture.unflushed_records >= CAPTURE_FLUSH_INTERVAL_RECORDS {
+        capture
+            .writer
+            .flush()
+            .map_err(|e| format!("Failed to flush packet capture: {}", e))?;
+        capture.unflushed_records = 0;
+    }
+    Ok(())
+}
+
+/// Create a capture file; the engine's packet types are named up front
+pub fn start_packet_capture(path: &Path) -> NetworkResult<PacketCaptureData> {
+    let file = File::create(path)
+        .map_err(|e| format!("Failed to create capture {}: {}", path.display(), e))?;
+    let mut writer = BufWriter::new(file);
+    writer
+        .write_all(&CAPTURE_MAGIC)
+        .and_then(|_| writer.write_all(&CAPTURE_VERSION.to_le_bytes()))
+        .map_err(|e| format!("Failed to write capture header: {}", e))?;
+
+    let mut capture = PacketCaptureData {
+        writer,
+        enabled: true,
+        packets_written: 0,
+        bytes_written: (CAPTURE_MAGIC.len() + 4) as u64,
+        unflushed_records: 0,
+    };
+    for (packet_type, name) in ENGINE_PACKET_TYPE_NAMES {
+        name_packet_type(&mut capture, packet_type, name)?;
+    }
+
+    log::info!("[PacketCapture] Capturing to {}", path.display());
+    Ok(capture)
+}
+
+/// Record a human-readable name for a game packet type
+pub fn name_packet_type(
+    capture: &mut PacketCaptureData,
+    packet_type: u16,
+    name: &str,
+) -> NetworkResult<()> {
+    write_record(
+        capture,
+        &CaptureRecord::TypeName(PacketTypeName {
+            packet_type,
+            name: name.to_string(),
+        }),
+    )
+}
+
+/// Pause or resume capturing without closing the file
+pub fn set_packet_capture_enabled(capture: &mut PacketCaptureData, enabled: bool) {
+    capture.enabled = enabled;
+}
+
+/// Record one packet
+pub fn capture_packet(
+    capture: &mut PacketCaptureData,
+    tick: u64,
+    direction: PacketDirection,
+    connection_id: u32,
+    packet_type: u16,
+    payload: &[u8],
+) -> NetworkResult<()> {
+    if !capture.enabled {
+        return Ok(());
+    }
+    write_record(
+        capture,
+        &CaptureRecord::Packet(CapturedPacket {
+            tick,
+            direction,
+            connection_id,
+            packet_type,
+            payload: payload.to_vec(),
+        }),
+    )?;
+    capture.packets_written += 1;
+    Ok(())
+}
+
+/// Record the packets released by `flush_connection`
+pub fn capture_sent_packets(
+    capture: &mut PacketCaptureData,
+    tick: u64,
+    connection_id: u32,
+    sent: &[OutgoingPacket],
+) -> NetworkResult<()> {
+    for packet in sent {
+        capture_packet(
+            capture,
+            tick,
+            PacketDirection::Outbound,
+            connection_id,
+            packet.packet_type,
+            &packet.payload,
+        )?;
+    }
+    Ok(())
+}
+
+/// Write buffered records out so the file can be read while capturing
+pub fn flush_packet_capture(capture: &mut PacketCaptureData) -> NetworkResult<()> {
+    capture
+        .writer
+        .flush()
+        .map_err(|e| format!("Failed to flush packet capture: {}", e))?;
+    capture.unflushed_records = 0;
+    Ok(())
+}
+
+/// Flush and close a capture, returning the number of packets recorded
+pub fn finish_packet_capture(mut capture: PacketCaptureData) -> NetworkResult<u64> {
+    flush_packet_capture(&mut capture)?;
+    log::info!(
+        "[PacketCapture] Captured {} packets ({} bytes)",
+        capture.packets_written,
+        capture.bytes_written
+    );
+    Ok(capture.packets_written)
+}
+
+// ============================================================================
+// LOADING
+// ============================================================================
+
+/// Decode a capture from any reader
+pub fn read_packet_capture(reader: &mut impl Read) -> NetworkResult<PacketCaptureFile> {
+    let mut magic = [0u8; 5];
+    let mut version = [0u8; 4];
+    reader
+        .read_exact(&mut magic)
+        .and_then(|_| reader.read_exact(&mut version))
+        .map_err(|e| format!("Failed to read capture header: {}", e))?;
+    if magic != CAPTURE_MAGIC {
+        return Err("Not a packet capture (bad magic)".to_string());
+    }
+    let version = u32::from_le_bytes(version);
+    if version != CAPTURE_VERSION {
+        return Err(format!(
+            "Unsupported capture version {} (expected {})",
+            version, CAPTURE_VERSION
+        ));
+    }
+
+    let mut capture = PacketCaptureFile::default();
+    loop {
+        let mut len = [0u8; 4];
+        match reader.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("Failed to read capture record: {}", e)),
+        }
+        let len = u32::from_le_bytes(len) as usize;
+        if len > MAX_CAPTURE_RECORD_BYTES {
+            return Err(format!("Capture record of {} bytes is too large", len));
+        }
+
+        let mut bytes = vec![0u8; len];
+        if reader.read_exact(&mut bytes).is_err() {
+            // A capture cut off mid-record (e.g. by a crash) keeps what was complete
+            log::warn!(
+                "[PacketCapture] Truncated record after {} packets",
+                capture.packets.len()
+            );
+            break;
+        }
+        match bincode::deserialize(&bytes).map_err(|e| format!("Corrupt capture record: {}", e))? {
+            CaptureRecord::TypeName(entry) => {
+                capture.type_names.insert(entry.packet_type, entry.name);
+            }
+            CaptureRecord::Packet(packet) => capture.packets.push(packet),
+        }
+    }
+    Ok(capture)
+}
+
+/// Load a capture file
+pub fn load_packet_capture(path: &Path) -> NetworkResult<PacketCaptureFile> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open capture {}: {}", path.display(), e))?;
+    read_packet_capture(&mut BufReader::new(file))
+}
+
+// ============================================================================
+// REPLAY
+// ============================================================================
+
+/// Prepare a replay of one direction of a capture
+pub fn create_packet_replay(
+    capture: PacketCaptureFile,
+    direction: PacketDirection,
+    connection_filter: Option<u32>,
+) -> PacketReplayData {
+    PacketReplayData {
+        capture,
+        direction,
+        connection_filter,
+        cursor: 0,
+        current_tick: None,
+        packets_delivered: 0,
+    }
+}
+
+fn replays_packet(replay: &PacketReplayData, packet: &CapturedPacket) -> bool {
+    packet.direction == replay.direction
+        && replay
+            .connection_filter
+            .is_none_or(|id| id == packet.connection_id)
+}
+
+/// Feed every selected packet captured up to and including `tick`.
+/// Ticks without traffic are skipped. Returns the packets delivered.
+pub fn replay_until_tick(
+    replay: &mut PacketReplayData,
+    tick: u64,
+    target: &mut impl ReplayTarget,
+) -> u64 {
+    let mut delivered = 0;
+    while let Some(packet) = replay.capture.packets.get(replay.cursor) {
+        if packet.tick > tick {
+            break;
+        }
+        replay.cursor += 1;
+        if !replays_packet(replay, packet) {
+            continue;
+        }
+        if replay.current_tick != Some(packet.tick) {
+            target.begin_tick(packet.tick);
+            replay.current_tick = Some(packet.tick);
+        }
+        target.deliver_packet(packet);
+        delivered += 1;
+    }
+    replay.packets_delivered += delivered;
+    delivered
+}
+
+/// Feed the rest of the capture
+pub fn replay_to_end(replay: &mut PacketReplayData, target: &mut impl ReplayTarget) -> u64 {
+    replay_until_tick(replay, u64::MAX, target)
+}
+
+/// Whether every packet has been considered
+pub fn is_replay_finished(replay: &PacketReplayData) -> bool {
+    replay.cursor >= replay.capture.packets.len()
+}
+
+/// Restart from the first packet
+pub fn rewind_packet_replay(replay: &mut PacketReplayData) {
+    replay.cursor = 0;
+    replay.current_tick = None;
+    replay.packets_delivered = 0;
+}
+
+// ============================================================================
+// DUMP
+// ============================================================================
+
+/// Packet type frequencies and sizes, split by direction
+pub fn summarize_capture(capture: &PacketCaptureFile) -> CaptureSummary {
+    let mut summary = CaptureSummary::default();
+    let mut types: HashMap<TypeKey, PacketTypeSummary> = HashMap::new();
+    let mut connections = HashSet::new();
+
+    for packet in &capture.packets {
+        let size = packet.payload.len();
+        if summary.packet_count == 0 {
+            summary.first_tick = packet.tick;
+            summary.last_tick = packet.tick;
+        }
+        summary.first_tick = summary.first_tick.min(packet.tick);
+        summary.last_tick = summary.last_tick.max(packet.tick);
+        summary.packet_count += 1;
+        summary.total_bytes += size as u64;
+        connections.insert(packet.connection_id);
+
+        let entry = types
+            .entry((packet.packet_type, packet.direction))
+            .or_insert_with(|| PacketTypeSummary {
+                packet_type: packet.packet_type,
+                name: capture
+                    .type_names
+                    .get(&packet.packet_type)
+                    .cloned()
+                    .unwrap_or_else(|| format!("type_{}", packet.packet_type)),
+                direction: packet.direction,
+                count: 0,
+                total_bytes: 0,
+                min_bytes: size,
+                max_bytes: 0,
+            });
+        entry.count += 1;
+        entry.total_bytes += size as u64;
+        entry.min_bytes = entry.min_bytes.min(size);
+        entry.max_bytes = entry.max_bytes.max(size);
+    }
+
+    summary.connections = connections.len();
+    summary.types = types.into_values().collect();
+    summary.types.sort_by(|a, b| {
+        b.total_bytes
+            .cmp(&a.total_bytes)
+            .then(a.packet_type.cmp(&b.packet_type))
+    });
+    summary
+}
+
+/// Render a summary as a plain-text table
+pub fn format_capture_summary(summary: &CaptureSummary) -> String {
+    let ticks = summary.last_tick.saturating_sub(summary.first_tick) + 1;
+    let mut out = format!(
+        "{} packets, {} bytes, {} connections, ticks {}..={} ({} ticks)\n",
+        summary.packet_count,
+        summary.total_bytes,
+        summary.connections,
+        summary.first_tick,
+        summary.last_tick,
+        ticks
+    );
+    out.push_str(&format!(
+        "{:<20} {:>4} {:>9} {:>10} {:>7} {:>8} {:>8} {:>8} {:>6}\n",
+        "type", "id", "dir", "count", "pkt/tk", "bytes", "avg", "min", "max"
+    ));
+
+    for entry in &summary.types {
+        let direction = match entry.direction {
+            PacketDirection::Inbound => "in",
+            PacketDirection::Outbound => "out",
+        };
+        out.push_str(&format!(
+            "{:<20} {:>4} {:>9} {:>10} {:>7.2} {:>8} {:>8} {:>8} {:>6}\n",
+            entry.name,
+            entry.packet_type,
+            direction,
+            entry.count,
+            entry.count as f64 / ticks as f64,
+            entry.total_bytes,
+            entry.total_bytes / entry.count.max(1),
+            entry.min_bytes,
+            entry.max_bytes
+        ));
+    }
+    out
+}
+
+/// Packet counts per tick, for spotting bursts
+pub fn packets_per_tick(capture: &PacketCaptureFile) -> BTreeMap<u64, u64> {
+    let mut per_tick = BTreeMap::new();
+    for packet in &capture.packets {
+        *per_tick.entry(packet.tick).or_insert(0) += 1;
+    }
+    per_tick
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::network_constants::{CHUNK_SECTION_PACKET_TYPE, LOCKSTEP_PACKET_TYPE};
+    use crate::network::connection::{
+        create_connection, flush_connection, queue_packet, BandwidthConfig, SendPriority,
+    };
+
+    #[derive(Default)]
+    struct RecordingTarget {
+        ticks: Vec<u64>,
+        payloads: Vec<Vec<u8>>,
+    }
+
+    impl ReplayTarget for RecordingTarget {
+        fn begin_tick(&mut self, tick: u64) {
+            self.ticks.push(tick);
+        }
+
+        fn deliver_packet(&mut self, packet: &CapturedPacket) {
+            self.payloads.push(packet.payload.clone());
+        }
+    }
+
+    #[test]
+    fn test_capture_replay_and_summary() {
+        let dir = tempfile::tempdir();
+        assert!(dir.is_ok());
+        let Ok(dir) = dir else { return };
+        let path = dir.path().join("session.hpcap");
+
+        let capture = start_packet_capture(&path);
+        assert!(capture.is_ok());
+        let Ok(mut capture) = capture else { return };
+        assert!(name_packet_type(&mut capture, 100, "chat").is_ok());
+
+        let mut conn = create_connection(3, BandwidthConfig::default());
+        queue_packet(
+            &mut conn,
+            SendPriority::Position,
+            LOCKSTEP_PACKET_TYPE,
+            vec![1; 16],
+        );
+        queue_packet(
+            &mut conn,
+            SendPriority::ChunkData,
+            CHUNK_SECTION_PACKET_TYPE,
+            vec![2; 512],
+        );
+        let sent = flush_connection(&mut conn, 0.05);
+        assert!(capture_sent_packets(&mut capture, 1, 3, &sent).is_ok());
+        assert!(capture_packet(&mut capture, 2, PacketDirection::Inbound, 3, 100, b"hi").is_ok());
+        assert!(capture_packet(&mut capture, 4, PacketDirection::Inbound, 3, 100, b"yo").is_ok());
+        assert_eq!(finish_packet_capture(capture).unwrap_or(0), 4);
+
+        let loaded = load_packet_capture(&path).unwrap_or_default();
+        assert_eq!(loaded.packets.len(), 4);
+        assert_eq!(
+            loaded.type_names.get(&100).map(String::as_str),
+            Some("chat")
+        );
+
+        let summary = summarize_capture(&loaded);
+        assert_eq!(summary.types[0].name, "chunk_section");
+        assert_eq!(summary.types[0].max_bytes, 512);
+        assert_eq!((summary.first_tick, summary.last_tick), (1, 4));
+        assert!(format_capture_summary(&summary).contains("chat"));
+
+        let mut replay = create_packet_replay(loaded, PacketDirection::Inbound, None);
+        let mut target = RecordingTarget::default();
+        assert_eq!(replay_until_tick(&mut replay, 2, &mut target), 1);
+        assert_eq!(replay_to_end(&mut replay, &mut target), 1);
+        assert!(is_replay_finished(&replay));
+        assert_eq!(target.ticks, vec![2, 4]);
+        assert_eq!(target.payloads, vec![b"hi".to_vec(), b"yo".to_vec()]);
+    }
+}
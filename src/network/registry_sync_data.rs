@@ -0,0 +1,48 @@
+//! Registry Sync Data - Pure DOP
+//!
+//! Dynamically registered blocks get their numeric ids in registration
+//! order, which can differ between a client and a server. After the hello,
+//! the server sends its name/id table as `HandshakeMessage::BlockRegistry`;
+//! the client matches names against its own registry and keeps a remap
+//! that every block id crossing the connection goes through. Server ids are
+//! canonical on the wire.
+//!
+//! NO METHODS - just data.
+
+use crate::world::core::BlockId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One dynamically registered block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryTableEntry {
+    pub name: String,
+    pub id: u16,
+}
+
+/// Server's name/id table, sent during the join handshake
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockRegistryTable {
+    pub entries: Vec<RegistryTableEntry>,
+}
+
+/// Client settings for registry sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegistrySyncConfig {
+    /// Local block used for server blocks with no local match
+    pub placeholder: BlockId,
+}
+
+/// Client-side translation between server and local block ids.
+/// Ids without an entry are the same on both sides (built-in blocks, air).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockIdRemap {
+    /// Server id -> local id
+    pub incoming: HashMap<BlockId, BlockId>,
+    /// Local id -> server id
+    pub outgoing: HashMap<BlockId, BlockId>,
+    /// Server blocks not registered locally (shown as the placeholder)
+    pub unknown_server_blocks: Vec<String>,
+    /// Local blocks the server does not have (sent as the placeholder)
+    pub missing_on_server: Vec<String>,
+}
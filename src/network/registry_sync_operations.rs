@@ -0,0 +1,224 @@
+//! Registry Sync Operations - Pure DOP Functions
+//!
+//! Server: after answering the client's hello, send
+//! `registry_sync_message(&registry)`.
+//!
+//! Client: pass that message to `accept_registry_sync` and keep the
+//! returned remap for the connection. Run every received block id through
+//! `remap_incoming_block(s)` and every sent one through
+//! `remap_outgoing_block(s)`.
+
+use std::collections::HashMap;
+
+use super::lan_discovery_data::HandshakeMessage;
+use super::registry_sync_data::{
+    BlockIdRemap, BlockRegistryTable, RegistrySyncConfig, RegistryTableEntry,
+};
+use super::NetworkResult;
+use crate::constants::network_constants::REGISTRY_PLACEHOLDER_BLOCK;
+use crate::world::core::{BlockId, BlockRegistry};
+
+/// Default client settings
+pub fn default_registry_sync_config() -> RegistrySyncConfig {
+    RegistrySyncConfig {
+        placeholder: BlockId(REGISTRY_PLACEHOLDER_BLOCK),
+    }
+}
+
+/// Name/id table of a registry, ordered by id
+pub fn build_registry_table(registry: &BlockRegistry) -> BlockRegistryTable {
+    let mut entries: Vec<RegistryTableEntry> = registry
+        .get_registrations()
+        .iter()
+        .map(|registration| RegistryTableEntry {
+            name: registration.name.clone(),
+            id: registration.id.0,
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.id);
+    BlockRegistryTable { entries }
+}
+
+/// Handshake message carrying the server's registry table
+pub fn registry_sync_message(registry: &BlockRegistry) -> HandshakeMessage {
+    HandshakeMessage::BlockRegistry(build_registry_table(registry))
+}
+
+/// Match the server's table against the local registry by name
+pub fn build_block_id_remap(
+    config: &RegistrySyncConfig,
+    local: &BlockRegistry,
+    server: &BlockRegistryTable,
+) -> BlockIdRemap {
+    let mut remap = BlockIdRemap::default();
+    let server_ids: HashMap<&str, BlockId> = server
+        .entries
+        .iter()
+        .map(|entry| (entry.name.as_str(), BlockId(entry.id)))
+        .collect();
+
+    for entry in &server.entries {
+        let server_id = BlockId(entry.id);
+        match local.get_id(&entry.name) {
+            Some(local_id) if local_id == server_id => {}
+            Some(local_id) => {
+                remap.incoming.insert(server_id, local_id);
+            }
+            None => {
+                remap.incoming.insert(server_id, config.placeholder);
+                remap.unknown_server_blocks.push(entry.name.clone());
+            }
+        }
+    }
+
+    let mut missing = Vec::new();
+    for registration in local.get_registrations() {
+        match server_ids.get(registration.name.as_str()) {
+            Some(server_id) if *server_id == registration.id => {}
+            Some(server_id) => {
+                remap.outgoing.insert(registration.id, *server_id);
+            }
+            None => {
+                missing.push(registration.id);
+                remap.missing_on_server.push(registration.name.clone());
+            }
+        }
+    }
+
+    // Resolved last: the placeholder may itself be a remapped block
+    let placeholder = remap_outgoing_block(&remap, config.placeholder);
+    for id in missing {
+        remap.outgoing.insert(id, placeholder);
+    }
+
+    remap
+}
+
+/// Client side: build the remap from the server's registry message
+pub fn accept_registry_sync(
+    config: &RegistrySyncConfig,
+    local: &BlockRegistry,
+    message: &HandshakeMessage,
+) -> NetworkResult<BlockIdRemap> {
+    let HandshakeMessage::BlockRegistry(table) = message else {
+        return Err("Expected block registry".to_string());
+    };
+
+    let remap = build_block_id_remap(config, local, table);
+    if !remap.unknown_server_blocks.is_empty() {
+        log::warn!(
+            "[Network] {} server blocks not registered locally, shown as {:?}: {:?}",
+            remap.unknown_server_blocks.len(),
+            config.placeholder,
+            remap.unknown_server_blocks
+        );
+    }
+    if !remap.missing_on_server.is_empty() {
+        log::warn!(
+            "[Network] {} local blocks unknown to the server: {:?}",
+            remap.missing_on_server.len(),
+            remap.missing_on_server
+        );
+    }
+    Ok(remap)
+}
+
+/// Whether the remap changes no ids (same registration order on both sides)
+pub fn is_identity_remap(remap: &BlockIdRemap) -> bool {
+    remap.incoming.is_empty() && remap.outgoing.is_empty()
+}
+
+/// Server id -> local id
+pub fn remap_incoming_block(remap: &BlockIdRemap, id: BlockId) -> BlockId {
+    remap.incoming.get(&id).copied().unwrap_or(id)
+}
+
+/// Local id -> server id
+pub fn remap_outgoing_block(remap: &BlockIdRemap, id: BlockId) -> BlockId {
+    remap.outgoing.get(&id).copied().unwrap_or(id)
+}
+
+/// Translate received block ids in place (e.g. chunk data)
+pub fn remap_incoming_blocks(remap: &BlockIdRemap, blocks: &mut [BlockId]) {
+    if remap.incoming.is_empty() {
+        return;
+    }
+    for block in blocks {
+        *block = remap_incoming_block(remap, *block);
+    }
+}
+
+/// Translate block ids about to be sent in place
+pub fn remap_outgoing_blocks(remap: &BlockIdRemap, blocks: &mut [BlockId]) {
+    if remap.outgoing.is_empty() {
+        return;
+    }
+    for block in blocks {
+        *block = remap_outgoing_block(remap, *block);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::{decode_handshake, encode_handshake};
+    use crate::world::blocks::register_basic_blocks;
+
+    fn registry_with(game_blocks: &[&str]) -> BlockRegistry {
+        let mut registry = BlockRegistry::new();
+        register_basic_blocks(&mut registry);
+        let properties = registry
+            .get_properties(BlockId(1))
+            .cloned()
+            .expect("basic block properties");
+        for name in game_blocks {
+            registry.register_block(name, properties.clone());
+        }
+        registry
+    }
+
+    #[test]
+    fn test_different_registration_order_is_remapped() {
+        let server = registry_with(&["mod:ruby", "mod:jade"]);
+        let client = registry_with(&["mod:jade", "mod:ruby"]);
+        let server_ruby = server.get_id("mod:ruby").expect("ruby");
+        let client_ruby = client.get_id("mod:ruby").expect("ruby");
+        assert_ne!(server_ruby, client_ruby);
+
+        let bytes = encode_handshake(&registry_sync_message(&server)).expect("encode");
+        let message = decode_handshake(&bytes).expect("decode");
+        let config = default_registry_sync_config();
+        let remap = accept_registry_sync(&config, &client, &message).expect("remap");
+
+        assert_eq!(remap_incoming_block(&remap, server_ruby), client_ruby);
+        assert_eq!(remap_outgoing_block(&remap, client_ruby), server_ruby);
+        // Shared engine blocks and air pass through
+        assert_eq!(remap_incoming_block(&remap, BlockId(1)), BlockId(1));
+        assert_eq!(remap_incoming_block(&remap, BlockId::AIR), BlockId::AIR);
+    }
+
+    #[test]
+    fn test_unknown_blocks_degrade_to_placeholder() {
+        let server = registry_with(&["mod:ruby", "mod:mithril"]);
+        let client = registry_with(&["mod:ruby", "mod:tin"]);
+        let config = RegistrySyncConfig {
+            placeholder: BlockId(2),
+        };
+        let remap = build_block_id_remap(&config, &client, &build_registry_table(&server));
+
+        let mithril = server.get_id("mod:mithril").expect("mithril");
+        let tin = client.get_id("mod:tin").expect("tin");
+        let mut blocks = vec![mithril, BlockId::AIR];
+        remap_incoming_blocks(&remap, &mut blocks);
+        assert_eq!(blocks, vec![BlockId(2), BlockId::AIR]);
+        assert_eq!(remap_outgoing_block(&remap, tin), BlockId(2));
+        assert_eq!(remap.unknown_server_blocks, vec!["mod:mithril".to_string()]);
+        assert_eq!(remap.missing_on_server, vec!["mod:tin".to_string()]);
+
+        assert!(is_identity_remap(&build_block_id_remap(
+            &config,
+            &server,
+            &build_registry_table(&server)
+        )));
+    }
+}
@@ -22,6 +22,10 @@ pub struct PanicTelemetry {
     pub message: String,
     pub backtrace: String,
     pub panic_count: usize,
+    /// Most recent log records leading up to the panic
+    pub recent_logs: Vec<String>,
+    /// Feature flag values at the time of the panic
+    pub feature_flags: Vec<String>,
 }
 
 impl PanicTelemetry {
@@ -54,6 +58,10 @@ impl PanicTelemetry {
             message,
             backtrace,
             panic_count,
+            recent_logs: crate::logging::crash_report_log_lines(
+                crate::constants::logging::CRASH_REPORT_ENTRIES,
+            ),
+            feature_flags: crate::feature_flags::crash_report_feature_flags(),
         }
     }
 
@@ -73,6 +81,18 @@ impl PanicTelemetry {
         writeln!(file, "Location: {}", self.location)?;
         writeln!(file, "Message: {}", self.message)?;
         writeln!(file, "Backtrace:\n{}", self.backtrace)?;
+        if !self.feature_flags.is_empty() {
+            writeln!(file, "Feature flags:")?;
+            for line in &self.feature_flags {
+                writeln!(file, "  {}", line)?;
+            }
+        }
+        if !self.recent_logs.is_empty() {
+            writeln!(file, "Recent log ({} records):", self.recent_logs.len())?;
+            for line in &self.recent_logs {
+                writeln!(file, "  {}", line)?;
+            }
+        }
         writeln!(file, "================\n")?;
 
         file.flush()?;
@@ -106,22 +126,25 @@ pub fn install_panic_handler() {
 
         // Log to file
         if let Err(e) = telemetry.write_to_log(&log_path) {
-            eprintln!("Failed to write panic log: {}", e);
+            eprintln!("{}", crate::tr!("engine.panic.log_write_failed", error = e));
         }
 
         // Send to monitoring
         telemetry.send_to_monitoring();
 
         // Print to stderr for immediate visibility
-        eprintln!("\n💥 Hearth Engine Panic! 💥");
-        eprintln!("This should never happen in production!");
-        eprintln!("Please report this issue with the panic log.");
-        eprintln!("Log location: {}", log_path.display());
+        eprintln!("\n{}", crate::tr!("engine.panic.title"));
+        eprintln!("{}", crate::tr!("engine.panic.unexpected"));
+        eprintln!("{}", crate::tr!("engine.panic.report"));
+        eprintln!(
+            "{}",
+            crate::tr!("engine.panic.log_location", path = log_path.display())
+        );
 
         // Call the default panic handler to maintain normal panic behavior
         // This is important for test frameworks and debugging
         if std::env::var("RUST_BACKTRACE").is_err() {
-            eprintln!("\nHint: Set RUST_BACKTRACE=1 for more detailed backtrace");
+            eprintln!("\n{}", crate::tr!("engine.panic.backtrace_hint"));
         }
 
         // In debug builds, also print the full backtrace
@@ -175,6 +198,8 @@ mod tests {
             message: "test panic".to_string(),
             backtrace: "backtrace here".to_string(),
             panic_count: 1,
+            recent_logs: vec!["12:00:00.000 INFO  world: loaded".to_string()],
+            feature_flags: vec!["shadows=on (default)".to_string()],
         };
 
         assert!(telemetry.location.contains("test.rs"));
@@ -1,9 +1,12 @@
 use crate::gpu::error_recovery::{GpuErrorRecovery, GpuRecoveryError, GpuResultExt};
+use crate::memory::{
+    create_tracked_buffer, create_tracked_buffer_init, register_global_gpu_owner,
+    GpuAllocationGuard, GpuOwnerGuard,
+};
 use anyhow::{anyhow, Result};
 use glam::Vec3;
 use std::sync::Arc;
 use std::time::Duration;
-use wgpu::util::DeviceExt;
 
 use crate::particles::particle_data::MAX_PARTICLES;
 use crate::particles::{ParticleGPUData, ParticleType, particle_type_to_id};
@@ -36,8 +39,14 @@ pub struct GpuParticleSystem {
     render_data: Vec<ParticleGPUData>,
     staging_buffer: wgpu::Buffer,
 
+    // Particle, emitter, spawn queue, params, staging and spawn count buffers
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
+
     // System state
     max_particles: u32,
+    /// Live particle cap set by adaptive quality (`ParticleBuffers::particle_budget`)
+    particle_budget: u32,
     active_particles: u32,
     emitter_count: u32,
     next_emitter_id: u64,
@@ -103,35 +112,43 @@ impl GpuParticleSystem {
         let max_particles = (max_particles as u32).min(MAX_PARTICLES as u32);
 
         // Create GPU buffers
-        let particle_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let gpu_owner = register_global_gpu_owner("particles");
+        let mut gpu_allocations = Vec::new();
+        let mut create_buffer = |descriptor: &wgpu::BufferDescriptor| {
+            let (buffer, guard) = create_tracked_buffer(&device, &gpu_owner, descriptor);
+            gpu_allocations.push(guard);
+            buffer
+        };
+
+        let particle_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Particle Buffer"),
             size: (std::mem::size_of::<GpuParticleData>() * max_particles as usize) as u64,
             usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
-        let emitter_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let emitter_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Emitter Buffer"),
             size: (std::mem::size_of::<GpuEmitterData>() * 128) as u64,
             usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
 
-        let spawn_queue_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let spawn_queue_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Spawn Queue Buffer"),
             size: (std::mem::size_of::<GpuParticleData>() * 1024) as u64,
             usage: wgpu::BufferUsages::STORAGE,
             mapped_at_creation: false,
         });
 
-        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let params_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Params Buffer"),
             size: std::mem::size_of::<SimParams>() as u64,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
 
-        let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let staging_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Particle Staging Buffer"),
             size: (std::mem::size_of::<GpuParticleData>() * max_particles as usize) as u64,
             usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
@@ -261,11 +278,16 @@ impl GpuParticleSystem {
         });
 
         // Create spawn counter buffer
-        let spawn_count_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Spawn Count Buffer"),
-            contents: bytemuck::cast_slice(&[0u32]),
-            usage: wgpu::BufferUsages::STORAGE,
-        });
+        let (spawn_count_buffer, spawn_count_allocation) = create_tracked_buffer_init(
+            &device,
+            &gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Spawn Count Buffer"),
+                contents: bytemuck::cast_slice(&[0u32]),
+                usage: wgpu::BufferUsages::STORAGE,
+            },
+        );
+        gpu_allocations.push(spawn_count_allocation);
 
         let spawn_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Particle Spawn Bind Group"),
@@ -300,7 +322,10 @@ impl GpuParticleSystem {
             spawn_bind_group,
             render_data: Vec::with_capacity(max_particles as usize),
             staging_buffer,
+            gpu_allocations,
+            gpu_owner,
             max_particles,
+            particle_budget: max_particles,
             active_particles: 0,
             emitter_count: 0,
             next_emitter_id: 0,
@@ -336,8 +361,8 @@ impl GpuParticleSystem {
 
         let encoder = safe_encoder.encoder()?;
 
-        // Spawn new particles from emitters
-        if self.emitter_count > 0 {
+        // Spawn new particles from emitters while under the budget
+        if self.emitter_count > 0 && self.active_particles < self.particle_budget {
             let mut spawn_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Particle Spawn Pass"),
                 timestamp_writes: None,
@@ -461,4 +486,15 @@ impl GpuParticleSystem {
         self.active_particles as usize
     }
 
+    /// Cap the live particles; particles over a lowered budget are dropped
+    pub fn set_particle_budget(&mut self, budget: u32) {
+        self.particle_budget = budget.min(self.max_particles);
+        self.active_particles = self.active_particles.min(self.particle_budget);
+    }
+
+    /// Current live particle cap
+    pub fn particle_budget(&self) -> u32 {
+        self.particle_budget
+    }
+
 }
@@ -1,5 +1,5 @@
 use glam::Vec3;
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
 use crate::particles::particle_data::{EmitterData, ParticleData, remove_particle_swap};
 use crate::{BlockId, VoxelPos, World};
@@ -269,15 +269,16 @@ fn temperature_to_color(temp: f32) -> (f32, f32, f32) {
     }
 }
 
-/// Update emitters and spawn new particles
+/// Update emitters and spawn new particles, drawing spawn positions and
+/// velocities from `rng` (e.g. a fork of the world's particle stream)
 pub fn update_emitters(
     emitters: &mut EmitterData,
     particles: &mut ParticleData,
     dt: f32,
     next_id: &mut u64,
+    rng: &mut impl Rng,
 ) -> usize {
     let mut total_spawned = 0;
-    let mut rng = thread_rng();
 
     // Update each emitter
     let mut i = 0;
@@ -304,7 +305,7 @@ pub fn update_emitters(
             }
 
             // Generate spawn position based on shape
-            let spawn_pos = generate_spawn_position(emitters, i, &mut rng);
+            let spawn_pos = generate_spawn_position(emitters, i, rng);
 
             // Generate velocity
             let base_vel = Vec3::new(
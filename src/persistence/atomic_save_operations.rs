@@ -1,2 +1,83 @@
-//! Atomic Save Operations - Stub
-pub fn save_atomic() -> Result<(), String> { Ok(()) }
+//! Atomic Save Operations - Pure DOP Functions
+//!
+//! Saves run on the thread pool so disk I/O never blocks the frame. They are
+//! never queued in the critical lane: even a critical save (player
+//! disconnect) only gets the high lane, so frame GPU submissions go first.
+
+use std::path::{Path, PathBuf};
+
+use super::chunk_serializer_data::BakedChunkLight;
+use super::chunk_serializer_operations::serialize_chunk_with_light;
+use super::save_encryption_data::SaveCipherData;
+use super::save_encryption_operations::write_save_file;
+use super::{PersistenceError, PersistenceResult, SavePriority};
+use crate::thread_pool::{
+    submit_with_priority, GpuThreadPoolData, GpuWorkloadCategory, TaskPriority,
+};
+use crate::world::data_types::ChunkData;
+
+pub fn save_atomic() -> Result<(), String> {
+    Ok(())
+}
+
+/// Thread pool lane for a save
+pub fn save_task_priority(priority: SavePriority) -> TaskPriority {
+    match priority {
+        SavePriority::Critical | SavePriority::High => TaskPriority::High,
+        SavePriority::Normal | SavePriority::Low => TaskPriority::Background,
+    }
+}
+
+/// Write `bytes` to `path` via a temporary file and rename
+pub fn write_file_atomic(path: &Path, bytes: &[u8]) -> PersistenceResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| PersistenceError::IoError(e.to_string()))?;
+    }
+
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, bytes).map_err(|e| PersistenceError::IoError(e.to_string()))?;
+    std::fs::rename(&temp_path, path).map_err(|e| PersistenceError::IoError(e.to_string()))
+}
+
+/// Serialize and write a chunk on the thread pool, sealed with `cipher`
+/// when there is one
+pub fn submit_chunk_save(
+    pool: &GpuThreadPoolData,
+    cipher: Option<SaveCipherData>,
+    chunk: ChunkData,
+    chunk_size: u32,
+    path: PathBuf,
+    priority: SavePriority,
+) -> PersistenceResult<()> {
+    submit_chunk_save_with_light(pool, cipher, chunk, None, chunk_size, path, priority)
+}
+
+/// Serialize and write a chunk with its baked light on the thread pool
+pub fn submit_chunk_save_with_light(
+    pool: &GpuThreadPoolData,
+    cipher: Option<SaveCipherData>,
+    chunk: ChunkData,
+    light: Option<BakedChunkLight>,
+    chunk_size: u32,
+    path: PathBuf,
+    priority: SavePriority,
+) -> PersistenceResult<()> {
+    submit_with_priority(
+        pool,
+        save_task_priority(priority),
+        GpuWorkloadCategory::Persistence,
+        move || {
+            let _span = crate::trace_span!(Persistence, "save_chunk");
+            let bytes = serialize_chunk_with_light(&chunk, chunk_size, light.as_ref());
+            if let Err(e) = write_save_file(cipher.as_ref(), &path, &bytes) {
+                log::error!(
+                    "[Persistence] Failed to save chunk {:?} to {}: {}",
+                    chunk.position,
+                    path.display(),
+                    e
+                );
+            }
+        },
+    )
+    .map_err(PersistenceError::SaveFailed)
+}
@@ -1,2 +1,74 @@
-//! Chunk Serializer Data - Stub
-pub struct ChunkSerializerData;
+//! Chunk Serializer Data - Pure DOP
+//!
+//! Binary chunk format:
+//! - magic "HCHK", format version (u32)
+//! - chunk position (3 x i32), chunk size (u32), last modified tick (u64)
+//! - block ids (u16 per block)
+//! - metadata entry count (u32), then (block index u32, metadata u8) pairs
+//! - version 2 and later: baked light flag (u8); when set, lighting version
+//!   (u32), block hash (u64) and one u16 per block in the
+//!   `pack_voxel_light` layout
+//!
+//! All values are little-endian. Version 1 chunks load without light.
+
+use crate::world::data_types::ChunkData;
+
+/// Format identifier at the start of every serialized chunk
+pub const CHUNK_FORMAT_MAGIC: [u8; 4] = *b"HCHK";
+
+/// Current chunk format version
+pub const CHUNK_FORMAT_VERSION: u32 = 2;
+
+/// Oldest chunk format version that still loads
+pub const CHUNK_FORMAT_MIN_VERSION: u32 = 1;
+
+/// Extension of chunk save files (`x_y_z.chunk`)
+pub const CHUNK_FILE_EXTENSION: &str = "chunk";
+
+/// Serializer settings
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkSerializerData {
+    pub version: u32,
+}
+
+impl Default for ChunkSerializerData {
+    fn default() -> Self {
+        Self {
+            version: CHUNK_FORMAT_VERSION,
+        }
+    }
+}
+
+/// Light computed for a chunk, saved so loading can skip relighting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BakedChunkLight {
+    /// `constants::lighting::BAKED_LIGHT_VERSION` at bake time
+    pub lighting_version: u32,
+    /// `chunk_block_hash` of the blocks the light was computed for
+    pub block_hash: u64,
+    /// Block and sky light per voxel (`pack_voxel_light` layout)
+    pub light: Vec<u16>,
+}
+
+/// Whether saved light can be used as-is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BakedLightStatus {
+    Valid,
+    /// Chunk was saved without light
+    Missing,
+    /// Saved by a build with different lighting rules
+    VersionMismatch,
+    /// Blocks differ from the ones the light was computed for
+    BlocksChanged,
+    /// Light array does not match the chunk size
+    SizeMismatch,
+}
+
+/// A chunk read from disk with its baked light, if usable
+#[derive(Clone)]
+pub struct LoadedChunk {
+    pub chunk: ChunkData,
+    /// Present only when `light_status` is `Valid`
+    pub baked_light: Option<BakedChunkLight>,
+    pub light_status: BakedLightStatus,
+}
@@ -1,2 +1,326 @@
-//! Chunk Serializer Operations - Stub
-pub fn serialize_chunk() -> Result<Vec<u8>, String> { Ok(Vec::new()) }
+//! Chunk Serializer Operations - Pure DOP Functions
+//!
+//! Converts `ChunkData` to and from the binary chunk format, including the
+//! sparse per-block metadata so block orientation survives saves.
+//!
+//! Chunks can carry baked light: `bake_chunk_light` stamps computed light
+//! with the lighting version and a hash of the blocks, and
+//! `load_chunk_with_light` hands it back only while both still match, so
+//! loading a world relights just the chunks that changed.
+
+use super::chunk_serializer_data::{
+    BakedChunkLight, BakedLightStatus, LoadedChunk, CHUNK_FILE_EXTENSION, CHUNK_FORMAT_MAGIC,
+    CHUNK_FORMAT_MIN_VERSION, CHUNK_FORMAT_VERSION,
+};
+use super::{PersistenceError, PersistenceResult};
+use crate::constants::lighting::BAKED_LIGHT_VERSION;
+use crate::world::core::{BlockId, ChunkPos};
+use crate::world::data_types::{ChunkData, ChunkMetadata};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// File a chunk is saved to inside a world's chunk directory
+pub fn chunk_save_path(directory: &Path, chunk: ChunkPos) -> PathBuf {
+    directory.join(format!(
+        "{}_{}_{}.{}",
+        chunk.x, chunk.y, chunk.z, CHUNK_FILE_EXTENSION
+    ))
+}
+
+/// Serialize a chunk without light
+pub fn serialize_chunk(chunk: &ChunkData, chunk_size: u32) -> Vec<u8> {
+    serialize_chunk_with_light(chunk, chunk_size, None)
+}
+
+/// Serialize a chunk together with its baked light
+pub fn serialize_chunk_with_light(
+    chunk: &ChunkData,
+    chunk_size: u32,
+    light: Option<&BakedChunkLight>,
+) -> Vec<u8> {
+    let light_bytes = light.map_or(0, |l| 12 + l.light.len() * 2);
+    let mut bytes = Vec::with_capacity(
+        33 + chunk.blocks.len() * 2 + chunk.block_metadata.len() * 5 + light_bytes,
+    );
+
+    bytes.extend_from_slice(&CHUNK_FORMAT_MAGIC);
+    bytes.extend_from_slice(&CHUNK_FORMAT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&chunk.position.x.to_le_bytes());
+    bytes.extend_from_slice(&chunk.position.y.to_le_bytes());
+    bytes.extend_from_slice(&chunk.position.z.to_le_bytes());
+    bytes.extend_from_slice(&chunk_size.to_le_bytes());
+    bytes.extend_from_slice(&chunk.last_modified.to_le_bytes());
+
+    for block in &chunk.blocks {
+        bytes.extend_from_slice(&block.0.to_le_bytes());
+    }
+
+    // Sorted so identical chunks serialize identically
+    let mut entries: Vec<u32> = chunk.block_metadata.keys().copied().collect();
+    entries.sort_unstable();
+    bytes.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    for index in entries {
+        bytes.extend_from_slice(&index.to_le_bytes());
+        bytes.push(chunk.block_metadata.get(&index).copied().unwrap_or(0));
+    }
+
+    match light {
+        Some(baked) => {
+            bytes.push(1);
+            bytes.extend_from_slice(&baked.lighting_version.to_le_bytes());
+            bytes.extend_from_slice(&baked.block_hash.to_le_bytes());
+            for value in &baked.light {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        None => bytes.push(0),
+    }
+
+    bytes
+}
+
+/// FNV-1a hash of a chunk's blocks and metadata; light is valid only for
+/// the blocks it was computed from
+pub fn chunk_block_hash(chunk: &ChunkData) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET;
+    let mut feed = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+    for block in &chunk.blocks {
+        feed(&block.0.to_le_bytes());
+    }
+    let mut indices: Vec<u32> = chunk.block_metadata.keys().copied().collect();
+    indices.sort_unstable();
+    for index in indices {
+        feed(&index.to_le_bytes());
+        feed(&[chunk.block_metadata.get(&index).copied().unwrap_or(0)]);
+    }
+    hash
+}
+
+/// Stamp light computed for `chunk` (values in the `pack_voxel_light` layout)
+pub fn bake_chunk_light(chunk: &ChunkData, light: Vec<u16>) -> BakedChunkLight {
+    BakedChunkLight {
+        lighting_version: BAKED_LIGHT_VERSION,
+        block_hash: chunk_block_hash(chunk),
+        light,
+    }
+}
+
+/// Check saved light against the current lighting version and the chunk's blocks
+pub fn validate_baked_light(chunk: &ChunkData, baked: &BakedChunkLight) -> BakedLightStatus {
+    if baked.lighting_version != BAKED_LIGHT_VERSION {
+        BakedLightStatus::VersionMismatch
+    } else if baked.light.len() != chunk.blocks.len() {
+        BakedLightStatus::SizeMismatch
+    } else if baked.block_hash != chunk_block_hash(chunk) {
+        BakedLightStatus::BlocksChanged
+    } else {
+        BakedLightStatus::Valid
+    }
+}
+
+/// Little-endian reader shared by the binary persistence formats
+pub(super) struct ByteReader<'a> {
+    pub(super) bytes: &'a [u8],
+    pub(super) offset: usize,
+}
+
+impl ByteReader<'_> {
+    pub(super) fn take<const N: usize>(&mut self) -> PersistenceResult<[u8; N]> {
+        let end = self.offset + N;
+        let slice = self.bytes.get(self.offset..end).ok_or_else(|| {
+            PersistenceError::CorruptedData(format!(
+                "Data truncated at byte {} (length {})",
+                self.offset,
+                self.bytes.len()
+            ))
+        })?;
+        self.offset = end;
+        let mut out = [0u8; N];
+        out.copy_from_slice(slice);
+        Ok(out)
+    }
+
+    pub(super) fn u16(&mut self) -> PersistenceResult<u16> {
+        Ok(u16::from_le_bytes(self.take()?))
+    }
+
+    pub(super) fn u32(&mut self) -> PersistenceResult<u32> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    pub(super) fn i32(&mut self) -> PersistenceResult<i32> {
+        Ok(i32::from_le_bytes(self.take()?))
+    }
+
+    pub(super) fn take_vec(&mut self, len: usize) -> PersistenceResult<Vec<u8>> {
+        let end = self.offset.saturating_add(len);
+        let slice = self.bytes.get(self.offset..end).ok_or_else(|| {
+            PersistenceError::CorruptedData(format!(
+                "Data truncated at byte {} (length {})",
+                self.offset,
+                self.bytes.len()
+            ))
+        })?;
+        self.offset = end;
+        Ok(slice.to_vec())
+    }
+}
+
+/// Deserialize a chunk written by `serialize_chunk`, ignoring any baked light
+pub fn deserialize_chunk(bytes: &[u8]) -> PersistenceResult<ChunkData> {
+    load_chunk_with_light(bytes).map(|loaded| loaded.chunk)
+}
+
+/// Deserialize a chunk and keep its baked light if it is still valid.
+/// With valid light the chunk is marked as not needing a lighting update.
+pub fn load_chunk_with_light(bytes: &[u8]) -> PersistenceResult<LoadedChunk> {
+    let mut reader = ByteReader { bytes, offset: 0 };
+
+    if reader.take::<4>()? != CHUNK_FORMAT_MAGIC {
+        return Err(PersistenceError::CorruptedData(
+            "Missing chunk format magic".to_string(),
+        ));
+    }
+    let version = reader.u32()?;
+    if !(CHUNK_FORMAT_MIN_VERSION..=CHUNK_FORMAT_VERSION).contains(&version) {
+        return Err(PersistenceError::VersionMismatch {
+            expected: CHUNK_FORMAT_VERSION.to_string(),
+            found: version.to_string(),
+        });
+    }
+
+    let position = ChunkPos::new(reader.i32()?, reader.i32()?, reader.i32()?);
+    let chunk_size = reader.u32()?;
+    let last_modified = u64::from_le_bytes(reader.take()?);
+
+    let block_count = (chunk_size as usize).pow(3);
+    let mut blocks = Vec::with_capacity(block_count);
+    for _ in 0..block_count {
+        blocks.push(BlockId(u16::from_le_bytes(reader.take()?)));
+    }
+
+    let entry_count = reader.u32()?;
+    let mut block_metadata = HashMap::with_capacity(entry_count as usize);
+    for _ in 0..entry_count {
+        let index = reader.u32()?;
+        let [metadata] = reader.take::<1>()?;
+        if index as usize >= block_count {
+            return Err(PersistenceError::CorruptedData(format!(
+                "Metadata index {} out of range for chunk size {}",
+                index, chunk_size
+            )));
+        }
+        block_metadata.insert(index, metadata);
+    }
+
+    let saved_light = if version >= 2 && reader.take::<1>()? == [1] {
+        let lighting_version = reader.u32()?;
+        let block_hash = u64::from_le_bytes(reader.take()?);
+        let mut light = Vec::with_capacity(block_count);
+        for _ in 0..block_count {
+            light.push(reader.u16()?);
+        }
+        Some(BakedChunkLight {
+            lighting_version,
+            block_hash,
+            light,
+        })
+    } else {
+        None
+    };
+
+    let is_empty = blocks.iter().all(|block| *block == BlockId::AIR);
+    let mut chunk = ChunkData {
+        position,
+        blocks,
+        block_metadata,
+        flags: ChunkMetadata {
+            is_generated: true,
+            is_dirty: false,
+            is_empty,
+            needs_lighting_update: true,
+        },
+        last_modified,
+    };
+
+    let light_status = match &saved_light {
+        Some(baked) => validate_baked_light(&chunk, baked),
+        None => BakedLightStatus::Missing,
+    };
+    let baked_light = saved_light.filter(|_| light_status == BakedLightStatus::Valid);
+    chunk.flags.needs_lighting_update = baked_light.is_none();
+
+    Ok(LoadedChunk {
+        chunk,
+        baked_light,
+        light_status,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHUNK_SIZE: u32 = 4;
+
+    fn test_chunk() -> ChunkData {
+        let mut chunk = ChunkData::new(ChunkPos::new(2, -1, 5), CHUNK_SIZE);
+        chunk.blocks[3] = BlockId(9);
+        chunk.block_metadata.insert(3, 2);
+        chunk
+    }
+
+    #[test]
+    fn test_baked_light_round_trip() {
+        let chunk = test_chunk();
+        let light: Vec<u16> = (0..chunk.blocks.len() as u16).collect();
+        let baked = bake_chunk_light(&chunk, light);
+        let bytes = serialize_chunk_with_light(&chunk, CHUNK_SIZE, Some(&baked));
+
+        let loaded = load_chunk_with_light(&bytes).expect("load");
+        assert_eq!(loaded.light_status, BakedLightStatus::Valid);
+        assert_eq!(loaded.baked_light, Some(baked));
+        assert!(!loaded.chunk.flags.needs_lighting_update);
+        assert_eq!(loaded.chunk.block_metadata.get(&3), Some(&2));
+    }
+
+    #[test]
+    fn test_stale_light_is_dropped() {
+        let chunk = test_chunk();
+        let mut baked = bake_chunk_light(&chunk, vec![0; chunk.blocks.len()]);
+
+        let mut edited = chunk.clone();
+        edited.blocks[0] = BlockId(1);
+        let bytes = serialize_chunk_with_light(&edited, CHUNK_SIZE, Some(&baked));
+        let loaded = load_chunk_with_light(&bytes).expect("load");
+        assert_eq!(loaded.light_status, BakedLightStatus::BlocksChanged);
+        assert!(loaded.baked_light.is_none());
+        assert!(loaded.chunk.flags.needs_lighting_update);
+
+        baked.lighting_version += 1;
+        let bytes = serialize_chunk_with_light(&chunk, CHUNK_SIZE, Some(&baked));
+        let loaded = load_chunk_with_light(&bytes).expect("load");
+        assert_eq!(loaded.light_status, BakedLightStatus::VersionMismatch);
+    }
+
+    #[test]
+    fn test_version_one_chunks_still_load() {
+        let chunk = test_chunk();
+        let mut bytes = serialize_chunk(&chunk, CHUNK_SIZE);
+        // Version 1 had no light flag
+        bytes[4..8].copy_from_slice(&1u32.to_le_bytes());
+        bytes.pop();
+
+        let loaded = load_chunk_with_light(&bytes).expect("load");
+        assert_eq!(loaded.light_status, BakedLightStatus::Missing);
+        assert_eq!(loaded.chunk.blocks, chunk.blocks);
+    }
+}
@@ -7,9 +7,14 @@ pub mod chunk_serializer_data;
 pub mod compression_data;
 pub mod metadata_data;
 pub mod migration_data;
+pub mod modification_log_data;
 pub mod network_validator_data;
+pub mod save_encryption_data;
+pub mod schematic_data;
 pub mod state_validator_data;
+pub mod world_diff_data;
 pub mod world_save_data;
+pub mod world_slot_data;
 
 // Operations modules
 pub mod atomic_save_operations;
@@ -18,20 +23,82 @@ pub mod chunk_serializer_operations;
 pub mod compression_operations;
 pub mod metadata_operations;
 pub mod migration_operations;
+pub mod modification_log_operations;
 pub mod network_validator_operations;
+pub mod save_encryption_operations;
+pub mod schematic_operations;
 pub mod state_validator_operations;
+pub mod world_diff_operations;
 pub mod world_save_operations;
+pub mod world_slot_operations;
 
 // Simple re-exports
 pub use atomic_save_data::AtomicSaveData;
+pub use atomic_save_operations::{
+    save_task_priority, submit_chunk_save, submit_chunk_save_with_light, write_file_atomic,
+};
 pub use backup_data::BackupData;
-pub use chunk_serializer_data::ChunkSerializerData;
+pub use chunk_serializer_data::{
+    BakedChunkLight, BakedLightStatus, ChunkSerializerData, LoadedChunk, CHUNK_FORMAT_MAGIC,
+    CHUNK_FORMAT_MIN_VERSION, CHUNK_FORMAT_VERSION,
+};
+pub use chunk_serializer_operations::{
+    bake_chunk_light, chunk_block_hash, chunk_save_path, deserialize_chunk, load_chunk_with_light,
+    serialize_chunk, serialize_chunk_with_light, validate_baked_light,
+};
 pub use compression_data::CompressionData;
 pub use metadata_data::MetadataData;
 pub use migration_data::MigrationData;
+pub use modification_log_data::{
+    BlockModificationRecord, JournalReplay, ModificationLogConfig, ModificationLogData,
+    ModificationLogStats, RegionJournalData, RegionPos, JOURNAL_EXTENSION, JOURNAL_MAGIC,
+    JOURNAL_VERSION,
+};
+pub use modification_log_operations::{
+    apply_block_modifications, compact_region, create_modification_log, decode_journal,
+    decode_journal_file, default_modification_log_config, flush_modification_log,
+    load_chunk_with_modifications, log_block_edit, log_block_modification,
+    modification_log_flush_due, open_modification_log, read_region_journal, region_for_chunk,
+    region_journal_path, regions_needing_compaction, replay_chunk_modifications,
+};
 pub use network_validator_data::NetworkValidatorData;
+pub use save_encryption_data::{
+    OpenedSaveFrames, SaveCipherData, SaveEncryptionKey, SaveEncryptionMigration,
+    SEALED_FRAME_HEADER_SIZE, SEALED_SAVE_MAGIC, SEALED_SAVE_VERSION,
+};
+pub use save_encryption_operations::{
+    create_save_cipher, default_save_encryption_filter, is_sealed_save, migrate_save_encryption,
+    open_save_frames, open_save_payload, read_save_file, save_encryption_key_from_bytes,
+    seal_save_payload, write_save_file,
+};
+pub use schematic_data::{
+    SchematicBlockEntity, SchematicData, SchematicImport, SchematicPaletteEntry,
+    SCHEMATIC_EXTENSION, SCHEMATIC_MAGIC, SCHEMATIC_VERSION,
+};
+pub use schematic_operations::{
+    decode_schematic, encode_schematic, export_region, import_schematic, paste_schematic,
+    read_schematic, schematic_from_region, write_schematic,
+};
 pub use state_validator_data::StateValidatorData;
+pub use world_diff_data::{
+    BlockDelta, ChunkDiff, EntityChange, FieldChange, FileChange, FileChangeKind, SnapshotEntity,
+    SnapshotFiles, WorldDiff, WorldDiffConfig, WorldDiffSummary, WorldSnapshot,
+};
+pub use world_diff_operations::{
+    add_snapshot_entities, default_world_diff_config, diff_world_saves, diff_world_snapshots,
+    load_world_snapshot, world_diff_is_empty, world_diff_to_json, world_snapshot_from_chunks,
+    write_snapshot_entities,
+};
 pub use world_save_data::WorldSaveData;
+pub use world_slot_data::{
+    WorldSelection, WorldSlot, WorldSlotManager, WorldSlotMetadata, WorldThumbnail,
+};
+pub use world_slot_operations::{
+    create_world_slot, delete_world_slot, duplicate_world_slot, find_world_slot,
+    load_world_thumbnail, open_world_slots, record_play_time, refresh_world_slots,
+    rename_world_slot, save_world_thumbnail, select_world_slot, selected_world_slot,
+    update_world_details,
+};
 
 // Error types (stubs)
 pub type PersistenceResult<T> = Result<T, PersistenceError>;
@@ -62,6 +129,14 @@ pub enum PersistenceError {
     PlayerNotFound(String),
     #[error("Capacity exceeded: {0}")]
     CapacityExceeded(String),
+    #[error("World not found: {0}")]
+    WorldNotFound(String),
+    #[error("Invalid world name: {0}")]
+    InvalidWorldName(String),
+    #[error("Encrypted save needs a key: {0}")]
+    EncryptionKeyMissing(String),
+    #[error("Wrong save encryption key: {0}")]
+    WrongEncryptionKey(String),
 }
 
 // Stub types for compatibility
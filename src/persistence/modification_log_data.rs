@@ -0,0 +1,119 @@
+//! Modification Log Data - Pure DOP
+//!
+//! Write-ahead journal of block edits. Each region of chunks has one
+//! append-only file; edits are buffered in memory and appended every few
+//! hundred milliseconds, then compacted into full chunk saves once a region
+//! has collected enough records. On load the journal is replayed over the
+//! saved chunk.
+//!
+//! Journal layout (all values little-endian):
+//! - magic "HJRN", format version (u32), region x, y, z (3 x i32)
+//! - records of `JOURNAL_RECORD_SIZE` bytes:
+//!   chunk x, y, z (3 x i32), block index (u32), block id (u16),
+//!   metadata (u8), tick (u64), CRC32 of the preceding 27 bytes (u32)
+//!
+//! Records hold absolute block values, so replaying one twice is harmless.
+//! A torn or corrupt record ends the replay; everything before it is kept.
+//!
+//! NO METHODS - just data.
+
+use super::save_encryption_data::SaveCipherData;
+use crate::world::core::{BlockId, ChunkPos};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Instant;
+
+/// Format identifier at the start of every journal
+pub const JOURNAL_MAGIC: [u8; 4] = *b"HJRN";
+
+/// Current journal format version
+pub const JOURNAL_VERSION: u32 = 1;
+
+/// File extension of region journals
+pub const JOURNAL_EXTENSION: &str = "hjrn";
+
+/// Bytes before the first record
+pub const JOURNAL_HEADER_SIZE: usize = 20;
+
+/// Bytes per record, checksum included
+pub const JOURNAL_RECORD_SIZE: usize = 31;
+
+/// Region of chunks sharing one journal file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RegionPos {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+/// One block edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockModificationRecord {
+    pub chunk: ChunkPos,
+    /// Flat index inside the chunk, same as `ChunkData::blocks`
+    pub block_index: u32,
+    pub block: BlockId,
+    /// Block metadata after the edit (0 = none)
+    pub metadata: u8,
+    /// World tick of the edit
+    pub tick: u64,
+}
+
+/// Journal settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModificationLogConfig {
+    /// Directory holding the region journals
+    pub directory: PathBuf,
+    pub region_size_chunks: i32,
+    pub flush_interval_ms: u64,
+    /// Records on disk before a region is due for compaction
+    pub compact_after_records: u64,
+    /// fsync after each flush so an OS crash keeps the edits
+    pub sync_on_flush: bool,
+}
+
+/// Journal state of one region
+#[derive(Debug, Clone)]
+pub struct RegionJournalData {
+    pub region: RegionPos,
+    /// Edits not yet appended to disk
+    pub buffered: Vec<BlockModificationRecord>,
+    pub records_on_disk: u64,
+    /// Chunks with records on disk or buffered
+    pub chunks: HashSet<ChunkPos>,
+}
+
+/// Records read back from a journal file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JournalReplay {
+    /// Region named in the header
+    pub region: RegionPos,
+    pub records: Vec<BlockModificationRecord>,
+    /// Trailing bytes dropped as torn or corrupt
+    pub discarded_bytes: usize,
+}
+
+/// Journal counters
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ModificationLogStats {
+    pub records_logged: u64,
+    pub records_flushed: u64,
+    pub bytes_flushed: u64,
+    pub flushes: u64,
+    pub compactions: u64,
+    /// Full chunk writes made by compaction
+    pub chunks_compacted: u64,
+    pub last_flush_ms: f32,
+}
+
+/// Write-ahead modification log for all regions
+#[derive(Debug, Clone)]
+pub struct ModificationLogData {
+    pub config: ModificationLogConfig,
+    /// Seals the journals and the chunk files compaction writes; `None`
+    /// keeps them plaintext
+    pub cipher: Option<SaveCipherData>,
+    pub regions: HashMap<RegionPos, RegionJournalData>,
+    pub last_flush: Option<Instant>,
+    pub stats: ModificationLogStats,
+}
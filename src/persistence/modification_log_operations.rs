@@ -0,0 +1,721 @@
+//! Modification Log Operations - Pure DOP Functions
+//!
+//! Block edits go through `log_block_edit`, which only buffers. The game
+//! calls `flush_modification_log` when `modification_log_flush_due` says so
+//! (a few times a second), which appends each region's edits to its journal
+//! in one write. `compact_region` folds a long journal into full chunk saves
+//! and deletes it; the chunk files are written first, so a crash in between
+//! only means the journal is replayed again on load.
+//!
+//! When the log has a save cipher each flush appends one sealed frame
+//! instead of raw records, and a plaintext journal left from before is
+//! rewritten sealed on its next flush.
+
+use super::atomic_save_operations::write_file_atomic;
+use super::chunk_serializer_data::{BakedLightStatus, LoadedChunk};
+use super::chunk_serializer_operations::{
+    deserialize_chunk, load_chunk_with_light, serialize_chunk, validate_baked_light, ByteReader,
+};
+use super::modification_log_data::{
+    BlockModificationRecord, JournalReplay, ModificationLogConfig, ModificationLogData,
+    ModificationLogStats, RegionJournalData, RegionPos, JOURNAL_EXTENSION, JOURNAL_HEADER_SIZE,
+    JOURNAL_MAGIC, JOURNAL_RECORD_SIZE, JOURNAL_VERSION,
+};
+use super::save_encryption_data::SaveCipherData;
+use super::save_encryption_operations::{
+    is_sealed_save, open_save_frames, open_save_payload, read_save_file, seal_save_payload,
+    write_save_file,
+};
+use super::{PersistenceError, PersistenceResult};
+use crate::constants::persistence_constants::{
+    JOURNAL_COMPACT_RECORDS, JOURNAL_FLUSH_INTERVAL_MS, JOURNAL_REGION_SIZE_CHUNKS,
+};
+use crate::world::core::{
+    chunk_layout_for_size, floor_div, voxel_chunk_index, BlockId, ChunkPos, VoxelPos,
+};
+use crate::world::data_types::ChunkData;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Default settings with journals stored in `directory`
+pub fn default_modification_log_config(directory: PathBuf) -> ModificationLogConfig {
+    ModificationLogConfig {
+        directory,
+        region_size_chunks: JOURNAL_REGION_SIZE_CHUNKS,
+        flush_interval_ms: JOURNAL_FLUSH_INTERVAL_MS,
+        compact_after_records: JOURNAL_COMPACT_RECORDS,
+        sync_on_flush: true,
+    }
+}
+
+/// Create an empty log (no journals read from disk) that seals its files
+/// with `cipher`
+pub fn create_modification_log(
+    config: ModificationLogConfig,
+    cipher: Option<SaveCipherData>,
+) -> ModificationLogData {
+    ModificationLogData {
+        config,
+        cipher,
+        regions: HashMap::new(),
+        last_flush: None,
+        stats: ModificationLogStats::default(),
+    }
+}
+
+/// Create a log and pick up journals left by a previous run, so they are
+/// replayed on load and eventually compacted
+pub fn open_modification_log(
+    config: ModificationLogConfig,
+    cipher: Option<SaveCipherData>,
+) -> PersistenceResult<ModificationLogData> {
+    let mut log = create_modification_log(config, cipher);
+    let entries = match std::fs::read_dir(&log.config.directory) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(log),
+        Err(e) => return Err(PersistenceError::IoError(e.to_string())),
+    };
+
+    for entry in entries {
+        let path = entry
+            .map_err(|e| PersistenceError::IoError(e.to_string()))?
+            .path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(JOURNAL_EXTENSION) {
+            continue;
+        }
+        let bytes = std::fs::read(&path).map_err(|e| PersistenceError::IoError(e.to_string()))?;
+        let replay = decode_journal_file(log.cipher.as_ref(), &bytes)?;
+        if replay.discarded_bytes > 0 {
+            log::warn!(
+                "[Persistence] Journal {} ends with {} torn bytes; keeping {} records",
+                path.display(),
+                replay.discarded_bytes,
+                replay.records.len()
+            );
+        }
+        let journal = region_journal(&mut log, replay.region);
+        journal.records_on_disk = replay.records.len() as u64;
+        journal.chunks = replay.records.iter().map(|r| r.chunk).collect();
+    }
+
+    log::info!(
+        "[Persistence] Modification log opened with {} region journals",
+        log.regions.len()
+    );
+    Ok(log)
+}
+
+/// Region containing a chunk
+pub fn region_for_chunk(chunk: ChunkPos, region_size_chunks: i32) -> RegionPos {
+    RegionPos {
+        x: floor_div(chunk.x, region_size_chunks),
+        y: floor_div(chunk.y, region_size_chunks),
+        z: floor_div(chunk.z, region_size_chunks),
+    }
+}
+
+/// Path of a region's journal
+pub fn region_journal_path(config: &ModificationLogConfig, region: RegionPos) -> PathBuf {
+    config.directory.join(format!(
+        "r.{}.{}.{}.{}",
+        region.x, region.y, region.z, JOURNAL_EXTENSION
+    ))
+}
+
+fn region_journal(log: &mut ModificationLogData, region: RegionPos) -> &mut RegionJournalData {
+    log.regions
+        .entry(region)
+        .or_insert_with(|| RegionJournalData {
+            region,
+            buffered: Vec::new(),
+            records_on_disk: 0,
+            chunks: HashSet::new(),
+        })
+}
+
+/// Journal header for a region
+pub fn encode_journal_header(region: RegionPos) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(JOURNAL_HEADER_SIZE);
+    bytes.extend_from_slice(&JOURNAL_MAGIC);
+    bytes.extend_from_slice(&JOURNAL_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&region.x.to_le_bytes());
+    bytes.extend_from_slice(&region.y.to_le_bytes());
+    bytes.extend_from_slice(&region.z.to_le_bytes());
+    bytes
+}
+
+/// Append one record with its checksum
+pub fn encode_modification_record(record: &BlockModificationRecord, bytes: &mut Vec<u8>) {
+    let start = bytes.len();
+    bytes.extend_from_slice(&record.chunk.x.to_le_bytes());
+    bytes.extend_from_slice(&record.chunk.y.to_le_bytes());
+    bytes.extend_from_slice(&record.chunk.z.to_le_bytes());
+    bytes.extend_from_slice(&record.block_index.to_le_bytes());
+    bytes.extend_from_slice(&record.block.0.to_le_bytes());
+    bytes.push(record.metadata);
+    bytes.extend_from_slice(&record.tick.to_le_bytes());
+    let checksum = crc32fast::hash(&bytes[start..]);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+}
+
+fn decode_modification_record(bytes: &[u8]) -> PersistenceResult<BlockModificationRecord> {
+    let (payload, checksum) = bytes.split_at(JOURNAL_RECORD_SIZE - 4);
+    if crc32fast::hash(payload).to_le_bytes() != checksum {
+        return Err(PersistenceError::CorruptedData(
+            "Journal record checksum mismatch".to_string(),
+        ));
+    }
+
+    let mut reader = ByteReader {
+        bytes: payload,
+        offset: 0,
+    };
+    Ok(BlockModificationRecord {
+        chunk: ChunkPos::new(reader.i32()?, reader.i32()?, reader.i32()?),
+        block_index: reader.u32()?,
+        block: BlockId(reader.u16()?),
+        metadata: reader.take::<1>()?[0],
+        tick: u64::from_le_bytes(reader.take()?),
+    })
+}
+
+/// Decode a journal. A bad header is an error; a torn or corrupt record
+/// stops the replay and the rest is reported as discarded.
+pub fn decode_journal(bytes: &[u8]) -> PersistenceResult<JournalReplay> {
+    let mut reader = ByteReader { bytes, offset: 0 };
+    if reader.take::<4>()? != JOURNAL_MAGIC {
+        return Err(PersistenceError::CorruptedData(
+            "Missing journal magic".to_string(),
+        ));
+    }
+    let version = reader.u32()?;
+    if version != JOURNAL_VERSION {
+        return Err(PersistenceError::VersionMismatch {
+            expected: JOURNAL_VERSION.to_string(),
+            found: version.to_string(),
+        });
+    }
+    let mut replay = JournalReplay {
+        region: RegionPos {
+            x: reader.i32()?,
+            y: reader.i32()?,
+            z: reader.i32()?,
+        },
+        ..JournalReplay::default()
+    };
+    let mut offset = JOURNAL_HEADER_SIZE;
+    while let Some(record_bytes) = bytes.get(offset..offset + JOURNAL_RECORD_SIZE) {
+        match decode_modification_record(record_bytes) {
+            Ok(record) => replay.records.push(record),
+            Err(_) => break,
+        }
+        offset += JOURNAL_RECORD_SIZE;
+    }
+    replay.discarded_bytes = bytes.len() - offset;
+
+    Ok(replay)
+}
+
+/// Decode a journal file as stored on disk, opening sealed frames first.
+/// A torn or damaged final frame counts as discarded bytes.
+pub fn decode_journal_file(
+    cipher: Option<&SaveCipherData>,
+    bytes: &[u8],
+) -> PersistenceResult<JournalReplay> {
+    if !is_sealed_save(bytes) {
+        return decode_journal(bytes);
+    }
+    let opened = open_save_frames(cipher, bytes)?;
+    let mut replay = decode_journal(&opened.plaintext)?;
+    replay.discarded_bytes += opened.discarded_bytes;
+    Ok(replay)
+}
+
+/// Read a region's journal; an absent file is an empty replay
+pub fn read_region_journal(
+    log: &ModificationLogData,
+    region: RegionPos,
+) -> PersistenceResult<JournalReplay> {
+    let path = region_journal_path(&log.config, region);
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(JournalReplay::default()),
+        Err(e) => return Err(PersistenceError::IoError(e.to_string())),
+    };
+    let replay = decode_journal_file(log.cipher.as_ref(), &bytes)?;
+    if replay.region != region {
+        return Err(PersistenceError::CorruptedData(format!(
+            "Journal {} belongs to region {:?}",
+            path.display(),
+            replay.region
+        )));
+    }
+    Ok(replay)
+}
+
+/// Buffer one edit for the next flush
+pub fn log_block_modification(log: &mut ModificationLogData, record: BlockModificationRecord) {
+    let region = region_for_chunk(record.chunk, log.config.region_size_chunks);
+    let journal = region_journal(log, region);
+    journal.chunks.insert(record.chunk);
+    journal.buffered.push(record);
+    log.stats.records_logged += 1;
+}
+
+/// Buffer an edit at a world position (as applied by `set_block_with_metadata`)
+pub fn log_block_edit(
+    log: &mut ModificationLogData,
+    position: VoxelPos,
+    block: BlockId,
+    metadata: u8,
+    tick: u64,
+    chunk_size: u32,
+) {
+    let (chunk, block_index) = voxel_chunk_index(chunk_layout_for_size(chunk_size), position);
+    log_block_modification(
+        log,
+        BlockModificationRecord {
+            chunk,
+            block_index,
+            block,
+            metadata,
+            tick,
+        },
+    );
+}
+
+/// True when buffered edits exist and the flush interval has passed
+pub fn modification_log_flush_due(log: &ModificationLogData, now: Instant) -> bool {
+    let has_buffered = log.regions.values().any(|j| !j.buffered.is_empty());
+    has_buffered
+        && log.last_flush.is_none_or(|last| {
+            now.duration_since(last).as_millis() as u64 >= log.config.flush_interval_ms
+        })
+}
+
+fn journal_is_sealed(path: &Path) -> PersistenceResult<bool> {
+    let mut magic = [0u8; 4];
+    match std::fs::File::open(path) {
+        Ok(mut file) => Ok(file.read_exact(&mut magic).is_ok() && is_sealed_save(&magic)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(PersistenceError::IoError(e.to_string())),
+    }
+}
+
+fn append_sealed_region_journal(
+    config: &ModificationLogConfig,
+    journal: &RegionJournalData,
+    cipher: &SaveCipherData,
+    path: &Path,
+) -> PersistenceResult<usize> {
+    let existing = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(PersistenceError::IoError(e.to_string())),
+    };
+    let mut records = Vec::with_capacity(journal.buffered.len() * JOURNAL_RECORD_SIZE);
+    for record in &journal.buffered {
+        encode_modification_record(record, &mut records);
+    }
+
+    let previous = if is_sealed_save(&existing) {
+        let opened = open_save_frames(Some(cipher), &existing)?;
+        if opened.plaintext.len() >= JOURNAL_HEADER_SIZE {
+            // Drop a torn frame, then append this flush as one frame
+            let frame = seal_save_payload(cipher, &records)?;
+            let mut file = std::fs::OpenOptions::new()
+                .append(true)
+                .open(path)
+                .map_err(|e| PersistenceError::IoError(e.to_string()))?;
+            file.set_len(opened.valid_len as u64)
+                .map_err(|e| PersistenceError::IoError(e.to_string()))?;
+            file.write_all(&frame)
+                .map_err(|e| PersistenceError::IoError(e.to_string()))?;
+            if config.sync_on_flush {
+                file.sync_data()
+                    .map_err(|e| PersistenceError::IoError(e.to_string()))?;
+            }
+            return Ok(frame.len());
+        }
+        // The header frame itself is torn: start over
+        Vec::new()
+    } else if existing.len() >= JOURNAL_HEADER_SIZE {
+        // Plaintext journal from before encryption was enabled
+        decode_journal(&existing)?.records
+    } else {
+        Vec::new()
+    };
+
+    let mut plaintext = encode_journal_header(journal.region);
+    for record in &previous {
+        encode_modification_record(record, &mut plaintext);
+    }
+    plaintext.extend_from_slice(&records);
+    let frame = seal_save_payload(cipher, &plaintext)?;
+    write_file_atomic(path, &frame)?;
+    Ok(frame.len())
+}
+
+fn append_region_journal(
+    config: &ModificationLogConfig,
+    cipher: Option<&SaveCipherData>,
+    journal: &RegionJournalData,
+) -> PersistenceResult<usize> {
+    let path = region_journal_path(config, journal.region);
+    std::fs::create_dir_all(&config.directory)
+        .map_err(|e| PersistenceError::IoError(e.to_string()))?;
+    if let Some(cipher) = cipher {
+        return append_sealed_region_journal(config, journal, cipher, &path);
+    }
+    if journal_is_sealed(&path)? {
+        return Err(PersistenceError::EncryptionKeyMissing(format!(
+            "journal {} is encrypted",
+            path.display()
+        )));
+    }
+
+    let mut bytes =
+        Vec::with_capacity(JOURNAL_HEADER_SIZE + journal.buffered.len() * JOURNAL_RECORD_SIZE);
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| PersistenceError::IoError(e.to_string()))?;
+    let existing = file
+        .metadata()
+        .map_err(|e| PersistenceError::IoError(e.to_string()))?
+        .len() as usize;
+
+    if existing < JOURNAL_HEADER_SIZE {
+        // New file, or a crash before the header landed: start over
+        file.set_len(0)
+            .map_err(|e| PersistenceError::IoError(e.to_string()))?;
+        bytes.extend_from_slice(&encode_journal_header(journal.region));
+    } else if !(existing - JOURNAL_HEADER_SIZE).is_multiple_of(JOURNAL_RECORD_SIZE) {
+        // Drop a torn tail so new records stay aligned
+        let aligned = existing - (existing - JOURNAL_HEADER_SIZE) % JOURNAL_RECORD_SIZE;
+        file.set_len(aligned as u64)
+            .map_err(|e| PersistenceError::IoError(e.to_string()))?;
+    }
+
+    for record in &journal.buffered {
+        encode_modification_record(record, &mut bytes);
+    }
+    file.write_all(&bytes)
+        .map_err(|e| PersistenceError::IoError(e.to_string()))?;
+    if config.sync_on_flush {
+        file.sync_data()
+            .map_err(|e| PersistenceError::IoError(e.to_string()))?;
+    }
+    Ok(bytes.len())
+}
+
+/// Append all buffered edits to their region journals. Returns the number
+/// of records written. Regions that fail keep their buffer for the next try.
+pub fn flush_modification_log(log: &mut ModificationLogData) -> PersistenceResult<usize> {
+    let _span = crate::trace_span!(Persistence, "flush_modification_log");
+    let start = Instant::now();
+    let mut records = 0;
+    let mut first_error = None;
+
+    for journal in log.regions.values_mut() {
+        if journal.buffered.is_empty() {
+            continue;
+        }
+        match append_region_journal(&log.config, log.cipher.as_ref(), journal) {
+            Ok(bytes) => {
+                records += journal.buffered.len();
+                journal.records_on_disk += journal.buffered.len() as u64;
+                journal.buffered.clear();
+                log.stats.bytes_flushed += bytes as u64;
+            }
+            Err(e) => {
+                log::error!(
+                    "[Persistence] Failed to flush journal for region {:?}: {}",
+                    journal.region,
+                    e
+                );
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    log.last_flush = Some(Instant::now());
+    log.stats.records_flushed += records as u64;
+    log.stats.flushes += 1;
+    log.stats.last_flush_ms = start.elapsed().as_secs_f32() * 1000.0;
+
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(records),
+    }
+}
+
+/// Apply the records for `chunk` in order. Returns how many applied.
+pub fn apply_block_modifications(
+    chunk: &mut ChunkData,
+    records: &[BlockModificationRecord],
+) -> usize {
+    let mut applied = 0;
+    for record in records.iter().filter(|r| r.chunk == chunk.position) {
+        let Some(block) = chunk.blocks.get_mut(record.block_index as usize) else {
+            continue;
+        };
+        *block = record.block;
+        if record.metadata != 0 {
+            chunk
+                .block_metadata
+                .insert(record.block_index, record.metadata);
+        } else {
+            chunk.block_metadata.remove(&record.block_index);
+        }
+        chunk.last_modified = chunk.last_modified.max(record.tick);
+        applied += 1;
+    }
+
+    if applied > 0 {
+        chunk.flags.is_empty = chunk.blocks.iter().all(|block| *block == BlockId::AIR);
+        chunk.flags.needs_lighting_update = true;
+    }
+    applied
+}
+
+/// Replay journaled and buffered edits over a freshly loaded chunk
+pub fn replay_chunk_modifications(
+    log: &ModificationLogData,
+    chunk: &mut ChunkData,
+) -> PersistenceResult<usize> {
+    let region = region_for_chunk(chunk.position, log.config.region_size_chunks);
+    let Some(journal) = log.regions.get(&region) else {
+        return Ok(0);
+    };
+    if !journal.chunks.contains(&chunk.position) {
+        return Ok(0);
+    }
+
+    let mut applied = 0;
+    if journal.records_on_disk > 0 {
+        let replay = read_region_journal(log, region)?;
+        applied += apply_block_modifications(chunk, &replay.records);
+    }
+    applied += apply_block_modifications(chunk, &journal.buffered);
+    Ok(applied)
+}
+
+/// Load a saved chunk and bring it up to date from the journal. Baked light
+/// is dropped if the replayed edits changed the chunk's blocks.
+pub fn load_chunk_with_modifications(
+    log: &ModificationLogData,
+    chunk_path: &Path,
+) -> PersistenceResult<LoadedChunk> {
+    let bytes = read_save_file(log.cipher.as_ref(), chunk_path)?;
+    let mut loaded = load_chunk_with_light(&bytes)?;
+    if replay_chunk_modifications(log, &mut loaded.chunk)? > 0 {
+        if let Some(baked) = &loaded.baked_light {
+            loaded.light_status = validate_baked_light(&loaded.chunk, baked);
+        }
+        if loaded.light_status != BakedLightStatus::Valid {
+            loaded.baked_light = None;
+            loaded.chunk.flags.needs_lighting_update = true;
+        }
+    }
+    Ok(loaded)
+}
+
+/// Regions whose journals are long enough to compact
+pub fn regions_needing_compaction(log: &ModificationLogData) -> Vec<RegionPos> {
+    log.regions
+        .values()
+        .filter(|j| j.records_on_disk + j.buffered.len() as u64 >= log.config.compact_after_records)
+        .map(|j| j.region)
+        .collect()
+}
+
+/// Fold a region's journal into full chunk saves and delete it.
+///
+/// `loaded_chunk` returns the live copy of a chunk if it is loaded (already
+/// up to date). Other chunks are read from `chunk_path`, replayed and
+/// written back. Records for chunks that have no saved base yet stay in the
+/// journal. Returns the number of chunks written.
+pub fn compact_region(
+    log: &mut ModificationLogData,
+    region: RegionPos,
+    chunk_size: u32,
+    chunk_path: impl Fn(ChunkPos) -> PathBuf,
+    mut loaded_chunk: impl FnMut(ChunkPos) -> Option<ChunkData>,
+) -> PersistenceResult<usize> {
+    let _span = crate::trace_span!(Persistence, "compact_region");
+    let Some(journal) = log.regions.get(&region) else {
+        return Ok(0);
+    };
+    let mut records = if journal.records_on_disk > 0 {
+        read_region_journal(log, region)?.records
+    } else {
+        Vec::new()
+    };
+    records.extend_from_slice(&journal.buffered);
+
+    let mut chunks: Vec<ChunkPos> = journal.chunks.iter().copied().collect();
+    chunks.sort_by_key(|c| (c.x, c.y, c.z));
+
+    let mut written = 0;
+    let mut kept = Vec::new();
+    for pos in chunks {
+        let path = chunk_path(pos);
+        let chunk = match loaded_chunk(pos) {
+            Some(chunk) => Some(chunk),
+            None => match std::fs::read(&path) {
+                Ok(bytes) => {
+                    let mut chunk =
+                        deserialize_chunk(&open_save_payload(log.cipher.as_ref(), bytes)?)?;
+                    apply_block_modifications(&mut chunk, &records);
+                    Some(chunk)
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(PersistenceError::IoError(e.to_string())),
+            },
+        };
+
+        match chunk {
+            Some(chunk) => {
+                write_save_file(
+                    log.cipher.as_ref(),
+                    &path,
+                    &serialize_chunk(&chunk, chunk_size),
+                )?;
+                written += 1;
+            }
+            None => kept.extend(records.iter().filter(|r| r.chunk == pos).copied()),
+        }
+    }
+
+    // Chunk files are on disk; only now drop the records they contain
+    let journal_path = region_journal_path(&log.config, region);
+    if kept.is_empty() {
+        match std::fs::remove_file(&journal_path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(PersistenceError::IoError(e.to_string())),
+        }
+        log.regions.remove(&region);
+    } else {
+        let mut bytes = encode_journal_header(region);
+        for record in &kept {
+            encode_modification_record(record, &mut bytes);
+        }
+        write_save_file(log.cipher.as_ref(), &journal_path, &bytes)?;
+        let journal = region_journal(log, region);
+        journal.buffered.clear();
+        journal.records_on_disk = kept.len() as u64;
+        journal.chunks = kept.iter().map(|r| r.chunk).collect();
+    }
+
+    log.stats.compactions += 1;
+    log.stats.chunks_compacted += written as u64;
+    log::info!(
+        "[Persistence] Compacted region {:?}: {} chunks written, {} records kept",
+        region,
+        written,
+        kept.len()
+    );
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CHUNK_SIZE: u32 = 4;
+
+    fn record(chunk: ChunkPos, block_index: u32, block: u16, tick: u64) -> BlockModificationRecord {
+        BlockModificationRecord {
+            chunk,
+            block_index,
+            block: BlockId(block),
+            metadata: 0,
+            tick,
+        }
+    }
+
+    #[test]
+    fn test_torn_record_ends_replay() {
+        let region = RegionPos { x: 0, y: 0, z: -1 };
+        let chunk = ChunkPos::new(1, 2, -3);
+        let mut bytes = encode_journal_header(region);
+        encode_modification_record(&record(chunk, 5, 7, 10), &mut bytes);
+        encode_modification_record(&record(chunk, 6, 8, 11), &mut bytes);
+        bytes.truncate(bytes.len() - 3);
+
+        let replay = decode_journal(&bytes).expect("valid header");
+        assert_eq!(replay.region, region);
+        assert_eq!(replay.records, vec![record(chunk, 5, 7, 10)]);
+        assert_eq!(replay.discarded_bytes, JOURNAL_RECORD_SIZE - 3);
+    }
+
+    #[test]
+    fn test_flushed_edits_replay_over_base_chunk() {
+        let dir = TempDir::new().expect("temp dir");
+        let config = ModificationLogConfig {
+            sync_on_flush: false,
+            ..default_modification_log_config(dir.path().to_path_buf())
+        };
+        let mut log = create_modification_log(config.clone(), None);
+        let pos = VoxelPos { x: -1, y: 0, z: 2 };
+        log_block_edit(&mut log, pos, BlockId(3), 2, 40, CHUNK_SIZE);
+        log_block_edit(&mut log, pos, BlockId(4), 0, 41, CHUNK_SIZE);
+        assert_eq!(flush_modification_log(&mut log).expect("flush"), 2);
+
+        // A restart sees the journal on disk
+        let reopened = open_modification_log(config, None).expect("open");
+        let mut chunk = ChunkData::new(ChunkPos::new(-1, 0, 0), CHUNK_SIZE);
+        assert_eq!(
+            replay_chunk_modifications(&reopened, &mut chunk).expect("replay"),
+            2
+        );
+        let index = 3 + 2 * CHUNK_SIZE * CHUNK_SIZE;
+        assert_eq!(chunk.blocks[index as usize], BlockId(4));
+        assert!(!chunk.block_metadata.contains_key(&index));
+        assert_eq!(chunk.last_modified, 41);
+    }
+
+    #[test]
+    fn test_compaction_writes_chunks_and_keeps_unsaved_records() {
+        let dir = TempDir::new().expect("temp dir");
+        let config = ModificationLogConfig {
+            sync_on_flush: false,
+            ..default_modification_log_config(dir.path().join("journal"))
+        };
+        let mut log = create_modification_log(config, None);
+        let saved = ChunkPos::new(0, 0, 0);
+        let unsaved = ChunkPos::new(1, 0, 0);
+        let chunk_path = |pos: ChunkPos| {
+            dir.path()
+                .join(format!("{}_{}_{}.chunk", pos.x, pos.y, pos.z))
+        };
+        write_file_atomic(
+            &chunk_path(saved),
+            &serialize_chunk(&ChunkData::new(saved, CHUNK_SIZE), CHUNK_SIZE),
+        )
+        .expect("base chunk");
+
+        log_block_modification(&mut log, record(saved, 1, 9, 5));
+        log_block_modification(&mut log, record(unsaved, 2, 9, 6));
+        flush_modification_log(&mut log).expect("flush");
+
+        let region = region_for_chunk(saved, log.config.region_size_chunks);
+        let written =
+            compact_region(&mut log, region, CHUNK_SIZE, chunk_path, |_| None).expect("compact");
+        assert_eq!(written, 1);
+
+        let bytes = std::fs::read(chunk_path(saved)).expect("chunk file");
+        let chunk = deserialize_chunk(&bytes).expect("chunk");
+        assert_eq!(chunk.blocks[1], BlockId(9));
+
+        let replay = read_region_journal(&log, region).expect("journal");
+        assert_eq!(replay.records, vec![record(unsaved, 2, 9, 6)]);
+        assert_eq!(log.regions[&region].records_on_disk, 1);
+    }
+}
@@ -0,0 +1,77 @@
+//! Save Encryption Data - Pure DOP
+//!
+//! Optional AES-256-GCM encryption of save payloads (chunks, modification
+//! journals, scoreboards) with a key the game supplies through
+//! `EngineConfig::save_encryption_key`. Sealed files are a sequence of
+//! frames, each starting with `SEALED_SAVE_MAGIC`; files without the magic
+//! are read as plaintext, so a world saved before encryption was enabled
+//! still loads and each file is sealed the next time it is written.
+//! World metadata and thumbnails stay plaintext so world lists work
+//! without the key.
+//!
+//! NO METHODS - just data.
+
+/// Prefix of every sealed frame
+pub const SEALED_SAVE_MAGIC: [u8; 4] = *b"HENC";
+
+/// Version of the sealed frame layout
+pub const SEALED_SAVE_VERSION: u8 = 1;
+
+/// Bytes of the key check stored in each frame
+pub const SAVE_KEY_CHECK_SIZE: usize = 8;
+
+/// Bytes of the GCM nonce
+pub const SAVE_NONCE_SIZE: usize = 12;
+
+/// Bytes of the GCM tag
+pub const SAVE_TAG_SIZE: usize = 16;
+
+/// Magic, version, key check, nonce and ciphertext length; also the
+/// frame's additional authenticated data
+pub const SEALED_FRAME_HEADER_SIZE: usize = 4 + 1 + SAVE_KEY_CHECK_SIZE + SAVE_NONCE_SIZE + 4;
+
+/// 256-bit key supplied by the game
+#[derive(Clone, PartialEq, Eq)]
+pub struct SaveEncryptionKey(pub [u8; 32]);
+
+impl std::fmt::Debug for SaveEncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SaveEncryptionKey(<redacted>)")
+    }
+}
+
+/// Expanded key, ready to seal and open frames
+#[derive(Clone)]
+pub struct SaveCipherData {
+    pub cipher: aes_gcm::Aes256Gcm,
+    /// Identifies the key in sealed frames without revealing it
+    pub key_check: [u8; SAVE_KEY_CHECK_SIZE],
+}
+
+impl std::fmt::Debug for SaveCipherData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SaveCipherData")
+            .field("key_check", &self.key_check)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Frames of a sealed file read up to the first torn or damaged one
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpenedSaveFrames {
+    pub plaintext: Vec<u8>,
+    /// Length of the intact frames; appends continue from here
+    pub valid_len: usize,
+    pub discarded_bytes: usize,
+}
+
+/// Outcome of `migrate_save_encryption`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SaveEncryptionMigration {
+    /// Files rewritten with the new key (or as plaintext)
+    pub files_rewritten: u32,
+    /// Files already in the requested form
+    pub files_unchanged: u32,
+    /// Files the filter left alone
+    pub files_skipped: u32,
+}
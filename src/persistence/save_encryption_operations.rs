@@ -0,0 +1,492 @@
+//! Save Encryption Operations - Pure DOP Functions
+//!
+//! `create_save_cipher` expands the game's key; the engine keeps the
+//! cipher for `EngineConfig::save_encryption_key` with its world save.
+//! Save code writes through `write_save_file` and reads through
+//! `read_save_file` / `open_save_payload`, passing that cipher (or `None`).
+//! They seal and open frames when given a cipher and pass plaintext files
+//! through either way. `migrate_save_encryption` rewrites an existing world
+//! when encryption is enabled, disabled or the key changes.
+//!
+//! The cipher is AES-256-GCM (the `aes-gcm` crate) with a random 96-bit
+//! nonce per frame.
+
+use super::atomic_save_operations::write_file_atomic;
+use super::save_encryption_data::{
+    OpenedSaveFrames, SaveCipherData, SaveEncryptionKey, SaveEncryptionMigration,
+    SAVE_KEY_CHECK_SIZE, SAVE_NONCE_SIZE, SAVE_TAG_SIZE, SEALED_FRAME_HEADER_SIZE,
+    SEALED_SAVE_MAGIC, SEALED_SAVE_VERSION,
+};
+use super::schematic_data::SCHEMATIC_EXTENSION;
+use super::{PersistenceError, PersistenceResult};
+use crate::constants::persistence_constants::{WORLD_METADATA_FILE, WORLD_THUMBNAIL_FILE};
+use aes_gcm::aead::AeadInPlace;
+use aes_gcm::aes::cipher::{Block, BlockEncrypt, KeyInit};
+use aes_gcm::aes::Aes256;
+use aes_gcm::{Aes256Gcm, Nonce, Tag};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::path::Path;
+
+/// Block encrypted to derive a key's check value
+const KEY_CHECK_BLOCK: [u8; 16] = *b"hearth-save-key\0";
+
+/// Encrypt `plaintext` in place and return the tag
+fn gcm_encrypt(
+    cipher: &SaveCipherData,
+    nonce: &[u8; SAVE_NONCE_SIZE],
+    aad: &[u8],
+    plaintext: &mut [u8],
+) -> PersistenceResult<[u8; SAVE_TAG_SIZE]> {
+    cipher
+        .cipher
+        .encrypt_in_place_detached(Nonce::from_slice(nonce), aad, plaintext)
+        .map(Into::into)
+        .map_err(|_| {
+            PersistenceError::SaveFailed(format!(
+                "{} byte save payload could not be encrypted",
+                plaintext.len()
+            ))
+        })
+}
+
+/// Check the tag and decrypt `ciphertext` in place; false (and untouched
+/// data) when the tag does not match
+fn gcm_decrypt(
+    cipher: &SaveCipherData,
+    nonce: &[u8; SAVE_NONCE_SIZE],
+    aad: &[u8],
+    ciphertext: &mut [u8],
+    tag: &[u8],
+) -> bool {
+    if tag.len() != SAVE_TAG_SIZE {
+        return false;
+    }
+    cipher
+        .cipher
+        .decrypt_in_place_detached(
+            Nonce::from_slice(nonce),
+            aad,
+            ciphertext,
+            Tag::from_slice(tag),
+        )
+        .is_ok()
+}
+
+// ============================================================================
+// KEYS
+// ============================================================================
+
+/// Key from raw bytes; AES-256 needs exactly 32
+pub fn save_encryption_key_from_bytes(bytes: &[u8]) -> PersistenceResult<SaveEncryptionKey> {
+    let key: [u8; 32] = bytes.try_into().map_err(|_| {
+        PersistenceError::WrongEncryptionKey(format!("expected 32 key bytes, got {}", bytes.len()))
+    })?;
+    Ok(SaveEncryptionKey(key))
+}
+
+/// Expand a key for sealing and opening
+pub fn create_save_cipher(key: &SaveEncryptionKey) -> SaveCipherData {
+    let mut check_block = Block::<Aes256>::from(KEY_CHECK_BLOCK);
+    Aes256::new(&key.0.into()).encrypt_block(&mut check_block);
+    let mut key_check = [0u8; SAVE_KEY_CHECK_SIZE];
+    key_check.copy_from_slice(&check_block[..SAVE_KEY_CHECK_SIZE]);
+    SaveCipherData {
+        cipher: Aes256Gcm::new(&key.0.into()),
+        key_check,
+    }
+}
+
+// ============================================================================
+// FRAMES
+// ============================================================================
+
+/// Whether `bytes` start with a sealed frame
+pub fn is_sealed_save(bytes: &[u8]) -> bool {
+    bytes.starts_with(&SEALED_SAVE_MAGIC)
+}
+
+/// Encrypt `plaintext` into one sealed frame. The frame stores its length
+/// as a u32, so larger payloads are an error.
+pub fn seal_save_payload(cipher: &SaveCipherData, plaintext: &[u8]) -> PersistenceResult<Vec<u8>> {
+    let body_len = u32::try_from(plaintext.len()).map_err(|_| {
+        PersistenceError::CapacityExceeded(format!(
+            "{} byte save payload does not fit in a sealed frame",
+            plaintext.len()
+        ))
+    })?;
+    let mut nonce = [0u8; SAVE_NONCE_SIZE];
+    OsRng.fill_bytes(&mut nonce);
+
+    let mut frame = Vec::with_capacity(SEALED_FRAME_HEADER_SIZE + plaintext.len() + SAVE_TAG_SIZE);
+    frame.extend_from_slice(&SEALED_SAVE_MAGIC);
+    frame.push(SEALED_SAVE_VERSION);
+    frame.extend_from_slice(&cipher.key_check);
+    frame.extend_from_slice(&nonce);
+    frame.extend_from_slice(&body_len.to_le_bytes());
+    frame.extend_from_slice(plaintext);
+    let (header, body) = frame.split_at_mut(SEALED_FRAME_HEADER_SIZE);
+    let tag = gcm_encrypt(cipher, &nonce, header, body)?;
+    frame.extend_from_slice(&tag);
+    Ok(frame)
+}
+
+/// One frame read from the front of a sealed file
+enum FrameOutcome {
+    Opened {
+        plaintext: Vec<u8>,
+        len: usize,
+    },
+    /// Ends before the frame does (an interrupted append)
+    Torn,
+    Damaged(&'static str),
+}
+
+fn open_frame(cipher: Option<&SaveCipherData>, bytes: &[u8]) -> PersistenceResult<FrameOutcome> {
+    if bytes.len() < SEALED_FRAME_HEADER_SIZE {
+        return Ok(FrameOutcome::Torn);
+    }
+    if !is_sealed_save(bytes) {
+        return Ok(FrameOutcome::Damaged("missing frame magic"));
+    }
+    if bytes[4] != SEALED_SAVE_VERSION {
+        return Err(PersistenceError::VersionMismatch {
+            expected: SEALED_SAVE_VERSION.to_string(),
+            found: bytes[4].to_string(),
+        });
+    }
+    let cipher = cipher.ok_or_else(|| {
+        PersistenceError::EncryptionKeyMissing(
+            "save is encrypted but no save encryption key is set".to_string(),
+        )
+    })?;
+    if bytes[5..5 + SAVE_KEY_CHECK_SIZE] != cipher.key_check {
+        return Err(PersistenceError::WrongEncryptionKey(
+            "save was encrypted with a different key".to_string(),
+        ));
+    }
+
+    let nonce_start = 5 + SAVE_KEY_CHECK_SIZE;
+    let mut nonce = [0u8; SAVE_NONCE_SIZE];
+    nonce.copy_from_slice(&bytes[nonce_start..nonce_start + SAVE_NONCE_SIZE]);
+    let mut length = [0u8; 4];
+    length.copy_from_slice(&bytes[nonce_start + SAVE_NONCE_SIZE..SEALED_FRAME_HEADER_SIZE]);
+    let body_len = u32::from_le_bytes(length) as usize;
+    let len = SEALED_FRAME_HEADER_SIZE + body_len + SAVE_TAG_SIZE;
+    if bytes.len() < len {
+        return Ok(FrameOutcome::Torn);
+    }
+
+    let mut plaintext =
+        bytes[SEALED_FRAME_HEADER_SIZE..SEALED_FRAME_HEADER_SIZE + body_len].to_vec();
+    let tag = &bytes[len - SAVE_TAG_SIZE..len];
+    if !gcm_decrypt(
+        cipher,
+        &nonce,
+        &bytes[..SEALED_FRAME_HEADER_SIZE],
+        &mut plaintext,
+        tag,
+    ) {
+        return Ok(FrameOutcome::Damaged("authentication failed"));
+    }
+    Ok(FrameOutcome::Opened { plaintext, len })
+}
+
+/// Open the frames of a sealed file up to the first torn or damaged one.
+/// A missing or wrong key is an error.
+pub fn open_save_frames(
+    cipher: Option<&SaveCipherData>,
+    bytes: &[u8],
+) -> PersistenceResult<OpenedSaveFrames> {
+    let mut opened = OpenedSaveFrames::default();
+    while opened.valid_len < bytes.len() {
+        match open_frame(cipher, &bytes[opened.valid_len..])? {
+            FrameOutcome::Opened { plaintext, len } => {
+                opened.plaintext.extend_from_slice(&plaintext);
+                opened.valid_len += len;
+            }
+            FrameOutcome::Torn => break,
+            FrameOutcome::Damaged(reason) => {
+                log::warn!(
+                    "[Persistence] Sealed save frame at byte {} is damaged: {}",
+                    opened.valid_len,
+                    reason
+                );
+                break;
+            }
+        }
+    }
+    opened.discarded_bytes = bytes.len() - opened.valid_len;
+    Ok(opened)
+}
+
+/// Plaintext of a whole save file: plaintext files pass through, sealed
+/// ones must open completely with `cipher`
+pub fn open_save_payload(
+    cipher: Option<&SaveCipherData>,
+    bytes: Vec<u8>,
+) -> PersistenceResult<Vec<u8>> {
+    if !is_sealed_save(&bytes) {
+        return Ok(bytes);
+    }
+    let opened = open_save_frames(cipher, &bytes)?;
+    if opened.discarded_bytes > 0 {
+        return Err(PersistenceError::CorruptedData(format!(
+            "encrypted save is truncated or was modified ({} bytes unreadable)",
+            opened.discarded_bytes
+        )));
+    }
+    Ok(opened.plaintext)
+}
+
+/// Write a save payload atomically, sealed when given a cipher
+pub fn write_save_file(
+    cipher: Option<&SaveCipherData>,
+    path: &Path,
+    bytes: &[u8],
+) -> PersistenceResult<()> {
+    match cipher {
+        Some(cipher) => write_file_atomic(path, &seal_save_payload(cipher, bytes)?),
+        None => write_file_atomic(path, bytes),
+    }
+}
+
+/// Read a save payload written by `write_save_file` (or before encryption
+/// was enabled)
+pub fn read_save_file(cipher: Option<&SaveCipherData>, path: &Path) -> PersistenceResult<Vec<u8>> {
+    let bytes = std::fs::read(path).map_err(|e| PersistenceError::IoError(e.to_string()))?;
+    open_save_payload(cipher, bytes)
+}
+
+// ============================================================================
+// MIGRATION
+// ============================================================================
+
+/// Files of a world directory that hold save payloads: everything except
+/// the world list's metadata and thumbnail, schematics and temporary files
+pub fn default_save_encryption_filter(path: &Path) -> bool {
+    let name = path.file_name().and_then(|name| name.to_str());
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    name != Some(WORLD_METADATA_FILE)
+        && name != Some(WORLD_THUMBNAIL_FILE)
+        && extension != Some(SCHEMATIC_EXTENSION)
+        && extension != Some("tmp")
+}
+
+/// Rewrite every save file under `directory` that `include` accepts from
+/// `old_key` (plaintext files need none) to `new_key` (`None` writes
+/// plaintext). Use it to enable encryption on an existing world, to rotate
+/// the key or to turn encryption off; run it while the world is closed.
+pub fn migrate_save_encryption(
+    directory: &Path,
+    old_key: Option<&SaveEncryptionKey>,
+    new_key: Option<&SaveEncryptionKey>,
+    include: impl Fn(&Path) -> bool,
+) -> PersistenceResult<SaveEncryptionMigration> {
+    let old_cipher = old_key.map(create_save_cipher);
+    let new_cipher = new_key.map(create_save_cipher);
+    let mut migration = SaveEncryptionMigration::default();
+
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(dir) = directories.pop() {
+        let entries =
+            std::fs::read_dir(&dir).map_err(|e| PersistenceError::IoError(e.to_string()))?;
+        for entry in entries {
+            let path = entry
+                .map_err(|e| PersistenceError::IoError(e.to_string()))?
+                .path();
+            if path.is_dir() {
+                directories.push(path);
+                continue;
+            }
+            if !include(&path) {
+                migration.files_skipped += 1;
+                continue;
+            }
+
+            let bytes =
+                std::fs::read(&path).map_err(|e| PersistenceError::IoError(e.to_string()))?;
+            let sealed = is_sealed_save(&bytes);
+            let file_key_check = bytes.get(5..5 + SAVE_KEY_CHECK_SIZE);
+            let already_done = match &new_cipher {
+                Some(cipher) => sealed && file_key_check == Some(&cipher.key_check[..]),
+                None => !sealed,
+            };
+            if already_done {
+                migration.files_unchanged += 1;
+                continue;
+            }
+
+            let plaintext = if sealed {
+                let opened = open_save_frames(old_cipher.as_ref(), &bytes)?;
+                if opened.discarded_bytes > 0 {
+                    log::warn!(
+                        "[Persistence] Dropping {} unreadable bytes of {} during key migration",
+                        opened.discarded_bytes,
+                        path.display()
+                    );
+                }
+                opened.plaintext
+            } else {
+                bytes
+            };
+            let rewritten = match &new_cipher {
+                Some(cipher) => seal_save_payload(cipher, &plaintext)?,
+                None => plaintext,
+            };
+            write_file_atomic(&path, &rewritten)?;
+            migration.files_rewritten += 1;
+        }
+    }
+
+    log::info!(
+        "[Persistence] Save encryption migration of {}: {} rewritten, {} unchanged, {} skipped",
+        directory.display(),
+        migration.files_rewritten,
+        migration.files_unchanged,
+        migration.files_skipped
+    );
+    Ok(migration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap_or(0))
+            .collect()
+    }
+
+    fn key(byte: u8) -> SaveEncryptionKey {
+        SaveEncryptionKey([byte; 32])
+    }
+
+    #[test]
+    fn test_gcm_matches_reference_vectors() {
+        // GCM spec test case 13: empty message under the zero key
+        let zero = create_save_cipher(&SaveEncryptionKey([0; 32]));
+        let tag = gcm_encrypt(&zero, &[0; 12], &[], &mut []).expect("encrypts");
+        assert_eq!(tag.to_vec(), hex("530f8afbc74536b9a963b4f1c4cb738b"));
+
+        // GCM spec test case 16: additional data and a partial last block
+        let cipher = create_save_cipher(
+            &save_encryption_key_from_bytes(&hex(
+                "feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308",
+            ))
+            .expect("32 bytes"),
+        );
+        let mut nonce = [0u8; 12];
+        nonce.copy_from_slice(&hex("cafebabefacedbaddecaf888"));
+        let aad = hex("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let plaintext = hex(
+            "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+             1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        );
+        let mut data = plaintext.clone();
+        let tag = gcm_encrypt(&cipher, &nonce, &aad, &mut data).expect("encrypts");
+        assert_eq!(
+            data,
+            hex(
+                "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa\
+                 8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662"
+            )
+        );
+        assert_eq!(tag.to_vec(), hex("76fc6ece0f4e1768cddf8853bb2d551b"));
+        assert!(gcm_decrypt(&cipher, &nonce, &aad, &mut data, &tag));
+        assert_eq!(data, plaintext);
+    }
+
+    #[test]
+    fn test_sealed_saves_report_key_errors_and_migrate() {
+        let first = key(1);
+        let second = key(2);
+        let cipher = create_save_cipher(&first);
+        let payload = b"chunk payload with player secrets".to_vec();
+
+        let sealed = seal_save_payload(&cipher, &payload).expect("seals");
+        assert!(is_sealed_save(&sealed));
+        assert_eq!(
+            open_save_payload(Some(&cipher), sealed.clone()).expect("opens"),
+            payload
+        );
+        assert_eq!(
+            open_save_payload(None, payload.clone()).expect("plaintext"),
+            payload
+        );
+        assert!(matches!(
+            open_save_payload(None, sealed.clone()),
+            Err(PersistenceError::EncryptionKeyMissing(_))
+        ));
+        assert!(matches!(
+            open_save_payload(Some(&create_save_cipher(&second)), sealed.clone()),
+            Err(PersistenceError::WrongEncryptionKey(_))
+        ));
+        let mut tampered = sealed.clone();
+        tampered[SEALED_FRAME_HEADER_SIZE + 3] ^= 1;
+        assert!(matches!(
+            open_save_payload(Some(&cipher), tampered),
+            Err(PersistenceError::CorruptedData(_))
+        ));
+
+        // Appended frames read up to a torn tail
+        let mut journal = sealed.clone();
+        journal.extend_from_slice(&seal_save_payload(&cipher, b"more").expect("seals"));
+        let third = seal_save_payload(&cipher, b"torn").expect("seals");
+        journal.extend_from_slice(&third[..third.len() - 3]);
+        let opened = open_save_frames(Some(&cipher), &journal).expect("opens");
+        assert_eq!(opened.plaintext, [&payload[..], b"more"].concat());
+        assert_eq!(opened.discarded_bytes, third.len() - 3);
+
+        // Enabling encryption on an existing world, then rotating the key
+        let dir = TempDir::new().expect("temp dir");
+        std::fs::create_dir_all(dir.path().join("chunks")).expect("chunk dir");
+        let chunk_path = dir.path().join("chunks").join("0.0.0.chunk");
+        std::fs::write(&chunk_path, &payload).expect("chunk");
+        std::fs::write(dir.path().join(WORLD_METADATA_FILE), b"{}").expect("metadata");
+
+        let enabled = migrate_save_encryption(
+            dir.path(),
+            None,
+            Some(&first),
+            default_save_encryption_filter,
+        )
+        .expect("migrates");
+        assert_eq!(enabled.files_rewritten, 1);
+        assert_eq!(enabled.files_skipped, 1);
+        let bytes = std::fs::read(&chunk_path).expect("chunk");
+        assert_eq!(
+            open_save_payload(Some(&cipher), bytes).expect("opens"),
+            payload
+        );
+
+        let rotated = migrate_save_encryption(
+            dir.path(),
+            Some(&first),
+            Some(&second),
+            default_save_encryption_filter,
+        )
+        .expect("rotates");
+        assert_eq!(rotated.files_rewritten, 1);
+        let again = migrate_save_encryption(
+            dir.path(),
+            Some(&first),
+            Some(&second),
+            default_save_encryption_filter,
+        )
+        .expect("no-op");
+        assert_eq!(again.files_unchanged, 1);
+        let bytes = std::fs::read(&chunk_path).expect("chunk");
+        assert_eq!(
+            open_save_payload(Some(&create_save_cipher(&second)), bytes).expect("opens"),
+            payload
+        );
+        assert_eq!(
+            std::fs::read(dir.path().join(WORLD_METADATA_FILE)).expect("metadata"),
+            b"{}"
+        );
+    }
+}
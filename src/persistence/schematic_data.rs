@@ -0,0 +1,75 @@
+//! Schematic Data - Pure DOP
+//!
+//! Portable format for sharing cuboid builds between worlds and with
+//! external tools. Blocks are stored by palette name, so a schematic pastes
+//! correctly into a world whose registry assigned different ids.
+//!
+//! Binary layout (all values little-endian):
+//! - magic "HSCH", format version (u32)
+//! - size x, y, z (3 x u32)
+//! - palette entry count (u32), then per entry:
+//!   block id (u16), metadata (u8), name length (u16), UTF-8 name
+//! - run count (u32), then (run length u32, palette index u16) pairs.
+//!   Voxels are ordered x fastest, then y, then z.
+//! - block entity count (u32), then per entity:
+//!   offset x, y, z (3 x u32), kind length (u16), UTF-8 kind,
+//!   data length (u32), opaque data bytes
+//!
+//! Importers resolve a palette entry by name first and fall back to its id
+//! when the name is empty or unknown.
+//!
+//! NO METHODS - just data.
+
+use crate::world::core::BlockId;
+
+/// Format identifier at the start of every schematic
+pub const SCHEMATIC_MAGIC: [u8; 4] = *b"HSCH";
+
+/// Current schematic format version
+pub const SCHEMATIC_VERSION: u32 = 1;
+
+/// File extension used by `export_region`
+pub const SCHEMATIC_EXTENSION: &str = "hsch";
+
+/// One distinct (block, metadata) pair in a schematic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchematicPaletteEntry {
+    /// Registry name (empty if the block was not registered)
+    pub name: String,
+    /// Id in the exporting world
+    pub block_id: BlockId,
+    pub metadata: u8,
+}
+
+/// Game-defined data attached to one block (chest contents, sign text, ...)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchematicBlockEntity {
+    /// Position relative to the schematic's minimum corner
+    pub offset: [u32; 3],
+    pub kind: String,
+    pub data: Vec<u8>,
+}
+
+/// Decoded schematic
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchematicData {
+    pub size: [u32; 3],
+    pub palette: Vec<SchematicPaletteEntry>,
+    /// Palette index per voxel, x fastest, then y, then z
+    pub voxels: Vec<u16>,
+    pub block_entities: Vec<SchematicBlockEntity>,
+}
+
+/// Result of pasting a schematic into a world
+#[derive(Debug, Clone, Default)]
+pub struct SchematicImport {
+    pub size: [u32; 3],
+    /// Blocks written
+    pub placed: usize,
+    /// Blocks skipped because their chunk is not loaded
+    pub unloaded: usize,
+    /// Palette entries resolved by id because their name is not registered
+    pub unresolved_names: Vec<String>,
+    /// Block entities for the game to recreate; world position = anchor + offset
+    pub block_entities: Vec<SchematicBlockEntity>,
+}
@@ -0,0 +1,421 @@
+//! Schematic Operations - Pure DOP Functions
+//!
+//! Export cuboid regions to the schematic format and paste them back through
+//! the world region API (`copy_region` / `paste_region`).
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::atomic_save_operations::write_file_atomic;
+use super::chunk_serializer_operations::ByteReader;
+use super::schematic_data::{
+    SchematicBlockEntity, SchematicData, SchematicImport, SchematicPaletteEntry, SCHEMATIC_MAGIC,
+    SCHEMATIC_VERSION,
+};
+use super::{PersistenceError, PersistenceResult};
+use crate::constants::persistence_constants::SCHEMATIC_MAX_VOLUME;
+use crate::world::core::{BlockId, BlockRegistry, VoxelPos};
+use crate::world::data_types::{RegionBlocks, WorldData};
+use crate::world::world_operations::{
+    copy_region, get_world_chunk_size, paste_region, region_size,
+};
+
+/// Registry name used for air, which is never registered
+const AIR_NAME: &str = "air";
+
+/// Distinct (block, metadata) pair
+type PaletteKey = (BlockId, u8);
+
+fn volume_of(size: [u32; 3]) -> u64 {
+    size.iter().map(|s| *s as u64).product()
+}
+
+fn check_volume(size: [u32; 3]) -> PersistenceResult<usize> {
+    let volume = volume_of(size);
+    if volume == 0 || volume > SCHEMATIC_MAX_VOLUME {
+        return Err(PersistenceError::CapacityExceeded(format!(
+            "Schematic region {}x{}x{} must hold 1..={} voxels",
+            size[0], size[1], size[2], SCHEMATIC_MAX_VOLUME
+        )));
+    }
+    Ok(volume as usize)
+}
+
+/// Build a schematic from the inclusive region between two corners
+pub fn schematic_from_region(
+    world: &WorldData,
+    registry: &BlockRegistry,
+    a: VoxelPos,
+    b: VoxelPos,
+    block_entities: Vec<SchematicBlockEntity>,
+) -> PersistenceResult<SchematicData> {
+    let size = region_size(a, b);
+    check_volume(size)?;
+
+    let names: HashMap<BlockId, &str> = registry
+        .get_registrations()
+        .iter()
+        .map(|registration| (registration.id, registration.name.as_str()))
+        .collect();
+
+    let region = copy_region(world, a, b, get_world_chunk_size(world));
+    let mut palette = Vec::new();
+    let mut palette_lookup: HashMap<PaletteKey, u16> = HashMap::new();
+    let mut voxels = Vec::with_capacity(region.blocks.len());
+    for (block, metadata) in region.blocks.iter().zip(&region.metadata) {
+        let key = (*block, *metadata);
+        let index = match palette_lookup.get(&key) {
+            Some(index) => *index,
+            None => {
+                let index = u16::try_from(palette.len()).map_err(|_| {
+                    PersistenceError::CapacityExceeded(
+                        "Schematic palette exceeds 65536 entries".to_string(),
+                    )
+                })?;
+                let name = match names.get(block) {
+                    Some(name) => name.to_string(),
+                    None if *block == BlockId::AIR => AIR_NAME.to_string(),
+                    None => String::new(),
+                };
+                palette.push(SchematicPaletteEntry {
+                    name,
+                    block_id: *block,
+                    metadata: *metadata,
+                });
+                palette_lookup.insert(key, index);
+                index
+            }
+        };
+        voxels.push(index);
+    }
+
+    let block_entities = block_entities
+        .into_iter()
+        .filter(|entity| entity.offset.iter().zip(size).all(|(o, s)| *o < s))
+        .collect();
+
+    Ok(SchematicData {
+        size,
+        palette,
+        voxels,
+        block_entities,
+    })
+}
+
+fn push_string(bytes: &mut Vec<u8>, value: &str) -> PersistenceResult<()> {
+    let len = u16::try_from(value.len()).map_err(|_| {
+        PersistenceError::SerializationError(format!("String too long ({} bytes)", value.len()))
+    })?;
+    bytes.extend_from_slice(&len.to_le_bytes());
+    bytes.extend_from_slice(value.as_bytes());
+    Ok(())
+}
+
+fn read_string(reader: &mut ByteReader) -> PersistenceResult<String> {
+    let len = reader.u16()? as usize;
+    String::from_utf8(reader.take_vec(len)?)
+        .map_err(|e| PersistenceError::CorruptedData(format!("Invalid UTF-8 string: {}", e)))
+}
+
+/// Encode a schematic into the binary format
+pub fn encode_schematic(schematic: &SchematicData) -> PersistenceResult<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(64 + schematic.palette.len() * 16);
+
+    bytes.extend_from_slice(&SCHEMATIC_MAGIC);
+    bytes.extend_from_slice(&SCHEMATIC_VERSION.to_le_bytes());
+    for axis in schematic.size {
+        bytes.extend_from_slice(&axis.to_le_bytes());
+    }
+
+    bytes.extend_from_slice(&(schematic.palette.len() as u32).to_le_bytes());
+    for entry in &schematic.palette {
+        bytes.extend_from_slice(&entry.block_id.0.to_le_bytes());
+        bytes.push(entry.metadata);
+        push_string(&mut bytes, &entry.name)?;
+    }
+
+    // Run-length encode palette indices
+    let mut runs: Vec<[u32; 2]> = Vec::new();
+    for index in &schematic.voxels {
+        match runs.last_mut() {
+            Some(run) if run[1] == *index as u32 => run[0] += 1,
+            _ => runs.push([1, *index as u32]),
+        }
+    }
+    bytes.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+    for [length, index] in runs {
+        bytes.extend_from_slice(&length.to_le_bytes());
+        bytes.extend_from_slice(&(index as u16).to_le_bytes());
+    }
+
+    bytes.extend_from_slice(&(schematic.block_entities.len() as u32).to_le_bytes());
+    for entity in &schematic.block_entities {
+        for axis in entity.offset {
+            bytes.extend_from_slice(&axis.to_le_bytes());
+        }
+        push_string(&mut bytes, &entity.kind)?;
+        bytes.extend_from_slice(&(entity.data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&entity.data);
+    }
+
+    Ok(bytes)
+}
+
+/// Decode a schematic written by `encode_schematic`
+pub fn decode_schematic(bytes: &[u8]) -> PersistenceResult<SchematicData> {
+    let mut reader = ByteReader { bytes, offset: 0 };
+
+    if reader.take::<4>()? != SCHEMATIC_MAGIC {
+        return Err(PersistenceError::CorruptedData(
+            "Missing schematic format magic".to_string(),
+        ));
+    }
+    let version = reader.u32()?;
+    if version != SCHEMATIC_VERSION {
+        return Err(PersistenceError::VersionMismatch {
+            expected: SCHEMATIC_VERSION.to_string(),
+            found: version.to_string(),
+        });
+    }
+
+    let size = [reader.u32()?, reader.u32()?, reader.u32()?];
+    let volume = check_volume(size)?;
+
+    let palette_len = reader.u32()? as usize;
+    let mut palette = Vec::with_capacity(palette_len.min(volume));
+    for _ in 0..palette_len {
+        let block_id = BlockId(reader.u16()?);
+        let [metadata] = reader.take::<1>()?;
+        let name = read_string(&mut reader)?;
+        palette.push(SchematicPaletteEntry {
+            name,
+            block_id,
+            metadata,
+        });
+    }
+
+    let run_count = reader.u32()?;
+    let mut voxels = Vec::with_capacity(volume);
+    for _ in 0..run_count {
+        let length = reader.u32()? as usize;
+        let index = reader.u16()?;
+        if index as usize >= palette.len() {
+            return Err(PersistenceError::CorruptedData(format!(
+                "Palette index {} out of range ({} entries)",
+                index,
+                palette.len()
+            )));
+        }
+        if voxels.len() + length > volume {
+            return Err(PersistenceError::CorruptedData(
+                "Voxel runs exceed schematic volume".to_string(),
+            ));
+        }
+        voxels.resize(voxels.len() + length, index);
+    }
+    if voxels.len() != volume {
+        return Err(PersistenceError::CorruptedData(format!(
+            "Voxel runs cover {} of {} voxels",
+            voxels.len(),
+            volume
+        )));
+    }
+
+    let entity_count = reader.u32()?;
+    let mut block_entities = Vec::new();
+    for _ in 0..entity_count {
+        let offset = [reader.u32()?, reader.u32()?, reader.u32()?];
+        if offset.iter().zip(size).any(|(o, s)| *o >= s) {
+            return Err(PersistenceError::CorruptedData(format!(
+                "Block entity offset {:?} outside schematic size {:?}",
+                offset, size
+            )));
+        }
+        let kind = read_string(&mut reader)?;
+        let data_len = reader.u32()? as usize;
+        let data = reader.take_vec(data_len)?;
+        block_entities.push(SchematicBlockEntity { offset, kind, data });
+    }
+
+    Ok(SchematicData {
+        size,
+        palette,
+        voxels,
+        block_entities,
+    })
+}
+
+/// Paste a schematic with its minimum corner at `anchor`
+pub fn paste_schematic(
+    world: &mut WorldData,
+    registry: &BlockRegistry,
+    schematic: &SchematicData,
+    anchor: VoxelPos,
+) -> SchematicImport {
+    let mut unresolved_names = Vec::new();
+    let resolved: Vec<BlockId> = schematic
+        .palette
+        .iter()
+        .map(|entry| {
+            if entry.name == AIR_NAME {
+                return BlockId::AIR;
+            }
+            match registry.get_id(&entry.name) {
+                Some(id) => id,
+                None => {
+                    if !entry.name.is_empty() {
+                        unresolved_names.push(entry.name.clone());
+                    }
+                    entry.block_id
+                }
+            }
+        })
+        .collect();
+
+    let region = RegionBlocks {
+        size: schematic.size,
+        blocks: schematic
+            .voxels
+            .iter()
+            .map(|index| resolved[*index as usize])
+            .collect(),
+        metadata: schematic
+            .voxels
+            .iter()
+            .map(|index| schematic.palette[*index as usize].metadata)
+            .collect(),
+    };
+
+    if !unresolved_names.is_empty() {
+        log::warn!(
+            "[Schematic] {} palette names not registered, pasted by id: {:?}",
+            unresolved_names.len(),
+            unresolved_names
+        );
+    }
+
+    let chunk_size = get_world_chunk_size(world);
+    let stats = paste_region(world, &region, anchor, chunk_size);
+    SchematicImport {
+        size: schematic.size,
+        placed: stats.placed,
+        unloaded: stats.unloaded,
+        unresolved_names,
+        block_entities: schematic.block_entities.clone(),
+    }
+}
+
+/// Write a schematic file
+pub fn write_schematic(schematic: &SchematicData, path: &Path) -> PersistenceResult<()> {
+    write_file_atomic(path, &encode_schematic(schematic)?)
+}
+
+/// Read a schematic file
+pub fn read_schematic(path: &Path) -> PersistenceResult<SchematicData> {
+    let bytes = std::fs::read(path).map_err(|e| PersistenceError::IoError(e.to_string()))?;
+    decode_schematic(&bytes)
+}
+
+/// Export the inclusive region between `min` and `max` to a schematic file
+pub fn export_region(
+    world: &WorldData,
+    registry: &BlockRegistry,
+    min: VoxelPos,
+    max: VoxelPos,
+    path: &Path,
+) -> PersistenceResult<SchematicData> {
+    let schematic = schematic_from_region(world, registry, min, max, Vec::new())?;
+    write_schematic(&schematic, path)?;
+    log::info!(
+        "[Schematic] Exported {:?} region ({} palette entries) to {}",
+        schematic.size,
+        schematic.palette.len(),
+        path.display()
+    );
+    Ok(schematic)
+}
+
+/// Paste a schematic file with its minimum corner at `anchor`
+pub fn import_schematic(
+    world: &mut WorldData,
+    registry: &BlockRegistry,
+    path: &Path,
+    anchor: VoxelPos,
+) -> PersistenceResult<SchematicImport> {
+    let schematic = read_schematic(path)?;
+    let import = paste_schematic(world, registry, &schematic, anchor);
+    if import.unloaded > 0 {
+        log::warn!(
+            "[Schematic] {} blocks of {} skipped (chunks not loaded)",
+            import.unloaded,
+            path.display()
+        );
+    }
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::core::ChunkPos;
+    use crate::world::data_types::ChunkData;
+    use crate::world::world_operations::{get_block, get_block_metadata, set_block_with_metadata};
+
+    fn test_world() -> WorldData {
+        let mut world = WorldData::new(0, 4, 4, 4);
+        let chunk_size = get_world_chunk_size(&world);
+        for x in 0..2 {
+            world
+                .chunks
+                .push(ChunkData::new(ChunkPos::new(x, 0, 0), chunk_size));
+        }
+        world
+    }
+
+    #[test]
+    fn test_schematic_round_trip_and_paste() {
+        let mut world = test_world();
+        let chunk_size = get_world_chunk_size(&world);
+        let registry = BlockRegistry::new();
+        for x in 0..4 {
+            let _ = set_block_with_metadata(
+                &mut world,
+                VoxelPos::new(x, 0, 0),
+                BlockId::STONE,
+                (x % 2) as u8,
+                chunk_size,
+            );
+        }
+
+        let schematic = schematic_from_region(
+            &world,
+            &registry,
+            VoxelPos::new(3, 1, 1),
+            VoxelPos::new(0, 0, 0),
+            vec![SchematicBlockEntity {
+                offset: [1, 0, 0],
+                kind: "sign".to_string(),
+                data: b"hello".to_vec(),
+            }],
+        )
+        .unwrap_or_default();
+        assert_eq!(schematic.size, [4, 2, 2]);
+        assert_eq!(schematic.palette.len(), 3);
+
+        let bytes = encode_schematic(&schematic).unwrap_or_default();
+        let decoded = decode_schematic(&bytes).unwrap_or_default();
+        assert_eq!(decoded, schematic);
+        assert!(decode_schematic(&bytes[..bytes.len() - 1]).is_err());
+
+        // Paste across the chunk boundary
+        let anchor = VoxelPos::new(chunk_size as i32 - 2, 5, 5);
+        let import = paste_schematic(&mut world, &registry, &decoded, anchor);
+        assert_eq!(import.placed, 16);
+        assert_eq!(import.unloaded, 0);
+        assert_eq!(import.block_entities.len(), 1);
+
+        let pasted = VoxelPos::new(anchor.x + 3, anchor.y, anchor.z);
+        assert_eq!(get_block(&world, pasted, chunk_size), BlockId::STONE);
+        assert_eq!(get_block_metadata(&world, pasted, chunk_size), 1);
+    }
+}
@@ -0,0 +1,140 @@
+//! World Diff Data - Pure DOP
+//!
+//! Structured differences between two snapshots of a world, for checking
+//! that an engine upgrade left existing worlds alone. A snapshot is read
+//! from a save directory (`world.json`, every `.chunk` file, the optional
+//! `entities.json` and any other save files) or built from a live world's
+//! chunks. Chunks are keyed by the position stored inside them, so the
+//! directory layout of the save does not matter.
+//!
+//! The diff serializes to JSON with chunks, block deltas and entities in a
+//! stable order, so two runs over the same worlds produce the same output.
+//!
+//! NO METHODS - just data.
+
+use crate::world::core::{ChunkPos, VoxelPos};
+use crate::world::data_types::ChunkData;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::world_slot_data::WorldSlotMetadata;
+
+/// Entity as recorded in a snapshot; `state` is whatever the game saves
+/// for it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntity {
+    pub id: u64,
+    pub kind: String,
+    pub position: [f32; 3],
+    #[serde(default)]
+    pub state: serde_json::Value,
+}
+
+/// Save files by path relative to the world directory, with their
+/// (decrypted) contents
+pub type SnapshotFiles = BTreeMap<String, Vec<u8>>;
+
+/// Everything a diff compares for one world
+#[derive(Clone, Default)]
+pub struct WorldSnapshot {
+    /// Contents of `world.json`, if the world has one
+    pub metadata: Option<WorldSlotMetadata>,
+    pub chunks: HashMap<ChunkPos, ChunkData>,
+    pub entities: BTreeMap<u64, SnapshotEntity>,
+    /// Save files other than metadata, chunks and entities
+    pub files: SnapshotFiles,
+}
+
+/// What to compare and how much detail to keep
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldDiffConfig {
+    /// Block deltas listed per chunk; the count covers all of them
+    pub max_block_deltas_per_chunk: usize,
+    /// `world.json` fields expected to change, e.g. `last_played`
+    pub ignored_metadata_fields: Vec<String>,
+    /// Entity positions closer than this count as unchanged
+    pub position_epsilon: f32,
+}
+
+/// One field that differs; missing values are `null`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: serde_json::Value,
+    pub after: serde_json::Value,
+}
+
+/// One voxel whose block or block metadata differs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BlockDelta {
+    pub voxel: VoxelPos,
+    pub before: u16,
+    pub after: u16,
+    pub before_metadata: u8,
+    pub after_metadata: u8,
+}
+
+/// Differences inside a chunk present in both snapshots
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChunkDiff {
+    pub position: ChunkPos,
+    /// Voxels that differ, including those past `deltas`
+    pub changed_blocks: u64,
+    /// First `max_block_deltas_per_chunk` changes, x fastest, then y, then z
+    pub deltas: Vec<BlockDelta>,
+    /// Edge lengths when the chunk changed size (no deltas are listed then)
+    pub size_change: Option<[u32; 2]>,
+}
+
+/// An entity present in both snapshots that differs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntityChange {
+    pub id: u64,
+    pub kind: String,
+    pub fields: Vec<FieldChange>,
+}
+
+/// How a non-chunk save file differs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    pub change: FileChangeKind,
+    pub before_bytes: u64,
+    pub after_bytes: u64,
+}
+
+/// Totals of a diff
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WorldDiffSummary {
+    pub chunks_added: u32,
+    pub chunks_removed: u32,
+    pub chunks_changed: u32,
+    pub blocks_changed: u64,
+    pub metadata_changes: u32,
+    pub entities_added: u32,
+    pub entities_removed: u32,
+    pub entities_changed: u32,
+    pub files_changed: u32,
+}
+
+/// Everything that differs from `before` to `after`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct WorldDiff {
+    pub summary: WorldDiffSummary,
+    pub metadata: Vec<FieldChange>,
+    pub chunks_added: Vec<ChunkPos>,
+    pub chunks_removed: Vec<ChunkPos>,
+    pub chunks_changed: Vec<ChunkDiff>,
+    pub entities_added: Vec<SnapshotEntity>,
+    pub entities_removed: Vec<SnapshotEntity>,
+    pub entities_changed: Vec<EntityChange>,
+    pub files: Vec<FileChange>,
+}
@@ -0,0 +1,529 @@
+//! World Diff Operations - Pure DOP Functions
+//!
+//! Snapshot a world from its save directory or from live chunks, compare
+//! two snapshots and write the result as JSON. Encrypted save files are
+//! read with the cipher passed in.
+
+use super::chunk_serializer_operations::deserialize_chunk;
+use super::save_encryption_data::SaveCipherData;
+use super::save_encryption_operations::{open_save_payload, read_save_file, write_save_file};
+use super::world_diff_data::{
+    BlockDelta, ChunkDiff, EntityChange, FieldChange, FileChange, FileChangeKind, SnapshotEntity,
+    SnapshotFiles, WorldDiff, WorldDiffConfig, WorldSnapshot,
+};
+use super::world_slot_data::WorldSlotMetadata;
+use super::{PersistenceError, PersistenceResult};
+use crate::constants::persistence_constants::{
+    CHUNK_FILE_EXTENSION, DIFF_MAX_BLOCK_DELTAS_PER_CHUNK, WORLD_ENTITIES_FILE,
+    WORLD_METADATA_FILE, WORLD_THUMBNAIL_FILE,
+};
+use crate::world::core::{chunk_layout_for_size, local_to_voxel, ChunkPos};
+use crate::world::data_types::ChunkData;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Every detail, nothing ignored
+pub fn default_world_diff_config() -> WorldDiffConfig {
+    WorldDiffConfig {
+        max_block_deltas_per_chunk: DIFF_MAX_BLOCK_DELTAS_PER_CHUNK,
+        ignored_metadata_fields: Vec::new(),
+        position_epsilon: 1e-4,
+    }
+}
+
+// ============================================================================
+// SNAPSHOTS
+// ============================================================================
+
+/// Read a world save directory. Chunk files anywhere below it are keyed by
+/// the position they store; unreadable chunks fail the snapshot, since a
+/// chunk that no longer loads is what a regression hunt is looking for.
+pub fn load_world_snapshot(
+    cipher: Option<&SaveCipherData>,
+    directory: &Path,
+) -> PersistenceResult<WorldSnapshot> {
+    let io_error = |e: std::io::Error| PersistenceError::IoError(e.to_string());
+    let mut snapshot = WorldSnapshot::default();
+
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(dir) = directories.pop() {
+        for entry in std::fs::read_dir(&dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+            // Half-built and half-removed world slot directories
+            if name.starts_with(".tmp-") || name.starts_with(".trash-") {
+                continue;
+            }
+            if path.is_dir() {
+                directories.push(path);
+                continue;
+            }
+            let extension = path.extension().and_then(|ext| ext.to_str());
+            let top_level = dir == directory;
+            if extension == Some("tmp") || (top_level && name == WORLD_THUMBNAIL_FILE) {
+                continue;
+            }
+
+            if top_level && name == WORLD_METADATA_FILE {
+                let bytes = std::fs::read(&path).map_err(io_error)?;
+                let metadata = serde_json::from_slice(&bytes)
+                    .map_err(|e| PersistenceError::DeserializationError(e.to_string()))?;
+                snapshot.metadata = Some(metadata);
+            } else if top_level && name == WORLD_ENTITIES_FILE {
+                let entities = read_snapshot_entities(cipher, &path)?;
+                add_snapshot_entities(&mut snapshot, entities);
+            } else if extension == Some(CHUNK_FILE_EXTENSION) {
+                let chunk = read_save_file(cipher, &path)
+                    .and_then(|bytes| deserialize_chunk(&bytes))
+                    .map_err(|e| {
+                        PersistenceError::CorruptedData(format!("{}: {}", path.display(), e))
+                    })?;
+                snapshot.chunks.insert(chunk.position, chunk);
+            } else {
+                let relative = path.strip_prefix(directory).unwrap_or(&path);
+                let key = relative.to_string_lossy().replace('\\', "/");
+                let bytes = std::fs::read(&path).map_err(io_error)?;
+                // Compare contents, not how they happen to be sealed
+                let bytes = open_save_payload(cipher, bytes.clone()).unwrap_or(bytes);
+                snapshot.files.insert(key, bytes);
+            }
+        }
+    }
+    Ok(snapshot)
+}
+
+/// Snapshot of a live world's loaded chunks
+pub fn world_snapshot_from_chunks<'a>(
+    chunks: impl IntoIterator<Item = &'a ChunkData>,
+    metadata: Option<WorldSlotMetadata>,
+) -> WorldSnapshot {
+    WorldSnapshot {
+        metadata,
+        chunks: chunks
+            .into_iter()
+            .map(|chunk| (chunk.position, chunk.clone()))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Add entities to a snapshot, replacing any with the same id
+pub fn add_snapshot_entities(
+    snapshot: &mut WorldSnapshot,
+    entities: impl IntoIterator<Item = SnapshotEntity>,
+) {
+    for entity in entities {
+        snapshot.entities.insert(entity.id, entity);
+    }
+}
+
+/// Write the entity records `load_world_snapshot` reads into a world
+/// directory
+pub fn write_snapshot_entities(
+    cipher: Option<&SaveCipherData>,
+    directory: &Path,
+    entities: &[SnapshotEntity],
+) -> PersistenceResult<()> {
+    let json = serde_json::to_vec_pretty(entities)
+        .map_err(|e| PersistenceError::SerializationError(e.to_string()))?;
+    write_save_file(cipher, &directory.join(WORLD_ENTITIES_FILE), &json)
+}
+
+fn read_snapshot_entities(
+    cipher: Option<&SaveCipherData>,
+    path: &Path,
+) -> PersistenceResult<Vec<SnapshotEntity>> {
+    let bytes = read_save_file(cipher, path)?;
+    serde_json::from_slice(&bytes)
+        .map_err(|e| PersistenceError::DeserializationError(e.to_string()))
+}
+
+// ============================================================================
+// DIFF
+// ============================================================================
+
+/// Compare two world save directories sealed with `cipher` (or plaintext)
+pub fn diff_world_saves(
+    cipher: Option<&SaveCipherData>,
+    before: &Path,
+    after: &Path,
+    config: &WorldDiffConfig,
+) -> PersistenceResult<WorldDiff> {
+    let before = load_world_snapshot(cipher, before)?;
+    let after = load_world_snapshot(cipher, after)?;
+    Ok(diff_world_snapshots(&before, &after, config))
+}
+
+/// Everything that differs from `before` to `after`
+pub fn diff_world_snapshots(
+    before: &WorldSnapshot,
+    after: &WorldSnapshot,
+    config: &WorldDiffConfig,
+) -> WorldDiff {
+    let mut diff = WorldDiff {
+        metadata: metadata_changes(before, after, config),
+        ..Default::default()
+    };
+
+    let mut positions: Vec<ChunkPos> = before
+        .chunks
+        .keys()
+        .chain(after.chunks.keys())
+        .copied()
+        .collect();
+    positions.sort_by_key(|pos| (pos.x, pos.y, pos.z));
+    positions.dedup();
+    for pos in positions {
+        match (before.chunks.get(&pos), after.chunks.get(&pos)) {
+            (Some(old), Some(new)) => {
+                if let Some(chunk) = diff_chunk(old, new, config.max_block_deltas_per_chunk) {
+                    diff.summary.blocks_changed += chunk.changed_blocks;
+                    diff.chunks_changed.push(chunk);
+                }
+            }
+            (Some(_), None) => diff.chunks_removed.push(pos),
+            (None, Some(_)) => diff.chunks_added.push(pos),
+            (None, None) => {}
+        }
+    }
+
+    let ids: BTreeSet<u64> = before
+        .entities
+        .keys()
+        .chain(after.entities.keys())
+        .copied()
+        .collect();
+    for id in ids {
+        match (before.entities.get(&id), after.entities.get(&id)) {
+            (Some(old), Some(new)) => {
+                let fields = entity_field_changes(old, new, config.position_epsilon);
+                if !fields.is_empty() {
+                    diff.entities_changed.push(EntityChange {
+                        id,
+                        kind: new.kind.clone(),
+                        fields,
+                    });
+                }
+            }
+            (Some(old), None) => diff.entities_removed.push(old.clone()),
+            (None, Some(new)) => diff.entities_added.push(new.clone()),
+            (None, None) => {}
+        }
+    }
+
+    diff.files = file_changes(&before.files, &after.files);
+
+    let summary = &mut diff.summary;
+    summary.chunks_added = diff.chunks_added.len() as u32;
+    summary.chunks_removed = diff.chunks_removed.len() as u32;
+    summary.chunks_changed = diff.chunks_changed.len() as u32;
+    summary.metadata_changes = diff.metadata.len() as u32;
+    summary.entities_added = diff.entities_added.len() as u32;
+    summary.entities_removed = diff.entities_removed.len() as u32;
+    summary.entities_changed = diff.entities_changed.len() as u32;
+    summary.files_changed = diff.files.len() as u32;
+    diff
+}
+
+/// True when the snapshots compared equal
+pub fn world_diff_is_empty(diff: &WorldDiff) -> bool {
+    diff.summary == Default::default()
+}
+
+/// Pretty-printed JSON of a diff
+pub fn world_diff_to_json(diff: &WorldDiff) -> PersistenceResult<String> {
+    serde_json::to_string_pretty(diff)
+        .map_err(|e| PersistenceError::SerializationError(e.to_string()))
+}
+
+/// Edge length of a cubic chunk holding `voxels` blocks
+fn chunk_edge(voxels: usize) -> u32 {
+    (voxels as f64).cbrt().round() as u32
+}
+
+/// Block and block metadata changes of a chunk; `None` if it is unchanged
+fn diff_chunk(before: &ChunkData, after: &ChunkData, max_deltas: usize) -> Option<ChunkDiff> {
+    if before.blocks == after.blocks && before.block_metadata == after.block_metadata {
+        return None;
+    }
+    let position = after.position;
+    if before.blocks.len() != after.blocks.len() {
+        return Some(ChunkDiff {
+            position,
+            changed_blocks: before.blocks.len().max(after.blocks.len()) as u64,
+            deltas: Vec::new(),
+            size_change: Some([
+                chunk_edge(before.blocks.len()),
+                chunk_edge(after.blocks.len()),
+            ]),
+        });
+    }
+
+    let mut changed: BTreeSet<u32> = before
+        .blocks
+        .iter()
+        .zip(&after.blocks)
+        .enumerate()
+        .filter(|(_, (old, new))| old != new)
+        .map(|(index, _)| index as u32)
+        .collect();
+    for index in before
+        .block_metadata
+        .keys()
+        .chain(after.block_metadata.keys())
+    {
+        if before.block_metadata.get(index) != after.block_metadata.get(index) {
+            changed.insert(*index);
+        }
+    }
+
+    let layout = chunk_layout_for_size(chunk_edge(after.blocks.len()));
+    let size = layout.size;
+    let deltas = changed
+        .iter()
+        .take(max_deltas)
+        .map(|&index| {
+            let local = [index % size, (index / size) % size, index / (size * size)];
+            let block = |chunk: &ChunkData| chunk.blocks.get(index as usize).map_or(0, |b| b.0);
+            let metadata = |chunk: &ChunkData| chunk.block_metadata.get(&index).copied();
+            BlockDelta {
+                voxel: local_to_voxel(layout, position, local),
+                before: block(before),
+                after: block(after),
+                before_metadata: metadata(before).unwrap_or(0),
+                after_metadata: metadata(after).unwrap_or(0),
+            }
+        })
+        .collect();
+    Some(ChunkDiff {
+        position,
+        changed_blocks: changed.len() as u64,
+        deltas,
+        size_change: None,
+    })
+}
+
+fn metadata_changes(
+    before: &WorldSnapshot,
+    after: &WorldSnapshot,
+    config: &WorldDiffConfig,
+) -> Vec<FieldChange> {
+    let to_value = |metadata: &Option<WorldSlotMetadata>| {
+        metadata
+            .as_ref()
+            .and_then(|metadata| serde_json::to_value(metadata).ok())
+            .unwrap_or(Value::Null)
+    };
+    object_field_changes("", &to_value(&before.metadata), &to_value(&after.metadata))
+        .into_iter()
+        .filter(|change| !config.ignored_metadata_fields.contains(&change.field))
+        .collect()
+}
+
+fn entity_field_changes(
+    before: &SnapshotEntity,
+    after: &SnapshotEntity,
+    epsilon: f32,
+) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    if before.kind != after.kind {
+        changes.push(FieldChange {
+            field: "kind".to_string(),
+            before: Value::from(before.kind.clone()),
+            after: Value::from(after.kind.clone()),
+        });
+    }
+    let moved = before
+        .position
+        .iter()
+        .zip(&after.position)
+        .any(|(old, new)| (old - new).abs() > epsilon);
+    if moved {
+        changes.push(FieldChange {
+            field: "position".to_string(),
+            before: Value::from(before.position.to_vec()),
+            after: Value::from(after.position.to_vec()),
+        });
+    }
+    changes.extend(object_field_changes("state", &before.state, &after.state));
+    changes
+}
+
+/// Changed top-level fields of two JSON objects, named `prefix.field`.
+/// Anything other than an object (or `null`) is compared whole.
+fn object_field_changes(prefix: &str, before: &Value, after: &Value) -> Vec<FieldChange> {
+    let field_name = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+    let is_object = |value: &Value| value.is_object() || value.is_null();
+    if !is_object(before) || !is_object(after) {
+        if before == after {
+            return Vec::new();
+        }
+        return vec![FieldChange {
+            field: prefix.to_string(),
+            before: before.clone(),
+            after: after.clone(),
+        }];
+    }
+
+    let empty = serde_json::Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let old = before.get(key).cloned().unwrap_or(Value::Null);
+            let new = after.get(key).cloned().unwrap_or(Value::Null);
+            (old != new).then(|| FieldChange {
+                field: field_name(key),
+                before: old,
+                after: new,
+            })
+        })
+        .collect()
+}
+
+fn file_changes(before: &SnapshotFiles, after: &SnapshotFiles) -> Vec<FileChange> {
+    let paths: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let old = before.get(path);
+            let new = after.get(path);
+            let change = match (old, new) {
+                (Some(old), Some(new)) if old == new => return None,
+                (Some(_), Some(_)) => FileChangeKind::Modified,
+                (Some(_), None) => FileChangeKind::Removed,
+                (None, _) => FileChangeKind::Added,
+            };
+            Some(FileChange {
+                path: path.clone(),
+                change,
+                before_bytes: old.map_or(0, |bytes| bytes.len() as u64),
+                after_bytes: new.map_or(0, |bytes| bytes.len() as u64),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{serialize_chunk, write_file_atomic};
+    use crate::world::core::{BlockId, VoxelPos};
+
+    const CHUNK_SIZE: u32 = 4;
+
+    fn metadata(last_played: u64) -> WorldSlotMetadata {
+        WorldSlotMetadata {
+            format_version: 1,
+            name: "Diff".to_string(),
+            seed: 7,
+            created_at: 1,
+            last_played,
+            play_time_secs: 0,
+            last_position: None,
+            world_day: 0,
+            thumbnail_taken_at: None,
+            engine_version: "0.1.0".to_string(),
+        }
+    }
+
+    fn entity(id: u64, x: f32, health: u32) -> SnapshotEntity {
+        SnapshotEntity {
+            id,
+            kind: "pig".to_string(),
+            position: [x, 64.0, 0.0],
+            state: serde_json::json!({ "health": health }),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_diff_reports_blocks_metadata_and_entities() {
+        let kept = ChunkData::new(ChunkPos::new(-1, 0, 0), CHUNK_SIZE);
+        let gone = ChunkData::new(ChunkPos::new(5, 0, 0), CHUNK_SIZE);
+        let mut edited = kept.clone();
+        edited.blocks[1 + CHUNK_SIZE as usize] = BlockId(3);
+        edited.block_metadata.insert(2, 4);
+        let fresh = ChunkData::new(ChunkPos::new(0, 1, 0), CHUNK_SIZE);
+
+        let mut before = world_snapshot_from_chunks([&kept, &gone], Some(metadata(10)));
+        let mut after = world_snapshot_from_chunks([&edited, &fresh], Some(metadata(20)));
+        if let Some(metadata) = after.metadata.as_mut() {
+            metadata.world_day = 2;
+        }
+        add_snapshot_entities(&mut before, [entity(1, 0.0, 10), entity(2, 0.0, 10)]);
+        add_snapshot_entities(&mut after, [entity(1, 0.0, 8), entity(3, 1.0, 10)]);
+
+        let config = WorldDiffConfig {
+            max_block_deltas_per_chunk: 1,
+            ignored_metadata_fields: vec!["last_played".to_string()],
+            ..default_world_diff_config()
+        };
+        let diff = diff_world_snapshots(&before, &after, &config);
+        assert_eq!(diff.chunks_added, vec![ChunkPos::new(0, 1, 0)]);
+        assert_eq!(diff.chunks_removed, vec![ChunkPos::new(5, 0, 0)]);
+        let chunk = &diff.chunks_changed[0];
+        assert_eq!(chunk.changed_blocks, 2);
+        // Only the first delta is listed: the metadata change at index 2
+        assert_eq!(chunk.deltas.len(), 1);
+        assert_eq!(chunk.deltas[0].voxel, VoxelPos::new(-2, 0, 0));
+        assert_eq!(chunk.deltas[0].after_metadata, 4);
+
+        let fields: Vec<&str> = diff.metadata.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["world_day"]);
+        assert_eq!(diff.entities_added[0].id, 3);
+        assert_eq!(diff.entities_removed[0].id, 2);
+        assert_eq!(diff.entities_changed[0].fields[0].field, "state.health");
+        assert!(!world_diff_is_empty(&diff));
+
+        let json = world_diff_to_json(&diff).expect("json");
+        let parsed: Value = serde_json::from_str(&json).expect("parse");
+        assert_eq!(parsed["summary"]["blocks_changed"], 2);
+
+        let same = diff_world_snapshots(&before, &before, &config);
+        assert!(world_diff_is_empty(&same));
+    }
+
+    #[test]
+    fn test_save_directories_diff_by_stored_chunk_position() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let write_world = |name: &str, chunk_file: &str, block: u16, extra: &[u8]| {
+            let root = dir.path().join(name);
+            let mut chunk = ChunkData::new(ChunkPos::new(2, -1, 3), CHUNK_SIZE);
+            chunk.blocks[0] = BlockId(block);
+            write_file_atomic(&root.join(chunk_file), &serialize_chunk(&chunk, CHUNK_SIZE))
+                .expect("chunk");
+            let json = serde_json::to_vec(&metadata(1)).expect("metadata");
+            write_file_atomic(&root.join(WORLD_METADATA_FILE), &json).expect("metadata");
+            write_snapshot_entities(None, &root, &[entity(1, 0.0, 10)]).expect("entities");
+            write_file_atomic(&root.join("scoreboard.bin"), extra).expect("extra");
+            root
+        };
+        // Same chunk under different file naming schemes
+        let before = write_world("old", "2_-1_3.chunk", 1, b"a");
+        let after = write_world("upgraded", "chunks/2.-1.3.chunk", 2, b"ab");
+
+        let diff =
+            diff_world_saves(None, &before, &after, &default_world_diff_config()).expect("diff");
+        assert_eq!(diff.summary.chunks_changed, 1);
+        assert_eq!(diff.summary.blocks_changed, 1);
+        assert_eq!(
+            diff.chunks_changed[0].deltas[0].voxel,
+            VoxelPos::new(8, -4, 12)
+        );
+        assert!(diff.chunks_added.is_empty() && diff.metadata.is_empty());
+        assert!(diff.entities_changed.is_empty());
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].change, FileChangeKind::Modified);
+    }
+}
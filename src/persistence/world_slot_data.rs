@@ -0,0 +1,86 @@
+//! World Slot Data - Pure DOP
+//!
+//! Saved worlds as the game menu sees them. Every world lives in its own
+//! directory under a saves root, named by a stable id, with a
+//! `world.json` metadata file and an optional `thumbnail.png` next to the
+//! chunk and player data. The thumbnail is captured from the renderer
+//! when the world is saved, so menus can preview a world, along with
+//! where the player was and the in-game day, without loading it.
+//!
+//! Directories being built are prefixed `.tmp-` and directories being
+//! removed `.trash-`; both are skipped when listing and cleaned up when
+//! the saves root is opened, so a crash never leaves a half-made world
+//! in the list.
+//!
+//! NO METHODS - just data.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Contents of `world.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldSlotMetadata {
+    pub format_version: u32,
+    /// Display name; unlike the directory id it can change
+    pub name: String,
+    pub seed: u32,
+    /// Seconds since the Unix epoch
+    pub created_at: u64,
+    /// Seconds since the Unix epoch
+    pub last_played: u64,
+    /// Total time spent in the world (seconds)
+    pub play_time_secs: u64,
+    /// Where the player was when the world was last saved
+    #[serde(default)]
+    pub last_position: Option<[f32; 3]>,
+    /// In-game day when the world was last saved
+    #[serde(default)]
+    pub world_day: u64,
+    /// When `thumbnail.png` was captured (seconds since the Unix epoch)
+    #[serde(default)]
+    pub thumbnail_taken_at: Option<u64>,
+    /// Engine version that last saved the world
+    #[serde(default)]
+    pub engine_version: String,
+}
+
+/// One saved world
+#[derive(Debug, Clone)]
+pub struct WorldSlot {
+    /// Directory name under the saves root
+    pub id: String,
+    pub path: PathBuf,
+    pub metadata: WorldSlotMetadata,
+    /// Menu screenshot, if one has been saved
+    pub thumbnail: Option<PathBuf>,
+    /// Bytes on disk, including chunk data
+    pub size_bytes: u64,
+}
+
+/// Decoded menu thumbnail, ready to upload as a texture
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldThumbnail {
+    pub width: u32,
+    pub height: u32,
+    /// `width * height` RGBA8 pixels, top row first
+    pub rgba: Vec<u8>,
+}
+
+/// Saved worlds under one saves root, most recently played first
+#[derive(Debug, Clone, Default)]
+pub struct WorldSlotManager {
+    pub root: PathBuf,
+    pub slots: Vec<WorldSlot>,
+    /// Id of the world the menu picked
+    pub selected: Option<String>,
+}
+
+/// What the engine needs to initialise the picked world
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldSelection {
+    pub id: String,
+    /// Save directory to hand to the world save system
+    pub path: PathBuf,
+    pub name: String,
+    pub seed: u32,
+}
@@ -0,0 +1,539 @@
+//! World Slot Operations - Pure DOP Functions
+//!
+//! List, create, delete, duplicate and rename saved worlds, and pick the one
+//! to load. The game menu drives these before the engine initialises a
+//! world, then hands the `WorldSelection` path to the world save system.
+//!
+//! Whole-directory changes are built or torn down under a hidden name and
+//! moved into place with a single rename, so the slot list only ever sees
+//! complete worlds.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::atomic_save_operations::write_file_atomic;
+use super::world_slot_data::{
+    WorldSelection, WorldSlot, WorldSlotManager, WorldSlotMetadata, WorldThumbnail,
+};
+use super::{PersistenceError, PersistenceResult};
+use crate::constants::persistence_constants::{
+    MAX_WORLD_NAME_LEN, WORLD_METADATA_FILE, WORLD_SLOT_FORMAT_VERSION, WORLD_THUMBNAIL_FILE,
+    WORLD_THUMBNAIL_SIZE,
+};
+
+/// Prefix of a world directory still being built
+const TEMP_PREFIX: &str = ".tmp-";
+
+/// Prefix of a world directory being removed
+const TRASH_PREFIX: &str = ".trash-";
+
+/// Version recorded in the metadata of worlds this engine saves
+const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Directory id used when a name has no usable characters
+const FALLBACK_ID: &str = "world";
+
+fn io_error(e: std::io::Error) -> PersistenceError {
+    PersistenceError::IoError(e.to_string())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn validate_world_name(name: &str) -> PersistenceResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(PersistenceError::InvalidWorldName(
+            "name is empty".to_string(),
+        ));
+    }
+    if name.chars().count() > MAX_WORLD_NAME_LEN {
+        return Err(PersistenceError::InvalidWorldName(format!(
+            "'{}' is longer than {} characters",
+            name, MAX_WORLD_NAME_LEN
+        )));
+    }
+    if name.chars().any(char::is_control) {
+        return Err(PersistenceError::InvalidWorldName(format!(
+            "'{}' contains control characters",
+            name.escape_default()
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// Filesystem-safe directory id for a world name: lowercase ASCII
+/// letters and digits, other runs collapsed to `_`
+fn slug_for_name(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    let slug = slug.trim_end_matches('_');
+    if slug.is_empty() {
+        FALLBACK_ID.to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// First id derived from `name` that no world or in-flight directory uses
+fn unique_world_id(root: &Path, name: &str) -> String {
+    let base = slug_for_name(name);
+    let taken = |id: &str| {
+        root.join(id).exists()
+            || root.join(format!("{}{}", TEMP_PREFIX, id)).exists()
+            || root.join(format!("{}{}", TRASH_PREFIX, id)).exists()
+    };
+    let mut id = base.clone();
+    let mut suffix = 2;
+    while taken(&id) {
+        id = format!("{}_{}", base, suffix);
+        suffix += 1;
+    }
+    id
+}
+
+fn directory_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => directory_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn copy_directory(from: &Path, to: &Path) -> PersistenceResult<()> {
+    fs::create_dir_all(to).map_err(io_error)?;
+    for entry in fs::read_dir(from).map_err(io_error)? {
+        let entry = entry.map_err(io_error)?;
+        let target = to.join(entry.file_name());
+        if entry.file_type().map_err(io_error)?.is_dir() {
+            copy_directory(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target).map_err(io_error)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_metadata(dir: &Path, metadata: &WorldSlotMetadata) -> PersistenceResult<()> {
+    let json = serde_json::to_vec_pretty(metadata)
+        .map_err(|e| PersistenceError::SerializationError(e.to_string()))?;
+    write_file_atomic(&dir.join(WORLD_METADATA_FILE), &json)
+}
+
+fn read_metadata(dir: &Path) -> PersistenceResult<WorldSlotMetadata> {
+    let bytes = fs::read(dir.join(WORLD_METADATA_FILE)).map_err(io_error)?;
+    let metadata: WorldSlotMetadata = serde_json::from_slice(&bytes)
+        .map_err(|e| PersistenceError::DeserializationError(e.to_string()))?;
+    if metadata.format_version > WORLD_SLOT_FORMAT_VERSION {
+        return Err(PersistenceError::VersionMismatch {
+            expected: WORLD_SLOT_FORMAT_VERSION.to_string(),
+            found: metadata.format_version.to_string(),
+        });
+    }
+    Ok(metadata)
+}
+
+fn load_world_slot(root: &Path, id: &str) -> PersistenceResult<WorldSlot> {
+    let path = root.join(id);
+    let metadata = read_metadata(&path)?;
+    let thumbnail = Some(path.join(WORLD_THUMBNAIL_FILE)).filter(|p| p.is_file());
+    Ok(WorldSlot {
+        id: id.to_string(),
+        size_bytes: directory_size(&path),
+        path,
+        metadata,
+        thumbnail,
+    })
+}
+
+/// Replace the manager's copy of one slot with what is on disk
+fn reload_slot(manager: &mut WorldSlotManager, id: &str) -> PersistenceResult<()> {
+    let slot = load_world_slot(&manager.root, id)?;
+    match manager.slots.iter_mut().find(|s| s.id == id) {
+        Some(existing) => *existing = slot,
+        None => manager.slots.push(slot),
+    }
+    sort_slots(&mut manager.slots);
+    Ok(())
+}
+
+fn sort_slots(slots: &mut [WorldSlot]) {
+    slots.sort_by(|a, b| {
+        b.metadata
+            .last_played
+            .cmp(&a.metadata.last_played)
+            .then_with(|| a.metadata.name.cmp(&b.metadata.name))
+    });
+}
+
+fn slot_index(manager: &WorldSlotManager, id: &str) -> PersistenceResult<usize> {
+    manager
+        .slots
+        .iter()
+        .position(|s| s.id == id)
+        .ok_or_else(|| PersistenceError::WorldNotFound(id.to_string()))
+}
+
+/// Remove directories left behind by a create, duplicate or delete that
+/// was interrupted
+fn remove_unfinished_directories(root: &Path) -> PersistenceResult<()> {
+    for entry in fs::read_dir(root).map_err(io_error)?.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with(TEMP_PREFIX) || name.starts_with(TRASH_PREFIX) {
+            if let Err(e) = fs::remove_dir_all(entry.path()) {
+                log::warn!("[WorldSlots] Could not remove {}: {}", name, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Open a saves root, creating it if needed, and list its worlds
+pub fn open_world_slots(root: &Path) -> PersistenceResult<WorldSlotManager> {
+    fs::create_dir_all(root).map_err(io_error)?;
+    remove_unfinished_directories(root)?;
+    let mut manager = WorldSlotManager {
+        root: root.to_path_buf(),
+        slots: Vec::new(),
+        selected: None,
+    };
+    refresh_world_slots(&mut manager)?;
+    Ok(manager)
+}
+
+/// Rescan the saves root. Directories without readable metadata are
+/// skipped; the selection is dropped if its world is gone.
+pub fn refresh_world_slots(manager: &mut WorldSlotManager) -> PersistenceResult<()> {
+    let mut slots = Vec::new();
+    for entry in fs::read_dir(&manager.root).map_err(io_error)?.flatten() {
+        if !entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            continue;
+        }
+        let name = entry.file_name();
+        let Some(id) = name.to_str() else {
+            continue;
+        };
+        if id.starts_with('.') {
+            continue;
+        }
+        match load_world_slot(&manager.root, id) {
+            Ok(slot) => slots.push(slot),
+            Err(e) => log::warn!("[WorldSlots] Skipping '{}': {}", id, e),
+        }
+    }
+    sort_slots(&mut slots);
+    manager.slots = slots;
+    if manager
+        .selected
+        .as_ref()
+        .is_some_and(|id| find_world_slot(manager, id).is_none())
+    {
+        manager.selected = None;
+    }
+    Ok(())
+}
+
+/// Slot by directory id
+pub fn find_world_slot<'a>(manager: &'a WorldSlotManager, id: &str) -> Option<&'a WorldSlot> {
+    manager.slots.iter().find(|s| s.id == id)
+}
+
+/// Create an empty world and return its id
+pub fn create_world_slot(
+    manager: &mut WorldSlotManager,
+    name: &str,
+    seed: u32,
+) -> PersistenceResult<String> {
+    let name = validate_world_name(name)?;
+    let id = unique_world_id(&manager.root, &name);
+    let staging = manager.root.join(format!("{}{}", TEMP_PREFIX, id));
+    let now = now_secs();
+    let metadata = WorldSlotMetadata {
+        format_version: WORLD_SLOT_FORMAT_VERSION,
+        name,
+        seed,
+        created_at: now,
+        last_played: now,
+        play_time_secs: 0,
+        last_position: None,
+        world_day: 0,
+        thumbnail_taken_at: None,
+        engine_version: ENGINE_VERSION.to_string(),
+    };
+
+    fs::create_dir_all(&staging).map_err(io_error)?;
+    let built = write_metadata(&staging, &metadata)
+        .and_then(|_| fs::rename(&staging, manager.root.join(&id)).map_err(io_error));
+    if let Err(e) = built {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    reload_slot(manager, &id)?;
+    Ok(id)
+}
+
+/// Delete a world and everything saved in it
+pub fn delete_world_slot(manager: &mut WorldSlotManager, id: &str) -> PersistenceResult<()> {
+    let index = slot_index(manager, id)?;
+    let trash = manager.root.join(format!("{}{}", TRASH_PREFIX, id));
+    // Once renamed the world is gone from the list even if removal fails;
+    // the next open finishes the job
+    fs::rename(&manager.slots[index].path, &trash).map_err(io_error)?;
+    manager.slots.remove(index);
+    if manager.selected.as_deref() == Some(id) {
+        manager.selected = None;
+    }
+    if let Err(e) = fs::remove_dir_all(&trash) {
+        log::warn!("[WorldSlots] Deferred removal of '{}': {}", id, e);
+    }
+    Ok(())
+}
+
+/// Copy a world, including its chunks and players, under a new name and
+/// return the copy's id
+pub fn duplicate_world_slot(
+    manager: &mut WorldSlotManager,
+    id: &str,
+    new_name: &str,
+) -> PersistenceResult<String> {
+    let name = validate_world_name(new_name)?;
+    let source = &manager.slots[slot_index(manager, id)?];
+    let new_id = unique_world_id(&manager.root, &name);
+    let staging = manager.root.join(format!("{}{}", TEMP_PREFIX, new_id));
+    let now = now_secs();
+    let metadata = WorldSlotMetadata {
+        name,
+        created_at: now,
+        last_played: now,
+        ..source.metadata.clone()
+    };
+
+    let built = copy_directory(&source.path, &staging)
+        .and_then(|_| write_metadata(&staging, &metadata))
+        .and_then(|_| fs::rename(&staging, manager.root.join(&new_id)).map_err(io_error));
+    if let Err(e) = built {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    reload_slot(manager, &new_id)?;
+    Ok(new_id)
+}
+
+/// Change a world's display name. The directory id stays the same so
+/// paths held elsewhere remain valid.
+pub fn rename_world_slot(
+    manager: &mut WorldSlotManager,
+    id: &str,
+    new_name: &str,
+) -> PersistenceResult<()> {
+    let name = validate_world_name(new_name)?;
+    let slot = &manager.slots[slot_index(manager, id)?];
+    let metadata = WorldSlotMetadata {
+        name,
+        ..slot.metadata.clone()
+    };
+    write_metadata(&slot.path, &metadata)?;
+    reload_slot(manager, id)
+}
+
+/// Store a screenshot as the world's menu thumbnail. `rgba` is
+/// `width * height` RGBA8 pixels; it is scaled down so the longest edge
+/// fits `WORLD_THUMBNAIL_SIZE`.
+pub fn save_world_thumbnail(
+    manager: &mut WorldSlotManager,
+    id: &str,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+) -> PersistenceResult<()> {
+    let path = manager.slots[slot_index(manager, id)?].path.clone();
+    let image = image::RgbaImage::from_raw(width, height, rgba).ok_or_else(|| {
+        PersistenceError::CorruptedData(format!(
+            "Thumbnail pixels do not match {}x{} RGBA",
+            width, height
+        ))
+    })?;
+
+    let longest = width.max(height).max(1);
+    let image = if longest > WORLD_THUMBNAIL_SIZE {
+        let scale =
+            |edge: u32| ((edge as u64 * WORLD_THUMBNAIL_SIZE as u64) / longest as u64).max(1);
+        image::imageops::thumbnail(&image, scale(width) as u32, scale(height) as u32)
+    } else {
+        image
+    };
+
+    let mut png = Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, image::ImageOutputFormat::Png)
+        .map_err(|e| PersistenceError::SerializationError(e.to_string()))?;
+    write_file_atomic(&path.join(WORLD_THUMBNAIL_FILE), png.get_ref())?;
+    let slot = &manager.slots[slot_index(manager, id)?];
+    let metadata = WorldSlotMetadata {
+        thumbnail_taken_at: Some(now_secs()),
+        ..slot.metadata.clone()
+    };
+    write_metadata(&slot.path, &metadata)?;
+    reload_slot(manager, id)
+}
+
+/// Decode a world's thumbnail for the menu without loading the world;
+/// `None` when no thumbnail has been saved
+pub fn load_world_thumbnail(
+    manager: &WorldSlotManager,
+    id: &str,
+) -> PersistenceResult<Option<WorldThumbnail>> {
+    let slot = &manager.slots[slot_index(manager, id)?];
+    let Some(path) = &slot.thumbnail else {
+        return Ok(None);
+    };
+    let image = image::open(path)
+        .map_err(|e| PersistenceError::DeserializationError(e.to_string()))?
+        .into_rgba8();
+    Ok(Some(WorldThumbnail {
+        width: image.width(),
+        height: image.height(),
+        rgba: image.into_raw(),
+    }))
+}
+
+/// Record where the player is and the in-game day, usually alongside
+/// the thumbnail on save or exit
+pub fn update_world_details(
+    manager: &mut WorldSlotManager,
+    id: &str,
+    last_position: Option<[f32; 3]>,
+    world_day: u64,
+) -> PersistenceResult<()> {
+    let slot = &manager.slots[slot_index(manager, id)?];
+    let metadata = WorldSlotMetadata {
+        last_position,
+        world_day,
+        engine_version: ENGINE_VERSION.to_string(),
+        ..slot.metadata.clone()
+    };
+    write_metadata(&slot.path, &metadata)?;
+    reload_slot(manager, id)
+}
+
+/// Pick the world to load: marks it as played now and returns what
+/// engine world init needs
+pub fn select_world_slot(
+    manager: &mut WorldSlotManager,
+    id: &str,
+) -> PersistenceResult<WorldSelection> {
+    let slot = &manager.slots[slot_index(manager, id)?];
+    let metadata = WorldSlotMetadata {
+        last_played: now_secs(),
+        ..slot.metadata.clone()
+    };
+    write_metadata(&slot.path, &metadata)?;
+    reload_slot(manager, id)?;
+    manager.selected = Some(id.to_string());
+    selected_world_slot(manager).ok_or_else(|| PersistenceError::WorldNotFound(id.to_string()))
+}
+
+/// The world picked by `select_world_slot`, if it still exists
+pub fn selected_world_slot(manager: &WorldSlotManager) -> Option<WorldSelection> {
+    let slot = find_world_slot(manager, manager.selected.as_deref()?)?;
+    Some(WorldSelection {
+        id: slot.id.clone(),
+        path: slot.path.clone(),
+        name: slot.metadata.name.clone(),
+        seed: slot.metadata.seed,
+    })
+}
+
+/// Add a session's play time to a world, usually on save or exit
+pub fn record_play_time(
+    manager: &mut WorldSlotManager,
+    id: &str,
+    seconds: u64,
+) -> PersistenceResult<()> {
+    let slot = &manager.slots[slot_index(manager, id)?];
+    let metadata = WorldSlotMetadata {
+        play_time_secs: slot.metadata.play_time_secs.saturating_add(seconds),
+        last_played: now_secs(),
+        ..slot.metadata.clone()
+    };
+    write_metadata(&slot.path, &metadata)?;
+    reload_slot(manager, id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_world_slot_lifecycle() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mut manager = open_world_slots(dir.path()).expect("open");
+        assert!(manager.slots.is_empty());
+
+        let id = create_world_slot(&mut manager, "My World!", 42).expect("create");
+        assert_eq!(id, "my_world");
+        fs::write(manager.root.join(&id).join("chunk.dat"), [7u8; 32]).expect("chunk");
+
+        let copy = duplicate_world_slot(&mut manager, &id, "My World!").expect("duplicate");
+        assert_eq!(copy, "my_world_2");
+        assert!(manager.root.join(&copy).join("chunk.dat").is_file());
+
+        rename_world_slot(&mut manager, &copy, "Backup").expect("rename");
+        save_world_thumbnail(&mut manager, &copy, 512, 256, vec![255; 512 * 256 * 4])
+            .expect("thumbnail");
+        let thumbnail = find_world_slot(&manager, &copy)
+            .and_then(|s| s.thumbnail.clone())
+            .expect("thumbnail path");
+        let (w, h) = image::image_dimensions(thumbnail).expect("png");
+        assert_eq!((w, h), (WORLD_THUMBNAIL_SIZE, WORLD_THUMBNAIL_SIZE / 2));
+        let preview = load_world_thumbnail(&manager, &copy)
+            .expect("decode")
+            .expect("preview");
+        assert_eq!(preview.rgba.len(), (w * h * 4) as usize);
+        assert!(load_world_thumbnail(&manager, &id).expect("none").is_none());
+
+        update_world_details(&mut manager, &copy, Some([1.0, 70.0, -3.0]), 12).expect("details");
+        let metadata = &find_world_slot(&manager, &copy).expect("slot").metadata;
+        assert_eq!(
+            (metadata.last_position, metadata.world_day),
+            (Some([1.0, 70.0, -3.0]), 12)
+        );
+        assert!(metadata.thumbnail_taken_at.is_some());
+
+        let selection = select_world_slot(&mut manager, &copy).expect("select");
+        assert_eq!(selection.name, "Backup");
+        assert_eq!(selection.seed, 42);
+
+        // Leftovers from an interrupted operation never show up as worlds
+        fs::create_dir_all(dir.path().join(".tmp-half")).expect("leftover");
+        delete_world_slot(&mut manager, &copy).expect("delete");
+        assert!(selected_world_slot(&manager).is_none());
+
+        let reopened = open_world_slots(dir.path()).expect("reopen");
+        assert_eq!(reopened.slots.len(), 1);
+        assert_eq!(reopened.slots[0].metadata.name, "My World!");
+        assert!(!dir.path().join(".tmp-half").exists());
+        assert!(create_world_slot(&mut manager, "  ", 1).is_err());
+    }
+}
@@ -3,6 +3,8 @@
 /// Pure functions for collision detection - no methods, just data transformations.
 
 use cgmath::{Vector3, Point3};
+use crate::simd_data::{AabbColumns, SweepHit};
+use crate::simd_operations::sweep_aabb;
 
 /// Axis-Aligned Bounding Box - pure data structure
 #[derive(Debug, Clone, Copy)]
@@ -194,3 +196,20 @@ pub fn aabb_swept_collision(aabb: &AABB, velocity: Vector3<f32>, other: &AABB, d
     }
 }
 
+/// Swept collision against many static boxes at once
+/// Pure function - earliest time of impact (0.0-1.0 of velocity * dt) over
+/// all of `others`, vectorized when the CPU supports it
+pub fn aabb_swept_collision_batch(
+    aabb: &AABB,
+    velocity: Vector3<f32>,
+    others: &AabbColumns,
+    dt: f32,
+) -> Option<SweepHit> {
+    let displacement = velocity * dt;
+    sweep_aabb(
+        [aabb.min.x, aabb.min.y, aabb.min.z],
+        [aabb.max.x, aabb.max.y, aabb.max.z],
+        [displacement.x, displacement.y, displacement.z],
+        others,
+    )
+}
@@ -0,0 +1,45 @@
+//! Buoyancy Data - Pure DOP
+//!
+//! Per-block fluid properties for entities in water and other liquids.
+//! Each tick an entity's box is intersected with the fluid voxels around it;
+//! the submerged fraction lifts it (Archimedes against gravity), damps its
+//! velocity relative to the fluid and carries it along the fluid's flow.
+//! Operations live in buoyancy_operations.rs.
+//!
+//! NO METHODS - just data.
+
+/// Response of one fluid block type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FluidMaterial {
+    /// kg/m³; entities less dense than this float
+    pub density: f32,
+    /// Velocity damping when fully submerged (1/s)
+    pub drag: f32,
+}
+
+/// Fluid materials indexed by block id. `None` marks blocks that are not
+/// fluids (air, solids, unregistered ids).
+#[derive(Debug, Clone, Default)]
+pub struct FluidMaterialTable {
+    pub materials: Vec<Option<FluidMaterial>>,
+}
+
+/// Fluid around one entity, averaged over the submerged volume
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FluidSubmersion {
+    /// Share of the entity's box inside fluid voxels (0..=1)
+    pub fraction: f32,
+    pub density: f32,
+    pub drag: f32,
+    /// Fluid velocity (voxels/s); zero until a fluid simulation writes it
+    pub flow: [f32; 3],
+}
+
+/// Buoyancy tuning shared by all entities
+#[derive(Debug, Clone, Copy)]
+pub struct BuoyancyConfig {
+    /// Downward acceleration the buoyant force cancels (voxels/s², negative)
+    pub gravity: f32,
+    /// Density of entities (kg/m³)
+    pub entity_density: f32,
+}
@@ -0,0 +1,287 @@
+//! Buoyancy Operations - Pure DOP Functions
+//!
+//! Per tick for each entity: `sample_fluid_submersion` over its box, then
+//! `apply_buoyancy` before integration. `update_entity_buoyancy` does both
+//! for every dynamic entity; pass it lookups backed by the shadow cache
+//! (misses read as air and queue a readback, so the next tick is exact).
+
+use super::buoyancy_data::{BuoyancyConfig, FluidMaterial, FluidMaterialTable, FluidSubmersion};
+use super::surface_material_operations::cell_overlaps;
+use crate::constants::physics_constants::{
+    DEFAULT_ENTITY_DENSITY, FLUID_LINEAR_DRAG, GRAVITY, REFERENCE_FLUID_DENSITY,
+};
+use crate::engine_buffers::PhysicsBuffers;
+use crate::world::core::{BlockId, BlockRegistry, PhysicsProperties, VoxelPos};
+
+/// Default tuning: world gravity, entities slightly lighter than water
+pub fn default_buoyancy_config() -> BuoyancyConfig {
+    BuoyancyConfig {
+        gravity: GRAVITY,
+        entity_density: DEFAULT_ENTITY_DENSITY,
+    }
+}
+
+/// Fluid material of a block's physics, `None` unless it is a non-solid
+/// block with mass
+pub fn fluid_material_from_physics(physics: &PhysicsProperties) -> Option<FluidMaterial> {
+    if physics.solid || physics.density <= 0.0 {
+        return None;
+    }
+    Some(FluidMaterial {
+        density: physics.density,
+        drag: FLUID_LINEAR_DRAG * physics.density / REFERENCE_FLUID_DENSITY,
+    })
+}
+
+/// Build the lookup table from every registered block
+pub fn build_fluid_material_table(registry: &BlockRegistry) -> FluidMaterialTable {
+    let mut table = FluidMaterialTable::default();
+    for registration in registry.get_registrations() {
+        set_fluid_material(
+            &mut table,
+            registration.id,
+            fluid_material_from_physics(&registration.properties.physics),
+        );
+    }
+    table
+}
+
+/// Override one block's fluid material
+pub fn set_fluid_material(
+    table: &mut FluidMaterialTable,
+    id: BlockId,
+    material: Option<FluidMaterial>,
+) {
+    let index = id.0 as usize;
+    if index >= table.materials.len() {
+        table.materials.resize(index + 1, None);
+    }
+    table.materials[index] = material;
+}
+
+/// Fluid material of a block, `None` if it is not a fluid
+pub fn fluid_material(table: &FluidMaterialTable, id: BlockId) -> Option<FluidMaterial> {
+    table.materials.get(id.0 as usize).copied().flatten()
+}
+
+/// Flow lookup for worlds without a fluid simulation: still everywhere
+pub fn no_fluid_flow(_pos: VoxelPos) -> [f32; 3] {
+    [0.0; 3]
+}
+
+/// Fluid inside the box [min, max), each voxel weighted by how much of the
+/// box it overlaps. `flow_at` gives the fluid velocity stored for a voxel.
+pub fn sample_fluid_submersion(
+    table: &FluidMaterialTable,
+    min: [f32; 3],
+    max: [f32; 3],
+    mut block_at: impl FnMut(VoxelPos) -> BlockId,
+    mut flow_at: impl FnMut(VoxelPos) -> [f32; 3],
+) -> FluidSubmersion {
+    let volume: f32 = (0..3)
+        .map(|axis| (max[axis] - min[axis]).max(0.0))
+        .product();
+    if volume <= f32::EPSILON {
+        return FluidSubmersion::default();
+    }
+
+    let mut submerged = 0.0;
+    let mut sum = FluidSubmersion::default();
+    for (x, width) in cell_overlaps(min[0], max[0]) {
+        for (y, height) in cell_overlaps(min[1], max[1]) {
+            for (z, depth) in cell_overlaps(min[2], max[2]) {
+                let weight = width * height * depth;
+                if weight <= 0.0 {
+                    continue;
+                }
+                let pos = VoxelPos { x, y, z };
+                let Some(material) = fluid_material(table, block_at(pos)) else {
+                    continue;
+                };
+                let flow = flow_at(pos);
+                submerged += weight;
+                sum.density += material.density * weight;
+                sum.drag += material.drag * weight;
+                for (total, v) in sum.flow.iter_mut().zip(flow) {
+                    *total += v * weight;
+                }
+            }
+        }
+    }
+    if submerged <= 0.0 {
+        return FluidSubmersion::default();
+    }
+
+    FluidSubmersion {
+        fraction: (submerged / volume).min(1.0),
+        density: sum.density / submerged,
+        drag: sum.drag / submerged,
+        flow: sum.flow.map(|v| v / submerged),
+    }
+}
+
+/// Apply buoyancy, drag and flow push to one entity for a tick.
+///
+/// The buoyant acceleration is gravity scaled by the fluid/entity density
+/// ratio and the submerged fraction, so a lighter entity rises until the
+/// submerged share balances its weight. Drag pulls the velocity toward the
+/// fluid's flow, which both slows motion through still fluid and carries
+/// the entity along a current. Returns whether the entity is in fluid.
+pub fn apply_buoyancy(
+    physics: &mut PhysicsBuffers,
+    index: usize,
+    submersion: &FluidSubmersion,
+    config: &BuoyancyConfig,
+    delta_time: f32,
+) -> bool {
+    if submersion.fraction <= 0.0 {
+        return false;
+    }
+    let Some(velocity) = physics.velocities.get_mut(index) else {
+        return false;
+    };
+
+    let density_ratio = submersion.density / config.entity_density.max(f32::EPSILON);
+    velocity[1] -= config.gravity * density_ratio * submersion.fraction * delta_time;
+
+    let damping = (-submersion.drag * submersion.fraction * delta_time).exp();
+    for (v, flow) in velocity.iter_mut().zip(submersion.flow) {
+        *v = flow + (*v - flow) * damping;
+    }
+    if let Some(flags) = physics.flags.get_mut(index) {
+        flags.is_grounded = false;
+    }
+    true
+}
+
+/// Sample and apply buoyancy for every dynamic, gravity-affected entity,
+/// using its box size centered on its current position. Returns how many
+/// entities were in fluid.
+pub fn update_entity_buoyancy(
+    physics: &mut PhysicsBuffers,
+    table: &FluidMaterialTable,
+    config: &BuoyancyConfig,
+    mut block_at: impl FnMut(VoxelPos) -> BlockId,
+    mut flow_at: impl FnMut(VoxelPos) -> [f32; 3],
+    delta_time: f32,
+) -> usize {
+    let mut in_fluid = 0;
+    for index in 0..physics.positions.len() {
+        let floating = physics
+            .flags
+            .get(index)
+            .is_some_and(|flags| flags.is_dynamic && flags.has_gravity);
+        let Some(aabb) = physics.aabbs.get(index) else {
+            continue;
+        };
+        if !floating {
+            continue;
+        }
+        let position = physics.positions[index];
+        let half = [0, 1, 2].map(|axis| (aabb.max[axis] - aabb.min[axis]) * 0.5);
+        let submersion = sample_fluid_submersion(
+            table,
+            [0, 1, 2].map(|axis| position[axis] - half[axis]),
+            [0, 1, 2].map(|axis| position[axis] + half[axis]),
+            &mut block_at,
+            &mut flow_at,
+        );
+        if apply_buoyancy(physics, index, &submersion, config, delta_time) {
+            in_fluid += 1;
+        }
+    }
+    in_fluid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_buffers::{create_engine_buffers, PhysicsFlags, AABB};
+
+    fn water_table() -> FluidMaterialTable {
+        let mut table = FluidMaterialTable::default();
+        set_fluid_material(
+            &mut table,
+            BlockId::WATER,
+            Some(FluidMaterial {
+                density: 1000.0,
+                drag: FLUID_LINEAR_DRAG,
+            }),
+        );
+        table
+    }
+
+    /// Still water below y = 0
+    fn pond(pos: VoxelPos) -> BlockId {
+        if pos.y < 0 {
+            BlockId::WATER
+        } else {
+            BlockId::AIR
+        }
+    }
+
+    #[test]
+    fn test_submersion_is_voxel_accurate() {
+        let table = water_table();
+        let sample = |min_y: f32, max_y: f32| {
+            sample_fluid_submersion(
+                &table,
+                [0.25, min_y, 0.25],
+                [1.75, max_y, 1.75],
+                pond,
+                no_fluid_flow,
+            )
+        };
+        assert_eq!(sample(1.0, 3.0).fraction, 0.0);
+        assert!((sample(-0.5, 1.5).fraction - 0.25).abs() < 1e-5);
+        assert!((sample(-4.0, -2.0).fraction - 1.0).abs() < 1e-5);
+        assert_eq!(sample(-4.0, -2.0).density, 1000.0);
+    }
+
+    #[test]
+    fn test_light_entity_floats_and_drifts_with_flow() {
+        let table = water_table();
+        let config = default_buoyancy_config();
+        let mut buffers = create_engine_buffers();
+        let physics = &mut buffers.physics;
+        physics.positions = vec![[0.5, -3.0, 0.5]];
+        physics.velocities = vec![[0.0; 3]];
+        physics.aabbs = vec![AABB {
+            min: [0.0, -4.0, 0.0],
+            max: [1.0, -2.0, 1.0],
+        }];
+        physics.flags = vec![PhysicsFlags {
+            is_static: false,
+            is_kinematic: false,
+            is_dynamic: true,
+            has_gravity: true,
+            is_grounded: true,
+        }];
+
+        // Fully submerged: buoyancy outweighs gravity and the entity rises
+        let dt = 1.0 / 60.0;
+        let flow = |_pos: VoxelPos| [6.0, 0.0, 0.0];
+        assert_eq!(
+            update_entity_buoyancy(physics, &table, &config, pond, flow, dt),
+            1
+        );
+        let velocity = physics.velocities[0];
+        assert!(velocity[1] + config.gravity * dt > 0.0);
+        assert!(velocity[0] > 0.0 && velocity[0] < 6.0);
+        assert!(!physics.flags[0].is_grounded);
+
+        // Drag slows motion through still water
+        physics.velocities[0] = [20.0, 0.0, 0.0];
+        update_entity_buoyancy(physics, &table, &config, pond, no_fluid_flow, dt);
+        assert!(physics.velocities[0][0] < 20.0);
+
+        // Out of the water nothing changes
+        physics.positions[0] = [0.5, 5.0, 0.5];
+        physics.velocities[0] = [1.0, 2.0, 3.0];
+        assert_eq!(
+            update_entity_buoyancy(physics, &table, &config, pond, no_fluid_flow, dt),
+            0
+        );
+        assert_eq!(physics.velocities[0], [1.0, 2.0, 3.0]);
+    }
+}
@@ -0,0 +1,101 @@
+//! Entity Collision Data - Pure DOP
+//!
+//! Player-vs-entity collision: the character sweeps against the entity BVH,
+//! overlaps are pushed apart by mass, entities can carry a player standing
+//! on them (moving platforms) and a player squeezed between an entity and
+//! solid voxels is reported as crushed. Operations live in
+//! entity_collision_operations.rs.
+//!
+//! NO METHODS - just data.
+
+use super::EntityId;
+use std::collections::HashMap;
+
+/// Collision shape of the player, centered on its position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlayerCollisionShape {
+    /// Axis-aligned box (voxels)
+    Box { half_extents: [f32; 3] },
+    /// Upright capsule; `half_height` includes the end caps (voxels)
+    Capsule { radius: f32, half_height: f32 },
+}
+
+/// Motion and mass of one entity the player can touch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityCollisionBody {
+    pub entity: EntityId,
+    /// Velocity this tick (voxels/s); carries players standing on top
+    pub velocity: [f32; 3],
+    /// 0.0 for kinematic entities (platforms, doors) the player cannot move
+    pub inverse_mass: f32,
+}
+
+/// Collision bodies by entity. Entities in the BVH without a body are
+/// treated as static and immovable.
+#[derive(Debug, Clone, Default)]
+pub struct EntityCollisionBodies {
+    pub bodies: HashMap<EntityId, EntityCollisionBody>,
+}
+
+/// Overlap between the player shape and an entity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityPenetration {
+    /// Unit direction that moves the player out of the entity
+    pub normal: [f32; 3],
+    /// Distance along `normal` to separate (voxels)
+    pub depth: f32,
+}
+
+/// Player tuning for entity collision
+#[derive(Debug, Clone, Copy)]
+pub struct PlayerEntityCollisionConfig {
+    pub shape: PlayerCollisionShape,
+    pub inverse_mass: f32,
+    /// Gap kept after a sweep hit (voxels)
+    pub skin: f32,
+    /// Slide iterations per tick
+    pub max_slides: usize,
+    /// Blocked push-out that counts as crushed (voxels)
+    pub crush_depth: f32,
+    /// How far below the feet supporting entities are found (voxels)
+    pub ground_probe: f32,
+}
+
+/// Player state carried between ticks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayerEntityCollisionState {
+    /// Center of the collision shape (voxels)
+    pub position: [f32; 3],
+    /// Player velocity (voxels/s)
+    pub velocity: [f32; 3],
+    /// Entity the player is standing on
+    pub ground_entity: Option<EntityId>,
+}
+
+/// Positional correction to apply to an entity the player pushed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityPush {
+    pub entity: EntityId,
+    /// Offset to add to the entity's position (voxels)
+    pub correction: [f32; 3],
+}
+
+/// Something that happened between the player and an entity this tick
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlayerEntityEvent {
+    /// The player's movement was stopped by an entity
+    Blocked { entity: EntityId, normal: [f32; 3] },
+    /// The player started standing on an entity
+    Landed { entity: EntityId },
+    /// The player stopped standing on an entity
+    LeftEntity { entity: EntityId },
+    /// An entity pressed the player into solid voxels
+    Crushed { entity: EntityId, depth: f32 },
+}
+
+/// Result of one player-vs-entity tick
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerEntityCollisionStep {
+    pub pushes: Vec<EntityPush>,
+    pub events: Vec<PlayerEntityEvent>,
+}
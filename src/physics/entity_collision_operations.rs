@@ -0,0 +1,760 @@
+//! Entity Collision Operations - Pure DOP
+//!
+//! Resolves the player against dynamic entities once per tick, after the
+//! character controller has moved it against voxels.
+
+use super::aabb::{aabb_penetration_vector, AABB};
+use super::entity_collision_data::{
+    EntityCollisionBodies, EntityCollisionBody, EntityPenetration, EntityPush,
+    PlayerCollisionShape, PlayerEntityCollisionConfig, PlayerEntityCollisionState,
+    PlayerEntityCollisionStep, PlayerEntityEvent,
+};
+use super::picking_data::{EntityHitboxes, EntityPickBvh};
+use super::picking_operations::{build_entity_pick_bvh, query_entity_pick_bvh};
+use super::EntityId;
+use crate::constants::physics_constants::{
+    CRUSH_DEPTH, DYNAMIC_ENTITY_MASS, ENTITY_CONTACT_SKIN, ENTITY_MAX_SLIDES, PLAYER_HALF_EXTENTS,
+    PLAYER_MASS, SURFACE_PROBE_DEPTH,
+};
+use crate::engine_buffers::PhysicsBuffers;
+use crate::world::core::{world_coord_to_voxel, VoxelPos};
+use cgmath::{InnerSpace, Point3, Vector3, Zero};
+
+/// Overlaps and gaps smaller than this count as touching (voxels)
+const CONTACT_EPSILON: f32 = 1e-4;
+
+/// Contact normals at least this upright mean the player stands on top
+const GROUND_NORMAL_Y: f32 = 0.7;
+
+/// Bisection steps when a move runs into voxels
+const VOXEL_BISECT_STEPS: u32 = 8;
+
+/// First entity a sweep touches
+struct SweptContact {
+    entity: EntityId,
+    /// Fraction of the displacement travelled before contact (0.0-1.0)
+    time: f32,
+    normal: Vector3<f32>,
+}
+
+/// Box-shaped player with the engine's default mass and tolerances
+pub fn default_player_entity_collision_config() -> PlayerEntityCollisionConfig {
+    PlayerEntityCollisionConfig {
+        shape: PlayerCollisionShape::Box {
+            half_extents: PLAYER_HALF_EXTENTS,
+        },
+        inverse_mass: 1.0 / PLAYER_MASS,
+        skin: ENTITY_CONTACT_SKIN,
+        max_slides: ENTITY_MAX_SLIDES,
+        crush_depth: CRUSH_DEPTH,
+        ground_probe: SURFACE_PROBE_DEPTH,
+    }
+}
+
+/// Add or replace the collision body of an entity
+pub fn set_entity_collision_body(bodies: &mut EntityCollisionBodies, body: EntityCollisionBody) {
+    bodies.bodies.insert(body.entity, body);
+}
+
+/// Forget an entity's body (it becomes static if still in the BVH)
+pub fn remove_entity_collision_body(bodies: &mut EntityCollisionBodies, entity: EntityId) {
+    bodies.bodies.remove(&entity);
+}
+
+/// Entity BVH and bodies for the entities in the physics buffers. Kinematic
+/// entities carry the player but cannot be pushed, dynamic ones are pushed
+/// as `DYNAMIC_ENTITY_MASS` and static ones get no body.
+pub fn physics_entity_collision_scene(
+    physics: &PhysicsBuffers,
+) -> (EntityPickBvh, EntityCollisionBodies) {
+    let mut hitboxes = Vec::with_capacity(physics.aabbs.len());
+    let mut bodies = EntityCollisionBodies::default();
+    for (index, aabb) in physics.aabbs.iter().enumerate() {
+        let entity = index as EntityId;
+        hitboxes.push(EntityHitboxes {
+            entity,
+            bounds: AABB {
+                min: Point3::from(aabb.min),
+                max: Point3::from(aabb.max),
+            },
+            parts: Vec::new(),
+        });
+        let flags = physics.flags.get(index).copied().unwrap_or_default();
+        let inverse_mass = match (flags.is_kinematic, flags.is_dynamic) {
+            (true, _) => 0.0,
+            (false, true) => 1.0 / DYNAMIC_ENTITY_MASS,
+            (false, false) => continue,
+        };
+        let velocity = physics.velocities.get(index).copied().unwrap_or([0.0; 3]);
+        set_entity_collision_body(
+            &mut bodies,
+            EntityCollisionBody {
+                entity,
+                velocity,
+                inverse_mass,
+            },
+        );
+    }
+    (build_entity_pick_bvh(hitboxes), bodies)
+}
+
+/// Move the entities the player pushed, with their bounds
+pub fn apply_entity_pushes(physics: &mut PhysicsBuffers, pushes: &[EntityPush]) {
+    for push in pushes {
+        let index = push.entity as usize;
+        if let Some(position) = physics.positions.get_mut(index) {
+            for (axis, correction) in push.correction.iter().enumerate() {
+                position[axis] += correction;
+            }
+        }
+        if let Some(aabb) = physics.aabbs.get_mut(index) {
+            for (axis, correction) in push.correction.iter().enumerate() {
+                aabb.min[axis] += correction;
+                aabb.max[axis] += correction;
+            }
+        }
+    }
+}
+
+/// Half-extents of the box enclosing the player shape
+pub fn player_shape_half_extents(shape: &PlayerCollisionShape) -> [f32; 3] {
+    match *shape {
+        PlayerCollisionShape::Box { half_extents } => half_extents,
+        PlayerCollisionShape::Capsule {
+            radius,
+            half_height,
+        } => [radius, half_height, radius],
+    }
+}
+
+/// Bounds of the player's collision shape centred at `position`
+pub fn player_bounds(shape: &PlayerCollisionShape, position: Vector3<f32>) -> AABB {
+    let [x, y, z] = player_shape_half_extents(shape);
+    AABB {
+        min: Point3::new(position.x - x, position.y - y, position.z - z),
+        max: Point3::new(position.x + x, position.y + y, position.z + z),
+    }
+}
+
+/// How far and in which direction the player must move to leave `other`.
+/// The normal points from the entity toward the player.
+pub fn player_entity_penetration(
+    shape: &PlayerCollisionShape,
+    position: [f32; 3],
+    other: &AABB,
+) -> Option<EntityPenetration> {
+    let position = Vector3::from(position);
+    match *shape {
+        PlayerCollisionShape::Box { .. } => {
+            let separation = aabb_penetration_vector(&player_bounds(shape, position), other)?;
+            let depth = separation.magnitude();
+            if depth <= CONTACT_EPSILON {
+                return None;
+            }
+            Some(EntityPenetration {
+                normal: (separation / depth).into(),
+                depth,
+            })
+        }
+        PlayerCollisionShape::Capsule {
+            radius,
+            half_height,
+        } => capsule_penetration(radius, half_height, position, other),
+    }
+}
+
+/// Upright capsule against a box: closest point between the capsule's
+/// vertical core segment and the box
+fn capsule_penetration(
+    radius: f32,
+    half_height: f32,
+    position: Vector3<f32>,
+    other: &AABB,
+) -> Option<EntityPenetration> {
+    let core = (half_height - radius).max(0.0);
+    let (bottom, top) = (position.y - core, position.y + core);
+    let closest_x = position.x.clamp(other.min.x, other.max.x);
+    let closest_z = position.z.clamp(other.min.z, other.max.z);
+
+    // Core segment entirely above or below the box: an end cap touches it
+    let cap = if bottom > other.max.y {
+        Some((bottom, other.max.y, 1.0))
+    } else if top < other.min.y {
+        Some((top, other.min.y, -1.0))
+    } else {
+        None
+    };
+    if let Some((cap_y, face_y, side)) = cap {
+        let offset = Vector3::new(
+            position.x - closest_x,
+            cap_y - face_y,
+            position.z - closest_z,
+        );
+        let distance = offset.magnitude();
+        if distance >= radius - CONTACT_EPSILON {
+            return None;
+        }
+        let normal = if distance > f32::EPSILON {
+            offset / distance
+        } else {
+            Vector3::new(0.0, side, 0.0)
+        };
+        return Some(EntityPenetration {
+            normal: normal.into(),
+            depth: radius - distance,
+        });
+    }
+
+    let (dx, dz) = (position.x - closest_x, position.z - closest_z);
+    let distance = (dx * dx + dz * dz).sqrt();
+    if distance > f32::EPSILON {
+        if distance >= radius - CONTACT_EPSILON {
+            return None;
+        }
+        return Some(EntityPenetration {
+            normal: [dx / distance, 0.0, dz / distance],
+            depth: radius - distance,
+        });
+    }
+
+    // Core axis inside the box footprint: leave through the nearest face
+    let exits = [
+        ([1.0, 0.0, 0.0], other.max.x - position.x + radius),
+        ([-1.0, 0.0, 0.0], position.x - other.min.x + radius),
+        ([0.0, 0.0, 1.0], other.max.z - position.z + radius),
+        ([0.0, 0.0, -1.0], position.z - other.min.z + radius),
+        ([0.0, 1.0, 0.0], other.max.y - bottom + radius),
+        ([0.0, -1.0, 0.0], top - other.min.y + radius),
+    ];
+    exits
+        .into_iter()
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(normal, depth)| EntityPenetration { normal, depth })
+}
+
+/// Whether any solid voxel overlaps `bounds` (touching faces do not count)
+fn overlaps_solid_voxels(bounds: &AABB, solid_at: &impl Fn(VoxelPos) -> bool) -> bool {
+    let first = |value: f32| world_coord_to_voxel(value + CONTACT_EPSILON);
+    let last = |value: f32| world_coord_to_voxel(value - CONTACT_EPSILON);
+    for x in first(bounds.min.x)..=last(bounds.max.x) {
+        for y in first(bounds.min.y)..=last(bounds.max.y) {
+            for z in first(bounds.min.z)..=last(bounds.max.z) {
+                if solid_at(VoxelPos { x, y, z }) {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Move the player by `offset`, stopping short of solid voxels. Returns the
+/// fraction of the offset travelled.
+fn move_against_voxels(
+    position: &mut Vector3<f32>,
+    offset: Vector3<f32>,
+    shape: &PlayerCollisionShape,
+    solid_at: &impl Fn(VoxelPos) -> bool,
+) -> f32 {
+    if offset.magnitude2() <= 0.0 {
+        return 1.0;
+    }
+
+    let start = *position;
+    let fits = |fraction: f32| {
+        !overlaps_solid_voxels(&player_bounds(shape, start + offset * fraction), solid_at)
+    };
+    let travelled = if fits(1.0) {
+        1.0
+    } else {
+        let (mut low, mut high) = (0.0f32, 1.0f32);
+        for _ in 0..VOXEL_BISECT_STEPS {
+            let middle = (low + high) * 0.5;
+            if fits(middle) {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    };
+    *position = start + offset * travelled;
+    travelled
+}
+
+/// Cancel the part of the player's velocity that moves into the entity
+fn remove_approach_velocity(
+    velocity: &mut Vector3<f32>,
+    normal: Vector3<f32>,
+    entity_velocity: Vector3<f32>,
+) {
+    let approach = (*velocity - entity_velocity).dot(normal);
+    if approach < 0.0 {
+        *velocity -= normal * approach;
+    }
+}
+
+fn body_velocity(body: Option<&EntityCollisionBody>) -> Vector3<f32> {
+    body.map_or(Vector3::zero(), |body| Vector3::from(body.velocity))
+}
+
+/// Separate the player from entities that moved into it. The overlap is
+/// split by inverse mass; whatever the voxels stop the player from taking
+/// goes to the entity, or crushes the player if the entity is kinematic.
+fn push_out_of_entities(
+    position: &mut Vector3<f32>,
+    velocity: &mut Vector3<f32>,
+    config: &PlayerEntityCollisionConfig,
+    bvh: &EntityPickBvh,
+    bodies: &EntityCollisionBodies,
+    solid_at: &impl Fn(VoxelPos) -> bool,
+    step: &mut PlayerEntityCollisionStep,
+) {
+    let bounds = player_bounds(&config.shape, *position);
+    for index in query_entity_pick_bvh(bvh, &bounds) {
+        let hitboxes = &bvh.entities[index];
+        let Some(contact) =
+            player_entity_penetration(&config.shape, (*position).into(), &hitboxes.bounds)
+        else {
+            continue;
+        };
+
+        let body = bodies.bodies.get(&hitboxes.entity);
+        let entity_inverse_mass = body.map_or(0.0, |body| body.inverse_mass);
+        let total = config.inverse_mass + entity_inverse_mass;
+        let player_share = if total > 0.0 {
+            config.inverse_mass / total
+        } else {
+            1.0
+        };
+
+        let normal = Vector3::from(contact.normal);
+        let wanted = contact.depth * player_share;
+        let moved =
+            wanted * move_against_voxels(position, normal * wanted, &config.shape, solid_at);
+        let remaining = contact.depth - moved;
+
+        if entity_inverse_mass > 0.0 {
+            if remaining > CONTACT_EPSILON {
+                step.pushes.push(EntityPush {
+                    entity: hitboxes.entity,
+                    correction: (-normal * remaining).into(),
+                });
+            }
+        } else if remaining > config.crush_depth {
+            step.events.push(PlayerEntityEvent::Crushed {
+                entity: hitboxes.entity,
+                depth: remaining,
+            });
+        }
+
+        remove_approach_velocity(velocity, normal, body_velocity(body));
+    }
+}
+
+/// Time of impact and normal of the player box swept against an entity.
+/// Entities the player already overlaps are left to the push-out.
+fn sweep_entity(
+    bounds: &AABB,
+    displacement: Vector3<f32>,
+    hitboxes: &EntityHitboxes,
+) -> Option<SweptContact> {
+    let other = &hitboxes.bounds;
+    let mut enter = f32::MIN;
+    let mut exit = f32::MAX;
+    let mut entry_axis = None;
+
+    for axis in 0..3 {
+        let moving = displacement[axis];
+        if moving.abs() < f32::EPSILON {
+            if bounds.max[axis] <= other.min[axis] + CONTACT_EPSILON
+                || bounds.min[axis] >= other.max[axis] - CONTACT_EPSILON
+            {
+                return None;
+            }
+            continue;
+        }
+
+        let (near, far) = if moving > 0.0 {
+            (
+                other.min[axis] - bounds.max[axis],
+                other.max[axis] - bounds.min[axis],
+            )
+        } else {
+            (
+                other.max[axis] - bounds.min[axis],
+                other.min[axis] - bounds.max[axis],
+            )
+        };
+        let (t_near, t_far) = (near / moving, far / moving);
+        if t_near > enter {
+            enter = t_near;
+            entry_axis = Some((axis, t_near * moving.abs()));
+        }
+        exit = exit.min(t_far);
+    }
+
+    let (axis, gap) = entry_axis?;
+    if gap < -CONTACT_EPSILON || enter > 1.0 || enter >= exit || exit <= 0.0 {
+        return None;
+    }
+    let mut normal = Vector3::zero();
+    normal[axis] = -displacement[axis].signum();
+    Some(SweptContact {
+        entity: hitboxes.entity,
+        time: enter.max(0.0),
+        normal,
+    })
+}
+
+fn first_swept_contact(
+    bounds: &AABB,
+    displacement: Vector3<f32>,
+    bvh: &EntityPickBvh,
+) -> Option<SweptContact> {
+    let moved = AABB {
+        min: bounds.min + displacement,
+        max: bounds.max + displacement,
+    };
+    let swept = AABB {
+        min: Point3::new(
+            bounds.min.x.min(moved.min.x),
+            bounds.min.y.min(moved.min.y),
+            bounds.min.z.min(moved.min.z),
+        ),
+        max: Point3::new(
+            bounds.max.x.max(moved.max.x),
+            bounds.max.y.max(moved.max.y),
+            bounds.max.z.max(moved.max.z),
+        ),
+    };
+
+    query_entity_pick_bvh(bvh, &swept)
+        .into_iter()
+        .filter_map(|index| sweep_entity(bounds, displacement, &bvh.entities[index]))
+        .min_by(|a, b| {
+            a.time
+                .partial_cmp(&b.time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
+
+/// Move along `displacement`, sliding over the faces of entities in the way.
+/// The sweep uses the shape's enclosing box.
+fn sweep_through_entities(
+    position: &mut Vector3<f32>,
+    velocity: &mut Vector3<f32>,
+    config: &PlayerEntityCollisionConfig,
+    displacement: Vector3<f32>,
+    bvh: &EntityPickBvh,
+    bodies: &EntityCollisionBodies,
+    solid_at: &impl Fn(VoxelPos) -> bool,
+    step: &mut PlayerEntityCollisionStep,
+) {
+    let mut remaining = displacement;
+    for _ in 0..config.max_slides {
+        if remaining.magnitude2() <= CONTACT_EPSILON * CONTACT_EPSILON {
+            return;
+        }
+
+        let bounds = player_bounds(&config.shape, *position);
+        let Some(contact) = first_swept_contact(&bounds, remaining, bvh) else {
+            move_against_voxels(position, remaining, &config.shape, solid_at);
+            return;
+        };
+
+        // Stop a skin's width short of the face, then slide along it
+        let along = remaining.dot(-contact.normal).max(f32::EPSILON);
+        let backoff = (config.skin / along).min(contact.time);
+        move_against_voxels(
+            position,
+            remaining * (contact.time - backoff),
+            &config.shape,
+            solid_at,
+        );
+        remaining *= 1.0 - contact.time;
+        remaining -= contact.normal * remaining.dot(contact.normal);
+
+        let body = bodies.bodies.get(&contact.entity);
+        remove_approach_velocity(velocity, contact.normal, body_velocity(body));
+        step.events.push(PlayerEntityEvent::Blocked {
+            entity: contact.entity,
+            normal: contact.normal.into(),
+        });
+    }
+}
+
+/// Entity directly under the player's feet, if any
+fn find_ground_entity(
+    position: Vector3<f32>,
+    config: &PlayerEntityCollisionConfig,
+    bvh: &EntityPickBvh,
+) -> Option<EntityId> {
+    let probe = position - Vector3::new(0.0, config.ground_probe + config.skin, 0.0);
+    let bounds = player_bounds(&config.shape, probe);
+    query_entity_pick_bvh(bvh, &bounds)
+        .into_iter()
+        .filter_map(|index| {
+            let hitboxes = &bvh.entities[index];
+            let contact = player_entity_penetration(&config.shape, probe.into(), &hitboxes.bounds)?;
+            (contact.normal[1] >= GROUND_NORMAL_Y).then_some(hitboxes)
+        })
+        .max_by(|a, b| {
+            a.bounds
+                .max
+                .y
+                .partial_cmp(&b.bounds.max.y)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|hitboxes| hitboxes.entity)
+}
+
+/// Resolve the player against dynamic entities for one tick.
+///
+/// `bvh` holds the entities at their positions after this tick's motion and
+/// `displacement` is the player's own movement, already clipped against
+/// voxels. The player first rides the entity it stood on, is then pushed
+/// out of entities that moved into it and finally swept along
+/// `displacement`. `solid_at` keeps every move out of solid voxels. The
+/// returned pushes should be applied to the entities before the next tick.
+pub fn resolve_player_entity_collisions(
+    state: &mut PlayerEntityCollisionState,
+    config: &PlayerEntityCollisionConfig,
+    displacement: [f32; 3],
+    bvh: &EntityPickBvh,
+    bodies: &EntityCollisionBodies,
+    delta_time: f32,
+    solid_at: impl Fn(VoxelPos) -> bool,
+) -> PlayerEntityCollisionStep {
+    let mut step = PlayerEntityCollisionStep::default();
+    let mut position = Vector3::from(state.position);
+    let mut velocity = Vector3::from(state.velocity);
+
+    let platform = state
+        .ground_entity
+        .and_then(|entity| bodies.bodies.get(&entity));
+    if let Some(platform) = platform {
+        let carry = Vector3::from(platform.velocity) * delta_time;
+        move_against_voxels(&mut position, carry, &config.shape, &solid_at);
+    }
+
+    push_out_of_entities(
+        &mut position,
+        &mut velocity,
+        config,
+        bvh,
+        bodies,
+        &solid_at,
+        &mut step,
+    );
+    sweep_through_entities(
+        &mut position,
+        &mut velocity,
+        config,
+        Vector3::from(displacement),
+        bvh,
+        bodies,
+        &solid_at,
+        &mut step,
+    );
+
+    let ground = find_ground_entity(position, config, bvh);
+    if ground != state.ground_entity {
+        if let Some(entity) = state.ground_entity {
+            step.events.push(PlayerEntityEvent::LeftEntity { entity });
+        }
+        if let Some(entity) = ground {
+            step.events.push(PlayerEntityEvent::Landed { entity });
+        }
+    }
+    // Standing on an entity: never fall faster than it moves
+    if let Some(body) = ground.and_then(|entity| bodies.bodies.get(&entity)) {
+        velocity.y = velocity.y.max(body.velocity[1]);
+    }
+
+    state.position = position.into();
+    state.velocity = velocity.into();
+    state.ground_entity = ground;
+    step
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::picking_operations::build_entity_pick_bvh;
+
+    const PLATFORM: EntityId = 1;
+    const CRATE: EntityId = 2;
+
+    fn entity(entity: EntityId, min: [f32; 3], max: [f32; 3]) -> EntityHitboxes {
+        EntityHitboxes {
+            entity,
+            bounds: AABB {
+                min: Point3::from(min),
+                max: Point3::from(max),
+            },
+            parts: Vec::new(),
+        }
+    }
+
+    fn player_at(position: [f32; 3]) -> PlayerEntityCollisionState {
+        PlayerEntityCollisionState {
+            position,
+            velocity: [0.0; 3],
+            ground_entity: None,
+        }
+    }
+
+    #[test]
+    fn test_platform_carries_and_crushes_player() {
+        let config = default_player_entity_collision_config();
+        let mut bodies = EntityCollisionBodies::default();
+        set_entity_collision_body(
+            &mut bodies,
+            EntityCollisionBody {
+                entity: PLATFORM,
+                velocity: [6.0, 0.0, 0.0],
+                inverse_mass: 0.0,
+            },
+        );
+        let open = |_: VoxelPos| false;
+
+        // Standing on a platform lands on it and then rides along
+        let mut state = player_at([0.0, 9.0, 0.0]);
+        let bvh = build_entity_pick_bvh(vec![entity(
+            PLATFORM,
+            [-10.0, -2.0, -10.0],
+            [10.0, 0.0, 10.0],
+        )]);
+        let step = resolve_player_entity_collisions(
+            &mut state, &config, [0.0; 3], &bvh, &bodies, 0.5, open,
+        );
+        assert_eq!(state.ground_entity, Some(PLATFORM));
+        assert!(step
+            .events
+            .contains(&PlayerEntityEvent::Landed { entity: PLATFORM }));
+        let bvh = build_entity_pick_bvh(vec![entity(
+            PLATFORM,
+            [-7.0, -2.0, -10.0],
+            [13.0, 0.0, 10.0],
+        )]);
+        resolve_player_entity_collisions(&mut state, &config, [0.0; 3], &bvh, &bodies, 0.5, open);
+        assert!((state.position[0] - 3.0).abs() < 1e-4);
+        assert_eq!(state.ground_entity, Some(PLATFORM));
+
+        // A kinematic platform rising into a player under a ceiling crushes
+        let ceiling = |pos: VoxelPos| pos.y >= 18;
+        let mut state = player_at([0.0, 9.0, 0.0]);
+        let bvh = build_entity_pick_bvh(vec![entity(
+            PLATFORM,
+            [-10.0, -2.0, -10.0],
+            [10.0, 2.0, 10.0],
+        )]);
+        let step = resolve_player_entity_collisions(
+            &mut state, &config, [0.0; 3], &bvh, &bodies, 0.5, ceiling,
+        );
+        assert!(step.events.iter().any(|event| matches!(
+            event,
+            PlayerEntityEvent::Crushed { entity: PLATFORM, depth } if (*depth - 2.0).abs() < 0.05
+        )));
+    }
+
+    #[test]
+    fn test_mass_weighted_push_and_sweep() {
+        let config = default_player_entity_collision_config();
+        let mut bodies = EntityCollisionBodies::default();
+        set_entity_collision_body(
+            &mut bodies,
+            EntityCollisionBody {
+                entity: CRATE,
+                velocity: [0.0; 3],
+                inverse_mass: 1.0 / PLAYER_MASS,
+            },
+        );
+        let open = |_: VoxelPos| false;
+
+        // Equal masses share a 2 voxel overlap evenly
+        let mut state = player_at([0.0, 9.0, 0.0]);
+        let bvh = build_entity_pick_bvh(vec![entity(CRATE, [2.0, 0.0, -4.0], [10.0, 8.0, 4.0])]);
+        let step = resolve_player_entity_collisions(
+            &mut state, &config, [0.0; 3], &bvh, &bodies, 0.1, open,
+        );
+        assert!((state.position[0] + 1.0).abs() < 1e-4);
+        assert_eq!(step.pushes.len(), 1);
+        assert!((step.pushes[0].correction[0] - 1.0).abs() < 1e-4);
+
+        // Walking into the crate stops at its face and slides along it
+        let mut state = player_at([-10.0, 9.0, 0.0]);
+        let step = resolve_player_entity_collisions(
+            &mut state,
+            &config,
+            [12.0, 0.0, 3.0],
+            &bvh,
+            &bodies,
+            0.1,
+            open,
+        );
+        assert!((state.position[0] - (-2.0 - ENTITY_CONTACT_SKIN)).abs() < 1e-3);
+        assert!((state.position[2] - 3.0).abs() < 1e-2);
+        assert!(step.events.contains(&PlayerEntityEvent::Blocked {
+            entity: CRATE,
+            normal: [-1.0, 0.0, 0.0],
+        }));
+
+        // A capsule resting against the crate's side is pushed straight out
+        let capsule = PlayerCollisionShape::Capsule {
+            radius: 4.0,
+            half_height: 9.0,
+        };
+        let contact =
+            player_entity_penetration(&capsule, [-1.0, 9.0, 0.0], &bvh.entities[0].bounds);
+        assert_eq!(
+            contact.map(|contact| contact.normal),
+            Some([-1.0, 0.0, 0.0])
+        );
+    }
+
+    #[test]
+    fn test_physics_buffer_entities_are_pushed() {
+        use crate::engine_buffers::{create_engine_buffers, PhysicsFlags, AABB as BufferAABB};
+
+        let mut physics = create_engine_buffers().physics;
+        physics.positions = vec![[6.0, 4.0, 0.0], [0.0, -4.0, 0.0]];
+        physics.velocities = vec![[0.0; 3], [3.0, 0.0, 0.0]];
+        physics.aabbs = vec![
+            BufferAABB {
+                min: [2.0, 0.0, -4.0],
+                max: [10.0, 8.0, 4.0],
+            },
+            BufferAABB {
+                min: [-20.0, -8.0, -20.0],
+                max: [20.0, 0.0, 20.0],
+            },
+        ];
+        physics.flags = vec![
+            PhysicsFlags::default(),
+            PhysicsFlags {
+                is_kinematic: true,
+                is_dynamic: false,
+                ..PhysicsFlags::default()
+            },
+        ];
+
+        let (bvh, bodies) = physics_entity_collision_scene(&physics);
+        assert_eq!(bvh.entities.len(), 2);
+        assert_eq!(bodies.bodies[&0].inverse_mass, 1.0 / DYNAMIC_ENTITY_MASS);
+        assert_eq!(bodies.bodies[&1].inverse_mass, 0.0);
+        assert_eq!(bodies.bodies[&1].velocity, [3.0, 0.0, 0.0]);
+
+        apply_entity_pushes(
+            &mut physics,
+            &[EntityPush {
+                entity: 0,
+                correction: [1.5, 0.0, 0.0],
+            }],
+        );
+        assert_eq!(physics.positions[0], [7.5, 4.0, 0.0]);
+        assert_eq!(physics.aabbs[0].min[0], 3.5);
+        assert_eq!(physics.aabbs[0].max[0], 11.5);
+    }
+}
@@ -0,0 +1,92 @@
+//! Integration Operations - Pure DOP
+//!
+//! Advances physics entities by one fixed tick. The engine passes only the
+//! entities inside the physics activation range; the others keep their
+//! state untouched until a player comes back.
+
+use crate::engine_buffers::PhysicsBuffers;
+
+/// Move `entities` (indices into the buffers) by one tick: acceleration
+/// into velocity for dynamic entities, velocity into position and bounds
+/// for dynamic and kinematic ones. Static entities never move. Returns how
+/// many entities moved.
+pub fn integrate_physics_entities(
+    physics: &mut PhysicsBuffers,
+    entities: &[usize],
+    delta_time: f32,
+) -> usize {
+    let mut moved = 0;
+    for &index in entities {
+        let Some(flags) = physics.flags.get(index).copied() else {
+            continue;
+        };
+        if flags.is_static || !(flags.is_dynamic || flags.is_kinematic) {
+            continue;
+        }
+        let Some(velocity) = physics.velocities.get_mut(index) else {
+            continue;
+        };
+        if flags.is_dynamic {
+            if let Some(acceleration) = physics.accelerations.get(index) {
+                for (v, a) in velocity.iter_mut().zip(acceleration) {
+                    *v += a * delta_time;
+                }
+            }
+        }
+        let step = velocity.map(|v| v * delta_time);
+        if let Some(position) = physics.positions.get_mut(index) {
+            for (p, s) in position.iter_mut().zip(step) {
+                *p += s;
+            }
+        }
+        if let Some(aabb) = physics.aabbs.get_mut(index) {
+            for (axis, s) in step.iter().enumerate() {
+                aabb.min[axis] += s;
+                aabb.max[axis] += s;
+            }
+        }
+        moved += 1;
+    }
+    physics.physics_tick += 1;
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_buffers::{create_engine_buffers, PhysicsFlags, AABB};
+
+    #[test]
+    fn test_only_listed_entities_move() {
+        let mut buffers = create_engine_buffers();
+        let physics = &mut buffers.physics;
+        let dynamic = PhysicsFlags {
+            is_dynamic: true,
+            ..PhysicsFlags::default()
+        };
+        physics.positions = vec![[0.0; 3]; 3];
+        physics.velocities = vec![[1.0, 0.0, 0.0]; 3];
+        physics.accelerations = vec![[0.0, -10.0, 0.0]; 3];
+        physics.aabbs = vec![
+            AABB {
+                min: [-0.5; 3],
+                max: [0.5; 3],
+            };
+            3
+        ];
+        physics.flags = vec![
+            dynamic,
+            dynamic,
+            PhysicsFlags {
+                is_static: true,
+                ..PhysicsFlags::default()
+            },
+        ];
+
+        assert_eq!(integrate_physics_entities(physics, &[0, 2], 0.5), 1);
+        assert_eq!(physics.positions[0], [0.5, -2.5, 0.0]);
+        assert_eq!(physics.aabbs[0].min, [0.0, -3.0, -0.5]);
+        assert_eq!(physics.positions[1], [0.0; 3]);
+        assert_eq!(physics.positions[2], [0.0; 3]);
+    }
+}
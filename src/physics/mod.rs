@@ -1,27 +1,69 @@
 //! Physics Module - Simplified for DOP conversion
 
 pub mod aabb;
+pub mod buoyancy_data;
+pub mod buoyancy_operations;
 pub mod collision_data;
+pub mod entity_collision_data;
+pub mod entity_collision_operations;
 pub mod gpu_physics_world;
 pub mod gpu_physics_world_data;
 pub mod gpu_physics_world_operations;
 pub mod integration;
+pub mod integration_operations;
 pub mod parallel_solver;
 pub mod parallel_solver_data;
 pub mod parallel_solver_operations;
+pub mod picking_data;
+pub mod picking_operations;
 pub mod preallocated_spatial_hash;
 pub mod spatial_hash;
+pub mod surface_material_data;
+pub mod surface_material_operations;
 
 // Simple re-exports
 pub use aabb::AABB;
+pub use buoyancy_data::{BuoyancyConfig, FluidMaterial, FluidMaterialTable, FluidSubmersion};
+pub use buoyancy_operations::{
+    apply_buoyancy, build_fluid_material_table, default_buoyancy_config, fluid_material,
+    fluid_material_from_physics, no_fluid_flow, sample_fluid_submersion, set_fluid_material,
+    update_entity_buoyancy,
+};
 pub use collision_data::{CollisionData, ContactPoint, ContactPair, CollisionStats};
+pub use entity_collision_data::{
+    EntityCollisionBodies, EntityCollisionBody, EntityPenetration, EntityPush,
+    PlayerCollisionShape, PlayerEntityCollisionConfig, PlayerEntityCollisionState,
+    PlayerEntityCollisionStep, PlayerEntityEvent,
+};
+pub use entity_collision_operations::{
+    apply_entity_pushes, default_player_entity_collision_config, physics_entity_collision_scene,
+    player_bounds, player_entity_penetration, player_shape_half_extents,
+    remove_entity_collision_body, resolve_player_entity_collisions, set_entity_collision_body,
+};
 pub use gpu_physics_world::GpuPhysicsWorld;
 pub use gpu_physics_world_data::GpuPhysicsWorldData;
 pub use integration::Integration;
+pub use integration_operations::integrate_physics_entities;
 pub use parallel_solver::ParallelSolver;
 pub use parallel_solver_data::ParallelSolverData;
+pub use picking_data::{
+    BodyPartId, EntityHitboxes, EntityPickBvh, EntityRayHit, HitboxPart, PickBvhNode, PickHit,
+    PickScene, PickTarget, WHOLE_BODY_PART,
+};
+pub use picking_operations::{
+    build_entity_pick_bvh, camera_cursor_ray, pick_from_camera, pick_from_cursor, pick_ray,
+    query_entity_pick_bvh, ray_aabb_distance, raycast_entities,
+};
 pub use preallocated_spatial_hash::PreallocatedSpatialHash;
 pub use spatial_hash::SpatialHash;
+pub use surface_material_data::{
+    CharacterSurfaceConfig, SurfaceMaterial, SurfaceMaterialTable, SurfaceSample,
+};
+pub use surface_material_operations::{
+    apply_character_surface, blend_surface_materials, build_surface_material_table,
+    default_character_surface_config, default_surface_material, sample_ground_material,
+    set_surface_material, surface_material, surface_material_from_physics,
+};
 
 // Re-export DOP operations
 pub use gpu_physics_world_operations::{initialize_gpu_physics_world, add_physics_entity, update_physics};
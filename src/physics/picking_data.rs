@@ -0,0 +1,88 @@
+//! Entity Picking Data - Pure DOP
+//!
+//! Hitboxes and the entity BVH used to pick entities and blocks from a ray.
+//! Operations live in picking_operations.rs.
+
+use super::aabb::AABB;
+use super::EntityId;
+use crate::world::data_types::WorldData;
+use crate::world::{BlockFace, BlockId, VoxelPos};
+use cgmath::Point3;
+
+/// Identifies a hitbox part within an entity (head, torso, ...)
+pub type BodyPartId = u16;
+
+/// Part reported for entities that have no per-part hitboxes
+pub const WHOLE_BODY_PART: BodyPartId = 0;
+
+/// One hitbox of an entity, in world space
+#[derive(Debug, Clone, Copy)]
+pub struct HitboxPart {
+    pub part: BodyPartId,
+    pub aabb: AABB,
+}
+
+/// Pickable entity: coarse bounds plus optional per-part hitboxes
+///
+/// When `parts` is empty the bounds are the hitbox.
+#[derive(Debug, Clone)]
+pub struct EntityHitboxes {
+    pub entity: EntityId,
+    pub bounds: AABB,
+    pub parts: Vec<HitboxPart>,
+}
+
+/// BVH node over entity bounds
+#[derive(Debug, Clone, Copy)]
+pub struct PickBvhNode {
+    pub aabb: AABB,
+    /// Left child index, or first entity index if leaf
+    pub left_first: u32,
+    /// Entity count (0 for internal nodes)
+    pub count: u32,
+}
+
+/// Entity BVH rebuilt by the caller when entities move
+#[derive(Debug, Clone, Default)]
+pub struct EntityPickBvh {
+    pub nodes: Vec<PickBvhNode>,
+    /// Entities, reordered so each leaf covers a contiguous range
+    pub entities: Vec<EntityHitboxes>,
+}
+
+/// Everything a pick ray can hit
+pub struct PickScene<'a> {
+    pub world: &'a WorldData,
+    pub chunk_size: u32,
+    pub entities: &'a EntityPickBvh,
+}
+
+/// What a pick ray hit, with sub-hit info
+#[derive(Debug, Clone, Copy)]
+pub enum PickTarget {
+    Block {
+        position: VoxelPos,
+        face: BlockFace,
+        block: BlockId,
+    },
+    Entity {
+        entity: EntityId,
+        part: BodyPartId,
+    },
+}
+
+/// Nearest hit along a pick ray
+#[derive(Debug, Clone, Copy)]
+pub struct PickHit {
+    pub target: PickTarget,
+    pub distance: f32,
+    pub point: Point3<f32>,
+}
+
+/// Nearest entity hit along a ray
+#[derive(Debug, Clone, Copy)]
+pub struct EntityRayHit {
+    pub entity: EntityId,
+    pub part: BodyPartId,
+    pub distance: f32,
+}
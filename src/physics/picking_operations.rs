@@ -0,0 +1,338 @@
+//! Entity Picking Operations - Pure DOP
+//!
+//! Combines the voxel raycast with an entity BVH so gameplay can select the
+//! nearest block or entity under the crosshair or cursor.
+
+use super::aabb::{aabb_intersects, AABB};
+use super::picking_data::{
+    EntityHitboxes, EntityPickBvh, EntityRayHit, PickBvhNode, PickHit, PickScene, PickTarget,
+    WHOLE_BODY_PART,
+};
+use crate::camera::{calculate_forward_vector, calculate_right_vector, CameraData};
+use crate::constants::physics_constants::PICK_BVH_LEAF_SIZE;
+use crate::world::world_operations;
+use crate::world::Ray;
+use cgmath::{InnerSpace, Point3, Vector3};
+
+/// Build the entity BVH (median split along the longest axis)
+pub fn build_entity_pick_bvh(entities: Vec<EntityHitboxes>) -> EntityPickBvh {
+    let mut bvh = EntityPickBvh {
+        nodes: Vec::with_capacity(entities.len().max(1) * 2),
+        entities,
+    };
+    if !bvh.entities.is_empty() {
+        let count = bvh.entities.len();
+        bvh.nodes.push(placeholder_node());
+        build_node(&mut bvh, 0, 0, count);
+    }
+    bvh
+}
+
+fn bounds_of(entities: &[EntityHitboxes]) -> AABB {
+    let mut min = Point3::new(f32::MAX, f32::MAX, f32::MAX);
+    let mut max = Point3::new(f32::MIN, f32::MIN, f32::MIN);
+    for entity in entities {
+        min.x = min.x.min(entity.bounds.min.x);
+        min.y = min.y.min(entity.bounds.min.y);
+        min.z = min.z.min(entity.bounds.min.z);
+        max.x = max.x.max(entity.bounds.max.x);
+        max.y = max.y.max(entity.bounds.max.y);
+        max.z = max.z.max(entity.bounds.max.z);
+    }
+    AABB { min, max }
+}
+
+fn center_on_axis(aabb: &AABB, axis: usize) -> f32 {
+    (aabb.min[axis] + aabb.max[axis]) * 0.5
+}
+
+/// Fill node `index` covering `entities[first..first + count]`
+///
+/// Children are allocated as a consecutive pair so only the left index is
+/// stored, matching `BvhNode` in the voxel BVH.
+fn build_node(bvh: &mut EntityPickBvh, index: usize, first: usize, count: usize) {
+    let aabb = bounds_of(&bvh.entities[first..first + count]);
+    bvh.nodes[index] = PickBvhNode {
+        aabb,
+        left_first: first as u32,
+        count: count as u32,
+    };
+
+    if count <= PICK_BVH_LEAF_SIZE {
+        return;
+    }
+
+    let extent = aabb.max - aabb.min;
+    let axis = if extent.x >= extent.y && extent.x >= extent.z {
+        0
+    } else if extent.y >= extent.z {
+        1
+    } else {
+        2
+    };
+
+    bvh.entities[first..first + count].sort_by(|a, b| {
+        center_on_axis(&a.bounds, axis)
+            .partial_cmp(&center_on_axis(&b.bounds, axis))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let left = bvh.nodes.len();
+    bvh.nodes.push(placeholder_node());
+    bvh.nodes.push(placeholder_node());
+    bvh.nodes[index].left_first = left as u32;
+    bvh.nodes[index].count = 0;
+
+    let half = count / 2;
+    build_node(bvh, left, first, half);
+    build_node(bvh, left + 1, first + half, count - half);
+}
+
+fn placeholder_node() -> PickBvhNode {
+    PickBvhNode {
+        aabb: AABB {
+            min: Point3::new(0.0, 0.0, 0.0),
+            max: Point3::new(0.0, 0.0, 0.0),
+        },
+        left_first: 0,
+        count: 0,
+    }
+}
+
+/// Slab test; returns the entry distance if the ray hits within `max_distance`
+pub fn ray_aabb_distance(ray: &Ray, aabb: &AABB, max_distance: f32) -> Option<f32> {
+    let mut t_min = 0.0f32;
+    let mut t_max = max_distance;
+
+    for axis in 0..3 {
+        let origin = ray.origin[axis];
+        let direction = ray.direction[axis];
+        if direction.abs() < f32::EPSILON {
+            if origin < aabb.min[axis] || origin > aabb.max[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let inv = 1.0 / direction;
+        let mut t0 = (aabb.min[axis] - origin) * inv;
+        let mut t1 = (aabb.max[axis] - origin) * inv;
+        if t0 > t1 {
+            std::mem::swap(&mut t0, &mut t1);
+        }
+        t_min = t_min.max(t0);
+        t_max = t_max.min(t1);
+        if t_min > t_max {
+            return None;
+        }
+    }
+
+    Some(t_min)
+}
+
+/// Test one entity's hitboxes; parts take precedence over the coarse bounds
+fn raycast_entity(ray: &Ray, entity: &EntityHitboxes, max_distance: f32) -> Option<EntityRayHit> {
+    let bounds_distance = ray_aabb_distance(ray, &entity.bounds, max_distance)?;
+
+    if entity.parts.is_empty() {
+        return Some(EntityRayHit {
+            entity: entity.entity,
+            part: WHOLE_BODY_PART,
+            distance: bounds_distance,
+        });
+    }
+
+    entity
+        .parts
+        .iter()
+        .filter_map(|part| {
+            ray_aabb_distance(ray, &part.aabb, max_distance).map(|distance| EntityRayHit {
+                entity: entity.entity,
+                part: part.part,
+                distance,
+            })
+        })
+        .min_by(|a, b| {
+            a.distance
+                .partial_cmp(&b.distance)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
+
+/// Nearest entity hitbox along the ray
+pub fn raycast_entities(bvh: &EntityPickBvh, ray: &Ray, max_distance: f32) -> Option<EntityRayHit> {
+    if bvh.nodes.is_empty() {
+        return None;
+    }
+
+    let mut best: Option<EntityRayHit> = None;
+    let mut closest = max_distance;
+    let mut stack = vec![0u32];
+
+    while let Some(node_index) = stack.pop() {
+        let node = &bvh.nodes[node_index as usize];
+        if ray_aabb_distance(ray, &node.aabb, closest).is_none() {
+            continue;
+        }
+
+        if node.count == 0 {
+            stack.push(node.left_first);
+            stack.push(node.left_first + 1);
+            continue;
+        }
+
+        let first = node.left_first as usize;
+        for entity in &bvh.entities[first..first + node.count as usize] {
+            if let Some(hit) = raycast_entity(ray, entity, closest) {
+                closest = hit.distance;
+                best = Some(hit);
+            }
+        }
+    }
+
+    best
+}
+
+/// Indices into `bvh.entities` whose bounds overlap `aabb`
+pub fn query_entity_pick_bvh(bvh: &EntityPickBvh, aabb: &AABB) -> Vec<usize> {
+    let mut found = Vec::new();
+    if bvh.nodes.is_empty() {
+        return found;
+    }
+
+    let mut stack = vec![0u32];
+    while let Some(node_index) = stack.pop() {
+        let node = &bvh.nodes[node_index as usize];
+        if !aabb_intersects(&node.aabb, aabb) {
+            continue;
+        }
+
+        if node.count == 0 {
+            stack.push(node.left_first);
+            stack.push(node.left_first + 1);
+            continue;
+        }
+
+        let first = node.left_first as usize;
+        for index in first..first + node.count as usize {
+            if aabb_intersects(&bvh.entities[index].bounds, aabb) {
+                found.push(index);
+            }
+        }
+    }
+
+    found
+}
+
+/// Nearest block or entity along an arbitrary ray
+pub fn pick_ray(ray: Ray, max_distance: f32, scene: &PickScene) -> Option<PickHit> {
+    let block_hit = world_operations::raycast(scene.world, ray, max_distance, scene.chunk_size);
+    let entity_range = block_hit
+        .as_ref()
+        .map(|hit| hit.distance)
+        .unwrap_or(max_distance);
+    let entity_hit = raycast_entities(scene.entities, &ray, entity_range);
+
+    let (target, distance) = match (entity_hit, block_hit) {
+        (Some(entity), _) => (
+            PickTarget::Entity {
+                entity: entity.entity,
+                part: entity.part,
+            },
+            entity.distance,
+        ),
+        (None, Some(block)) => (
+            PickTarget::Block {
+                position: block.position,
+                face: block.face,
+                block: block.block,
+            },
+            block.distance,
+        ),
+        (None, None) => return None,
+    };
+
+    Some(PickHit {
+        target,
+        distance,
+        point: ray.origin + ray.direction * distance,
+    })
+}
+
+/// Pick along the camera's forward vector (crosshair)
+pub fn pick_from_camera(
+    camera: &CameraData,
+    max_distance: f32,
+    scene: &PickScene,
+) -> Option<PickHit> {
+    let forward = calculate_forward_vector(camera.yaw_radians, camera.pitch_radians);
+    pick_ray(Ray::new(camera.position, forward), max_distance, scene)
+}
+
+/// Ray through a cursor position in normalized device coordinates
+/// (-1..1, +y up)
+pub fn camera_cursor_ray(camera: &CameraData, ndc_x: f32, ndc_y: f32) -> Ray {
+    let forward = calculate_forward_vector(camera.yaw_radians, camera.pitch_radians);
+    let right = calculate_right_vector(camera.yaw_radians);
+    let up = right.cross(forward).normalize();
+    let half_height = (camera.fov_radians * 0.5).tan();
+    let half_width = half_height * camera.aspect_ratio;
+
+    let direction: Vector3<f32> =
+        forward + right * (ndc_x * half_width) + up * (ndc_y * half_height);
+    Ray::new(camera.position, direction.normalize())
+}
+
+/// Pick through a cursor position in normalized device coordinates
+pub fn pick_from_cursor(
+    camera: &CameraData,
+    ndc_x: f32,
+    ndc_y: f32,
+    max_distance: f32,
+    scene: &PickScene,
+) -> Option<PickHit> {
+    pick_ray(camera_cursor_ray(camera, ndc_x, ndc_y), max_distance, scene)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::aabb::create_aabb;
+    use crate::physics::picking_data::HitboxPart;
+
+    fn entity_at(entity: u32, x: f32) -> EntityHitboxes {
+        EntityHitboxes {
+            entity,
+            bounds: create_aabb(Point3::new(x, 0.0, -1.0), Point3::new(x + 1.0, 2.0, 1.0)),
+            parts: vec![
+                HitboxPart {
+                    part: 1,
+                    aabb: create_aabb(Point3::new(x, 0.0, -1.0), Point3::new(x + 1.0, 1.0, 1.0)),
+                },
+                HitboxPart {
+                    part: 2,
+                    aabb: create_aabb(Point3::new(x, 1.5, -0.5), Point3::new(x + 1.0, 2.0, 0.5)),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_raycast_entities_returns_nearest_part() {
+        let entities = (0..10)
+            .map(|i| entity_at(i, 5.0 + i as f32 * 3.0))
+            .collect();
+        let bvh = build_entity_pick_bvh(entities);
+
+        let ray = Ray::new(Point3::new(0.0, 1.75, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        let hit = raycast_entities(&bvh, &ray, 100.0);
+        assert_eq!(hit.map(|h| (h.entity, h.part)), Some((0, 2)));
+
+        // Gap between the two parts: inside the bounds but no hitbox
+        let gap = Ray::new(Point3::new(0.0, 1.25, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        assert!(raycast_entities(&bvh, &gap, 100.0).is_none());
+
+        // Out of range
+        assert!(raycast_entities(&bvh, &ray, 4.0).is_none());
+    }
+}
@@ -0,0 +1,45 @@
+//! Surface Material Data - Pure DOP
+//!
+//! Per-block surface feel for the character controller: friction (ice
+//! slides), restitution (slime bounces) and a speed multiplier (soul sand
+//! slows). Taken from each block's `PhysicsProperties` at registration and
+//! blended by footprint overlap where a character stands across blocks.
+//! Operations live in surface_material_operations.rs.
+//!
+//! NO METHODS - just data.
+
+/// Surface response of one block type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceMaterial {
+    pub friction: f32,
+    pub restitution: f32,
+    pub speed_multiplier: f32,
+}
+
+/// One block under a character's footprint
+#[derive(Debug, Clone, Copy)]
+pub struct SurfaceSample {
+    pub material: SurfaceMaterial,
+    /// Footprint area resting on the block (voxels²)
+    pub weight: f32,
+}
+
+/// Surface materials indexed by block id. `None` marks blocks that cannot
+/// be stood on (air, liquids, unregistered ids).
+#[derive(Debug, Clone, Default)]
+pub struct SurfaceMaterialTable {
+    pub materials: Vec<Option<SurfaceMaterial>>,
+}
+
+/// Character controller tuning for surface response
+#[derive(Debug, Clone, Copy)]
+pub struct CharacterSurfaceConfig {
+    /// Horizontal acceleration on full-grip ground (voxels/s²)
+    pub ground_acceleration: f32,
+    /// Horizontal acceleration while airborne (voxels/s²)
+    pub air_acceleration: f32,
+    /// Landing speeds below this do not bounce (voxels/s)
+    pub min_bounce_speed: f32,
+    /// Collision half-extents of the character (voxels)
+    pub half_extents: [f32; 3],
+}
@@ -0,0 +1,288 @@
+//! Surface Material Operations - Pure DOP Functions
+//!
+//! Per tick for each character: `sample_ground_material` under its feet,
+//! then `apply_character_surface` with the walk input and the downward
+//! speed it hit the ground with (0 when it did not land this tick).
+
+use super::surface_material_data::{
+    CharacterSurfaceConfig, SurfaceMaterial, SurfaceMaterialTable, SurfaceSample,
+};
+use crate::constants::physics_constants::{
+    CHARACTER_AIR_ACCELERATION, CHARACTER_GROUND_ACCELERATION, DEFAULT_BLOCK_FRICTION,
+    DEFAULT_BLOCK_RESTITUTION, DEFAULT_SPEED_MULTIPLIER, MIN_BOUNCE_SPEED, PLAYER_HALF_EXTENTS,
+    SURFACE_PROBE_DEPTH,
+};
+use crate::engine_buffers::PhysicsBuffers;
+use crate::world::core::{
+    world_coord_to_voxel, BlockId, BlockRegistry, PhysicsProperties, VoxelPos,
+};
+
+/// Material of ordinary ground
+pub fn default_surface_material() -> SurfaceMaterial {
+    SurfaceMaterial {
+        friction: DEFAULT_BLOCK_FRICTION,
+        restitution: DEFAULT_BLOCK_RESTITUTION,
+        speed_multiplier: DEFAULT_SPEED_MULTIPLIER,
+    }
+}
+
+/// Default controller tuning for a player-sized character
+pub fn default_character_surface_config() -> CharacterSurfaceConfig {
+    CharacterSurfaceConfig {
+        ground_acceleration: CHARACTER_GROUND_ACCELERATION,
+        air_acceleration: CHARACTER_AIR_ACCELERATION,
+        min_bounce_speed: MIN_BOUNCE_SPEED,
+        half_extents: PLAYER_HALF_EXTENTS,
+    }
+}
+
+/// Surface material of a block's physics, `None` if it cannot be stood on
+pub fn surface_material_from_physics(physics: &PhysicsProperties) -> Option<SurfaceMaterial> {
+    if !physics.solid {
+        return None;
+    }
+    Some(SurfaceMaterial {
+        friction: physics.friction.clamp(0.0, 1.0),
+        restitution: physics.restitution.clamp(0.0, 1.0),
+        speed_multiplier: physics.speed_multiplier.max(0.0),
+    })
+}
+
+/// Build the lookup table from every registered block
+pub fn build_surface_material_table(registry: &BlockRegistry) -> SurfaceMaterialTable {
+    let mut table = SurfaceMaterialTable::default();
+    for registration in registry.get_registrations() {
+        set_surface_material(
+            &mut table,
+            registration.id,
+            surface_material_from_physics(&registration.properties.physics),
+        );
+    }
+    table
+}
+
+/// Override one block's surface material
+pub fn set_surface_material(
+    table: &mut SurfaceMaterialTable,
+    id: BlockId,
+    material: Option<SurfaceMaterial>,
+) {
+    let index = id.0 as usize;
+    if index >= table.materials.len() {
+        table.materials.resize(index + 1, None);
+    }
+    table.materials[index] = material;
+}
+
+/// Surface material of a block, `None` if it cannot be stood on
+pub fn surface_material(table: &SurfaceMaterialTable, id: BlockId) -> Option<SurfaceMaterial> {
+    table.materials.get(id.0 as usize).copied().flatten()
+}
+
+/// Weighted average of materials; `None` if there is no weight
+pub fn blend_surface_materials(samples: &[SurfaceSample]) -> Option<SurfaceMaterial> {
+    let total: f32 = samples.iter().map(|sample| sample.weight).sum();
+    if total <= f32::EPSILON {
+        return None;
+    }
+
+    let mut blended = SurfaceMaterial {
+        friction: 0.0,
+        restitution: 0.0,
+        speed_multiplier: 0.0,
+    };
+    for sample in samples {
+        let share = sample.weight / total;
+        blended.friction += sample.material.friction * share;
+        blended.restitution += sample.material.restitution * share;
+        blended.speed_multiplier += sample.material.speed_multiplier * share;
+    }
+    Some(blended)
+}
+
+/// Overlap of the span [min, max) with each unit cell it touches
+pub(crate) fn cell_overlaps(min: f32, max: f32) -> impl Iterator<Item = (i32, f32)> {
+    let first = world_coord_to_voxel(min);
+    let last = (max.ceil() as i32 - 1).max(first);
+    (first..=last).map(move |cell| {
+        let start = min.max(cell as f32);
+        let end = max.min(cell as f32 + 1.0);
+        (cell, (end - start).max(0.0))
+    })
+}
+
+/// Material under a character centered at `position`, blended by how much
+/// of its footprint rests on each block. `None` when nothing solid is
+/// underneath.
+pub fn sample_ground_material(
+    table: &SurfaceMaterialTable,
+    config: &CharacterSurfaceConfig,
+    position: [f32; 3],
+    block_at: impl Fn(VoxelPos) -> BlockId,
+) -> Option<SurfaceMaterial> {
+    let [half_x, half_y, half_z] = config.half_extents;
+    let y = world_coord_to_voxel(position[1] - half_y - SURFACE_PROBE_DEPTH);
+
+    let mut samples = Vec::new();
+    for (x, width) in cell_overlaps(position[0] - half_x, position[0] + half_x) {
+        for (z, depth) in cell_overlaps(position[2] - half_z, position[2] + half_z) {
+            let weight = width * depth;
+            if weight <= 0.0 {
+                continue;
+            }
+            if let Some(material) = surface_material(table, block_at(VoxelPos { x, y, z })) {
+                samples.push(SurfaceSample { material, weight });
+            }
+        }
+    }
+    blend_surface_materials(&samples)
+}
+
+/// Move a horizontal velocity toward the wished one by at most `max_change`
+fn approach_velocity(velocity: &mut [f32; 3], target: [f32; 2], max_change: f32) {
+    let dx = target[0] - velocity[0];
+    let dz = target[1] - velocity[2];
+    let distance = (dx * dx + dz * dz).sqrt();
+    if distance <= max_change || distance <= f32::EPSILON {
+        velocity[0] = target[0];
+        velocity[2] = target[1];
+        return;
+    }
+    let scale = max_change / distance;
+    velocity[0] += dx * scale;
+    velocity[2] += dz * scale;
+}
+
+/// Apply the surface response to one character for a tick.
+///
+/// `wish_velocity` is the walk input on x/z in voxels/s, `impact_speed`
+/// the downward speed the character landed with this tick. Low friction
+/// makes both speeding up and stopping slow; speed multipliers scale the
+/// walk target. Returns whether the character bounced off the ground.
+pub fn apply_character_surface(
+    physics: &mut PhysicsBuffers,
+    index: usize,
+    ground: Option<SurfaceMaterial>,
+    wish_velocity: [f32; 2],
+    impact_speed: f32,
+    config: &CharacterSurfaceConfig,
+    delta_time: f32,
+) -> bool {
+    let Some(velocity) = physics.velocities.get_mut(index) else {
+        return false;
+    };
+
+    let Some(material) = ground else {
+        approach_velocity(
+            velocity,
+            wish_velocity,
+            config.air_acceleration * delta_time,
+        );
+        return false;
+    };
+
+    let target = [
+        wish_velocity[0] * material.speed_multiplier,
+        wish_velocity[1] * material.speed_multiplier,
+    ];
+    approach_velocity(
+        velocity,
+        target,
+        config.ground_acceleration * material.friction * delta_time,
+    );
+
+    let bounce_speed = impact_speed * material.restitution;
+    let bounced = impact_speed >= config.min_bounce_speed && bounce_speed > 0.0;
+    if bounced {
+        velocity[1] = bounce_speed;
+    }
+    if let Some(flags) = physics.flags.get_mut(index) {
+        flags.is_grounded = !bounced;
+    }
+    bounced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_buffers::create_engine_buffers;
+
+    const ICE: BlockId = BlockId(7);
+    const SLIME: BlockId = BlockId(8);
+    const SOUL_SAND: BlockId = BlockId(9);
+
+    fn test_table() -> SurfaceMaterialTable {
+        let mut table = SurfaceMaterialTable::default();
+        set_surface_material(&mut table, BlockId(1), Some(default_surface_material()));
+        let material = |friction, restitution, speed_multiplier| {
+            Some(SurfaceMaterial {
+                friction,
+                restitution,
+                speed_multiplier,
+            })
+        };
+        set_surface_material(&mut table, ICE, material(0.05, 0.0, 1.0));
+        set_surface_material(&mut table, SLIME, material(1.0, 0.8, 1.0));
+        set_surface_material(&mut table, SOUL_SAND, material(1.0, 0.0, 0.4));
+        table
+    }
+
+    fn step(ground: BlockId, velocity: [f32; 3], wish: [f32; 2], impact: f32) -> [f32; 3] {
+        let mut buffers = create_engine_buffers();
+        buffers.physics.velocities = vec![velocity];
+        let table = test_table();
+        let config = default_character_surface_config();
+        let material = surface_material(&table, ground);
+        apply_character_surface(
+            &mut buffers.physics,
+            0,
+            material,
+            wish,
+            impact,
+            &config,
+            0.05,
+        );
+        buffers.physics.velocities[0]
+    }
+
+    #[test]
+    fn test_surfaces_change_character_response() {
+        // Stopping on stone is immediate, on ice the character keeps sliding
+        assert_eq!(step(BlockId(1), [10.0, 0.0, 0.0], [0.0, 0.0], 0.0)[0], 0.0);
+        let sliding = step(ICE, [10.0, 0.0, 0.0], [0.0, 0.0], 0.0)[0];
+        assert!((sliding - 9.0).abs() < 1e-4);
+
+        // Slime bounces hard landings but not gentle ones
+        assert!((step(SLIME, [0.0; 3], [0.0, 0.0], 50.0)[1] - 40.0).abs() < 1e-4);
+        assert_eq!(step(SLIME, [0.0; 3], [0.0, 0.0], 5.0)[1], 0.0);
+
+        // Soul sand caps walking speed
+        assert!((step(SOUL_SAND, [0.0; 3], [15.0, 0.0], 0.0)[0] - 6.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_ground_blends_across_block_boundary() {
+        let table = test_table();
+        let config = CharacterSurfaceConfig {
+            half_extents: [2.0, 9.0, 2.0],
+            ..default_character_surface_config()
+        };
+        // Feet at y = 0, three quarters of the footprint over ice (x < 0)
+        let block_at = |pos: VoxelPos| {
+            if pos.y != -1 {
+                BlockId::AIR
+            } else if pos.x < 0 {
+                ICE
+            } else {
+                BlockId(1)
+            }
+        };
+
+        let material =
+            sample_ground_material(&table, &config, [-1.0, 9.0, 0.5], block_at).expect("ground");
+        assert!((material.friction - (0.05 * 0.75 + 0.25)).abs() < 1e-4);
+
+        let airborne = sample_ground_material(&table, &config, [-1.0, 20.0, 0.5], block_at);
+        assert!(airborne.is_none());
+    }
+}
@@ -0,0 +1,30 @@
+//! Plugin Module - Native plugins loaded from dynamic libraries
+//!
+//! - plugin_data.rs: Pure data structures with NO methods
+//! - plugin_operations.rs: Loading, version checks and isolated hook calls
+//!
+//! Plugins get a subset of the game gateway (events, block registration,
+//! commands) and lifecycle hooks (`on_load`, `on_world_start`, `on_tick`,
+//! `on_unload`). The versioned C ABI they are built against lives in the
+//! `hearth-plugin-api` crate, so plugin crates can use it without pulling
+//! in the engine. The engine loads `EngineConfig::plugin_dir` at startup.
+
+pub mod plugin_data;
+pub mod plugin_operations;
+
+pub use hearth_plugin_api::{
+    declare_plugin, guard_hook, plugin_descriptor, plugin_hook, plugin_str, PluginBlock,
+    PluginCommand, PluginDescriptor, PluginEntryFn, PluginEvent, PluginHook, PluginHostApi,
+    PluginStr, PluginTickHook, PluginVersion, PluginWorldStartHook, ENGINE_PLUGIN_VERSION,
+    PLUGIN_ABI_VERSION, PLUGIN_ENTRY_SYMBOL, PLUGIN_ERROR, PLUGIN_OK,
+};
+
+pub use plugin_data::{
+    LoadedPlugin, PluginError, PluginHostData, PluginOutbox, PluginResult, PluginState,
+};
+
+pub use plugin_operations::{
+    create_plugin_host, drain_plugin_outbox, find_plugin, load_plugin, load_plugins_from_dir,
+    plugins_tick, plugins_world_start, register_static_plugin, submit_plugin_outbox,
+    unload_plugins, versions_compatible,
+};
@@ -0,0 +1,86 @@
+//! Plugin Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Loading, version checks and hook calls live in plugin_operations.rs
+
+use crate::game::{BlockRegistration, GameCommand, GameEvent};
+use hearth_plugin_api::{PluginDescriptor, PluginVersion};
+use std::path::PathBuf;
+
+/// Lifecycle state of a plugin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginState {
+    /// `on_load` succeeded; hooks run every tick
+    Active,
+    /// A hook failed (or panicked, under `guard_hook`); no hook is called
+    /// again
+    Failed(String),
+}
+
+/// Gateway requests made by plugins during hook calls, forwarded to the
+/// gateway once the hooks return
+#[derive(Debug, Clone, Default)]
+pub struct PluginOutbox {
+    pub events: Vec<GameEvent>,
+    pub blocks: Vec<BlockRegistration>,
+    pub commands: Vec<GameCommand>,
+}
+
+/// One plugin known to the host
+pub struct LoadedPlugin {
+    pub name: String,
+    pub version: PluginVersion,
+    /// Library file, `None` for plugins linked into the executable
+    pub path: Option<PathBuf>,
+    pub descriptor: PluginDescriptor,
+    pub state: PluginState,
+    /// Keeps the code behind `descriptor` mapped; dropped last
+    pub library: Option<libloading::Library>,
+}
+
+/// All plugins of the running engine
+pub struct PluginHostData {
+    pub engine_version: PluginVersion,
+    pub plugins: Vec<LoadedPlugin>,
+    /// Requests collected since the last drain
+    pub outbox: PluginOutbox,
+}
+
+/// Plugin loading errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PluginError {
+    #[error("Failed to load plugin library {0}")]
+    Library(String),
+
+    #[error("Plugin library {0} does not export hearth_plugin_entry")]
+    MissingEntry(String),
+
+    #[error("Plugin {0} returned no descriptor")]
+    NullDescriptor(String),
+
+    #[error("Plugin {plugin} uses ABI version {found}, engine uses {expected}")]
+    AbiMismatch {
+        plugin: String,
+        expected: u32,
+        found: u32,
+    },
+
+    #[error("Plugin {plugin} was built for engine {built_for:?}, incompatible with {engine:?}")]
+    EngineVersion {
+        plugin: String,
+        built_for: PluginVersion,
+        engine: PluginVersion,
+    },
+
+    #[error("A plugin named {0} is already loaded")]
+    DuplicateName(String),
+
+    #[error("Plugin {plugin} failed in {hook}: {reason}")]
+    HookFailed {
+        plugin: String,
+        hook: String,
+        reason: String,
+    },
+}
+
+pub type PluginResult<T> = Result<T, PluginError>;
@@ -0,0 +1,582 @@
+//! Plugin Operations - Pure DOP functions
+//!
+//! Loading: `load_plugin` maps a library, checks its ABI and engine version
+//! and runs `on_load`. Per frame: `plugins_tick`, then `drain_plugin_outbox`
+//! and `submit_plugin_outbox` to hand the collected events, blocks and
+//! commands to the gateway. `Engine::frame` does both.
+//!
+//! A hook that returns an error disables its plugin and the requests it
+//! made during that call are dropped; other plugins keep running. Panics
+//! are caught on the plugin side (`guard_hook`, applied by
+//! `declare_plugin!`) and arrive here as `PLUGIN_ERROR`; the engine cannot
+//! catch a panic unwinding out of a library built by another compiler.
+
+use super::plugin_data::{
+    LoadedPlugin, PluginError, PluginHostData, PluginOutbox, PluginResult, PluginState,
+};
+use crate::game::{
+    add_block_registration, queue_command, queue_events, BlockProperties, BlockRegistration,
+    GameCommand, GameEvent,
+};
+use crate::world::core::{BlockId, VoxelPos};
+use hearth_plugin_api::{
+    PluginBlock, PluginCommand, PluginDescriptor, PluginEntryFn, PluginEvent, PluginHostApi,
+    PluginStr, PluginVersion, ENGINE_PLUGIN_VERSION, PLUGIN_ABI_VERSION, PLUGIN_COMMAND_LOAD_WORLD,
+    PLUGIN_COMMAND_SAVE_WORLD, PLUGIN_COMMAND_SHUTDOWN, PLUGIN_COMMAND_UPDATE_SETTING,
+    PLUGIN_ENTRY_SYMBOL, PLUGIN_ERROR, PLUGIN_EVENT_BLOCK_BREAK, PLUGIN_EVENT_BLOCK_PLACE,
+    PLUGIN_EVENT_CUSTOM, PLUGIN_EVENT_SPAWN_PARTICLE, PLUGIN_LOG_DEBUG, PLUGIN_LOG_INFO,
+    PLUGIN_LOG_WARN, PLUGIN_NO_PLAYER, PLUGIN_OK,
+};
+use std::ffi::c_void;
+use std::path::{Path, PathBuf};
+
+/// Host with no plugins, for this engine build
+pub fn create_plugin_host() -> PluginHostData {
+    PluginHostData {
+        engine_version: ENGINE_PLUGIN_VERSION,
+        plugins: Vec::new(),
+        outbox: PluginOutbox::default(),
+    }
+}
+
+/// Whether a plugin built against `built_for` runs on `engine`: same major
+/// version (same minor before 1.0) and not newer than the engine
+pub fn versions_compatible(built_for: PluginVersion, engine: PluginVersion) -> bool {
+    let same_line = if engine.major == 0 {
+        built_for.major == 0 && built_for.minor == engine.minor
+    } else {
+        built_for.major == engine.major
+    };
+    same_line && built_for <= engine
+}
+
+/// Copy a borrowed ABI string
+///
+/// # Safety
+/// `text` must point at `len` readable bytes, or be null.
+unsafe fn read_plugin_bytes(text: PluginStr) -> Vec<u8> {
+    if text.ptr.is_null() || text.len == 0 {
+        return Vec::new();
+    }
+    std::slice::from_raw_parts(text.ptr, text.len).to_vec()
+}
+
+/// # Safety
+/// As `read_plugin_bytes`.
+unsafe fn read_plugin_str(text: PluginStr) -> String {
+    String::from_utf8_lossy(&read_plugin_bytes(text)).into_owned()
+}
+
+fn check_descriptor(
+    host: &PluginHostData,
+    name: &str,
+    descriptor: &PluginDescriptor,
+) -> PluginResult<()> {
+    if descriptor.abi_version != PLUGIN_ABI_VERSION {
+        return Err(PluginError::AbiMismatch {
+            plugin: name.to_string(),
+            expected: PLUGIN_ABI_VERSION,
+            found: descriptor.abi_version,
+        });
+    }
+    if !versions_compatible(descriptor.engine_version, host.engine_version) {
+        return Err(PluginError::EngineVersion {
+            plugin: name.to_string(),
+            built_for: descriptor.engine_version,
+            engine: host.engine_version,
+        });
+    }
+    if host.plugins.iter().any(|plugin| plugin.name == name) {
+        return Err(PluginError::DuplicateName(name.to_string()));
+    }
+    Ok(())
+}
+
+fn add_plugin(
+    host: &mut PluginHostData,
+    descriptor: PluginDescriptor,
+    path: Option<PathBuf>,
+    library: Option<libloading::Library>,
+) -> PluginResult<usize> {
+    // SAFETY: the descriptor name points into the plugin, which is loaded
+    let name = unsafe { read_plugin_str(descriptor.name) };
+    check_descriptor(host, &name, &descriptor)?;
+
+    let index = host.plugins.len();
+    host.plugins.push(LoadedPlugin {
+        name,
+        version: descriptor.version,
+        path,
+        descriptor,
+        state: PluginState::Active,
+        library,
+    });
+
+    if let Some(on_load) = descriptor.on_load {
+        let plugin = &mut host.plugins[index];
+        if let Err(error) = call_hook(plugin, &mut host.outbox, "on_load", |api| on_load(api)) {
+            host.plugins.pop();
+            return Err(error);
+        }
+    }
+    log::info!(
+        "[Plugin] Loaded {} {}.{}.{}",
+        host.plugins[index].name,
+        descriptor.version.major,
+        descriptor.version.minor,
+        descriptor.version.patch
+    );
+    Ok(index)
+}
+
+/// Add a plugin linked into the executable
+pub fn register_static_plugin(
+    host: &mut PluginHostData,
+    descriptor: PluginDescriptor,
+) -> PluginResult<usize> {
+    add_plugin(host, descriptor, None, None)
+}
+
+/// Load a plugin library and run its `on_load`
+pub fn load_plugin(host: &mut PluginHostData, path: &Path) -> PluginResult<usize> {
+    let display = path.display().to_string();
+    // SAFETY: loading runs the library's initialisers; plugins are trusted
+    // native code the user installed
+    let library = unsafe { libloading::Library::new(path) }
+        .map_err(|e| PluginError::Library(format!("{}: {}", display, e)))?;
+
+    let descriptor = {
+        // SAFETY: the symbol has the documented `PluginEntryFn` signature
+        let entry = unsafe { library.get::<PluginEntryFn>(PLUGIN_ENTRY_SYMBOL) }
+            .map_err(|_| PluginError::MissingEntry(display.clone()))?;
+        // SAFETY: see above
+        let pointer = unsafe { entry() };
+        if pointer.is_null() {
+            return Err(PluginError::NullDescriptor(display));
+        }
+        // The ABI version is the first field in every layout, so check it
+        // before reading the rest
+        // SAFETY: the descriptor is valid while the library is loaded
+        let abi_version = unsafe { std::ptr::read(pointer as *const u32) };
+        if abi_version != PLUGIN_ABI_VERSION {
+            return Err(PluginError::AbiMismatch {
+                plugin: display,
+                expected: PLUGIN_ABI_VERSION,
+                found: abi_version,
+            });
+        }
+        // SAFETY: same layout as ours, checked above
+        unsafe { *pointer }
+    };
+
+    add_plugin(host, descriptor, Some(path.to_path_buf()), Some(library))
+}
+
+/// Load every library in `dir` (by platform extension, in name order)
+pub fn load_plugins_from_dir(host: &mut PluginHostData, dir: &Path) -> Vec<PluginResult<usize>> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == std::env::consts::DLL_EXTENSION)
+        })
+        .collect();
+    paths.sort();
+    paths.iter().map(|path| load_plugin(host, path)).collect()
+}
+
+/// Plugin by name
+pub fn find_plugin<'a>(host: &'a PluginHostData, name: &str) -> Option<&'a LoadedPlugin> {
+    host.plugins.iter().find(|plugin| plugin.name == name)
+}
+
+/// Run a hook on every active plugin, returning the failures
+fn run_hooks(
+    host: &mut PluginHostData,
+    hook: &str,
+    call: impl Fn(&PluginDescriptor, *const PluginHostApi) -> Option<i32>,
+) -> Vec<PluginError> {
+    let mut failures = Vec::new();
+    for plugin in &mut host.plugins {
+        if plugin.state != PluginState::Active {
+            continue;
+        }
+        let descriptor = plugin.descriptor;
+        let result = call_hook(plugin, &mut host.outbox, hook, |api| {
+            call(&descriptor, api).unwrap_or(PLUGIN_OK)
+        });
+        if let Err(error) = result {
+            failures.push(error);
+        }
+    }
+    failures
+}
+
+/// Call `on_world_start` on every active plugin
+pub fn plugins_world_start(host: &mut PluginHostData, seed: u32) -> Vec<PluginError> {
+    run_hooks(host, "on_world_start", |descriptor, api| {
+        descriptor.on_world_start.map(|hook| hook(api, seed))
+    })
+}
+
+/// Call `on_tick` on every active plugin
+pub fn plugins_tick(host: &mut PluginHostData, tick: u64, delta_seconds: f32) -> Vec<PluginError> {
+    run_hooks(host, "on_tick", |descriptor, api| {
+        descriptor
+            .on_tick
+            .map(|hook| hook(api, tick, delta_seconds))
+    })
+}
+
+/// Call `on_unload` on every active plugin and unmap all libraries
+pub fn unload_plugins(host: &mut PluginHostData) -> Vec<PluginError> {
+    let failures = run_hooks(host, "on_unload", |descriptor, api| {
+        descriptor.on_unload.map(|hook| hook(api))
+    });
+    host.plugins.clear();
+    failures
+}
+
+/// Take the requests plugins made since the last drain
+pub fn drain_plugin_outbox(host: &mut PluginHostData) -> PluginOutbox {
+    std::mem::take(&mut host.outbox)
+}
+
+/// Hand plugin requests to the game gateway. Block events a plugin sends
+/// for a player pass the region claims and action cooldowns like any other
+/// player's (see `queue_events`).
+pub fn submit_plugin_outbox(outbox: PluginOutbox) {
+    queue_events(outbox.events);
+    for block in outbox.blocks {
+        add_block_registration(block);
+    }
+    for command in outbox.commands {
+        queue_command(command);
+    }
+}
+
+// ============================================================================
+// HOOK CALLS
+// ============================================================================
+
+/// What `PluginHostApi::context` points at during a hook
+struct HookContext<'a> {
+    plugin: &'a str,
+    outbox: &'a mut PluginOutbox,
+}
+
+/// Call one hook of `plugin` with a host API collecting into `outbox`
+fn call_hook(
+    plugin: &mut LoadedPlugin,
+    outbox: &mut PluginOutbox,
+    hook: &str,
+    call: impl FnOnce(*const PluginHostApi) -> i32,
+) -> PluginResult<()> {
+    let mut staged = PluginOutbox::default();
+    let result = {
+        let mut context = HookContext {
+            plugin: &plugin.name,
+            outbox: &mut staged,
+        };
+        let api = PluginHostApi {
+            abi_version: PLUGIN_ABI_VERSION,
+            engine_version: ENGINE_PLUGIN_VERSION,
+            context: &mut context as *mut HookContext as *mut c_void,
+            queue_event: host_queue_event,
+            register_block: host_register_block,
+            queue_command: host_queue_command,
+            log: host_log,
+        };
+        call(&api)
+    };
+
+    if result == PLUGIN_OK {
+        outbox.events.append(&mut staged.events);
+        outbox.blocks.append(&mut staged.blocks);
+        outbox.commands.append(&mut staged.commands);
+        return Ok(());
+    }
+    let reason = format!("returned error code {}", result);
+
+    log::error!("[Plugin] {} disabled, {} {}", plugin.name, hook, reason);
+    plugin.state = PluginState::Failed(reason.clone());
+    Err(PluginError::HookFailed {
+        plugin: plugin.name.clone(),
+        hook: hook.to_string(),
+        reason,
+    })
+}
+
+/// # Safety
+/// `context` must be the `HookContext` of the running hook.
+unsafe fn hook_context<'a>(context: *mut c_void) -> Option<&'a mut HookContext<'a>> {
+    (context as *mut HookContext).as_mut()
+}
+
+/// # Safety
+/// Strings in `event` must be valid for the call.
+unsafe fn convert_event(event: &PluginEvent) -> Option<GameEvent> {
+    let position = VoxelPos::new(event.position[0], event.position[1], event.position[2]);
+    let player_id = (event.player_id != PLUGIN_NO_PLAYER).then_some(event.player_id);
+    match event.kind {
+        PLUGIN_EVENT_BLOCK_BREAK => Some(GameEvent::BlockBreak {
+            position,
+            block_id: BlockId(event.id),
+            player_id,
+        }),
+        PLUGIN_EVENT_BLOCK_PLACE => Some(GameEvent::BlockPlace {
+            position,
+            block_id: BlockId(event.id),
+            metadata: event.metadata,
+            player_id,
+        }),
+        PLUGIN_EVENT_SPAWN_PARTICLE => Some(GameEvent::SpawnParticle {
+            position: event.world_position,
+            particle_type: event.id,
+            count: event.count,
+        }),
+        PLUGIN_EVENT_CUSTOM => Some(GameEvent::Custom {
+            event_type: read_plugin_str(event.name),
+            data: read_plugin_bytes(event.data),
+        }),
+        _ => None,
+    }
+}
+
+/// # Safety
+/// Strings in `block` must be valid for the call.
+unsafe fn convert_block(block: &PluginBlock) -> Option<BlockRegistration> {
+    let name = read_plugin_str(block.name);
+    if name.is_empty() {
+        return None;
+    }
+    Some(BlockRegistration {
+        id: BlockId(block.id),
+        name,
+        properties: BlockProperties {
+            is_solid: block.is_solid != 0,
+            is_transparent: block.is_transparent != 0,
+            is_liquid: block.is_liquid != 0,
+            light_emission: block.light_emission,
+            hardness: block.hardness,
+            friction: block.friction,
+            restitution: block.restitution,
+            speed_multiplier: block.speed_multiplier,
+            can_interact: block.can_interact != 0,
+        },
+    })
+}
+
+/// # Safety
+/// Strings in `command` must be valid for the call.
+unsafe fn convert_command(command: &PluginCommand) -> Option<GameCommand> {
+    match command.kind {
+        PLUGIN_COMMAND_SAVE_WORLD => Some(GameCommand::SaveWorld {
+            path: read_plugin_str(command.name),
+        }),
+        PLUGIN_COMMAND_LOAD_WORLD => Some(GameCommand::LoadWorld {
+            path: read_plugin_str(command.name),
+        }),
+        PLUGIN_COMMAND_UPDATE_SETTING => Some(GameCommand::UpdateSettings {
+            setting_name: read_plugin_str(command.name),
+            value: read_plugin_str(command.value),
+        }),
+        PLUGIN_COMMAND_SHUTDOWN => Some(GameCommand::Shutdown),
+        _ => None,
+    }
+}
+
+extern "C" fn host_queue_event(context: *mut c_void, event: *const PluginEvent) -> i32 {
+    // SAFETY: plugins pass back the context of the running hook and an
+    // event valid for this call
+    let converted = unsafe { event.as_ref().and_then(|event| convert_event(event)) };
+    match (unsafe { hook_context(context) }, converted) {
+        (Some(context), Some(event)) => {
+            context.outbox.events.push(event);
+            PLUGIN_OK
+        }
+        _ => PLUGIN_ERROR,
+    }
+}
+
+extern "C" fn host_register_block(context: *mut c_void, block: *const PluginBlock) -> i32 {
+    // SAFETY: as in `host_queue_event`
+    let converted = unsafe { block.as_ref().and_then(|block| convert_block(block)) };
+    match (unsafe { hook_context(context) }, converted) {
+        (Some(context), Some(block)) => {
+            context.outbox.blocks.push(block);
+            PLUGIN_OK
+        }
+        _ => PLUGIN_ERROR,
+    }
+}
+
+extern "C" fn host_queue_command(context: *mut c_void, command: *const PluginCommand) -> i32 {
+    // SAFETY: as in `host_queue_event`
+    let converted = unsafe {
+        command
+            .as_ref()
+            .and_then(|command| convert_command(command))
+    };
+    match (unsafe { hook_context(context) }, converted) {
+        (Some(context), Some(command)) => {
+            context.outbox.commands.push(command);
+            PLUGIN_OK
+        }
+        _ => PLUGIN_ERROR,
+    }
+}
+
+extern "C" fn host_log(context: *mut c_void, level: u32, message: PluginStr) {
+    // SAFETY: as in `host_queue_event`
+    let Some(context) = (unsafe { hook_context(context) }) else {
+        return;
+    };
+    // SAFETY: the message is valid for this call
+    let message = unsafe { read_plugin_str(message) };
+    match level {
+        PLUGIN_LOG_DEBUG => log::debug!("[Plugin {}] {}", context.plugin, message),
+        PLUGIN_LOG_INFO => log::info!("[Plugin {}] {}", context.plugin, message),
+        PLUGIN_LOG_WARN => log::warn!("[Plugin {}] {}", context.plugin, message),
+        _ => log::error!("[Plugin {}] {}", context.plugin, message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hearth_plugin_api::{plugin_hook, plugin_str};
+
+    fn register_glow_block(host: &PluginHostApi) -> i32 {
+        let block = PluginBlock {
+            id: 200,
+            name: plugin_str("glowstone"),
+            is_solid: 1,
+            is_transparent: 0,
+            is_liquid: 0,
+            can_interact: 0,
+            light_emission: 15,
+            hardness: 0.3,
+            friction: 0.6,
+            restitution: 0.0,
+            speed_multiplier: 1.0,
+        };
+        (host.register_block)(host.context, &block)
+    }
+
+    fn queue_tick_event(host: &PluginHostApi, tick: u64, _dt: f32) -> i32 {
+        let bytes = tick.to_le_bytes();
+        let event = PluginEvent {
+            kind: PLUGIN_EVENT_CUSTOM,
+            player_id: PLUGIN_NO_PLAYER,
+            position: [0; 3],
+            world_position: [0.0; 3],
+            id: 0,
+            metadata: 0,
+            count: 0,
+            name: plugin_str("tick"),
+            data: PluginStr {
+                ptr: bytes.as_ptr(),
+                len: bytes.len(),
+            },
+        };
+        (host.queue_event)(host.context, &event)
+    }
+
+    fn panic_after_event(host: &PluginHostApi, tick: u64, dt: f32) -> i32 {
+        queue_tick_event(host, tick, dt);
+        panic!("plugin bug");
+    }
+
+    fn descriptor(name: &'static str) -> PluginDescriptor {
+        PluginDescriptor {
+            abi_version: PLUGIN_ABI_VERSION,
+            name: plugin_str(name),
+            version: PluginVersion {
+                major: 1,
+                minor: 0,
+                patch: 0,
+            },
+            engine_version: ENGINE_PLUGIN_VERSION,
+            on_load: None,
+            on_world_start: None,
+            on_tick: None,
+            on_unload: None,
+        }
+    }
+
+    #[test]
+    fn test_static_plugin_reaches_gateway_requests() {
+        let mut host = create_plugin_host();
+        let plugin = PluginDescriptor {
+            on_load: Some(plugin_hook!(on_load, register_glow_block)),
+            on_tick: Some(plugin_hook!(on_tick, queue_tick_event)),
+            ..descriptor("glow")
+        };
+        register_static_plugin(&mut host, plugin).expect("compatible plugin");
+        assert!(plugins_tick(&mut host, 42, 0.05).is_empty());
+
+        let outbox = drain_plugin_outbox(&mut host);
+        assert_eq!(outbox.blocks.len(), 1);
+        assert_eq!(outbox.blocks[0].name, "glowstone");
+        assert_eq!(outbox.blocks[0].properties.light_emission, 15);
+        assert!(matches!(
+            &outbox.events[..],
+            [GameEvent::Custom { event_type, data }]
+                if event_type == "tick" && data[..] == 42u64.to_le_bytes()
+        ));
+
+        // Duplicates and other ABI or engine lines are refused
+        assert!(matches!(
+            register_static_plugin(&mut host, descriptor("glow")),
+            Err(PluginError::DuplicateName(_))
+        ));
+        let old_abi = PluginDescriptor {
+            abi_version: PLUGIN_ABI_VERSION + 1,
+            ..descriptor("old")
+        };
+        assert!(matches!(
+            register_static_plugin(&mut host, old_abi),
+            Err(PluginError::AbiMismatch { .. })
+        ));
+        let engine = ENGINE_PLUGIN_VERSION;
+        let newer = PluginVersion {
+            patch: engine.patch + 1,
+            ..engine
+        };
+        assert!(!versions_compatible(newer, engine));
+        assert!(versions_compatible(engine, newer));
+    }
+
+    #[test]
+    fn test_panicking_plugin_is_isolated() {
+        let mut host = create_plugin_host();
+        let faulty = PluginDescriptor {
+            on_tick: Some(plugin_hook!(on_tick, panic_after_event)),
+            ..descriptor("faulty")
+        };
+        let healthy = PluginDescriptor {
+            on_tick: Some(plugin_hook!(on_tick, queue_tick_event)),
+            ..descriptor("healthy")
+        };
+        register_static_plugin(&mut host, faulty).expect("compatible plugin");
+        register_static_plugin(&mut host, healthy).expect("compatible plugin");
+
+        let failures = plugins_tick(&mut host, 1, 0.05);
+        assert_eq!(failures.len(), 1);
+        assert!(matches!(
+            find_plugin(&host, "faulty").map(|plugin| &plugin.state),
+            Some(PluginState::Failed(_))
+        ));
+        // Only the healthy plugin's event survives, and the faulty plugin
+        // is not called again
+        assert_eq!(drain_plugin_outbox(&mut host).events.len(), 1);
+        assert!(plugins_tick(&mut host, 2, 0.05).is_empty());
+        assert_eq!(drain_plugin_outbox(&mut host).events.len(), 1);
+        assert!(unload_plugins(&mut host).is_empty());
+        assert!(host.plugins.is_empty());
+    }
+}
@@ -0,0 +1,25 @@
+//! Process Anchor Data
+//!
+//! Binds a process to a block entity in the world (a furnace, a crafting
+//! table), so it stops when the block goes away and its indicator floats
+//! over the block.
+use crate::world::core::{BlockId, VoxelPos};
+use serde::{Deserialize, Serialize};
+
+/// What happens to an anchored process when its block is broken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnchorBreakPolicy {
+    /// Cancel, returning partial outputs and refunds
+    Cancel,
+    /// Pause until the same block is placed back
+    Pause,
+}
+
+/// Block a process runs in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessAnchor {
+    pub position: VoxelPos,
+    /// Block expected at `position`; anything else counts as broken
+    pub block: BlockId,
+    pub on_break: AnchorBreakPolicy,
+}
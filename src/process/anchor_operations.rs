@@ -0,0 +1,40 @@
+//! Process Anchor Operations
+//!
+//! Placement queries for anchored processes. Reacting to block and chunk
+//! changes lives on `ProcessManager`, which owns the interrupts.
+use super::anchor_data::{AnchorBreakPolicy, ProcessAnchor};
+use crate::constants::process_anchor::INDICATOR_HEIGHT;
+use crate::world::core::{BlockId, ChunkPos, VoxelPos};
+
+/// Anchor to the block at `position`
+pub fn create_anchor(
+    position: VoxelPos,
+    block: BlockId,
+    on_break: AnchorBreakPolicy,
+) -> ProcessAnchor {
+    ProcessAnchor {
+        position,
+        block,
+        on_break,
+    }
+}
+
+/// Chunk holding the anchor block
+pub fn anchor_chunk(anchor: &ProcessAnchor, chunk_size: u32) -> ChunkPos {
+    anchor.position.to_chunk_pos(chunk_size)
+}
+
+/// Whether `block` at the anchor position still is the anchor block
+pub fn anchor_intact(anchor: &ProcessAnchor, block: BlockId) -> bool {
+    block == anchor.block
+}
+
+/// World-space point (voxels) centred above the anchor block where its
+/// indicator is drawn
+pub fn anchor_indicator_position(anchor: &ProcessAnchor) -> [f32; 3] {
+    [
+        anchor.position.x as f32 + 0.5,
+        anchor.position.y as f32 + 1.0 + INDICATOR_HEIGHT,
+        anchor.position.z as f32 + 0.5,
+    ]
+}
@@ -0,0 +1,131 @@
+//! Process Exchange Data
+//!
+//! Trades run as processes: a trader (a villager, a shop block) lists
+//! offers that take one set of items from the buyer and give another set
+//! back. Each offer has limited stock that refills on a timer. The engine
+//! validates exchanges against the buyer's inventory and reports finished
+//! ones as transfers; games draw the trade screen from `OfferView`s and
+//! apply the transfers to their own inventories.
+use crate::instance::InstanceId;
+use crate::process::ProcessId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Stack of a resource given or taken by an offer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExchangeItem {
+    pub resource_id: u32,
+    pub quantity: u32,
+}
+
+/// Trade a trader lists
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExchangeOffer {
+    /// Unique per trader; saved stock is matched by it
+    pub name: String,
+    /// Taken from the buyer
+    pub inputs: Vec<ExchangeItem>,
+    /// Given to the buyer
+    pub outputs: Vec<ExchangeItem>,
+    /// Trades before the offer is sold out
+    pub max_stock: u32,
+    /// Ticks after the first trade until the stock is full again
+    pub restock_ticks: u64,
+    /// Ticks an exchange takes (0 = done on the next collect)
+    pub duration_ticks: u64,
+}
+
+/// Offers of one trader, one row per offer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraderStock {
+    pub offers: Vec<ExchangeOffer>,
+    /// Trades left; running exchanges already took theirs
+    pub stock: Vec<u32>,
+    /// Ticks until the stock refills (None while full)
+    pub restock_in: Vec<Option<u64>>,
+}
+
+/// Exchange process in flight, with the items of its offer as they were
+/// when it started
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingExchange {
+    pub trader: InstanceId,
+    pub buyer: InstanceId,
+    pub offer: String,
+    pub inputs: Vec<ExchangeItem>,
+    pub outputs: Vec<ExchangeItem>,
+}
+
+/// Traders and running exchanges
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExchangeData {
+    pub traders: HashMap<InstanceId, TraderStock>,
+    pub pending: HashMap<ProcessId, PendingExchange>,
+}
+
+/// Finished exchange for the game to apply to its inventories
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExchangeTransfer {
+    pub process: ProcessId,
+    pub trader: InstanceId,
+    pub buyer: InstanceId,
+    pub offer: String,
+    /// Remove from the buyer's inventory
+    pub from_buyer: Vec<ExchangeItem>,
+    /// Add to the buyer's inventory
+    pub to_buyer: Vec<ExchangeItem>,
+}
+
+/// Offer as a trade screen shows it to one buyer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfferView {
+    pub offer: String,
+    pub inputs: Vec<ExchangeItem>,
+    pub outputs: Vec<ExchangeItem>,
+    pub stock: u32,
+    pub max_stock: u32,
+    pub restock_in: Option<u64>,
+    /// Buyer holds every input and the offer is in stock
+    pub available: bool,
+}
+
+/// Stock of one offer as written to a save
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedOfferStock {
+    pub offer: String,
+    pub stock: u32,
+    pub restock_in: Option<u64>,
+}
+
+/// Stock of one trader as written to a save; offers themselves come from
+/// the game when it registers the trader again
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedTraderStock {
+    pub trader: InstanceId,
+    pub offers: Vec<SavedOfferStock>,
+}
+
+/// Why an exchange cannot run
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ExchangeError {
+    #[error("Unknown trader: {trader}")]
+    UnknownTrader { trader: InstanceId },
+
+    #[error("Trader has no offer {offer}")]
+    UnknownOffer { offer: String },
+
+    #[error("Offer {offer} is sold out")]
+    OutOfStock {
+        offer: String,
+        restock_in: Option<u64>,
+    },
+
+    #[error("Buyer holds {held} of resource {resource_id}, needs {needed}")]
+    MissingItems {
+        resource_id: u32,
+        needed: u32,
+        held: u32,
+    },
+}
+
+pub type ExchangeResult<T> = Result<T, ExchangeError>;
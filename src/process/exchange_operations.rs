@@ -0,0 +1,246 @@
+//! Process Exchange Operations
+//!
+//! Offer, stock and persistence functions over `ExchangeData`. Starting
+//! and collecting exchanges lives on `ProcessManager`, which owns the
+//! processes they run as.
+use super::exchange_data::{
+    ExchangeData, ExchangeError, ExchangeItem, ExchangeOffer, ExchangeResult, ExchangeTransfer,
+    OfferView, PendingExchange, SavedOfferStock, SavedTraderStock, TraderStock,
+};
+use crate::instance::InstanceId;
+use crate::process::{ProcessId, TimeUnit};
+use std::collections::BTreeMap;
+
+/// Offer trading `inputs` for `outputs`, `max_stock` times per restock
+pub fn create_exchange_offer(
+    name: &str,
+    inputs: Vec<ExchangeItem>,
+    outputs: Vec<ExchangeItem>,
+    max_stock: u32,
+    restock: TimeUnit,
+    duration: TimeUnit,
+) -> ExchangeOffer {
+    ExchangeOffer {
+        name: name.to_string(),
+        inputs,
+        outputs,
+        max_stock,
+        restock_ticks: restock.to_ticks(),
+        duration_ticks: duration.to_ticks(),
+    }
+}
+
+/// List a trader's offers with full stock, replacing earlier ones
+pub fn register_trader(data: &mut ExchangeData, trader: InstanceId, offers: Vec<ExchangeOffer>) {
+    let stock = offers.iter().map(|offer| offer.max_stock).collect();
+    let restock_in = vec![None; offers.len()];
+    data.traders.insert(
+        trader,
+        TraderStock {
+            offers,
+            stock,
+            restock_in,
+        },
+    );
+}
+
+/// Forget a trader (it despawned); running exchanges still complete
+pub fn remove_trader(data: &mut ExchangeData, trader: InstanceId) -> Option<TraderStock> {
+    data.traders.remove(&trader)
+}
+
+/// Row of the named offer
+pub fn find_offer(stock: &TraderStock, offer: &str) -> Option<usize> {
+    stock.offers.iter().position(|listed| listed.name == offer)
+}
+
+/// Check that the offer exists, is in stock and that the buyer holds its
+/// inputs; `held` returns how many of a resource the buyer has. Returns
+/// the offer's row.
+pub fn validate_exchange(
+    data: &ExchangeData,
+    trader: InstanceId,
+    offer: &str,
+    held: impl Fn(u32) -> u32,
+) -> ExchangeResult<usize> {
+    let stock = data
+        .traders
+        .get(&trader)
+        .ok_or(ExchangeError::UnknownTrader { trader })?;
+    let row = find_offer(stock, offer).ok_or_else(|| ExchangeError::UnknownOffer {
+        offer: offer.to_string(),
+    })?;
+    if stock.stock[row] == 0 {
+        return Err(ExchangeError::OutOfStock {
+            offer: offer.to_string(),
+            restock_in: stock.restock_in[row],
+        });
+    }
+    check_buyer_holds(&stock.offers[row].inputs, held)?;
+    Ok(row)
+}
+
+/// Check the buyer holds every input, counting repeated resources once
+pub fn check_buyer_holds(inputs: &[ExchangeItem], held: impl Fn(u32) -> u32) -> ExchangeResult<()> {
+    let mut needed: BTreeMap<u32, u32> = BTreeMap::new();
+    for input in inputs {
+        *needed.entry(input.resource_id).or_default() += input.quantity;
+    }
+    for (resource_id, needed) in needed {
+        let held = held(resource_id);
+        if held < needed {
+            return Err(ExchangeError::MissingItems {
+                resource_id,
+                needed,
+                held,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Take one trade of stock from the validated offer at `row` for an
+/// exchange that is starting, starting the restock timer if the offer was
+/// full. Returns false if there is no such offer.
+pub fn reserve_exchange_stock(
+    data: &mut ExchangeData,
+    process: ProcessId,
+    trader: InstanceId,
+    buyer: InstanceId,
+    row: usize,
+) -> bool {
+    let Some(stock) = data.traders.get_mut(&trader) else {
+        return false;
+    };
+    let Some(offer) = stock.offers.get(row) else {
+        return false;
+    };
+    let exchange = PendingExchange {
+        trader,
+        buyer,
+        offer: offer.name.clone(),
+        inputs: offer.inputs.clone(),
+        outputs: offer.outputs.clone(),
+    };
+    stock.stock[row] = stock.stock[row].saturating_sub(1);
+    if stock.restock_in[row].is_none() {
+        stock.restock_in[row] = Some(offer.restock_ticks);
+    }
+    data.pending.insert(process, exchange);
+    true
+}
+
+/// Drop an exchange that did not go through, giving its trade back to the
+/// trader's stock
+pub fn release_exchange(data: &mut ExchangeData, process: ProcessId) -> Option<PendingExchange> {
+    let exchange = data.pending.remove(&process)?;
+    if let Some(stock) = data.traders.get_mut(&exchange.trader) {
+        if let Some(row) = find_offer(stock, &exchange.offer) {
+            stock.stock[row] = (stock.stock[row] + 1).min(stock.offers[row].max_stock);
+            if stock.stock[row] == stock.offers[row].max_stock {
+                stock.restock_in[row] = None;
+            }
+        }
+    }
+    Some(exchange)
+}
+
+/// Close a finished exchange into the transfer the game applies
+pub fn finish_exchange(data: &mut ExchangeData, process: ProcessId) -> Option<ExchangeTransfer> {
+    let exchange = data.pending.remove(&process)?;
+    Some(ExchangeTransfer {
+        process,
+        trader: exchange.trader,
+        buyer: exchange.buyer,
+        offer: exchange.offer,
+        from_buyer: exchange.inputs,
+        to_buyer: exchange.outputs,
+    })
+}
+
+/// Advance restock timers, refilling offers whose timer ran out
+pub fn tick_exchange_restock(data: &mut ExchangeData, delta_ticks: u64) {
+    for stock in data.traders.values_mut() {
+        for row in 0..stock.offers.len() {
+            let Some(remaining) = stock.restock_in[row] else {
+                continue;
+            };
+            if remaining <= delta_ticks {
+                stock.stock[row] = stock.offers[row].max_stock;
+                stock.restock_in[row] = None;
+            } else {
+                stock.restock_in[row] = Some(remaining - delta_ticks);
+            }
+        }
+    }
+}
+
+/// A trader's offers as a trade screen shows them to a buyer holding
+/// `held` of each resource
+pub fn trader_offer_views(
+    data: &ExchangeData,
+    trader: InstanceId,
+    held: impl Fn(u32) -> u32,
+) -> Vec<OfferView> {
+    let Some(stock) = data.traders.get(&trader) else {
+        return Vec::new();
+    };
+    stock
+        .offers
+        .iter()
+        .enumerate()
+        .map(|(row, offer)| OfferView {
+            offer: offer.name.clone(),
+            inputs: offer.inputs.clone(),
+            outputs: offer.outputs.clone(),
+            stock: stock.stock[row],
+            max_stock: offer.max_stock,
+            restock_in: stock.restock_in[row],
+            available: stock.stock[row] > 0 && check_buyer_holds(&offer.inputs, &held).is_ok(),
+        })
+        .collect()
+}
+
+/// Stock of every trader for saving, in trader order
+pub fn save_trader_stock(data: &ExchangeData) -> Vec<SavedTraderStock> {
+    let mut saved: Vec<SavedTraderStock> = data
+        .traders
+        .iter()
+        .map(|(trader, stock)| SavedTraderStock {
+            trader: *trader,
+            offers: stock
+                .offers
+                .iter()
+                .enumerate()
+                .map(|(row, offer)| SavedOfferStock {
+                    offer: offer.name.clone(),
+                    stock: stock.stock[row],
+                    restock_in: stock.restock_in[row],
+                })
+                .collect(),
+        })
+        .collect();
+    saved.sort_by_key(|trader| trader.trader);
+    saved
+}
+
+/// Restore saved stock onto traders the game registered again. Offers that
+/// no longer exist are ignored and new offers keep full stock. Returns how
+/// many offers were restored.
+pub fn load_trader_stock(data: &mut ExchangeData, saved: &[SavedTraderStock]) -> usize {
+    let mut restored = 0;
+    for trader in saved {
+        let Some(stock) = data.traders.get_mut(&trader.trader) else {
+            continue;
+        };
+        for offer in &trader.offers {
+            let Some(row) = find_offer(stock, &offer.offer) else {
+                continue;
+            };
+            stock.stock[row] = offer.stock.min(stock.offers[row].max_stock);
+            stock.restock_in[row] = offer.restock_in;
+            restored += 1;
+        }
+    }
+    restored
+}
@@ -1,4 +1,8 @@
+pub mod anchor_data;
+pub mod anchor_operations;
 pub mod error;
+pub mod exchange_data;
+pub mod exchange_operations;
 // pub mod parallel_processor; // Removed - using DOP modules instead
 pub mod parallel_processor_data;
 pub mod parallel_processor_operations;
@@ -19,38 +23,60 @@ pub mod transform_stage_operations;
 pub mod visual_indicators_data;
 pub mod visual_indicators_operations;
 
+pub use anchor_data::{AnchorBreakPolicy, ProcessAnchor};
+pub use anchor_operations::{
+    anchor_chunk, anchor_indicator_position, anchor_intact, create_anchor,
+};
+pub use exchange_data::{
+    ExchangeData, ExchangeError, ExchangeItem, ExchangeOffer, ExchangeResult, ExchangeTransfer,
+    OfferView, PendingExchange, SavedOfferStock, SavedTraderStock, TraderStock,
+};
+pub use exchange_operations::{
+    check_buyer_holds, create_exchange_offer, find_offer, finish_exchange, load_trader_stock,
+    register_trader, release_exchange, remove_trader, reserve_exchange_stock, save_trader_stock,
+    tick_exchange_restock, trader_offer_views, validate_exchange,
+};
 pub use parallel_processor_data::ParallelProcessorData;
-pub use parallel_processor_data::ProcessBatch;
-pub use parallel_processor_operations::{create_parallel_processor_data, submit_process_batch_to_gpu};
-pub use process_control::{InterruptReason, ProcessControl};
-pub use process_data::{ProcessData, ProcessId, ProcessStatus, ProcessType};
+pub use parallel_processor_data::{ProcessBackend, ProcessBatch, ProcessBatchStats, StageRange};
+pub use parallel_processor_operations::{
+    advance_process_timer, advance_processes_parallel, create_parallel_processor_data,
+    enable_process_gpu, submit_process_batch_to_gpu,
+};
+pub use process_control::{CancelOutcome, ControlResult, InterruptReason, ProcessControl};
+pub use process_data::{ProcessData, ProcessId, ProcessStatus, ProcessType, SavedProcess};
 pub use process_executor::{ExecutionResult, ProcessExecutor};
 pub use stage_validator::StageValidator;
 pub use state_machine::{ProcessState, StateMachine, StateTransition, TransitionAction};
 pub use transform_stage_data::{
-    ActualOutput, OutputType, StageOutput, StageRequirement, TransformStage,
+    ActualOutput, OutputType, StageOutput, StagePosition, StageRequirement, TransformStage,
     ValidationContext, ValidationResult, ItemRequirement, ToolRequirement,
     EnvironmentRequirement, WeatherType,
 };
 pub use transform_stage_operations::{
-    validate_requirements, calculate_outputs, create_crafting_stage, create_smelting_stage,
+    validate_requirements, calculate_outputs, calculate_refunds, create_crafting_stage,
+    create_smelting_stage, stage_duration_ticks, stage_position,
 };
 pub use visual_indicators_data::{
-    ProcessVisual, ProgressBar, StatusIcon, ProgressColor, BarAnimation,
+    AnchoredIndicator, ProcessVisual, ProgressBar, StatusIcon, ProgressColor, BarAnimation,
     TextOverlay, TextPosition, TextStyle, ParticleEffect, ParticleType, AnimationState,
 };
 pub use visual_indicators_operations::{
-    update_progress, update_status, calculate_segments, add_text, add_particle,
+    update_progress, set_world_position, update_status, calculate_segments, add_text, add_particle,
     update_visual, create_crafting_visual, create_smelting_visual, create_growth_visual,
     quality_to_visual, generate_progress_bar_vertices,
 };
 
 use crate::instance::InstanceId;
+use crate::world::core::{BlockId, ChunkPos, VoxelPos};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Maximum concurrent processes
 pub const MAX_PROCESSES: usize = 1 << 16; // 65k
 
+/// Game ticks per second used for process timing
+pub const TICKS_PER_SECOND: u64 = 20;
+
 /// Process types for categorization
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -62,6 +88,8 @@ pub enum ProcessCategory {
     Research = 4,
     Repair = 5,
     Upgrade = 6,
+    /// Trade with a trader (see `ProcessManager::start_exchange`)
+    Exchange = 7,
     Custom = 255,
 }
 
@@ -123,6 +151,16 @@ pub struct ProcessManager {
     /// Visual indicators
     pub visuals: Vec<ProcessVisual>,
 
+    /// Block each process is bound to, if any
+    pub anchors: Vec<Option<ProcessAnchor>>,
+
+    /// Traders, their stock and running exchanges
+    pub exchanges: ExchangeData,
+
+    /// Speed of each category's processes (missing = 1.0), e.g. crop
+    /// growth slowed by the season
+    pub category_rates: HashMap<ProcessCategory, f32>,
+
     /// Process executor
     pub executor: ProcessExecutor,
 
@@ -143,6 +181,9 @@ impl ProcessManager {
             state_machines: Vec::with_capacity(MAX_PROCESSES),
             transform_stages: Vec::with_capacity(MAX_PROCESSES),
             visuals: Vec::with_capacity(MAX_PROCESSES),
+            anchors: Vec::with_capacity(MAX_PROCESSES),
+            exchanges: ExchangeData::default(),
+            category_rates: HashMap::new(),
             executor: ProcessExecutor::new(),
             parallel_data: create_parallel_processor_data()
                 .map_err(|e| crate::error::EngineError::InitializationError(e))?,
@@ -175,24 +216,57 @@ impl ProcessManager {
         // Initialize visual
         self.visuals.push(ProcessVisual::default());
 
+        // Not bound to a block until `bind_to_block`
+        self.anchors.push(None);
+
         id
     }
 
     /// Update all processes (called each tick)
     pub fn update(&mut self, delta_ticks: u64) {
-        // Use parallel processor for batch updates
+        let deltas = vec![delta_ticks; self.processes.len()];
+        self.update_scaled(&deltas, delta_ticks);
+    }
+
+    /// Update each process by its own ticks (`delta_ticks` indexed like
+    /// `processes`, e.g. the ticks its chunk simulated); traders restock by
+    /// the `elapsed_ticks` of the world
+    pub fn update_scaled(&mut self, delta_ticks: &[u64], elapsed_ticks: u64) {
+        // Use parallel processor for batch updates, scaling each process's
+        // ticks by its category rate
+        let mut stage_ends = Vec::new();
+        let stage_ranges = self
+            .transform_stages
+            .iter()
+            .map(|stages| {
+                let first = stage_ends.len() as u32;
+                let mut end = 0u64;
+                for stage in stages {
+                    end += stage_duration_ticks(stage);
+                    stage_ends.push(end.min(u32::MAX as u64) as u32);
+                }
+                StageRange {
+                    first,
+                    count: stages.len() as u32,
+                }
+            })
+            .collect();
+        let indices: Vec<usize> = (0..self.processes.len())
+            .filter(|&i| delta_ticks.get(i).is_some_and(|&ticks| ticks > 0))
+            .collect();
         let batch = ProcessBatch {
-            indices: (0..self.processes.len()).collect(),
-            delta_ticks: vec![delta_ticks as f32], // Convert u64 to Vec<f32>
+            delta_ticks: indices
+                .iter()
+                .map(|&i| {
+                    delta_ticks[i] as f32 * self.category_rate(self.processes.types[i].category)
+                })
+                .collect(),
+            indices,
+            stage_ends,
+            stage_ranges,
         };
 
-        submit_process_batch_to_gpu(
-            &mut self.parallel_data,
-            &self.gpu_thread_pool,
-            &mut self.processes,
-            &mut self.state_machines,
-            batch,
-        );
+        submit_process_batch_to_gpu(&mut self.parallel_data, &mut self.processes, batch);
 
         // Update visuals based on progress
         for i in 0..self.processes.len() {
@@ -201,6 +275,401 @@ impl ProcessManager {
                 update_progress(&mut self.visuals[i], progress);
             }
         }
+
+        tick_exchange_restock(&mut self.exchanges, elapsed_ticks);
+    }
+
+    /// Advance large batches in a compute shader on `device`; without this
+    /// every update runs on the CPU thread pool
+    pub fn enable_gpu(
+        &mut self,
+        device: std::sync::Arc<wgpu::Device>,
+        queue: std::sync::Arc<wgpu::Queue>,
+    ) -> Result<(), crate::error::EngineError> {
+        enable_process_gpu(&mut self.parallel_data, device, queue)
+            .map_err(crate::error::EngineError::InitializationError)
+    }
+
+    /// Run processes of `category` at `rate` times normal speed
+    pub fn set_category_rate(&mut self, category: ProcessCategory, rate: f32) {
+        self.category_rates.insert(category, rate.max(0.0));
+    }
+
+    /// Speed of a category's processes (1.0 = normal)
+    pub fn category_rate(&self, category: ProcessCategory) -> f32 {
+        self.category_rates.get(&category).copied().unwrap_or(1.0)
+    }
+
+    /// Start trading `buyer` one of `trader`'s offers. `held` returns how
+    /// many of a resource the buyer holds. The trade is taken from the
+    /// offer's stock now and handed back if the exchange is cancelled.
+    pub fn start_exchange(
+        &mut self,
+        trader: InstanceId,
+        buyer: InstanceId,
+        offer: &str,
+        held: impl Fn(u32) -> u32,
+    ) -> ExchangeResult<ProcessId> {
+        let row = validate_exchange(&self.exchanges, trader, offer, held)?;
+        let duration = self.exchanges.traders[&trader].offers[row].duration_ticks;
+        let process_type = ProcessType {
+            category: ProcessCategory::Exchange,
+            sub_type: row as u16,
+        };
+        let id = self.start_process(process_type, buyer, vec![], TimeUnit::Ticks(duration));
+        reserve_exchange_stock(&mut self.exchanges, id, trader, buyer, row);
+
+        // Exchanges start right away; instant ones finish here
+        if let Some(index) = self.processes.find_index(id) {
+            self.processes.status[index] = ProcessStatus::Active;
+            self.processes.update(index, 0);
+        }
+        Ok(id)
+    }
+
+    /// Finish completed exchanges into the transfers the game applies to
+    /// its inventories. The buyer is checked again with `held` (buyer,
+    /// resource -> count); exchanges whose buyer no longer holds the
+    /// inputs fail and hand their trade back to the trader.
+    pub fn collect_exchanges(
+        &mut self,
+        held: impl Fn(InstanceId, u32) -> u32,
+    ) -> Vec<ExchangeTransfer> {
+        let mut completed: Vec<ProcessId> = self
+            .exchanges
+            .pending
+            .keys()
+            .copied()
+            .filter(|id| {
+                self.processes
+                    .find_index(*id)
+                    .is_some_and(|index| self.processes.status[index] == ProcessStatus::Completed)
+            })
+            .collect();
+        completed.sort_by_key(|id| id.0);
+
+        let mut transfers = Vec::new();
+        for id in completed {
+            let Some(exchange) = self.exchanges.pending.get(&id) else {
+                continue;
+            };
+            let buyer = exchange.buyer;
+            if check_buyer_holds(&exchange.inputs, |resource| held(buyer, resource)).is_err() {
+                release_exchange(&mut self.exchanges, id);
+                if let Some(index) = self.processes.find_index(id) {
+                    self.processes.status[index] = ProcessStatus::Failed;
+                    self.processes.active[index] = false;
+                }
+                continue;
+            }
+            if let Some(transfer) = finish_exchange(&mut self.exchanges, id) {
+                if let Some(index) = self.processes.find_index(id) {
+                    self.processes.active[index] = false;
+                }
+                transfers.push(transfer);
+            }
+        }
+        transfers
+    }
+
+    /// Set the stages a process runs through
+    pub fn set_process_stages(
+        &mut self,
+        id: ProcessId,
+        stages: Vec<TransformStage>,
+    ) -> ControlResult<()> {
+        let index = self
+            .processes
+            .find_index(id)
+            .ok_or_else(|| "Process not found".to_string())?;
+        self.transform_stages[index] = stages;
+        Ok(())
+    }
+
+    /// Pause a process, keeping its progress
+    pub fn interrupt_process(&mut self, id: ProcessId, reason: InterruptReason) -> ControlResult<()> {
+        self.control.interrupt_process(id, reason, &mut self.processes)
+    }
+
+    /// Clear one interrupt; the process continues once none remain
+    pub fn resume_process(&mut self, id: ProcessId, reason: &InterruptReason) -> ControlResult<bool> {
+        self.control.resume_after(id, reason, &mut self.processes)
+    }
+
+    /// Cancel a process (and its dependents, per policy), returning the
+    /// partial outputs and refunds of each cancelled process
+    pub fn cancel_process(&mut self, id: ProcessId) -> ControlResult<Vec<CancelOutcome>> {
+        let cancelled = self.control.cancel_process(id, &mut self.processes)?;
+        for process in &cancelled {
+            release_exchange(&mut self.exchanges, *process);
+        }
+        Ok(cancelled
+            .into_iter()
+            .filter_map(|process| self.cancel_outcome(process))
+            .collect())
+    }
+
+    /// Auto-cancel everything an owner was running when it despawns
+    pub fn cancel_owned_by(&mut self, owner: InstanceId) -> Vec<CancelOutcome> {
+        let owned: Vec<ProcessId> = (0..self.processes.len())
+            .filter(|&i| self.processes.active[i] && self.processes.owners[i] == owner)
+            .map(|i| self.processes.ids[i])
+            .collect();
+
+        let mut outcomes = Vec::new();
+        for id in owned {
+            if let Ok(cancelled) = self.cancel_process(id) {
+                outcomes.extend(cancelled);
+            }
+        }
+        outcomes
+    }
+
+    /// Advance interrupt timers, cancelling processes interrupted longer
+    /// than the policy allows
+    pub fn tick_interrupts(&mut self, delta_ticks: u64) -> Vec<CancelOutcome> {
+        let expired = self.control.tick_interrupts(delta_ticks, &self.processes);
+        let mut outcomes = Vec::new();
+        for id in expired {
+            if let Ok(cancelled) = self.cancel_process(id) {
+                outcomes.extend(cancelled);
+            }
+        }
+        outcomes
+    }
+
+    /// Partial outputs and refunds of the stage a process stopped in
+    fn cancel_outcome(&self, id: ProcessId) -> Option<CancelOutcome> {
+        let index = self.processes.find_index(id)?;
+        let stages = &self.transform_stages[index];
+        let quality = self.processes.quality[index] as u8 as f32 / 4.0;
+        let (outputs, refunds) = match stage_position(stages, self.processes.elapsed[index]) {
+            Some(position) => {
+                let stage = &stages[position.stage];
+                (
+                    calculate_outputs(stage, position.completion, quality),
+                    calculate_refunds(stage, position.completion),
+                )
+            }
+            None => (Vec::new(), Vec::new()),
+        };
+
+        Some(CancelOutcome {
+            process: id,
+            owner: self.processes.owners[index],
+            outputs,
+            refunds,
+        })
+    }
+
+    /// Unfinished processes for saving, with their interrupts, and
+    /// exchanges whose transfer was not collected yet
+    pub fn save_processes(&self) -> Vec<SavedProcess> {
+        (0..self.processes.len())
+            .filter(|&i| {
+                self.processes.active[i]
+                    && (matches!(
+                        self.processes.status[i],
+                        ProcessStatus::Pending | ProcessStatus::Active | ProcessStatus::Paused
+                    ) || self.exchanges.pending.contains_key(&self.processes.ids[i]))
+            })
+            .map(|i| {
+                let id = self.processes.ids[i];
+                SavedProcess {
+                    id,
+                    process_type: self.processes.types[i],
+                    owner: self.processes.owners[i],
+                    status: self.processes.status[i],
+                    priority: self.processes.priority[i],
+                    quality: self.processes.quality[i],
+                    duration: self.processes.duration[i],
+                    elapsed: self.processes.elapsed[i],
+                    stages: self.transform_stages[i].clone(),
+                    interrupts: self.control.get_interrupts(id).to_vec(),
+                    interrupted_ticks: self.control.get_interrupted_ticks(id),
+                    anchor: self.anchors[i],
+                    exchange: self.exchanges.pending.get(&id).cloned(),
+                }
+            })
+            .collect()
+    }
+
+    /// Restore saved processes with their progress. Interrupts caused by the
+    /// shutdown are dropped, so processes only they paused continue.
+    pub fn load_processes(&mut self, saved: Vec<SavedProcess>) {
+        for process in saved {
+            ProcessId::reserve(process.id);
+            let index = self.processes.add(
+                process.id,
+                process.process_type,
+                process.owner,
+                process.duration,
+            );
+            self.processes.status[index] = process.status;
+            self.processes.priority[index] = process.priority;
+            self.processes.quality[index] = process.quality;
+            self.processes.elapsed[index] = process.elapsed;
+
+            let mut state_machine = StateMachine::new();
+            if process.status != ProcessStatus::Pending {
+                state_machine.force_transition(ProcessState::PREPARING);
+            }
+            self.state_machines.push(state_machine);
+            self.transform_stages.push(process.stages);
+            let mut visual = ProcessVisual::default();
+            update_progress(&mut visual, self.processes.get_progress(index));
+            set_world_position(
+                &mut visual,
+                process.anchor.as_ref().map(anchor_indicator_position),
+            );
+            self.visuals.push(visual);
+            self.anchors.push(process.anchor);
+            if let Some(exchange) = process.exchange {
+                self.exchanges.pending.insert(process.id, exchange);
+            }
+
+            let interrupts: Vec<InterruptReason> = process
+                .interrupts
+                .into_iter()
+                .filter(|reason| *reason != InterruptReason::ServerShutdown)
+                .collect();
+            if interrupts.is_empty() && process.status == ProcessStatus::Paused {
+                self.processes.resume(index);
+            }
+            self.control
+                .restore_interrupts(process.id, interrupts, process.interrupted_ticks);
+        }
+    }
+
+    /// Bind a process to a block; its indicator moves over the block
+    pub fn bind_to_block(&mut self, id: ProcessId, anchor: ProcessAnchor) -> ControlResult<()> {
+        let index = self
+            .processes
+            .find_index(id)
+            .ok_or_else(|| "Process not found".to_string())?;
+        self.anchors[index] = Some(anchor);
+        set_world_position(
+            &mut self.visuals[index],
+            Some(anchor_indicator_position(&anchor)),
+        );
+        Ok(())
+    }
+
+    /// Detach a process from its block, resuming it if only the anchor
+    /// held it
+    pub fn unbind_from_block(&mut self, id: ProcessId) -> ControlResult<Option<ProcessAnchor>> {
+        let index = self
+            .processes
+            .find_index(id)
+            .ok_or_else(|| "Process not found".to_string())?;
+        set_world_position(&mut self.visuals[index], None);
+        self.clear_anchor_interrupt(id, InterruptReason::AnchorBroken);
+        self.clear_anchor_interrupt(id, InterruptReason::AnchorUnloaded);
+        Ok(self.anchors[index].take())
+    }
+
+    /// Block a process is bound to
+    pub fn process_anchor(&self, id: ProcessId) -> Option<ProcessAnchor> {
+        let index = self.processes.find_index(id)?;
+        self.anchors[index]
+    }
+
+    /// Unfinished processes bound to the block at `position`
+    pub fn processes_at(&self, position: VoxelPos) -> Vec<ProcessId> {
+        self.anchored_where(|anchor| anchor.position == position)
+    }
+
+    /// The block at `position` changed to `block`. Processes whose anchor
+    /// block is gone are cancelled or paused per their policy; paused ones
+    /// resume once the block is back.
+    pub fn on_block_changed(&mut self, position: VoxelPos, block: BlockId) -> Vec<CancelOutcome> {
+        let mut outcomes = Vec::new();
+        for id in self.processes_at(position) {
+            outcomes.extend(self.check_anchor_block(id, block));
+        }
+        outcomes
+    }
+
+    /// Pause processes anchored in a chunk that is being unloaded. They do
+    /// not count towards the interrupt timeout while unloaded.
+    pub fn on_chunk_unloaded(&mut self, chunk_pos: ChunkPos, chunk_size: u32) {
+        for id in self.anchored_where(|anchor| anchor_chunk(anchor, chunk_size) == chunk_pos) {
+            self.add_anchor_interrupt(id, InterruptReason::AnchorUnloaded);
+        }
+    }
+
+    /// Resume processes anchored in a chunk that was loaded again, checking
+    /// their blocks with `block_at` in case the world changed meanwhile
+    pub fn on_chunk_loaded(
+        &mut self,
+        chunk_pos: ChunkPos,
+        chunk_size: u32,
+        block_at: impl Fn(VoxelPos) -> BlockId,
+    ) -> Vec<CancelOutcome> {
+        let mut outcomes = Vec::new();
+        for id in self.anchored_where(|anchor| anchor_chunk(anchor, chunk_size) == chunk_pos) {
+            self.clear_anchor_interrupt(id, InterruptReason::AnchorUnloaded);
+            if let Some(anchor) = self.process_anchor(id) {
+                outcomes.extend(self.check_anchor_block(id, block_at(anchor.position)));
+            }
+        }
+        outcomes
+    }
+
+    /// Indicators of unfinished anchored processes, in world space
+    pub fn anchored_indicators(&self) -> Vec<AnchoredIndicator> {
+        (0..self.processes.len())
+            .filter(|&i| self.processes.active[i])
+            .filter_map(|i| {
+                Some(AnchoredIndicator {
+                    process: self.processes.ids[i],
+                    position: self.visuals[i].world_position?,
+                    progress: self.processes.get_progress(i),
+                    paused: self.processes.status[i] == ProcessStatus::Paused,
+                })
+            })
+            .collect()
+    }
+
+    /// Unfinished processes whose anchor matches `predicate`
+    fn anchored_where(&self, predicate: impl Fn(&ProcessAnchor) -> bool) -> Vec<ProcessId> {
+        (0..self.processes.len())
+            .filter(|&i| self.processes.active[i])
+            .filter(|&i| self.anchors[i].as_ref().is_some_and(&predicate))
+            .map(|i| self.processes.ids[i])
+            .collect()
+    }
+
+    /// Apply the break policy of one process given the block now at its
+    /// anchor
+    fn check_anchor_block(&mut self, id: ProcessId, block: BlockId) -> Vec<CancelOutcome> {
+        let Some(anchor) = self.process_anchor(id) else {
+            return Vec::new();
+        };
+        if anchor_intact(&anchor, block) {
+            self.clear_anchor_interrupt(id, InterruptReason::AnchorBroken);
+            return Vec::new();
+        }
+        match anchor.on_break {
+            AnchorBreakPolicy::Cancel => self.cancel_process(id).unwrap_or_default(),
+            AnchorBreakPolicy::Pause => {
+                self.add_anchor_interrupt(id, InterruptReason::AnchorBroken);
+                Vec::new()
+            }
+        }
+    }
+
+    fn add_anchor_interrupt(&mut self, id: ProcessId, reason: InterruptReason) {
+        if !self.control.get_interrupts(id).contains(&reason) {
+            // Pending processes have nothing to pause
+            let _ = self.interrupt_process(id, reason);
+        }
+    }
+
+    fn clear_anchor_interrupt(&mut self, id: ProcessId, reason: InterruptReason) {
+        if self.control.get_interrupts(id).contains(&reason) {
+            let _ = self.resume_process(id, &reason);
+        }
     }
 
     /// Get process info
@@ -260,4 +729,192 @@ mod tests {
         assert_eq!(info.owner, owner);
         assert_eq!(info.time_remaining, 100); // 5 seconds * 20 ticks
     }
+
+    fn running_smelt(manager: &mut ProcessManager, owner: InstanceId, elapsed: u64) -> ProcessId {
+        // 8 ore at 10 s each: 1600 ticks
+        let id = manager.start_process(ProcessType::default(), owner, vec![], TimeUnit::Ticks(1600));
+        manager
+            .set_process_stages(id, vec![create_smelting_stage(1, 2, 8, 10.0)])
+            .expect("stages");
+        let index = manager.processes.find_index(id).expect("index");
+        manager.processes.status[index] = ProcessStatus::Active;
+        manager.processes.update(index, elapsed);
+        id
+    }
+
+    #[test]
+    fn test_interrupt_keeps_progress_and_cancel_refunds() {
+        let mut manager = ProcessManager::new().expect("Failed to create manager");
+        let owner = InstanceId::new();
+        let id = running_smelt(&mut manager, owner, 700);
+        let index = manager.processes.find_index(id).expect("index");
+
+        manager
+            .interrupt_process(id, InterruptReason::UserPaused)
+            .expect("interrupt");
+        manager
+            .interrupt_process(id, InterruptReason::PlayerOutOfRange)
+            .expect("interrupt");
+        manager.processes.update(index, 100);
+        assert_eq!(manager.processes.elapsed[index], 700);
+
+        assert_eq!(manager.resume_process(id, &InterruptReason::UserPaused), Ok(false));
+        assert_eq!(
+            manager.resume_process(id, &InterruptReason::PlayerOutOfRange),
+            Ok(true)
+        );
+        assert_eq!(manager.processes.status[index], ProcessStatus::Active);
+
+        let outcomes = manager.cancel_owned_by(owner);
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].outputs[0].quantity, 3);
+        assert_eq!(outcomes[0].refunds[0].output_type, OutputType::Item(1));
+        assert_eq!(outcomes[0].refunds[0].quantity, 4);
+        assert_eq!(manager.processes.status[index], ProcessStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_anchored_furnace_follows_its_block() {
+        let mut manager = ProcessManager::new().expect("Failed to create manager");
+        let owner = InstanceId::new();
+        let furnace = VoxelPos::new(40, 12, -3);
+        let paused = running_smelt(&mut manager, owner, 400);
+        let cancelled = running_smelt(&mut manager, owner, 800);
+        manager
+            .bind_to_block(paused, create_anchor(furnace, BlockId(7), AnchorBreakPolicy::Pause))
+            .expect("bind");
+        manager
+            .bind_to_block(
+                cancelled,
+                create_anchor(VoxelPos::new(41, 12, -3), BlockId(7), AnchorBreakPolicy::Cancel),
+            )
+            .expect("bind");
+
+        let indicators = manager.anchored_indicators();
+        assert_eq!(indicators.len(), 2);
+        assert_eq!(indicators[0].position, [40.5, 13.0 + 3.0, -2.5]);
+        assert!((indicators[0].progress - 0.25).abs() < 1e-6);
+
+        // Unloaded chunks pause without ticking toward the timeout
+        let chunk = furnace.to_chunk_pos(32);
+        manager.on_chunk_unloaded(chunk, 32);
+        assert!(manager.anchored_indicators().iter().all(|i| i.paused));
+        assert!(manager.tick_interrupts(u64::MAX / 2).is_empty());
+        assert!(manager.on_chunk_loaded(chunk, 32, |_| BlockId(7)).is_empty());
+        assert!(manager.anchored_indicators().iter().all(|i| !i.paused));
+
+        // Breaking the blocks pauses one and cancels the other
+        assert!(manager.on_block_changed(furnace, BlockId::AIR).is_empty());
+        let outcomes = manager.on_block_changed(VoxelPos::new(41, 12, -3), BlockId::AIR);
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].process, cancelled);
+        let indicators = manager.anchored_indicators();
+        assert_eq!(indicators.len(), 1);
+        assert!(indicators[0].paused);
+
+        // Placing the furnace back resumes it
+        manager.on_block_changed(furnace, BlockId(7));
+        let info = manager.get_process(paused).expect("process");
+        assert_eq!(info.status, ProcessStatus::Active);
+        assert_eq!(info.time_remaining, 1200);
+    }
+
+    #[test]
+    fn test_interrupted_smelt_resumes_after_reload() {
+        let mut manager = ProcessManager::new().expect("Failed to create manager");
+        let owner = InstanceId::new();
+        let shutdown = running_smelt(&mut manager, owner, 800);
+        let paused = running_smelt(&mut manager, owner, 400);
+        manager
+            .interrupt_process(shutdown, InterruptReason::ServerShutdown)
+            .expect("interrupt");
+        manager
+            .interrupt_process(paused, InterruptReason::UserPaused)
+            .expect("interrupt");
+
+        let bytes = bincode::serialize(&manager.save_processes()).expect("serialize");
+        let saved: Vec<SavedProcess> = bincode::deserialize(&bytes).expect("deserialize");
+
+        let mut reloaded = ProcessManager::new().expect("Failed to create manager");
+        reloaded.load_processes(saved);
+
+        let info = reloaded.get_process(shutdown).expect("restored");
+        assert_eq!(info.status, ProcessStatus::Active);
+        assert_eq!(info.time_remaining, 800);
+
+        let info = reloaded.get_process(paused).expect("restored");
+        assert_eq!(info.status, ProcessStatus::Paused);
+        assert_eq!(
+            reloaded.control.get_interrupts(paused),
+            &[InterruptReason::UserPaused]
+        );
+        assert_ne!(ProcessId::new().0, paused.0);
+    }
+
+    #[test]
+    fn test_exchange_stock_transfers_and_persistence() {
+        const EMERALD: u32 = 40;
+        const BREAD: u32 = 41;
+        let mut manager = ProcessManager::new().expect("Failed to create manager");
+        let trader = InstanceId::new();
+        let buyer = InstanceId::new();
+        let offer = create_exchange_offer(
+            "bread",
+            vec![ExchangeItem { resource_id: EMERALD, quantity: 2 }],
+            vec![ExchangeItem { resource_id: BREAD, quantity: 6 }],
+            2,
+            TimeUnit::Ticks(100),
+            TimeUnit::Ticks(0),
+        );
+        register_trader(&mut manager.exchanges, trader, vec![offer]);
+        let emeralds = |held: u32| move |resource: u32| if resource == EMERALD { held } else { 0 };
+
+        assert_eq!(
+            manager.start_exchange(trader, buyer, "bread", emeralds(1)),
+            Err(ExchangeError::MissingItems { resource_id: EMERALD, needed: 2, held: 1 })
+        );
+        let views = trader_offer_views(&manager.exchanges, trader, emeralds(1));
+        assert!(!views[0].available);
+
+        // Instant trade completes on collect
+        let first = manager
+            .start_exchange(trader, buyer, "bread", emeralds(4))
+            .expect("trade");
+        let transfers = manager.collect_exchanges(|_, resource| emeralds(4)(resource));
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].process, first);
+        assert_eq!(transfers[0].to_buyer[0].quantity, 6);
+
+        // Cancelling hands the trade back; selling out blocks further trades
+        let second = manager
+            .start_exchange(trader, buyer, "bread", emeralds(4))
+            .expect("trade");
+        manager.cancel_process(second).expect("cancel");
+        assert_eq!(trader_offer_views(&manager.exchanges, trader, emeralds(4))[0].stock, 1);
+        manager
+            .start_exchange(trader, buyer, "bread", emeralds(4))
+            .expect("trade");
+        assert!(matches!(
+            manager.start_exchange(trader, buyer, "bread", emeralds(4)),
+            Err(ExchangeError::OutOfStock { restock_in: Some(100), .. })
+        ));
+
+        // Stock and the uncollected trade survive a reload
+        let stock = bincode::serialize(&save_trader_stock(&manager.exchanges)).expect("serialize");
+        let processes = manager.save_processes();
+        let mut reloaded = ProcessManager::new().expect("Failed to create manager");
+        register_trader(
+            &mut reloaded.exchanges,
+            trader,
+            manager.exchanges.traders[&trader].offers.clone(),
+        );
+        let stock: Vec<SavedTraderStock> = bincode::deserialize(&stock).expect("deserialize");
+        assert_eq!(load_trader_stock(&mut reloaded.exchanges, &stock), 1);
+        reloaded.load_processes(processes);
+        assert_eq!(reloaded.collect_exchanges(|_, _| 0).len(), 0);
+        assert_eq!(trader_offer_views(&reloaded.exchanges, trader, emeralds(4))[0].stock, 1);
+
+        reloaded.update(100);
+        assert_eq!(trader_offer_views(&reloaded.exchanges, trader, emeralds(4))[0].stock, 2);
+    }
 }
@@ -1,7 +1,118 @@
-//! Parallel Processor Data - Stub
-pub struct ParallelProcessorData;
+//! Parallel Processor Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Timer advancement lives in parallel_processor_operations.rs
+//!
+//! Every tick each active process's elapsed time moves forward by its
+//! (rate-scaled, possibly fractional) ticks, its current stage is worked
+//! out from the elapsed time and it completes once the duration is
+//! reached. With a GPU attached, large batches upload the `ProcessData`
+//! columns and advance them in a compute shader; the results are read back
+//! without blocking and applied on a later update, while the ticks of the
+//! updates in between queue up for the next dispatch. Headless, or for
+//! small batches, the same step runs on the CPU across threads.
 
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use std::sync::atomic::AtomicU8;
+use std::sync::Arc;
+
+/// Processes to advance and by how much
 pub struct ProcessBatch {
     pub indices: Vec<usize>,
+    /// Ticks for each entry of `indices` (fractions carry over)
+    pub delta_ticks: Vec<f32>,
+    /// Cumulative end tick of each stage, all processes back to back
+    pub stage_ends: Vec<u32>,
+    /// Stages of each process in `stage_ends`, by process index; missing
+    /// entries mean no stages
+    pub stage_ranges: Vec<StageRange>,
+}
+
+/// Slice of `ProcessBatch::stage_ends` belonging to one process
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct StageRange {
+    pub first: u32,
+    pub count: u32,
+}
+
+/// Where the last batch ran
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProcessBackend {
+    #[default]
+    ParallelCpu,
+    Gpu,
+}
+
+/// Batch counters since the processor was created
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessBatchStats {
+    pub cpu_batches: u64,
+    pub gpu_dispatches: u64,
+    /// GPU results applied
+    pub gpu_readbacks: u64,
+    pub gpu_failures: u64,
+    pub completed: u64,
+    /// Wall time of the last CPU batch or GPU submission (microseconds)
+    pub last_batch_us: f32,
+    pub last_backend: ProcessBackend,
+}
+
+/// Dispatch whose results have not been applied yet
+pub struct ProcessGpuDispatch {
+    /// Processes in the dispatch (indices `0..count`)
+    pub count: u32,
+    /// Ticks sent with the dispatch, handed back to the queue if the
+    /// readback fails
     pub delta_ticks: Vec<f32>,
 }
+
+/// GPU side of the processor
+pub struct ProcessGpuData {
+    pub device: Arc<wgpu::Device>,
+    pub queue: Arc<wgpu::Queue>,
+    pub pipeline: wgpu::ComputePipeline,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    /// Processes the buffers hold
+    pub capacity: u32,
+    pub params: wgpu::Buffer,
+    /// Read-write columns, also copied to `readback` in this order
+    pub elapsed: wgpu::Buffer,
+    pub remainder: wgpu::Buffer,
+    pub status: wgpu::Buffer,
+    pub stage: wgpu::Buffer,
+    /// Read-only columns
+    pub duration: wgpu::Buffer,
+    pub delta: wgpu::Buffer,
+    pub stage_ranges: wgpu::Buffer,
+    pub stage_ends: wgpu::Buffer,
+    /// Entries `stage_ends` holds
+    pub stage_capacity: u32,
+    pub readback: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+    pub in_flight: Option<ProcessGpuDispatch>,
+    /// `READBACK_*` state of the in-flight readback, set by `map_async`
+    pub readback_state: Arc<AtomicU8>,
+    /// Tracked `params`, per-process columns (replaced whenever `capacity`
+    /// grows) and `stage_ends`
+    pub params_allocation: GpuAllocationGuard,
+    pub column_allocations: Vec<GpuAllocationGuard>,
+    pub stage_ends_allocation: GpuAllocationGuard,
+    pub gpu_owner: GpuOwnerGuard,
+}
+
+/// Batch update state of a `ProcessManager`
+#[derive(Default)]
+pub struct ParallelProcessorData {
+    /// Set by `enable_process_gpu`; `None` runs every batch on the CPU
+    pub gpu: Option<ProcessGpuData>,
+    /// Batches smaller than this stay on the CPU
+    pub min_gpu_batch: usize,
+    /// Fraction of a tick each process has left over
+    pub remainders: Vec<f32>,
+    /// Ticks waiting for the next GPU dispatch
+    pub queued: Vec<f32>,
+    /// Current stage of each process
+    pub stages: Vec<u32>,
+    pub stats: ProcessBatchStats,
+}
@@ -1,19 +1,649 @@
-//! Parallel Processor Operations - Stub
-use super::{ParallelProcessorData, ProcessBatch, ProcessData, StateMachine};
-use crate::thread_pool::GpuThreadPoolData;
+//! Parallel Processor Operations - Pure DOP Functions
+//!
+//! `submit_process_batch_to_gpu` is called once per tick. With a GPU
+//! attached (`enable_process_gpu`) and a large enough batch it first applies
+//! the previous dispatch if its readback has mapped, then dispatches the
+//! ticks queued since, if nothing is in flight. Otherwise it runs
+//! `advance_process_timer` over every process on the rayon pool.
 
-pub fn process_parallel() {}
+use super::parallel_processor_data::{
+    ParallelProcessorData, ProcessBackend, ProcessBatch, ProcessGpuData, ProcessGpuDispatch,
+    StageRange,
+};
+use super::{ProcessData, ProcessStatus};
+use crate::constants::process_batch::{
+    INITIAL_PROCESS_GPU_CAPACITY, MIN_GPU_PROCESS_BATCH, PROCESS_WORKGROUP_SIZE,
+};
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
 
+/// `ProcessGpuData::readback_state` values
+const READBACK_IDLE: u8 = 0;
+const READBACK_WAITING: u8 = 1;
+const READBACK_MAPPED: u8 = 2;
+const READBACK_FAILED: u8 = 3;
+
+/// Status code the shader skips (inactive processes)
+const STATUS_SKIPPED: u32 = u32::MAX;
+
+/// Read-write columns copied back, in readback order
+const READBACK_COLUMNS: u64 = 4;
+
+/// CPU-only processor; attach a GPU with `enable_process_gpu`
 pub fn create_parallel_processor_data() -> Result<ParallelProcessorData, String> {
-    Ok(ParallelProcessorData)
+    Ok(ParallelProcessorData {
+        min_gpu_batch: MIN_GPU_PROCESS_BATCH,
+        ..Default::default()
+    })
 }
 
+/// Run large batches on `device` from now on
+pub fn enable_process_gpu(
+    data: &mut ParallelProcessorData,
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+) -> Result<(), String> {
+    let shader = crate::gpu::automation::create_gpu_shader(
+        &device,
+        "process_timers",
+        include_str!("../shaders/compute/process_timers.wgsl"),
+    )
+    .map_err(|e| format!("Failed to create process timer shader: {}", e))?;
+
+    let storage_entry = |binding: u32, read_only: bool| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    };
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Process Timer Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            storage_entry(1, false),
+            storage_entry(2, false),
+            storage_entry(3, false),
+            storage_entry(4, false),
+            storage_entry(5, true),
+            storage_entry(6, true),
+            storage_entry(7, true),
+            storage_entry(8, true),
+        ],
+    });
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Process Timer Pipeline Layout"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("Process Timer Pipeline"),
+        layout: Some(&pipeline_layout),
+        module: &shader.module,
+        entry_point: "advance_processes",
+    });
+
+    let gpu_owner = register_global_gpu_owner("process_timer");
+    let (params, params_allocation) = create_tracked_buffer(
+        &device,
+        &gpu_owner,
+        &wgpu::BufferDescriptor {
+            label: Some("Process Timer Params"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
+    let capacity = INITIAL_PROCESS_GPU_CAPACITY;
+    let mut column_allocations = Vec::new();
+    let mut column = |label: &str, readback: bool| {
+        let (buffer, allocation) = column_buffer(&device, &gpu_owner, label, capacity, readback);
+        column_allocations.push(allocation);
+        buffer
+    };
+    let elapsed = column("Process Elapsed", true);
+    let remainder = column("Process Remainder", true);
+    let status = column("Process Status", true);
+    let stage = column("Process Stage", true);
+    let duration = column("Process Duration", false);
+    let delta = column("Process Delta", false);
+    let (stage_ranges, stage_ranges_allocation) =
+        stage_ranges_buffer(&device, &gpu_owner, capacity);
+    column_allocations.push(stage_ranges_allocation);
+    let (readback, readback_allocation) = readback_buffer(&device, &gpu_owner, capacity);
+    column_allocations.push(readback_allocation);
+    let (stage_ends, stage_ends_allocation) =
+        column_buffer(&device, &gpu_owner, "Process Stage Ends", capacity, false);
+    let bind_group = create_process_bind_group(
+        &device,
+        &bind_group_layout,
+        [
+            &params,
+            &elapsed,
+            &remainder,
+            &status,
+            &stage,
+            &duration,
+            &delta,
+            &stage_ranges,
+            &stage_ends,
+        ],
+    );
+
+    data.gpu = Some(ProcessGpuData {
+        device,
+        queue,
+        pipeline,
+        bind_group_layout,
+        capacity,
+        params,
+        elapsed,
+        remainder,
+        status,
+        stage,
+        duration,
+        delta,
+        stage_ranges,
+        stage_ends,
+        stage_capacity: capacity,
+        readback,
+        bind_group,
+        in_flight: None,
+        readback_state: Arc::new(AtomicU8::new(READBACK_IDLE)),
+        params_allocation,
+        column_allocations,
+        stage_ends_allocation,
+        gpu_owner,
+    });
+    log::info!("[Process] GPU batch updates enabled");
+    Ok(())
+}
+
+/// Advance one process by `delta` ticks; returns its current stage.
+/// Only active processes move. `stage_ends` are the cumulative end ticks
+/// of its stages.
+pub fn advance_process_timer(
+    elapsed: &mut u64,
+    remainder: &mut f32,
+    status: &mut ProcessStatus,
+    duration: u64,
+    delta: f32,
+    stage_ends: &[u32],
+) -> u32 {
+    if *status != ProcessStatus::Active {
+        return current_stage(*elapsed, stage_ends);
+    }
+    let total = *remainder + delta.max(0.0);
+    let whole = total.floor();
+    *remainder = total - whole;
+    *elapsed += whole as u64;
+    if *elapsed >= duration {
+        *status = ProcessStatus::Completed;
+    }
+    current_stage(*elapsed, stage_ends)
+}
+
+/// Stage `elapsed` ticks fall in; past the end is the last stage
+fn current_stage(elapsed: u64, stage_ends: &[u32]) -> u32 {
+    let passed = stage_ends
+        .iter()
+        .filter(|&&end| elapsed >= end as u64)
+        .count() as u32;
+    passed.min(stage_ends.len().saturating_sub(1) as u32)
+}
+
+/// Stage end ticks of process `index` in a batch
+fn process_stage_ends(batch: &ProcessBatch, index: usize) -> &[u32] {
+    let Some(range) = batch.stage_ranges.get(index) else {
+        return &[];
+    };
+    let first = range.first as usize;
+    batch
+        .stage_ends
+        .get(first..first + range.count as usize)
+        .unwrap_or(&[])
+}
+
+/// Advance every process by its ticks in `deltas` (indexed like
+/// `ProcessData`) across the rayon pool; returns the processes that
+/// completed
+pub fn advance_processes_parallel(
+    processes: &mut ProcessData,
+    data: &mut ParallelProcessorData,
+    deltas: &[f32],
+    batch: &ProcessBatch,
+) -> Vec<usize> {
+    let ProcessData {
+        elapsed,
+        status,
+        duration,
+        active,
+        ..
+    } = processes;
+    elapsed
+        .par_iter_mut()
+        .zip(status.par_iter_mut())
+        .zip(data.remainders.par_iter_mut())
+        .zip(data.stages.par_iter_mut())
+        .enumerate()
+        .filter_map(|(index, (((elapsed, status), remainder), stage))| {
+            let delta = deltas.get(index).copied().unwrap_or(0.0);
+            if !active[index] || *status != ProcessStatus::Active || delta <= 0.0 {
+                return None;
+            }
+            *stage = advance_process_timer(
+                elapsed,
+                remainder,
+                status,
+                duration[index],
+                delta,
+                process_stage_ends(batch, index),
+            );
+            (*status == ProcessStatus::Completed).then_some(index)
+        })
+        .collect()
+}
+
+/// Advance a batch of processes, on the GPU when one is attached and the
+/// batch is large enough. Returns the processes whose completion was
+/// applied by this call; GPU results arrive on a later call.
 pub fn submit_process_batch_to_gpu(
-    _parallel_data: &mut ParallelProcessorData,
-    _thread_pool: &GpuThreadPoolData,
-    _processes: &mut ProcessData,
-    _state_machines: &mut [StateMachine],
-    _batch: ProcessBatch,
+    data: &mut ParallelProcessorData,
+    processes: &mut ProcessData,
+    batch: ProcessBatch,
+) -> Vec<usize> {
+    let _span = crate::trace_span!(Gpu, "submit_process_batch_to_gpu");
+    let started = Instant::now();
+    let count = processes.len();
+    data.remainders.resize(count, 0.0);
+    data.queued.resize(count, 0.0);
+    data.stages.resize(count, 0);
+    for (&index, &delta) in batch.indices.iter().zip(&batch.delta_ticks) {
+        if let Some(queued) = data.queued.get_mut(index) {
+            *queued += delta.max(0.0);
+        }
+    }
+
+    // Results of a dispatch from before the batch shrank still count
+    let mut completed = collect_gpu_results(data, processes);
+    if data.gpu.is_some() && count >= data.min_gpu_batch {
+        let idle = data.gpu.as_ref().is_some_and(|gpu| gpu.in_flight.is_none());
+        if idle && data.queued.iter().any(|&ticks| ticks > 0.0) {
+            dispatch_gpu_batch(data, processes, &batch);
+            data.stats.last_batch_us = started.elapsed().as_secs_f32() * 1_000_000.0;
+        }
+    } else {
+        let deltas = std::mem::take(&mut data.queued);
+        completed.extend(advance_processes_parallel(processes, data, &deltas, &batch));
+        data.queued = deltas;
+        data.queued.iter_mut().for_each(|ticks| *ticks = 0.0);
+        data.stats.cpu_batches += 1;
+        data.stats.last_backend = ProcessBackend::ParallelCpu;
+        data.stats.last_batch_us = started.elapsed().as_secs_f32() * 1_000_000.0;
+    }
+    data.stats.completed += completed.len() as u64;
+    completed
+}
+
+/// Upload the queued ticks and process columns and start the shader
+fn dispatch_gpu_batch(
+    data: &mut ParallelProcessorData,
+    processes: &ProcessData,
+    batch: &ProcessBatch,
 ) {
-    // Stub implementation
+    let Some(gpu) = data.gpu.as_mut() else {
+        return;
+    };
+    let count = processes.len() as u32;
+    ensure_gpu_capacity(gpu, count, batch.stage_ends.len() as u32);
+
+    let clamp = |value: u64| value.min(u32::MAX as u64) as u32;
+    let elapsed: Vec<u32> = processes.elapsed.iter().map(|&e| clamp(e)).collect();
+    let duration: Vec<u32> = processes.duration.iter().map(|&d| clamp(d)).collect();
+    let status: Vec<u32> = processes
+        .status
+        .iter()
+        .zip(&processes.active)
+        .map(|(&status, &active)| {
+            if active {
+                status as u32
+            } else {
+                STATUS_SKIPPED
+            }
+        })
+        .collect();
+    let mut stage_ranges = batch.stage_ranges.clone();
+    stage_ranges.resize(count as usize, StageRange::default());
+    let delta_ticks = std::mem::replace(&mut data.queued, vec![0.0; count as usize]);
+
+    let queue = &gpu.queue;
+    queue.write_buffer(&gpu.params, 0, bytemuck::cast_slice(&[count, 0, 0, 0]));
+    queue.write_buffer(&gpu.elapsed, 0, bytemuck::cast_slice(&elapsed));
+    queue.write_buffer(&gpu.remainder, 0, bytemuck::cast_slice(&data.remainders));
+    queue.write_buffer(&gpu.status, 0, bytemuck::cast_slice(&status));
+    queue.write_buffer(&gpu.stage, 0, bytemuck::cast_slice(&data.stages));
+    queue.write_buffer(&gpu.duration, 0, bytemuck::cast_slice(&duration));
+    queue.write_buffer(&gpu.delta, 0, bytemuck::cast_slice(&delta_ticks));
+    queue.write_buffer(&gpu.stage_ranges, 0, bytemuck::cast_slice(&stage_ranges));
+    if !batch.stage_ends.is_empty() {
+        queue.write_buffer(&gpu.stage_ends, 0, bytemuck::cast_slice(&batch.stage_ends));
+    }
+
+    let mut encoder = gpu
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Process Timer Encoder"),
+        });
+    {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Process Timer Pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&gpu.pipeline);
+        pass.set_bind_group(0, &gpu.bind_group, &[]);
+        pass.dispatch_workgroups(count.div_ceil(PROCESS_WORKGROUP_SIZE), 1, 1);
+    }
+    let column_bytes = count as u64 * 4;
+    for (slot, column) in [&gpu.elapsed, &gpu.remainder, &gpu.status, &gpu.stage]
+        .into_iter()
+        .enumerate()
+    {
+        let offset = slot as u64 * gpu.capacity as u64 * 4;
+        encoder.copy_buffer_to_buffer(column, 0, &gpu.readback, offset, column_bytes);
+    }
+    queue.submit(Some(encoder.finish()));
+
+    gpu.readback_state
+        .store(READBACK_WAITING, Ordering::Release);
+    let state = Arc::clone(&gpu.readback_state);
+    gpu.readback
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, move |result| {
+            let value = if result.is_ok() {
+                READBACK_MAPPED
+            } else {
+                READBACK_FAILED
+            };
+            state.store(value, Ordering::Release);
+        });
+    gpu.in_flight = Some(ProcessGpuDispatch { count, delta_ticks });
+    data.stats.gpu_dispatches += 1;
+    data.stats.last_backend = ProcessBackend::Gpu;
+}
+
+/// Apply the in-flight dispatch once its readback has mapped (without
+/// blocking). Processes paused, cancelled or finished on the CPU since the
+/// dispatch keep their CPU state.
+fn collect_gpu_results(
+    data: &mut ParallelProcessorData,
+    processes: &mut ProcessData,
+) -> Vec<usize> {
+    let mut completed = Vec::new();
+    let Some(gpu) = data.gpu.as_mut() else {
+        return completed;
+    };
+    let Some(dispatch) = gpu.in_flight.as_ref() else {
+        return completed;
+    };
+    gpu.device.poll(wgpu::Maintain::Poll);
+    match gpu.readback_state.load(Ordering::Acquire) {
+        READBACK_WAITING => return completed,
+        READBACK_MAPPED => {
+            {
+                let mapped = gpu.readback.slice(..).get_mapped_range();
+                let words: &[u32] = bytemuck::cast_slice(&mapped);
+                let capacity = gpu.capacity as usize;
+                let column = |slot: usize| {
+                    &words[slot * capacity..slot * capacity + dispatch.count as usize]
+                };
+                let (elapsed, status, stage) = (column(0), column(2), column(3));
+                let remainder: &[f32] = bytemuck::cast_slice(column(1));
+                for index in 0..dispatch.count as usize {
+                    let still_active = processes.active.get(index).copied().unwrap_or(false)
+                        && processes.status[index] == ProcessStatus::Active;
+                    if !still_active {
+                        continue;
+                    }
+                    processes.elapsed[index] = elapsed[index] as u64;
+                    data.remainders[index] = remainder[index];
+                    data.stages[index] = stage[index];
+                    if status[index] == ProcessStatus::Completed as u32 {
+                        processes.status[index] = ProcessStatus::Completed;
+                        completed.push(index);
+                    }
+                }
+            }
+            gpu.readback.unmap();
+            data.stats.gpu_readbacks += 1;
+        }
+        _ => {
+            log::warn!("[Process] GPU process readback failed; ticks queued again");
+            gpu.readback.unmap();
+            for (queued, ticks) in data.queued.iter_mut().zip(&dispatch.delta_ticks) {
+                *queued += ticks;
+            }
+            data.stats.gpu_failures += 1;
+        }
+    }
+    gpu.in_flight = None;
+    gpu.readback_state.store(READBACK_IDLE, Ordering::Release);
+    completed
+}
+
+/// Grow the GPU columns to hold `count` processes and `stage_count` stage
+/// ends, rebuilding the bind group
+fn ensure_gpu_capacity(gpu: &mut ProcessGpuData, count: u32, stage_count: u32) {
+    if count <= gpu.capacity && stage_count <= gpu.stage_capacity {
+        return;
+    }
+    let device = &gpu.device;
+    if count > gpu.capacity {
+        let capacity = count.next_power_of_two();
+        gpu.capacity = capacity;
+        let owner = &gpu.gpu_owner;
+        let mut column_allocations = Vec::new();
+        let mut column = |label: &str, readback: bool| {
+            let (buffer, allocation) = column_buffer(device, owner, label, capacity, readback);
+            column_allocations.push(allocation);
+            buffer
+        };
+        gpu.elapsed = column("Process Elapsed", true);
+        gpu.remainder = column("Process Remainder", true);
+        gpu.status = column("Process Status", true);
+        gpu.stage = column("Process Stage", true);
+        gpu.duration = column("Process Duration", false);
+        gpu.delta = column("Process Delta", false);
+        let (stage_ranges, stage_ranges_allocation) = stage_ranges_buffer(device, owner, capacity);
+        gpu.stage_ranges = stage_ranges;
+        column_allocations.push(stage_ranges_allocation);
+        let (readback, readback_allocation) = readback_buffer(device, owner, capacity);
+        gpu.readback = readback;
+        column_allocations.push(readback_allocation);
+        gpu.column_allocations = column_allocations;
+    }
+    if stage_count > gpu.stage_capacity {
+        gpu.stage_capacity = stage_count.next_power_of_two();
+        let (stage_ends, stage_ends_allocation) = column_buffer(
+            device,
+            &gpu.gpu_owner,
+            "Process Stage Ends",
+            gpu.stage_capacity,
+            false,
+        );
+        gpu.stage_ends = stage_ends;
+        gpu.stage_ends_allocation = stage_ends_allocation;
+    }
+    gpu.bind_group = create_process_bind_group(
+        device,
+        &gpu.bind_group_layout,
+        [
+            &gpu.params,
+            &gpu.elapsed,
+            &gpu.remainder,
+            &gpu.status,
+            &gpu.stage,
+            &gpu.duration,
+            &gpu.delta,
+            &gpu.stage_ranges,
+            &gpu.stage_ends,
+        ],
+    );
+}
+
+/// Bind group over the params and columns, in binding order
+fn create_process_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    buffers: [&wgpu::Buffer; 9],
+) -> wgpu::BindGroup {
+    let entries: Vec<wgpu::BindGroupEntry> = buffers
+        .iter()
+        .enumerate()
+        .map(|(binding, buffer)| wgpu::BindGroupEntry {
+            binding: binding as u32,
+            resource: buffer.as_entire_binding(),
+        })
+        .collect();
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Process Timer Bind Group"),
+        layout,
+        entries: &entries,
+    })
+}
+
+/// One 4-byte-per-process storage column
+fn column_buffer(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    label: &str,
+    capacity: u32,
+    readback: bool,
+) -> (wgpu::Buffer, GpuAllocationGuard) {
+    let mut usage = wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST;
+    if readback {
+        usage |= wgpu::BufferUsages::COPY_SRC;
+    }
+    create_tracked_buffer(
+        device,
+        owner,
+        &wgpu::BufferDescriptor {
+            label: Some(label),
+            size: capacity as u64 * 4,
+            usage,
+            mapped_at_creation: false,
+        },
+    )
+}
+
+/// Stage range of each process
+fn stage_ranges_buffer(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    capacity: u32,
+) -> (wgpu::Buffer, GpuAllocationGuard) {
+    create_tracked_buffer(
+        device,
+        owner,
+        &wgpu::BufferDescriptor {
+            label: Some("Process Stage Ranges"),
+            size: capacity as u64 * std::mem::size_of::<StageRange>() as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    )
+}
+
+/// Readback holding the read-write columns back to back
+fn readback_buffer(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    capacity: u32,
+) -> (wgpu::Buffer, GpuAllocationGuard) {
+    create_tracked_buffer(
+        device,
+        owner,
+        &wgpu::BufferDescriptor {
+            label: Some("Process Timer Readback"),
+            size: READBACK_COLUMNS * capacity as u64 * 4,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::InstanceId;
+    use crate::process::{ProcessId, ProcessType};
+
+    fn batch_for(processes: &ProcessData, ticks: f32) -> ProcessBatch {
+        ProcessBatch {
+            indices: (0..processes.len()).collect(),
+            delta_ticks: vec![ticks; processes.len()],
+            stage_ends: vec![4, 10],
+            stage_ranges: vec![StageRange { first: 0, count: 2 }],
+        }
+    }
+
+    #[test]
+    fn cpu_batches_carry_fractional_ticks_and_stages() {
+        let mut data = create_parallel_processor_data().unwrap_or_default();
+        let mut processes = ProcessData::new();
+        let owner = InstanceId::new();
+        let staged = processes.add(ProcessId::new(), ProcessType::default(), owner, 10);
+        let paused = processes.add(ProcessId::new(), ProcessType::default(), owner, 10);
+        processes.status[staged] = ProcessStatus::Active;
+        processes.status[paused] = ProcessStatus::Paused;
+
+        for _ in 0..3 {
+            let batch = batch_for(&processes, 1.5);
+            assert!(submit_process_batch_to_gpu(&mut data, &mut processes, batch).is_empty());
+        }
+        assert_eq!(processes.elapsed[staged], 4);
+        assert!((data.remainders[staged] - 0.5).abs() < 1e-6);
+        assert_eq!(data.stages[staged], 1);
+        assert_eq!(processes.elapsed[paused], 0);
+
+        let batch = batch_for(&processes, 6.0);
+        let completed = submit_process_batch_to_gpu(&mut data, &mut processes, batch);
+        assert_eq!(completed, vec![staged]);
+        assert_eq!(processes.status[staged], ProcessStatus::Completed);
+        assert_eq!(data.stages[staged], 1);
+        assert_eq!(data.stats.last_backend, ProcessBackend::ParallelCpu);
+        assert_eq!(data.stats.completed, 1);
+    }
+
+    #[test]
+    fn timer_matches_process_data_update() {
+        let mut status = ProcessStatus::Active;
+        let (mut elapsed, mut remainder) = (0u64, 0.0f32);
+        assert_eq!(
+            advance_process_timer(&mut elapsed, &mut remainder, &mut status, 3, 2.0, &[]),
+            0
+        );
+        assert_eq!(status, ProcessStatus::Active);
+        advance_process_timer(&mut elapsed, &mut remainder, &mut status, 3, 1.0, &[]);
+        assert_eq!((elapsed, status), (3, ProcessStatus::Completed));
+        // Completed processes no longer move
+        advance_process_timer(&mut elapsed, &mut remainder, &mut status, 3, 5.0, &[]);
+        assert_eq!(elapsed, 3);
+    }
 }
@@ -3,10 +3,13 @@ use crate::instance::InstanceId;
 ///
 /// Handles process interruption, cancellation, and control flow.
 /// Manages dependencies between processes.
-use crate::process::{ProcessData, ProcessId, ProcessStatus};
+use crate::process::{ActualOutput, ProcessData, ProcessId, ProcessStatus};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
+/// Result of a control operation; errors are human-readable
+pub type ControlResult<T> = Result<T, String>;
+
 /// Reason for process interruption
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum InterruptReason {
@@ -31,6 +34,12 @@ pub enum InterruptReason {
     /// Server shutdown/maintenance
     ServerShutdown,
 
+    /// Block the process is anchored to was broken or replaced
+    AnchorBroken,
+
+    /// Chunk holding the anchor block was unloaded
+    AnchorUnloaded,
+
     /// Custom reason
     Custom(String),
 }
@@ -40,6 +49,9 @@ pub struct ProcessControl {
     /// Active interrupts per process
     interrupts: HashMap<ProcessId, Vec<InterruptReason>>,
 
+    /// Ticks each process has spent interrupted
+    interrupted_ticks: HashMap<ProcessId, u64>,
+
     /// Process dependencies
     dependencies: HashMap<ProcessId, HashSet<ProcessId>>,
 
@@ -53,6 +65,17 @@ pub struct ProcessControl {
     pub policies: ControlPolicies,
 }
 
+/// What a cancelled process leaves behind
+#[derive(Debug, Clone, PartialEq)]
+pub struct CancelOutcome {
+    pub process: ProcessId,
+    pub owner: InstanceId,
+    /// Partial outputs of the stage that was running
+    pub outputs: Vec<ActualOutput>,
+    /// Unused inputs of the stage that was running
+    pub refunds: Vec<ActualOutput>,
+}
+
 /// Control policies configuration
 #[derive(Clone)]
 pub struct ControlPolicies {
@@ -88,6 +111,7 @@ impl ProcessControl {
     pub fn new() -> Self {
         Self {
             interrupts: HashMap::new(),
+            interrupted_ticks: HashMap::new(),
             dependencies: HashMap::new(),
             dependents: HashMap::new(),
             handlers: Vec::new(),
@@ -95,7 +119,8 @@ impl ProcessControl {
         }
     }
 
-    /// Interrupt a process
+    /// Interrupt a process. Progress is kept while it is paused; further
+    /// reasons stack on an already interrupted process.
     pub fn interrupt_process(
         &mut self,
         process_id: ProcessId,
@@ -106,7 +131,10 @@ impl ProcessControl {
             .find_index(process_id)
             .ok_or_else(|| "Process not found".to_string())?;
 
-        if data.status[index] != ProcessStatus::Active {
+        if !matches!(
+            data.status[index],
+            ProcessStatus::Active | ProcessStatus::Paused
+        ) {
             return Err("Process not active".to_string());
         }
 
@@ -156,32 +184,58 @@ impl ProcessControl {
         // Resume
         data.resume(index);
         self.interrupts.remove(&process_id);
+        self.interrupted_ticks.remove(&process_id);
 
         Ok(())
     }
 
-    /// Cancel a process
+    /// Clear one interrupt and resume if it was the last.
+    /// Returns whether the process is running again.
+    pub fn resume_after(
+        &mut self,
+        process_id: ProcessId,
+        reason: &InterruptReason,
+        data: &mut ProcessData,
+    ) -> ControlResult<bool> {
+        if !self.clear_interrupt(process_id, reason) {
+            return Err(format!("Process not interrupted by {:?}", reason));
+        }
+        if !self.get_interrupts(process_id).is_empty() {
+            return Ok(false);
+        }
+        self.resume_process(process_id, data)?;
+        Ok(true)
+    }
+
+    /// Cancel a process. Returns every process cancelled, including
+    /// dependents reached by the cascade; finished processes are skipped.
     pub fn cancel_process(
         &mut self,
         process_id: ProcessId,
         data: &mut ProcessData,
-    ) -> Result<(), String> {
+    ) -> ControlResult<Vec<ProcessId>> {
         let index = data
             .find_index(process_id)
             .ok_or_else(|| "Process not found".to_string())?;
 
+        if !data.active[index] {
+            return Ok(Vec::new());
+        }
+
         // Cancel the process
         data.cancel(index);
+        let mut cancelled = vec![process_id];
 
         // Remove interrupts
         self.interrupts.remove(&process_id);
+        self.interrupted_ticks.remove(&process_id);
 
         // Handle dependents
         if self.policies.cascade_cancel {
-            self.cascade_cancel(process_id, data);
+            cancelled.extend(self.cascade_cancel(process_id, data));
         }
 
-        Ok(())
+        Ok(cancelled)
     }
 
     /// Add process dependency
@@ -217,13 +271,75 @@ impl ProcessControl {
 
     /// Clear specific interrupt
     pub fn clear_interrupt(&mut self, process_id: ProcessId, reason: &InterruptReason) -> bool {
-        if let Some(interrupts) = self.interrupts.get_mut(&process_id) {
+        let cleared = if let Some(interrupts) = self.interrupts.get_mut(&process_id) {
             let len_before = interrupts.len();
             interrupts.retain(|r| r != reason);
             len_before != interrupts.len()
         } else {
             false
+        };
+
+        if cleared {
+            for handler in &self.handlers {
+                handler.on_interrupt_cleared(process_id, reason);
+            }
         }
+        cleared
+    }
+
+    /// Active interrupts of a process
+    pub fn get_interrupts(&self, process_id: ProcessId) -> &[InterruptReason] {
+        self.interrupts
+            .get(&process_id)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Ticks a process has spent interrupted
+    pub fn get_interrupted_ticks(&self, process_id: ProcessId) -> u64 {
+        self.interrupted_ticks
+            .get(&process_id)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Put back interrupts of a reloaded process without notifying handlers
+    pub fn restore_interrupts(
+        &mut self,
+        process_id: ProcessId,
+        reasons: Vec<InterruptReason>,
+        interrupted_ticks: u64,
+    ) {
+        if reasons.is_empty() {
+            return;
+        }
+        self.interrupts.insert(process_id, reasons);
+        self.interrupted_ticks.insert(process_id, interrupted_ticks);
+    }
+
+    /// Count time spent interrupted. Returns processes that have been
+    /// interrupted longer than `max_interrupt_time` and should be cancelled.
+    pub fn tick_interrupts(&mut self, delta_ticks: u64, data: &ProcessData) -> Vec<ProcessId> {
+        let mut expired = Vec::new();
+        for (&process_id, interrupts) in &self.interrupts {
+            // Time stands still in unloaded chunks
+            if interrupts.is_empty() || interrupts.contains(&InterruptReason::AnchorUnloaded) {
+                continue;
+            }
+            let paused = data
+                .find_index(process_id)
+                .is_some_and(|index| data.status[index] == ProcessStatus::Paused);
+            if !paused {
+                continue;
+            }
+
+            let ticks = self.interrupted_ticks.entry(process_id).or_insert(0);
+            *ticks += delta_ticks;
+            if *ticks > self.policies.max_interrupt_time {
+                expired.push(process_id);
+            }
+        }
+        expired
     }
 
     /// Auto-resume check
@@ -259,12 +375,16 @@ impl ProcessControl {
     }
 
     /// Cascade cancellation to dependents
-    fn cascade_cancel(&mut self, cancelled: ProcessId, data: &mut ProcessData) {
+    fn cascade_cancel(&mut self, cancelled: ProcessId, data: &mut ProcessData) -> Vec<ProcessId> {
+        let mut cascaded = Vec::new();
         if let Some(dependents) = self.dependents.get(&cancelled).cloned() {
             for dependent in dependents {
-                let _ = self.cancel_process(dependent, data);
+                if let Ok(ids) = self.cancel_process(dependent, data) {
+                    cascaded.extend(ids);
+                }
             }
         }
+        cascaded
     }
 
     /// Get player's active process count
@@ -3,8 +3,14 @@
 /// Structure of Arrays storage for all process data.
 /// No process objects - just tables of process properties.
 use crate::instance::InstanceId;
-use crate::process::{ProcessCategory, ProcessPriority, QualityLevel};
+use crate::process::{
+    InterruptReason, PendingExchange, ProcessAnchor, ProcessCategory, ProcessPriority, QualityLevel, TransformStage,
+};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Next process id to hand out
+static NEXT_PROCESS_ID: AtomicU64 = AtomicU64::new(1);
 
 /// Unique process identifier
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -12,8 +18,12 @@ pub struct ProcessId(pub u64);
 
 impl ProcessId {
     pub fn new() -> Self {
-        static COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
-        Self(COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+        Self(NEXT_PROCESS_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Keep new ids clear of an id restored from a save
+    pub fn reserve(id: ProcessId) {
+        NEXT_PROCESS_ID.fetch_max(id.0 + 1, Ordering::Relaxed);
     }
 }
 
@@ -233,6 +243,27 @@ impl ProcessData {
     }
 }
 
+/// Unfinished process as written to a save, including why it was
+/// interrupted, so a half-finished smelt resumes where it stopped
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedProcess {
+    pub id: ProcessId,
+    pub process_type: ProcessType,
+    pub owner: InstanceId,
+    pub status: ProcessStatus,
+    pub priority: ProcessPriority,
+    pub quality: QualityLevel,
+    pub duration: u64,
+    pub elapsed: u64,
+    pub stages: Vec<TransformStage>,
+    pub interrupts: Vec<InterruptReason>,
+    pub interrupted_ticks: u64,
+    /// Block the process was bound to
+    pub anchor: Option<ProcessAnchor>,
+    /// Trade the process runs, for exchange processes
+    pub exchange: Option<PendingExchange>,
+}
+
 /// Input/output storage for processes
 pub struct ProcessIO {
     /// All input instances
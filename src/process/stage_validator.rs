@@ -1,4 +1,5 @@
 //! Stage Validator - Stub
+use super::transform_stage_operations::calculate_outputs;
 use super::{ActualOutput, TransformStage};
 use rand::Rng;
 
@@ -6,11 +7,10 @@ pub struct StageValidator;
 
 impl StageValidator {
     pub fn calculate_outputs<R: Rng>(
-        _stage: &TransformStage,
-        _quality: f32,
+        stage: &TransformStage,
+        quality: f32,
         _rng: &mut R,
     ) -> Vec<ActualOutput> {
-        // Stub implementation - return empty vec
-        Vec::new()
+        calculate_outputs(stage, 1.0, quality)
     }
 }
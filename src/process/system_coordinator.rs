@@ -9,8 +9,9 @@
 /// 3. Monitoring system health and performance
 /// 4. Providing loose coupling through events
 /// 5. Handling cross-system synchronization
+/// 6. Running independent systems concurrently in dependency waves
 use crate::error::{EngineError, EngineResult};
-use crate::thread_pool::{GpuWorkloadCategory, GpuThreadPoolData, ThreadPoolManager, submit_gpu_command_task};
+use crate::thread_pool::{GpuWorkloadCategory, ScopedJob, ThreadPoolManager};
 use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -90,6 +91,19 @@ pub struct SystemDependencies {
     pub max_wait_time_ms: u64,
 }
 
+/// Work performed by a system each frame
+pub type SystemRunner = Arc<dyn Fn() -> EngineResult<()> + Send + Sync>;
+
+/// Systems that may run concurrently, in topological order
+pub type ExecutionWave = Vec<SystemId>;
+
+/// Outcome of one system run within a wave
+struct SystemRunOutcome {
+    system_id: SystemId,
+    duration: Duration,
+    result: EngineResult<()>,
+}
+
 /// System execution context
 pub struct SystemExecutionContext {
     pub frame_budget_ms: f64,
@@ -111,6 +125,16 @@ pub struct SystemCoordinator {
     /// System execution order (topologically sorted)
     execution_order: Vec<SystemId>,
 
+    /// Execution waves; systems within a wave have no dependencies or
+    /// conflicts between them and may run concurrently
+    execution_waves: Vec<ExecutionWave>,
+
+    /// Whether waves are dispatched on the thread pool
+    parallel_execution: bool,
+
+    /// Per-system frame work
+    system_runners: HashMap<SystemId, SystemRunner>,
+
     /// System execution times for scheduling
     execution_times: RwLock<HashMap<SystemId, VecDeque<Duration>>>,
 
@@ -202,6 +226,9 @@ impl SystemCoordinator {
             health_monitor: Arc::new(RwLock::new(HashMap::new())),
             dependencies: HashMap::new(),
             execution_order: Vec::new(),
+            execution_waves: Vec::new(),
+            parallel_execution: true,
+            system_runners: HashMap::new(),
             execution_times: RwLock::new(HashMap::new()),
             frame_budget: FrameBudgetManager::new(target_frame_time_ms),
             sync_barriers: Mutex::new(HashMap::new()),
@@ -270,9 +297,80 @@ impl SystemCoordinator {
         }
 
         self.execution_order = order;
+        self.execution_waves = self.build_execution_waves();
         Ok(())
     }
 
+    /// Group the topological order into waves
+    ///
+    /// A system's wave is one past the latest wave of its dependencies.
+    /// Systems that declare a conflict (in either direction) are pushed into
+    /// a later wave so they never overlap.
+    fn build_execution_waves(&self) -> Vec<ExecutionWave> {
+        let mut waves: Vec<ExecutionWave> = Vec::new();
+        let mut wave_of: HashMap<SystemId, usize> = HashMap::new();
+
+        for &system_id in &self.execution_order {
+            let mut wave = self
+                .dependencies
+                .get(&system_id)
+                .map(|deps| {
+                    deps.depends_on
+                        .iter()
+                        .filter_map(|dep| wave_of.get(dep))
+                        .map(|&w| w + 1)
+                        .max()
+                        .unwrap_or(0)
+                })
+                .unwrap_or(0);
+
+            while wave < waves.len()
+                && waves[wave]
+                    .iter()
+                    .any(|&other| self.systems_conflict(system_id, other))
+            {
+                wave += 1;
+            }
+
+            if wave == waves.len() {
+                waves.push(Vec::new());
+            }
+            waves[wave].push(system_id);
+            wave_of.insert(system_id, wave);
+        }
+
+        waves
+    }
+
+    /// Whether either system declares a conflict with the other
+    fn systems_conflict(&self, a: SystemId, b: SystemId) -> bool {
+        let declares = |from: SystemId, to: SystemId| {
+            self.dependencies
+                .get(&from)
+                .map(|deps| deps.conflicts_with.contains(&to))
+                .unwrap_or(false)
+        };
+        declares(a, b) || declares(b, a)
+    }
+
+    /// Set the work a system performs each frame
+    pub fn set_system_runner<F>(&mut self, system_id: SystemId, runner: F)
+    where
+        F: Fn() -> EngineResult<()> + Send + Sync + 'static,
+    {
+        self.system_runners.insert(system_id, Arc::new(runner));
+    }
+
+    /// Enable or disable concurrent wave execution
+    pub fn set_parallel_execution(&mut self, enabled: bool) {
+        self.parallel_execution = enabled;
+    }
+
+    /// Current execution waves
+    pub fn execution_waves(&self) -> &[ExecutionWave] {
+        &self.execution_waves
+    }
+
     fn visit_system(
         &self,
         system_id: SystemId,
@@ -320,7 +418,27 @@ impl SystemCoordinator {
             ctx.systems_in_progress.clear();
         }
 
-        // Execute systems in order
+        if self.parallel_execution {
+            for wave in &self.execution_waves {
+                self.execute_wave(wave, frame_start, &mut report);
+            }
+        } else {
+            self.execute_sequential(frame_start, &mut report);
+        }
+
+        // Update metrics
+        let total_frame_time = frame_start.elapsed();
+        self.update_metrics(&report, total_frame_time);
+
+        // Process events
+        self.event_bus.process_events();
+
+        report.total_frame_time = total_frame_time;
+        Ok(report)
+    }
+
+    /// Execute systems one at a time in topological order
+    fn execute_sequential(&self, frame_start: Instant, report: &mut FrameExecutionReport) {
         for &system_id in &self.execution_order {
             let system_start = Instant::now();
 
@@ -346,45 +464,172 @@ impl SystemCoordinator {
             }
 
             // Execute system
-            match self.execute_system(system_id) {
-                Ok(()) => {
-                    let execution_time = system_start.elapsed();
-                    report.executed_systems.push((system_id, execution_time));
+            let result = self.execute_system(system_id);
+            self.record_system_outcome(
+                SystemRunOutcome {
+                    system_id,
+                    duration: system_start.elapsed(),
+                    result,
+                },
+                report,
+            );
+        }
+    }
+
+    /// Execute one wave, running its systems concurrently on the thread pool
+    ///
+    /// Dependencies are guaranteed by wave order, so a system whose dependency
+    /// was skipped or failed this frame fails immediately instead of waiting.
+    /// The budget check uses wall-clock frame time, since systems in the same
+    /// wave overlap rather than add up.
+    fn execute_wave(
+        &self,
+        wave: &[SystemId],
+        frame_start: Instant,
+        report: &mut FrameExecutionReport,
+    ) {
+        let elapsed = frame_start.elapsed().as_secs_f64() * 1000.0;
+        let mut runnable = Vec::with_capacity(wave.len());
+
+        for &system_id in wave {
+            if !self.is_system_healthy(system_id) {
+                report.skipped_systems.push(system_id);
+                continue;
+            }
+
+            if let Some(dep) = self.incomplete_dependency(system_id) {
+                report.failed_systems.push((
+                    system_id,
+                    format!("Dependency {:?} did not complete this frame", dep),
+                ));
+                continue;
+            }
+
+            let budget = self.frame_budget.get_system_budget(system_id);
+            if elapsed + budget > self.frame_budget.target_frame_time_ms {
+                report.budget_exceeded_systems.push(system_id);
+                continue;
+            }
+
+            runnable.push(system_id);
+        }
+
+        if runnable.is_empty() {
+            return;
+        }
+
+        let wave_start = Instant::now();
+        let run = |system_id: SystemId| {
+            let start = Instant::now();
+            let result = self.execute_system(system_id);
+            SystemRunOutcome {
+                system_id,
+                duration: start.elapsed(),
+                result,
+            }
+        };
 
-                    // Update execution times history
+        // Waves go through the engine pool so its lanes and caps apply;
+        // run_scoped returning is the completion barrier
+        let outcomes: Vec<SystemRunOutcome> = if runnable.len() == 1 {
+            vec![run(runnable[0])]
+        } else {
+            let run = &run;
+            let jobs: Vec<ScopedJob<'_, SystemRunOutcome>> = runnable
+                .iter()
+                .map(|&system_id| Box::new(move || run(system_id)) as ScopedJob<'_, _>)
+                .collect();
+            ThreadPoolManager::global()
+                .run_scoped(GpuWorkloadCategory::Compute, jobs)
+                .into_iter()
+                .zip(&runnable)
+                .map(|(outcome, &system_id)| {
+                    outcome.unwrap_or_else(|| SystemRunOutcome {
+                        system_id,
+                        duration: Duration::ZERO,
+                        result: Err(EngineError::SystemError {
+                            component: format!("{:?}", system_id),
+                            error: "System panicked".to_string(),
+                        }),
+                    })
+                })
+                .collect()
+        };
+
+        let wave_time = wave_start.elapsed();
+        let busy_time: Duration = outcomes.iter().map(|outcome| outcome.duration).sum();
+        report.parallel_time_saved += busy_time.saturating_sub(wave_time);
+        report.waves_executed += 1;
+
+        for outcome in outcomes {
+            self.record_system_outcome(outcome, report);
+        }
+    }
+
+    /// First dependency of a system that has not completed this frame
+    fn incomplete_dependency(&self, system_id: SystemId) -> Option<SystemId> {
+        let deps = self.dependencies.get(&system_id)?;
+        let ctx = self.current_frame.read();
+        deps.depends_on
+            .iter()
+            .copied()
+            .find(|dep| !ctx.systems_completed.contains(dep))
+    }
+
+    /// Fold a system run into the report, timing history, budget and health
+    fn record_system_outcome(&self, outcome: SystemRunOutcome, report: &mut FrameExecutionReport) {
+        let SystemRunOutcome {
+            system_id,
+            duration,
+            result,
+        } = outcome;
+
+        match result {
+            Ok(()) => {
+                report.executed_systems.push((system_id, duration));
+                let time_ms = duration.as_secs_f64() * 1000.0;
+
+                // Update execution times history
+                {
                     let mut times = self.execution_times.write();
                     let history = times.entry(system_id).or_insert_with(VecDeque::new);
-                    history.push_back(execution_time);
+                    history.push_back(duration);
                     if history.len() > 60 {
                         // Keep last 60 frames
                         history.pop_front();
                     }
-
-                    // Mark as completed
-                    self.current_frame
-                        .write()
-                        .systems_completed
-                        .insert(system_id);
                 }
-                Err(e) => {
-                    log::error!("System {:?} execution failed: {}", system_id, e);
-                    report.failed_systems.push((system_id, e.to_string()));
 
-                    // Handle error recovery
-                    self.handle_system_error(system_id, e);
+                self.frame_budget
+                    .budget_usage
+                    .write()
+                    .insert(system_id, time_ms);
+
+                {
+                    let mut health = self.health_monitor.write();
+                    if let Some(h) = health.get_mut(&system_id) {
+                        h.average_frame_time_ms = if h.average_frame_time_ms == 0.0 {
+                            time_ms
+                        } else {
+                            h.average_frame_time_ms * 0.9 + time_ms * 0.1
+                        };
+                    }
                 }
-            }
-        }
-
-        // Update metrics
-        let total_frame_time = frame_start.elapsed();
-        self.update_metrics(&report, total_frame_time);
 
-        // Process events
-        self.event_bus.process_events();
+                // Mark as completed
+                self.current_frame
+                    .write()
+                    .systems_completed
+                    .insert(system_id);
+            }
+            Err(e) => {
+                log::error!("System {:?} execution failed: {}", system_id, e);
+                report.failed_systems.push((system_id, e.to_string()));
 
-        report.total_frame_time = total_frame_time;
-        Ok(report)
+                // Handle error recovery
+                self.handle_system_error(system_id, e);
+            }
+        }
     }
 
     /// Execute a single system
@@ -404,30 +649,19 @@ impl SystemCoordinator {
             }
         }
 
-        // Execute on appropriate thread pool
-        let pool_category = self.get_pool_category(system_id);
-
-        // Convert PoolCategory to GpuWorkloadCategory
-        let gpu_category = match pool_category {
-            PoolCategory::Physics => crate::thread_pool::GpuWorkloadCategory::Physics,
-            PoolCategory::MeshBuilding => crate::thread_pool::GpuWorkloadCategory::Rendering,
-            _ => crate::thread_pool::GpuWorkloadCategory::Compute,
+        // Systems without registered work complete immediately
+        let result = match self.system_runners.get(&system_id) {
+            Some(runner) => runner(),
+            None => Ok(()),
         };
 
-        // For now, this is a placeholder - actual system execution will be
-        // handled by the specific system implementations
-        ThreadPoolManager::global().execute(gpu_category, || {
-            // System-specific execution logic would go here
-            std::thread::sleep(Duration::from_millis(1)); // Placeholder
-        });
-
         // Remove from in progress
         self.current_frame
             .write()
             .systems_in_progress
             .remove(&system_id);
 
-        Ok(())
+        result
     }
 
     /// Wait for system dependencies to complete
@@ -538,7 +772,7 @@ impl SystemCoordinator {
     }
 
     /// Get thread pool category for system
-    fn get_pool_category(&self, system_id: SystemId) -> PoolCategory {
+    pub fn get_pool_category(&self, system_id: SystemId) -> PoolCategory {
         match system_id {
             SystemId::WorldGeneration => PoolCategory::WorldGeneration,
             SystemId::Physics => PoolCategory::Physics,
@@ -595,6 +829,10 @@ pub struct FrameExecutionReport {
     pub skipped_systems: Vec<SystemId>,
     pub budget_exceeded_systems: Vec<SystemId>,
     pub total_frame_time: Duration,
+    /// Number of waves that ran at least one system
+    pub waves_executed: u32,
+    /// Summed system time minus wall-clock wave time
+    pub parallel_time_saved: Duration,
 }
 
 impl FrameExecutionReport {
@@ -605,6 +843,8 @@ impl FrameExecutionReport {
             skipped_systems: Vec::new(),
             budget_exceeded_systems: Vec::new(),
             total_frame_time: Duration::ZERO,
+            waves_executed: 0,
+            parallel_time_saved: Duration::ZERO,
         }
     }
 }
@@ -761,4 +1001,92 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    fn no_deps() -> SystemDependencies {
+        SystemDependencies {
+            depends_on: vec![],
+            conflicts_with: vec![],
+            max_wait_time_ms: 1000,
+        }
+    }
+
+    #[test]
+    fn test_execution_waves_respect_dependencies_and_conflicts() {
+        let mut coordinator = SystemCoordinator::new(60.0);
+        coordinator
+            .register_system(SystemId::Audio, no_deps(), 5.0)
+            .expect("Failed to register Audio system");
+        coordinator
+            .register_system(SystemId::Input, no_deps(), 5.0)
+            .expect("Failed to register Input system");
+        coordinator
+            .register_system(
+                SystemId::Network,
+                SystemDependencies {
+                    conflicts_with: vec![SystemId::Input],
+                    ..no_deps()
+                },
+                5.0,
+            )
+            .expect("Failed to register Network system");
+        coordinator
+            .register_system(
+                SystemId::UI,
+                SystemDependencies {
+                    depends_on: vec![SystemId::Audio, SystemId::Input],
+                    ..no_deps()
+                },
+                5.0,
+            )
+            .expect("Failed to register UI system");
+
+        let wave_of = |id: SystemId| {
+            coordinator
+                .execution_waves()
+                .iter()
+                .position(|wave| wave.contains(&id))
+        };
+
+        assert_eq!(wave_of(SystemId::Audio), Some(0));
+        assert!(wave_of(SystemId::UI) > wave_of(SystemId::Input));
+        assert_ne!(wave_of(SystemId::Network), wave_of(SystemId::Input));
+    }
+
+    #[test]
+    fn test_parallel_frame_runs_dependents_after_dependencies() {
+        let mut coordinator = SystemCoordinator::new(60.0);
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        for id in [SystemId::Audio, SystemId::Input] {
+            coordinator
+                .register_system(id, no_deps(), 5.0)
+                .expect("Failed to register system");
+        }
+        coordinator
+            .register_system(
+                SystemId::UI,
+                SystemDependencies {
+                    depends_on: vec![SystemId::Audio, SystemId::Input],
+                    ..no_deps()
+                },
+                5.0,
+            )
+            .expect("Failed to register UI system");
+
+        for id in [SystemId::Audio, SystemId::Input, SystemId::UI] {
+            let order = Arc::clone(&order);
+            coordinator.set_system_runner(id, move || {
+                order.lock().push(id);
+                Ok(())
+            });
+        }
+
+        let report = coordinator
+            .execute_frame()
+            .expect("Frame execution failed");
+
+        assert_eq!(report.executed_systems.len(), 3);
+        assert_eq!(report.waves_executed, 2);
+        assert_eq!(order.lock().last().copied(), Some(SystemId::UI));
+    }
 }
@@ -1,25 +1,52 @@
 //! Transform Stage Data - Stub
+use serde::{Deserialize, Serialize};
+
 pub struct TransformStageData;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ActualOutput {
     pub output_type: OutputType,
     pub quantity: u32,
     pub quality: f32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OutputType {
     Primary,
     Secondary,
     Item(u32), // Resource ID
 }
 
-pub struct StageOutput;
-pub struct StageRequirement;
+/// What a stage yields when it runs to completion
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageOutput {
+    pub output_type: OutputType,
+    pub quantity: u32,
+}
+
+/// Resource a stage consumes when it starts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageRequirement {
+    pub resource_id: u32,
+    pub quantity: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TransformStage {
+    /// Seconds
     pub duration: f32,
+    pub inputs: Vec<StageRequirement>,
+    pub outputs: Vec<StageOutput>,
 }
+
+/// Where a process is within its stages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StagePosition {
+    pub stage: usize,
+    /// Completion of that stage (0.0-1.0)
+    pub completion: f32,
+}
+
 pub struct ValidationContext;
 pub enum ValidationResult { Valid, Invalid }
 pub struct ItemRequirement;
@@ -1,7 +1,132 @@
-//! Transform Stage Operations - Stub
+//! Transform Stage Operations
+//!
+//! Stage timing plus the partial outputs and refunds of a stage that
+//! stopped early (cancelled or owner gone).
+use super::transform_stage_data::{
+    ActualOutput, OutputType, StageOutput, StagePosition, StageRequirement, TransformStage,
+};
+use super::TICKS_PER_SECOND;
+
 pub fn transform_stage() {}
 
 pub fn validate_requirements() {}
-pub fn calculate_outputs() {}
-pub fn create_crafting_stage() {}
-pub fn create_smelting_stage() {}
+
+/// Stage length in game ticks
+pub fn stage_duration_ticks(stage: &TransformStage) -> u64 {
+    (stage.duration.max(0.0) * TICKS_PER_SECOND as f32) as u64
+}
+
+/// Which stage `elapsed` ticks fall in, and how far through it.
+/// `None` when there are no stages; past the end is the last stage at 1.0.
+pub fn stage_position(stages: &[TransformStage], elapsed: u64) -> Option<StagePosition> {
+    let mut start = 0u64;
+    for (stage, data) in stages.iter().enumerate() {
+        let duration = stage_duration_ticks(data);
+        if elapsed < start + duration {
+            return Some(StagePosition {
+                stage,
+                completion: (elapsed - start) as f32 / duration as f32,
+            });
+        }
+        start += duration;
+    }
+    stages.len().checked_sub(1).map(|stage| StagePosition {
+        stage,
+        completion: 1.0,
+    })
+}
+
+/// Outputs of a stage that got `completion` (0.0-1.0) of the way through.
+/// Partial runs round each output down, so only a finished stage yields
+/// everything.
+pub fn calculate_outputs(
+    stage: &TransformStage,
+    completion: f32,
+    quality: f32,
+) -> Vec<ActualOutput> {
+    let completion = completion.clamp(0.0, 1.0);
+    stage
+        .outputs
+        .iter()
+        .filter_map(|output| {
+            let quantity = (output.quantity as f32 * completion).floor() as u32;
+            (quantity > 0).then_some(ActualOutput {
+                output_type: output.output_type,
+                quantity,
+                quality,
+            })
+        })
+        .collect()
+}
+
+/// Inputs handed back when a stage stops at `completion`: the unused
+/// share of each input, rounded down
+pub fn calculate_refunds(stage: &TransformStage, completion: f32) -> Vec<ActualOutput> {
+    let remaining = 1.0 - completion.clamp(0.0, 1.0);
+    stage
+        .inputs
+        .iter()
+        .filter_map(|input| {
+            let quantity = (input.quantity as f32 * remaining).floor() as u32;
+            (quantity > 0).then_some(ActualOutput {
+                output_type: OutputType::Item(input.resource_id),
+                quantity,
+                quality: 1.0,
+            })
+        })
+        .collect()
+}
+
+/// One-step crafting: consume `inputs`, yield `outputs` after `duration` seconds
+pub fn create_crafting_stage(
+    duration: f32,
+    inputs: Vec<StageRequirement>,
+    outputs: Vec<StageOutput>,
+) -> TransformStage {
+    TransformStage {
+        duration,
+        inputs,
+        outputs,
+    }
+}
+
+/// Smelting: each ore unit becomes one output unit, one unit per
+/// `seconds_per_item`
+pub fn create_smelting_stage(
+    ore: u32,
+    product: u32,
+    count: u32,
+    seconds_per_item: f32,
+) -> TransformStage {
+    create_crafting_stage(
+        seconds_per_item * count as f32,
+        vec![StageRequirement {
+            resource_id: ore,
+            quantity: count,
+        }],
+        vec![StageOutput {
+            output_type: OutputType::Item(product),
+            quantity: count,
+        }],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_half_finished_smelt_splits_outputs_and_refunds() {
+        // 8 ore at 10 s each: 1600 ticks
+        let stage = create_smelting_stage(1, 2, 8, 10.0);
+        let position = stage_position(std::slice::from_ref(&stage), 700).expect("stage");
+        assert_eq!(position.stage, 0);
+
+        let outputs = calculate_outputs(&stage, position.completion, 1.0);
+        let refunds = calculate_refunds(&stage, position.completion);
+        assert_eq!(outputs[0].output_type, OutputType::Item(2));
+        assert_eq!(outputs[0].quantity, 3);
+        assert_eq!(refunds[0].output_type, OutputType::Item(1));
+        assert_eq!(refunds[0].quantity, 4);
+    }
+}
@@ -1,13 +1,27 @@
 //! Visual Indicators Data - Stub
 pub struct VisualIndicatorsData;
 
-pub struct ProcessVisual;
+/// Indicator state of one process
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessVisual {
+    /// Completion in 0..=1
+    pub progress: f32,
+    /// World-space point (voxels) the indicator is drawn at; `None` for
+    /// processes not anchored to a block
+    pub world_position: Option<[f32; 3]>,
+}
 
-impl ProcessVisual {
-    pub fn default() -> Self {
-        Self
-    }
+/// Indicator to draw over an anchored process
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnchoredIndicator {
+    pub process: crate::process::ProcessId,
+    /// World space (voxels)
+    pub position: [f32; 3],
+    pub progress: f32,
+    /// Drawn greyed out while the process is paused
+    pub paused: bool,
 }
+
 pub struct ProgressBar;
 pub struct StatusIcon;
 pub enum ProgressColor { Green, Yellow, Red }
@@ -3,8 +3,14 @@ use super::ProcessVisual;
 
 pub fn update_indicators() {}
 
-pub fn update_progress(_visual: &mut ProcessVisual, _progress: f32) {
-    // Stub implementation
+/// Store process completion (clamped to 0..=1)
+pub fn update_progress(visual: &mut ProcessVisual, progress: f32) {
+    visual.progress = progress.clamp(0.0, 1.0);
+}
+
+/// Place the indicator in world space, or detach it with `None`
+pub fn set_world_position(visual: &mut ProcessVisual, position: Option<[f32; 3]>) {
+    visual.world_position = position;
 }
 pub fn update_status() {}
 pub fn calculate_segments() {}
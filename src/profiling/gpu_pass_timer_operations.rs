@@ -0,0 +1,197 @@
+//! GPU Pass Timer Operations - Pure DOP functions
+//!
+//! Per frame: `begin_gpu_pass_timing` for each pass to time (None while a
+//! readback is outstanding or no capture runs), pass the returned writes to
+//! the pass descriptor, `resolve_gpu_pass_timer` before finishing the
+//! encoder and `request_gpu_pass_readback` after submitting. A later frame
+//! picks the timings up with `collect_gpu_pass_timings`.
+
+use super::trace_data::{GpuPassTimerData, GpuPassTiming};
+use crate::memory::{create_tracked_buffer, register_global_gpu_owner};
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+const READBACK_WAITING: u8 = 0;
+const READBACK_MAPPED: u8 = 1;
+const READBACK_FAILED: u8 = 2;
+
+const TIMESTAMP_BYTES: u64 = std::mem::size_of::<u64>() as u64;
+
+/// Timer for up to `max_passes` passes per submission on `queue_index`.
+/// None when the device was created without `Features::TIMESTAMP_QUERY`.
+pub fn create_gpu_pass_timer(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    max_passes: u32,
+    queue_index: u32,
+) -> Option<GpuPassTimerData> {
+    if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) || max_passes == 0 {
+        return None;
+    }
+    let count = max_passes * 2;
+    let size = count as u64 * TIMESTAMP_BYTES;
+    let gpu_owner = register_global_gpu_owner("gpu_pass_timer");
+    let (resolve_buffer, resolve_allocation) = create_tracked_buffer(
+        device,
+        &gpu_owner,
+        &wgpu::BufferDescriptor {
+            label: Some("GPU Pass Timer Resolve"),
+            size,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        },
+    );
+    let (readback_buffer, readback_allocation) = create_tracked_buffer(
+        device,
+        &gpu_owner,
+        &wgpu::BufferDescriptor {
+            label: Some("GPU Pass Timer Readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
+    Some(GpuPassTimerData {
+        query_set: device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("GPU Pass Timer Queries"),
+            ty: wgpu::QueryType::Timestamp,
+            count,
+        }),
+        resolve_buffer,
+        readback_buffer,
+        max_passes,
+        passes: Vec::new(),
+        queue: queue_index,
+        period_ns: queue.get_timestamp_period(),
+        submit_ns: 0,
+        readback_pending: false,
+        readback_state: Arc::new(AtomicU8::new(READBACK_WAITING)),
+        gpu_allocations: vec![resolve_allocation, readback_allocation],
+        gpu_owner,
+    })
+}
+
+/// Reserve timestamps for a pass; returns its slot, or None when the
+/// timer is full or still waiting for the last readback
+pub fn begin_gpu_pass_timing(
+    timer: &mut GpuPassTimerData,
+    name: impl Into<Cow<'static, str>>,
+) -> Option<u32> {
+    if timer.readback_pending || timer.passes.len() as u32 >= timer.max_passes {
+        return None;
+    }
+    timer.passes.push(name.into());
+    Some(timer.passes.len() as u32 - 1)
+}
+
+/// Timestamp writes for a render pass in `slot`
+pub fn render_pass_timestamp_writes(
+    timer: &GpuPassTimerData,
+    slot: u32,
+) -> wgpu::RenderPassTimestampWrites<'_> {
+    wgpu::RenderPassTimestampWrites {
+        query_set: &timer.query_set,
+        beginning_of_pass_write_index: Some(slot * 2),
+        end_of_pass_write_index: Some(slot * 2 + 1),
+    }
+}
+
+/// Timestamp writes for a compute pass in `slot`
+pub fn compute_pass_timestamp_writes(
+    timer: &GpuPassTimerData,
+    slot: u32,
+) -> wgpu::ComputePassTimestampWrites<'_> {
+    wgpu::ComputePassTimestampWrites {
+        query_set: &timer.query_set,
+        beginning_of_pass_write_index: Some(slot * 2),
+        end_of_pass_write_index: Some(slot * 2 + 1),
+    }
+}
+
+/// Copy this submission's timestamps into the readback buffer
+pub fn resolve_gpu_pass_timer(timer: &GpuPassTimerData, encoder: &mut wgpu::CommandEncoder) {
+    if timer.readback_pending || timer.passes.is_empty() {
+        return;
+    }
+    let count = timer.passes.len() as u32 * 2;
+    encoder.resolve_query_set(&timer.query_set, 0..count, &timer.resolve_buffer, 0);
+    encoder.copy_buffer_to_buffer(
+        &timer.resolve_buffer,
+        0,
+        &timer.readback_buffer,
+        0,
+        count as u64 * TIMESTAMP_BYTES,
+    );
+}
+
+/// Start mapping the readback after the submission that resolved it;
+/// `submit_ns` is the trace clock time of that submission
+pub fn request_gpu_pass_readback(timer: &mut GpuPassTimerData, submit_ns: u64) {
+    if timer.readback_pending || timer.passes.is_empty() {
+        return;
+    }
+    timer.readback_pending = true;
+    timer.submit_ns = submit_ns;
+    timer
+        .readback_state
+        .store(READBACK_WAITING, Ordering::Release);
+    let state = Arc::clone(&timer.readback_state);
+    let size = timer.passes.len() as u64 * 2 * TIMESTAMP_BYTES;
+    timer
+        .readback_buffer
+        .slice(..size)
+        .map_async(wgpu::MapMode::Read, move |result| {
+            let value = if result.is_ok() {
+                READBACK_MAPPED
+            } else {
+                READBACK_FAILED
+            };
+            state.store(value, Ordering::Release);
+        });
+}
+
+/// Timings of the last submission once its readback has mapped (without
+/// blocking); empty until then. The timer accepts passes again afterwards.
+pub fn collect_gpu_pass_timings(
+    timer: &mut GpuPassTimerData,
+    device: &wgpu::Device,
+) -> Vec<GpuPassTiming> {
+    if !timer.readback_pending {
+        return Vec::new();
+    }
+    device.poll(wgpu::Maintain::Poll);
+
+    let mut timings = Vec::new();
+    match timer.readback_state.load(Ordering::Acquire) {
+        READBACK_WAITING => return timings,
+        READBACK_MAPPED => {
+            let size = timer.passes.len() as u64 * 2 * TIMESTAMP_BYTES;
+            {
+                let mapped = timer.readback_buffer.slice(..size).get_mapped_range();
+                let ticks: Vec<u64> = mapped
+                    .chunks_exact(TIMESTAMP_BYTES as usize)
+                    .map(|bytes| {
+                        let mut word = [0u8; 8];
+                        word.copy_from_slice(bytes);
+                        u64::from_le_bytes(word)
+                    })
+                    .collect();
+                for (name, pair) in timer.passes.iter().zip(ticks.chunks_exact(2)) {
+                    timings.push(GpuPassTiming {
+                        name: name.clone(),
+                        queue: timer.queue,
+                        start_ticks: pair[0],
+                        end_ticks: pair[1],
+                    });
+                }
+            }
+            timer.readback_buffer.unmap();
+        }
+        _ => log::warn!("[Profiling] GPU pass timestamp readback failed"),
+    }
+
+    timer.passes.clear();
+    timer.readback_pending = false;
+    timings
+}
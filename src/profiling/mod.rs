@@ -0,0 +1,154 @@
+/// Profiling Module - Data-Oriented Programming (DOP) style
+///
+/// This module follows pure DOP principles:
+/// - trace_data.rs: Pure data structures with NO methods
+/// - trace_operations.rs, gpu_pass_timer_operations.rs: Pure functions
+///   that operate on data
+///
+/// Systems mark their work with `trace_span!`. Nothing is recorded until a
+/// capture is started with the `trace start` console command; `trace stop`
+/// writes Chrome trace JSON (chrome://tracing) or a Perfetto trace. The
+/// renderer adds GPU pass timings from timestamp queries on their own
+/// tracks when the device supports them.
+pub mod gpu_pass_timer_operations;
+pub mod trace_data;
+pub mod trace_operations;
+
+use crate::constants::profiling;
+use parking_lot::Mutex;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::Instant;
+
+// Re-export data structures
+pub use trace_data::{
+    GpuPassTimerData, GpuPassTiming, TraceCaptureData, TraceCategory, TraceError, TraceEvent,
+    TraceFormat, TraceResult, TraceSpanGuard, TraceTrack,
+};
+
+// Re-export all operations
+pub use gpu_pass_timer_operations::{
+    begin_gpu_pass_timing, collect_gpu_pass_timings, compute_pass_timestamp_writes,
+    create_gpu_pass_timer, render_pass_timestamp_writes, request_gpu_pass_readback,
+    resolve_gpu_pass_timer,
+};
+pub use trace_operations::{
+    create_trace_capture, default_trace_path, encode_chrome_trace, encode_perfetto_trace,
+    execute_trace_command, merge_gpu_pass_timings, name_trace_gpu_queue, name_trace_thread,
+    record_trace_event, start_trace_capture, stop_trace_capture, trace_category_name,
+    write_trace_capture,
+};
+
+lazy_static::lazy_static! {
+    /// Capture shared by every traced thread
+    pub static ref GLOBAL_TRACE: Mutex<TraceCaptureData> =
+        Mutex::new(create_trace_capture(profiling::MAX_TRACE_EVENTS));
+
+    static ref TRACE_EPOCH: Instant = Instant::now();
+}
+
+/// Mirrors `GLOBAL_TRACE.recording` so spans cost one atomic load while no
+/// capture runs
+static TRACE_RECORDING: AtomicBool = AtomicBool::new(false);
+
+static NEXT_TRACE_THREAD: AtomicU32 = AtomicU32::new(0);
+
+thread_local! {
+    static TRACE_THREAD: Cell<Option<u32>> = const { Cell::new(None) };
+}
+
+/// Nanoseconds on the trace clock
+pub fn trace_now_ns() -> u64 {
+    TRACE_EPOCH.elapsed().as_nanos() as u64
+}
+
+/// Whether a capture is running
+pub fn trace_recording() -> bool {
+    TRACE_RECORDING.load(Ordering::Relaxed)
+}
+
+/// Track of the calling thread, named after the thread the first time
+fn current_trace_thread(capture: &mut TraceCaptureData) -> u32 {
+    let thread = TRACE_THREAD.with(|id| match id.get() {
+        Some(thread) => thread,
+        None => {
+            let thread = NEXT_TRACE_THREAD.fetch_add(1, Ordering::Relaxed);
+            id.set(Some(thread));
+            thread
+        }
+    });
+    if !capture.thread_names.contains_key(&thread) {
+        let name = std::thread::current()
+            .name()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("thread {}", thread));
+        name_trace_thread(capture, thread, &name);
+    }
+    thread
+}
+
+/// Open a span that is recorded when the guard drops; None (and free)
+/// while no capture runs. Use through `trace_span!`.
+pub fn begin_trace_span(name: &'static str, category: TraceCategory) -> Option<TraceSpanGuard> {
+    trace_recording().then(|| TraceSpanGuard {
+        name,
+        category,
+        start_ns: trace_now_ns(),
+    })
+}
+
+impl Drop for TraceSpanGuard {
+    fn drop(&mut self) {
+        let end_ns = trace_now_ns();
+        let mut capture = GLOBAL_TRACE.lock();
+        let track = TraceTrack::Thread(current_trace_thread(&mut capture));
+        record_trace_event(
+            &mut capture,
+            TraceEvent {
+                name: self.name.into(),
+                category: self.category,
+                track,
+                start_ns: self.start_ns,
+                duration_ns: end_ns.saturating_sub(self.start_ns),
+            },
+        );
+    }
+}
+
+/// Run a `trace ...` console command against the global capture
+pub fn run_trace_command(command: &str) -> TraceResult<String> {
+    let mut capture = GLOBAL_TRACE.lock();
+    let output = execute_trace_command(&mut capture, command, trace_now_ns());
+    TRACE_RECORDING.store(capture.recording, Ordering::Relaxed);
+    output
+}
+
+/// Add a GPU timer's finished readback to the global capture; call once
+/// per frame before timing new passes. Returns the passes recorded.
+pub fn collect_global_gpu_pass_timings(
+    timer: &mut GpuPassTimerData,
+    device: &wgpu::Device,
+    queue_name: &str,
+) -> usize {
+    let timings = collect_gpu_pass_timings(timer, device);
+    if timings.is_empty() {
+        return 0;
+    }
+    let mut capture = GLOBAL_TRACE.lock();
+    if !capture.gpu_queue_names.contains_key(&timer.queue) {
+        name_trace_gpu_queue(&mut capture, timer.queue, queue_name);
+    }
+    merge_gpu_pass_timings(&mut capture, &timings, timer.period_ns, timer.submit_ns)
+}
+
+/// Time the rest of the enclosing scope as a span on the current thread
+///
+/// ```ignore
+/// let _span = trace_span!(Meshing, "generate_chunk_meshes");
+/// ```
+#[macro_export]
+macro_rules! trace_span {
+    ($category:ident, $name:expr) => {
+        $crate::profiling::begin_trace_span($name, $crate::profiling::TraceCategory::$category)
+    };
+}
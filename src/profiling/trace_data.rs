@@ -0,0 +1,133 @@
+//! Trace Data - Pure DOP
+//!
+//! Timeline capture for chrome://tracing and Perfetto. Systems open spans
+//! with `trace_span!`; while a capture is running each finished span
+//! becomes one `TraceEvent` on its thread's track. GPU pass timings read
+//! back from timestamp queries are placed on per-queue tracks of the same
+//! timeline, so CPU and GPU work line up in the viewer.
+//!
+//! NO METHODS - just data.
+
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::atomic::AtomicU8;
+use std::sync::Arc;
+
+/// Engine system a span belongs to (the trace category)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraceCategory {
+    Frame,
+    Generation,
+    Meshing,
+    Culling,
+    Persistence,
+    Network,
+    Render,
+    Gpu,
+}
+
+/// Timeline row an event is drawn on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TraceTrack {
+    /// CPU thread, numbered in the order threads first traced
+    Thread(u32),
+    /// GPU queue
+    GpuQueue(u32),
+}
+
+/// One finished span
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    pub name: Cow<'static, str>,
+    pub category: TraceCategory,
+    pub track: TraceTrack,
+    /// Nanoseconds since the trace clock's epoch
+    pub start_ns: u64,
+    pub duration_ns: u64,
+}
+
+/// File format written when a capture stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// Chrome trace event JSON (chrome://tracing, Perfetto UI, Speedscope)
+    ChromeJson,
+    /// Perfetto protobuf trace
+    Perfetto,
+}
+
+/// Capture state and recorded events
+#[derive(Debug, Clone)]
+pub struct TraceCaptureData {
+    pub recording: bool,
+    pub format: TraceFormat,
+    /// Trace clock time the capture started
+    pub started_ns: u64,
+    pub events: Vec<TraceEvent>,
+    /// Events kept per capture; later ones are counted in `dropped_events`
+    pub max_events: usize,
+    pub dropped_events: u64,
+    pub thread_names: BTreeMap<u32, String>,
+    pub gpu_queue_names: BTreeMap<u32, String>,
+}
+
+/// Open span; records itself when dropped (see `begin_trace_span`)
+#[derive(Debug)]
+pub struct TraceSpanGuard {
+    pub name: &'static str,
+    pub category: TraceCategory,
+    pub start_ns: u64,
+}
+
+/// Begin and end of one GPU pass in timestamp ticks
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuPassTiming {
+    pub name: Cow<'static, str>,
+    pub queue: u32,
+    pub start_ticks: u64,
+    pub end_ticks: u64,
+}
+
+/// Timestamp queries for the passes of one submission, and the buffers
+/// they are read back through
+#[derive(Debug)]
+pub struct GpuPassTimerData {
+    pub query_set: wgpu::QuerySet,
+    pub resolve_buffer: wgpu::Buffer,
+    pub readback_buffer: wgpu::Buffer,
+    /// Passes one submission can time
+    pub max_passes: u32,
+    /// Passes timed in the submission being recorded
+    pub passes: Vec<Cow<'static, str>>,
+    pub queue: u32,
+    /// Nanoseconds per timestamp tick
+    pub period_ns: f32,
+    /// Trace clock time of the submission being read back
+    pub submit_ns: u64,
+    /// A readback is mapped or being mapped; no passes are timed until it
+    /// has been collected
+    pub readback_pending: bool,
+    /// Set by the map callback: 0 = waiting, 1 = mapped, 2 = mapping failed
+    pub readback_state: Arc<AtomicU8>,
+    /// `resolve_buffer` and `readback_buffer`; the query set is not tracked
+    pub gpu_allocations: Vec<GpuAllocationGuard>,
+    pub gpu_owner: GpuOwnerGuard,
+}
+
+/// Trace capture errors
+#[derive(Debug, thiserror::Error)]
+pub enum TraceError {
+    #[error("A trace capture is already running")]
+    AlreadyRecording,
+
+    #[error("No trace capture is running")]
+    NotRecording,
+
+    #[error("Unknown trace command: {command}")]
+    UnknownCommand { command: String },
+
+    #[error("Failed to write trace {path}: {message}")]
+    Io { path: String, message: String },
+}
+
+pub type TraceResult<T> = Result<T, TraceError>;
@@ -0,0 +1,518 @@
+//! Trace Operations - Pure DOP functions
+//!
+//! Capture bookkeeping, placing GPU pass timings on the timeline, the
+//! Chrome JSON and Perfetto exporters and the `trace` console command.
+//! The global capture in mod.rs calls these.
+
+use super::trace_data::{
+    GpuPassTiming, TraceCaptureData, TraceCategory, TraceError, TraceEvent, TraceFormat,
+    TraceResult, TraceTrack,
+};
+use crate::constants::profiling;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Stopped capture keeping at most `max_events` events
+pub fn create_trace_capture(max_events: usize) -> TraceCaptureData {
+    TraceCaptureData {
+        recording: false,
+        format: TraceFormat::ChromeJson,
+        started_ns: 0,
+        events: Vec::new(),
+        max_events,
+        dropped_events: 0,
+        thread_names: BTreeMap::new(),
+        gpu_queue_names: BTreeMap::new(),
+    }
+}
+
+/// Category label shown in the viewer
+pub fn trace_category_name(category: TraceCategory) -> &'static str {
+    match category {
+        TraceCategory::Frame => "frame",
+        TraceCategory::Generation => "generation",
+        TraceCategory::Meshing => "meshing",
+        TraceCategory::Culling => "culling",
+        TraceCategory::Persistence => "persistence",
+        TraceCategory::Network => "network",
+        TraceCategory::Render => "render",
+        TraceCategory::Gpu => "gpu",
+    }
+}
+
+/// Start recording; events of the previous capture are discarded. Track
+/// names are kept, threads only announce themselves once.
+pub fn start_trace_capture(
+    capture: &mut TraceCaptureData,
+    format: TraceFormat,
+    now_ns: u64,
+) -> TraceResult<()> {
+    if capture.recording {
+        return Err(TraceError::AlreadyRecording);
+    }
+    capture.recording = true;
+    capture.format = format;
+    capture.started_ns = now_ns;
+    capture.events.clear();
+    capture.dropped_events = 0;
+    Ok(())
+}
+
+/// Stop recording; the events stay until the next start
+pub fn stop_trace_capture(capture: &mut TraceCaptureData) -> TraceResult<()> {
+    if !capture.recording {
+        return Err(TraceError::NotRecording);
+    }
+    capture.recording = false;
+    Ok(())
+}
+
+/// Add a finished span. Returns false when not recording or full.
+pub fn record_trace_event(capture: &mut TraceCaptureData, event: TraceEvent) -> bool {
+    if !capture.recording {
+        return false;
+    }
+    if capture.events.len() >= capture.max_events {
+        capture.dropped_events += 1;
+        return false;
+    }
+    capture.events.push(event);
+    true
+}
+
+/// Name a CPU thread track
+pub fn name_trace_thread(capture: &mut TraceCaptureData, thread: u32, name: &str) {
+    capture.thread_names.insert(thread, name.to_string());
+}
+
+/// Name a GPU queue track
+pub fn name_trace_gpu_queue(capture: &mut TraceCaptureData, queue: u32, name: &str) {
+    capture.gpu_queue_names.insert(queue, name.to_string());
+}
+
+/// Place one submission's GPU pass timings on the timeline. GPU and CPU
+/// clocks are not synchronized, so the earliest pass is placed at the
+/// submission's trace time `submit_ns`: offsets from the CPU are off by the
+/// submit latency, durations and gaps between passes are exact. Returns the
+/// number of passes recorded.
+pub fn merge_gpu_pass_timings(
+    capture: &mut TraceCaptureData,
+    timings: &[GpuPassTiming],
+    period_ns: f32,
+    submit_ns: u64,
+) -> usize {
+    let Some(origin) = timings.iter().map(|t| t.start_ticks).min() else {
+        return 0;
+    };
+    let to_ns = |ticks: u64| (ticks as f64 * period_ns as f64) as u64;
+
+    let mut recorded = 0;
+    for timing in timings {
+        // Unwritten or wrapped timestamps
+        if timing.end_ticks < timing.start_ticks {
+            continue;
+        }
+        let event = TraceEvent {
+            name: timing.name.clone(),
+            category: TraceCategory::Gpu,
+            track: TraceTrack::GpuQueue(timing.queue),
+            start_ns: submit_ns + to_ns(timing.start_ticks - origin),
+            duration_ns: to_ns(timing.end_ticks - timing.start_ticks),
+        };
+        if record_trace_event(capture, event) {
+            recorded += 1;
+        }
+    }
+    recorded
+}
+
+// ===== Chrome trace JSON =====
+
+const CPU_PROCESS_ID: u32 = 1;
+const GPU_PROCESS_ID: u32 = 2;
+
+fn chrome_ids(track: TraceTrack) -> (u32, u32) {
+    match track {
+        TraceTrack::Thread(thread) => (CPU_PROCESS_ID, thread),
+        TraceTrack::GpuQueue(queue) => (GPU_PROCESS_ID, queue),
+    }
+}
+
+fn chrome_metadata(kind: &str, pid: u32, tid: u32, name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "ph": "M",
+        "name": kind,
+        "pid": pid,
+        "tid": tid,
+        "args": { "name": name },
+    })
+}
+
+/// Capture as Chrome trace event JSON: CPU threads in one process, GPU
+/// queues in another, times in microseconds from the capture start
+pub fn encode_chrome_trace(capture: &TraceCaptureData) -> String {
+    let micros = |ns: u64| ns as f64 / 1000.0;
+    let mut events = vec![
+        chrome_metadata("process_name", CPU_PROCESS_ID, 0, "Hearth Engine"),
+        chrome_metadata("process_name", GPU_PROCESS_ID, 0, "GPU"),
+    ];
+    for (thread, name) in &capture.thread_names {
+        events.push(chrome_metadata(
+            "thread_name",
+            CPU_PROCESS_ID,
+            *thread,
+            name,
+        ));
+    }
+    for (queue, name) in &capture.gpu_queue_names {
+        events.push(chrome_metadata("thread_name", GPU_PROCESS_ID, *queue, name));
+    }
+    for event in &capture.events {
+        let (pid, tid) = chrome_ids(event.track);
+        events.push(serde_json::json!({
+            "name": event.name,
+            "cat": trace_category_name(event.category),
+            "ph": "X",
+            "ts": micros(event.start_ns.saturating_sub(capture.started_ns)),
+            "dur": micros(event.duration_ns),
+            "pid": pid,
+            "tid": tid,
+        }));
+    }
+
+    serde_json::json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+        "otherData": { "dropped_events": capture.dropped_events },
+    })
+    .to_string()
+}
+
+// ===== Perfetto protobuf =====
+
+// Field numbers from perfetto/trace/trace.proto and track_event/*.proto
+const TRACE_PACKET: u32 = 1;
+const PACKET_TIMESTAMP: u32 = 8;
+const PACKET_SEQUENCE_ID: u32 = 10;
+const PACKET_TRACK_EVENT: u32 = 11;
+const PACKET_SEQUENCE_FLAGS: u32 = 13;
+const PACKET_TRACK_DESCRIPTOR: u32 = 60;
+const DESCRIPTOR_UUID: u32 = 1;
+const DESCRIPTOR_NAME: u32 = 2;
+const DESCRIPTOR_PARENT_UUID: u32 = 5;
+const EVENT_TYPE: u32 = 9;
+const EVENT_TRACK_UUID: u32 = 11;
+const EVENT_CATEGORIES: u32 = 22;
+const EVENT_NAME: u32 = 23;
+
+const SLICE_BEGIN: u64 = 1;
+const SLICE_END: u64 = 2;
+const SEQ_INCREMENTAL_STATE_CLEARED: u64 = 1;
+const SEQUENCE_ID: u64 = 1;
+
+const CPU_TRACK_UUID: u64 = 1;
+const GPU_TRACK_UUID: u64 = 2;
+
+fn proto_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn proto_uint(out: &mut Vec<u8>, field: u32, value: u64) {
+    proto_varint(out, (field as u64) << 3);
+    proto_varint(out, value);
+}
+
+fn proto_bytes(out: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    proto_varint(out, ((field as u64) << 3) | 2);
+    proto_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn track_uuid(track: TraceTrack) -> u64 {
+    match track {
+        TraceTrack::Thread(thread) => 0x100 + thread as u64,
+        TraceTrack::GpuQueue(queue) => 0x1_0000_0000 + queue as u64,
+    }
+}
+
+fn track_descriptor_packet(out: &mut Vec<u8>, uuid: u64, parent: Option<u64>, name: &str) {
+    let mut descriptor = Vec::new();
+    proto_uint(&mut descriptor, DESCRIPTOR_UUID, uuid);
+    proto_bytes(&mut descriptor, DESCRIPTOR_NAME, name.as_bytes());
+    if let Some(parent) = parent {
+        proto_uint(&mut descriptor, DESCRIPTOR_PARENT_UUID, parent);
+    }
+    let mut packet = Vec::new();
+    proto_uint(&mut packet, PACKET_SEQUENCE_ID, SEQUENCE_ID);
+    proto_bytes(&mut packet, PACKET_TRACK_DESCRIPTOR, &descriptor);
+    proto_bytes(out, TRACE_PACKET, &packet);
+}
+
+/// Capture as a Perfetto protobuf trace: one track per thread and GPU
+/// queue under a CPU and a GPU parent track
+pub fn encode_perfetto_trace(capture: &TraceCaptureData) -> Vec<u8> {
+    let mut out = Vec::new();
+
+    let mut first = Vec::new();
+    proto_uint(&mut first, PACKET_SEQUENCE_ID, SEQUENCE_ID);
+    proto_uint(
+        &mut first,
+        PACKET_SEQUENCE_FLAGS,
+        SEQ_INCREMENTAL_STATE_CLEARED,
+    );
+    proto_bytes(&mut out, TRACE_PACKET, &first);
+
+    track_descriptor_packet(&mut out, CPU_TRACK_UUID, None, "Hearth Engine");
+    track_descriptor_packet(&mut out, GPU_TRACK_UUID, None, "GPU");
+    let mut tracks: Vec<TraceTrack> = capture.events.iter().map(|e| e.track).collect();
+    tracks.sort();
+    tracks.dedup();
+    for track in tracks {
+        let (parent, name) = match track {
+            TraceTrack::Thread(thread) => {
+                (CPU_TRACK_UUID, capture.thread_names.get(&thread).cloned())
+            }
+            TraceTrack::GpuQueue(queue) => {
+                (GPU_TRACK_UUID, capture.gpu_queue_names.get(&queue).cloned())
+            }
+        };
+        let name = name.unwrap_or_else(|| format!("{:?}", track));
+        track_descriptor_packet(&mut out, track_uuid(track), Some(parent), &name);
+    }
+
+    // Slices on a track must nest: ends come before begins at the same
+    // time, inner slices end first and outer slices begin first
+    let mut markers = Vec::with_capacity(capture.events.len() * 2);
+    for (index, event) in capture.events.iter().enumerate() {
+        let end = event.start_ns + event.duration_ns;
+        markers.push((end, 0, u64::MAX - event.start_ns, index));
+        markers.push((event.start_ns, 1, u64::MAX - event.duration_ns, index));
+    }
+    markers.sort_unstable();
+
+    for (timestamp, kind, _, index) in markers {
+        let event = &capture.events[index];
+        let mut track_event = Vec::new();
+        let event_type = if kind == 1 { SLICE_BEGIN } else { SLICE_END };
+        proto_uint(&mut track_event, EVENT_TYPE, event_type);
+        proto_uint(&mut track_event, EVENT_TRACK_UUID, track_uuid(event.track));
+        if kind == 1 {
+            proto_bytes(
+                &mut track_event,
+                EVENT_CATEGORIES,
+                trace_category_name(event.category).as_bytes(),
+            );
+            proto_bytes(&mut track_event, EVENT_NAME, event.name.as_bytes());
+        }
+        let mut packet = Vec::new();
+        proto_uint(&mut packet, PACKET_TIMESTAMP, timestamp);
+        proto_uint(&mut packet, PACKET_SEQUENCE_ID, SEQUENCE_ID);
+        proto_bytes(&mut packet, PACKET_TRACK_EVENT, &track_event);
+        proto_bytes(&mut out, TRACE_PACKET, &packet);
+    }
+    out
+}
+
+/// Path `trace stop` writes to when none is given
+pub fn default_trace_path(format: TraceFormat) -> &'static str {
+    match format {
+        TraceFormat::ChromeJson => profiling::DEFAULT_CHROME_TRACE_PATH,
+        TraceFormat::Perfetto => profiling::DEFAULT_PERFETTO_TRACE_PATH,
+    }
+}
+
+/// Write the capture in its format; returns the number of events written
+pub fn write_trace_capture(capture: &TraceCaptureData, path: &Path) -> TraceResult<usize> {
+    let io_error = |e: std::io::Error| TraceError::Io {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    };
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    let bytes = match capture.format {
+        TraceFormat::ChromeJson => encode_chrome_trace(capture).into_bytes(),
+        TraceFormat::Perfetto => encode_perfetto_trace(capture),
+    };
+    std::fs::write(path, bytes).map_err(io_error)?;
+    Ok(capture.events.len())
+}
+
+// ===== Console command =====
+
+/// Run a `trace` console command and return the text to print:
+///
+/// - `trace start [chrome|perfetto]` - start a capture (Chrome JSON by default)
+/// - `trace stop [path]` - stop and write the capture
+/// - `trace status`
+pub fn execute_trace_command(
+    capture: &mut TraceCaptureData,
+    command: &str,
+    now_ns: u64,
+) -> TraceResult<String> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let args = match words.first() {
+        Some(&"trace") => &words[1..],
+        _ => &words[..],
+    };
+    let unknown = || TraceError::UnknownCommand {
+        command: command.trim().to_string(),
+    };
+
+    match args {
+        ["start", rest @ ..] => {
+            let format = match rest {
+                [] | ["chrome"] | ["json"] => TraceFormat::ChromeJson,
+                ["perfetto"] => TraceFormat::Perfetto,
+                _ => return Err(unknown()),
+            };
+            start_trace_capture(capture, format, now_ns)?;
+            Ok(crate::tr!(
+                "console.trace.started",
+                format = format!("{:?}", format)
+            ))
+        }
+        ["stop", rest @ ..] => {
+            let path = match rest {
+                [] => default_trace_path(capture.format),
+                [path] => path,
+                _ => return Err(unknown()),
+            };
+            stop_trace_capture(capture)?;
+            let written = write_trace_capture(capture, Path::new(path))?;
+            let seconds = now_ns.saturating_sub(capture.started_ns) as f64 / 1e9;
+            let mut output = crate::tr!(
+                "console.trace.wrote",
+                count = written,
+                seconds = format!("{:.1}", seconds),
+                path = path,
+            );
+            if capture.dropped_events > 0 {
+                output.push_str(&crate::tr!(
+                    "console.trace.dropped",
+                    count = capture.dropped_events
+                ));
+            }
+            Ok(output)
+        }
+        ["status"] => Ok(if capture.recording {
+            let seconds = now_ns.saturating_sub(capture.started_ns) as f64 / 1e9;
+            crate::tr!(
+                "console.trace.status",
+                format = format!("{:?}", capture.format),
+                count = capture.events.len(),
+                seconds = format!("{:.1}", seconds),
+            )
+        } else {
+            crate::tr!("console.trace.idle")
+        }),
+        _ => Err(unknown()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+    use tempfile::TempDir;
+
+    fn span(name: &'static str, track: TraceTrack, start_ns: u64, duration_ns: u64) -> TraceEvent {
+        TraceEvent {
+            name: Cow::Borrowed(name),
+            category: TraceCategory::Meshing,
+            track,
+            start_ns,
+            duration_ns,
+        }
+    }
+
+    #[test]
+    fn test_capture_merges_gpu_passes_and_exports() {
+        let mut capture = create_trace_capture(3);
+        assert!(!record_trace_event(
+            &mut capture,
+            span("early", TraceTrack::Thread(0), 0, 1)
+        ));
+
+        execute_trace_command(&mut capture, "trace start perfetto", 1_000).expect("starts");
+        assert!(matches!(
+            start_trace_capture(&mut capture, TraceFormat::ChromeJson, 2_000),
+            Err(TraceError::AlreadyRecording)
+        ));
+        name_trace_thread(&mut capture, 0, "main");
+        name_trace_gpu_queue(&mut capture, 0, "Graphics Queue");
+        record_trace_event(
+            &mut capture,
+            span("frame", TraceTrack::Thread(0), 2_000, 9_000),
+        );
+        record_trace_event(
+            &mut capture,
+            span("mesh", TraceTrack::Thread(0), 3_000, 1_000),
+        );
+
+        // Two passes 500 ticks apart at 2 ns per tick, placed at the submit
+        let timings = [
+            GpuPassTiming {
+                name: Cow::Borrowed("Frame Pass"),
+                queue: 0,
+                start_ticks: 10_000,
+                end_ticks: 10_250,
+            },
+            GpuPassTiming {
+                name: Cow::Borrowed("Post Pass"),
+                queue: 0,
+                start_ticks: 10_500,
+                end_ticks: 10_400,
+            },
+            GpuPassTiming {
+                name: Cow::Borrowed("Shadow Pass"),
+                queue: 0,
+                start_ticks: 10_500,
+                end_ticks: 10_600,
+            },
+        ];
+        // The reversed pass is skipped, the last one hits the event limit
+        assert_eq!(
+            merge_gpu_pass_timings(&mut capture, &timings, 2.0, 8_000),
+            1
+        );
+        assert_eq!(capture.events[2].start_ns, 8_000);
+        assert_eq!(capture.events[2].duration_ns, 500);
+        assert_eq!(capture.events.len(), 3);
+        assert_eq!(capture.dropped_events, 1);
+
+        let perfetto = encode_perfetto_trace(&capture);
+        // Every top-level field is a length-delimited TracePacket
+        assert_eq!(perfetto[0], (TRACE_PACKET << 3 | 2) as u8);
+        assert!(perfetto.windows(10).any(|w| w == b"Frame Pass"));
+
+        capture.format = TraceFormat::ChromeJson;
+        let json: serde_json::Value =
+            serde_json::from_str(&encode_chrome_trace(&capture)).expect("valid JSON");
+        let events = json["traceEvents"].as_array().expect("events");
+        let gpu = events
+            .iter()
+            .find(|e| e["name"] == "Frame Pass")
+            .expect("GPU pass");
+        assert_eq!(gpu["pid"], GPU_PROCESS_ID);
+        assert_eq!(gpu["ts"], 7.0);
+        assert_eq!(gpu["dur"], 0.5);
+
+        let dir = TempDir::new().expect("temp dir");
+        let path = dir.path().join("captures").join("frame.json");
+        let command = format!("trace stop {}", path.display());
+        let output = execute_trace_command(&mut capture, &command, 20_000).expect("stops");
+        assert!(output.contains("1 events dropped"));
+        assert!(path.exists());
+        assert!(matches!(
+            execute_trace_command(&mut capture, "trace stop", 21_000),
+            Err(TraceError::NotRecording)
+        ));
+        assert!(execute_trace_command(&mut capture, "trace start svg", 22_000).is_err());
+    }
+}
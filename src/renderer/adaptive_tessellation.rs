@@ -1,27 +1,31 @@
-/// Adaptive Tessellation for SDF Terrain
-/// 
+/// Adaptive Tessellation for Terrain Surfaces
+///
 /// Dynamically adjusts mesh density based on surface curvature and
 /// screen space error for optimal quality/performance tradeoff.
+use bytemuck::{Pod, Zeroable};
+use cgmath::{ElementWise, InnerSpace, Vector3, Zero};
 
-use cgmath::{Vector3, Vector2};
-use crate::sdf::SdfBuffer;
-use crate::renderer::Vertex;
+/// Signed distance field sampled during tessellation
+pub trait SurfaceField {
+    /// Signed distance to the surface (negative inside)
+    fn sample(&self, pos: Vector3<f32>) -> f32;
+}
 
 /// Tessellation parameters
 #[derive(Debug, Clone)]
 pub struct TessellationParams {
     /// Maximum tessellation level (subdivisions)
     pub max_level: u32,
-    
+
     /// Screen space error threshold in pixels
     pub screen_error_threshold: f32,
-    
+
     /// Curvature threshold for subdivision
     pub curvature_threshold: f32,
-    
+
     /// Distance-based LOD factor
     pub distance_factor: f32,
-    
+
     /// Minimum edge length to prevent over-tessellation
     pub min_edge_length: f32,
 }
@@ -38,7 +42,28 @@ impl Default for TessellationParams {
     }
 }
 
-/// Adaptive tessellation system for SDF meshes
+/// View used for screen space error estimation
+#[derive(Debug, Clone, Copy)]
+pub struct TessellationView {
+    pub view_pos: Vector3<f32>,
+    /// Viewport height in pixels
+    pub viewport_height: f32,
+    /// Vertical field of view in radians
+    pub fov: f32,
+}
+
+/// Horizontal ring (annulus) around a center, in world units
+///
+/// Patches fully inside `inner_radius` (Chebyshev distance, matching the
+/// square voxel render region) are dropped.
+#[derive(Debug, Clone, Copy)]
+pub struct RingRegion {
+    pub center: Vector3<f32>,
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+}
+
+/// Adaptive tessellation system for terrain meshes
 pub struct AdaptiveTessellator {
     params: TessellationParams,
 }
@@ -47,88 +72,95 @@ impl AdaptiveTessellator {
     pub fn new(params: TessellationParams) -> Self {
         Self { params }
     }
-    
-    /// Tessellate SDF surface adaptively
-    pub fn tessellate_sdf(
+
+    /// Tessellate a distance field surface adaptively
+    pub fn tessellate_field<F: SurfaceField>(
         &self,
-        sdf: &SdfBuffer,
-        region: (Vector3<f32>, Vector3<f32>),
-        view_pos: Vector3<f32>,
-        viewport_size: (f32, f32),
-        fov: f32,
+        field: &F,
+        region_min: Vector3<f32>,
+        region_max: Vector3<f32>,
+        view: &TessellationView,
     ) -> TessellatedMesh {
-        let mut vertices = Vec::new();
-        let mut indices = Vec::new();
-        
         // Start with base grid
         let base_resolution = 16;
-        let mut patches = self.create_base_patches(region, base_resolution);
-        
+        let mut patches = self.create_base_patches(region_min, region_max, base_resolution);
+
         // Adaptively subdivide patches
         let mut final_patches = Vec::new();
-        
+
         while let Some(patch) = patches.pop() {
-            if self.should_subdivide(&patch, sdf, view_pos, viewport_size, fov) {
+            if self.should_subdivide(&patch, view)
+                && self.estimate_curvature(&patch, field) > self.params.curvature_threshold
+            {
                 // Subdivide into 4 sub-patches
-                let sub_patches = self.subdivide_patch(&patch);
-                patches.extend(sub_patches);
+                patches.extend(self.subdivide_patch(&patch));
             } else {
                 final_patches.push(patch);
             }
         }
-        
-        // Generate vertices and indices for final patches
-        let mut vertex_map = std::collections::HashMap::new();
-        
-        for patch in &final_patches {
-            let patch_indices = self.generate_patch_mesh(
-                &patch,
-                sdf,
-                &mut vertices,
-                &mut vertex_map,
-            );
-            indices.extend(patch_indices);
-        }
-        
-        // Optimize vertex order for GPU cache
-        self.optimize_vertex_order(&mut vertices, &mut indices);
-        
-        TessellatedMesh {
-            vertices,
-            indices,
-            stats: TessellationStats {
-                patch_count: final_patches.len(),
-                vertex_count: vertices.len(),
-                triangle_count: indices.len() / 3,
-            },
+
+        self.build_mesh(&final_patches, |pos| {
+            let surface_pos = self.project_to_surface(pos, field);
+            let normal = self.sample_gradient(surface_pos, field).normalize();
+            (surface_pos, normal)
+        })
+    }
+
+    /// Tessellate a flat ring at `region.center.y` by screen space error only
+    ///
+    /// Heights are applied later (e.g. displaced in a vertex shader from a
+    /// heightmap), so the surface is treated as a plane and normals point up.
+    pub fn tessellate_ring(&self, region: &RingRegion, view: &TessellationView) -> TessellatedMesh {
+        let extent = Vector3::new(region.outer_radius, 0.0, region.outer_radius);
+        let base_resolution = 16;
+        let mut patches = self.create_base_patches(
+            region.center - extent,
+            region.center + extent,
+            base_resolution,
+        );
+
+        let mut final_patches = Vec::new();
+
+        while let Some(patch) = patches.pop() {
+            if Self::patch_inside_ring_hole(&patch, region) {
+                continue;
+            }
+            if self.should_subdivide(&patch, view) {
+                patches.extend(self.subdivide_patch(&patch));
+            } else {
+                final_patches.push(patch);
+            }
         }
+
+        self.build_mesh(&final_patches, |pos| (pos, Vector3::unit_y()))
     }
-    
+
+    fn patch_inside_ring_hole(patch: &TerrainPatch, region: &RingRegion) -> bool {
+        let dx_min = (patch.min.x - region.center.x).abs();
+        let dx_max = (patch.max.x - region.center.x).abs();
+        let dz_min = (patch.min.z - region.center.z).abs();
+        let dz_max = (patch.max.z - region.center.z).abs();
+        dx_min.max(dx_max) <= region.inner_radius && dz_min.max(dz_max) <= region.inner_radius
+    }
+
     /// Create initial patches covering the region
     fn create_base_patches(
         &self,
-        region: (Vector3<f32>, Vector3<f32>),
+        min: Vector3<f32>,
+        max: Vector3<f32>,
         resolution: u32,
     ) -> Vec<TerrainPatch> {
         let mut patches = Vec::new();
-        let (min, max) = region;
         let size = max - min;
         let patch_size = size / resolution as f32;
-        
+
         for x in 0..resolution {
             for z in 0..resolution {
-                let patch_min = min + Vector3::new(
-                    x as f32 * patch_size.x,
-                    0.0,
-                    z as f32 * patch_size.z,
-                );
-                
-                let patch_max = patch_min + Vector3::new(
-                    patch_size.x,
-                    size.y,
-                    patch_size.z,
-                );
-                
+                let patch_min =
+                    min + Vector3::new(x as f32 * patch_size.x, 0.0, z as f32 * patch_size.z);
+
+                let patch_max = patch_min + Vector3::new(patch_size.x, size.y, patch_size.z);
+
                 patches.push(TerrainPatch {
                     min: patch_min,
                     max: patch_max,
@@ -136,226 +168,191 @@ impl AdaptiveTessellator {
                 });
             }
         }
-        
+
         patches
     }
-    
-    /// Check if patch should be subdivided
-    fn should_subdivide(
-        &self,
-        patch: &TerrainPatch,
-        sdf: &SdfBuffer,
-        view_pos: Vector3<f32>,
-        viewport_size: (f32, f32),
-        fov: f32,
-    ) -> bool {
+
+    /// Check if patch is large enough on screen to subdivide
+    fn should_subdivide(&self, patch: &TerrainPatch, view: &TessellationView) -> bool {
         // Don't subdivide beyond max level
         if patch.level >= self.params.max_level {
             return false;
         }
-        
+
         // Check edge length
         let edge_length = (patch.max - patch.min).x;
         if edge_length < self.params.min_edge_length {
             return false;
         }
-        
+
         // Distance-based check
         let center = (patch.min + patch.max) * 0.5;
-        let distance = (center - view_pos).magnitude();
+        let distance = (center - view.view_pos).magnitude();
         let distance_factor = 1.0 / (1.0 + distance * self.params.distance_factor);
-        
+
         // Screen space error check
-        let screen_size = self.estimate_screen_size(patch, view_pos, viewport_size, fov);
-        if screen_size < self.params.screen_error_threshold * distance_factor {
-            return false;
-        }
-        
-        // Curvature check
-        let curvature = self.estimate_curvature(patch, sdf);
-        if curvature > self.params.curvature_threshold {
-            return true;
-        }
-        
-        false
+        let screen_size = self.estimate_screen_size(patch, view);
+        screen_size >= self.params.screen_error_threshold * distance_factor
     }
-    
+
     /// Estimate screen space size of patch
-    fn estimate_screen_size(
-        &self,
-        patch: &TerrainPatch,
-        view_pos: Vector3<f32>,
-        viewport_size: (f32, f32),
-        fov: f32,
-    ) -> f32 {
+    fn estimate_screen_size(&self, patch: &TerrainPatch, view: &TessellationView) -> f32 {
         let center = (patch.min + patch.max) * 0.5;
         let size = (patch.max - patch.min).magnitude();
-        let distance = (center - view_pos).magnitude();
-        
+        let distance = (center - view.view_pos).magnitude().max(f32::EPSILON);
+
         // Project to screen space
         let angular_size = (size / distance).atan();
-        let screen_size = angular_size / fov * viewport_size.1;
-        
-        screen_size
+        angular_size / view.fov * view.viewport_height
     }
-    
+
     /// Estimate surface curvature in patch
-    fn estimate_curvature(&self, patch: &TerrainPatch, sdf: &SdfBuffer) -> f32 {
-        // Sample SDF at multiple points
+    fn estimate_curvature<F: SurfaceField>(&self, patch: &TerrainPatch, field: &F) -> f32 {
+        // Sample the field at multiple points
         let samples = 4;
         let mut gradients = Vec::new();
-        
+
         for i in 0..samples {
             for j in 0..samples {
                 let u = i as f32 / (samples - 1) as f32;
                 let v = j as f32 / (samples - 1) as f32;
-                
-                let pos = patch.min + (patch.max - patch.min) * Vector3::new(u, 0.5, v);
-                
+
+                let pos =
+                    patch.min + (patch.max - patch.min).mul_element_wise(Vector3::new(u, 0.5, v));
+
                 // Sample gradient
-                let gradient = self.sample_gradient(pos, sdf);
-                gradients.push(gradient);
+                gradients.push(self.sample_gradient(pos, field));
             }
         }
-        
+
         // Compute curvature as gradient variation
-        let mut max_variation = 0.0;
+        let mut max_variation = 0.0f32;
         for i in 0..gradients.len() {
-            for j in i+1..gradients.len() {
+            for j in i + 1..gradients.len() {
                 let variation = (gradients[i] - gradients[j]).magnitude();
                 max_variation = max_variation.max(variation);
             }
         }
-        
+
         max_variation
     }
-    
-    /// Sample SDF gradient at position
-    fn sample_gradient(&self, pos: Vector3<f32>, sdf: &SdfBuffer) -> Vector3<f32> {
+
+    /// Sample field gradient at position
+    fn sample_gradient<F: SurfaceField>(&self, pos: Vector3<f32>, field: &F) -> Vector3<f32> {
         let h = 0.1; // Small offset for finite difference
-        
-        let dx = sdf.sample(pos + Vector3::unit_x() * h) - 
-                 sdf.sample(pos - Vector3::unit_x() * h);
-        let dy = sdf.sample(pos + Vector3::unit_y() * h) - 
-                 sdf.sample(pos - Vector3::unit_y() * h);
-        let dz = sdf.sample(pos + Vector3::unit_z() * h) - 
-                 sdf.sample(pos - Vector3::unit_z() * h);
-        
+
+        let dx =
+            field.sample(pos + Vector3::unit_x() * h) - field.sample(pos - Vector3::unit_x() * h);
+        let dy =
+            field.sample(pos + Vector3::unit_y() * h) - field.sample(pos - Vector3::unit_y() * h);
+        let dz =
+            field.sample(pos + Vector3::unit_z() * h) - field.sample(pos - Vector3::unit_z() * h);
+
         Vector3::new(dx, dy, dz) / (2.0 * h)
     }
-    
-    /// Subdivide patch into 4 sub-patches
+
+    /// Subdivide patch into 4 sub-patches (split in X and Z only)
     fn subdivide_patch(&self, patch: &TerrainPatch) -> Vec<TerrainPatch> {
         let center = (patch.min + patch.max) * 0.5;
         let level = patch.level + 1;
-        
+
         vec![
             TerrainPatch {
                 min: patch.min,
-                max: center,
+                max: Vector3::new(center.x, patch.max.y, center.z),
                 level,
             },
             TerrainPatch {
                 min: Vector3::new(center.x, patch.min.y, patch.min.z),
-                max: Vector3::new(patch.max.x, center.y, center.z),
+                max: Vector3::new(patch.max.x, patch.max.y, center.z),
                 level,
             },
             TerrainPatch {
                 min: Vector3::new(patch.min.x, patch.min.y, center.z),
-                max: Vector3::new(center.x, center.y, patch.max.z),
+                max: Vector3::new(center.x, patch.max.y, patch.max.z),
                 level,
             },
             TerrainPatch {
                 min: Vector3::new(center.x, patch.min.y, center.z),
-                max: Vector3::new(patch.max.x, center.y, patch.max.z),
+                max: patch.max,
                 level,
             },
         ]
     }
-    
-    /// Generate mesh for a patch
-    fn generate_patch_mesh(
-        &self,
-        patch: &TerrainPatch,
-        sdf: &SdfBuffer,
-        vertices: &mut Vec<Vertex>,
-        vertex_map: &mut std::collections::HashMap<(u32, u32), u32>,
-    ) -> Vec<u32> {
-        let mut indices = Vec::new();
-        
-        // Resolution based on tessellation level
-        let resolution = 2u32.pow(patch.level) + 1;
-        
-        // Generate vertices
-        for i in 0..resolution {
-            for j in 0..resolution {
-                let u = i as f32 / (resolution - 1) as f32;
-                let v = j as f32 / (resolution - 1) as f32;
-                
-                let pos = patch.min + (patch.max - patch.min) * Vector3::new(u, 0.0, v);
-                
-                // Project to SDF surface
-                let surface_pos = self.project_to_surface(pos, sdf);
-                let normal = self.sample_gradient(surface_pos, sdf).normalize();
-                
-                let vertex_key = (i, j);
-                let vertex_index = vertices.len() as u32;
-                
-                vertices.push(Vertex {
-                    position: surface_pos.into(),
+
+    /// Emit one quad per final patch; `place` maps a grid point to position and normal
+    fn build_mesh<P>(&self, patches: &[TerrainPatch], place: P) -> TessellatedMesh
+    where
+        P: Fn(Vector3<f32>) -> (Vector3<f32>, Vector3<f32>),
+    {
+        let mut vertices = Vec::with_capacity(patches.len() * 4);
+        let mut indices = Vec::with_capacity(patches.len() * 6);
+
+        for patch in patches {
+            let base = vertices.len() as u32;
+            for (u, v) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)] {
+                let pos =
+                    patch.min + (patch.max - patch.min).mul_element_wise(Vector3::new(u, 0.0, v));
+                let (position, normal) = place(pos);
+                vertices.push(TessellatedVertex {
+                    position: position.into(),
                     normal: normal.into(),
                     tex_coords: [u, v],
-                    color: [1.0, 1.0, 1.0, 1.0],
-                    ao: 1.0,
                 });
-                
-                vertex_map.insert(vertex_key, vertex_index);
             }
+
+            // Two triangles per quad
+            indices.extend_from_slice(&[base, base + 2, base + 3, base, base + 3, base + 1]);
         }
-        
-        // Generate indices
-        for i in 0..resolution-1 {
-            for j in 0..resolution-1 {
-                let v00 = *vertex_map.get(&(i, j))
-                    .expect("Vertex (i, j) should exist in map");
-                let v10 = *vertex_map.get(&(i+1, j))
-                    .expect("Vertex (i+1, j) should exist in map");
-                let v01 = *vertex_map.get(&(i, j+1))
-                    .expect("Vertex (i, j+1) should exist in map");
-                let v11 = *vertex_map.get(&(i+1, j+1))
-                    .expect("Vertex (i+1, j+1) should exist in map");
-                
-                // Two triangles per quad
-                indices.extend_from_slice(&[v00, v10, v11]);
-                indices.extend_from_slice(&[v00, v11, v01]);
-            }
+
+        // Optimize vertex order for GPU cache
+        self.optimize_vertex_order(&mut vertices, &mut indices);
+
+        TessellatedMesh {
+            stats: TessellationStats {
+                patch_count: patches.len(),
+                vertex_count: vertices.len(),
+                triangle_count: indices.len() / 3,
+            },
+            vertices,
+            indices,
         }
-        
-        indices
     }
-    
-    /// Project point to SDF surface
-    fn project_to_surface(&self, mut pos: Vector3<f32>, sdf: &SdfBuffer) -> Vector3<f32> {
+
+    /// Project point to the field surface
+    fn project_to_surface<F: SurfaceField>(
+        &self,
+        mut pos: Vector3<f32>,
+        field: &F,
+    ) -> Vector3<f32> {
         // Simple iterative projection
         for _ in 0..10 {
-            let distance = sdf.sample(pos);
+            let distance = field.sample(pos);
             if distance.abs() < 0.01 {
                 break;
             }
-            
-            let gradient = self.sample_gradient(pos, sdf);
+
+            let gradient = self.sample_gradient(pos, field);
+            if gradient.is_zero() {
+                break;
+            }
             pos -= gradient.normalize() * distance;
         }
-        
+
         pos
     }
-    
+
     /// Optimize vertex order for GPU vertex cache
-    fn optimize_vertex_order(&self, vertices: &mut Vec<Vertex>, indices: &mut Vec<u32>) {
+    fn optimize_vertex_order(&self, vertices: &mut Vec<TessellatedVertex>, indices: &mut [u32]) {
         // Simple optimization: sort by spatial locality
         // In practice, would use more sophisticated vertex cache optimization
-        
-        // Create vertex remapping
+
+        // Create vertex remapping; positions are offset so Morton codes stay positive
+        let origin = vertices
+            .iter()
+            .fold(Vector3::zero(), |acc: Vector3<f32>, v| {
+                Vector3::new(acc.x.min(v.position[0]), 0.0, acc.z.min(v.position[2]))
+            });
         let mut vertex_remap: Vec<u32> = (0..vertices.len() as u32).collect();
         vertex_remap.sort_by_key(|&idx| {
             let pos = match vertices.get(idx as usize) {
@@ -366,11 +363,11 @@ impl AdaptiveTessellator {
                 }
             };
             // Sort by Morton code for spatial locality
-            let x = (pos.x * 1000.0) as u32;
-            let z = (pos.z * 1000.0) as u32;
+            let x = (pos.x - origin.x) as u32;
+            let z = (pos.z - origin.z) as u32;
             morton_2d(x, z)
         });
-        
+
         // Build inverse mapping
         let mut inverse_remap = vec![0u32; vertices.len()];
         for (new_idx, &old_idx) in vertex_remap.iter().enumerate() {
@@ -378,7 +375,7 @@ impl AdaptiveTessellator {
                 *elem = new_idx as u32;
             }
         }
-        
+
         // Reorder vertices
         let mut new_vertices = Vec::with_capacity(vertices.len());
         for &old_idx in &vertex_remap {
@@ -387,7 +384,7 @@ impl AdaptiveTessellator {
             }
         }
         *vertices = new_vertices;
-        
+
         // Update indices
         for idx in indices.iter_mut() {
             *idx = match inverse_remap.get(*idx as usize) {
@@ -409,9 +406,18 @@ struct TerrainPatch {
     level: u32,
 }
 
+/// Vertex produced by the tessellator
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct TessellatedVertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub tex_coords: [f32; 2],
+}
+
 /// Tessellated mesh result
 pub struct TessellatedMesh {
-    pub vertices: Vec<Vertex>,
+    pub vertices: Vec<TessellatedVertex>,
     pub indices: Vec<u32>,
     pub stats: TessellationStats,
 }
@@ -428,8 +434,40 @@ pub struct TessellationStats {
 fn morton_2d(x: u32, y: u32) -> u64 {
     let mut result = 0u64;
     for i in 0..16 {
-        result |= ((x & (1 << i)) as u64) << (2 * i);
-        result |= ((y & (1 << i)) as u64) << (2 * i + 1);
+        result |= ((x & (1 << i)) as u64) << i;
+        result |= ((y & (1 << i)) as u64) << (i + 1);
     }
     result
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_tessellation_leaves_hole() {
+        let tessellator = AdaptiveTessellator::new(TessellationParams::default());
+        let region = RingRegion {
+            center: Vector3::new(0.0, 0.0, 0.0),
+            inner_radius: 300.0,
+            outer_radius: 1000.0,
+        };
+        let view = TessellationView {
+            view_pos: Vector3::new(0.0, 50.0, 0.0),
+            viewport_height: 1080.0,
+            fov: std::f32::consts::FRAC_PI_3,
+        };
+
+        let mesh = tessellator.tessellate_ring(&region, &view);
+
+        assert!(mesh.stats.triangle_count > 0);
+        assert_eq!(mesh.indices.len(), mesh.stats.triangle_count * 3);
+        assert!(mesh.vertices.iter().all(|v| {
+            v.position[0].abs() <= region.outer_radius && v.position[2].abs() <= region.outer_radius
+        }));
+        assert!(!mesh
+            .vertices
+            .iter()
+            .any(|v| v.position[0].abs() < 1.0 && v.position[2].abs() < 1.0));
+    }
+}
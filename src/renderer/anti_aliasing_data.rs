@@ -0,0 +1,104 @@
+//! Anti-Aliasing Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! All transformations happen in anti_aliasing_operations.rs
+//!
+//! The main pass either renders multisampled and resolves into the target,
+//! or renders into an offscreen scene texture that a fullscreen FXAA or TAA
+//! pass filters into the target. MSAA requests fall back to FXAA on GPUs
+//! whose target format has no multisample support. Below native render
+//! scale the main pass always draws offscreen at the reduced size and the
+//! post pass (or a plain upscale) fills the target.
+
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use bytemuck::{Pod, Zeroable};
+
+/// Anti-aliasing technique for the main pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AntiAliasingMode {
+    Off,
+    /// Hardware multisampling resolved at the end of the main pass
+    Msaa {
+        samples: u32,
+    },
+    /// Fast approximate AA post pass
+    Fxaa,
+    /// Temporal AA post pass blending with the previous frames
+    Taa,
+}
+
+/// Uniform read by the FXAA and TAA passes
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct AntiAliasingUniform {
+    /// 1 / target size in pixels
+    pub inv_resolution: [f32; 2],
+    /// Weight of the current frame in the TAA blend (1 = ignore history)
+    pub taa_blend: f32,
+    pub fxaa_edge_threshold: f32,
+    pub fxaa_edge_threshold_min: f32,
+    pub fxaa_span_max: f32,
+    pub _padding: [f32; 2],
+}
+
+/// Texture and the view the passes use
+pub struct RenderTexture {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    /// Record of `texture` in the GPU allocation tracker
+    pub allocation: GpuAllocationGuard,
+}
+
+/// Size-dependent textures; rebuilt when the target size or format changes
+pub struct AntiAliasingTargets {
+    pub width: u32,
+    pub height: u32,
+    /// Size the main pass draws at (target size times the render scale)
+    pub scene_width: u32,
+    pub scene_height: u32,
+    pub format: wgpu::TextureFormat,
+    /// Multisampled color the main pass draws into (MSAA only)
+    pub msaa: Option<RenderTexture>,
+    /// Single-sampled scene the post pass reads (FXAA and TAA)
+    pub scene: Option<RenderTexture>,
+    /// TAA history ping-pong pair (empty for other modes)
+    pub history: Vec<RenderTexture>,
+    /// Post pass bind groups; TAA has one per history texture it reads
+    pub bind_groups: Vec<wgpu::BindGroup>,
+}
+
+/// FXAA and TAA pipelines; created the first time a post mode is selected
+pub struct PostAntiAliasingData {
+    pub format: wgpu::TextureFormat,
+    pub uniform_buffer: wgpu::Buffer,
+    pub uniform_allocation: GpuAllocationGuard,
+    pub sampler: wgpu::Sampler,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub fxaa_pipeline: wgpu::RenderPipeline,
+    pub taa_pipeline: wgpu::RenderPipeline,
+    /// Plain bilinear upscale for a reduced render scale without FXAA/TAA
+    pub upscale_pipeline: wgpu::RenderPipeline,
+}
+
+/// Anti-aliasing state for a renderer
+pub struct AntiAliasingData {
+    /// Mode chosen through the settings
+    pub requested: AntiAliasingMode,
+    /// Mode actually in use after falling back for unsupported hardware
+    pub active: AntiAliasingMode,
+    /// Highest MSAA sample count the target format supports (1 = none)
+    pub max_msaa_samples: u32,
+    pub targets: Option<AntiAliasingTargets>,
+    pub post: Option<PostAntiAliasingData>,
+    /// Drives the TAA jitter sequence
+    pub frame_index: u64,
+    /// History texture the next TAA pass reads
+    pub history_read: usize,
+    /// False until a TAA frame has been written to the history
+    pub history_valid: bool,
+    /// Internal resolution scale of the main pass (1.0 = native)
+    pub render_scale: f32,
+    /// Owner of the targets and post pass buffers in the GPU allocation
+    /// tracker
+    pub gpu_owner: GpuOwnerGuard,
+}
@@ -0,0 +1,697 @@
+//! Anti-Aliasing Operations - Pure DOP
+//!
+//! Functions that pick an AA mode the hardware supports, manage the
+//! offscreen targets it needs and encode the FXAA/TAA post passes. The
+//! render scale the adaptive quality lowers is applied here as well.
+
+use super::anti_aliasing_data::{
+    AntiAliasingData, AntiAliasingMode, AntiAliasingTargets, AntiAliasingUniform,
+    PostAntiAliasingData, RenderTexture,
+};
+use super::error::RendererResult;
+use crate::constants::anti_aliasing;
+use crate::memory::{
+    create_tracked_buffer, create_tracked_texture, register_global_gpu_owner, GpuOwnerGuard,
+};
+
+/// Highest MSAA sample count (1, 2, 4 or 8) usable for a format with these
+/// features; the main pass also needs to resolve it
+pub fn max_msaa_samples(features: &wgpu::TextureFormatFeatures) -> u32 {
+    if !features
+        .flags
+        .contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE)
+    {
+        return 1;
+    }
+    [8, 4, 2]
+        .into_iter()
+        .filter(|&samples| samples <= anti_aliasing::MAX_MSAA_SAMPLES)
+        .find(|&samples| features.flags.sample_count_supported(samples))
+        .unwrap_or(1)
+}
+
+/// Mode to run for a requested one: MSAA drops to the largest supported
+/// sample count, or to FXAA when the format cannot be multisampled
+pub fn resolve_anti_aliasing_mode(
+    requested: AntiAliasingMode,
+    max_msaa_samples: u32,
+) -> AntiAliasingMode {
+    match requested {
+        AntiAliasingMode::Msaa { samples } => {
+            let limit = samples.min(max_msaa_samples);
+            if limit < 2 {
+                return AntiAliasingMode::Fxaa;
+            }
+            // Sample counts are powers of two
+            let samples = 1 << (31 - limit.leading_zeros());
+            AntiAliasingMode::Msaa { samples }
+        }
+        other => other,
+    }
+}
+
+/// Sample count pipelines drawing in the main pass must use
+pub fn anti_aliasing_sample_count(mode: AntiAliasingMode) -> u32 {
+    match mode {
+        AntiAliasingMode::Msaa { samples } => samples,
+        _ => 1,
+    }
+}
+
+/// Parse an `anti_aliasing` setting value: "off", "msaa", "msaa2",
+/// "msaa4", "msaa8", "fxaa" or "taa"
+pub fn parse_anti_aliasing_mode(value: &str) -> Option<AntiAliasingMode> {
+    let value = value.trim().to_ascii_lowercase();
+    match value.as_str() {
+        "off" | "none" => Some(AntiAliasingMode::Off),
+        "fxaa" => Some(AntiAliasingMode::Fxaa),
+        "taa" => Some(AntiAliasingMode::Taa),
+        "msaa" => Some(AntiAliasingMode::Msaa {
+            samples: anti_aliasing::DEFAULT_MSAA_SAMPLES,
+        }),
+        _ => {
+            let samples: u32 = value
+                .strip_prefix("msaa")?
+                .trim_end_matches('x')
+                .parse()
+                .ok()?;
+            if samples.is_power_of_two() && (2..=anti_aliasing::MAX_MSAA_SAMPLES).contains(&samples)
+            {
+                Some(AntiAliasingMode::Msaa { samples })
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn halton(mut index: u64, base: u64) -> f32 {
+    let mut fraction = 1.0;
+    let mut result = 0.0;
+    while index > 0 {
+        fraction /= base as f32;
+        result += fraction * (index % base) as f32;
+        index /= base;
+    }
+    result
+}
+
+/// Sub-pixel projection offset (clip space) for a TAA frame; the world pass
+/// adds it to the projection matrix's third column x/y
+pub fn taa_jitter(frame_index: u64, width: u32, height: u32) -> [f32; 2] {
+    let index = frame_index % anti_aliasing::TAA_JITTER_SEQUENCE_LENGTH + 1;
+    let x = halton(index, 2) - 0.5;
+    let y = halton(index, 3) - 0.5;
+    [
+        x * 2.0 / width.max(1) as f32,
+        y * 2.0 / height.max(1) as f32,
+    ]
+}
+
+/// AA state for a target whose format supports up to `max_msaa_samples`
+pub fn create_anti_aliasing(max_msaa_samples: u32) -> AntiAliasingData {
+    AntiAliasingData {
+        requested: AntiAliasingMode::Off,
+        active: AntiAliasingMode::Off,
+        max_msaa_samples,
+        targets: None,
+        post: None,
+        frame_index: 0,
+        history_read: 0,
+        history_valid: false,
+        render_scale: 1.0,
+        gpu_owner: register_global_gpu_owner("anti_aliasing"),
+    }
+}
+
+/// Size the main pass draws at for a target size and render scale; never
+/// below one pixel
+pub fn scaled_render_size(width: u32, height: u32, render_scale: f32) -> (u32, u32) {
+    let scale = render_scale.clamp(anti_aliasing::MIN_RENDER_SCALE, 1.0);
+    let scaled = |size: u32| ((size as f32 * scale).round() as u32).clamp(1, size.max(1));
+    (scaled(width), scaled(height))
+}
+
+/// Change the internal resolution scale; targets are rebuilt on the next
+/// frame when the scene size changes
+pub fn set_anti_aliasing_render_scale(data: &mut AntiAliasingData, render_scale: f32) {
+    let render_scale = render_scale.clamp(anti_aliasing::MIN_RENDER_SCALE, 1.0);
+    if render_scale == data.render_scale {
+        return;
+    }
+    data.render_scale = render_scale;
+    data.history_valid = false;
+}
+
+/// Select a mode; returns the mode actually used. Targets are rebuilt on
+/// the next frame.
+pub fn set_anti_aliasing_mode(
+    data: &mut AntiAliasingData,
+    requested: AntiAliasingMode,
+) -> AntiAliasingMode {
+    let active = resolve_anti_aliasing_mode(requested, data.max_msaa_samples);
+    if active != requested {
+        log::warn!(
+            "[Renderer] {:?} unsupported (max {}x MSAA), using {:?}",
+            requested,
+            data.max_msaa_samples,
+            active
+        );
+    }
+    data.requested = requested;
+    data.active = active;
+    data.targets = None;
+    data.history_valid = false;
+    active
+}
+
+/// Change the multisample support after the target format changed; the
+/// requested mode is resolved again
+pub fn set_anti_aliasing_max_samples(
+    data: &mut AntiAliasingData,
+    max_msaa_samples: u32,
+) -> AntiAliasingMode {
+    data.max_msaa_samples = max_msaa_samples;
+    set_anti_aliasing_mode(data, data.requested)
+}
+
+fn uses_post_pass(mode: AntiAliasingMode) -> bool {
+    matches!(mode, AntiAliasingMode::Fxaa | AntiAliasingMode::Taa)
+}
+
+/// Whether the post pipelines are needed: FXAA/TAA, or a scene drawn
+/// below native resolution that has to be upscaled
+fn needs_post_pipelines(data: &AntiAliasingData) -> bool {
+    uses_post_pass(data.active) || data.render_scale < 1.0
+}
+
+fn create_render_texture(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    label: &str,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+) -> RenderTexture {
+    let usage = if sample_count > 1 {
+        wgpu::TextureUsages::RENDER_ATTACHMENT
+    } else {
+        wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING
+    };
+    let (texture, allocation) = create_tracked_texture(
+        device,
+        owner,
+        &wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        },
+    );
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    RenderTexture {
+        texture,
+        view,
+        allocation,
+    }
+}
+
+fn create_post_anti_aliasing(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    format: wgpu::TextureFormat,
+) -> RendererResult<PostAntiAliasingData> {
+    let shader = crate::gpu::automation::create_gpu_shader(
+        device,
+        "anti_aliasing",
+        include_str!("../shaders/rendering/anti_aliasing.wgsl"),
+    )
+    .map_err(|e| format!("Failed to create anti-aliasing shader: {}", e))?;
+
+    let (uniform_buffer, uniform_allocation) = create_tracked_buffer(
+        device,
+        owner,
+        &wgpu::BufferDescriptor {
+            label: Some("Anti-Aliasing Uniform Buffer"),
+            size: std::mem::size_of::<AntiAliasingUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
+
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("Anti-Aliasing Sampler"),
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    });
+
+    let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    };
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Anti-Aliasing Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+            texture_entry(2),
+            texture_entry(3),
+        ],
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Anti-Aliasing Pipeline Layout"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let color_target = Some(wgpu::ColorTargetState {
+        format,
+        blend: None,
+        write_mask: wgpu::ColorWrites::ALL,
+    });
+    let create_pipeline = |label, entry_point, target_count: usize| {
+        let targets = vec![color_target.clone(); target_count];
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader.module,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader.module,
+                entry_point,
+                targets: &targets,
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        })
+    };
+    let fxaa_pipeline = create_pipeline("FXAA Pipeline", "fs_fxaa", 1);
+    // TAA writes the target and the next history texture
+    let taa_pipeline = create_pipeline("TAA Pipeline", "fs_taa", 2);
+    let upscale_pipeline = create_pipeline("Render Scale Upscale Pipeline", "fs_upscale", 1);
+
+    Ok(PostAntiAliasingData {
+        format,
+        uniform_buffer,
+        uniform_allocation,
+        sampler,
+        bind_group_layout,
+        fxaa_pipeline,
+        taa_pipeline,
+        upscale_pipeline,
+    })
+}
+
+fn create_post_bind_group(
+    device: &wgpu::Device,
+    post: &PostAntiAliasingData,
+    scene: &wgpu::TextureView,
+    history: &wgpu::TextureView,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Anti-Aliasing Bind Group"),
+        layout: &post.bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: post.uniform_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&post.sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::TextureView(scene),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: wgpu::BindingResource::TextureView(history),
+            },
+        ],
+    })
+}
+
+fn create_targets(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    mode: AntiAliasingMode,
+    post: Option<&PostAntiAliasingData>,
+    (width, height): (u32, u32),
+    (scene_width, scene_height): (u32, u32),
+    format: wgpu::TextureFormat,
+) -> AntiAliasingTargets {
+    let mut targets = AntiAliasingTargets {
+        width,
+        height,
+        scene_width,
+        scene_height,
+        format,
+        msaa: None,
+        scene: None,
+        history: Vec::new(),
+        bind_groups: Vec::new(),
+    };
+    let scaled = (scene_width, scene_height) != (width, height);
+
+    match mode {
+        AntiAliasingMode::Off | AntiAliasingMode::Msaa { .. } => {
+            if let AntiAliasingMode::Msaa { samples } = mode {
+                targets.msaa = Some(create_render_texture(
+                    device,
+                    owner,
+                    "MSAA Color Texture",
+                    scene_width,
+                    scene_height,
+                    format,
+                    samples,
+                ));
+            }
+            if scaled {
+                // MSAA resolves into the reduced scene, which is upscaled
+                let scene = create_render_texture(
+                    device,
+                    owner,
+                    "Scaled Scene Texture",
+                    scene_width,
+                    scene_height,
+                    format,
+                    1,
+                );
+                if let Some(post) = post {
+                    targets.bind_groups = vec![create_post_bind_group(
+                        device,
+                        post,
+                        &scene.view,
+                        &scene.view,
+                    )];
+                }
+                targets.scene = Some(scene);
+            }
+        }
+        AntiAliasingMode::Fxaa | AntiAliasingMode::Taa => {
+            let scene = create_render_texture(
+                device,
+                owner,
+                "AA Scene Texture",
+                scene_width,
+                scene_height,
+                format,
+                1,
+            );
+            if let Some(post) = post {
+                if mode == AntiAliasingMode::Taa {
+                    targets.history = (0..2)
+                        .map(|_| {
+                            create_render_texture(
+                                device,
+                                owner,
+                                "TAA History",
+                                width,
+                                height,
+                                format,
+                                1,
+                            )
+                        })
+                        .collect();
+                    targets.bind_groups = targets
+                        .history
+                        .iter()
+                        .map(|history| {
+                            create_post_bind_group(device, post, &scene.view, &history.view)
+                        })
+                        .collect();
+                } else {
+                    // FXAA never reads the history binding
+                    targets.bind_groups = vec![create_post_bind_group(
+                        device,
+                        post,
+                        &scene.view,
+                        &scene.view,
+                    )];
+                }
+            }
+            targets.scene = Some(scene);
+        }
+    }
+    targets
+}
+
+/// Build or resize the AA targets for this frame and upload the post pass
+/// parameters
+pub fn prepare_anti_aliasing(
+    data: &mut AntiAliasingData,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+) -> RendererResult<()> {
+    let mode = data.active;
+    if needs_post_pipelines(data) && data.post.as_ref().map(|post| post.format) != Some(format) {
+        data.post = Some(create_post_anti_aliasing(device, &data.gpu_owner, format)?);
+        data.targets = None;
+    }
+
+    let (scene_width, scene_height) = scaled_render_size(width, height, data.render_scale);
+    let stale = data.targets.as_ref().is_none_or(|targets| {
+        targets.width != width
+            || targets.height != height
+            || targets.scene_width != scene_width
+            || targets.scene_height != scene_height
+            || targets.format != format
+    });
+    if stale {
+        data.targets = Some(create_targets(
+            device,
+            &data.gpu_owner,
+            mode,
+            data.post.as_ref(),
+            (width, height),
+            (scene_width, scene_height),
+            format,
+        ));
+        data.history_valid = false;
+    }
+
+    if let Some(post) = data.post.as_ref().filter(|_| needs_post_pipelines(data)) {
+        // Filters sample the scene, so texel steps are in scene pixels
+        let uniform = AntiAliasingUniform {
+            inv_resolution: [
+                1.0 / scene_width.max(1) as f32,
+                1.0 / scene_height.max(1) as f32,
+            ],
+            taa_blend: if data.history_valid {
+                anti_aliasing::TAA_HISTORY_BLEND
+            } else {
+                1.0
+            },
+            fxaa_edge_threshold: anti_aliasing::FXAA_EDGE_THRESHOLD,
+            fxaa_edge_threshold_min: anti_aliasing::FXAA_EDGE_THRESHOLD_MIN,
+            fxaa_span_max: anti_aliasing::FXAA_SPAN_MAX,
+            _padding: [0.0; 2],
+        };
+        queue.write_buffer(&post.uniform_buffer, 0, bytemuck::bytes_of(&uniform));
+    }
+    Ok(())
+}
+
+/// View the main pass draws into and the view MSAA resolves into
+type SceneAttachment<'a> = (&'a wgpu::TextureView, Option<&'a wgpu::TextureView>);
+
+/// Color attachment for the main pass
+pub fn scene_color_attachment<'a>(
+    data: &'a AntiAliasingData,
+    output: &'a wgpu::TextureView,
+) -> SceneAttachment<'a> {
+    let Some(targets) = data.targets.as_ref() else {
+        return (output, None);
+    };
+    if let Some(msaa) = &targets.msaa {
+        let resolve = targets.scene.as_ref().map_or(output, |scene| &scene.view);
+        return (&msaa.view, Some(resolve));
+    }
+    match &targets.scene {
+        Some(scene) => (&scene.view, None),
+        None => (output, None),
+    }
+}
+
+/// Filter the scene into `output` with the FXAA or TAA pass, or upscale a
+/// scene drawn below native resolution; does nothing otherwise
+pub fn encode_anti_aliasing_pass(
+    data: &AntiAliasingData,
+    encoder: &mut wgpu::CommandEncoder,
+    output: &wgpu::TextureView,
+) {
+    let (Some(post), Some(targets)) = (data.post.as_ref(), data.targets.as_ref()) else {
+        return;
+    };
+    let (pipeline, bind_group, history_target) = match data.active {
+        AntiAliasingMode::Fxaa => (&post.fxaa_pipeline, targets.bind_groups.first(), None),
+        AntiAliasingMode::Taa => (
+            &post.taa_pipeline,
+            targets.bind_groups.get(data.history_read),
+            targets.history.get(1 - data.history_read),
+        ),
+        _ if targets.scene.is_some() => (&post.upscale_pipeline, targets.bind_groups.first(), None),
+        _ => return,
+    };
+    let Some(bind_group) = bind_group else {
+        return;
+    };
+
+    let attachment = |view| {
+        Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: wgpu::StoreOp::Store,
+            },
+        })
+    };
+    let mut color_attachments = vec![attachment(output)];
+    if let Some(history) = history_target {
+        color_attachments.push(attachment(&history.view));
+    }
+
+    let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Anti-Aliasing Pass"),
+        color_attachments: &color_attachments,
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    pass.set_pipeline(pipeline);
+    pass.set_bind_group(0, bind_group, &[]);
+    pass.draw(0..3, 0..1);
+}
+
+/// Advance the jitter sequence and swap the TAA history after a frame
+pub fn finish_anti_aliasing_frame(data: &mut AntiAliasingData) {
+    data.frame_index = data.frame_index.wrapping_add(1);
+    if data.active == AntiAliasingMode::Taa && data.targets.is_some() {
+        data.history_read = 1 - data.history_read;
+        data.history_valid = true;
+    }
+}
+
+/// Projection jitter for the current frame (zero unless TAA is active)
+pub fn anti_aliasing_jitter(data: &AntiAliasingData) -> [f32; 2] {
+    match (data.active, data.targets.as_ref()) {
+        (AntiAliasingMode::Taa, Some(targets)) => {
+            taa_jitter(data.frame_index, targets.scene_width, targets.scene_height)
+        }
+        _ => [0.0; 2],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_msaa_falls_back_to_supported_modes() {
+        let msaa8 = AntiAliasingMode::Msaa { samples: 8 };
+        assert_eq!(
+            resolve_anti_aliasing_mode(msaa8, 4),
+            AntiAliasingMode::Msaa { samples: 4 }
+        );
+        assert_eq!(resolve_anti_aliasing_mode(msaa8, 1), AntiAliasingMode::Fxaa);
+        assert_eq!(
+            resolve_anti_aliasing_mode(AntiAliasingMode::Taa, 1),
+            AntiAliasingMode::Taa
+        );
+        assert_eq!(anti_aliasing_sample_count(AntiAliasingMode::Fxaa), 1);
+    }
+
+    #[test]
+    fn test_parse_setting_values() {
+        assert_eq!(
+            parse_anti_aliasing_mode("MSAA4"),
+            Some(AntiAliasingMode::Msaa { samples: 4 })
+        );
+        assert_eq!(
+            parse_anti_aliasing_mode("msaa8x"),
+            Some(AntiAliasingMode::Msaa { samples: 8 })
+        );
+        assert_eq!(
+            parse_anti_aliasing_mode("fxaa"),
+            Some(AntiAliasingMode::Fxaa)
+        );
+        assert_eq!(
+            parse_anti_aliasing_mode(" off "),
+            Some(AntiAliasingMode::Off)
+        );
+        assert_eq!(parse_anti_aliasing_mode("msaa3"), None);
+        assert_eq!(parse_anti_aliasing_mode("smaa"), None);
+    }
+
+    #[test]
+    fn test_jitter_stays_within_a_pixel() {
+        for frame in 0..32 {
+            let [x, y] = taa_jitter(frame, 1920, 1080);
+            assert!(x.abs() <= 1.0 / 1920.0);
+            assert!(y.abs() <= 1.0 / 1080.0);
+        }
+        assert_ne!(taa_jitter(0, 100, 100), taa_jitter(1, 100, 100));
+    }
+
+    #[test]
+    fn test_render_scale_shrinks_the_scene() {
+        assert_eq!(scaled_render_size(1920, 1080, 1.0), (1920, 1080));
+        assert_eq!(scaled_render_size(1920, 1080, 0.5), (960, 540));
+        // Clamped to the minimum scale and at least one pixel
+        assert_eq!(scaled_render_size(1920, 1080, 0.0), (480, 270));
+        assert_eq!(scaled_render_size(1, 1, 0.25), (1, 1));
+
+        let mut data = create_anti_aliasing(4);
+        set_anti_aliasing_render_scale(&mut data, 2.0);
+        assert_eq!(data.render_scale, 1.0);
+        set_anti_aliasing_render_scale(&mut data, 0.75);
+        assert_eq!(data.render_scale, 0.75);
+    }
+}
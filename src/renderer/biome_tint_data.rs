@@ -0,0 +1,47 @@
+//! Biome Tint Data - Pure DOP
+//!
+//! Per-biome grass, foliage and water colors, and the per-chunk maps of
+//! those colors blended across biome borders that meshing samples from.
+//! Operations live in biome_tint_operations.rs.
+//!
+//! NO METHODS - just data.
+
+/// Linear RGB color (0.0-1.0)
+pub type TintColor = [f32; 3];
+
+/// Which biome color a block face takes. The order is the layout of the
+/// tint kinds in a packed chunk tint map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TintKind {
+    Grass = 0,
+    Foliage = 1,
+    Water = 2,
+}
+
+/// Colors of one biome
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiomeColors {
+    /// Biome name, matching the names `WeatherManager` and ore placement use
+    pub name: String,
+    pub grass: TintColor,
+    pub foliage: TintColor,
+    pub water: TintColor,
+}
+
+/// Registered biome colors; a biome id is an index into `biomes`.
+/// Unknown ids use the first entry.
+#[derive(Debug, Clone, Default)]
+pub struct BiomeColorMap {
+    pub biomes: Vec<BiomeColors>,
+}
+
+/// Blended colors at every vertex corner of one chunk's columns.
+/// Corners are indexed `z * corners_per_side + x` in chunk-local
+/// coordinates, 0..=chunk_size on each axis.
+#[derive(Debug, Clone, Default)]
+pub struct ChunkTintMap {
+    pub corners_per_side: u32,
+    pub grass: Vec<TintColor>,
+    pub foliage: Vec<TintColor>,
+    pub water: Vec<TintColor>,
+}
@@ -0,0 +1,265 @@
+//! Biome Tint Operations - Pure DOP Functions
+//!
+//! Build a `ChunkTintMap` per chunk from the biome of each column around
+//! it, then either sample it while meshing on the CPU or pack it for the
+//! GPU mesher. Every corner averages the columns within the blend radius,
+//! so colors fade over a border instead of switching at it, and the
+//! corners two chunks share get the same color.
+
+use super::biome_tint_data::{BiomeColorMap, BiomeColors, ChunkTintMap, TintColor, TintKind};
+use super::gpu_meshing::pack_color_light;
+use crate::constants::biome_tint::TINT_KIND_COUNT;
+use crate::world::core::{BlockId, ChunkPos};
+use crate::world::SeasonParams;
+
+/// Color used when no biome colors are registered
+const NEUTRAL_TINT: TintColor = [1.0, 1.0, 1.0];
+
+const TINT_KINDS: [TintKind; TINT_KIND_COUNT] =
+    [TintKind::Grass, TintKind::Foliage, TintKind::Water];
+
+/// Colors for the built-in biomes; `temperate` comes first and is the
+/// fallback for unknown ids
+pub fn default_biome_color_map() -> BiomeColorMap {
+    let biome = |name: &str, grass, foliage, water| BiomeColors {
+        name: name.to_string(),
+        grass,
+        foliage,
+        water,
+    };
+    BiomeColorMap {
+        biomes: vec![
+            biome(
+                "temperate",
+                [0.36, 0.70, 0.28],
+                [0.28, 0.58, 0.22],
+                [0.22, 0.42, 0.80],
+            ),
+            biome(
+                "desert",
+                [0.75, 0.72, 0.42],
+                [0.68, 0.64, 0.36],
+                [0.30, 0.58, 0.72],
+            ),
+            biome(
+                "tundra",
+                [0.50, 0.68, 0.58],
+                [0.44, 0.60, 0.52],
+                [0.22, 0.36, 0.66],
+            ),
+            biome(
+                "taiga",
+                [0.34, 0.56, 0.40],
+                [0.24, 0.46, 0.32],
+                [0.18, 0.34, 0.62],
+            ),
+            biome(
+                "rainforest",
+                [0.26, 0.74, 0.18],
+                [0.18, 0.62, 0.12],
+                [0.20, 0.50, 0.56],
+            ),
+        ],
+    }
+}
+
+/// Add or replace a biome's colors and return its id
+pub fn register_biome_colors(map: &mut BiomeColorMap, colors: BiomeColors) -> u8 {
+    if let Some(index) = map.biomes.iter().position(|b| b.name == colors.name) {
+        map.biomes[index] = colors;
+        return index as u8;
+    }
+    map.biomes.push(colors);
+    (map.biomes.len() - 1) as u8
+}
+
+/// Id of a biome by name
+pub fn biome_id(map: &BiomeColorMap, name: &str) -> Option<u8> {
+    map.biomes
+        .iter()
+        .position(|b| b.name == name)
+        .map(|index| index as u8)
+}
+
+/// Color of one tint kind in a biome, falling back to the first biome
+pub fn biome_tint(map: &BiomeColorMap, biome: u8, kind: TintKind) -> TintColor {
+    let Some(colors) = map.biomes.get(biome as usize).or(map.biomes.first()) else {
+        return NEUTRAL_TINT;
+    };
+    match kind {
+        TintKind::Grass => colors.grass,
+        TintKind::Foliage => colors.foliage,
+        TintKind::Water => colors.water,
+    }
+}
+
+/// Biome colors with a season's grass and foliage tints applied; water
+/// keeps its color. Rebuild chunk tint maps after the season params change.
+pub fn apply_season_tint(map: &BiomeColorMap, season: &SeasonParams) -> BiomeColorMap {
+    let tint = |color: TintColor, by: [f32; 3]| -> TintColor {
+        std::array::from_fn(|i| (color[i] * by[i]).clamp(0.0, 1.0))
+    };
+    BiomeColorMap {
+        biomes: map
+            .biomes
+            .iter()
+            .map(|colors| BiomeColors {
+                name: colors.name.clone(),
+                grass: tint(colors.grass, season.grass_tint),
+                foliage: tint(colors.foliage, season.foliage_tint),
+                water: colors.water,
+            })
+            .collect(),
+    }
+}
+
+/// Which biome color a block takes, `None` for blocks with fixed colors
+pub fn tint_kind_for_block(block: BlockId) -> Option<TintKind> {
+    match block {
+        BlockId::GRASS | BlockId::TALL_GRASS => Some(TintKind::Grass),
+        BlockId::LEAVES => Some(TintKind::Foliage),
+        BlockId::WATER => Some(TintKind::Water),
+        _ => None,
+    }
+}
+
+/// Blend biome colors for every vertex corner of a chunk.
+///
+/// `biome_at(x, z)` gives the biome id of the world column at x, z. Each
+/// corner averages the `2 * blend_radius` square of columns around it;
+/// `constants::biome_tint::BLEND_RADIUS` is the usual radius.
+pub fn build_chunk_tint_map(
+    map: &BiomeColorMap,
+    chunk_pos: ChunkPos,
+    chunk_size: u32,
+    blend_radius: u32,
+    biome_at: impl Fn(i32, i32) -> u8,
+) -> ChunkTintMap {
+    let radius = blend_radius.max(1) as usize;
+    let size = chunk_size as usize;
+    let window = 2 * radius;
+    let span = size + window;
+    let stride = span + 1;
+    let origin_x = chunk_pos.x * chunk_size as i32 - radius as i32;
+    let origin_z = chunk_pos.z * chunk_size as i32 - radius as i32;
+
+    // Summed-area table per kind over the sampled columns
+    let mut sums: [Vec<TintColor>; TINT_KIND_COUNT] =
+        std::array::from_fn(|_| vec![[0.0; 3]; stride * stride]);
+    for z in 0..span {
+        for x in 0..span {
+            let biome = biome_at(origin_x + x as i32, origin_z + z as i32);
+            for (kind, table) in TINT_KINDS.iter().zip(sums.iter_mut()) {
+                let color = biome_tint(map, biome, *kind);
+                let above = table[z * stride + x + 1];
+                let left = table[(z + 1) * stride + x];
+                let corner = table[z * stride + x];
+                table[(z + 1) * stride + x + 1] =
+                    std::array::from_fn(|c| color[c] + above[c] + left[c] - corner[c]);
+            }
+        }
+    }
+
+    let corners = size + 1;
+    let area = (window * window) as f32;
+    let average = |table: &[TintColor], x: usize, z: usize| -> TintColor {
+        let far = table[(z + window) * stride + x + window];
+        let top = table[z * stride + x + window];
+        let left = table[(z + window) * stride + x];
+        let near = table[z * stride + x];
+        std::array::from_fn(|c| (far[c] - top[c] - left[c] + near[c]) / area)
+    };
+
+    let [grass, foliage, water] = sums.map(|table| {
+        (0..corners)
+            .flat_map(|z| (0..corners).map(move |x| (x, z)))
+            .map(|(x, z)| average(&table, x, z))
+            .collect()
+    });
+    ChunkTintMap {
+        corners_per_side: corners as u32,
+        grass,
+        foliage,
+        water,
+    }
+}
+
+/// Corner colors of one tint kind
+pub fn tint_corners(tints: &ChunkTintMap, kind: TintKind) -> &[TintColor] {
+    match kind {
+        TintKind::Grass => &tints.grass,
+        TintKind::Foliage => &tints.foliage,
+        TintKind::Water => &tints.water,
+    }
+}
+
+/// Tint at a chunk-local x, z (voxels), bilinear between corners
+pub fn sample_chunk_tint(tints: &ChunkTintMap, kind: TintKind, x: f32, z: f32) -> TintColor {
+    let side = tints.corners_per_side as usize;
+    let corners = tint_corners(tints, kind);
+    if side == 0 || corners.len() != side * side {
+        return NEUTRAL_TINT;
+    }
+    let last = (tints.corners_per_side - 1) as f32;
+    let x = x.clamp(0.0, last);
+    let z = z.clamp(0.0, last);
+    let (x0, z0) = (x.floor() as usize, z.floor() as usize);
+    let (x1, z1) = ((x0 + 1).min(last as usize), (z0 + 1).min(last as usize));
+    let (fx, fz) = (x - x0 as f32, z - z0 as f32);
+
+    let at = |x: usize, z: usize| corners[z * side + x];
+    let (c00, c10, c01, c11) = (at(x0, z0), at(x1, z0), at(x0, z1), at(x1, z1));
+    std::array::from_fn(|c| {
+        let near = c00[c] + (c10[c] - c00[c]) * fx;
+        let far = c01[c] + (c11[c] - c01[c]) * fx;
+        near + (far - near) * fz
+    })
+}
+
+/// u32 count of a packed tint map for a chunk size
+pub fn packed_tint_map_len(chunk_size: u32) -> usize {
+    let corners = chunk_size as usize + 1;
+    TINT_KIND_COUNT * corners * corners
+}
+
+/// Tint map as RGBA8 words for the GPU mesher: grass corners, then
+/// foliage, then water
+pub fn pack_chunk_tint_map(tints: &ChunkTintMap) -> Vec<u32> {
+    TINT_KINDS
+        .iter()
+        .flat_map(|&kind| tint_corners(tints, kind).iter())
+        .map(|color| pack_color_light(color[0], color[1], color[2], 1.0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tints_fade_across_biome_border_and_match_between_chunks() {
+        let map = default_biome_color_map();
+        let desert = biome_id(&map, "desert").expect("desert");
+        let temperate = biome_id(&map, "temperate").expect("temperate");
+        let biome_at = |x: i32, _z: i32| if x < 0 { desert } else { temperate };
+
+        let east = build_chunk_tint_map(&map, ChunkPos { x: 0, y: 0, z: 0 }, 16, 4, biome_at);
+        let west = build_chunk_tint_map(&map, ChunkPos { x: -1, y: 0, z: 0 }, 16, 4, biome_at);
+
+        let grass =
+            |tints: &ChunkTintMap, x: f32| sample_chunk_tint(tints, TintKind::Grass, x, 3.0)[0];
+        let desert_red = biome_tint(&map, desert, TintKind::Grass)[0];
+        let temperate_red = biome_tint(&map, temperate, TintKind::Grass)[0];
+
+        // Half way on the border, fully temperate a blend radius in
+        assert!((grass(&east, 0.0) - (desert_red + temperate_red) / 2.0).abs() < 1e-4);
+        assert!((grass(&east, 4.0) - temperate_red).abs() < 1e-4);
+        assert!(grass(&east, 1.0) > grass(&east, 2.0));
+        // The shared border corners agree, so there is no seam
+        assert!((grass(&west, 16.0) - grass(&east, 0.0)).abs() < 1e-5);
+
+        let packed = pack_chunk_tint_map(&east);
+        assert_eq!(packed.len(), packed_tint_map_len(16));
+        assert_eq!(packed[4] >> 24, (temperate_red * 255.0) as u32);
+    }
+}
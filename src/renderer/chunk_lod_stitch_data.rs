@@ -0,0 +1,54 @@
+//! Chunk LOD Stitch Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Level tracking and skirt building live in chunk_lod_stitch_operations.rs
+//!
+//! Chunks meshed at a coarser LOD level than a neighbor leave T-junction
+//! cracks along the shared face. The LOD tracker keeps every chunk's level
+//! and, per chunk, a mask of the faces whose neighbor is finer. Those faces
+//! get skirts: quads hanging from the mesh's boundary edges, one coarse
+//! cell deep, that cover whatever gap the finer neighbor leaves. Chunks
+//! whose mask changes are queued so their skirts are rebuilt.
+
+use crate::gpu::buffer_layouts::mesh::Vertex;
+use crate::world::core::ChunkPos;
+use std::collections::{HashMap, HashSet};
+
+/// One bit per chunk face, indexed like `gpu_meshing::FaceDirection`
+/// (+X, -X, +Y, -Y, +Z, -Z); a set bit means the face needs a skirt
+pub type StitchMask = u8;
+
+/// Faces of a chunk
+pub const CHUNK_FACE_COUNT: usize = 6;
+
+/// Level of the chunk across each face, `None` where none is tracked
+pub type NeighborLevels = [Option<u32>; CHUNK_FACE_COUNT];
+
+/// Skirt settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkLodStitchConfig {
+    /// Coarsest level chunks are clamped to
+    pub max_level: u32,
+    /// Skirt depth in cells of the chunk's own level
+    pub skirt_depth_cells: f32,
+}
+
+/// LOD level of every meshed chunk and the stitching each one needs
+#[derive(Debug, Clone)]
+pub struct ChunkLodStitchData {
+    pub config: ChunkLodStitchConfig,
+    /// LOD level per chunk (0 = full detail)
+    pub levels: HashMap<ChunkPos, u32>,
+    /// Faces needing skirts; chunks without stitching are absent
+    pub masks: HashMap<ChunkPos, StitchMask>,
+    /// Chunks whose mask changed since the last `take_restitch_chunks`
+    pub restitch: HashSet<ChunkPos>,
+}
+
+/// Skirt geometry for one chunk, in the chunk mesh's space; drawn with
+/// the chunk's own mesh
+#[derive(Debug, Clone, Default)]
+pub struct StitchGeometry {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+}
@@ -0,0 +1,306 @@
+//! Chunk LOD Stitch Operations - Pure functions over ChunkLodStitchData
+//!
+//! `set_chunk_lod` records the level a chunk was meshed at and refreshes
+//! the stitch masks of the chunk and its six neighbors; every chunk whose
+//! mask changed is queued. The mesher drains the queue with
+//! `take_restitch_chunks` and rebuilds those chunks' skirts with
+//! `stitch_chunk_mesh`. Only the coarser side of a boundary gets skirts:
+//! its cells span several of the finer neighbor's, so its edges are the
+//! ones that leave the gap.
+
+use super::chunk_lod_stitch_data::{
+    ChunkLodStitchConfig, ChunkLodStitchData, NeighborLevels, StitchGeometry, StitchMask,
+    CHUNK_FACE_COUNT,
+};
+use crate::constants::chunk_lod::{BOUNDARY_EPSILON, MAX_CHUNK_LOD, SKIRT_DEPTH_CELLS};
+use crate::gpu::buffer_layouts::mesh::Vertex;
+use crate::world::core::ChunkPos;
+use std::collections::{HashMap, HashSet};
+
+/// Neighbor offset of each face, indexed like `gpu_meshing::FaceDirection`
+const FACE_OFFSETS: [[i32; 3]; CHUNK_FACE_COUNT] = [
+    [1, 0, 0],
+    [-1, 0, 0],
+    [0, 1, 0],
+    [0, -1, 0],
+    [0, 0, 1],
+    [0, 0, -1],
+];
+
+/// One coarse cell of skirt at every level up to `MAX_CHUNK_LOD`
+pub fn default_chunk_lod_stitch_config() -> ChunkLodStitchConfig {
+    ChunkLodStitchConfig {
+        max_level: MAX_CHUNK_LOD,
+        skirt_depth_cells: SKIRT_DEPTH_CELLS,
+    }
+}
+
+/// Tracker with no chunks
+pub fn create_chunk_lod_stitch(config: ChunkLodStitchConfig) -> ChunkLodStitchData {
+    ChunkLodStitchData {
+        config,
+        levels: HashMap::new(),
+        masks: HashMap::new(),
+        restitch: HashSet::new(),
+    }
+}
+
+/// Edge length of one mesh cell at `level` (voxels)
+pub fn lod_cell_size(level: u32) -> f32 {
+    (1u32 << level.min(MAX_CHUNK_LOD)) as f32
+}
+
+/// Chunk sharing face `face` of `pos`
+pub fn chunk_face_neighbor(pos: ChunkPos, face: usize) -> ChunkPos {
+    let [dx, dy, dz] = FACE_OFFSETS[face % CHUNK_FACE_COUNT];
+    pos.offset(dx, dy, dz)
+}
+
+/// Faces of a chunk at `level` whose neighbor is meshed finer
+pub fn compute_stitch_mask(level: u32, neighbor_levels: &NeighborLevels) -> StitchMask {
+    neighbor_levels
+        .iter()
+        .enumerate()
+        .filter(|(_, neighbor)| neighbor.is_some_and(|neighbor| neighbor < level))
+        .fold(0, |mask, (face, _)| mask | (1 << face))
+}
+
+/// Level a chunk was meshed at, if it is tracked
+pub fn chunk_lod_level(data: &ChunkLodStitchData, pos: ChunkPos) -> Option<u32> {
+    data.levels.get(&pos).copied()
+}
+
+/// Faces of a chunk that need skirts
+pub fn chunk_stitch_mask(data: &ChunkLodStitchData, pos: ChunkPos) -> StitchMask {
+    data.masks.get(&pos).copied().unwrap_or(0)
+}
+
+/// Record the level a chunk is meshed at; returns the level after
+/// clamping to `config.max_level`
+pub fn set_chunk_lod(data: &mut ChunkLodStitchData, pos: ChunkPos, level: u32) -> u32 {
+    let level = level.min(data.config.max_level);
+    if data.levels.insert(pos, level) == Some(level) {
+        return level;
+    }
+    refresh_stitch_masks(data, pos);
+    level
+}
+
+/// Forget an unloaded chunk; its neighbors lose any skirts facing it
+pub fn remove_chunk_lod(data: &mut ChunkLodStitchData, pos: ChunkPos) {
+    if data.levels.remove(&pos).is_none() {
+        return;
+    }
+    data.masks.remove(&pos);
+    data.restitch.remove(&pos);
+    refresh_stitch_masks(data, pos);
+}
+
+/// Chunks whose skirts must be rebuilt, emptying the queue
+pub fn take_restitch_chunks(data: &mut ChunkLodStitchData) -> Vec<ChunkPos> {
+    data.restitch.drain().collect()
+}
+
+/// Skirts for a tracked chunk's mesh, chosen from its neighbors' levels;
+/// empty when no neighbor is finer
+pub fn stitch_chunk_mesh(
+    data: &ChunkLodStitchData,
+    pos: ChunkPos,
+    chunk_size: f32,
+    vertices: &[Vertex],
+    indices: &[u32],
+) -> StitchGeometry {
+    match chunk_lod_level(data, pos) {
+        Some(level) => build_stitch_skirts(
+            &data.config,
+            chunk_size,
+            level,
+            chunk_stitch_mask(data, pos),
+            vertices,
+            indices,
+        ),
+        None => StitchGeometry::default(),
+    }
+}
+
+/// Hang a skirt from every mesh edge lying on a masked chunk face. The
+/// skirt stays in the face plane and runs away from the surface (against
+/// its normal), `skirt_depth_cells` cells of `level` deep, facing out of
+/// the chunk. Edges of triangles lying in the face plane get none.
+pub fn build_stitch_skirts(
+    config: &ChunkLodStitchConfig,
+    chunk_size: f32,
+    level: u32,
+    mask: StitchMask,
+    vertices: &[Vertex],
+    indices: &[u32],
+) -> StitchGeometry {
+    let mut skirts = StitchGeometry::default();
+    if mask == 0 {
+        return skirts;
+    }
+    let depth = config.skirt_depth_cells * lod_cell_size(level);
+    let mut stitched = HashSet::new();
+
+    for triangle in indices.chunks_exact(3) {
+        for corner in 0..3 {
+            let (a, b) = (triangle[corner], triangle[(corner + 1) % 3]);
+            let (Some(start), Some(end)) = (vertices.get(a as usize), vertices.get(b as usize))
+            else {
+                continue;
+            };
+            for face in (0..CHUNK_FACE_COUNT).filter(|face| mask & (1 << face) != 0) {
+                if !on_chunk_face(start.position, face, chunk_size)
+                    || !on_chunk_face(end.position, face, chunk_size)
+                    || !stitched.insert((a.min(b), a.max(b), face))
+                {
+                    continue;
+                }
+                push_skirt_quad(&mut skirts, start, end, face, depth);
+            }
+        }
+    }
+    skirts
+}
+
+/// Recompute the masks of `pos` and its neighbors, queueing changes
+fn refresh_stitch_masks(data: &mut ChunkLodStitchData, pos: ChunkPos) {
+    refresh_stitch_mask(data, pos);
+    for face in 0..CHUNK_FACE_COUNT {
+        refresh_stitch_mask(data, chunk_face_neighbor(pos, face));
+    }
+}
+
+fn refresh_stitch_mask(data: &mut ChunkLodStitchData, pos: ChunkPos) {
+    let Some(level) = chunk_lod_level(data, pos) else {
+        return;
+    };
+    let neighbor_levels: NeighborLevels =
+        std::array::from_fn(|face| chunk_lod_level(data, chunk_face_neighbor(pos, face)));
+    let mask = compute_stitch_mask(level, &neighbor_levels);
+    if mask == chunk_stitch_mask(data, pos) {
+        return;
+    }
+    if mask == 0 {
+        data.masks.remove(&pos);
+    } else {
+        data.masks.insert(pos, mask);
+    }
+    data.restitch.insert(pos);
+}
+
+/// Whether a chunk-local position lies on chunk face `face`
+fn on_chunk_face(position: [f32; 3], face: usize, chunk_size: f32) -> bool {
+    let plane = if face.is_multiple_of(2) {
+        chunk_size
+    } else {
+        0.0
+    };
+    (position[face / 2] - plane).abs() <= BOUNDARY_EPSILON
+}
+
+fn push_skirt_quad(
+    skirts: &mut StitchGeometry,
+    start: &Vertex,
+    end: &Vertex,
+    face: usize,
+    depth: f32,
+) {
+    let axis = face / 2;
+    let outward = if face.is_multiple_of(2) { 1.0 } else { -1.0 };
+
+    // Run against the surface normal, flattened into the face plane
+    let mut direction: [f32; 3] = std::array::from_fn(|i| -(start.normal[i] + end.normal[i]));
+    direction[axis] = 0.0;
+    let length = direction.iter().map(|d| d * d).sum::<f32>().sqrt();
+    if length < 0.5 {
+        return;
+    }
+    let drop = |vertex: &Vertex| Vertex {
+        position: std::array::from_fn(|i| vertex.position[i] + direction[i] / length * depth),
+        ..*vertex
+    };
+    let quad = [*start, *end, drop(end), drop(start)];
+
+    // Wind the quad to face out of the chunk
+    let edge = sub(quad[1].position, quad[0].position);
+    let down = sub(quad[2].position, quad[1].position);
+    let facing =
+        edge[(axis + 1) % 3] * down[(axis + 2) % 3] - edge[(axis + 2) % 3] * down[(axis + 1) % 3];
+    let base = skirts.vertices.len() as u32;
+    skirts.vertices.extend_from_slice(&quad);
+    let order: [u32; 6] = if facing * outward > 0.0 {
+        [0, 1, 2, 0, 2, 3]
+    } else {
+        [0, 2, 1, 0, 3, 2]
+    };
+    skirts.indices.extend(order.iter().map(|i| base + i));
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    std::array::from_fn(|i| a[i] - b[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertex(position: [f32; 3], normal: [f32; 3]) -> Vertex {
+        Vertex {
+            position,
+            normal,
+            tex_coords: [0.0, 0.0],
+        }
+    }
+
+    #[test]
+    fn coarse_side_of_a_band_boundary_is_stitched() {
+        let mut data = create_chunk_lod_stitch(default_chunk_lod_stitch_config());
+        let coarse = ChunkPos::new(0, 0, 0);
+        let fine = chunk_face_neighbor(coarse, 0);
+
+        set_chunk_lod(&mut data, coarse, 1);
+        assert!(take_restitch_chunks(&mut data).is_empty());
+        set_chunk_lod(&mut data, fine, 0);
+        assert_eq!(chunk_stitch_mask(&data, coarse), 1);
+        assert_eq!(chunk_stitch_mask(&data, fine), 0);
+        assert_eq!(take_restitch_chunks(&mut data), vec![coarse]);
+
+        // Flat top surface spanning the chunk at y = 10
+        let up = [0.0, 1.0, 0.0];
+        let vertices = [
+            vertex([0.0, 10.0, 0.0], up),
+            vertex([0.0, 10.0, 32.0], up),
+            vertex([32.0, 10.0, 32.0], up),
+            vertex([32.0, 10.0, 0.0], up),
+        ];
+        let indices = [0, 1, 2, 0, 2, 3];
+        let skirts = stitch_chunk_mesh(&data, coarse, 32.0, &vertices, &indices);
+        assert_eq!(skirts.vertices.len(), 4);
+        assert_eq!(skirts.indices.len(), 6);
+        assert!(skirts
+            .vertices
+            .iter()
+            .all(|v| v.position[0] == 32.0 && (v.position[1] == 10.0 || v.position[1] == 8.0)));
+
+        // The skirt faces +X, toward the finer neighbor
+        let p = |i: usize| skirts.vertices[skirts.indices[i] as usize].position;
+        let (e1, e2) = (sub(p(1), p(0)), sub(p(2), p(0)));
+        assert!(e1[1] * e2[2] - e1[2] * e2[1] > 0.0);
+
+        // Neighbor turns coarser: the stitching moves to its side
+        set_chunk_lod(&mut data, fine, 9);
+        assert_eq!(chunk_lod_level(&data, fine), Some(MAX_CHUNK_LOD));
+        assert_eq!(chunk_stitch_mask(&data, coarse), 0);
+        assert_eq!(chunk_stitch_mask(&data, fine), 1 << 1);
+        let mut restitch = take_restitch_chunks(&mut data);
+        restitch.sort_by_key(|pos| pos.x);
+        assert_eq!(restitch, vec![coarse, fine]);
+        assert!(stitch_chunk_mesh(&data, coarse, 32.0, &vertices, &indices)
+            .indices
+            .is_empty());
+
+        remove_chunk_lod(&mut data, coarse);
+        assert_eq!(chunk_stitch_mask(&data, fine), 0);
+    }
+}
@@ -0,0 +1,75 @@
+//! Cloud Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! All transformations happen in cloud_operations.rs
+//!
+//! A noise-based cloud plane at a fixed altitude, drifting with the weather's
+//! wind. The same density function (`cloud_shadow.wgsl`) is evaluated by the
+//! lighting pass along the sun direction to darken terrain under clouds.
+
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use bytemuck::{Pod, Zeroable};
+
+/// Cloud layer settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CloudConfig {
+    /// Draw clouds and cast their shadows; off on low-end hardware
+    pub enabled: bool,
+    /// Plane altitude (voxels)
+    pub altitude: f32,
+    /// Apparent thickness used for shading (voxels)
+    pub thickness: f32,
+    /// Noise frequency (cycles per voxel)
+    pub noise_scale: f32,
+    /// Half-width of the plane around the camera (voxels)
+    pub half_extent: f32,
+    /// Density ramp width above the coverage threshold
+    pub edge_softness: f32,
+    /// Light removed under the densest clouds (0 = no shadows)
+    pub shadow_strength: f32,
+    pub opacity: f32,
+    /// Cloud drift relative to the surface wind speed
+    pub wind_drift_scale: f32,
+}
+
+/// Uniform shared by the cloud shader and the lighting pass
+/// (`CloudUniform` in cloud_shadow.wgsl)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct CloudUniform {
+    pub view_proj: [[f32; 4]; 4],
+    pub camera_pos: [f32; 4],
+    /// xyz = direction toward the sun, w = shadow strength
+    pub sun_direction: [f32; 4],
+    /// rgb = sun color, a = opacity
+    pub sun_color: [f32; 4],
+    /// rgb = ambient sky color
+    pub ambient_color: [f32; 4],
+    /// altitude, thickness, noise scale, coverage
+    pub layer: [f32; 4],
+    /// wind offset x, wind offset z, half-extent, edge softness
+    pub wind: [f32; 4],
+}
+
+/// GPU resources and state for the cloud layer
+pub struct CloudData {
+    pub config: CloudConfig,
+    pub uniform: CloudUniform,
+    /// Accumulated wind drift in world XZ (voxels)
+    pub wind_offset: [f32; 2],
+    /// Coverage from the latest weather update
+    pub coverage: f32,
+    /// Whether the clouds cast shadows (the `shadows` feature flag)
+    pub shadows_enabled: bool,
+
+    pub uniform_buffer: wgpu::Buffer,
+    pub render_pipeline: wgpu::RenderPipeline,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub bind_group: wgpu::BindGroup,
+    /// `uniform_buffer`, the pass's one tracked allocation
+    pub gpu_allocations: Vec<GpuAllocationGuard>,
+    pub gpu_owner: GpuOwnerGuard,
+
+    /// False until the first update has written the uniform
+    pub updated: bool,
+}
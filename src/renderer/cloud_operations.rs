@@ -0,0 +1,399 @@
+//! Cloud Operations - Pure DOP
+//!
+//! Functions that create, animate and draw the cloud layer, plus a CPU copy
+//! of the cloud density for gameplay queries (e.g. "is this spot shaded").
+
+use super::cloud_data::{CloudConfig, CloudData, CloudUniform};
+use super::error::RendererResult;
+use super::sky_data::SkyColors;
+use super::sky_operations::cloud_coverage;
+use crate::camera::{build_projection_matrix, build_view_matrix, CameraData};
+use crate::constants::{clouds, measurements};
+use crate::memory::{create_tracked_buffer_init, register_global_gpu_owner};
+use crate::world::WeatherData;
+use cgmath::{Matrix4, SquareMatrix};
+use wgpu::util::DeviceExt;
+
+/// Default cloud layer settings
+pub fn default_cloud_config() -> CloudConfig {
+    CloudConfig {
+        enabled: true,
+        altitude: clouds::DEFAULT_ALTITUDE,
+        thickness: clouds::DEFAULT_THICKNESS,
+        noise_scale: clouds::NOISE_SCALE,
+        half_extent: clouds::PLANE_HALF_EXTENT,
+        edge_softness: clouds::EDGE_SOFTNESS,
+        shadow_strength: clouds::SHADOW_STRENGTH,
+        opacity: clouds::OPACITY,
+        wind_drift_scale: clouds::WIND_DRIFT_SCALE,
+    }
+}
+
+/// Cloud drift in world XZ over `delta_seconds` (voxels).
+///
+/// Uses the same angle convention as the weather particles: the wind blows
+/// toward `(cos(direction), sin(direction))`.
+pub fn cloud_wind_step(
+    weather_data: &WeatherData,
+    delta_seconds: f32,
+    drift_scale: f32,
+) -> [f32; 2] {
+    let angle = (weather_data.wind_direction as f32).to_radians();
+    let speed = weather_data.wind_speed as f32 * 0.1 * measurements::METERS_TO_VOXELS;
+    let distance = speed * drift_scale * delta_seconds;
+    [angle.cos() * distance, angle.sin() * distance]
+}
+
+fn cloud_hash(x: i32, z: i32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(0x8da6_b343) ^ (z as u32).wrapping_mul(0xd816_3841);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h as f32 / u32::MAX as f32
+}
+
+fn cloud_value_noise(x: f32, z: f32) -> f32 {
+    let (cx, cz) = (x.floor(), z.floor());
+    let (fx, fz) = (x - cx, z - cz);
+    let (ux, uz) = (fx * fx * (3.0 - 2.0 * fx), fz * fz * (3.0 - 2.0 * fz));
+    let (ix, iz) = (cx as i32, cz as i32);
+    let a = cloud_hash(ix, iz);
+    let b = cloud_hash(ix.wrapping_add(1), iz);
+    let c = cloud_hash(ix, iz.wrapping_add(1));
+    let d = cloud_hash(ix.wrapping_add(1), iz.wrapping_add(1));
+    let top = a + (b - a) * ux;
+    let bottom = c + (d - c) * ux;
+    top + (bottom - top) * uz
+}
+
+/// Cloud density (0-1) above a world XZ position; CPU copy of
+/// `cloud_density` in cloud_shadow.wgsl
+pub fn cloud_density(data: &CloudData, world_x: f32, world_z: f32) -> f32 {
+    if !data.config.enabled || data.coverage <= 0.0 {
+        return 0.0;
+    }
+
+    let scale = data.config.noise_scale;
+    let mut x = (world_x - data.wind_offset[0]) * scale;
+    let mut z = (world_z - data.wind_offset[1]) * scale;
+    let mut amplitude = 0.5;
+    let mut value = 0.0;
+    for _ in 0..4 {
+        value += cloud_value_noise(x, z) * amplitude;
+        x *= 2.0;
+        z *= 2.0;
+        amplitude *= 0.5;
+    }
+    value /= 0.9375;
+
+    let threshold = 1.0 - data.coverage;
+    let t = ((value - threshold) / data.config.edge_softness).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Light multiplier (1 = unshadowed) at a world position, projecting along
+/// `sun_direction` onto the cloud plane
+pub fn cloud_shadow_factor(data: &CloudData, world_pos: [f32; 3], sun_direction: [f32; 3]) -> f32 {
+    let strength = if data.config.enabled && data.shadows_enabled {
+        data.config.shadow_strength
+    } else {
+        0.0
+    };
+    if strength <= 0.0 || sun_direction[1] <= 0.05 {
+        return 1.0;
+    }
+    let distance_to_layer = (data.config.altitude - world_pos[1]) / sun_direction[1];
+    if distance_to_layer <= 0.0 {
+        return 1.0;
+    }
+    let x = world_pos[0] + sun_direction[0] * distance_to_layer;
+    let z = world_pos[2] + sun_direction[2] * distance_to_layer;
+    1.0 - strength * cloud_density(data, x, z)
+}
+
+fn empty_cloud_uniform(config: &CloudConfig) -> CloudUniform {
+    CloudUniform {
+        view_proj: Matrix4::identity().into(),
+        camera_pos: [0.0; 4],
+        sun_direction: [0.0, 1.0, 0.0, 0.0],
+        sun_color: [0.0, 0.0, 0.0, config.opacity],
+        ambient_color: [0.0; 4],
+        layer: [config.altitude, config.thickness, config.noise_scale, 0.0],
+        wind: [0.0, 0.0, config.half_extent, config.edge_softness],
+    }
+}
+
+fn create_cloud_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<wgpu::RenderPipeline> {
+    let shader = crate::gpu::automation::create_gpu_shader(
+        device,
+        "clouds",
+        include_str!("../shaders/rendering/clouds.wgsl"),
+    )
+    .map_err(|e| format!("Failed to create cloud shader: {}", e))?;
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Cloud Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    Ok(device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Cloud Render Pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader.module,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader.module,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: color_format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            cull_mode: None,
+            ..Default::default()
+        },
+        // Translucent: tested against the world but never written
+        depth_stencil: depth_format.map(|format| wgpu::DepthStencilState {
+            format,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+    }))
+}
+
+/// Create the cloud pipeline; `sample_count` must match the main pass
+pub fn create_clouds(
+    device: &wgpu::Device,
+    config: CloudConfig,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<CloudData> {
+    let uniform = empty_cloud_uniform(&config);
+    let gpu_owner = register_global_gpu_owner("clouds");
+    let (uniform_buffer, uniform_allocation) = create_tracked_buffer_init(
+        device,
+        &gpu_owner,
+        &wgpu::util::BufferInitDescriptor {
+            label: Some("Cloud Uniform Buffer"),
+            contents: bytemuck::bytes_of(&uniform),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        },
+    );
+
+    // Also bound by the lighting pass for cloud shadows
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Cloud Bind Group Layout"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+    });
+
+    let render_pipeline = create_cloud_pipeline(
+        device,
+        &bind_group_layout,
+        color_format,
+        depth_format,
+        sample_count,
+    )?;
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Cloud Bind Group"),
+        layout: &bind_group_layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: uniform_buffer.as_entire_binding(),
+        }],
+    });
+
+    Ok(CloudData {
+        config,
+        uniform,
+        wind_offset: [0.0; 2],
+        coverage: 0.0,
+        shadows_enabled: true,
+        uniform_buffer,
+        render_pipeline,
+        bind_group_layout,
+        bind_group,
+        gpu_allocations: vec![uniform_allocation],
+        gpu_owner,
+        updated: false,
+    })
+}
+
+/// Recreate the cloud pipeline for a new target format or MSAA sample
+/// count, keeping the wind drift
+pub fn rebuild_cloud_pipeline(
+    data: &mut CloudData,
+    device: &wgpu::Device,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<()> {
+    data.render_pipeline = create_cloud_pipeline(
+        device,
+        &data.bind_group_layout,
+        color_format,
+        depth_format,
+        sample_count,
+    )?;
+    Ok(())
+}
+
+/// Turn the cloud layer and its shadows on or off
+pub fn set_clouds_enabled(data: &mut CloudData, enabled: bool) {
+    data.config.enabled = enabled;
+}
+
+/// Turn the cloud shadows on or off, keeping the clouds drawn. The uniform
+/// is rewritten at once so the lighting pass follows without an update.
+pub fn set_cloud_shadows_enabled(data: &mut CloudData, queue: &wgpu::Queue, enabled: bool) {
+    data.shadows_enabled = enabled;
+    data.uniform.sun_direction[3] = cloud_shadow_strength(data);
+    queue.write_buffer(&data.uniform_buffer, 0, bytemuck::bytes_of(&data.uniform));
+}
+
+/// Shadow strength the lighting pass reads
+fn cloud_shadow_strength(data: &CloudData) -> f32 {
+    if data.config.enabled && data.shadows_enabled {
+        data.config.shadow_strength
+    } else {
+        0.0
+    }
+}
+
+/// Advance the wind drift and update the uniform for this frame
+pub fn update_clouds(
+    data: &mut CloudData,
+    queue: &wgpu::Queue,
+    weather_data: &WeatherData,
+    sky: &SkyColors,
+    camera: &CameraData,
+    delta_seconds: f32,
+) {
+    let step = cloud_wind_step(weather_data, delta_seconds, data.config.wind_drift_scale);
+    data.wind_offset[0] += step[0];
+    data.wind_offset[1] += step[1];
+    data.coverage = cloud_coverage(weather_data);
+
+    // Disabled clouds still upload a uniform with zero coverage and shadow
+    // strength so the lighting pass sees no shadows
+    let config = data.config;
+    let coverage = if config.enabled { data.coverage } else { 0.0 };
+    let shadow_strength = cloud_shadow_strength(data);
+
+    let view_proj = build_projection_matrix(camera) * build_view_matrix(camera);
+    let [sx, sy, sz] = sky.sun_direction;
+    let position = camera.position;
+    data.uniform = CloudUniform {
+        view_proj: view_proj.into(),
+        camera_pos: [position.x, position.y, position.z, 1.0],
+        sun_direction: [sx, sy, sz, shadow_strength],
+        sun_color: [sky.sun[0], sky.sun[1], sky.sun[2], config.opacity],
+        ambient_color: [sky.horizon[0], sky.horizon[1], sky.horizon[2], 0.0],
+        layer: [
+            config.altitude,
+            config.thickness,
+            config.noise_scale,
+            coverage,
+        ],
+        wind: [
+            data.wind_offset[0],
+            data.wind_offset[1],
+            config.half_extent,
+            config.edge_softness,
+        ],
+    };
+    queue.write_buffer(&data.uniform_buffer, 0, bytemuck::bytes_of(&data.uniform));
+    data.updated = true;
+}
+
+/// Copy the drift and lighting of `source` and view it from `camera`,
+/// e.g. for a secondary view; the wind does not advance
+pub fn follow_clouds(
+    data: &mut CloudData,
+    queue: &wgpu::Queue,
+    source: &CloudData,
+    camera: &CameraData,
+) {
+    let view_proj = build_projection_matrix(camera) * build_view_matrix(camera);
+    let position = camera.position;
+    data.config = source.config;
+    data.wind_offset = source.wind_offset;
+    data.coverage = source.coverage;
+    data.shadows_enabled = source.shadows_enabled;
+    data.uniform = CloudUniform {
+        view_proj: view_proj.into(),
+        camera_pos: [position.x, position.y, position.z, 1.0],
+        ..source.uniform
+    };
+    queue.write_buffer(&data.uniform_buffer, 0, bytemuck::bytes_of(&data.uniform));
+    data.updated = source.updated;
+}
+
+/// Draw the cloud plane; call after the sky and opaque world passes
+pub fn render_clouds<'a>(data: &'a CloudData, pass: &mut wgpu::RenderPass<'a>) {
+    if !data.config.enabled || !data.updated || data.coverage <= 0.0 {
+        return;
+    }
+    pass.set_pipeline(&data.render_pipeline);
+    pass.set_bind_group(0, &data.bind_group, &[]);
+    pass.draw(0..6, 0..1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wind_step_follows_direction() {
+        let mut weather_data = WeatherData::clear();
+        weather_data.wind_speed = 100; // 10 m/s
+        weather_data.wind_direction = 90;
+
+        let step = cloud_wind_step(&weather_data, 1.0, 1.0);
+        assert!(step[0].abs() < 1e-3);
+        assert!((step[1] - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_noise_is_deterministic_and_bounded() {
+        for i in -20..20 {
+            let x = i as f32 * 0.37;
+            let z = i as f32 * -1.13;
+            let value = cloud_value_noise(x, z);
+            assert!((0.0..=1.0).contains(&value));
+            assert_eq!(value, cloud_value_noise(x, z));
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Device Recovery Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Detection, recreation and re-upload live in device_recovery_operations.rs
+//!
+//! A GPU reset, driver update or unplugged eGPU loses the wgpu device and
+//! every resource created on it. The loss is flagged from the device-lost
+//! callback (or from a surface that keeps failing), and the renderer is then
+//! rebuilt on a fresh device: target reconfigured, pipelines recreated and
+//! persistent buffers re-uploaded from the CPU shadows kept here.
+
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU32};
+use std::sync::Arc;
+
+/// Loss reason shared with the device-lost callback
+pub type DeviceLossReason = Arc<Mutex<Option<String>>>;
+
+/// Loss flag shared with the device-lost callback; cloning shares the flag
+#[derive(Debug, Clone, Default)]
+pub struct DeviceLossSignal {
+    pub lost: Arc<AtomicBool>,
+    /// What the driver or engine reported when the device was lost
+    pub reason: DeviceLossReason,
+    /// Frames in a row the surface was lost or outdated
+    pub surface_losses: Arc<AtomicU32>,
+}
+
+/// Handle to a shadowed persistent buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BufferShadowId(pub u32);
+
+/// A GPU buffer with the CPU copy it is restored from after a device loss
+pub struct BufferShadow {
+    pub label: String,
+    pub usage: wgpu::BufferUsages,
+    pub contents: Vec<u8>,
+    pub buffer: wgpu::Buffer,
+    /// Record of `buffer` in the GPU allocation tracker
+    pub allocation: GpuAllocationGuard,
+}
+
+/// Persistent buffers re-uploaded when the device is recreated
+pub struct GpuBufferShadows {
+    /// Indexed by `BufferShadowId`
+    pub shadows: Vec<BufferShadow>,
+    /// Owner of the shadowed buffers and the recovered render texture in
+    /// the GPU allocation tracker
+    pub gpu_owner: GpuOwnerGuard,
+}
+
+/// Device recovery counters
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DeviceRecoveryStats {
+    pub losses: u32,
+    pub recoveries: u32,
+    /// Recovery attempts that failed (e.g. the driver was still resetting)
+    pub failed_attempts: u32,
+    /// Bytes re-uploaded from shadows by the last recovery
+    pub restored_bytes: u64,
+    /// Wall time of the last recovery in milliseconds
+    pub last_recovery_ms: f32,
+}
+
+/// Device loss detection and recovery state of a renderer
+pub struct DeviceRecoveryData {
+    pub signal: DeviceLossSignal,
+    pub shadows: GpuBufferShadows,
+    /// Features and limits the replacement device is requested with; a lost
+    /// device can no longer be queried for them
+    pub features: wgpu::Features,
+    pub limits: wgpu::Limits,
+    pub stats: DeviceRecoveryStats,
+    /// Record of the render texture recreated for a texture target
+    pub target_allocation: Option<GpuAllocationGuard>,
+}
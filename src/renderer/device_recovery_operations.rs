@@ -0,0 +1,342 @@
+//! Device Recovery Operations - Pure DOP functions
+//!
+//! Call `poll_device_recovery` once per frame before rendering. While the
+//! device is healthy it only reads an atomic flag; after a loss it requests
+//! a replacement device from the instance and rebuilds the renderer on it,
+//! so play resumes after a hitch of one frame. A failed attempt (the driver
+//! may still be resetting) is retried on the next call.
+//!
+//! Buffers whose contents cannot be regenerated each frame are created with
+//! `create_buffer_shadow` and written with `write_buffer_shadow`, which keep
+//! a CPU copy to re-upload from.
+
+use super::anti_aliasing_operations::{
+    anti_aliasing_sample_count, create_anti_aliasing, max_msaa_samples, set_anti_aliasing_mode,
+};
+use super::cloud_operations::create_clouds;
+use super::device_recovery_data::{
+    BufferShadow, BufferShadowId, DeviceLossSignal, DeviceRecoveryData, DeviceRecoveryStats,
+    GpuBufferShadows,
+};
+use super::error::RendererResult;
+use super::placement_preview_operations::create_placement_preview;
+use super::renderer_data::{RenderTarget, Renderer};
+use super::renderer_operations::{render_target_format, texture_max_msaa_samples};
+use super::secondary_view_operations::rebuild_secondary_views;
+use super::sky_operations::create_sky;
+use crate::constants::device_recovery::SURFACE_LOSS_RECOVERY_FRAMES;
+use crate::constants::profiling::MAX_TIMED_GPU_PASSES;
+use crate::memory::{
+    create_tracked_buffer_init, create_tracked_texture, register_global_gpu_owner,
+};
+use crate::profiling::create_gpu_pass_timer;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Recovery state for a renderer on `device`, watching it for loss
+pub fn create_device_recovery(device: &wgpu::Device) -> DeviceRecoveryData {
+    DeviceRecoveryData {
+        signal: watch_device_loss(device),
+        shadows: GpuBufferShadows {
+            shadows: Vec::new(),
+            gpu_owner: register_global_gpu_owner("device_recovery"),
+        },
+        features: device.features(),
+        limits: device.limits(),
+        stats: DeviceRecoveryStats::default(),
+        target_allocation: None,
+    }
+}
+
+/// Flag set when `device` is lost. Replaces any device-lost callback
+/// installed earlier; dropping the device on purpose is not a loss.
+pub fn watch_device_loss(device: &wgpu::Device) -> DeviceLossSignal {
+    let signal = DeviceLossSignal::default();
+    let callback_signal = signal.clone();
+    device.set_device_lost_callback(move |reason, message| match reason {
+        wgpu::DeviceLostReason::Unknown | wgpu::DeviceLostReason::Destroyed => {
+            mark_device_lost(&callback_signal, &format!("{:?}: {}", reason, message));
+        }
+        wgpu::DeviceLostReason::Dropped | wgpu::DeviceLostReason::ReplacedCallback => {}
+    });
+    signal
+}
+
+/// Flag the device as lost (the first reason is kept)
+pub fn mark_device_lost(signal: &DeviceLossSignal, reason: &str) {
+    if !signal.lost.swap(true, Ordering::AcqRel) {
+        log::error!("[Renderer] GPU device lost: {}", reason);
+        *signal.reason.lock() = Some(reason.to_string());
+    }
+}
+
+/// Whether the device has been lost and not yet replaced
+pub fn device_lost(signal: &DeviceLossSignal) -> bool {
+    signal.lost.load(Ordering::Acquire)
+}
+
+/// Why the device was lost, while it is
+pub fn device_loss_reason(signal: &DeviceLossSignal) -> Option<String> {
+    signal.reason.lock().clone()
+}
+
+/// Count a frame whose surface was lost or outdated after reconfiguring;
+/// too many in a row are treated as a lost device
+pub fn note_surface_lost(signal: &DeviceLossSignal) {
+    let losses = signal.surface_losses.fetch_add(1, Ordering::AcqRel) + 1;
+    if losses >= SURFACE_LOSS_RECOVERY_FRAMES {
+        mark_device_lost(
+            signal,
+            &format!("surface lost for {} frames in a row", losses),
+        );
+    }
+}
+
+/// Reset the surface loss streak after a frame was presented
+pub fn note_surface_presented(signal: &DeviceLossSignal) {
+    signal.surface_losses.store(0, Ordering::Release);
+}
+
+/// Create a persistent buffer holding `contents` that survives device loss
+pub fn create_buffer_shadow(
+    shadows: &mut GpuBufferShadows,
+    device: &wgpu::Device,
+    label: &str,
+    usage: wgpu::BufferUsages,
+    contents: &[u8],
+) -> BufferShadowId {
+    let usage = usage | wgpu::BufferUsages::COPY_DST;
+    let (buffer, allocation) = create_tracked_buffer_init(
+        device,
+        &shadows.gpu_owner,
+        &wgpu::util::BufferInitDescriptor {
+            label: Some(label),
+            contents,
+            usage,
+        },
+    );
+    shadows.shadows.push(BufferShadow {
+        label: label.to_string(),
+        usage,
+        contents: contents.to_vec(),
+        buffer,
+        allocation,
+    });
+    BufferShadowId(shadows.shadows.len() as u32 - 1)
+}
+
+/// Write `bytes` at `offset` into a shadowed buffer and its CPU copy.
+/// Returns false when the write does not fit the buffer.
+pub fn write_buffer_shadow(
+    shadows: &mut GpuBufferShadows,
+    queue: &wgpu::Queue,
+    id: BufferShadowId,
+    offset: u64,
+    bytes: &[u8],
+) -> bool {
+    let Some(shadow) = shadows.shadows.get_mut(id.0 as usize) else {
+        return false;
+    };
+    let start = offset as usize;
+    let Some(range) = shadow.contents.get_mut(start..start + bytes.len()) else {
+        return false;
+    };
+    range.copy_from_slice(bytes);
+    queue.write_buffer(&shadow.buffer, offset, bytes);
+    true
+}
+
+/// GPU buffer of a shadow; replaced on recovery, so look it up again (and
+/// recreate bind groups using it) after `poll_device_recovery` returns true
+pub fn shadow_buffer(shadows: &GpuBufferShadows, id: BufferShadowId) -> Option<&wgpu::Buffer> {
+    shadows
+        .shadows
+        .get(id.0 as usize)
+        .map(|shadow| &shadow.buffer)
+}
+
+/// Recreate every shadowed buffer on `device` from its CPU copy; returns
+/// the bytes uploaded
+pub fn restore_buffer_shadows(shadows: &mut GpuBufferShadows, device: &wgpu::Device) -> u64 {
+    let mut bytes = 0;
+    for shadow in &mut shadows.shadows {
+        (shadow.buffer, shadow.allocation) = create_tracked_buffer_init(
+            device,
+            &shadows.gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some(&shadow.label),
+                contents: &shadow.contents,
+                usage: shadow.usage,
+            },
+        );
+        bytes += shadow.contents.len() as u64;
+    }
+    bytes
+}
+
+/// Request an adapter and device to replace the renderer's lost device,
+/// with the features and limits the old device had where still supported
+pub async fn request_replacement_device(
+    instance: &wgpu::Instance,
+    renderer: &Renderer,
+) -> RendererResult<(wgpu::Adapter, wgpu::Device, wgpu::Queue)> {
+    let surface = match &renderer.target {
+        RenderTarget::Surface { surface, .. } => Some(surface),
+        RenderTarget::Texture { .. } => None,
+    };
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: surface,
+            force_fallback_adapter: false,
+        })
+        .await
+        .ok_or_else(|| "No GPU adapter available after device loss".to_string())?;
+
+    let (device, queue) = adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("Recovered Device"),
+                required_features: renderer.recovery.features & adapter.features(),
+                required_limits: renderer.recovery.limits.clone(),
+            },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Failed to recreate GPU device: {}", e))?;
+    Ok((adapter, device, queue))
+}
+
+/// Move the renderer onto a replacement device: reconfigure the surface
+/// (or recreate the host texture with the same descriptor), rebuild every
+/// pipeline through `gpu::automation` and re-upload shadowed buffers.
+/// Texture targets hand a new texture to the host, which re-reads
+/// `renderer.target` afterwards.
+pub fn rebuild_renderer_on_device(
+    renderer: &mut Renderer,
+    adapter: &wgpu::Adapter,
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+) -> RendererResult<()> {
+    crate::gpu::automation::clear_gpu_device_resources();
+
+    let max_samples = match &mut renderer.target {
+        RenderTarget::Surface {
+            surface, config, ..
+        } => {
+            let capabilities = surface.get_capabilities(adapter);
+            if !capabilities.formats.contains(&config.format) {
+                config.format = *capabilities
+                    .formats
+                    .first()
+                    .ok_or_else(|| "Surface is not supported by the new adapter".to_string())?;
+            }
+            surface.configure(&device, config);
+            max_msaa_samples(&adapter.get_texture_format_features(config.format))
+        }
+        RenderTarget::Texture { texture, view } => {
+            let (recovered, allocation) = create_tracked_texture(
+                &device,
+                &renderer.recovery.shadows.gpu_owner,
+                &wgpu::TextureDescriptor {
+                    label: Some("Recovered Render Texture"),
+                    size: texture.size(),
+                    mip_level_count: texture.mip_level_count(),
+                    sample_count: texture.sample_count(),
+                    dimension: texture.dimension(),
+                    format: texture.format(),
+                    usage: texture.usage(),
+                    view_formats: &[],
+                },
+            );
+            *texture = recovered;
+            renderer.recovery.target_allocation = Some(allocation);
+            *view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            texture_max_msaa_samples(&device, texture.format())
+        }
+    };
+
+    let requested = renderer.anti_aliasing.requested;
+    renderer.anti_aliasing = create_anti_aliasing(max_samples);
+    set_anti_aliasing_mode(&mut renderer.anti_aliasing, requested);
+    let format = render_target_format(renderer);
+    let samples = anti_aliasing_sample_count(renderer.anti_aliasing.active);
+
+    if let Some(old) = renderer.sky.take() {
+        let mut sky = create_sky(&device, old.config, format, None, samples)?;
+        sky.colors = old.colors;
+        renderer.sky = Some(sky);
+    }
+    if let Some(old) = renderer.clouds.take() {
+        let mut clouds = create_clouds(&device, old.config, format, None, samples)?;
+        clouds.wind_offset = old.wind_offset;
+        clouds.coverage = old.coverage;
+        renderer.clouds = Some(clouds);
+    }
+    if let Some(old) = renderer.placement_preview.take() {
+        let mut ghost = create_placement_preview(&device, format, None, samples)?;
+        ghost.preview = old.preview;
+        renderer.placement_preview = Some(ghost);
+    }
+    rebuild_secondary_views(&mut renderer.secondary_views, &device);
+    if renderer.gpu_pass_timer.is_some() {
+        renderer.gpu_pass_timer = create_gpu_pass_timer(&device, &queue, MAX_TIMED_GPU_PASSES, 0);
+    }
+
+    let recovery = &mut renderer.recovery;
+    recovery.stats.restored_bytes = restore_buffer_shadows(&mut recovery.shadows, &device);
+    recovery.signal = watch_device_loss(&device);
+    recovery.features = device.features();
+    renderer.device = device;
+    renderer.queue = queue;
+    Ok(())
+}
+
+/// Replace a lost device now, blocking until the new one is ready
+pub fn recover_renderer_device(
+    renderer: &mut Renderer,
+    instance: &wgpu::Instance,
+) -> RendererResult<()> {
+    let started = Instant::now();
+    let reason = device_loss_reason(&renderer.recovery.signal);
+    let rebuilt = pollster::block_on(request_replacement_device(instance, renderer)).and_then(
+        |(adapter, device, queue)| {
+            rebuild_renderer_on_device(renderer, &adapter, Arc::new(device), Arc::new(queue))
+        },
+    );
+    if let Err(e) = rebuilt {
+        renderer.recovery.stats.failed_attempts += 1;
+        return Err(e);
+    }
+
+    let stats = &mut renderer.recovery.stats;
+    stats.recoveries += 1;
+    stats.last_recovery_ms = started.elapsed().as_secs_f32() * 1000.0;
+    log::info!(
+        "[Renderer] Recovered from GPU device loss ({}) in {:.1}ms, {} bytes restored",
+        reason.unwrap_or_else(|| "unknown".to_string()),
+        stats.last_recovery_ms,
+        stats.restored_bytes
+    );
+    Ok(())
+}
+
+/// Recover if the device was lost since the last call. Returns true when
+/// the renderer moved to a new device this call; resources created outside
+/// the renderer must then be recreated on `renderer.device`.
+pub fn poll_device_recovery(
+    renderer: &mut Renderer,
+    instance: &wgpu::Instance,
+) -> RendererResult<bool> {
+    if !device_lost(&renderer.recovery.signal) {
+        return Ok(false);
+    }
+    // A loss still being retried was already counted
+    let stats = &mut renderer.recovery.stats;
+    if stats.losses == stats.recoveries {
+        stats.losses += 1;
+    }
+    recover_renderer_device(renderer, instance)?;
+    Ok(true)
+}
@@ -0,0 +1,92 @@
+//! Edge Highlight Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! All transformations happen in edge_highlight_operations.rs
+//!
+//! Optional post pass drawing outlines along block edges, for players with
+//! low vision and as a debug view of mesh topology. The world pass writes
+//! linear depth and face normals into the edge geometry targets next to
+//! its color; a fullscreen pass finds depth and normal discontinuities
+//! there and blends outlines over the final image. The topology mode also
+//! marks where the face id stored in the normal target's alpha changes, so
+//! every mesh quad shows, and darkens the scene behind the edges.
+
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use bytemuck::{Pod, Zeroable};
+
+/// What the edge pass draws
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeHighlightMode {
+    Off,
+    /// Outlines along block edges over the normal image
+    Outline,
+    /// Block edges and mesh quad borders over a darkened image
+    Topology,
+}
+
+/// Edge pass settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeHighlightConfig {
+    pub mode: EdgeHighlightMode,
+    /// Outline color, RGBA; alpha scales the outline's opacity
+    pub color: [f32; 4],
+    /// Outline width in pixels
+    pub thickness: f32,
+    /// Depth step, relative to the nearer depth, treated as an edge
+    pub depth_threshold: f32,
+    /// Normal change (1 - cosine) treated as an edge
+    pub normal_threshold: f32,
+}
+
+/// Uniform read by the edge pass
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct EdgeHighlightUniform {
+    pub color: [f32; 4],
+    /// 1 / target size in pixels
+    pub inv_resolution: [f32; 2],
+    pub thickness: f32,
+    pub depth_threshold: f32,
+    pub normal_threshold: f32,
+    /// 1 in the topology mode
+    pub topology: f32,
+    /// Darkening behind the edges (0 = none)
+    pub background_dim: f32,
+    pub _padding: f32,
+}
+
+/// Depth and normal targets the world pass writes; rebuilt on resize
+pub struct EdgeGeometryTargets {
+    pub width: u32,
+    pub height: u32,
+    /// Linear view depth (0 = nothing drawn)
+    pub depth: wgpu::Texture,
+    pub depth_view: wgpu::TextureView,
+    /// Face normal in rgb, face id in alpha
+    pub normal: wgpu::Texture,
+    pub normal_view: wgpu::TextureView,
+    pub bind_group: wgpu::BindGroup,
+    /// Records of `depth` and `normal` in the GPU allocation tracker
+    pub gpu_allocations: [GpuAllocationGuard; 2],
+}
+
+/// Edge pass pipeline; created the first time the pass is turned on
+pub struct EdgeHighlightPipeline {
+    pub format: wgpu::TextureFormat,
+    pub uniform_buffer: wgpu::Buffer,
+    pub uniform_allocation: GpuAllocationGuard,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub pipeline: wgpu::RenderPipeline,
+}
+
+/// Edge highlight state for a renderer
+pub struct EdgeHighlightData {
+    pub config: EdgeHighlightConfig,
+    pub pipeline: Option<EdgeHighlightPipeline>,
+    pub targets: Option<EdgeGeometryTargets>,
+    /// Whether the world pass wrote the geometry targets this frame; the
+    /// edge pass is skipped otherwise
+    pub geometry_written: bool,
+    /// Owner of the pass resources in the GPU allocation tracker
+    pub gpu_owner: GpuOwnerGuard,
+}
@@ -0,0 +1,459 @@
+//! Edge Highlight Operations - Pure DOP
+//!
+//! Functions that configure the edge highlight mode, manage the depth and
+//! normal targets the world pass writes and encode the outline pass over
+//! the final image.
+
+use super::edge_highlight_data::{
+    EdgeGeometryTargets, EdgeHighlightConfig, EdgeHighlightData, EdgeHighlightMode,
+    EdgeHighlightPipeline, EdgeHighlightUniform,
+};
+use super::error::RendererResult;
+use crate::constants::edge_highlight;
+use crate::memory::{
+    create_tracked_buffer, create_tracked_texture, register_global_gpu_owner, GpuAllocationGuard,
+    GpuOwnerGuard, TrackedTextureView,
+};
+
+/// Linear view depth written by the world pass
+const EDGE_DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Float;
+
+/// Face normal and face id written by the world pass
+const EDGE_NORMAL_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// Off, with high-contrast yellow outlines once turned on
+pub fn default_edge_highlight_config() -> EdgeHighlightConfig {
+    EdgeHighlightConfig {
+        mode: EdgeHighlightMode::Off,
+        color: edge_highlight::DEFAULT_COLOR,
+        thickness: edge_highlight::DEFAULT_THICKNESS,
+        depth_threshold: edge_highlight::DEFAULT_DEPTH_THRESHOLD,
+        normal_threshold: edge_highlight::DEFAULT_NORMAL_THRESHOLD,
+    }
+}
+
+/// Edge highlight state with the default config; GPU resources are created
+/// the first time the pass is turned on
+pub fn create_edge_highlight() -> EdgeHighlightData {
+    EdgeHighlightData {
+        config: default_edge_highlight_config(),
+        pipeline: None,
+        targets: None,
+        geometry_written: false,
+        gpu_owner: register_global_gpu_owner("edge_highlight"),
+    }
+}
+
+/// Parse an `edge_highlight` setting value: "off", "outline" or "topology"
+pub fn parse_edge_highlight_mode(value: &str) -> Option<EdgeHighlightMode> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "off" | "none" => Some(EdgeHighlightMode::Off),
+        "outline" | "on" => Some(EdgeHighlightMode::Outline),
+        "topology" | "debug" => Some(EdgeHighlightMode::Topology),
+        _ => None,
+    }
+}
+
+/// Parse an `edge_highlight_color` setting value: "#rrggbb" or "#rrggbbaa"
+pub fn parse_edge_highlight_color(value: &str) -> Option<[f32; 4]> {
+    let hex = value.trim().trim_start_matches('#');
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |index: usize| {
+        hex.get(index * 2..index * 2 + 2)
+            .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+            .map(|byte| byte as f32 / 255.0)
+    };
+    let alpha = if hex.len() == 8 { channel(3)? } else { 1.0 };
+    Some([channel(0)?, channel(1)?, channel(2)?, alpha])
+}
+
+/// Replace the config; the thickness is kept within what the pass samples
+pub fn set_edge_highlight_config(data: &mut EdgeHighlightData, config: EdgeHighlightConfig) {
+    data.config = EdgeHighlightConfig {
+        thickness: config.thickness.clamp(1.0, edge_highlight::MAX_THICKNESS),
+        ..config
+    };
+    if data.config.mode == EdgeHighlightMode::Off {
+        // Nothing reads the geometry targets while off
+        data.targets = None;
+    }
+}
+
+/// Whether the world pass should write the geometry targets this frame
+pub fn edge_highlight_enabled(data: &EdgeHighlightData) -> bool {
+    data.config.mode != EdgeHighlightMode::Off
+}
+
+/// Uniform for a target of the given size
+pub fn edge_highlight_uniform(
+    config: &EdgeHighlightConfig,
+    width: u32,
+    height: u32,
+) -> EdgeHighlightUniform {
+    let topology = config.mode == EdgeHighlightMode::Topology;
+    EdgeHighlightUniform {
+        color: config.color,
+        inv_resolution: [1.0 / width.max(1) as f32, 1.0 / height.max(1) as f32],
+        thickness: config.thickness,
+        depth_threshold: config.depth_threshold,
+        normal_threshold: config.normal_threshold,
+        topology: if topology { 1.0 } else { 0.0 },
+        background_dim: if topology {
+            edge_highlight::TOPOLOGY_BACKGROUND_DIM
+        } else {
+            0.0
+        },
+        _padding: 0.0,
+    }
+}
+
+fn create_edge_highlight_pipeline(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    format: wgpu::TextureFormat,
+) -> RendererResult<EdgeHighlightPipeline> {
+    let shader = crate::gpu::automation::create_gpu_shader(
+        device,
+        "edge_highlight",
+        include_str!("../shaders/rendering/edge_highlight.wgsl"),
+    )
+    .map_err(|e| format!("Failed to create edge highlight shader: {}", e))?;
+
+    let (uniform_buffer, uniform_allocation) = create_tracked_buffer(
+        device,
+        owner,
+        &wgpu::BufferDescriptor {
+            label: Some("Edge Highlight Uniform Buffer"),
+            size: std::mem::size_of::<EdgeHighlightUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
+
+    let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    };
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Edge Highlight Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            texture_entry(1),
+            texture_entry(2),
+        ],
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Edge Highlight Pipeline Layout"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Edge Highlight Pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader.module,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader.module,
+            entry_point: "fs_edges",
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            cull_mode: None,
+            ..Default::default()
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+    });
+
+    Ok(EdgeHighlightPipeline {
+        format,
+        uniform_buffer,
+        uniform_allocation,
+        bind_group_layout,
+        pipeline,
+    })
+}
+
+fn create_geometry_texture(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    label: &str,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+) -> TrackedTextureView {
+    let (texture, allocation) = create_tracked_texture(
+        device,
+        owner,
+        &wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        },
+    );
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view, allocation)
+}
+
+fn create_geometry_targets(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    pipeline: &EdgeHighlightPipeline,
+    width: u32,
+    height: u32,
+) -> EdgeGeometryTargets {
+    let (depth, depth_view, depth_allocation) = create_geometry_texture(
+        device,
+        owner,
+        "Edge Depth Texture",
+        width,
+        height,
+        EDGE_DEPTH_FORMAT,
+    );
+    let (normal, normal_view, normal_allocation) = create_geometry_texture(
+        device,
+        owner,
+        "Edge Normal Texture",
+        width,
+        height,
+        EDGE_NORMAL_FORMAT,
+    );
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Edge Highlight Bind Group"),
+        layout: &pipeline.bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: pipeline.uniform_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(&depth_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::TextureView(&normal_view),
+            },
+        ],
+    });
+    EdgeGeometryTargets {
+        width,
+        height,
+        depth,
+        depth_view,
+        normal,
+        normal_view,
+        bind_group,
+        gpu_allocations: [depth_allocation, normal_allocation],
+    }
+}
+
+/// Build or resize the pipeline and geometry targets for this frame and
+/// upload the pass parameters; does nothing while off
+pub fn prepare_edge_highlight(
+    data: &mut EdgeHighlightData,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+) -> RendererResult<()> {
+    if !edge_highlight_enabled(data) {
+        return Ok(());
+    }
+    if data.pipeline.as_ref().map(|pipeline| pipeline.format) != Some(format) {
+        data.pipeline = Some(create_edge_highlight_pipeline(
+            device,
+            &data.gpu_owner,
+            format,
+        )?);
+        data.targets = None;
+    }
+    let Some(pipeline) = data.pipeline.as_ref() else {
+        return Ok(());
+    };
+
+    let stale = data
+        .targets
+        .as_ref()
+        .is_none_or(|targets| targets.width != width || targets.height != height);
+    if stale {
+        data.targets = Some(create_geometry_targets(
+            device,
+            &data.gpu_owner,
+            pipeline,
+            width,
+            height,
+        ));
+    }
+
+    let uniform = edge_highlight_uniform(&data.config, width, height);
+    queue.write_buffer(&pipeline.uniform_buffer, 0, bytemuck::bytes_of(&uniform));
+    Ok(())
+}
+
+/// Depth and normal color attachments, in that order
+type EdgeGeometryAttachments<'a> = [Option<wgpu::RenderPassColorAttachment<'a>>; 2];
+
+/// Depth and normal attachments for the world pass, cleared to "nothing
+/// drawn"; None while off. Its pipelines write linear view depth to the
+/// first and the face normal (alpha = face id) to the second, then call
+/// `mark_edge_geometry_written`.
+pub fn edge_geometry_attachments(
+    data: &EdgeHighlightData,
+) -> Option<EdgeGeometryAttachments<'_>> {
+    let targets = data
+        .targets
+        .as_ref()
+        .filter(|_| edge_highlight_enabled(data))?;
+    let attachment = |view| {
+        Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                store: wgpu::StoreOp::Store,
+            },
+        })
+    };
+    Some([
+        attachment(&targets.depth_view),
+        attachment(&targets.normal_view),
+    ])
+}
+
+/// Note that the world pass wrote the geometry targets this frame
+pub fn mark_edge_geometry_written(data: &mut EdgeHighlightData) {
+    data.geometry_written = true;
+}
+
+/// Blend outlines over `output`; does nothing while off or when no world
+/// pass wrote the geometry targets this frame
+pub fn encode_edge_highlight_pass(
+    data: &EdgeHighlightData,
+    encoder: &mut wgpu::CommandEncoder,
+    output: &wgpu::TextureView,
+) {
+    if !edge_highlight_enabled(data) || !data.geometry_written {
+        return;
+    }
+    let (Some(pipeline), Some(targets)) = (data.pipeline.as_ref(), data.targets.as_ref()) else {
+        return;
+    };
+
+    let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Edge Highlight Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: output,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Load,
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    pass.set_pipeline(&pipeline.pipeline);
+    pass.set_bind_group(0, &targets.bind_group, &[]);
+    pass.draw(0..3, 0..1);
+}
+
+/// Forget this frame's geometry; the next frame's world pass writes it again
+pub fn finish_edge_highlight_frame(data: &mut EdgeHighlightData) {
+    data.geometry_written = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_setting_values() {
+        assert_eq!(
+            parse_edge_highlight_mode(" Topology "),
+            Some(EdgeHighlightMode::Topology)
+        );
+        assert_eq!(
+            parse_edge_highlight_mode("outline"),
+            Some(EdgeHighlightMode::Outline)
+        );
+        assert_eq!(parse_edge_highlight_mode("wireframe"), None);
+
+        assert_eq!(
+            parse_edge_highlight_color("#ff0000"),
+            Some([1.0, 0.0, 0.0, 1.0])
+        );
+        assert_eq!(
+            parse_edge_highlight_color("00ff0080"),
+            Some([0.0, 1.0, 0.0, 128.0 / 255.0])
+        );
+        assert_eq!(parse_edge_highlight_color("#ff00"), None);
+        assert_eq!(parse_edge_highlight_color("#gg0000"), None);
+    }
+
+    #[test]
+    fn test_config_clamps_and_topology_dims_scene() {
+        let mut data = create_edge_highlight();
+        set_edge_highlight_config(
+            &mut data,
+            EdgeHighlightConfig {
+                mode: EdgeHighlightMode::Topology,
+                thickness: 12.0,
+                ..default_edge_highlight_config()
+            },
+        );
+        assert!(edge_highlight_enabled(&data));
+        assert_eq!(data.config.thickness, edge_highlight::MAX_THICKNESS);
+
+        let uniform = edge_highlight_uniform(&data.config, 200, 100);
+        assert_eq!(uniform.topology, 1.0);
+        assert_eq!(uniform.inv_resolution, [0.005, 0.01]);
+        assert!(uniform.background_dim > 0.0);
+
+        let outline = EdgeHighlightConfig {
+            mode: EdgeHighlightMode::Outline,
+            ..data.config
+        };
+        assert_eq!(edge_highlight_uniform(&outline, 1, 1).background_dim, 0.0);
+        assert!(edge_geometry_attachments(&data).is_none());
+    }
+}
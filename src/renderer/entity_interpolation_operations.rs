@@ -0,0 +1,154 @@
+//! Entity Interpolation Operations - Pure DOP Functions
+//!
+//! Simulation runs at a fixed tick rate while frames come at any rate, so
+//! entities are drawn between their last two ticks. Per fixed tick:
+//! `begin_transform_tick`, run the tick, then `capture_physics_transforms`.
+//! Per frame: `interpolate_entity_transforms` with the frame pacer's alpha
+//! before the renderer reads the interpolated arrays.
+
+use crate::engine_buffers::{PhysicsBuffers, TransformBuffers};
+
+/// Quaternion with no rotation (x, y, z, w)
+pub const IDENTITY_ROTATION: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+/// Grow or shrink the transform arrays to `count` entities; new entities
+/// start at the origin with no rotation
+pub fn resize_entity_transforms(transforms: &mut TransformBuffers, count: usize) {
+    transforms.previous_positions.resize(count, [0.0; 3]);
+    transforms.current_positions.resize(count, [0.0; 3]);
+    transforms
+        .previous_rotations
+        .resize(count, IDENTITY_ROTATION);
+    transforms
+        .current_rotations
+        .resize(count, IDENTITY_ROTATION);
+    transforms.interpolated_positions.resize(count, [0.0; 3]);
+    transforms
+        .interpolated_rotations
+        .resize(count, IDENTITY_ROTATION);
+}
+
+/// Keep the latest tick as the previous one before simulating the next
+pub fn begin_transform_tick(transforms: &mut TransformBuffers) {
+    transforms
+        .previous_positions
+        .clone_from(&transforms.current_positions);
+    transforms
+        .previous_rotations
+        .clone_from(&transforms.current_rotations);
+}
+
+/// Take the positions the physics tick produced as the latest tick.
+/// Entities added during the tick start without interpolation.
+pub fn capture_physics_transforms(transforms: &mut TransformBuffers, physics: &PhysicsBuffers) {
+    let existing = transforms.current_positions.len();
+    resize_entity_transforms(transforms, physics.positions.len());
+    transforms
+        .current_positions
+        .copy_from_slice(&physics.positions);
+    for index in existing..physics.positions.len() {
+        transforms.previous_positions[index] = physics.positions[index];
+    }
+}
+
+/// Set an entity's rotation for the latest tick
+pub fn set_entity_rotation(transforms: &mut TransformBuffers, index: usize, rotation: [f32; 4]) {
+    if let Some(current) = transforms.current_rotations.get_mut(index) {
+        *current = rotation;
+    }
+}
+
+/// Move an entity without interpolating across the jump (respawn, portal)
+pub fn teleport_entity(transforms: &mut TransformBuffers, index: usize, position: [f32; 3]) {
+    if let Some(current) = transforms.current_positions.get_mut(index) {
+        *current = position;
+    }
+    if let Some(previous) = transforms.previous_positions.get_mut(index) {
+        *previous = position;
+    }
+}
+
+/// Linear blend of two positions
+pub fn lerp_position(from: [f32; 3], to: [f32; 3], alpha: f32) -> [f32; 3] {
+    [
+        from[0] + (to[0] - from[0]) * alpha,
+        from[1] + (to[1] - from[1]) * alpha,
+        from[2] + (to[2] - from[2]) * alpha,
+    ]
+}
+
+/// Normalized blend of two rotations along the shorter arc
+pub fn nlerp_rotation(from: [f32; 4], to: [f32; 4], alpha: f32) -> [f32; 4] {
+    let dot: f32 = (0..4).map(|i| from[i] * to[i]).sum();
+    let sign = if dot < 0.0 { -1.0 } else { 1.0 };
+
+    let mut blended = [0.0; 4];
+    for i in 0..4 {
+        blended[i] = from[i] + (to[i] * sign - from[i]) * alpha;
+    }
+    let length = blended.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if length <= f32::EPSILON {
+        return to;
+    }
+    blended.map(|v| v / length)
+}
+
+/// Fill the interpolated arrays for this frame
+pub fn interpolate_entity_transforms(transforms: &mut TransformBuffers, alpha: f32) {
+    let alpha = alpha.clamp(0.0, 1.0);
+    let count = transforms.current_positions.len();
+    resize_entity_transforms(transforms, count);
+    transforms.alpha = alpha;
+
+    for index in 0..count {
+        transforms.interpolated_positions[index] = lerp_position(
+            transforms.previous_positions[index],
+            transforms.current_positions[index],
+            alpha,
+        );
+        transforms.interpolated_rotations[index] = nlerp_rotation(
+            transforms.previous_rotations[index],
+            transforms.current_rotations[index],
+            alpha,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_buffers::create_engine_buffers;
+
+    #[test]
+    fn test_positions_blend_between_ticks() {
+        let mut buffers = create_engine_buffers();
+        buffers.physics.positions = vec![[0.0, 0.0, 0.0], [4.0, 0.0, 0.0]];
+        capture_physics_transforms(&mut buffers.transforms, &buffers.physics);
+
+        begin_transform_tick(&mut buffers.transforms);
+        buffers.physics.positions = vec![[2.0, 4.0, 0.0], [4.0, 0.0, 0.0], [9.0, 9.0, 9.0]];
+        capture_physics_transforms(&mut buffers.transforms, &buffers.physics);
+        teleport_entity(&mut buffers.transforms, 1, [100.0, 0.0, 0.0]);
+
+        interpolate_entity_transforms(&mut buffers.transforms, 0.25);
+        let positions = &buffers.transforms.interpolated_positions;
+        assert_eq!(positions[0], [0.5, 1.0, 0.0]);
+        // Teleported and newly added entities do not slide
+        assert_eq!(positions[1], [100.0, 0.0, 0.0]);
+        assert_eq!(positions[2], [9.0, 9.0, 9.0]);
+    }
+
+    #[test]
+    fn test_rotation_blend_takes_short_arc() {
+        let half = std::f32::consts::FRAC_1_SQRT_2;
+        // 90 degrees about y, given with the negated (equivalent) quaternion
+        let to = [0.0, -half, 0.0, -half];
+        let mid = nlerp_rotation(IDENTITY_ROTATION, to, 0.5);
+
+        let length = mid.iter().map(|v| v * v).sum::<f32>().sqrt();
+        assert!((length - 1.0).abs() < 1e-5);
+        // Halfway is 45 degrees about +y, not the long way round
+        assert!(mid[1] > 0.0 && mid[3] > 0.0);
+        assert!((mid[1] - (std::f32::consts::PI / 8.0).sin()).abs() < 1e-4);
+    }
+}
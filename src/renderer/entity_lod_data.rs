@@ -0,0 +1,87 @@
+//! Entity LOD Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Band selection, update throttling and draw building live in
+//! entity_lod_operations.rs
+//!
+//! With thousands of entities, each one is put in a distance band every
+//! frame: near entities draw their full mesh and animate every frame,
+//! farther ones swap to a simplified mesh, then to a billboard impostor,
+//! and past the last band they are not drawn at all. Animation and AI run
+//! less often the farther the band. Drawn entities are listed as
+//! `DrawMetadata` with their band's mesh and bounding sphere; no pass draws
+//! them yet.
+
+use crate::gpu::buffer_layouts::DrawMetadata;
+
+/// Distance band of an entity, nearest first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EntityLodBand {
+    #[default]
+    Full = 0,
+    Simplified = 1,
+    Impostor = 2,
+    /// Beyond the last band: not drawn, AI throttled hardest
+    Culled = 3,
+}
+
+/// World position of an entity (voxels)
+pub type EntityPosition = [f32; 3];
+
+/// Band count including `Culled`
+pub const ENTITY_LOD_BAND_COUNT: usize = 4;
+
+/// One drawn band
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityLodBandConfig {
+    /// Entities up to this distance from the camera use the band (voxels)
+    pub max_distance: f32,
+    /// Mesh drawn for the band (simplified mesh, impostor quad, ...)
+    pub mesh_id: u32,
+    /// Frames between animation updates (0 = frozen pose)
+    pub animation_interval: u32,
+    /// Ticks between AI updates
+    pub ai_interval: u32,
+    pub cast_shadows: bool,
+}
+
+/// Bands and switching behaviour
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityLodConfig {
+    /// Full, simplified and impostor bands, in order of distance
+    pub bands: [EntityLodBandConfig; 3],
+    /// Ticks between AI updates of culled entities
+    pub culled_ai_interval: u32,
+    /// Distance past a band edge before an entity changes band (voxels)
+    pub hysteresis: f32,
+    /// Bounding radius of entities without their own (voxels)
+    pub default_radius: f32,
+}
+
+/// Entity LOD counters for the last update
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EntityLodStats {
+    /// Entities per band, indexed by `EntityLodBand as usize`
+    pub band_counts: [u32; ENTITY_LOD_BAND_COUNT],
+    pub animation_updates: u32,
+    pub animation_skipped: u32,
+    pub ai_updates: u32,
+    pub ai_skipped: u32,
+    /// Entities that changed band
+    pub band_changes: u32,
+}
+
+/// LOD state of all entities, indexed like `PhysicsBuffers`
+#[derive(Debug, Clone)]
+pub struct EntityLodData {
+    pub config: EntityLodConfig,
+    pub bands: Vec<EntityLodBand>,
+    /// Bounding radius per entity (`config.default_radius` when unset)
+    pub radii: Vec<f32>,
+    /// Draws of this frame's non-culled entities; `instance_offset` is the
+    /// entity index
+    pub draws: Vec<DrawMetadata>,
+    /// Updates so far, staggers throttled entities across frames
+    pub frame: u64,
+    pub stats: EntityLodStats,
+}
@@ -0,0 +1,277 @@
+//! Entity LOD Operations - Pure DOP Functions
+//!
+//! Each frame after `update_renderer_entities`: `update_entity_lod` with the
+//! camera position, then ask `entity_animation_due` / `entity_ai_due` before
+//! updating an entity. `data.draws` lists the mesh each drawn entity would
+//! use; the renderer has no entity pass that consumes it yet.
+
+use super::entity_lod_data::{
+    EntityLodBand, EntityLodBandConfig, EntityLodConfig, EntityLodData, EntityLodStats,
+    EntityPosition, ENTITY_LOD_BAND_COUNT,
+};
+use crate::constants::entity_lod::*;
+use crate::engine_buffers::MetricsBuffers;
+use crate::gpu::buffer_layouts::DrawMetadata;
+
+/// Bands from the engine constants; meshes 0, 1 and 2
+pub fn default_entity_lod_config() -> EntityLodConfig {
+    EntityLodConfig {
+        bands: [
+            EntityLodBandConfig {
+                max_distance: FULL_DETAIL_DISTANCE,
+                mesh_id: 0,
+                animation_interval: 1,
+                ai_interval: 1,
+                cast_shadows: true,
+            },
+            EntityLodBandConfig {
+                max_distance: SIMPLIFIED_DISTANCE,
+                mesh_id: 1,
+                animation_interval: SIMPLIFIED_ANIMATION_INTERVAL,
+                ai_interval: SIMPLIFIED_AI_INTERVAL,
+                cast_shadows: true,
+            },
+            EntityLodBandConfig {
+                max_distance: IMPOSTOR_DISTANCE,
+                mesh_id: 2,
+                animation_interval: 0,
+                ai_interval: IMPOSTOR_AI_INTERVAL,
+                cast_shadows: false,
+            },
+        ],
+        culled_ai_interval: CULLED_AI_INTERVAL,
+        hysteresis: BAND_HYSTERESIS,
+        default_radius: DEFAULT_ENTITY_RADIUS,
+    }
+}
+
+/// No entities yet
+pub fn create_entity_lod(config: EntityLodConfig) -> EntityLodData {
+    EntityLodData {
+        config,
+        bands: Vec::new(),
+        radii: Vec::new(),
+        draws: Vec::new(),
+        frame: 0,
+        stats: EntityLodStats::default(),
+    }
+}
+
+/// Bounding radius of one entity, used for its distance and draw
+pub fn set_entity_lod_radius(data: &mut EntityLodData, index: usize, radius: f32) {
+    if index >= data.radii.len() {
+        data.radii.resize(index + 1, data.config.default_radius);
+    }
+    data.radii[index] = radius.max(0.0);
+}
+
+fn band_from_index(index: usize) -> EntityLodBand {
+    match index {
+        0 => EntityLodBand::Full,
+        1 => EntityLodBand::Simplified,
+        2 => EntityLodBand::Impostor,
+        _ => EntityLodBand::Culled,
+    }
+}
+
+/// Band for a distance, without hysteresis
+pub fn entity_lod_band_at(config: &EntityLodConfig, distance: f32) -> EntityLodBand {
+    let index = config
+        .bands
+        .iter()
+        .position(|band| distance <= band.max_distance)
+        .unwrap_or(ENTITY_LOD_BAND_COUNT - 1);
+    band_from_index(index)
+}
+
+/// Band for a distance, keeping `previous` while the distance stays within
+/// the hysteresis of its edges
+pub fn select_entity_lod_band(
+    config: &EntityLodConfig,
+    distance: f32,
+    previous: EntityLodBand,
+) -> EntityLodBand {
+    let finest = entity_lod_band_at(config, distance - config.hysteresis);
+    let coarsest = entity_lod_band_at(config, distance + config.hysteresis);
+    if (finest..=coarsest).contains(&previous) {
+        previous
+    } else {
+        entity_lod_band_at(config, distance)
+    }
+}
+
+/// Settings of a drawn band, `None` for `Culled`
+pub fn entity_lod_band_config(
+    config: &EntityLodConfig,
+    band: EntityLodBand,
+) -> Option<&EntityLodBandConfig> {
+    config.bands.get(band as usize)
+}
+
+fn interval_due(interval: u32, frame: u64, index: usize) -> bool {
+    // Staggered by index so throttled entities spread over frames
+    interval > 0 && (frame + index as u64).is_multiple_of(u64::from(interval))
+}
+
+/// Whether the entity's animation advances this frame
+pub fn entity_animation_due(data: &EntityLodData, index: usize) -> bool {
+    let band = data.bands.get(index).copied().unwrap_or_default();
+    entity_lod_band_config(&data.config, band)
+        .is_some_and(|config| interval_due(config.animation_interval, data.frame, index))
+}
+
+/// Whether the entity's AI runs this tick
+pub fn entity_ai_due(data: &EntityLodData, index: usize) -> bool {
+    let band = data.bands.get(index).copied().unwrap_or_default();
+    let interval = entity_lod_band_config(&data.config, band)
+        .map_or(data.config.culled_ai_interval, |config| config.ai_interval);
+    interval_due(interval, data.frame, index)
+}
+
+/// Put every entity in a band for this frame, rebuild the draws and count
+/// bands and throttled updates. `positions` are this frame's interpolated
+/// entity positions.
+pub fn update_entity_lod(
+    data: &mut EntityLodData,
+    camera_position: [f32; 3],
+    positions: &[EntityPosition],
+) {
+    data.frame += 1;
+    data.bands.resize(positions.len(), EntityLodBand::Full);
+    data.radii
+        .resize(positions.len(), data.config.default_radius);
+    data.draws.clear();
+    let mut stats = EntityLodStats::default();
+
+    for (index, position) in positions.iter().enumerate() {
+        let radius = data.radii[index];
+        let center_distance = (0..3)
+            .map(|axis| (position[axis] - camera_position[axis]).powi(2))
+            .sum::<f32>()
+            .sqrt();
+        let distance = (center_distance - radius).max(0.0);
+
+        let previous = data.bands[index];
+        let band = select_entity_lod_band(&data.config, distance, previous);
+        if band != previous {
+            stats.band_changes += 1;
+        }
+        data.bands[index] = band;
+        stats.band_counts[band as usize] += 1;
+
+        if entity_animation_due(data, index) {
+            stats.animation_updates += 1;
+        } else {
+            stats.animation_skipped += 1;
+        }
+        if entity_ai_due(data, index) {
+            stats.ai_updates += 1;
+        } else {
+            stats.ai_skipped += 1;
+        }
+
+        let Some(band_config) = entity_lod_band_config(&data.config, band) else {
+            continue;
+        };
+        let min_distance = match band as usize {
+            0 => 0.0,
+            previous_band => data.config.bands[previous_band - 1].max_distance,
+        };
+        let mut draw = DrawMetadata::new(*position, radius, 0, band_config.mesh_id).with_lod_range(
+            min_distance,
+            band_config.max_distance,
+            band as u32,
+        );
+        draw.instance_offset = index as u32;
+        if !band_config.cast_shadows {
+            draw.flags &= !DrawMetadata::FLAG_CAST_SHADOWS;
+        }
+        data.draws.push(draw);
+    }
+    data.stats = stats;
+}
+
+/// Publish the last update's band counts to the metrics buffers
+pub fn record_entity_lod_metrics(data: &EntityLodData, metrics: &mut MetricsBuffers) {
+    metrics.entity_lod = data.stats;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bands_follow_distance_with_hysteresis() {
+        let config = default_entity_lod_config();
+        assert_eq!(entity_lod_band_at(&config, 10.0), EntityLodBand::Full);
+        assert_eq!(
+            entity_lod_band_at(&config, 300.0),
+            EntityLodBand::Simplified
+        );
+        assert_eq!(entity_lod_band_at(&config, 1000.0), EntityLodBand::Impostor);
+        assert_eq!(entity_lod_band_at(&config, 5000.0), EntityLodBand::Culled);
+
+        // Just past the edge keeps the old band, well past it switches
+        let edge = FULL_DETAIL_DISTANCE;
+        let full = EntityLodBand::Full;
+        assert_eq!(select_entity_lod_band(&config, edge + 4.0, full), full);
+        assert_eq!(
+            select_entity_lod_band(&config, edge + 20.0, full),
+            EntityLodBand::Simplified
+        );
+        let simplified = EntityLodBand::Simplified;
+        assert_eq!(
+            select_entity_lod_band(&config, edge - 4.0, simplified),
+            simplified
+        );
+        assert_eq!(
+            select_entity_lod_band(&config, 0.0, EntityLodBand::Culled),
+            full
+        );
+    }
+
+    #[test]
+    fn test_distant_entities_draw_cheaper_and_update_less() {
+        let mut data = create_entity_lod(default_entity_lod_config());
+        let positions: Vec<[f32; 3]> = [20.0, 300.0, 301.0, 1000.0, 9000.0]
+            .iter()
+            .map(|&x| [x, 0.0, 0.0])
+            .collect();
+
+        let mut animation_updates = [0; 5];
+        let mut ai_updates = [0; 5];
+        for _ in 0..8 {
+            update_entity_lod(&mut data, [0.0; 3], &positions);
+            for index in 0..positions.len() {
+                animation_updates[index] += u32::from(entity_animation_due(&data, index));
+                ai_updates[index] += u32::from(entity_ai_due(&data, index));
+            }
+        }
+
+        // Distance minus the default radius picks the band
+        assert_eq!(data.stats.band_counts, [1, 2, 1, 1]);
+        assert_eq!(animation_updates, [8, 4, 4, 0, 0]);
+        assert_eq!(ai_updates, [8, 4, 4, 2, 1]);
+        assert_eq!(
+            data.stats.animation_updates + data.stats.animation_skipped,
+            5
+        );
+
+        // The culled entity has no draw, the impostor casts no shadow
+        assert_eq!(data.draws.len(), 4);
+        let impostor = data
+            .draws
+            .iter()
+            .find(|draw| draw.instance_offset == 3)
+            .expect("impostor draw");
+        assert_eq!(impostor.mesh_id, 2);
+        assert!(!impostor.casts_shadows());
+
+        let mut metrics = MetricsBuffers::default();
+        record_entity_lod_metrics(&data, &mut metrics);
+        assert_eq!(
+            metrics.entity_lod.band_counts[EntityLodBand::Culled as usize],
+            1
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Far Terrain Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! All transformations happen in far_terrain_operations.rs
+//!
+//! A low-resolution heightfield ring drawn beyond voxel render distance. The
+//! heightmap is generated on the GPU from terrain parameters and the ring
+//! mesh comes from the adaptive tessellator.
+
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use crate::world::generation::FarSurface;
+use bytemuck::{Pod, Zeroable};
+
+/// Ring configuration
+#[derive(Debug, Clone, Copy)]
+pub struct FarTerrainConfig {
+    /// Half-width of the voxel render region (voxels)
+    pub inner_radius: f32,
+    /// Ring outer radius (voxels)
+    pub outer_radius: f32,
+    /// Fade width past the inner radius (voxels)
+    pub blend_width: f32,
+    /// Heightmap texels per side
+    pub heightmap_resolution: u32,
+    /// Camera travel before the heightmap is regenerated (voxels)
+    pub recenter_distance: f32,
+}
+
+/// Uniform shared by the heightmap and ring shaders
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct FarTerrainUniform {
+    pub view_proj: [[f32; 4]; 4],
+    pub camera_pos: [f32; 4],
+    pub heightmap_origin: [f32; 2],
+    pub texel_size: f32,
+    pub resolution: u32,
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub blend_width: f32,
+    pub sea_level: f32,
+    pub boundary_sink: f32,
+    /// `far_terrain::SURFACE_REFERENCE` or `SURFACE_FLAT`
+    pub surface_mode: u32,
+    /// Top voxel of a flat surface
+    pub flat_height: f32,
+    pub _padding: f32,
+}
+
+/// GPU resources and state for the far terrain ring
+pub struct FarTerrainData {
+    pub config: FarTerrainConfig,
+    pub uniform: FarTerrainUniform,
+
+    pub heightmap: wgpu::Texture,
+    pub uniform_buffer: wgpu::Buffer,
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub index_count: u32,
+
+    pub heightmap_pipeline: wgpu::ComputePipeline,
+    pub render_pipeline: wgpu::RenderPipeline,
+    /// Kept to rebuild `render_pipeline` when the main pass changes
+    pub render_bind_group_layout: wgpu::BindGroupLayout,
+    pub heightmap_bind_group: wgpu::BindGroup,
+    pub render_bind_group: wgpu::BindGroup,
+    /// The heightmap texture plus the uniform and ring vertex and index buffers
+    pub gpu_allocations: Vec<GpuAllocationGuard>,
+    pub gpu_owner: GpuOwnerGuard,
+
+    /// World XZ the current heightmap is centered on (None until generated)
+    pub heightmap_center: Option<[f32; 2]>,
+    /// Sea level the current heightmap was generated with
+    pub generated_sea_level: f32,
+    /// Surface the current heightmap was generated with; nothing is drawn
+    /// for `FarSurface::Empty`
+    pub generated_surface: FarSurface,
+}
@@ -0,0 +1,439 @@
+//! Far Terrain Operations - Pure DOP
+//!
+//! Functions that create, regenerate and draw the far terrain ring.
+
+use super::adaptive_tessellation::{
+    AdaptiveTessellator, RingRegion, TessellatedMesh, TessellatedVertex, TessellationParams,
+    TessellationView,
+};
+use super::error::RendererResult;
+use super::far_terrain_data::{FarTerrainConfig, FarTerrainData, FarTerrainUniform};
+use crate::constants::{camera_constants, far_terrain};
+use crate::gpu::TerrainParamsSOA;
+use crate::memory::{
+    create_tracked_buffer_init, create_tracked_texture, register_global_gpu_owner,
+};
+use crate::world::generation::FarSurface;
+use cgmath::{Matrix4, Point3, Vector3};
+
+/// Heightmap texel format: rgb = color, a = height
+const HEIGHTMAP_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba32Float;
+
+/// Default ring configuration around a voxel region of the given half-width
+pub fn default_far_terrain_config(inner_radius: f32) -> FarTerrainConfig {
+    FarTerrainConfig {
+        inner_radius,
+        outer_radius: far_terrain::OUTER_RADIUS,
+        blend_width: far_terrain::BLEND_WIDTH,
+        heightmap_resolution: far_terrain::HEIGHTMAP_RESOLUTION,
+        recenter_distance: far_terrain::RECENTER_DISTANCE,
+    }
+}
+
+fn texel_size(config: &FarTerrainConfig) -> f32 {
+    2.0 * config.outer_radius / config.heightmap_resolution as f32
+}
+
+/// Tessellate the ring mesh, relative to the heightmap center
+///
+/// The hole is shrunk by the recenter distance so the ring still reaches the
+/// voxel region after the camera drifts from the heightmap center.
+fn build_ring_mesh(config: &FarTerrainConfig) -> TessellatedMesh {
+    let tessellator = AdaptiveTessellator::new(TessellationParams {
+        max_level: 5,
+        screen_error_threshold: 48.0,
+        curvature_threshold: 0.0,
+        distance_factor: 0.0,
+        min_edge_length: texel_size(config),
+    });
+
+    let region = RingRegion {
+        center: Vector3::new(0.0, 0.0, 0.0),
+        inner_radius: (config.inner_radius - config.recenter_distance).max(0.0),
+        outer_radius: config.outer_radius,
+    };
+    let view = TessellationView {
+        view_pos: Vector3::new(0.0, camera_constants::DEFAULT_HEIGHT, 0.0),
+        viewport_height: 1080.0,
+        fov: std::f32::consts::FRAC_PI_3,
+    };
+
+    let mesh = tessellator.tessellate_ring(&region, &view);
+    log::debug!(
+        "[FarTerrain] Ring mesh: {} patches, {} triangles",
+        mesh.stats.patch_count,
+        mesh.stats.triangle_count
+    );
+    mesh
+}
+
+/// Create the heightmap, ring mesh and pipelines
+pub fn create_far_terrain(
+    device: &wgpu::Device,
+    config: FarTerrainConfig,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<FarTerrainData> {
+    let resolution = config.heightmap_resolution;
+
+    let gpu_owner = register_global_gpu_owner("far_terrain");
+    let (heightmap, heightmap_allocation) = create_tracked_texture(
+        device,
+        &gpu_owner,
+        &wgpu::TextureDescriptor {
+            label: Some("Far Terrain Heightmap"),
+            size: wgpu::Extent3d {
+                width: resolution,
+                height: resolution,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: HEIGHTMAP_FORMAT,
+            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        },
+    );
+    let mut gpu_allocations = vec![heightmap_allocation];
+    let mut create_buffer_init = |descriptor: &wgpu::util::BufferInitDescriptor| {
+        let (buffer, guard) = create_tracked_buffer_init(device, &gpu_owner, descriptor);
+        gpu_allocations.push(guard);
+        buffer
+    };
+    let heightmap_view = heightmap.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let uniform = FarTerrainUniform {
+        view_proj: Matrix4::from_scale(1.0).into(),
+        camera_pos: [0.0; 4],
+        heightmap_origin: [0.0; 2],
+        texel_size: texel_size(&config),
+        resolution,
+        inner_radius: config.inner_radius,
+        outer_radius: config.outer_radius,
+        blend_width: config.blend_width,
+        sea_level: 0.0,
+        boundary_sink: far_terrain::BOUNDARY_SINK,
+        surface_mode: far_terrain::SURFACE_REFERENCE,
+        flat_height: 0.0,
+        _padding: 0.0,
+    };
+    let uniform_buffer = create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Far Terrain Uniform Buffer"),
+        contents: bytemuck::bytes_of(&uniform),
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
+
+    let TessellatedMesh {
+        vertices, indices, ..
+    } = build_ring_mesh(&config);
+    let vertex_buffer = create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Far Terrain Ring Vertices"),
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+    let index_buffer = create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Far Terrain Ring Indices"),
+        contents: bytemuck::cast_slice(&indices),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+
+    let uniform_entry = |visibility| wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    };
+
+    // Heightmap generation pipeline
+    let heightmap_shader = crate::gpu::automation::create_gpu_shader(
+        device,
+        "far_terrain_heightmap",
+        include_str!("../shaders/compute/far_terrain_heightmap.wgsl"),
+    )
+    .map_err(|e| format!("Failed to create far terrain heightmap shader: {}", e))?;
+
+    let heightmap_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Far Terrain Heightmap Bind Group Layout"),
+        entries: &[
+            uniform_entry(wgpu::ShaderStages::COMPUTE),
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::StorageTexture {
+                    access: wgpu::StorageTextureAccess::WriteOnly,
+                    format: HEIGHTMAP_FORMAT,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                },
+                count: None,
+            },
+        ],
+    });
+
+    let heightmap_pipeline_layout =
+        device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Far Terrain Heightmap Pipeline Layout"),
+            bind_group_layouts: &[&heightmap_layout],
+            push_constant_ranges: &[],
+        });
+
+    let heightmap_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("Far Terrain Heightmap Pipeline"),
+        layout: Some(&heightmap_pipeline_layout),
+        module: &heightmap_shader.module,
+        entry_point: "generate_heightmap",
+    });
+
+    let heightmap_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Far Terrain Heightmap Bind Group"),
+        layout: &heightmap_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(&heightmap_view),
+            },
+        ],
+    });
+
+    // Ring render pipeline
+    let render_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Far Terrain Render Bind Group Layout"),
+        entries: &[
+            uniform_entry(wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT),
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+        ],
+    });
+    let render_pipeline = create_far_terrain_pipeline(
+        device,
+        &render_layout,
+        color_format,
+        depth_format,
+        sample_count,
+    )?;
+
+    let render_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Far Terrain Render Bind Group"),
+        layout: &render_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(&heightmap_view),
+            },
+        ],
+    });
+
+    Ok(FarTerrainData {
+        config,
+        uniform,
+        heightmap,
+        uniform_buffer,
+        vertex_buffer,
+        index_buffer,
+        index_count: indices.len() as u32,
+        heightmap_pipeline,
+        render_pipeline,
+        render_bind_group_layout: render_layout,
+        heightmap_bind_group,
+        render_bind_group,
+        gpu_allocations,
+        gpu_owner,
+        heightmap_center: None,
+        generated_sea_level: 0.0,
+        generated_surface: FarSurface::Reference,
+    })
+}
+
+/// Ring pipeline for the main pass's color, depth and sample count
+fn create_far_terrain_pipeline(
+    device: &wgpu::Device,
+    render_layout: &wgpu::BindGroupLayout,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<wgpu::RenderPipeline> {
+    let ring_shader = crate::gpu::automation::create_gpu_shader(
+        device,
+        "far_terrain",
+        include_str!("../shaders/rendering/far_terrain.wgsl"),
+    )
+    .map_err(|e| format!("Failed to create far terrain shader: {}", e))?;
+
+    let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Far Terrain Render Pipeline Layout"),
+        bind_group_layouts: &[render_layout],
+        push_constant_ranges: &[],
+    });
+
+    Ok(
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Far Terrain Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &ring_shader.module,
+                entry_point: "vs_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<TessellatedVertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x3],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &ring_shader.module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: color_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: depth_format.map(|format| wgpu::DepthStencilState {
+                format,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        }),
+    )
+}
+
+/// Recreate the ring pipeline after the main pass's formats or sample
+/// count changed
+pub fn rebuild_far_terrain_pipeline(
+    data: &mut FarTerrainData,
+    device: &wgpu::Device,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<()> {
+    data.render_pipeline = create_far_terrain_pipeline(
+        device,
+        &data.render_bind_group_layout,
+        color_format,
+        depth_format,
+        sample_count,
+    )?;
+    Ok(())
+}
+
+/// Whether the heightmap must be regenerated around `center`
+pub fn far_terrain_needs_regeneration(
+    data: &FarTerrainData,
+    center: [f32; 2],
+    sea_level: f32,
+    surface: FarSurface,
+) -> bool {
+    match data.heightmap_center {
+        None => true,
+        Some(current) => {
+            let drift = (center[0] - current[0])
+                .abs()
+                .max((center[1] - current[1]).abs());
+            drift > data.config.recenter_distance
+                || sea_level != data.generated_sea_level
+                || surface != data.generated_surface
+        }
+    }
+}
+
+/// Update the ring for this frame; regenerates the heightmap when the camera
+/// has drifted or the terrain params or surface changed. Returns true if
+/// regenerated. An empty surface clears the ring.
+pub fn update_far_terrain(
+    data: &mut FarTerrainData,
+    queue: &wgpu::Queue,
+    encoder: &mut wgpu::CommandEncoder,
+    camera_pos: Point3<f32>,
+    view_proj: Matrix4<f32>,
+    terrain: &TerrainParamsSOA,
+    surface: FarSurface,
+) -> bool {
+    let (surface_mode, flat_height) = match surface {
+        FarSurface::Reference => (far_terrain::SURFACE_REFERENCE, 0.0),
+        FarSurface::Flat { height } => (far_terrain::SURFACE_FLAT, height as f32),
+        FarSurface::Empty => {
+            data.heightmap_center = None;
+            data.generated_surface = surface;
+            return false;
+        }
+    };
+
+    // Snap to the texel grid so regenerated heightmaps line up
+    let texel = data.uniform.texel_size;
+    let center = [
+        (camera_pos.x / texel).round() * texel,
+        (camera_pos.z / texel).round() * texel,
+    ];
+    let regenerate = far_terrain_needs_regeneration(data, center, terrain.sea_level, surface);
+
+    data.uniform.view_proj = view_proj.into();
+    data.uniform.camera_pos = [camera_pos.x, camera_pos.y, camera_pos.z, 1.0];
+    if regenerate {
+        let half_extent = data.config.heightmap_resolution as f32 * texel * 0.5;
+        data.uniform.heightmap_origin = [center[0] - half_extent, center[1] - half_extent];
+        data.uniform.sea_level = terrain.sea_level;
+        data.uniform.surface_mode = surface_mode;
+        data.uniform.flat_height = flat_height;
+        data.heightmap_center = Some(center);
+        data.generated_sea_level = terrain.sea_level;
+        data.generated_surface = surface;
+    }
+    queue.write_buffer(&data.uniform_buffer, 0, bytemuck::bytes_of(&data.uniform));
+
+    if regenerate {
+        let groups = data.config.heightmap_resolution.div_ceil(8);
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Far Terrain Heightmap Pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&data.heightmap_pipeline);
+        pass.set_bind_group(0, &data.heightmap_bind_group, &[]);
+        pass.dispatch_workgroups(groups, groups, 1);
+    }
+
+    regenerate
+}
+
+/// Draw the ring; call before or after chunk rendering in the opaque pass
+pub fn render_far_terrain<'a>(data: &'a FarTerrainData, pass: &mut wgpu::RenderPass<'a>) {
+    if data.heightmap_center.is_none() || data.index_count == 0 {
+        return;
+    }
+    pass.set_pipeline(&data.render_pipeline);
+    pass.set_bind_group(0, &data.render_bind_group, &[]);
+    pass.set_vertex_buffer(0, data.vertex_buffer.slice(..));
+    pass.set_index_buffer(data.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+    pass.draw_indexed(0..data.index_count, 0, 0..1);
+}
@@ -0,0 +1,17 @@
+//! Frame Capture Data - Pure DOP
+//!
+//! A frame rendered off screen at the target's size and read back to the
+//! CPU, for world thumbnails and screenshots. Capturing renders the same
+//! passes as a presented frame into a private texture, so the host's
+//! window or texture is never touched.
+//!
+//! NO METHODS - just data.
+
+/// Pixels of one captured frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    /// `width * height` RGBA8 pixels, top row first
+    pub rgba: Vec<u8>,
+}
@@ -0,0 +1,172 @@
+//! Frame Capture Operations - Pure DOP Functions
+//!
+//! `capture_renderer_frame` renders the current scene into an off-screen
+//! copy of the target, copies it into a readback buffer and waits for the
+//! GPU. It blocks, so call it on save or exit rather than every frame.
+
+use super::anti_aliasing_operations::prepare_anti_aliasing;
+use super::edge_highlight_operations::prepare_edge_highlight;
+use super::error::{buffer_mapping_error, RendererResult};
+use super::frame_capture_data::CapturedFrame;
+use super::renderer_data::Renderer;
+use super::renderer_operations::{encode_frame_pass, render_target_format, render_target_size};
+use crate::memory::{create_tracked_buffer, create_tracked_texture, register_global_gpu_owner};
+
+/// Bytes per captured pixel
+const BYTES_PER_PIXEL: u32 = 4;
+
+/// Render a frame off screen and read it back as RGBA8
+pub fn capture_renderer_frame(renderer: &mut Renderer) -> RendererResult<CapturedFrame> {
+    let _span = crate::trace_span!(Render, "capture_renderer_frame");
+    let (width, height) = render_target_size(renderer);
+    let format = render_target_format(renderer);
+    let swap_red_blue = match format {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+        other => return Err(format!("Cannot capture {:?} render targets", other)),
+    };
+    prepare_anti_aliasing(
+        &mut renderer.anti_aliasing,
+        &renderer.device,
+        &renderer.queue,
+        width,
+        height,
+        format,
+    )?;
+    prepare_edge_highlight(
+        &mut renderer.edge_highlight,
+        &renderer.device,
+        &renderer.queue,
+        width,
+        height,
+        format,
+    )?;
+
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let gpu_owner = register_global_gpu_owner("frame_capture");
+    let (texture, _texture_allocation) = create_tracked_texture(
+        &renderer.device,
+        &gpu_owner,
+        &wgpu::TextureDescriptor {
+            label: Some("Frame Capture Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        },
+    );
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let frame = encode_frame_pass(renderer, &view, None);
+
+    let padded_row = padded_bytes_per_row(width);
+    let (readback, _readback_allocation) = create_tracked_buffer(
+        &renderer.device,
+        &gpu_owner,
+        &wgpu::BufferDescriptor {
+            label: Some("Frame Capture Readback"),
+            size: padded_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        },
+    );
+    let mut encoder = renderer
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Frame Capture Encoder"),
+        });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &readback,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row),
+                rows_per_image: Some(height),
+            },
+        },
+        size,
+    );
+    renderer.queue.submit([frame, encoder.finish()]);
+
+    let slice = readback.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    renderer.device.poll(wgpu::Maintain::Wait);
+    receiver
+        .recv()
+        .map_err(|_| buffer_mapping_error("frame capture recv"))?
+        .map_err(|_| buffer_mapping_error("frame capture map_async"))?;
+
+    let rgba = {
+        let mapped = slice.get_mapped_range();
+        unpad_capture_rows(&mapped, width, height, padded_row, swap_red_blue)
+    };
+    readback.unmap();
+    Ok(CapturedFrame {
+        width,
+        height,
+        rgba,
+    })
+}
+
+/// Row pitch of a texture copy: whole pixels rounded up to wgpu's copy
+/// alignment
+pub fn padded_bytes_per_row(width: u32) -> u32 {
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    (width * BYTES_PER_PIXEL).div_ceil(align) * align
+}
+
+/// Tightly packed RGBA8 rows from a padded readback, swapping red and blue
+/// for BGRA targets
+pub fn unpad_capture_rows(
+    padded: &[u8],
+    width: u32,
+    height: u32,
+    padded_row: u32,
+    swap_red_blue: bool,
+) -> Vec<u8> {
+    let row = (width * BYTES_PER_PIXEL) as usize;
+    let mut rgba = Vec::with_capacity(row * height as usize);
+    for line in padded.chunks(padded_row as usize).take(height as usize) {
+        rgba.extend_from_slice(&line[..row.min(line.len())]);
+    }
+    if swap_red_blue {
+        for pixel in rgba.chunks_exact_mut(BYTES_PER_PIXEL as usize) {
+            pixel.swap(0, 2);
+        }
+    }
+    rgba
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readback_rows_are_unpadded_and_swizzled() {
+        let padded_row = padded_bytes_per_row(3);
+        assert_eq!(padded_row, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        // Two rows of three BGRA pixels, then row padding
+        let mut padded = vec![0u8; padded_row as usize * 2];
+        for y in 0..2 {
+            for x in 0..3 {
+                let at = y * padded_row as usize + x * 4;
+                padded[at..at + 4].copy_from_slice(&[10 * x as u8, 1, 2 + y as u8, 255]);
+            }
+        }
+        let rgba = unpad_capture_rows(&padded, 3, 2, padded_row, true);
+        assert_eq!(rgba.len(), 3 * 2 * 4);
+        assert_eq!(&rgba[4..8], &[2, 1, 10, 255]);
+        assert_eq!(&rgba[12..16], &[3, 1, 0, 255]);
+    }
+}
@@ -0,0 +1,71 @@
+//! Frame Pacer Data - Pure DOP
+//!
+//! Frame limiter state: the configured cap, window focus and the deadline of
+//! the next frame. Waiting sleeps until shortly before the deadline and spins
+//! the rest of the way, which keeps pacing accurate without burning a core.
+//!
+//! The pacer also splits frame time into fixed simulation ticks and keeps
+//! the leftover as the interpolation alpha used to blend entity transforms.
+//!
+//! NO METHODS - just data.
+
+use std::time::{Duration, Instant};
+
+/// Frame limiter configuration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FramePacerConfig {
+    /// Maximum frames per second while focused (None = uncapped)
+    pub fps_cap: Option<u32>,
+    /// Present with vsync; a cap at or above the display refresh rate is
+    /// then left to the swapchain
+    pub vsync: bool,
+    /// Frame rate while the window is unfocused (None = no throttling)
+    pub unfocused_fps: Option<u32>,
+    /// Final stretch before a deadline spent spinning instead of sleeping
+    pub spin_threshold: Duration,
+    /// Length of one fixed simulation tick
+    pub fixed_tick_interval: Duration,
+}
+
+/// Pacing statistics, mirrored into `MetricsBuffers::frame_pacing`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FramePacingStats {
+    /// Frames paced since creation
+    pub frames: u64,
+    /// Frames that ended past their deadline
+    pub missed_deadlines: u64,
+    /// Frames limited to the unfocused rate
+    pub throttled_frames: u64,
+    /// Time of the most recent frame including the wait (milliseconds)
+    pub last_frame_ms: f32,
+    /// Time spent waiting before the most recent frame (milliseconds)
+    pub last_wait_ms: f32,
+    /// How late the most recent missed frame was (milliseconds)
+    pub last_miss_ms: f32,
+    /// Worst lateness seen (milliseconds)
+    pub worst_miss_ms: f32,
+    /// Frame interval currently enforced (0 = not limiting)
+    pub target_frame_ms: f32,
+    /// Fixed ticks handed out since creation
+    pub fixed_ticks: u64,
+    /// Ticks dropped because a frame exceeded `MAX_TICKS_PER_FRAME`
+    pub dropped_ticks: u64,
+}
+
+/// Frame limiter state
+#[derive(Debug, Clone)]
+pub struct FramePacerData {
+    pub config: FramePacerConfig,
+    pub focused: bool,
+    /// Display refresh rate, when known
+    pub refresh_rate_hz: Option<u32>,
+    /// When the next frame may start (None until the first frame)
+    pub next_deadline: Option<Instant>,
+    /// Start of the previous frame
+    pub last_frame_start: Option<Instant>,
+    /// Frame time not yet consumed by fixed ticks
+    pub tick_accumulator: Duration,
+    /// Position between the previous and the latest tick (0..1)
+    pub interpolation_alpha: f32,
+    pub stats: FramePacingStats,
+}
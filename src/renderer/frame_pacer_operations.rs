@@ -0,0 +1,287 @@
+//! Frame Pacer Operations - Pure DOP Functions
+//!
+//! Call `wait_for_next_frame` once at the start of every frame. With vsync
+//! the swapchain already blocks at the display rate, so a cap at or above
+//! the refresh rate is skipped instead of fighting the present queue.
+//!
+//! `advance_fixed_ticks` then turns the frame time into the number of fixed
+//! simulation ticks to run; what is left over becomes the interpolation
+//! alpha that rendering uses to blend the last two ticks.
+
+use std::time::{Duration, Instant};
+
+use super::frame_pacer_data::{FramePacerConfig, FramePacerData, FramePacingStats};
+use crate::constants::frame_pacing::{
+    FIXED_TICK_RATE, MAX_FPS_CAP, MAX_TICKS_PER_FRAME, MISSED_DEADLINE_TOLERANCE_MICROS,
+    SPIN_THRESHOLD_MICROS, UNFOCUSED_FPS,
+};
+use crate::engine_buffers::MetricsBuffers;
+
+/// Uncapped, vsync on, throttled while unfocused
+pub fn default_frame_pacer_config() -> FramePacerConfig {
+    FramePacerConfig {
+        fps_cap: None,
+        vsync: true,
+        unfocused_fps: Some(UNFOCUSED_FPS),
+        spin_threshold: Duration::from_micros(SPIN_THRESHOLD_MICROS),
+        fixed_tick_interval: interval_for(FIXED_TICK_RATE),
+    }
+}
+
+fn clamp_fps(fps: Option<u32>) -> Option<u32> {
+    fps.map(|fps| fps.clamp(1, MAX_FPS_CAP))
+}
+
+/// Create a frame pacer; caps are clamped to 1..=`MAX_FPS_CAP`
+pub fn create_frame_pacer(config: FramePacerConfig) -> FramePacerData {
+    FramePacerData {
+        config: FramePacerConfig {
+            fps_cap: clamp_fps(config.fps_cap),
+            unfocused_fps: clamp_fps(config.unfocused_fps),
+            ..config
+        },
+        focused: true,
+        refresh_rate_hz: None,
+        next_deadline: None,
+        last_frame_start: None,
+        tick_accumulator: Duration::ZERO,
+        interpolation_alpha: 0.0,
+        stats: FramePacingStats::default(),
+    }
+}
+
+/// Change the focused frame rate cap (None = uncapped)
+pub fn set_fps_cap(pacer: &mut FramePacerData, fps_cap: Option<u32>) {
+    pacer.config.fps_cap = clamp_fps(fps_cap);
+    pacer.next_deadline = None;
+}
+
+/// Record whether the swapchain presents with vsync
+pub fn set_frame_pacer_vsync(pacer: &mut FramePacerData, vsync: bool) {
+    pacer.config.vsync = vsync;
+    pacer.next_deadline = None;
+}
+
+/// Track window focus; unfocused windows drop to `unfocused_fps`
+pub fn set_window_focused(pacer: &mut FramePacerData, focused: bool) {
+    if pacer.focused != focused {
+        pacer.focused = focused;
+        pacer.next_deadline = None;
+    }
+}
+
+/// Set the display refresh rate in Hz (None when the monitor is unknown)
+pub fn set_display_refresh_rate(pacer: &mut FramePacerData, refresh_rate_hz: Option<u32>) {
+    pacer.refresh_rate_hz = refresh_rate_hz.filter(|hz| *hz > 0);
+}
+
+fn interval_for(fps: u32) -> Duration {
+    Duration::from_secs_f64(1.0 / fps.max(1) as f64)
+}
+
+/// Whether the unfocused rate is currently limiting frames
+fn is_throttled(pacer: &FramePacerData) -> bool {
+    !pacer.focused && pacer.config.unfocused_fps.is_some()
+}
+
+/// Minimum time between frame starts, or None when the pacer does not limit
+pub fn frame_interval(pacer: &FramePacerData) -> Option<Duration> {
+    let vsync_covers_cap =
+        |cap: u32| pacer.config.vsync && pacer.refresh_rate_hz.is_some_and(|hz| cap >= hz);
+    let focused = pacer
+        .config
+        .fps_cap
+        .filter(|cap| !vsync_covers_cap(*cap))
+        .map(interval_for);
+
+    let unfocused = if is_throttled(pacer) {
+        pacer.config.unfocused_fps.map(interval_for)
+    } else {
+        None
+    };
+
+    // The slower of the two limits wins
+    match (focused, unfocused) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Sleep until `spin_threshold` before the deadline, then spin to it
+fn hybrid_wait_until(deadline: Instant, spin_threshold: Duration) {
+    let now = Instant::now();
+    if deadline <= now {
+        return;
+    }
+    let remaining = deadline - now;
+    if remaining > spin_threshold {
+        std::thread::sleep(remaining - spin_threshold);
+    }
+    while Instant::now() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
+fn millis(duration: Duration) -> f32 {
+    duration.as_secs_f32() * 1000.0
+}
+
+/// Block until the next frame may start and update pacing statistics.
+/// Returns the time spent waiting.
+pub fn wait_for_next_frame(pacer: &mut FramePacerData) -> Duration {
+    let interval = frame_interval(pacer);
+    let wait_start = Instant::now();
+
+    pacer.next_deadline = match (interval, pacer.next_deadline) {
+        (Some(interval), Some(deadline)) if wait_start > deadline => {
+            let late = wait_start - deadline;
+            if late > Duration::from_micros(MISSED_DEADLINE_TOLERANCE_MICROS) {
+                let late_ms = millis(late);
+                pacer.stats.missed_deadlines += 1;
+                pacer.stats.last_miss_ms = late_ms;
+                pacer.stats.worst_miss_ms = pacer.stats.worst_miss_ms.max(late_ms);
+            }
+            // Resync instead of rushing catch-up frames after a long one
+            Some(wait_start + interval)
+        }
+        (Some(interval), Some(deadline)) => {
+            hybrid_wait_until(deadline, pacer.config.spin_threshold);
+            // Advance from the deadline, not the wake-up time, to avoid drift
+            Some(deadline + interval)
+        }
+        (Some(interval), None) => Some(wait_start + interval),
+        (None, _) => None,
+    };
+
+    let frame_start = Instant::now();
+    let waited = frame_start - wait_start;
+    if let Some(last) = pacer.last_frame_start {
+        pacer.stats.last_frame_ms = millis(frame_start - last);
+    }
+    pacer.last_frame_start = Some(frame_start);
+
+    pacer.stats.frames += 1;
+    if is_throttled(pacer) {
+        pacer.stats.throttled_frames += 1;
+    }
+    pacer.stats.last_wait_ms = millis(waited);
+    pacer.stats.target_frame_ms = interval.map(millis).unwrap_or(0.0);
+    waited
+}
+
+/// Add a frame's time to the tick accumulator and return how many fixed
+/// ticks to run now. Updates the interpolation alpha for rendering.
+pub fn advance_fixed_ticks(pacer: &mut FramePacerData, frame_time: Duration) -> u32 {
+    let interval = pacer.config.fixed_tick_interval;
+    if interval.is_zero() {
+        pacer.interpolation_alpha = 1.0;
+        return 1;
+    }
+
+    pacer.tick_accumulator += frame_time;
+    let mut ticks = 0;
+    while pacer.tick_accumulator >= interval {
+        pacer.tick_accumulator -= interval;
+        ticks += 1;
+    }
+
+    if ticks > MAX_TICKS_PER_FRAME {
+        pacer.stats.dropped_ticks += (ticks - MAX_TICKS_PER_FRAME) as u64;
+        ticks = MAX_TICKS_PER_FRAME;
+    }
+    pacer.stats.fixed_ticks += ticks as u64;
+    pacer.interpolation_alpha =
+        (pacer.tick_accumulator.as_secs_f32() / interval.as_secs_f32()).clamp(0.0, 1.0);
+    ticks
+}
+
+/// Blend factor between the previous and the latest fixed tick
+pub fn interpolation_alpha(pacer: &FramePacerData) -> f32 {
+    pacer.interpolation_alpha
+}
+
+/// Change the fixed simulation tick rate (ticks per second)
+pub fn set_fixed_tick_rate(pacer: &mut FramePacerData, ticks_per_second: u32) {
+    pacer.config.fixed_tick_interval = interval_for(ticks_per_second.max(1));
+    pacer.tick_accumulator = Duration::ZERO;
+}
+
+/// Copy pacing statistics into the metrics buffers
+pub fn record_frame_pacing(metrics: &mut MetricsBuffers, pacer: &FramePacerData) {
+    metrics.frame_pacing = pacer.stats;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capped(fps: u32) -> FramePacerData {
+        create_frame_pacer(FramePacerConfig {
+            fps_cap: Some(fps),
+            ..default_frame_pacer_config()
+        })
+    }
+
+    #[test]
+    fn test_frame_interval_limits() {
+        let mut pacer = capped(100);
+        assert_eq!(frame_interval(&pacer), Some(Duration::from_millis(10)));
+
+        // Vsync at 60Hz already holds frames below a 100 fps cap
+        set_display_refresh_rate(&mut pacer, Some(60));
+        assert_eq!(frame_interval(&pacer), None);
+        set_frame_pacer_vsync(&mut pacer, false);
+        assert_eq!(frame_interval(&pacer), Some(Duration::from_millis(10)));
+
+        set_window_focused(&mut pacer, false);
+        assert_eq!(frame_interval(&pacer), Some(interval_for(UNFOCUSED_FPS)));
+
+        set_fps_cap(&mut pacer, Some(0));
+        assert_eq!(pacer.config.fps_cap, Some(1));
+        assert_eq!(frame_interval(&pacer), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_wait_paces_and_counts_missed_deadlines() {
+        let mut pacer = capped(200);
+        let start = Instant::now();
+        for _ in 0..4 {
+            wait_for_next_frame(&mut pacer);
+        }
+        // First frame starts immediately, then three 5ms intervals
+        assert!(start.elapsed() >= Duration::from_millis(15));
+        assert_eq!(pacer.stats.frames, 4);
+
+        let missed = pacer.stats.missed_deadlines;
+        std::thread::sleep(Duration::from_millis(20));
+        wait_for_next_frame(&mut pacer);
+        assert_eq!(pacer.stats.missed_deadlines, missed + 1);
+        assert!(pacer.stats.worst_miss_ms >= 10.0);
+
+        let mut metrics = MetricsBuffers::default();
+        record_frame_pacing(&mut metrics, &pacer);
+        assert_eq!(metrics.frame_pacing.frames, 5);
+    }
+
+    #[test]
+    fn test_fixed_ticks_and_interpolation_alpha() {
+        let mut pacer = create_frame_pacer(default_frame_pacer_config());
+        set_fixed_tick_rate(&mut pacer, 50);
+
+        assert_eq!(
+            advance_fixed_ticks(&mut pacer, Duration::from_millis(10)),
+            0
+        );
+        assert!((interpolation_alpha(&pacer) - 0.5).abs() < 1e-3);
+        assert_eq!(
+            advance_fixed_ticks(&mut pacer, Duration::from_millis(35)),
+            2
+        );
+        assert!((interpolation_alpha(&pacer) - 0.25).abs() < 1e-3);
+
+        // A long stall is capped instead of replayed
+        let ticks = advance_fixed_ticks(&mut pacer, Duration::from_secs(1));
+        assert_eq!(ticks, MAX_TICKS_PER_FRAME);
+        assert_eq!(pacer.stats.dropped_ticks, 50 - MAX_TICKS_PER_FRAME as u64);
+    }
+}
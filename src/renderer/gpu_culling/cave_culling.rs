@@ -0,0 +1,190 @@
+//! Cave Culling
+//!
+//! Per-frame traversal of the chunk visibility graph starting at the camera
+//! chunk. A neighbour is only entered if the current chunk connects the face
+//! we came in through to the face we leave through, and the traversal never
+//! turns back toward the camera. Enclosed underground chunks are never
+//! reached, so they are dropped before frustum and occlusion culling.
+
+use crate::gpu::buffer_layouts::DrawMetadata;
+use crate::world::compute::{
+    face_offset, faces_connected, opposite_face, ChunkFace, ALL_FACES_CONNECTED, CHUNK_FACES,
+};
+use crate::world::core::ChunkPos;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Per-chunk face connectivity, filled from generation results
+#[derive(Debug, Default)]
+pub struct VisibilityGraphData {
+    pub connectivity: HashMap<ChunkPos, u16>,
+}
+
+/// Result of one cave culling traversal
+#[derive(Debug, Default)]
+pub struct CaveCullingResult {
+    pub visible: HashSet<ChunkPos>,
+    pub chunks_visited: u32,
+}
+
+/// Store connectivity for a chunk (e.g. from `ChunkConnectivityCompute::poll_results`)
+pub fn set_chunk_connectivity(
+    graph: &mut VisibilityGraphData,
+    chunk_pos: ChunkPos,
+    connectivity: u16,
+) {
+    graph.connectivity.insert(chunk_pos, connectivity);
+}
+
+/// Forget a chunk (unloaded or modified; it is treated as open until recomputed)
+pub fn remove_chunk_connectivity(graph: &mut VisibilityGraphData, chunk_pos: ChunkPos) {
+    graph.connectivity.remove(&chunk_pos);
+}
+
+/// Connectivity of a chunk; unknown chunks are treated as fully open
+pub fn chunk_connectivity(graph: &VisibilityGraphData, chunk_pos: ChunkPos) -> u16 {
+    graph
+        .connectivity
+        .get(&chunk_pos)
+        .copied()
+        .unwrap_or(ALL_FACES_CONNECTED)
+}
+
+/// Pending traversal node
+struct TraversalStep {
+    chunk_pos: ChunkPos,
+    /// Face we entered through (None for the camera chunk)
+    entry_face: Option<ChunkFace>,
+    /// Directions travelled so far, one bit per face
+    directions: u8,
+}
+
+fn direction_bit(face: ChunkFace) -> u8 {
+    1 << face as u8
+}
+
+/// Flood the visibility graph from the camera chunk
+///
+/// `in_frustum` lets the caller prune chunks outside the view frustum during
+/// the traversal; pass `|_| true` to disable.
+pub fn cave_culling_traversal<F>(
+    graph: &VisibilityGraphData,
+    camera_chunk: ChunkPos,
+    max_distance: i32,
+    in_frustum: F,
+) -> CaveCullingResult
+where
+    F: Fn(ChunkPos) -> bool,
+{
+    let _span = crate::trace_span!(Culling, "cave_culling_traversal");
+    let mut result = CaveCullingResult::default();
+    let mut queue = VecDeque::new();
+
+    result.visible.insert(camera_chunk);
+    queue.push_back(TraversalStep {
+        chunk_pos: camera_chunk,
+        entry_face: None,
+        directions: 0,
+    });
+
+    while let Some(TraversalStep {
+        chunk_pos,
+        entry_face,
+        directions,
+    }) = queue.pop_front()
+    {
+        result.chunks_visited += 1;
+        let connectivity = chunk_connectivity(graph, chunk_pos);
+
+        for exit_face in CHUNK_FACES {
+            // Never travel back toward the camera
+            if directions & direction_bit(opposite_face(exit_face)) != 0 {
+                continue;
+            }
+
+            if let Some(entry) = entry_face {
+                if !faces_connected(connectivity, entry, exit_face) {
+                    continue;
+                }
+            }
+
+            let [dx, dy, dz] = face_offset(exit_face);
+            let next = chunk_pos.offset(dx, dy, dz);
+
+            if (next.x - camera_chunk.x).abs() > max_distance
+                || (next.y - camera_chunk.y).abs() > max_distance
+                || (next.z - camera_chunk.z).abs() > max_distance
+            {
+                continue;
+            }
+
+            if result.visible.contains(&next) || !in_frustum(next) {
+                continue;
+            }
+
+            result.visible.insert(next);
+            queue.push_back(TraversalStep {
+                chunk_pos: next,
+                entry_face: Some(opposite_face(exit_face)),
+                directions: directions | direction_bit(exit_face),
+            });
+        }
+    }
+
+    result
+}
+
+/// Clear the visible flag on draws whose chunk was not reached.
+/// Returns the number of draws culled.
+pub fn apply_cave_culling(
+    metadata: &mut [DrawMetadata],
+    chunk_positions: &[ChunkPos],
+    result: &CaveCullingResult,
+) -> u32 {
+    let mut culled = 0;
+    for (meta, chunk_pos) in metadata.iter_mut().zip(chunk_positions) {
+        if !result.visible.contains(chunk_pos) && meta.is_visible() {
+            meta.flags &= !DrawMetadata::FLAG_VISIBLE;
+            culled += 1;
+        }
+    }
+    culled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enclosed_chunk_is_culled() {
+        let mut graph = VisibilityGraphData::default();
+        let camera = ChunkPos::new(0, 1, 0);
+
+        // Solid ground layer under the camera; nothing below it is reachable
+        for x in -2..=2 {
+            for z in -2..=2 {
+                set_chunk_connectivity(&mut graph, ChunkPos::new(x, 0, z), 0);
+            }
+        }
+        set_chunk_connectivity(&mut graph, ChunkPos::new(0, -1, 0), 0);
+
+        let result = cave_culling_traversal(&graph, camera, 2, |_| true);
+
+        // The solid layer itself is visible (its top faces can be seen)...
+        assert!(result.visible.contains(&ChunkPos::new(0, 0, 0)));
+        // ...but the cave below it is not
+        assert!(!result.visible.contains(&ChunkPos::new(0, -1, 0)));
+        assert!(result.visible.contains(&ChunkPos::new(1, 1, 1)));
+    }
+
+    #[test]
+    fn test_apply_cave_culling_clears_visible_flag() {
+        let mut metadata = vec![DrawMetadata::new([0.0; 3], 1.0, 0, 0); 2];
+        let positions = [ChunkPos::new(0, 0, 0), ChunkPos::new(5, 5, 5)];
+        let mut result = CaveCullingResult::default();
+        result.visible.insert(positions[0]);
+
+        assert_eq!(apply_cave_culling(&mut metadata, &positions, &result), 1);
+        assert!(metadata[0].is_visible());
+        assert!(!metadata[1].is_visible());
+    }
+}
@@ -0,0 +1,226 @@
+//! Chunk Culling
+//!
+//! Frustum and distance culling of chunk draws on the GPU (gpu_culling.wgsl).
+//! Draws arrive as `DrawMetadata` with cave culling already applied, so
+//! chunks the cave traversal did not reach never get an indirect command.
+//! Streamed prop cells are culled the same way with instanced draws.
+//! The indirect buffer grows to fit and is rewritten by every dispatch.
+
+use super::GpuCamera;
+use crate::gpu::buffer_layouts::{DrawMetadata, IndirectDrawIndexedCommand};
+use crate::memory::{
+    create_tracked_buffer, create_tracked_buffer_init, register_global_gpu_owner,
+    GpuAllocationGuard, GpuOwnerGuard,
+};
+
+/// Draws that start out fitting in the indirect buffer
+const INITIAL_DRAW_CAPACITY: u32 = 256;
+
+/// Threads per workgroup of `cull_objects`
+const CULL_WORKGROUP_SIZE: u32 = 64;
+
+/// Counters written by `cull_objects` (`CullingStats` in gpu_culling.wgsl)
+const CULL_STATS_SIZE: u64 = 16;
+
+pub struct ChunkCulling {
+    cull_pipeline: wgpu::ComputePipeline,
+    reset_pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    indirect_buffer: wgpu::Buffer,
+    draw_count_buffer: wgpu::Buffer,
+    stats_buffer: wgpu::Buffer,
+    draw_capacity: u32,
+    indirect_allocation: GpuAllocationGuard,
+    counter_allocations: [GpuAllocationGuard; 2],
+    gpu_owner: GpuOwnerGuard,
+}
+
+impl ChunkCulling {
+    pub fn new(device: &wgpu::Device) -> Result<Self, String> {
+        let shader = crate::gpu::automation::create_gpu_shader(
+            device,
+            "gpu_culling",
+            include_str!("../../shaders/rendering/gpu_culling.wgsl"),
+        )
+        .map_err(|e| format!("Failed to create chunk culling shader: {}", e))?;
+
+        let buffer_entry = |binding: u32, ty: wgpu::BufferBindingType| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Chunk Culling Bind Group Layout"),
+            entries: &[
+                buffer_entry(0, wgpu::BufferBindingType::Uniform),
+                buffer_entry(1, wgpu::BufferBindingType::Storage { read_only: true }),
+                buffer_entry(2, wgpu::BufferBindingType::Storage { read_only: false }),
+                buffer_entry(3, wgpu::BufferBindingType::Storage { read_only: false }),
+                buffer_entry(4, wgpu::BufferBindingType::Storage { read_only: false }),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Chunk Culling Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |label, entry_point| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                module: &shader.module,
+                entry_point,
+            })
+        };
+
+        let gpu_owner = register_global_gpu_owner("chunk_culling");
+        let (indirect_buffer, indirect_allocation) =
+            create_indirect_buffer(device, &gpu_owner, INITIAL_DRAW_CAPACITY);
+        let (draw_count_buffer, draw_count_allocation) =
+            create_counter_buffer(device, &gpu_owner, "Chunk Culling Draw Count", 4);
+        let (stats_buffer, stats_allocation) =
+            create_counter_buffer(device, &gpu_owner, "Chunk Culling Stats", CULL_STATS_SIZE);
+
+        Ok(Self {
+            cull_pipeline: pipeline("Chunk Culling Pipeline", "cull_objects"),
+            reset_pipeline: pipeline("Chunk Culling Reset Pipeline", "reset_counters"),
+            bind_group_layout,
+            indirect_buffer,
+            draw_count_buffer,
+            stats_buffer,
+            draw_capacity: INITIAL_DRAW_CAPACITY,
+            indirect_allocation,
+            counter_allocations: [draw_count_allocation, stats_allocation],
+            gpu_owner,
+        })
+    }
+
+    /// Record the culling of `draws` from `camera` into `encoder`; visible
+    /// draws get an indirect command, counted in `draw_count_buffer`
+    pub fn encode(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        camera: &GpuCamera,
+        draws: &[DrawMetadata],
+    ) {
+        if draws.is_empty() {
+            return;
+        }
+        let _span = crate::trace_span!(Culling, "ChunkCulling::encode");
+        let draw_count = draws.len() as u32;
+        if draw_count > self.draw_capacity {
+            self.draw_capacity = draw_count.next_power_of_two();
+            (self.indirect_buffer, self.indirect_allocation) =
+                create_indirect_buffer(device, &self.gpu_owner, self.draw_capacity);
+        }
+
+        // Sized to the draws: the shader culls arrayLength(&draw_metadata)
+        let (camera_buffer, _camera_allocation) = create_tracked_buffer_init(
+            device,
+            &self.gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Chunk Culling Camera"),
+                contents: bytemuck::bytes_of(camera),
+                usage: wgpu::BufferUsages::UNIFORM,
+            },
+        );
+        let (draw_buffer, _draw_allocation) = create_tracked_buffer_init(
+            device,
+            &self.gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Chunk Culling Draws"),
+                contents: bytemuck::cast_slice(draws),
+                usage: wgpu::BufferUsages::STORAGE,
+            },
+        );
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Chunk Culling Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: draw_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.indirect_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.draw_count_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: self.stats_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Chunk Culling Pass"),
+            timestamp_writes: None,
+        });
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.set_pipeline(&self.reset_pipeline);
+        pass.dispatch_workgroups(1, 1, 1);
+        pass.set_pipeline(&self.cull_pipeline);
+        pass.dispatch_workgroups(draw_count.div_ceil(CULL_WORKGROUP_SIZE), 1, 1);
+    }
+
+    /// Indirect commands of the draws that passed the last dispatch
+    pub fn indirect_buffer(&self) -> &wgpu::Buffer {
+        &self.indirect_buffer
+    }
+
+    /// Number of commands in `indirect_buffer`
+    pub fn draw_count_buffer(&self) -> &wgpu::Buffer {
+        &self.draw_count_buffer
+    }
+}
+
+fn create_indirect_buffer(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    capacity: u32,
+) -> (wgpu::Buffer, GpuAllocationGuard) {
+    create_tracked_buffer(
+        device,
+        owner,
+        &wgpu::BufferDescriptor {
+            label: Some("Chunk Culling Indirect Commands"),
+            size: capacity as u64 * std::mem::size_of::<IndirectDrawIndexedCommand>() as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::INDIRECT,
+            mapped_at_creation: false,
+        },
+    )
+}
+
+fn create_counter_buffer(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    label: &str,
+    size: u64,
+) -> (wgpu::Buffer, GpuAllocationGuard) {
+    create_tracked_buffer(
+        device,
+        owner,
+        &wgpu::BufferDescriptor {
+            label: Some(label),
+            size,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::INDIRECT
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        },
+    )
+}
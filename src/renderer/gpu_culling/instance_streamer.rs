@@ -1,4 +1,163 @@
-//! Instance Streamer - Stub
-pub struct InstanceStreamer;
+//! Instance Streamer
+//!
+//! Keeps streamed instances on the GPU: the instance buffer the draw reads,
+//! one `ChunkInstance` culling record per streamed cell for `GpuCullingSystem`,
+//! each cell's instance range and its instanced draw for `ChunkCulling`. Buffers grow to fit and are rewritten
+//! whole on every upload, which happens only when the streamed set changes.
 
-pub struct StreamingMetrics;
+use super::ChunkInstance;
+use crate::gpu::buffer_layouts::instance::InstanceData;
+use crate::gpu::buffer_layouts::DrawMetadata;
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
+use crate::renderer::prop_instance_data::{PackedPropInstances, PropDrawRange};
+
+/// Instances and culling records that start out fitting in the buffers
+const INITIAL_INSTANCE_CAPACITY: u32 = 4096;
+const INITIAL_CELL_CAPACITY: u32 = 256;
+
+pub struct InstanceStreamer {
+    instance_buffer: wgpu::Buffer,
+    cell_buffer: wgpu::Buffer,
+    instance_capacity: u32,
+    cell_capacity: u32,
+    ranges: Vec<PropDrawRange>,
+    draws: Vec<DrawMetadata>,
+    metrics: StreamingMetrics,
+    instance_allocation: GpuAllocationGuard,
+    cell_allocation: GpuAllocationGuard,
+    gpu_owner: GpuOwnerGuard,
+}
+
+/// Upload counters since the streamer was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamingMetrics {
+    pub uploads: u64,
+    pub bytes_uploaded: u64,
+    /// Times a buffer was recreated larger
+    pub reallocations: u64,
+    pub instance_count: u32,
+    pub cell_count: u32,
+}
+
+impl InstanceStreamer {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let gpu_owner = register_global_gpu_owner("instance_streamer");
+        let (instance_buffer, instance_allocation) =
+            create_instance_buffer(device, &gpu_owner, INITIAL_INSTANCE_CAPACITY);
+        let (cell_buffer, cell_allocation) =
+            create_cell_buffer(device, &gpu_owner, INITIAL_CELL_CAPACITY);
+        Self {
+            instance_buffer,
+            cell_buffer,
+            instance_capacity: INITIAL_INSTANCE_CAPACITY,
+            cell_capacity: INITIAL_CELL_CAPACITY,
+            ranges: Vec::new(),
+            draws: Vec::new(),
+            metrics: StreamingMetrics::default(),
+            instance_allocation,
+            cell_allocation,
+            gpu_owner,
+        }
+    }
+
+    /// Replace the streamed set with freshly packed props
+    pub fn upload_props(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        packed: &PackedPropInstances,
+    ) -> StreamingMetrics {
+        let _span = crate::trace_span!(Render, "InstanceStreamer::upload_props");
+        let instance_count = packed.instances.len() as u32;
+        let cell_count = packed.cells.len() as u32;
+        if instance_count > self.instance_capacity {
+            self.instance_capacity = instance_count.next_power_of_two();
+            (self.instance_buffer, self.instance_allocation) =
+                create_instance_buffer(device, &self.gpu_owner, self.instance_capacity);
+            self.metrics.reallocations += 1;
+        }
+        if cell_count > self.cell_capacity {
+            self.cell_capacity = cell_count.next_power_of_two();
+            (self.cell_buffer, self.cell_allocation) =
+                create_cell_buffer(device, &self.gpu_owner, self.cell_capacity);
+            self.metrics.reallocations += 1;
+        }
+
+        let instances: &[u8] = bytemuck::cast_slice(&packed.instances);
+        let cells: &[u8] = bytemuck::cast_slice(&packed.cells);
+        queue.write_buffer(&self.instance_buffer, 0, instances);
+        queue.write_buffer(&self.cell_buffer, 0, cells);
+        self.ranges.clone_from(&packed.ranges);
+        self.draws.clone_from(&packed.draws);
+
+        self.metrics.uploads += 1;
+        self.metrics.bytes_uploaded += (instances.len() + cells.len()) as u64;
+        self.metrics.instance_count = instance_count;
+        self.metrics.cell_count = cell_count;
+        self.metrics
+    }
+
+    /// Per-instance vertex buffer (`InstanceBufferLayout::vertex_layout`)
+    pub fn instance_buffer(&self) -> &wgpu::Buffer {
+        &self.instance_buffer
+    }
+
+    /// Culling records to pass to `GpuCullingSystem::cull` with `cell_count`
+    pub fn cell_buffer(&self) -> &wgpu::Buffer {
+        &self.cell_buffer
+    }
+
+    pub fn cell_count(&self) -> u32 {
+        self.metrics.cell_count
+    }
+
+    /// Instance range of each culling record
+    pub fn draw_ranges(&self) -> &[PropDrawRange] {
+        &self.ranges
+    }
+
+    /// Instanced draw of each culling record, to cull with `ChunkCulling`
+    pub fn culling_draws(&self) -> &[DrawMetadata] {
+        &self.draws
+    }
+
+    pub fn metrics(&self) -> StreamingMetrics {
+        self.metrics
+    }
+}
+
+fn create_instance_buffer(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    capacity: u32,
+) -> (wgpu::Buffer, GpuAllocationGuard) {
+    create_tracked_buffer(
+        device,
+        owner,
+        &wgpu::BufferDescriptor {
+            label: Some("Streamed Instance Buffer"),
+            size: capacity as u64 * std::mem::size_of::<InstanceData>() as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    )
+}
+
+fn create_cell_buffer(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    capacity: u32,
+) -> (wgpu::Buffer, GpuAllocationGuard) {
+    create_tracked_buffer(
+        device,
+        owner,
+        &wgpu::BufferDescriptor {
+            label: Some("Streamed Cell Culling Buffer"),
+            size: capacity as u64 * std::mem::size_of::<ChunkInstance>() as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    )
+}
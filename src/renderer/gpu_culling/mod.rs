@@ -6,11 +6,18 @@ use cgmath::{Matrix4, Vector3, Vector4};
 /// Manages frustum and occlusion culling entirely on GPU.
 use wgpu::{Buffer, Device, Queue};
 
+pub mod cave_culling;
+pub mod chunk_culling;
 pub mod frustum_culler;
 pub mod hzb_builder;
 pub mod indirect_renderer;
 pub mod instance_streamer;
 
+pub use cave_culling::{
+    apply_cave_culling, cave_culling_traversal, chunk_connectivity, remove_chunk_connectivity,
+    set_chunk_connectivity, CaveCullingResult, VisibilityGraphData,
+};
+pub use chunk_culling::ChunkCulling;
 pub use frustum_culler::FrustumCuller;
 pub use hzb_builder::HierarchicalZBuffer;
 pub use indirect_renderer::IndirectRenderer;
@@ -86,7 +93,7 @@ impl NormalizePlane for Vector4<f32> {
 
 /// Chunk instance data for culling
 #[repr(C)]
-#[derive(Clone, Copy, Pod, Zeroable)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct ChunkInstance {
     pub world_position: [f32; 3],
     pub chunk_size: f32,
@@ -95,6 +102,18 @@ pub struct ChunkInstance {
     _padding: [f32; 2],
 }
 
+impl ChunkInstance {
+    pub fn new(world_position: [f32; 3], chunk_size: f32, lod_level: u32, flags: u32) -> Self {
+        Self {
+            world_position,
+            chunk_size,
+            lod_level,
+            flags,
+            _padding: [0.0; 2],
+        }
+    }
+}
+
 /// Indirect draw command
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
@@ -124,6 +143,8 @@ pub struct GpuCullingSystem {
     // Statistics
     stats_buffer: Buffer,
     stats_readback: Buffer,
+    gpu_allocations: Vec<crate::memory::GpuAllocationGuard>,
+    gpu_owner: crate::memory::GpuOwnerGuard,
 }
 
 impl GpuCullingSystem {
@@ -133,14 +154,23 @@ impl GpuCullingSystem {
         let indirect_renderer = IndirectRenderer::new(device, max_chunks);
 
         // Create stats buffers
-        let stats_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let gpu_owner = crate::memory::register_global_gpu_owner("gpu_culling");
+        let mut gpu_allocations = Vec::new();
+        let mut create_buffer = |descriptor: &wgpu::BufferDescriptor| {
+            let (buffer, guard) =
+                crate::memory::create_tracked_buffer(device, &gpu_owner, descriptor);
+            gpu_allocations.push(guard);
+            buffer
+        };
+
+        let stats_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Culling Stats Buffer"),
             size: std::mem::size_of::<CullingStats>() as u64,
             usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
-        let stats_readback = device.create_buffer(&wgpu::BufferDescriptor {
+        let stats_readback = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Culling Stats Readback"),
             size: std::mem::size_of::<CullingStats>() as u64,
             usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
@@ -153,6 +183,8 @@ impl GpuCullingSystem {
             indirect_renderer,
             stats_buffer,
             stats_readback,
+            gpu_allocations,
+            gpu_owner,
         }
     }
 
@@ -166,8 +198,14 @@ impl GpuCullingSystem {
         chunk_count: u32,
         depth_texture: &wgpu::TextureView,
     ) -> Option<&Buffer> {
+        let _span = crate::trace_span!(Culling, "gpu_culling::cull");
+        let occlusion = crate::feature_flags::feature_enabled(
+            crate::feature_flags::FLAG_HZB_OCCLUSION,
+        );
         // Step 1: Build HZB from depth buffer
-        self.hzb.build(encoder, depth_texture);
+        if occlusion {
+            self.hzb.build(encoder, depth_texture);
+        }
 
         // Step 2: Frustum culling
         let frustum_visible = self.frustum_culler.cull(
@@ -180,9 +218,12 @@ impl GpuCullingSystem {
         );
 
         // Step 3: Occlusion culling using HZB
-        let final_visible =
+        let final_visible = if occlusion {
             self.hzb
-                .cull_occlusion(encoder, camera, chunk_instances, frustum_visible);
+                .cull_occlusion(encoder, camera, chunk_instances, frustum_visible)
+        } else {
+            frustum_visible
+        };
 
         // Step 4: Generate indirect draw commands
         self.indirect_renderer
@@ -233,6 +274,7 @@ impl GpuCullingSystem {
 #[derive(Debug, Default)]
 pub struct GpuCullingMetrics {
     pub total_chunks: u32,
+    pub visible_after_cave: u32,
     pub visible_after_frustum: u32,
     pub visible_after_occlusion: u32,
     pub culling_time_ms: f32,
@@ -0,0 +1,484 @@
+//! Mesh arenas - shared vertex and index buffers with extent allocation
+//!
+//! Chunk meshes are sub-allocated from one large vertex buffer and one large
+//! index buffer. Mesh churn leaves holes behind, so `defragment_mesh_arena`
+//! compacts live meshes toward the start of the buffer during idle frames.
+//! Each move is a pair of `copy_buffer_to_buffer` calls through a scratch
+//! buffer (wgpu forbids overlapping copies within one buffer), followed by a
+//! copy that patches the mesh's `base_vertex` (vertex arena) or `first_index`
+//! (index arena) in the indirect draw buffer. All three are encoded into the
+//! same command stream, so no draw can observe a moved mesh with a stale
+//! offset.
+
+use crate::gpu::buffer_layouts::IndirectDrawIndexedCommand;
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
+use crate::world::core::ChunkPos;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Default vertex arena size (64MB)
+pub const DEFAULT_MESH_ARENA_SIZE: u64 = 64 * 1024 * 1024;
+/// Default index arena size (32MB)
+pub const DEFAULT_MESH_INDEX_ARENA_SIZE: u64 = 32 * 1024 * 1024;
+/// Maximum meshes relocated in a single defragmentation step
+pub const MAX_DEFRAG_MOVES_PER_STEP: usize = 64;
+/// Fragmentation ratio above which idle frames should compact the arena
+pub const DEFRAG_FRAGMENTATION_THRESHOLD: f32 = 0.3;
+
+/// Live mesh in the arena
+#[derive(Debug, Clone, Copy)]
+pub struct MeshArenaAllocation {
+    pub chunk_pos: ChunkPos,
+    /// Byte offset into the vertex buffer (multiple of the vertex stride)
+    pub offset: u64,
+    /// Size in bytes
+    pub size: u64,
+    /// Index of this mesh's command in the indirect draw buffer
+    pub draw_index: u32,
+}
+
+/// Unused range of the arena
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FreeExtent {
+    pub offset: u64,
+    pub size: u64,
+}
+
+/// Arena fragmentation metrics
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MeshArenaStats {
+    pub capacity: u64,
+    pub used_bytes: u64,
+    pub free_bytes: u64,
+    pub largest_free_extent: u64,
+    pub free_extent_count: u32,
+    pub live_meshes: u32,
+    /// 0.0 = all free space contiguous, 1.0 = free space fully scattered
+    pub fragmentation: f32,
+    pub total_moves: u64,
+    pub total_bytes_moved: u64,
+}
+
+/// Per-step defragmentation limits
+#[derive(Debug, Clone, Copy)]
+pub struct DefragBudget {
+    pub max_bytes: u64,
+    pub max_time: Duration,
+}
+
+/// Outcome of one defragmentation step
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefragStepResult {
+    pub meshes_moved: u32,
+    pub bytes_moved: u64,
+    /// True when the arena is fully compacted
+    pub complete: bool,
+}
+
+/// Shared vertex or index arena state
+pub struct MeshArena {
+    pub buffer: wgpu::Buffer,
+    /// Staging area for relocations (sized for the largest allowed mesh)
+    pub scratch_buffer: wgpu::Buffer,
+    /// New base_vertex or first_index values, copied into the indirect buffer
+    pub patch_buffer: wgpu::Buffer,
+    pub capacity: u64,
+    /// Bytes per vertex or index
+    pub stride: u64,
+    /// Byte offset of the field a move patches in `IndirectDrawIndexedCommand`
+    pub draw_field: u64,
+    pub allocations: HashMap<ChunkPos, MeshArenaAllocation>,
+    /// Sorted by offset, adjacent extents coalesced
+    pub free_extents: Vec<FreeExtent>,
+    pub stats: MeshArenaStats,
+    pub gpu_allocations: Vec<GpuAllocationGuard>,
+    pub gpu_owner: GpuOwnerGuard,
+}
+
+/// Create an arena of `capacity` bytes for vertices of `vertex_stride` bytes
+pub fn create_mesh_arena(device: &wgpu::Device, capacity: u64, vertex_stride: u64) -> MeshArena {
+    let vertex_stride = vertex_stride.max(wgpu::COPY_BUFFER_ALIGNMENT);
+    create_arena(
+        device,
+        "Mesh Vertex Arena",
+        capacity,
+        vertex_stride,
+        super::MAX_VERTICES_PER_CHUNK as u64 * vertex_stride,
+        wgpu::BufferUsages::VERTEX,
+        std::mem::offset_of!(IndirectDrawIndexedCommand, base_vertex) as u64,
+    )
+}
+
+/// Create an arena of `capacity` bytes for u32 indices
+pub fn create_mesh_index_arena(device: &wgpu::Device, capacity: u64) -> MeshArena {
+    let index_stride = std::mem::size_of::<u32>() as u64;
+    create_arena(
+        device,
+        "Mesh Index Arena",
+        capacity,
+        index_stride,
+        super::MAX_INDICES_PER_CHUNK as u64 * index_stride,
+        wgpu::BufferUsages::INDEX,
+        std::mem::offset_of!(IndirectDrawIndexedCommand, first_index) as u64,
+    )
+}
+
+fn create_arena(
+    device: &wgpu::Device,
+    label: &str,
+    capacity: u64,
+    stride: u64,
+    max_mesh_size: u64,
+    usage: wgpu::BufferUsages,
+    draw_field: u64,
+) -> MeshArena {
+    let capacity = capacity - capacity % stride;
+    let scratch_size = max_mesh_size.min(capacity);
+
+    let gpu_owner = register_global_gpu_owner("mesh_arena");
+    let mut gpu_allocations = Vec::new();
+    let mut create_buffer = |descriptor: &wgpu::BufferDescriptor| {
+        let (buffer, guard) = create_tracked_buffer(device, &gpu_owner, descriptor);
+        gpu_allocations.push(guard);
+        buffer
+    };
+
+    let buffer = create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size: capacity,
+        usage: usage
+            | wgpu::BufferUsages::STORAGE
+            | wgpu::BufferUsages::COPY_SRC
+            | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let scratch_buffer = create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Mesh Arena Defrag Scratch"),
+        size: scratch_size,
+        usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let patch_buffer = create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Mesh Arena Draw Patches"),
+        size: (MAX_DEFRAG_MOVES_PER_STEP * std::mem::size_of::<i32>()) as u64,
+        usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    MeshArena {
+        buffer,
+        scratch_buffer,
+        patch_buffer,
+        capacity,
+        stride,
+        draw_field,
+        allocations: HashMap::new(),
+        free_extents: vec![FreeExtent {
+            offset: 0,
+            size: capacity,
+        }],
+        stats: MeshArenaStats {
+            capacity,
+            ..Default::default()
+        },
+        gpu_allocations,
+        gpu_owner,
+    }
+}
+
+// ============================================================================
+// ALLOCATION
+// ============================================================================
+
+/// Allocate `size` bytes for a chunk mesh (first fit). Any previous
+/// allocation for the chunk is released first. Returns the byte offset.
+pub fn allocate_mesh(
+    arena: &mut MeshArena,
+    chunk_pos: ChunkPos,
+    size: u64,
+    draw_index: u32,
+) -> Option<u64> {
+    free_mesh(arena, chunk_pos);
+
+    let size = size.div_ceil(arena.stride) * arena.stride;
+    if size == 0 {
+        return None;
+    }
+
+    let index = arena
+        .free_extents
+        .iter()
+        .position(|extent| extent.size >= size)?;
+    let extent = &mut arena.free_extents[index];
+    let offset = extent.offset;
+    extent.offset += size;
+    extent.size -= size;
+    if extent.size == 0 {
+        arena.free_extents.remove(index);
+    }
+
+    arena.allocations.insert(
+        chunk_pos,
+        MeshArenaAllocation {
+            chunk_pos,
+            offset,
+            size,
+            draw_index,
+        },
+    );
+    update_arena_stats(arena);
+    Some(offset)
+}
+
+/// Release a chunk's mesh, coalescing the freed range with its neighbours
+pub fn free_mesh(arena: &mut MeshArena, chunk_pos: ChunkPos) -> bool {
+    let Some(allocation) = arena.allocations.remove(&chunk_pos) else {
+        return false;
+    };
+    insert_free_extent(
+        &mut arena.free_extents,
+        FreeExtent {
+            offset: allocation.offset,
+            size: allocation.size,
+        },
+    );
+    update_arena_stats(arena);
+    true
+}
+
+fn insert_free_extent(extents: &mut Vec<FreeExtent>, extent: FreeExtent) {
+    let index = extents.partition_point(|e| e.offset < extent.offset);
+    extents.insert(index, extent);
+
+    // Merge with the following extent, then with the preceding one
+    if index + 1 < extents.len()
+        && extents[index].offset + extents[index].size == extents[index + 1].offset
+    {
+        extents[index].size += extents[index + 1].size;
+        extents.remove(index + 1);
+    }
+    if index > 0 && extents[index - 1].offset + extents[index - 1].size == extents[index].offset {
+        extents[index - 1].size += extents[index].size;
+        extents.remove(index);
+    }
+}
+
+/// Base vertex (vertex arena) or first index (index arena) of a mesh, for
+/// building its indirect draw command
+pub fn mesh_base_vertex(arena: &MeshArena, chunk_pos: ChunkPos) -> Option<i32> {
+    arena
+        .allocations
+        .get(&chunk_pos)
+        .map(|allocation| (allocation.offset / arena.stride) as i32)
+}
+
+// ============================================================================
+// FRAGMENTATION
+// ============================================================================
+
+fn update_arena_stats(arena: &mut MeshArena) {
+    let free_bytes: u64 = arena.free_extents.iter().map(|e| e.size).sum();
+    let largest = arena.free_extents.iter().map(|e| e.size).max().unwrap_or(0);
+
+    let stats = &mut arena.stats;
+    stats.free_bytes = free_bytes;
+    stats.used_bytes = arena.capacity - free_bytes;
+    stats.largest_free_extent = largest;
+    stats.free_extent_count = arena.free_extents.len() as u32;
+    stats.live_meshes = arena.allocations.len() as u32;
+    stats.fragmentation = if free_bytes > 0 {
+        1.0 - largest as f32 / free_bytes as f32
+    } else {
+        0.0
+    };
+}
+
+/// Current fragmentation metrics
+pub fn mesh_arena_stats(arena: &MeshArena) -> MeshArenaStats {
+    arena.stats
+}
+
+/// Publish the worst fragmentation of `arenas` to the engine metrics read
+/// by the system monitor
+pub fn record_mesh_arena_metrics(
+    arenas: &[&MeshArena],
+    metrics: &mut crate::engine_buffers::MetricsBuffers,
+) {
+    metrics.mesh_arena_fragmentation = arenas
+        .iter()
+        .map(|arena| arena.stats.fragmentation)
+        .fold(0.0, f32::max);
+}
+
+/// Whether an idle frame should spend time compacting the arena
+pub fn needs_defragmentation(arena: &MeshArena) -> bool {
+    arena.stats.fragmentation > DEFRAG_FRAGMENTATION_THRESHOLD
+}
+
+// ============================================================================
+// DEFRAGMENTATION
+// ============================================================================
+
+/// Slide live meshes toward the start of the arena until the budget runs out
+///
+/// Meshes are processed in offset order, so every step shrinks the prefix of
+/// holes and repeated calls converge to a fully compacted arena.
+/// Call at most once per queue submission: the patch buffer is rewritten by
+/// each step.
+pub fn defragment_mesh_arena(
+    arena: &mut MeshArena,
+    encoder: &mut wgpu::CommandEncoder,
+    queue: &wgpu::Queue,
+    indirect_buffer: &wgpu::Buffer,
+    budget: DefragBudget,
+) -> DefragStepResult {
+    let start = Instant::now();
+    let mut result = DefragStepResult::default();
+
+    let mut live: Vec<MeshArenaAllocation> = arena.allocations.values().copied().collect();
+    live.sort_by_key(|allocation| allocation.offset);
+
+    let command_size = std::mem::size_of::<IndirectDrawIndexedCommand>() as u64;
+    let scratch_size = arena.scratch_buffer.size();
+
+    let mut cursor = 0u64;
+    let mut moves = 0usize;
+    let mut budget_exhausted = false;
+
+    for allocation in live {
+        if allocation.offset == cursor {
+            cursor += allocation.size;
+            continue;
+        }
+
+        if moves >= MAX_DEFRAG_MOVES_PER_STEP
+            || result.bytes_moved + allocation.size > budget.max_bytes
+            || start.elapsed() >= budget.max_time
+        {
+            budget_exhausted = true;
+            break;
+        }
+        if allocation.size > scratch_size {
+            // Cannot relocate through the scratch buffer; leave it in place
+            cursor = allocation.offset + allocation.size;
+            continue;
+        }
+
+        encoder.copy_buffer_to_buffer(
+            &arena.buffer,
+            allocation.offset,
+            &arena.scratch_buffer,
+            0,
+            allocation.size,
+        );
+        encoder.copy_buffer_to_buffer(
+            &arena.scratch_buffer,
+            0,
+            &arena.buffer,
+            cursor,
+            allocation.size,
+        );
+
+        // Patch the draw in the same command stream as the move
+        let base_vertex = (cursor / arena.stride) as i32;
+        let patch_offset = (moves * std::mem::size_of::<i32>()) as u64;
+        queue.write_buffer(
+            &arena.patch_buffer,
+            patch_offset,
+            bytemuck::bytes_of(&base_vertex),
+        );
+        encoder.copy_buffer_to_buffer(
+            &arena.patch_buffer,
+            patch_offset,
+            indirect_buffer,
+            allocation.draw_index as u64 * command_size + arena.draw_field,
+            std::mem::size_of::<i32>() as u64,
+        );
+
+        if let Some(entry) = arena.allocations.get_mut(&allocation.chunk_pos) {
+            entry.offset = cursor;
+        }
+
+        cursor += allocation.size;
+        moves += 1;
+        result.meshes_moved += 1;
+        result.bytes_moved += allocation.size;
+    }
+
+    if result.meshes_moved > 0 {
+        rebuild_free_extents(arena);
+        arena.stats.total_moves += result.meshes_moved as u64;
+        arena.stats.total_bytes_moved += result.bytes_moved;
+        log::debug!(
+            "[MeshArena] Defragmented {} meshes ({} KB), fragmentation now {:.2}",
+            result.meshes_moved,
+            result.bytes_moved / 1024,
+            arena.stats.fragmentation
+        );
+    }
+
+    result.complete = !budget_exhausted;
+    result
+}
+
+/// Recompute the free list from the live allocations
+fn rebuild_free_extents(arena: &mut MeshArena) {
+    let mut live: Vec<MeshArenaAllocation> = arena.allocations.values().copied().collect();
+    live.sort_by_key(|allocation| allocation.offset);
+
+    let mut extents = Vec::new();
+    let mut cursor = 0u64;
+    for allocation in live {
+        if allocation.offset > cursor {
+            extents.push(FreeExtent {
+                offset: cursor,
+                size: allocation.offset - cursor,
+            });
+        }
+        cursor = cursor.max(allocation.offset + allocation.size);
+    }
+    if cursor < arena.capacity {
+        extents.push(FreeExtent {
+            offset: cursor,
+            size: arena.capacity - cursor,
+        });
+    }
+
+    arena.free_extents = extents;
+    update_arena_stats(arena);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_extents_coalesce() {
+        let mut extents = vec![
+            FreeExtent {
+                offset: 0,
+                size: 16,
+            },
+            FreeExtent {
+                offset: 48,
+                size: 16,
+            },
+        ];
+        insert_free_extent(
+            &mut extents,
+            FreeExtent {
+                offset: 16,
+                size: 32,
+            },
+        );
+        assert_eq!(
+            extents,
+            vec![FreeExtent {
+                offset: 0,
+                size: 64
+            }]
+        );
+    }
+}
@@ -1,28 +1,41 @@
 //! GPU mesh generation dispatch - pure functions for executing mesh generation
 
+use crate::memory::{
+    alloc_frame_slice, create_tracked_buffer, frame_slice, frame_slice_mut, GpuAllocationGuard,
+};
+use crate::renderer::biome_tint_data::ChunkTintMap;
+use crate::renderer::biome_tint_operations::{pack_chunk_tint_map, packed_tint_map_len};
 use crate::renderer::gpu_meshing::{
-    GpuMeshBuffer, GpuMeshingState, MeshRequest, MeshingParams, MAX_CONCURRENT_MESHES,
-    WORKGROUP_SIZE,
+    GpuMeshBuffer, GpuMeshMetadata, GpuMeshingState, MeshRequest, MeshWorldBuffers, MeshingParams,
+    PackedTintMaps, MAX_MESH_REQUESTS_PER_DISPATCH, MESH_FLAG_BIOME_TINT, MESH_LIGHT_PACKED, MESH_NO_LIGHT_SLOT,
+    MESH_STITCH_SHIFT, StitchMasks, WORKGROUP_SIZE,
 };
 use crate::world::core::ChunkPos;
-
-// Import constants properly
-use crate::constants::*;
+use crate::world::storage::{LightingStorageMode, WorldBuffer};
+use bytemuck::Zeroable;
 
 /// Mesh generation result
 pub struct MeshGenerationResult {
     pub chunk_pos: ChunkPos,
     pub buffer_index: u32,
     // Indirect commands are stored in the global indirect buffer at offset buffer_index * 20 bytes
+    /// Request slot in buffer 0: the mesh starts at vertex
+    /// `request_index * MAX_VERTICES_PER_CHUNK` and index
+    /// `request_index * MAX_INDICES_PER_CHUNK`; indices are relative to it
+    pub request_index: u32,
+    pub vertex_count: u32,
+    pub index_count: u32,
 }
 
-/// Generate meshes for a batch of chunks
+/// Generate meshes for a batch of chunks, shading faces with the light
+/// `world_buffer` holds for them
 pub fn generate_chunk_meshes(
     state: &GpuMeshingState,
-    world_buffer: &wgpu::Buffer,
+    world_buffer: &WorldBuffer,
     chunk_positions: &[ChunkPos],
     lod_level: u32,
 ) -> Vec<MeshGenerationResult> {
+    let _span = crate::trace_span!(Meshing, "generate_chunk_meshes");
     log::info!(
         "[GPU Meshing] generate_chunk_meshes called with {} chunks",
         chunk_positions.len()
@@ -32,13 +45,9 @@ pub fn generate_chunk_meshes(
         return Vec::new();
     }
 
-    let batch_size = chunk_positions.len().min(MAX_CONCURRENT_MESHES);
+    let batch_size = chunk_positions.len().min(MAX_MESH_REQUESTS_PER_DISPATCH);
     let chunks = &chunk_positions[..batch_size];
 
-    // Allocate buffer indices and create mesh requests
-    let mut allocated_indices = Vec::new();
-    let mut requests = Vec::new();
-
     let allocator = match state.allocator.lock() {
         Ok(guard) => guard,
         Err(poisoned) => {
@@ -49,55 +58,92 @@ pub fn generate_chunk_meshes(
     // Note: allocator is not mutated, only read
     let _ = &allocator; // Suppress unused warning if needed
 
-    for chunk_pos in chunks {
-        // For GPU-driven rendering, all chunks use buffer 0
-        let buffer_index = 0u32;
-        
-        log::debug!(
-            "[generate_chunk_meshes] Using buffer 0 for chunk {:?}",
-            chunk_pos
-        );
-
-        allocated_indices.push((chunk_pos, buffer_index));
-        requests.push(MeshRequest {
-            chunk_pos: [chunk_pos.x, chunk_pos.y, chunk_pos.z],
-            lod_level,
-            buffer_index,
-            flags: 0,
-            _padding: [0; 2],
-        });
-    }
+    let tint_maps = match state.tint_maps.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let stitch_masks = match state.stitch_masks.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    // Storage bindings cannot be empty
+    let tint_len = chunks
+        .iter()
+        .filter_map(|chunk_pos| tint_maps.get(chunk_pos))
+        .map(|packed| packed.len())
+        .sum::<usize>()
+        .max(1);
 
-    // Create request buffer
-    let request_buffer = state.device.create_buffer(&wgpu::BufferDescriptor {
-        label: Some("Mesh Request Buffer"),
-        size: (std::mem::size_of::<MeshRequest>() * requests.len()) as u64,
-        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-        mapped_at_creation: false,
-    });
+    // Requests and tint data only live until they are uploaded, so they come
+    // from the frame arena when there is one
+    let mut dispatch_allocations = Vec::new();
+    let (request_buffer, tint_buffer) = match &state.frame_arena {
+        Some(arena) => {
+            let mut arena = arena.lock();
+            let requests = alloc_frame_slice(&mut arena, chunks.len());
+            let tint_data = alloc_frame_slice(&mut arena, tint_len);
+            write_mesh_requests(
+                frame_slice_mut(&mut arena, requests),
+                chunks,
+                &tint_maps,
+                &stitch_masks,
+                world_buffer,
+                lod_level,
+            );
+            write_tint_data(frame_slice_mut(&mut arena, tint_data), chunks, &tint_maps);
+            upload_mesh_inputs(
+                state,
+                frame_slice(&arena, requests),
+                frame_slice(&arena, tint_data),
+                &mut dispatch_allocations,
+            )
+        }
+        None => {
+            let mut requests = vec![MeshRequest::zeroed(); chunks.len()];
+            let mut tint_data = vec![0u32; tint_len];
+            write_mesh_requests(
+                &mut requests,
+                chunks,
+                &tint_maps,
+                &stitch_masks,
+                world_buffer,
+                lod_level,
+            );
+            write_tint_data(&mut tint_data, chunks, &tint_maps);
+            upload_mesh_inputs(state, &requests, &tint_data, &mut dispatch_allocations)
+        }
+    };
+    drop(tint_maps);
+    drop(stitch_masks);
 
-    // Upload requests
-    state
-        .queue
-        .write_buffer(&request_buffer, 0, bytemuck::cast_slice(&requests));
+    let (light_mode, light_buffer) =
+        match (world_buffer.lighting_mode(), world_buffer.light_buffer()) {
+            (LightingStorageMode::Dedicated, Some(buffer)) => (0, buffer),
+            _ => (MESH_LIGHT_PACKED, &state.light_placeholder),
+        };
 
     // Create parameters
     let params = MeshingParams {
-        chunk_size: core::CHUNK_SIZE,
-        request_count: requests.len() as u32,
+        chunk_size: state.chunk_layout.size,
+        request_count: chunks.len() as u32,
         enable_greedy: 1,
         enable_ao: 1,
         max_vertices: super::MAX_VERTICES_PER_CHUNK as u32,
         max_indices: super::MAX_INDICES_PER_CHUNK as u32,
-        _padding: [0; 2],
+        light_mode,
+        smooth_blocks: state.smooth_blocks,
     };
 
-    let params_buffer = state.device.create_buffer(&wgpu::BufferDescriptor {
-        label: Some("Meshing Parameters"),
-        size: std::mem::size_of::<MeshingParams>() as u64,
-        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        mapped_at_creation: false,
-    });
+    let params_buffer = create_dispatch_buffer(
+        state,
+        &mut dispatch_allocations,
+        &wgpu::BufferDescriptor {
+            label: Some("Meshing Parameters"),
+            size: std::mem::size_of::<MeshingParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
 
     state
         .queue
@@ -108,12 +154,15 @@ pub fn generate_chunk_meshes(
     let bind_group = super::pipeline::create_mesh_bind_group_for_buffer(
         &state.device,
         &state.bind_group_layout,
-        world_buffer,
+        &MeshWorldBuffers {
+            voxels: world_buffer.voxel_buffer(),
+            light: light_buffer,
+        },
         &request_buffer,
-        &state.mesh_buffers,
+        &state.mesh_buffers[0], // Always use buffer 0 for merged rendering
         &state.indirect_buffer,
         &params_buffer,
-        0, // Always use buffer 0 for merged rendering
+        &tint_buffer,
     );
 
     // Create command encoder
@@ -124,7 +173,10 @@ pub fn generate_chunk_meshes(
         });
 
     // One workgroup per chunk
-    let workgroups = requests.len() as u32;
+    let workgroups = chunks.len() as u32;
+    let metadata_buffer = &state.mesh_buffers[0].metadata;
+    let metadata_size = (std::mem::size_of::<GpuMeshMetadata>() * chunks.len()) as u64;
+    encoder.clear_buffer(metadata_buffer, 0, Some(metadata_size));
 
     // Dispatch compute
     {
@@ -139,12 +191,24 @@ pub fn generate_chunk_meshes(
         compute_pass.dispatch_workgroups(workgroups, 1, 1);
     }
 
+    let metadata_readback = create_dispatch_buffer(
+        state,
+        &mut dispatch_allocations,
+        &wgpu::BufferDescriptor {
+            label: Some("Mesh Metadata Readback"),
+            size: metadata_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
+    encoder.copy_buffer_to_buffer(metadata_buffer, 0, &metadata_readback, 0, metadata_size);
+
     // Submit
     state.queue.submit(std::iter::once(encoder.finish()));
 
     log::info!(
         "[GPU Meshing] Dispatched mesh generation for {} chunks with {} workgroups",
-        requests.len(),
+        chunks.len(),
         workgroups
     );
 
@@ -153,21 +217,206 @@ pub fn generate_chunk_meshes(
     state.device.poll(wgpu::Maintain::Wait);
 
     log::info!("[GPU Meshing] GPU synchronization complete - meshes should be ready");
+    let metadata = read_mesh_metadata(state, &metadata_readback);
 
     // Return mesh generation results using the allocated buffer indices
     // Note: indirect commands are written to the global indirect buffer by the GPU
-    allocated_indices
+    chunks
         .iter()
-        .map(|(chunk_pos, buffer_index)| {
+        .enumerate()
+        .map(|(request_index, chunk_pos)| {
+            let (vertex_count, index_count) = metadata
+                .get(request_index)
+                .map(|metadata| (metadata.vertex_count, metadata.index_count))
+                .unwrap_or((0, 0));
             MeshGenerationResult {
-                chunk_pos: **chunk_pos,
-                buffer_index: *buffer_index,
+                chunk_pos: *chunk_pos,
+                // For GPU-driven rendering, all chunks use buffer 0
+                buffer_index: 0,
                 // Indirect commands are stored in the global indirect buffer at offset buffer_index * 20
+                request_index: request_index as u32,
+                vertex_count: vertex_count.min(super::MAX_VERTICES_PER_CHUNK as u32),
+                index_count: index_count.min(super::MAX_INDICES_PER_CHUNK as u32),
             }
         })
         .collect()
 }
 
+/// Mesh metadata (vertex and index counts) per request, read back after the
+/// dispatch was waited on. Empty if the readback fails.
+fn read_mesh_metadata(state: &GpuMeshingState, readback: &wgpu::Buffer) -> Vec<GpuMeshMetadata> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    readback
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+    state.device.poll(wgpu::Maintain::Wait);
+    match receiver.recv() {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            log::warn!("[GPU Meshing] Mesh metadata readback failed: {:?}", e);
+            return Vec::new();
+        }
+        Err(_) => return Vec::new(),
+    }
+    let metadata = bytemuck::cast_slice(&readback.slice(..).get_mapped_range()).to_vec();
+    readback.unmap();
+    metadata
+}
+
+/// Fill one mesh request per chunk. Chunks with a tint map point at their
+/// packed map inside the data `write_tint_data` lays out; chunks with a full
+/// world buffer slot read their light from it; stitch masks ride in the
+/// flags above `MESH_STITCH_SHIFT`.
+fn write_mesh_requests(
+    requests: &mut [MeshRequest],
+    chunks: &[ChunkPos],
+    tint_maps: &PackedTintMaps,
+    stitch_masks: &StitchMasks,
+    world_buffer: &WorldBuffer,
+    lod_level: u32,
+) {
+    let mut tint_offset = 0u32;
+    for (request, chunk_pos) in requests.iter_mut().zip(chunks) {
+        log::debug!(
+            "[generate_chunk_meshes] Using buffer 0 for chunk {:?}",
+            chunk_pos
+        );
+
+        // Chunks without a tint map keep the shader's fixed block colors
+        let (flags, offset) = match tint_maps.get(chunk_pos) {
+            Some(packed) => {
+                let offset = tint_offset;
+                tint_offset += packed.len() as u32;
+                (MESH_FLAG_BIOME_TINT, offset)
+            }
+            None => (0, 0),
+        };
+        let stitch = stitch_masks.get(chunk_pos).copied().unwrap_or(0) as u32;
+        *request = MeshRequest {
+            chunk_pos: [chunk_pos.x, chunk_pos.y, chunk_pos.z],
+            lod_level,
+            // For GPU-driven rendering, all chunks use buffer 0
+            buffer_index: 0,
+            flags: flags | (stitch << MESH_STITCH_SHIFT),
+            tint_offset: offset,
+            light_slot: world_buffer
+                .existing_chunk_slot(*chunk_pos)
+                .unwrap_or(MESH_NO_LIGHT_SLOT),
+        };
+    }
+}
+
+/// Concatenate the packed tint maps of `chunks`, in request order
+fn write_tint_data(tint_data: &mut [u32], chunks: &[ChunkPos], tint_maps: &PackedTintMaps) {
+    let mut offset = 0;
+    for packed in chunks.iter().filter_map(|chunk_pos| tint_maps.get(chunk_pos)) {
+        tint_data[offset..offset + packed.len()].copy_from_slice(packed);
+        offset += packed.len();
+    }
+}
+
+/// Buffer that lives for one dispatch, recorded until `allocations` drops
+fn create_dispatch_buffer(
+    state: &GpuMeshingState,
+    allocations: &mut Vec<GpuAllocationGuard>,
+    descriptor: &wgpu::BufferDescriptor,
+) -> wgpu::Buffer {
+    let (buffer, guard) = create_tracked_buffer(&state.device, &state.gpu_owner, descriptor);
+    allocations.push(guard);
+    buffer
+}
+
+/// Upload the mesh requests and tint data of one dispatch
+fn upload_mesh_inputs(
+    state: &GpuMeshingState,
+    requests: &[MeshRequest],
+    tint_data: &[u32],
+    allocations: &mut Vec<GpuAllocationGuard>,
+) -> (wgpu::Buffer, wgpu::Buffer) {
+    let tint_buffer = create_dispatch_buffer(
+        state,
+        allocations,
+        &wgpu::BufferDescriptor {
+            label: Some("Mesh Biome Tint Buffer"),
+            size: std::mem::size_of_val(tint_data) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
+    state
+        .queue
+        .write_buffer(&tint_buffer, 0, bytemuck::cast_slice(tint_data));
+
+    // Create request buffer
+    let request_buffer = create_dispatch_buffer(
+        state,
+        allocations,
+        &wgpu::BufferDescriptor {
+            label: Some("Mesh Request Buffer"),
+            size: std::mem::size_of_val(requests) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
+
+    // Upload requests
+    state
+        .queue
+        .write_buffer(&request_buffer, 0, bytemuck::cast_slice(requests));
+    (request_buffer, tint_buffer)
+}
+
+/// Color a chunk's grass, foliage and water from `tints` the next time it
+/// is meshed. Returns false (and keeps the fixed colors) if the map does
+/// not match the meshing chunk size.
+pub fn set_chunk_tint_map(
+    state: &GpuMeshingState,
+    chunk_pos: ChunkPos,
+    tints: &ChunkTintMap,
+) -> bool {
+    let packed = pack_chunk_tint_map(tints);
+    if packed.len() != packed_tint_map_len(state.chunk_layout.size) {
+        log::warn!(
+            "[GPU Meshing] Tint map for chunk {:?} has {} corners per side, expected {}",
+            chunk_pos,
+            tints.corners_per_side,
+            state.chunk_layout.size + 1
+        );
+        return false;
+    }
+    let mut tint_maps = match state.tint_maps.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    tint_maps.insert(chunk_pos, packed);
+    true
+}
+
+/// Drop a chunk's tint map (e.g. when it unloads)
+pub fn remove_chunk_tint_map(state: &GpuMeshingState, chunk_pos: &ChunkPos) {
+    let mut tint_maps = match state.tint_maps.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    tint_maps.remove(chunk_pos);
+}
+
+/// Hang skirts from the faces in `mask` the next time the chunk is meshed;
+/// a zero mask (e.g. when it unloads) drops them
+pub fn set_chunk_stitch_mask(state: &GpuMeshingState, chunk_pos: ChunkPos, mask: u8) {
+    let mut stitch_masks = match state.stitch_masks.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if mask == 0 {
+        stitch_masks.remove(&chunk_pos);
+    } else {
+        stitch_masks.insert(chunk_pos, mask);
+    }
+}
+
 /// Get mesh statistics from GPU
 pub fn update_mesh_statistics(state: &mut GpuMeshingState, generated_count: u32) {
     state.stats.total_meshes += generated_count as u64;
@@ -2,10 +2,12 @@
 //!
 //! All mesh generation happens on GPU with zero CPU involvement
 
+pub mod arena;
 pub mod dispatch;
 pub mod pipeline;
 pub mod types;
 
+pub use arena::*;
 pub use dispatch::*;
 pub use pipeline::*;
 pub use types::*;
@@ -28,13 +30,47 @@ pub struct GpuMeshingState {
     /// Indirect draw command buffer
     pub indirect_buffer: wgpu::Buffer,
 
+    /// Bound as the lighting buffer when the world packs light into voxels
+    pub light_placeholder: wgpu::Buffer,
+
     /// Mesh generation statistics
     pub stats: MeshingStats,
 
     /// Track buffer allocation (wrapped in Mutex for interior mutability)
     pub allocator: std::sync::Mutex<BufferAllocator>,
+
+    /// Chunk dimensions of the world being meshed
+    pub chunk_layout: crate::world::core::ChunkLayout,
+
+    /// Packed biome tint maps of chunks that have one (see
+    /// `set_chunk_tint_map`)
+    pub tint_maps: std::sync::Mutex<PackedTintMaps>,
+
+    /// LOD stitch masks of chunks that need skirts (see
+    /// `set_chunk_stitch_mask`)
+    pub stitch_masks: std::sync::Mutex<StitchMasks>,
+
+    /// `MeshingParams::smooth_blocks` of every dispatch (see
+    /// `smooth_block_mask`)
+    pub smooth_blocks: u32,
+
+    /// Per-frame arena for mesh requests and tint data (heap `Vec`s when
+    /// `None`)
+    pub frame_arena: Option<crate::memory::SharedFrameArena>,
+
+    /// Records of `indirect_buffer` and `light_placeholder` in the GPU
+    /// allocation tracker; mesh buffers and dispatch inputs are recorded
+    /// against the same owner
+    pub gpu_allocations: Vec<crate::memory::GpuAllocationGuard>,
+    pub gpu_owner: crate::memory::GpuOwnerGuard,
 }
 
+/// Packed biome tint map per chunk
+pub type PackedTintMaps = std::collections::HashMap<crate::ChunkPos, Vec<u32>>;
+
+/// LOD stitch mask per chunk
+pub type StitchMasks = std::collections::HashMap<crate::ChunkPos, u8>;
+
 /// Buffer allocation tracker
 pub struct BufferAllocator {
     /// Track which buffer slots are in use (chunk_pos -> buffer_index)
@@ -43,10 +79,13 @@ pub struct BufferAllocator {
     pub free_buffers: Vec<u32>,
 }
 
-/// Initialize GPU meshing system
+/// Initialize GPU meshing system; mesh requests come from `frame_arena`
+/// when one is given (the engine passes its per-frame arena)
 pub fn create_gpu_meshing_state(
     device: Arc<wgpu::Device>,
     queue: Arc<wgpu::Queue>,
+    chunk_layout: crate::world::core::ChunkLayout,
+    frame_arena: Option<crate::memory::SharedFrameArena>,
 ) -> GpuMeshingState {
     log::info!("[create_gpu_meshing_state] 🚀 Initializing GPU meshing system");
 
@@ -56,12 +95,21 @@ pub fn create_gpu_meshing_state(
     log::info!("[create_gpu_meshing_state] ✅ Pipeline created successfully");
 
     // Pre-allocate mesh buffers
+    let gpu_owner = crate::memory::register_global_gpu_owner("meshing");
     let mesh_buffers = (0..MAX_CONCURRENT_MESHES)
-        .map(|i| create_gpu_mesh_buffer(&device, i as u32))
+        .map(|i| create_gpu_mesh_buffer(&device, &gpu_owner, i as u32))
         .collect();
 
+    let mut gpu_allocations = Vec::new();
+    let mut create_buffer = |descriptor: &wgpu::BufferDescriptor| {
+        let (buffer, guard) =
+            crate::memory::create_tracked_buffer(&device, &gpu_owner, descriptor);
+        gpu_allocations.push(guard);
+        buffer
+    };
+
     // Create indirect command buffer for indexed drawing
-    let indirect_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+    let indirect_buffer = create_buffer(&wgpu::BufferDescriptor {
         label: Some("Indirect Mesh Commands"),
         size: (std::mem::size_of::<crate::gpu::buffer_layouts::IndirectDrawIndexedCommand>() * MAX_CONCURRENT_MESHES) as u64,
         usage: wgpu::BufferUsages::INDIRECT
@@ -70,6 +118,13 @@ pub fn create_gpu_meshing_state(
         mapped_at_creation: false,
     });
 
+    let light_placeholder = create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Mesh Light Placeholder"),
+        size: 4,
+        usage: wgpu::BufferUsages::STORAGE,
+        mapped_at_creation: false,
+    });
+
     // Initialize allocator
     let allocator = std::sync::Mutex::new(BufferAllocator {
         allocated_buffers: std::collections::HashMap::new(),
@@ -83,8 +138,16 @@ pub fn create_gpu_meshing_state(
         bind_group_layout,
         mesh_buffers,
         indirect_buffer,
+        light_placeholder,
         stats: MeshingStats::default(),
         allocator,
+        chunk_layout,
+        tint_maps: std::sync::Mutex::new(std::collections::HashMap::new()),
+        stitch_masks: std::sync::Mutex::new(std::collections::HashMap::new()),
+        smooth_blocks: 0,
+        frame_arena,
+        gpu_allocations,
+        gpu_owner,
     }
 }
 
@@ -92,4 +155,11 @@ pub fn create_gpu_meshing_state(
 pub const MAX_CONCURRENT_MESHES: usize = 256;
 pub const MAX_VERTICES_PER_CHUNK: usize = 65536;
 pub const MAX_INDICES_PER_CHUNK: usize = 98304; // 1.5x vertices
+/// Bytes per vertex written by mesh_generation.wgsl (three vec3 fields
+/// aligned to 16 bytes, then light and ao)
+pub const MESH_VERTEX_STRIDE: u64 = 64;
+/// Chunks one dispatch can mesh: every request owns a fixed region of the
+/// merged buffer 0, and its vertex regions run out first
+pub const MAX_MESH_REQUESTS_PER_DISPATCH: usize = (crate::constants::buffer_sizes::VERTEX_BUFFER_SIZE
+    / (MAX_VERTICES_PER_CHUNK as u64 * MESH_VERTEX_STRIDE)) as usize;
 pub const WORKGROUP_SIZE: u32 = 64; // 4x4x4 voxels per workgroup
@@ -1,5 +1,6 @@
 //! GPU mesh generation pipeline - pure functions only
 
+use crate::memory::{create_tracked_buffer, GpuOwnerGuard};
 use crate::renderer::gpu_meshing::types::*;
 use std::sync::Arc;
 
@@ -67,7 +68,9 @@ pub fn create_mesh_generation_pipeline(
         3 => buffer(storage),       // Index buffer output
         4 => buffer(storage),       // Metadata output
         5 => buffer(storage),       // Indirect commands output
-        6 => buffer(uniform)        // Meshing parameters
+        6 => buffer(uniform),       // Meshing parameters
+        7 => buffer(storage_read),  // Packed biome tint maps
+        8 => buffer(storage_read)   // World lighting buffer
     );
 
     // Create pipeline layout
@@ -94,7 +97,11 @@ pub fn create_mesh_generation_pipeline(
 }
 
 /// Create GPU mesh buffer
-pub fn create_gpu_mesh_buffer(device: &wgpu::Device, buffer_id: u32) -> GpuMeshBuffer {
+pub fn create_gpu_mesh_buffer(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    buffer_id: u32,
+) -> GpuMeshBuffer {
     // CRITICAL FIX: Buffer 0 is used for merged meshes and needs to be MUCH larger
     let (vertex_buffer_size, index_buffer_size) = if buffer_id == 0 {
         // Buffer 0 holds ALL merged meshes - use the large constants
@@ -110,9 +117,16 @@ pub fn create_gpu_mesh_buffer(device: &wgpu::Device, buffer_id: u32) -> GpuMeshB
             (MAX_INDICES_PER_CHUNK * std::mem::size_of::<u32>()) as u64,
         )
     };
-    
+
+    let mut gpu_allocations = Vec::new();
+    let mut create_buffer = |descriptor: &wgpu::BufferDescriptor| {
+        let (buffer, guard) = create_tracked_buffer(device, owner, descriptor);
+        gpu_allocations.push(guard);
+        buffer
+    };
+
     // Vertex buffer (interleaved: position + color + normal + light + ao)
-    let vertices = device.create_buffer(&wgpu::BufferDescriptor {
+    let vertices = create_buffer(&wgpu::BufferDescriptor {
         label: Some(&format!("Mesh {} Vertices", buffer_id)),
         size: vertex_buffer_size,
         usage: wgpu::BufferUsages::STORAGE
@@ -123,7 +137,7 @@ pub fn create_gpu_mesh_buffer(device: &wgpu::Device, buffer_id: u32) -> GpuMeshB
     });
 
     // Index buffer
-    let indices = device.create_buffer(&wgpu::BufferDescriptor {
+    let indices = create_buffer(&wgpu::BufferDescriptor {
         label: Some(&format!("Mesh {} Indices", buffer_id)),
         size: index_buffer_size,
         usage: wgpu::BufferUsages::STORAGE
@@ -133,11 +147,18 @@ pub fn create_gpu_mesh_buffer(device: &wgpu::Device, buffer_id: u32) -> GpuMeshB
         mapped_at_creation: false,
     });
 
-    // Metadata buffer
-    let metadata = device.create_buffer(&wgpu::BufferDescriptor {
+    // Metadata buffer: one entry per request for the merged buffer 0
+    let metadata_entries = if buffer_id == 0 {
+        super::MAX_MESH_REQUESTS_PER_DISPATCH
+    } else {
+        1
+    };
+    let metadata = create_buffer(&wgpu::BufferDescriptor {
         label: Some(&format!("Mesh {} Metadata", buffer_id)),
-        size: std::mem::size_of::<GpuMeshMetadata>() as u64,
-        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        size: (std::mem::size_of::<GpuMeshMetadata>() * metadata_entries) as u64,
+        usage: wgpu::BufferUsages::STORAGE
+            | wgpu::BufferUsages::COPY_SRC
+            | wgpu::BufferUsages::COPY_DST,
         mapped_at_creation: false,
     });
 
@@ -146,6 +167,7 @@ pub fn create_gpu_mesh_buffer(device: &wgpu::Device, buffer_id: u32) -> GpuMeshB
         indices,
         metadata,
         id: buffer_id,
+        gpu_allocations,
     }
 }
 
@@ -153,11 +175,12 @@ pub fn create_gpu_mesh_buffer(device: &wgpu::Device, buffer_id: u32) -> GpuMeshB
 pub fn create_mesh_bind_group(
     device: &wgpu::Device,
     layout: &wgpu::BindGroupLayout,
-    world_buffer: &wgpu::Buffer,
+    world: &MeshWorldBuffers,
     request_buffer: &wgpu::Buffer,
     mesh_buffers: &[GpuMeshBuffer],
     indirect_buffer: &wgpu::Buffer,
     params_buffer: &wgpu::Buffer,
+    tint_buffer: &wgpu::Buffer,
 ) -> wgpu::BindGroup {
     // For simplicity, bind the first mesh buffer
     // In practice, you'd cycle through buffers
@@ -167,13 +190,15 @@ pub fn create_mesh_bind_group(
         device,
         "Mesh Generation Bind Group",
         layout,
-        0 => world_buffer.as_entire_binding(),
+        0 => world.voxels.as_entire_binding(),
         1 => request_buffer.as_entire_binding(),
         2 => mesh.vertices.as_entire_binding(),
         3 => mesh.indices.as_entire_binding(),
         4 => mesh.metadata.as_entire_binding(),
         5 => indirect_buffer.as_entire_binding(),
-        6 => params_buffer.as_entire_binding()
+        6 => params_buffer.as_entire_binding(),
+        7 => tint_buffer.as_entire_binding(),
+        8 => world.light.as_entire_binding()
     )
 }
 
@@ -181,26 +206,26 @@ pub fn create_mesh_bind_group(
 pub fn create_mesh_bind_group_for_buffer(
     device: &wgpu::Device,
     layout: &wgpu::BindGroupLayout,
-    world_buffer: &wgpu::Buffer,
+    world: &MeshWorldBuffers,
     request_buffer: &wgpu::Buffer,
-    mesh_buffers: &[GpuMeshBuffer],
+    mesh: &GpuMeshBuffer,
     indirect_buffer: &wgpu::Buffer,
     params_buffer: &wgpu::Buffer,
-    buffer_index: u32,
+    tint_buffer: &wgpu::Buffer,
 ) -> wgpu::BindGroup {
-    let mesh = &mesh_buffers[buffer_index as usize];
-
     crate::create_bind_group!(
         device,
         "Mesh Generation Bind Group",
         layout,
-        0 => world_buffer.as_entire_binding(),
+        0 => world.voxels.as_entire_binding(),
         1 => request_buffer.as_entire_binding(),
         2 => mesh.vertices.as_entire_binding(),
         3 => mesh.indices.as_entire_binding(),
         4 => mesh.metadata.as_entire_binding(),
         5 => indirect_buffer.as_entire_binding(),
-        6 => params_buffer.as_entire_binding()
+        6 => params_buffer.as_entire_binding(),
+        7 => tint_buffer.as_entire_binding(),
+        8 => world.light.as_entire_binding()
     )
 }
 
@@ -1,6 +1,7 @@
 //! GPU meshing data types - SOA layout for GPU efficiency
 //! No methods, only data structures
 
+use crate::memory::GpuAllocationGuard;
 use bytemuck::{Pod, Zeroable};
 
 /// GPU mesh buffer - Now uses interleaved vertices
@@ -13,6 +14,8 @@ pub struct GpuMeshBuffer {
     pub metadata: wgpu::Buffer,
     /// Buffer ID
     pub id: u32,
+    /// Tracked `vertices`, `indices` and `metadata`, freed with the mesh
+    pub gpu_allocations: Vec<GpuAllocationGuard>,
 }
 
 /// Mesh metadata for GPU
@@ -44,12 +47,39 @@ pub struct MeshRequest {
     pub lod_level: u32,
     /// Output buffer index
     pub buffer_index: u32,
-    /// Mesh flags
+    /// Mesh flags (`MESH_FLAG_*`)
     pub flags: u32,
-    /// Padding
-    pub _padding: [u32; 2],
+    /// Start of this chunk's packed biome tint map in the tint buffer
+    /// (used with `MESH_FLAG_BIOME_TINT`)
+    pub tint_offset: u32,
+    /// World buffer slot holding the chunk's light (`MESH_NO_LIGHT_SLOT`
+    /// when the chunk has no full slot)
+    pub light_slot: u32,
+}
+
+/// World buffers a mesh dispatch reads
+pub struct MeshWorldBuffers<'a> {
+    /// Voxel buffer of the world
+    pub voxels: &'a wgpu::Buffer,
+    /// Dedicated lighting buffer (a placeholder when light is packed into
+    /// the voxels)
+    pub light: &'a wgpu::Buffer,
 }
 
+/// Request flag: color grass, foliage and water from the chunk's biome tint map
+pub const MESH_FLAG_BIOME_TINT: u32 = 1;
+
+/// First request flag bit of the chunk's stitch mask: one bit per chunk face
+/// (+X, -X, +Y, -Y, +Z, -Z) whose neighbor is meshed finer, where the
+/// shader hangs skirts from the boundary edges
+pub const MESH_STITCH_SHIFT: u32 = 8;
+
+/// Request light slot of chunks meshed fully lit
+pub const MESH_NO_LIGHT_SLOT: u32 = u32::MAX;
+
+/// `MeshingParams::light_mode` of light packed into the voxel words
+pub const MESH_LIGHT_PACKED: u32 = 1;
+
 /// Mesh generation parameters
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
@@ -66,8 +96,12 @@ pub struct MeshingParams {
     pub max_vertices: u32,
     /// Maximum indices per mesh
     pub max_indices: u32,
-    /// Padding
-    pub _padding: [u32; 2],
+    /// Where the world keeps light: 0 = dedicated lighting buffer,
+    /// `MESH_LIGHT_PACKED` = voxel words
+    pub light_mode: u32,
+    /// One bit per block id below 32 that the smooth terrain mesh draws
+    /// instead of cubes (0 while smooth terrain is off)
+    pub smooth_blocks: u32,
 }
 
 /// Meshing statistics
@@ -0,0 +1,70 @@
+//! Light Preview Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! All transformations happen in light_preview_operations.rs
+//!
+//! Preview of the light a held emissive block would cast at the targeted
+//! position. A small volume around the target is seeded from the world
+//! buffer, flood-filled on the GPU (opaque voxels block propagation) and
+//! resolved into a 3D overlay texture that the overlay pass samples to tint
+//! lit faces.
+
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use crate::world::core::VoxelPos;
+use bytemuck::{Pod, Zeroable};
+
+/// Chunks the preview volume can overlap (2 per axis)
+pub const LIGHT_PREVIEW_CHUNKS: usize = 8;
+
+/// Uniform shared by the propagation and overlay shaders
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct LightPreviewUniform {
+    pub view_proj: [[f32; 4]; 4],
+    /// xyz = world min corner of the volume, w = cells per side
+    pub origin: [i32; 4],
+    /// xyz = world position of the light, w = emission level
+    pub source: [i32; 4],
+    /// xyz = chunk containing the volume's min corner
+    pub chunk_origin: [i32; 4],
+    /// World buffer slot per overlapped chunk (u32::MAX = not resident)
+    pub chunk_slots: [[u32; 4]; 2],
+    /// Voxel to use for non-resident chunks (sparse fill or air)
+    pub chunk_fill: [[u32; 4]; 2],
+    /// rgb = tint, a = alpha at full brightness
+    pub color: [f32; 4],
+}
+
+/// What the current overlay was computed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LightPreviewKey {
+    pub target: VoxelPos,
+    pub emission: u8,
+}
+
+/// GPU resources and state for the light placement preview
+pub struct LightPreviewData {
+    pub uniform: LightPreviewUniform,
+    pub uniform_buffer: wgpu::Buffer,
+    /// Bitset of block ids light passes through
+    pub transparency_buffer: wgpu::Buffer,
+    /// Ping-pong cell buffers (level + opacity per cell)
+    pub cell_buffers: [wgpu::Buffer; 2],
+    pub overlay: wgpu::Texture,
+
+    pub seed_pipeline: wgpu::ComputePipeline,
+    pub propagate_pipeline: wgpu::ComputePipeline,
+    pub resolve_pipeline: wgpu::ComputePipeline,
+    pub render_pipeline: wgpu::RenderPipeline,
+    /// Kept to rebuild `render_pipeline` when the main pass changes
+    pub render_bind_group_layout: wgpu::BindGroupLayout,
+    /// [0] reads cells 0 and writes cells 1, [1] the reverse
+    pub compute_bind_groups: [wgpu::BindGroup; 2],
+    pub render_bind_group: wgpu::BindGroup,
+    /// Uniform, transparency mask, both cell buffers and the overlay texture
+    pub gpu_allocations: Vec<GpuAllocationGuard>,
+    pub gpu_owner: GpuOwnerGuard,
+
+    /// Target the overlay holds (None = nothing to draw)
+    pub previewed: Option<LightPreviewKey>,
+}
@@ -0,0 +1,545 @@
+//! Light Preview Operations - Pure DOP
+//!
+//! Functions that create, recompute and draw the light placement preview.
+//! Propagation only runs when the targeted voxel or emission level changes;
+//! other frames just refresh the view matrix.
+
+use super::error::RendererResult;
+use super::light_preview_data::{
+    LightPreviewData, LightPreviewKey, LightPreviewUniform, LIGHT_PREVIEW_CHUNKS,
+};
+use crate::constants::blocks;
+use crate::constants::light_preview::{
+    OVERLAY_COLOR, PREVIEW_EXTENT, PREVIEW_RADIUS, PROPAGATION_PASSES,
+};
+use crate::memory::{
+    create_tracked_buffer, create_tracked_buffer_init, create_tracked_texture,
+    register_global_gpu_owner,
+};
+use crate::world::core::{voxel_to_chunk_pos, BlockId, BlockRegistry, ChunkPos, VoxelPos};
+use crate::world::storage::WorldBuffer;
+use cgmath::Matrix4;
+
+/// Overlay texel format: level + lit face mask
+const OVERLAY_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+
+/// Workgroup edge of the propagation shader
+const WORKGROUP_SIZE: u32 = 4;
+
+/// Slot value for chunks without a world buffer slot
+const NO_SLOT: u32 = u32::MAX;
+
+/// Slot bit marking a palette tier slot (PALETTE_SLOT_FLAG in voxel_palette.wgsl)
+const PALETTE_SLOT_FLAG: u32 = 0x8000_0000;
+
+/// Bitset of block ids light passes through
+pub fn build_light_transparency_mask(registry: &BlockRegistry) -> Vec<u32> {
+    let mut mask = vec![0u32; (u16::MAX as usize + 1) / 32];
+    let mut set = |id: u16| mask[id as usize / 32] |= 1 << (id % 32);
+
+    set(blocks::AIR);
+    set(blocks::WATER);
+    for registration in registry.get_registrations() {
+        if registration.properties.is_transparent || registration.properties.transparent {
+            set(registration.id.0);
+        }
+    }
+    mask
+}
+
+/// Light level a held block would emit (0 = no preview)
+pub fn held_block_emission(registry: &BlockRegistry, block: BlockId) -> u8 {
+    registry
+        .get_properties(block)
+        .map(|properties| properties.light_emission)
+        .unwrap_or(0)
+}
+
+/// Create the preview volume, overlay texture and pipelines
+pub fn create_light_preview(
+    device: &wgpu::Device,
+    world_buffer: &WorldBuffer,
+    registry: &BlockRegistry,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<LightPreviewData> {
+    let chunk_size = world_buffer.chunk_layout().size;
+    if chunk_size < PREVIEW_EXTENT {
+        return Err(format!(
+            "Light preview needs chunks of at least {} voxels, world uses {}",
+            PREVIEW_EXTENT, chunk_size
+        ));
+    }
+
+    let uniform = LightPreviewUniform {
+        view_proj: Matrix4::from_scale(1.0).into(),
+        origin: [0, 0, 0, PREVIEW_EXTENT as i32],
+        source: [0; 4],
+        chunk_origin: [0; 4],
+        chunk_slots: [[NO_SLOT; 4]; 2],
+        chunk_fill: [[0; 4]; 2],
+        color: OVERLAY_COLOR,
+    };
+    let gpu_owner = register_global_gpu_owner("light_preview");
+    let mut gpu_allocations = Vec::new();
+    let (uniform_buffer, allocation) = create_tracked_buffer_init(
+        device,
+        &gpu_owner,
+        &wgpu::util::BufferInitDescriptor {
+            label: Some("Light Preview Uniform Buffer"),
+            contents: bytemuck::bytes_of(&uniform),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        },
+    );
+    gpu_allocations.push(allocation);
+    let (transparency_buffer, allocation) = create_tracked_buffer_init(
+        device,
+        &gpu_owner,
+        &wgpu::util::BufferInitDescriptor {
+            label: Some("Light Preview Transparency Mask"),
+            contents: bytemuck::cast_slice(&build_light_transparency_mask(registry)),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        },
+    );
+    gpu_allocations.push(allocation);
+
+    let cell_bytes = (PREVIEW_EXTENT * PREVIEW_EXTENT * PREVIEW_EXTENT) as u64 * 4;
+    let mut cell_buffer = |label| {
+        let (buffer, allocation) = create_tracked_buffer(
+            device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some(label),
+                size: cell_bytes,
+                usage: wgpu::BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            },
+        );
+        gpu_allocations.push(allocation);
+        buffer
+    };
+    let cell_buffers = [
+        cell_buffer("Light Preview Cells A"),
+        cell_buffer("Light Preview Cells B"),
+    ];
+
+    let (overlay, allocation) = create_tracked_texture(
+        device,
+        &gpu_owner,
+        &wgpu::TextureDescriptor {
+            label: Some("Light Preview Overlay"),
+            size: wgpu::Extent3d {
+                width: PREVIEW_EXTENT,
+                height: PREVIEW_EXTENT,
+                depth_or_array_layers: PREVIEW_EXTENT,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D3,
+            format: OVERLAY_FORMAT,
+            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        },
+    );
+    gpu_allocations.push(allocation);
+    let overlay_view = overlay.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let uniform_entry = |visibility| wgpu::BindGroupLayoutEntry {
+        binding: 0,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    };
+    let storage_entry = |binding, read_only| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::COMPUTE,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Storage { read_only },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    };
+
+    // Propagation pipelines
+    let compute_shader = crate::gpu::automation::create_gpu_shader(
+        device,
+        "light_preview",
+        include_str!("../shaders/compute/light_preview.wgsl"),
+    )
+    .map_err(|e| format!("Failed to create light preview shader: {}", e))?;
+
+    let compute_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Light Preview Compute Bind Group Layout"),
+        entries: &[
+            uniform_entry(wgpu::ShaderStages::COMPUTE),
+            storage_entry(1, true),
+            storage_entry(2, true),
+            storage_entry(3, true),
+            storage_entry(4, false),
+            storage_entry(6, true),
+            storage_entry(7, true),
+            wgpu::BindGroupLayoutEntry {
+                binding: 5,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::StorageTexture {
+                    access: wgpu::StorageTextureAccess::WriteOnly,
+                    format: OVERLAY_FORMAT,
+                    view_dimension: wgpu::TextureViewDimension::D3,
+                },
+                count: None,
+            },
+        ],
+    });
+
+    let compute_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Light Preview Compute Pipeline Layout"),
+        bind_group_layouts: &[&compute_layout],
+        push_constant_ranges: &[],
+    });
+
+    let compute_pipeline = |label, entry_point| {
+        device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(label),
+            layout: Some(&compute_pipeline_layout),
+            module: &compute_shader.module,
+            entry_point,
+        })
+    };
+    let seed_pipeline = compute_pipeline("Light Preview Seed Pipeline", "seed_preview");
+    let propagate_pipeline =
+        compute_pipeline("Light Preview Propagate Pipeline", "propagate_preview");
+    let resolve_pipeline = compute_pipeline("Light Preview Resolve Pipeline", "resolve_preview");
+
+    let compute_bind_group = |label, src: &wgpu::Buffer, dst: &wgpu::Buffer| {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(label),
+            layout: &compute_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: world_buffer.voxel_buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: transparency_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: src.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: dst.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: wgpu::BindingResource::TextureView(&overlay_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: world_buffer.palette_index_buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 7,
+                    resource: world_buffer.palette_buffer().as_entire_binding(),
+                },
+            ],
+        })
+    };
+    let compute_bind_groups = [
+        compute_bind_group(
+            "Light Preview Compute Bind Group A->B",
+            &cell_buffers[0],
+            &cell_buffers[1],
+        ),
+        compute_bind_group(
+            "Light Preview Compute Bind Group B->A",
+            &cell_buffers[1],
+            &cell_buffers[0],
+        ),
+    ];
+
+    // Overlay render pipeline
+    let render_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Light Preview Render Bind Group Layout"),
+        entries: &[
+            uniform_entry(wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT),
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Uint,
+                    view_dimension: wgpu::TextureViewDimension::D3,
+                    multisampled: false,
+                },
+                count: None,
+            },
+        ],
+    });
+
+    let render_pipeline = create_light_preview_pipeline(
+        device,
+        &render_layout,
+        color_format,
+        depth_format,
+        sample_count,
+    )?;
+
+    let render_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Light Preview Render Bind Group"),
+        layout: &render_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(&overlay_view),
+            },
+        ],
+    });
+
+    Ok(LightPreviewData {
+        uniform,
+        uniform_buffer,
+        transparency_buffer,
+        cell_buffers,
+        overlay,
+        seed_pipeline,
+        propagate_pipeline,
+        resolve_pipeline,
+        render_pipeline,
+        render_bind_group_layout: render_layout,
+        compute_bind_groups,
+        render_bind_group,
+        gpu_allocations,
+        gpu_owner,
+        previewed: None,
+    })
+}
+
+/// Overlay pipeline for the main pass's color, depth and sample count
+fn create_light_preview_pipeline(
+    device: &wgpu::Device,
+    render_layout: &wgpu::BindGroupLayout,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<wgpu::RenderPipeline> {
+    let overlay_shader = crate::gpu::automation::create_gpu_shader(
+        device,
+        "light_preview_overlay",
+        include_str!("../shaders/rendering/light_preview.wgsl"),
+    )
+    .map_err(|e| format!("Failed to create light preview overlay shader: {}", e))?;
+
+    let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Light Preview Render Pipeline Layout"),
+        bind_group_layouts: &[render_layout],
+        push_constant_ranges: &[],
+    });
+
+    let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Light Preview Render Pipeline"),
+        layout: Some(&render_pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &overlay_shader.module,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &overlay_shader.module,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: color_format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            cull_mode: None,
+            ..Default::default()
+        },
+        // Tested against the scene but never written, so the overlay does
+        // not hide anything drawn after it
+        depth_stencil: depth_format.map(|format| wgpu::DepthStencilState {
+            format,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::LessEqual,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+    });
+    Ok(render_pipeline)
+}
+
+/// Recreate the overlay pipeline after the main pass's formats or sample
+/// count changed
+pub fn rebuild_light_preview_pipeline(
+    data: &mut LightPreviewData,
+    device: &wgpu::Device,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<()> {
+    data.render_pipeline = create_light_preview_pipeline(
+        device,
+        &data.render_bind_group_layout,
+        color_format,
+        depth_format,
+        sample_count,
+    )?;
+    Ok(())
+}
+
+/// Re-upload the transparency mask after blocks are registered
+pub fn refresh_light_preview_blocks(
+    data: &mut LightPreviewData,
+    queue: &wgpu::Queue,
+    registry: &BlockRegistry,
+) {
+    let mask = build_light_transparency_mask(registry);
+    queue.write_buffer(&data.transparency_buffer, 0, bytemuck::cast_slice(&mask));
+    data.previewed = None;
+}
+
+/// Force the next update to recompute (e.g. after blocks near the target change)
+pub fn invalidate_light_preview(data: &mut LightPreviewData) {
+    data.previewed = None;
+}
+
+/// Fill the uniform's chunk table for a volume starting at `origin`
+///
+/// The volume is never wider than a chunk, so it overlaps at most two chunks
+/// per axis. Sparse chunks supply their uniform voxel, palette chunks are
+/// decoded in the shader and chunks that are not loaded read as air.
+fn fill_chunk_table(
+    uniform: &mut LightPreviewUniform,
+    world_buffer: &WorldBuffer,
+    origin: VoxelPos,
+) {
+    let chunk_origin = voxel_to_chunk_pos(world_buffer.chunk_layout(), origin);
+    uniform.chunk_origin = [chunk_origin.x, chunk_origin.y, chunk_origin.z, 0];
+
+    for index in 0..LIGHT_PREVIEW_CHUNKS {
+        let chunk = ChunkPos::new(
+            chunk_origin.x + (index & 1) as i32,
+            chunk_origin.y + ((index >> 1) & 1) as i32,
+            chunk_origin.z + ((index >> 2) & 1) as i32,
+        );
+
+        let resident = world_buffer.existing_chunk_slot(chunk).or_else(|| {
+            world_buffer
+                .palette_chunk_slot(chunk)
+                .map(|slot| slot | PALETTE_SLOT_FLAG)
+        });
+        let (slot, fill) = match resident {
+            Some(slot) => (slot, 0),
+            None => (
+                NO_SLOT,
+                world_buffer
+                    .sparse_chunk(chunk)
+                    .map(|sparse| sparse.voxel.0)
+                    .unwrap_or(0),
+            ),
+        };
+        uniform.chunk_slots[index / 4][index % 4] = slot;
+        uniform.chunk_fill[index / 4][index % 4] = fill;
+    }
+}
+
+/// Update the preview for this frame
+///
+/// `target` is where the held block would be placed and `emission` its light
+/// level; pass `None` or 0 to hide the preview. Propagation is recorded into
+/// `encoder` only when the target or emission changed. Returns true if it was.
+pub fn update_light_preview(
+    data: &mut LightPreviewData,
+    queue: &wgpu::Queue,
+    encoder: &mut wgpu::CommandEncoder,
+    world_buffer: &WorldBuffer,
+    view_proj: Matrix4<f32>,
+    target: Option<VoxelPos>,
+    emission: u8,
+) -> bool {
+    let key = match target {
+        Some(target) if emission > 0 => LightPreviewKey { target, emission },
+        _ => {
+            data.previewed = None;
+            return false;
+        }
+    };
+
+    data.uniform.view_proj = view_proj.into();
+    let recompute = data.previewed != Some(key);
+    if recompute {
+        let radius = PREVIEW_RADIUS as i32;
+        let origin = VoxelPos::new(
+            key.target.x - radius,
+            key.target.y - radius,
+            key.target.z - radius,
+        );
+        data.uniform.origin = [origin.x, origin.y, origin.z, PREVIEW_EXTENT as i32];
+        data.uniform.source = [key.target.x, key.target.y, key.target.z, emission as i32];
+        fill_chunk_table(&mut data.uniform, world_buffer, origin);
+        data.previewed = Some(key);
+    }
+    queue.write_buffer(&data.uniform_buffer, 0, bytemuck::bytes_of(&data.uniform));
+
+    if recompute {
+        let groups = PREVIEW_EXTENT.div_ceil(WORKGROUP_SIZE);
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Light Preview Pass"),
+            timestamp_writes: None,
+        });
+
+        // Seed into cells A, ping-pong, then resolve from whichever holds the result
+        pass.set_pipeline(&data.seed_pipeline);
+        pass.set_bind_group(0, &data.compute_bind_groups[1], &[]);
+        pass.dispatch_workgroups(groups, groups, groups);
+
+        pass.set_pipeline(&data.propagate_pipeline);
+        for step in 0..PROPAGATION_PASSES as usize {
+            pass.set_bind_group(0, &data.compute_bind_groups[step % 2], &[]);
+            pass.dispatch_workgroups(groups, groups, groups);
+        }
+
+        pass.set_pipeline(&data.resolve_pipeline);
+        pass.set_bind_group(
+            0,
+            &data.compute_bind_groups[PROPAGATION_PASSES as usize % 2],
+            &[],
+        );
+        pass.dispatch_workgroups(groups, groups, groups);
+    }
+
+    recompute
+}
+
+/// Draw the overlay; call after opaque chunk rendering
+pub fn render_light_preview<'a>(data: &'a LightPreviewData, pass: &mut wgpu::RenderPass<'a>) {
+    if data.previewed.is_none() {
+        return;
+    }
+    let instances = PREVIEW_EXTENT * PREVIEW_EXTENT * PREVIEW_EXTENT * 6;
+    pass.set_pipeline(&data.render_pipeline);
+    pass.set_bind_group(0, &data.render_bind_group, &[]);
+    pass.draw(0..6, 0..instances);
+}
@@ -2,7 +2,7 @@
 //! Following DOP principles - pure functions that generate mesh data
 
 use crate::renderer::vertex::Vertex;
-use crate::world::core::{BlockId, ChunkPos, VoxelPos};
+use crate::world::core::{floor_div, BlockId, ChunkPos, VoxelPos};
 use crate::world::{world_operations, data_types::WorldData};
 
 /// Generate vertices for a simple unit cube
@@ -187,9 +187,9 @@ pub fn generate_chunk_terrain_mesh(
                         let neighbor_block = world_operations::get_block(world, neighbor_pos, chunk_size);
 
                         // Check if neighbor is at chunk boundary
-                        let neighbor_chunk_x = neighbor_pos.x.div_euclid(chunk_size as i32);
-                        let neighbor_chunk_y = neighbor_pos.y.div_euclid(chunk_size as i32);
-                        let neighbor_chunk_z = neighbor_pos.z.div_euclid(chunk_size as i32);
+                        let neighbor_chunk_x = floor_div(neighbor_pos.x, chunk_size as i32);
+                        let neighbor_chunk_y = floor_div(neighbor_pos.y, chunk_size as i32);
+                        let neighbor_chunk_z = floor_div(neighbor_pos.z, chunk_size as i32);
 
                         let neighbor_chunk_pos = ChunkPos::new(neighbor_chunk_x, neighbor_chunk_y, neighbor_chunk_z);
 
@@ -1,24 +1,241 @@
 //! Renderer Module - Simplified for DOP conversion
 
+pub mod adaptive_tessellation;
+pub mod anti_aliasing_data;
+pub mod anti_aliasing_operations;
+pub mod biome_tint_data;
+pub mod biome_tint_operations;
+pub mod chunk_lod_stitch_data;
+pub mod chunk_lod_stitch_operations;
+pub mod cloud_data;
+pub mod cloud_operations;
 pub mod compute_pipeline;
+pub mod device_recovery_data;
+pub mod device_recovery_operations;
+pub mod edge_highlight_data;
+pub mod edge_highlight_operations;
+pub mod entity_interpolation_operations;
+pub mod entity_lod_data;
+pub mod entity_lod_operations;
 pub mod error;
+pub mod far_terrain_data;
+pub mod far_terrain_operations;
+pub mod frame_capture_data;
+pub mod frame_capture_operations;
+pub mod frame_pacer_data;
+pub mod frame_pacer_operations;
 pub mod gpu_culling;
 pub mod gpu_driven;
 pub mod gpu_meshing;
 pub mod gpu_progress;
 pub mod gpu_state_data;
 pub mod gpu_state_operations;
+pub mod light_preview_data;
+pub mod light_preview_operations;
 pub mod mesh_optimizer;
 pub mod mesh_utils;
+pub mod parallel_encoding_data;
+pub mod parallel_encoding_operations;
+pub mod pipeline_cache_data;
+pub mod pipeline_cache_operations;
+pub mod placement_preview_data;
+pub mod placement_preview_operations;
+pub mod prop_instance_data;
+pub mod prop_instance_operations;
 pub mod renderer_data;
 pub mod renderer_operations;
+pub mod secondary_view_data;
+pub mod secondary_view_operations;
 pub mod selection_renderer;
+pub mod smooth_terrain_data;
+pub mod smooth_terrain_operations;
+pub mod sky_data;
+pub mod sky_operations;
+pub mod texture_streaming_data;
+pub mod texture_streaming_operations;
 pub mod vertex;
 
 // Simple re-exports
+pub use adaptive_tessellation::{
+    AdaptiveTessellator, RingRegion, SurfaceField, TessellatedMesh, TessellatedVertex,
+    TessellationParams, TessellationView,
+};
+pub use anti_aliasing_data::{AntiAliasingData, AntiAliasingMode, AntiAliasingUniform};
+pub use anti_aliasing_operations::{
+    anti_aliasing_sample_count, max_msaa_samples, parse_anti_aliasing_mode,
+    resolve_anti_aliasing_mode, scaled_render_size, set_anti_aliasing_mode,
+    set_anti_aliasing_render_scale, taa_jitter,
+};
+pub use biome_tint_data::{BiomeColorMap, BiomeColors, ChunkTintMap, TintColor, TintKind};
+pub use biome_tint_operations::{
+    apply_season_tint, biome_id, biome_tint, build_chunk_tint_map, default_biome_color_map,
+    pack_chunk_tint_map, packed_tint_map_len, register_biome_colors, sample_chunk_tint,
+    tint_corners, tint_kind_for_block,
+};
+pub use chunk_lod_stitch_data::{
+    ChunkLodStitchConfig, ChunkLodStitchData, NeighborLevels, StitchGeometry, StitchMask,
+    CHUNK_FACE_COUNT,
+};
+pub use chunk_lod_stitch_operations::{
+    build_stitch_skirts, chunk_face_neighbor, chunk_lod_level, chunk_stitch_mask,
+    compute_stitch_mask, create_chunk_lod_stitch, default_chunk_lod_stitch_config,
+    lod_cell_size, remove_chunk_lod, set_chunk_lod, stitch_chunk_mesh, take_restitch_chunks,
+};
+pub use cloud_data::{CloudConfig, CloudData, CloudUniform};
+pub use cloud_operations::{
+    cloud_density, cloud_shadow_factor, cloud_wind_step, create_clouds, default_cloud_config,
+    follow_clouds, rebuild_cloud_pipeline, render_clouds, set_cloud_shadows_enabled,
+    set_clouds_enabled, update_clouds,
+};
 pub use compute_pipeline::ComputePipeline;
+pub use device_recovery_data::{
+    BufferShadow, BufferShadowId, DeviceLossSignal, DeviceRecoveryData, DeviceRecoveryStats,
+    GpuBufferShadows,
+};
+pub use device_recovery_operations::{
+    create_buffer_shadow, create_device_recovery, device_loss_reason, device_lost,
+    mark_device_lost, note_surface_lost, note_surface_presented, poll_device_recovery,
+    rebuild_renderer_on_device, recover_renderer_device, request_replacement_device,
+    restore_buffer_shadows, shadow_buffer, watch_device_loss, write_buffer_shadow,
+};
+pub use edge_highlight_data::{
+    EdgeGeometryTargets, EdgeHighlightConfig, EdgeHighlightData, EdgeHighlightMode,
+    EdgeHighlightPipeline, EdgeHighlightUniform,
+};
+pub use edge_highlight_operations::{
+    create_edge_highlight, default_edge_highlight_config, edge_geometry_attachments,
+    edge_highlight_enabled, edge_highlight_uniform, encode_edge_highlight_pass,
+    finish_edge_highlight_frame, mark_edge_geometry_written, parse_edge_highlight_color,
+    parse_edge_highlight_mode, prepare_edge_highlight, set_edge_highlight_config,
+};
+pub use entity_interpolation_operations::{
+    begin_transform_tick, capture_physics_transforms, interpolate_entity_transforms,
+    lerp_position, nlerp_rotation, resize_entity_transforms, set_entity_rotation,
+    teleport_entity, IDENTITY_ROTATION,
+};
+pub use entity_lod_data::{
+    EntityLodBand, EntityLodBandConfig, EntityLodConfig, EntityLodData, EntityLodStats,
+    EntityPosition, ENTITY_LOD_BAND_COUNT,
+};
+pub use entity_lod_operations::{
+    create_entity_lod, default_entity_lod_config, entity_ai_due, entity_animation_due,
+    entity_lod_band_at, entity_lod_band_config, record_entity_lod_metrics,
+    select_entity_lod_band, set_entity_lod_radius, update_entity_lod,
+};
+pub use far_terrain_data::{FarTerrainConfig, FarTerrainData, FarTerrainUniform};
+pub use far_terrain_operations::{
+    create_far_terrain, default_far_terrain_config, far_terrain_needs_regeneration,
+    rebuild_far_terrain_pipeline, render_far_terrain, update_far_terrain,
+};
+pub use frame_capture_data::CapturedFrame;
+pub use frame_capture_operations::{
+    capture_renderer_frame, padded_bytes_per_row, unpad_capture_rows,
+};
+pub use frame_pacer_data::{FramePacerConfig, FramePacerData, FramePacingStats};
+pub use frame_pacer_operations::{
+    advance_fixed_ticks, create_frame_pacer, default_frame_pacer_config, frame_interval,
+    interpolation_alpha, record_frame_pacing, set_display_refresh_rate, set_fixed_tick_rate,
+    set_fps_cap, set_frame_pacer_vsync, set_window_focused, wait_for_next_frame,
+};
+pub use light_preview_data::{
+    LightPreviewData, LightPreviewKey, LightPreviewUniform, LIGHT_PREVIEW_CHUNKS,
+};
+pub use light_preview_operations::{
+    build_light_transparency_mask, create_light_preview, held_block_emission,
+    invalidate_light_preview, rebuild_light_preview_pipeline, refresh_light_preview_blocks,
+    render_light_preview, update_light_preview,
+};
 pub use mesh_optimizer::MeshOptimizer;
 pub use mesh_utils::MeshUtils;
-pub use renderer_data::{RendererData, Renderer};
-pub use renderer_operations::run_with_buffers;
+pub use parallel_encoding_data::{
+    FrameEncodingTimes, ParallelEncodingConfig, ParallelEncodingData, ParallelEncodingStats,
+    RecordedFrame, RecordedPasses,
+};
+pub use parallel_encoding_operations::{
+    create_parallel_encoding, default_parallel_encoding_config, record_frame_commands,
+    record_independent_passes, record_parallel_encoding_frame, set_parallel_encoding_enabled,
+    should_record_in_parallel,
+};
+pub use pipeline_cache_data::{
+    PipelineCacheData, PipelineCacheError, PipelineCacheKey, PipelineCacheResult,
+    PipelineCacheStats, PipelineCacheStatus, SavedPipelineCache,
+};
+pub use pipeline_cache_operations::{
+    create_pipeline_cache, load_pipeline_cache, pipeline_cache_key, pipeline_cache_path,
+    record_pipeline_cache_metrics, save_pipeline_cache, timed_pipeline_creation,
+};
+pub use placement_preview_data::{
+    GhostMeshKey, GhostVertex, PlacementPreview, PlacementPreviewData, PlacementPreviewUniform,
+    PlacementValidity, GHOST_BOX_VERTEX_COUNT, GHOST_MAX_VERTEX_COUNT,
+};
+pub use placement_preview_operations::{
+    build_ghost_mesh, compute_placement_preview, create_placement_preview,
+    placement_preview_color, placement_validity, rebuild_placement_preview_pipeline,
+    render_placement_preview, update_placement_preview,
+};
+pub use prop_instance_data::{
+    PackedPropInstances, PropCell, PropCellInstances, PropDrawRange, PropInstanceConfig,
+    PropInstanceData, PropKind, PropStreamUpdate, PropSurface,
+};
+pub use prop_instance_operations::{
+    create_prop_instances, default_prop_instance_config, generate_prop_cell,
+    invalidate_prop_cell, pack_prop_instances, prop_cell_at, prop_colliders,
+    update_prop_streaming,
+};
+pub use renderer_data::{RenderTarget, RendererData, Renderer};
+pub use renderer_operations::{
+    attach_renderer_to_texture, attach_renderer_to_window, enable_renderer_clouds,
+    enable_renderer_far_terrain, enable_renderer_light_preview, enable_renderer_pipeline_cache, enable_renderer_placement_preview, enable_renderer_sky,
+    render_embedded_frame, save_renderer_pipeline_cache,
+    render_target_format, render_target_size, renderer_projection_jitter, renderer_refresh_rate,
+    create_window_renderer, resize_renderer, set_render_texture, set_renderer_anti_aliasing,
+    set_renderer_parallel_encoding, set_renderer_edge_highlight, set_renderer_render_scale,
+    set_renderer_block_atlas, set_renderer_cloud_shadows, set_renderer_clouds_enabled,
+    set_renderer_vsync, update_renderer_clouds,
+    update_renderer_entities, update_renderer_entity_lod, update_renderer_far_terrain,
+    update_renderer_light_preview, update_renderer_placement_preview, update_renderer_sky,
+    update_renderer_texture_streaming,
+};
+pub use secondary_view_data::{
+    FrustumPlanes, SecondaryView, SecondaryViewBudget, SecondaryViewConfig, SecondaryViewId, SecondaryViewStats,
+    SecondaryViewTiming, SecondaryViewsData,
+};
+pub use secondary_view_operations::{
+    add_secondary_view, chunk_in_frustum, create_secondary_views, cull_secondary_view,
+    default_secondary_view_budget, default_secondary_view_config, effective_view_interval,
+    prepare_secondary_views, rebuild_secondary_views, record_secondary_view_frame_time, remove_secondary_view,
+    render_secondary_views, resize_secondary_view, schedule_secondary_views, secondary_view,
+    secondary_view_due, secondary_view_texture, set_secondary_view_budget,
+    set_secondary_view_camera, set_secondary_view_enabled, set_secondary_view_interval,
+    submit_secondary_views,
+};
 pub use selection_renderer::SelectionRenderer;
+pub use sky_data::{SkyColors, SkyConfig, SkyData, SkyUniform};
+pub use sky_operations::{
+    calculate_sky_colors, cloud_coverage, create_sky, default_sky_config, rebuild_sky_pipeline,
+    render_sky, sky_fog_color, sky_sun_direction, update_sky, update_sky_colors,
+};
+pub use smooth_terrain_data::{
+    ChunkSdf, MaterialBlend, SmoothBlockSet, SmoothChunkMesh, SmoothChunkMeshes,
+    SmoothTerrainConfig, SmoothTerrainData,
+};
+pub use smooth_terrain_operations::{
+    build_chunk_sdf, chunk_sdf_material, create_smooth_terrain, cubic_mesh_block,
+    default_smooth_terrain_config, is_field_solid, is_smooth_block, remove_chunk_sdf,
+    sample_chunk_sdf, set_smooth_terrain_enabled, smooth_block_mask, take_smooth_remesh_chunks,
+    tessellate_smooth_chunk, tessellate_smooth_chunks, update_chunk_sdf,
+};
+pub use texture_streaming_data::{
+    MemoryBudgetView, MipLevelData, ResidencyChange, StreamedTexture, StreamedTextureGpu,
+    TextureResidency, TextureStreamId, TextureStreamingConfig, TextureStreamingData,
+    TextureStreamingStats,
+};
+pub use texture_streaming_operations::{
+    apply_texture_streaming, build_block_atlas, build_mip_chain, coarsest_streamed_mip,
+    commit_residency_change,
+    create_texture_streaming, default_streamed_residency, default_texture_streaming_config,
+    find_streamed_texture, memory_budget_view, mip_bytes, next_residency_changes,
+    note_texture_use, record_texture_streaming_metrics, refresh_streaming_stats,
+    register_streamed_texture, remove_streamed_texture, resident_bytes, set_texture_residency,
+    streamed_texture_view, update_texture_streaming, wanted_mip,
+};
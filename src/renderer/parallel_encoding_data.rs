@@ -0,0 +1,86 @@
+//! Parallel Encoding Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Recording lives in parallel_encoding_operations.rs
+//!
+//! Passes that do not depend on the main pass (secondary views today;
+//! shadow maps and compute prepasses as they are added) are recorded into
+//! their own command buffers on worker threads while the main thread
+//! records the main pass. The buffers are submitted in pass order ahead of
+//! the main pass. Recording is scoped to the frame: workers only borrow
+//! renderer resources until every buffer is finished, and each finished
+//! buffer keeps the GPU resources it uses alive until it has executed.
+
+/// When independent passes leave the main thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParallelEncodingConfig {
+    /// Record independent passes on worker threads
+    pub enabled: bool,
+    /// Fewer independent passes than this are recorded on the main thread
+    pub min_parallel_passes: usize,
+    /// Passes one worker records before another worker is used
+    pub passes_per_worker: usize,
+}
+
+/// Recording times of the last frame, for the profiler and debug overlays
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ParallelEncodingStats {
+    /// Frames recorded since creation
+    pub frames: u64,
+    /// Frames whose independent passes were recorded on workers
+    pub parallel_frames: u64,
+    /// Independent passes recorded in the last frame
+    pub passes_last_frame: u32,
+    /// Command buffers submitted ahead of the main pass in the last frame
+    pub command_buffers_last_frame: u32,
+    /// Main thread time spent recording the last frame, including waiting
+    /// for workers (milliseconds)
+    pub main_thread_ms: f32,
+    /// Time the independent passes took to record, summed over every
+    /// thread that recorded one (milliseconds)
+    pub pass_recording_ms: f32,
+    /// Time the main pass took to record (milliseconds)
+    pub main_pass_ms: f32,
+    /// Main thread time saved against recording every pass on it
+    /// (milliseconds)
+    pub saved_ms: f32,
+}
+
+/// Parallel recording settings and statistics of a renderer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParallelEncodingData {
+    pub config: ParallelEncodingConfig,
+    pub stats: ParallelEncodingStats,
+}
+
+/// Recording times of one frame, reported by the recording functions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameEncodingTimes {
+    /// Independent passes recorded
+    pub passes: u32,
+    /// Command buffers they were recorded into
+    pub command_buffers: u32,
+    /// Whether they were recorded on workers
+    pub parallel: bool,
+    /// Main thread time, including waiting for workers (nanoseconds)
+    pub main_thread_ns: u64,
+    /// Independent pass recording time summed over threads (nanoseconds)
+    pub pass_recording_ns: u64,
+    /// Main pass recording time (nanoseconds)
+    pub main_pass_ns: u64,
+}
+
+/// Command buffers of independent passes, in pass order
+#[derive(Debug, Default)]
+pub struct RecordedPasses {
+    pub buffers: Vec<wgpu::CommandBuffer>,
+    /// Recording time summed over the threads that recorded (nanoseconds)
+    pub recording_ns: u64,
+}
+
+/// Command buffers of a frame in submission order, with its times
+#[derive(Debug)]
+pub struct RecordedFrame {
+    pub buffers: Vec<wgpu::CommandBuffer>,
+    pub times: FrameEncodingTimes,
+}
@@ -0,0 +1,201 @@
+//! Parallel Encoding Operations - Pure functions over ParallelEncodingData
+//!
+//! `record_frame_commands` hands the independent passes of a frame to
+//! worker threads and records the main pass on the calling thread
+//! meanwhile. Workers record `passes_per_worker` passes each into their own
+//! encoder; the command buffers come back in pass order so submitting them
+//! as returned keeps the frame's pass order. Worker spans show up on their
+//! own thread tracks in trace captures, next to `record_frame_commands` on
+//! the main thread.
+
+use super::parallel_encoding_data::{
+    FrameEncodingTimes, ParallelEncodingConfig, ParallelEncodingData, ParallelEncodingStats,
+    RecordedFrame, RecordedPasses,
+};
+use crate::constants::parallel_encoding::{MIN_PARALLEL_PASSES, PASSES_PER_WORKER};
+use rayon::prelude::*;
+use std::time::Instant;
+
+const NANOS_PER_MS: f32 = 1_000_000.0;
+
+/// Parallel recording on, from two independent passes
+pub fn default_parallel_encoding_config() -> ParallelEncodingConfig {
+    ParallelEncodingConfig {
+        enabled: true,
+        min_parallel_passes: MIN_PARALLEL_PASSES,
+        passes_per_worker: PASSES_PER_WORKER,
+    }
+}
+
+/// Default settings and empty statistics
+pub fn create_parallel_encoding() -> ParallelEncodingData {
+    ParallelEncodingData {
+        config: default_parallel_encoding_config(),
+        stats: ParallelEncodingStats::default(),
+    }
+}
+
+/// Turn worker recording on or off (off records every pass on the main
+/// thread into one encoder, as before)
+pub fn set_parallel_encoding_enabled(data: &mut ParallelEncodingData, enabled: bool) {
+    data.config.enabled = enabled;
+}
+
+/// Whether `passes` independent passes are worth handing to workers
+pub fn should_record_in_parallel(config: &ParallelEncodingConfig, passes: usize) -> bool {
+    config.enabled && passes > 0 && passes >= config.min_parallel_passes
+}
+
+fn elapsed_ns(start: Instant) -> u64 {
+    start.elapsed().as_nanos() as u64
+}
+
+/// Record independent passes into command buffers in pass order. Below the
+/// parallel threshold every pass goes into one encoder on the calling
+/// thread.
+pub fn record_independent_passes<P: Sync>(
+    config: &ParallelEncodingConfig,
+    device: &wgpu::Device,
+    passes: &[P],
+    label: &'static str,
+    record: impl Fn(&P, &mut wgpu::CommandEncoder) + Sync,
+) -> RecordedPasses {
+    if passes.is_empty() {
+        return RecordedPasses::default();
+    }
+    let encode = |batch: &[P]| {
+        let _span = crate::trace_span!(Render, "record_independent_passes");
+        let start = Instant::now();
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some(label) });
+        for pass in batch {
+            record(pass, &mut encoder);
+        }
+        RecordedPasses {
+            buffers: vec![encoder.finish()],
+            recording_ns: elapsed_ns(start),
+        }
+    };
+
+    if !should_record_in_parallel(config, passes.len()) {
+        return encode(passes);
+    }
+    // Indexed collect keeps pass order whichever worker finishes first
+    let batches: Vec<RecordedPasses> = passes
+        .par_chunks(config.passes_per_worker.max(1))
+        .map(encode)
+        .collect();
+    RecordedPasses {
+        recording_ns: batches.iter().map(|batch| batch.recording_ns).sum(),
+        buffers: batches
+            .into_iter()
+            .flat_map(|batch| batch.buffers)
+            .collect(),
+    }
+}
+
+/// Record a frame: the independent passes on workers while `main_pass`
+/// records on the calling thread. The buffers come back in submission
+/// order, independent passes first. Workers only borrow `passes` and the
+/// resources `record` reaches until this returns; the buffers keep what
+/// they use alive until executed.
+pub fn record_frame_commands<P: Sync>(
+    config: &ParallelEncodingConfig,
+    device: &wgpu::Device,
+    passes: &[P],
+    label: &'static str,
+    record: impl Fn(&P, &mut wgpu::CommandEncoder) + Sync,
+    main_pass: impl FnOnce() -> wgpu::CommandBuffer,
+) -> RecordedFrame {
+    let start = Instant::now();
+    let parallel = should_record_in_parallel(config, passes.len());
+
+    let (independent, main_buffer, main_pass_ns) = if parallel {
+        std::thread::scope(|scope| {
+            let workers =
+                scope.spawn(|| record_independent_passes(config, device, passes, label, &record));
+            let main_start = Instant::now();
+            let main_buffer = main_pass();
+            let main_pass_ns = elapsed_ns(main_start);
+            let independent = match workers.join() {
+                Ok(independent) => independent,
+                Err(panic) => std::panic::resume_unwind(panic),
+            };
+            (independent, main_buffer, main_pass_ns)
+        })
+    } else {
+        let independent = record_independent_passes(config, device, passes, label, &record);
+        let main_start = Instant::now();
+        let main_buffer = main_pass();
+        (independent, main_buffer, elapsed_ns(main_start))
+    };
+
+    let times = FrameEncodingTimes {
+        passes: passes.len() as u32,
+        command_buffers: independent.buffers.len() as u32,
+        parallel,
+        main_thread_ns: elapsed_ns(start),
+        pass_recording_ns: independent.recording_ns,
+        main_pass_ns,
+    };
+    let mut buffers = independent.buffers;
+    buffers.push(main_buffer);
+    RecordedFrame { buffers, times }
+}
+
+/// Fold a frame's recording times into the statistics
+pub fn record_parallel_encoding_frame(data: &mut ParallelEncodingData, times: &FrameEncodingTimes) {
+    let stats = &mut data.stats;
+    stats.frames += 1;
+    if times.parallel {
+        stats.parallel_frames += 1;
+    }
+    stats.passes_last_frame = times.passes;
+    stats.command_buffers_last_frame = times.command_buffers;
+    stats.main_thread_ms = times.main_thread_ns as f32 / NANOS_PER_MS;
+    stats.pass_recording_ms = times.pass_recording_ns as f32 / NANOS_PER_MS;
+    stats.main_pass_ms = times.main_pass_ns as f32 / NANOS_PER_MS;
+    let sequential_ns = times.pass_recording_ns + times.main_pass_ns;
+    stats.saved_ms = sequential_ns.saturating_sub(times.main_thread_ns) as f32 / NANOS_PER_MS;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel_threshold_and_savings() {
+        let mut data = create_parallel_encoding();
+        assert!(!should_record_in_parallel(&data.config, 0));
+        assert!(!should_record_in_parallel(
+            &data.config,
+            MIN_PARALLEL_PASSES - 1
+        ));
+        assert!(should_record_in_parallel(&data.config, MIN_PARALLEL_PASSES));
+        set_parallel_encoding_enabled(&mut data, false);
+        assert!(!should_record_in_parallel(&data.config, 8));
+
+        // Four 2 ms views recorded on workers behind a 3 ms main pass
+        let times = FrameEncodingTimes {
+            passes: 4,
+            command_buffers: 4,
+            parallel: true,
+            main_thread_ns: 4_000_000,
+            pass_recording_ns: 8_000_000,
+            main_pass_ns: 3_000_000,
+        };
+        record_parallel_encoding_frame(&mut data, &times);
+        assert_eq!(data.stats.parallel_frames, 1);
+        assert!((data.stats.saved_ms - 7.0).abs() < 1e-4);
+
+        // Sequential frames never report negative savings
+        let times = FrameEncodingTimes {
+            parallel: false,
+            main_thread_ns: 11_500_000,
+            ..times
+        };
+        record_parallel_encoding_frame(&mut data, &times);
+        assert_eq!((data.stats.frames, data.stats.parallel_frames), (2, 1));
+        assert_eq!(data.stats.saved_ms, 0.0);
+    }
+}
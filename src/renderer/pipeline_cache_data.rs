@@ -0,0 +1,91 @@
+//! Pipeline Cache Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Keying, loading, saving and timing live in pipeline_cache_operations.rs
+//!
+//! Driver pipeline caches are only valid for the adapter and driver that
+//! produced them, so each adapter gets its own file keyed by vendor, device,
+//! backend, driver version and engine version; a file saved under any other
+//! key is discarded on load. Pipeline creation is timed at startup and the
+//! previous run's time is kept in the file, so a warm start can be compared
+//! with the one before it.
+//!
+//! wgpu 0.19 does not expose driver pipeline caches yet (`PipelineCache`
+//! arrives in a later wgpu), so `data` stays empty and every start compiles
+//! from scratch; the file still carries the key and startup timings, and
+//! the blob is filled once the engine moves to a wgpu that supports it.
+
+use serde::{Deserialize, Serialize};
+
+/// Adapter and versions a cache blob is valid for
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PipelineCacheKey {
+    pub vendor: u32,
+    pub device: u32,
+    /// wgpu backend name ("Vulkan", "Metal", ...)
+    pub backend: String,
+    pub driver: String,
+    pub driver_info: String,
+    pub engine_version: String,
+}
+
+/// What happened to the cache file at startup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PipelineCacheStatus {
+    /// Not loaded yet
+    #[default]
+    Cold,
+    /// No file for this adapter
+    Missing,
+    /// File loaded for this adapter, driver and engine version
+    Loaded,
+    /// File was written under another driver or engine version, or was
+    /// unreadable; it is replaced on the next save
+    Invalidated,
+}
+
+/// Startup pipeline counters
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PipelineCacheStats {
+    pub status: PipelineCacheStatus,
+    /// Bytes of cache blob loaded
+    pub loaded_bytes: u64,
+    /// Pipeline builds timed so far this run (a build may create several
+    /// pipelines)
+    pub pipelines_created: u32,
+    /// Time spent creating them (milliseconds)
+    pub creation_ms: f32,
+    /// Same time from the previous run with this key (None on the first)
+    pub previous_creation_ms: Option<f32>,
+}
+
+/// Pipeline cache of the current adapter
+#[derive(Debug, Clone)]
+pub struct PipelineCacheData {
+    pub key: PipelineCacheKey,
+    /// Opaque backend cache blob (empty while unsupported)
+    pub data: Vec<u8>,
+    pub stats: PipelineCacheStats,
+}
+
+/// Pipeline cache as stored on disk, one file per adapter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedPipelineCache {
+    pub version: u32,
+    pub key: PipelineCacheKey,
+    pub data: Vec<u8>,
+    /// Pipeline creation time of the run that saved the file (milliseconds)
+    pub creation_ms: f32,
+}
+
+/// Pipeline cache errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PipelineCacheError {
+    #[error("Pipeline cache serialization failed: {0}")]
+    Serialization(String),
+
+    #[error("Pipeline cache I/O failed: {0}")]
+    Io(String),
+}
+
+pub type PipelineCacheResult<T> = Result<T, PipelineCacheError>;
@@ -0,0 +1,203 @@
+//! Pipeline Cache Operations - Pure DOP Functions
+//!
+//! At startup: `load_pipeline_cache` with the adapter's `pipeline_cache_key`
+//! before creating pipelines, wrap pipeline creation in
+//! `timed_pipeline_creation`, and `save_pipeline_cache` once startup is
+//! done. The renderer does all three through `enable_renderer_pipeline_cache`
+//! and `save_renderer_pipeline_cache`.
+
+use super::pipeline_cache_data::{
+    PipelineCacheData, PipelineCacheError, PipelineCacheKey, PipelineCacheResult,
+    PipelineCacheStats, PipelineCacheStatus, SavedPipelineCache,
+};
+use crate::constants::persistence_constants::{
+    PIPELINE_CACHE_FILE_PREFIX, PIPELINE_CACHE_FORMAT_VERSION,
+};
+use crate::engine_buffers::MetricsBuffers;
+use crate::persistence::write_file_atomic;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Key of an adapter under the running engine version
+pub fn pipeline_cache_key(info: &wgpu::AdapterInfo) -> PipelineCacheKey {
+    PipelineCacheKey {
+        vendor: info.vendor,
+        device: info.device,
+        backend: format!("{:?}", info.backend),
+        driver: info.driver.clone(),
+        driver_info: info.driver_info.clone(),
+        engine_version: env!("CARGO_PKG_VERSION").to_string(),
+    }
+}
+
+/// Empty cache for a key, before anything is loaded
+pub fn create_pipeline_cache(key: PipelineCacheKey) -> PipelineCacheData {
+    PipelineCacheData {
+        key,
+        data: Vec::new(),
+        stats: PipelineCacheStats::default(),
+    }
+}
+
+/// File of an adapter inside the cache directory. Driver and engine
+/// versions are left out so an update overwrites the stale file.
+pub fn pipeline_cache_path(key: &PipelineCacheKey, cache_dir: &Path) -> PathBuf {
+    cache_dir.join(format!(
+        "{}-{}-{:04x}-{:04x}.bin",
+        PIPELINE_CACHE_FILE_PREFIX,
+        key.backend.to_lowercase(),
+        key.vendor,
+        key.device
+    ))
+}
+
+/// Load the adapter's cache from `cache_dir`. Never fails: a missing,
+/// unreadable or stale file only means compiling from scratch.
+pub fn load_pipeline_cache(key: PipelineCacheKey, cache_dir: &Path) -> PipelineCacheData {
+    let mut cache = create_pipeline_cache(key);
+    let path = pipeline_cache_path(&cache.key, cache_dir);
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            cache.stats.status = PipelineCacheStatus::Missing;
+            return cache;
+        }
+        Err(e) => {
+            log::warn!("[PipelineCache] Failed to read {}: {}", path.display(), e);
+            cache.stats.status = PipelineCacheStatus::Invalidated;
+            return cache;
+        }
+    };
+
+    let saved = match bincode::deserialize::<SavedPipelineCache>(&bytes) {
+        Ok(saved) if saved.version == PIPELINE_CACHE_FORMAT_VERSION => saved,
+        Ok(saved) => {
+            log::info!(
+                "[PipelineCache] Discarding cache format version {}",
+                saved.version
+            );
+            cache.stats.status = PipelineCacheStatus::Invalidated;
+            return cache;
+        }
+        Err(e) => {
+            log::warn!(
+                "[PipelineCache] Discarding corrupt {}: {}",
+                path.display(),
+                e
+            );
+            cache.stats.status = PipelineCacheStatus::Invalidated;
+            return cache;
+        }
+    };
+    if saved.key != cache.key {
+        log::info!(
+            "[PipelineCache] Driver or engine changed ({} {} -> {} {}), recompiling",
+            saved.key.driver_info,
+            saved.key.engine_version,
+            cache.key.driver_info,
+            cache.key.engine_version
+        );
+        cache.stats.status = PipelineCacheStatus::Invalidated;
+        return cache;
+    }
+
+    cache.stats.status = PipelineCacheStatus::Loaded;
+    cache.stats.loaded_bytes = saved.data.len() as u64;
+    cache.stats.previous_creation_ms = Some(saved.creation_ms);
+    cache.data = saved.data;
+    cache
+}
+
+/// Run one pipeline build and add its time to the startup counters
+pub fn timed_pipeline_creation<R>(cache: &mut PipelineCacheData, create: impl FnOnce() -> R) -> R {
+    let started = Instant::now();
+    let result = create();
+    cache.stats.pipelines_created += 1;
+    cache.stats.creation_ms += started.elapsed().as_secs_f32() * 1000.0;
+    result
+}
+
+/// Write the cache and this run's creation time into `cache_dir`
+pub fn save_pipeline_cache(cache: &PipelineCacheData, cache_dir: &Path) -> PipelineCacheResult<()> {
+    let saved = SavedPipelineCache {
+        version: PIPELINE_CACHE_FORMAT_VERSION,
+        key: cache.key.clone(),
+        data: cache.data.clone(),
+        creation_ms: cache.stats.creation_ms,
+    };
+    let bytes =
+        bincode::serialize(&saved).map_err(|e| PipelineCacheError::Serialization(e.to_string()))?;
+    std::fs::create_dir_all(cache_dir).map_err(|e| PipelineCacheError::Io(e.to_string()))?;
+    write_file_atomic(&pipeline_cache_path(&cache.key, cache_dir), &bytes)
+        .map_err(|e| PipelineCacheError::Io(e.to_string()))?;
+
+    match cache.stats.previous_creation_ms {
+        Some(previous) => log::info!(
+            "[PipelineCache] {} pipeline builds took {:.1} ms (previous run {:.1} ms)",
+            cache.stats.pipelines_created,
+            cache.stats.creation_ms,
+            previous
+        ),
+        None => log::info!(
+            "[PipelineCache] {} pipeline builds took {:.1} ms (no previous run)",
+            cache.stats.pipelines_created,
+            cache.stats.creation_ms
+        ),
+    }
+    Ok(())
+}
+
+/// Publish the startup pipeline counters to the metrics buffers
+pub fn record_pipeline_cache_metrics(cache: &PipelineCacheData, metrics: &mut MetricsBuffers) {
+    metrics.pipeline_cache = cache.stats;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(driver_info: &str) -> PipelineCacheKey {
+        PipelineCacheKey {
+            vendor: 0x10de,
+            device: 0x2684,
+            backend: "Vulkan".to_string(),
+            driver: "NVIDIA".to_string(),
+            driver_info: driver_info.to_string(),
+            engine_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cache_reloads_for_same_key_and_invalidates_on_driver_change() {
+        let dir = tempfile::tempdir().expect("temp dir");
+
+        let mut cache = load_pipeline_cache(key("550.54"), dir.path());
+        assert_eq!(cache.stats.status, PipelineCacheStatus::Missing);
+        let answer = timed_pipeline_creation(&mut cache, || 42);
+        assert_eq!(answer, 42);
+        assert_eq!(cache.stats.pipelines_created, 1);
+        cache.data = vec![1, 2, 3];
+        save_pipeline_cache(&cache, dir.path()).expect("saves");
+
+        let warm = load_pipeline_cache(key("550.54"), dir.path());
+        assert_eq!(warm.stats.status, PipelineCacheStatus::Loaded);
+        assert_eq!(warm.data, [1, 2, 3]);
+        assert_eq!(
+            warm.stats.previous_creation_ms,
+            Some(cache.stats.creation_ms)
+        );
+
+        // A driver update shares the file name but not the key
+        let updated = load_pipeline_cache(key("555.42"), dir.path());
+        assert_eq!(updated.stats.status, PipelineCacheStatus::Invalidated);
+        assert!(updated.data.is_empty());
+        assert_eq!(
+            pipeline_cache_path(&updated.key, dir.path()),
+            pipeline_cache_path(&warm.key, dir.path())
+        );
+
+        let mut metrics = MetricsBuffers::default();
+        record_pipeline_cache_metrics(&warm, &mut metrics);
+        assert_eq!(metrics.pipeline_cache.loaded_bytes, 3);
+    }
+}
@@ -0,0 +1,92 @@
+//! Placement Preview Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! All transformations happen in placement_preview_operations.rs
+//!
+//! Translucent ghost of the active block at the position it would be placed
+//! on the targeted face. The ghost is the block model turned by the
+//! orientation placement would store, and is tinted red when placing there
+//! would fail.
+
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use crate::world::blocks::{BlockModel, BlockRotation, MAX_MODEL_BOXES};
+use crate::world::core::{BlockId, VoxelPos};
+use bytemuck::{Pod, Zeroable};
+
+/// Vertices per model box in the ghost mesh (6 faces, 2 triangles each)
+pub const GHOST_BOX_VERTEX_COUNT: usize = 36;
+
+/// Capacity of the ghost vertex buffer: the largest model's mesh
+pub const GHOST_MAX_VERTEX_COUNT: usize = GHOST_BOX_VERTEX_COUNT * MAX_MODEL_BOXES;
+
+/// Ghost mesh vertex in model space (0..1 block space)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct GhostVertex {
+    pub position: [f32; 3],
+    /// Face brightness; the model front face is emphasised
+    pub shade: f32,
+}
+
+/// Uniform of the ghost shader
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct PlacementPreviewUniform {
+    pub view_proj: [[f32; 4]; 4],
+    /// xyz = world min corner of the target voxel, w = inflation (voxels)
+    pub origin: [f32; 4],
+    /// rgb = tint, a = alpha
+    pub color: [f32; 4],
+}
+
+/// Whether the block can go where the ghost is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementValidity {
+    Valid,
+    /// The target is farther from the camera than the reach
+    OutOfReach,
+    /// The block would overlap the player
+    IntersectsPlayer,
+}
+
+/// Where and how the active block would be placed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacementPreview {
+    pub target: VoxelPos,
+    pub block: BlockId,
+    pub model: BlockModel,
+    /// Metadata placement would store (orientation)
+    pub metadata: u8,
+    pub rotation: BlockRotation,
+    /// The block has an orientation mode, so its front face is marked
+    pub directional: bool,
+    pub validity: PlacementValidity,
+}
+
+/// What the ghost vertex buffer was built for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GhostMeshKey {
+    pub model: BlockModel,
+    pub rotation: BlockRotation,
+    pub directional: bool,
+}
+
+/// GPU resources and state for the placement ghost
+pub struct PlacementPreviewData {
+    pub uniform: PlacementPreviewUniform,
+    pub uniform_buffer: wgpu::Buffer,
+    pub vertex_buffer: wgpu::Buffer,
+    pub render_pipeline: wgpu::RenderPipeline,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub bind_group: wgpu::BindGroup,
+    /// `uniform_buffer` and the ghost `vertex_buffer`
+    pub gpu_allocations: Vec<GpuAllocationGuard>,
+    pub gpu_owner: GpuOwnerGuard,
+
+    /// Mesh the vertex buffer holds
+    pub meshed: Option<GhostMeshKey>,
+    /// Vertices of that mesh
+    pub vertex_count: u32,
+    /// Preview being drawn (None = nothing to draw)
+    pub preview: Option<PlacementPreview>,
+}
@@ -0,0 +1,524 @@
+//! Placement Preview Operations - Pure DOP
+//!
+//! Functions that work out where the active block would be placed, build
+//! its ghost mesh and draw it in the translucent part of the main pass.
+//! The mesh is only rebuilt when the placement orientation changes; other
+//! frames just move and tint it.
+
+use super::error::RendererResult;
+use super::placement_preview_data::{
+    GhostMeshKey, GhostVertex, PlacementPreview, PlacementPreviewData, PlacementPreviewUniform,
+    PlacementValidity, GHOST_BOX_VERTEX_COUNT, GHOST_MAX_VERTEX_COUNT,
+};
+use crate::camera::{
+    build_projection_matrix, build_view_matrix, calculate_forward_vector, CameraData,
+};
+use crate::constants::placement_preview::{
+    FRONT_FACE_EMPHASIS, GHOST_INFLATE, INVALID_COLOR, MAX_REACH, VALID_COLOR,
+};
+use crate::memory::{create_tracked_buffer_init, register_global_gpu_owner};
+use crate::physics::aabb::{aabb_intersects, create_aabb};
+use crate::physics::AABB;
+use crate::world::blocks::orientation::IDENTITY_ROTATION;
+use crate::world::blocks::{
+    block_model, block_model_boxes, block_orientation_mode, block_rotation,
+    compute_placement_metadata, rotate_model_normal, rotate_model_position, BlockModel,
+    BlockRotation, MetadataSchemaRegistry, OrientationMode,
+};
+use crate::world::core::{BlockId, RaycastHit, VoxelPos};
+use crate::world::data_types::WorldData;
+use crate::world::world_operations;
+use bytemuck::Zeroable;
+use cgmath::{Matrix4, MetricSpace, Point3, SquareMatrix};
+use wgpu::util::DeviceExt;
+
+/// Corners of one cube face in model space (0..1 block space)
+type FaceCorners = [[f32; 3]; 4];
+
+/// Cube faces in `BLOCK_FACINGS` order (+X, -X, +Y, -Y, +Z, -Z)
+const CUBE_FACES: [FaceCorners; 6] = [
+    [
+        [1.0, 0.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [1.0, 1.0, 1.0],
+        [1.0, 0.0, 1.0],
+    ],
+    [
+        [0.0, 0.0, 1.0],
+        [0.0, 1.0, 1.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0],
+    ],
+    [
+        [0.0, 1.0, 0.0],
+        [0.0, 1.0, 1.0],
+        [1.0, 1.0, 1.0],
+        [1.0, 1.0, 0.0],
+    ],
+    [
+        [0.0, 0.0, 1.0],
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 0.0, 1.0],
+    ],
+    [
+        [1.0, 0.0, 1.0],
+        [1.0, 1.0, 1.0],
+        [0.0, 1.0, 1.0],
+        [0.0, 0.0, 1.0],
+    ],
+    [
+        [0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [1.0, 0.0, 0.0],
+    ],
+];
+
+const FACE_NORMALS: [[f32; 3]; 6] = [
+    [1.0, 0.0, 0.0],
+    [-1.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0],
+    [0.0, -1.0, 0.0],
+    [0.0, 0.0, 1.0],
+    [0.0, 0.0, -1.0],
+];
+
+/// Models are authored facing +Z
+const MODEL_FRONT_FACE: usize = 4;
+
+/// Two triangles per face
+const FACE_TRIANGLES: [usize; 6] = [0, 1, 2, 0, 2, 3];
+
+/// Shrink of the ghost bounds before the player overlap test, so a player
+/// standing against the target voxel does not count as inside it (voxels)
+const CONTACT_EPSILON: f32 = 1e-3;
+
+/// Fixed directional shading so the ghost reads as a solid
+fn face_shade(normal: [f32; 3]) -> f32 {
+    if normal[1] > 0.5 {
+        1.0
+    } else if normal[1] < -0.5 {
+        0.55
+    } else if normal[0].abs() > 0.5 {
+        0.8
+    } else {
+        0.7
+    }
+}
+
+/// Ghost mesh of a block model turned by `rotation`, one cube per model
+/// box. Directional blocks get brighter front faces so the preview shows
+/// which way they will face.
+pub fn build_ghost_mesh(
+    model: BlockModel,
+    rotation: BlockRotation,
+    directional: bool,
+) -> Vec<GhostVertex> {
+    let boxes = block_model_boxes(model);
+    let mut vertices = Vec::with_capacity(GHOST_BOX_VERTEX_COUNT * boxes.len());
+    for [min, max] in boxes {
+        for (face, corners) in CUBE_FACES.iter().enumerate() {
+            let normal = rotate_model_normal(rotation, FACE_NORMALS[face]);
+            let emphasis = if directional && face == MODEL_FRONT_FACE {
+                FRONT_FACE_EMPHASIS
+            } else {
+                1.0
+            };
+            let shade = face_shade(normal) * emphasis;
+            vertices.extend(FACE_TRIANGLES.iter().map(|&corner| {
+                let unit = corners[corner];
+                let position =
+                    [0, 1, 2].map(|axis| min[axis] + unit[axis] * (max[axis] - min[axis]));
+                GhostVertex {
+                    position: rotate_model_position(rotation, position),
+                    shade,
+                }
+            }));
+        }
+    }
+    vertices
+}
+
+/// Whether a block can go at `target` for a player with bounds `player`
+/// looking from `eye`
+pub fn placement_validity(eye: Point3<f32>, target: VoxelPos, player: &AABB) -> PlacementValidity {
+    let min = Point3::new(target.x as f32, target.y as f32, target.z as f32);
+    let center = Point3::new(min.x + 0.5, min.y + 0.5, min.z + 0.5);
+    if eye.distance(center) > MAX_REACH {
+        return PlacementValidity::OutOfReach;
+    }
+
+    let ghost = create_aabb(
+        Point3::new(
+            min.x + CONTACT_EPSILON,
+            min.y + CONTACT_EPSILON,
+            min.z + CONTACT_EPSILON,
+        ),
+        Point3::new(
+            min.x + 1.0 - CONTACT_EPSILON,
+            min.y + 1.0 - CONTACT_EPSILON,
+            min.z + 1.0 - CONTACT_EPSILON,
+        ),
+    );
+    if aabb_intersects(&ghost, player) {
+        return PlacementValidity::IntersectsPlayer;
+    }
+    PlacementValidity::Valid
+}
+
+/// Where and how `block` would be placed against the face `hit`, or None
+/// when there is nothing to preview (air held, or the target is occupied)
+pub fn compute_placement_preview(
+    world: &WorldData,
+    schemas: &MetadataSchemaRegistry,
+    camera: &CameraData,
+    hit: &RaycastHit,
+    block: BlockId,
+    player: &AABB,
+    chunk_size: u32,
+) -> Option<PlacementPreview> {
+    if block == BlockId::AIR {
+        return None;
+    }
+    let offset = hit.face.offset();
+    let target = VoxelPos::new(
+        hit.position.x + offset.x,
+        hit.position.y + offset.y,
+        hit.position.z + offset.z,
+    );
+    if world_operations::get_block(world, target, chunk_size) != BlockId::AIR {
+        return None;
+    }
+
+    let forward = calculate_forward_vector(camera.yaw_radians, camera.pitch_radians);
+    let metadata = compute_placement_metadata(schemas, block, forward, hit.face);
+    Some(PlacementPreview {
+        target,
+        block,
+        model: block_model(schemas, block),
+        metadata,
+        rotation: block_rotation(schemas, block, metadata),
+        directional: block_orientation_mode(schemas, block) != OrientationMode::Fixed,
+        validity: placement_validity(camera.position, target, player),
+    })
+}
+
+/// Ghost tint for a placement result
+pub fn placement_preview_color(validity: PlacementValidity) -> [f32; 4] {
+    match validity {
+        PlacementValidity::Valid => VALID_COLOR,
+        PlacementValidity::OutOfReach | PlacementValidity::IntersectsPlayer => INVALID_COLOR,
+    }
+}
+
+fn create_placement_preview_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<wgpu::RenderPipeline> {
+    let shader = crate::gpu::automation::create_gpu_shader(
+        device,
+        "placement_preview",
+        include_str!("../shaders/rendering/placement_preview.wgsl"),
+    )
+    .map_err(|e| format!("Failed to create placement preview shader: {}", e))?;
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Placement Preview Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    Ok(
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Placement Preview Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader.module,
+                entry_point: "vs_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<GhostVertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader.module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: color_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: Some(wgpu::Face::Back),
+                ..Default::default()
+            },
+            // Translucent: tested against the world but never written
+            depth_stencil: depth_format.map(|format| wgpu::DepthStencilState {
+                format,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::LessEqual,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        }),
+    )
+}
+
+/// Create the ghost pipeline; `sample_count` must match the main pass
+pub fn create_placement_preview(
+    device: &wgpu::Device,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<PlacementPreviewData> {
+    let uniform = PlacementPreviewUniform {
+        view_proj: Matrix4::identity().into(),
+        origin: [0.0, 0.0, 0.0, GHOST_INFLATE],
+        color: VALID_COLOR,
+    };
+    let gpu_owner = register_global_gpu_owner("placement_preview");
+    let (uniform_buffer, uniform_allocation) = create_tracked_buffer_init(
+        device,
+        &gpu_owner,
+        &wgpu::util::BufferInitDescriptor {
+            label: Some("Placement Preview Uniform Buffer"),
+            contents: bytemuck::bytes_of(&uniform),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        },
+    );
+    // Sized for the largest model; a cube until the first preview
+    let mut mesh = build_ghost_mesh(BlockModel::Cube, IDENTITY_ROTATION, false);
+    let vertex_count = mesh.len() as u32;
+    mesh.resize(GHOST_MAX_VERTEX_COUNT, GhostVertex::zeroed());
+    let (vertex_buffer, vertex_allocation) = create_tracked_buffer_init(
+        device,
+        &gpu_owner,
+        &wgpu::util::BufferInitDescriptor {
+            label: Some("Placement Preview Vertex Buffer"),
+            contents: bytemuck::cast_slice(&mesh),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        },
+    );
+
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Placement Preview Bind Group Layout"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+    });
+
+    let render_pipeline = create_placement_preview_pipeline(
+        device,
+        &bind_group_layout,
+        color_format,
+        depth_format,
+        sample_count,
+    )?;
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Placement Preview Bind Group"),
+        layout: &bind_group_layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: uniform_buffer.as_entire_binding(),
+        }],
+    });
+
+    Ok(PlacementPreviewData {
+        uniform,
+        uniform_buffer,
+        vertex_buffer,
+        render_pipeline,
+        bind_group_layout,
+        bind_group,
+        gpu_allocations: vec![uniform_allocation, vertex_allocation],
+        gpu_owner,
+        meshed: None,
+        vertex_count,
+        preview: None,
+    })
+}
+
+/// Recreate the ghost pipeline for a new target format or MSAA sample count
+pub fn rebuild_placement_preview_pipeline(
+    data: &mut PlacementPreviewData,
+    device: &wgpu::Device,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<()> {
+    data.render_pipeline = create_placement_preview_pipeline(
+        device,
+        &data.bind_group_layout,
+        color_format,
+        depth_format,
+        sample_count,
+    )?;
+    Ok(())
+}
+
+/// Update the ghost for this frame; `None` hides it
+pub fn update_placement_preview(
+    data: &mut PlacementPreviewData,
+    queue: &wgpu::Queue,
+    camera: &CameraData,
+    preview: Option<PlacementPreview>,
+) {
+    data.preview = preview;
+    let Some(preview) = preview else {
+        return;
+    };
+
+    let key = GhostMeshKey {
+        model: preview.model,
+        rotation: preview.rotation,
+        directional: preview.directional,
+    };
+    if data.meshed != Some(key) {
+        let mesh = build_ghost_mesh(preview.model, preview.rotation, preview.directional);
+        queue.write_buffer(&data.vertex_buffer, 0, bytemuck::cast_slice(&mesh));
+        data.meshed = Some(key);
+        data.vertex_count = mesh.len() as u32;
+    }
+
+    let view_proj = build_projection_matrix(camera) * build_view_matrix(camera);
+    let target = preview.target;
+    data.uniform = PlacementPreviewUniform {
+        view_proj: view_proj.into(),
+        origin: [
+            target.x as f32,
+            target.y as f32,
+            target.z as f32,
+            GHOST_INFLATE,
+        ],
+        color: placement_preview_color(preview.validity),
+    };
+    queue.write_buffer(&data.uniform_buffer, 0, bytemuck::bytes_of(&data.uniform));
+}
+
+/// Draw the ghost; call in the translucent part of the main pass, after
+/// opaque chunk rendering
+pub fn render_placement_preview<'a>(
+    data: &'a PlacementPreviewData,
+    pass: &mut wgpu::RenderPass<'a>,
+) {
+    if data.preview.is_none() {
+        return;
+    }
+    pass.set_pipeline(&data.render_pipeline);
+    pass.set_bind_group(0, &data.bind_group, &[]);
+    pass.set_vertex_buffer(0, data.vertex_buffer.slice(..));
+    pass.draw(0..data.vertex_count, 0..1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::blocks::{
+        create_metadata_schema_registry, register_block_orientation, rotate_facing, BlockFacing,
+    };
+    use crate::world::core::BlockFace;
+    use cgmath::Vector3;
+
+    #[test]
+    fn test_placement_validity() {
+        // Player 8 x 18 voxels standing on the ground at the origin
+        let player = create_aabb(Point3::new(-4.0, 0.0, -4.0), Point3::new(4.0, 18.0, 4.0));
+        let eye = Point3::new(0.0, 16.0, 0.0);
+
+        let inside = VoxelPos::new(0, 5, 0);
+        let beside = VoxelPos::new(4, 5, 0);
+        let far = VoxelPos::new(60, 5, 0);
+        assert_eq!(
+            placement_validity(eye, inside, &player),
+            PlacementValidity::IntersectsPlayer
+        );
+        // Touching the player's side is fine
+        assert_eq!(
+            placement_validity(eye, beside, &player),
+            PlacementValidity::Valid
+        );
+        assert_eq!(
+            placement_validity(eye, far, &player),
+            PlacementValidity::OutOfReach
+        );
+    }
+
+    #[test]
+    fn test_ghost_front_face_follows_orientation() {
+        let mut schemas = create_metadata_schema_registry();
+        assert!(register_block_orientation(
+            &mut schemas,
+            BlockId::FURNACE,
+            OrientationMode::Horizontal
+        )
+        .is_ok());
+        // Looking toward +X, so the furnace front faces -X
+        let metadata = compute_placement_metadata(
+            &schemas,
+            BlockId::FURNACE,
+            Vector3::new(1.0, 0.0, 0.0),
+            BlockFace::Top,
+        );
+        let rotation = block_rotation(&schemas, BlockId::FURNACE, metadata);
+        assert_eq!(
+            rotate_facing(rotation, BlockFacing::PosZ),
+            BlockFacing::NegX
+        );
+
+        let mesh = build_ghost_mesh(BlockModel::Cube, rotation, true);
+        assert_eq!(mesh.len(), GHOST_BOX_VERTEX_COUNT);
+        let brightest = mesh
+            .iter()
+            .copied()
+            .fold(mesh[0], |a, b| if b.shade > a.shade { b } else { a });
+        let front_vertices: Vec<_> = mesh.iter().filter(|v| v.shade == brightest.shade).collect();
+        assert_eq!(front_vertices.len(), 6);
+        assert!(front_vertices.iter().all(|v| v.position[0] == 0.0));
+    }
+
+    #[test]
+    fn test_ghost_mesh_takes_the_block_model() {
+        let slab = build_ghost_mesh(BlockModel::Slab, IDENTITY_ROTATION, false);
+        assert_eq!(slab.len(), GHOST_BOX_VERTEX_COUNT);
+        assert!(slab.iter().all(|v| v.position[1] <= 0.5));
+
+        // Upside-down stairs: the full-width half is on top
+        let mut schemas = create_metadata_schema_registry();
+        assert!(
+            register_block_orientation(&mut schemas, BlockId::DIRT, OrientationMode::Stairs)
+                .is_ok()
+        );
+        let metadata = compute_placement_metadata(
+            &schemas,
+            BlockId::DIRT,
+            Vector3::new(0.0, 0.0, 1.0),
+            BlockFace::Bottom,
+        );
+        let rotation = block_rotation(&schemas, BlockId::DIRT, metadata);
+        let model = block_model(&schemas, BlockId::DIRT);
+        let stairs = build_ghost_mesh(model, rotation, true);
+        assert_eq!(stairs.len(), GHOST_MAX_VERTEX_COUNT);
+        let full_width = &stairs[..GHOST_BOX_VERTEX_COUNT];
+        assert!(full_width.iter().all(|v| v.position[1] >= 0.5));
+    }
+}
@@ -0,0 +1,106 @@
+//! Prop Instance Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Streaming, generation and culling records live in
+//! prop_instance_operations.rs
+//!
+//! Grass, flowers and rocks drawn as GPU instances instead of blocks. The
+//! world is split into square columns of cells; cells within a ring around
+//! the camera are generated from the world seed and the surface under each
+//! column, so a cell looks the same every time it streams back in. Each
+//! loaded cell is one `ChunkInstance` for the GPU culling pass and one
+//! contiguous range of the instance buffer. Props are not physical unless
+//! `PropInstanceConfig::collide` is set, and then only rocks are.
+
+use crate::gpu::buffer_layouts::instance::InstanceData;
+use crate::world::core::BlockId;
+use std::collections::HashMap;
+
+/// Kind of prop; also the mesh index the shader reads from
+/// `InstanceData::custom_data[0]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PropKind {
+    Grass = 0,
+    Flower = 1,
+    Rock = 2,
+}
+
+/// Streaming cell, in cell coordinates on the XZ plane
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PropCell {
+    pub x: i32,
+    pub z: i32,
+}
+
+/// Top of a world column, as the caller's surface query reports it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropSurface {
+    /// Y of the topmost solid block
+    pub height: i32,
+    pub block: BlockId,
+}
+
+/// Streaming ring and density
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PropInstanceConfig {
+    /// World seed the placement is derived from
+    pub seed: u64,
+    /// Width of a cell (voxels)
+    pub cell_size: u32,
+    /// Cells kept loaded on each side of the camera's cell
+    pub ring_radius: u32,
+    /// Extra cells past the ring before a cell unloads
+    pub unload_margin: u32,
+    pub cells_per_update: usize,
+    pub grass_chance: f32,
+    pub flower_chance: f32,
+    pub rock_chance: f32,
+    /// Give rocks colliders (off by default)
+    pub collide: bool,
+}
+
+/// Instances of one loaded cell
+#[derive(Debug, Clone, Default)]
+pub struct PropCellInstances {
+    pub instances: Vec<InstanceData>,
+    pub kinds: Vec<PropKind>,
+    /// Lowest and highest prop base (Y); the culling cube spans them
+    pub min_y: f32,
+    pub max_y: f32,
+}
+
+/// Cells loaded and unloaded by one streaming update
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropStreamUpdate {
+    pub loaded: Vec<PropCell>,
+    pub unloaded: Vec<PropCell>,
+}
+
+/// Instance range of a cell after packing; index `i` belongs to culling
+/// record `i`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PropDrawRange {
+    pub first_instance: u32,
+    pub instance_count: u32,
+}
+
+/// Buffers ready to hand to `InstanceStreamer`
+#[derive(Debug, Clone, Default)]
+pub struct PackedPropInstances {
+    pub instances: Vec<InstanceData>,
+    pub cells: Vec<crate::renderer::gpu_culling::ChunkInstance>,
+    pub ranges: Vec<PropDrawRange>,
+    /// One instanced draw per cell for `ChunkCulling`, in `ranges` order
+    pub draws: Vec<crate::gpu::buffer_layouts::DrawMetadata>,
+}
+
+/// Prop streaming state around the camera
+#[derive(Debug, Clone)]
+pub struct PropInstanceData {
+    pub config: PropInstanceConfig,
+    pub cells: HashMap<PropCell, PropCellInstances>,
+    /// Cell the ring was last centered on
+    pub center: Option<PropCell>,
+    /// Cells changed since the last `pack_prop_instances`
+    pub dirty: bool,
+}
@@ -0,0 +1,341 @@
+//! Prop Instance Operations - Pure functions over PropInstanceData
+//!
+//! Call `update_prop_streaming` once per frame with the camera position and
+//! a surface query; when it reports changes, `pack_prop_instances` builds
+//! the instance, culling and draw-range buffers for `InstanceStreamer`;
+//! its cell draws go through `ChunkCulling` like chunk draws.
+//! Cells load nearest first, a few per update, and unload only once they
+//! are past the ring plus a margin. Call `invalidate_prop_cell` when a
+//! block edit changes the surface so the cell regenerates.
+
+use super::gpu_culling::ChunkInstance;
+use super::prop_instance_data::{
+    PackedPropInstances, PropCell, PropCellInstances, PropDrawRange, PropInstanceConfig,
+    PropInstanceData, PropKind, PropStreamUpdate, PropSurface,
+};
+use crate::constants::prop_instances::{
+    FLOWER_PROP_CHANCE, GRASS_PROP_CHANCE, PROPS_COLLIDE, PROP_CELLS_PER_UPDATE,
+    PROP_CELL_CULL_FLAG, PROP_CELL_SIZE, PROP_RING_RADIUS, PROP_UNLOAD_MARGIN, ROCK_PROP_CHANCE,
+};
+use crate::gpu::buffer_layouts::instance::InstanceData;
+use crate::gpu::buffer_layouts::DrawMetadata;
+use crate::physics::aabb::{aabb_from_center_half_extents, AABB};
+use crate::world::core::BlockId;
+use crate::world_random_operations::{position_fork_key, splitmix64};
+use cgmath::{Matrix4, Point3, Rad, Vector3};
+use std::collections::HashMap;
+
+/// Default ring and density for a world seed
+pub fn default_prop_instance_config(seed: u64) -> PropInstanceConfig {
+    PropInstanceConfig {
+        seed,
+        cell_size: PROP_CELL_SIZE,
+        ring_radius: PROP_RING_RADIUS,
+        unload_margin: PROP_UNLOAD_MARGIN,
+        cells_per_update: PROP_CELLS_PER_UPDATE,
+        grass_chance: GRASS_PROP_CHANCE,
+        flower_chance: FLOWER_PROP_CHANCE,
+        rock_chance: ROCK_PROP_CHANCE,
+        collide: PROPS_COLLIDE,
+    }
+}
+
+/// No cells loaded
+pub fn create_prop_instances(config: PropInstanceConfig) -> PropInstanceData {
+    PropInstanceData {
+        config,
+        cells: HashMap::new(),
+        center: None,
+        dirty: false,
+    }
+}
+
+/// Cell containing a world position
+pub fn prop_cell_at(config: &PropInstanceConfig, position: [f32; 3]) -> PropCell {
+    let size = config.cell_size.max(1) as f32;
+    PropCell {
+        x: (position[0] / size).floor() as i32,
+        z: (position[2] / size).floor() as i32,
+    }
+}
+
+/// Load the nearest missing cells of the ring around the camera and drop
+/// cells past it. `surface` answers the top of a world column, or `None`
+/// while its chunk is not loaded; such columns get no props until the cell
+/// is invalidated.
+pub fn update_prop_streaming(
+    data: &mut PropInstanceData,
+    camera_position: [f32; 3],
+    mut surface: impl FnMut(i32, i32) -> Option<PropSurface>,
+) -> PropStreamUpdate {
+    let center = prop_cell_at(&data.config, camera_position);
+    data.center = Some(center);
+    let ring = data.config.ring_radius as i32;
+    let keep = ring + data.config.unload_margin as i32;
+    let ring_distance = |cell: PropCell| (cell.x - center.x).abs().max((cell.z - center.z).abs());
+
+    let mut update = PropStreamUpdate::default();
+    data.cells.retain(|&cell, _| {
+        let kept = ring_distance(cell) <= keep;
+        if !kept {
+            update.unloaded.push(cell);
+        }
+        kept
+    });
+
+    let mut missing: Vec<PropCell> = (-ring..=ring)
+        .flat_map(|dz| {
+            (-ring..=ring).map(move |dx| PropCell {
+                x: center.x + dx,
+                z: center.z + dz,
+            })
+        })
+        .filter(|cell| !data.cells.contains_key(cell))
+        .collect();
+    missing.sort_by_key(|cell| {
+        let (dx, dz) = (cell.x - center.x, cell.z - center.z);
+        (dx * dx + dz * dz, *cell)
+    });
+    for cell in missing.into_iter().take(data.config.cells_per_update) {
+        let instances = generate_prop_cell(&data.config, cell, &mut surface);
+        data.cells.insert(cell, instances);
+        update.loaded.push(cell);
+    }
+
+    update.unloaded.sort();
+    if !update.loaded.is_empty() || !update.unloaded.is_empty() {
+        data.dirty = true;
+    }
+    update
+}
+
+/// Drop a cell so the next update regenerates it (after block edits or
+/// once its chunks load)
+pub fn invalidate_prop_cell(data: &mut PropInstanceData, cell: PropCell) {
+    if data.cells.remove(&cell).is_some() {
+        data.dirty = true;
+    }
+}
+
+/// Props of one cell; the same seed and surface always give the same
+/// instances
+pub fn generate_prop_cell(
+    config: &PropInstanceConfig,
+    cell: PropCell,
+    mut surface: impl FnMut(i32, i32) -> Option<PropSurface>,
+) -> PropCellInstances {
+    let size = config.cell_size as i32;
+    let mut props = PropCellInstances {
+        min_y: f32::MAX,
+        max_y: f32::MIN,
+        ..Default::default()
+    };
+    for z in cell.z * size..(cell.z + 1) * size {
+        for x in cell.x * size..(cell.x + 1) * size {
+            let Some(top) = surface(x, z) else {
+                continue;
+            };
+            let mut state = config.seed ^ position_fork_key(x, top.height, z);
+            let roll = unit_roll(&mut state);
+            let Some(kind) = prop_kind(config, top.block, roll) else {
+                continue;
+            };
+            let jitter = [unit_roll(&mut state) - 0.5, unit_roll(&mut state) - 0.5];
+            let angle = unit_roll(&mut state) * std::f32::consts::TAU;
+            let variation = unit_roll(&mut state);
+            let base = [
+                x as f32 + 0.5 + jitter[0] * 0.6,
+                (top.height + 1) as f32,
+                z as f32 + 0.5 + jitter[1] * 0.6,
+            ];
+            let (scale, color) = match kind {
+                PropKind::Grass => (0.6 + variation * 0.4, [0.45, 0.72, 0.3, 1.0]),
+                PropKind::Flower if variation < 0.5 => (0.7, [0.9, 0.2, 0.2, 1.0]),
+                PropKind::Flower => (0.7, [0.95, 0.85, 0.2, 1.0]),
+                PropKind::Rock => (0.3 + variation * 0.5, [0.5, 0.5, 0.52, 1.0]),
+            };
+            let model = Matrix4::from_translation(Vector3::from(base))
+                * Matrix4::from_angle_y(Rad(angle))
+                * Matrix4::from_scale(scale);
+            let mut instance = InstanceData::from_matrix(model, color);
+            instance.custom_data[0] = kind as u32 as f32;
+            props.instances.push(instance);
+            props.kinds.push(kind);
+            props.min_y = props.min_y.min(base[1]);
+            props.max_y = props.max_y.max(base[1]);
+        }
+    }
+    if props.instances.is_empty() {
+        props.min_y = 0.0;
+        props.max_y = 0.0;
+    }
+    props
+}
+
+/// Instance buffer, one culling cube and instanced draw per non-empty cell
+/// and the matching draw ranges, in a stable cell order; clears the dirty
+/// flag
+pub fn pack_prop_instances(data: &mut PropInstanceData) -> PackedPropInstances {
+    let mut cells: Vec<_> = data
+        .cells
+        .iter()
+        .filter(|(_, props)| !props.instances.is_empty())
+        .collect();
+    cells.sort_by_key(|(cell, _)| **cell);
+
+    let cell_size = data.config.cell_size as f32;
+    let mut packed = PackedPropInstances::default();
+    for (cell, props) in cells {
+        // Cube around the cell, tall enough for its props (plus one block
+        // for their height)
+        let side = cell_size.max(props.max_y - props.min_y + 1.0);
+        let center = [
+            (cell.x as f32 + 0.5) * cell_size,
+            (props.min_y + props.max_y + 1.0) * 0.5,
+            (cell.z as f32 + 0.5) * cell_size,
+        ];
+        packed.cells.push(ChunkInstance::new(
+            center.map(|c| c - side * 0.5),
+            side,
+            0,
+            PROP_CELL_CULL_FLAG,
+        ));
+        let range = PropDrawRange {
+            first_instance: packed.instances.len() as u32,
+            instance_count: props.instances.len() as u32,
+        };
+        packed.draws.push(DrawMetadata {
+            instance_offset: range.first_instance,
+            flags: DrawMetadata::FLAG_VISIBLE | DrawMetadata::FLAG_INSTANCED,
+            ..DrawMetadata::new(center, side * 0.5 * 3.0f32.sqrt(), 0, range.instance_count)
+        });
+        packed.ranges.push(range);
+        packed.instances.extend_from_slice(&props.instances);
+    }
+    data.dirty = false;
+    packed
+}
+
+/// Collision boxes of loaded props; empty unless `collide` is set, and
+/// then only rocks
+pub fn prop_colliders(data: &PropInstanceData) -> Vec<AABB> {
+    if !data.config.collide {
+        return Vec::new();
+    }
+    data.cells
+        .values()
+        .flat_map(|props| props.instances.iter().zip(&props.kinds))
+        .filter(|(_, kind)| **kind == PropKind::Rock)
+        .map(|(instance, _)| {
+            let position = instance.position();
+            // Uniform scale: the length of the first basis column
+            let [x, y, z, _] = instance.model_matrix[0];
+            let half = (x * x + y * y + z * z).sqrt() * 0.5;
+            aabb_from_center_half_extents(
+                Point3::new(position.x, position.y + half, position.z),
+                Vector3::new(half, half, half),
+            )
+        })
+        .collect()
+}
+
+/// Prop a column grows for a roll, if any
+fn prop_kind(config: &PropInstanceConfig, block: BlockId, roll: f32) -> Option<PropKind> {
+    match block {
+        BlockId::GRASS if roll < config.grass_chance => Some(PropKind::Grass),
+        BlockId::GRASS if roll < config.grass_chance + config.flower_chance => {
+            Some(PropKind::Flower)
+        }
+        BlockId::GRASS
+            if roll < config.grass_chance + config.flower_chance + config.rock_chance =>
+        {
+            Some(PropKind::Rock)
+        }
+        BlockId::DIRT | BlockId::STONE if roll < config.rock_chance => Some(PropKind::Rock),
+        _ => None,
+    }
+}
+
+/// Next uniform 0.0..1.0 value of a column's random stream
+fn unit_roll(state: &mut u64) -> f32 {
+    (splitmix64(state) >> 40) as f32 / (1u64 << 24) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat_grass(_x: i32, _z: i32) -> Option<PropSurface> {
+        Some(PropSurface {
+            height: 10,
+            block: BlockId::GRASS,
+        })
+    }
+
+    #[test]
+    fn ring_streams_nearest_cells_and_unloads_past_the_margin() {
+        let config = PropInstanceConfig {
+            cell_size: 4,
+            ring_radius: 1,
+            unload_margin: 1,
+            cells_per_update: 5,
+            ..default_prop_instance_config(3)
+        };
+        let mut data = create_prop_instances(config);
+        let update = update_prop_streaming(&mut data, [1.0, 0.0, 1.0], flat_grass);
+        assert_eq!(update.loaded[0], PropCell { x: 0, z: 0 });
+        assert_eq!(update.loaded.len(), 5);
+        let update = update_prop_streaming(&mut data, [1.0, 0.0, 1.0], flat_grass);
+        assert_eq!(update.loaded.len(), 4);
+        assert_eq!(data.cells.len(), 9);
+
+        // Two cells over: the far column is past ring + margin
+        let update = update_prop_streaming(&mut data, [9.0, 0.0, 1.0], flat_grass);
+        assert_eq!(update.unloaded.len(), 3);
+        assert!(update.unloaded.iter().all(|cell| cell.x == -1));
+
+        let packed = pack_prop_instances(&mut data);
+        assert!(!data.dirty);
+        assert_eq!(packed.cells.len(), packed.ranges.len());
+        assert_eq!(packed.draws.len(), packed.ranges.len());
+        assert_eq!(
+            packed.draws[1].instance_offset,
+            packed.ranges[1].first_instance
+        );
+        assert_eq!(packed.draws[1].mesh_id, packed.ranges[1].instance_count);
+        let total: u32 = packed.ranges.iter().map(|r| r.instance_count).sum();
+        assert_eq!(total as usize, packed.instances.len());
+        assert!(prop_colliders(&data).is_empty());
+    }
+
+    #[test]
+    fn cells_regenerate_identically() {
+        let config = PropInstanceConfig {
+            grass_chance: 0.3,
+            flower_chance: 0.1,
+            rock_chance: 0.1,
+            collide: true,
+            ..default_prop_instance_config(11)
+        };
+        let cell = PropCell { x: -2, z: 5 };
+        let first = generate_prop_cell(&config, cell, flat_grass);
+        let second = generate_prop_cell(&config, cell, flat_grass);
+        assert!(!first.instances.is_empty());
+        assert_eq!(first.kinds, second.kinds);
+        assert_eq!(
+            first.instances[0].model_matrix,
+            second.instances[0].model_matrix
+        );
+        assert_eq!(first.min_y, 11.0);
+        assert!(first.kinds.contains(&PropKind::Rock));
+
+        let mut data = create_prop_instances(config);
+        data.cells.insert(cell, first);
+        let rocks = data.cells[&cell]
+            .kinds
+            .iter()
+            .filter(|kind| **kind == PropKind::Rock)
+            .count();
+        assert_eq!(prop_colliders(&data).len(), rocks);
+    }
+}
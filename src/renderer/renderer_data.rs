@@ -1,6 +1,88 @@
 //! Renderer Data - Stub
+use super::anti_aliasing_data::AntiAliasingData;
+use super::cloud_data::CloudData;
+use super::device_recovery_data::DeviceRecoveryData;
+use super::edge_highlight_data::EdgeHighlightData;
+use super::entity_lod_data::EntityLodData;
+use super::far_terrain_data::FarTerrainData;
+use super::light_preview_data::LightPreviewData;
+use super::pipeline_cache_data::PipelineCacheData;
+use super::parallel_encoding_data::ParallelEncodingData;
+use super::placement_preview_data::PlacementPreviewData;
+use super::secondary_view_data::SecondaryViewsData;
+use super::sky_data::SkyData;
+use super::texture_streaming_data::{TextureStreamId, TextureStreamingData};
+use crate::profiling::GpuPassTimerData;
+use std::sync::Arc;
+
 pub struct RendererData;
-pub struct Renderer;
+
+/// Where an embedded renderer draws each frame
+pub enum RenderTarget {
+    /// Host-owned window; the engine acquires and presents surface frames
+    Surface {
+        window: Arc<winit::window::Window>,
+        surface: wgpu::Surface<'static>,
+        config: wgpu::SurfaceConfiguration,
+    },
+    /// Host-owned texture; the host composites it (e.g. into an egui panel)
+    Texture {
+        texture: wgpu::Texture,
+        view: wgpu::TextureView,
+    },
+}
+
+/// Renderer attached to a host-provided window surface or texture
+pub struct Renderer {
+    pub device: Arc<wgpu::Device>,
+    pub queue: Arc<wgpu::Queue>,
+    /// Adapter the device came from, which keys the pipeline cache (None
+    /// when a host attached a texture without it)
+    pub adapter_info: Option<wgpu::AdapterInfo>,
+    pub target: RenderTarget,
+    pub clear_color: wgpu::Color,
+    /// Procedural sky drawn before the world (None = clear to `clear_color`)
+    pub sky: Option<SkyData>,
+    /// Cloud layer drawn over the world (None = no clouds)
+    pub clouds: Option<CloudData>,
+    /// Heightfield ring beyond the voxel render distance (None = no ring)
+    pub far_terrain: Option<FarTerrainData>,
+    /// Ghost of the block about to be placed (None = no preview)
+    pub placement_preview: Option<PlacementPreviewData>,
+    /// Light a held emissive block would cast (None = no preview)
+    pub light_preview: Option<LightPreviewData>,
+    /// Fog color for the world and post-process passes, taken from the sky
+    pub fog_color: [f32; 3],
+    /// MSAA, FXAA or TAA for the main pass
+    pub anti_aliasing: AntiAliasingData,
+    /// Accessibility outlines and mesh topology view drawn after anti-aliasing
+    pub edge_highlight: EdgeHighlightData,
+    /// Interpolated entity positions for this frame (see `update_renderer_entities`)
+    pub entity_positions: Vec<[f32; 3]>,
+    /// Interpolated entity rotations for this frame, quaternion x, y, z, w
+    pub entity_rotations: Vec<[f32; 4]>,
+    /// Distance bands, update throttling and draws of the entities
+    /// (see `update_renderer_entity_lod`)
+    pub entity_lod: EntityLodData,
+    /// Times the frame pass for trace captures (None when the device has
+    /// no `Features::TIMESTAMP_QUERY`)
+    pub gpu_pass_timer: Option<GpuPassTimerData>,
+    /// Device loss detection, buffer shadows and recovery counters
+    pub recovery: DeviceRecoveryData,
+    /// Portal, camera and map views rendered to textures before the main pass
+    pub secondary_views: SecondaryViewsData,
+    /// Worker recording of the passes independent of the main pass
+    pub parallel_encoding: ParallelEncodingData,
+    /// Pipeline cache of the adapter and startup pipeline timings
+    /// (None until `enable_renderer_pipeline_cache`)
+    pub pipeline_cache: Option<PipelineCacheData>,
+    /// Textures whose finer mips stream in near the camera and out under
+    /// memory pressure (see `update_renderer_texture_streaming`)
+    pub texture_streaming: TextureStreamingData,
+    /// Block atlas in `texture_streaming` (None until `set_renderer_block_atlas`)
+    pub block_atlas: Option<TextureStreamId>,
+    pub frames_rendered: u64,
+}
 
 pub struct GpuInitProgressData;
 pub struct ProgressStepData;
@@ -1,13 +1,870 @@
 //! Renderer Operations - Stub
 
-pub fn render_frame() {}
+use super::anti_aliasing_data::AntiAliasingMode;
+use super::anti_aliasing_operations::{
+    anti_aliasing_jitter, anti_aliasing_sample_count, create_anti_aliasing,
+    encode_anti_aliasing_pass, finish_anti_aliasing_frame, max_msaa_samples,
+    prepare_anti_aliasing, scene_color_attachment, set_anti_aliasing_max_samples,
+    set_anti_aliasing_mode, set_anti_aliasing_render_scale,
+};
+use super::cloud_data::CloudConfig;
+use super::device_recovery_operations::{
+    create_device_recovery, device_lost, note_surface_lost, note_surface_presented,
+};
+use super::cloud_operations::{
+    create_clouds, rebuild_cloud_pipeline, render_clouds, set_cloud_shadows_enabled,
+    set_clouds_enabled, update_clouds,
+};
+use super::entity_lod_operations::{
+    create_entity_lod, default_entity_lod_config, update_entity_lod,
+};
+use super::error::RendererResult;
+use super::far_terrain_operations::{
+    create_far_terrain, default_far_terrain_config, rebuild_far_terrain_pipeline,
+    render_far_terrain, update_far_terrain,
+};
+use super::light_preview_operations::{
+    create_light_preview, rebuild_light_preview_pipeline, render_light_preview,
+    update_light_preview,
+};
+use super::pipeline_cache_data::{PipelineCacheData, PipelineCacheResult};
+use super::pipeline_cache_operations::{
+    load_pipeline_cache, pipeline_cache_key, save_pipeline_cache, timed_pipeline_creation,
+};
+use super::placement_preview_data::PlacementPreview;
+use super::placement_preview_operations::{
+    create_placement_preview, rebuild_placement_preview_pipeline, render_placement_preview,
+    update_placement_preview,
+};
+use super::renderer_data::{RenderTarget, Renderer};
+use super::edge_highlight_data::EdgeHighlightConfig;
+use super::edge_highlight_operations::{
+    create_edge_highlight, encode_edge_highlight_pass, finish_edge_highlight_frame,
+    prepare_edge_highlight, set_edge_highlight_config,
+};
+use super::parallel_encoding_data::FrameEncodingTimes;
+use super::parallel_encoding_operations::{
+    create_parallel_encoding, record_frame_commands, record_parallel_encoding_frame,
+    set_parallel_encoding_enabled,
+};
+use super::secondary_view_operations::{
+    create_secondary_views, encode_secondary_view_pass, prepare_secondary_views,
+    submit_secondary_views,
+};
+use super::sky_data::SkyConfig;
+use super::sky_operations::{
+    calculate_sky_colors, create_sky, rebuild_sky_pipeline, render_sky, sky_fog_color, update_sky,
+};
+use super::texture_streaming_data::MemoryBudgetView;
+use super::texture_streaming_operations::{
+    apply_texture_streaming, build_block_atlas, create_texture_streaming,
+    default_streamed_residency, default_texture_streaming_config, note_texture_use,
+    register_streamed_texture, remove_streamed_texture, update_texture_streaming,
+};
+use crate::camera::{build_projection_matrix, build_view_matrix, CameraData};
+use crate::constants::profiling::MAX_TIMED_GPU_PASSES;
+use crate::engine_buffers::TransformBuffers;
+use crate::gpu::TerrainParamsSOA;
+use crate::profiling::{
+    begin_gpu_pass_timing, collect_global_gpu_pass_timings, create_gpu_pass_timer,
+    render_pass_timestamp_writes, request_gpu_pass_readback, resolve_gpu_pass_timer,
+    trace_now_ns, trace_recording, GpuPassTimerData,
+};
+use crate::world::core::{BlockRegistry, VoxelPos};
+use crate::world::generation::FarSurface;
+use crate::world::lighting::{noon_time, TimeOfDayData};
+use crate::world::storage::WorldBuffer;
+use crate::world::WeatherData;
+use std::path::Path;
+use std::sync::Arc;
 
-// Stub for compatibility
-pub fn run_with_buffers<G>(
-    _event_loop: winit::event_loop::EventLoop<()>,
-    _config: crate::EngineConfig,
-    _game: G,
-    _buffers: crate::SharedEngineBuffers,
-) -> anyhow::Result<()> {
+/// Sky color used to clear embedded render targets
+const DEFAULT_CLEAR_COLOR: wgpu::Color = wgpu::Color {
+    r: 0.53,
+    g: 0.81,
+    b: 0.92,
+    a: 1.0,
+};
+
+/// Create a device for a window the engine opened itself and attach a
+/// renderer to it (the windowed `Engine::run` path)
+pub fn create_window_renderer(window: Arc<winit::window::Window>) -> RendererResult<Renderer> {
+    let instance = wgpu::Instance::default();
+    let surface = instance
+        .create_surface(window.clone())
+        .map_err(|e| format!("Failed to create window surface: {}", e))?;
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        compatible_surface: Some(&surface),
+        force_fallback_adapter: false,
+    }))
+    .ok_or_else(|| "No GPU adapter can present to the window".to_string())?;
+    let (device, queue) = pollster::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: Some("Engine Device"),
+            // The GPU world buffer exposes voxels to vertex shaders read-write
+            required_features: adapter.features() & wgpu::Features::VERTEX_WRITABLE_STORAGE,
+            required_limits: adapter.limits(),
+        },
+        None,
+    ))
+    .map_err(|e| format!("Failed to create GPU device: {}", e))?;
+    attach_renderer_to_window(
+        window,
+        surface,
+        &adapter,
+        Arc::new(device),
+        Arc::new(queue),
+    )
+}
+
+/// Attach a renderer to a window and surface owned by the host application
+pub fn attach_renderer_to_window(
+    window: Arc<winit::window::Window>,
+    surface: wgpu::Surface<'static>,
+    adapter: &wgpu::Adapter,
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+) -> RendererResult<Renderer> {
+    let size = window.inner_size();
+    let config = surface
+        .get_default_config(adapter, size.width.max(1), size.height.max(1))
+        .ok_or_else(|| "Surface is not supported by the provided adapter".to_string())?;
+    surface.configure(&device, &config);
+    let max_samples = max_msaa_samples(&adapter.get_texture_format_features(config.format));
+
+    log::info!(
+        "[Renderer] Attached to host window ({}x{}, {:?})",
+        config.width,
+        config.height,
+        config.format
+    );
+
+    let gpu_pass_timer = create_gpu_pass_timer(&device, &queue, MAX_TIMED_GPU_PASSES, 0);
+    let recovery = create_device_recovery(&device);
+    Ok(Renderer {
+        device,
+        queue,
+        adapter_info: Some(adapter.get_info()),
+        target: RenderTarget::Surface {
+            window,
+            surface,
+            config,
+        },
+        clear_color: DEFAULT_CLEAR_COLOR,
+        sky: None,
+        clouds: None,
+        far_terrain: None,
+        light_preview: None,
+        placement_preview: None,
+        fog_color: [
+            DEFAULT_CLEAR_COLOR.r as f32,
+            DEFAULT_CLEAR_COLOR.g as f32,
+            DEFAULT_CLEAR_COLOR.b as f32,
+        ],
+        anti_aliasing: create_anti_aliasing(max_samples),
+        edge_highlight: create_edge_highlight(),
+        entity_positions: Vec::new(),
+        entity_rotations: Vec::new(),
+        entity_lod: create_entity_lod(default_entity_lod_config()),
+        gpu_pass_timer,
+        recovery,
+        secondary_views: create_secondary_views(),
+        parallel_encoding: create_parallel_encoding(),
+        pipeline_cache: None,
+        texture_streaming: create_texture_streaming(default_texture_streaming_config()),
+        block_atlas: None,
+        frames_rendered: 0,
+    })
+}
+
+/// MSAA support for a host texture format; only the guaranteed features
+/// are known without the adapter
+pub(super) fn texture_max_msaa_samples(device: &wgpu::Device, format: wgpu::TextureFormat) -> u32 {
+    max_msaa_samples(&format.guaranteed_format_features(device.features()))
+}
+
+/// Attach a renderer to a texture owned by the host application.
+/// The texture must include `RENDER_ATTACHMENT` usage. The adapter is not
+/// known here; set `adapter_info` to let the engine keep a pipeline cache.
+pub fn attach_renderer_to_texture(
+    texture: wgpu::Texture,
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+) -> RendererResult<Renderer> {
+    if !texture
+        .usage()
+        .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+    {
+        return Err("Embedded render texture must have RENDER_ATTACHMENT usage".to_string());
+    }
+
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let max_samples = texture_max_msaa_samples(&device, texture.format());
+    let gpu_pass_timer = create_gpu_pass_timer(&device, &queue, MAX_TIMED_GPU_PASSES, 0);
+    let recovery = create_device_recovery(&device);
+    Ok(Renderer {
+        device,
+        queue,
+        adapter_info: None,
+        target: RenderTarget::Texture { texture, view },
+        clear_color: DEFAULT_CLEAR_COLOR,
+        sky: None,
+        clouds: None,
+        far_terrain: None,
+        light_preview: None,
+        placement_preview: None,
+        fog_color: [
+            DEFAULT_CLEAR_COLOR.r as f32,
+            DEFAULT_CLEAR_COLOR.g as f32,
+            DEFAULT_CLEAR_COLOR.b as f32,
+        ],
+        anti_aliasing: create_anti_aliasing(max_samples),
+        edge_highlight: create_edge_highlight(),
+        entity_positions: Vec::new(),
+        entity_rotations: Vec::new(),
+        entity_lod: create_entity_lod(default_entity_lod_config()),
+        gpu_pass_timer,
+        recovery,
+        secondary_views: create_secondary_views(),
+        parallel_encoding: create_parallel_encoding(),
+        pipeline_cache: None,
+        texture_streaming: create_texture_streaming(default_texture_streaming_config()),
+        block_atlas: None,
+        frames_rendered: 0,
+    })
+}
+
+/// Resize the render target. Texture targets are owned by the host, which
+/// replaces them with `set_render_texture` instead.
+pub fn resize_renderer(renderer: &mut Renderer, width: u32, height: u32) {
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    if let RenderTarget::Surface {
+        surface, config, ..
+    } = &mut renderer.target
+    {
+        config.width = width;
+        config.height = height;
+        surface.configure(&renderer.device, config);
+    }
+}
+
+/// Switch the surface between vsync and immediate presentation.
+/// Texture targets are presented by the host and are left unchanged.
+pub fn set_renderer_vsync(renderer: &mut Renderer, vsync: bool) {
+    if let RenderTarget::Surface {
+        surface, config, ..
+    } = &mut renderer.target
+    {
+        config.present_mode = if vsync {
+            wgpu::PresentMode::AutoVsync
+        } else {
+            wgpu::PresentMode::AutoNoVsync
+        };
+        surface.configure(&renderer.device, config);
+    }
+}
+
+/// Display refresh rate of the monitor showing the window, in Hz
+pub fn renderer_refresh_rate(renderer: &Renderer) -> Option<u32> {
+    match &renderer.target {
+        RenderTarget::Surface { window, .. } => window
+            .current_monitor()
+            .and_then(|monitor| monitor.refresh_rate_millihertz())
+            .map(|millihertz| (millihertz + 500) / 1000),
+        RenderTarget::Texture { .. } => None,
+    }
+}
+
+/// Swap the host texture the renderer draws into
+pub fn set_render_texture(renderer: &mut Renderer, texture: wgpu::Texture) -> RendererResult<()> {
+    let old_format = render_target_format(renderer);
+    let old_samples = anti_aliasing_sample_count(renderer.anti_aliasing.active);
+    let format = texture.format();
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    renderer.target = RenderTarget::Texture { texture, view };
+
+    if format != old_format {
+        let max_samples = texture_max_msaa_samples(&renderer.device, format);
+        set_anti_aliasing_max_samples(&mut renderer.anti_aliasing, max_samples);
+    }
+    if format != old_format
+        || anti_aliasing_sample_count(renderer.anti_aliasing.active) != old_samples
+    {
+        rebuild_main_pass_pipelines(renderer)?;
+    }
     Ok(())
 }
+
+/// Color format of the render target
+pub fn render_target_format(renderer: &Renderer) -> wgpu::TextureFormat {
+    match &renderer.target {
+        RenderTarget::Surface { config, .. } => config.format,
+        RenderTarget::Texture { texture, .. } => texture.format(),
+    }
+}
+
+/// Load the adapter's pipeline cache from `cache_dir`; call before enabling
+/// the sky, clouds and other passes so their pipeline builds are timed
+pub fn enable_renderer_pipeline_cache(
+    renderer: &mut Renderer,
+    adapter_info: &wgpu::AdapterInfo,
+    cache_dir: &Path,
+) {
+    renderer.pipeline_cache = Some(load_pipeline_cache(
+        pipeline_cache_key(adapter_info),
+        cache_dir,
+    ));
+}
+
+/// Save the pipeline cache once startup pipelines are built
+pub fn save_renderer_pipeline_cache(
+    renderer: &Renderer,
+    cache_dir: &Path,
+) -> PipelineCacheResult<()> {
+    match &renderer.pipeline_cache {
+        Some(cache) => save_pipeline_cache(cache, cache_dir),
+        None => Ok(()),
+    }
+}
+
+/// Build pipelines, timing them when the pipeline cache is enabled
+fn create_pipelines<R>(cache: &mut Option<PipelineCacheData>, create: impl FnOnce() -> R) -> R {
+    match cache {
+        Some(cache) => timed_pipeline_creation(cache, create),
+        None => create(),
+    }
+}
+
+/// Draw a procedural sky before the world instead of a flat clear color
+pub fn enable_renderer_sky(renderer: &mut Renderer, config: SkyConfig) -> RendererResult<()> {
+    let format = render_target_format(renderer);
+    let samples = anti_aliasing_sample_count(renderer.anti_aliasing.active);
+    let device = &renderer.device;
+    let sky = create_pipelines(&mut renderer.pipeline_cache, || {
+        create_sky(device, config, format, None, samples)
+    })?;
+    renderer.sky = Some(sky);
+    Ok(())
+}
+
+/// Update the sky for this frame. The fog color and the clear color behind
+/// the sky follow the sky's horizon.
+pub fn update_renderer_sky(
+    renderer: &mut Renderer,
+    time: &TimeOfDayData,
+    weather_data: &WeatherData,
+    camera: &CameraData,
+) {
+    let Some(sky) = renderer.sky.as_mut() else {
+        return;
+    };
+    update_sky(sky, &renderer.queue, time, weather_data, camera);
+    let fog = sky_fog_color(sky);
+    renderer.fog_color = fog;
+    renderer.clear_color = wgpu::Color {
+        r: fog[0] as f64,
+        g: fog[1] as f64,
+        b: fog[2] as f64,
+        a: 1.0,
+    };
+}
+
+/// Draw a cloud layer over the world
+pub fn enable_renderer_clouds(renderer: &mut Renderer, config: CloudConfig) -> RendererResult<()> {
+    let format = render_target_format(renderer);
+    let samples = anti_aliasing_sample_count(renderer.anti_aliasing.active);
+    let device = &renderer.device;
+    let clouds = create_pipelines(&mut renderer.pipeline_cache, || {
+        create_clouds(device, config, format, None, samples)
+    })?;
+    renderer.clouds = Some(clouds);
+    Ok(())
+}
+
+/// Toggle clouds and cloud shadows (e.g. off on low-end hardware)
+pub fn set_renderer_clouds_enabled(renderer: &mut Renderer, enabled: bool) {
+    if let Some(clouds) = renderer.clouds.as_mut() {
+        set_clouds_enabled(clouds, enabled);
+    }
+}
+
+/// Toggle the cloud shadows alone (the `shadows` feature flag)
+pub fn set_renderer_cloud_shadows(renderer: &mut Renderer, enabled: bool) {
+    if let Some(clouds) = renderer.clouds.as_mut() {
+        set_cloud_shadows_enabled(clouds, &renderer.queue, enabled);
+    }
+}
+
+/// Drift the clouds with the wind and light them from the current sky
+/// (noon lighting when the sky is disabled)
+pub fn update_renderer_clouds(
+    renderer: &mut Renderer,
+    weather_data: &WeatherData,
+    camera: &CameraData,
+    delta_seconds: f32,
+) {
+    let Some(clouds) = renderer.clouds.as_mut() else {
+        return;
+    };
+    let sky_colors = match &renderer.sky {
+        Some(sky) => sky.colors,
+        None => calculate_sky_colors(&noon_time(), weather_data),
+    };
+    update_clouds(
+        clouds,
+        &renderer.queue,
+        weather_data,
+        &sky_colors,
+        camera,
+        delta_seconds,
+    );
+}
+
+/// Draw a heightfield ring past the voxel world, from `inner_radius` (the
+/// voxel render distance, in voxels) out to the far terrain's outer radius
+pub fn enable_renderer_far_terrain(renderer: &mut Renderer, inner_radius: f32) -> RendererResult<()> {
+    let format = render_target_format(renderer);
+    let samples = anti_aliasing_sample_count(renderer.anti_aliasing.active);
+    let device = &renderer.device;
+    let far_terrain = create_pipelines(&mut renderer.pipeline_cache, || {
+        create_far_terrain(
+            device,
+            default_far_terrain_config(inner_radius),
+            format,
+            None,
+            samples,
+        )
+    })?;
+    renderer.far_terrain = Some(far_terrain);
+    Ok(())
+}
+
+/// Center the far terrain ring on the camera; its heightmap is regenerated
+/// from `terrain` and `surface` once the camera drifted far enough or they
+/// changed
+pub fn update_renderer_far_terrain(
+    renderer: &mut Renderer,
+    camera: &CameraData,
+    terrain: &TerrainParamsSOA,
+    surface: FarSurface,
+) {
+    let Some(far_terrain) = renderer.far_terrain.as_mut() else {
+        return;
+    };
+    let view_proj = build_projection_matrix(camera) * build_view_matrix(camera);
+    let mut encoder = renderer
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Far Terrain Encoder"),
+        });
+    if update_far_terrain(
+        far_terrain,
+        &renderer.queue,
+        &mut encoder,
+        camera.position,
+        view_proj,
+        terrain,
+        surface,
+    ) {
+        renderer.queue.submit(std::iter::once(encoder.finish()));
+    }
+}
+
+/// Stream a block atlas built from `registry`, replacing the previous one;
+/// call again when blocks are registered
+pub fn set_renderer_block_atlas(renderer: &mut Renderer, registry: &BlockRegistry) {
+    if let Some(previous) = renderer.block_atlas.take() {
+        remove_streamed_texture(&mut renderer.texture_streaming, previous);
+    }
+    let atlas = build_block_atlas(registry);
+    renderer.block_atlas = Some(register_streamed_texture(
+        &mut renderer.texture_streaming,
+        "block_atlas",
+        &atlas,
+        default_streamed_residency(),
+    ));
+}
+
+/// Note the block atlas drawn `atlas_distance` voxels from the camera, pick
+/// each streamed texture's mips against `memory` and upload or evict them
+pub fn update_renderer_texture_streaming(
+    renderer: &mut Renderer,
+    atlas_distance: f32,
+    memory: MemoryBudgetView,
+) {
+    if let Some(atlas) = renderer.block_atlas {
+        note_texture_use(&mut renderer.texture_streaming, atlas, atlas_distance);
+    }
+    update_texture_streaming(&mut renderer.texture_streaming, memory);
+    apply_texture_streaming(
+        &mut renderer.texture_streaming,
+        &renderer.device,
+        &renderer.queue,
+    );
+}
+
+/// Tint the faces a held light would reach, reading the voxels of
+/// `world_buffer`; `registry` decides which blocks light passes through
+pub fn enable_renderer_light_preview(
+    renderer: &mut Renderer,
+    world_buffer: &WorldBuffer,
+    registry: &BlockRegistry,
+) -> RendererResult<()> {
+    let format = render_target_format(renderer);
+    let samples = anti_aliasing_sample_count(renderer.anti_aliasing.active);
+    let device = &renderer.device;
+    let preview = create_pipelines(&mut renderer.pipeline_cache, || {
+        create_light_preview(device, world_buffer, registry, format, None, samples)
+    })?;
+    renderer.light_preview = Some(preview);
+    Ok(())
+}
+
+/// Preview a light of level `emission` placed at `target` (`None` or
+/// level 0 hides it); propagation reruns only when either changed
+pub fn update_renderer_light_preview(
+    renderer: &mut Renderer,
+    world_buffer: &WorldBuffer,
+    camera: &CameraData,
+    target: Option<VoxelPos>,
+    emission: u8,
+) {
+    let Some(preview) = renderer.light_preview.as_mut() else {
+        return;
+    };
+    let view_proj = build_projection_matrix(camera) * build_view_matrix(camera);
+    let mut encoder = renderer
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Light Preview Encoder"),
+        });
+    if update_light_preview(
+        preview,
+        &renderer.queue,
+        &mut encoder,
+        world_buffer,
+        view_proj,
+        target,
+        emission,
+    ) {
+        renderer.queue.submit(std::iter::once(encoder.finish()));
+    }
+}
+
+/// Show a ghost of the block about to be placed
+pub fn enable_renderer_placement_preview(renderer: &mut Renderer) -> RendererResult<()> {
+    let format = render_target_format(renderer);
+    let samples = anti_aliasing_sample_count(renderer.anti_aliasing.active);
+    let device = &renderer.device;
+    let preview = create_pipelines(&mut renderer.pipeline_cache, || {
+        create_placement_preview(device, format, None, samples)
+    })?;
+    renderer.placement_preview = Some(preview);
+    Ok(())
+}
+
+/// Move the placement ghost to this frame's target (`None` hides it)
+pub fn update_renderer_placement_preview(
+    renderer: &mut Renderer,
+    camera: &CameraData,
+    preview: Option<PlacementPreview>,
+) {
+    if let Some(ghost) = renderer.placement_preview.as_mut() {
+        update_placement_preview(ghost, &renderer.queue, camera, preview);
+    }
+}
+
+/// Recreate the pipelines drawn in the main pass after the target format
+/// or MSAA sample count changed
+fn rebuild_main_pass_pipelines(renderer: &mut Renderer) -> RendererResult<()> {
+    let format = render_target_format(renderer);
+    let samples = anti_aliasing_sample_count(renderer.anti_aliasing.active);
+    if let Some(sky) = renderer.sky.as_mut() {
+        rebuild_sky_pipeline(sky, &renderer.device, format, None, samples)?;
+    }
+    if let Some(clouds) = renderer.clouds.as_mut() {
+        rebuild_cloud_pipeline(clouds, &renderer.device, format, None, samples)?;
+    }
+    if let Some(far_terrain) = renderer.far_terrain.as_mut() {
+        rebuild_far_terrain_pipeline(far_terrain, &renderer.device, format, None, samples)?;
+    }
+    if let Some(ghost) = renderer.placement_preview.as_mut() {
+        rebuild_placement_preview_pipeline(ghost, &renderer.device, format, None, samples)?;
+    }
+    if let Some(preview) = renderer.light_preview.as_mut() {
+        rebuild_light_preview_pipeline(preview, &renderer.device, format, None, samples)?;
+    }
+    Ok(())
+}
+
+/// Select the anti-aliasing mode; returns the mode actually used, which
+/// differs when the GPU cannot multisample the target format
+pub fn set_renderer_anti_aliasing(
+    renderer: &mut Renderer,
+    mode: AntiAliasingMode,
+) -> RendererResult<AntiAliasingMode> {
+    let old_samples = anti_aliasing_sample_count(renderer.anti_aliasing.active);
+    let active = set_anti_aliasing_mode(&mut renderer.anti_aliasing, mode);
+    if anti_aliasing_sample_count(active) != old_samples {
+        rebuild_main_pass_pipelines(renderer)?;
+    }
+    log::info!("[Renderer] Anti-aliasing: {:?}", active);
+    Ok(active)
+}
+
+/// Draw the scene at a fraction of the target resolution (adaptive quality
+/// lowers `RenderBuffers::render_scale` under load) and upscale it
+pub fn set_renderer_render_scale(renderer: &mut Renderer, render_scale: f32) {
+    set_anti_aliasing_render_scale(&mut renderer.anti_aliasing, render_scale);
+}
+
+/// Record independent passes on worker threads or all on the main thread;
+/// compare `parallel_encoding.stats` or a trace capture with each to see
+/// the main thread time saved
+pub fn set_renderer_parallel_encoding(renderer: &mut Renderer, enabled: bool) {
+    set_parallel_encoding_enabled(&mut renderer.parallel_encoding, enabled);
+    log::info!("[Renderer] Parallel pass recording: {}", enabled);
+}
+
+/// Change the edge highlight mode, color or thresholds
+pub fn set_renderer_edge_highlight(renderer: &mut Renderer, config: EdgeHighlightConfig) {
+    set_edge_highlight_config(&mut renderer.edge_highlight, config);
+    log::info!("[Renderer] Edge highlight: {:?}", config.mode);
+}
+
+/// Sub-pixel offset the world pass adds to its projection this frame
+/// (non-zero only with TAA)
+pub fn renderer_projection_jitter(renderer: &Renderer) -> [f32; 2] {
+    anti_aliasing_jitter(&renderer.anti_aliasing)
+}
+
+/// Take this frame's interpolated entity transforms for drawing. Call after
+/// `interpolate_entity_transforms` so motion stays smooth at any frame rate.
+pub fn update_renderer_entities(renderer: &mut Renderer, transforms: &TransformBuffers) {
+    renderer
+        .entity_positions
+        .clone_from(&transforms.interpolated_positions);
+    renderer
+        .entity_rotations
+        .clone_from(&transforms.interpolated_rotations);
+}
+
+/// Put this frame's entities in LOD bands around the camera. Call after
+/// `update_renderer_entities`.
+pub fn update_renderer_entity_lod(renderer: &mut Renderer, camera: &CameraData) {
+    update_entity_lod(
+        &mut renderer.entity_lod,
+        camera.position.into(),
+        &renderer.entity_positions,
+    );
+}
+
+/// Current render target size in pixels
+pub fn render_target_size(renderer: &Renderer) -> (u32, u32) {
+    match &renderer.target {
+        RenderTarget::Surface { config, .. } => (config.width, config.height),
+        RenderTarget::Texture { texture, .. } => (texture.width(), texture.height()),
+    }
+}
+
+pub(super) fn encode_frame_pass(
+    renderer: &Renderer,
+    view: &wgpu::TextureView,
+    mut timer: Option<&mut GpuPassTimerData>,
+) -> wgpu::CommandBuffer {
+    let mut encoder = renderer
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Embedded Frame Encoder"),
+        });
+    let timed_slot = match timer.as_deref_mut() {
+        Some(timer) if trace_recording() => begin_gpu_pass_timing(timer, "Embedded Frame Pass"),
+        _ => None,
+    };
+    {
+        // MSAA resolves into the target; FXAA/TAA draw offscreen first
+        let (scene_view, resolve_target) = scene_color_attachment(&renderer.anti_aliasing, view);
+        let store = if resolve_target.is_some() {
+            wgpu::StoreOp::Discard
+        } else {
+            wgpu::StoreOp::Store
+        };
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Embedded Frame Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: scene_view,
+                resolve_target,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(renderer.clear_color),
+                    store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: timed_slot
+                .zip(timer.as_deref())
+                .map(|(slot, timer)| render_pass_timestamp_writes(timer, slot)),
+            occlusion_query_set: None,
+        });
+        if let Some(sky) = &renderer.sky {
+            render_sky(sky, &mut pass);
+        }
+        if let Some(far_terrain) = &renderer.far_terrain {
+            render_far_terrain(far_terrain, &mut pass);
+        }
+        if let Some(clouds) = &renderer.clouds {
+            render_clouds(clouds, &mut pass);
+        }
+        if let Some(ghost) = &renderer.placement_preview {
+            render_placement_preview(ghost, &mut pass);
+        }
+        if let Some(preview) = &renderer.light_preview {
+            render_light_preview(preview, &mut pass);
+        }
+    }
+    encode_anti_aliasing_pass(&renderer.anti_aliasing, &mut encoder, view);
+    encode_edge_highlight_pass(&renderer.edge_highlight, &mut encoder, view);
+    if let Some(timer) = timer {
+        resolve_gpu_pass_timer(timer, &mut encoder);
+    }
+    encoder.finish()
+}
+
+/// Render one frame into the attached target.
+/// Returns false when the frame was skipped (surface lost, outdated or busy,
+/// or the device lost and waiting for `poll_device_recovery`).
+pub fn render_embedded_frame(renderer: &mut Renderer) -> RendererResult<bool> {
+    let _span = crate::trace_span!(Render, "render_embedded_frame");
+    if device_lost(&renderer.recovery.signal) {
+        return Ok(false);
+    }
+    let (width, height) = render_target_size(renderer);
+    let format = render_target_format(renderer);
+    prepare_anti_aliasing(
+        &mut renderer.anti_aliasing,
+        &renderer.device,
+        &renderer.queue,
+        width,
+        height,
+        format,
+    )?;
+    prepare_edge_highlight(
+        &mut renderer.edge_highlight,
+        &renderer.device,
+        &renderer.queue,
+        width,
+        height,
+        format,
+    )?;
+    let secondary = prepare_secondary_views(renderer)?;
+
+    // Taken out so passes can be timed while the renderer is borrowed
+    let mut timer = renderer.gpu_pass_timer.take();
+    if let Some(timer) = timer.as_mut() {
+        collect_global_gpu_pass_timings(timer, &renderer.device, "Graphics Queue");
+    }
+    let result = present_embedded_frame(renderer, &secondary, timer.as_mut());
+    renderer.gpu_pass_timer = timer;
+    let Some(times) = result? else {
+        return Ok(false);
+    };
+
+    record_parallel_encoding_frame(&mut renderer.parallel_encoding, &times);
+    finish_anti_aliasing_frame(&mut renderer.anti_aliasing);
+    finish_edge_highlight_frame(&mut renderer.edge_highlight);
+    renderer.frames_rendered += 1;
+    Ok(true)
+}
+
+/// Record the prepared secondary views on workers while the main pass
+/// records here, then submit them in order ahead of the main pass
+fn submit_frame_commands(
+    renderer: &Renderer,
+    secondary: &[usize],
+    view: &wgpu::TextureView,
+    timer: Option<&mut GpuPassTimerData>,
+) -> FrameEncodingTimes {
+    let _span = crate::trace_span!(Render, "record_frame_commands");
+    let views = &renderer.secondary_views.views;
+    let clear_color = renderer.clear_color;
+    let frame = record_frame_commands(
+        &renderer.parallel_encoding.config,
+        &renderer.device,
+        secondary,
+        "Secondary View Encoder",
+        |&index, encoder| encode_secondary_view_pass(&views[index], encoder, clear_color),
+        || encode_frame_pass(renderer, view, timer),
+    );
+    renderer.queue.submit(frame.buffers);
+    frame.times
+}
+
+/// Present a frame; None when it was skipped (the prepared secondary views
+/// are still rendered)
+fn present_embedded_frame(
+    renderer: &Renderer,
+    secondary: &[usize],
+    mut timer: Option<&mut GpuPassTimerData>,
+) -> RendererResult<Option<FrameEncodingTimes>> {
+    let times = match &renderer.target {
+        RenderTarget::Surface {
+            surface, config, ..
+        } => {
+            let frame = match surface.get_current_texture() {
+                Ok(frame) => frame,
+                Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                    surface.configure(&renderer.device, config);
+                    note_surface_lost(&renderer.recovery.signal);
+                    submit_secondary_views(renderer, secondary);
+                    return Ok(None);
+                }
+                Err(wgpu::SurfaceError::Timeout) => {
+                    submit_secondary_views(renderer, secondary);
+                    return Ok(None);
+                }
+                Err(wgpu::SurfaceError::OutOfMemory) => {
+                    return Err("Out of memory while acquiring surface texture".to_string())
+                }
+            };
+            let view = frame
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default());
+            let times = submit_frame_commands(renderer, secondary, &view, timer.as_deref_mut());
+            frame.present();
+            note_surface_presented(&renderer.recovery.signal);
+            times
+        }
+        RenderTarget::Texture { view, .. } => {
+            submit_frame_commands(renderer, secondary, view, timer.as_deref_mut())
+        }
+    };
+    if let Some(timer) = timer {
+        request_gpu_pass_readback(timer, trace_now_ns());
+    }
+    Ok(Some(times))
+}
+
+impl Renderer {
+    /// Embed the engine renderer into a host application's window
+    pub fn attach_to_window(
+        window: Arc<winit::window::Window>,
+        surface: wgpu::Surface<'static>,
+        adapter: &wgpu::Adapter,
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+    ) -> RendererResult<Self> {
+        attach_renderer_to_window(window, surface, adapter, device, queue)
+    }
+
+    /// Embed the engine renderer into a host-owned texture
+    pub fn attach_to_texture(
+        texture: wgpu::Texture,
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+    ) -> RendererResult<Self> {
+        attach_renderer_to_texture(texture, device, queue)
+    }
+}
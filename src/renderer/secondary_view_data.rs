@@ -0,0 +1,108 @@
+//! Secondary View Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Scheduling, culling and rendering live in secondary_view_operations.rs
+//!
+//! A secondary view renders the world from another camera into a texture the
+//! game samples elsewhere: the far side of a portal, a security camera
+//! monitor, a map item. Each view has its own resolution, render interval
+//! and frustum-culled chunk list. A per-frame budget (view count and pixels)
+//! decides which due views render; the pixel budget shrinks while frames run
+//! over the target time, and views deferred for a whole interval have their
+//! interval stretched until the load drops again.
+
+use super::cloud_data::CloudData;
+use super::sky_data::SkyData;
+use crate::camera::CameraData;
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use crate::world::core::ChunkPos;
+
+/// Handle to a secondary view, never reused by a renderer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SecondaryViewId(pub u32);
+
+/// Frustum planes (a, b, c, d) pointing inward: left, right, top, bottom,
+/// near, far
+pub type FrustumPlanes = [[f32; 4]; 6];
+
+/// Resolution, interval and culling range of a secondary view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecondaryViewConfig {
+    pub width: u32,
+    pub height: u32,
+    /// Frames between renders (1 = every frame)
+    pub interval_frames: u32,
+    /// Culling radius around the view camera (chunks)
+    pub view_distance_chunks: u32,
+    /// Chunk edge length of the world the view looks into (voxels)
+    pub chunk_size: u32,
+}
+
+/// Per-frame limits shared by all secondary views
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SecondaryViewBudget {
+    pub max_views_per_frame: u32,
+    /// Pixels per frame at a load factor of 1
+    pub pixel_budget: u64,
+    /// Frame time the load factor is steered toward (ms)
+    pub target_frame_ms: f32,
+}
+
+/// When a view last rendered and how far it is degraded; kept apart from
+/// the GPU resources so scheduling runs on plain data
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SecondaryViewTiming {
+    pub enabled: bool,
+    pub interval_frames: u32,
+    /// Pixels one render of the view costs
+    pub pixels: u64,
+    /// None until the view renders for the first time
+    pub last_rendered_frame: Option<u64>,
+    /// Each level doubles the interval
+    pub degradation: u32,
+    /// Frames in a row the view was due but over budget
+    pub consecutive_deferrals: u32,
+}
+
+/// GPU resources and culling result of one secondary view
+pub struct SecondaryView {
+    pub id: SecondaryViewId,
+    pub config: SecondaryViewConfig,
+    pub camera: CameraData,
+    /// Sampled by the game; recreated on resize and device recovery
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    /// Record of `texture` in the GPU allocation tracker
+    pub texture_allocation: GpuAllocationGuard,
+    /// Copies of the renderer's sky and clouds with this view's camera
+    /// (created on first render, dropped when the renderer disables them)
+    pub sky: Option<SkyData>,
+    pub clouds: Option<CloudData>,
+    /// Chunks inside the view frustum at the last render
+    pub visible_chunks: Vec<ChunkPos>,
+}
+
+/// Secondary view counters for the last frame and since creation
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SecondaryViewStats {
+    pub rendered_last_frame: u32,
+    /// Due views pushed to a later frame by the budget
+    pub deferred_last_frame: u32,
+    pub pixels_last_frame: u64,
+    pub visible_chunks_last_frame: u32,
+    pub total_renders: u64,
+    pub total_deferrals: u64,
+}
+
+/// Secondary views of a renderer; `views` and `timings` share indices
+pub struct SecondaryViewsData {
+    pub views: Vec<SecondaryView>,
+    pub timings: Vec<SecondaryViewTiming>,
+    pub next_id: u32,
+    pub budget: SecondaryViewBudget,
+    /// Fraction of the pixel budget usable this frame, from recent frame times
+    pub load_factor: f32,
+    pub stats: SecondaryViewStats,
+    /// Owner of the view textures in the GPU allocation tracker
+    pub gpu_owner: GpuOwnerGuard,
+}
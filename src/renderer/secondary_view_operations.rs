@@ -0,0 +1,620 @@
+//! Secondary View Operations - Pure DOP
+//!
+//! Functions that add and configure secondary views, pick the views that fit
+//! this frame's budget, cull their chunks and render them into their textures
+//! ahead of the main pass.
+
+use super::cloud_data::CloudData;
+use super::cloud_operations::{create_clouds, follow_clouds, render_clouds};
+use super::error::RendererResult;
+use super::gpu_culling::GpuCamera;
+use super::parallel_encoding_operations::record_independent_passes;
+use super::renderer_data::Renderer;
+use super::secondary_view_data::{
+    FrustumPlanes, SecondaryView, SecondaryViewBudget, SecondaryViewConfig, SecondaryViewId,
+    SecondaryViewStats, SecondaryViewTiming, SecondaryViewsData,
+};
+use super::sky_data::SkyData;
+use super::sky_operations::{create_sky, render_sky, update_sky_colors};
+use crate::camera::{
+    build_projection_matrix, build_view_matrix, chunks_in_view_distance, update_aspect_ratio,
+    CameraData,
+};
+use crate::constants::{core, secondary_views};
+use crate::memory::{
+    create_tracked_texture, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+    TrackedTextureView,
+};
+use crate::world::core::ChunkPos;
+use cgmath::EuclideanSpace;
+use std::cmp::Reverse;
+
+/// Color format of secondary view textures
+const SECONDARY_VIEW_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Default per-frame budget
+pub fn default_secondary_view_budget() -> SecondaryViewBudget {
+    SecondaryViewBudget {
+        max_views_per_frame: secondary_views::DEFAULT_MAX_VIEWS_PER_FRAME,
+        pixel_budget: secondary_views::DEFAULT_PIXEL_BUDGET,
+        target_frame_ms: secondary_views::DEFAULT_TARGET_FRAME_MS,
+    }
+}
+
+/// Config for a view rendered every frame at the given resolution
+pub fn default_secondary_view_config(width: u32, height: u32) -> SecondaryViewConfig {
+    SecondaryViewConfig {
+        width,
+        height,
+        interval_frames: 1,
+        view_distance_chunks: secondary_views::DEFAULT_VIEW_DISTANCE_CHUNKS,
+        chunk_size: core::CHUNK_SIZE,
+    }
+}
+
+/// No views, default budget, full load factor
+pub fn create_secondary_views() -> SecondaryViewsData {
+    SecondaryViewsData {
+        views: Vec::new(),
+        timings: Vec::new(),
+        next_id: 0,
+        budget: default_secondary_view_budget(),
+        load_factor: 1.0,
+        stats: SecondaryViewStats::default(),
+        gpu_owner: register_global_gpu_owner("secondary_views"),
+    }
+}
+
+fn validate_secondary_view_config(
+    device: &wgpu::Device,
+    config: &SecondaryViewConfig,
+) -> RendererResult<()> {
+    let max = device.limits().max_texture_dimension_2d;
+    if config.width == 0 || config.height == 0 || config.width > max || config.height > max {
+        return Err(format!(
+            "Secondary view size {}x{} outside 1..={}",
+            config.width, config.height, max
+        ));
+    }
+    if config.chunk_size == 0 {
+        return Err("Secondary view chunk size must not be 0".to_string());
+    }
+    Ok(())
+}
+
+fn create_secondary_view_texture(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    config: &SecondaryViewConfig,
+) -> TrackedTextureView {
+    let (texture, allocation) = create_tracked_texture(
+        device,
+        owner,
+        &wgpu::TextureDescriptor {
+            label: Some("Secondary View Texture"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: SECONDARY_VIEW_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        },
+    );
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view, allocation)
+}
+
+fn secondary_view_pixels(config: &SecondaryViewConfig) -> u64 {
+    u64::from(config.width) * u64::from(config.height)
+}
+
+fn secondary_view_index(data: &SecondaryViewsData, id: SecondaryViewId) -> Option<usize> {
+    data.views.iter().position(|view| view.id == id)
+}
+
+/// Add a view rendering the world from `camera` into its own texture.
+/// The camera's aspect ratio is taken from the view resolution.
+pub fn add_secondary_view(
+    renderer: &mut Renderer,
+    camera: CameraData,
+    config: SecondaryViewConfig,
+) -> RendererResult<SecondaryViewId> {
+    let data = &mut renderer.secondary_views;
+    if data.views.len() >= secondary_views::MAX_SECONDARY_VIEWS {
+        return Err(format!(
+            "Too many secondary views (max {})",
+            secondary_views::MAX_SECONDARY_VIEWS
+        ));
+    }
+    validate_secondary_view_config(&renderer.device, &config)?;
+
+    let id = SecondaryViewId(data.next_id);
+    data.next_id += 1;
+    let (texture, view, texture_allocation) =
+        create_secondary_view_texture(&renderer.device, &data.gpu_owner, &config);
+    data.views.push(SecondaryView {
+        id,
+        config,
+        camera,
+        texture,
+        view,
+        texture_allocation,
+        sky: None,
+        clouds: None,
+        visible_chunks: Vec::new(),
+    });
+    data.timings.push(SecondaryViewTiming {
+        enabled: true,
+        interval_frames: config.interval_frames.max(1),
+        pixels: secondary_view_pixels(&config),
+        ..Default::default()
+    });
+    log::debug!(
+        "[Renderer] Added secondary view {} ({}x{}, every {} frames)",
+        id.0,
+        config.width,
+        config.height,
+        config.interval_frames.max(1)
+    );
+    Ok(id)
+}
+
+/// Remove a view and free its texture; false for an unknown id
+pub fn remove_secondary_view(renderer: &mut Renderer, id: SecondaryViewId) -> bool {
+    let data = &mut renderer.secondary_views;
+    let Some(index) = secondary_view_index(data, id) else {
+        return false;
+    };
+    data.views.remove(index);
+    data.timings.remove(index);
+    true
+}
+
+/// A view and its culling result
+pub fn secondary_view(renderer: &Renderer, id: SecondaryViewId) -> Option<&SecondaryView> {
+    let data = &renderer.secondary_views;
+    secondary_view_index(data, id).map(|index| &data.views[index])
+}
+
+/// Texture a view renders into, for binding in the game's own passes.
+/// It changes after `resize_secondary_view` and device recovery.
+pub fn secondary_view_texture(
+    renderer: &Renderer,
+    id: SecondaryViewId,
+) -> Option<&wgpu::TextureView> {
+    secondary_view(renderer, id).map(|view| &view.view)
+}
+
+/// Move a view's camera (e.g. follow the linked portal); false for an unknown id
+pub fn set_secondary_view_camera(
+    renderer: &mut Renderer,
+    id: SecondaryViewId,
+    camera: CameraData,
+) -> bool {
+    let data = &mut renderer.secondary_views;
+    let Some(index) = secondary_view_index(data, id) else {
+        return false;
+    };
+    data.views[index].camera = camera;
+    true
+}
+
+/// Frames between renders of a view (clamped to at least 1)
+pub fn set_secondary_view_interval(
+    renderer: &mut Renderer,
+    id: SecondaryViewId,
+    interval_frames: u32,
+) -> bool {
+    let data = &mut renderer.secondary_views;
+    let Some(index) = secondary_view_index(data, id) else {
+        return false;
+    };
+    data.views[index].config.interval_frames = interval_frames.max(1);
+    data.timings[index].interval_frames = interval_frames.max(1);
+    true
+}
+
+/// Pause or resume a view; a paused view keeps its last image
+pub fn set_secondary_view_enabled(
+    renderer: &mut Renderer,
+    id: SecondaryViewId,
+    enabled: bool,
+) -> bool {
+    let data = &mut renderer.secondary_views;
+    let Some(index) = secondary_view_index(data, id) else {
+        return false;
+    };
+    data.timings[index].enabled = enabled;
+    true
+}
+
+/// Change a view's resolution; the texture is recreated and the view renders
+/// again on the next frame. Returns false for an unknown id.
+pub fn resize_secondary_view(
+    renderer: &mut Renderer,
+    id: SecondaryViewId,
+    width: u32,
+    height: u32,
+) -> RendererResult<bool> {
+    let data = &mut renderer.secondary_views;
+    let Some(index) = secondary_view_index(data, id) else {
+        return Ok(false);
+    };
+    let config = SecondaryViewConfig {
+        width,
+        height,
+        ..data.views[index].config
+    };
+    validate_secondary_view_config(&renderer.device, &config)?;
+
+    let (texture, view, texture_allocation) =
+        create_secondary_view_texture(&renderer.device, &data.gpu_owner, &config);
+    let secondary = &mut data.views[index];
+    secondary.config = config;
+    secondary.texture = texture;
+    secondary.view = view;
+    secondary.texture_allocation = texture_allocation;
+    let timing = &mut data.timings[index];
+    timing.pixels = secondary_view_pixels(&config);
+    timing.last_rendered_frame = None;
+    Ok(true)
+}
+
+/// Replace the per-frame budget shared by all views
+pub fn set_secondary_view_budget(renderer: &mut Renderer, budget: SecondaryViewBudget) {
+    renderer.secondary_views.budget = budget;
+}
+
+/// Feed the last frame time; frames over the target shrink the pixel budget
+/// proportionally, frames within it slowly restore it
+pub fn record_secondary_view_frame_time(data: &mut SecondaryViewsData, frame_ms: f32) {
+    let target = data.budget.target_frame_ms;
+    data.load_factor = if frame_ms > target && target > 0.0 {
+        (data.load_factor * target / frame_ms).max(secondary_views::MIN_LOAD_FACTOR)
+    } else {
+        (data.load_factor + secondary_views::LOAD_RECOVERY_PER_FRAME).min(1.0)
+    };
+}
+
+/// Frames between renders after degradation
+pub fn effective_view_interval(timing: &SecondaryViewTiming) -> u64 {
+    u64::from(timing.interval_frames.max(1)) << timing.degradation
+}
+
+/// Whether a view wants to render in `frame`
+pub fn secondary_view_due(timing: &SecondaryViewTiming, frame: u64) -> bool {
+    timing.enabled
+        && match timing.last_rendered_frame {
+            None => true,
+            Some(last) => frame.saturating_sub(last) >= effective_view_interval(timing),
+        }
+}
+
+/// Pick the views rendered in `frame` and mark them rendered. Views that
+/// never rendered go first, then the most overdue. The first pick always
+/// fits; after it views are taken while the count and the pixel budget
+/// scaled by `load_factor` allow. A view deferred for a whole interval is
+/// degraded (its interval doubles); views rendered at full load factor
+/// recover one level.
+pub fn schedule_secondary_views(
+    timings: &mut [SecondaryViewTiming],
+    budget: &SecondaryViewBudget,
+    load_factor: f32,
+    frame: u64,
+    stats: &mut SecondaryViewStats,
+) -> Vec<usize> {
+    let pixel_budget = (budget.pixel_budget as f64 * f64::from(load_factor)) as u64;
+    let mut due: Vec<usize> = (0..timings.len())
+        .filter(|&index| secondary_view_due(&timings[index], frame))
+        .collect();
+    due.sort_by_key(|&index| {
+        let timing = &timings[index];
+        let overdue = match timing.last_rendered_frame {
+            None => u64::MAX,
+            Some(last) => frame
+                .saturating_sub(last)
+                .saturating_sub(effective_view_interval(timing)),
+        };
+        (Reverse(overdue), index)
+    });
+
+    let mut scheduled = Vec::new();
+    let mut pixels = 0u64;
+    let mut deferred = 0u32;
+    for index in due {
+        let timing = &mut timings[index];
+        let fits = (scheduled.len() as u32) < budget.max_views_per_frame
+            && (scheduled.is_empty() || pixels + timing.pixels <= pixel_budget);
+        if fits {
+            pixels += timing.pixels;
+            timing.last_rendered_frame = Some(frame);
+            timing.consecutive_deferrals = 0;
+            if load_factor >= 1.0 {
+                timing.degradation = timing.degradation.saturating_sub(1);
+            }
+            scheduled.push(index);
+            continue;
+        }
+        deferred += 1;
+        timing.consecutive_deferrals += 1;
+        if u64::from(timing.consecutive_deferrals) >= effective_view_interval(timing)
+            && timing.degradation < secondary_views::MAX_DEGRADATION_LEVEL
+        {
+            timing.degradation += 1;
+            timing.consecutive_deferrals = 0;
+        }
+    }
+
+    stats.rendered_last_frame = scheduled.len() as u32;
+    stats.deferred_last_frame = deferred;
+    stats.pixels_last_frame = pixels;
+    stats.total_renders += scheduled.len() as u64;
+    stats.total_deferrals += u64::from(deferred);
+    scheduled
+}
+
+/// Whether a chunk's bounds are at least partly inside the frustum planes
+/// (`GpuCamera::frustum_planes`, pointing inward)
+pub fn chunk_in_frustum(planes: &FrustumPlanes, pos: ChunkPos, chunk_size: u32) -> bool {
+    let size = chunk_size as f32;
+    let min = [
+        pos.x as f32 * size,
+        pos.y as f32 * size,
+        pos.z as f32 * size,
+    ];
+    planes.iter().all(|&[a, b, c, d]| {
+        // Corner furthest along the plane normal
+        let x = if a >= 0.0 { min[0] + size } else { min[0] };
+        let y = if b >= 0.0 { min[1] + size } else { min[1] };
+        let z = if c >= 0.0 { min[2] + size } else { min[2] };
+        a * x + b * y + c * z + d >= 0.0
+    })
+}
+
+/// Chunks within the view distance of `camera` that intersect its frustum
+pub fn cull_secondary_view(camera: &CameraData, config: &SecondaryViewConfig) -> Vec<ChunkPos> {
+    let gpu_camera = GpuCamera::from_matrices(
+        &build_view_matrix(camera),
+        &build_projection_matrix(camera),
+        camera.position.to_vec(),
+    );
+    let mut chunks =
+        chunks_in_view_distance(camera, config.chunk_size, config.view_distance_chunks);
+    chunks.retain(|&pos| chunk_in_frustum(&gpu_camera.frustum_planes, pos, config.chunk_size));
+    chunks
+}
+
+/// Keep a view's sky and clouds in step with the renderer's, seen from the
+/// view camera
+fn sync_secondary_view_passes(
+    view: &mut SecondaryView,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    main_sky: Option<&SkyData>,
+    main_clouds: Option<&CloudData>,
+    camera: &CameraData,
+) -> RendererResult<()> {
+    match main_sky {
+        Some(main) => {
+            let sky = match view.sky.as_mut() {
+                Some(sky) => sky,
+                None => view.sky.insert(create_sky(
+                    device,
+                    main.config,
+                    SECONDARY_VIEW_FORMAT,
+                    None,
+                    1,
+                )?),
+            };
+            sky.config = main.config;
+            if main.updated {
+                update_sky_colors(sky, queue, &main.colors, camera);
+            }
+        }
+        None => view.sky = None,
+    }
+    match main_clouds {
+        Some(main) => {
+            let clouds = match view.clouds.as_mut() {
+                Some(clouds) => clouds,
+                None => view.clouds.insert(create_clouds(
+                    device,
+                    main.config,
+                    SECONDARY_VIEW_FORMAT,
+                    None,
+                    1,
+                )?),
+            };
+            follow_clouds(clouds, queue, main, camera);
+        }
+        None => view.clouds = None,
+    }
+    Ok(())
+}
+
+/// Record one view's pass; views record independently of each other and of
+/// the main pass
+pub(super) fn encode_secondary_view_pass(
+    view: &SecondaryView,
+    encoder: &mut wgpu::CommandEncoder,
+    clear_color: wgpu::Color,
+) {
+    let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Secondary View Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: &view.view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(clear_color),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    if let Some(sky) = &view.sky {
+        render_sky(sky, &mut pass);
+    }
+    if let Some(clouds) = &view.clouds {
+        render_clouds(clouds, &mut pass);
+    }
+}
+
+/// Schedule, cull and update the views due this frame; returns their
+/// indices for recording. `render_embedded_frame` records them on workers
+/// alongside the main pass.
+pub fn prepare_secondary_views(renderer: &mut Renderer) -> RendererResult<Vec<usize>> {
+    let _span = crate::trace_span!(Render, "prepare_secondary_views");
+    let frame = renderer.frames_rendered;
+    let Renderer {
+        device,
+        queue,
+        sky,
+        clouds,
+        secondary_views: data,
+        ..
+    } = renderer;
+
+    let scheduled = schedule_secondary_views(
+        &mut data.timings,
+        &data.budget,
+        data.load_factor,
+        frame,
+        &mut data.stats,
+    );
+    data.stats.visible_chunks_last_frame = 0;
+
+    for &index in &scheduled {
+        let view = &mut data.views[index];
+        let camera = update_aspect_ratio(&view.camera, view.config.width, view.config.height);
+        view.visible_chunks = cull_secondary_view(&camera, &view.config);
+        data.stats.visible_chunks_last_frame += view.visible_chunks.len() as u32;
+        sync_secondary_view_passes(view, device, queue, sky.as_ref(), clouds.as_ref(), &camera)?;
+    }
+    Ok(scheduled)
+}
+
+/// Record and submit prepared views on their own, for frames without a
+/// main pass. Returns the views rendered.
+pub fn submit_secondary_views(renderer: &Renderer, scheduled: &[usize]) -> u32 {
+    if scheduled.is_empty() {
+        return 0;
+    }
+    let views = &renderer.secondary_views.views;
+    let clear_color = renderer.clear_color;
+    let recorded = record_independent_passes(
+        &renderer.parallel_encoding.config,
+        &renderer.device,
+        scheduled,
+        "Secondary View Encoder",
+        |&index, encoder| encode_secondary_view_pass(&views[index], encoder, clear_color),
+    );
+    renderer.queue.submit(recorded.buffers);
+    scheduled.len() as u32
+}
+
+/// Render the views scheduled for this frame into their textures without a
+/// main pass. Returns the views rendered.
+pub fn render_secondary_views(renderer: &mut Renderer) -> RendererResult<u32> {
+    let _span = crate::trace_span!(Render, "render_secondary_views");
+    let scheduled = prepare_secondary_views(renderer)?;
+    Ok(submit_secondary_views(renderer, &scheduled))
+}
+
+/// Recreate view textures on a new device after a device loss. Sky and
+/// clouds are recreated on the next render; every view renders again.
+pub fn rebuild_secondary_views(data: &mut SecondaryViewsData, device: &wgpu::Device) {
+    for view in &mut data.views {
+        let (texture, texture_view, texture_allocation) =
+            create_secondary_view_texture(device, &data.gpu_owner, &view.config);
+        view.texture = texture;
+        view.view = texture_view;
+        view.texture_allocation = texture_allocation;
+        view.sky = None;
+        view.clouds = None;
+    }
+    for timing in &mut data.timings {
+        timing.last_rendered_frame = None;
+        timing.consecutive_deferrals = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::init_camera;
+    use cgmath::Point3;
+
+    fn timing(pixels: u64) -> SecondaryViewTiming {
+        SecondaryViewTiming {
+            enabled: true,
+            interval_frames: 1,
+            pixels,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_budget_defers_and_degrades_views() {
+        let budget = SecondaryViewBudget {
+            max_views_per_frame: 2,
+            pixel_budget: 1000,
+            target_frame_ms: 16.7,
+        };
+        let mut stats = SecondaryViewStats::default();
+        let mut timings = vec![timing(600), timing(600), timing(300)];
+
+        // The first view always fits; the second exceeds the pixel budget
+        let first = schedule_secondary_views(&mut timings, &budget, 1.0, 0, &mut stats);
+        assert_eq!(first, vec![0, 2]);
+        assert_eq!(stats.deferred_last_frame, 1);
+        assert_eq!(timings[1].degradation, 1);
+
+        // The deferred view is the most overdue on the next frame
+        let second = schedule_secondary_views(&mut timings, &budget, 1.0, 1, &mut stats);
+        assert_eq!(second[0], 1);
+        assert_eq!(timings[1].degradation, 0);
+
+        // Disabled views and views inside their interval are skipped
+        timings[0].enabled = false;
+        timings[2].interval_frames = 4;
+        let third = schedule_secondary_views(&mut timings, &budget, 0.5, 2, &mut stats);
+        assert_eq!(third, vec![1]);
+    }
+
+    #[test]
+    fn test_frame_time_scales_load_factor() {
+        let mut data = create_secondary_views();
+        let target = data.budget.target_frame_ms;
+        record_secondary_view_frame_time(&mut data, target * 2.0);
+        assert!((data.load_factor - 0.5).abs() < 1e-4);
+        for _ in 0..100 {
+            record_secondary_view_frame_time(&mut data, 1000.0);
+        }
+        assert_eq!(data.load_factor, secondary_views::MIN_LOAD_FACTOR);
+        for _ in 0..100 {
+            record_secondary_view_frame_time(&mut data, 1.0);
+        }
+        assert_eq!(data.load_factor, 1.0);
+    }
+
+    #[test]
+    fn test_culling_keeps_chunks_in_front() {
+        // Yaw 0 looks down +X
+        let camera = init_camera(Point3::new(25.0, 25.0, 25.0), 0.0, 0.0);
+        let config = default_secondary_view_config(64, 64);
+        let visible = cull_secondary_view(&camera, &config);
+        assert!(visible.contains(&ChunkPos::new(0, 0, 0)));
+        assert!(visible.contains(&ChunkPos::new(2, 0, 0)));
+        assert!(!visible.contains(&ChunkPos::new(-2, 0, 0)));
+        assert!(visible.len() < chunks_in_view_distance(&camera, 50, 4).len());
+    }
+}
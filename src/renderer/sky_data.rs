@@ -0,0 +1,82 @@
+//! Sky Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! All transformations happen in sky_operations.rs
+//!
+//! Procedural sky drawn as a fullscreen triangle before the world pass: a
+//! horizon-to-zenith gradient driven by the time of day, sun and moon discs,
+//! a star field at night and a grey tint for cloud coverage from the weather.
+//! The horizon color doubles as the fog color for the passes drawn after it.
+
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use bytemuck::{Pod, Zeroable};
+
+/// Sky appearance settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkyConfig {
+    /// Sun disc angular radius (radians)
+    pub sun_angular_radius: f32,
+    /// Moon disc angular radius (radians)
+    pub moon_angular_radius: f32,
+    /// Fraction of star grid cells holding a star
+    pub star_density: f32,
+    /// Draw stars at night
+    pub stars_enabled: bool,
+}
+
+/// Colors derived from the time of day and weather for one frame
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SkyColors {
+    pub zenith: [f32; 3],
+    pub horizon: [f32; 3],
+    /// Color distant geometry fades into (horizon after cloud tint)
+    pub fog: [f32; 3],
+    pub sun: [f32; 3],
+    pub moon: [f32; 3],
+    /// Unit vector toward the sun (below the horizon at night)
+    pub sun_direction: [f32; 3],
+    /// Unit vector toward the moon
+    pub moon_direction: [f32; 3],
+    /// 0 in daylight, 1 at full night
+    pub star_visibility: f32,
+    /// 0 = clear sky, 1 = overcast
+    pub cloud_coverage: f32,
+}
+
+/// Uniform read by the sky shader
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct SkyUniform {
+    /// Inverse of projection * rotation-only view; maps clip space to view rays
+    pub inv_view_proj: [[f32; 4]; 4],
+    /// xyz = direction, w = cosine of the disc angular radius
+    pub sun_direction: [f32; 4],
+    /// xyz = direction, w = cosine of the disc angular radius
+    pub moon_direction: [f32; 4],
+    /// rgb, a = star visibility
+    pub zenith_color: [f32; 4],
+    /// rgb, a = cloud coverage
+    pub horizon_color: [f32; 4],
+    /// rgb, a = star grid cells per unit of view direction
+    pub sun_color: [f32; 4],
+    /// rgb, a = star density
+    pub moon_color: [f32; 4],
+}
+
+/// GPU resources and state for the sky pass
+pub struct SkyData {
+    pub config: SkyConfig,
+    pub colors: SkyColors,
+    pub uniform: SkyUniform,
+
+    pub uniform_buffer: wgpu::Buffer,
+    pub render_pipeline: wgpu::RenderPipeline,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub bind_group: wgpu::BindGroup,
+    /// Tracks `uniform_buffer` under the `sky` owner
+    pub gpu_allocations: Vec<GpuAllocationGuard>,
+    pub gpu_owner: GpuOwnerGuard,
+
+    /// False until the first update has written the uniform
+    pub updated: bool,
+}
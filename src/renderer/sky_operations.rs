@@ -0,0 +1,373 @@
+//! Sky Operations - Pure DOP
+//!
+//! Functions that derive sky colors from the time of day and weather, and
+//! create, update and draw the sky pass.
+
+use super::error::RendererResult;
+use super::sky_data::{SkyColors, SkyConfig, SkyData, SkyUniform};
+use crate::camera::{build_projection_matrix, build_view_matrix, CameraData};
+use crate::constants::{sky, weather};
+use crate::memory::{create_tracked_buffer_init, register_global_gpu_owner};
+use crate::world::lighting::{calculate_sun_color, TimeOfDayData};
+use crate::world::WeatherData;
+use cgmath::{Matrix4, SquareMatrix, Vector4};
+use wgpu::util::DeviceExt;
+
+const DAY_ZENITH: [f32; 3] = [0.22, 0.45, 0.88];
+const DAY_HORIZON: [f32; 3] = [0.68, 0.82, 0.96];
+const NIGHT_ZENITH: [f32; 3] = [0.005, 0.008, 0.03];
+const NIGHT_HORIZON: [f32; 3] = [0.04, 0.05, 0.11];
+const TWILIGHT_HORIZON: [f32; 3] = [0.95, 0.52, 0.28];
+const MOON_COLOR: [f32; 3] = [0.82, 0.85, 0.95];
+const CLOUD_GREY: [f32; 3] = [0.62, 0.64, 0.68];
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn mix(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+    ]
+}
+
+/// Default sky settings
+pub fn default_sky_config() -> SkyConfig {
+    SkyConfig {
+        sun_angular_radius: sky::SUN_ANGULAR_RADIUS,
+        moon_angular_radius: sky::MOON_ANGULAR_RADIUS,
+        star_density: sky::STAR_DENSITY,
+        stars_enabled: true,
+    }
+}
+
+/// Direction toward the sun over the full day.
+///
+/// Matches `calculate_sun_direction` between sunrise and sunset but keeps
+/// turning below the horizon at night instead of clamping.
+pub fn sky_sun_direction(time: &TimeOfDayData) -> [f32; 3] {
+    let angle = (time.hours - 6.0) / 12.0 * std::f32::consts::PI;
+    [angle.cos(), angle.sin(), 0.0]
+}
+
+/// Cloud coverage (0-1) implied by the weather
+pub fn cloud_coverage(weather_data: &WeatherData) -> f32 {
+    let weather_type = weather_data.weather_type_intensity & 0xFF;
+    let intensity = ((weather_data.weather_type_intensity >> 8) & 0xFF) as f32 / 255.0;
+    let coverage = match weather_type {
+        weather::WEATHER_CLEAR => {
+            weather_data.humidity as f32 / 10000.0 * sky::CLEAR_HUMIDITY_COVERAGE
+        }
+        weather::WEATHER_SANDSTORM => 0.3 + 0.5 * intensity,
+        _ => 0.4 + 0.6 * intensity,
+    };
+    coverage.clamp(0.0, 1.0)
+}
+
+/// Sky colors for a time of day and weather
+pub fn calculate_sky_colors(time: &TimeOfDayData, weather_data: &WeatherData) -> SkyColors {
+    let sun_direction = sky_sun_direction(time);
+    let moon_direction = [-sun_direction[0], -sun_direction[1], -sun_direction[2]];
+    let elevation = sun_direction[1];
+
+    let daylight = smoothstep(sky::NIGHT_ELEVATION, sky::DAYLIGHT_ELEVATION, elevation);
+    let twilight = 1.0 - smoothstep(0.0, sky::DAYLIGHT_ELEVATION, elevation.abs());
+
+    let zenith = mix(NIGHT_ZENITH, DAY_ZENITH, daylight);
+    let horizon = mix(
+        mix(NIGHT_HORIZON, DAY_HORIZON, daylight),
+        TWILIGHT_HORIZON,
+        twilight * 0.7,
+    );
+
+    // Clouds pull the sky toward a grey that darkens with the daylight
+    let coverage = cloud_coverage(weather_data);
+    let cloud = mix(NIGHT_HORIZON, CLOUD_GREY, daylight);
+    let tint = coverage * sky::MAX_CLOUD_TINT;
+    let zenith = mix(zenith, cloud, tint);
+    let horizon = mix(horizon, cloud, tint);
+
+    // Poor visibility (fog, heavy precipitation) thickens the fog toward grey
+    let visibility = (weather_data.visibility as f32 / 1000.0).clamp(0.0, 1.0);
+    let fog = mix(horizon, cloud, (1.0 - visibility) * 0.5);
+
+    let star_visibility = (1.0
+        - smoothstep(sky::NIGHT_ELEVATION, sky::STAR_FADE_ELEVATION, elevation))
+        * (1.0 - coverage);
+
+    SkyColors {
+        zenith,
+        horizon,
+        fog,
+        sun: calculate_sun_color(time),
+        moon: MOON_COLOR,
+        sun_direction,
+        moon_direction,
+        star_visibility,
+        cloud_coverage: coverage,
+    }
+}
+
+fn build_sky_uniform(
+    config: &SkyConfig,
+    colors: &SkyColors,
+    inv_view_proj: Matrix4<f32>,
+) -> SkyUniform {
+    let [sx, sy, sz] = colors.sun_direction;
+    let [mx, my, mz] = colors.moon_direction;
+    let star_density = if config.stars_enabled {
+        config.star_density
+    } else {
+        0.0
+    };
+    SkyUniform {
+        inv_view_proj: inv_view_proj.into(),
+        sun_direction: [sx, sy, sz, config.sun_angular_radius.cos()],
+        moon_direction: [mx, my, mz, config.moon_angular_radius.cos()],
+        zenith_color: [
+            colors.zenith[0],
+            colors.zenith[1],
+            colors.zenith[2],
+            colors.star_visibility,
+        ],
+        horizon_color: [
+            colors.horizon[0],
+            colors.horizon[1],
+            colors.horizon[2],
+            colors.cloud_coverage,
+        ],
+        sun_color: [
+            colors.sun[0],
+            colors.sun[1],
+            colors.sun[2],
+            sky::STAR_GRID_SCALE,
+        ],
+        moon_color: [colors.moon[0], colors.moon[1], colors.moon[2], star_density],
+    }
+}
+
+fn create_sky_pipeline(
+    device: &wgpu::Device,
+    bind_group_layout: &wgpu::BindGroupLayout,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<wgpu::RenderPipeline> {
+    let shader = crate::gpu::automation::create_gpu_shader(
+        device,
+        "sky",
+        include_str!("../shaders/rendering/sky.wgsl"),
+    )
+    .map_err(|e| format!("Failed to create sky shader: {}", e))?;
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Sky Pipeline Layout"),
+        bind_group_layouts: &[bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    Ok(device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Sky Render Pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader.module,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader.module,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: color_format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            cull_mode: None,
+            ..Default::default()
+        },
+        // Drawn at the far plane without writing depth, so the world covers it
+        depth_stencil: depth_format.map(|format| wgpu::DepthStencilState {
+            format,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::LessEqual,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+    }))
+}
+
+/// Create the sky pipeline; `sample_count` must match the main pass
+pub fn create_sky(
+    device: &wgpu::Device,
+    config: SkyConfig,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<SkyData> {
+    let uniform = build_sky_uniform(&config, &SkyColors::default(), Matrix4::identity());
+    let gpu_owner = register_global_gpu_owner("sky");
+    let (uniform_buffer, uniform_allocation) = create_tracked_buffer_init(
+        device,
+        &gpu_owner,
+        &wgpu::util::BufferInitDescriptor {
+            label: Some("Sky Uniform Buffer"),
+            contents: bytemuck::bytes_of(&uniform),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        },
+    );
+
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Sky Bind Group Layout"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+    });
+
+    let render_pipeline = create_sky_pipeline(
+        device,
+        &bind_group_layout,
+        color_format,
+        depth_format,
+        sample_count,
+    )?;
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Sky Bind Group"),
+        layout: &bind_group_layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: uniform_buffer.as_entire_binding(),
+        }],
+    });
+
+    Ok(SkyData {
+        config,
+        colors: SkyColors::default(),
+        uniform,
+        uniform_buffer,
+        render_pipeline,
+        bind_group_layout,
+        bind_group,
+        gpu_allocations: vec![uniform_allocation],
+        gpu_owner,
+        updated: false,
+    })
+}
+
+/// Recreate the sky pipeline for a new target format or MSAA sample count,
+/// keeping the current colors
+pub fn rebuild_sky_pipeline(
+    data: &mut SkyData,
+    device: &wgpu::Device,
+    color_format: wgpu::TextureFormat,
+    depth_format: Option<wgpu::TextureFormat>,
+    sample_count: u32,
+) -> RendererResult<()> {
+    data.render_pipeline = create_sky_pipeline(
+        device,
+        &data.bind_group_layout,
+        color_format,
+        depth_format,
+        sample_count,
+    )?;
+    Ok(())
+}
+
+/// Update the sky for this frame from the time of day, weather and camera
+pub fn update_sky(
+    data: &mut SkyData,
+    queue: &wgpu::Queue,
+    time: &TimeOfDayData,
+    weather_data: &WeatherData,
+    camera: &CameraData,
+) {
+    let colors = calculate_sky_colors(time, weather_data);
+    update_sky_colors(data, queue, &colors, camera);
+}
+
+/// Update the sky from already computed colors, e.g. a secondary view
+/// following the main sky from another camera
+pub fn update_sky_colors(
+    data: &mut SkyData,
+    queue: &wgpu::Queue,
+    colors: &SkyColors,
+    camera: &CameraData,
+) {
+    // The sky is infinitely far away: only the camera rotation matters
+    let mut view = build_view_matrix(camera);
+    view.w = Vector4::new(0.0, 0.0, 0.0, 1.0);
+    let inv_view_proj = (build_projection_matrix(camera) * view)
+        .invert()
+        .unwrap_or_else(Matrix4::identity);
+
+    data.colors = *colors;
+    data.uniform = build_sky_uniform(&data.config, &data.colors, inv_view_proj);
+    queue.write_buffer(&data.uniform_buffer, 0, bytemuck::bytes_of(&data.uniform));
+    data.updated = true;
+}
+
+/// Color distant geometry should fade into
+pub fn sky_fog_color(data: &SkyData) -> [f32; 3] {
+    data.colors.fog
+}
+
+/// Draw the sky; call first in the frame's color pass, before the world
+pub fn render_sky<'a>(data: &'a SkyData, pass: &mut wgpu::RenderPass<'a>) {
+    if !data.updated {
+        return;
+    }
+    pass.set_pipeline(&data.render_pipeline);
+    pass.set_bind_group(0, &data.bind_group, &[]);
+    pass.draw(0..3, 0..1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::lighting::{midnight_time, noon_time};
+
+    #[test]
+    fn test_day_and_night_colors() {
+        let clear = WeatherData::clear();
+
+        let noon = calculate_sky_colors(&noon_time(), &clear);
+        assert!(noon.sun_direction[1] > 0.99);
+        assert!(noon.star_visibility < 0.01);
+        assert!(noon.zenith[2] > noon.zenith[0]);
+
+        let midnight = calculate_sky_colors(&midnight_time(), &clear);
+        assert!(midnight.sun_direction[1] < -0.99);
+        assert!(midnight.moon_direction[1] > 0.99);
+        assert!(midnight.star_visibility > 0.5);
+        assert!(midnight.fog[2] < noon.fog[2]);
+    }
+
+    #[test]
+    fn test_clouds_hide_stars() {
+        let mut storm = WeatherData::clear();
+        storm.weather_type_intensity = weather::WEATHER_STORM | (weather::INTENSITY_EXTREME << 8);
+        assert!(cloud_coverage(&storm) > 0.99);
+        assert!(cloud_coverage(&WeatherData::clear()) < sky::CLEAR_HUMIDITY_COVERAGE);
+
+        let colors = calculate_sky_colors(&midnight_time(), &storm);
+        assert!(colors.star_visibility < 0.01);
+    }
+}
@@ -0,0 +1,88 @@
+//! Smooth Terrain Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Field building and tessellation live in smooth_terrain_operations.rs
+//!
+//! Optional terrain mode where blocks flagged smooth (grass, dirt, sand)
+//! are drawn as a continuous surface instead of cubes. Each chunk holding
+//! smooth blocks keeps a signed distance field sampled at voxel centers,
+//! padded past its faces. The adaptive tessellator projects a grid onto
+//! the field's zero crossing. Where the surface runs over a cubic block,
+//! vertices are snapped onto that block's face so the two meshes meet
+//! without a gap. Vertices next to another material carry a blend toward
+//! it, so textures fade across material boundaries instead of cutting.
+
+use super::adaptive_tessellation::TessellationParams;
+use crate::gpu::buffer_layouts::mesh::Vertex;
+use crate::world::core::{BlockId, ChunkPos};
+use std::collections::{HashMap, HashSet};
+
+/// Set of block types
+pub type SmoothBlockSet = HashSet<BlockId>;
+
+/// Smooth terrain settings
+#[derive(Debug, Clone)]
+pub struct SmoothTerrainConfig {
+    /// Off by default; every block is then meshed as a cube
+    pub enabled: bool,
+    /// Blocks drawn as a smooth surface
+    pub smooth_blocks: SmoothBlockSet,
+    /// Non-air blocks the surface passes through (fluids, plants)
+    pub open_blocks: SmoothBlockSet,
+    pub tessellation: TessellationParams,
+    /// Distances are clamped to this many voxels
+    pub max_distance: f32,
+}
+
+/// Signed distance field of one chunk, negative inside solid blocks.
+/// Samples sit at voxel centers, `padding` voxels past every chunk face,
+/// indexed `x + y * n + z * n * n` with `n = samples_per_side`.
+#[derive(Debug, Clone)]
+pub struct ChunkSdf {
+    pub chunk_pos: ChunkPos,
+    pub chunk_size: u32,
+    pub padding: u32,
+    /// `chunk_size + 2 * padding`
+    pub samples_per_side: u32,
+    pub distances: Vec<f32>,
+    /// Block at each sample
+    pub materials: Vec<BlockId>,
+}
+
+/// Smooth surface of one chunk, in the chunk mesh's space; drawn with
+/// the chunk's own mesh
+#[derive(Debug, Clone, Default)]
+pub struct SmoothChunkMesh {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    /// Block under each vertex, for picking its texture
+    pub materials: Vec<BlockId>,
+    /// Blend of each vertex toward a neighboring material
+    pub blends: Vec<MaterialBlend>,
+    /// Vertices snapped onto a cubic block's face
+    pub seam_vertices: usize,
+}
+
+/// How much of a neighboring material a vertex's texture mixes in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaterialBlend {
+    /// Most common other material among the vertex's triangle neighbors;
+    /// the vertex's own material when it has none
+    pub neighbor: BlockId,
+    /// 0 inside a material, up to 0.5 on the boundary, where both sides
+    /// meet halfway
+    pub weight: f32,
+}
+
+/// Smooth meshes of several chunks
+pub type SmoothChunkMeshes = Vec<(ChunkPos, SmoothChunkMesh)>;
+
+/// Distance fields of every chunk with a smooth surface
+#[derive(Debug, Clone)]
+pub struct SmoothTerrainData {
+    pub config: SmoothTerrainConfig,
+    /// Chunks without smooth blocks near their surface are absent
+    pub fields: HashMap<ChunkPos, ChunkSdf>,
+    /// Chunks whose smooth mesh must be rebuilt (or dropped)
+    pub remesh: HashSet<ChunkPos>,
+}
@@ -0,0 +1,657 @@
+//! Smooth Terrain Operations - Pure functions over SmoothTerrainData
+//!
+//! `update_chunk_sdf` rebuilds a chunk's distance field from its blocks
+//! and queues the chunk. The mesher drains the queue with
+//! `take_smooth_remesh_chunks` and tessellates those chunks with
+//! `tessellate_smooth_chunks`. It meshes the cubes through
+//! `cubic_mesh_block`, which hides smooth blocks while the mode is on.
+//!
+//! The field treats every solid block as inside, cubic ones included, so
+//! the smooth surface runs up to cubic neighbors instead of dipping away
+//! from them. Triangles are kept only where at least one corner lies on a
+//! smooth block. Corners lying on a cubic block are snapped onto its face
+//! plane and take its face normal, which blends the seam into the cube.
+//! Where triangles span two materials, each vertex blends toward the
+//! other one by the share of its neighbors made of it.
+
+use super::adaptive_tessellation::{
+    AdaptiveTessellator, SurfaceField, TessellationParams, TessellationView,
+};
+use super::smooth_terrain_data::{
+    ChunkSdf, MaterialBlend, SmoothBlockSet, SmoothChunkMesh, SmoothChunkMeshes,
+    SmoothTerrainConfig, SmoothTerrainData,
+};
+use crate::constants::smooth_terrain::{MAX_SDF_DISTANCE, SDF_PADDING, SURFACE_TOLERANCE};
+use crate::gpu::buffer_layouts::mesh::Vertex;
+use crate::world::core::{BlockId, ChunkPos};
+use cgmath::Vector3;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Natural ground blocks
+const DEFAULT_SMOOTH_BLOCKS: [BlockId; 4] = [
+    BlockId::GRASS,
+    BlockId::DIRT,
+    BlockId::SAND,
+    BlockId::RED_SAND,
+];
+
+/// Fluids and plants the surface passes through
+const DEFAULT_OPEN_BLOCKS: [BlockId; 12] = [
+    BlockId::WATER,
+    BlockId::LAVA,
+    BlockId::TALL_GRASS,
+    BlockId::FLOWER_RED,
+    BlockId::FLOWER_YELLOW,
+    BlockId::DEAD_BUSH,
+    BlockId::MUSHROOM_RED,
+    BlockId::MUSHROOM_BROWN,
+    BlockId::SUGAR_CANE,
+    BlockId::VINES,
+    BlockId::TORCH,
+    BlockId::LADDER,
+];
+
+/// Face neighbors of a sample
+const FACE_OFFSETS: [[i32; 3]; 6] = [
+    [1, 0, 0],
+    [-1, 0, 0],
+    [0, 1, 0],
+    [0, -1, 0],
+    [0, 0, 1],
+    [0, 0, -1],
+];
+
+/// Tessellated vertex with the block under it and whether it was
+/// snapped onto a cubic block
+type PlacedVertex = (Vertex, BlockId, bool);
+
+/// Neighbor offset and step length of the chamfer transform
+type ChamferStep = ([i32; 3], f32);
+
+impl SurfaceField for ChunkSdf {
+    fn sample(&self, pos: Vector3<f32>) -> f32 {
+        sample_chunk_sdf(self, pos)
+    }
+}
+
+/// Mode off; ground blocks smooth once enabled
+pub fn default_smooth_terrain_config() -> SmoothTerrainConfig {
+    SmoothTerrainConfig {
+        enabled: false,
+        smooth_blocks: SmoothBlockSet::from(DEFAULT_SMOOTH_BLOCKS),
+        open_blocks: SmoothBlockSet::from(DEFAULT_OPEN_BLOCKS),
+        tessellation: TessellationParams::default(),
+        max_distance: MAX_SDF_DISTANCE,
+    }
+}
+
+/// Tracker with no chunks
+pub fn create_smooth_terrain(config: SmoothTerrainConfig) -> SmoothTerrainData {
+    SmoothTerrainData {
+        config,
+        fields: HashMap::new(),
+        remesh: HashSet::new(),
+    }
+}
+
+/// Turn the mode on or off. Turning it off drops every field and queues
+/// those chunks so their smooth meshes are removed; after turning it on,
+/// loaded chunks need `update_chunk_sdf` and a cubic remesh.
+pub fn set_smooth_terrain_enabled(data: &mut SmoothTerrainData, enabled: bool) {
+    if data.config.enabled == enabled {
+        return;
+    }
+    data.config.enabled = enabled;
+    if !enabled {
+        data.remesh.extend(data.fields.drain().map(|(pos, _)| pos));
+    }
+}
+
+/// Whether a block is drawn as part of the smooth surface
+pub fn is_smooth_block(config: &SmoothTerrainConfig, block: BlockId) -> bool {
+    config.smooth_blocks.contains(&block)
+}
+
+/// Whether a block counts as inside the distance field
+pub fn is_field_solid(config: &SmoothTerrainConfig, block: BlockId) -> bool {
+    block != BlockId::AIR && !config.open_blocks.contains(&block)
+}
+
+/// Block the cubic mesher should see: air for smooth blocks while the
+/// mode is on, so their cubes are not drawn and cubic neighbors keep the
+/// faces the smooth surface ends against
+pub fn cubic_mesh_block(config: &SmoothTerrainConfig, block: BlockId) -> BlockId {
+    if config.enabled && is_smooth_block(config, block) {
+        BlockId::AIR
+    } else {
+        block
+    }
+}
+
+/// `MeshingParams::smooth_blocks` of the GPU mesher: one bit per smooth
+/// block id below 32, none while the mode is off. Smooth blocks with
+/// larger ids stay cubes too.
+pub fn smooth_block_mask(config: &SmoothTerrainConfig) -> u32 {
+    if !config.enabled {
+        return 0;
+    }
+    config
+        .smooth_blocks
+        .iter()
+        .filter(|block| block.0 < u32::BITS as u16)
+        .fold(0, |mask, block| mask | (1 << block.0))
+}
+
+/// Distance field of a chunk, `None` when no smooth block in it touches
+/// the surface.
+///
+/// `block_at(x, y, z)` gives the block at a world voxel; it is also read
+/// `SDF_PADDING` voxels past every chunk face. Distances come from a
+/// two-pass chamfer transform seeded half a voxel from every boundary.
+pub fn build_chunk_sdf(
+    config: &SmoothTerrainConfig,
+    chunk_pos: ChunkPos,
+    chunk_size: u32,
+    block_at: impl Fn(i32, i32, i32) -> BlockId,
+) -> Option<ChunkSdf> {
+    let padding = SDF_PADDING;
+    let n = (chunk_size + 2 * padding) as i32;
+    let origin = [chunk_pos.x, chunk_pos.y, chunk_pos.z].map(|c| c * chunk_size as i32);
+
+    let mut materials = Vec::with_capacity((n * n * n) as usize);
+    for z in 0..n {
+        for y in 0..n {
+            for x in 0..n {
+                let [ox, oy, oz] = origin.map(|o| o - padding as i32);
+                materials.push(block_at(ox + x, oy + y, oz + z));
+            }
+        }
+    }
+    let solid: Vec<bool> = materials
+        .iter()
+        .map(|&block| is_field_solid(config, block))
+        .collect();
+
+    let in_chunk = |c: i32| c >= padding as i32 && c < (padding + chunk_size) as i32;
+    let mut distances = vec![f32::INFINITY; materials.len()];
+    let mut smooth_surface = false;
+    for z in 0..n {
+        for y in 0..n {
+            for x in 0..n {
+                let index = sample_index(n, [x, y, z]);
+                let boundary = FACE_OFFSETS.iter().any(|[dx, dy, dz]| {
+                    let neighbor = [x + dx, y + dy, z + dz];
+                    neighbor.iter().all(|c| (0..n).contains(c))
+                        && solid[sample_index(n, neighbor)] != solid[index]
+                });
+                if !boundary {
+                    continue;
+                }
+                distances[index] = 0.5;
+                smooth_surface |= is_smooth_block(config, materials[index])
+                    && in_chunk(x)
+                    && in_chunk(y)
+                    && in_chunk(z);
+            }
+        }
+    }
+    if !smooth_surface {
+        return None;
+    }
+
+    chamfer_distances(&mut distances, n);
+    for (distance, &inside) in distances.iter_mut().zip(&solid) {
+        let clamped = distance.min(config.max_distance);
+        *distance = if inside { -clamped } else { clamped };
+    }
+
+    Some(ChunkSdf {
+        chunk_pos,
+        chunk_size,
+        padding,
+        samples_per_side: n as u32,
+        distances,
+        materials,
+    })
+}
+
+/// Rebuild a chunk's field after it loads or its blocks change, and
+/// queue it for remeshing. Returns whether the chunk has a smooth surface.
+/// Edits within `SDF_PADDING` of a face change the neighbor's field too.
+pub fn update_chunk_sdf(
+    data: &mut SmoothTerrainData,
+    chunk_pos: ChunkPos,
+    chunk_size: u32,
+    block_at: impl Fn(i32, i32, i32) -> BlockId,
+) -> bool {
+    if !data.config.enabled {
+        return false;
+    }
+    match build_chunk_sdf(&data.config, chunk_pos, chunk_size, block_at) {
+        Some(field) => {
+            data.fields.insert(chunk_pos, field);
+            data.remesh.insert(chunk_pos);
+            true
+        }
+        None => {
+            if data.fields.remove(&chunk_pos).is_some() {
+                data.remesh.insert(chunk_pos);
+            }
+            false
+        }
+    }
+}
+
+/// Forget an unloaded chunk
+pub fn remove_chunk_sdf(data: &mut SmoothTerrainData, chunk_pos: ChunkPos) {
+    data.fields.remove(&chunk_pos);
+    data.remesh.remove(&chunk_pos);
+}
+
+/// Chunks whose smooth meshes must be rebuilt, emptying the queue
+pub fn take_smooth_remesh_chunks(data: &mut SmoothTerrainData) -> Vec<ChunkPos> {
+    data.remesh.drain().collect()
+}
+
+/// Signed distance at a chunk-local position, trilinear between samples
+pub fn sample_chunk_sdf(sdf: &ChunkSdf, pos: Vector3<f32>) -> f32 {
+    let n = sdf.samples_per_side as i32;
+    let grid: [f32; 3] =
+        [pos.x, pos.y, pos.z].map(|c| (c + sdf.padding as f32 - 0.5).clamp(0.0, (n - 1) as f32));
+    let base = grid.map(|c| (c.floor() as i32).min(n - 2).max(0));
+    let t: [f32; 3] = std::array::from_fn(|axis| grid[axis] - base[axis] as f32);
+
+    let mut value = 0.0;
+    for corner in 0..8 {
+        let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+        let weight: f32 = (0..3)
+            .map(|axis| {
+                if offset[axis] == 1 {
+                    t[axis]
+                } else {
+                    1.0 - t[axis]
+                }
+            })
+            .product();
+        let sample = std::array::from_fn(|axis| base[axis] + offset[axis]);
+        value += weight
+            * sdf
+                .distances
+                .get(sample_index(n, sample))
+                .copied()
+                .unwrap_or(MAX_SDF_DISTANCE);
+    }
+    value
+}
+
+/// Block containing a chunk-local position (air outside the samples)
+pub fn chunk_sdf_material(sdf: &ChunkSdf, pos: Vector3<f32>) -> BlockId {
+    let n = sdf.samples_per_side as i32;
+    let sample = [pos.x, pos.y, pos.z].map(|c| (c + sdf.padding as f32).floor() as i32);
+    if sample.iter().any(|c| !(0..n).contains(c)) {
+        return BlockId::AIR;
+    }
+    sdf.materials
+        .get(sample_index(n, sample))
+        .copied()
+        .unwrap_or(BlockId::AIR)
+}
+
+/// Tessellate a chunk's smooth surface. `view` is relative to the chunk
+/// origin. The grid is projected down from the chunk top, so each column
+/// gets its topmost surface; surfaces at the top face belong to the
+/// chunk above.
+pub fn tessellate_smooth_chunk(
+    config: &SmoothTerrainConfig,
+    sdf: &ChunkSdf,
+    view: &TessellationView,
+) -> SmoothChunkMesh {
+    let size = sdf.chunk_size as f32;
+    let tessellator = AdaptiveTessellator::new(config.tessellation.clone());
+    let surface = tessellator.tessellate_field(
+        sdf,
+        Vector3::new(0.0, size, 0.0),
+        Vector3::new(size, size, size),
+        view,
+    );
+
+    // Place every vertex; `None` where projection missed the surface
+    let placed: Vec<Option<PlacedVertex>> = surface
+        .vertices
+        .iter()
+        .map(|vertex| {
+            let position = Vector3::from(vertex.position);
+            if sample_chunk_sdf(sdf, position).abs() > SURFACE_TOLERANCE {
+                return None;
+            }
+            let normal = Vector3::from(vertex.normal);
+            let block = chunk_sdf_material(sdf, position - normal * 0.5);
+            let mut placed = Vertex {
+                position: vertex.position,
+                normal: vertex.normal,
+                tex_coords: vertex.tex_coords,
+            };
+            let seam = !is_smooth_block(config, block) && is_field_solid(config, block);
+            if seam {
+                snap_to_block_face(&mut placed, position - normal * 0.5);
+            }
+            Some((placed, block, seam))
+        })
+        .collect();
+
+    let mut mesh = SmoothChunkMesh::default();
+    let mut remap: HashMap<u32, u32> = HashMap::new();
+    for triangle in surface.indices.chunks_exact(3) {
+        let Some(corners) = triangle
+            .iter()
+            .map(|&index| placed.get(index as usize).and_then(Option::as_ref))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+        let centroid_y = corners.iter().map(|c| c.0.position[1]).sum::<f32>() / 3.0;
+        if !(0.0..size).contains(&centroid_y)
+            || !corners.iter().any(|c| is_smooth_block(config, c.1))
+        {
+            continue;
+        }
+        for (&index, &&(vertex, block, seam)) in triangle.iter().zip(&corners) {
+            let remapped = *remap.entry(index).or_insert_with(|| {
+                mesh.vertices.push(vertex);
+                mesh.materials.push(block);
+                mesh.seam_vertices += usize::from(seam);
+                (mesh.vertices.len() - 1) as u32
+            });
+            mesh.indices.push(remapped);
+        }
+    }
+    mesh.blends = blend_materials(&mesh);
+    mesh
+}
+
+/// Blend of every vertex toward the most common other material among the
+/// corners of its triangles, weighted by that material's share of them
+fn blend_materials(mesh: &SmoothChunkMesh) -> Vec<MaterialBlend> {
+    let mut neighbors: Vec<Vec<BlockId>> = vec![Vec::new(); mesh.vertices.len()];
+    for triangle in mesh.indices.chunks_exact(3) {
+        for &corner in triangle {
+            for &other in triangle.iter().filter(|&&other| other != corner) {
+                neighbors[corner as usize].push(mesh.materials[other as usize]);
+            }
+        }
+    }
+    neighbors
+        .iter()
+        .zip(&mesh.materials)
+        .map(|(around, &own)| {
+            let mut counts: HashMap<BlockId, usize> = HashMap::new();
+            for &block in around.iter().filter(|&&block| block != own) {
+                *counts.entry(block).or_default() += 1;
+            }
+            match counts
+                .into_iter()
+                .max_by_key(|&(block, count)| (count, std::cmp::Reverse(block.0)))
+            {
+                Some((neighbor, count)) => MaterialBlend {
+                    neighbor,
+                    weight: 0.5 * count as f32 / around.len() as f32,
+                },
+                None => MaterialBlend {
+                    neighbor: own,
+                    weight: 0.0,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Tessellate several chunks in parallel; `view` is in world space.
+/// Chunks without a field get an empty mesh, so their old one is dropped.
+pub fn tessellate_smooth_chunks(
+    data: &SmoothTerrainData,
+    chunks: &[ChunkPos],
+    view: &TessellationView,
+) -> SmoothChunkMeshes {
+    chunks
+        .par_iter()
+        .map(|&chunk_pos| {
+            let Some(sdf) = data.fields.get(&chunk_pos) else {
+                return (chunk_pos, SmoothChunkMesh::default());
+            };
+            let size = sdf.chunk_size as f32;
+            let origin = Vector3::new(
+                chunk_pos.x as f32 * size,
+                chunk_pos.y as f32 * size,
+                chunk_pos.z as f32 * size,
+            );
+            let local_view = TessellationView {
+                view_pos: view.view_pos - origin,
+                ..*view
+            };
+            (
+                chunk_pos,
+                tessellate_smooth_chunk(&data.config, sdf, &local_view),
+            )
+        })
+        .collect()
+}
+
+fn sample_index(n: i32, [x, y, z]: [i32; 3]) -> usize {
+    (x + y * n + z * n * n) as usize
+}
+
+/// Two-pass chamfer distance transform over a cube of `n` samples per
+/// side, with 26-neighbor steps of length 1, √2 and √3
+fn chamfer_distances(distances: &mut [f32], n: i32) {
+    let mut backward = Vec::with_capacity(13);
+    for dz in -1..=1i32 {
+        for dy in -1..=1i32 {
+            for dx in -1..=1i32 {
+                if (dz, dy, dx) > (0, 0, 0) {
+                    let length = ((dx * dx + dy * dy + dz * dz) as f32).sqrt();
+                    backward.push(([dx, dy, dz], length));
+                }
+            }
+        }
+    }
+    let forward: Vec<ChamferStep> = backward
+        .iter()
+        .map(|&(offset, length)| (offset.map(|c| -c), length))
+        .collect();
+
+    let mut relax = |[x, y, z]: [i32; 3], steps: &[ChamferStep]| {
+        let index = sample_index(n, [x, y, z]);
+        for &([dx, dy, dz], length) in steps {
+            let neighbor = [x + dx, y + dy, z + dz];
+            if neighbor.iter().all(|c| (0..n).contains(c)) {
+                let through = distances[sample_index(n, neighbor)] + length;
+                if through < distances[index] {
+                    distances[index] = through;
+                }
+            }
+        }
+    };
+    for z in 0..n {
+        for y in 0..n {
+            for x in 0..n {
+                relax([x, y, z], &forward);
+            }
+        }
+    }
+    for z in (0..n).rev() {
+        for y in (0..n).rev() {
+            for x in (0..n).rev() {
+                relax([x, y, z], &backward);
+            }
+        }
+    }
+}
+
+/// Move a vertex onto the face of the block containing `inside` that its
+/// normal points out of, and give it that face's normal
+fn snap_to_block_face(vertex: &mut Vertex, inside: Vector3<f32>) {
+    let normal = vertex.normal;
+    let axis = (0..3)
+        .max_by(|&a, &b| normal[a].abs().total_cmp(&normal[b].abs()))
+        .unwrap_or(1);
+    let outward = if normal[axis] >= 0.0 { 1.0 } else { -1.0 };
+    let block = [inside.x, inside.y, inside.z][axis].floor();
+    vertex.position[axis] = if outward > 0.0 { block + 1.0 } else { block };
+    vertex.normal = [0.0; 3];
+    vertex.normal[axis] = outward;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHUNK_SIZE: u32 = 32;
+
+    fn enabled_config() -> SmoothTerrainConfig {
+        SmoothTerrainConfig {
+            enabled: true,
+            ..default_smooth_terrain_config()
+        }
+    }
+
+    fn view_above() -> TessellationView {
+        TessellationView {
+            view_pos: Vector3::new(16.0, 40.0, 16.0),
+            viewport_height: 1080.0,
+            fov: std::f32::consts::FRAC_PI_3,
+        }
+    }
+
+    #[test]
+    fn flat_ground_becomes_a_smooth_surface() {
+        let mut data = create_smooth_terrain(default_smooth_terrain_config());
+        let chunk = ChunkPos::new(0, 0, 0);
+        let ground = |_: i32, y: i32, _: i32| {
+            if y < 10 {
+                BlockId::DIRT
+            } else {
+                BlockId::AIR
+            }
+        };
+        assert!(!update_chunk_sdf(&mut data, chunk, CHUNK_SIZE, ground));
+        set_smooth_terrain_enabled(&mut data, true);
+        assert_eq!(cubic_mesh_block(&data.config, BlockId::DIRT), BlockId::AIR);
+        assert_eq!(
+            cubic_mesh_block(&data.config, BlockId::STONE),
+            BlockId::STONE
+        );
+
+        assert!(update_chunk_sdf(&mut data, chunk, CHUNK_SIZE, ground));
+        let sdf = &data.fields[&chunk];
+        assert!(sample_chunk_sdf(sdf, Vector3::new(5.0, 10.0, 5.0)).abs() < 1e-4);
+        assert!((sample_chunk_sdf(sdf, Vector3::new(5.0, 12.0, 5.0)) - 2.0).abs() < 1e-4);
+        assert!((sample_chunk_sdf(sdf, Vector3::new(5.0, 7.0, 5.0)) + 3.0).abs() < 1e-4);
+
+        let remesh = take_smooth_remesh_chunks(&mut data);
+        let meshes = tessellate_smooth_chunks(&data, &remesh, &view_above());
+        let [(pos, mesh)] = meshes.as_slice() else {
+            panic!("expected one chunk, got {}", meshes.len());
+        };
+        assert_eq!(*pos, chunk);
+        assert!(!mesh.indices.is_empty());
+        assert_eq!(mesh.seam_vertices, 0);
+        assert!(mesh.materials.iter().all(|&block| block == BlockId::DIRT));
+        assert!(mesh
+            .vertices
+            .iter()
+            .all(|v| (v.position[1] - 10.0).abs() < SURFACE_TOLERANCE && v.normal[1] > 0.99));
+
+        // Stone only: nothing smooth, and turning the mode off drops fields
+        let stone = |_: i32, y: i32, _: i32| {
+            if y < 10 {
+                BlockId::STONE
+            } else {
+                BlockId::AIR
+            }
+        };
+        assert!(build_chunk_sdf(&data.config, chunk, CHUNK_SIZE, stone).is_none());
+        set_smooth_terrain_enabled(&mut data, false);
+        assert!(data.fields.is_empty());
+        assert_eq!(take_smooth_remesh_chunks(&mut data), vec![chunk]);
+    }
+
+    #[test]
+    fn surface_meets_cubic_blocks_flush() {
+        let config = enabled_config();
+        let chunk = ChunkPos::new(0, 0, 0);
+        // Dirt on the low-x half, a stone step one voxel higher beside it
+        let terrain = |x: i32, y: i32, _: i32| match (x < 16, y) {
+            (true, y) if y < 10 => BlockId::DIRT,
+            (false, y) if y < 11 => BlockId::STONE,
+            _ => BlockId::AIR,
+        };
+        let sdf = build_chunk_sdf(&config, chunk, CHUNK_SIZE, terrain).expect("smooth surface");
+        let mesh = tessellate_smooth_chunk(&config, &sdf, &view_above());
+
+        assert!(!mesh.indices.is_empty());
+        assert!(mesh.seam_vertices > 0);
+        assert_eq!(mesh.indices.len() % 3, 0);
+        for (vertex, &block) in mesh.vertices.iter().zip(&mesh.materials) {
+            if block == BlockId::STONE {
+                // Snapped onto a stone face: the top at 11 or the side at 16
+                let on_top = vertex.position[1] == 11.0 && vertex.normal == [0.0, 1.0, 0.0];
+                let on_side = vertex.position[0] == 16.0 && vertex.normal == [-1.0, 0.0, 0.0];
+                assert!(on_top || on_side, "{:?}", vertex);
+            } else {
+                assert_eq!(block, BlockId::DIRT);
+                assert!(vertex.position[0] <= 16.5);
+            }
+        }
+        // Snapped stone vertices blend toward the dirt they border
+        assert_eq!(mesh.blends.len(), mesh.vertices.len());
+        assert!(mesh
+            .blends
+            .iter()
+            .zip(&mesh.materials)
+            .filter(|(_, &block)| block == BlockId::STONE)
+            .all(|(blend, _)| blend.neighbor == BlockId::DIRT && blend.weight > 0.0));
+        // No triangle lies entirely on stone
+        assert!(mesh.indices.chunks_exact(3).all(|t| t
+            .iter()
+            .any(|&i| mesh.materials[i as usize] == BlockId::DIRT)));
+    }
+
+    #[test]
+    fn materials_blend_across_their_boundary() {
+        let config = enabled_config();
+        assert_eq!(
+            smooth_block_mask(&config),
+            (1 << 1) | (1 << 2) | (1 << 5) | (1 << 24)
+        );
+        assert_eq!(smooth_block_mask(&default_smooth_terrain_config()), 0);
+
+        // Flat ground, dirt on the low-x half and sand on the other
+        let terrain = |x: i32, y: i32, _: i32| match (y < 10, x < 16) {
+            (true, true) => BlockId::DIRT,
+            (true, false) => BlockId::SAND,
+            _ => BlockId::AIR,
+        };
+        let chunk = ChunkPos::new(0, 0, 0);
+        let sdf = build_chunk_sdf(&config, chunk, CHUNK_SIZE, terrain).expect("smooth surface");
+        let mesh = tessellate_smooth_chunk(&config, &sdf, &view_above());
+
+        let mut blended = 0;
+        for ((vertex, &block), blend) in mesh.vertices.iter().zip(&mesh.materials).zip(&mesh.blends)
+        {
+            assert!((0.0..=0.5).contains(&blend.weight));
+            if (vertex.position[0] - 16.0).abs() > 4.0 {
+                assert_eq!(blend.weight, 0.0, "{:?}", vertex);
+            } else if blend.weight > 0.0 {
+                let other = if block == BlockId::DIRT {
+                    BlockId::SAND
+                } else {
+                    BlockId::DIRT
+                };
+                assert_eq!(blend.neighbor, other);
+                blended += 1;
+            }
+        }
+        assert!(blended > 0);
+    }
+}
@@ -6,6 +6,7 @@
 use super::soa_mesh_builder_data::{GreedyMeshBuilderSoAData, MeshBuilderSoAData, MeshBuilderStats};
 use super::vertex_soa_data::VertexBufferSoAData;
 use super::vertex_soa_operations;
+use crate::simd_operations::{all_voxels_equal, matching_run_length};
 use crate::BlockId;
 use std::collections::HashMap;
 
@@ -219,6 +220,10 @@ pub fn build_greedy_mesh(
     clear(&mut data.builder);
     data.visited.fill(false);
 
+    if all_voxels_equal(blocks, BlockId::AIR) {
+        return build_vertex_buffer(&data.builder);
+    }
+
     // Process each face direction for greedy meshing
     for axis in 0..3 {
         for direction in 0..2 {
@@ -412,6 +417,13 @@ fn find_quad_size(
     v_axis: usize,
     block_type: BlockId,
 ) -> (usize, usize) {
+    // Along x the voxels are contiguous, so whole rows compare at SIMD width
+    if u_axis == 0 {
+        return find_quad_size_contiguous(
+            data, blocks, chunk_size, axis, layer, start_u, start_v, v_axis, block_type,
+        );
+    }
+
     // Find width (expand in U direction)
     let mut width = 1;
     while start_u + width < chunk_size {
@@ -454,6 +466,57 @@ fn find_quad_size(
     (width, height)
 }
 
+/// Unvisited voxels at the start of `first..first + len` that equal `block`
+fn contiguous_run(
+    data: &GreedyMeshBuilderSoAData,
+    blocks: &[BlockId],
+    first: usize,
+    len: usize,
+    block: BlockId,
+) -> usize {
+    let end = (first + len).min(blocks.len()).min(data.visited.len());
+    if first >= end {
+        return 0;
+    }
+    let run = matching_run_length(&blocks[first..end], block);
+    data.visited[first..first + run]
+        .iter()
+        .position(|&visited| visited)
+        .unwrap_or(run)
+}
+
+/// `find_quad_size` for U along x, where a row is one slice of `blocks`
+fn find_quad_size_contiguous(
+    data: &GreedyMeshBuilderSoAData,
+    blocks: &[BlockId],
+    chunk_size: usize,
+    axis: usize,
+    layer: usize,
+    start_u: usize,
+    start_v: usize,
+    v_axis: usize,
+    block_type: BlockId,
+) -> (usize, usize) {
+    let row_start = |v: usize| get_block_index(data.chunk_size, axis, layer, start_u, v, 0, v_axis);
+
+    let width = 1 + contiguous_run(
+        data,
+        blocks,
+        row_start(start_v) + 1,
+        chunk_size - start_u - 1,
+        block_type,
+    );
+
+    let mut height = 1;
+    while start_v + height < chunk_size
+        && contiguous_run(data, blocks, row_start(start_v + height), width, block_type) == width
+    {
+        height += 1;
+    }
+
+    (width, height)
+}
+
 /// Generate a quad with the given parameters
 fn generate_quad(
     data: &mut GreedyMeshBuilderSoAData,
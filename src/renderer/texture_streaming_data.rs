@@ -0,0 +1,130 @@
+//! Texture Streaming Data - Pure DOP
+//!
+//! Large textures (block atlases, biome maps) keep only the mips they need
+//! resident in VRAM. Each texture's GPU copy holds the mip chain from its
+//! finest resident level down to 1x1; finer levels are uploaded over later
+//! frames as the camera gets close, and dropped again when the texture goes
+//! unused or the GPU memory budget runs short.
+//!
+//! NO METHODS - just data.
+
+use crate::memory::{GpuAllocationGuard, GpuOwnerGuard};
+use wgpu::{Texture, TextureFormat, TextureView};
+
+/// Handle of a streamed texture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureStreamId(pub u32);
+
+/// How a texture's mips may be streamed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureResidency {
+    /// Every mip stays resident (UI, small textures)
+    Pinned,
+    /// Finer mips stream in by camera distance
+    Streamed {
+        /// Coarsest mips that are always resident
+        min_resident_mips: u32,
+        /// Distance (voxels) within which mip 0 is wanted
+        full_detail_distance: f32,
+    },
+}
+
+/// One mip level kept on the CPU so it can be uploaded again after eviction
+#[derive(Debug, Clone, PartialEq)]
+pub struct MipLevelData {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA8 pixels, row-major
+    pub pixels: Vec<u8>,
+}
+
+/// GPU copy of a streamed texture, holding mips `resident_mip..`
+#[derive(Debug)]
+pub struct StreamedTextureGpu {
+    pub texture: Texture,
+    pub view: TextureView,
+    /// Record of the texture in the GPU allocation tracker
+    pub allocation: GpuAllocationGuard,
+}
+
+/// One texture managed by the streamer
+#[derive(Debug)]
+pub struct StreamedTexture {
+    pub id: TextureStreamId,
+    pub name: String,
+    pub residency: TextureResidency,
+    pub format: TextureFormat,
+    /// Mip 0 first
+    pub mips: Vec<MipLevelData>,
+    /// Finest mip on the GPU (`mips.len()` when nothing is resident)
+    pub resident_mip: u32,
+    /// Finest mip wanted after the latest update
+    pub target_mip: u32,
+    /// Closest reported use this frame (voxels)
+    pub nearest_distance: f32,
+    /// Closest use in the latest frame that reported one (voxels)
+    pub last_use_distance: f32,
+    pub last_used_frame: u64,
+    pub gpu: Option<StreamedTextureGpu>,
+    /// Bumped whenever `gpu` is replaced, so bind groups can be rebuilt
+    pub generation: u64,
+}
+
+/// GPU memory in use against the budget of the memory monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryBudgetView {
+    pub used_bytes: u64,
+    /// 0 = unknown (no eviction for memory pressure)
+    pub budget_bytes: u64,
+}
+
+/// Residency step decided by the streamer; `mip` is the new finest
+/// resident level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResidencyChange {
+    Upload { id: TextureStreamId, mip: u32 },
+    Evict { id: TextureStreamId, mip: u32 },
+}
+
+/// Streaming settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureStreamingConfig {
+    pub max_upload_bytes_per_frame: u64,
+    pub evict_memory_percent: f64,
+    pub restore_memory_percent: f64,
+    pub unused_frames: u64,
+}
+
+/// Streaming counters shown by the system monitor
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TextureStreamingStats {
+    pub textures: u32,
+    /// Bytes of all resident mips
+    pub resident_bytes: u64,
+    /// Bytes if every mip of every texture were resident
+    pub full_bytes: u64,
+    /// Bytes of wanted mips not uploaded yet
+    pub pending_upload_bytes: u64,
+    pub mips_uploaded: u64,
+    pub bytes_uploaded: u64,
+    pub mips_evicted: u64,
+    pub bytes_evicted: u64,
+    pub last_frame_upload_bytes: u64,
+    /// Whether eviction for memory pressure is active
+    pub memory_pressure: bool,
+}
+
+/// All streamed textures
+#[derive(Debug)]
+pub struct TextureStreamingData {
+    pub config: TextureStreamingConfig,
+    pub textures: Vec<StreamedTexture>,
+    pub next_id: u32,
+    pub frame: u64,
+    /// Set above `evict_memory_percent`, cleared below `restore_memory_percent`
+    pub memory_pressure: bool,
+    pub stats: TextureStreamingStats,
+    /// Owner of the streamed textures in the GPU allocation tracker, which
+    /// accounts their resident bytes in `MetricsBuffers::gpu_memory_usage`
+    pub gpu_owner: GpuOwnerGuard,
+}
@@ -0,0 +1,712 @@
+//! Texture Streaming Operations - Pure DOP functions
+//!
+//! Per frame: report where textures are used with `note_texture_use`, call
+//! `update_texture_streaming` with the memory monitor's budget view, then
+//! `apply_texture_streaming` to upload or evict mips within the per-frame
+//! upload budget, and `record_texture_streaming_metrics` so the monitor sees
+//! the residency and upload backlog.
+
+use super::texture_streaming_data::{
+    MemoryBudgetView, MipLevelData, ResidencyChange, StreamedTexture, StreamedTextureGpu,
+    TextureResidency, TextureStreamId, TextureStreamingConfig, TextureStreamingData,
+    TextureStreamingStats,
+};
+use crate::constants::texture_streaming::{
+    BLOCK_ATLAS_COLUMNS, BLOCK_ATLAS_EMPTY_TILE, BLOCK_ATLAS_TILE_SIZE,
+    DEFAULT_FULL_DETAIL_DISTANCE, DEFAULT_MIN_RESIDENT_MIPS, MAX_TEXTURE_UPLOAD_BYTES_PER_FRAME,
+    TEXTURE_EVICT_MEMORY_PERCENT, TEXTURE_RESTORE_MEMORY_PERCENT, TEXTURE_UNUSED_FRAMES,
+};
+use crate::engine_buffers::{EngineBuffers, MetricsBuffers};
+use crate::memory::{create_tracked_texture, register_global_gpu_owner, GpuOwnerGuard};
+use crate::system_monitor_data::SystemMonitorData;
+use crate::world::core::BlockRegistry;
+use image::imageops::FilterType;
+use image::RgbaImage;
+
+/// Defaults from `constants::texture_streaming`
+pub fn default_texture_streaming_config() -> TextureStreamingConfig {
+    TextureStreamingConfig {
+        max_upload_bytes_per_frame: MAX_TEXTURE_UPLOAD_BYTES_PER_FRAME,
+        evict_memory_percent: TEXTURE_EVICT_MEMORY_PERCENT,
+        restore_memory_percent: TEXTURE_RESTORE_MEMORY_PERCENT,
+        unused_frames: TEXTURE_UNUSED_FRAMES,
+    }
+}
+
+/// Streamed residency with the default distances
+pub fn default_streamed_residency() -> TextureResidency {
+    TextureResidency::Streamed {
+        min_resident_mips: DEFAULT_MIN_RESIDENT_MIPS,
+        full_detail_distance: DEFAULT_FULL_DETAIL_DISTANCE,
+    }
+}
+
+/// Create a streamer with no textures
+pub fn create_texture_streaming(config: TextureStreamingConfig) -> TextureStreamingData {
+    TextureStreamingData {
+        config,
+        textures: Vec::new(),
+        next_id: 0,
+        frame: 0,
+        memory_pressure: false,
+        stats: TextureStreamingStats::default(),
+        gpu_owner: register_global_gpu_owner("texture_streaming"),
+    }
+}
+
+/// Full mip chain of an image, halving down to 1x1
+pub fn build_mip_chain(image: &RgbaImage) -> Vec<MipLevelData> {
+    let mut mips = vec![MipLevelData {
+        width: image.width().max(1),
+        height: image.height().max(1),
+        pixels: image.as_raw().clone(),
+    }];
+    let mut previous = image.clone();
+    while previous.width() > 1 || previous.height() > 1 {
+        let width = (previous.width() / 2).max(1);
+        let height = (previous.height() / 2).max(1);
+        previous = image::imageops::resize(&previous, width, height, FilterType::Triangle);
+        mips.push(MipLevelData {
+            width,
+            height,
+            pixels: previous.as_raw().clone(),
+        });
+    }
+    mips
+}
+
+/// Block atlas: one `BLOCK_ATLAS_TILE_SIZE` tile per block texture id, in
+/// rows of `BLOCK_ATLAS_COLUMNS`, filled with the color of the first block
+/// registered with that id
+pub fn build_block_atlas(registry: &BlockRegistry) -> RgbaImage {
+    let registrations = registry.get_registrations();
+    let tiles = registrations
+        .iter()
+        .map(|registration| registration.properties.render_data.texture_id + 1)
+        .max()
+        .unwrap_or(1);
+    let rows = tiles.div_ceil(BLOCK_ATLAS_COLUMNS);
+    let mut atlas = RgbaImage::from_pixel(
+        BLOCK_ATLAS_COLUMNS * BLOCK_ATLAS_TILE_SIZE,
+        rows * BLOCK_ATLAS_TILE_SIZE,
+        image::Rgba(BLOCK_ATLAS_EMPTY_TILE),
+    );
+    let mut filled = vec![false; tiles as usize];
+    for registration in registrations {
+        let render = registration.properties.render_data;
+        let tile = render.texture_id;
+        if std::mem::replace(&mut filled[tile as usize], true) {
+            continue;
+        }
+        let [r, g, b] = render
+            .color
+            .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+        let left = tile % BLOCK_ATLAS_COLUMNS * BLOCK_ATLAS_TILE_SIZE;
+        let top = tile / BLOCK_ATLAS_COLUMNS * BLOCK_ATLAS_TILE_SIZE;
+        for y in top..top + BLOCK_ATLAS_TILE_SIZE {
+            for x in left..left + BLOCK_ATLAS_TILE_SIZE {
+                atlas.put_pixel(x, y, image::Rgba([r, g, b, 255]));
+            }
+        }
+    }
+    atlas
+}
+
+// ============================================================================
+// TEXTURES
+// ============================================================================
+
+/// Add a texture; nothing is resident until the next `apply_texture_streaming`
+pub fn register_streamed_texture(
+    data: &mut TextureStreamingData,
+    name: &str,
+    image: &RgbaImage,
+    residency: TextureResidency,
+) -> TextureStreamId {
+    let id = TextureStreamId(data.next_id);
+    data.next_id += 1;
+
+    let mips = build_mip_chain(image);
+    let mip_count = mips.len() as u32;
+    data.textures.push(StreamedTexture {
+        id,
+        name: name.to_string(),
+        residency,
+        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+        mips,
+        resident_mip: mip_count,
+        target_mip: mip_count,
+        nearest_distance: f32::INFINITY,
+        last_use_distance: f32::INFINITY,
+        last_used_frame: data.frame,
+        gpu: None,
+        generation: 0,
+    });
+    refresh_streaming_stats(data);
+    id
+}
+
+/// Remove a texture, freeing its GPU copy
+pub fn remove_streamed_texture(data: &mut TextureStreamingData, id: TextureStreamId) -> bool {
+    let before = data.textures.len();
+    data.textures.retain(|texture| texture.id != id);
+    refresh_streaming_stats(data);
+    data.textures.len() != before
+}
+
+/// Change how a texture streams; takes effect on the next update
+pub fn set_texture_residency(
+    data: &mut TextureStreamingData,
+    id: TextureStreamId,
+    residency: TextureResidency,
+) {
+    if let Some(texture) = find_texture_mut(data, id) {
+        texture.residency = residency;
+    }
+}
+
+/// Report that a texture is drawn `distance` voxels from the camera this frame
+pub fn note_texture_use(data: &mut TextureStreamingData, id: TextureStreamId, distance: f32) {
+    let frame = data.frame;
+    if let Some(texture) = find_texture_mut(data, id) {
+        texture.nearest_distance = texture.nearest_distance.min(distance.max(0.0));
+        texture.last_used_frame = frame;
+    }
+}
+
+/// Streamed texture by handle
+pub fn find_streamed_texture(
+    data: &TextureStreamingData,
+    id: TextureStreamId,
+) -> Option<&StreamedTexture> {
+    data.textures.iter().find(|texture| texture.id == id)
+}
+
+fn find_texture_mut(
+    data: &mut TextureStreamingData,
+    id: TextureStreamId,
+) -> Option<&mut StreamedTexture> {
+    data.textures.iter_mut().find(|texture| texture.id == id)
+}
+
+/// View of the resident mips (None until the first upload)
+pub fn streamed_texture_view(
+    data: &TextureStreamingData,
+    id: TextureStreamId,
+) -> Option<&wgpu::TextureView> {
+    find_streamed_texture(data, id)
+        .and_then(|texture| texture.gpu.as_ref())
+        .map(|gpu| &gpu.view)
+}
+
+/// Bytes of one mip level
+pub fn mip_bytes(texture: &StreamedTexture, mip: u32) -> u64 {
+    texture
+        .mips
+        .get(mip as usize)
+        .map_or(0, |level| level.pixels.len() as u64)
+}
+
+/// Bytes of the mips `from..to`
+fn mip_range_bytes(texture: &StreamedTexture, from: u32, to: u32) -> u64 {
+    (from..to).map(|mip| mip_bytes(texture, mip)).sum()
+}
+
+/// Bytes currently resident on the GPU
+pub fn resident_bytes(texture: &StreamedTexture) -> u64 {
+    mip_range_bytes(texture, texture.resident_mip, texture.mips.len() as u32)
+}
+
+/// Finest mip a texture may drop to when evicted or unused
+pub fn coarsest_streamed_mip(texture: &StreamedTexture) -> u32 {
+    let mip_count = texture.mips.len() as u32;
+    match texture.residency {
+        TextureResidency::Pinned => 0,
+        TextureResidency::Streamed {
+            min_resident_mips, ..
+        } => mip_count.saturating_sub(min_resident_mips.max(1)),
+    }
+}
+
+/// Mip a texture would like resident, ignoring memory pressure
+pub fn wanted_mip(config: &TextureStreamingConfig, texture: &StreamedTexture, frame: u64) -> u32 {
+    let coarsest = coarsest_streamed_mip(texture);
+    let TextureResidency::Streamed {
+        full_detail_distance,
+        ..
+    } = texture.residency
+    else {
+        return 0;
+    };
+    let unused = frame.saturating_sub(texture.last_used_frame) > config.unused_frames;
+    if unused || !texture.last_use_distance.is_finite() {
+        return coarsest;
+    }
+    let ratio = (texture.last_use_distance / full_detail_distance.max(f32::EPSILON)).max(1.0);
+    (ratio.log2().floor() as u32).min(coarsest)
+}
+
+// ============================================================================
+// STREAMING
+// ============================================================================
+
+/// Memory use and budget as tracked by the system monitor
+pub fn memory_budget_view(
+    monitor: &SystemMonitorData,
+    buffers: &EngineBuffers,
+) -> MemoryBudgetView {
+    MemoryBudgetView {
+        used_bytes: buffers.metrics.gpu_memory_usage,
+        budget_bytes: monitor.gpu_memory_budget,
+    }
+}
+
+/// Pick each texture's target mip for this frame. Under memory pressure no
+/// finer mips are wanted, and the least recently used textures give up
+/// their streamed mips until use would drop to `restore_memory_percent`.
+pub fn update_texture_streaming(data: &mut TextureStreamingData, memory: MemoryBudgetView) {
+    data.frame += 1;
+    let config = data.config;
+    let frame = data.frame;
+
+    let budget = memory.budget_bytes as f64;
+    let percent = if budget > 0.0 {
+        memory.used_bytes as f64 / budget * 100.0
+    } else {
+        0.0
+    };
+    if budget <= 0.0 || percent <= config.restore_memory_percent {
+        data.memory_pressure = false;
+    } else if percent > config.evict_memory_percent {
+        data.memory_pressure = true;
+    }
+    let pressure = data.memory_pressure;
+
+    for texture in &mut data.textures {
+        if texture.nearest_distance.is_finite() {
+            texture.last_use_distance = texture.nearest_distance;
+            texture.nearest_distance = f32::INFINITY;
+        }
+        let wanted = wanted_mip(&config, texture, frame);
+        texture.target_mip = if pressure {
+            // Always-resident mips still load; nothing finer than what is there
+            wanted.max(texture.resident_mip.min(coarsest_streamed_mip(texture)))
+        } else {
+            wanted
+        };
+    }
+
+    if pressure {
+        let target_bytes = (budget * config.restore_memory_percent / 100.0) as u64;
+        let to_free = memory.used_bytes.saturating_sub(target_bytes);
+        evict_for_pressure(data, to_free);
+    }
+
+    refresh_streaming_stats(data);
+}
+
+/// Raise targets of the least recently used, farthest textures until
+/// `to_free` bytes would be released
+fn evict_for_pressure(data: &mut TextureStreamingData, to_free: u64) {
+    let mut order: Vec<usize> = (0..data.textures.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (a, b) = (&data.textures[a], &data.textures[b]);
+        a.last_used_frame
+            .cmp(&b.last_used_frame)
+            .then(b.last_use_distance.total_cmp(&a.last_use_distance))
+    });
+
+    let mut freed = 0;
+    for index in order {
+        let texture = &mut data.textures[index];
+        let coarsest = coarsest_streamed_mip(texture);
+        while freed < to_free && texture.target_mip < coarsest {
+            if texture.target_mip >= texture.resident_mip {
+                freed += mip_bytes(texture, texture.target_mip);
+            }
+            texture.target_mip += 1;
+        }
+        if freed >= to_free {
+            break;
+        }
+    }
+}
+
+/// Residency steps for this frame: every eviction, plus uploads one mip at
+/// a time (most starved texture first) within the per-frame upload budget
+pub fn next_residency_changes(data: &TextureStreamingData) -> Vec<ResidencyChange> {
+    let mut changes = Vec::new();
+    let mut resident: Vec<u32> = data.textures.iter().map(|t| t.resident_mip).collect();
+
+    for (texture, resident) in data.textures.iter().zip(&mut resident) {
+        let mip_count = texture.mips.len() as u32;
+        if texture.target_mip > *resident && *resident < mip_count {
+            *resident = texture.target_mip.min(mip_count);
+            changes.push(ResidencyChange::Evict {
+                id: texture.id,
+                mip: *resident,
+            });
+        }
+    }
+
+    let mut uploaded = 0u64;
+    loop {
+        let next = data
+            .textures
+            .iter()
+            .zip(&resident)
+            .enumerate()
+            .filter(|(_, (texture, resident))| texture.target_mip < **resident)
+            .max_by_key(|(index, (texture, resident))| {
+                (**resident - texture.target_mip, std::cmp::Reverse(*index))
+            })
+            .map(|(index, _)| index);
+        let Some(index) = next else {
+            break;
+        };
+
+        let texture = &data.textures[index];
+        let mip = resident[index] - 1;
+        let bytes = mip_bytes(texture, mip);
+        // A mip larger than the whole budget still goes through on its own
+        if uploaded > 0 && uploaded + bytes > data.config.max_upload_bytes_per_frame {
+            break;
+        }
+        uploaded += bytes;
+        resident[index] = mip;
+        changes.push(ResidencyChange::Upload {
+            id: texture.id,
+            mip,
+        });
+    }
+    changes
+}
+
+/// Record a residency step as done (after the GPU copy was updated)
+pub fn commit_residency_change(data: &mut TextureStreamingData, change: ResidencyChange) {
+    let (id, mip) = match change {
+        ResidencyChange::Upload { id, mip } | ResidencyChange::Evict { id, mip } => (id, mip),
+    };
+    let stats = &mut data.stats;
+    let Some(texture) = data.textures.iter_mut().find(|texture| texture.id == id) else {
+        return;
+    };
+    let previous = texture.resident_mip;
+    let mip = mip.min(texture.mips.len() as u32);
+    texture.resident_mip = mip;
+
+    if mip < previous {
+        let bytes = mip_range_bytes(texture, mip, previous);
+        stats.mips_uploaded += (previous - mip) as u64;
+        stats.bytes_uploaded += bytes;
+        stats.last_frame_upload_bytes += bytes;
+    } else if mip > previous {
+        stats.mips_evicted += (mip - previous) as u64;
+        stats.bytes_evicted += mip_range_bytes(texture, previous, mip);
+    }
+}
+
+/// Upload and evict mips for this frame. Textures whose GPU copy changed
+/// get a new `generation`; their bind groups must be rebuilt.
+pub fn apply_texture_streaming(
+    data: &mut TextureStreamingData,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+) -> Vec<ResidencyChange> {
+    data.stats.last_frame_upload_bytes = 0;
+    let changes = next_residency_changes(data);
+    if changes.is_empty() {
+        return changes;
+    }
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Texture Streaming"),
+    });
+    for texture in &mut data.textures {
+        // Several steps of one texture collapse into a single reallocation
+        let last = changes.iter().rev().find_map(|change| match *change {
+            ResidencyChange::Upload { id, mip } | ResidencyChange::Evict { id, mip }
+                if id == texture.id =>
+            {
+                Some(mip)
+            }
+            _ => None,
+        });
+        if let Some(mip) = last {
+            realize_residency(texture, &data.gpu_owner, device, queue, &mut encoder, mip);
+        }
+    }
+    queue.submit(std::iter::once(encoder.finish()));
+
+    for change in &changes {
+        commit_residency_change(data, *change);
+    }
+    refresh_streaming_stats(data);
+    changes
+}
+
+/// Replace a texture's GPU copy with one holding mips `new_mip..`, copying
+/// levels that were already resident and uploading the rest
+fn realize_residency(
+    texture: &mut StreamedTexture,
+    owner: &GpuOwnerGuard,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    encoder: &mut wgpu::CommandEncoder,
+    new_mip: u32,
+) {
+    let mip_count = texture.mips.len() as u32;
+    texture.generation += 1;
+    let Some(base) = texture.mips.get(new_mip as usize) else {
+        texture.gpu = None;
+        return;
+    };
+
+    let (gpu_texture, allocation) = create_tracked_texture(
+        device,
+        owner,
+        &wgpu::TextureDescriptor {
+            label: Some(&texture.name),
+            size: wgpu::Extent3d {
+                width: base.width,
+                height: base.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: mip_count - new_mip,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: texture.format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        },
+    );
+
+    for mip in new_mip..mip_count {
+        let level = &texture.mips[mip as usize];
+        let size = wgpu::Extent3d {
+            width: level.width,
+            height: level.height,
+            depth_or_array_layers: 1,
+        };
+        let destination = wgpu::ImageCopyTexture {
+            texture: &gpu_texture,
+            mip_level: mip - new_mip,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        };
+        match &texture.gpu {
+            Some(old) if mip >= texture.resident_mip => encoder.copy_texture_to_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &old.texture,
+                    mip_level: mip - texture.resident_mip,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                destination,
+                size,
+            ),
+            _ => queue.write_texture(
+                destination,
+                &level.pixels,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(level.width * 4),
+                    rows_per_image: Some(level.height),
+                },
+                size,
+            ),
+        }
+    }
+
+    let view = gpu_texture.create_view(&wgpu::TextureViewDescriptor::default());
+    texture.gpu = Some(StreamedTextureGpu {
+        texture: gpu_texture,
+        view,
+        allocation,
+    });
+}
+
+// ============================================================================
+// STATISTICS
+// ============================================================================
+
+/// Recompute the byte totals in `data.stats`
+pub fn refresh_streaming_stats(data: &mut TextureStreamingData) {
+    let stats = &mut data.stats;
+    stats.textures = data.textures.len() as u32;
+    stats.resident_bytes = data.textures.iter().map(resident_bytes).sum();
+    stats.full_bytes = data
+        .textures
+        .iter()
+        .map(|texture| mip_range_bytes(texture, 0, texture.mips.len() as u32))
+        .sum();
+    stats.pending_upload_bytes = data
+        .textures
+        .iter()
+        .filter(|texture| texture.target_mip < texture.resident_mip)
+        .map(|texture| mip_range_bytes(texture, texture.target_mip, texture.resident_mip))
+        .sum();
+    stats.memory_pressure = data.memory_pressure;
+}
+
+/// Publish the stats to the metrics buffers; resident texture bytes reach
+/// `gpu_memory_usage` through the GPU allocation tracker
+pub fn record_texture_streaming_metrics(data: &TextureStreamingData, metrics: &mut MetricsBuffers) {
+    metrics.texture_streaming = data.stats;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit_all(data: &mut TextureStreamingData) -> Vec<ResidencyChange> {
+        let changes = next_residency_changes(data);
+        for change in &changes {
+            commit_residency_change(data, *change);
+        }
+        refresh_streaming_stats(data);
+        changes
+    }
+
+    fn streamer_with_texture() -> (TextureStreamingData, TextureStreamId) {
+        let config = TextureStreamingConfig {
+            max_upload_bytes_per_frame: 100_000,
+            ..default_texture_streaming_config()
+        };
+        let mut data = create_texture_streaming(config);
+        let image = RgbaImage::from_pixel(256, 256, image::Rgba([90, 140, 60, 255]));
+        let residency = TextureResidency::Streamed {
+            min_resident_mips: 2,
+            full_detail_distance: 16.0,
+        };
+        let id = register_streamed_texture(&mut data, "atlas", &image, residency);
+        (data, id)
+    }
+
+    #[test]
+    fn test_mips_stream_in_near_camera_within_budget() {
+        let (mut data, id) = streamer_with_texture();
+        let texture = find_streamed_texture(&data, id).expect("registered");
+        assert_eq!(texture.mips.len(), 9);
+        assert_eq!(texture.mips[8].pixels.len(), 4);
+
+        // Unused: only the two coarsest mips
+        update_texture_streaming(&mut data, MemoryBudgetView::default());
+        commit_all(&mut data);
+        assert_eq!(
+            find_streamed_texture(&data, id).map(|t| t.resident_mip),
+            Some(7)
+        );
+
+        // At 40 voxels (2.5x the full detail distance) mip 1 is enough
+        note_texture_use(&mut data, id, 40.0);
+        update_texture_streaming(&mut data, MemoryBudgetView::default());
+        commit_all(&mut data);
+        assert_eq!(
+            find_streamed_texture(&data, id).map(|t| t.resident_mip),
+            Some(1)
+        );
+
+        // Mip 0 (256 KB) is over the per-frame budget but still uploads alone
+        note_texture_use(&mut data, id, 2.0);
+        update_texture_streaming(&mut data, MemoryBudgetView::default());
+        assert_eq!(data.stats.pending_upload_bytes, 256 * 256 * 4);
+        assert_eq!(
+            commit_all(&mut data),
+            vec![ResidencyChange::Upload { id, mip: 0 }]
+        );
+        assert_eq!(data.stats.resident_bytes, data.stats.full_bytes);
+    }
+
+    #[test]
+    fn test_memory_pressure_evicts_and_reports() {
+        let (mut data, id) = streamer_with_texture();
+        for _ in 0..3 {
+            note_texture_use(&mut data, id, 0.0);
+            update_texture_streaming(&mut data, MemoryBudgetView::default());
+            commit_all(&mut data);
+        }
+        let full = data.stats.resident_bytes;
+        assert_eq!(full, data.stats.full_bytes);
+
+        let mut metrics = MetricsBuffers::default();
+        record_texture_streaming_metrics(&data, &mut metrics);
+        assert_eq!(metrics.texture_streaming.resident_bytes, full);
+
+        // 500 KB of other allocations plus the resident mips, as the GPU
+        // allocation tracker reports them
+        let used_bytes = |data: &TextureStreamingData| 500_000 + data.stats.resident_bytes;
+
+        // 95% of a budget: evict streamed mips, and upload nothing new
+        let used = used_bytes(&data);
+        let budget = used * 100 / 95;
+        note_texture_use(&mut data, id, 0.0);
+        update_texture_streaming(
+            &mut data,
+            MemoryBudgetView {
+                used_bytes: used,
+                budget_bytes: budget,
+            },
+        );
+        assert!(data.stats.memory_pressure);
+        let changes = commit_all(&mut data);
+        assert!(matches!(changes[..], [ResidencyChange::Evict { .. }]));
+        record_texture_streaming_metrics(&data, &mut metrics);
+        assert!(used_bytes(&data) * 100 <= budget * 70);
+        assert_eq!(
+            metrics.texture_streaming.mips_evicted,
+            data.stats.mips_evicted
+        );
+
+        // Between the restore and evict levels the pressure holds: no uploads
+        note_texture_use(&mut data, id, 0.0);
+        update_texture_streaming(
+            &mut data,
+            MemoryBudgetView {
+                used_bytes: budget * 80 / 100,
+                budget_bytes: budget,
+            },
+        );
+        assert!(data.memory_pressure);
+        assert!(commit_all(&mut data)
+            .iter()
+            .all(|change| matches!(change, ResidencyChange::Evict { .. })));
+
+        // Below the restore level mips stream back in
+        note_texture_use(&mut data, id, 0.0);
+        update_texture_streaming(
+            &mut data,
+            MemoryBudgetView {
+                used_bytes: budget / 2,
+                budget_bytes: budget,
+            },
+        );
+        assert!(!data.memory_pressure);
+        assert!(matches!(
+            commit_all(&mut data).last(),
+            Some(ResidencyChange::Upload { .. })
+        ));
+    }
+
+    #[test]
+    fn test_block_atlas_tiles_take_block_colors() {
+        let mut registry = BlockRegistry::new();
+        crate::world::blocks::register_basic_blocks(&mut registry);
+
+        let atlas = build_block_atlas(&registry);
+        assert_eq!(atlas.width(), BLOCK_ATLAS_COLUMNS * BLOCK_ATLAS_TILE_SIZE);
+        assert_eq!(atlas.height(), BLOCK_ATLAS_TILE_SIZE);
+        assert_eq!(atlas.get_pixel(0, 0).0, BLOCK_ATLAS_EMPTY_TILE);
+        // Grass uses texture 1, dirt texture 2
+        assert_eq!(
+            atlas.get_pixel(BLOCK_ATLAS_TILE_SIZE, 0).0,
+            [77, 204, 51, 255]
+        );
+        assert_eq!(
+            atlas.get_pixel(2 * BLOCK_ATLAS_TILE_SIZE + 5, 7).0,
+            [128, 77, 26, 255]
+        );
+    }
+}
@@ -2,8 +2,7 @@
 // Calculates ambient occlusion values for voxels based on neighboring blocks
 
 // World constants - auto-generated from constants.rs
-// CHUNK_SIZE, MAX_WORLD_SIZE are auto-generated
-const WORLD_HEIGHT: u32 = 256u;
+// CHUNK_SIZE, WORLD_SIZE and WORLD_HEIGHT are auto-generated
 
 // Voxel packing constants
 const BLOCK_ID_MASK: u32 = 0xFFFFu;
@@ -17,18 +16,27 @@ const METADATA_MASK: u32 = 0xFFu;
 const AO_MASK: u32 = 0xFu;
 const AO_SHIFT: u32 = 0u;
 
-// Block types
-const BLOCK_AIR: u32 = 0u;
-const BLOCK_WATER: u32 = 5u;
+// Block types (BLOCK_AIR, BLOCK_WATER) are auto-generated
 
 // AO calculation constants
 const AO_STRENGTH: f32 = 0.2; // How much each occluder darkens
 const AO_MAX: u32 = 15u;      // Maximum AO value (4 bits)
 
+// Dirty sub-volume relit by the region kernels
+struct LightRegion {
+    // xyz = world min corner (inclusive)
+    min: vec4<i32>,
+    // xyz = extent in voxels, w = voxels in all earlier regions
+    size: vec4<u32>,
+}
+
 // Bindings
 @group(0) @binding(0) var<storage, read_write> world_voxels: array<atomic<u32>>;
 @group(0) @binding(1) var<storage, read> chunk_positions: array<vec4<i32>>;
 
+// Region kernels bind the region list instead of chunk positions
+@group(1) @binding(0) var<storage, read> light_regions: array<LightRegion>;
+
 // Helper functions
 fn world_to_index(pos: vec3<i32>) -> u32 {
     // Bounds check
@@ -92,6 +100,117 @@ fn vertex_ao(side1: f32, side2: f32, corner: f32) -> f32 {
     return 3.0 - (side1 + side2 + corner);
 }
 
+// Recompute the AO of one voxel from its face and edge neighbours
+fn update_voxel_ao(world_pos: vec3<i32>) {
+    let voxel_idx = world_to_index(world_pos);
+    if (voxel_idx == 0xFFFFFFFFu) {
+        return;
+    }
+
+    let voxel = atomicLoad(&world_voxels[voxel_idx]);
+    let block_id = unpack_block_id(voxel);
+
+    // Skip air blocks and water
+    if (!is_solid_block(block_id)) {
+        return;
+    }
+
+    // Calculate AO by checking neighbors
+    // We use a simplified approach: count solid neighbors in 6 directions
+    var occlusion = 0.0;
+
+    // Check 6 face neighbors
+    occlusion += is_occluder(world_pos + vec3<i32>(1, 0, 0));
+    occlusion += is_occluder(world_pos + vec3<i32>(-1, 0, 0));
+    occlusion += is_occluder(world_pos + vec3<i32>(0, 1, 0));
+    occlusion += is_occluder(world_pos + vec3<i32>(0, -1, 0));
+    occlusion += is_occluder(world_pos + vec3<i32>(0, 0, 1));
+    occlusion += is_occluder(world_pos + vec3<i32>(0, 0, -1));
+
+    // Check 12 edge neighbors (reduced weight)
+    occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(1, 1, 0));
+    occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(1, -1, 0));
+    occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(-1, 1, 0));
+    occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(-1, -1, 0));
+    occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(1, 0, 1));
+    occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(1, 0, -1));
+    occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(-1, 0, 1));
+    occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(-1, 0, -1));
+    occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(0, 1, 1));
+    occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(0, 1, -1));
+    occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(0, -1, 1));
+    occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(0, -1, -1));
+
+    // Normalize and convert to 4-bit value
+    let ao_factor = clamp(occlusion / 12.0, 0.0, 1.0);
+    let ao_value = u32(ao_factor * f32(AO_MAX));
+
+    // Update metadata with AO value
+    let old_metadata = unpack_metadata(voxel);
+    let new_metadata = pack_ao_in_metadata(old_metadata, ao_value);
+
+    // Atomically update only if metadata changed
+    if (new_metadata != old_metadata) {
+        // Reconstruct voxel with new metadata
+        let new_voxel = (voxel & ~(METADATA_MASK << METADATA_SHIFT)) |
+                       (new_metadata << METADATA_SHIFT);
+        atomicStore(&world_voxels[voxel_idx], new_voxel);
+    }
+}
+
+// Average the AO of one voxel with its solid 3x3x3 neighbourhood
+fn smooth_voxel_ao(world_pos: vec3<i32>) {
+    let center_idx = world_to_index(world_pos);
+    if (center_idx == 0xFFFFFFFFu) {
+        return;
+    }
+
+    let center_voxel = atomicLoad(&world_voxels[center_idx]);
+    let center_block = unpack_block_id(center_voxel);
+
+    if (!is_solid_block(center_block)) {
+        return;
+    }
+
+    // Average AO with neighbors
+    var ao_sum = 0.0;
+    var count = 0.0;
+
+    // Sample 3x3x3 neighborhood
+    for (var dx = -1; dx <= 1; dx++) {
+        for (var dy = -1; dy <= 1; dy++) {
+            for (var dz = -1; dz <= 1; dz++) {
+                let neighbor_pos = world_pos + vec3<i32>(dx, dy, dz);
+                let neighbor_idx = world_to_index(neighbor_pos);
+
+                if (neighbor_idx != 0xFFFFFFFFu) {
+                    let neighbor_voxel = atomicLoad(&world_voxels[neighbor_idx]);
+                    let neighbor_block = unpack_block_id(neighbor_voxel);
+
+                    if (is_solid_block(neighbor_block)) {
+                        let neighbor_metadata = unpack_metadata(neighbor_voxel);
+                        let neighbor_ao = neighbor_metadata & AO_MASK;
+                        ao_sum += f32(neighbor_ao);
+                        count += 1.0;
+                    }
+                }
+            }
+        }
+    }
+
+    if (count > 0.0) {
+        let smoothed_ao = u32(round(ao_sum / count));
+        let old_metadata = unpack_metadata(center_voxel);
+        let new_metadata = pack_ao_in_metadata(old_metadata, smoothed_ao);
+
+        if (new_metadata != old_metadata) {
+            let new_voxel = (center_voxel & ~(METADATA_MASK << METADATA_SHIFT)) |
+                           (new_metadata << METADATA_SHIFT);
+            atomicStore(&world_voxels[center_idx], new_voxel);
+        }
+    }
+}
+
 // Main AO calculation kernel
 @compute @workgroup_size(8, 8, 4)
 fn calculate_ao(
@@ -104,17 +223,17 @@ fn calculate_ao(
     if (chunk_idx >= arrayLength(&chunk_positions)) {
         return;
     }
-    
+
     let chunk_pos = chunk_positions[chunk_idx];
     let chunk_world_x = chunk_pos.x * i32(CHUNK_SIZE);
     let chunk_world_y = chunk_pos.y * i32(CHUNK_SIZE);
     let chunk_world_z = chunk_pos.z * i32(CHUNK_SIZE);
-    
+
     // Calculate position for this thread
     let local_x = local_id.x;
     let local_y = local_id.y;
     let local_z = local_id.z;
-    
+
     // Each thread processes a 4x4x4 block
     for (var dx = 0u; dx < 4u; dx++) {
         for (var dy = 0u; dy < 4u; dy++) {
@@ -122,71 +241,16 @@ fn calculate_ao(
                 let x = local_x * 4u + dx;
                 let y = local_y * 4u + dy;
                 let z = local_z * 4u + dz;
-                
+
                 if (x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE) {
                     continue;
                 }
-                
-                let world_pos = vec3<i32>(
+
+                update_voxel_ao(vec3<i32>(
                     chunk_world_x + i32(x),
                     chunk_world_y + i32(y),
                     chunk_world_z + i32(z)
-                );
-                
-                let voxel_idx = world_to_index(world_pos);
-                if (voxel_idx == 0xFFFFFFFFu) {
-                    continue;
-                }
-                
-                let voxel = atomicLoad(&world_voxels[voxel_idx]);
-                let block_id = unpack_block_id(voxel);
-                
-                // Skip air blocks and water
-                if (!is_solid_block(block_id)) {
-                    continue;
-                }
-                
-                // Calculate AO by checking neighbors
-                // We use a simplified approach: count solid neighbors in 6 directions
-                var occlusion = 0.0;
-                
-                // Check 6 face neighbors
-                occlusion += is_occluder(world_pos + vec3<i32>(1, 0, 0));
-                occlusion += is_occluder(world_pos + vec3<i32>(-1, 0, 0));
-                occlusion += is_occluder(world_pos + vec3<i32>(0, 1, 0));
-                occlusion += is_occluder(world_pos + vec3<i32>(0, -1, 0));
-                occlusion += is_occluder(world_pos + vec3<i32>(0, 0, 1));
-                occlusion += is_occluder(world_pos + vec3<i32>(0, 0, -1));
-                
-                // Check 12 edge neighbors (reduced weight)
-                occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(1, 1, 0));
-                occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(1, -1, 0));
-                occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(-1, 1, 0));
-                occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(-1, -1, 0));
-                occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(1, 0, 1));
-                occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(1, 0, -1));
-                occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(-1, 0, 1));
-                occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(-1, 0, -1));
-                occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(0, 1, 1));
-                occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(0, 1, -1));
-                occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(0, -1, 1));
-                occlusion += 0.5 * is_occluder(world_pos + vec3<i32>(0, -1, -1));
-                
-                // Normalize and convert to 4-bit value
-                let ao_factor = clamp(occlusion / 12.0, 0.0, 1.0);
-                let ao_value = u32(ao_factor * f32(AO_MAX));
-                
-                // Update metadata with AO value
-                let old_metadata = unpack_metadata(voxel);
-                let new_metadata = pack_ao_in_metadata(old_metadata, ao_value);
-                
-                // Atomically update only if metadata changed
-                if (new_metadata != old_metadata) {
-                    // Reconstruct voxel with new metadata
-                    let new_voxel = (voxel & ~(METADATA_MASK << METADATA_SHIFT)) | 
-                                   (new_metadata << METADATA_SHIFT);
-                    atomicStore(&world_voxels[voxel_idx], new_voxel);
-                }
+                ));
             }
         }
     }
@@ -204,74 +268,74 @@ fn smooth_ao(
     if (chunk_idx >= arrayLength(&chunk_positions)) {
         return;
     }
-    
+
     let chunk_pos = chunk_positions[chunk_idx];
     let chunk_world_x = chunk_pos.x * i32(CHUNK_SIZE);
     let chunk_world_y = chunk_pos.y * i32(CHUNK_SIZE);
     let chunk_world_z = chunk_pos.z * i32(CHUNK_SIZE);
-    
+
     // Calculate position for this thread
     let x = local_id.x * 4u;
     let y = local_id.y * 4u;
     let z = local_id.z * 4u;
-    
+
     if (x >= CHUNK_SIZE || y >= CHUNK_SIZE || z >= CHUNK_SIZE) {
         return;
     }
-    
-    let world_pos = vec3<i32>(
+
+    smooth_voxel_ao(vec3<i32>(
         chunk_world_x + i32(x),
         chunk_world_y + i32(y),
         chunk_world_z + i32(z)
-    );
-    
-    let center_idx = world_to_index(world_pos);
-    if (center_idx == 0xFFFFFFFFu) {
-        return;
-    }
-    
-    let center_voxel = atomicLoad(&world_voxels[center_idx]);
-    let center_block = unpack_block_id(center_voxel);
-    
-    if (!is_solid_block(center_block)) {
-        return;
+    ));
+}
+
+// World position of the n-th voxel across all dirty regions, or a
+// sentinel w = 0 when n is past the last region
+fn region_voxel(n: u32) -> vec4<i32> {
+    let count = arrayLength(&light_regions);
+    if (count == 0u) {
+        return vec4<i32>(0, 0, 0, 0);
     }
-    
-    // Average AO with neighbors
-    var ao_sum = 0.0;
-    var count = 0.0;
-    
-    // Sample 3x3x3 neighborhood
-    for (var dx = -1; dx <= 1; dx++) {
-        for (var dy = -1; dy <= 1; dy++) {
-            for (var dz = -1; dz <= 1; dz++) {
-                let neighbor_pos = world_pos + vec3<i32>(dx, dy, dz);
-                let neighbor_idx = world_to_index(neighbor_pos);
-                
-                if (neighbor_idx != 0xFFFFFFFFu) {
-                    let neighbor_voxel = atomicLoad(&world_voxels[neighbor_idx]);
-                    let neighbor_block = unpack_block_id(neighbor_voxel);
-                    
-                    if (is_solid_block(neighbor_block)) {
-                        let neighbor_metadata = unpack_metadata(neighbor_voxel);
-                        let neighbor_ao = neighbor_metadata & AO_MASK;
-                        ao_sum += f32(neighbor_ao);
-                        count += 1.0;
-                    }
-                }
-            }
+    // Last region whose first voxel is at or before n
+    var lo = 0u;
+    var hi = count - 1u;
+    while (lo < hi) {
+        let mid = (lo + hi + 1u) / 2u;
+        if (light_regions[mid].size.w <= n) {
+            lo = mid;
+        } else {
+            hi = mid - 1u;
         }
     }
-    
-    if (count > 0.0) {
-        let smoothed_ao = u32(round(ao_sum / count));
-        let old_metadata = unpack_metadata(center_voxel);
-        let new_metadata = pack_ao_in_metadata(old_metadata, smoothed_ao);
-        
-        if (new_metadata != old_metadata) {
-            let new_voxel = (center_voxel & ~(METADATA_MASK << METADATA_SHIFT)) | 
-                           (new_metadata << METADATA_SHIFT);
-            atomicStore(&world_voxels[center_idx], new_voxel);
-        }
+    let region = light_regions[lo];
+    let local = n - region.size.w;
+    let volume = region.size.x * region.size.y * region.size.z;
+    if (local >= volume) {
+        return vec4<i32>(0, 0, 0, 0);
     }
-}
\ No newline at end of file
+    let x = local % region.size.x;
+    let y = (local / region.size.x) % region.size.y;
+    let z = local / (region.size.x * region.size.y);
+    return vec4<i32>(region.min.xyz + vec3<i32>(i32(x), i32(y), i32(z)), 1);
+}
+
+// AO for the voxels of the dirty regions only, one thread per voxel
+@compute @workgroup_size(64, 1, 1)
+fn calculate_ao_region(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let voxel = region_voxel(global_id.x);
+    if (voxel.w == 0) {
+        return;
+    }
+    update_voxel_ao(voxel.xyz);
+}
+
+// Smoothing for the voxels of the dirty regions only
+@compute @workgroup_size(64, 1, 1)
+fn smooth_ao_region(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let voxel = region_voxel(global_id.x);
+    if (voxel.w == 0) {
+        return;
+    }
+    smooth_voxel_ao(voxel.xyz);
+}
@@ -0,0 +1,183 @@
+// GPU Chunk Connectivity Flood Fill
+// Computes which pairs of chunk faces are connected through passable voxels.
+// The result is a 15-bit mask (one bit per face pair) consumed by cave culling.
+//
+// Face order: 0=+X, 1=-X, 2=+Y, 3=-Y, 4=+Z, 5=-Z
+// Pair bits are assigned in (i, j) order with i < j, matching
+// face_pair_bit in chunk_connectivity.rs.
+//
+// CHUNK_SIZE, VOXELS_PER_CHUNK and BLOCK_* constants are auto-generated.
+
+struct ConnectivityJob {
+    slot: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+struct ConnectivityParams {
+    job_count: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Bindings
+@group(0) @binding(0) var<storage, read> world_voxels: array<u32>;
+@group(0) @binding(1) var<storage, read_write> face_masks: array<atomic<u32>>;
+@group(0) @binding(2) var<storage, read> jobs: array<ConnectivityJob>;
+// Two words per job: [connectivity bits, unconverged flag]
+@group(0) @binding(3) var<storage, read_write> results: array<atomic<u32>>;
+@group(0) @binding(4) var<uniform> params: ConnectivityParams;
+
+// Marks a voxel that blocks the flood fill
+const SOLID_MASK: u32 = 0x80000000u;
+// Bits 0-5: set of chunk faces this voxel is reachable from
+const FACE_BITS: u32 = 0x3Fu;
+
+var<workgroup> group_connectivity: atomic<u32>;
+
+fn is_passable(voxel: u32) -> bool {
+    let id = voxel & 0xFFFFu;
+    return id == BLOCK_AIR || id == BLOCK_WATER || id == BLOCK_GLASS || id == BLOCK_LEAVES;
+}
+
+fn local_coords(index: u32) -> vec3<u32> {
+    return vec3<u32>(
+        index % CHUNK_SIZE,
+        (index / CHUNK_SIZE) % CHUNK_SIZE,
+        index / (CHUNK_SIZE * CHUNK_SIZE)
+    );
+}
+
+fn boundary_faces(p: vec3<u32>) -> u32 {
+    let last = CHUNK_SIZE - 1u;
+    var faces = 0u;
+    if (p.x == last) { faces |= 1u; }
+    if (p.x == 0u) { faces |= 2u; }
+    if (p.y == last) { faces |= 4u; }
+    if (p.y == 0u) { faces |= 8u; }
+    if (p.z == last) { faces |= 16u; }
+    if (p.z == 0u) { faces |= 32u; }
+    return faces;
+}
+
+fn connectivity_from_faces(faces: u32) -> u32 {
+    var bits = 0u;
+    var bit = 0u;
+    for (var i = 0u; i < 6u; i++) {
+        for (var j = i + 1u; j < 6u; j++) {
+            if (((faces >> i) & (faces >> j) & 1u) != 0u) {
+                bits |= 1u << bit;
+            }
+            bit += 1u;
+        }
+    }
+    return bits;
+}
+
+fn neighbor_faces(base: u32, index: u32) -> u32 {
+    // Solid voxels only carry SOLID_MASK, so this yields 0 for them
+    return atomicLoad(&face_masks[base + index]) & FACE_BITS;
+}
+
+// Pull face reachability from the six neighbours; returns true if it grew
+fn propagate_voxel(job: u32, index: u32) -> bool {
+    let base = job * VOXELS_PER_CHUNK;
+    let current = atomicLoad(&face_masks[base + index]);
+    if ((current & SOLID_MASK) != 0u) {
+        return false;
+    }
+
+    let p = local_coords(index);
+    let last = CHUNK_SIZE - 1u;
+    let row = CHUNK_SIZE;
+    let layer = CHUNK_SIZE * CHUNK_SIZE;
+
+    var gathered = current;
+    if (p.x > 0u) { gathered |= neighbor_faces(base, index - 1u); }
+    if (p.x < last) { gathered |= neighbor_faces(base, index + 1u); }
+    if (p.y > 0u) { gathered |= neighbor_faces(base, index - row); }
+    if (p.y < last) { gathered |= neighbor_faces(base, index + row); }
+    if (p.z > 0u) { gathered |= neighbor_faces(base, index - layer); }
+    if (p.z < last) { gathered |= neighbor_faces(base, index + layer); }
+
+    if (gathered != current) {
+        atomicOr(&face_masks[base + index], gathered);
+        return true;
+    }
+    return false;
+}
+
+// Initialize face masks: boundary air voxels are reachable from their faces
+@compute @workgroup_size(256, 1, 1)
+fn seed_faces(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    let job = gid.y;
+    if (job >= params.job_count || index >= VOXELS_PER_CHUNK) {
+        return;
+    }
+
+    let voxel = world_voxels[jobs[job].slot * VOXELS_PER_CHUNK + index];
+    var mask = SOLID_MASK;
+    if (is_passable(voxel)) {
+        mask = boundary_faces(local_coords(index));
+    }
+    atomicStore(&face_masks[job * VOXELS_PER_CHUNK + index], mask);
+
+    if (index == 0u) {
+        atomicStore(&results[job * 2u], 0u);
+        atomicStore(&results[job * 2u + 1u], 0u);
+    }
+}
+
+// One flood fill iteration
+@compute @workgroup_size(256, 1, 1)
+fn propagate(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.y >= params.job_count || gid.x >= VOXELS_PER_CHUNK) {
+        return;
+    }
+    propagate_voxel(gid.y, gid.x);
+}
+
+// Last flood fill iteration; flags the job if it has not converged yet
+@compute @workgroup_size(256, 1, 1)
+fn propagate_final(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.y >= params.job_count || gid.x >= VOXELS_PER_CHUNK) {
+        return;
+    }
+    if (propagate_voxel(gid.y, gid.x)) {
+        atomicStore(&results[gid.y * 2u + 1u], 1u);
+    }
+}
+
+// Reduce per-voxel face sets into the chunk connectivity mask
+@compute @workgroup_size(256, 1, 1)
+fn resolve_connectivity(
+    @builtin(global_invocation_id) gid: vec3<u32>,
+    @builtin(local_invocation_index) local_index: u32
+) {
+    if (local_index == 0u) {
+        atomicStore(&group_connectivity, 0u);
+    }
+    workgroupBarrier();
+
+    let job = gid.y;
+    if (job < params.job_count && gid.x < VOXELS_PER_CHUNK) {
+        let mask = atomicLoad(&face_masks[job * VOXELS_PER_CHUNK + gid.x]);
+        if ((mask & SOLID_MASK) == 0u) {
+            let bits = connectivity_from_faces(mask & FACE_BITS);
+            if (bits != 0u) {
+                atomicOr(&group_connectivity, bits);
+            }
+        }
+    }
+    workgroupBarrier();
+
+    if (local_index == 0u && job < params.job_count) {
+        let bits = atomicLoad(&group_connectivity);
+        if (bits != 0u) {
+            atomicOr(&results[job * 2u], bits);
+        }
+    }
+}
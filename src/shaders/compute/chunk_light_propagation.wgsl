@@ -0,0 +1,163 @@
+// Chunk Light Propagation
+// Lights resident world chunks slot by slot with the rules of
+// light_fixture.wgsl: every dispatch is one relaxation step in which each
+// voxel takes the brightest of its current light, its emission and what its
+// face neighbours pass on. Light does not cross chunk borders; skylight
+// enters through the top face of chunks whose job marks them sky-open.
+//
+// Light goes to the dedicated lighting buffer (light_storage.wgsl) or, in
+// packed mode, to the 4-bit light fields of the voxel words.
+
+#include "light_storage.wgsl"
+
+// CHUNK_SIZE and VOXELS_PER_CHUNK are auto-generated
+
+struct ChunkLightJob {
+    slot: u32,
+    sky_open: u32,
+    _padding: vec2<u32>,
+}
+
+struct ChunkLightParams {
+    job_count: u32,
+    // 0 = dedicated lighting buffer, 1 = packed into the voxel words
+    light_mode: u32,
+    _padding: vec2<u32>,
+}
+
+@group(0) @binding(0) var<uniform> params: ChunkLightParams;
+@group(0) @binding(1) var<storage, read> jobs: array<ChunkLightJob>;
+// Per block id: bits 0-3 = emission, bit 4 = opaque
+@group(0) @binding(2) var<storage, read> block_cells: array<u32>;
+@group(0) @binding(3) var<storage, read_write> world_voxels: array<atomic<u32>>;
+// Unused placeholder in packed mode
+@group(0) @binding(4) var<storage, read_write> world_light: array<atomic<u32>>;
+
+const MAX_LIGHT: u32 = 15u;
+const FALLOFF: u32 = 1u;
+const EMISSION_MASK: u32 = 15u;
+const CELL_OPAQUE: u32 = 16u;
+const BLOCK_ID_MASK: u32 = 0xFFFFu;
+const LIGHT_MODE_PACKED: u32 = 1u;
+const PACKED_LIGHT_SHIFT: u32 = 16u;
+const PACKED_SKY_SHIFT: u32 = 20u;
+const PACKED_LEVEL_MASK: u32 = 15u;
+
+fn local_index_of(cell: vec3<u32>) -> u32 {
+    return cell.x + cell.y * CHUNK_SIZE + cell.z * CHUNK_SIZE * CHUNK_SIZE;
+}
+
+fn block_cell(slot: u32, local_index: u32) -> u32 {
+    let voxel = atomicLoad(&world_voxels[slot * VOXELS_PER_CHUNK + local_index]);
+    let block_id = voxel & BLOCK_ID_MASK;
+    if (block_id >= arrayLength(&block_cells)) {
+        return CELL_OPAQUE;
+    }
+    return block_cells[block_id];
+}
+
+// Packed 16-bit light (block light low byte, sky light high byte)
+fn load_light(slot: u32, local_index: u32) -> u32 {
+    if (params.light_mode == LIGHT_MODE_PACKED) {
+        let voxel = atomicLoad(&world_voxels[slot * VOXELS_PER_CHUNK + local_index]);
+        let block = (voxel >> PACKED_LIGHT_SHIFT) & PACKED_LEVEL_MASK;
+        let sky = (voxel >> PACKED_SKY_SHIFT) & PACKED_LEVEL_MASK;
+        return block | (sky << 8u);
+    }
+    let word = atomicLoad(&world_light[light_word_index(slot, local_index, CHUNK_SIZE)]);
+    return unpack_voxel_light(word, local_index);
+}
+
+fn store_light(slot: u32, local_index: u32, block: u32, sky: u32) {
+    if (params.light_mode == LIGHT_MODE_PACKED) {
+        // Only the voxel's own thread writes its word during a pass
+        let index = slot * VOXELS_PER_CHUNK + local_index;
+        let voxel = atomicLoad(&world_voxels[index]);
+        let cleared = voxel & ~(0xFFu << PACKED_LIGHT_SHIFT);
+        atomicStore(
+            &world_voxels[index],
+            cleared | (block << PACKED_LIGHT_SHIFT) | (sky << PACKED_SKY_SHIFT)
+        );
+        return;
+    }
+    // Two voxels share a word and only this thread writes this half, so
+    // adding the (wrapping) difference leaves the other half intact
+    let index = light_word_index(slot, local_index, CHUNK_SIZE);
+    let current = unpack_voxel_light(atomicLoad(&world_light[index]), local_index);
+    let updated = (block & 0xFFu) | ((sky & 0xFFu) << 8u);
+    atomicAdd(&world_light[index], (updated - current) << ((local_index & 1u) * 16u));
+}
+
+fn dimmed(level: u32) -> u32 {
+    return select(level - FALLOFF, 0u, level < FALLOFF);
+}
+
+// Job and chunk-local voxel of a thread; jobs are stacked along z.
+// w = 0 when the thread is outside every job.
+fn job_cell(gid: vec3<u32>) -> vec4<u32> {
+    let job_index = gid.z / CHUNK_SIZE;
+    if (job_index >= params.job_count || gid.x >= CHUNK_SIZE || gid.y >= CHUNK_SIZE) {
+        return vec4<u32>(0u);
+    }
+    return vec4<u32>(gid.x, gid.y, gid.z % CHUNK_SIZE, job_index + 1u);
+}
+
+// Dark start: light only grows during the relaxation steps
+@compute @workgroup_size(4, 4, 4)
+fn clear_chunk_light(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let cell = job_cell(gid);
+    if (cell.w == 0u) {
+        return;
+    }
+    store_light(jobs[cell.w - 1u].slot, local_index_of(cell.xyz), 0u, 0u);
+}
+
+@compute @workgroup_size(4, 4, 4)
+fn propagate_chunk_light(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let cell = job_cell(gid);
+    if (cell.w == 0u) {
+        return;
+    }
+    let job = jobs[cell.w - 1u];
+    let local_index = local_index_of(cell.xyz);
+
+    let block_info = block_cell(job.slot, local_index);
+    let emission = block_info & EMISSION_MASK;
+    if ((block_info & CELL_OPAQUE) != 0u) {
+        store_light(job.slot, local_index, emission, 0u);
+        return;
+    }
+
+    let current = load_light(job.slot, local_index);
+    var block = max(emission, block_light_of(current));
+    var sky = sky_light_of(current);
+    if (job.sky_open != 0u && cell.y == CHUNK_SIZE - 1u) {
+        sky = MAX_LIGHT;
+    }
+
+    var offsets = array<vec3<i32>, 6>(
+        vec3<i32>(1, 0, 0),
+        vec3<i32>(-1, 0, 0),
+        vec3<i32>(0, 1, 0),
+        vec3<i32>(0, -1, 0),
+        vec3<i32>(0, 0, 1),
+        vec3<i32>(0, 0, -1),
+    );
+    for (var face = 0u; face < 6u; face++) {
+        let neighbour = vec3<i32>(cell.xyz) + offsets[face];
+        if (any(neighbour < vec3<i32>(0)) || any(neighbour >= vec3<i32>(i32(CHUNK_SIZE)))) {
+            continue;
+        }
+        let neighbour_light = load_light(job.slot, local_index_of(vec3<u32>(neighbour)));
+        block = max(block, dimmed(block_light_of(neighbour_light)));
+        let neighbour_sky = sky_light_of(neighbour_light);
+        // Full skylight from the voxel above passes down without loss
+        if (face == 2u && neighbour_sky == MAX_LIGHT) {
+            sky = MAX_LIGHT;
+        } else {
+            sky = max(sky, dimmed(neighbour_sky));
+        }
+    }
+
+    store_light(job.slot, local_index, block, sky);
+}
@@ -1,7 +1,10 @@
 // GPU Chunk Modification Shader
-// Handles thread-safe voxel modifications using atomic operations
+// Applies block edits and explosions to chunks resident in WorldBuffer slots
 
-struct ModificationCommand {
+// Command resolved against the slot table on the CPU (one per edited chunk)
+struct ResolvedModification {
+    chunk_origin: vec3<i32>,
+    slot: u32,
     position: vec3<i32>,
     block_id: u32,
     mod_type: u32,      // 0=set, 1=break, 2=explode
@@ -9,11 +12,13 @@ struct ModificationCommand {
     _padding: vec2<u32>,
 }
 
-// World constants - auto-generated from constants.rs
-// CHUNK_SIZE, MAX_WORLD_SIZE, BLOCK_AIR, BLOCK_STONE are auto-generated
-const WORLD_HEIGHT: u32 = 256u;
-const CHUNK_SIZE: u32 = 50u; // Matches constants.rs
-const BLOCK_BEDROCK: u32 = 13u; // Matches constants.rs blocks::BEDROCK
+struct ModificationCounts {
+    block_count: u32,
+    explosion_count: u32,
+    _padding: vec2<u32>,
+}
+
+// CHUNK_SIZE, VOXELS_PER_CHUNK, BLOCK_AIR and BLOCK_BEDROCK are auto-generated
 
 // Voxel packing constants
 const BLOCK_ID_MASK: u32 = 0xFFFFu;
@@ -24,32 +29,17 @@ const METADATA_SHIFT: u32 = 24u;
 
 // Bindings
 @group(0) @binding(0) var<storage, read_write> world_voxels: array<atomic<u32>>;
-@group(0) @binding(1) var<storage, read> commands: array<ModificationCommand>;
-@group(0) @binding(2) var<uniform> command_count: u32;
-
-// Helper functions
-fn world_to_index(pos: vec3<i32>) -> u32 {
-    // Bounds check
-    if (pos.x < 0 || pos.y < 0 || pos.z < 0 ||
-        pos.x >= i32(WORLD_SIZE * CHUNK_SIZE) ||
-        pos.y >= i32(WORLD_HEIGHT) ||
-        pos.z >= i32(WORLD_SIZE * CHUNK_SIZE)) {
-        return 0xFFFFFFFFu; // Invalid index
+@group(0) @binding(1) var<storage, read> commands: array<ResolvedModification>;
+@group(0) @binding(2) var<uniform> counts: ModificationCounts;
+
+// Index of a chunk-local position in the slot, or 0xFFFFFFFF outside the chunk
+fn slot_voxel_index(slot: u32, local: vec3<i32>) -> u32 {
+    let size = i32(CHUNK_SIZE);
+    if (any(local < vec3<i32>(0)) || any(local >= vec3<i32>(size))) {
+        return 0xFFFFFFFFu;
     }
-    
-    let chunk_x = u32(pos.x) / CHUNK_SIZE;
-    let chunk_y = u32(pos.y) / CHUNK_SIZE;
-    let chunk_z = u32(pos.z) / CHUNK_SIZE;
-    
-    let local_x = u32(pos.x) % CHUNK_SIZE;
-    let local_y = u32(pos.y) % CHUNK_SIZE;
-    let local_z = u32(pos.z) % CHUNK_SIZE;
-    
-    let chunk_index = chunk_x + chunk_y * WORLD_SIZE + chunk_z * WORLD_SIZE * WORLD_SIZE;
-    let chunk_offset = chunk_index * CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
-    let local_index = local_x + local_y * CHUNK_SIZE + local_z * CHUNK_SIZE * CHUNK_SIZE;
-    
-    return chunk_offset + local_index;
+    let l = vec3<u32>(local);
+    return slot * VOXELS_PER_CHUNK + l.x + l.y * CHUNK_SIZE + l.z * CHUNK_SIZE * CHUNK_SIZE;
 }
 
 fn pack_voxel(block_id: u32, light: u32, skylight: u32, metadata: u32) -> u32 {
@@ -68,21 +58,20 @@ fn unpack_skylight(voxel: u32) -> u32 {
     return (voxel >> SKYLIGHT_SHIFT) & LIGHT_MASK;
 }
 
-// Single block modification kernel
+// Single block modification kernel: one thread per command
 @compute @workgroup_size(64, 1, 1)
 fn modify_blocks(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let cmd_idx = global_id.x;
-    if (cmd_idx >= command_count) {
+    if (cmd_idx >= counts.block_count) {
         return;
     }
-    
+
     let cmd = commands[cmd_idx];
-    let voxel_idx = world_to_index(cmd.position);
-    
+    let voxel_idx = slot_voxel_index(cmd.slot, cmd.position - cmd.chunk_origin);
     if (voxel_idx == 0xFFFFFFFFu) {
-        return; // Out of bounds
+        return;
     }
-    
+
     if (cmd.mod_type == 0u) {
         // Set block - preserve lighting
         let old_voxel = atomicLoad(&world_voxels[voxel_idx]);
@@ -97,69 +86,40 @@ fn modify_blocks(@builtin(global_invocation_id) global_id: vec3<u32>) {
     }
 }
 
-// Explosion effect kernel
-@compute @workgroup_size(8, 8, 4)
-fn explode_blocks(
-    @builtin(global_invocation_id) global_id: vec3<u32>,
-    @builtin(workgroup_id) workgroup_id: vec3<u32>
-) {
-    // Each explosion is processed by multiple workgroups
-    let explosion_idx = workgroup_id.x / 8u;
-    if (explosion_idx >= command_count) {
+// Explosion kernel: each command covers one chunk, one thread per voxel.
+// Workgroups along z are split into CHUNK_SIZE / 4 groups per command.
+@compute @workgroup_size(4, 4, 4)
+fn explode_blocks(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let z_extent = ((CHUNK_SIZE + 3u) / 4u) * 4u;
+    let explosion_idx = global_id.z / z_extent;
+    if (explosion_idx >= counts.explosion_count) {
+        return;
+    }
+
+    let cmd = commands[counts.block_count + explosion_idx];
+    let local = vec3<i32>(vec3<u32>(global_id.x, global_id.y, global_id.z % z_extent));
+    let voxel_idx = slot_voxel_index(cmd.slot, local);
+    if (voxel_idx == 0xFFFFFFFFu) {
         return;
     }
-    
-    let cmd = commands[explosion_idx];
-    let center = vec3<f32>(cmd.position);
-    let radius = cmd.radius;
-    let radius_squared = radius * radius;
-    
-    // Calculate position to check
-    let local_offset = vec3<i32>(global_id % 64u) - vec3<i32>(32);
-    let check_pos = cmd.position + local_offset;
-    
-    // Calculate distance from explosion center
-    let offset = vec3<f32>(check_pos) - center;
+
+    let check_pos = cmd.chunk_origin + local;
+    let offset = vec3<f32>(check_pos - cmd.position);
     let dist_squared = dot(offset, offset);
-    
-    if (dist_squared <= radius_squared) {
-        let voxel_idx = world_to_index(check_pos);
-        if (voxel_idx != 0xFFFFFFFFu) {
-            // Calculate damage based on distance
-            let damage_factor = 1.0 - sqrt(dist_squared) / radius;
-            
-            // Random chance to destroy block based on damage
-            let hash = u32(check_pos.x * 73856093) ^ u32(check_pos.y * 19349663) ^ u32(check_pos.z * 83492791);
-            let random = f32(hash & 0xFFFFu) / 65535.0;
-            
-            if (random < damage_factor * damage_factor) {
-                // Destroy block
-                let old_voxel = atomicLoad(&world_voxels[voxel_idx]);
-                let old_block = unpack_block_id(old_voxel);
-                
-                // Don't destroy bedrock
-                if (old_block != BLOCK_BEDROCK) {
-                    let new_voxel = pack_voxel(BLOCK_AIR, 0u, 0u, 0u);
-                    atomicStore(&world_voxels[voxel_idx], new_voxel);
-                }
-            }
-        }
+    if (dist_squared > cmd.radius * cmd.radius) {
+        return;
     }
-}
 
-// Bulk fill operation (for editor tools)
-@compute @workgroup_size(8, 8, 4)
-fn fill_region(
-    @builtin(global_invocation_id) global_id: vec3<u32>,
-    @builtin(num_workgroups) num_workgroups: vec3<u32>
-) {
-    // TODO: Implement region filling for editor tools
-    // This would read region bounds and block type from a uniform buffer
-}
+    // Random chance to destroy block based on damage
+    let damage_factor = 1.0 - sqrt(dist_squared) / cmd.radius;
+    let hash = u32(check_pos.x * 73856093) ^ u32(check_pos.y * 19349663) ^ u32(check_pos.z * 83492791);
+    let random = f32(hash & 0xFFFFu) / 65535.0;
 
-// Lighting update kernel (marks chunks for relighting)
-@compute @workgroup_size(32, 1, 1)
-fn mark_for_relighting(@builtin(global_invocation_id) global_id: vec3<u32>) {
-    // TODO: Mark affected chunks for lighting updates
-    // This would update chunk metadata flags
-}
\ No newline at end of file
+    if (random < damage_factor * damage_factor) {
+        let old_voxel = atomicLoad(&world_voxels[voxel_idx]);
+        // Don't destroy bedrock
+        if (unpack_block_id(old_voxel) != BLOCK_BEDROCK) {
+            atomicStore(&world_voxels[voxel_idx], pack_voxel(BLOCK_AIR, 0u, 0u, 0u));
+        }
+    }
+}
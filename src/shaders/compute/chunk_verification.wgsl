@@ -0,0 +1,56 @@
+// GPU Chunk Generation Verification
+// Counts non-air voxels per generated chunk so the CPU can spot chunks that
+// generation silently left empty (bad dispatch, lost device).
+//
+// CHUNK_SIZE, VOXELS_PER_CHUNK and BLOCK_* constants are auto-generated.
+
+struct VerificationJob {
+    slot: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+struct VerificationParams {
+    job_count: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Bindings
+@group(0) @binding(0) var<storage, read> world_voxels: array<u32>;
+@group(0) @binding(1) var<storage, read> jobs: array<VerificationJob>;
+// One word per job: non-air voxel count (cleared by the host before dispatch)
+@group(0) @binding(2) var<storage, read_write> results: array<atomic<u32>>;
+@group(0) @binding(3) var<uniform> params: VerificationParams;
+
+var<workgroup> group_count: atomic<u32>;
+
+// Reduce within the workgroup first so each group issues one global atomic
+@compute @workgroup_size(256, 1, 1)
+fn count_non_air(
+    @builtin(global_invocation_id) gid: vec3<u32>,
+    @builtin(local_invocation_index) local_index: u32
+) {
+    if (local_index == 0u) {
+        atomicStore(&group_count, 0u);
+    }
+    workgroupBarrier();
+
+    let job = gid.y;
+    if (job < params.job_count && gid.x < VOXELS_PER_CHUNK) {
+        let voxel = world_voxels[jobs[job].slot * VOXELS_PER_CHUNK + gid.x];
+        if ((voxel & 0xFFFFu) != BLOCK_AIR) {
+            atomicAdd(&group_count, 1u);
+        }
+    }
+    workgroupBarrier();
+
+    if (local_index == 0u && job < params.job_count) {
+        let count = atomicLoad(&group_count);
+        if (count != 0u) {
+            atomicAdd(&results[job], count);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+// GPU Column Heightmap
+// Records the highest non-air voxel of every column of a chunk into a
+// persistent heightmap buffer, one section of CHUNK_SIZE * CHUNK_SIZE words
+// per world buffer slot. Each word holds the local y + 1 of the column top,
+// 0 when the column is empty in this chunk, matching the CPU mirror in
+// world_operations.
+//
+// CHUNK_SIZE, VOXELS_PER_CHUNK and BLOCK_* constants are auto-generated.
+
+struct HeightmapJob {
+    slot: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+struct HeightmapParams {
+    job_count: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Bindings
+@group(0) @binding(0) var<storage, read> world_voxels: array<u32>;
+@group(0) @binding(1) var<storage, read> jobs: array<HeightmapJob>;
+// Sections of the job slots are cleared by the host before dispatch
+@group(0) @binding(2) var<storage, read_write> heights: array<atomic<u32>>;
+@group(0) @binding(3) var<uniform> params: HeightmapParams;
+
+@compute @workgroup_size(256, 1, 1)
+fn column_tops(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let job = gid.y;
+    if (job >= params.job_count || gid.x >= VOXELS_PER_CHUNK) {
+        return;
+    }
+
+    let slot = jobs[job].slot;
+    let voxel = world_voxels[slot * VOXELS_PER_CHUNK + gid.x];
+    if ((voxel & 0xFFFFu) == BLOCK_AIR) {
+        return;
+    }
+
+    let x = gid.x % CHUNK_SIZE;
+    let y = (gid.x / CHUNK_SIZE) % CHUNK_SIZE;
+    let z = gid.x / (CHUNK_SIZE * CHUNK_SIZE);
+    let column = slot * CHUNK_SIZE * CHUNK_SIZE + x + z * CHUNK_SIZE;
+    atomicMax(&heights[column], y + 1u);
+}
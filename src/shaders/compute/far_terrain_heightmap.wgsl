@@ -0,0 +1,53 @@
+// Far Terrain Heightmap Generation
+// Evaluates the terrain surface on a coarse grid around the camera for the
+// heightfield ring drawn beyond voxel render distance.
+// Output texel: rgb = surface color, a = surface height (voxels).
+//
+// TERRAIN_THRESHOLD, FAR_SURFACE_FLAT and other constants are auto-generated.
+
+struct FarTerrainUniform {
+    view_proj: mat4x4<f32>,
+    camera_pos: vec4<f32>,
+    heightmap_origin: vec2<f32>,
+    texel_size: f32,
+    resolution: u32,
+    inner_radius: f32,
+    outer_radius: f32,
+    blend_width: f32,
+    sea_level: f32,
+    boundary_sink: f32,
+    surface_mode: u32,
+    flat_height: f32,
+    _padding0: f32,
+}
+
+@group(0) @binding(0) var<uniform> far: FarTerrainUniform;
+@group(0) @binding(1) var heightmap: texture_storage_2d<rgba32float, write>;
+
+// Must match the surface height in terrain_generation.wgsl
+fn surface_height(world_x: f32, world_z: f32) -> f32 {
+    let height_variation = sin(world_x * 0.05) * 5.0 + cos(world_z * 0.05) * 5.0;
+    return f32(TERRAIN_THRESHOLD) + height_variation;
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn generate_heightmap(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= far.resolution || gid.y >= far.resolution) {
+        return;
+    }
+
+    let world_xz = far.heightmap_origin + (vec2<f32>(gid.xy) + 0.5) * far.texel_size;
+    var height = floor(surface_height(world_xz.x, world_xz.y));
+    if (far.surface_mode == FAR_SURFACE_FLAT) {
+        height = far.flat_height;
+    }
+
+    // Grass surface; water fills up to sea level
+    var color = vec3<f32>(0.30, 0.55, 0.20);
+    if (height < far.sea_level) {
+        height = far.sea_level;
+        color = vec3<f32>(0.15, 0.30, 0.60);
+    }
+
+    textureStore(heightmap, vec2<i32>(gid.xy), vec4<f32>(color, height));
+}
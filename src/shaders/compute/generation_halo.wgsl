@@ -0,0 +1,89 @@
+// Generation Halo Heightmaps
+// Evaluates and erodes one surface heightmap per batch of neighbouring
+// chunks, halo included, before the terrain pass reads it (see
+// generation_halo_operations.rs for the CPU reference).
+// One workgroup layer per batch: workgroup_id.z indexes the tile table.
+//
+// TERRAIN_THRESHOLD and other constants are auto-generated.
+
+struct HaloParams {
+    talus: f32,
+    erosion_rate: f32,
+    tile_count: u32,
+    _padding: u32,
+}
+
+struct HaloTile {
+    min_x: i32,
+    min_z: i32,
+    width: u32,
+    depth: u32,
+    offset: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+@group(0) @binding(0) var<uniform> halo: HaloParams;
+@group(0) @binding(1) var<storage, read> tiles: array<HaloTile>;
+@group(0) @binding(2) var<storage, read> source_heights: array<f32>;
+@group(0) @binding(3) var<storage, read_write> target_heights: array<f32>;
+
+// Must match the surface height in terrain_generation.wgsl
+fn surface_height(world_x: f32, world_z: f32) -> f32 {
+    let height_variation = sin(world_x * 0.05) * 5.0 + cos(world_z * 0.05) * 5.0;
+    return f32(TERRAIN_THRESHOLD) + height_variation;
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn evaluate_heights(
+    @builtin(global_invocation_id) gid: vec3<u32>,
+    @builtin(workgroup_id) workgroup_id: vec3<u32>
+) {
+    if (workgroup_id.z >= halo.tile_count) {
+        return;
+    }
+    let tile = tiles[workgroup_id.z];
+    if (gid.x >= tile.width || gid.y >= tile.depth) {
+        return;
+    }
+
+    let world_x = f32(tile.min_x + i32(gid.x));
+    let world_z = f32(tile.min_z + i32(gid.y));
+    target_heights[tile.offset + gid.x + gid.y * tile.width] = surface_height(world_x, world_z);
+}
+
+// Height moved between a column and one neighbour, positive when gaining
+fn slope_flow(height: f32, neighbour: f32) -> f32 {
+    let slope = neighbour - height;
+    return max(slope - halo.talus, 0.0) - max(-slope - halo.talus, 0.0);
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn erode_heights(
+    @builtin(global_invocation_id) gid: vec3<u32>,
+    @builtin(workgroup_id) workgroup_id: vec3<u32>
+) {
+    if (workgroup_id.z >= halo.tile_count) {
+        return;
+    }
+    let tile = tiles[workgroup_id.z];
+    if (gid.x >= tile.width || gid.y >= tile.depth) {
+        return;
+    }
+
+    let index = tile.offset + gid.x + gid.y * tile.width;
+    let height = source_heights[index];
+
+    // The outer ring has no neighbours inside the tile and keeps its height
+    if (gid.x == 0u || gid.y == 0u || gid.x + 1u == tile.width || gid.y + 1u == tile.depth) {
+        target_heights[index] = height;
+        return;
+    }
+
+    let flow = slope_flow(height, source_heights[index - 1u])
+        + slope_flow(height, source_heights[index + 1u])
+        + slope_flow(height, source_heights[index - tile.width])
+        + slope_flow(height, source_heights[index + tile.width]);
+    target_heights[index] = height + halo.erosion_rate * flow * 0.25;
+}
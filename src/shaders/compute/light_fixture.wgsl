@@ -0,0 +1,83 @@
+// Lighting Fixture Propagation
+// Lights a small standalone volume with the rules of the CPU reference in
+// light_reference_operations.rs, so the lighting harness can diff the two.
+// Every dispatch is one relaxation step: each voxel takes the brightest of
+// its current light, its emission and what its face neighbours pass on.
+// Light only ever grows, so voxels may read neighbours mid-update.
+//
+// Cell format: bits 0-3 = emission, bit 4 = opaque.
+// Light format: bits 0-7 = block light, bits 8-15 = sky light.
+
+struct FixtureParams {
+    // xyz = voxels per axis, w = 1 when skylight enters from the top
+    size: vec4<u32>,
+}
+
+@group(0) @binding(0) var<uniform> params: FixtureParams;
+@group(0) @binding(1) var<storage, read> cells: array<u32>;
+@group(0) @binding(2) var<storage, read_write> light: array<atomic<u32>>;
+
+const MAX_LIGHT: u32 = 15u;
+const FALLOFF: u32 = 1u;
+const EMISSION_MASK: u32 = 15u;
+const CELL_OPAQUE: u32 = 16u;
+const CHANNEL_MASK: u32 = 255u;
+const SKY_SHIFT: u32 = 8u;
+
+fn cell_index(cell: vec3<u32>) -> u32 {
+    let size = params.size.xyz;
+    return cell.x + cell.y * size.x + cell.z * size.x * size.y;
+}
+
+fn dimmed(level: u32) -> u32 {
+    return select(level - FALLOFF, 0u, level < FALLOFF);
+}
+
+@compute @workgroup_size(4, 4, 4)
+fn propagate_fixture_light(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let size = params.size.xyz;
+    if (any(gid >= size)) {
+        return;
+    }
+
+    let index = cell_index(gid);
+    let cell = cells[index];
+    let emission = cell & EMISSION_MASK;
+    if ((cell & CELL_OPAQUE) != 0u) {
+        atomicStore(&light[index], emission);
+        return;
+    }
+
+    let current = atomicLoad(&light[index]);
+    var block = max(emission, current & CHANNEL_MASK);
+    var sky = (current >> SKY_SHIFT) & CHANNEL_MASK;
+    if (params.size.w != 0u && gid.y == size.y - 1u) {
+        sky = MAX_LIGHT;
+    }
+
+    var offsets = array<vec3<i32>, 6>(
+        vec3<i32>(1, 0, 0),
+        vec3<i32>(-1, 0, 0),
+        vec3<i32>(0, 1, 0),
+        vec3<i32>(0, -1, 0),
+        vec3<i32>(0, 0, 1),
+        vec3<i32>(0, 0, -1),
+    );
+    for (var face = 0u; face < 6u; face++) {
+        let neighbour = vec3<i32>(gid) + offsets[face];
+        if (any(neighbour < vec3<i32>(0)) || any(neighbour >= vec3<i32>(size))) {
+            continue;
+        }
+        let neighbour_light = atomicLoad(&light[cell_index(vec3<u32>(neighbour))]);
+        block = max(block, dimmed(neighbour_light & CHANNEL_MASK));
+        let neighbour_sky = (neighbour_light >> SKY_SHIFT) & CHANNEL_MASK;
+        // Full skylight from the voxel above passes down without loss
+        if (face == 2u && neighbour_sky == MAX_LIGHT) {
+            sky = MAX_LIGHT;
+        } else {
+            sky = max(sky, dimmed(neighbour_sky));
+        }
+    }
+
+    atomicStore(&light[index], block | (sky << SKY_SHIFT));
+}
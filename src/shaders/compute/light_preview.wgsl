@@ -0,0 +1,170 @@
+// Light Placement Preview Propagation
+// Flood-fills the light of a single source through a small volume around the
+// targeted voxel. Opaque voxels block propagation, so the preview shows where
+// the light would actually reach.
+//
+// Cell format: bits 0-3 = light level, bit 4 = opaque.
+// Overlay texel: bits 0-3 = light level, bits 4-9 = faces that border an
+// opaque neighbor (0=+X, 1=-X, 2=+Y, 3=-Y, 4=+Z, 5=-Z).
+//
+// CHUNK_SIZE and VOXELS_PER_CHUNK are auto-generated.
+
+#include "voxel_palette.wgsl"
+
+struct LightPreviewUniform {
+    view_proj: mat4x4<f32>,
+    origin: vec4<i32>,
+    source: vec4<i32>,
+    chunk_origin: vec4<i32>,
+    chunk_slots: array<vec4<u32>, 2>,
+    chunk_fill: array<vec4<u32>, 2>,
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> preview: LightPreviewUniform;
+@group(0) @binding(1) var<storage, read> world_voxels: array<u32>;
+@group(0) @binding(2) var<storage, read> transparent_blocks: array<u32>;
+@group(0) @binding(3) var<storage, read> cells_in: array<u32>;
+@group(0) @binding(4) var<storage, read_write> cells_out: array<u32>;
+@group(0) @binding(5) var overlay: texture_storage_3d<r32uint, write>;
+@group(0) @binding(6) var<storage, read> palette_indices: array<u32>;
+@group(0) @binding(7) var<storage, read> palettes: array<u32>;
+
+const NO_SLOT: u32 = 0xFFFFFFFFu;
+const LEVEL_MASK: u32 = 15u;
+const CELL_OPAQUE: u32 = 16u;
+const FACE_SHIFT: u32 = 4u;
+
+fn extent() -> u32 {
+    return u32(preview.origin.w);
+}
+
+fn cell_index(cell: vec3<u32>) -> u32 {
+    let e = extent();
+    return cell.x + cell.y * e + cell.z * e * e;
+}
+
+fn in_volume(cell: vec3<i32>) -> bool {
+    let e = i32(extent());
+    return all(cell >= vec3<i32>(0)) && all(cell < vec3<i32>(e));
+}
+
+fn face_offset(face: u32) -> vec3<i32> {
+    var offsets = array<vec3<i32>, 6>(
+        vec3<i32>(1, 0, 0),
+        vec3<i32>(-1, 0, 0),
+        vec3<i32>(0, 1, 0),
+        vec3<i32>(0, -1, 0),
+        vec3<i32>(0, 0, 1),
+        vec3<i32>(0, 0, -1),
+    );
+    return offsets[face];
+}
+
+fn floor_div(a: i32, b: i32) -> i32 {
+    let q = a / b;
+    if (a % b != 0 && a < 0) {
+        return q - 1;
+    }
+    return q;
+}
+
+// Packed voxel at a world position inside the preview volume
+fn world_voxel(world: vec3<i32>) -> u32 {
+    let size = i32(CHUNK_SIZE);
+    let chunk = vec3<i32>(floor_div(world.x, size), floor_div(world.y, size), floor_div(world.z, size));
+    let rel = chunk - preview.chunk_origin.xyz;
+    let idx = u32(rel.x + rel.y * 2 + rel.z * 4);
+
+    let slot = preview.chunk_slots[idx / 4u][idx % 4u];
+    if (slot == NO_SLOT) {
+        return preview.chunk_fill[idx / 4u][idx % 4u];
+    }
+
+    let local = vec3<u32>(world - chunk * size);
+    let index = local.x + local.y * CHUNK_SIZE + local.z * CHUNK_SIZE * CHUNK_SIZE;
+    if ((slot & PALETTE_SLOT_FLAG) != 0u) {
+        return palette_voxel(slot & ~PALETTE_SLOT_FLAG, index);
+    }
+    return world_voxels[slot * VOXELS_PER_CHUNK + index];
+}
+
+fn is_transparent(block_id: u32) -> bool {
+    return ((transparent_blocks[block_id / 32u] >> (block_id % 32u)) & 1u) != 0u;
+}
+
+// Initialize cells from the world and place the source
+@compute @workgroup_size(4, 4, 4)
+fn seed_preview(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (any(gid >= vec3<u32>(extent()))) {
+        return;
+    }
+
+    let world = preview.origin.xyz + vec3<i32>(gid);
+    if (all(world == preview.source.xyz)) {
+        cells_out[cell_index(gid)] = u32(preview.source.w) & LEVEL_MASK;
+        return;
+    }
+
+    let block_id = world_voxel(world) & 0xFFFFu;
+    var cell = 0u;
+    if (!is_transparent(block_id)) {
+        cell = CELL_OPAQUE;
+    }
+    cells_out[cell_index(gid)] = cell;
+}
+
+// One propagation step: each open cell takes its brightest neighbor minus one
+@compute @workgroup_size(4, 4, 4)
+fn propagate_preview(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (any(gid >= vec3<u32>(extent()))) {
+        return;
+    }
+
+    let index = cell_index(gid);
+    let cell = cells_in[index];
+    if ((cell & CELL_OPAQUE) != 0u) {
+        cells_out[index] = cell;
+        return;
+    }
+
+    var level = cell & LEVEL_MASK;
+    for (var face = 0u; face < 6u; face++) {
+        let neighbor = vec3<i32>(gid) + face_offset(face);
+        if (!in_volume(neighbor)) {
+            continue;
+        }
+        let neighbor_level = cells_in[cell_index(vec3<u32>(neighbor))] & LEVEL_MASK;
+        if (neighbor_level > level + 1u) {
+            level = neighbor_level - 1u;
+        }
+    }
+
+    cells_out[index] = (cell & ~LEVEL_MASK) | level;
+}
+
+// Write lit open cells and the faces they share with opaque neighbors
+@compute @workgroup_size(4, 4, 4)
+fn resolve_preview(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (any(gid >= vec3<u32>(extent()))) {
+        return;
+    }
+
+    let cell = cells_in[cell_index(gid)];
+    let level = cell & LEVEL_MASK;
+    var texel = 0u;
+    if ((cell & CELL_OPAQUE) == 0u && level > 0u) {
+        var faces = 0u;
+        for (var face = 0u; face < 6u; face++) {
+            let neighbor = vec3<i32>(gid) + face_offset(face);
+            if (in_volume(neighbor) && (cells_in[cell_index(vec3<u32>(neighbor))] & CELL_OPAQUE) != 0u) {
+                faces |= 1u << face;
+            }
+        }
+        if (faces != 0u) {
+            texel = level | (faces << FACE_SHIFT);
+        }
+    }
+
+    textureStore(overlay, vec3<i32>(gid), vec4<u32>(texel, 0u, 0u, 0u));
+}
@@ -0,0 +1,39 @@
+// Palette Chunk Decode
+// Expands palettized chunks into full 32-bit world buffer slots, e.g. right
+// before a modification pass writes into them.
+//
+// VOXELS_PER_CHUNK is auto-generated.
+
+struct DecodeJob {
+    palette_slot: u32,
+    dense_slot: u32,
+    _padding0: u32,
+    _padding1: u32,
+}
+
+struct DecodeParams {
+    job_count: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+// Bindings
+@group(0) @binding(0) var<storage, read_write> world_voxels: array<u32>;
+@group(0) @binding(1) var<storage, read> palette_indices: array<u32>;
+@group(0) @binding(2) var<storage, read> palettes: array<u32>;
+@group(0) @binding(3) var<storage, read> jobs: array<DecodeJob>;
+@group(0) @binding(4) var<uniform> params: DecodeParams;
+
+#include "voxel_palette.wgsl"
+
+@compute @workgroup_size(256, 1, 1)
+fn decode_palette_chunks(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let job = gid.y;
+    if (job >= params.job_count || gid.x >= VOXELS_PER_CHUNK) {
+        return;
+    }
+
+    let decoded = palette_voxel(jobs[job].palette_slot, gid.x);
+    world_voxels[jobs[job].dense_slot * VOXELS_PER_CHUNK + gid.x] = decoded;
+}
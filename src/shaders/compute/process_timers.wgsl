@@ -0,0 +1,61 @@
+// Process Timer Advancement
+//
+// One thread per process. Active processes add their ticks (keeping the
+// fraction for the next dispatch), find their current stage from the
+// cumulative stage end ticks and complete once the duration is reached.
+// Mirrors advance_process_timer in parallel_processor_operations.rs.
+
+struct Params {
+    count: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+}
+
+struct StageRange {
+    first: u32,
+    count: u32,
+}
+
+const STATUS_ACTIVE: u32 = 1u;
+const STATUS_COMPLETED: u32 = 3u;
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> elapsed: array<u32>;
+@group(0) @binding(2) var<storage, read_write> remainder: array<f32>;
+@group(0) @binding(3) var<storage, read_write> status: array<u32>;
+@group(0) @binding(4) var<storage, read_write> stage: array<u32>;
+@group(0) @binding(5) var<storage, read> duration: array<u32>;
+@group(0) @binding(6) var<storage, read> delta: array<f32>;
+@group(0) @binding(7) var<storage, read> stage_ranges: array<StageRange>;
+@group(0) @binding(8) var<storage, read> stage_ends: array<u32>;
+
+@compute @workgroup_size(64)
+fn advance_processes(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= params.count || status[i] != STATUS_ACTIVE) {
+        return;
+    }
+
+    let total = remainder[i] + delta[i];
+    let whole = floor(total);
+    remainder[i] = total - whole;
+    let now = elapsed[i] + u32(whole);
+    elapsed[i] = now;
+
+    let range = stage_ranges[i];
+    var current = 0u;
+    for (var s = 0u; s < range.count; s++) {
+        if (now >= stage_ends[range.first + s]) {
+            current = s + 1u;
+        }
+    }
+    if (range.count > 0u) {
+        current = min(current, range.count - 1u);
+    }
+    stage[i] = current;
+
+    if (now >= duration[i]) {
+        status[i] = STATUS_COMPLETED;
+    }
+}
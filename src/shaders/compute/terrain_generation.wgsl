@@ -107,6 +107,17 @@ fn check_height_soa(world_y: i32) -> u32 {
     return 0u; // No custom block
 }
 
+// Surface height of a chunk column. Chunks planned into a batch read it
+// from the batch heightmap built by generation_halo.wgsl (_reserved holds
+// base, row stride and a has-heightmap flag); others use the raw formula.
+fn column_surface_height(chunk_meta: ChunkMetadata, x: u32, z: u32, world_x: f32, world_z: f32) -> f32 {
+    if (chunk_meta._reserved[2] != 0u) {
+        return halo_heights[chunk_meta._reserved[0] + x + z * chunk_meta._reserved[1]];
+    }
+    let height_variation = sin(world_x * 0.05) * 5.0 + cos(world_z * 0.05) * 5.0;
+    return f32(TERRAIN_THRESHOLD) + height_variation;
+}
+
 // Main terrain generation kernel
 @compute @workgroup_size(8, 4, 4)
 fn generate_terrain(
@@ -115,8 +126,8 @@ fn generate_terrain(
     @builtin(local_invocation_id) local_id: vec3<u32>
 ) {
     // Calculate chunk index and local workgroup within chunk
-    // For chunk_size=50 and workgroup_size=8x4x4, we need 7x13x13 workgroups per chunk
-    let workgroups_per_chunk_x = 7u; // (50 + 7) / 8 = 7
+    // Must match the dispatch in terrain_gpu.rs: ceil(CHUNK_SIZE / 8) workgroups per chunk in X
+    let workgroups_per_chunk_x = (CHUNK_SIZE + 7u) / 8u;
     let chunk_idx = workgroup_id.x / workgroups_per_chunk_x;
     let local_workgroup_x = workgroup_id.x % workgroups_per_chunk_x;
     
@@ -169,9 +180,7 @@ fn generate_terrain(
                 var skylight = 15u; // Full skylight by default
                 
                 // Improved terrain generation with height variation and proper surface topology
-                // Calculate terrain height with variation (matching CPU fallback algorithm)
-                let height_variation = sin(world_x * 0.05) * 5.0 + cos(world_z * 0.05) * 5.0;
-                let surface_height = f32(TERRAIN_THRESHOLD) + height_variation;
+                let surface_height = column_surface_height(chunk_meta, x, z, world_x, world_z);
                 
                 if (world_y < surface_height - 3.0) {
                     // Deep underground: stone
@@ -298,8 +307,8 @@ fn generate_terrain_vectorized(
     @builtin(local_invocation_id) local_id: vec3<u32>
 ) {
     // Calculate chunk index and local workgroup within chunk
-    // For chunk_size=50 and workgroup_size=8x4x4, we need 7x13x13 workgroups per chunk
-    let workgroups_per_chunk_x = 7u; // (50 + 7) / 8 = 7
+    // Must match the dispatch in terrain_gpu.rs: ceil(CHUNK_SIZE / 16) workgroups per chunk in X
+    let workgroups_per_chunk_x = (CHUNK_SIZE + 15u) / 16u;
     let chunk_idx = workgroup_id.x / workgroups_per_chunk_x;
     let local_workgroup_x = workgroup_id.x % workgroups_per_chunk_x;
     
@@ -328,11 +337,8 @@ fn generate_terrain_vectorized(
     );
     
     // Calculate local position for this thread (vectorized approach)
-    // For chunk_size=50 and workgroup_size=16x2x2, we need 4x25x25 workgroups per chunk
-    let workgroups_per_chunk_x_vec = 4u; // (50 + 15) / 16 = 4
-    let local_workgroup_x_vec = (workgroup_id.x % workgroups_per_chunk_x_vec);
     let local_pos = vec3<u32>(
-        local_workgroup_x_vec * 16u + local_id.x,
+        local_workgroup_x * 16u + local_id.x,
         workgroup_id.y * 2u + local_id.y,
         workgroup_id.z * 2u + local_id.z
     );
@@ -368,9 +374,7 @@ fn generate_terrain_vectorized(
                     var block_id = BLOCK_AIR;
                     var skylight = 15u;
                     
-                    // Calculate terrain height with variation (matching CPU fallback algorithm)
-                    let height_variation = sin(world_x * 0.05) * 5.0 + cos(world_z * 0.05) * 5.0;
-                    let surface_height = f32(TERRAIN_THRESHOLD) + height_variation;
+                    let surface_height = column_surface_height(chunk_meta, base_x, z, world_x, world_z);
                     
                     if (world_y < surface_height - 3.0) {
                         // Deep underground: stone
@@ -460,9 +464,7 @@ fn generate_terrain_vectorized(
                     var block_id = BLOCK_AIR;
                     var skylight = 15u;
                     
-                    // Calculate terrain height with variation (matching CPU fallback algorithm)
-                    let height_variation = sin(world_x * 0.05) * 5.0 + cos(world_z_offset * 0.05) * 5.0;
-                    let surface_height = 64.0 + height_variation;
+                    let surface_height = column_surface_height(chunk_meta, base_x, final_z, world_x, world_z_offset);
                     
                     if (world_y < surface_height - 3.0) {
                         // Deep underground: stone
@@ -1,8 +1,10 @@
 // GPU Mesh Generation Compute Shader
 // Generates chunk meshes entirely on GPU with zero CPU involvement
 
+#include "light_storage.wgsl"
+
 // Constants
-// CHUNK_SIZE is auto-generated from constants.rs
+// CHUNK_SIZE and VOXELS_PER_CHUNK are auto-generated from constants.rs
 const WORKGROUP_SIZE: u32 = 64u; // 4x4x4 voxels
 
 // Face constants for clarity
@@ -14,9 +16,29 @@ struct MeshRequest {
     lod_level: u32,
     buffer_index: u32,
     flags: u32,
-    _padding: vec2<u32>,
+    tint_offset: u32,
+    // World buffer slot holding the chunk's light, MESH_NO_LIGHT_SLOT if none
+    light_slot: u32,
 }
 
+// Request flag: color grass/foliage/water from the chunk's biome tint map
+const MESH_FLAG_BIOME_TINT: u32 = 1u;
+
+// First flag bit of the stitch mask: one bit per chunk face (same order as
+// the face encoding) whose neighbor is meshed at a finer LOD
+const MESH_STITCH_SHIFT: u32 = 8u;
+const MESH_STITCH_MASK: u32 = 63u;
+
+// Skirt depth in cells of the chunk's LOD (constants::chunk_lod)
+const SKIRT_DEPTH_CELLS: f32 = 1.0;
+const MAX_CHUNK_LOD: u32 = 4u;
+
+// Mesh request light slot of chunks without a full world buffer slot
+const MESH_NO_LIGHT_SLOT: u32 = 0xFFFFFFFFu;
+
+// MeshingParams::light_mode: light packed into the voxel words (bits 16-23)
+const MESH_LIGHT_PACKED: u32 = 1u;
+
 // Meshing parameters
 struct MeshingParams {
     chunk_size: u32,
@@ -25,7 +47,10 @@ struct MeshingParams {
     enable_ao: u32,
     max_vertices: u32,
     max_indices: u32,
-    _padding: vec2<u32>,
+    // 0 = dedicated lighting buffer, 1 = packed into the voxel words
+    light_mode: u32,
+    // Bit per block id (below 32) drawn by the smooth terrain mesh
+    smooth_blocks: u32,
 }
 
 // Mesh metadata
@@ -55,6 +80,11 @@ struct Vertex {
 @group(0) @binding(4) var<storage, read_write> metadata: array<MeshMetadata>;
 @group(0) @binding(5) var<storage, read_write> indirect_commands: array<u32>;
 @group(0) @binding(6) var<uniform> params: MeshingParams;
+// Per-chunk biome colors at every column corner, RGBA8 packed:
+// grass corners, then foliage, then water ((chunk_size + 1)^2 each)
+@group(0) @binding(7) var<storage, read> biome_tints: array<u32>;
+// Dedicated lighting buffer of the world (placeholder in packed mode)
+@group(0) @binding(8) var<storage, read> world_light: array<u32>;
 
 // Shared memory for face culling
 var<workgroup> voxel_cache: array<u32, 512>; // 8x8x8 with padding
@@ -79,6 +109,16 @@ fn get_voxel(world_pos: vec3<i32>) -> u32 {
     }
 }
 
+// Block the cubes are built from: air for blocks the smooth terrain mesh
+// draws, so they get no cube and cubic neighbors keep the faces the smooth
+// surface ends against (cubic_mesh_block in smooth_terrain_operations.rs)
+fn cubic_voxel(voxel: u32) -> u32 {
+    if (voxel < 32u && ((params.smooth_blocks >> voxel) & 1u) != 0u) {
+        return 0u;
+    }
+    return voxel;
+}
+
 // Check if voxel is transparent
 fn is_transparent(voxel: u32) -> bool {
     return voxel == 0u || voxel == 6u; // AIR (0) or WATER (6)
@@ -122,6 +162,52 @@ fn compute_face_normal(face: u32) -> vec3<f32> {
     return normal;
 }
 
+// Biome tint kind of a voxel type: 0=grass, 1=foliage, 2=water, none otherwise
+const TINT_NONE: u32 = 0xFFFFFFFFu;
+
+fn get_tint_kind(voxel_type: u32) -> u32 {
+    switch (voxel_type) {
+        case 1u, 26u: { return 0u; } // GRASS, TALL_GRASS
+        case 7u: { return 1u; }      // LEAVES
+        case 6u: { return 2u; }      // WATER
+        default: { return TINT_NONE; }
+    }
+}
+
+// Blended biome color at a chunk-local column corner
+fn get_biome_tint(request: MeshRequest, tint_kind: u32, corner_x: f32, corner_z: f32) -> vec3<f32> {
+    let corners = params.chunk_size + 1u;
+    let x = min(u32(max(corner_x, 0.0)), params.chunk_size);
+    let z = min(u32(max(corner_z, 0.0)), params.chunk_size);
+    let rgba = biome_tints[request.tint_offset + tint_kind * corners * corners + z * corners + x];
+    return vec3<f32>(
+        f32((rgba >> 24u) & 0xFFu),
+        f32((rgba >> 16u) & 0xFFu),
+        f32((rgba >> 8u) & 0xFFu)
+    ) / 255.0;
+}
+
+// Light reaching a face: the light of the chunk-local voxel in front of it.
+// Faces on the chunk border and chunks without light stay fully lit.
+fn get_face_light(request: MeshRequest, neighbor_local: vec3<i32>) -> f32 {
+    let size = i32(CHUNK_SIZE);
+    if (request.light_slot == MESH_NO_LIGHT_SLOT ||
+        any(neighbor_local < vec3<i32>(0)) || any(neighbor_local >= vec3<i32>(size))) {
+        return 1.0;
+    }
+    let cell = vec3<u32>(neighbor_local);
+    let local_index = cell.x + cell.y * CHUNK_SIZE + cell.z * CHUNK_SIZE * CHUNK_SIZE;
+    var light: u32;
+    if (params.light_mode == MESH_LIGHT_PACKED) {
+        let voxel = world_data[request.light_slot * VOXELS_PER_CHUNK + local_index];
+        light = ((voxel >> 16u) & 0xFu) | (((voxel >> 20u) & 0xFu) << 8u);
+    } else {
+        let word = world_light[light_word_index(request.light_slot, local_index, CHUNK_SIZE)];
+        light = unpack_voxel_light(word, local_index);
+    }
+    return voxel_light_intensity(light, 1.0);
+}
+
 // Add a face to the mesh
 fn add_face(
     request_idx: u32,
@@ -139,6 +225,12 @@ fn add_face(
     // Get face color
     let color = get_voxel_color(voxel_type);
     let normal = compute_face_normal(face);
+    let request = requests[request_idx];
+    let light = get_face_light(request, vec3<i32>(local_pos) + vec3<i32>(normal));
+    var tint_kind = TINT_NONE;
+    if ((request.flags & MESH_FLAG_BIOME_TINT) != 0u) {
+        tint_kind = get_tint_kind(voxel_type);
+    }
     
     // Add vertices
     for (var i = 0u; i < 4u; i = i + 1u) {
@@ -149,23 +241,97 @@ fn add_face(
         var vertex: Vertex;
         vertex.position = vertex_pos;
         vertex.color = color;
+        if (tint_kind != TINT_NONE) {
+            vertex.color = get_biome_tint(request, tint_kind, vertex_pos.x, vertex_pos.z);
+        }
         vertex.normal = normal;
-        vertex.light = 1.0;  // Full light for now
+        vertex.light = light;
         vertex.ao = 1.0;     // No ambient occlusion for now
         
         vertices[vertex_offset] = vertex;
     }
     
     // Add indices (two triangles)
-    // Indices need to include base_vertex_offset for proper indexing in shared buffer
+    // Indices are relative to the request's first vertex; draws add it back
+    // through base_vertex
     let base_idx = base_index_offset + index_idx;
-    let absolute_vertex_base = base_vertex_offset + vertex_idx;
-    indices[base_idx + 0u] = absolute_vertex_base + 0u;
-    indices[base_idx + 1u] = absolute_vertex_base + 1u;
-    indices[base_idx + 2u] = absolute_vertex_base + 2u;
-    indices[base_idx + 3u] = absolute_vertex_base + 0u;
-    indices[base_idx + 4u] = absolute_vertex_base + 2u;
-    indices[base_idx + 5u] = absolute_vertex_base + 3u;
+    indices[base_idx + 0u] = vertex_idx + 0u;
+    indices[base_idx + 1u] = vertex_idx + 1u;
+    indices[base_idx + 2u] = vertex_idx + 2u;
+    indices[base_idx + 3u] = vertex_idx + 0u;
+    indices[base_idx + 4u] = vertex_idx + 2u;
+    indices[base_idx + 5u] = vertex_idx + 3u;
+}
+
+// Skirts for a face of a voxel on stitched chunk faces: for every stitched
+// chunk face the voxel touches (other than along the face's own axis), a quad
+// in the chunk face plane hangs from the face's edge on it, running against
+// the face normal one LOD cell deep. It covers the crack the finer neighbor
+// leaves along that edge.
+fn add_face_skirts(
+    request_idx: u32,
+    local_pos: vec3<f32>,
+    face: u32,
+    voxel_type: u32,
+    stitch: u32
+) {
+    let request = requests[request_idx];
+    let face_axis = face / 2u;
+    let normal = compute_face_normal(face);
+    let cell = f32(1u << min(request.lod_level, MAX_CHUNK_LOD));
+    let hang = -normal * (SKIRT_DEPTH_CELLS * cell);
+    let light = get_face_light(request, vec3<i32>(local_pos) + vec3<i32>(normal));
+    let color = get_voxel_color(voxel_type);
+
+    for (var boundary = 0u; boundary < 6u; boundary = boundary + 1u) {
+        let axis = boundary / 2u;
+        if ((stitch & (1u << boundary)) == 0u || axis == face_axis) {
+            continue;
+        }
+        let positive = (boundary & 1u) == 0u;
+        let plane = select(0.0, f32(params.chunk_size), positive);
+        if (local_pos[axis] + select(0.0, 1.0, positive) != plane) {
+            continue;
+        }
+
+        // Edge of the face on the boundary plane, along the remaining axis
+        let run_axis = 3u - axis - face_axis;
+        var edge_start = local_pos;
+        edge_start[axis] = plane;
+        edge_start[face_axis] = local_pos[face_axis] + select(0.0, 1.0, (face & 1u) == 0u);
+        var edge_end = edge_start;
+        edge_end[run_axis] = edge_end[run_axis] + 1.0;
+        var corners = array<vec3<f32>, 4>(
+            edge_start,
+            edge_end,
+            edge_end + hang,
+            edge_start + hang
+        );
+
+        // Wind the quad to face out of the chunk
+        let boundary_normal = compute_face_normal(boundary);
+        let facing = dot(cross(edge_end - edge_start, hang), boundary_normal);
+        let vertex_idx = atomicAdd(&metadata[request_idx].vertex_count, 4u);
+        let index_idx = atomicAdd(&metadata[request_idx].index_count, 6u);
+        for (var i = 0u; i < 4u; i = i + 1u) {
+            var vertex: Vertex;
+            vertex.position = corners[i];
+            vertex.color = color;
+            vertex.normal = boundary_normal;
+            vertex.light = light;
+            vertex.ao = 1.0;
+            vertices[request_idx * params.max_vertices + vertex_idx + i] = vertex;
+        }
+        let base_idx = request_idx * params.max_indices + index_idx;
+        let second = select(3u, 1u, facing > 0.0);
+        let third = select(1u, 3u, facing > 0.0);
+        indices[base_idx + 0u] = vertex_idx + 0u;
+        indices[base_idx + 1u] = vertex_idx + second;
+        indices[base_idx + 2u] = vertex_idx + 2u;
+        indices[base_idx + 3u] = vertex_idx + 0u;
+        indices[base_idx + 4u] = vertex_idx + 2u;
+        indices[base_idx + 5u] = vertex_idx + third;
+    }
 }
 
 // Get color for voxel type
@@ -211,7 +377,7 @@ fn generate_mesh(
         voxel_offset.z < i32(params.chunk_size)) {
         
         let world_pos = chunk_origin + voxel_offset;
-        let voxel = get_voxel(world_pos);
+        let voxel = cubic_voxel(get_voxel(world_pos));
         
         // Skip air voxels
         if (!is_transparent(voxel)) {
@@ -221,11 +387,15 @@ fn generate_mesh(
             for (var face = 0u; face < 6u; face = face + 1u) {
                 let normal = compute_face_normal(face);
                 let neighbor_pos = world_pos + vec3<i32>(normal);
-                let neighbor = get_voxel(neighbor_pos);
+                let neighbor = cubic_voxel(get_voxel(neighbor_pos));
                 
                 // Only add face if neighbor is transparent
                 if (is_transparent(neighbor)) {
                     add_face(request_idx, local_pos, face, voxel);
+                    let stitch = (request.flags >> MESH_STITCH_SHIFT) & MESH_STITCH_MASK;
+                    if (stitch != 0u) {
+                        add_face_skirts(request_idx, local_pos, face, voxel, stitch);
+                    }
                 }
             }
         }
@@ -0,0 +1,119 @@
+// Anti-Aliasing Post Passes
+// Fullscreen triangle reading the offscreen scene. fs_fxaa finds edges from
+// luma contrast and blurs along them; fs_taa blends the scene into a history
+// of previous frames, clamping the history to the current neighbourhood to
+// limit ghosting. TAA writes the result to the target and the next history.
+// fs_upscale copies a scene drawn below native resolution into the target.
+
+struct AntiAliasingUniform {
+    inv_resolution: vec2<f32>,
+    // Weight of the current frame (1 = ignore history)
+    taa_blend: f32,
+    fxaa_edge_threshold: f32,
+    fxaa_edge_threshold_min: f32,
+    fxaa_span_max: f32,
+    _padding0: f32,
+    _padding1: f32,
+}
+
+@group(0) @binding(0) var<uniform> params: AntiAliasingUniform;
+@group(0) @binding(1) var linear_sampler: sampler;
+@group(0) @binding(2) var scene_texture: texture_2d<f32>;
+@group(0) @binding(3) var history_texture: texture_2d<f32>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Oversized triangle covering the screen
+    let corner = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(corner * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(corner.x, 1.0 - corner.y);
+    return out;
+}
+
+fn luma(color: vec3<f32>) -> f32 {
+    return dot(color, vec3<f32>(0.299, 0.587, 0.114));
+}
+
+// Explicit LOD: the early-outs below make control flow non-uniform
+fn scene_at(uv: vec2<f32>) -> vec3<f32> {
+    return textureSampleLevel(scene_texture, linear_sampler, uv, 0.0).rgb;
+}
+
+@fragment
+fn fs_fxaa(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = params.inv_resolution;
+    let color_m = scene_at(in.uv);
+    let luma_m = luma(color_m);
+    let luma_nw = luma(scene_at(in.uv + vec2<f32>(-1.0, -1.0) * texel));
+    let luma_ne = luma(scene_at(in.uv + vec2<f32>(1.0, -1.0) * texel));
+    let luma_sw = luma(scene_at(in.uv + vec2<f32>(-1.0, 1.0) * texel));
+    let luma_se = luma(scene_at(in.uv + vec2<f32>(1.0, 1.0) * texel));
+
+    let luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    let luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+    let range = luma_max - luma_min;
+    if (range < max(params.fxaa_edge_threshold_min, luma_max * params.fxaa_edge_threshold)) {
+        return vec4<f32>(color_m, 1.0);
+    }
+
+    // Edge direction is perpendicular to the luma gradient
+    var dir = vec2<f32>(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se)
+    );
+    let dir_reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.03125, 1.0 / 128.0);
+    let inv_dir_min = 1.0 / (min(abs(dir.x), abs(dir.y)) + dir_reduce);
+    dir = clamp(dir * inv_dir_min, vec2<f32>(-params.fxaa_span_max), vec2<f32>(params.fxaa_span_max)) * texel;
+
+    let color_a = 0.5 * (scene_at(in.uv + dir * (1.0 / 3.0 - 0.5)) + scene_at(in.uv + dir * (2.0 / 3.0 - 0.5)));
+    let color_b = color_a * 0.5 + 0.25 * (scene_at(in.uv - dir * 0.5) + scene_at(in.uv + dir * 0.5));
+
+    // The wide blur crossed another edge: keep the narrow one
+    let luma_b = luma(color_b);
+    if (luma_b < luma_min || luma_b > luma_max) {
+        return vec4<f32>(color_a, 1.0);
+    }
+    return vec4<f32>(color_b, 1.0);
+}
+
+struct TaaOutput {
+    @location(0) color: vec4<f32>,
+    @location(1) history: vec4<f32>,
+};
+
+@fragment
+fn fs_taa(in: VertexOutput) -> TaaOutput {
+    let texel = params.inv_resolution;
+    let current = scene_at(in.uv);
+
+    var neighbourhood_min = current;
+    var neighbourhood_max = current;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let neighbour = scene_at(in.uv + vec2<f32>(f32(x), f32(y)) * texel);
+            neighbourhood_min = min(neighbourhood_min, neighbour);
+            neighbourhood_max = max(neighbourhood_max, neighbour);
+        }
+    }
+
+    let history = textureSampleLevel(history_texture, linear_sampler, in.uv, 0.0).rgb;
+    let clamped_history = clamp(history, neighbourhood_min, neighbourhood_max);
+    let resolved = mix(clamped_history, current, params.taa_blend);
+
+    var out: TaaOutput;
+    out.color = vec4<f32>(resolved, 1.0);
+    out.history = vec4<f32>(resolved, 1.0);
+    return out;
+}
+
+@fragment
+fn fs_upscale(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(scene_at(in.uv), 1.0);
+}
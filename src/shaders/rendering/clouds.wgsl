@@ -0,0 +1,49 @@
+// Cloud Layer
+// A horizontal plane at the cloud altitude, centered on the camera. The
+// fragment shader samples the shared cloud density, shades thicker areas
+// darker to fake volume and fades the plane out toward its edge.
+
+#include "cloud_shadow.wgsl"
+
+@group(0) @binding(0) var<uniform> clouds: CloudUniform;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_xz: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Two triangles covering [-1, 1]^2
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, -1.0), vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, 1.0), vec2<f32>(-1.0, 1.0)
+    );
+    let corner = corners[vertex_index];
+    let world_xz = clouds.camera_pos.xz + corner * clouds.wind.z;
+
+    var out: VertexOutput;
+    out.clip_position = clouds.view_proj * vec4<f32>(world_xz.x, clouds.layer.x, world_xz.y, 1.0);
+    out.world_xz = world_xz;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let density = cloud_density(in.world_xz, clouds);
+    if (density <= 0.001) {
+        discard;
+    }
+
+    // Denser cores read as thicker, so they let less sunlight through
+    let thickness = clamp(density * clouds.layer.y / 100.0, 0.0, 1.0);
+    let sun_height = clamp(clouds.sun_direction.y, 0.0, 1.0);
+    let lit = clouds.sun_color.rgb * sun_height * (1.0 - 0.5 * thickness);
+    let color = clouds.ambient_color.rgb * 0.6 + lit * 0.6;
+
+    // Fade toward the edge of the plane so it never ends in a hard line
+    let offset = abs(in.world_xz - clouds.camera_pos.xz) / clouds.wind.z;
+    let edge_fade = 1.0 - smoothstep(0.6, 1.0, max(offset.x, offset.y));
+
+    return vec4<f32>(color, density * clouds.sun_color.a * edge_fade);
+}
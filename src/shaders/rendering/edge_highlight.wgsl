@@ -0,0 +1,93 @@
+// Edge Highlight Post Pass
+// Fullscreen triangle reading the depth and normal targets the world pass
+// wrote. A pixel is on an edge when a neighbour up to half the outline
+// width away has a depth step or a normal change above the thresholds; the
+// topology mode also counts face id changes and darkens everything else.
+// The result blends over the final image.
+
+struct EdgeHighlightUniform {
+    color: vec4<f32>,
+    inv_resolution: vec2<f32>,
+    thickness: f32,
+    depth_threshold: f32,
+    normal_threshold: f32,
+    // 1 in the topology mode
+    topology: f32,
+    background_dim: f32,
+    _padding: f32,
+}
+
+@group(0) @binding(0) var<uniform> params: EdgeHighlightUniform;
+// Linear view depth, 0 where nothing was drawn
+@group(0) @binding(1) var depth_texture: texture_2d<f32>;
+// Face normal in rgb, face id in alpha
+@group(0) @binding(2) var normal_texture: texture_2d<f32>;
+
+// Smallest face id step written by the world pass
+const FACE_ID_EPSILON: f32 = 0.0005;
+
+// Neighbours checked in each direction at most (MAX_THICKNESS / 2)
+const MAX_REACH: i32 = 2;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Oversized triangle covering the screen
+    let corner = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(corner * 2.0 - 1.0, 0.0, 1.0);
+    return out;
+}
+
+fn clamp_pixel(pixel: vec2<i32>) -> vec2<i32> {
+    let size = vec2<i32>(textureDimensions(depth_texture));
+    return clamp(pixel, vec2<i32>(0, 0), size - vec2<i32>(1, 1));
+}
+
+fn is_edge(center: vec2<i32>, neighbour: vec2<i32>) -> bool {
+    let depth_a = textureLoad(depth_texture, clamp_pixel(center), 0).r;
+    let depth_b = textureLoad(depth_texture, clamp_pixel(neighbour), 0).r;
+    if (depth_a <= 0.0 && depth_b <= 0.0) {
+        return false;
+    }
+    // Silhouette against the sky
+    if (depth_a <= 0.0 || depth_b <= 0.0) {
+        return true;
+    }
+    if (abs(depth_a - depth_b) / min(depth_a, depth_b) > params.depth_threshold) {
+        return true;
+    }
+
+    let normal_a = textureLoad(normal_texture, clamp_pixel(center), 0);
+    let normal_b = textureLoad(normal_texture, clamp_pixel(neighbour), 0);
+    if (1.0 - dot(normal_a.xyz, normal_b.xyz) > params.normal_threshold) {
+        return true;
+    }
+    return params.topology > 0.5 && abs(normal_a.w - normal_b.w) > FACE_ID_EPSILON;
+}
+
+@fragment
+fn fs_edges(in: VertexOutput) -> @location(0) vec4<f32> {
+    let pixel = vec2<i32>(in.clip_position.xy);
+    let reach = clamp(i32(round(params.thickness * 0.5)), 1, MAX_REACH);
+
+    var edge = false;
+    for (var offset = 1; offset <= MAX_REACH; offset = offset + 1) {
+        if (offset > reach || edge) {
+            break;
+        }
+        edge = is_edge(pixel, pixel + vec2<i32>(offset, 0))
+            || is_edge(pixel, pixel - vec2<i32>(offset, 0))
+            || is_edge(pixel, pixel + vec2<i32>(0, offset))
+            || is_edge(pixel, pixel - vec2<i32>(0, offset));
+    }
+
+    if (edge) {
+        return params.color;
+    }
+    return vec4<f32>(0.0, 0.0, 0.0, params.background_dim);
+}
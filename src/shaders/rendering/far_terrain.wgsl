@@ -0,0 +1,93 @@
+// Far Terrain Ring Rendering
+// Draws the tessellated heightfield ring, displacing vertices from the
+// heightmap and dithering out inside the voxel render distance so the ring
+// hands over to real chunks without a hard seam.
+
+struct FarTerrainUniform {
+    view_proj: mat4x4<f32>,
+    camera_pos: vec4<f32>,
+    heightmap_origin: vec2<f32>,
+    texel_size: f32,
+    resolution: u32,
+    inner_radius: f32,
+    outer_radius: f32,
+    blend_width: f32,
+    sea_level: f32,
+    boundary_sink: f32,
+    surface_mode: u32,
+    flat_height: f32,
+    _padding0: f32,
+}
+
+@group(0) @binding(0) var<uniform> far: FarTerrainUniform;
+@group(0) @binding(1) var heightmap: texture_2d<f32>;
+
+struct VertexInput {
+    // Position relative to the heightmap center; y is ignored
+    @location(0) position: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_xz: vec2<f32>,
+    @location(1) color: vec3<f32>,
+    @location(2) normal: vec3<f32>,
+};
+
+fn load_texel(world_xz: vec2<f32>) -> vec4<f32> {
+    let max_texel = i32(far.resolution) - 1;
+    let texel = vec2<i32>(floor((world_xz - far.heightmap_origin) / far.texel_size));
+    return textureLoad(heightmap, clamp(texel, vec2<i32>(0), vec2<i32>(max_texel)), 0);
+}
+
+// 0 inside the voxel region, 1 past the blend band (square distance, like chunk loading)
+fn boundary_fade(world_xz: vec2<f32>) -> f32 {
+    let offset = abs(world_xz - far.camera_pos.xz);
+    let dist = max(offset.x, offset.y);
+    return smoothstep(far.inner_radius, far.inner_radius + far.blend_width, dist);
+}
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    let half_extent = f32(far.resolution) * far.texel_size * 0.5;
+    let world_xz = input.position.xz + far.heightmap_origin + vec2<f32>(half_extent);
+
+    let texel = load_texel(world_xz);
+    let texel_step = far.texel_size;
+    let dx = load_texel(world_xz + vec2<f32>(texel_step, 0.0)).a - load_texel(world_xz - vec2<f32>(texel_step, 0.0)).a;
+    let dz = load_texel(world_xz + vec2<f32>(0.0, texel_step)).a - load_texel(world_xz - vec2<f32>(0.0, texel_step)).a;
+
+    // Sink toward the boundary so real voxels win the depth test where both draw
+    let height = texel.a - (1.0 - boundary_fade(world_xz)) * far.boundary_sink;
+
+    out.clip_position = far.view_proj * vec4<f32>(world_xz.x, height, world_xz.y, 1.0);
+    out.world_xz = world_xz;
+    out.color = texel.rgb;
+    out.normal = normalize(vec3<f32>(-dx, 2.0 * texel_step, -dz));
+    return out;
+}
+
+// 4x4 ordered dither threshold
+fn dither_threshold(pixel: vec2<f32>) -> f32 {
+    let p = vec2<u32>(pixel) % 4u;
+    let index = p.x + p.y * 4u;
+    var bayer = array<f32, 16>(
+        0.0, 8.0, 2.0, 10.0,
+        12.0, 4.0, 14.0, 6.0,
+        3.0, 11.0, 1.0, 9.0,
+        15.0, 7.0, 13.0, 5.0
+    );
+    return (bayer[index] + 0.5) / 16.0;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    if (boundary_fade(in.world_xz) <= dither_threshold(in.clip_position.xy)) {
+        discard;
+    }
+
+    let sun_dir = normalize(vec3<f32>(0.3, 1.0, 0.2));
+    let diffuse = max(dot(normalize(in.normal), sun_dir), 0.0);
+    return vec4<f32>(in.color * (0.35 + 0.65 * diffuse), 1.0);
+}
@@ -14,7 +14,7 @@ struct DrawMetadata {
     material_id: u32,
     mesh_id: u32,
     instance_offset: u32,
-    flags: u32,                  // bit 0 = visible, bit 2 = always visible
+    flags: u32,                  // bit 0 = visible, bit 2 = always visible, bit 4 = instanced
 };
 
 struct IndirectCommand {
@@ -47,12 +47,13 @@ const FLAG_VISIBLE: u32 = 1u;
 const FLAG_SKIP_FRUSTUM: u32 = 2u;
 const FLAG_ALWAYS_VISIBLE: u32 = 4u;
 const FLAG_SHADOW_CASTER: u32 = 8u;
+// Instanced draw: mesh_id holds the instance count
+const FLAG_INSTANCED: u32 = 16u;
 
 // Mesh constants from constants.rs buffer_layouts
 const CUBE_INDEX_COUNT: u32 = 36u;
 
-// Culling constants - TODO: These should come from generated constants
-const MAX_RENDER_DISTANCE: f32 = 500.0;
+// MAX_RENDER_DISTANCE is auto-generated from constants.rs
 
 // Check if a sphere is inside the frustum
 fn sphere_inside_frustum(center: vec3<f32>, radius: f32) -> bool {
@@ -126,9 +127,10 @@ fn cull_objects(@builtin(global_invocation_id) global_id: vec3<u32>) {
     // Get index offset from lod_info.w for merged meshes
     let index_offset = u32(metadata.lod_info.w);
     
+    let instance_count = select(1u, metadata.mesh_id, (metadata.flags & FLAG_INSTANCED) != 0u);
     indirect_commands[draw_index] = IndirectCommand(
         actual_index_count,          // index_count - use actual mesh index count
-        1u,                          // instance_count - one instance per draw
+        instance_count,              // instance_count - one unless instanced
         index_offset,                // first_index - use offset for merged meshes
         0,                           // base_vertex
         metadata.instance_offset     // first_instance - use the instance offset
@@ -0,0 +1,97 @@
+// Light Placement Preview Overlay
+// Draws a translucent tint on every face lit by the previewed light source.
+// One instance per (cell, face); faces without light or without an opaque
+// neighbor collapse to a degenerate quad.
+
+struct LightPreviewUniform {
+    view_proj: mat4x4<f32>,
+    origin: vec4<i32>,
+    source: vec4<i32>,
+    chunk_origin: vec4<i32>,
+    chunk_slots: array<vec4<u32>, 2>,
+    chunk_fill: array<vec4<u32>, 2>,
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> preview: LightPreviewUniform;
+@group(0) @binding(1) var overlay: texture_3d<u32>;
+
+const LEVEL_MASK: u32 = 15u;
+const FACE_SHIFT: u32 = 4u;
+const MAX_PREVIEW_LEVEL: f32 = 15.0;
+// Lift off the lit surface to avoid z-fighting (voxels)
+const SURFACE_OFFSET: f32 = 0.01;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) intensity: f32,
+}
+
+fn face_normal(face: u32) -> vec3<f32> {
+    var normals = array<vec3<f32>, 6>(
+        vec3<f32>(1.0, 0.0, 0.0),
+        vec3<f32>(-1.0, 0.0, 0.0),
+        vec3<f32>(0.0, 1.0, 0.0),
+        vec3<f32>(0.0, -1.0, 0.0),
+        vec3<f32>(0.0, 0.0, 1.0),
+        vec3<f32>(0.0, 0.0, -1.0),
+    );
+    return normals[face];
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(2.0, 2.0, 2.0, 1.0);
+    out.intensity = 0.0;
+
+    let e = u32(preview.origin.w);
+    let face = instance_index % 6u;
+    let cell_id = instance_index / 6u;
+    let cell = vec3<u32>(cell_id % e, (cell_id / e) % e, cell_id / (e * e));
+
+    let texel = textureLoad(overlay, vec3<i32>(cell), 0).x;
+    let level = texel & LEVEL_MASK;
+    if (level == 0u || ((texel >> (FACE_SHIFT + face)) & 1u) == 0u) {
+        return out;
+    }
+
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-0.5, -0.5),
+        vec2<f32>(0.5, -0.5),
+        vec2<f32>(0.5, 0.5),
+        vec2<f32>(-0.5, -0.5),
+        vec2<f32>(0.5, 0.5),
+        vec2<f32>(-0.5, 0.5),
+    );
+    let corner = corners[vertex_index % 6u];
+
+    // Tangent axes of the face plane
+    let axis = face / 2u;
+    var tangent_u = vec3<f32>(0.0, 1.0, 0.0);
+    var tangent_v = vec3<f32>(0.0, 0.0, 1.0);
+    if (axis == 1u) {
+        tangent_u = vec3<f32>(1.0, 0.0, 0.0);
+    } else if (axis == 2u) {
+        tangent_u = vec3<f32>(1.0, 0.0, 0.0);
+        tangent_v = vec3<f32>(0.0, 1.0, 0.0);
+    }
+
+    let center = vec3<f32>(preview.origin.xyz + vec3<i32>(cell)) + vec3<f32>(0.5);
+    let world = center
+        + face_normal(face) * (0.5 - SURFACE_OFFSET)
+        + tangent_u * corner.x
+        + tangent_v * corner.y;
+
+    out.clip_position = preview.view_proj * vec4<f32>(world, 1.0);
+    out.intensity = f32(level) / MAX_PREVIEW_LEVEL;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(preview.color.rgb, preview.color.a * in.intensity);
+}
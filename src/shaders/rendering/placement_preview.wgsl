@@ -0,0 +1,42 @@
+// Block Placement Preview Ghost
+// Draws the active block's model, already turned to the orientation it
+// would be placed with, as a translucent tinted cube at the target voxel.
+
+struct PlacementPreviewUniform {
+    view_proj: mat4x4<f32>,
+    // xyz = world min corner of the target voxel, w = inflation (voxels)
+    origin: vec4<f32>,
+    // rgb = tint, a = alpha
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> preview: PlacementPreviewUniform;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) shade: f32,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) shade: f32,
+}
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    // Grow around the block center so the ghost sits just outside the
+    // faces of neighbouring blocks
+    let scale = 1.0 + 2.0 * preview.origin.w;
+    let local = (input.position - vec3<f32>(0.5)) * scale + vec3<f32>(0.5);
+
+    var out: VertexOutput;
+    out.clip_position = preview.view_proj * vec4<f32>(preview.origin.xyz + local, 1.0);
+    out.shade = input.shade;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let rgb = min(preview.color.rgb * in.shade, vec3<f32>(1.0));
+    return vec4<f32>(rgb, preview.color.a);
+}
@@ -0,0 +1,99 @@
+// Procedural Sky
+// Fullscreen triangle at the far plane. Each pixel reconstructs its view ray
+// and shades a horizon-to-zenith gradient, sun and moon discs, a hashed star
+// field at night and a grey cloud tint. Colors come from sky_operations.rs.
+
+struct SkyUniform {
+    inv_view_proj: mat4x4<f32>,
+    // xyz = direction, w = cosine of the disc angular radius
+    sun_direction: vec4<f32>,
+    moon_direction: vec4<f32>,
+    // a = star visibility
+    zenith_color: vec4<f32>,
+    // a = cloud coverage
+    horizon_color: vec4<f32>,
+    // a = star grid cells per unit of view direction
+    sun_color: vec4<f32>,
+    // a = star density
+    moon_color: vec4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> sky: SkyUniform;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Oversized triangle covering the screen
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    let ndc = uv * 2.0 - 1.0;
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(ndc, 1.0, 1.0);
+    out.ndc = ndc;
+    return out;
+}
+
+fn view_ray(ndc: vec2<f32>) -> vec3<f32> {
+    let far_point = sky.inv_view_proj * vec4<f32>(ndc, 1.0, 1.0);
+    let near_point = sky.inv_view_proj * vec4<f32>(ndc, 0.0, 1.0);
+    return normalize(far_point.xyz / far_point.w - near_point.xyz / near_point.w);
+}
+
+fn hash3(p: vec3<f32>) -> f32 {
+    let q = fract(p * vec3<f32>(0.1031, 0.1030, 0.0973));
+    let r = q + dot(q, q.yxz + 33.33);
+    return fract((r.x + r.y) * r.z);
+}
+
+fn disc(ray: vec3<f32>, direction: vec4<f32>) -> f32 {
+    let cos_angle = dot(ray, direction.xyz);
+    // Soft edge one tenth of the disc radius wide
+    let edge = (1.0 - direction.w) * 0.2;
+    return smoothstep(direction.w - edge, direction.w + edge, cos_angle);
+}
+
+fn stars(ray: vec3<f32>) -> f32 {
+    let density = sky.moon_color.a;
+    if (density <= 0.0 || ray.y <= 0.0) {
+        return 0.0;
+    }
+    let grid_scale = sky.sun_color.a;
+    let cell = floor(ray * grid_scale);
+    let h = hash3(cell);
+    if (h > density) {
+        return 0.0;
+    }
+    // Star at a hashed offset inside its cell, sized by its brightness
+    let center = (cell + vec3<f32>(hash3(cell + 17.0), hash3(cell + 31.0), hash3(cell + 53.0))) / grid_scale;
+    let dist = length(ray - normalize(center)) * grid_scale;
+    let brightness = 0.4 + 0.6 * (h / density);
+    return brightness * (1.0 - smoothstep(0.05, 0.25, dist));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let ray = view_ray(in.ndc);
+    let star_visibility = sky.zenith_color.a;
+    let cloud_coverage = sky.horizon_color.a;
+
+    // Gradient: horizon color near and below the horizon, zenith overhead
+    let height = clamp(ray.y, 0.0, 1.0);
+    var color = mix(sky.horizon_color.rgb, sky.zenith_color.rgb, pow(height, 0.5));
+
+    // Glow around the sun, strongest near the horizon
+    let sun_amount = max(dot(ray, sky.sun_direction.xyz), 0.0);
+    let glow = pow(sun_amount, 8.0) * (1.0 - cloud_coverage) * 0.35;
+    color += sky.sun_color.rgb * glow * smoothstep(-0.1, 0.1, sky.sun_direction.y);
+
+    // Clouds hide the discs and stars
+    let clear_sky = 1.0 - cloud_coverage * 0.9;
+    color += sky.sun_color.rgb * disc(ray, sky.sun_direction) * clear_sky;
+    color += sky.moon_color.rgb * disc(ray, sky.moon_direction) * clear_sky * star_visibility;
+    color += vec3<f32>(stars(ray) * star_visibility);
+
+    return vec4<f32>(color, 1.0);
+}
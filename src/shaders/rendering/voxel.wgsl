@@ -9,6 +9,12 @@ struct CameraUniform {
 @group(0) @binding(0)
 var<uniform> camera: CameraUniform;
 
+#include "cloud_shadow.wgsl"
+
+// Cloud layer parameters; a zero shadow strength disables cloud shadows
+@group(1) @binding(0)
+var<uniform> clouds: CloudUniform;
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) color: vec3<f32>,
@@ -45,6 +51,9 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Combine block/sky light with simple directional shading
     let light_dir = normalize(vec3<f32>(0.5, -1.0, 0.3));
     let directional = max(dot(in.normal, -light_dir), 0.0) * 0.3;
+
+    // Clouds overhead darken the direct light reaching this fragment
+    let cloud_shadow = cloud_shadow_factor(in.world_pos, clouds);
     
     // Use the per-vertex light level
     let block_light = in.light;
@@ -53,7 +62,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let ao_factor = in.ao;
     
     // Combine all lighting
-    let final_light = (block_light + directional) * ao_factor;
+    let final_light = (block_light + directional * cloud_shadow) * ao_factor;
     
     // Apply fog based on distance from camera
     // Calculate the distance from the fragment's world position to the camera position
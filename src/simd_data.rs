@@ -0,0 +1,35 @@
+//! SIMD Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Feature detection and the vectorized kernels live in simd_operations.rs
+
+/// Widest instruction set the CPU kernels may use, detected at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SimdLevel {
+    /// Plain loops; every target
+    Scalar,
+    /// 128-bit lanes: 8 voxels or 4 floats per step (x86_64 baseline)
+    Sse2,
+    /// 256-bit lanes: 16 voxels or 8 floats per step
+    Avx2,
+}
+
+/// Static boxes in column layout so a sweep can load several at once
+#[derive(Debug, Clone, Default)]
+pub struct AabbColumns {
+    pub min_x: Vec<f32>,
+    pub min_y: Vec<f32>,
+    pub min_z: Vec<f32>,
+    pub max_x: Vec<f32>,
+    pub max_y: Vec<f32>,
+    pub max_z: Vec<f32>,
+}
+
+/// First box a moving box touches during a sweep
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepHit {
+    /// Index into the `AabbColumns`
+    pub index: usize,
+    /// Fraction of the displacement travelled before contact (0.0-1.0)
+    pub time: f32,
+}
@@ -0,0 +1,467 @@
+//! SIMD Operations - Pure DOP Functions
+//!
+//! Vectorized inner loops for the CPU paths: voxel row compares for greedy
+//! meshing and one-against-many AABB sweeps for physics. The instruction
+//! set is picked once at runtime by `simd_level`; every kernel has a scalar
+//! twin that produces the same results, used on other targets and as the
+//! baseline in benches/simd_voxel_ops.rs. The voxel row kernels fall
+//! back to scalar while the `vectorized_terrain` feature flag is off.
+
+use crate::simd_data::{AabbColumns, SimdLevel, SweepHit};
+use crate::world::core::BlockId;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Displacements below this on an axis are treated as not moving on it
+const PARALLEL_EPSILON: f32 = 1e-6;
+
+/// Whether the voxel row kernels may use `simd_level`
+static VOXEL_KERNELS_VECTORIZED: AtomicBool = AtomicBool::new(true);
+
+/// Widest instruction set this CPU supports
+#[cfg(target_arch = "x86_64")]
+pub fn detect_simd_level() -> SimdLevel {
+    if std::arch::is_x86_feature_detected!("avx2") {
+        SimdLevel::Avx2
+    } else {
+        SimdLevel::Sse2
+    }
+}
+
+/// Widest instruction set this CPU supports
+#[cfg(not(target_arch = "x86_64"))]
+pub fn detect_simd_level() -> SimdLevel {
+    SimdLevel::Scalar
+}
+
+/// Detected level, cached after the first call
+pub fn simd_level() -> SimdLevel {
+    static LEVEL: OnceLock<SimdLevel> = OnceLock::new();
+    *LEVEL.get_or_init(|| {
+        let level = detect_simd_level();
+        log::info!("[SIMD] CPU kernels using {:?}", level);
+        level
+    })
+}
+
+/// Requested level, lowered to what the CPU supports
+fn supported_level(requested: SimdLevel) -> SimdLevel {
+    requested.min(simd_level())
+}
+
+/// Switch the voxel row kernels between `simd_level` and scalar. Fails
+/// when vectorized kernels are requested on a CPU without any.
+pub fn set_voxel_kernels_vectorized(vectorized: bool) -> Result<(), String> {
+    if vectorized && simd_level() == SimdLevel::Scalar {
+        return Err("this CPU has no vectorized voxel kernels".to_string());
+    }
+    VOXEL_KERNELS_VECTORIZED.store(vectorized, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Level the voxel row kernels run at
+pub fn voxel_kernel_level() -> SimdLevel {
+    if VOXEL_KERNELS_VECTORIZED.load(Ordering::Relaxed) {
+        simd_level()
+    } else {
+        SimdLevel::Scalar
+    }
+}
+
+// ============================================================================
+// VOXEL ROWS
+// ============================================================================
+
+/// Number of leading voxels in `row` equal to `block`
+pub fn matching_run_length(row: &[BlockId], block: BlockId) -> usize {
+    matching_run_length_with(voxel_kernel_level(), row, block)
+}
+
+/// `matching_run_length` at a chosen level (capped to the CPU's)
+pub fn matching_run_length_with(level: SimdLevel, row: &[BlockId], block: BlockId) -> usize {
+    match supported_level(level) {
+        SimdLevel::Scalar => matching_run_length_scalar(row, block),
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Sse2 => x86::matching_run_length_sse2(row, block),
+        // SAFETY: supported_level only returns Avx2 when the CPU reports it
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe { x86::matching_run_length_avx2(row, block) },
+        #[cfg(not(target_arch = "x86_64"))]
+        _ => matching_run_length_scalar(row, block),
+    }
+}
+
+/// Scalar baseline of `matching_run_length`
+pub fn matching_run_length_scalar(row: &[BlockId], block: BlockId) -> usize {
+    row.iter().take_while(|&&voxel| voxel == block).count()
+}
+
+/// Whether every voxel equals `block` (e.g. an all-air chunk)
+pub fn all_voxels_equal(voxels: &[BlockId], block: BlockId) -> bool {
+    matching_run_length(voxels, block) == voxels.len()
+}
+
+// ============================================================================
+// AABB SWEEPS
+// ============================================================================
+
+/// Append a box to the columns
+pub fn push_aabb_column(columns: &mut AabbColumns, min: [f32; 3], max: [f32; 3]) {
+    columns.min_x.push(min[0]);
+    columns.min_y.push(min[1]);
+    columns.min_z.push(min[2]);
+    columns.max_x.push(max[0]);
+    columns.max_y.push(max[1]);
+    columns.max_z.push(max[2]);
+}
+
+/// Remove every box, keeping capacity
+pub fn clear_aabb_columns(columns: &mut AabbColumns) {
+    columns.min_x.clear();
+    columns.min_y.clear();
+    columns.min_z.clear();
+    columns.max_x.clear();
+    columns.max_y.clear();
+    columns.max_z.clear();
+}
+
+/// Per-axis setup shared by every box of a sweep
+#[derive(Clone, Copy)]
+struct SweepAxis {
+    origin: f32,
+    half: f32,
+    /// 1 / displacement, unused when `parallel`
+    inverse: f32,
+    parallel: bool,
+}
+
+fn sweep_axes(min: [f32; 3], max: [f32; 3], displacement: [f32; 3]) -> [SweepAxis; 3] {
+    std::array::from_fn(|axis| {
+        let parallel = displacement[axis].abs() < PARALLEL_EPSILON;
+        SweepAxis {
+            origin: (min[axis] + max[axis]) * 0.5,
+            half: (max[axis] - min[axis]) * 0.5,
+            inverse: if parallel {
+                0.0
+            } else {
+                1.0 / displacement[axis]
+            },
+            parallel,
+        }
+    })
+}
+
+fn axis_mins(columns: &AabbColumns, axis: usize) -> &[f32] {
+    match axis {
+        0 => &columns.min_x,
+        1 => &columns.min_y,
+        _ => &columns.min_z,
+    }
+}
+
+fn axis_maxs(columns: &AabbColumns, axis: usize) -> &[f32] {
+    match axis {
+        0 => &columns.max_x,
+        1 => &columns.max_y,
+        _ => &columns.max_z,
+    }
+}
+
+/// Sweep the box `min..max` by `displacement` against every box in
+/// `columns` and return the earliest contact. Boxes already overlapping at
+/// the start hit at time 0; ties go to the lower index.
+pub fn sweep_aabb(
+    min: [f32; 3],
+    max: [f32; 3],
+    displacement: [f32; 3],
+    columns: &AabbColumns,
+) -> Option<SweepHit> {
+    sweep_aabb_with(simd_level(), min, max, displacement, columns)
+}
+
+/// `sweep_aabb` at a chosen level (capped to the CPU's)
+pub fn sweep_aabb_with(
+    level: SimdLevel,
+    min: [f32; 3],
+    max: [f32; 3],
+    displacement: [f32; 3],
+    columns: &AabbColumns,
+) -> Option<SweepHit> {
+    let axes = sweep_axes(min, max, displacement);
+    match supported_level(level) {
+        SimdLevel::Scalar => sweep_range_scalar(&axes, columns, 0, None),
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Sse2 => x86::sweep_sse2(&axes, columns),
+        // SAFETY: supported_level only returns Avx2 when the CPU reports it
+        #[cfg(target_arch = "x86_64")]
+        SimdLevel::Avx2 => unsafe { x86::sweep_avx2(&axes, columns) },
+        #[cfg(not(target_arch = "x86_64"))]
+        _ => sweep_range_scalar(&axes, columns, 0, None),
+    }
+}
+
+/// Scalar baseline of `sweep_aabb`
+pub fn sweep_aabb_scalar(
+    min: [f32; 3],
+    max: [f32; 3],
+    displacement: [f32; 3],
+    columns: &AabbColumns,
+) -> Option<SweepHit> {
+    sweep_range_scalar(&sweep_axes(min, max, displacement), columns, 0, None)
+}
+
+/// Entry time of one box, `None` if the sweep misses it
+fn sweep_one(axes: &[SweepAxis; 3], columns: &AabbColumns, index: usize) -> Option<f32> {
+    let mut enter = 0.0f32;
+    let mut exit = 1.0f32;
+    for (axis, setup) in axes.iter().enumerate() {
+        let (mins, maxs) = (axis_mins(columns, axis), axis_maxs(columns, axis));
+        let low = mins[index] - setup.half - setup.origin;
+        let high = maxs[index] + setup.half - setup.origin;
+        if setup.parallel {
+            if low > 0.0 || high < 0.0 {
+                return None;
+            }
+        } else {
+            let t1 = low * setup.inverse;
+            let t2 = high * setup.inverse;
+            enter = enter.max(t1.min(t2));
+            exit = exit.min(t1.max(t2));
+        }
+    }
+    (enter <= exit).then_some(enter)
+}
+
+/// Scalar sweep over `start..`, continuing from an earlier best hit
+fn sweep_range_scalar(
+    axes: &[SweepAxis; 3],
+    columns: &AabbColumns,
+    start: usize,
+    mut best: Option<SweepHit>,
+) -> Option<SweepHit> {
+    for index in start..columns.min_x.len() {
+        if let Some(time) = sweep_one(axes, columns, index) {
+            if best.is_none_or(|hit| time < hit.time) {
+                best = Some(SweepHit { index, time });
+            }
+        }
+    }
+    best
+}
+
+/// Fold lane results of one SIMD step into the best hit
+fn merge_lane_hits(best: &mut Option<SweepHit>, base: usize, hit_mask: u32, times: &[f32]) {
+    let mut mask = hit_mask;
+    while mask != 0 {
+        let lane = mask.trailing_zeros() as usize;
+        mask &= mask - 1;
+        let time = times[lane];
+        if best.is_none_or(|hit| time < hit.time) {
+            *best = Some(SweepHit {
+                index: base + lane,
+                time,
+            });
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use super::{axis_maxs, axis_mins, merge_lane_hits, sweep_range_scalar, SweepAxis};
+    use crate::simd_data::{AabbColumns, SweepHit};
+    use crate::world::core::BlockId;
+    use std::arch::x86_64::*;
+
+    /// Eight voxels per compare
+    pub fn matching_run_length_sse2(row: &[BlockId], block: BlockId) -> usize {
+        let mut index = 0;
+        // SAFETY: SSE2 is part of the x86_64 baseline. BlockId is
+        // repr(transparent) over u16, so 8 voxels are one 128-bit load, and
+        // every load stays within `row` (index + 8 <= len).
+        unsafe {
+            let target = _mm_set1_epi16(block.0 as i16);
+            while index + 8 <= row.len() {
+                let voxels = _mm_loadu_si128(row.as_ptr().add(index) as *const __m128i);
+                let equal = _mm_movemask_epi8(_mm_cmpeq_epi16(voxels, target)) as u32;
+                if equal != 0xFFFF {
+                    return index + equal.trailing_ones() as usize / 2;
+                }
+                index += 8;
+            }
+        }
+        index + super::matching_run_length_scalar(&row[index..], block)
+    }
+
+    /// Sixteen voxels per compare
+    ///
+    /// # Safety
+    /// The CPU must support AVX2.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn matching_run_length_avx2(row: &[BlockId], block: BlockId) -> usize {
+        let target = _mm256_set1_epi16(block.0 as i16);
+        let mut index = 0;
+        while index + 16 <= row.len() {
+            // In bounds (index + 16 <= len); BlockId is repr(transparent) over u16
+            let voxels = _mm256_loadu_si256(row.as_ptr().add(index) as *const __m256i);
+            let equal = _mm256_movemask_epi8(_mm256_cmpeq_epi16(voxels, target)) as u32;
+            if equal != u32::MAX {
+                return index + equal.trailing_ones() as usize / 2;
+            }
+            index += 16;
+        }
+        index + matching_run_length_sse2(&row[index..], block)
+    }
+
+    /// Four boxes per step
+    pub fn sweep_sse2(axes: &[SweepAxis; 3], columns: &AabbColumns) -> Option<SweepHit> {
+        let count = columns.min_x.len();
+        let mut best = None;
+        let mut base = 0;
+        // SAFETY: SSE2 is part of the x86_64 baseline; all column vectors
+        // have `count` entries and every load reads base..base + 4 <= count.
+        unsafe {
+            while base + 4 <= count {
+                let mut enter = _mm_setzero_ps();
+                let mut exit = _mm_set1_ps(1.0);
+                let mut valid = _mm_castsi128_ps(_mm_set1_epi32(-1));
+                for (axis, setup) in axes.iter().enumerate() {
+                    let (mins, maxs) = (axis_mins(columns, axis), axis_maxs(columns, axis));
+                    let half = _mm_set1_ps(setup.half);
+                    let origin = _mm_set1_ps(setup.origin);
+                    let low = _mm_sub_ps(
+                        _mm_sub_ps(_mm_loadu_ps(mins.as_ptr().add(base)), half),
+                        origin,
+                    );
+                    let high = _mm_sub_ps(
+                        _mm_add_ps(_mm_loadu_ps(maxs.as_ptr().add(base)), half),
+                        origin,
+                    );
+                    if setup.parallel {
+                        let zero = _mm_setzero_ps();
+                        let inside = _mm_and_ps(_mm_cmple_ps(low, zero), _mm_cmpge_ps(high, zero));
+                        valid = _mm_and_ps(valid, inside);
+                    } else {
+                        let inverse = _mm_set1_ps(setup.inverse);
+                        let t1 = _mm_mul_ps(low, inverse);
+                        let t2 = _mm_mul_ps(high, inverse);
+                        enter = _mm_max_ps(enter, _mm_min_ps(t1, t2));
+                        exit = _mm_min_ps(exit, _mm_max_ps(t1, t2));
+                    }
+                }
+                let hit = _mm_and_ps(valid, _mm_cmple_ps(enter, exit));
+                let hit_mask = _mm_movemask_ps(hit) as u32;
+                if hit_mask != 0 {
+                    let mut times = [0.0f32; 4];
+                    _mm_storeu_ps(times.as_mut_ptr(), enter);
+                    merge_lane_hits(&mut best, base, hit_mask, &times);
+                }
+                base += 4;
+            }
+        }
+        sweep_range_scalar(axes, columns, base, best)
+    }
+
+    /// Eight boxes per step
+    ///
+    /// # Safety
+    /// The CPU must support AVX2.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sweep_avx2(axes: &[SweepAxis; 3], columns: &AabbColumns) -> Option<SweepHit> {
+        let count = columns.min_x.len();
+        let mut best = None;
+        let mut base = 0;
+        while base + 8 <= count {
+            let mut enter = _mm256_setzero_ps();
+            let mut exit = _mm256_set1_ps(1.0);
+            let mut valid = _mm256_castsi256_ps(_mm256_set1_epi32(-1));
+            for (axis, setup) in axes.iter().enumerate() {
+                let (mins, maxs) = (axis_mins(columns, axis), axis_maxs(columns, axis));
+                let half = _mm256_set1_ps(setup.half);
+                let origin = _mm256_set1_ps(setup.origin);
+                // In bounds: base + 8 <= count for every column
+                let low = _mm256_sub_ps(
+                    _mm256_sub_ps(_mm256_loadu_ps(mins.as_ptr().add(base)), half),
+                    origin,
+                );
+                let high = _mm256_sub_ps(
+                    _mm256_add_ps(_mm256_loadu_ps(maxs.as_ptr().add(base)), half),
+                    origin,
+                );
+                if setup.parallel {
+                    let zero = _mm256_setzero_ps();
+                    let inside = _mm256_and_ps(
+                        _mm256_cmp_ps::<_CMP_LE_OQ>(low, zero),
+                        _mm256_cmp_ps::<_CMP_GE_OQ>(high, zero),
+                    );
+                    valid = _mm256_and_ps(valid, inside);
+                } else {
+                    let inverse = _mm256_set1_ps(setup.inverse);
+                    let t1 = _mm256_mul_ps(low, inverse);
+                    let t2 = _mm256_mul_ps(high, inverse);
+                    enter = _mm256_max_ps(enter, _mm256_min_ps(t1, t2));
+                    exit = _mm256_min_ps(exit, _mm256_max_ps(t1, t2));
+                }
+            }
+            let hit = _mm256_and_ps(valid, _mm256_cmp_ps::<_CMP_LE_OQ>(enter, exit));
+            let hit_mask = _mm256_movemask_ps(hit) as u32;
+            if hit_mask != 0 {
+                let mut times = [0.0f32; 8];
+                _mm256_storeu_ps(times.as_mut_ptr(), enter);
+                merge_lane_hits(&mut best, base, hit_mask, &times);
+            }
+            base += 8;
+        }
+        sweep_range_scalar(axes, columns, base, best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEVELS: [SimdLevel; 3] = [SimdLevel::Scalar, SimdLevel::Sse2, SimdLevel::Avx2];
+
+    #[test]
+    fn test_run_length_matches_scalar_at_every_level() {
+        let mut row = vec![BlockId::STONE; 50];
+        for stop in [0, 3, 7, 8, 15, 16, 17, 31, 49] {
+            row[stop] = BlockId::DIRT;
+            for level in LEVELS {
+                assert_eq!(matching_run_length_with(level, &row, BlockId::STONE), stop);
+            }
+            row[stop] = BlockId::STONE;
+        }
+        for level in LEVELS {
+            assert_eq!(matching_run_length_with(level, &row, BlockId::STONE), 50);
+        }
+        assert!(all_voxels_equal(&[BlockId::AIR; 37], BlockId::AIR));
+    }
+
+    #[test]
+    fn test_sweep_matches_scalar_at_every_level() {
+        let mut columns = AabbColumns::default();
+        // Staggered unit boxes ahead on +x; index 2 is the nearest
+        for i in 0..13 {
+            let x = 10.0 + (i as f32 - 2.0).abs() * 0.25;
+            push_aabb_column(&mut columns, [x, 0.0, 0.0], [x + 1.0, 1.0, 1.0]);
+        }
+        push_aabb_column(&mut columns, [4.0, 5.0, 0.0], [5.0, 6.0, 1.0]);
+
+        let min = [0.0, 0.0, 0.0];
+        let max = [1.0, 1.0, 1.0];
+        let expected = sweep_aabb_scalar(min, max, [20.0, 0.0, 0.0], &columns).expect("hit");
+        assert_eq!(expected.index, 2);
+        assert!((expected.time - 0.45).abs() < 1e-6);
+        for level in LEVELS {
+            assert_eq!(
+                sweep_aabb_with(level, min, max, [20.0, 0.0, 0.0], &columns),
+                Some(expected)
+            );
+            // Straight up passes beside every box
+            assert_eq!(
+                sweep_aabb_with(level, min, max, [0.0, 10.0, 0.0], &columns),
+                None
+            );
+        }
+    }
+}
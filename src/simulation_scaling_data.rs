@@ -0,0 +1,79 @@
+//! Simulation Scaling Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Tiering and catch-up logic lives in simulation_scaling_operations.rs
+
+use crate::world::ChunkPos;
+use std::collections::HashMap;
+
+/// How often a chunk simulates, based on distance to the nearest player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimulationTier {
+    /// Ticks every world tick
+    Full,
+    /// Ticks every `reduced_tick_interval` world ticks with the ticks in between batched
+    Reduced,
+    /// Does not tick; elapsed ticks are banked and replayed when it wakes
+    Suspended,
+}
+
+/// Distance thresholds and rates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationScalingConfig {
+    /// When false every chunk ticks at the full rate
+    pub enabled: bool,
+    /// Chebyshev distance in chunks for full-rate simulation
+    pub full_rate_distance: u32,
+    /// Chebyshev distance in chunks for reduced-rate simulation
+    pub reduced_rate_distance: u32,
+    pub reduced_tick_interval: u64,
+    /// Cap on banked ticks replayed when a chunk wakes
+    pub max_catch_up_ticks: u64,
+    /// World ticks per second while any chunk is awake
+    pub active_tick_rate: u32,
+    /// World ticks per second while every chunk is suspended
+    pub sleep_tick_rate: u32,
+}
+
+/// Per-chunk scheduling state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkSimulationState {
+    pub tier: SimulationTier,
+    /// Ticks elapsed but not yet simulated
+    pub pending_ticks: u64,
+    /// World tick of the last simulation step
+    pub last_ticked: u64,
+}
+
+/// Simulation work for one chunk this tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkTickWork {
+    pub chunk_pos: ChunkPos,
+    /// Ticks to simulate in one step (elapsed plus any catch-up)
+    pub delta_ticks: u64,
+}
+
+/// Counters for the server status view
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimulationScalingStats {
+    pub full_chunks: u32,
+    pub reduced_chunks: u32,
+    pub suspended_chunks: u32,
+    /// Chunk ticks not simulated since creation (batched or banked)
+    pub ticks_skipped: u64,
+    /// Banked ticks replayed when chunks woke or reduced chunks stepped
+    pub catch_up_ticks_applied: u64,
+    /// Banked ticks dropped by the catch-up cap
+    pub catch_up_ticks_dropped: u64,
+    /// True while no chunk is awake
+    pub sleeping: bool,
+}
+
+/// Scheduler state
+#[derive(Debug, Clone)]
+pub struct SimulationScalingData {
+    pub config: SimulationScalingConfig,
+    pub chunks: HashMap<ChunkPos, ChunkSimulationState>,
+    pub world_tick: u64,
+    pub stats: SimulationScalingStats,
+}
@@ -0,0 +1,330 @@
+//! Simulation Scaling Operations - Pure DOP functions
+//!
+//! Per world tick: `advance_simulation` sorts loaded chunks into tiers by
+//! distance to the nearest player and returns the chunks that simulate this
+//! tick. Near chunks tick every tick, mid-range chunks step every few ticks
+//! with the skipped ticks batched, and far chunks suspend while banking their
+//! elapsed ticks. A chunk that comes back into full range replays its banked
+//! ticks on the very next step, so a player arriving sees the world as if it
+//! had kept running. With every chunk suspended the server drops to
+//! `sleep_tick_rate`.
+
+use crate::constants::simulation_scaling::*;
+use crate::process::{ProcessId, ProcessManager};
+use crate::simulation_scaling_data::{
+    ChunkSimulationState, ChunkTickWork, SimulationScalingConfig, SimulationScalingData,
+    SimulationScalingStats, SimulationTier,
+};
+use crate::world::ChunkPos;
+use std::collections::{HashMap, HashSet};
+
+/// Default thresholds from `constants::simulation_scaling`
+pub fn default_simulation_scaling_config() -> SimulationScalingConfig {
+    SimulationScalingConfig {
+        enabled: true,
+        full_rate_distance: FULL_RATE_DISTANCE,
+        reduced_rate_distance: REDUCED_RATE_DISTANCE,
+        reduced_tick_interval: REDUCED_TICK_INTERVAL,
+        max_catch_up_ticks: MAX_CATCH_UP_TICKS,
+        active_tick_rate: ACTIVE_TICK_RATE,
+        sleep_tick_rate: SLEEP_TICK_RATE,
+    }
+}
+
+/// Create a scheduler with no tracked chunks
+pub fn create_simulation_scaling(config: SimulationScalingConfig) -> SimulationScalingData {
+    SimulationScalingData {
+        config,
+        chunks: HashMap::new(),
+        world_tick: 0,
+        stats: SimulationScalingStats::default(),
+    }
+}
+
+/// Chebyshev distance between two chunks
+pub fn chunk_distance(a: ChunkPos, b: ChunkPos) -> u32 {
+    (a.x - b.x)
+        .unsigned_abs()
+        .max((a.y - b.y).unsigned_abs())
+        .max((a.z - b.z).unsigned_abs())
+}
+
+/// Tier for a chunk given the chunks players stand in
+pub fn simulation_tier(
+    config: &SimulationScalingConfig,
+    chunk_pos: ChunkPos,
+    player_chunks: &[ChunkPos],
+) -> SimulationTier {
+    if !config.enabled {
+        return SimulationTier::Full;
+    }
+    let Some(distance) = player_chunks
+        .iter()
+        .map(|&player| chunk_distance(chunk_pos, player))
+        .min()
+    else {
+        return SimulationTier::Suspended;
+    };
+
+    if distance <= config.full_rate_distance {
+        SimulationTier::Full
+    } else if distance <= config.reduced_rate_distance {
+        SimulationTier::Reduced
+    } else {
+        SimulationTier::Suspended
+    }
+}
+
+/// Stable per-chunk offset so reduced chunks don't all step on the same tick
+fn reduced_tick_phase(chunk_pos: ChunkPos, interval: u64) -> u64 {
+    let hash = (chunk_pos.x as u32 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (chunk_pos.y as u32 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (chunk_pos.z as u32 as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    (hash >> 32) % interval.max(1)
+}
+
+/// Bank ticks for a chunk that is not stepping, honouring the catch-up cap
+fn bank_ticks(
+    state: &mut ChunkSimulationState,
+    ticks: u64,
+    max_catch_up_ticks: u64,
+    stats: &mut SimulationScalingStats,
+) {
+    let banked = (state.pending_ticks + ticks).min(max_catch_up_ticks);
+    stats.catch_up_ticks_dropped += state.pending_ticks + ticks - banked;
+    stats.ticks_skipped += ticks;
+    state.pending_ticks = banked;
+}
+
+/// Advance the world by `elapsed_ticks` and return the chunks that simulate
+/// now. Chunks missing from `loaded_chunks` are forgotten.
+pub fn advance_simulation(
+    data: &mut SimulationScalingData,
+    loaded_chunks: &[ChunkPos],
+    player_chunks: &[ChunkPos],
+    elapsed_ticks: u64,
+) -> Vec<ChunkTickWork> {
+    data.world_tick += elapsed_ticks;
+    let world_tick = data.world_tick;
+    let config = data.config;
+
+    let loaded: HashSet<ChunkPos> = loaded_chunks.iter().copied().collect();
+    data.chunks.retain(|pos, _| loaded.contains(pos));
+
+    let mut stats = SimulationScalingStats {
+        ticks_skipped: data.stats.ticks_skipped,
+        catch_up_ticks_applied: data.stats.catch_up_ticks_applied,
+        catch_up_ticks_dropped: data.stats.catch_up_ticks_dropped,
+        ..SimulationScalingStats::default()
+    };
+    let mut work = Vec::new();
+
+    for &chunk_pos in loaded_chunks {
+        let tier = simulation_tier(&config, chunk_pos, player_chunks);
+        let tracked = data.chunks.contains_key(&chunk_pos);
+        let state = data
+            .chunks
+            .entry(chunk_pos)
+            .or_insert(ChunkSimulationState {
+                tier,
+                pending_ticks: 0,
+                last_ticked: world_tick.saturating_sub(elapsed_ticks),
+            });
+
+        if tier == SimulationTier::Reduced && (!tracked || state.tier != tier) {
+            state.last_ticked = state
+                .last_ticked
+                .saturating_sub(reduced_tick_phase(chunk_pos, config.reduced_tick_interval));
+        }
+        state.tier = tier;
+
+        match tier {
+            SimulationTier::Full => {
+                stats.full_chunks += 1;
+                stats.catch_up_ticks_applied += state.pending_ticks;
+                work.push(ChunkTickWork {
+                    chunk_pos,
+                    delta_ticks: elapsed_ticks + state.pending_ticks,
+                });
+                state.pending_ticks = 0;
+                state.last_ticked = world_tick;
+            }
+            SimulationTier::Reduced => {
+                stats.reduced_chunks += 1;
+                if world_tick.saturating_sub(state.last_ticked) >= config.reduced_tick_interval {
+                    stats.catch_up_ticks_applied += state.pending_ticks;
+                    work.push(ChunkTickWork {
+                        chunk_pos,
+                        delta_ticks: elapsed_ticks + state.pending_ticks,
+                    });
+                    state.pending_ticks = 0;
+                    state.last_ticked = world_tick;
+                } else {
+                    bank_ticks(state, elapsed_ticks, config.max_catch_up_ticks, &mut stats);
+                }
+            }
+            SimulationTier::Suspended => {
+                stats.suspended_chunks += 1;
+                bank_ticks(state, elapsed_ticks, config.max_catch_up_ticks, &mut stats);
+            }
+        }
+    }
+
+    stats.sleeping = stats.full_chunks == 0 && stats.reduced_chunks == 0;
+    if stats.sleeping != data.stats.sleeping {
+        log::info!(
+            "[SimulationScaling] World {} ({} chunks suspended)",
+            if stats.sleeping { "sleeping" } else { "awake" },
+            stats.suspended_chunks
+        );
+    }
+    data.stats = stats;
+    work
+}
+
+/// True while every loaded chunk is suspended
+pub fn is_world_sleeping(data: &SimulationScalingData) -> bool {
+    data.stats.sleeping
+}
+
+/// Ticks per second the server loop should run at right now. Switches back
+/// to the active rate on the first tick a chunk wakes.
+pub fn world_tick_rate(data: &SimulationScalingData) -> u32 {
+    if data.config.enabled && data.stats.sleeping {
+        data.config.sleep_tick_rate.max(1)
+    } else {
+        data.config.active_tick_rate.max(1)
+    }
+}
+
+/// Game ticks each server tick covers at the current rate, so game time keeps
+/// pace while the loop runs slower
+pub fn ticks_per_step(data: &SimulationScalingData) -> u64 {
+    let rate = world_tick_rate(data) as u64;
+    (data.config.active_tick_rate.max(1) as u64).div_ceil(rate)
+}
+
+/// Release every chunk's banked ticks regardless of distance (e.g. before
+/// saving so persisted progress is current)
+pub fn flush_pending_simulation(data: &mut SimulationScalingData) -> Vec<ChunkTickWork> {
+    let mut work = Vec::new();
+    for (&chunk_pos, state) in data.chunks.iter_mut() {
+        if state.pending_ticks > 0 {
+            data.stats.catch_up_ticks_applied += state.pending_ticks;
+            work.push(ChunkTickWork {
+                chunk_pos,
+                delta_ticks: state.pending_ticks,
+            });
+            state.pending_ticks = 0;
+            state.last_ticked = data.world_tick;
+        }
+    }
+    work
+}
+
+/// Advance processes by the ticks their chunk simulated. Processes without a
+/// chunk (e.g. inventory crafting) always advance by `elapsed_ticks`;
+/// processes in a chunk that did not step this tick are left paused in time.
+pub fn apply_scaled_process_ticks(
+    processes: &mut ProcessManager,
+    process_chunks: &HashMap<ProcessId, ChunkPos>,
+    work: &[ChunkTickWork],
+    elapsed_ticks: u64,
+) {
+    let chunk_ticks: HashMap<ChunkPos, u64> =
+        work.iter().map(|w| (w.chunk_pos, w.delta_ticks)).collect();
+
+    let deltas: Vec<u64> = processes
+        .processes
+        .ids
+        .iter()
+        .map(|id| match process_chunks.get(id) {
+            Some(chunk_pos) => chunk_ticks.get(chunk_pos).copied().unwrap_or(0),
+            None => elapsed_ticks,
+        })
+        .collect();
+    processes.update_scaled(&deltas, elapsed_ticks);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SimulationScalingConfig {
+        SimulationScalingConfig {
+            full_rate_distance: 2,
+            reduced_rate_distance: 4,
+            reduced_tick_interval: 5,
+            max_catch_up_ticks: 100,
+            ..default_simulation_scaling_config()
+        }
+    }
+
+    #[test]
+    fn test_tiers_follow_player_distance() {
+        let config = config();
+        let player = [ChunkPos::new(0, 0, 0)];
+        assert_eq!(
+            simulation_tier(&config, ChunkPos::new(2, 0, -1), &player),
+            SimulationTier::Full
+        );
+        assert_eq!(
+            simulation_tier(&config, ChunkPos::new(0, 4, 0), &player),
+            SimulationTier::Reduced
+        );
+        assert_eq!(
+            simulation_tier(&config, ChunkPos::new(5, 0, 0), &player),
+            SimulationTier::Suspended
+        );
+        assert_eq!(
+            simulation_tier(&config, ChunkPos::new(0, 0, 0), &[]),
+            SimulationTier::Suspended
+        );
+    }
+
+    #[test]
+    fn test_suspended_chunk_catches_up_when_player_arrives() {
+        let mut data = create_simulation_scaling(config());
+        let chunk = ChunkPos::new(10, 0, 0);
+
+        for _ in 0..30 {
+            let work = advance_simulation(&mut data, &[chunk], &[ChunkPos::new(0, 0, 0)], 1);
+            assert!(work.is_empty());
+        }
+        assert!(is_world_sleeping(&data));
+        assert_eq!(world_tick_rate(&data), SLEEP_TICK_RATE);
+        assert_eq!(ticks_per_step(&data), 20);
+
+        let work = advance_simulation(&mut data, &[chunk], &[chunk], 1);
+        assert_eq!(
+            work,
+            vec![ChunkTickWork {
+                chunk_pos: chunk,
+                delta_ticks: 31
+            }]
+        );
+        assert!(!is_world_sleeping(&data));
+        assert_eq!(world_tick_rate(&data), ACTIVE_TICK_RATE);
+    }
+
+    #[test]
+    fn test_reduced_chunk_batches_ticks_and_caps_catch_up() {
+        let mut data = create_simulation_scaling(config());
+        let near = ChunkPos::new(3, 0, 0);
+        let player = [ChunkPos::new(0, 0, 0)];
+
+        let steps: Vec<ChunkTickWork> = (0..20)
+            .flat_map(|_| advance_simulation(&mut data, &[near], &player, 1))
+            .collect();
+        let stepped: u64 = steps.iter().map(|w| w.delta_ticks).sum();
+        let pending = data.chunks[&near].pending_ticks;
+        assert_eq!(stepped + pending, 20);
+        assert!(steps.len() <= 5 && pending < 5);
+
+        let far = ChunkPos::new(50, 0, 0);
+        advance_simulation(&mut data, &[far], &player, 250);
+        assert_eq!(data.chunks[&far].pending_ticks, 100);
+        assert_eq!(data.stats.catch_up_ticks_dropped, 150);
+        assert!(!data.chunks.contains_key(&near));
+    }
+}
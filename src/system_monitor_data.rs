@@ -1,2 +1,134 @@
-//! System Monitor Data - Stub
-pub struct SystemMonitorData;
+//! System Monitor Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Threshold evaluation and mitigations live in system_monitor_operations.rs
+
+/// Handle for a registered alert rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AlertRuleId(pub u32);
+
+/// Handle for an alert subscription
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AlertSubscriptionId(pub u32);
+
+/// Metric an alert rule watches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertMetric {
+    /// Most recent frame time in milliseconds
+    FrameTimeMs,
+    /// GPU memory in use as a percentage of the configured budget
+    GpuMemoryPercent,
+    /// Chunks waiting for generation
+    ChunkQueueDepth,
+    /// Process CPU usage percentage
+    CpuUsagePercent,
+    /// Mesh vertex arena fragmentation (0.0 - 1.0)
+    MeshArenaFragmentation,
+    /// Texture mips wanted but not uploaded yet (MB)
+    TextureUploadBacklogMb,
+    /// Texture bytes resident as a percentage of all mips (0 - 100)
+    TextureResidencyPercent,
+    /// GPU memory held by allocations that outlived their owning system (MB)
+    GpuLeakedMb,
+}
+
+/// When a rule fires
+#[derive(Debug, Clone, Copy)]
+pub struct AlertThreshold {
+    pub metric: AlertMetric,
+    /// Fires when the metric is strictly above this value
+    pub limit: f64,
+    /// Consecutive frames above the limit before the alert triggers
+    pub sustain_frames: u32,
+    /// Frames between repeated mitigations while the alert stays active
+    pub cooldown_frames: u32,
+}
+
+/// Built-in response tied to the adaptive quality buffers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlertMitigation {
+    /// Only notify subscribers
+    None,
+    /// Multiply the particle budget by `factor` (floored at `min_budget`)
+    ReduceParticleBudget { factor: f32, min_budget: u32 },
+    /// Lower the render scale by `step` (floored at `min_scale`)
+    LowerRenderScale { step: f32, min_scale: f32 },
+}
+
+/// Registered rule plus its evaluation state
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub id: AlertRuleId,
+    pub name: String,
+    pub threshold: AlertThreshold,
+    pub mitigation: AlertMitigation,
+    pub enabled: bool,
+    /// Consecutive frames the metric has been above the limit
+    pub frames_over: u32,
+    pub active: bool,
+    /// Frames until the mitigation may be applied again
+    pub cooldown_remaining: u32,
+}
+
+/// Alert state change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertEventKind {
+    Triggered,
+    Resolved,
+}
+
+/// Emitted when a rule triggers or resolves
+#[derive(Debug, Clone)]
+pub struct AlertEvent {
+    pub rule_id: AlertRuleId,
+    pub rule_name: String,
+    pub kind: AlertEventKind,
+    pub metric: AlertMetric,
+    pub value: f64,
+    pub limit: f64,
+    pub frame: u64,
+}
+
+/// Mitigation that was applied to the engine buffers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MitigationApplied {
+    pub rule_id: AlertRuleId,
+    pub mitigation: AlertMitigation,
+    /// Value after the change (particle budget or render scale)
+    pub new_value: f64,
+}
+
+/// One frame of monitored values
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricSample {
+    pub frame_time_ms: f64,
+    pub gpu_memory_percent: f64,
+    pub chunk_queue_depth: f64,
+    pub cpu_usage_percent: f64,
+    pub mesh_arena_fragmentation: f64,
+    pub texture_upload_backlog_mb: f64,
+    pub texture_residency_percent: f64,
+    pub gpu_leaked_mb: f64,
+}
+
+/// Callback invoked for every alert event
+pub type AlertCallback = Box<dyn Fn(&AlertEvent) + Send + Sync>;
+
+/// Registered alert callback
+pub struct AlertSubscriber {
+    pub id: AlertSubscriptionId,
+    pub callback: AlertCallback,
+}
+
+/// Monitor state: rules, subscribers and undelivered events
+pub struct SystemMonitorData {
+    pub rules: Vec<AlertRule>,
+    pub subscribers: Vec<AlertSubscriber>,
+    /// Events kept for polling consumers (drained by `drain_alert_events`)
+    pub pending_events: Vec<AlertEvent>,
+    /// GPU memory budget used for `GpuMemoryPercent` (bytes, 0 = unknown)
+    pub gpu_memory_budget: u64,
+    pub frame: u64,
+    pub next_rule_id: u32,
+    pub next_subscription_id: u32,
+}
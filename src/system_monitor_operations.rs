@@ -1,2 +1,380 @@
-//! System Monitor Operations - Stub
-pub fn monitor_system() {}
+//! System Monitor Operations - Pure DOP functions
+//!
+//! Per frame: `sample_metrics` reads the engine buffers, `evaluate_alerts`
+//! updates rule state and notifies subscribers, and `apply_alert_mitigations`
+//! adjusts the adaptive quality values (particle budget, render scale).
+
+use crate::constants::monitoring::*;
+use crate::engine_buffers::EngineBuffers;
+use crate::system_monitor_data::{
+    AlertCallback, AlertEvent, AlertEventKind, AlertMetric, AlertMitigation, AlertRule,
+    AlertRuleId, AlertSubscriber, AlertSubscriptionId, AlertThreshold, MetricSample,
+    MitigationApplied, SystemMonitorData,
+};
+
+// ============================================================================
+// INITIALIZATION
+// ============================================================================
+
+/// Create an empty monitor
+pub fn create_system_monitor(gpu_memory_budget: u64) -> SystemMonitorData {
+    SystemMonitorData {
+        rules: Vec::new(),
+        subscribers: Vec::new(),
+        pending_events: Vec::new(),
+        gpu_memory_budget,
+        frame: 0,
+        next_rule_id: 0,
+        next_subscription_id: 0,
+    }
+}
+
+/// Create a monitor with the built-in rules registered
+pub fn create_default_system_monitor(gpu_memory_budget: u64) -> SystemMonitorData {
+    let mut monitor = create_system_monitor(gpu_memory_budget);
+
+    add_alert_rule(
+        &mut monitor,
+        "frame_time_critical",
+        AlertThreshold {
+            metric: AlertMetric::FrameTimeMs,
+            limit: DEFAULT_FRAME_TIME_CRITICAL_MS,
+            sustain_frames: DEFAULT_ALERT_SUSTAIN_FRAMES,
+            cooldown_frames: DEFAULT_ALERT_COOLDOWN_FRAMES,
+        },
+        AlertMitigation::ReduceParticleBudget {
+            factor: MITIGATION_PARTICLE_BUDGET_FACTOR,
+            min_budget: MIN_PARTICLE_BUDGET,
+        },
+    );
+
+    add_alert_rule(
+        &mut monitor,
+        "gpu_memory_critical",
+        AlertThreshold {
+            metric: AlertMetric::GpuMemoryPercent,
+            limit: DEFAULT_MEMORY_CRITICAL_PERCENT,
+            sustain_frames: 1,
+            cooldown_frames: DEFAULT_ALERT_COOLDOWN_FRAMES,
+        },
+        AlertMitigation::LowerRenderScale {
+            step: MITIGATION_RENDER_SCALE_STEP,
+            min_scale: MIN_RENDER_SCALE,
+        },
+    );
+
+    add_alert_rule(
+        &mut monitor,
+        "chunk_queue_backlog",
+        AlertThreshold {
+            metric: AlertMetric::ChunkQueueDepth,
+            limit: DEFAULT_CHUNK_QUEUE_WARNING,
+            sustain_frames: DEFAULT_ALERT_SUSTAIN_FRAMES,
+            cooldown_frames: DEFAULT_ALERT_COOLDOWN_FRAMES,
+        },
+        AlertMitigation::None,
+    );
+
+    add_alert_rule(
+        &mut monitor,
+        "gpu_memory_leak",
+        AlertThreshold {
+            metric: AlertMetric::GpuLeakedMb,
+            limit: DEFAULT_GPU_LEAK_WARNING_MB,
+            sustain_frames: 1,
+            cooldown_frames: DEFAULT_ALERT_COOLDOWN_FRAMES,
+        },
+        AlertMitigation::None,
+    );
+
+    monitor
+}
+
+// ============================================================================
+// RULES AND SUBSCRIPTIONS
+// ============================================================================
+
+/// Register an alert rule
+pub fn add_alert_rule(
+    monitor: &mut SystemMonitorData,
+    name: &str,
+    threshold: AlertThreshold,
+    mitigation: AlertMitigation,
+) -> AlertRuleId {
+    let id = AlertRuleId(monitor.next_rule_id);
+    monitor.next_rule_id += 1;
+
+    monitor.rules.push(AlertRule {
+        id,
+        name: name.to_string(),
+        threshold,
+        mitigation,
+        enabled: true,
+        frames_over: 0,
+        active: false,
+        cooldown_remaining: 0,
+    });
+    id
+}
+
+/// Remove an alert rule. Returns false if it was not registered.
+pub fn remove_alert_rule(monitor: &mut SystemMonitorData, id: AlertRuleId) -> bool {
+    let before = monitor.rules.len();
+    monitor.rules.retain(|rule| rule.id != id);
+    monitor.rules.len() != before
+}
+
+/// Enable or disable a rule; disabling resets its state
+pub fn set_alert_rule_enabled(monitor: &mut SystemMonitorData, id: AlertRuleId, enabled: bool) {
+    if let Some(rule) = monitor.rules.iter_mut().find(|rule| rule.id == id) {
+        rule.enabled = enabled;
+        if !enabled {
+            rule.frames_over = 0;
+            rule.active = false;
+            rule.cooldown_remaining = 0;
+        }
+    }
+}
+
+/// Subscribe a callback to all alert events
+pub fn subscribe_alerts(
+    monitor: &mut SystemMonitorData,
+    callback: AlertCallback,
+) -> AlertSubscriptionId {
+    let id = AlertSubscriptionId(monitor.next_subscription_id);
+    monitor.next_subscription_id += 1;
+    monitor.subscribers.push(AlertSubscriber { id, callback });
+    id
+}
+
+/// Remove a subscription
+pub fn unsubscribe_alerts(monitor: &mut SystemMonitorData, id: AlertSubscriptionId) {
+    monitor.subscribers.retain(|subscriber| subscriber.id != id);
+}
+
+/// Take all events produced since the last call
+pub fn drain_alert_events(monitor: &mut SystemMonitorData) -> Vec<AlertEvent> {
+    std::mem::take(&mut monitor.pending_events)
+}
+
+/// Rules that are currently firing
+pub fn active_alerts(monitor: &SystemMonitorData) -> impl Iterator<Item = &AlertRule> {
+    monitor.rules.iter().filter(|rule| rule.active)
+}
+
+// ============================================================================
+// EVALUATION
+// ============================================================================
+
+/// Read the monitored values from the engine buffers
+pub fn sample_metrics(buffers: &EngineBuffers, gpu_memory_budget: u64) -> MetricSample {
+    let gpu_memory_percent = if gpu_memory_budget > 0 {
+        buffers.metrics.gpu_memory_usage as f64 / gpu_memory_budget as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    let textures = &buffers.metrics.texture_streaming;
+    let texture_residency_percent = if textures.full_bytes > 0 {
+        textures.resident_bytes as f64 / textures.full_bytes as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    MetricSample {
+        frame_time_ms: buffers.metrics.frame_times.back().copied().unwrap_or(0.0) as f64,
+        gpu_memory_percent,
+        chunk_queue_depth: buffers.world.pending_generation.len() as f64,
+        cpu_usage_percent: buffers.metrics.cpu_usage as f64,
+        mesh_arena_fragmentation: buffers.metrics.mesh_arena_fragmentation as f64,
+        texture_upload_backlog_mb: textures.pending_upload_bytes as f64 / (1024.0 * 1024.0),
+        texture_residency_percent,
+        gpu_leaked_mb: buffers.metrics.gpu_allocations.leaked_bytes as f64 / (1024.0 * 1024.0),
+    }
+}
+
+fn metric_value(sample: &MetricSample, metric: AlertMetric) -> f64 {
+    match metric {
+        AlertMetric::FrameTimeMs => sample.frame_time_ms,
+        AlertMetric::GpuMemoryPercent => sample.gpu_memory_percent,
+        AlertMetric::ChunkQueueDepth => sample.chunk_queue_depth,
+        AlertMetric::CpuUsagePercent => sample.cpu_usage_percent,
+        AlertMetric::MeshArenaFragmentation => sample.mesh_arena_fragmentation,
+        AlertMetric::TextureUploadBacklogMb => sample.texture_upload_backlog_mb,
+        AlertMetric::TextureResidencyPercent => sample.texture_residency_percent,
+        AlertMetric::GpuLeakedMb => sample.gpu_leaked_mb,
+    }
+}
+
+/// Advance one frame: update rule state, notify subscribers and return the
+/// events produced this frame (they are also queued for `drain_alert_events`).
+pub fn evaluate_alerts(monitor: &mut SystemMonitorData, sample: &MetricSample) -> Vec<AlertEvent> {
+    let frame = monitor.frame;
+    monitor.frame += 1;
+
+    let mut events = Vec::new();
+    for rule in monitor.rules.iter_mut().filter(|rule| rule.enabled) {
+        let value = metric_value(sample, rule.threshold.metric);
+        let over = value > rule.threshold.limit;
+
+        rule.cooldown_remaining = rule.cooldown_remaining.saturating_sub(1);
+        rule.frames_over = if over { rule.frames_over + 1 } else { 0 };
+
+        let kind = if !rule.active && rule.frames_over >= rule.threshold.sustain_frames.max(1) {
+            rule.active = true;
+            Some(AlertEventKind::Triggered)
+        } else if rule.active && !over {
+            rule.active = false;
+            rule.cooldown_remaining = 0;
+            Some(AlertEventKind::Resolved)
+        } else {
+            None
+        };
+
+        if let Some(kind) = kind {
+            events.push(AlertEvent {
+                rule_id: rule.id,
+                rule_name: rule.name.clone(),
+                kind,
+                metric: rule.threshold.metric,
+                value,
+                limit: rule.threshold.limit,
+                frame,
+            });
+        }
+    }
+
+    for event in &events {
+        match event.kind {
+            AlertEventKind::Triggered => log::warn!(
+                "[SystemMonitor] Alert '{}' triggered: {:?} = {:.2} (limit {:.2})",
+                event.rule_name,
+                event.metric,
+                event.value,
+                event.limit
+            ),
+            AlertEventKind::Resolved => {
+                log::info!("[SystemMonitor] Alert '{}' resolved", event.rule_name)
+            }
+        }
+        for subscriber in &monitor.subscribers {
+            (subscriber.callback)(event);
+        }
+    }
+
+    monitor.pending_events.extend(events.iter().cloned());
+    events
+}
+
+// ============================================================================
+// MITIGATION
+// ============================================================================
+
+/// Apply mitigations for active rules whose cooldown has expired
+pub fn apply_alert_mitigations(
+    monitor: &mut SystemMonitorData,
+    buffers: &mut EngineBuffers,
+) -> Vec<MitigationApplied> {
+    let mut applied = Vec::new();
+
+    for rule in monitor.rules.iter_mut() {
+        if !rule.enabled || !rule.active || rule.cooldown_remaining > 0 {
+            continue;
+        }
+
+        let new_value = match rule.mitigation {
+            AlertMitigation::None => continue,
+            AlertMitigation::ReduceParticleBudget { factor, min_budget } => {
+                let particles = &mut buffers.particles;
+                let reduced = (particles.particle_budget as f32 * factor.clamp(0.0, 1.0)) as u32;
+                let budget = reduced.max(min_budget).min(particles.particle_budget);
+                if budget == particles.particle_budget {
+                    continue;
+                }
+                particles.particle_budget = budget;
+                budget as f64
+            }
+            AlertMitigation::LowerRenderScale { step, min_scale } => {
+                let render = &mut buffers.render;
+                let scale = (render.render_scale - step.abs()).max(min_scale);
+                if scale >= render.render_scale {
+                    continue;
+                }
+                render.render_scale = scale;
+                scale as f64
+            }
+        };
+
+        rule.cooldown_remaining = rule.threshold.cooldown_frames;
+        log::info!(
+            "[SystemMonitor] Mitigation for '{}': {:?} -> {:.2}",
+            rule.name,
+            rule.mitigation,
+            new_value
+        );
+        applied.push(MitigationApplied {
+            rule_id: rule.id,
+            mitigation: rule.mitigation,
+            new_value,
+        });
+    }
+
+    applied
+}
+
+/// Restore adaptive quality values to their defaults
+pub fn reset_adaptive_quality(buffers: &mut EngineBuffers) {
+    buffers.particles.particle_budget = crate::particles::particle_data::MAX_PARTICLES as u32;
+    buffers.render.render_scale = 1.0;
+}
+
+/// Convenience: sample, evaluate and mitigate in one call
+pub fn update_system_monitor(
+    monitor: &mut SystemMonitorData,
+    buffers: &mut EngineBuffers,
+) -> Vec<AlertEvent> {
+    let sample = sample_metrics(buffers, monitor.gpu_memory_budget);
+    let events = evaluate_alerts(monitor, &sample);
+    apply_alert_mitigations(monitor, buffers);
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_buffers::create_engine_buffers;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_frame_time_alert_reduces_particle_budget() {
+        let mut monitor = create_default_system_monitor(0);
+        let mut buffers = create_engine_buffers();
+        let initial_budget = buffers.particles.particle_budget;
+
+        let triggered = Arc::new(AtomicU32::new(0));
+        let counter = triggered.clone();
+        subscribe_alerts(
+            &mut monitor,
+            Box::new(move |event| {
+                if event.kind == AlertEventKind::Triggered {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+            }),
+        );
+
+        buffers.metrics.frame_times.push_back(50.0);
+        for _ in 0..DEFAULT_ALERT_SUSTAIN_FRAMES {
+            update_system_monitor(&mut monitor, &mut buffers);
+        }
+
+        assert_eq!(triggered.load(Ordering::SeqCst), 1);
+        assert!(buffers.particles.particle_budget < initial_budget);
+
+        // Recovery resolves the alert
+        buffers.metrics.frame_times.push_back(10.0);
+        let events = update_system_monitor(&mut monitor, &mut buffers);
+        assert!(events.iter().any(|e| e.kind == AlertEventKind::Resolved));
+        assert_eq!(active_alerts(&monitor).count(), 0);
+    }
+}
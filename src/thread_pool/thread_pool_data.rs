@@ -1,24 +1,152 @@
-//! Thread Pool Data - Stub
+//! Thread Pool Data - Pure DOP
+//!
+//! Work is split into priority lanes. Frame-critical GPU command tasks go to
+//! the critical lane, chunk generation to high/background depending on
+//! distance, and persistence I/O to background. Workers have a home lane and
+//! steal from the others when it is empty; a reserved background worker and
+//! an age threshold keep background I/O from starving.
+//!
+//! NO METHODS - just data.
+
+use std::collections::VecDeque;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::constants::thread_pool_constants::*;
+
+/// Legacy placeholder kept for API compatibility
 pub struct ThreadPoolData;
-pub enum GpuWorkloadCategory { Rendering, Physics, Compute }
-pub struct GpuThreadPoolData;
-pub struct GpuThreadPoolConfig;
 
-pub struct ThreadPoolManager;
+/// Kind of work a task performs (used for logging and metrics)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GpuWorkloadCategory {
+    Rendering,
+    Physics,
+    Compute,
+    Generation,
+    Persistence,
+}
 
-impl ThreadPoolManager {
-    pub fn global() -> &'static Self {
-        static INSTANCE: ThreadPoolManager = ThreadPoolManager;
-        &INSTANCE
-    }
+/// Priority lane a task is queued in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TaskPriority {
+    /// Frame-critical GPU command submission
+    Critical = 0,
+    /// Latency-sensitive work such as chunks near the player
+    High = 1,
+    /// Throughput work such as saves and distant chunks
+    Background = 2,
+}
 
-    pub fn execute<F: FnOnce() + Send + 'static>(&self, _category: GpuWorkloadCategory, _f: F) {
-        // Stub - would execute on thread pool
-    }
+/// Number of priority lanes
+pub const TASK_PRIORITY_COUNT: usize = 3;
+
+/// All lanes, highest priority first
+pub const TASK_PRIORITIES: [TaskPriority; TASK_PRIORITY_COUNT] = [
+    TaskPriority::Critical,
+    TaskPriority::High,
+    TaskPriority::Background,
+];
+
+/// Thread pool configuration
+#[derive(Debug, Clone)]
+pub struct GpuThreadPoolConfig {
+    pub worker_count: usize,
+    /// Workers whose home lane is background (clamped below worker_count)
+    pub reserved_background_workers: usize,
+    /// Tasks queued longer than this run ahead of higher lanes
+    pub starvation_threshold: Duration,
+    /// Maximum queued tasks per lane; submissions beyond it are rejected
+    pub max_lane_depth: usize,
+    pub idle_poll_interval: Duration,
 }
 
 impl Default for GpuThreadPoolConfig {
     fn default() -> Self {
-        Self
+        Self {
+            worker_count: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(DEFAULT_WORKER_COUNT),
+            reserved_background_workers: RESERVED_BACKGROUND_WORKERS,
+            starvation_threshold: Duration::from_millis(STARVATION_THRESHOLD_MS),
+            max_lane_depth: MAX_LANE_DEPTH,
+            idle_poll_interval: Duration::from_millis(IDLE_POLL_INTERVAL_MS),
+        }
     }
 }
+
+/// Boxed unit of work
+pub type PoolJob = Box<dyn FnOnce() + Send + 'static>;
+
+/// Job that may borrow from the caller, for `run_scoped_jobs`
+pub type ScopedJob<'scope, T> = Box<dyn FnOnce() -> T + Send + 'scope>;
+
+/// Job the pool refused, with the reason
+pub type RejectedJob = (String, PoolJob);
+
+/// A queued task
+pub struct PooledTask {
+    pub job: PoolJob,
+    pub category: GpuWorkloadCategory,
+    pub enqueued_at: Instant,
+}
+
+/// Per-lane counters
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LaneMetrics {
+    /// Tasks currently queued
+    pub depth: usize,
+    pub peak_depth: usize,
+    pub submitted: u64,
+    pub completed: u64,
+    pub rejected: u64,
+    pub panicked: u64,
+    /// Tasks run by a worker whose home lane is a different one
+    pub stolen: u64,
+    /// Tasks run early because they exceeded the starvation threshold
+    pub starvation_promotions: u64,
+    /// Sum of queue wait times, for averaging
+    pub total_wait: Duration,
+    pub max_wait: Duration,
+}
+
+/// Snapshot of all lanes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadPoolMetrics {
+    pub lanes: [LaneMetrics; TASK_PRIORITY_COUNT],
+    pub worker_count: usize,
+    /// Tasks currently executing
+    pub running: usize,
+}
+
+/// Lane queues and metrics, guarded by one mutex
+#[derive(Default)]
+pub struct LaneState {
+    pub queues: [VecDeque<PooledTask>; TASK_PRIORITY_COUNT],
+    pub metrics: [LaneMetrics; TASK_PRIORITY_COUNT],
+    pub running: usize,
+}
+
+/// State shared between the pool handle and its workers
+pub struct ThreadPoolShared {
+    pub config: GpuThreadPoolConfig,
+    pub state: Mutex<LaneState>,
+    /// Signalled when a task is queued or the pool shuts down
+    pub task_available: Condvar,
+    /// Signalled when the pool becomes idle
+    pub idle: Condvar,
+    pub shutdown: AtomicBool,
+}
+
+/// Thread pool with priority lanes
+pub struct GpuThreadPoolData {
+    pub shared: Arc<ThreadPoolShared>,
+    pub workers: Vec<JoinHandle<()>>,
+    /// Home lane of each worker, by worker index
+    pub home_lanes: Vec<TaskPriority>,
+}
+
+/// Process-wide pool accessor for code without access to a pool handle
+pub struct ThreadPoolManager;
@@ -1,8 +1,515 @@
-//! Thread Pool Operations - Stub
-use super::thread_pool_data::{GpuThreadPoolData, GpuThreadPoolConfig};
+//! Thread Pool Operations - Pure DOP
+//!
+//! Lane selection for a worker, in order:
+//! 1. the oldest task of a lower lane that has waited past the starvation
+//!    threshold (lowest lane first),
+//! 2. the worker's home lane,
+//! 3. the other lanes, highest priority first (stealing).
 
-pub fn execute_task() {}
-pub fn submit_gpu_command_task() {}
-pub fn create_gpu_thread_pool_data(_config: GpuThreadPoolConfig) -> Result<GpuThreadPoolData, String> {
-    Ok(GpuThreadPoolData)
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::Ordering;
+use std::sync::{mpsc, Arc, Condvar, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+use super::thread_pool_data::*;
+
+/// Name prefix of pool worker threads
+const WORKER_THREAD_PREFIX: &str = "hearth-pool-";
+
+/// Task picked by a worker
+struct TakenTask {
+    lane: usize,
+    task: PooledTask,
+}
+
+fn lock_state(shared: &ThreadPoolShared) -> MutexGuard<'_, LaneState> {
+    match shared.state.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Home lane for each worker: the last workers are reserved for background,
+/// one for high when there is room, the rest serve the critical lane
+pub fn assign_home_lanes(config: &GpuThreadPoolConfig) -> Vec<TaskPriority> {
+    let worker_count = config.worker_count.max(1);
+    let background = config
+        .reserved_background_workers
+        .min(worker_count.saturating_sub(1));
+    let high = usize::from(worker_count >= background + 3);
+
+    (0..worker_count)
+        .map(|index| {
+            if index >= worker_count - background {
+                TaskPriority::Background
+            } else if index >= worker_count - background - high {
+                TaskPriority::High
+            } else {
+                TaskPriority::Critical
+            }
+        })
+        .collect()
+}
+
+/// Create the pool and start its workers
+pub fn create_gpu_thread_pool_data(
+    config: GpuThreadPoolConfig,
+) -> Result<GpuThreadPoolData, String> {
+    let home_lanes = assign_home_lanes(&config);
+    let shared = Arc::new(ThreadPoolShared {
+        config,
+        state: Mutex::new(LaneState::default()),
+        task_available: Condvar::new(),
+        idle: Condvar::new(),
+        shutdown: Default::default(),
+    });
+
+    let mut workers = Vec::with_capacity(home_lanes.len());
+    for (index, home) in home_lanes.iter().copied().enumerate() {
+        let worker_shared = Arc::clone(&shared);
+        let handle = std::thread::Builder::new()
+            .name(format!("{}{}-{:?}", WORKER_THREAD_PREFIX, index, home))
+            .spawn(move || worker_loop(&worker_shared, home))
+            .map_err(|e| format!("Failed to spawn thread pool worker {}: {}", index, e))?;
+        workers.push(handle);
+    }
+
+    log::info!(
+        "[ThreadPool] Started {} workers (home lanes: {:?})",
+        workers.len(),
+        home_lanes
+    );
+
+    Ok(GpuThreadPoolData {
+        shared,
+        workers,
+        home_lanes,
+    })
+}
+
+/// Queue a task in the given priority lane
+pub fn submit_with_priority<F>(
+    pool: &GpuThreadPoolData,
+    priority: TaskPriority,
+    category: GpuWorkloadCategory,
+    job: F,
+) -> Result<(), String>
+where
+    F: FnOnce() + Send + 'static,
+{
+    submit_to_shared(&pool.shared, priority, category, Box::new(job)).map_err(|(e, _)| e)
+}
+
+/// Queue a job; a rejected job is handed back with the reason
+fn submit_to_shared(
+    shared: &ThreadPoolShared,
+    priority: TaskPriority,
+    category: GpuWorkloadCategory,
+    job: PoolJob,
+) -> Result<(), RejectedJob> {
+    if shared.shutdown.load(Ordering::Acquire) {
+        return Err(("Thread pool is shut down".to_string(), job));
+    }
+
+    let lane = priority as usize;
+    {
+        let mut state = lock_state(shared);
+        if state.queues[lane].len() >= shared.config.max_lane_depth {
+            state.metrics[lane].rejected += 1;
+            let reason = format!(
+                "{:?} lane is full ({} tasks queued)",
+                priority, shared.config.max_lane_depth
+            );
+            return Err((reason, job));
+        }
+
+        state.queues[lane].push_back(PooledTask {
+            job,
+            category,
+            enqueued_at: Instant::now(),
+        });
+        let depth = state.queues[lane].len();
+        let metrics = &mut state.metrics[lane];
+        metrics.submitted += 1;
+        metrics.depth = depth;
+        metrics.peak_depth = metrics.peak_depth.max(depth);
+    }
+
+    shared.task_available.notify_one();
+    Ok(())
+}
+
+/// Queue a frame-critical GPU command task
+pub fn submit_gpu_command_task<F>(
+    pool: &GpuThreadPoolData,
+    category: GpuWorkloadCategory,
+    job: F,
+) -> Result<(), String>
+where
+    F: FnOnce() + Send + 'static,
+{
+    submit_with_priority(pool, TaskPriority::Critical, category, job)
+}
+
+/// Run jobs that borrow from the caller in `priority` lane and wait for all
+/// of them; results come back in job order, None for a job that panicked.
+///
+/// Jobs the pool rejects run inline on the calling thread, as do all jobs
+/// when there is no pool or the caller is itself a pool worker (waiting on
+/// the pool from a worker could leave no thread to run the jobs).
+pub fn run_scoped_jobs<'scope, T>(
+    pool: Option<&GpuThreadPoolData>,
+    priority: TaskPriority,
+    category: GpuWorkloadCategory,
+    jobs: Vec<ScopedJob<'scope, T>>,
+) -> Vec<Option<T>>
+where
+    T: Send + 'scope,
+{
+    let on_worker = std::thread::current()
+        .name()
+        .is_some_and(|name| name.starts_with(WORKER_THREAD_PREFIX));
+    let pool = pool.filter(|_| !on_worker);
+
+    let mut results: Vec<Option<T>> = (0..jobs.len()).map(|_| None).collect();
+    let (sender, receiver) = mpsc::channel::<(usize, T)>();
+    let mut inline: Vec<PoolJob> = Vec::new();
+
+    for (index, job) in jobs.into_iter().enumerate() {
+        let sender = sender.clone();
+        let task: ScopedJob<'scope, ()> = Box::new(move || {
+            let _ = sender.send((index, job()));
+        });
+        // SAFETY: only the lifetime is erased. Every task owns a clone of
+        // `sender`, and the receive loop below runs until all clones are
+        // gone, so no task (run or dropped unrun) outlives 'scope.
+        let task: PoolJob = unsafe { std::mem::transmute::<ScopedJob<'scope, ()>, PoolJob>(task) };
+
+        let Some(pool) = pool else {
+            inline.push(task);
+            continue;
+        };
+        if let Err((reason, task)) = submit_to_shared(&pool.shared, priority, category, task) {
+            log::debug!("[ThreadPool] Running {:?} job inline: {}", category, reason);
+            inline.push(task);
+        }
+    }
+    drop(sender);
+
+    for task in inline {
+        if catch_unwind(AssertUnwindSafe(task)).is_err() {
+            log::error!("[ThreadPool] Inline {:?} job panicked", category);
+        }
+    }
+
+    for (index, value) in receiver {
+        results[index] = Some(value);
+    }
+    results
+}
+
+/// Pick the next task for a worker whose home lane is `home`
+fn take_task(
+    state: &mut LaneState,
+    home: TaskPriority,
+    starvation_threshold: Duration,
+    now: Instant,
+) -> Option<TakenTask> {
+    // Starved lower-priority work first, lowest lane first
+    for lane in (1..TASK_PRIORITY_COUNT).rev() {
+        let starved = state.queues[lane]
+            .front()
+            .is_some_and(|task| now.duration_since(task.enqueued_at) >= starvation_threshold);
+        if starved && lane != home as usize {
+            state.metrics[lane].starvation_promotions += 1;
+            return pop_lane(state, lane);
+        }
+    }
+
+    if !state.queues[home as usize].is_empty() {
+        return pop_lane(state, home as usize);
+    }
+
+    let lane = TASK_PRIORITIES
+        .iter()
+        .map(|priority| *priority as usize)
+        .find(|lane| !state.queues[*lane].is_empty())?;
+    state.metrics[lane].stolen += 1;
+    pop_lane(state, lane)
+}
+
+fn pop_lane(state: &mut LaneState, lane: usize) -> Option<TakenTask> {
+    let task = state.queues[lane].pop_front()?;
+    state.metrics[lane].depth = state.queues[lane].len();
+    state.running += 1;
+    Some(TakenTask { lane, task })
+}
+
+fn worker_loop(shared: &ThreadPoolShared, home: TaskPriority) {
+    loop {
+        let taken = {
+            let mut state = lock_state(shared);
+            loop {
+                let now = Instant::now();
+                if let Some(taken) =
+                    take_task(&mut state, home, shared.config.starvation_threshold, now)
+                {
+                    break Some(taken);
+                }
+                if shared.shutdown.load(Ordering::Acquire) {
+                    break None;
+                }
+                state = match shared
+                    .task_available
+                    .wait_timeout(state, shared.config.idle_poll_interval)
+                {
+                    Ok((guard, _)) => guard,
+                    Err(poisoned) => poisoned.into_inner().0,
+                };
+            }
+        };
+
+        let Some(TakenTask { lane, task }) = taken else {
+            return;
+        };
+
+        let wait = task.enqueued_at.elapsed();
+        let category = task.category;
+        let panicked = catch_unwind(AssertUnwindSafe(task.job)).is_err();
+        if panicked {
+            log::error!("[ThreadPool] {:?} task in lane {} panicked", category, lane);
+        }
+
+        let mut state = lock_state(shared);
+        state.running -= 1;
+        let metrics = &mut state.metrics[lane];
+        metrics.completed += 1;
+        metrics.panicked += u64::from(panicked);
+        metrics.total_wait += wait;
+        metrics.max_wait = metrics.max_wait.max(wait);
+
+        if state.running == 0 && state.queues.iter().all(|queue| queue.is_empty()) {
+            shared.idle.notify_all();
+        }
+    }
+}
+
+/// Snapshot of per-lane queue depth and throughput
+pub fn thread_pool_metrics(pool: &GpuThreadPoolData) -> ThreadPoolMetrics {
+    let state = lock_state(&pool.shared);
+    ThreadPoolMetrics {
+        lanes: state.metrics,
+        worker_count: pool.workers.len(),
+        running: state.running,
+    }
+}
+
+/// Average queue wait of a lane
+pub fn lane_average_wait(metrics: &LaneMetrics) -> Duration {
+    if metrics.completed == 0 {
+        Duration::ZERO
+    } else {
+        metrics.total_wait / metrics.completed as u32
+    }
+}
+
+/// Block until every queued task has finished; false on timeout
+pub fn wait_for_idle(pool: &GpuThreadPoolData, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    let mut state = lock_state(&pool.shared);
+    while state.running > 0 || state.queues.iter().any(|queue| !queue.is_empty()) {
+        let now = Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        state = match pool.shared.idle.wait_timeout(state, deadline - now) {
+            Ok((guard, _)) => guard,
+            Err(poisoned) => poisoned.into_inner().0,
+        };
+    }
+    true
+}
+
+/// Stop accepting work, drain the queues and join the workers
+pub fn shutdown_thread_pool(pool: &mut GpuThreadPoolData) {
+    pool.shared.shutdown.store(true, Ordering::Release);
+    pool.shared.task_available.notify_all();
+    for worker in pool.workers.drain(..) {
+        if worker.join().is_err() {
+            log::error!("[ThreadPool] Worker thread panicked during shutdown");
+        }
+    }
+}
+
+impl Drop for GpuThreadPoolData {
+    fn drop(&mut self) {
+        shutdown_thread_pool(self);
+    }
+}
+
+/// Process-wide pool, created on first use with the default configuration
+pub fn global_thread_pool() -> Option<&'static GpuThreadPoolData> {
+    static POOL: OnceLock<Option<GpuThreadPoolData>> = OnceLock::new();
+    POOL.get_or_init(
+        || match create_gpu_thread_pool_data(GpuThreadPoolConfig::default()) {
+            Ok(pool) => Some(pool),
+            Err(e) => {
+                log::error!("[ThreadPool] Failed to create global pool: {}", e);
+                None
+            }
+        },
+    )
+    .as_ref()
+}
+
+impl ThreadPoolManager {
+    pub fn global() -> &'static Self {
+        static INSTANCE: ThreadPoolManager = ThreadPoolManager;
+        &INSTANCE
+    }
+
+    /// Run `f` on the global pool's high lane, inline if no pool is available
+    pub fn execute<F: FnOnce() + Send + 'static>(&self, category: GpuWorkloadCategory, f: F) {
+        match global_thread_pool() {
+            Some(pool) => {
+                if let Err(e) = submit_with_priority(pool, TaskPriority::High, category, f) {
+                    log::warn!("[ThreadPool] Dropped {:?} task: {}", category, e);
+                }
+            }
+            None => f(),
+        }
+    }
+
+    /// `run_scoped_jobs` on the global pool's high lane
+    pub fn run_scoped<'scope, T: Send + 'scope>(
+        &self,
+        category: GpuWorkloadCategory,
+        jobs: Vec<ScopedJob<'scope, T>>,
+    ) -> Vec<Option<T>> {
+        run_scoped_jobs(global_thread_pool(), TaskPriority::High, category, jobs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn test_config(worker_count: usize) -> GpuThreadPoolConfig {
+        GpuThreadPoolConfig {
+            worker_count,
+            reserved_background_workers: 1,
+            starvation_threshold: Duration::from_millis(20),
+            max_lane_depth: 64,
+            idle_poll_interval: Duration::from_millis(5),
+        }
+    }
+
+    #[test]
+    fn test_home_lane_assignment() {
+        let lanes = assign_home_lanes(&test_config(4));
+        assert_eq!(
+            lanes,
+            vec![
+                TaskPriority::Critical,
+                TaskPriority::Critical,
+                TaskPriority::High,
+                TaskPriority::Background
+            ]
+        );
+        assert_eq!(
+            assign_home_lanes(&test_config(1)),
+            vec![TaskPriority::Critical]
+        );
+    }
+
+    #[test]
+    fn test_background_not_starved_by_critical_flood() {
+        let mut state = LaneState::default();
+        let old = Instant::now();
+        state.queues[TaskPriority::Background as usize].push_back(PooledTask {
+            job: Box::new(|| {}),
+            category: GpuWorkloadCategory::Persistence,
+            enqueued_at: old,
+        });
+        for _ in 0..8 {
+            state.queues[TaskPriority::Critical as usize].push_back(PooledTask {
+                job: Box::new(|| {}),
+                category: GpuWorkloadCategory::Rendering,
+                enqueued_at: old,
+            });
+        }
+
+        let later = old + Duration::from_millis(50);
+        let taken = take_task(
+            &mut state,
+            TaskPriority::Critical,
+            Duration::from_millis(20),
+            later,
+        );
+        assert_eq!(
+            taken.map(|t| t.lane),
+            Some(TaskPriority::Background as usize)
+        );
+        assert_eq!(
+            state.metrics[TaskPriority::Background as usize].starvation_promotions,
+            1
+        );
+    }
+
+    #[test]
+    fn test_scoped_jobs_borrow_and_return_in_order() {
+        let Ok(pool) = create_gpu_thread_pool_data(test_config(2)) else {
+            return;
+        };
+        let inputs: Vec<u64> = (1..=16).collect();
+        let jobs: Vec<ScopedJob<'_, u64>> = inputs
+            .iter()
+            .map(|value| Box::new(move || value * 10) as ScopedJob<'_, u64>)
+            .collect();
+        let results = run_scoped_jobs(
+            Some(&pool),
+            TaskPriority::High,
+            GpuWorkloadCategory::Compute,
+            jobs,
+        );
+        assert_eq!(
+            results,
+            inputs
+                .iter()
+                .map(|value| Some(value * 10))
+                .collect::<Vec<_>>()
+        );
+
+        let jobs: Vec<ScopedJob<'_, u64>> = vec![Box::new(|| 1), Box::new(|| panic!("job failed"))];
+        let results = run_scoped_jobs(None, TaskPriority::High, GpuWorkloadCategory::Compute, jobs);
+        assert_eq!(results, vec![Some(1), None]);
+    }
+
+    #[test]
+    fn test_tasks_run_and_metrics_recorded() {
+        let pool = create_gpu_thread_pool_data(test_config(2));
+        assert!(pool.is_ok());
+        let Ok(pool) = pool else { return };
+
+        let counter = Arc::new(AtomicUsize::new(0));
+        for priority in TASK_PRIORITIES {
+            let counter = Arc::clone(&counter);
+            let submitted =
+                submit_with_priority(&pool, priority, GpuWorkloadCategory::Compute, move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                });
+            assert!(submitted.is_ok());
+        }
+
+        assert!(wait_for_idle(&pool, Duration::from_secs(5)));
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+
+        let metrics = thread_pool_metrics(&pool);
+        for lane in metrics.lanes {
+            assert_eq!(lane.submitted, 1);
+            assert_eq!(lane.completed, 1);
+            assert_eq!(lane.depth, 0);
+        }
+    }
 }
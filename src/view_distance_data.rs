@@ -0,0 +1,64 @@
+//! View Distance Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! The controller logic lives in view_distance_operations.rs
+
+/// Why the effective view distance changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewDistanceChangeReason {
+    /// The configured distance needs more GPU memory than a single binding allows
+    GpuMemoryLimit,
+    /// A GPU allocation for the current distance failed
+    AllocationFailed,
+    /// GPU memory use stayed above the pressure threshold
+    GpuMemoryPressure,
+    /// Frame time stayed over budget
+    FrameTimeOverBudget,
+    /// Frame time and memory had headroom long enough to grow again
+    Headroom,
+    /// The game changed the configured distance
+    Configured,
+}
+
+/// Controller thresholds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewDistanceConfig {
+    pub min_distance: u32,
+    /// Upper bound; the distance the game asked for
+    pub max_distance: u32,
+    pub frame_budget_ms: f64,
+    pub shrink_after_frames: u32,
+    pub grow_after_frames: u32,
+    /// Frame time below `frame_budget_ms * headroom_ratio` counts as headroom
+    pub headroom_ratio: f64,
+    pub memory_pressure_percent: f64,
+    pub memory_headroom_percent: f64,
+    pub cooldown_frames: u32,
+    pub allocation_ceiling_frames: u32,
+}
+
+/// One frame of controller input
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ViewDistanceSample {
+    /// Frame time excluding pacing waits (milliseconds)
+    pub frame_time_ms: f64,
+    /// GPU memory in use as a percentage of the budget (0 = unknown)
+    pub gpu_memory_percent: f64,
+}
+
+/// Controller state
+#[derive(Debug, Clone)]
+pub struct ViewDistanceControllerData {
+    pub config: ViewDistanceConfig,
+    /// Effective view distance in chunks
+    pub current: u32,
+    /// Temporary cap after a failed allocation
+    pub allocation_ceiling: Option<u32>,
+    pub ceiling_frames_remaining: u32,
+    pub frames_over: u32,
+    pub frames_with_headroom: u32,
+    pub cooldown_remaining: u32,
+    pub frame: u64,
+    /// Distance changes since creation
+    pub change_count: u64,
+}
@@ -0,0 +1,274 @@
+//! View Distance Operations - Pure DOP functions
+//!
+//! Per frame: `update_view_distance` (or `update_view_distance_from_buffers`)
+//! shrinks the effective distance one chunk at a time while frame time or GPU
+//! memory stays over budget and grows it back after a long stretch of
+//! headroom. Allocation failures shrink immediately and cap regrowth for a
+//! while. Every change is returned as `GameEvent::ViewDistanceChanged` so the
+//! game can update its UI.
+
+use crate::constants::view_distance::*;
+use crate::engine_buffers::EngineBuffers;
+use crate::game::GameEvent;
+use crate::system_monitor_operations::sample_metrics;
+use crate::view_distance_data::{
+    ViewDistanceChangeReason, ViewDistanceConfig, ViewDistanceControllerData, ViewDistanceSample,
+};
+
+/// Default thresholds with `max_distance` as the upper bound
+pub fn default_view_distance_config(max_distance: u32) -> ViewDistanceConfig {
+    ViewDistanceConfig {
+        min_distance: MIN_VIEW_DISTANCE.min(max_distance),
+        max_distance,
+        frame_budget_ms: DEFAULT_FRAME_BUDGET_MS,
+        shrink_after_frames: SHRINK_AFTER_FRAMES,
+        grow_after_frames: GROW_AFTER_FRAMES,
+        headroom_ratio: FRAME_HEADROOM_RATIO,
+        memory_pressure_percent: MEMORY_PRESSURE_PERCENT,
+        memory_headroom_percent: MEMORY_HEADROOM_PERCENT,
+        cooldown_frames: CHANGE_COOLDOWN_FRAMES,
+        allocation_ceiling_frames: ALLOCATION_CEILING_FRAMES,
+    }
+}
+
+/// Create a controller starting at `config.max_distance`
+pub fn create_view_distance_controller(config: ViewDistanceConfig) -> ViewDistanceControllerData {
+    ViewDistanceControllerData {
+        current: config.max_distance.max(config.min_distance),
+        config,
+        allocation_ceiling: None,
+        ceiling_frames_remaining: 0,
+        frames_over: 0,
+        frames_with_headroom: 0,
+        cooldown_remaining: 0,
+        frame: 0,
+        change_count: 0,
+    }
+}
+
+/// Effective view distance in chunks
+pub fn current_view_distance(controller: &ViewDistanceControllerData) -> u32 {
+    controller.current
+}
+
+/// Highest distance the controller may currently grow to
+pub fn view_distance_limit(controller: &ViewDistanceControllerData) -> u32 {
+    controller
+        .allocation_ceiling
+        .map_or(controller.config.max_distance, |ceiling| {
+            ceiling.min(controller.config.max_distance)
+        })
+        .max(controller.config.min_distance)
+}
+
+fn change_view_distance(
+    controller: &mut ViewDistanceControllerData,
+    distance: u32,
+    reason: ViewDistanceChangeReason,
+) -> Option<GameEvent> {
+    let distance = distance.max(controller.config.min_distance);
+    if distance == controller.current {
+        return None;
+    }
+
+    let previous = controller.current;
+    controller.current = distance;
+    controller.frames_over = 0;
+    controller.frames_with_headroom = 0;
+    controller.cooldown_remaining = controller.config.cooldown_frames;
+    controller.change_count += 1;
+
+    log::info!(
+        "[ViewDistance] {} -> {} chunks ({:?})",
+        previous,
+        distance,
+        reason
+    );
+    Some(GameEvent::ViewDistanceChanged {
+        previous,
+        current: distance,
+        reason,
+    })
+}
+
+/// Change the configured (maximum) distance; the effective distance follows it
+pub fn set_max_view_distance(
+    controller: &mut ViewDistanceControllerData,
+    max_distance: u32,
+) -> Vec<GameEvent> {
+    controller.config.max_distance = max_distance.max(controller.config.min_distance);
+    let target = view_distance_limit(controller);
+    change_view_distance(controller, target, ViewDistanceChangeReason::Configured)
+        .into_iter()
+        .collect()
+}
+
+/// Cap the distance at a hard memory limit (e.g. the largest world buffer a
+/// single GPU binding can hold) without rejecting the configuration
+pub fn apply_memory_limit(
+    controller: &mut ViewDistanceControllerData,
+    limit: u32,
+) -> Vec<GameEvent> {
+    let limit = limit.max(controller.config.min_distance);
+    controller.config.max_distance = controller.config.max_distance.min(limit);
+    if controller.current <= limit {
+        return Vec::new();
+    }
+    change_view_distance(controller, limit, ViewDistanceChangeReason::GpuMemoryLimit)
+        .into_iter()
+        .collect()
+}
+
+/// A GPU allocation for `attempted_distance` failed: drop below it at once
+/// and do not grow back to it for `allocation_ceiling_frames`
+pub fn report_allocation_failure(
+    controller: &mut ViewDistanceControllerData,
+    attempted_distance: u32,
+) -> Vec<GameEvent> {
+    let ceiling = attempted_distance
+        .saturating_sub(1)
+        .max(controller.config.min_distance);
+    controller.allocation_ceiling = Some(
+        controller
+            .allocation_ceiling
+            .map_or(ceiling, |existing| existing.min(ceiling)),
+    );
+    controller.ceiling_frames_remaining = controller.config.allocation_ceiling_frames;
+
+    if controller.current <= ceiling {
+        return Vec::new();
+    }
+    change_view_distance(
+        controller,
+        ceiling,
+        ViewDistanceChangeReason::AllocationFailed,
+    )
+    .into_iter()
+    .collect()
+}
+
+/// Advance one frame
+pub fn update_view_distance(
+    controller: &mut ViewDistanceControllerData,
+    sample: &ViewDistanceSample,
+) -> Vec<GameEvent> {
+    controller.frame += 1;
+    controller.cooldown_remaining = controller.cooldown_remaining.saturating_sub(1);
+    if controller.allocation_ceiling.is_some() {
+        controller.ceiling_frames_remaining = controller.ceiling_frames_remaining.saturating_sub(1);
+        if controller.ceiling_frames_remaining == 0 {
+            controller.allocation_ceiling = None;
+        }
+    }
+
+    let config = controller.config;
+    let memory_pressure = sample.gpu_memory_percent > config.memory_pressure_percent;
+    let frame_over = sample.frame_time_ms > config.frame_budget_ms;
+    let headroom = sample.frame_time_ms < config.frame_budget_ms * config.headroom_ratio
+        && sample.gpu_memory_percent < config.memory_headroom_percent;
+
+    if memory_pressure || frame_over {
+        controller.frames_over += 1;
+        controller.frames_with_headroom = 0;
+    } else if headroom {
+        controller.frames_with_headroom += 1;
+        controller.frames_over = 0;
+    } else {
+        controller.frames_over = 0;
+        controller.frames_with_headroom = 0;
+    }
+
+    if controller.cooldown_remaining > 0 {
+        return Vec::new();
+    }
+
+    let event = if controller.frames_over >= config.shrink_after_frames
+        && controller.current > config.min_distance
+    {
+        let reason = if memory_pressure {
+            ViewDistanceChangeReason::GpuMemoryPressure
+        } else {
+            ViewDistanceChangeReason::FrameTimeOverBudget
+        };
+        change_view_distance(controller, controller.current - 1, reason)
+    } else if controller.frames_with_headroom >= config.grow_after_frames
+        && controller.current < view_distance_limit(controller)
+    {
+        change_view_distance(
+            controller,
+            controller.current + 1,
+            ViewDistanceChangeReason::Headroom,
+        )
+    } else {
+        None
+    };
+    event.into_iter().collect()
+}
+
+/// Advance one frame using the latest engine metrics
+pub fn update_view_distance_from_buffers(
+    controller: &mut ViewDistanceControllerData,
+    buffers: &EngineBuffers,
+    gpu_memory_budget: u64,
+) -> Vec<GameEvent> {
+    let metrics = sample_metrics(buffers, gpu_memory_budget);
+    update_view_distance(
+        controller,
+        &ViewDistanceSample {
+            frame_time_ms: metrics.frame_time_ms,
+            gpu_memory_percent: metrics.gpu_memory_percent,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(frame_time_ms: f64) -> ViewDistanceSample {
+        ViewDistanceSample {
+            frame_time_ms,
+            gpu_memory_percent: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_shrinks_over_budget_and_grows_back() {
+        let mut controller = create_view_distance_controller(default_view_distance_config(8));
+
+        let mut events = Vec::new();
+        for _ in 0..SHRINK_AFTER_FRAMES {
+            events.extend(update_view_distance(&mut controller, &sample(40.0)));
+        }
+        assert_eq!(current_view_distance(&controller), 7);
+        assert!(matches!(
+            events.as_slice(),
+            [GameEvent::ViewDistanceChanged {
+                previous: 8,
+                current: 7,
+                reason: ViewDistanceChangeReason::FrameTimeOverBudget,
+            }]
+        ));
+
+        for _ in 0..(CHANGE_COOLDOWN_FRAMES + GROW_AFTER_FRAMES) {
+            update_view_distance(&mut controller, &sample(5.0));
+        }
+        assert_eq!(current_view_distance(&controller), 8);
+    }
+
+    #[test]
+    fn test_allocation_failure_caps_regrowth() {
+        let mut controller = create_view_distance_controller(default_view_distance_config(10));
+        assert_eq!(apply_memory_limit(&mut controller, 9).len(), 1);
+
+        let events = report_allocation_failure(&mut controller, 9);
+        assert_eq!(events.len(), 1);
+        assert_eq!(current_view_distance(&controller), 8);
+        assert_eq!(view_distance_limit(&controller), 8);
+
+        for _ in 0..(CHANGE_COOLDOWN_FRAMES + GROW_AFTER_FRAMES) {
+            update_view_distance(&mut controller, &sample(5.0));
+        }
+        assert_eq!(current_view_distance(&controller), 8);
+    }
+}
@@ -3,8 +3,11 @@
 //! This module defines the fundamental blocks that come with the engine.
 //! Games can register additional blocks on top of these.
 
-use crate::world::core::{BlockId, BlockRegistry, PhysicsProperties, RenderData};
+use crate::constants::physics_constants::{
+    DEFAULT_BLOCK_FRICTION, DEFAULT_BLOCK_RESTITUTION, DEFAULT_SPEED_MULTIPLIER,
+};
 use crate::world::blocks::block_data::BlockProperties;
+use crate::world::core::{BlockId, BlockRegistry, PhysicsProperties, RenderData};
 
 /// Create grass block properties
 pub fn create_grass_properties() -> BlockProperties {
@@ -24,6 +27,9 @@ pub fn create_grass_properties() -> BlockProperties {
         physics: PhysicsProperties {
             solid: true,
             density: 1500.0, // kg/m³
+            friction: DEFAULT_BLOCK_FRICTION,
+            restitution: DEFAULT_BLOCK_RESTITUTION,
+            speed_multiplier: DEFAULT_SPEED_MULTIPLIER,
         },
         hardness: 0.6, // Quick to break
         flammable: false,
@@ -49,6 +55,9 @@ pub fn create_dirt_properties() -> BlockProperties {
         physics: PhysicsProperties {
             solid: true,
             density: 1600.0,
+            friction: DEFAULT_BLOCK_FRICTION,
+            restitution: DEFAULT_BLOCK_RESTITUTION,
+            speed_multiplier: DEFAULT_SPEED_MULTIPLIER,
         },
         hardness: 0.5,
         flammable: false,
@@ -74,6 +83,9 @@ pub fn create_stone_properties() -> BlockProperties {
         physics: PhysicsProperties {
             solid: true,
             density: 2500.0,
+            friction: DEFAULT_BLOCK_FRICTION,
+            restitution: DEFAULT_BLOCK_RESTITUTION,
+            speed_multiplier: DEFAULT_SPEED_MULTIPLIER,
         },
         hardness: 1.5, // Harder to break
         flammable: false,
@@ -99,6 +111,9 @@ pub fn create_water_properties() -> BlockProperties {
         physics: PhysicsProperties {
             solid: false,
             density: 1000.0,
+            friction: DEFAULT_BLOCK_FRICTION,
+            restitution: DEFAULT_BLOCK_RESTITUTION,
+            speed_multiplier: DEFAULT_SPEED_MULTIPLIER,
         },
         hardness: 100.0, // Can't break water
         flammable: false,
@@ -124,6 +139,9 @@ pub fn create_sand_properties() -> BlockProperties {
         physics: PhysicsProperties {
             solid: true,
             density: 1800.0,
+            friction: DEFAULT_BLOCK_FRICTION,
+            restitution: DEFAULT_BLOCK_RESTITUTION,
+            speed_multiplier: DEFAULT_SPEED_MULTIPLIER,
         },
         hardness: 0.5,
         flammable: false,
@@ -149,6 +167,9 @@ pub fn create_glowstone_properties() -> BlockProperties {
         physics: PhysicsProperties {
             solid: true,
             density: 2000.0,
+            friction: DEFAULT_BLOCK_FRICTION,
+            restitution: DEFAULT_BLOCK_RESTITUTION,
+            speed_multiplier: DEFAULT_SPEED_MULTIPLIER,
         },
         hardness: 0.8,
         flammable: false,
@@ -162,7 +183,7 @@ pub fn create_glowstone_properties() -> BlockProperties {
 /// Games should call this before registering their own blocks.
 pub fn register_basic_blocks(registry: &mut BlockRegistry) {
     // Note: Air (BlockId 0) is handled specially by the engine
-    
+
     // Register terrain blocks with their properties
     registry.register_block("engine:grass", create_grass_properties());
     registry.register_block("engine:dirt", create_dirt_properties());
@@ -173,11 +194,11 @@ pub fn register_basic_blocks(registry: &mut BlockRegistry) {
 }
 
 // Usage example for games:
-// 
+//
 // let mut registry = BlockRegistry::new();
 // register_basic_blocks(&mut registry);
-// 
+//
 // // Get properties for a block
 // if let Some(props) = registry.get_properties(BlockId::GRASS) {
 //     println!("Grass hardness: {}", props.hardness);
-// }
\ No newline at end of file
+// }
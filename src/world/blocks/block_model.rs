@@ -0,0 +1,86 @@
+//! Block models
+//!
+//! The shape of a block as boxes in model space (0..1 block space). Models
+//! are authored like every block model, facing +Z with their long axis
+//! along +Y, and are turned by the block's stored orientation. Blocks
+//! without a registered model are full cubes; stairs default to the stairs
+//! model.
+
+use super::metadata_schema::MetadataSchemaRegistry;
+use super::orientation::OrientationMode;
+use crate::world::core::BlockId;
+
+/// Model-space box: (min, max) corners
+pub type ModelBox = [[f32; 3]; 2];
+
+/// Shape of a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlockModel {
+    #[default]
+    Cube,
+    /// Lower half of the block
+    Slab,
+    /// Lower half plus the upper half of the front (+Z) side
+    Stairs,
+}
+
+/// Most boxes any model is made of
+pub const MAX_MODEL_BOXES: usize = 2;
+
+const CUBE_BOXES: [ModelBox; 1] = [[[0.0, 0.0, 0.0], [1.0, 1.0, 1.0]]];
+const SLAB_BOXES: [ModelBox; 1] = [[[0.0, 0.0, 0.0], [1.0, 0.5, 1.0]]];
+const STAIRS_BOXES: [ModelBox; 2] = [
+    [[0.0, 0.0, 0.0], [1.0, 0.5, 1.0]],
+    [[0.0, 0.5, 0.5], [1.0, 1.0, 1.0]],
+];
+
+/// Boxes a model is made of, unrotated
+pub fn block_model_boxes(model: BlockModel) -> &'static [ModelBox] {
+    match model {
+        BlockModel::Cube => &CUBE_BOXES,
+        BlockModel::Slab => &SLAB_BOXES,
+        BlockModel::Stairs => &STAIRS_BOXES,
+    }
+}
+
+/// Give a block a non-cube model; blocks without metadata fields get an
+/// empty schema to hold it
+pub fn register_block_model(
+    registry: &mut MetadataSchemaRegistry,
+    block: BlockId,
+    model: BlockModel,
+) {
+    registry.schemas.entry(block).or_default().model = model;
+}
+
+/// Model of a block: the registered one, else stairs for stair-oriented
+/// blocks, else a cube
+pub fn block_model(registry: &MetadataSchemaRegistry, block: BlockId) -> BlockModel {
+    match registry.schemas.get(&block) {
+        Some(schema) if schema.model != BlockModel::Cube => schema.model,
+        Some(schema) if schema.orientation == OrientationMode::Stairs => BlockModel::Stairs,
+        _ => BlockModel::Cube,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::blocks::{create_metadata_schema_registry, register_block_orientation};
+
+    #[test]
+    fn test_block_models_follow_registration() {
+        let mut registry = create_metadata_schema_registry();
+        assert_eq!(block_model(&registry, BlockId::STONE), BlockModel::Cube);
+
+        register_block_model(&mut registry, BlockId::STONE, BlockModel::Slab);
+        assert_eq!(block_model(&registry, BlockId::STONE), BlockModel::Slab);
+
+        assert!(
+            register_block_orientation(&mut registry, BlockId::DIRT, OrientationMode::Stairs)
+                .is_ok()
+        );
+        assert_eq!(block_model(&registry, BlockId::DIRT), BlockModel::Stairs);
+        assert_eq!(block_model_boxes(BlockModel::Stairs).len(), MAX_MODEL_BOXES);
+    }
+}
@@ -0,0 +1,142 @@
+//! Block metadata schema registry
+//!
+//! Every voxel carries a few metadata bits (bits 24-27 of `VoxelData`, and a
+//! sparse per-chunk map on the CPU side). A schema names the fields packed
+//! into those bits for one block type, so systems such as orientation read and
+//! write them by name instead of hard-coding bit positions.
+
+use super::block_model::BlockModel;
+use super::orientation::OrientationMode;
+use crate::world::core::BlockId;
+use std::collections::HashMap;
+
+/// Metadata bits available per voxel
+pub const METADATA_BITS: u8 = 4;
+
+/// Named bit field inside the voxel metadata
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataField {
+    pub name: &'static str,
+    pub shift: u8,
+    pub bits: u8,
+}
+
+/// Field name and width in bits, as passed to `register_metadata_schema`
+pub type MetadataFieldSpec = (&'static str, u8);
+
+/// Fields used by one block type
+#[derive(Debug, Clone, Default)]
+pub struct BlockMetadataSchema {
+    pub fields: Vec<MetadataField>,
+    pub orientation: OrientationMode,
+    pub model: BlockModel,
+}
+
+/// Schemas for all block types that use metadata
+#[derive(Debug, Clone, Default)]
+pub struct MetadataSchemaRegistry {
+    pub schemas: HashMap<BlockId, BlockMetadataSchema>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MetadataSchemaError {
+    #[error("Block {block:?} metadata needs {required} bits, only {available} available")]
+    TooManyBits {
+        block: BlockId,
+        required: u8,
+        available: u8,
+    },
+
+    #[error("Block {block:?} has no metadata field '{field}'")]
+    UnknownField { block: BlockId, field: String },
+}
+
+pub fn create_metadata_schema_registry() -> MetadataSchemaRegistry {
+    MetadataSchemaRegistry::default()
+}
+
+/// Register the fields for a block; bits are assigned in order from bit 0
+pub fn register_metadata_schema(
+    registry: &mut MetadataSchemaRegistry,
+    block: BlockId,
+    fields: &[MetadataFieldSpec],
+) -> Result<(), MetadataSchemaError> {
+    let required: u8 = fields.iter().map(|(_, bits)| *bits).sum();
+    if required > METADATA_BITS {
+        return Err(MetadataSchemaError::TooManyBits {
+            block,
+            required,
+            available: METADATA_BITS,
+        });
+    }
+
+    let mut shift = 0;
+    let fields = fields
+        .iter()
+        .map(|&(name, bits)| {
+            let field = MetadataField { name, shift, bits };
+            shift += bits;
+            field
+        })
+        .collect();
+
+    // A model registered before the fields is kept
+    let model = registry
+        .schemas
+        .get(&block)
+        .map(|schema| schema.model)
+        .unwrap_or_default();
+    registry.schemas.insert(
+        block,
+        BlockMetadataSchema {
+            fields,
+            orientation: OrientationMode::Fixed,
+            model,
+        },
+    );
+    Ok(())
+}
+
+/// Look up a named field of a block's schema
+pub fn metadata_field(
+    registry: &MetadataSchemaRegistry,
+    block: BlockId,
+    name: &str,
+) -> Option<MetadataField> {
+    registry
+        .schemas
+        .get(&block)?
+        .fields
+        .iter()
+        .find(|field| field.name == name)
+        .copied()
+}
+
+/// Read a field value from packed metadata (0 if the field does not exist)
+pub fn read_metadata_field(
+    registry: &MetadataSchemaRegistry,
+    block: BlockId,
+    metadata: u8,
+    name: &str,
+) -> u8 {
+    metadata_field(registry, block, name)
+        .map(|field| (metadata >> field.shift) & ((1 << field.bits) - 1))
+        .unwrap_or(0)
+}
+
+/// Write a field value into packed metadata
+pub fn write_metadata_field(
+    registry: &MetadataSchemaRegistry,
+    block: BlockId,
+    metadata: u8,
+    name: &str,
+    value: u8,
+) -> Result<u8, MetadataSchemaError> {
+    let field =
+        metadata_field(registry, block, name).ok_or_else(|| MetadataSchemaError::UnknownField {
+            block,
+            field: name.to_string(),
+        })?;
+    let mask = ((1u8 << field.bits) - 1) << field.shift;
+    Ok((metadata & !mask) | ((value << field.shift) & mask))
+}
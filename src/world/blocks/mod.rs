@@ -4,5 +4,21 @@
 
 mod basic_blocks;
 pub mod block_data;
+pub mod block_model;
+pub mod metadata_schema;
+pub mod orientation;
 
 pub use basic_blocks::register_basic_blocks;
+pub use block_model::{
+    block_model, block_model_boxes, register_block_model, BlockModel, ModelBox, MAX_MODEL_BOXES,
+};
+pub use metadata_schema::{
+    create_metadata_schema_registry, metadata_field, read_metadata_field, register_metadata_schema,
+    write_metadata_field, BlockMetadataSchema, MetadataField, MetadataFieldSpec,
+    MetadataSchemaError, MetadataSchemaRegistry, METADATA_BITS,
+};
+pub use orientation::{
+    block_orientation_mode, block_rotation, compute_placement_metadata, register_block_orientation,
+    rotate_block_bounds, rotate_facing, rotate_model_normal, rotate_model_position, BlockFacing,
+    BlockRotation, OrientationMode,
+};
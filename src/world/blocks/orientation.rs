@@ -0,0 +1,388 @@
+//! Block orientation
+//!
+//! Directional blocks (furnaces, logs, stairs) store their orientation in the
+//! voxel metadata through the metadata schema registry. Block models are
+//! authored facing +Z (`BlockFace::Front`) with their long axis along +Y; the
+//! stored orientation is turned into a quarter-turn rotation that is applied to
+//! model vertices, normals and collision bounds around the block center.
+
+use super::metadata_schema::{
+    read_metadata_field, register_metadata_schema, write_metadata_field, MetadataFieldSpec,
+    MetadataSchemaError, MetadataSchemaRegistry,
+};
+use crate::world::core::{BlockFace, BlockId};
+use cgmath::Vector3;
+
+pub const FACING_FIELD: &str = "facing";
+pub const HALF_FIELD: &str = "half";
+pub const AXIS_FIELD: &str = "axis";
+
+/// How a block type is oriented when placed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrientationMode {
+    /// No orientation (default)
+    #[default]
+    Fixed,
+    /// Four horizontal facings, front toward the player (furnace, chest)
+    Horizontal,
+    /// Six facings, front toward the player (dispenser, piston)
+    AllFacings,
+    /// Long axis along the clicked face's normal (logs, pillars)
+    Axis,
+    /// Horizontal facing away from the player plus upper/lower half
+    Stairs,
+}
+
+/// Axis-aligned direction, same order as the mesher's face offsets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockFacing {
+    PosX = 0,
+    NegX = 1,
+    PosY = 2,
+    NegY = 3,
+    PosZ = 4,
+    NegZ = 5,
+}
+
+pub const BLOCK_FACINGS: [BlockFacing; 6] = [
+    BlockFacing::PosX,
+    BlockFacing::NegX,
+    BlockFacing::PosY,
+    BlockFacing::NegY,
+    BlockFacing::PosZ,
+    BlockFacing::NegZ,
+];
+
+/// Quarter-turn rotation matrix (rows), applied around the block center
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockRotation {
+    pub matrix: [[i8; 3]; 3],
+}
+
+pub const IDENTITY_ROTATION: BlockRotation = BlockRotation {
+    matrix: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+};
+
+// ============================================================================
+// REGISTRATION
+// ============================================================================
+
+/// Register a block as directional, reserving the metadata fields it needs
+pub fn register_block_orientation(
+    registry: &mut MetadataSchemaRegistry,
+    block: BlockId,
+    mode: OrientationMode,
+) -> Result<(), MetadataSchemaError> {
+    let fields: &[MetadataFieldSpec] = match mode {
+        OrientationMode::Fixed => &[],
+        OrientationMode::Horizontal => &[(FACING_FIELD, 2)],
+        OrientationMode::AllFacings => &[(FACING_FIELD, 3)],
+        OrientationMode::Axis => &[(AXIS_FIELD, 2)],
+        OrientationMode::Stairs => &[(FACING_FIELD, 2), (HALF_FIELD, 1)],
+    };
+    register_metadata_schema(registry, block, fields)?;
+    if let Some(schema) = registry.schemas.get_mut(&block) {
+        schema.orientation = mode;
+    }
+    Ok(())
+}
+
+/// Orientation mode of a block (Fixed if unregistered)
+pub fn block_orientation_mode(
+    registry: &MetadataSchemaRegistry,
+    block: BlockId,
+) -> OrientationMode {
+    registry
+        .schemas
+        .get(&block)
+        .map(|schema| schema.orientation)
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// DIRECTIONS
+// ============================================================================
+
+pub fn facing_from_block_face(face: BlockFace) -> BlockFacing {
+    match face {
+        BlockFace::Right | BlockFace::East => BlockFacing::PosX,
+        BlockFace::Left | BlockFace::West => BlockFacing::NegX,
+        BlockFace::Top => BlockFacing::PosY,
+        BlockFace::Bottom => BlockFacing::NegY,
+        BlockFace::Front | BlockFace::North => BlockFacing::PosZ,
+        BlockFace::Back | BlockFace::South => BlockFacing::NegZ,
+    }
+}
+
+pub fn facing_vector(facing: BlockFacing) -> [i8; 3] {
+    match facing {
+        BlockFacing::PosX => [1, 0, 0],
+        BlockFacing::NegX => [-1, 0, 0],
+        BlockFacing::PosY => [0, 1, 0],
+        BlockFacing::NegY => [0, -1, 0],
+        BlockFacing::PosZ => [0, 0, 1],
+        BlockFacing::NegZ => [0, 0, -1],
+    }
+}
+
+pub fn opposite_facing(facing: BlockFacing) -> BlockFacing {
+    match facing {
+        BlockFacing::PosX => BlockFacing::NegX,
+        BlockFacing::NegX => BlockFacing::PosX,
+        BlockFacing::PosY => BlockFacing::NegY,
+        BlockFacing::NegY => BlockFacing::PosY,
+        BlockFacing::PosZ => BlockFacing::NegZ,
+        BlockFacing::NegZ => BlockFacing::PosZ,
+    }
+}
+
+/// Dominant axis direction of a vector (horizontal only if requested)
+pub fn dominant_facing(direction: Vector3<f32>, horizontal_only: bool) -> BlockFacing {
+    let (ax, ay, az) = (direction.x.abs(), direction.y.abs(), direction.z.abs());
+    if !horizontal_only && ay >= ax && ay >= az {
+        if direction.y >= 0.0 {
+            BlockFacing::PosY
+        } else {
+            BlockFacing::NegY
+        }
+    } else if ax >= az {
+        if direction.x >= 0.0 {
+            BlockFacing::PosX
+        } else {
+            BlockFacing::NegX
+        }
+    } else if direction.z >= 0.0 {
+        BlockFacing::PosZ
+    } else {
+        BlockFacing::NegZ
+    }
+}
+
+// Horizontal facings in yaw quarter-turn order starting at the model front (+Z)
+const HORIZONTAL_ORDER: [BlockFacing; 4] = [
+    BlockFacing::PosZ,
+    BlockFacing::PosX,
+    BlockFacing::NegZ,
+    BlockFacing::NegX,
+];
+
+fn horizontal_index(facing: BlockFacing) -> u8 {
+    HORIZONTAL_ORDER
+        .iter()
+        .position(|&f| f == facing)
+        .unwrap_or(0) as u8
+}
+
+// ============================================================================
+// PLACEMENT
+// ============================================================================
+
+/// Metadata for a block placed by a player looking along `forward` at `hit_face`
+pub fn compute_placement_metadata(
+    registry: &MetadataSchemaRegistry,
+    block: BlockId,
+    forward: Vector3<f32>,
+    hit_face: BlockFace,
+) -> u8 {
+    let write = |metadata: u8, field: &str, value: u8| {
+        write_metadata_field(registry, block, metadata, field, value).unwrap_or(metadata)
+    };
+
+    match block_orientation_mode(registry, block) {
+        OrientationMode::Fixed => 0,
+        OrientationMode::Horizontal => {
+            let front = opposite_facing(dominant_facing(forward, true));
+            write(0, FACING_FIELD, horizontal_index(front))
+        }
+        OrientationMode::AllFacings => {
+            let front = opposite_facing(dominant_facing(forward, false));
+            write(0, FACING_FIELD, front as u8)
+        }
+        OrientationMode::Axis => {
+            let axis = match facing_from_block_face(hit_face) {
+                BlockFacing::PosY | BlockFacing::NegY => 0,
+                BlockFacing::PosX | BlockFacing::NegX => 1,
+                BlockFacing::PosZ | BlockFacing::NegZ => 2,
+            };
+            write(0, AXIS_FIELD, axis)
+        }
+        OrientationMode::Stairs => {
+            let facing = dominant_facing(forward, true);
+            // Placing against the underside of a block yields upside-down stairs
+            let upper = u8::from(matches!(hit_face, BlockFace::Bottom));
+            let metadata = write(0, FACING_FIELD, horizontal_index(facing));
+            write(metadata, HALF_FIELD, upper)
+        }
+    }
+}
+
+// ============================================================================
+// ROTATION
+// ============================================================================
+
+fn multiply(a: BlockRotation, b: BlockRotation) -> BlockRotation {
+    let mut matrix = [[0i8; 3]; 3];
+    for (row, out) in matrix.iter_mut().enumerate() {
+        for (col, value) in out.iter_mut().enumerate() {
+            *value = (0..3).map(|k| a.matrix[row][k] * b.matrix[k][col]).sum();
+        }
+    }
+    BlockRotation { matrix }
+}
+
+/// Rotation about +Y by `quarter_turns` * 90 degrees (+Z turns toward +X)
+fn yaw_rotation(quarter_turns: u8) -> BlockRotation {
+    let (sin, cos) = match quarter_turns % 4 {
+        0 => (0, 1),
+        1 => (1, 0),
+        2 => (0, -1),
+        _ => (-1, 0),
+    };
+    BlockRotation {
+        matrix: [[cos, 0, sin], [0, 1, 0], [-sin, 0, cos]],
+    }
+}
+
+/// Rotation mapping the model front (+Z) to `facing`
+fn facing_rotation(facing: BlockFacing) -> BlockRotation {
+    match facing {
+        BlockFacing::PosY => BlockRotation {
+            matrix: [[1, 0, 0], [0, 0, 1], [0, -1, 0]],
+        },
+        BlockFacing::NegY => BlockRotation {
+            matrix: [[1, 0, 0], [0, 0, -1], [0, 1, 0]],
+        },
+        horizontal => yaw_rotation(horizontal_index(horizontal)),
+    }
+}
+
+/// Rotation described by a block's metadata
+pub fn block_rotation(
+    registry: &MetadataSchemaRegistry,
+    block: BlockId,
+    metadata: u8,
+) -> BlockRotation {
+    let field = |name: &str| read_metadata_field(registry, block, metadata, name);
+
+    match block_orientation_mode(registry, block) {
+        OrientationMode::Fixed => IDENTITY_ROTATION,
+        OrientationMode::Horizontal => yaw_rotation(field(FACING_FIELD)),
+        OrientationMode::AllFacings => {
+            let facing = BLOCK_FACINGS
+                .get(field(FACING_FIELD) as usize)
+                .copied()
+                .unwrap_or(BlockFacing::PosZ);
+            facing_rotation(facing)
+        }
+        OrientationMode::Axis => match field(AXIS_FIELD) {
+            // Long axis +Y -> +X
+            1 => BlockRotation {
+                matrix: [[0, 1, 0], [-1, 0, 0], [0, 0, 1]],
+            },
+            // Long axis +Y -> +Z
+            2 => BlockRotation {
+                matrix: [[1, 0, 0], [0, 0, -1], [0, 1, 0]],
+            },
+            _ => IDENTITY_ROTATION,
+        },
+        OrientationMode::Stairs => {
+            let yaw = yaw_rotation(field(FACING_FIELD));
+            if field(HALF_FIELD) == 1 {
+                // Upside down: half turn about the facing axis
+                let flip = BlockRotation {
+                    matrix: [[-1, 0, 0], [0, -1, 0], [0, 0, 1]],
+                };
+                multiply(yaw, flip)
+            } else {
+                yaw
+            }
+        }
+    }
+}
+
+/// Rotate a direction (normal) by a block rotation
+pub fn rotate_model_normal(rotation: BlockRotation, normal: [f32; 3]) -> [f32; 3] {
+    let m = rotation.matrix;
+    [
+        m[0][0] as f32 * normal[0] + m[0][1] as f32 * normal[1] + m[0][2] as f32 * normal[2],
+        m[1][0] as f32 * normal[0] + m[1][1] as f32 * normal[1] + m[1][2] as f32 * normal[2],
+        m[2][0] as f32 * normal[0] + m[2][1] as f32 * normal[1] + m[2][2] as f32 * normal[2],
+    ]
+}
+
+/// Rotate a model-space position (0..1 block space) around the block center
+pub fn rotate_model_position(rotation: BlockRotation, position: [f32; 3]) -> [f32; 3] {
+    let centered = [position[0] - 0.5, position[1] - 0.5, position[2] - 0.5];
+    let rotated = rotate_model_normal(rotation, centered);
+    [rotated[0] + 0.5, rotated[1] + 0.5, rotated[2] + 0.5]
+}
+
+/// Rotate a model-space collision box; returns the new (min, max)
+pub fn rotate_block_bounds(rotation: BlockRotation, min: [f32; 3], max: [f32; 3]) -> [[f32; 3]; 2] {
+    let a = rotate_model_position(rotation, min);
+    let b = rotate_model_position(rotation, max);
+    [
+        [a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2])],
+        [a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2])],
+    ]
+}
+
+/// World direction a model face points to after rotation (for face textures)
+pub fn rotate_facing(rotation: BlockRotation, facing: BlockFacing) -> BlockFacing {
+    let v = facing_vector(facing);
+    let rotated = rotate_model_normal(rotation, [v[0] as f32, v[1] as f32, v[2] as f32]);
+    dominant_facing(Vector3::new(rotated[0], rotated[1], rotated[2]), false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::blocks::metadata_schema::create_metadata_schema_registry;
+
+    #[test]
+    fn test_furnace_faces_player() {
+        let mut registry = create_metadata_schema_registry();
+        let furnace = BlockId::FURNACE;
+        assert!(
+            register_block_orientation(&mut registry, furnace, OrientationMode::Horizontal).is_ok()
+        );
+
+        // Player looks toward +X, so the furnace front must face -X
+        let metadata = compute_placement_metadata(
+            &registry,
+            furnace,
+            Vector3::new(1.0, -0.2, 0.1),
+            BlockFace::Top,
+        );
+        let rotation = block_rotation(&registry, furnace, metadata);
+        assert_eq!(
+            rotate_facing(rotation, BlockFacing::PosZ),
+            BlockFacing::NegX
+        );
+    }
+
+    #[test]
+    fn test_log_axis_follows_clicked_face() {
+        let mut registry = create_metadata_schema_registry();
+        assert!(
+            register_block_orientation(&mut registry, BlockId::LOG, OrientationMode::Axis).is_ok()
+        );
+
+        let metadata = compute_placement_metadata(
+            &registry,
+            BlockId::LOG,
+            Vector3::new(0.0, 0.0, 1.0),
+            BlockFace::East,
+        );
+        let rotation = block_rotation(&registry, BlockId::LOG, metadata);
+        assert_eq!(
+            rotate_facing(rotation, BlockFacing::PosY),
+            BlockFacing::PosX
+        );
+
+        // Collision bounds rotate with the model
+        let [min, max] = rotate_block_bounds(rotation, [0.25, 0.0, 0.25], [0.75, 1.0, 0.75]);
+        assert_eq!(min, [0.0, 0.25, 0.25]);
+        assert_eq!(max, [1.0, 0.75, 0.75]);
+    }
+}
@@ -1,3 +1,6 @@
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
 use crate::memory::MemoryManager;
 use crate::world::core::ChunkPos;
 use bytemuck::{Pod, Zeroable};
@@ -79,6 +82,10 @@ pub struct VoxelBvh {
     node_count: u32,
     primitive_count: u32,
     max_depth: u32,
+
+    /// `node_buffer` and `primitive_buffer` in the tracker
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
 }
 
 impl VoxelBvh {
@@ -92,19 +99,28 @@ impl VoxelBvh {
         let node_buffer_size = max_nodes as u64 * std::mem::size_of::<BvhNode>() as u64;
         let primitive_buffer_size = max_primitives as u64 * std::mem::size_of::<u32>() as u64;
 
-        let node_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("BVH Node Buffer"),
-            size: node_buffer_size,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let gpu_owner = register_global_gpu_owner("bvh");
+        let (node_buffer, node_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("BVH Node Buffer"),
+                size: node_buffer_size,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
 
-        let primitive_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("BVH Primitive Buffer"),
-            size: primitive_buffer_size,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let (primitive_buffer, primitive_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("BVH Primitive Buffer"),
+                size: primitive_buffer_size,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
 
         Self {
             device,
@@ -113,6 +129,8 @@ impl VoxelBvh {
             node_count: 0,
             primitive_count: 0,
             max_depth: 0,
+            gpu_allocations: vec![node_allocation, primitive_allocation],
+            gpu_owner,
         }
     }
 
@@ -0,0 +1,660 @@
+//! GPU chunk face connectivity for cave culling
+//!
+//! After a chunk is generated, a flood fill over its passable voxels records
+//! which pairs of chunk faces are connected. A fully enclosed cave chunk has
+//! no connections, so a traversal from the camera never passes through it.
+//!
+//! Face order is +X, -X, +Y, -Y, +Z, -Z. Each of the 15 face pairs gets one
+//! bit; see [`face_pair_bit`]. The GPU shader in `chunk_connectivity.wgsl`
+//! uses the same encoding.
+
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
+use crate::world::core::{BlockId, ChunkLayout, ChunkPos};
+use crate::world::storage::{VoxelData, WorldBuffer};
+use bytemuck::{Pod, Zeroable};
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::Arc;
+
+/// Number of chunk faces
+pub const CHUNK_FACE_COUNT: usize = 6;
+
+/// Connectivity mask with every face pair connected
+pub const ALL_FACES_CONNECTED: u16 = 0x7FFF;
+
+/// Flood fill iterations per voxel along a chunk edge
+pub const CONNECTIVITY_PASSES_PER_EDGE_VOXEL: u32 = 3;
+
+/// Flood fill iterations per chunk; a chunk that has not converged after
+/// this many passes is conservatively marked fully connected
+pub fn connectivity_propagation_passes(layout: ChunkLayout) -> u32 {
+    layout.size * CONNECTIVITY_PASSES_PER_EDGE_VOXEL
+}
+
+/// Maximum chunks processed by one connectivity dispatch
+pub const CONNECTIVITY_MAX_BATCH: usize = 16;
+
+const CONNECTIVITY_WORKGROUP_SIZE: u32 = 256;
+
+/// Chunk face, in connectivity bit order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChunkFace {
+    PosX = 0,
+    NegX = 1,
+    PosY = 2,
+    NegY = 3,
+    PosZ = 4,
+    NegZ = 5,
+}
+
+/// All faces in bit order
+pub const CHUNK_FACES: [ChunkFace; CHUNK_FACE_COUNT] = [
+    ChunkFace::PosX,
+    ChunkFace::NegX,
+    ChunkFace::PosY,
+    ChunkFace::NegY,
+    ChunkFace::PosZ,
+    ChunkFace::NegZ,
+];
+
+/// Face on the other side of the chunk
+pub fn opposite_face(face: ChunkFace) -> ChunkFace {
+    match face {
+        ChunkFace::PosX => ChunkFace::NegX,
+        ChunkFace::NegX => ChunkFace::PosX,
+        ChunkFace::PosY => ChunkFace::NegY,
+        ChunkFace::NegY => ChunkFace::PosY,
+        ChunkFace::PosZ => ChunkFace::NegZ,
+        ChunkFace::NegZ => ChunkFace::PosZ,
+    }
+}
+
+/// Offset to the neighbouring chunk across a face
+pub fn face_offset(face: ChunkFace) -> [i32; 3] {
+    match face {
+        ChunkFace::PosX => [1, 0, 0],
+        ChunkFace::NegX => [-1, 0, 0],
+        ChunkFace::PosY => [0, 1, 0],
+        ChunkFace::NegY => [0, -1, 0],
+        ChunkFace::PosZ => [0, 0, 1],
+        ChunkFace::NegZ => [0, 0, -1],
+    }
+}
+
+/// Bit index (0..15) for an unordered pair of distinct faces
+pub fn face_pair_bit(a: ChunkFace, b: ChunkFace) -> Option<u32> {
+    let (i, j) = if (a as u32) < (b as u32) {
+        (a as u32, b as u32)
+    } else {
+        (b as u32, a as u32)
+    };
+    if i == j {
+        return None;
+    }
+    Some(i * (11 - i) / 2 + (j - i - 1))
+}
+
+/// Check whether two faces are connected in a connectivity mask
+pub fn faces_connected(connectivity: u16, a: ChunkFace, b: ChunkFace) -> bool {
+    match face_pair_bit(a, b) {
+        Some(bit) => connectivity & (1 << bit) != 0,
+        // A face always "sees" itself if anything is passable on it
+        None => connectivity != 0,
+    }
+}
+
+/// Blocks the flood fill can pass through
+pub fn is_connectivity_passable(block: BlockId) -> bool {
+    block == BlockId::AIR
+        || block == BlockId::WATER
+        || block == BlockId::GLASS
+        || block == BlockId::LEAVES
+}
+
+fn boundary_faces(x: u32, y: u32, z: u32, chunk_size: u32) -> u8 {
+    let last = chunk_size - 1;
+    let mut faces = 0u8;
+    if x == last {
+        faces |= 1 << ChunkFace::PosX as u8;
+    }
+    if x == 0 {
+        faces |= 1 << ChunkFace::NegX as u8;
+    }
+    if y == last {
+        faces |= 1 << ChunkFace::PosY as u8;
+    }
+    if y == 0 {
+        faces |= 1 << ChunkFace::NegY as u8;
+    }
+    if z == last {
+        faces |= 1 << ChunkFace::PosZ as u8;
+    }
+    if z == 0 {
+        faces |= 1 << ChunkFace::NegZ as u8;
+    }
+    faces
+}
+
+fn connectivity_from_faces(faces: u8) -> u16 {
+    let mut bits = 0u16;
+    for a in CHUNK_FACES {
+        for b in CHUNK_FACES {
+            if (a as u32) < (b as u32) && faces & (1 << a as u8) != 0 && faces & (1 << b as u8) != 0
+            {
+                if let Some(bit) = face_pair_bit(a, b) {
+                    bits |= 1 << bit;
+                }
+            }
+        }
+    }
+    bits
+}
+
+/// CPU reference flood fill (used as fallback and to verify the GPU path)
+pub fn compute_chunk_connectivity_cpu(voxels: &[VoxelData], chunk_size: u32) -> u16 {
+    let cs = chunk_size as usize;
+    if voxels.len() < cs * cs * cs {
+        return ALL_FACES_CONNECTED;
+    }
+
+    let index = |x: usize, y: usize, z: usize| x + y * cs + z * cs * cs;
+    let mut visited = vec![false; cs * cs * cs];
+    let mut queue = VecDeque::new();
+    let mut connectivity = 0u16;
+
+    for start in 0..cs * cs * cs {
+        if visited[start] || !is_connectivity_passable(BlockId(voxels[start].block_id())) {
+            continue;
+        }
+
+        // Flood one region and collect the faces it touches
+        let mut faces = 0u8;
+        visited[start] = true;
+        queue.push_back(start);
+
+        while let Some(current) = queue.pop_front() {
+            let x = current % cs;
+            let y = (current / cs) % cs;
+            let z = current / (cs * cs);
+            faces |= boundary_faces(x as u32, y as u32, z as u32, chunk_size);
+
+            let neighbors = [
+                (x > 0).then(|| index(x - 1, y, z)),
+                (x + 1 < cs).then(|| index(x + 1, y, z)),
+                (y > 0).then(|| index(x, y - 1, z)),
+                (y + 1 < cs).then(|| index(x, y + 1, z)),
+                (z > 0).then(|| index(x, y, z - 1)),
+                (z + 1 < cs).then(|| index(x, y, z + 1)),
+            ];
+
+            for next in neighbors.into_iter().flatten() {
+                if !visited[next] && is_connectivity_passable(BlockId(voxels[next].block_id())) {
+                    visited[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        connectivity |= connectivity_from_faces(faces);
+        if connectivity == ALL_FACES_CONNECTED {
+            break;
+        }
+    }
+
+    connectivity
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct ConnectivityJob {
+    slot: u32,
+    _padding: [u32; 3],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct ConnectivityParams {
+    job_count: u32,
+    _padding: [u32; 3],
+}
+
+/// Connectivity mask computed for a chunk
+pub type ChunkConnectivityResult = (ChunkPos, u16);
+
+type MapResultReceiver = Receiver<Result<(), wgpu::BufferAsyncError>>;
+
+/// Batch of connectivity results waiting to be read back
+struct ConnectivityReadback {
+    positions: Vec<ChunkPos>,
+    buffer: wgpu::Buffer,
+    allocation: GpuAllocationGuard,
+    receiver: Option<MapResultReceiver>,
+}
+
+/// GPU flood fill computing per-chunk face connectivity
+pub struct ChunkConnectivityCompute {
+    device: Arc<wgpu::Device>,
+
+    seed_pipeline: wgpu::ComputePipeline,
+    propagate_pipeline: wgpu::ComputePipeline,
+    propagate_final_pipeline: wgpu::ComputePipeline,
+    resolve_pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+
+    /// Per-voxel face reachability scratch (one chunk per batch entry)
+    face_mask_buffer: wgpu::Buffer,
+    job_buffer: wgpu::Buffer,
+    result_buffer: wgpu::Buffer,
+    params_buffer: wgpu::Buffer,
+
+    /// Chunks waiting for a connectivity pass
+    queued: parking_lot::Mutex<VecDeque<ChunkPos>>,
+    /// Dispatched batches waiting for readback
+    in_flight: parking_lot::Mutex<Vec<ConnectivityReadback>>,
+
+    /// Chunk dimensions the shader and scratch buffer were built for
+    chunk_layout: ChunkLayout,
+
+    /// Face mask, job, result and params buffers in the tracker
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
+}
+
+impl std::fmt::Debug for ChunkConnectivityCompute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChunkConnectivityCompute")
+            .field("queued", &self.queued.lock().len())
+            .field("in_flight", &self.in_flight.lock().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ChunkConnectivityCompute {
+    pub fn new(device: Arc<wgpu::Device>) -> Self {
+        let chunk_layout = crate::gpu::automation::gpu_chunk_layout();
+
+        // Create shader module using unified GPU system
+        let shader_source = include_str!("../../shaders/compute/chunk_connectivity.wgsl");
+        let validated_shader = match crate::gpu::automation::create_gpu_shader(
+            &device,
+            "chunk_connectivity",
+            shader_source,
+        ) {
+            Ok(shader) => shader,
+            Err(e) => {
+                log::error!("Failed to create chunk connectivity shader: {}", e);
+                panic!("Failed to create chunk connectivity shader: {}", e);
+            }
+        };
+
+        let bind_group_layout = crate::create_bind_group_layout!(
+            &device,
+            "Chunk Connectivity Bind Group Layout",
+            0 => buffer(storage_read),  // World voxels
+            1 => buffer(storage),       // Face mask scratch
+            2 => buffer(storage_read),  // Jobs
+            3 => buffer(storage),       // Results
+            4 => buffer(uniform)        // Params
+        );
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Chunk Connectivity Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |label: &str, entry_point: &str| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                module: &validated_shader.module,
+                entry_point,
+            })
+        };
+
+        let seed_pipeline = create_pipeline("Connectivity Seed Pipeline", "seed_faces");
+        let propagate_pipeline = create_pipeline("Connectivity Propagate Pipeline", "propagate");
+        let propagate_final_pipeline =
+            create_pipeline("Connectivity Propagate Final Pipeline", "propagate_final");
+        let resolve_pipeline =
+            create_pipeline("Connectivity Resolve Pipeline", "resolve_connectivity");
+
+        let gpu_owner = register_global_gpu_owner("chunk_connectivity");
+        let mut gpu_allocations = Vec::new();
+        let mut create_buffer = |descriptor: &wgpu::BufferDescriptor| {
+            let (buffer, guard) = create_tracked_buffer(&device, &gpu_owner, descriptor);
+            gpu_allocations.push(guard);
+            buffer
+        };
+
+        let face_mask_buffer = create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Connectivity Face Mask Buffer"),
+            size: CONNECTIVITY_MAX_BATCH as u64 * chunk_layout.voxels_per_chunk as u64 * 4,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        let job_buffer = create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Connectivity Job Buffer"),
+            size: (CONNECTIVITY_MAX_BATCH * std::mem::size_of::<ConnectivityJob>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let result_buffer = create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Connectivity Result Buffer"),
+            size: (CONNECTIVITY_MAX_BATCH * 2 * std::mem::size_of::<u32>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let params_buffer = create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Connectivity Params Buffer"),
+            size: std::mem::size_of::<ConnectivityParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            device,
+            seed_pipeline,
+            propagate_pipeline,
+            propagate_final_pipeline,
+            resolve_pipeline,
+            bind_group_layout,
+            face_mask_buffer,
+            job_buffer,
+            result_buffer,
+            params_buffer,
+            queued: parking_lot::Mutex::new(VecDeque::new()),
+            in_flight: parking_lot::Mutex::new(Vec::new()),
+            chunk_layout,
+            gpu_allocations,
+            gpu_owner,
+        }
+    }
+
+    /// Queue chunks for a connectivity pass (typically right after generation)
+    pub fn queue_chunks(&self, positions: &[ChunkPos]) {
+        let mut queued = self.queued.lock();
+        for pos in positions {
+            if !queued.contains(pos) {
+                queued.push_back(*pos);
+            }
+        }
+    }
+
+    /// Whether chunks are queued or awaiting readback
+    pub fn has_pending_work(&self) -> bool {
+        !self.queued.lock().is_empty() || !self.in_flight.lock().is_empty()
+    }
+
+    /// Encode one batch of queued chunks. Returns the number of chunks encoded.
+    ///
+    /// Only one batch may be encoded per submission because the job and
+    /// result buffers are shared; call again after the encoder is submitted.
+    pub fn encode_batch(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        queue: &wgpu::Queue,
+        world_buffer: &WorldBuffer,
+    ) -> usize {
+        if world_buffer.chunk_layout() != self.chunk_layout {
+            log::error!(
+                "[ChunkConnectivity] World chunk size {} does not match shader chunk size {}",
+                world_buffer.chunk_layout().size,
+                self.chunk_layout.size
+            );
+            return 0;
+        }
+
+        let mut positions = Vec::new();
+        let mut jobs = Vec::new();
+        {
+            let mut queued = self.queued.lock();
+            while jobs.len() < CONNECTIVITY_MAX_BATCH {
+                let Some(pos) = queued.pop_front() else {
+                    break;
+                };
+                // Chunks without a resident slot have nothing to flood yet
+                if let Some(slot) = world_buffer.existing_chunk_slot(pos) {
+                    positions.push(pos);
+                    jobs.push(ConnectivityJob {
+                        slot,
+                        _padding: [0; 3],
+                    });
+                }
+            }
+        }
+
+        if jobs.is_empty() {
+            return 0;
+        }
+
+        let job_count = jobs.len() as u32;
+        queue.write_buffer(&self.job_buffer, 0, bytemuck::cast_slice(&jobs));
+        queue.write_buffer(
+            &self.params_buffer,
+            0,
+            bytemuck::bytes_of(&ConnectivityParams {
+                job_count,
+                _padding: [0; 3],
+            }),
+        );
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Chunk Connectivity Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: world_buffer.voxel_buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.face_mask_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.job_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.result_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: self.params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let workgroups_x = self
+            .chunk_layout
+            .voxels_per_chunk
+            .div_ceil(CONNECTIVITY_WORKGROUP_SIZE);
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Chunk Connectivity Flood Fill"),
+                timestamp_writes: None,
+            });
+            pass.set_bind_group(0, &bind_group, &[]);
+
+            pass.set_pipeline(&self.seed_pipeline);
+            pass.dispatch_workgroups(workgroups_x, job_count, 1);
+
+            pass.set_pipeline(&self.propagate_pipeline);
+            for _ in 1..connectivity_propagation_passes(self.chunk_layout) {
+                pass.dispatch_workgroups(workgroups_x, job_count, 1);
+            }
+
+            pass.set_pipeline(&self.propagate_final_pipeline);
+            pass.dispatch_workgroups(workgroups_x, job_count, 1);
+
+            pass.set_pipeline(&self.resolve_pipeline);
+            pass.dispatch_workgroups(workgroups_x, job_count, 1);
+        }
+
+        let result_bytes = job_count as u64 * 2 * std::mem::size_of::<u32>() as u64;
+        let (readback, allocation) = create_tracked_buffer(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Connectivity Readback Buffer"),
+                size: result_bytes,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
+        encoder.copy_buffer_to_buffer(&self.result_buffer, 0, &readback, 0, result_bytes);
+
+        self.in_flight.lock().push(ConnectivityReadback {
+            positions,
+            buffer: readback,
+            allocation,
+            receiver: None,
+        });
+
+        job_count as usize
+    }
+
+    /// Collect finished connectivity results without blocking.
+    ///
+    /// Must be called after the encoder passed to `encode_batch` was submitted.
+    pub fn poll_results(&self) -> Vec<ChunkConnectivityResult> {
+        let mut in_flight = self.in_flight.lock();
+        if in_flight.is_empty() {
+            return Vec::new();
+        }
+
+        for readback in in_flight.iter_mut() {
+            if readback.receiver.is_none() {
+                let (sender, receiver) = channel();
+                readback
+                    .buffer
+                    .slice(..)
+                    .map_async(wgpu::MapMode::Read, move |result| {
+                        let _ = sender.send(result);
+                    });
+                readback.receiver = Some(receiver);
+            }
+        }
+
+        self.device.poll(wgpu::Maintain::Poll);
+
+        let mut results = Vec::new();
+        in_flight.retain(|readback| {
+            let Some(receiver) = readback.receiver.as_ref() else {
+                return true;
+            };
+            match receiver.try_recv() {
+                Ok(Ok(())) => {
+                    {
+                        let mapped = readback.buffer.slice(..).get_mapped_range();
+                        let words: &[u32] = bytemuck::cast_slice(&mapped);
+                        for (i, pos) in readback.positions.iter().enumerate() {
+                            let bits = words.get(i * 2).copied().unwrap_or(0) as u16;
+                            let unconverged = words.get(i * 2 + 1).copied().unwrap_or(1) != 0;
+                            let connectivity = if unconverged {
+                                ALL_FACES_CONNECTED
+                            } else {
+                                bits & ALL_FACES_CONNECTED
+                            };
+                            results.push((*pos, connectivity));
+                        }
+                    }
+                    readback.buffer.unmap();
+                    false
+                }
+                Ok(Err(e)) => {
+                    log::warn!("[CONNECTIVITY] Readback mapping failed: {:?}", e);
+                    // Unknown connectivity is treated as fully open
+                    results.extend(
+                        readback
+                            .positions
+                            .iter()
+                            .map(|pos| (*pos, ALL_FACES_CONNECTED)),
+                    );
+                    false
+                }
+                Err(TryRecvError::Empty) => true,
+                Err(TryRecvError::Disconnected) => false,
+            }
+        });
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::core::{CHUNK_SIZE, VOXELS_PER_CHUNK};
+
+    fn chunk_with(fill: BlockId) -> Vec<VoxelData> {
+        vec![VoxelData::new(fill.0, 0, 0, 0); VOXELS_PER_CHUNK as usize]
+    }
+
+    #[test]
+    fn test_face_pair_bits_are_unique() {
+        let mut seen = 0u16;
+        for a in CHUNK_FACES {
+            for b in CHUNK_FACES {
+                if (a as u32) < (b as u32) {
+                    let bit = face_pair_bit(a, b).expect("distinct faces have a bit");
+                    assert!(bit < 15);
+                    assert_eq!(seen & (1 << bit), 0);
+                    seen |= 1 << bit;
+                }
+            }
+        }
+        assert_eq!(seen, ALL_FACES_CONNECTED);
+        assert_eq!(face_pair_bit(ChunkFace::PosY, ChunkFace::PosY), None);
+    }
+
+    #[test]
+    fn test_cpu_connectivity() {
+        // Solid chunk: nothing connects
+        assert_eq!(
+            compute_chunk_connectivity_cpu(&chunk_with(BlockId::STONE), CHUNK_SIZE),
+            0
+        );
+
+        // Open chunk: everything connects
+        assert_eq!(
+            compute_chunk_connectivity_cpu(&chunk_with(BlockId::AIR), CHUNK_SIZE),
+            ALL_FACES_CONNECTED
+        );
+
+        // Enclosed cave pocket touches no face
+        let cs = CHUNK_SIZE as usize;
+        let mut cave = chunk_with(BlockId::STONE);
+        for z in 10..20 {
+            for y in 10..20 {
+                for x in 10..20 {
+                    cave[x + y * cs + z * cs * cs] = VoxelData::AIR;
+                }
+            }
+        }
+        assert_eq!(compute_chunk_connectivity_cpu(&cave, CHUNK_SIZE), 0);
+
+        // Vertical shaft connects only top and bottom
+        let mut shaft = chunk_with(BlockId::STONE);
+        for y in 0..cs {
+            shaft[5 + y * cs + 5 * cs * cs] = VoxelData::AIR;
+        }
+        let connectivity = compute_chunk_connectivity_cpu(&shaft, CHUNK_SIZE);
+        assert!(faces_connected(
+            connectivity,
+            ChunkFace::PosY,
+            ChunkFace::NegY
+        ));
+        assert!(!faces_connected(
+            connectivity,
+            ChunkFace::PosX,
+            ChunkFace::NegY
+        ));
+    }
+}
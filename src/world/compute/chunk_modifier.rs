@@ -1,4 +1,10 @@
-use crate::world::storage::WorldBuffer;
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
+use crate::world::core::{chunk_origin_voxel, voxel_to_chunk_pos, ChunkPos, VoxelPos};
+use crate::world::storage::{
+    apply_modifications_to_shadow, SharedShadowCache, VoxelStorageFormat, WorldBuffer,
+};
 use bytemuck::{Pod, Zeroable};
 use std::sync::Arc;
 // use crate::memory::PersistentBuffer; // Not needed anymore
@@ -52,6 +58,144 @@ impl ModificationCommand {
     }
 }
 
+/// Command resolved against the world buffer's slot table; explosions are
+/// split into one command per resident chunk they reach
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ResolvedModification {
+    chunk_origin: [i32; 3],
+    slot: u32,
+    position: [i32; 3],
+    block_id: u32,
+    mod_type: u32,
+    radius: f32,
+    _padding: [u32; 2],
+}
+
+/// Output of `resolve_modifications`: the first `block_count` entries are
+/// block edits, the rest explosions
+struct ResolvedModifications {
+    entries: Vec<ResolvedModification>,
+    block_count: u32,
+}
+
+/// Counts uniform of the modification shader
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ModificationCounts {
+    block_count: u32,
+    explosion_count: u32,
+    _padding: [u32; 2],
+}
+
+fn resolve_command(
+    world_buffer: &WorldBuffer,
+    cmd: &ModificationCommand,
+    chunk_pos: ChunkPos,
+) -> Option<ResolvedModification> {
+    let slot = world_buffer.existing_chunk_slot(chunk_pos)?;
+    let origin = chunk_origin_voxel(world_buffer.chunk_layout(), chunk_pos);
+    Some(ResolvedModification {
+        chunk_origin: [origin.x, origin.y, origin.z],
+        slot,
+        position: cmd.position,
+        block_id: cmd.block_id,
+        mod_type: cmd.mod_type,
+        radius: cmd.radius,
+        _padding: [0; 2],
+    })
+}
+
+/// Resolve commands to slots, block edits first and explosions after.
+/// Edits of chunks without a slot (not loaded) are dropped.
+fn resolve_modifications(
+    world_buffer: &WorldBuffer,
+    commands: &[ModificationCommand],
+) -> ResolvedModifications {
+    let layout = world_buffer.chunk_layout();
+    let mut resolved = Vec::with_capacity(commands.len());
+    for cmd in commands.iter().filter(|cmd| cmd.mod_type < 2) {
+        let [x, y, z] = cmd.position;
+        let chunk_pos = voxel_to_chunk_pos(layout, VoxelPos::new(x, y, z));
+        match resolve_command(world_buffer, cmd, chunk_pos) {
+            Some(command) => resolved.push(command),
+            None => log::debug!(
+                "[ChunkModifier] Dropping edit at {:?}: chunk {:?} is not resident",
+                cmd.position,
+                chunk_pos
+            ),
+        }
+    }
+    let block_count = resolved.len() as u32;
+
+    for cmd in commands.iter().filter(|cmd| cmd.mod_type == 2) {
+        let reach = cmd.radius.ceil().max(0.0) as i32;
+        let [x, y, z] = cmd.position;
+        let min = voxel_to_chunk_pos(layout, VoxelPos::new(x - reach, y - reach, z - reach));
+        let max = voxel_to_chunk_pos(layout, VoxelPos::new(x + reach, y + reach, z + reach));
+        for cx in min.x..=max.x {
+            for cy in min.y..=max.y {
+                for cz in min.z..=max.z {
+                    let chunk_pos = ChunkPos::new(cx, cy, cz);
+                    resolved.extend(resolve_command(world_buffer, cmd, chunk_pos));
+                }
+            }
+        }
+    }
+
+    ResolvedModifications {
+        entries: resolved,
+        block_count,
+    }
+}
+
+/// Promote every sparse or palettized chunk touched by the commands to a
+/// full slot; palette chunks are decoded into `encoder` ahead of the edits
+fn promote_modified_chunks(
+    encoder: &mut wgpu::CommandEncoder,
+    queue: &wgpu::Queue,
+    world_buffer: &WorldBuffer,
+    commands: &[ModificationCommand],
+) {
+    let sparse = world_buffer.sparse_chunks_enabled();
+    let palette = world_buffer.voxel_format() == VoxelStorageFormat::Palette8;
+    if !sparse && !palette {
+        return;
+    }
+
+    let mut palette_chunks = Vec::new();
+    let layout = world_buffer.chunk_layout();
+    for cmd in commands {
+        let reach = if cmd.mod_type == 2 {
+            cmd.radius.ceil().max(0.0) as i32
+        } else {
+            0
+        };
+        let [x, y, z] = cmd.position;
+        let min = voxel_to_chunk_pos(layout, VoxelPos::new(x - reach, y - reach, z - reach));
+        let max = voxel_to_chunk_pos(layout, VoxelPos::new(x + reach, y + reach, z + reach));
+
+        for cx in min.x..=max.x {
+            for cy in min.y..=max.y {
+                for cz in min.z..=max.z {
+                    let chunk_pos = ChunkPos::new(cx, cy, cz);
+                    if sparse {
+                        world_buffer.promote_sparse_chunk(queue, chunk_pos);
+                    }
+                    if palette
+                        && world_buffer.is_chunk_palettized(chunk_pos)
+                        && !palette_chunks.contains(&chunk_pos)
+                    {
+                        palette_chunks.push(chunk_pos);
+                    }
+                }
+            }
+        }
+    }
+
+    world_buffer.promote_palette_chunks(queue, encoder, &palette_chunks);
+}
+
 /// GPU-based chunk modification system
 pub struct ChunkModifier {
     device: Arc<wgpu::Device>,
@@ -74,6 +218,13 @@ pub struct ChunkModifier {
 
     /// Bind group layout
     bind_group_layout: wgpu::BindGroupLayout,
+
+    /// CPU shadow cache mirrored with every applied batch
+    shadow: Option<SharedShadowCache>,
+
+    /// Tracker entries for `command_buffer` and `count_buffer`
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
 }
 
 impl ChunkModifier {
@@ -156,20 +307,29 @@ impl ChunkModifier {
 
         // Create command buffer
         let command_capacity = 10000;
-        let command_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Modification Commands Buffer"),
-            size: (command_capacity * std::mem::size_of::<ModificationCommand>()) as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let gpu_owner = register_global_gpu_owner("chunk_modifier");
+        let (command_buffer, command_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Modification Commands Buffer"),
+                size: (command_capacity * std::mem::size_of::<ResolvedModification>()) as u64,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
 
         // Create persistent count buffer
-        let count_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Modification Count Buffer"),
-            size: std::mem::size_of::<u32>() as u64,
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let (count_buffer, count_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Modification Count Buffer"),
+                size: std::mem::size_of::<ModificationCounts>() as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
 
         Self {
             device,
@@ -180,10 +340,22 @@ impl ChunkModifier {
             count_buffer,
             cached_bind_groups: std::sync::Mutex::new(std::collections::HashMap::new()),
             bind_group_layout,
+            shadow: None,
+            gpu_allocations: vec![command_allocation, count_allocation],
+            gpu_owner,
         }
     }
 
-    /// Apply a batch of modifications to the world
+    /// Mirror every applied batch into a CPU shadow cache
+    pub fn with_shadow_cache(mut self, shadow: SharedShadowCache) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+
+    /// Apply a batch of modifications to the world.
+    ///
+    /// Commands are uploaded with `write_buffer`, so call this at most once
+    /// per submitted command buffer.
     pub fn apply_modifications(
         &self,
         encoder: &mut wgpu::CommandEncoder,
@@ -195,42 +367,56 @@ impl ChunkModifier {
             return;
         }
 
-        // Split commands by type
-        let mut block_mods = Vec::new();
-        let mut explosions = Vec::new();
-
-        for cmd in commands {
-            match cmd.mod_type {
-                0 | 1 => block_mods.push(*cmd),
-                2 => explosions.push(*cmd),
-                _ => {}
-            }
+        if let Some(shadow) = &self.shadow {
+            let mut cache = match shadow.lock() {
+                Ok(cache) => cache,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            apply_modifications_to_shadow(&mut cache, commands);
         }
 
-        // Process block modifications
-        if !block_mods.is_empty() {
-            let _ = self.apply_block_modifications(encoder, queue, world_buffer, &block_mods);
+        // Sparse and palette chunks have no full slot to write into; give them one first
+        promote_modified_chunks(encoder, queue, world_buffer, commands);
+
+        let ResolvedModifications {
+            entries: mut resolved,
+            mut block_count,
+        } = resolve_modifications(world_buffer, commands);
+        if resolved.len() > self.command_capacity {
+            log::warn!(
+                "[ChunkModifier] {} resolved modifications exceed capacity {}, truncating",
+                resolved.len(),
+                self.command_capacity
+            );
+            resolved.truncate(self.command_capacity);
+            block_count = block_count.min(self.command_capacity as u32);
+        }
+        if resolved.is_empty() {
+            return;
         }
 
-        // Process explosions
-        if !explosions.is_empty() {
-            let _ = self.apply_explosions(encoder, queue, world_buffer, &explosions);
+        if let Err(e) = self.dispatch_modifications(encoder, queue, world_buffer, &resolved, block_count)
+        {
+            log::error!("[ChunkModifier] Failed to apply modifications: {}", e);
         }
     }
 
-    fn apply_block_modifications(
+    fn dispatch_modifications(
         &self,
         encoder: &mut wgpu::CommandEncoder,
         queue: &wgpu::Queue,
         world_buffer: &WorldBuffer,
-        commands: &[ModificationCommand],
+        resolved: &[ResolvedModification],
+        block_count: u32,
     ) -> WorldGpuResult<()> {
-        // Upload commands
-        queue.write_buffer(&self.command_buffer, 0, bytemuck::cast_slice(commands));
-
-        // Update count in persistent buffer
-        let count_data = [commands.len() as u32];
-        queue.write_buffer(&self.count_buffer, 0, bytemuck::cast_slice(&count_data));
+        let explosion_count = resolved.len() as u32 - block_count;
+        queue.write_buffer(&self.command_buffer, 0, bytemuck::cast_slice(resolved));
+        let counts = ModificationCounts {
+            block_count,
+            explosion_count,
+            _padding: [0; 2],
+        };
+        queue.write_buffer(&self.count_buffer, 0, bytemuck::bytes_of(&counts));
 
         // Get or create cached bind group
         let cache_key = world_buffer.voxel_buffer() as *const _ as u64;
@@ -260,63 +446,24 @@ impl ChunkModifier {
             })
         });
 
-        // Dispatch compute shader
         let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
             label: Some("Block Modification Pass"),
             timestamp_writes: None,
         });
+        compute_pass.set_bind_group(0, &*bind_group, &[]);
 
-        compute_pass.set_pipeline(&self.modify_pipeline);
-        compute_pass.set_bind_group(0, &bind_group, &[]);
-
-        // One thread per command
-        let workgroups = ((commands.len() + 63) / 64) as u32;
-        compute_pass.dispatch_workgroups(workgroups, 1, 1);
-
-        Ok(())
-    }
-
-    fn apply_explosions(
-        &self,
-        encoder: &mut wgpu::CommandEncoder,
-        queue: &wgpu::Queue,
-        world_buffer: &WorldBuffer,
-        explosions: &[ModificationCommand],
-    ) -> WorldGpuResult<()> {
-        // Upload explosion commands
-        queue.write_buffer(&self.command_buffer, 0, bytemuck::cast_slice(explosions));
-
-        // Update count in persistent buffer
-        let count_data = [explosions.len() as u32];
-        queue.write_buffer(&self.count_buffer, 0, bytemuck::cast_slice(&count_data));
-
-        // Use cached bind group (same as block modifications)
-        let cache_key = world_buffer.voxel_buffer() as *const _ as u64;
-        let cached_groups = self
-            .cached_bind_groups
-            .lock()
-            .world_context("cached_bind_groups")?;
-
-        let bind_group = cached_groups
-            .get(&cache_key)
-            .expect("Bind group should be cached from block modifications");
-
-        // Dispatch compute shader
-        let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-            label: Some("Explosion Pass"),
-            timestamp_writes: None,
-        });
-
-        compute_pass.set_pipeline(&self.explode_pipeline);
-        compute_pass.set_bind_group(0, &bind_group, &[]);
+        // One thread per block edit
+        if block_count > 0 {
+            compute_pass.set_pipeline(&self.modify_pipeline);
+            compute_pass.dispatch_workgroups(block_count.div_ceil(64), 1, 1);
+        }
 
-        // Process explosions with enough threads to cover blast radius
-        // Each explosion uses multiple workgroups
-        compute_pass.dispatch_workgroups(
-            explosions.len() as u32 * 8, // 8 workgroups per explosion
-            8,
-            8,
-        );
+        // One thread per voxel of each chunk an explosion reaches
+        if explosion_count > 0 {
+            let groups = world_buffer.chunk_layout().size.div_ceil(4);
+            compute_pass.set_pipeline(&self.explode_pipeline);
+            compute_pass.dispatch_workgroups(groups, groups, groups * explosion_count);
+        }
 
         Ok(())
     }
@@ -0,0 +1,425 @@
+//! GPU verification of generated chunks
+//!
+//! GPU generation can fail without reporting an error: a dispatch that never
+//! ran or a device that was lost mid-frame leaves the chunk slot full of air.
+//! After generation, a small reduction counts the non-air voxels of each
+//! chunk; chunks that should be mostly solid but come back (nearly) empty are
+//! flagged so the generator can run them again.
+
+use crate::constants::terrain::MIN_HEIGHT;
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
+use crate::world::core::{BlockId, ChunkLayout, ChunkPos};
+use crate::world::storage::{VoxelData, WorldBuffer};
+use bytemuck::{Pod, Zeroable};
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::Arc;
+
+/// Maximum chunks processed by one verification dispatch
+pub const VERIFICATION_MAX_BATCH: usize = 64;
+
+/// Fraction of a fully underground chunk that must be non-air; caves never
+/// carve out more than this
+pub const VERIFICATION_MIN_SOLID_FRACTION: f32 = 0.01;
+
+/// Regeneration attempts per chunk before it is left as is
+pub const MAX_REGENERATION_ATTEMPTS: u32 = 3;
+
+const VERIFICATION_WORKGROUP_SIZE: u32 = 256;
+
+/// Minimum non-air voxels a correctly generated chunk must contain.
+///
+/// Only chunks entirely below the lowest possible terrain surface have a
+/// known lower bound; anything higher may legitimately be empty.
+pub fn expected_min_non_air_voxels(chunk_pos: ChunkPos, layout: ChunkLayout) -> u32 {
+    let chunk_top = (chunk_pos.y + 1) * layout.size as i32;
+    if chunk_top > MIN_HEIGHT {
+        return 0;
+    }
+    ((layout.voxels_per_chunk as f32 * VERIFICATION_MIN_SOLID_FRACTION) as u32).max(1)
+}
+
+/// Whether a chunk's non-air voxel count points at failed generation
+pub fn is_chunk_suspicious(chunk_pos: ChunkPos, layout: ChunkLayout, non_air_voxels: u32) -> bool {
+    non_air_voxels < expected_min_non_air_voxels(chunk_pos, layout)
+}
+
+/// CPU reference count (used as fallback and to verify the GPU path)
+pub fn count_non_air_voxels_cpu(voxels: &[VoxelData]) -> u32 {
+    voxels
+        .iter()
+        .filter(|voxel| BlockId(voxel.block_id()) != BlockId::AIR)
+        .count() as u32
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct VerificationJob {
+    slot: u32,
+    _padding: [u32; 3],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct VerificationParams {
+    job_count: u32,
+    _padding: [u32; 3],
+}
+
+/// Non-air voxel count measured for a chunk
+pub type ChunkVerificationResult = (ChunkPos, u32);
+
+/// Outcome of one verification poll
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChunkVerificationReport {
+    pub chunks_verified: u64,
+    pub suspicious_chunks: u64,
+    pub chunks_regenerated: u64,
+    /// Suspicious chunks that ran out of regeneration attempts
+    pub chunks_abandoned: u64,
+}
+
+type MapResultReceiver = Receiver<Result<(), wgpu::BufferAsyncError>>;
+
+/// Batch of counts waiting to be read back
+struct VerificationReadback {
+    positions: Vec<ChunkPos>,
+    buffer: wgpu::Buffer,
+    allocation: GpuAllocationGuard,
+    receiver: Option<MapResultReceiver>,
+}
+
+/// GPU reduction counting non-air voxels per generated chunk
+pub struct ChunkVerificationCompute {
+    device: Arc<wgpu::Device>,
+
+    count_pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+
+    job_buffer: wgpu::Buffer,
+    result_buffer: wgpu::Buffer,
+    params_buffer: wgpu::Buffer,
+
+    /// Chunks waiting for verification
+    queued: parking_lot::Mutex<VecDeque<ChunkPos>>,
+    /// Dispatched batches waiting for readback
+    in_flight: parking_lot::Mutex<Vec<VerificationReadback>>,
+
+    /// Chunk dimensions the shader was built for
+    chunk_layout: ChunkLayout,
+
+    /// Job, result and params buffers; each readback holds its own guard
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
+}
+
+impl ChunkVerificationCompute {
+    pub fn new(device: Arc<wgpu::Device>) -> Self {
+        let chunk_layout = crate::gpu::automation::gpu_chunk_layout();
+
+        // Create shader module using unified GPU system
+        let shader_source = include_str!("../../shaders/compute/chunk_verification.wgsl");
+        let validated_shader = match crate::gpu::automation::create_gpu_shader(
+            &device,
+            "chunk_verification",
+            shader_source,
+        ) {
+            Ok(shader) => shader,
+            Err(e) => {
+                log::error!("Failed to create chunk verification shader: {}", e);
+                panic!("Failed to create chunk verification shader: {}", e);
+            }
+        };
+
+        let bind_group_layout = crate::create_bind_group_layout!(
+            &device,
+            "Chunk Verification Bind Group Layout",
+            0 => buffer(storage_read),  // World voxels
+            1 => buffer(storage_read),  // Jobs
+            2 => buffer(storage),       // Counts
+            3 => buffer(uniform)        // Params
+        );
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Chunk Verification Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let count_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Chunk Verification Count Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &validated_shader.module,
+            entry_point: "count_non_air",
+        });
+
+        let gpu_owner = register_global_gpu_owner("chunk_verification");
+        let mut gpu_allocations = Vec::new();
+        let mut create_buffer = |descriptor: &wgpu::BufferDescriptor| {
+            let (buffer, guard) = create_tracked_buffer(&device, &gpu_owner, descriptor);
+            gpu_allocations.push(guard);
+            buffer
+        };
+
+        let job_buffer = create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Verification Job Buffer"),
+            size: (VERIFICATION_MAX_BATCH * std::mem::size_of::<VerificationJob>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let result_buffer = create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Verification Result Buffer"),
+            size: (VERIFICATION_MAX_BATCH * std::mem::size_of::<u32>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let params_buffer = create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Verification Params Buffer"),
+            size: std::mem::size_of::<VerificationParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            device,
+            count_pipeline,
+            bind_group_layout,
+            job_buffer,
+            result_buffer,
+            params_buffer,
+            queued: parking_lot::Mutex::new(VecDeque::new()),
+            in_flight: parking_lot::Mutex::new(Vec::new()),
+            chunk_layout,
+            gpu_allocations,
+            gpu_owner,
+        }
+    }
+
+    /// Chunk dimensions the verification shader was built for
+    pub fn chunk_layout(&self) -> ChunkLayout {
+        self.chunk_layout
+    }
+
+    /// Queue freshly generated chunks for verification
+    pub fn queue_chunks(&self, positions: &[ChunkPos]) {
+        let mut queued = self.queued.lock();
+        for pos in positions {
+            if !queued.contains(pos) {
+                queued.push_back(*pos);
+            }
+        }
+    }
+
+    /// Whether chunks are queued or awaiting readback
+    pub fn has_pending_work(&self) -> bool {
+        !self.queued.lock().is_empty() || !self.in_flight.lock().is_empty()
+    }
+
+    /// Encode one batch of queued chunks. Returns the number of chunks encoded.
+    ///
+    /// Only one batch may be encoded per submission because the job and
+    /// result buffers are shared; call again after the encoder is submitted.
+    pub fn encode_batch(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        queue: &wgpu::Queue,
+        world_buffer: &WorldBuffer,
+    ) -> usize {
+        if world_buffer.chunk_layout() != self.chunk_layout {
+            log::error!(
+                "[ChunkVerification] World chunk size {} does not match shader chunk size {}",
+                world_buffer.chunk_layout().size,
+                self.chunk_layout.size
+            );
+            return 0;
+        }
+
+        let mut positions = Vec::new();
+        let mut jobs = Vec::new();
+        {
+            let mut queued = self.queued.lock();
+            while jobs.len() < VERIFICATION_MAX_BATCH {
+                let Some(pos) = queued.pop_front() else {
+                    break;
+                };
+                // Sparse and evicted chunks have no generated slot to check
+                if let Some(slot) = world_buffer.existing_chunk_slot(pos) {
+                    positions.push(pos);
+                    jobs.push(VerificationJob {
+                        slot,
+                        _padding: [0; 3],
+                    });
+                }
+            }
+        }
+
+        if jobs.is_empty() {
+            return 0;
+        }
+
+        let job_count = jobs.len() as u32;
+        queue.write_buffer(&self.job_buffer, 0, bytemuck::cast_slice(&jobs));
+        queue.write_buffer(
+            &self.params_buffer,
+            0,
+            bytemuck::bytes_of(&VerificationParams {
+                job_count,
+                _padding: [0; 3],
+            }),
+        );
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Chunk Verification Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: world_buffer.voxel_buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.job_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.result_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let result_bytes = job_count as u64 * std::mem::size_of::<u32>() as u64;
+        encoder.clear_buffer(&self.result_buffer, 0, Some(result_bytes));
+
+        let workgroups_x = self
+            .chunk_layout
+            .voxels_per_chunk
+            .div_ceil(VERIFICATION_WORKGROUP_SIZE);
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Chunk Verification Count"),
+                timestamp_writes: None,
+            });
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.set_pipeline(&self.count_pipeline);
+            pass.dispatch_workgroups(workgroups_x, job_count, 1);
+        }
+
+        let (readback, allocation) = create_tracked_buffer(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Verification Readback Buffer"),
+                size: result_bytes,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
+        encoder.copy_buffer_to_buffer(&self.result_buffer, 0, &readback, 0, result_bytes);
+
+        self.in_flight.lock().push(VerificationReadback {
+            positions,
+            buffer: readback,
+            allocation,
+            receiver: None,
+        });
+
+        job_count as usize
+    }
+
+    /// Collect finished counts without blocking.
+    ///
+    /// Must be called after the encoder passed to `encode_batch` was submitted.
+    pub fn poll_results(&self) -> Vec<ChunkVerificationResult> {
+        let mut in_flight = self.in_flight.lock();
+        if in_flight.is_empty() {
+            return Vec::new();
+        }
+
+        for readback in in_flight.iter_mut() {
+            if readback.receiver.is_none() {
+                let (sender, receiver) = channel();
+                readback
+                    .buffer
+                    .slice(..)
+                    .map_async(wgpu::MapMode::Read, move |result| {
+                        let _ = sender.send(result);
+                    });
+                readback.receiver = Some(receiver);
+            }
+        }
+
+        self.device.poll(wgpu::Maintain::Poll);
+
+        let mut results = Vec::new();
+        in_flight.retain(|readback| {
+            let Some(receiver) = readback.receiver.as_ref() else {
+                return true;
+            };
+            match receiver.try_recv() {
+                Ok(Ok(())) => {
+                    {
+                        let mapped = readback.buffer.slice(..).get_mapped_range();
+                        let counts: &[u32] = bytemuck::cast_slice(&mapped);
+                        for (i, pos) in readback.positions.iter().enumerate() {
+                            results.push((*pos, counts.get(i).copied().unwrap_or(0)));
+                        }
+                    }
+                    readback.buffer.unmap();
+                    false
+                }
+                Ok(Err(e)) => {
+                    // A failed readback says nothing about the chunks; do not
+                    // flag them
+                    log::warn!("[VERIFICATION] Readback mapping failed: {:?}", e);
+                    false
+                }
+                Err(TryRecvError::Empty) => true,
+                Err(TryRecvError::Disconnected) => false,
+            }
+        });
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_deep_chunks_have_expectations() {
+        let layout = ChunkLayout::default();
+        let size = layout.size as i32;
+
+        let deep = ChunkPos::new(0, MIN_HEIGHT / size - 1, 0);
+        assert!(expected_min_non_air_voxels(deep, layout) > 0);
+        assert!(is_chunk_suspicious(deep, layout, 0));
+        assert!(!is_chunk_suspicious(deep, layout, layout.voxels_per_chunk));
+
+        let surface = ChunkPos::new(0, MIN_HEIGHT / size + 1, 0);
+        assert_eq!(expected_min_non_air_voxels(surface, layout), 0);
+        assert!(!is_chunk_suspicious(surface, layout, 0));
+    }
+
+    #[test]
+    fn test_cpu_count() {
+        let layout = ChunkLayout::default();
+        let mut voxels = vec![VoxelData::AIR; layout.voxels_per_chunk as usize];
+        assert_eq!(count_non_air_voxels_cpu(&voxels), 0);
+
+        for voxel in voxels.iter_mut().take(10) {
+            *voxel = VoxelData::new(BlockId::STONE.0, 0, 0, 0);
+        }
+        assert_eq!(count_non_air_voxels_cpu(&voxels), 10);
+    }
+}
@@ -0,0 +1,484 @@
+//! GPU column heightmap
+//!
+//! After a chunk is generated or edited on the GPU, one pass records the
+//! highest non-air voxel of each of its columns into a persistent heightmap
+//! buffer (one section per world buffer slot) that GPU consumers such as
+//! the minimap or impostors bind directly. The same sections are read back
+//! and fed to `world_operations::apply_chunk_column_tops`, which keeps the
+//! CPU mirror behind `get_surface_height` in step.
+
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
+use crate::world::core::{BlockId, ChunkLayout, ChunkPos};
+use crate::world::storage::{VoxelData, WorldBuffer};
+use bytemuck::{Pod, Zeroable};
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::Arc;
+
+/// Maximum chunks processed by one heightmap dispatch
+pub const HEIGHTMAP_MAX_BATCH: usize = 64;
+
+const HEIGHTMAP_WORKGROUP_SIZE: u32 = 256;
+
+/// Heightmap words per chunk section
+pub fn heightmap_section_len(layout: ChunkLayout) -> u32 {
+    layout.size * layout.size
+}
+
+/// CPU reference pass (used as fallback and to verify the GPU path).
+/// Returns local y + 1 of each column top, indexed `x + z * chunk_size`.
+pub fn compute_column_tops_cpu(voxels: &[VoxelData], chunk_size: u32) -> Vec<u32> {
+    let cs = chunk_size as usize;
+    let mut tops = vec![0u32; cs * cs];
+    for (index, voxel) in voxels.iter().enumerate().take(cs * cs * cs) {
+        if BlockId(voxel.block_id()) == BlockId::AIR {
+            continue;
+        }
+        let column = index % cs + (index / (cs * cs)) * cs;
+        let top = ((index / cs) % cs) as u32 + 1;
+        tops[column] = tops[column].max(top);
+    }
+    tops
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct HeightmapJob {
+    slot: u32,
+    _padding: [u32; 3],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct HeightmapParams {
+    job_count: u32,
+    _padding: [u32; 3],
+}
+
+/// Column tops computed for a chunk
+pub type ChunkHeightmapResult = (ChunkPos, Vec<u32>);
+
+type MapResultReceiver = Receiver<Result<(), wgpu::BufferAsyncError>>;
+
+/// Batch of sections waiting to be read back
+struct HeightmapReadback {
+    positions: Vec<ChunkPos>,
+    buffer: wgpu::Buffer,
+    allocation: GpuAllocationGuard,
+    receiver: Option<MapResultReceiver>,
+}
+
+/// GPU pass maintaining the per-slot column heightmap
+pub struct ColumnHeightmapCompute {
+    device: Arc<wgpu::Device>,
+
+    tops_pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+
+    /// Persistent heightmap, `heightmap_section_len` words per slot
+    heightmap_buffer: wgpu::Buffer,
+    job_buffer: wgpu::Buffer,
+    params_buffer: wgpu::Buffer,
+
+    /// Chunks waiting for a heightmap pass
+    queued: parking_lot::Mutex<VecDeque<ChunkPos>>,
+    /// Dispatched batches waiting for readback
+    in_flight: parking_lot::Mutex<Vec<HeightmapReadback>>,
+
+    /// Chunk dimensions the shader was built for
+    chunk_layout: ChunkLayout,
+    /// World buffer slots the heightmap has sections for
+    max_slots: u32,
+
+    /// Heightmap, job and params buffers; readbacks carry their own guard
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
+}
+
+impl ColumnHeightmapCompute {
+    /// Create the pass with a section for each of `max_slots` world buffer
+    /// slots (see `WorldBuffer::max_chunks`)
+    pub fn new(device: Arc<wgpu::Device>, max_slots: u32) -> Self {
+        let chunk_layout = crate::gpu::automation::gpu_chunk_layout();
+
+        // Create shader module using unified GPU system
+        let shader_source = include_str!("../../shaders/compute/column_heightmap.wgsl");
+        let validated_shader = match crate::gpu::automation::create_gpu_shader(
+            &device,
+            "column_heightmap",
+            shader_source,
+        ) {
+            Ok(shader) => shader,
+            Err(e) => {
+                log::error!("Failed to create column heightmap shader: {}", e);
+                panic!("Failed to create column heightmap shader: {}", e);
+            }
+        };
+
+        let bind_group_layout = crate::create_bind_group_layout!(
+            &device,
+            "Column Heightmap Bind Group Layout",
+            0 => buffer(storage_read),  // World voxels
+            1 => buffer(storage_read),  // Jobs
+            2 => buffer(storage),       // Heightmap
+            3 => buffer(uniform)        // Params
+        );
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Column Heightmap Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let tops_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Column Heightmap Tops Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &validated_shader.module,
+            entry_point: "column_tops",
+        });
+
+        let gpu_owner = register_global_gpu_owner("column_heightmap");
+        let mut gpu_allocations = Vec::new();
+        let mut create_buffer = |descriptor: &wgpu::BufferDescriptor| {
+            let (buffer, guard) = create_tracked_buffer(&device, &gpu_owner, descriptor);
+            gpu_allocations.push(guard);
+            buffer
+        };
+
+        let heightmap_buffer = create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Column Heightmap Buffer"),
+            size: max_slots.max(1) as u64 * heightmap_section_len(chunk_layout) as u64 * 4,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let job_buffer = create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Heightmap Job Buffer"),
+            size: (HEIGHTMAP_MAX_BATCH * std::mem::size_of::<HeightmapJob>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let params_buffer = create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Heightmap Params Buffer"),
+            size: std::mem::size_of::<HeightmapParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            device,
+            tops_pipeline,
+            bind_group_layout,
+            heightmap_buffer,
+            job_buffer,
+            params_buffer,
+            queued: parking_lot::Mutex::new(VecDeque::new()),
+            in_flight: parking_lot::Mutex::new(Vec::new()),
+            chunk_layout,
+            max_slots,
+            gpu_allocations,
+            gpu_owner,
+        }
+    }
+
+    /// Persistent heightmap for other GPU passes; the section of a chunk
+    /// starts at its world buffer slot times `heightmap_section_len`
+    pub fn heightmap_buffer(&self) -> &wgpu::Buffer {
+        &self.heightmap_buffer
+    }
+
+    /// Chunk dimensions the heightmap shader was built for
+    pub fn chunk_layout(&self) -> ChunkLayout {
+        self.chunk_layout
+    }
+
+    /// Queue chunks whose voxels changed on the GPU (generation, GPU edits)
+    pub fn queue_chunks(&self, positions: &[ChunkPos]) {
+        let mut queued = self.queued.lock();
+        for pos in positions {
+            if !queued.contains(pos) {
+                queued.push_back(*pos);
+            }
+        }
+    }
+
+    /// Whether chunks are queued or awaiting readback
+    pub fn has_pending_work(&self) -> bool {
+        !self.queued.lock().is_empty() || !self.in_flight.lock().is_empty()
+    }
+
+    /// Encode one batch of queued chunks. Returns the number of chunks encoded.
+    ///
+    /// Only one batch may be encoded per submission because the job buffer
+    /// is shared; call again after the encoder is submitted.
+    pub fn encode_batch(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        queue: &wgpu::Queue,
+        world_buffer: &WorldBuffer,
+    ) -> usize {
+        if world_buffer.chunk_layout() != self.chunk_layout {
+            log::error!(
+                "[ColumnHeightmap] World chunk size {} does not match shader chunk size {}",
+                world_buffer.chunk_layout().size,
+                self.chunk_layout.size
+            );
+            return 0;
+        }
+
+        let mut positions = Vec::new();
+        let mut jobs = Vec::new();
+        {
+            let mut queued = self.queued.lock();
+            while jobs.len() < HEIGHTMAP_MAX_BATCH {
+                let Some(pos) = queued.pop_front() else {
+                    break;
+                };
+                // Sparse chunks have no slot; their tops are known on the CPU
+                match world_buffer.existing_chunk_slot(pos) {
+                    Some(slot) if slot < self.max_slots => {
+                        positions.push(pos);
+                        jobs.push(HeightmapJob {
+                            slot,
+                            _padding: [0; 3],
+                        });
+                    }
+                    Some(slot) => log::warn!(
+                        "[ColumnHeightmap] Slot {} of chunk {:?} is beyond the heightmap ({} slots)",
+                        slot,
+                        pos,
+                        self.max_slots
+                    ),
+                    None => {}
+                }
+            }
+        }
+
+        if jobs.is_empty() {
+            return 0;
+        }
+
+        let job_count = jobs.len() as u32;
+        queue.write_buffer(&self.job_buffer, 0, bytemuck::cast_slice(&jobs));
+        queue.write_buffer(
+            &self.params_buffer,
+            0,
+            bytemuck::bytes_of(&HeightmapParams {
+                job_count,
+                _padding: [0; 3],
+            }),
+        );
+
+        let section_bytes = heightmap_section_len(self.chunk_layout) as u64 * 4;
+        for job in &jobs {
+            encoder.clear_buffer(
+                &self.heightmap_buffer,
+                job.slot as u64 * section_bytes,
+                Some(section_bytes),
+            );
+        }
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Column Heightmap Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: world_buffer.voxel_buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.job_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.heightmap_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let workgroups_x = self
+            .chunk_layout
+            .voxels_per_chunk
+            .div_ceil(HEIGHTMAP_WORKGROUP_SIZE);
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Column Heightmap Tops"),
+                timestamp_writes: None,
+            });
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.set_pipeline(&self.tops_pipeline);
+            pass.dispatch_workgroups(workgroups_x, job_count, 1);
+        }
+
+        let (readback, allocation) = create_tracked_buffer(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Heightmap Readback Buffer"),
+                size: job_count as u64 * section_bytes,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
+        for (i, job) in jobs.iter().enumerate() {
+            encoder.copy_buffer_to_buffer(
+                &self.heightmap_buffer,
+                job.slot as u64 * section_bytes,
+                &readback,
+                i as u64 * section_bytes,
+                section_bytes,
+            );
+        }
+
+        self.in_flight.lock().push(HeightmapReadback {
+            positions,
+            buffer: readback,
+            allocation,
+            receiver: None,
+        });
+
+        job_count as usize
+    }
+
+    /// Collect finished sections without blocking; pass each to
+    /// `world_operations::apply_chunk_column_tops`.
+    ///
+    /// Must be called after the encoder passed to `encode_batch` was submitted.
+    pub fn poll_results(&self) -> Vec<ChunkHeightmapResult> {
+        let mut in_flight = self.in_flight.lock();
+        if in_flight.is_empty() {
+            return Vec::new();
+        }
+
+        for readback in in_flight.iter_mut() {
+            if readback.receiver.is_none() {
+                let (sender, receiver) = channel();
+                readback
+                    .buffer
+                    .slice(..)
+                    .map_async(wgpu::MapMode::Read, move |result| {
+                        let _ = sender.send(result);
+                    });
+                readback.receiver = Some(receiver);
+            }
+        }
+
+        self.device.poll(wgpu::Maintain::Poll);
+
+        let section_len = heightmap_section_len(self.chunk_layout) as usize;
+        let mut results = Vec::new();
+        in_flight.retain(|readback| {
+            let Some(receiver) = readback.receiver.as_ref() else {
+                return true;
+            };
+            match receiver.try_recv() {
+                Ok(Ok(())) => {
+                    {
+                        let mapped = readback.buffer.slice(..).get_mapped_range();
+                        let words: &[u32] = bytemuck::cast_slice(&mapped);
+                        for (section, pos) in
+                            words.chunks_exact(section_len).zip(&readback.positions)
+                        {
+                            results.push((*pos, section.to_vec()));
+                        }
+                    }
+                    readback.buffer.unmap();
+                    false
+                }
+                Ok(Err(e)) => {
+                    // The CPU mirror keeps its previous heights; queue the
+                    // chunks again to retry
+                    log::warn!("[HEIGHTMAP] Readback mapping failed: {:?}", e);
+                    false
+                }
+                Err(TryRecvError::Empty) => true,
+                Err(TryRecvError::Disconnected) => false,
+            }
+        });
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::core::VoxelPos;
+    use crate::world::data_types::WorldData;
+    use crate::world::world_operations::{
+        compute_chunk_column_tops, fill_region, get_surface_height, load_chunk,
+        rebuild_chunk_heightmap, set_block,
+    };
+
+    #[test]
+    fn test_cpu_tops_match_world_mirror() {
+        let layout = ChunkLayout::default();
+        let cs = layout.size;
+        let mut world = WorldData::with_chunk_layout(0, 4, 4, 4, layout);
+        let chunk_pos = ChunkPos::new(0, 0, 0);
+        load_chunk(&mut world, chunk_pos, cs).expect("load");
+
+        // Empty columns have no surface
+        assert_eq!(get_surface_height(&world, 3, 4), None);
+
+        // Edits raise and lower the surface incrementally
+        set_block(&mut world, VoxelPos::new(3, 5, 4), BlockId::STONE, cs).expect("set");
+        set_block(&mut world, VoxelPos::new(3, 9, 4), BlockId::STONE, cs).expect("set");
+        assert_eq!(get_surface_height(&world, 3, 4), Some(9));
+        set_block(&mut world, VoxelPos::new(3, 9, 4), BlockId::AIR, cs).expect("set");
+        assert_eq!(get_surface_height(&world, 3, 4), Some(5));
+
+        // Sections stack: a block in the chunk above wins
+        load_chunk(&mut world, ChunkPos::new(0, 1, 0), cs).expect("load");
+        set_block(
+            &mut world,
+            VoxelPos::new(3, cs as i32 + 2, 4),
+            BlockId::DIRT,
+            cs,
+        )
+        .expect("set");
+        assert_eq!(get_surface_height(&world, 3, 4), Some(cs as i32 + 2));
+
+        fill_region(
+            &mut world,
+            VoxelPos::new(0, 0, 0),
+            VoxelPos::new(1, 7, 1),
+            BlockId::STONE,
+            cs,
+        );
+        assert_eq!(get_surface_height(&world, 1, 1), Some(7));
+
+        // The GPU reference agrees with the mirror
+        let chunk = world
+            .chunks
+            .iter()
+            .find(|c| c.position == chunk_pos)
+            .expect("chunk");
+        let voxels: Vec<VoxelData> = chunk
+            .blocks
+            .iter()
+            .map(|block| VoxelData::new(block.0, 0, 0, 0))
+            .collect();
+        let tops = compute_column_tops_cpu(&voxels, cs);
+        assert_eq!(tops, compute_chunk_column_tops(chunk, cs));
+        assert_eq!(tops[(3 + 4 * cs) as usize], 6);
+
+        // Blocks written behind the heightmap's back are picked up by a rebuild
+        for y in 0..8 {
+            world.chunks[0].blocks[(y * cs) as usize] = BlockId::AIR;
+        }
+        assert_eq!(get_surface_height(&world, 0, 0), Some(7));
+        rebuild_chunk_heightmap(&mut world, chunk_pos, cs).expect("rebuild");
+        assert_eq!(get_surface_height(&world, 0, 0), None);
+    }
+}
@@ -1,3 +1,6 @@
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
 use crate::world::core::{BlockId, ChunkPos, VoxelPos};
 use crate::world::storage::WorldBuffer;
 use bytemuck::{Pod, Zeroable};
@@ -6,7 +9,6 @@ use bytemuck::{Pod, Zeroable};
 /// This module provides high-performance block queries that run entirely on GPU,
 /// eliminating the CPU bottleneck of transferring data for every block access.
 use std::sync::Arc;
-use wgpu::util::DeviceExt;
 
 // Import constants properly
 use crate::constants::*;
@@ -54,6 +56,10 @@ pub struct GpuBlockQuery {
 
     /// Maximum queries per batch
     max_batch_size: u32,
+
+    /// Both staging buffers, tracked under `block_query`
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
 }
 
 impl GpuBlockQuery {
@@ -102,21 +108,30 @@ impl GpuBlockQuery {
         const MAX_BATCH_SIZE: u32 = 65536; // 64K queries per batch
 
         // Create staging buffers
-        let query_staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Query Staging Buffer"),
-            size: (std::mem::size_of::<BlockQueryRequest>() * MAX_BATCH_SIZE as usize) as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let gpu_owner = register_global_gpu_owner("block_query");
+        let (query_staging_buffer, query_staging_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Query Staging Buffer"),
+                size: (std::mem::size_of::<BlockQueryRequest>() * MAX_BATCH_SIZE as usize) as u64,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
 
-        let result_staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Result Staging Buffer"),
-            size: (std::mem::size_of::<BlockQueryResult>() * MAX_BATCH_SIZE as usize) as u64,
-            usage: wgpu::BufferUsages::STORAGE
-                | wgpu::BufferUsages::COPY_SRC
-                | wgpu::BufferUsages::MAP_READ,
-            mapped_at_creation: false,
-        });
+        let (result_staging_buffer, result_staging_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Result Staging Buffer"),
+                size: (std::mem::size_of::<BlockQueryResult>() * MAX_BATCH_SIZE as usize) as u64,
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_SRC
+                    | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            },
+        );
 
         Self {
             device,
@@ -126,6 +141,8 @@ impl GpuBlockQuery {
             query_staging_buffer,
             result_staging_buffer,
             max_batch_size: MAX_BATCH_SIZE,
+            gpu_allocations: vec![query_staging_allocation, result_staging_allocation],
+            gpu_owner,
         }
     }
 
@@ -174,7 +191,7 @@ impl GpuBlockQuery {
             compute_pass.set_bind_group(0, &bind_group, &[]);
             compute_pass.set_push_constants(
                 0,
-                bytemuck::cast_slice(&[query_count as u32, crate::constants::core::CHUNK_SIZE]),
+                bytemuck::cast_slice(&[query_count as u32, world_buffer.chunk_layout().size]),
             );
 
             // One workgroup per MAX_WORKGROUP_SIZE queries
@@ -185,12 +202,16 @@ impl GpuBlockQuery {
 
         // Copy results to mappable buffer
         let result_size = (std::mem::size_of::<BlockQueryResult>() * query_count) as u64;
-        let download_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Query Download Buffer"),
-            size: result_size,
-            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-            mapped_at_creation: false,
-        });
+        let (download_buffer, _download_allocation) = create_tracked_buffer(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Query Download Buffer"),
+                size: result_size,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            },
+        );
 
         encoder.copy_buffer_to_buffer(
             &self.result_staging_buffer,
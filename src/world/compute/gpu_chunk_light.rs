@@ -0,0 +1,269 @@
+//! Block and sky light of resident world chunks
+//!
+//! Runs chunk_light_propagation.wgsl over the full slots of a `WorldBuffer`,
+//! writing the dedicated lighting buffer or, in packed mode, the light bits
+//! of the voxels. Commands are only recorded; the caller submits them.
+
+use crate::constants::lighting::{
+    CHUNK_LIGHT_BLOCK_TABLE_SIZE, CHUNK_LIGHT_WORKGROUP_SIZE, MAX_LIGHT_LEVEL,
+};
+use crate::memory::{
+    create_tracked_buffer, create_tracked_buffer_init, register_global_gpu_owner,
+    GpuAllocationGuard, GpuOwnerGuard,
+};
+use crate::world::core::{BlockId, ChunkPos};
+use crate::world::lighting::{block_light_emission, blocks_skylight};
+use crate::world::storage::{LightingStorageMode, WorldBuffer};
+use bytemuck::{Pod, Zeroable};
+use std::sync::Arc;
+
+/// Block table bit marking an opaque block
+const CELL_OPAQUE: u32 = 16;
+
+/// `ChunkLightParams::light_mode` of packed voxel light
+const LIGHT_MODE_PACKED: u32 = 1;
+
+/// One chunk to light (`ChunkLightJob` in chunk_light_propagation.wgsl)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct ChunkLightJobGpu {
+    slot: u32,
+    sky_open: u32,
+    _padding: [u32; 2],
+}
+
+/// Emission and opacity of every block id the kernel knows
+pub fn chunk_light_block_table() -> Vec<u32> {
+    (0..CHUNK_LIGHT_BLOCK_TABLE_SIZE)
+        .map(|id| {
+            let block = BlockId(id as u16);
+            let emission = u32::from(block_light_emission(block).min(MAX_LIGHT_LEVEL));
+            if blocks_skylight(block) {
+                emission | CELL_OPAQUE
+            } else {
+                emission
+            }
+        })
+        .collect()
+}
+
+/// Whether skylight enters a chunk through its top face: the chunk above is
+/// not loaded or is a uniform chunk of a block that lets skylight through
+pub fn chunk_sky_open(world_buffer: &WorldBuffer, chunk_pos: ChunkPos) -> bool {
+    let above = ChunkPos::new(chunk_pos.x, chunk_pos.y + 1, chunk_pos.z);
+    match world_buffer.sparse_chunk(above) {
+        Some(sparse) => !blocks_skylight(BlockId(sparse.voxel.block_id())),
+        None => {
+            world_buffer.existing_chunk_slot(above).is_none()
+                && !world_buffer.is_chunk_palettized(above)
+        }
+    }
+}
+
+pub struct GpuChunkLight {
+    device: Arc<wgpu::Device>,
+    clear_pipeline: wgpu::ComputePipeline,
+    propagate_pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    block_table: wgpu::Buffer,
+    /// Bound as the lighting buffer in packed mode
+    placeholder_light: wgpu::Buffer,
+
+    /// `block_table` and `placeholder_light`, tracked under `chunk_light`
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
+}
+
+impl GpuChunkLight {
+    pub fn new(device: Arc<wgpu::Device>) -> Result<Self, String> {
+        let shader = crate::gpu::automation::create_gpu_shader(
+            &device,
+            "chunk_light_propagation",
+            include_str!("../../shaders/compute/chunk_light_propagation.wgsl"),
+        )
+        .map_err(|e| format!("Failed to create chunk light shader: {}", e))?;
+
+        let buffer_entry = |binding: u32, ty: wgpu::BufferBindingType| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Chunk Light Bind Group Layout"),
+            entries: &[
+                buffer_entry(0, wgpu::BufferBindingType::Uniform),
+                buffer_entry(1, wgpu::BufferBindingType::Storage { read_only: true }),
+                buffer_entry(2, wgpu::BufferBindingType::Storage { read_only: true }),
+                buffer_entry(3, wgpu::BufferBindingType::Storage { read_only: false }),
+                buffer_entry(4, wgpu::BufferBindingType::Storage { read_only: false }),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Chunk Light Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |label, entry_point| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                module: &shader.module,
+                entry_point,
+            })
+        };
+        let clear_pipeline = pipeline("Chunk Light Clear Pipeline", "clear_chunk_light");
+        let propagate_pipeline =
+            pipeline("Chunk Light Propagation Pipeline", "propagate_chunk_light");
+
+        let gpu_owner = register_global_gpu_owner("chunk_light");
+        let (block_table, block_table_allocation) = create_tracked_buffer_init(
+            &device,
+            &gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Chunk Light Block Table"),
+                contents: bytemuck::cast_slice(&chunk_light_block_table()),
+                usage: wgpu::BufferUsages::STORAGE,
+            },
+        );
+        let (placeholder_light, placeholder_light_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Chunk Light Placeholder"),
+                size: 4,
+                usage: wgpu::BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            },
+        );
+
+        Ok(Self {
+            device,
+            clear_pipeline,
+            propagate_pipeline,
+            bind_group_layout,
+            block_table,
+            placeholder_light,
+            gpu_allocations: vec![block_table_allocation, placeholder_light_allocation],
+            gpu_owner,
+        })
+    }
+
+    /// Record the relighting of `chunks` into `encoder`. Chunks without a
+    /// full slot (sparse, palettized or not loaded) are skipped. Returns the
+    /// chunks lit.
+    pub fn encode_chunk_light(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        world_buffer: &WorldBuffer,
+        chunks: &[ChunkPos],
+    ) -> Vec<ChunkPos> {
+        let mut lit = Vec::new();
+        let jobs: Vec<ChunkLightJobGpu> = chunks
+            .iter()
+            .filter_map(|chunk_pos| {
+                let slot = world_buffer.existing_chunk_slot(*chunk_pos)?;
+                lit.push(*chunk_pos);
+                Some(ChunkLightJobGpu {
+                    slot,
+                    sky_open: u32::from(chunk_sky_open(world_buffer, *chunk_pos)),
+                    _padding: [0; 2],
+                })
+            })
+            .collect();
+        if jobs.is_empty() {
+            return lit;
+        }
+
+        let (light_mode, light_buffer) =
+            match (world_buffer.lighting_mode(), world_buffer.light_buffer()) {
+                (LightingStorageMode::Dedicated, Some(buffer)) => (0, buffer),
+                _ => (LIGHT_MODE_PACKED, &self.placeholder_light),
+            };
+        let params = [jobs.len() as u32, light_mode, 0, 0];
+        let (params_buffer, _params_allocation) = create_tracked_buffer_init(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Chunk Light Params"),
+                contents: bytemuck::cast_slice(&params),
+                usage: wgpu::BufferUsages::UNIFORM,
+            },
+        );
+        let (job_buffer, _job_allocation) = create_tracked_buffer_init(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Chunk Light Jobs"),
+                contents: bytemuck::cast_slice(&jobs),
+                usage: wgpu::BufferUsages::STORAGE,
+            },
+        );
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Chunk Light Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: job_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.block_table.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: world_buffer.voxel_buffer().as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: light_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        // Light spreads one voxel per step; the longest path is full
+        // skylight falling through the chunk, then fading out sideways
+        let size = world_buffer.chunk_layout().size;
+        let steps = size + u32::from(MAX_LIGHT_LEVEL) + 1;
+        let groups = size.div_ceil(CHUNK_LIGHT_WORKGROUP_SIZE);
+        let depth_groups = (size * jobs.len() as u32).div_ceil(CHUNK_LIGHT_WORKGROUP_SIZE);
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Chunk Light Pass"),
+            timestamp_writes: None,
+        });
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.set_pipeline(&self.clear_pipeline);
+        pass.dispatch_workgroups(groups, groups, depth_groups);
+        pass.set_pipeline(&self.propagate_pipeline);
+        for _ in 0..steps {
+            pass.dispatch_workgroups(groups, groups, depth_groups);
+        }
+        lit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_table_marks_emitters_and_opaque_blocks() {
+        let table = chunk_light_block_table();
+        assert_eq!(table.len(), CHUNK_LIGHT_BLOCK_TABLE_SIZE);
+        assert_eq!(table[BlockId::AIR.0 as usize], 0);
+        assert_eq!(table[BlockId::STONE.0 as usize], CELL_OPAQUE);
+        assert_eq!(
+            table[BlockId::GLOWSTONE.0 as usize],
+            u32::from(MAX_LIGHT_LEVEL) | CELL_OPAQUE
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! GPU side of the lighting harness
+//!
+//! Lights a `LightFixture` with light_fixture.wgsl and reads the result
+//! back, for `run_light_harness` to diff against the CPU reference. Each
+//! run blocks until the readback is mapped; it is meant for tests and
+//! tools, not the frame loop.
+
+use crate::constants::lighting::{LIGHT_FIXTURE_WORKGROUP_SIZE, MAX_LIGHT_LEVEL};
+use crate::memory::{
+    create_tracked_buffer, create_tracked_buffer_init, register_global_gpu_owner, GpuOwnerGuard,
+};
+use crate::world::lighting::{LightField, LightFixture};
+use std::sync::Arc;
+
+/// `cells` bit marking an opaque voxel
+const CELL_OPAQUE: u32 = 16;
+
+pub struct GpuLightFixtureRunner {
+    device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
+    pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+
+    /// Owner of the per-fixture buffers in the GPU allocation tracker
+    gpu_owner: GpuOwnerGuard,
+}
+
+impl GpuLightFixtureRunner {
+    pub fn new(device: Arc<wgpu::Device>, queue: Arc<wgpu::Queue>) -> Result<Self, String> {
+        let shader = crate::gpu::automation::create_gpu_shader(
+            &device,
+            "light_fixture",
+            include_str!("../../shaders/compute/light_fixture.wgsl"),
+        )
+        .map_err(|e| format!("Failed to create light fixture shader: {}", e))?;
+
+        let buffer_entry = |binding: u32, ty: wgpu::BufferBindingType| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Light Fixture Bind Group Layout"),
+            entries: &[
+                buffer_entry(0, wgpu::BufferBindingType::Uniform),
+                buffer_entry(1, wgpu::BufferBindingType::Storage { read_only: true }),
+                buffer_entry(2, wgpu::BufferBindingType::Storage { read_only: false }),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Light Fixture Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Light Fixture Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader.module,
+            entry_point: "propagate_fixture_light",
+        });
+
+        Ok(Self {
+            device,
+            queue,
+            pipeline,
+            bind_group_layout,
+            gpu_owner: register_global_gpu_owner("light_fixture"),
+        })
+    }
+
+    /// Light a fixture on the GPU and wait for the result
+    pub fn light_fixture(&self, fixture: &LightFixture) -> Result<LightField, String> {
+        let voxels = fixture.opaque.len();
+        let size = fixture.size;
+        if voxels == 0 || voxels != (size[0] * size[1] * size[2]) as usize {
+            return Err(format!(
+                "fixture {} has no voxels or the wrong count",
+                fixture.name
+            ));
+        }
+
+        let cells: Vec<u32> = fixture
+            .opaque
+            .iter()
+            .zip(&fixture.emission)
+            .map(|(&opaque, &emission)| {
+                u32::from(emission.min(MAX_LIGHT_LEVEL)) | if opaque { CELL_OPAQUE } else { 0 }
+            })
+            .collect();
+        let params = [size[0], size[1], size[2], u32::from(fixture.sky_open)];
+        let light_bytes = voxels as u64 * 4;
+
+        let (params_buffer, _params_allocation) = create_tracked_buffer_init(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Light Fixture Params"),
+                contents: bytemuck::cast_slice(&params),
+                usage: wgpu::BufferUsages::UNIFORM,
+            },
+        );
+        let (cell_buffer, _cell_allocation) = create_tracked_buffer_init(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Light Fixture Cells"),
+                contents: bytemuck::cast_slice(&cells),
+                usage: wgpu::BufferUsages::STORAGE,
+            },
+        );
+        let (light_buffer, _light_allocation) = create_tracked_buffer(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Light Fixture Light"),
+                size: light_bytes,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            },
+        );
+        let (readback, _readback_allocation) = create_tracked_buffer(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Light Fixture Readback"),
+                size: light_bytes,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Light Fixture Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: cell_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: light_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        // Light spreads one voxel per step; the longest path is full
+        // skylight falling the whole height, then fading out sideways
+        let steps = size[1] + u32::from(MAX_LIGHT_LEVEL) + 1;
+        let workgroups = size.map(|axis| axis.div_ceil(LIGHT_FIXTURE_WORKGROUP_SIZE));
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Light Fixture Encoder"),
+            });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Light Fixture Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            for _ in 0..steps {
+                pass.dispatch_workgroups(workgroups[0], workgroups[1], workgroups[2]);
+            }
+        }
+        encoder.copy_buffer_to_buffer(&light_buffer, 0, &readback, 0, light_bytes);
+        self.queue.submit(Some(encoder.finish()));
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        readback
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|e| format!("Light fixture readback dropped: {}", e))?
+            .map_err(|e| format!("Light fixture readback failed: {}", e))?;
+
+        let field = {
+            let mapped = readback.slice(..).get_mapped_range();
+            let packed: &[u32] = bytemuck::cast_slice(&mapped);
+            LightField {
+                size,
+                block: packed.iter().map(|&light| (light & 0xFF) as u8).collect(),
+                sky: packed
+                    .iter()
+                    .map(|&light| ((light >> 8) & 0xFF) as u8)
+                    .collect(),
+            }
+        };
+        readback.unmap();
+        Ok(field)
+    }
+}
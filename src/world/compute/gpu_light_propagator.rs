@@ -1,11 +1,15 @@
 use crate::{
+    constants::core::CHUNK_SIZE,
+    constants::lighting::{LIGHT_DIRTY_HALO, LIGHT_DIRTY_SMOOTH_PASSES},
     memory::BandwidthProfiler,
-    world::compute::GpuLighting,
+    world::compute::{GpuChunkLight, GpuLighting},
     world::core::{BlockId, ChunkPos, VoxelPos},
-    world::lighting::{BlockProvider, LightUpdate, LightingStats},
+    world::lighting::{
+        create_light_dirty, record_light_edit, take_light_dirty_regions, BlockProvider,
+        LightDirtyData, LightDirtyStats, LightUpdate, LightingStats,
+    },
     world::storage::WorldBuffer,
 };
-use std::collections::VecDeque;
 /// GPU Lighting Propagation
 ///
 /// Provides GPU-accelerated light propagation that replaces the CPU version.
@@ -17,10 +21,13 @@ pub struct GpuLightPropagator {
     device: Arc<Device>,
     queue: Arc<Queue>,
     gpu_lighting: Arc<GpuLighting>,
+    /// Block and sky light of the dirty chunks, written to the world
+    /// buffer's lighting storage; None when its shader failed to build
+    chunk_light: Option<GpuChunkLight>,
     world_buffer: Arc<std::sync::Mutex<WorldBuffer>>,
 
-    /// Pending light updates to be processed
-    pending_updates: Arc<parking_lot::Mutex<VecDeque<LightUpdate>>>,
+    /// Dirty sub-volumes of the pending light updates
+    dirty: Arc<parking_lot::Mutex<LightDirtyData>>,
 
     /// Statistics tracking
     stats: Arc<parking_lot::RwLock<LightingStats>>,
@@ -37,13 +44,28 @@ impl GpuLightPropagator {
         world_buffer: Arc<std::sync::Mutex<WorldBuffer>>,
     ) -> Self {
         let gpu_lighting = Arc::new(GpuLighting::new(device.clone()));
+        let chunk_light = match GpuChunkLight::new(device.clone()) {
+            Ok(chunk_light) => Some(chunk_light),
+            Err(e) => {
+                log::error!("[GpuLightPropagator] {}; light levels stay unchanged", e);
+                None
+            }
+        };
+        let chunk_size = world_buffer
+            .lock()
+            .map(|buffer| buffer.chunk_layout().size)
+            .unwrap_or(CHUNK_SIZE);
 
         Self {
             device,
             queue,
             gpu_lighting,
+            chunk_light,
             world_buffer,
-            pending_updates: Arc::new(parking_lot::Mutex::new(VecDeque::new())),
+            dirty: Arc::new(parking_lot::Mutex::new(create_light_dirty(
+                chunk_size,
+                LIGHT_DIRTY_HALO,
+            ))),
             stats: Arc::new(parking_lot::RwLock::new(LightingStats::default())),
             profiler: None,
         }
@@ -55,33 +77,30 @@ impl GpuLightPropagator {
         self
     }
 
-    /// Add a light update to the queue
+    /// Add a light update to the queue; only the sub-volume around it is
+    /// relit by the next `process_updates`
     pub fn add_update(&self, update: LightUpdate) {
-        self.pending_updates.lock().push_back(update);
+        record_light_edit(&mut self.dirty.lock(), update.pos);
     }
 
-    /// Process all pending light updates on the GPU
+    /// Relight the dirty sub-volumes of all pending light updates on the GPU
     pub fn process_updates(&self) -> anyhow::Result<()> {
-        let updates = {
-            let mut pending = self.pending_updates.lock();
-            std::mem::take(&mut *pending)
+        let (regions, dirty_chunks) = {
+            let mut dirty = self.dirty.lock();
+            let dirty_chunks: Vec<ChunkPos> = dirty.chunks.keys().copied().collect();
+            (take_light_dirty_regions(&mut dirty), dirty_chunks)
         };
+        let chunks_affected = dirty_chunks.len();
 
-        if updates.is_empty() {
+        if regions.is_empty() {
             return Ok(());
         }
 
-        // Group updates by chunk
-        let chunk_size = crate::constants::core::CHUNK_SIZE as i32;
-        let mut chunks_to_update = std::collections::HashSet::new();
-        for update in updates {
-            let chunk_pos = ChunkPos {
-                x: update.pos.x / chunk_size,
-                y: update.pos.y / chunk_size,
-                z: update.pos.z / chunk_size,
-            };
-            chunks_to_update.insert(chunk_pos);
-        }
+        // Get world buffer
+        let world_buffer = self
+            .world_buffer
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to lock world buffer: {}", e))?;
 
         // Create command encoder
         let mut encoder = self
@@ -90,23 +109,23 @@ impl GpuLightPropagator {
                 label: Some("Light Propagation Encoder"),
             });
 
-        // Get world buffer
-        let world_buffer = self
-            .world_buffer
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Failed to lock world buffer: {}", e))?;
-
-        // Update lighting for affected chunks
-        let chunk_positions: Vec<ChunkPos> = chunks_to_update.into_iter().collect();
-        self.gpu_lighting
-            .batch_update_lighting(&mut encoder, &world_buffer, &chunk_positions);
+        // Update lighting for the dirty sub-volumes only
+        self.gpu_lighting.relight_regions(
+            &mut encoder,
+            &world_buffer,
+            &regions,
+            LIGHT_DIRTY_SMOOTH_PASSES,
+        );
+        if let Some(chunk_light) = &self.chunk_light {
+            chunk_light.encode_chunk_light(&mut encoder, &world_buffer, &dirty_chunks);
+        }
 
         // Submit commands
         self.queue.submit(std::iter::once(encoder.finish()));
 
         // Update stats
         let mut stats = self.stats.write();
-        stats.chunks_affected += chunk_positions.len();
+        stats.chunks_affected += chunks_affected;
         stats.updates_processed += 1;
 
         Ok(())
@@ -127,6 +146,9 @@ impl GpuLightPropagator {
 
         self.gpu_lighting
             .update_chunk_lighting(&mut encoder, &world_buffer, chunk_pos);
+        if let Some(chunk_light) = &self.chunk_light {
+            chunk_light.encode_chunk_light(&mut encoder, &world_buffer, &[chunk_pos]);
+        }
 
         self.queue.submit(std::iter::once(encoder.finish()));
 
@@ -138,6 +160,11 @@ impl GpuLightPropagator {
         self.stats.read().clone()
     }
 
+    /// Voxels relit incrementally versus whole-chunk relights
+    pub fn get_dirty_stats(&self) -> LightDirtyStats {
+        self.dirty.lock().stats
+    }
+
     /// Reset statistics
     pub fn reset_stats(&self) {
         *self.stats.write() = LightingStats::default();
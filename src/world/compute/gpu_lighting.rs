@@ -1,7 +1,21 @@
+use crate::constants::lighting::LIGHT_REGION_WORKGROUP_SIZE;
+use crate::memory::{create_tracked_buffer_init, register_global_gpu_owner, GpuOwnerGuard};
 use crate::world::core::ChunkPos;
+use crate::world::lighting::{light_box_voxels, split_light_box, LightDirtyBox};
 use crate::world::storage::WorldBuffer;
+use bytemuck::{Pod, Zeroable};
 use std::sync::Arc;
-use wgpu::util::DeviceExt;
+
+/// Dirty region as read by the region kernels (`LightRegion` in
+/// ambient_occlusion.wgsl)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct LightRegionGpu {
+    /// xyz = world min corner
+    pub min: [i32; 4],
+    /// xyz = extent, w = voxels in all earlier regions of the dispatch
+    pub size: [u32; 4],
+}
 
 /// GPU-based ambient occlusion and lighting system
 pub struct GpuLighting {
@@ -15,6 +29,17 @@ pub struct GpuLighting {
 
     /// Bind group layout for lighting operations
     bind_group_layout: wgpu::BindGroupLayout,
+
+    /// Pipelines relighting dirty sub-volumes only
+    region_ao_pipeline: wgpu::ComputePipeline,
+    region_smooth_pipeline: wgpu::ComputePipeline,
+
+    /// World voxels (group 0) and region list (group 1) of the region kernels
+    region_voxel_layout: wgpu::BindGroupLayout,
+    region_list_layout: wgpu::BindGroupLayout,
+
+    /// Owner of the per-dispatch buffers in the GPU allocation tracker
+    gpu_owner: GpuOwnerGuard,
 }
 
 impl GpuLighting {
@@ -84,11 +109,57 @@ impl GpuLighting {
             entry_point: "smooth_ao",
         });
 
+        let storage_entry = |read_only| wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let region_voxel_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Light Region Voxel Layout"),
+                entries: &[storage_entry(false)],
+            });
+        let region_list_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Light Region List Layout"),
+            entries: &[storage_entry(true)],
+        });
+        let region_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Light Region Pipeline Layout"),
+                bind_group_layouts: &[&region_voxel_layout, &region_list_layout],
+                push_constant_ranges: &[],
+            });
+
+        let region_ao_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Region AO Calculation Pipeline"),
+            layout: Some(&region_pipeline_layout),
+            module: &validated_shader.module,
+            entry_point: "calculate_ao_region",
+        });
+
+        let region_smooth_pipeline =
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("Region AO Smoothing Pipeline"),
+                layout: Some(&region_pipeline_layout),
+                module: &validated_shader.module,
+                entry_point: "smooth_ao_region",
+            });
+
         Self {
             device,
             ao_pipeline,
             smooth_pipeline,
             bind_group_layout,
+            region_ao_pipeline,
+            region_smooth_pipeline,
+            region_voxel_layout,
+            region_list_layout,
+            gpu_owner: register_global_gpu_owner("lighting"),
         }
     }
 
@@ -110,13 +181,15 @@ impl GpuLighting {
             .map(|pos| [pos.x, pos.y, pos.z, 0])
             .collect();
 
-        let positions_buffer = self
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        let (positions_buffer, _positions_allocation) = create_tracked_buffer_init(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
                 label: Some("AO Chunk Positions Buffer"),
                 contents: bytemuck::cast_slice(&positions_data),
                 usage: wgpu::BufferUsages::STORAGE,
-            });
+            },
+        );
 
         // Create bind group
         let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -246,6 +319,96 @@ impl GpuLighting {
             );
         }
     }
+
+    /// Relight only the given dirty regions (see `take_light_dirty_regions`),
+    /// one thread per voxel. Regions are batched so no dispatch exceeds the
+    /// workgroup limit. Returns the voxels dispatched.
+    pub fn relight_regions(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        world_buffer: &WorldBuffer,
+        regions: &[LightDirtyBox],
+        smooth_passes: u32,
+    ) -> u64 {
+        let max_voxels = u64::from(self.device.limits().max_compute_workgroups_per_dimension)
+            * u64::from(LIGHT_REGION_WORKGROUP_SIZE);
+        let voxel_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Light Region Voxel Bind Group"),
+            layout: &self.region_voxel_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: world_buffer.voxel_buffer().as_entire_binding(),
+            }],
+        });
+
+        // Pack regions into batches of at most `max_voxels`
+        let mut batches: Vec<Vec<LightRegionGpu>> = Vec::new();
+        let mut batch_voxels = 0u64;
+        let mut total = 0u64;
+        for slab in regions
+            .iter()
+            .flat_map(|region| split_light_box(region, max_voxels))
+        {
+            let voxels = light_box_voxels(&slab);
+            if voxels == 0 {
+                continue;
+            }
+            if batches.is_empty() || batch_voxels + voxels > max_voxels {
+                batches.push(Vec::new());
+                batch_voxels = 0;
+            }
+            let size = [0, 1, 2].map(|axis| (slab.max[axis] - slab.min[axis]) as u32);
+            if let Some(batch) = batches.last_mut() {
+                batch.push(LightRegionGpu {
+                    min: [slab.min[0], slab.min[1], slab.min[2], 0],
+                    size: [size[0], size[1], size[2], batch_voxels as u32],
+                });
+            }
+            batch_voxels += voxels;
+            total += voxels;
+        }
+
+        for batch in &batches {
+            let voxels: u64 = batch
+                .iter()
+                .map(|region| {
+                    u64::from(region.size[0]) * u64::from(region.size[1]) * u64::from(region.size[2])
+                })
+                .sum();
+            let workgroups = voxels.div_ceil(u64::from(LIGHT_REGION_WORKGROUP_SIZE)) as u32;
+            let (region_buffer, _region_allocation) = create_tracked_buffer_init(
+                &self.device,
+                &self.gpu_owner,
+                &wgpu::util::BufferInitDescriptor {
+                    label: Some("Light Region Buffer"),
+                    contents: bytemuck::cast_slice(batch),
+                    usage: wgpu::BufferUsages::STORAGE,
+                },
+            );
+            let region_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Light Region List Bind Group"),
+                layout: &self.region_list_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: region_buffer.as_entire_binding(),
+                }],
+            });
+
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Region AO Pass"),
+                timestamp_writes: None,
+            });
+            compute_pass.set_bind_group(0, &voxel_bind_group, &[]);
+            compute_pass.set_bind_group(1, &region_bind_group, &[]);
+            compute_pass.set_pipeline(&self.region_ao_pipeline);
+            compute_pass.dispatch_workgroups(workgroups, 1, 1);
+            compute_pass.set_pipeline(&self.region_smooth_pipeline);
+            for _ in 0..smooth_passes {
+                compute_pass.dispatch_workgroups(workgroups, 1, 1);
+            }
+        }
+        total
+    }
 }
 
 /// Extract AO value from voxel metadata
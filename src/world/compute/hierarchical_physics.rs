@@ -1,5 +1,8 @@
 use super::{SparseVoxelOctree, VoxelBvh};
 use crate::error::EngineError;
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
 use crate::memory::MemoryManager;
 use crate::world::error::WorldGpuResult;
 use crate::world::storage::WorldBuffer;
@@ -88,6 +91,10 @@ pub struct HierarchicalPhysics {
 
     /// Bind group layout
     bind_group_layout: wgpu::BindGroupLayout,
+
+    /// `query_buffer` and `result_buffer` as tracked allocations
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
 }
 
 impl HierarchicalPhysics {
@@ -206,19 +213,28 @@ impl HierarchicalPhysics {
         });
 
         // Allocate buffers
-        let query_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Physics Query Buffer"),
-            size: max_queries as u64 * std::mem::size_of::<PhysicsQuery>() as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let gpu_owner = register_global_gpu_owner("hierarchical_physics");
+        let (query_buffer, query_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Physics Query Buffer"),
+                size: max_queries as u64 * std::mem::size_of::<PhysicsQuery>() as u64,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
 
-        let result_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Physics Result Buffer"),
-            size: max_queries as u64 * std::mem::size_of::<QueryResult>() as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
-            mapped_at_creation: false,
-        });
+        let (result_buffer, result_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Physics Result Buffer"),
+                size: max_queries as u64 * std::mem::size_of::<QueryResult>() as u64,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            },
+        );
 
         Self {
             device,
@@ -230,6 +246,8 @@ impl HierarchicalPhysics {
             query_capacity: max_queries,
             result_buffer,
             bind_group_layout,
+            gpu_allocations: vec![query_allocation, result_allocation],
+            gpu_owner,
         }
     }
 
@@ -348,12 +366,16 @@ impl HierarchicalPhysics {
         count: usize,
     ) -> WorldGpuResult<Vec<QueryResult>> {
         // Create staging buffer
-        let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Physics Results Staging"),
-            size: (count * std::mem::size_of::<QueryResult>()) as u64,
-            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let (staging_buffer, _staging_allocation) = create_tracked_buffer(
+            device,
+            &self.gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Physics Results Staging"),
+                size: (count * std::mem::size_of::<QueryResult>()) as u64,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
 
         // Copy results to staging
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
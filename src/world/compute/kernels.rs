@@ -1,4 +1,8 @@
-use crate::memory::{Implementation, MemoryManager, MetricType, PerformanceMetrics};
+use crate::memory::{
+    create_tracked_buffer, fill_frame_slice, register_global_gpu_owner, GpuAllocationGuard,
+    GpuOwnerGuard, Implementation, MemoryManager, MetricType, PerformanceMetrics,
+    SharedFrameArena,
+};
 use crate::world::core::ChunkPos;
 use bytemuck::{Pod, Zeroable};
 /// Unified World Kernel
@@ -86,6 +90,35 @@ pub struct WorkNode {
     pub priority: u32,
 }
 
+/// Fill the terrain, lighting and physics nodes of each active chunk
+fn write_work_nodes(work_nodes: &mut [WorkNode]) {
+    for (i, nodes) in work_nodes.chunks_exact_mut(3).enumerate() {
+        // Terrain generation node
+        nodes[0] = WorkNode {
+            work_type: 0, // Terrain
+            region_index: i as u32,
+            dependencies: 0, // No dependencies
+            priority: 10,
+        };
+
+        // Lighting node (depends on terrain)
+        nodes[1] = WorkNode {
+            work_type: 1, // Lighting
+            region_index: i as u32,
+            dependencies: 1 << 0, // Depends on terrain
+            priority: 8,
+        };
+
+        // Physics node (depends on terrain)
+        nodes[2] = WorkNode {
+            work_type: 2, // Physics
+            region_index: i as u32,
+            dependencies: 1 << 0, // Depends on terrain
+            priority: 9,
+        };
+    }
+}
+
 /// Unified world kernel system
 pub struct UnifiedWorldKernel {
     device: Arc<Device>,
@@ -104,6 +137,14 @@ pub struct UnifiedWorldKernel {
 
     /// Performance metrics
     metrics: Option<PerformanceMetrics>,
+
+    /// Per-frame arena for work graph nodes (heap `Vec` when `None`)
+    frame_arena: Option<SharedFrameArena>,
+
+    /// Records of the kernel's buffers, including those only the bind group
+    /// holds, in the GPU allocation tracker
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
 }
 
 impl UnifiedWorldKernel {
@@ -243,14 +284,22 @@ fn unified_world_update(@builtin(global_invocation_id) global_id: vec3<u32>) {
         });
 
         // Allocate buffers
-        let config_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let gpu_owner = register_global_gpu_owner("unified_kernel");
+        let mut gpu_allocations = Vec::new();
+        let mut create_buffer = |descriptor: &wgpu::BufferDescriptor| {
+            let (buffer, guard) = create_tracked_buffer(&device, &gpu_owner, descriptor);
+            gpu_allocations.push(guard);
+            buffer
+        };
+
+        let config_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Unified Kernel Config"),
             size: std::mem::size_of::<UnifiedKernelConfig>() as u64,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
 
-        let work_graph_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let work_graph_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Work Graph Buffer"),
             size: 1024 * 1024, // 1MB for work graph
             usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
@@ -258,14 +307,14 @@ fn unified_world_update(@builtin(global_invocation_id) global_id: vec3<u32>) {
         });
 
         // Placeholder buffers for octree and BVH (will be implemented)
-        let octree_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let octree_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Octree Buffer"),
             size: 16 * 1024 * 1024, // 16MB for octree
             usage: wgpu::BufferUsages::STORAGE,
             mapped_at_creation: false,
         });
 
-        let bvh_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let bvh_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("BVH Buffer"),
             size: 8 * 1024 * 1024, // 8MB for BVH
             usage: wgpu::BufferUsages::STORAGE,
@@ -273,14 +322,14 @@ fn unified_world_update(@builtin(global_invocation_id) global_id: vec3<u32>) {
         });
 
         // Placeholder for instance and modification buffers
-        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let instance_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Instance Buffer"),
             size: 4 * 1024 * 1024, // 4MB
             usage: wgpu::BufferUsages::STORAGE,
             mapped_at_creation: false,
         });
 
-        let modification_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let modification_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Modification Buffer"),
             size: 1024 * 1024, // 1MB
             usage: wgpu::BufferUsages::STORAGE,
@@ -288,14 +337,14 @@ fn unified_world_update(@builtin(global_invocation_id) global_id: vec3<u32>) {
         });
 
         // Create placeholder world buffer for now - reduced size to stay within GPU limits
-        let world_voxel_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let world_voxel_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("World Voxel Buffer"),
             size: 64 * 1024 * 1024, // 64MB placeholder (within 134MB limit)
             usage: wgpu::BufferUsages::STORAGE,
             mapped_at_creation: false,
         });
 
-        let chunk_metadata_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let chunk_metadata_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Chunk Metadata Buffer"),
             size: 16 * 1024 * 1024, // 16MB placeholder
             usage: wgpu::BufferUsages::STORAGE,
@@ -349,9 +398,18 @@ fn unified_world_update(@builtin(global_invocation_id) global_id: vec3<u32>) {
             work_graph_buffer,
             world_bind_group,
             metrics: None, // Will be set up separately
+            frame_arena: None,
+            gpu_allocations,
+            gpu_owner,
         })
     }
 
+    /// Build work graphs in a per-frame arena
+    pub fn with_frame_arena(mut self, arena: SharedFrameArena) -> Self {
+        self.frame_arena = Some(arena);
+        self
+    }
+
     /// Execute a compute pass
     pub fn execute_pass(
         &self,
@@ -400,41 +458,20 @@ fn unified_world_update(@builtin(global_invocation_id) global_id: vec3<u32>) {
 
     /// Build work graph for GPU scheduling
     pub fn build_work_graph(&self, queue: &Queue, active_chunks: &[ChunkPos]) {
-        let mut work_nodes = Vec::new();
-
-        // Create work nodes for each active chunk
-        for (i, chunk_pos) in active_chunks.iter().enumerate() {
-            // Terrain generation node
-            work_nodes.push(WorkNode {
-                work_type: 0, // Terrain
-                region_index: i as u32,
-                dependencies: 0, // No dependencies
-                priority: 10,
-            });
-
-            // Lighting node (depends on terrain)
-            work_nodes.push(WorkNode {
-                work_type: 1, // Lighting
-                region_index: i as u32,
-                dependencies: 1 << 0, // Depends on terrain
-                priority: 8,
-            });
-
-            // Physics node (depends on terrain)
-            work_nodes.push(WorkNode {
-                work_type: 2, // Physics
-                region_index: i as u32,
-                dependencies: 1 << 0, // Depends on terrain
-                priority: 9,
-            });
+        // Three nodes per active chunk
+        let node_count = active_chunks.len() * 3;
+        match &self.frame_arena {
+            Some(arena) => {
+                let mut arena = arena.lock();
+                let work_nodes = fill_frame_slice(&mut arena, node_count, write_work_nodes);
+                queue.write_buffer(&self.work_graph_buffer, 0, bytemuck::cast_slice(work_nodes));
+            }
+            None => {
+                let mut work_nodes = vec![WorkNode::zeroed(); node_count];
+                write_work_nodes(&mut work_nodes);
+                queue.write_buffer(&self.work_graph_buffer, 0, bytemuck::cast_slice(&work_nodes));
+            }
         }
-
-        // Upload work graph
-        queue.write_buffer(
-            &self.work_graph_buffer,
-            0,
-            bytemuck::cast_slice(&work_nodes),
-        );
     }
 
     /// Get performance report
@@ -4,9 +4,14 @@
 //! including unified kernels, optimization structures, and effects.
 
 pub mod bvh;
+mod chunk_connectivity;
 mod chunk_modifier;
+mod chunk_verification;
+mod column_heightmap;
 mod effects;
 mod gpu_block_query;
+mod gpu_chunk_light;
+mod gpu_light_fixture;
 mod gpu_light_propagator;
 mod gpu_lighting;
 pub mod hierarchical_physics;
@@ -38,8 +43,32 @@ pub use effects::{
 // Skylight calculation
 pub use skylight::{SkylightCalculator, MAX_SKY_LIGHT};
 
+// Chunk face connectivity for cave culling
+pub use chunk_connectivity::{
+    compute_chunk_connectivity_cpu, connectivity_propagation_passes, face_offset, face_pair_bit, faces_connected,
+    is_connectivity_passable, opposite_face, ChunkConnectivityCompute, ChunkConnectivityResult,
+    ChunkFace,
+    ALL_FACES_CONNECTED, CHUNK_FACES, CHUNK_FACE_COUNT, CONNECTIVITY_MAX_BATCH,
+    CONNECTIVITY_PASSES_PER_EDGE_VOXEL,
+};
+
+// Post-generation verification (empty chunk detection)
+pub use chunk_verification::{
+    count_non_air_voxels_cpu, expected_min_non_air_voxels, is_chunk_suspicious,
+    ChunkVerificationCompute, ChunkVerificationReport, ChunkVerificationResult,
+    MAX_REGENERATION_ATTEMPTS, VERIFICATION_MAX_BATCH, VERIFICATION_MIN_SOLID_FRACTION,
+};
+
+// Per-column surface heightmap
+pub use column_heightmap::{
+    compute_column_tops_cpu, heightmap_section_len, ChunkHeightmapResult, ColumnHeightmapCompute,
+    HEIGHTMAP_MAX_BATCH,
+};
+
 // GPU block queries
 pub use gpu_block_query::{BlockQueryHandle, BlockQueryRequest, BlockQueryResult, GpuBlockQuery};
+pub use gpu_chunk_light::{chunk_light_block_table, chunk_sky_open, GpuChunkLight};
+pub use gpu_light_fixture::GpuLightFixtureRunner;
 
 /// Unified compute backend for GPU world processing
 pub struct UnifiedCompute {
@@ -56,7 +85,10 @@ impl UnifiedCompute {
         queue: std::sync::Arc<wgpu::Queue>,
         config: UnifiedComputeConfig,
     ) -> Result<Self, ComputeError> {
-        let kernel = UnifiedWorldKernel::new(device.clone(), config.kernel_config)?;
+        let mut kernel = UnifiedWorldKernel::new(device.clone(), config.kernel_config)?;
+        if let Some(arena) = config.frame_arena {
+            kernel = kernel.with_frame_arena(arena);
+        }
         // FIXME: UnifiedMemoryManager tries to allocate 204GB for entire world (327k chunks)
         // Disabled until it's fixed to only allocate for loaded chunks
         // let memory_manager = unified_memory::UnifiedMemoryManager::new(device.clone(), 256, 256);
@@ -109,6 +141,8 @@ pub struct UnifiedComputeConfig {
     pub memory_config: MemoryConfig,
     pub enable_optimizations: bool,
     pub enable_effects: bool,
+    /// Per-frame arena for kernel work graphs (the engine's, see `Engine::frame_arena`)
+    pub frame_arena: Option<crate::memory::SharedFrameArena>,
 }
 
 impl Default for UnifiedComputeConfig {
@@ -118,6 +152,7 @@ impl Default for UnifiedComputeConfig {
             memory_config: MemoryConfig::default(),
             enable_optimizations: true,
             enable_effects: true,
+            frame_arena: None,
         }
     }
 }
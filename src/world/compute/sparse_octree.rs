@@ -1,3 +1,6 @@
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
 use crate::memory::MemoryManager;
 use crate::world::core::ChunkPos;
 use crate::world::storage::WorldBuffer;
@@ -69,6 +72,10 @@ pub struct SparseVoxelOctree {
     /// Octree configuration
     world_size: u32,
     max_depth: u32,
+
+    /// Tracker entry for `node_buffer`
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
 }
 
 impl SparseVoxelOctree {
@@ -79,12 +86,17 @@ impl SparseVoxelOctree {
 
         // Allocate GPU buffer for nodes
         let node_buffer_size = node_capacity as u64 * std::mem::size_of::<OctreeNode>() as u64;
-        let node_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Octree Node Buffer"),
-            size: node_buffer_size,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let gpu_owner = register_global_gpu_owner("sparse_octree");
+        let (node_buffer, node_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Octree Node Buffer"),
+                size: node_buffer_size,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
 
         Self {
             device,
@@ -93,6 +105,8 @@ impl SparseVoxelOctree {
             next_free_node: 1, // 0 is reserved for null
             world_size,
             max_depth,
+            gpu_allocations: vec![node_allocation],
+            gpu_owner,
         }
     }
 
@@ -8,6 +8,9 @@
 use std::sync::Arc;
 use crate::constants::core::CHUNK_SIZE;
 use crate::constants::buffer_layouts::CHUNK_METADATA_SIZE;
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
 
 /// Unified memory layout for all GPU world systems
 /// This ensures all systems can access world data efficiently without copies
@@ -114,6 +117,10 @@ pub struct UnifiedMemoryManager {
 
     /// The main unified buffer containing all world data
     pub unified_buffer: Arc<wgpu::Buffer>,
+
+    /// Record of the unified buffer in the GPU allocation tracker
+    unified_allocation: GpuAllocationGuard,
+    gpu_owner: GpuOwnerGuard,
 }
 
 impl UnifiedMemoryManager {
@@ -121,19 +128,26 @@ impl UnifiedMemoryManager {
         let layout = UnifiedMemoryLayout::new(world_size, world_height);
 
         // Create the unified buffer
-        let unified_buffer = Arc::new(device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Unified World Buffer"),
-            size: layout.total_size,
-            usage: wgpu::BufferUsages::STORAGE
-                | wgpu::BufferUsages::COPY_DST
-                | wgpu::BufferUsages::COPY_SRC,
-            mapped_at_creation: false,
-        }));
+        let gpu_owner = register_global_gpu_owner("unified_memory");
+        let (unified_buffer, unified_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Unified World Buffer"),
+                size: layout.total_size,
+                usage: wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_DST
+                    | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            },
+        );
 
         Self {
             device,
             layout,
-            unified_buffer,
+            unified_buffer: Arc::new(unified_buffer),
+            unified_allocation,
+            gpu_owner,
         }
     }
 
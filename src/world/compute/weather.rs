@@ -4,6 +4,9 @@ use std::sync::Arc;
 
 // Import constants properly
 use crate::constants::*;
+use crate::memory::{
+    create_tracked_buffer, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
 
 /// Weather data stored on GPU per chunk or region
 #[repr(C)]
@@ -110,6 +113,10 @@ pub struct WeatherGpu {
 
     /// Error recovery
     error_recovery: Arc<GpuErrorRecovery>,
+
+    /// Tracked weather and particle buffers
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
 }
 
 /// Weather GPU descriptor
@@ -131,23 +138,32 @@ impl WeatherGpu {
         // Create weather data buffer
         let weather_buffer_size =
             std::mem::size_of::<WeatherTransition>() * config.region_count as usize;
-        let weather_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Weather Data Buffer"),
-            size: weather_buffer_size as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
+        let gpu_owner = register_global_gpu_owner("weather");
+        let (weather_buffer, weather_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Weather Data Buffer"),
+                size: weather_buffer_size as u64,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
 
         // Create particle buffer
         let particle_buffer_size = std::mem::size_of::<PrecipitationParticle>()
             * config.region_count as usize
             * config.max_particles_per_region as usize;
-        let particle_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Particle Buffer"),
-            size: particle_buffer_size as u64,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::VERTEX,
-            mapped_at_creation: false,
-        });
+        let (particle_buffer, particle_allocation) = create_tracked_buffer(
+            &device,
+            &gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Particle Buffer"),
+                size: particle_buffer_size as u64,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::VERTEX,
+                mapped_at_creation: false,
+            },
+        );
 
         // Create shader through unified GPU system (Single Source of Truth)
         let shader_source = include_str!("../../shaders/compute/weather_compute.wgsl");
@@ -211,11 +227,19 @@ impl WeatherGpu {
             compute_pipeline,
             bind_group_layout,
             error_recovery,
+            gpu_allocations: vec![weather_allocation, particle_allocation],
+            gpu_owner,
         }
     }
 
-    /// Update weather simulation with error recovery
-    pub fn update(&self, frame_time: f32, delta_time: f32) -> Result<(), GpuRecoveryError> {
+    /// Update weather simulation with error recovery; `seed` varies the
+    /// shader's randomness per update (draw it from the weather stream)
+    pub fn update(
+        &self,
+        frame_time: f32,
+        delta_time: f32,
+        seed: u32,
+    ) -> Result<(), GpuRecoveryError> {
         // Create safe command encoder
         let mut safe_encoder =
             self.error_recovery
@@ -244,8 +268,8 @@ impl WeatherGpu {
         let push_constants = [
             frame_time.to_bits(),
             delta_time.to_bits(),
-            rand::random::<u32>(), // Random seed
-            0,                     // Flags
+            seed, // Random seed
+            0,    // Flags
         ];
 
         // Execute weather and particle updates with error recovery
@@ -125,6 +125,12 @@ pub struct RenderData {
 pub struct PhysicsProperties {
     pub solid: bool,
     pub density: f32,
+    /// How much grip the surface gives walking characters (ice is low)
+    pub friction: f32,
+    /// Fraction of landing speed bounced back (slime is high)
+    pub restitution: f32,
+    /// Scales walking speed on the surface (soul sand is below 1)
+    pub speed_multiplier: f32,
 }
 
 // Block trait has been removed in favor of data-oriented design
@@ -0,0 +1,221 @@
+//! Chunk coordinate math
+//!
+//! The one place world positions are split into chunk and local
+//! coordinates. Division rounds toward negative infinity, so voxel -1 is
+//! the last voxel of chunk -1 rather than a voxel of chunk 0, and world
+//! points are floored rather than truncated. Every helper takes the
+//! world's [`ChunkLayout`] instead of assuming a chunk size.
+
+use super::chunk_layout::{layout_voxel_index, ChunkLayout};
+use super::position::{ChunkPos, VoxelPos};
+
+/// `value / size` rounded toward negative infinity (`size` below 1 counts
+/// as 1)
+pub fn floor_div(value: i32, size: i32) -> i32 {
+    value.div_euclid(size.max(1))
+}
+
+/// Remainder of [`floor_div`]; always in `0..size`
+pub fn floor_mod(value: i32, size: i32) -> i32 {
+    value.rem_euclid(size.max(1))
+}
+
+/// Voxel coordinate containing a world coordinate
+pub fn world_coord_to_voxel(value: f32) -> i32 {
+    value.floor() as i32
+}
+
+/// Voxel containing a world point
+pub fn world_point_to_voxel(point: [f32; 3]) -> VoxelPos {
+    let [x, y, z] = point.map(world_coord_to_voxel);
+    VoxelPos { x, y, z }
+}
+
+/// Chunk containing a voxel
+pub fn voxel_to_chunk_pos(layout: ChunkLayout, pos: VoxelPos) -> ChunkPos {
+    let size = layout.size as i32;
+    ChunkPos {
+        x: floor_div(pos.x, size),
+        y: floor_div(pos.y, size),
+        z: floor_div(pos.z, size),
+    }
+}
+
+/// Chunk containing a world point (f64 so far-out network positions keep
+/// their precision)
+pub fn world_point_to_chunk_pos(layout: ChunkLayout, point: [f64; 3]) -> ChunkPos {
+    let [x, y, z] = point.map(|value| value.floor() as i32);
+    voxel_to_chunk_pos(layout, VoxelPos { x, y, z })
+}
+
+/// Position of a voxel inside its chunk, each axis in `0..size`
+pub fn voxel_to_local(layout: ChunkLayout, pos: VoxelPos) -> [u32; 3] {
+    let size = layout.size as i32;
+    [pos.x, pos.y, pos.z].map(|value| floor_mod(value, size) as u32)
+}
+
+/// Voxel at a chunk's minimum corner
+pub fn chunk_origin_voxel(layout: ChunkLayout, chunk: ChunkPos) -> VoxelPos {
+    let size = layout.size as i32;
+    VoxelPos {
+        x: chunk.x * size,
+        y: chunk.y * size,
+        z: chunk.z * size,
+    }
+}
+
+/// World voxel of a local position inside a chunk
+pub fn local_to_voxel(layout: ChunkLayout, chunk: ChunkPos, local: [u32; 3]) -> VoxelPos {
+    let origin = chunk_origin_voxel(layout, chunk);
+    VoxelPos {
+        x: origin.x + local[0] as i32,
+        y: origin.y + local[1] as i32,
+        z: origin.z + local[2] as i32,
+    }
+}
+
+/// Chunk of a voxel and the voxel's index in the chunk's block array
+/// (see [`layout_voxel_index`])
+pub fn voxel_chunk_index(layout: ChunkLayout, pos: VoxelPos) -> (ChunkPos, u32) {
+    let [x, y, z] = voxel_to_local(layout, pos);
+    (
+        voxel_to_chunk_pos(layout, pos),
+        layout_voxel_index(layout, x, y, z),
+    )
+}
+
+/// Chunk column (x, z) containing a world column
+pub fn column_to_chunk(layout: ChunkLayout, x: i32, z: i32) -> (i32, i32) {
+    let size = layout.size as i32;
+    (floor_div(x, size), floor_div(z, size))
+}
+
+/// Index of a world column in its chunk's `size * size` column arrays
+/// (x fastest)
+pub fn column_local_index(layout: ChunkLayout, x: i32, z: i32) -> usize {
+    let size = layout.size as i32;
+    (floor_mod(x, size) + floor_mod(z, size) * size) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::core::SUPPORTED_CHUNK_SIZES;
+    use crate::world::core::create_chunk_layout;
+    use proptest::prelude::*;
+
+    fn layouts() -> Vec<ChunkLayout> {
+        SUPPORTED_CHUNK_SIZES
+            .iter()
+            .map(|&size| create_chunk_layout(size).unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn voxels_round_trip_across_negative_chunk_boundaries() {
+        for layout in layouts() {
+            let size = layout.size as i32;
+            for value in -3 * size..=3 * size {
+                let pos = VoxelPos::new(value, -value - 1, value / 2);
+                let chunk = voxel_to_chunk_pos(layout, pos);
+                let local = voxel_to_local(layout, pos);
+                assert!(local.iter().all(|&axis| axis < layout.size));
+                assert_eq!(local_to_voxel(layout, chunk, local), pos);
+
+                let origin = chunk_origin_voxel(layout, chunk);
+                assert!(origin.x <= pos.x && pos.x < origin.x + size);
+
+                let (indexed_chunk, index) = voxel_chunk_index(layout, pos);
+                assert_eq!(indexed_chunk, chunk);
+                assert!(index < layout.voxels_per_chunk);
+
+                let (chunk_x, chunk_z) = column_to_chunk(layout, pos.x, pos.z);
+                assert_eq!((chunk_x, chunk_z), (chunk.x, chunk.z));
+                assert_eq!(
+                    column_local_index(layout, pos.x, pos.z),
+                    (local[0] + local[2] * layout.size) as usize
+                );
+            }
+
+            // The voxel just below zero belongs to chunk -1, at its far edge
+            let below = VoxelPos::new(-1, -1, -1);
+            assert_eq!(voxel_to_chunk_pos(layout, below), ChunkPos::new(-1, -1, -1));
+            assert_eq!(voxel_to_local(layout, below), [layout.size - 1; 3]);
+            let edge = VoxelPos::new(-size, 0, size);
+            assert_eq!(voxel_to_chunk_pos(layout, edge), ChunkPos::new(-1, 0, 1));
+        }
+    }
+
+    #[test]
+    fn world_points_floor_instead_of_truncating() {
+        let layout = layouts()[0];
+        let size = layout.size as f64;
+        assert_eq!(world_coord_to_voxel(-0.25), -1);
+        assert_eq!(world_coord_to_voxel(0.75), 0);
+        assert_eq!(
+            world_point_to_voxel([-0.5, 1.5, -2.0]),
+            VoxelPos::new(-1, 1, -2)
+        );
+        assert_eq!(
+            world_point_to_chunk_pos(layout, [-0.01, size - 0.01, -size]),
+            ChunkPos::new(-1, 0, -1)
+        );
+        assert_eq!(
+            world_point_to_chunk_pos(layout, [-size - 0.5, 0.0, 2.0 * size]),
+            ChunkPos::new(-2, 0, 2)
+        );
+        assert_eq!(floor_div(-7, 0), -7);
+        assert_eq!(floor_mod(-7, 4), 1);
+    }
+
+    fn supported_layout() -> impl Strategy<Value = ChunkLayout> {
+        proptest::sample::select(layouts())
+    }
+
+    proptest! {
+        #[test]
+        fn negative_voxels_split_into_floor_chunks(
+            layout in supported_layout(),
+            x in -1_000_000_000i32..0,
+            y in -1_000_000_000i32..0,
+            z in -1_000_000_000i32..0,
+        ) {
+            let size = layout.size as i32;
+            let pos = VoxelPos::new(x, y, z);
+            let chunk = voxel_to_chunk_pos(layout, pos);
+            let local = voxel_to_local(layout, pos);
+
+            prop_assert!(chunk.x < 0 && chunk.y < 0 && chunk.z < 0);
+            prop_assert!(local.iter().all(|&axis| axis < layout.size));
+            prop_assert_eq!(local_to_voxel(layout, chunk, local), pos);
+
+            let origin = chunk_origin_voxel(layout, chunk);
+            for (origin, value) in [(origin.x, x), (origin.y, y), (origin.z, z)] {
+                prop_assert!(origin <= value && value < origin + size);
+            }
+
+            let (indexed_chunk, index) = voxel_chunk_index(layout, pos);
+            prop_assert_eq!(indexed_chunk, chunk);
+            prop_assert_eq!(index, layout_voxel_index(layout, local[0], local[1], local[2]));
+            prop_assert_eq!(column_to_chunk(layout, x, z), (chunk.x, chunk.z));
+            prop_assert_eq!(
+                column_local_index(layout, x, z),
+                (local[0] + local[2] * layout.size) as usize
+            );
+        }
+
+        #[test]
+        fn negative_world_points_floor_into_their_voxel_chunk(
+            layout in supported_layout(),
+            voxel in -1_000_000i32..0,
+            fraction in 0.0f64..1.0,
+        ) {
+            let point = voxel as f64 + fraction;
+            let expected = voxel_to_chunk_pos(layout, VoxelPos::new(voxel, voxel, voxel));
+            prop_assert_eq!(world_point_to_chunk_pos(layout, [point; 3]), expected);
+            let size = layout.size as i32;
+            prop_assert_eq!(floor_div(voxel, size), expected.x);
+            prop_assert_eq!(floor_div(voxel, size) * size + floor_mod(voxel, size), voxel);
+        }
+    }
+}
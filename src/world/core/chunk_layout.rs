@@ -0,0 +1,154 @@
+//! Runtime chunk layout
+//!
+//! `CHUNK_SIZE` in `constants::core` is only the default. A world picks its
+//! chunk size when it is created and carries a [`ChunkLayout`] through the
+//! WorldBuffer, generation, meshing and world operations. WGSL shaders get
+//! their `CHUNK_SIZE` / `VOXELS_PER_CHUNK` constants generated from the same
+//! layout, and [`validate_wgsl_chunk_layout`] catches shaders that were built
+//! for a different size.
+
+use crate::constants::core::{CHUNK_SIZE, SUPPORTED_CHUNK_SIZES, VOXELS_PER_CHUNK};
+
+/// Chunk dimensions used by one world
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChunkLayout {
+    /// Voxels along each chunk edge
+    pub size: u32,
+    /// `size³`, cached because nearly every buffer calculation needs it
+    pub voxels_per_chunk: u32,
+}
+
+/// Layout matching the compile-time default chunk size
+pub const DEFAULT_CHUNK_LAYOUT: ChunkLayout = ChunkLayout {
+    size: CHUNK_SIZE,
+    voxels_per_chunk: VOXELS_PER_CHUNK,
+};
+
+impl Default for ChunkLayout {
+    fn default() -> Self {
+        DEFAULT_CHUNK_LAYOUT
+    }
+}
+
+/// Chunk layout errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ChunkLayoutError {
+    #[error("Unsupported chunk size {size} (supported: {supported:?})")]
+    UnsupportedSize { size: u32, supported: Vec<u32> },
+
+    #[error("Shader constant {name} is {found}, world chunk layout expects {expected}")]
+    ShaderConstantMismatch {
+        name: &'static str,
+        expected: u32,
+        found: u32,
+    },
+
+    #[error("Shader source does not define {0}")]
+    MissingShaderConstant(&'static str),
+}
+
+/// Create a layout for `size`, rejecting sizes the GPU pipelines do not support
+pub fn create_chunk_layout(size: u32) -> Result<ChunkLayout, ChunkLayoutError> {
+    if !SUPPORTED_CHUNK_SIZES.contains(&size) {
+        return Err(ChunkLayoutError::UnsupportedSize {
+            size,
+            supported: SUPPORTED_CHUNK_SIZES.to_vec(),
+        });
+    }
+
+    Ok(ChunkLayout {
+        size,
+        voxels_per_chunk: size * size * size,
+    })
+}
+
+/// Layout for `size` without the GPU support check, for CPU-side math in
+/// code that is handed a bare chunk size
+pub fn chunk_layout_for_size(size: u32) -> ChunkLayout {
+    let size = size.max(1);
+    ChunkLayout {
+        size,
+        voxels_per_chunk: size * size * size,
+    }
+}
+
+/// Linear voxel index for local chunk coordinates (x fastest, then y, then z)
+pub fn layout_voxel_index(layout: ChunkLayout, x: u32, y: u32, z: u32) -> u32 {
+    x + y * layout.size + z * layout.size * layout.size
+}
+
+/// Size in bytes of one chunk of `voxel_bytes`-sized voxels
+pub fn layout_chunk_bytes(layout: ChunkLayout, voxel_bytes: u64) -> u64 {
+    layout.voxels_per_chunk as u64 * voxel_bytes
+}
+
+/// Workgroups needed to cover one chunk edge with `workgroup_size` threads
+pub fn layout_workgroups_per_axis(layout: ChunkLayout, workgroup_size: u32) -> u32 {
+    layout.size.div_ceil(workgroup_size.max(1))
+}
+
+/// Read a `const NAME: u32 = Nu;` declaration from WGSL source
+fn find_wgsl_u32_constant(wgsl: &str, name: &str) -> Option<u32> {
+    let prefix = format!("const {}:", name);
+    wgsl.lines()
+        .map(str::trim)
+        .find(|line| line.starts_with(&prefix))
+        .and_then(|line| line.split('=').nth(1))
+        .and_then(|value| {
+            value
+                .trim()
+                .trim_end_matches(';')
+                .trim_end_matches('u')
+                .parse()
+                .ok()
+        })
+}
+
+/// Check that generated WGSL constants match the world chunk layout
+pub fn validate_wgsl_chunk_layout(wgsl: &str, layout: ChunkLayout) -> Result<(), ChunkLayoutError> {
+    let expected = [
+        ("CHUNK_SIZE", layout.size),
+        ("VOXELS_PER_CHUNK", layout.voxels_per_chunk),
+    ];
+
+    for (name, expected) in expected {
+        let found = find_wgsl_u32_constant(wgsl, name)
+            .ok_or(ChunkLayoutError::MissingShaderConstant(name))?;
+        if found != expected {
+            return Err(ChunkLayoutError::ShaderConstantMismatch {
+                name,
+                expected,
+                found,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::generate_wgsl_constants_for_chunk_size;
+
+    #[test]
+    fn test_supported_sizes_and_shader_validation() {
+        assert!(create_chunk_layout(17).is_err());
+
+        for size in SUPPORTED_CHUNK_SIZES {
+            let layout = create_chunk_layout(size).unwrap_or_default();
+            assert_eq!(layout.size, size);
+            assert_eq!(layout.voxels_per_chunk, size * size * size);
+
+            let wgsl = generate_wgsl_constants_for_chunk_size(size);
+            assert!(validate_wgsl_chunk_layout(&wgsl, layout).is_ok());
+        }
+
+        let layout = create_chunk_layout(64).unwrap_or_default();
+        let stale = generate_wgsl_constants_for_chunk_size(32);
+        assert!(matches!(
+            validate_wgsl_chunk_layout(&stale, layout),
+            Err(ChunkLayoutError::ShaderConstantMismatch { found: 32, .. })
+        ));
+    }
+}
@@ -4,11 +4,22 @@
 //! of the world system, independent of whether CPU or GPU backend is used.
 
 mod block;
+mod chunk_coords;
+mod chunk_layout;
 mod position;
 mod ray;
 mod registry;
 
 pub use block::{BlockId, PhysicsProperties, RenderData};
+pub use chunk_coords::{
+    chunk_origin_voxel, column_local_index, column_to_chunk, floor_div, floor_mod,
+    local_to_voxel, voxel_chunk_index, voxel_to_chunk_pos, voxel_to_local, world_coord_to_voxel,
+    world_point_to_chunk_pos, world_point_to_voxel,
+};
+pub use chunk_layout::{
+    chunk_layout_for_size, create_chunk_layout, layout_chunk_bytes, layout_voxel_index, layout_workgroups_per_axis,
+    validate_wgsl_chunk_layout, ChunkLayout, ChunkLayoutError, DEFAULT_CHUNK_LAYOUT,
+};
 pub use position::{ChunkPos, VoxelPos};
 pub use ray::{BlockFace, Ray, RaycastHit};
 pub use registry::{BlockRegistry, BlockRegistration};
@@ -2,6 +2,8 @@ use cgmath::Vector3;
 use serde::{Deserialize, Serialize};
 
 // Import constants properly
+use super::chunk_coords::{floor_div, voxel_to_chunk_pos, voxel_to_local};
+use super::chunk_layout::{chunk_layout_for_size, DEFAULT_CHUNK_LAYOUT};
 use crate::constants::core::CHUNK_SIZE;
 
 /// Position of a chunk in the world (chunk coordinates)
@@ -20,19 +22,15 @@ impl ChunkPos {
     /// Create ChunkPos from world position coordinates
     pub fn from_world_pos(world_x: i32, world_z: i32) -> Self {
         Self::new(
-            world_x.div_euclid(CHUNK_SIZE as i32),
+            floor_div(world_x, CHUNK_SIZE as i32),
             0,
-            world_z.div_euclid(CHUNK_SIZE as i32),
+            floor_div(world_z, CHUNK_SIZE as i32),
         )
     }
 
     /// Create ChunkPos from VoxelPos
     pub fn from_voxel_pos(voxel_pos: VoxelPos) -> Self {
-        Self::new(
-            voxel_pos.x.div_euclid(CHUNK_SIZE as i32),
-            voxel_pos.y.div_euclid(CHUNK_SIZE as i32),
-            voxel_pos.z.div_euclid(CHUNK_SIZE as i32),
-        )
+        voxel_to_chunk_pos(DEFAULT_CHUNK_LAYOUT, voxel_pos)
     }
 
     /// Convert to world position (multiply by chunk size)
@@ -73,22 +71,13 @@ impl VoxelPos {
 
     /// Get the chunk this voxel belongs to
     pub fn to_chunk_pos(&self, chunk_size: u32) -> ChunkPos {
-        let size = chunk_size as i32;
-        ChunkPos::new(
-            self.x.div_euclid(size),
-            self.y.div_euclid(size),
-            self.z.div_euclid(size),
-        )
+        voxel_to_chunk_pos(chunk_layout_for_size(chunk_size), *self)
     }
 
     /// Get local position within chunk
     pub fn to_local_pos(&self, chunk_size: u32) -> (u32, u32, u32) {
-        let size = chunk_size as i32;
-        (
-            self.x.rem_euclid(size) as u32,
-            self.y.rem_euclid(size) as u32,
-            self.z.rem_euclid(size) as u32,
-        )
+        let [x, y, z] = voxel_to_local(chunk_layout_for_size(chunk_size), *self);
+        (x, y, z)
     }
 
     /// Get chunk offset (same as to_local_pos but returns VoxelPos)
@@ -3,8 +3,8 @@
 //! These are the data structures that world_operations functions operate on.
 //! NO METHODS - just pure data.
 
-use super::core::{BlockId, ChunkPos};
-use std::collections::HashSet;
+use super::core::{BlockId, ChunkLayout, ChunkPos};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// World data - the main data structure for world state
 ///
@@ -26,6 +26,12 @@ pub struct WorldData {
     /// Chunk capacity (pre-allocated size)
     pub chunk_capacity: usize,
 
+    /// Chunk dimensions; every chunk holds `chunk_layout.voxels_per_chunk` blocks
+    pub chunk_layout: ChunkLayout,
+
+    /// Surface heightmap per chunk column (x, z), kept in step with the blocks
+    pub column_heights: ColumnHeightsMap,
+
     /// World generation seed
     pub seed: u32,
 
@@ -43,6 +49,10 @@ pub struct ChunkData {
     /// For chunk_size=50: 50*50*50 = 125,000 blocks
     pub blocks: Vec<BlockId>,
 
+    /// Per-block metadata (orientation etc.), keyed by flat block index.
+    /// Sparse: only blocks with non-zero metadata are stored.
+    pub block_metadata: HashMap<u32, u8>,
+
     /// Chunk metadata flags
     pub flags: ChunkMetadata,
 
@@ -61,6 +71,26 @@ impl ChunkData {
     }
 }
 
+/// Heightmaps by chunk column (x, z)
+pub type ColumnHeightsMap = HashMap<(i32, i32), ColumnHeights>;
+
+/// Column tops of each loaded chunk section, by chunk y
+pub type ColumnSections = BTreeMap<i32, Vec<u32>>;
+
+/// Surface heights of one chunk column (CPU mirror of the GPU heightmap)
+///
+/// Columns are indexed `x + z * chunk_size` within the chunk column.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColumnHeights {
+    /// World y of the highest non-air block per column, or
+    /// `NO_SURFACE_HEIGHT` if every loaded section of the column is empty
+    pub surface: Vec<i32>,
+
+    /// Per chunk y: highest non-air local y + 1 per column (0 = empty).
+    /// Same encoding as the GPU heightmap buffer.
+    pub sections: ColumnSections,
+}
+
 /// Chunk metadata
 #[derive(Clone, Copy, Debug)]
 pub struct ChunkMetadata {
@@ -91,11 +121,27 @@ impl WorldData {
             size_y,
             size_z,
             chunk_capacity: 0,
+            chunk_layout: ChunkLayout::default(),
+            column_heights: HashMap::new(),
             seed,
             tick: 0,
         }
     }
 
+    /// Create new empty world data using a non-default chunk layout
+    pub fn with_chunk_layout(
+        seed: u32,
+        size_x: u32,
+        size_y: u32,
+        size_z: u32,
+        chunk_layout: ChunkLayout,
+    ) -> Self {
+        Self {
+            chunk_layout,
+            ..Self::new(seed, size_x, size_y, size_z)
+        }
+    }
+
     /// Create with pre-allocated chunk capacity
     pub fn with_capacity(seed: u32, size_x: u32, size_y: u32, size_z: u32, capacity: usize) -> Self {
         Self {
@@ -105,6 +151,8 @@ impl WorldData {
             size_y,
             size_z,
             chunk_capacity: capacity,
+            chunk_layout: ChunkLayout::default(),
+            column_heights: HashMap::new(),
             seed,
             tick: 0,
         }
@@ -119,6 +167,7 @@ impl ChunkData {
         Self {
             position,
             blocks: vec![BlockId::AIR; total_blocks],
+            block_metadata: HashMap::new(),
             flags: ChunkMetadata::default(),
             last_modified: 0,
         }
@@ -131,6 +180,7 @@ impl ChunkData {
         Self {
             position,
             blocks: vec![block; total_blocks],
+            block_metadata: HashMap::new(),
             flags: ChunkMetadata {
                 is_generated: true,
                 is_dirty: false,
@@ -169,6 +219,26 @@ pub struct BlockChange {
     pub timestamp: u64,
 }
 
+/// Blocks copied out of a cuboid region
+///
+/// Indexed `x + y * size[0] + z * size[0] * size[1]` like chunk data.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RegionBlocks {
+    pub size: [u32; 3],
+    pub blocks: Vec<BlockId>,
+    /// Per-block metadata (0 = none), same indexing as `blocks`
+    pub metadata: Vec<u8>,
+}
+
+/// Outcome of pasting a region
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RegionPasteStats {
+    /// Blocks written
+    pub placed: usize,
+    /// Blocks skipped because their chunk is not loaded
+    pub unloaded: usize,
+}
+
 /// World statistics
 #[derive(Clone, Copy, Debug, Default)]
 pub struct WorldStats {
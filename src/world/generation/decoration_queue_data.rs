@@ -0,0 +1,86 @@
+//! Decoration Queue Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Queueing, budgeting and the CPU passes live in
+//! decoration_queue_operations.rs
+//!
+//! Grass tufts, flowers and pebbles are left out of generation so chunks
+//! appear as fast as possible. Once a chunk has been on screen for a short
+//! delay it joins the decoration queue, which runs one pass at a time,
+//! nearest chunk first, within a per-frame time budget. Each chunk goes
+//! through every pass once and then stays decorated until it unloads.
+
+use crate::world::core::ChunkPos;
+use std::collections::HashSet;
+
+/// Decoration step, run in this order on every chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecorationPass {
+    Grass = 0,
+    Flowers = 1,
+    Pebbles = 2,
+}
+
+/// Every pass in the order chunks go through them
+pub const DECORATION_PASSES: [DecorationPass; 3] = [
+    DecorationPass::Grass,
+    DecorationPass::Flowers,
+    DecorationPass::Pebbles,
+];
+
+/// Timing and density of decoration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecorationConfig {
+    /// World seed the placement is derived from
+    pub seed: u64,
+    /// Seconds a chunk is on screen before it is decorated
+    pub delay_secs: f32,
+    /// Time passes may use per frame (ms); the first pass of a frame always
+    /// runs so the queue keeps moving
+    pub frame_budget_ms: f32,
+    pub passes_per_frame: u32,
+    pub tall_grass_chance: f32,
+    pub flower_chance: f32,
+    pub pebble_chance: f32,
+}
+
+/// Chunk waiting for its remaining passes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PendingDecoration {
+    pub chunk: ChunkPos,
+    /// Queue clock time the chunk may be decorated from (seconds)
+    pub ready_at: f32,
+    /// Index into `DECORATION_PASSES` of the next pass
+    pub next_pass: usize,
+}
+
+/// One pass the budget ran
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecorationStep {
+    pub chunk: ChunkPos,
+    pub pass: DecorationPass,
+    /// Blocks the pass placed; chunks with any need remeshing
+    pub placed: u32,
+}
+
+/// Decoration counters since the queue was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecorationStats {
+    pub passes_run: u64,
+    pub blocks_placed: u64,
+    pub chunks_finished: u64,
+    /// Frames that ended with ready chunks still waiting
+    pub frames_over_budget: u64,
+}
+
+/// Post-load decoration state of the loaded world
+#[derive(Debug, Clone)]
+pub struct DecorationQueueData {
+    pub config: DecorationConfig,
+    /// Seconds since the queue was created
+    pub clock: f32,
+    pub pending: Vec<PendingDecoration>,
+    /// Chunks that went through every pass
+    pub decorated: HashSet<ChunkPos>,
+    pub stats: DecorationStats,
+}
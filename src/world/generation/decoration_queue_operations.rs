@@ -0,0 +1,277 @@
+//! Decoration Queue Operations - Pure functions over DecorationQueueData
+//!
+//! The renderer reports chunks with `note_chunk_rendered` the first time
+//! they draw; `run_decoration_budget` is called once per frame after
+//! `advance_decoration_clock` and hands each pass to the caller, which
+//! runs it on the CPU with `decorate_chunk_pass` or dispatches a GPU
+//! equivalent. Steps that placed blocks name the chunks to remesh.
+//! Placement is a hash of the seed, pass and position, so a chunk looks
+//! the same however late it is decorated.
+
+use super::decoration_queue_data::{
+    DecorationConfig, DecorationPass, DecorationQueueData, DecorationStats, DecorationStep,
+    PendingDecoration, DECORATION_PASSES,
+};
+use crate::constants::decoration::{
+    DECORATION_DELAY_SECS, DECORATION_FRAME_BUDGET_MS, DECORATION_PASSES_PER_FRAME, FLOWER_CHANCE,
+    PEBBLE_CHANCE, TALL_GRASS_CHANCE,
+};
+use crate::world::core::{BlockId, ChunkPos};
+use crate::world_random_operations::{position_fork_key, splitmix64};
+use std::collections::HashSet;
+use std::time::Instant;
+
+/// Default timing and density for a world seed
+pub fn default_decoration_config(seed: u64) -> DecorationConfig {
+    DecorationConfig {
+        seed,
+        delay_secs: DECORATION_DELAY_SECS,
+        frame_budget_ms: DECORATION_FRAME_BUDGET_MS,
+        passes_per_frame: DECORATION_PASSES_PER_FRAME,
+        tall_grass_chance: TALL_GRASS_CHANCE,
+        flower_chance: FLOWER_CHANCE,
+        pebble_chance: PEBBLE_CHANCE,
+    }
+}
+
+/// Empty queue
+pub fn create_decoration_queue(config: DecorationConfig) -> DecorationQueueData {
+    DecorationQueueData {
+        config,
+        clock: 0.0,
+        pending: Vec::new(),
+        decorated: HashSet::new(),
+        stats: DecorationStats::default(),
+    }
+}
+
+/// Queue a chunk that has just drawn for the first time; chunks already
+/// queued or decorated are left alone
+pub fn note_chunk_rendered(queue: &mut DecorationQueueData, chunk: ChunkPos) {
+    if queue.decorated.contains(&chunk) || queue.pending.iter().any(|p| p.chunk == chunk) {
+        return;
+    }
+    queue.pending.push(PendingDecoration {
+        chunk,
+        ready_at: queue.clock + queue.config.delay_secs,
+        next_pass: 0,
+    });
+}
+
+/// Drop an unloaded chunk; it is decorated again if it reloads
+pub fn forget_chunk_decoration(queue: &mut DecorationQueueData, chunk: ChunkPos) {
+    queue.pending.retain(|p| p.chunk != chunk);
+    queue.decorated.remove(&chunk);
+}
+
+/// Whether a chunk has been through every pass
+pub fn chunk_decorated(queue: &DecorationQueueData, chunk: ChunkPos) -> bool {
+    queue.decorated.contains(&chunk)
+}
+
+/// Move the queue clock forward by the frame time
+pub fn advance_decoration_clock(queue: &mut DecorationQueueData, dt: f32) {
+    queue.clock += dt.max(0.0);
+}
+
+/// Run ready passes, nearest chunk to the camera first, until the frame
+/// budget or pass cap is used up. `run_pass` returns the blocks it placed,
+/// or `None` when the chunk's data is gone, which drops the chunk.
+pub fn run_decoration_budget(
+    queue: &mut DecorationQueueData,
+    camera_chunk: ChunkPos,
+    mut run_pass: impl FnMut(ChunkPos, DecorationPass) -> Option<u32>,
+) -> Vec<DecorationStep> {
+    let started = Instant::now();
+    let mut steps = Vec::new();
+    loop {
+        let ready = queue
+            .pending
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.ready_at <= queue.clock)
+            .min_by_key(|(_, p)| p.chunk.distance_squared_to(camera_chunk))
+            .map(|(index, _)| index);
+        let Some(index) = ready else {
+            break;
+        };
+        let out_of_budget = steps.len() as u32 >= queue.config.passes_per_frame
+            || (!steps.is_empty()
+                && started.elapsed().as_secs_f32() * 1000.0 >= queue.config.frame_budget_ms);
+        if out_of_budget {
+            queue.stats.frames_over_budget += 1;
+            break;
+        }
+
+        let pending = &mut queue.pending[index];
+        let chunk = pending.chunk;
+        let pass = DECORATION_PASSES[pending.next_pass];
+        let Some(placed) = run_pass(chunk, pass) else {
+            queue.pending.swap_remove(index);
+            continue;
+        };
+        pending.next_pass += 1;
+        if pending.next_pass >= DECORATION_PASSES.len() {
+            queue.pending.swap_remove(index);
+            queue.decorated.insert(chunk);
+            queue.stats.chunks_finished += 1;
+        }
+        queue.stats.passes_run += 1;
+        queue.stats.blocks_placed += placed as u64;
+        steps.push(DecorationStep {
+            chunk,
+            pass,
+            placed,
+        });
+    }
+    steps
+}
+
+/// CPU version of a pass over a chunk's blocks (indexed like `TempChunk`);
+/// returns the blocks placed. Only air directly above the topmost solid
+/// block of a column is decorated, and never in the chunk's top layer,
+/// which the chunk above owns.
+pub fn decorate_chunk_pass(
+    config: &DecorationConfig,
+    chunk: ChunkPos,
+    size: u32,
+    blocks: &mut [BlockId],
+    pass: DecorationPass,
+) -> u32 {
+    let index = |x: u32, y: u32, z: u32| (y * size * size + z * size + x) as usize;
+    if blocks.len() < (size * size * size) as usize {
+        return 0;
+    }
+    let origin = [
+        chunk.x * size as i32,
+        chunk.y * size as i32,
+        chunk.z * size as i32,
+    ];
+    let mut placed = 0;
+    for z in 0..size {
+        for x in 0..size {
+            let Some(y) = (0..size.saturating_sub(1))
+                .rev()
+                .find(|&y| blocks[index(x, y, z)] != BlockId::AIR)
+            else {
+                continue;
+            };
+            let above = index(x, y + 1, z);
+            if blocks[above] != BlockId::AIR {
+                continue;
+            }
+            let roll = decoration_roll(
+                config.seed,
+                pass,
+                [
+                    origin[0] + x as i32,
+                    origin[1] + y as i32,
+                    origin[2] + z as i32,
+                ],
+            );
+            let decoration = match (pass, blocks[index(x, y, z)]) {
+                (DecorationPass::Grass, BlockId::GRASS) if roll < config.tall_grass_chance => {
+                    BlockId::TALL_GRASS
+                }
+                (DecorationPass::Flowers, BlockId::GRASS) if roll < config.flower_chance => {
+                    // Low bits of the roll pick the color
+                    if ((roll * 1024.0) as u32).is_multiple_of(2) {
+                        BlockId::FLOWER_RED
+                    } else {
+                        BlockId::FLOWER_YELLOW
+                    }
+                }
+                (DecorationPass::Pebbles, BlockId::STONE | BlockId::DIRT | BlockId::SAND)
+                    if roll < config.pebble_chance =>
+                {
+                    BlockId::COBBLESTONE
+                }
+                _ => continue,
+            };
+            blocks[above] = decoration;
+            placed += 1;
+        }
+    }
+    placed
+}
+
+/// Uniform 0.0..1.0 value for a pass at a world position
+fn decoration_roll(seed: u64, pass: DecorationPass, [x, y, z]: [i32; 3]) -> f32 {
+    let mut state = seed ^ position_fork_key(x, y, z) ^ ((pass as u64 + 1) << 56);
+    (splitmix64(&mut state) >> 40) as f32 / (1u64 << 24) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_converge_one_pass_per_frame_after_the_delay() {
+        let config = DecorationConfig {
+            passes_per_frame: 1,
+            ..default_decoration_config(7)
+        };
+        let mut queue = create_decoration_queue(config);
+        let near = ChunkPos::new(0, 0, 0);
+        let far = ChunkPos::new(5, 0, 0);
+        note_chunk_rendered(&mut queue, far);
+        note_chunk_rendered(&mut queue, near);
+        note_chunk_rendered(&mut queue, near);
+        assert_eq!(queue.pending.len(), 2);
+
+        let mut ran = Vec::new();
+        let frame = |queue: &mut DecorationQueueData, ran: &mut Vec<_>| {
+            advance_decoration_clock(queue, 0.5);
+            let steps = run_decoration_budget(queue, near, |chunk, pass| {
+                ran.push((chunk, pass));
+                Some(1)
+            });
+            steps.len()
+        };
+        // Nothing runs on the critical path right after the chunks appear
+        assert_eq!(frame(&mut queue, &mut ran), 0);
+        for _ in 0..6 {
+            assert_eq!(frame(&mut queue, &mut ran), 1);
+        }
+        assert_eq!(ran[0], (near, DecorationPass::Grass));
+        assert_eq!(ran[2], (near, DecorationPass::Pebbles));
+        assert_eq!(ran[3], (far, DecorationPass::Grass));
+        assert!(chunk_decorated(&queue, near) && chunk_decorated(&queue, far));
+        assert_eq!(queue.stats.passes_run, 6);
+
+        note_chunk_rendered(&mut queue, near);
+        assert!(queue.pending.is_empty());
+        forget_chunk_decoration(&mut queue, near);
+        assert!(!chunk_decorated(&queue, near));
+    }
+
+    #[test]
+    fn cpu_passes_decorate_exposed_surfaces() {
+        let size = 4;
+        let config = DecorationConfig {
+            tall_grass_chance: 1.0,
+            flower_chance: 1.0,
+            pebble_chance: 1.0,
+            ..default_decoration_config(1)
+        };
+        let mut blocks = vec![BlockId::AIR; 64];
+        for z in 0..size {
+            for x in 0..size {
+                let surface = if x < 2 {
+                    BlockId::GRASS
+                } else {
+                    BlockId::STONE
+                };
+                blocks[(z * size + x) as usize] = surface;
+            }
+        }
+        let chunk = ChunkPos::new(0, 0, 0);
+        let mut run = |pass| decorate_chunk_pass(&config, chunk, size, &mut blocks, pass);
+        assert_eq!(run(DecorationPass::Grass), 8);
+        // Tufts took every grass column, so flowers find no room
+        assert_eq!(run(DecorationPass::Flowers), 0);
+        assert_eq!(run(DecorationPass::Pebbles), 8);
+        assert_eq!(blocks[(size * size) as usize], BlockId::TALL_GRASS);
+        assert_eq!(blocks[(size * size + 3) as usize], BlockId::COBBLESTONE);
+    }
+}
@@ -0,0 +1,99 @@
+//! Generation halo data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Chunks requested together are grouped into batches of neighbouring
+//! columns. Each batch gets one surface heightmap covering its chunks plus a
+//! halo of extra columns on every side, evaluated and eroded on the GPU
+//! before the terrain pass, and every chunk of the batch reads its surface
+//! from it. Erosion reads neighbouring columns, so a column only comes out
+//! the same in two batches when both saw the same surroundings: with one
+//! halo column per erosion pass, every column a chunk reads is exact, and
+//! chunks generated in different batches still meet without seams.
+//! Planning and the CPU reference live in generation_halo_operations.rs
+
+use crate::world::core::ChunkPos;
+use bytemuck::{Pod, Zeroable};
+
+/// Halo sizing and erosion settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GenerationHaloConfig {
+    /// Extra columns on each side of a batch, and erosion passes run
+    pub halo_width: u32,
+    /// Edge of the square chunk tiles a batch may span (chunks)
+    pub tile_chunks: i32,
+    /// Slope erosion leaves alone (voxels per column)
+    pub talus: f32,
+    /// Share of the excess slope moved per pass
+    pub erosion_rate: f32,
+}
+
+/// Columns covered by a batch heightmap, halo included (world voxels)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HaloRect {
+    pub min_x: i32,
+    pub min_z: i32,
+    pub width: u32,
+    pub depth: u32,
+}
+
+/// Chunks sharing one heightmap
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationBatch {
+    /// Chunks of the batch, in request order
+    pub chunks: Vec<ChunkPos>,
+    pub rect: HaloRect,
+    /// First height of the batch in the combined heightmap buffer
+    pub offset: u32,
+}
+
+/// How many columns batching saved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GenerationPlanStats {
+    pub chunks: u32,
+    pub batches: u32,
+    /// Heights evaluated for the plan
+    pub columns: u32,
+    /// Heights a separate halo per chunk would have evaluated
+    pub unbatched_columns: u32,
+}
+
+/// Batches of one generation request
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationPlan {
+    pub batches: Vec<GenerationBatch>,
+    pub stats: GenerationPlanStats,
+}
+
+/// Where a chunk's columns start in the combined heightmap buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HaloColumns {
+    /// Height of the chunk's (0, 0) column
+    pub base: u32,
+    /// Heights per row of the batch heightmap
+    pub stride: u32,
+}
+
+/// Columns of a requested chunk, `None` when the plan was not made for it
+pub type PlannedColumns = Option<HaloColumns>;
+
+/// Batch heightmap as seen by generation_halo.wgsl
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Pod, Zeroable)]
+pub struct HaloTileGpu {
+    pub min_x: i32,
+    pub min_z: i32,
+    pub width: u32,
+    pub depth: u32,
+    pub offset: u32,
+    pub _padding: [u32; 3],
+}
+
+/// Erosion settings as seen by generation_halo.wgsl
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Pod, Zeroable)]
+pub struct HaloParamsGpu {
+    pub talus: f32,
+    pub erosion_rate: f32,
+    pub tile_count: u32,
+    pub _padding: u32,
+}
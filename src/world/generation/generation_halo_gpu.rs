@@ -0,0 +1,180 @@
+//! GPU pass building the batch heightmaps the terrain pass reads
+//!
+//! Encodes generation_halo.wgsl for a `GenerationPlan`: one evaluate pass,
+//! then one erosion pass per halo column, ping-ponging between two buffers.
+
+use super::generation_halo_data::{GenerationHaloConfig, GenerationPlan};
+use super::generation_halo_operations::{halo_dispatch_extent, halo_params, halo_tiles};
+use crate::gpu::GpuError;
+use crate::memory::{
+    create_tracked_buffer, create_tracked_buffer_init, register_global_gpu_owner,
+    GpuAllocationGuard, GpuOwnerGuard,
+};
+use std::sync::Arc;
+
+/// Workgroup edge of both halo entry points
+const HALO_WORKGROUP_SIZE: u32 = 8;
+
+/// Batch heightmap pipelines
+pub struct GenerationHaloCompute {
+    device: Arc<wgpu::Device>,
+    evaluate_pipeline: wgpu::ComputePipeline,
+    erode_pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    gpu_owner: GpuOwnerGuard,
+}
+
+impl GenerationHaloCompute {
+    pub fn new(device: Arc<wgpu::Device>) -> Result<Self, GpuError> {
+        let shader_source = include_str!("../../shaders/compute/generation_halo.wgsl");
+        let validated_shader =
+            crate::gpu::automation::create_gpu_shader(&device, "generation_halo", shader_source)
+                .map_err(|e| GpuError::ShaderCompilation {
+                    message: format!("Failed to create generation halo shader: {}", e),
+                })?;
+
+        let bind_group_layout = crate::create_bind_group_layout!(
+            &device,
+            "Generation Halo Bind Group Layout",
+            0 => buffer(uniform),       // Params
+            1 => buffer(storage_read),  // Tiles
+            2 => buffer(storage_read),  // Source heights
+            3 => buffer(storage)        // Target heights
+        );
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Generation Halo Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |label, entry_point| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                module: &validated_shader.module,
+                entry_point,
+            })
+        };
+        let evaluate_pipeline =
+            create_pipeline("Generation Halo Evaluate Pipeline", "evaluate_heights");
+        let erode_pipeline = create_pipeline("Generation Halo Erode Pipeline", "erode_heights");
+
+        Ok(Self {
+            device,
+            evaluate_pipeline,
+            erode_pipeline,
+            bind_group_layout,
+            gpu_owner: register_global_gpu_owner("generation_halo"),
+        })
+    }
+
+    /// Encode the heightmaps of every batch of `plan` and return the buffer
+    /// holding the eroded heights, laid out as the plan says, with its record
+    /// in the GPU allocation tracker
+    pub fn encode(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        plan: &GenerationPlan,
+        config: &GenerationHaloConfig,
+    ) -> (wgpu::Buffer, GpuAllocationGuard) {
+        let _span = crate::trace_span!(Generation, "generation_halo");
+        let tiles = halo_tiles(plan);
+        let params = halo_params(config, tiles.len() as u32);
+
+        let (params_buffer, _params_allocation) = create_tracked_buffer_init(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Generation Halo Params"),
+                contents: bytemuck::bytes_of(&params),
+                usage: wgpu::BufferUsages::UNIFORM,
+            },
+        );
+        let (tile_buffer, _tile_allocation) = create_tracked_buffer_init(
+            &self.device,
+            &self.gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Generation Halo Tiles"),
+                contents: bytemuck::cast_slice(&tiles),
+                usage: wgpu::BufferUsages::STORAGE,
+            },
+        );
+        let heights_size = plan.stats.columns.max(1) as u64 * 4;
+        let create_heights = |label| {
+            create_tracked_buffer(
+                &self.device,
+                &self.gpu_owner,
+                &wgpu::BufferDescriptor {
+                    label: Some(label),
+                    size: heights_size,
+                    usage: wgpu::BufferUsages::STORAGE,
+                    mapped_at_creation: false,
+                },
+            )
+        };
+        let heights = [
+            create_heights("Generation Halo Heights A"),
+            create_heights("Generation Halo Heights B"),
+        ];
+
+        // Bind group `i` reads heights[i] and writes the other buffer
+        let bind_groups: Vec<wgpu::BindGroup> = (0..2)
+            .map(|source| {
+                self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Generation Halo Bind Group"),
+                    layout: &self.bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: params_buffer.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: tile_buffer.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: heights[source].0.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 3,
+                            resource: heights[1 - source].0.as_entire_binding(),
+                        },
+                    ],
+                })
+            })
+            .collect();
+
+        let (width, depth) = halo_dispatch_extent(plan);
+        let workgroups_x = width.div_ceil(HALO_WORKGROUP_SIZE);
+        let workgroups_y = depth.div_ceil(HALO_WORKGROUP_SIZE);
+
+        // Evaluate writes B; each erosion pass then swaps the buffers
+        let mut source = 0;
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Generation Halo Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.evaluate_pipeline);
+            pass.set_bind_group(0, &bind_groups[source], &[]);
+            pass.dispatch_workgroups(workgroups_x, workgroups_y, tiles.len() as u32);
+            source = 1 - source;
+
+            pass.set_pipeline(&self.erode_pipeline);
+            for _ in 0..config.halo_width {
+                pass.set_bind_group(0, &bind_groups[source], &[]);
+                pass.dispatch_workgroups(workgroups_x, workgroups_y, tiles.len() as u32);
+                source = 1 - source;
+            }
+        }
+
+        let [first, second] = heights;
+        if source == 0 {
+            first
+        } else {
+            second
+        }
+    }
+}
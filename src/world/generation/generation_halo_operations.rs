@@ -0,0 +1,316 @@
+//! Generation halo operations - Pure DOP
+//!
+//! `plan_generation_batches` groups a generation request into batches and
+//! lays their heightmaps out in one buffer; `chunk_halo_columns` tells the
+//! terrain pass where each chunk's surface is. The evaluate and erode
+//! functions are the CPU reference of generation_halo.wgsl.
+
+use super::generation_halo_data::{
+    GenerationBatch, GenerationHaloConfig, GenerationPlan, GenerationPlanStats, HaloColumns,
+    HaloParamsGpu, HaloRect, HaloTileGpu, PlannedColumns,
+};
+use crate::constants::generation_halo::{EROSION_RATE, EROSION_TALUS, HALO_WIDTH, TILE_CHUNKS};
+use crate::constants::terrain::TERRAIN_THRESHOLD;
+use crate::world::core::ChunkPos;
+use std::collections::HashMap;
+
+/// Tile of `tile_chunks` by `tile_chunks` chunk columns
+type TileCoord = (i32, i32);
+
+/// Halo settings from the engine constants
+pub fn default_generation_halo_config() -> GenerationHaloConfig {
+    GenerationHaloConfig {
+        halo_width: HALO_WIDTH,
+        tile_chunks: TILE_CHUNKS,
+        talus: EROSION_TALUS,
+        erosion_rate: EROSION_RATE,
+    }
+}
+
+/// Halo settings for the `erosion` feature flag. Without erosion a
+/// column's height depends on that column alone, so batches need no halo.
+pub fn erosion_halo_config(config: &GenerationHaloConfig, erosion: bool) -> GenerationHaloConfig {
+    if erosion {
+        *config
+    } else {
+        GenerationHaloConfig {
+            halo_width: 0,
+            ..*config
+        }
+    }
+}
+
+/// Columns of a set of chunks plus the halo around them
+fn batch_rect(chunks: &[ChunkPos], chunk_size: u32, halo_width: u32) -> HaloRect {
+    let min_x = chunks.iter().map(|c| c.x).min().unwrap_or(0);
+    let max_x = chunks.iter().map(|c| c.x).max().unwrap_or(0);
+    let min_z = chunks.iter().map(|c| c.z).min().unwrap_or(0);
+    let max_z = chunks.iter().map(|c| c.z).max().unwrap_or(0);
+    let size = chunk_size as i32;
+    let halo = halo_width as i32;
+    HaloRect {
+        min_x: min_x * size - halo,
+        min_z: min_z * size - halo,
+        width: (max_x - min_x + 1) as u32 * chunk_size + 2 * halo_width,
+        depth: (max_z - min_z + 1) as u32 * chunk_size + 2 * halo_width,
+    }
+}
+
+/// Group chunks by square tile of columns, so stacked and neighbouring
+/// chunks share one heightmap. Batches keep the order their first chunk
+/// was requested in, and chunks keep request order inside a batch.
+pub fn plan_generation_batches(
+    chunks: &[ChunkPos],
+    chunk_size: u32,
+    config: &GenerationHaloConfig,
+) -> GenerationPlan {
+    let tile = config.tile_chunks.max(1);
+    let mut tiles: HashMap<TileCoord, usize> = HashMap::new();
+    let mut grouped: Vec<Vec<ChunkPos>> = Vec::new();
+    for &chunk in chunks {
+        let key = (chunk.x.div_euclid(tile), chunk.z.div_euclid(tile));
+        let index = *tiles.entry(key).or_insert_with(|| {
+            grouped.push(Vec::new());
+            grouped.len() - 1
+        });
+        grouped[index].push(chunk);
+    }
+
+    let mut offset = 0;
+    let batches: Vec<GenerationBatch> = grouped
+        .into_iter()
+        .map(|chunks| {
+            let rect = batch_rect(&chunks, chunk_size, config.halo_width);
+            let batch = GenerationBatch {
+                chunks,
+                rect,
+                offset,
+            };
+            offset += rect.width * rect.depth;
+            batch
+        })
+        .collect();
+
+    let chunk_columns = chunk_size + 2 * config.halo_width;
+    let stats = GenerationPlanStats {
+        chunks: chunks.len() as u32,
+        batches: batches.len() as u32,
+        columns: offset,
+        unbatched_columns: chunks.len() as u32 * chunk_columns * chunk_columns,
+    };
+    GenerationPlan { batches, stats }
+}
+
+/// Where a chunk of `batch` finds its columns
+pub fn chunk_halo_columns(
+    batch: &GenerationBatch,
+    chunk: ChunkPos,
+    chunk_size: u32,
+) -> HaloColumns {
+    let size = chunk_size as i32;
+    let x = (chunk.x * size - batch.rect.min_x) as u32;
+    let z = (chunk.z * size - batch.rect.min_z) as u32;
+    HaloColumns {
+        base: batch.offset + x + z * batch.rect.width,
+        stride: batch.rect.width,
+    }
+}
+
+/// Columns of each requested chunk, in request order (`None` for a chunk
+/// the plan was not made for)
+pub fn plan_halo_columns(
+    plan: &GenerationPlan,
+    chunks: &[ChunkPos],
+    chunk_size: u32,
+) -> Vec<PlannedColumns> {
+    let columns: HashMap<ChunkPos, HaloColumns> = plan
+        .batches
+        .iter()
+        .flat_map(|batch| {
+            batch
+                .chunks
+                .iter()
+                .map(move |&chunk| (chunk, chunk_halo_columns(batch, chunk, chunk_size)))
+        })
+        .collect();
+    chunks
+        .iter()
+        .map(|chunk| columns.get(chunk).copied())
+        .collect()
+}
+
+/// Batch heightmaps in the layout the shader reads
+pub fn halo_tiles(plan: &GenerationPlan) -> Vec<HaloTileGpu> {
+    plan.batches
+        .iter()
+        .map(|batch| HaloTileGpu {
+            min_x: batch.rect.min_x,
+            min_z: batch.rect.min_z,
+            width: batch.rect.width,
+            depth: batch.rect.depth,
+            offset: batch.offset,
+            _padding: [0; 3],
+        })
+        .collect()
+}
+
+/// Erosion settings in the layout the shader reads
+pub fn halo_params(config: &GenerationHaloConfig, tile_count: u32) -> HaloParamsGpu {
+    HaloParamsGpu {
+        talus: config.talus,
+        erosion_rate: config.erosion_rate,
+        tile_count,
+        _padding: 0,
+    }
+}
+
+/// Widest and deepest batch heightmap, for sizing the halo dispatches
+pub fn halo_dispatch_extent(plan: &GenerationPlan) -> (u32, u32) {
+    plan.batches.iter().fold((0, 0), |(width, depth), batch| {
+        (width.max(batch.rect.width), depth.max(batch.rect.depth))
+    })
+}
+
+/// Uneroded surface height of a column, before rounding. Same formula as
+/// `reference_surface_height` and `evaluate_heights` in
+/// generation_halo.wgsl.
+pub fn halo_surface_height(world_x: i32, world_z: i32) -> f32 {
+    let height_variation =
+        (world_x as f32 * 0.05).sin() * 5.0 + (world_z as f32 * 0.05).cos() * 5.0;
+    TERRAIN_THRESHOLD as f32 + height_variation
+}
+
+/// Heights of every column of `rect`, row by row
+pub fn evaluate_halo_heights(rect: &HaloRect, height_at: impl Fn(i32, i32) -> f32) -> Vec<f32> {
+    (0..rect.depth)
+        .flat_map(|z| (0..rect.width).map(move |x| (x, z)))
+        .map(|(x, z)| height_at(rect.min_x + x as i32, rect.min_z + z as i32))
+        .collect()
+}
+
+/// One thermal erosion pass: material slides from each column to its four
+/// neighbours where the slope exceeds the talus. The outer ring has no
+/// neighbours outside the rect and is copied unchanged.
+pub fn erode_halo_step(
+    heights: &[f32],
+    rect: &HaloRect,
+    config: &GenerationHaloConfig,
+) -> Vec<f32> {
+    let width = rect.width as usize;
+    let depth = rect.depth as usize;
+    let mut eroded = heights.to_vec();
+    for z in 1..depth.saturating_sub(1) {
+        for x in 1..width.saturating_sub(1) {
+            let index = x + z * width;
+            let height = heights[index];
+            let flow: f32 = [index - 1, index + 1, index - width, index + width]
+                .iter()
+                .map(|&neighbour| {
+                    let slope = heights[neighbour] - height;
+                    (slope - config.talus).max(0.0) - (-slope - config.talus).max(0.0)
+                })
+                .sum();
+            eroded[index] = height + config.erosion_rate * flow * 0.25;
+        }
+    }
+    eroded
+}
+
+/// All erosion passes, one per halo column
+pub fn erode_halo_heights(
+    heights: &[f32],
+    rect: &HaloRect,
+    config: &GenerationHaloConfig,
+) -> Vec<f32> {
+    (0..config.halo_width).fold(heights.to_vec(), |heights, _| {
+        erode_halo_step(&heights, rect, config)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHUNK_SIZE: u32 = 8;
+
+    fn chunk(x: i32, y: i32, z: i32) -> ChunkPos {
+        ChunkPos { x, y, z }
+    }
+
+    #[test]
+    fn test_neighbouring_chunks_share_one_heightmap() {
+        let config = default_generation_halo_config();
+        let chunks = [
+            chunk(0, 0, 0),
+            chunk(1, 0, 0),
+            chunk(0, 1, 0),
+            chunk(4, 0, 0),
+            chunk(1, 0, 1),
+        ];
+        let plan = plan_generation_batches(&chunks, CHUNK_SIZE, &config);
+
+        assert_eq!(plan.batches.len(), 2);
+        let first = &plan.batches[0];
+        assert_eq!(
+            first.chunks,
+            [
+                chunk(0, 0, 0),
+                chunk(1, 0, 0),
+                chunk(0, 1, 0),
+                chunk(1, 0, 1)
+            ]
+        );
+        let halo = HALO_WIDTH as i32;
+        assert_eq!((first.rect.min_x, first.rect.min_z), (-halo, -halo));
+        assert_eq!(first.rect.width, 2 * CHUNK_SIZE + 2 * HALO_WIDTH);
+        assert_eq!(plan.batches[1].offset, first.rect.width * first.rect.depth);
+        assert!(plan.stats.columns < plan.stats.unbatched_columns);
+
+        // Stacked chunks read the same columns
+        let columns = chunk_halo_columns(first, chunk(1, 0, 1), CHUNK_SIZE);
+        assert_eq!(
+            columns.base,
+            (CHUNK_SIZE + HALO_WIDTH) + (CHUNK_SIZE + HALO_WIDTH) * first.rect.width
+        );
+        assert_eq!(
+            chunk_halo_columns(first, chunk(0, 0, 0), CHUNK_SIZE),
+            chunk_halo_columns(first, chunk(0, 1, 0), CHUNK_SIZE)
+        );
+    }
+
+    #[test]
+    fn test_chunk_surface_is_the_same_in_any_batch() {
+        let config = default_generation_halo_config();
+        // Steep enough for erosion to move material
+        let cliffs = |x: i32, z: i32| ((x * 7 + z * 13).rem_euclid(11) * 3) as f32;
+        let target = chunk(1, 0, 2);
+
+        let eroded_columns = |chunks: &[ChunkPos]| {
+            let plan = plan_generation_batches(chunks, CHUNK_SIZE, &config);
+            let batch = plan
+                .batches
+                .iter()
+                .find(|batch| batch.chunks.contains(&target))
+                .expect("target is planned");
+            let raw = evaluate_halo_heights(&batch.rect, cliffs);
+            let eroded = erode_halo_heights(&raw, &batch.rect, &config);
+            let columns = chunk_halo_columns(batch, target, CHUNK_SIZE);
+            let base = (columns.base - batch.offset) as usize;
+            let stride = columns.stride as usize;
+            let size = CHUNK_SIZE as usize;
+            let read = |heights: &[f32]| {
+                (0..size)
+                    .flat_map(|z| (0..size).map(move |x| base + x + z * stride))
+                    .map(|index| heights[index])
+                    .collect::<Vec<f32>>()
+            };
+            (read(&raw), read(&eroded))
+        };
+
+        let (alone_raw, alone) = eroded_columns(&[target]);
+        let (_, with_neighbours) =
+            eroded_columns(&[chunk(0, 0, 2), target, chunk(1, 0, 3), chunk(2, 0, 1)]);
+        assert_ne!(alone, alone_raw, "erosion should change the cliffs");
+        assert_eq!(alone, with_neighbours);
+    }
+}
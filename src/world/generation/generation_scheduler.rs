@@ -0,0 +1,73 @@
+//! Chunk generation scheduling on the thread pool
+//!
+//! Chunks near the camera go to the high lane so they appear first; distant
+//! chunks go to the background lane next to persistence I/O. Neither lane
+//! competes with frame-critical GPU command submission.
+
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+
+use super::WorldGenerator;
+use crate::constants::thread_pool_constants::NEAR_CHUNK_GENERATION_RADIUS;
+use crate::thread_pool::{
+    submit_with_priority, GpuThreadPoolData, GpuWorkloadCategory, TaskPriority,
+};
+use crate::world::core::ChunkPos;
+use crate::world::storage::TempChunk;
+
+/// Lane for generating `chunk_pos` while the camera is in `camera_chunk`
+pub fn chunk_generation_priority(chunk_pos: ChunkPos, camera_chunk: ChunkPos) -> TaskPriority {
+    let distance = (chunk_pos.x - camera_chunk.x)
+        .abs()
+        .max((chunk_pos.y - camera_chunk.y).abs())
+        .max((chunk_pos.z - camera_chunk.z).abs());
+
+    if distance <= NEAR_CHUNK_GENERATION_RADIUS {
+        TaskPriority::High
+    } else {
+        TaskPriority::Background
+    }
+}
+
+/// Queue generation of `chunks`, nearest first; finished chunks are sent to `results`
+///
+/// Returns the chunks queued. Chunks rejected by a full lane are skipped
+/// and can be rescheduled on a later frame.
+pub fn schedule_chunk_generation(
+    pool: &GpuThreadPoolData,
+    generator: &Arc<dyn WorldGenerator>,
+    chunks: &[ChunkPos],
+    camera_chunk: ChunkPos,
+    chunk_size: u32,
+    results: &Sender<TempChunk>,
+) -> Vec<ChunkPos> {
+    let mut ordered = chunks.to_vec();
+    ordered.sort_by_key(|pos| pos.distance_squared_to(camera_chunk));
+
+    let mut scheduled = Vec::new();
+    for chunk_pos in ordered {
+        let generator = Arc::clone(generator);
+        let results = results.clone();
+        let priority = chunk_generation_priority(chunk_pos, camera_chunk);
+
+        let submitted =
+            submit_with_priority(pool, priority, GpuWorkloadCategory::Generation, move || {
+                let chunk = generator.generate_chunk(chunk_pos, chunk_size);
+                // The receiver may be gone if the world was unloaded meanwhile
+                let _ = results.send(chunk);
+            });
+
+        match submitted {
+            Ok(()) => scheduled.push(chunk_pos),
+            Err(e) => {
+                log::debug!(
+                    "[GenerationScheduler] Deferred chunk {:?}: {}",
+                    chunk_pos,
+                    e
+                );
+            }
+        }
+    }
+
+    scheduled
+}
@@ -0,0 +1,117 @@
+//! Golden Data - Pure DOP
+//!
+//! Reference chunks recorded from the generator for a fixed set of seeds
+//! and positions. Regenerating them and diffing against the recorded
+//! blocks catches accidental terrain drift when the generator is
+//! refactored. Operations live in golden_operations.rs.
+//!
+//! NO METHODS - just data.
+
+use crate::world::core::{BlockId, ChunkPos};
+use serde::{Deserialize, Serialize};
+
+/// One seed/position pair that has a golden file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GoldenFixture {
+    pub seed: u32,
+    pub chunk_pos: ChunkPos,
+    /// Edge of the chunk (voxels)
+    pub chunk_size: u32,
+}
+
+/// Run of identical blocks in chunk storage order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoldenRun {
+    pub block: u16,
+    pub count: u32,
+}
+
+/// Recorded generator output for one fixture
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoldenChunk {
+    pub format_version: u32,
+    pub fixture: GoldenFixture,
+    /// FNV-1a hash of the block ids, for a quick equality check
+    pub block_hash: u64,
+    pub runs: Vec<GoldenRun>,
+}
+
+/// How much drift a verification accepts
+#[derive(Debug, Clone, Default)]
+pub struct GoldenTolerance {
+    /// Differing voxels per fixture that still count as a pass
+    pub max_mismatched_voxels: usize,
+    /// Voxels where either side is one of these are not compared, for
+    /// blocks whose placement is being reworked on purpose
+    pub ignored_blocks: Vec<BlockId>,
+}
+
+/// Verification settings
+#[derive(Debug, Clone, Default)]
+pub struct GoldenConfig {
+    pub tolerance: GoldenTolerance,
+    /// Rewrite drifted or missing golden files instead of failing, after
+    /// an intentional generation change
+    pub bless: bool,
+}
+
+/// One voxel that differs from the golden data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GoldenMismatch {
+    /// Position inside the chunk (x, y, z)
+    pub local: [u32; 3],
+    pub expected: BlockId,
+    pub actual: BlockId,
+}
+
+/// Difference between a regenerated chunk and its golden data
+#[derive(Debug, Clone, Default)]
+pub struct GoldenDiff {
+    /// Compared voxels that differ
+    pub mismatched_voxels: usize,
+    /// First few differences, in storage order
+    pub mismatches: Vec<GoldenMismatch>,
+    /// Regenerated chunk has a different voxel count; nothing was compared
+    pub size_mismatch: bool,
+}
+
+/// Result of verifying one fixture
+#[derive(Debug, Clone)]
+pub enum GoldenOutcome {
+    Matched,
+    /// Differs, but no more than the tolerance allows
+    WithinTolerance(GoldenDiff),
+    Drifted(GoldenDiff),
+    /// No golden file recorded for the fixture
+    Missing,
+    /// Golden file was (re)written from the current generator
+    Blessed,
+}
+
+/// Outcome for one fixture
+#[derive(Debug, Clone)]
+pub struct GoldenFixtureResult {
+    pub fixture: GoldenFixture,
+    pub outcome: GoldenOutcome,
+}
+
+/// Outcome of a verification run
+#[derive(Debug, Clone, Default)]
+pub struct GoldenReport {
+    pub results: Vec<GoldenFixtureResult>,
+}
+
+/// Golden file failures
+#[derive(Debug, thiserror::Error)]
+pub enum GoldenError {
+    #[error("Golden file I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Golden file is malformed: {0}")]
+    Format(String),
+
+    #[error("Golden file format version {found} is not supported (expected {expected})")]
+    Version { found: u32, expected: u32 },
+}
+
+pub type GoldenResult<T> = Result<T, GoldenError>;
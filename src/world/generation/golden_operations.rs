@@ -0,0 +1,369 @@
+//! Golden Data Operations - Pure DOP Functions
+//!
+//! Record generator output for the fixtures with `bless` set, then
+//! `verify_golden_fixtures` on every run to regenerate and diff. Golden
+//! files are run-length encoded, bincode serialized and deflated.
+
+use super::golden_data::{
+    GoldenChunk, GoldenConfig, GoldenDiff, GoldenError, GoldenFixture, GoldenFixtureResult,
+    GoldenMismatch, GoldenOutcome, GoldenReport, GoldenResult, GoldenRun, GoldenTolerance,
+};
+use crate::constants::golden_worldgen::{
+    BLESS_ENV_VAR, GOLDEN_CHUNK_SIZE, GOLDEN_FORMAT_VERSION, MAX_REPORTED_MISMATCHES,
+    TOLERANCE_ENV_VAR,
+};
+use crate::world::core::{BlockId, ChunkPos};
+use crate::world::storage::TempChunk;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Seeds every default fixture position is recorded for
+const FIXTURE_SEEDS: [u32; 2] = [12345, 42];
+
+/// Chunks covering solid ground, the surface band, off-origin surface in
+/// each direction and open sky
+const FIXTURE_POSITIONS: [[i32; 3]; 5] = [[0, 1, 0], [0, 2, 0], [5, 2, -3], [-9, 2, 14], [0, 4, 0]];
+
+/// The fixture set the repository's golden files cover
+pub fn default_golden_fixtures() -> Vec<GoldenFixture> {
+    FIXTURE_SEEDS
+        .iter()
+        .flat_map(|&seed| {
+            FIXTURE_POSITIONS
+                .iter()
+                .map(move |&[x, y, z]| GoldenFixture {
+                    seed,
+                    chunk_pos: ChunkPos { x, y, z },
+                    chunk_size: GOLDEN_CHUNK_SIZE,
+                })
+        })
+        .collect()
+}
+
+/// Settings from the environment: the bless switch and a per-fixture
+/// voxel tolerance
+pub fn golden_config_from_env() -> GoldenConfig {
+    let bless = std::env::var(BLESS_ENV_VAR).is_ok_and(|value| !value.is_empty() && value != "0");
+    let max_mismatched_voxels = std::env::var(TOLERANCE_ENV_VAR)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0);
+    GoldenConfig {
+        tolerance: GoldenTolerance {
+            max_mismatched_voxels,
+            ignored_blocks: Vec::new(),
+        },
+        bless,
+    }
+}
+
+/// File name of a fixture's golden data
+pub fn golden_file_name(fixture: &GoldenFixture) -> String {
+    let ChunkPos { x, y, z } = fixture.chunk_pos;
+    format!(
+        "seed{}_{}_{}_{}_s{}.golden",
+        fixture.seed, x, y, z, fixture.chunk_size
+    )
+}
+
+/// FNV-1a over the block ids
+pub fn hash_blocks(blocks: &[BlockId]) -> u64 {
+    blocks.iter().fold(0xcbf2_9ce4_8422_2325, |hash, block| {
+        block.0.to_le_bytes().iter().fold(hash, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+    })
+}
+
+/// Record a generated chunk as golden data
+pub fn encode_golden_chunk(fixture: GoldenFixture, blocks: &[BlockId]) -> GoldenChunk {
+    let mut runs: Vec<GoldenRun> = Vec::new();
+    for block in blocks {
+        match runs.last_mut() {
+            Some(run) if run.block == block.0 => run.count += 1,
+            _ => runs.push(GoldenRun {
+                block: block.0,
+                count: 1,
+            }),
+        }
+    }
+    GoldenChunk {
+        format_version: GOLDEN_FORMAT_VERSION,
+        fixture,
+        block_hash: hash_blocks(blocks),
+        runs,
+    }
+}
+
+/// Expand golden data back into block ids in chunk storage order
+pub fn decode_golden_blocks(golden: &GoldenChunk) -> Vec<BlockId> {
+    golden
+        .runs
+        .iter()
+        .flat_map(|run| std::iter::repeat_n(BlockId(run.block), run.count as usize))
+        .collect()
+}
+
+/// Compressed file contents of golden data
+pub fn serialize_golden_chunk(golden: &GoldenChunk) -> GoldenResult<Vec<u8>> {
+    let encoded = bincode::serialize(golden).map_err(|e| GoldenError::Format(e.to_string()))?;
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&encoded)?;
+    Ok(encoder.finish()?)
+}
+
+/// Golden data from compressed file contents
+pub fn deserialize_golden_chunk(bytes: &[u8]) -> GoldenResult<GoldenChunk> {
+    let mut encoded = Vec::new();
+    DeflateDecoder::new(bytes).read_to_end(&mut encoded)?;
+    let golden: GoldenChunk =
+        bincode::deserialize(&encoded).map_err(|e| GoldenError::Format(e.to_string()))?;
+    if golden.format_version != GOLDEN_FORMAT_VERSION {
+        return Err(GoldenError::Version {
+            found: golden.format_version,
+            expected: GOLDEN_FORMAT_VERSION,
+        });
+    }
+    Ok(golden)
+}
+
+/// Write a fixture's golden file into `dir`, creating it if needed
+pub fn write_golden_chunk(dir: &Path, golden: &GoldenChunk) -> GoldenResult<()> {
+    std::fs::create_dir_all(dir)?;
+    let bytes = serialize_golden_chunk(golden)?;
+    std::fs::write(dir.join(golden_file_name(&golden.fixture)), bytes)?;
+    Ok(())
+}
+
+/// Read a fixture's golden file from `dir`; `None` if it was never recorded
+pub fn read_golden_chunk(dir: &Path, fixture: &GoldenFixture) -> GoldenResult<Option<GoldenChunk>> {
+    let path = dir.join(golden_file_name(fixture));
+    if !path.exists() {
+        return Ok(None);
+    }
+    let golden = deserialize_golden_chunk(&std::fs::read(&path)?)?;
+    if golden.fixture != *fixture {
+        return Err(GoldenError::Format(format!(
+            "{} records fixture {:?}",
+            path.display(),
+            golden.fixture
+        )));
+    }
+    Ok(Some(golden))
+}
+
+/// Compare regenerated blocks with golden data, skipping ignored blocks
+pub fn diff_golden_chunk(
+    golden: &GoldenChunk,
+    blocks: &[BlockId],
+    tolerance: &GoldenTolerance,
+) -> GoldenDiff {
+    let mut diff = GoldenDiff::default();
+    if golden.block_hash == hash_blocks(blocks) {
+        return diff;
+    }
+
+    let expected = decode_golden_blocks(golden);
+    if expected.len() != blocks.len() {
+        diff.size_mismatch = true;
+        return diff;
+    }
+
+    let size = golden.fixture.chunk_size.max(1);
+    for (index, (&expected, &actual)) in expected.iter().zip(blocks).enumerate() {
+        if expected == actual
+            || tolerance.ignored_blocks.contains(&expected)
+            || tolerance.ignored_blocks.contains(&actual)
+        {
+            continue;
+        }
+        diff.mismatched_voxels += 1;
+        if diff.mismatches.len() < MAX_REPORTED_MISMATCHES {
+            let index = index as u32;
+            diff.mismatches.push(GoldenMismatch {
+                local: [index % size, index / (size * size), (index / size) % size],
+                expected,
+                actual,
+            });
+        }
+    }
+    diff
+}
+
+/// Regenerate every fixture with `generate` and diff it against the golden
+/// files in `dir`. With `bless` set, drifted and missing files are
+/// rewritten from the regenerated chunks instead.
+pub fn verify_golden_fixtures(
+    dir: &Path,
+    fixtures: &[GoldenFixture],
+    config: &GoldenConfig,
+    mut generate: impl FnMut(&GoldenFixture) -> TempChunk,
+) -> GoldenResult<GoldenReport> {
+    let mut report = GoldenReport::default();
+    for fixture in fixtures {
+        let chunk = generate(fixture);
+        let golden = match read_golden_chunk(dir, fixture) {
+            Ok(golden) => golden,
+            // Unreadable files are replaced when blessing
+            Err(_) if config.bless => None,
+            Err(e) => return Err(e),
+        };
+
+        let outcome = match golden {
+            None if config.bless => {
+                write_golden_chunk(dir, &encode_golden_chunk(*fixture, &chunk.blocks))?;
+                GoldenOutcome::Blessed
+            }
+            None => GoldenOutcome::Missing,
+            Some(golden) => {
+                let diff = diff_golden_chunk(&golden, &chunk.blocks, &config.tolerance);
+                if diff.mismatched_voxels == 0 && !diff.size_mismatch {
+                    GoldenOutcome::Matched
+                } else if config.bless {
+                    write_golden_chunk(dir, &encode_golden_chunk(*fixture, &chunk.blocks))?;
+                    GoldenOutcome::Blessed
+                } else if !diff.size_mismatch
+                    && diff.mismatched_voxels <= config.tolerance.max_mismatched_voxels
+                {
+                    GoldenOutcome::WithinTolerance(diff)
+                } else {
+                    GoldenOutcome::Drifted(diff)
+                }
+            }
+        };
+        report.results.push(GoldenFixtureResult {
+            fixture: *fixture,
+            outcome,
+        });
+    }
+    Ok(report)
+}
+
+/// Whether no fixture drifted or is missing its golden file
+pub fn golden_report_passed(report: &GoldenReport) -> bool {
+    report.results.iter().all(|result| {
+        !matches!(
+            result.outcome,
+            GoldenOutcome::Drifted(_) | GoldenOutcome::Missing
+        )
+    })
+}
+
+/// Human-readable summary of the failing fixtures
+pub fn format_golden_report(report: &GoldenReport) -> String {
+    let mut summary = String::new();
+    for result in &report.results {
+        let name = golden_file_name(&result.fixture);
+        match &result.outcome {
+            GoldenOutcome::Missing => {
+                summary.push_str(&format!("{}: no golden file recorded\n", name));
+            }
+            GoldenOutcome::Drifted(diff) if diff.size_mismatch => {
+                summary.push_str(&format!("{}: chunk size changed\n", name));
+            }
+            GoldenOutcome::Drifted(diff) => {
+                summary.push_str(&format!(
+                    "{}: {} voxels differ\n",
+                    name, diff.mismatched_voxels
+                ));
+                for mismatch in &diff.mismatches {
+                    summary.push_str(&format!(
+                        "    at {:?}: expected {:?}, got {:?}\n",
+                        mismatch.local, mismatch.expected, mismatch.actual
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    if !summary.is_empty() {
+        summary.push_str(&format!(
+            "Rerun with {}=1 if the generation change is intentional\n",
+            BLESS_ENV_VAR
+        ));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> GoldenFixture {
+        GoldenFixture {
+            seed: 7,
+            chunk_pos: ChunkPos { x: 1, y: 2, z: 3 },
+            chunk_size: 4,
+        }
+    }
+
+    fn layered_chunk() -> TempChunk {
+        let mut chunk = TempChunk::new_empty(fixture().chunk_pos, 4);
+        for x in 0..4 {
+            for z in 0..4 {
+                chunk.set_block(x, 0, z, BlockId(1));
+                chunk.set_block(x, 1, z, BlockId(3));
+            }
+        }
+        chunk
+    }
+
+    #[test]
+    fn test_golden_chunk_round_trip_and_tolerance() {
+        let chunk = layered_chunk();
+        let golden = encode_golden_chunk(fixture(), &chunk.blocks);
+        assert_eq!(golden.runs.len(), 3);
+
+        let bytes = serialize_golden_chunk(&golden).expect("serialize");
+        let restored = deserialize_golden_chunk(&bytes).expect("deserialize");
+        assert_eq!(decode_golden_blocks(&restored), chunk.blocks);
+
+        let mut drifted = chunk.clone();
+        drifted.set_block(2, 1, 3, BlockId(5));
+        let diff = diff_golden_chunk(&golden, &drifted.blocks, &GoldenTolerance::default());
+        assert_eq!(diff.mismatched_voxels, 1);
+        assert_eq!(diff.mismatches[0].local, [2, 1, 3]);
+
+        let ignoring = GoldenTolerance {
+            max_mismatched_voxels: 0,
+            ignored_blocks: vec![BlockId(5)],
+        };
+        assert_eq!(
+            diff_golden_chunk(&golden, &drifted.blocks, &ignoring).mismatched_voxels,
+            0
+        );
+    }
+
+    #[test]
+    fn test_verify_blesses_then_detects_drift() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let fixtures = [fixture()];
+        let mut config = GoldenConfig::default();
+
+        let report = verify_golden_fixtures(dir.path(), &fixtures, &config, |_| layered_chunk())
+            .expect("verify");
+        assert!(!golden_report_passed(&report));
+
+        config.bless = true;
+        verify_golden_fixtures(dir.path(), &fixtures, &config, |_| layered_chunk()).expect("bless");
+        config.bless = false;
+        let report = verify_golden_fixtures(dir.path(), &fixtures, &config, |_| layered_chunk())
+            .expect("verify");
+        assert!(matches!(report.results[0].outcome, GoldenOutcome::Matched));
+
+        let drift = |_: &GoldenFixture| {
+            let mut chunk = layered_chunk();
+            chunk.set_block(0, 3, 0, BlockId(1));
+            chunk
+        };
+        let report = verify_golden_fixtures(dir.path(), &fixtures, &config, drift).expect("verify");
+        assert!(!golden_report_passed(&report));
+
+        config.tolerance.max_mismatched_voxels = 1;
+        let report = verify_golden_fixtures(dir.path(), &fixtures, &config, drift).expect("verify");
+        assert!(golden_report_passed(&report));
+    }
+}
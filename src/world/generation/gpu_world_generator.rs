@@ -1,13 +1,22 @@
 //! GPU world generator wrapper that implements the WorldGenerator trait
 
-use crate::gpu::{GpuError, GpuErrorRecovery, GpuRecoveryError};
+use crate::constants::terrain::{DRY_SEA_LEVEL, SPARSE_AIR_HEIGHT};
+use crate::gpu::{GpuError, GpuErrorRecovery, GpuRecoveryError, TerrainParamsSOA};
 use crate::world::{
+    compute::{
+        is_chunk_suspicious, ChunkConnectivityCompute, ChunkVerificationCompute,
+        ChunkVerificationReport, ColumnHeightmapCompute, MAX_REGENERATION_ATTEMPTS,
+    },
     core::{BlockId, ChunkPos},
     generation::{TerrainGeneratorSOA, WorldGenerator},
-    storage::{TempChunk, WorldBuffer},
+    storage::{TempChunk, VoxelData, WorldBuffer},
 };
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
+/// Regenerations issued per chunk
+type RegenerationAttempts = HashMap<ChunkPos, u32>;
+
 /// GPU world generator that wraps TerrainGeneratorSOA to implement WorldGenerator trait
 ///
 /// This is a wrapper that defers actual GPU generation until a proper command encoder
@@ -17,8 +26,16 @@ use std::sync::{Arc, Mutex};
 pub struct GpuWorldGenerator {
     terrain_generator: Arc<TerrainGeneratorSOA>,
     device: Arc<wgpu::Device>,
+    queue: Arc<wgpu::Queue>,
     world_buffer: Arc<Mutex<WorldBuffer>>,
     error_recovery: Arc<GpuErrorRecovery>,
+    /// Optional post-generation connectivity pass for cave culling
+    connectivity: Option<Arc<ChunkConnectivityCompute>>,
+    /// Optional post-generation check for chunks left empty by a failed dispatch
+    verification: Option<Arc<ChunkVerificationCompute>>,
+    /// Optional post-generation column heightmap pass
+    heightmap: Option<Arc<ColumnHeightmapCompute>>,
+    regeneration_attempts: parking_lot::Mutex<RegenerationAttempts>,
 }
 
 impl GpuWorldGenerator {
@@ -29,16 +46,41 @@ impl GpuWorldGenerator {
         queue: Arc<wgpu::Queue>,
         world_buffer: Arc<Mutex<WorldBuffer>>,
     ) -> Self {
-        let error_recovery = Arc::new(GpuErrorRecovery::new(device.clone(), queue));
+        let error_recovery = Arc::new(GpuErrorRecovery::new(device.clone(), queue.clone()));
 
         Self {
             terrain_generator,
             device,
+            queue,
             world_buffer,
             error_recovery,
+            connectivity: None,
+            verification: None,
+            heightmap: None,
+            regeneration_attempts: parking_lot::Mutex::new(HashMap::new()),
         }
     }
 
+    /// Compute chunk face connectivity after each generation batch
+    pub fn with_connectivity(mut self, connectivity: Arc<ChunkConnectivityCompute>) -> Self {
+        self.connectivity = Some(connectivity);
+        self
+    }
+
+    /// Count non-air voxels after each generation batch and regenerate
+    /// chunks that came back empty (see `poll_verification`)
+    pub fn with_verification(mut self, verification: Arc<ChunkVerificationCompute>) -> Self {
+        self.verification = Some(verification);
+        self
+    }
+
+    /// Record column tops after each generation batch; results are
+    /// collected with `ColumnHeightmapCompute::poll_results`
+    pub fn with_heightmap(mut self, heightmap: Arc<ColumnHeightmapCompute>) -> Self {
+        self.heightmap = Some(heightmap);
+        self
+    }
+
     /// Generate chunks on GPU when a command encoder is available
     /// This is the proper way to use GPU generation
     pub fn generate_chunks_with_encoder(
@@ -57,6 +99,14 @@ impl GpuWorldGenerator {
             return Err(GpuError::InvalidEncoder);
         }
 
+        // Chunks entirely above the terrain are pure air: keep them sparse
+        // instead of generating them into a full slot
+        let chunk_positions = self.filter_sparse_air_chunks(chunk_positions);
+        let chunk_positions = chunk_positions.as_slice();
+        if chunk_positions.is_empty() {
+            return Ok(());
+        }
+
         // Execute terrain generation with error recovery
         let result = self.error_recovery.execute_with_recovery(|| {
             let mut world_buffer = match self.world_buffer.lock() {
@@ -73,6 +123,36 @@ impl GpuWorldGenerator {
                 })
         });
 
+        // Connectivity reads the freshly generated voxels, so it is encoded
+        // after generation. Results are collected via poll_results() once
+        // the encoder has been submitted.
+        if result.is_ok() {
+            if let Some(connectivity) = &self.connectivity {
+                connectivity.queue_chunks(chunk_positions);
+                let world_buffer = match self.world_buffer.lock() {
+                    Ok(guard) => guard,
+                    Err(poisoned) => poisoned.into_inner(),
+                };
+                connectivity.encode_batch(encoder, &self.queue, &world_buffer);
+            }
+            if let Some(verification) = &self.verification {
+                verification.queue_chunks(chunk_positions);
+                let world_buffer = match self.world_buffer.lock() {
+                    Ok(guard) => guard,
+                    Err(poisoned) => poisoned.into_inner(),
+                };
+                verification.encode_batch(encoder, &self.queue, &world_buffer);
+            }
+            if let Some(heightmap) = &self.heightmap {
+                heightmap.queue_chunks(chunk_positions);
+                let world_buffer = match self.world_buffer.lock() {
+                    Ok(guard) => guard,
+                    Err(poisoned) => poisoned.into_inner(),
+                };
+                heightmap.encode_batch(encoder, &self.queue, &world_buffer);
+            }
+        }
+
         match result {
             Ok(_metadata_buffer) => Ok(()),
             Err(GpuRecoveryError::DeviceLost) => Err(GpuError::DeviceLost),
@@ -91,55 +171,95 @@ impl GpuWorldGenerator {
         }
     }
 
-    /// Generate a chunk using CPU fallback with proper terrain logic
-    fn generate_cpu_fallback(&self, chunk_pos: ChunkPos, chunk_size: u32) -> TempChunk {
-        use crate::world::core::{BlockId, VoxelPos};
-        
-        let mut chunk = TempChunk::new_empty(chunk_pos, chunk_size);
-        
-        // Use the same terrain generation logic as in the GPU shader
-        // TERRAIN_THRESHOLD = 64
-        const TERRAIN_THRESHOLD: i32 = 64;
-        
-        let world_x_base = chunk_pos.x * chunk_size as i32;
-        let world_y_base = chunk_pos.y * chunk_size as i32;
-        let world_z_base = chunk_pos.z * chunk_size as i32;
-        
-        for x in 0..chunk_size {
-            for z in 0..chunk_size {
-                let world_x = world_x_base + x as i32;
-                let world_z = world_z_base + z as i32;
-                
-                // Calculate terrain height with variation (matching GPU shader)
-                let height_variation = (world_x as f32 * 0.05).sin() * 5.0 + (world_z as f32 * 0.05).cos() * 5.0;
-                let surface_height = TERRAIN_THRESHOLD as f32 + height_variation;
-                
-                for y in 0..chunk_size {
-                    let world_y = world_y_base + y as i32;
-                    
-                    let block_id = if world_y < surface_height as i32 - 3 {
-                        // Deep underground: stone
-                        BlockId(1) // BLOCK_STONE
-                    } else if world_y < surface_height as i32 {
-                        // Just below surface: stone with occasional air (caves)
-                        let cave_noise_val = ((world_x + world_y * 7 + world_z * 13) % 100) as f32 / 100.0;
-                        if cave_noise_val > 0.85 && world_y < surface_height as i32 - 5 {
-                            BlockId(0) // BLOCK_AIR - cave
-                        } else {
-                            BlockId(1) // BLOCK_STONE
-                        }
-                    } else if world_y <= surface_height as i32 {
-                        // Surface layer: grass
-                        BlockId(3) // BLOCK_GRASS
-                    } else {
-                        // Above surface: air
-                        BlockId(0) // BLOCK_AIR
-                    };
-                    
-                    chunk.set_block(x, y, z, block_id);
+    /// Collect verification counts and regenerate suspicious chunks into
+    /// `encoder`.
+    ///
+    /// Call once per frame after the encoder used for generation has been
+    /// submitted. A chunk is regenerated up to `MAX_REGENERATION_ATTEMPTS`
+    /// times; after that it is left as is and counted as abandoned.
+    pub fn poll_verification(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Result<ChunkVerificationReport, GpuError> {
+        let mut report = ChunkVerificationReport::default();
+        let Some(verification) = &self.verification else {
+            return Ok(report);
+        };
+
+        let layout = verification.chunk_layout();
+        let mut regenerate = Vec::new();
+        {
+            let mut attempts = self.regeneration_attempts.lock();
+            for (chunk_pos, non_air_voxels) in verification.poll_results() {
+                report.chunks_verified += 1;
+                if !is_chunk_suspicious(chunk_pos, layout, non_air_voxels) {
+                    attempts.remove(&chunk_pos);
+                    continue;
+                }
+
+                report.suspicious_chunks += 1;
+                let count = attempts.entry(chunk_pos).or_insert(0);
+                if *count < MAX_REGENERATION_ATTEMPTS {
+                    *count += 1;
+                    log::warn!(
+                        "[GpuWorldGenerator] Chunk {:?} generated with only {} non-air voxels, regenerating (attempt {})",
+                        chunk_pos,
+                        non_air_voxels,
+                        count
+                    );
+                    regenerate.push(chunk_pos);
+                } else {
+                    log::error!(
+                        "[GpuWorldGenerator] Chunk {:?} still empty after {} regenerations, giving up",
+                        chunk_pos,
+                        MAX_REGENERATION_ATTEMPTS
+                    );
+                    attempts.remove(&chunk_pos);
+                    report.chunks_abandoned += 1;
                 }
             }
         }
+
+        if regenerate.is_empty() {
+            // Drain chunks queued beyond the previous batch
+            let world_buffer = match self.world_buffer.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            verification.encode_batch(encoder, &self.queue, &world_buffer);
+        } else {
+            // Regeneration queues the chunks for verification again
+            self.generate_chunks_with_encoder(&regenerate, encoder)?;
+            report.chunks_regenerated = regenerate.len() as u64;
+        }
+
+        Ok(report)
+    }
+
+    /// Store chunks above `SPARSE_AIR_HEIGHT` as sparse air; returns the rest
+    fn filter_sparse_air_chunks(&self, chunk_positions: &[ChunkPos]) -> Vec<ChunkPos> {
+        let world_buffer = match self.world_buffer.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if !world_buffer.sparse_chunks_enabled() {
+            return chunk_positions.to_vec();
+        }
+
+        let chunk_size = world_buffer.chunk_layout().size as i32;
+        chunk_positions
+            .iter()
+            .copied()
+            .filter(|pos| {
+                let above_terrain = pos.y * chunk_size > SPARSE_AIR_HEIGHT;
+                !(above_terrain && world_buffer.store_sparse_chunk(*pos, VoxelData::AIR))
+            })
+            .collect()
+    }
+
+    /// Generate a chunk using CPU fallback with proper terrain logic
+    fn generate_cpu_fallback(&self, chunk_pos: ChunkPos, chunk_size: u32) -> TempChunk {
+        let chunk = generate_reference_chunk(chunk_pos, chunk_size);
         
         log::info!("CPU fallback generated terrain chunk {:?} with surface at ~{}", chunk_pos, TERRAIN_THRESHOLD);
         chunk
@@ -191,4 +311,95 @@ impl WorldGenerator for GpuWorldGenerator {
     fn get_world_buffer(&self) -> Option<Arc<Mutex<WorldBuffer>>> {
         Some(self.world_buffer.clone())
     }
+
+    fn set_terrain_params(&self, params: &TerrainParamsSOA) {
+        if let Err(e) = self.terrain_generator.update_params_soa(params) {
+            log::warn!("[GpuWorldGenerator] Terrain params not updated: {:?}", e);
+        }
+    }
+}
+
+/// Surface height of the CPU fallback terrain (matches the GPU shader)
+const TERRAIN_THRESHOLD: i32 = 64;
+
+/// The reference terrain as a generator, for engines without a GPU
+/// generator or a game-supplied one
+pub struct ReferenceGenerator;
+
+impl WorldGenerator for ReferenceGenerator {
+    fn generate_chunk(&self, chunk_pos: ChunkPos, chunk_size: u32) -> TempChunk {
+        generate_reference_chunk(chunk_pos, chunk_size)
+    }
+
+    fn get_surface_height(&self, world_x: f64, world_z: f64) -> i32 {
+        reference_surface_height(world_x.floor() as i32, world_z.floor() as i32)
+    }
+
+    fn is_gpu(&self) -> bool {
+        false
+    }
+
+    /// The reference terrain is unseeded and has no water
+    fn terrain_params(&self) -> Option<TerrainParamsSOA> {
+        Some(TerrainParamsSOA {
+            sea_level: DRY_SEA_LEVEL,
+            ..Default::default()
+        })
+    }
+}
+
+/// Top solid voxel of the reference terrain column at `world_x`, `world_z`
+pub fn reference_surface_height(world_x: i32, world_z: i32) -> i32 {
+    // Terrain height with variation (matching GPU shader)
+    let height_variation =
+        (world_x as f32 * 0.05).sin() * 5.0 + (world_z as f32 * 0.05).cos() * 5.0;
+    (TERRAIN_THRESHOLD as f32 + height_variation) as i32
+}
+
+/// Deterministic CPU terrain used when the GPU path is unavailable.
+/// Also the reference the world generation golden data is recorded from.
+pub fn generate_reference_chunk(chunk_pos: ChunkPos, chunk_size: u32) -> TempChunk {
+    use crate::world::core::{BlockId, VoxelPos};
+    
+    let mut chunk = TempChunk::new_empty(chunk_pos, chunk_size);
+    
+    // Use the same terrain generation logic as in the GPU shader
+    let world_x_base = chunk_pos.x * chunk_size as i32;
+    let world_y_base = chunk_pos.y * chunk_size as i32;
+    let world_z_base = chunk_pos.z * chunk_size as i32;
+    
+    for x in 0..chunk_size {
+        for z in 0..chunk_size {
+            let world_x = world_x_base + x as i32;
+            let world_z = world_z_base + z as i32;
+            
+            let surface_height = reference_surface_height(world_x, world_z);
+            
+            for y in 0..chunk_size {
+                let world_y = world_y_base + y as i32;
+                
+                let block_id = if world_y < surface_height - 3 {
+                    // Deep underground: stone
+                    BlockId(1) // BLOCK_STONE
+                } else if world_y < surface_height {
+                    // Just below surface: stone with occasional air (caves)
+                    let cave_noise_val = ((world_x + world_y * 7 + world_z * 13) % 100) as f32 / 100.0;
+                    if cave_noise_val > 0.85 && world_y < surface_height - 5 {
+                        BlockId(0) // BLOCK_AIR - cave
+                    } else {
+                        BlockId(1) // BLOCK_STONE
+                    }
+                } else if world_y <= surface_height {
+                    // Surface layer: grass
+                    BlockId(3) // BLOCK_GRASS
+                } else {
+                    // Above surface: air
+                    BlockId(0) // BLOCK_AIR
+                };
+                
+                chunk.set_block(x, y, z, block_id);
+            }
+        }
+    }
+    chunk
 }
@@ -6,22 +6,84 @@
 use crate::constants::terrain::SEA_LEVEL;
 
 mod caves;
+mod decoration_queue_data;
+mod decoration_queue_operations;
+mod generation_halo_data;
+mod generation_halo_gpu;
+mod generation_halo_operations;
+mod generation_scheduler;
+mod golden_data;
+mod golden_operations;
 mod gpu_world_generator;
 mod ores;
+mod preset_data;
+mod preset_operations;
 mod terrain_gpu;
 mod unified_generator;
 
 // GPU generation
-pub use gpu_world_generator::GpuWorldGenerator;
+pub use generation_scheduler::{chunk_generation_priority, schedule_chunk_generation};
+pub use gpu_world_generator::{
+    generate_reference_chunk, reference_surface_height, GpuWorldGenerator, ReferenceGenerator,
+};
 pub use terrain_gpu::{TerrainGeneratorSOA, TerrainGeneratorSOABuilder};
 
+// Grass, flowers and pebbles added after chunks first render
+pub use decoration_queue_data::{
+    DecorationConfig, DecorationPass, DecorationQueueData, DecorationStats, DecorationStep,
+    PendingDecoration, DECORATION_PASSES,
+};
+pub use decoration_queue_operations::{
+    advance_decoration_clock, chunk_decorated, create_decoration_queue, decorate_chunk_pass,
+    default_decoration_config, forget_chunk_decoration, note_chunk_rendered,
+    run_decoration_budget,
+};
+
+// Heightmap halos shared by neighbouring chunks
+pub use generation_halo_data::{
+    GenerationBatch, GenerationHaloConfig, GenerationPlan, GenerationPlanStats, HaloColumns,
+    HaloParamsGpu, HaloRect, HaloTileGpu, PlannedColumns,
+};
+pub use generation_halo_gpu::GenerationHaloCompute;
+pub use generation_halo_operations::{
+    chunk_halo_columns, default_generation_halo_config, erode_halo_heights, erode_halo_step,
+    erosion_halo_config, evaluate_halo_heights, halo_dispatch_extent, halo_params, halo_surface_height, halo_tiles,
+    plan_generation_batches, plan_halo_columns,
+};
+
+// Golden data for catching generation drift
+pub use golden_data::{
+    GoldenChunk, GoldenConfig, GoldenDiff, GoldenError, GoldenFixture, GoldenFixtureResult,
+    GoldenMismatch, GoldenOutcome, GoldenReport, GoldenResult, GoldenRun, GoldenTolerance,
+};
+pub use golden_operations::{
+    decode_golden_blocks, default_golden_fixtures, deserialize_golden_chunk, diff_golden_chunk,
+    encode_golden_chunk, format_golden_report, golden_config_from_env, golden_file_name,
+    golden_report_passed, hash_blocks, read_golden_chunk, serialize_golden_chunk,
+    verify_golden_fixtures, write_golden_chunk,
+};
+
+// Built-in presets for predictable test worlds
+pub use preset_data::{
+    CheckerboardConfig, GenerationStages, PresetGenerator, SuperflatConfig, SuperflatLayer,
+    WorldPreset,
+};
+pub use preset_operations::{
+    checkerboard_block_at, create_preset_generator, default_checkerboard_config,
+    default_generation_stages, default_superflat_config, generate_preset_chunk, preset_biome,
+    preset_for_generator_type, preset_surface_height, superflat_block_at, superflat_surface_y,
+};
+
 // Supporting generators (these should also be GPU-based eventually)
 pub use caves::CaveGenerator;
-pub use ores::OreGenerator;
+pub use ores::{
+    biome_multiplier, default_ore_config, depth_density, BiomeOreMultiplier, DepthCurve,
+    OreConfig, OreDistribution, OreGenerator, VeinShape, VeinSizeRange,
+};
 
 // Unified generation interface
 pub use unified_generator::{
-    BlockIds, GeneratorConfig, GeneratorError, UnifiedGenerator, WorldGenerator,
+    BlockIds, FarSurface, GeneratorConfig, GeneratorError, UnifiedGenerator, WorldGenerator,
 };
 
 /// Create a GPU-based generator
@@ -1,16 +1,309 @@
+//! Ore placement with per-ore distribution curves
+//!
+//! The world is split into cubic cells. Each ore seeds a number of veins per
+//! cell from its depth curve and biome multiplier, using a hash of the world
+//! seed, the ore name and the cell coordinates, so placement is deterministic
+//! and independent of generation order. Registering a new ore never moves
+//! the veins of existing ones.
+
+use super::unified_generator::GeneratorError;
+use crate::constants::ore_generation::{MAX_VEINS_PER_CELL, ORE_CELL_SIZE, STREAK_RADIUS};
 use crate::BlockId;
-use noise::{NoiseFn, Perlin};
+
+/// Vein geometry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VeinShape {
+    /// Roughly spherical cluster
+    Blob,
+    /// Thin randomly oriented line
+    Streak,
+}
+
+/// Ore blocks per vein; sizes cluster around the middle of the range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VeinSizeRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+/// Triangular density curve over world height
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthCurve {
+    pub min_y: i32,
+    /// Height with the highest density
+    pub peak_y: i32,
+    pub max_y: i32,
+    /// Expected veins per cell at `peak_y`
+    pub peak_density: f32,
+}
+
+/// Density multiplier for one biome (biome names match `WeatherManager`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiomeOreMultiplier {
+    pub biome: String,
+    pub multiplier: f32,
+}
+
+/// How one ore is distributed through the world
+#[derive(Debug, Clone, PartialEq)]
+pub struct OreDistribution {
+    /// Unique name; also seeds the ore's placement
+    pub name: String,
+    pub block: BlockId,
+    pub depth: DepthCurve,
+    /// Biomes not listed use a multiplier of 1.0
+    pub biome_multipliers: Vec<BiomeOreMultiplier>,
+    pub shape: VeinShape,
+    pub vein_size: VeinSizeRange,
+    /// Blocks the ore may replace (empty = any)
+    pub host_blocks: Vec<BlockId>,
+}
+
+/// Ore set used by an `OreGenerator`
+#[derive(Debug, Clone, PartialEq)]
+pub struct OreConfig {
+    /// Edge of the cells veins are seeded in (voxels)
+    pub cell_size: i32,
+    /// Checked in order; the first ore containing a voxel wins
+    pub ores: Vec<OreDistribution>,
+}
+
+/// Built-in ores, rarest first so common ores never hide them
+pub fn default_ore_config() -> OreConfig {
+    let ore = |name: &str, block, depth, shape, min, max, biome_multipliers| OreDistribution {
+        name: name.to_string(),
+        block,
+        depth,
+        biome_multipliers,
+        shape,
+        vein_size: VeinSizeRange { min, max },
+        host_blocks: vec![BlockId::STONE],
+    };
+    let biome = |biome: &str, multiplier| BiomeOreMultiplier {
+        biome: biome.to_string(),
+        multiplier,
+    };
+    let curve = |min_y, peak_y, max_y, peak_density| DepthCurve {
+        min_y,
+        peak_y,
+        max_y,
+        peak_density,
+    };
+
+    OreConfig {
+        cell_size: ORE_CELL_SIZE,
+        ores: vec![
+            ore(
+                "diamond_ore",
+                BlockId::DIAMOND_ORE,
+                curve(-64, -16, 16, 0.6),
+                VeinShape::Blob,
+                2,
+                6,
+                Vec::new(),
+            ),
+            ore(
+                "gold_ore",
+                BlockId::GOLD_ORE,
+                curve(-64, 0, 32, 1.2),
+                VeinShape::Blob,
+                3,
+                8,
+                vec![biome("desert", 2.0)],
+            ),
+            ore(
+                "iron_ore",
+                BlockId::IRON_ORE,
+                curve(-64, 16, 64, 3.0),
+                VeinShape::Streak,
+                4,
+                14,
+                vec![biome("tundra", 1.5)],
+            ),
+            ore(
+                "coal_ore",
+                BlockId::COAL_ORE,
+                curve(-64, 48, 128, 6.0),
+                VeinShape::Blob,
+                8,
+                24,
+                vec![biome("taiga", 1.5), biome("rainforest", 0.5)],
+            ),
+        ],
+    }
+}
+
+/// Expected veins per cell at height `y`
+pub fn depth_density(curve: &DepthCurve, y: i32) -> f32 {
+    if y < curve.min_y || y > curve.max_y {
+        return 0.0;
+    }
+    let t = if y <= curve.peak_y {
+        (y - curve.min_y + 1) as f32 / (curve.peak_y - curve.min_y + 1) as f32
+    } else {
+        (curve.max_y - y + 1) as f32 / (curve.max_y - curve.peak_y + 1) as f32
+    };
+    curve.peak_density * t
+}
+
+/// Density multiplier of `ore` in `biome` (1.0 when unknown)
+pub fn biome_multiplier(ore: &OreDistribution, biome: Option<&str>) -> f32 {
+    biome
+        .and_then(|biome| ore.biome_multipliers.iter().find(|m| m.biome == biome))
+        .map(|m| m.multiplier)
+        .unwrap_or(1.0)
+}
+
+fn blob_radius(size: u32) -> f32 {
+    (3.0 * size as f32 / (4.0 * std::f32::consts::PI)).cbrt()
+}
+
+fn streak_length(size: u32) -> f32 {
+    // Voxels within STREAK_RADIUS of a line, per unit of length
+    size as f32 / (std::f32::consts::PI * STREAK_RADIUS * STREAK_RADIUS)
+}
+
+/// Largest distance from a vein's center to one of its voxels
+fn vein_extent(shape: VeinShape, size: u32) -> f32 {
+    // Jitter scales squared distances by up to 1.25
+    let jitter = 1.25f32.sqrt();
+    match shape {
+        VeinShape::Blob => blob_radius(size) * jitter,
+        VeinShape::Streak => streak_length(size) * 0.5 + STREAK_RADIUS * jitter,
+    }
+}
+
+fn validate_ore(ore: &OreDistribution, cell_size: i32) -> Result<(), GeneratorError> {
+    let invalid = |reason: &str| {
+        Err(GeneratorError::ConfigError(format!(
+            "Ore '{}': {}",
+            ore.name, reason
+        )))
+    };
+
+    let depth = &ore.depth;
+    if !(depth.min_y <= depth.peak_y && depth.peak_y <= depth.max_y) {
+        return invalid("depth curve requires min_y <= peak_y <= max_y");
+    }
+    if !depth.peak_density.is_finite() || depth.peak_density < 0.0 {
+        return invalid("peak density must be finite and non-negative");
+    }
+    if ore
+        .biome_multipliers
+        .iter()
+        .any(|m| !m.multiplier.is_finite() || m.multiplier < 0.0)
+    {
+        return invalid("biome multipliers must be finite and non-negative");
+    }
+    if ore.vein_size.min == 0 || ore.vein_size.min > ore.vein_size.max {
+        return invalid("vein size requires 1 <= min <= max");
+    }
+    if vein_extent(ore.shape, ore.vein_size.max) > cell_size as f32 {
+        return invalid("largest vein does not fit in one ore cell");
+    }
+    Ok(())
+}
+
+/// SplitMix64 finalizer
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Uniform value in [0, 1) from the `index`th draw of `hash`
+fn unit(hash: u64, index: u64) -> f32 {
+    (mix(hash ^ index.wrapping_mul(0xD6E8_FEB8_6659_FD93)) >> 40) as f32 / (1u64 << 24) as f32
+}
+
+fn position_hash(base: u64, pos: [i32; 3]) -> u64 {
+    pos.iter()
+        .fold(base, |hash, axis| mix(hash ^ (*axis as u32 as u64)))
+}
+
+/// FNV-1a, so an ore's salt depends only on its name
+fn name_hash(name: &str) -> u64 {
+    name.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+fn dist_sq(a: [f32; 3], b: [f32; 3]) -> f32 {
+    (0..3).map(|i| (a[i] - b[i]) * (a[i] - b[i])).sum()
+}
+
+/// Registered ore with its placement salt
+struct OreEntry {
+    distribution: OreDistribution,
+    salt: u64,
+    /// Vertical reach of the largest vein beyond the depth curve
+    reach: i32,
+}
 
 pub struct OreGenerator {
-    ore_noise: Perlin,
     seed: u32,
+    cell_size: i32,
+    ores: Vec<OreEntry>,
 }
 
 impl OreGenerator {
+    /// Generator with the built-in ores
     pub fn new(seed: u32) -> Self {
-        let ore_noise = Perlin::new(seed.wrapping_add(200)); // Different seed for ores
+        let config = default_ore_config();
+        let mut generator = Self {
+            seed,
+            cell_size: config.cell_size,
+            ores: Vec::new(),
+        };
+        for ore in config.ores {
+            generator.push_ore(ore);
+        }
+        generator
+    }
+
+    /// Generator with a game-defined ore set
+    pub fn with_config(seed: u32, config: OreConfig) -> Result<Self, GeneratorError> {
+        if config.cell_size <= 0 {
+            return Err(GeneratorError::ConfigError(
+                "Ore cell size must be positive".to_string(),
+            ));
+        }
+        let mut generator = Self {
+            seed,
+            cell_size: config.cell_size,
+            ores: Vec::new(),
+        };
+        for ore in config.ores {
+            generator.register_ore(ore)?;
+        }
+        Ok(generator)
+    }
+
+    /// Add a custom ore after the existing ones
+    pub fn register_ore(&mut self, ore: OreDistribution) -> Result<(), GeneratorError> {
+        validate_ore(&ore, self.cell_size)?;
+        if self.ores.iter().any(|e| e.distribution.name == ore.name) {
+            return Err(GeneratorError::ConfigError(format!(
+                "Ore '{}' is already registered",
+                ore.name
+            )));
+        }
+        self.push_ore(ore);
+        Ok(())
+    }
 
-        Self { ore_noise, seed }
+    fn push_ore(&mut self, ore: OreDistribution) {
+        self.ores.push(OreEntry {
+            salt: name_hash(&ore.name),
+            reach: vein_extent(ore.shape, ore.vein_size.max).ceil() as i32,
+            distribution: ore,
+        });
+    }
+
+    /// Registered ores in placement priority order
+    pub fn ores(&self) -> impl Iterator<Item = &OreDistribution> {
+        self.ores.iter().map(|entry| &entry.distribution)
     }
 
     pub fn get_ore_at(
@@ -20,54 +313,218 @@ impl OreGenerator {
         world_z: i32,
         default_block: BlockId,
     ) -> BlockId {
-        // Different ores at different depths
-        if world_y > 128 {
-            return default_block; // No ores in high mountains
-        }
+        self.get_ore_at_in_biome(world_x, world_y, world_z, default_block, None)
+    }
 
-        // Use noise to create ore veins
-        let scale = 0.1;
-        let noise_value = self.ore_noise.get([
-            world_x as f64 * scale,
-            world_y as f64 * scale,
-            world_z as f64 * scale,
-        ]);
-
-        // Coal - common, found at all depths below 128
-        if world_y <= 128 && noise_value > 0.85 {
-            return BlockId(8); // Coal ore
+    /// Ore at a voxel, applying the biome's density multipliers
+    pub fn get_ore_at_in_biome(
+        &self,
+        world_x: i32,
+        world_y: i32,
+        world_z: i32,
+        default_block: BlockId,
+        biome: Option<&str>,
+    ) -> BlockId {
+        let pos = [world_x, world_y, world_z];
+        for entry in &self.ores {
+            let ore = &entry.distribution;
+            if !ore.host_blocks.is_empty() && !ore.host_blocks.contains(&default_block) {
+                continue;
+            }
+            if world_y < ore.depth.min_y - entry.reach || world_y > ore.depth.max_y + entry.reach {
+                continue;
+            }
+            let multiplier = biome_multiplier(ore, biome);
+            if multiplier <= 0.0 {
+                continue;
+            }
+            if self.ore_contains(entry, multiplier, pos) {
+                return ore.block;
+            }
         }
+        default_block
+    }
+
+    /// Whether any vein of `entry` seeded in the cells around `pos` covers it
+    fn ore_contains(&self, entry: &OreEntry, multiplier: f32, pos: [i32; 3]) -> bool {
+        let ore = &entry.distribution;
+        let cs = self.cell_size;
+        let cell = pos.map(|axis| axis.div_euclid(cs));
+        let point = pos.map(|axis| axis as f32 + 0.5);
+        let base = mix(self.seed as u64 ^ entry.salt);
+
+        for dz in -1..=1 {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let c = [cell[0] + dx, cell[1] + dy, cell[2] + dz];
+                    let center_y = c[1] * cs + cs / 2;
+                    let expected = depth_density(&ore.depth, center_y) * multiplier;
+                    if expected <= 0.0 {
+                        continue;
+                    }
 
-        // Iron - less common, below 64
-        if world_y <= 64 && noise_value > 0.9 {
-            return BlockId(9); // Iron ore
+                    let cell_hash = position_hash(base, c);
+                    let mut count = expected.floor() as u32;
+                    if unit(cell_hash, 0) < expected.fract() {
+                        count += 1;
+                    }
+
+                    for vein in 0..count.min(MAX_VEINS_PER_CELL) {
+                        let vein_hash = mix(cell_hash ^ (vein as u64 + 1));
+                        if self.vein_contains(ore, vein_hash, c, point, pos) {
+                            return true;
+                        }
+                    }
+                }
+            }
         }
+        false
+    }
 
-        // Gold - rare, below 32
-        if world_y <= 32 && noise_value > 0.95 {
-            return BlockId(10); // Gold ore
+    fn vein_contains(
+        &self,
+        ore: &OreDistribution,
+        vein_hash: u64,
+        cell: [i32; 3],
+        point: [f32; 3],
+        pos: [i32; 3],
+    ) -> bool {
+        let cs = self.cell_size as f32;
+        let center = [
+            (cell[0] as f32 + unit(vein_hash, 0)) * cs,
+            (cell[1] as f32 + unit(vein_hash, 1)) * cs,
+            (cell[2] as f32 + unit(vein_hash, 2)) * cs,
+        ];
+        if depth_density(&ore.depth, center[1].floor() as i32) <= 0.0 {
+            return false;
         }
 
-        // Diamond - very rare, below 16
-        if world_y <= 16 && noise_value > 0.98 {
-            return BlockId(11); // Diamond ore
+        let span = (ore.vein_size.max - ore.vein_size.min) as f32;
+        let t = (unit(vein_hash, 3) + unit(vein_hash, 4)) * 0.5;
+        let size = ore.vein_size.min + (span * t).round() as u32;
+
+        let extent = vein_extent(ore.shape, size);
+        if dist_sq(point, center) > extent * extent {
+            return false;
         }
 
-        default_block
+        // Per-voxel jitter roughens the vein surface
+        let jitter = 0.75 + 0.5 * unit(position_hash(vein_hash, pos), 0);
+        match ore.shape {
+            VeinShape::Blob => {
+                let radius = blob_radius(size);
+                dist_sq(point, center) <= radius * radius * jitter
+            }
+            VeinShape::Streak => {
+                let theta = unit(vein_hash, 5) * std::f32::consts::TAU;
+                let z = unit(vein_hash, 6) * 2.0 - 1.0;
+                let r = (1.0 - z * z).max(0.0).sqrt();
+                let dir = [r * theta.cos(), z, r * theta.sin()];
+
+                let half = streak_length(size) * 0.5;
+                let offset = [
+                    point[0] - center[0],
+                    point[1] - center[1],
+                    point[2] - center[2],
+                ];
+                let along = (offset[0] * dir[0] + offset[1] * dir[1] + offset[2] * dir[2])
+                    .clamp(-half, half);
+                let closest = [
+                    center[0] + dir[0] * along,
+                    center[1] + dir[1] * along,
+                    center[2] + dir[2] * along,
+                ];
+                dist_sq(point, closest) <= STREAK_RADIUS * STREAK_RADIUS * jitter
+            }
+        }
     }
 
+    /// Expected fraction of voxels at height `world_y` that are ore
     pub fn get_ore_density(&self, world_y: i32) -> f64 {
-        // Higher density at lower depths
-        if world_y > 128 {
-            0.0
-        } else if world_y > 64 {
-            0.02
-        } else if world_y > 32 {
-            0.03
-        } else if world_y > 16 {
-            0.04
-        } else {
-            0.05
+        let cell_volume = (self.cell_size as f64).powi(3);
+        self.ores
+            .iter()
+            .map(|entry| {
+                let ore = &entry.distribution;
+                let mean_size = (ore.vein_size.min + ore.vein_size.max) as f64 * 0.5;
+                depth_density(&ore.depth, world_y) as f64 * mean_size / cell_volume
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_ore(generator: &OreGenerator, y: i32, biome: Option<&str>) -> usize {
+        let mut count = 0;
+        for x in 0..48 {
+            for z in 0..48 {
+                if generator.get_ore_at_in_biome(x, y, z, BlockId::STONE, biome) != BlockId::STONE {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_ore_placement_is_deterministic_and_follows_curves() {
+        let a = OreGenerator::new(42);
+        let b = OreGenerator::new(42);
+        for y in [-40, 0, 20, 60] {
+            assert_eq!(count_ore(&a, y, None), count_ore(&b, y, None));
         }
+        assert!(count_ore(&a, 20, None) > 0);
+        assert_eq!(count_ore(&a, 200, None), 0);
+        assert_eq!(a.get_ore_density(200), 0.0);
+
+        // Ores only replace their host blocks
+        assert!((0..48).all(|x| a.get_ore_at(x, 20, 0, BlockId::DIRT) == BlockId::DIRT));
+    }
+
+    #[test]
+    fn test_custom_ore_registration_and_biomes() {
+        let mut generator = OreGenerator::with_config(
+            7,
+            OreConfig {
+                cell_size: ORE_CELL_SIZE,
+                ores: Vec::new(),
+            },
+        )
+        .unwrap_or_else(|_| OreGenerator::new(7));
+        let ruby = OreDistribution {
+            name: "ruby_ore".to_string(),
+            block: BlockId(200),
+            depth: DepthCurve {
+                min_y: 0,
+                peak_y: 10,
+                max_y: 20,
+                peak_density: 8.0,
+            },
+            biome_multipliers: vec![BiomeOreMultiplier {
+                biome: "tundra".to_string(),
+                multiplier: 0.0,
+            }],
+            shape: VeinShape::Streak,
+            vein_size: VeinSizeRange { min: 4, max: 10 },
+            host_blocks: Vec::new(),
+        };
+        assert!(generator.register_ore(ruby.clone()).is_ok());
+        assert!(generator.register_ore(ruby.clone()).is_err());
+
+        let oversized = OreDistribution {
+            name: "huge".to_string(),
+            vein_size: VeinSizeRange {
+                min: 1,
+                max: 100_000,
+            },
+            ..ruby
+        };
+        assert!(generator.register_ore(oversized).is_err());
+
+        assert!(count_ore(&generator, 10, Some("desert")) > 0);
+        assert_eq!(count_ore(&generator, 10, Some("tundra")), 0);
     }
 }
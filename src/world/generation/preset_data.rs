@@ -0,0 +1,69 @@
+//! World generation preset data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Predictable worlds for testing: superflat layer stacks, checkerboard and
+//! void debug worlds, and the normal terrain locked to a single biome.
+//! Chunk generation lives in preset_operations.rs
+
+use super::caves::CaveGenerator;
+use super::ores::OreGenerator;
+use crate::world::core::BlockId;
+
+/// One layer of a superflat world
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuperflatLayer {
+    pub block: BlockId,
+    /// Layer height (voxels)
+    pub thickness: u32,
+}
+
+/// Superflat layer stack, listed bottom to top
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuperflatConfig {
+    pub layers: Vec<SuperflatLayer>,
+    /// World y of the bottom of the first layer (voxels)
+    pub base_y: i32,
+    /// Biome reported for every column
+    pub biome: String,
+}
+
+/// Debug floor of alternating blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckerboardConfig {
+    pub blocks: [BlockId; 2],
+    /// Edge of one square (voxels)
+    pub cell_size: u32,
+    /// World y of the floor (voxels)
+    pub floor_y: i32,
+}
+
+/// Built-in generator preset
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldPreset {
+    Superflat(SuperflatConfig),
+    Checkerboard(CheckerboardConfig),
+    /// Nothing but air
+    Void,
+    /// Normal terrain with every column forced to one biome
+    SingleBiome {
+        biome: String,
+    },
+}
+
+/// Stages run on top of the preset's base terrain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenerationStages {
+    /// Carve caves out of solid blocks
+    pub caves: bool,
+    /// Replace host blocks with ore veins
+    pub ores: bool,
+}
+
+/// Preset generator: the base terrain plus the seeded stages
+pub struct PresetGenerator {
+    pub preset: WorldPreset,
+    pub seed: u32,
+    pub stages: GenerationStages,
+    pub caves: CaveGenerator,
+    pub ores: OreGenerator,
+}
@@ -0,0 +1,364 @@
+//! World generation preset operations - Pure DOP
+//!
+//! Builds chunks for the presets in preset_data.rs. Presets only replace the
+//! base terrain: the seeded cave and ore stages run on top exactly as they
+//! do for the default generator.
+
+use super::caves::CaveGenerator;
+use super::gpu_world_generator::{generate_reference_chunk, reference_surface_height};
+use super::ores::OreGenerator;
+use super::preset_data::{
+    CheckerboardConfig, GenerationStages, PresetGenerator, SuperflatConfig, SuperflatLayer,
+    WorldPreset,
+};
+use super::unified_generator::{FarSurface, GeneratorError, WorldGenerator};
+use crate::constants::terrain::DRY_SEA_LEVEL;
+use crate::constants::world_presets::{
+    CHECKERBOARD_CELL_SIZE, DEBUG_FLOOR_Y, DEFAULT_PRESET_BIOME, SUPERFLAT_DIRT_DEPTH,
+    SUPERFLAT_STONE_DEPTH,
+};
+use crate::gpu::TerrainParamsSOA;
+use crate::world::core::{BlockId, ChunkPos};
+use crate::world::storage::TempChunk;
+use crate::WorldGeneratorType;
+
+/// Bedrock, stone, dirt and grass starting at y = 0
+pub fn default_superflat_config() -> SuperflatConfig {
+    let layer = |block, thickness| SuperflatLayer { block, thickness };
+    SuperflatConfig {
+        layers: vec![
+            layer(BlockId::BEDROCK, 1),
+            layer(BlockId::STONE, SUPERFLAT_STONE_DEPTH),
+            layer(BlockId::DIRT, SUPERFLAT_DIRT_DEPTH),
+            layer(BlockId::GRASS, 1),
+        ],
+        base_y: 0,
+        biome: DEFAULT_PRESET_BIOME.to_string(),
+    }
+}
+
+/// Stone and planks in one meter squares
+pub fn default_checkerboard_config() -> CheckerboardConfig {
+    CheckerboardConfig {
+        blocks: [BlockId::STONE, BlockId::PLANKS],
+        cell_size: CHECKERBOARD_CELL_SIZE,
+        floor_y: DEBUG_FLOOR_Y,
+    }
+}
+
+/// Single-biome worlds keep the full pipeline; the flat and debug worlds
+/// stay predictable unless stages are enabled explicitly
+pub fn default_generation_stages(preset: &WorldPreset) -> GenerationStages {
+    let full = matches!(preset, WorldPreset::SingleBiome { .. });
+    GenerationStages {
+        caves: full,
+        ores: full,
+    }
+}
+
+/// Preset selected by an engine config, if any
+pub fn preset_for_generator_type(kind: &WorldGeneratorType) -> Option<&WorldPreset> {
+    match kind {
+        WorldGeneratorType::Preset(preset) => Some(preset),
+        _ => None,
+    }
+}
+
+fn validate_preset(preset: &WorldPreset) -> Result<(), GeneratorError> {
+    let invalid = |message: &str| Err(GeneratorError::ConfigError(message.to_string()));
+    match preset {
+        WorldPreset::Superflat(config) if config.layers.iter().any(|l| l.thickness == 0) => {
+            invalid("Superflat layers must be at least one voxel thick")
+        }
+        WorldPreset::Superflat(config) if config.biome.is_empty() => {
+            invalid("Superflat biome must not be empty")
+        }
+        WorldPreset::Checkerboard(config) if config.cell_size == 0 => {
+            invalid("Checkerboard cell size must be positive")
+        }
+        WorldPreset::SingleBiome { biome } if biome.is_empty() => {
+            invalid("Single-biome preset needs a biome name")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Generator for `preset`, seeding the stages from `seed`
+pub fn create_preset_generator(
+    preset: WorldPreset,
+    seed: u32,
+    stages: GenerationStages,
+) -> Result<PresetGenerator, GeneratorError> {
+    validate_preset(&preset)?;
+    Ok(PresetGenerator {
+        preset,
+        seed,
+        stages,
+        caves: CaveGenerator::new(seed),
+        ores: OreGenerator::new(seed),
+    })
+}
+
+/// Top solid voxel of a superflat world (`base_y - 1` with no layers)
+pub fn superflat_surface_y(config: &SuperflatConfig) -> i32 {
+    let height: u32 = config.layers.iter().map(|layer| layer.thickness).sum();
+    config.base_y + height as i32 - 1
+}
+
+/// Block of the superflat stack at `world_y`
+pub fn superflat_block_at(config: &SuperflatConfig, world_y: i32) -> BlockId {
+    let mut top = config.base_y;
+    if world_y < top {
+        return BlockId::AIR;
+    }
+    for layer in &config.layers {
+        top += layer.thickness as i32;
+        if world_y < top {
+            return layer.block;
+        }
+    }
+    BlockId::AIR
+}
+
+/// Block of the checkerboard floor at a voxel
+pub fn checkerboard_block_at(config: &CheckerboardConfig, x: i32, y: i32, z: i32) -> BlockId {
+    if y != config.floor_y {
+        return BlockId::AIR;
+    }
+    let cell = config.cell_size.max(1) as i32;
+    let parity = (x.div_euclid(cell) + z.div_euclid(cell)).rem_euclid(2);
+    config.blocks[parity as usize]
+}
+
+/// Biome forced on every column, or `None` when the preset leaves biomes
+/// to the normal generator (or has none)
+pub fn preset_biome(preset: &WorldPreset) -> Option<&str> {
+    match preset {
+        WorldPreset::Superflat(config) => Some(&config.biome),
+        WorldPreset::SingleBiome { biome } => Some(biome),
+        WorldPreset::Checkerboard(_) | WorldPreset::Void => None,
+    }
+}
+
+/// Top solid voxel of a column
+pub fn preset_surface_height(preset: &WorldPreset, world_x: i32, world_z: i32) -> i32 {
+    match preset {
+        WorldPreset::Superflat(config) => superflat_surface_y(config),
+        WorldPreset::Checkerboard(config) => config.floor_y,
+        WorldPreset::Void => DEBUG_FLOOR_Y,
+        WorldPreset::SingleBiome { .. } => reference_surface_height(world_x, world_z),
+    }
+}
+
+/// Base terrain of a chunk before any stage runs
+fn generate_base_chunk(preset: &WorldPreset, chunk_pos: ChunkPos, chunk_size: u32) -> TempChunk {
+    let mut chunk = match preset {
+        WorldPreset::SingleBiome { .. } => return generate_reference_chunk(chunk_pos, chunk_size),
+        _ => TempChunk::new_empty(chunk_pos, chunk_size),
+    };
+
+    let origin = [
+        chunk_pos.x * chunk_size as i32,
+        chunk_pos.y * chunk_size as i32,
+        chunk_pos.z * chunk_size as i32,
+    ];
+    for y in 0..chunk_size {
+        for z in 0..chunk_size {
+            for x in 0..chunk_size {
+                let (world_x, world_y, world_z) = (
+                    origin[0] + x as i32,
+                    origin[1] + y as i32,
+                    origin[2] + z as i32,
+                );
+                let block = match preset {
+                    WorldPreset::Superflat(config) => superflat_block_at(config, world_y),
+                    WorldPreset::Checkerboard(config) => {
+                        checkerboard_block_at(config, world_x, world_y, world_z)
+                    }
+                    WorldPreset::Void | WorldPreset::SingleBiome { .. } => BlockId::AIR,
+                };
+                if block != BlockId::AIR {
+                    chunk.set_block(x, y, z, block);
+                }
+            }
+        }
+    }
+    chunk
+}
+
+/// Run the enabled stages over the solid blocks of a base chunk
+fn apply_generation_stages(generator: &PresetGenerator, chunk: &mut TempChunk) {
+    let stages = generator.stages;
+    if !stages.caves && !stages.ores {
+        return;
+    }
+
+    let size = chunk.size;
+    let origin = [
+        chunk.position.x * size as i32,
+        chunk.position.y * size as i32,
+        chunk.position.z * size as i32,
+    ];
+    let biome = preset_biome(&generator.preset);
+    for y in 0..size {
+        for z in 0..size {
+            for x in 0..size {
+                let index = (y * size * size + z * size + x) as usize;
+                let block = chunk.blocks[index];
+                if block == BlockId::AIR || block == BlockId::BEDROCK {
+                    continue;
+                }
+                let (world_x, world_y, world_z) = (
+                    origin[0] + x as i32,
+                    origin[1] + y as i32,
+                    origin[2] + z as i32,
+                );
+                chunk.blocks[index] =
+                    if stages.caves && generator.caves.is_cave(world_x, world_y, world_z) {
+                        BlockId::AIR
+                    } else if stages.ores {
+                        generator
+                            .ores
+                            .get_ore_at_in_biome(world_x, world_y, world_z, block, biome)
+                    } else {
+                        block
+                    };
+            }
+        }
+    }
+}
+
+/// Generate one chunk of a preset world
+pub fn generate_preset_chunk(
+    generator: &PresetGenerator,
+    chunk_pos: ChunkPos,
+    chunk_size: u32,
+) -> TempChunk {
+    if generator.preset == WorldPreset::Void {
+        return TempChunk::new_empty(chunk_pos, chunk_size);
+    }
+    let mut chunk = generate_base_chunk(&generator.preset, chunk_pos, chunk_size);
+    apply_generation_stages(generator, &mut chunk);
+    chunk
+}
+
+impl WorldGenerator for PresetGenerator {
+    fn generate_chunk(&self, chunk_pos: ChunkPos, chunk_size: u32) -> TempChunk {
+        generate_preset_chunk(self, chunk_pos, chunk_size)
+    }
+
+    fn get_surface_height(&self, world_x: f64, world_z: f64) -> i32 {
+        preset_surface_height(&self.preset, world_x.floor() as i32, world_z.floor() as i32)
+    }
+
+    fn is_gpu(&self) -> bool {
+        false
+    }
+
+    /// Presets place no water
+    fn terrain_params(&self) -> Option<TerrainParamsSOA> {
+        Some(TerrainParamsSOA {
+            seed: self.seed,
+            sea_level: DRY_SEA_LEVEL,
+            ..Default::default()
+        })
+    }
+
+    fn far_surface(&self) -> FarSurface {
+        match &self.preset {
+            WorldPreset::Superflat(config) => FarSurface::Flat {
+                height: superflat_surface_y(config),
+            },
+            WorldPreset::Checkerboard(config) => FarSurface::Flat {
+                height: config.floor_y,
+            },
+            WorldPreset::Void => FarSurface::Empty,
+            WorldPreset::SingleBiome { .. } => FarSurface::Reference,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(chunk: &TempChunk, x: u32, y: u32, z: u32) -> BlockId {
+        chunk.blocks[(y * chunk.size * chunk.size + z * chunk.size + x) as usize]
+    }
+
+    #[test]
+    fn test_presets_describe_their_far_surface() {
+        let preset = WorldPreset::Superflat(default_superflat_config());
+        let stages = default_generation_stages(&preset);
+        let generator = create_preset_generator(preset, 7, stages).expect("valid superflat");
+        assert_eq!(generator.far_surface(), FarSurface::Flat { height: 44 });
+        let params = generator.terrain_params().expect("preset params");
+        assert_eq!(params.seed, 7);
+        assert_eq!(params.sea_level, DRY_SEA_LEVEL);
+
+        let void = create_preset_generator(
+            WorldPreset::Void,
+            7,
+            default_generation_stages(&WorldPreset::Void),
+        )
+        .expect("valid void");
+        assert_eq!(void.far_surface(), FarSurface::Empty);
+    }
+
+    #[test]
+    fn test_flat_and_debug_presets() {
+        let config = default_superflat_config();
+        assert_eq!(superflat_surface_y(&config), 44);
+        let preset = WorldPreset::Superflat(config);
+        let generator =
+            create_preset_generator(preset.clone(), 7, default_generation_stages(&preset))
+                .expect("valid superflat");
+        let chunk = generate_preset_chunk(&generator, ChunkPos { x: 3, y: 1, z: -2 }, 32);
+        assert_eq!(block(&chunk, 5, 0, 5), BlockId::STONE);
+        assert_eq!(block(&chunk, 5, 10, 5), BlockId::DIRT);
+        assert_eq!(block(&chunk, 5, 12, 5), BlockId::GRASS);
+        assert_eq!(block(&chunk, 5, 13, 5), BlockId::AIR);
+        assert_eq!(preset_biome(&generator.preset), Some(DEFAULT_PRESET_BIOME));
+
+        let floor = default_checkerboard_config();
+        assert_eq!(checkerboard_block_at(&floor, 0, 64, 0), BlockId::STONE);
+        assert_eq!(checkerboard_block_at(&floor, 10, 64, 0), BlockId::PLANKS);
+        assert_eq!(checkerboard_block_at(&floor, -1, 64, 0), BlockId::PLANKS);
+        assert_eq!(checkerboard_block_at(&floor, 0, 65, 0), BlockId::AIR);
+
+        let bad = WorldPreset::Checkerboard(CheckerboardConfig {
+            cell_size: 0,
+            ..floor
+        });
+        assert!(
+            create_preset_generator(bad, 7, default_generation_stages(&WorldPreset::Void)).is_err()
+        );
+    }
+
+    #[test]
+    fn test_stages_run_on_top_of_presets() {
+        let preset = WorldPreset::Superflat(default_superflat_config());
+        let stages = GenerationStages {
+            caves: true,
+            ores: true,
+        };
+        let generate = |seed| {
+            let generator =
+                create_preset_generator(preset.clone(), seed, stages).expect("valid superflat");
+            generate_preset_chunk(&generator, ChunkPos { x: 0, y: 0, z: 0 }, 32)
+        };
+
+        // Same seed, same world; the stages change the plain stack
+        let first = generate(11);
+        assert_eq!(first.blocks, generate(11).blocks);
+        let plain = generate_preset_chunk(
+            &create_preset_generator(preset.clone(), 11, default_generation_stages(&preset))
+                .expect("valid superflat"),
+            ChunkPos { x: 0, y: 0, z: 0 },
+            32,
+        );
+        assert_ne!(first.blocks, plain.blocks);
+        // Bedrock is never carved or replaced
+        assert_eq!(block(&first, 0, 0, 0), BlockId::BEDROCK);
+    }
+}
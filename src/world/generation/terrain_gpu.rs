@@ -3,8 +3,14 @@
 //! This module provides a Structure of Arrays version of the terrain generator
 //! for maximum GPU performance and memory bandwidth efficiency.
 
+use super::generation_halo_data::{GenerationHaloConfig, PlannedColumns};
+use super::generation_halo_gpu::GenerationHaloCompute;
+use super::generation_halo_operations::{
+    default_generation_halo_config, erosion_halo_config, plan_generation_batches,
+    plan_halo_columns,
+};
+use crate::feature_flags::{feature_enabled, FLAG_EROSION};
 use crate::gpu::types::terrain::TerrainParams;
-use crate::constants::core::CHUNK_SIZE;
 use crate::gpu::{
     buffer_layouts::{bindings, layouts, usage},
     soa::{
@@ -14,11 +20,14 @@ use crate::gpu::{
     types::TypedGpuBuffer,
     GpuBufferManager, GpuError,
 };
-use crate::world::core::ChunkPos;
+use crate::memory::{
+    create_tracked_buffer, create_tracked_buffer_init, fill_frame_slice, register_global_gpu_owner,
+    GpuAllocationGuard, GpuOwnerGuard, SharedFrameArena,
+};
+use crate::world::core::{layout_workgroups_per_axis, ChunkPos};
 use crate::world::storage::WorldBuffer;
 use std::sync::Arc;
 use std::time::Instant;
-use wgpu::util::DeviceExt;
 
 /// SOA-optimized GPU terrain generator
 pub struct TerrainGeneratorSOA {
@@ -41,8 +50,25 @@ pub struct TerrainGeneratorSOA {
 
     /// Whether to use vectorized shader variant
     use_vectorized: bool,
+
+    /// Per-frame arena for the chunk metadata (heap `Vec` when `None`)
+    frame_arena: Option<SharedFrameArena>,
+
+    /// Batch heightmaps shared by neighbouring chunks
+    halo: GenerationHaloCompute,
+    halo_config: GenerationHaloConfig,
+
+    /// Tracker entry for the terrain parameters buffer
+    gpu_allocations: Vec<GpuAllocationGuard>,
+    gpu_owner: GpuOwnerGuard,
 }
 
+/// u32 values per ChunkMetadata struct (5 fields + 3 reserved)
+const CHUNK_METADATA_WORDS: usize = 8;
+
+/// Bindings of the terrain pass: voxels, metadata, params, halo heights
+const TERRAIN_BINDING_COUNT: u32 = 4;
+
 impl TerrainGeneratorSOA {
     /// Validate that a shader entry point exists in the shader source
     fn validate_shader_entry_point(shader_source: &str, entry_point: &str) -> Result<(), String> {
@@ -277,6 +303,12 @@ impl TerrainGeneratorSOA {
                     true,
                     wgpu::ShaderStages::COMPUTE,
                 ),
+                // Batch heightmaps
+                layouts::storage_buffer_entry(
+                    bindings::world::HALO_HEIGHTS_BUFFER,
+                    true,
+                    wgpu::ShaderStages::COMPUTE,
+                ),
             ],
         });
 
@@ -320,7 +352,7 @@ impl TerrainGeneratorSOA {
             "[TerrainGeneratorSOA] Creating compute pipeline - Entry: {}, Shader size: {} chars, Layout bindings: {}",
             entry_point,
             shader_code.len(),
-            3  // We have 3 bindings: voxel_buffer, metadata_buffer, params_buffer
+            TERRAIN_BINDING_COUNT
         );
 
         // Attempt pipeline creation with error recovery
@@ -360,17 +392,24 @@ impl TerrainGeneratorSOA {
 
         log::info!("[TerrainGeneratorSOA] Creating SOA parameters buffer");
 
-        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("SOA Terrain Parameters"),
-            contents: bytemuck::bytes_of(&soa_params),
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-        });
+        let gpu_owner = register_global_gpu_owner("terrain_generation");
+        let (params_buffer, params_allocation) = create_tracked_buffer_init(
+            &device,
+            &gpu_owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("SOA Terrain Parameters"),
+                contents: bytemuck::bytes_of(&soa_params),
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            },
+        );
 
         let params_buffer = TypedGpuBuffer::new(
             params_buffer,
             std::mem::size_of::<TerrainParamsSOA>() as u64,
         );
 
+        let halo = GenerationHaloCompute::new(device.clone())?;
+
         log::info!("[TerrainGeneratorSOA] SOA terrain generator ready!");
 
         Ok(Self {
@@ -381,9 +420,20 @@ impl TerrainGeneratorSOA {
             params_buffer,
             bind_group_layout,
             use_vectorized,
+            frame_arena: None,
+            halo,
+            halo_config: default_generation_halo_config(),
+            gpu_allocations: vec![params_allocation],
+            gpu_owner,
         })
     }
 
+    /// Allocate per-batch chunk metadata from a per-frame arena
+    pub fn with_frame_arena(mut self, arena: SharedFrameArena) -> Self {
+        self.frame_arena = Some(arena);
+        self
+    }
+
     /// Update terrain parameters (converts from AOS to SOA)
     pub fn update_params(&self, params: &TerrainParams) -> Result<(), GpuError> {
         let queue = &self.buffer_manager.queue();
@@ -421,15 +471,20 @@ impl TerrainGeneratorSOA {
         chunk_positions: &[ChunkPos],
         encoder: &mut wgpu::CommandEncoder,
     ) -> Result<wgpu::Buffer, GpuError> {
+        let _span = crate::trace_span!(Generation, "generate_chunks");
         log::debug!("[TerrainGeneratorSOA::generate_chunks] Entry point reached");
         if chunk_positions.is_empty() {
             // Return a dummy buffer if no chunks to generate
-            let dummy_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
-                label: Some("Empty metadata buffer"),
-                size: 4,
-                usage: wgpu::BufferUsages::STORAGE,
-                mapped_at_creation: false,
-            });
+            let (dummy_buffer, _dummy_allocation) = create_tracked_buffer(
+                &self.device,
+                &self.gpu_owner,
+                &wgpu::BufferDescriptor {
+                    label: Some("Empty metadata buffer"),
+                    size: 4,
+                    usage: wgpu::BufferUsages::STORAGE,
+                    mapped_at_creation: false,
+                },
+            );
             return Ok(dummy_buffer);
         }
 
@@ -441,63 +496,62 @@ impl TerrainGeneratorSOA {
             batch_size
         );
 
+        // Surface heightmaps for the batches of neighbouring chunks, built
+        // before the terrain pass reads them
+        let chunk_size = world_buffer.chunk_layout().size;
+        let halo_config = erosion_halo_config(&self.halo_config, feature_enabled(FLAG_EROSION));
+        let plan = plan_generation_batches(chunk_positions, chunk_size, &halo_config);
+        let halo_columns = plan_halo_columns(&plan, chunk_positions, chunk_size);
+        let (halo_heights, _halo_allocation) = self.halo.encode(encoder, &plan, &halo_config);
+        log::debug!(
+            "[TerrainGeneratorSOA] {} chunks in {} halo batches ({} columns, {} without batching)",
+            plan.stats.chunks,
+            plan.stats.batches,
+            plan.stats.columns,
+            plan.stats.unbatched_columns
+        );
+
         log::debug!("[TerrainGeneratorSOA] About to create metadata buffer");
 
         // Create metadata buffer for chunk generation
         // Each chunk needs a full ChunkMetadata struct (8 u32 values: 5 fields + 3 reserved)
-        log::debug!("[TerrainGeneratorSOA] Starting metadata data creation...");
-        let metadata_data: Vec<u32> = chunk_positions
-            .iter()
-            .enumerate()
-            .flat_map(|(idx, pos)| {
-                log::debug!(
-                    "[TerrainGeneratorSOA] Processing chunk {:?} (index {})",
-                    pos, idx
-                );
-                // Get the slot assignment from WorldBuffer
-                let slot = world_buffer.get_chunk_slot(*pos);
-                log::debug!(
-                    "[TerrainGeneratorSOA] Chunk {:?} (index {}) assigned to slot {}",
-                    pos, idx, slot
+        let metadata_len = chunk_positions.len() * CHUNK_METADATA_WORDS;
+        let create_metadata_buffer = |metadata_data: &[u32]| {
+            log::debug!(
+                "[TerrainGeneratorSOA] Creating metadata buffer with {} u32 values ({} bytes) for {} chunks",
+                metadata_data.len(),
+                std::mem::size_of_val(metadata_data),
+                chunk_positions.len()
+            );
+            create_tracked_buffer_init(
+                &self.device,
+                &self.gpu_owner,
+                &wgpu::util::BufferInitDescriptor {
+                    label: Some("SOA Chunk Metadata"),
+                    contents: bytemuck::cast_slice(metadata_data),
+                    usage: usage::STORAGE,
+                },
+            )
+        };
+        let (metadata_buffer, _metadata_allocation) = match &self.frame_arena {
+            Some(arena) => {
+                let mut arena = arena.lock();
+                let metadata_data = fill_frame_slice(&mut arena, metadata_len, |metadata_data| {
+                    write_chunk_metadata(world_buffer, chunk_positions, &halo_columns, metadata_data)
+                });
+                create_metadata_buffer(metadata_data)
+            }
+            None => {
+                let mut metadata_data = vec![0u32; metadata_len];
+                write_chunk_metadata(
+                    world_buffer,
+                    chunk_positions,
+                    &halo_columns,
+                    &mut metadata_data,
                 );
-
-                // Create ChunkMetadata for each chunk
-                // Properly encode signed positions as 16-bit values
-                let x_16bit = (pos.x as i16) as u16 as u32;
-                let z_16bit = (pos.z as i16) as u16 as u32;
-                let flags = (x_16bit << 16) | z_16bit;
-                let timestamp = 0u32;
-                let checksum = 0u32; // Proper checksum would be calculated from chunk data
-                let y_position = pos.y as i32 as u32; // Preserve sign through i32
-                let slot_index = slot;
-                let _reserved = [0u32; 3];
-                vec![
-                    flags,
-                    timestamp,
-                    checksum,
-                    y_position,
-                    slot_index,
-                    _reserved[0],
-                    _reserved[1],
-                    _reserved[2],
-                ]
-            })
-            .collect();
-
-        log::debug!(
-            "[TerrainGeneratorSOA] Creating metadata buffer with {} u32 values ({} bytes) for {} chunks",
-            metadata_data.len(),
-            metadata_data.len() * std::mem::size_of::<u32>(),
-            chunk_positions.len()
-        );
-        
-        let metadata_buffer = self
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("SOA Chunk Metadata"),
-                contents: bytemuck::cast_slice(&metadata_data),
-                usage: usage::STORAGE,
-            });
+                create_metadata_buffer(&metadata_data)
+            }
+        };
 
         // Create bind group
         let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -516,6 +570,10 @@ impl TerrainGeneratorSOA {
                     binding: bindings::world::PARAMS_BUFFER,
                     resource: self.params_buffer.buffer.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: bindings::world::HALO_HEIGHTS_BUFFER,
+                    resource: halo_heights.as_entire_binding(),
+                },
             ],
         });
 
@@ -540,19 +598,19 @@ impl TerrainGeneratorSOA {
             // Validate bind group before use
             log::debug!(
                 "[TerrainGeneratorSOA] Setting bind group with {} entries",
-                3
+                TERRAIN_BINDING_COUNT
             );
             compute_pass.set_bind_group(0, &bind_group, &[]);
 
             // Calculate workgroups needed based on chunk size and workgroup size
-            let chunk_size = CHUNK_SIZE;
+            let chunk_layout = world_buffer.chunk_layout();
             let workgroup_size_x = if self.use_vectorized { 16 } else { 8 };
             let workgroup_size_y = if self.use_vectorized { 2 } else { 4 };
             let workgroup_size_z = if self.use_vectorized { 2 } else { 4 };
 
-            let workgroups_per_chunk_x = (chunk_size + workgroup_size_x - 1) / workgroup_size_x;
-            let workgroups_per_chunk_y = (chunk_size + workgroup_size_y - 1) / workgroup_size_y;
-            let workgroups_per_chunk_z = (chunk_size + workgroup_size_z - 1) / workgroup_size_z;
+            let workgroups_per_chunk_x = layout_workgroups_per_axis(chunk_layout, workgroup_size_x);
+            let workgroups_per_chunk_y = layout_workgroups_per_axis(chunk_layout, workgroup_size_y);
+            let workgroups_per_chunk_z = layout_workgroups_per_axis(chunk_layout, workgroup_size_z);
 
             // Total workgroups in X = workgroups per chunk * number of chunks
             let total_workgroups_x = workgroups_per_chunk_x * chunk_positions.len() as u32;
@@ -604,9 +662,47 @@ impl TerrainGeneratorSOA {
     }
 }
 
+/// Write one ChunkMetadata struct per chunk into `metadata_data`
+fn write_chunk_metadata(
+    world_buffer: &WorldBuffer,
+    chunk_positions: &[ChunkPos],
+    halo_columns: &[PlannedColumns],
+    metadata_data: &mut [u32],
+) {
+    for (idx, ((pos, columns), metadata)) in chunk_positions
+        .iter()
+        .zip(halo_columns)
+        .zip(metadata_data.chunks_exact_mut(CHUNK_METADATA_WORDS))
+        .enumerate()
+    {
+        // Get the slot assignment from WorldBuffer
+        let slot = world_buffer.get_chunk_slot(*pos);
+        log::debug!(
+            "[TerrainGeneratorSOA] Chunk {:?} (index {}) assigned to slot {}",
+            pos, idx, slot
+        );
+
+        // Properly encode signed positions as 16-bit values
+        let x_16bit = (pos.x as i16) as u16 as u32;
+        let z_16bit = (pos.z as i16) as u16 as u32;
+        let flags = (x_16bit << 16) | z_16bit;
+        let timestamp = 0u32;
+        let checksum = 0u32; // Proper checksum would be calculated from chunk data
+        let y_position = pos.y as i32 as u32; // Preserve sign through i32
+        metadata[..5].copy_from_slice(&[flags, timestamp, checksum, y_position, slot]);
+        // Reserved values point the shader at the chunk's batch heightmap
+        // (base, row stride, has-heightmap flag)
+        metadata[5..].copy_from_slice(&match columns {
+            Some(columns) => [columns.base, columns.stride, 1],
+            None => [0, 0, 0],
+        });
+    }
+}
+
 /// Builder for creating SOA terrain generator with options
 pub struct TerrainGeneratorSOABuilder {
     use_vectorized: bool,
+    frame_arena: Option<SharedFrameArena>,
 }
 
 impl TerrainGeneratorSOABuilder {
@@ -614,6 +710,7 @@ impl TerrainGeneratorSOABuilder {
     pub fn new() -> Self {
         Self {
             use_vectorized: false,
+            frame_arena: None,
         }
     }
 
@@ -623,13 +720,24 @@ impl TerrainGeneratorSOABuilder {
         self
     }
 
+    /// Allocate chunk metadata from a per-frame arena
+    pub fn with_frame_arena(mut self, arena: SharedFrameArena) -> Self {
+        self.frame_arena = Some(arena);
+        self
+    }
+
     /// Build the SOA terrain generator
     pub fn build(
         self,
         device: Arc<wgpu::Device>,
         buffer_manager: Arc<GpuBufferManager>,
     ) -> Result<TerrainGeneratorSOA, GpuError> {
-        TerrainGeneratorSOA::new_with_manager(device, buffer_manager, self.use_vectorized)
+        let generator =
+            TerrainGeneratorSOA::new_with_manager(device, buffer_manager, self.use_vectorized)?;
+        Ok(match self.frame_arena {
+            Some(arena) => generator.with_frame_arena(arena),
+            None => generator,
+        })
     }
 }
 
@@ -1,9 +1,21 @@
 //! GPU-first generation interface
 
 use super::TerrainParams;
-use crate::world::core::{BlockId, ChunkPos};
+use crate::gpu::TerrainParamsSOA;
+use crate::world::core::{BlockId, ChunkLayout, ChunkPos};
 use crate::world::storage::TempChunk;
 
+/// Surface beyond the loaded chunks, as the far terrain ring draws it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FarSurface {
+    /// The rolling hills of `reference_surface_height`
+    Reference,
+    /// Level ground with its top voxel at `height`
+    Flat { height: i32 },
+    /// Nothing to draw
+    Empty,
+}
+
 /// Universal world generation interface
 pub trait WorldGenerator: Send + Sync {
     /// Generate a chunk at the given position
@@ -29,6 +41,21 @@ pub trait WorldGenerator: Send + Sync {
     ) -> Option<std::sync::Arc<std::sync::Mutex<crate::world::storage::WorldBuffer>>> {
         None
     }
+
+    /// Terrain params (seed, sea level) the far terrain ring is drawn from
+    /// (None = the engine defaults with the configured world seed)
+    fn terrain_params(&self) -> Option<TerrainParamsSOA> {
+        None
+    }
+
+    /// Terrain params the chunks generated from now on use (seasonal snow
+    /// cover and temperature); generators without params ignore them
+    fn set_terrain_params(&self, _params: &TerrainParamsSOA) {}
+
+    /// Surface the far terrain ring draws beyond the loaded chunks
+    fn far_surface(&self) -> FarSurface {
+        FarSurface::Reference
+    }
 }
 
 /// GPU-based unified generator
@@ -36,6 +63,9 @@ pub struct UnifiedGenerator {
     generator: Box<dyn WorldGenerator>,
     device: std::sync::Arc<wgpu::Device>,
     buffer_manager: std::sync::Arc<crate::gpu::GpuBufferManager>,
+    /// Params the GPU terrain was configured with (None = the wrapped
+    /// generator's)
+    terrain_params: Option<TerrainParamsSOA>,
 }
 
 impl UnifiedGenerator {
@@ -49,6 +79,7 @@ impl UnifiedGenerator {
             generator,
             device,
             buffer_manager,
+            terrain_params: None,
         })
     }
 
@@ -59,8 +90,12 @@ impl UnifiedGenerator {
         config: GeneratorConfig,
     ) -> Result<Self, GeneratorError> {
         // Create the GPU terrain generator
-        let terrain_generator = super::TerrainGeneratorSOABuilder::new()
-            .with_vectorization(config.use_vectorization)
+        let mut builder =
+            super::TerrainGeneratorSOABuilder::new().with_vectorization(config.use_vectorization);
+        if let Some(arena) = config.frame_arena.clone() {
+            builder = builder.with_frame_arena(arena);
+        }
+        let terrain_generator = builder
             .build(device.clone(), buffer_manager.clone())
             .map_err(|e| GeneratorError::InitError(format!("Failed to create terrain generator: {:?}", e)))?;
 
@@ -69,6 +104,13 @@ impl UnifiedGenerator {
             view_distance: 16, // 16 chunks view distance
             enable_atomics: true,
             enable_readback: false,
+            // 16-chunk view distance is large; keep light packed to bound memory
+            lighting_mode: crate::world::storage::LightingStorageMode::Packed,
+            enable_sparse_chunks: true,
+            chunk_layout: config.chunk_layout,
+            chunk_upload: crate::world::storage::ChunkUploadConfig::default(),
+            // Generation writes voxels into full slots on the GPU
+            voxel_format: crate::world::storage::VoxelStorageFormat::Full,
         };
         let world_buffer = std::sync::Arc::new(std::sync::Mutex::new(
             crate::world::storage::WorldBuffer::new(device.clone(), &world_buffer_desc),
@@ -79,17 +121,28 @@ impl UnifiedGenerator {
         // must be done through the renderer when a command encoder is available.
 
         // Create GPU world generator wrapper
-        let gpu_generator = super::GpuWorldGenerator::new(
+        let mut gpu_generator = super::GpuWorldGenerator::new(
             std::sync::Arc::new(terrain_generator),
             device.clone(),
             buffer_manager.queue(),
             world_buffer,
         );
+        if let Some(connectivity) = config.connectivity.clone() {
+            gpu_generator = gpu_generator.with_connectivity(connectivity);
+        }
 
         Ok(UnifiedGenerator {
             generator: Box::new(gpu_generator) as Box<dyn WorldGenerator>,
             device,
             buffer_manager,
+            terrain_params: Some(TerrainParamsSOA {
+                seed: config.terrain_params.seed,
+                sea_level: config.terrain_params.sea_level,
+                terrain_scale: config.terrain_params.terrain_scale,
+                mountain_threshold: config.terrain_params.mountain_threshold,
+                cave_threshold: config.terrain_params.cave_threshold,
+                ..Default::default()
+            }),
         })
     }
 
@@ -117,6 +170,19 @@ impl WorldGenerator for UnifiedGenerator {
     ) -> Option<std::sync::Arc<std::sync::Mutex<crate::world::storage::WorldBuffer>>> {
         self.generator.get_world_buffer()
     }
+
+    fn terrain_params(&self) -> Option<TerrainParamsSOA> {
+        self.terrain_params
+            .or_else(|| self.generator.terrain_params())
+    }
+
+    fn set_terrain_params(&self, params: &TerrainParamsSOA) {
+        self.generator.set_terrain_params(params)
+    }
+
+    fn far_surface(&self) -> FarSurface {
+        self.generator.far_surface()
+    }
 }
 
 /// Configuration for unified generator
@@ -124,7 +190,15 @@ impl WorldGenerator for UnifiedGenerator {
 pub struct GeneratorConfig {
     pub terrain_params: TerrainParams,
     pub block_ids: BlockIds,
+    /// Vectorized terrain kernel; defaults to the `vectorized_terrain` flag
     pub use_vectorization: bool,
+    /// Chunk dimensions of the generated world
+    pub chunk_layout: ChunkLayout,
+    /// Per-frame arena for chunk metadata (the engine's, see `Engine::frame_arena`)
+    pub frame_arena: Option<crate::memory::SharedFrameArena>,
+    /// Connectivity pass run after generation (the engine's, see
+    /// `Engine::chunk_connectivity`)
+    pub connectivity: Option<std::sync::Arc<crate::world::compute::ChunkConnectivityCompute>>,
 }
 
 impl Default for GeneratorConfig {
@@ -132,7 +206,12 @@ impl Default for GeneratorConfig {
         Self {
             terrain_params: TerrainParams::default(),
             block_ids: BlockIds::default(),
-            use_vectorization: true,
+            use_vectorization: crate::feature_flags::feature_enabled(
+                crate::feature_flags::FLAG_VECTORIZED_TERRAIN,
+            ),
+            chunk_layout: ChunkLayout::default(),
+            frame_arena: None,
+            connectivity: None,
         }
     }
 }
@@ -181,7 +260,10 @@ mod tests {
     #[test]
     fn test_generator_config_default() {
         let config = GeneratorConfig::default();
-        assert!(config.use_vectorization);
+        assert_eq!(
+            config.use_vectorization,
+            crate::feature_flags::feature_enabled(crate::feature_flags::FLAG_VECTORIZED_TERRAIN)
+        );
     }
 
     #[test]
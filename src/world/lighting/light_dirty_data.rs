@@ -0,0 +1,44 @@
+//! Light Dirty Data - Pure DOP
+//!
+//! Fine-grained dirty tracking for incremental relighting. A block edit
+//! grows a min/max box in each chunk it touches (edit plus a halo for the
+//! voxels whose AO reads it); the lighting pass then dispatches only over
+//! those boxes, with boxes of neighbouring chunks merged when their union
+//! wastes little volume, instead of relighting whole chunks and their
+//! neighbours.
+//!
+//! NO METHODS - just data.
+
+use crate::world::core::ChunkPos;
+use std::collections::{HashMap, HashSet};
+
+/// World voxel box, `min` inclusive and `max` exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LightDirtyBox {
+    pub min: [i32; 3],
+    pub max: [i32; 3],
+}
+
+/// Work done by incremental relights compared with whole-chunk relights
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LightDirtyStats {
+    pub edits_recorded: u64,
+    pub regions_dispatched: u64,
+    pub voxels_dispatched: u64,
+    /// Voxels the same edits would have relit as whole chunks plus their
+    /// 26 neighbours
+    pub full_relight_voxels: u64,
+}
+
+/// Dirty sub-volume per chunk, waiting for the next lighting pass
+#[derive(Debug, Clone, Default)]
+pub struct LightDirtyData {
+    pub chunk_size: u32,
+    /// Voxels added around each edit
+    pub halo: u32,
+    /// Dirty box of each chunk, clipped to the chunk
+    pub chunks: HashMap<ChunkPos, LightDirtyBox>,
+    /// Chunks holding the edits themselves, for `full_relight_voxels`
+    pub edited_chunks: HashSet<ChunkPos>,
+    pub stats: LightDirtyStats,
+}
@@ -0,0 +1,272 @@
+//! Light Dirty Operations - Pure DOP
+//!
+//! Functions that record block edits as dirty sub-volumes and turn them into
+//! the merged regions the lighting pass dispatches over.
+
+use super::light_dirty_data::{LightDirtyBox, LightDirtyData, LightDirtyStats};
+use crate::constants::lighting::LIGHT_DIRTY_MERGE_WASTE;
+use crate::world::core::{floor_div, ChunkPos, VoxelPos};
+use std::collections::HashSet;
+
+/// Empty tracker for a world with the given chunk size
+pub fn create_light_dirty(chunk_size: u32, halo: u32) -> LightDirtyData {
+    LightDirtyData {
+        chunk_size: chunk_size.max(1),
+        halo,
+        ..Default::default()
+    }
+}
+
+/// Voxels inside a box
+pub fn light_box_voxels(dirty: &LightDirtyBox) -> u64 {
+    (0..3)
+        .map(|axis| (dirty.max[axis] - dirty.min[axis]).max(0) as u64)
+        .product()
+}
+
+/// Smallest box holding both
+pub fn union_light_boxes(a: &LightDirtyBox, b: &LightDirtyBox) -> LightDirtyBox {
+    LightDirtyBox {
+        min: [0, 1, 2].map(|axis| a.min[axis].min(b.min[axis])),
+        max: [0, 1, 2].map(|axis| a.max[axis].max(b.max[axis])),
+    }
+}
+
+/// Whether two boxes overlap or share a face, edge or corner
+pub fn light_boxes_touch(a: &LightDirtyBox, b: &LightDirtyBox) -> bool {
+    (0..3).all(|axis| a.min[axis] <= b.max[axis] && b.min[axis] <= a.max[axis])
+}
+
+fn chunk_bounds(chunk: ChunkPos, chunk_size: u32) -> LightDirtyBox {
+    let size = chunk_size as i32;
+    let min = [chunk.x * size, chunk.y * size, chunk.z * size];
+    LightDirtyBox {
+        min,
+        max: min.map(|v| v + size),
+    }
+}
+
+/// Mark a box dirty; it grows by the halo and is split among the chunks it
+/// overlaps
+pub fn record_light_edit_box(data: &mut LightDirtyData, dirty: LightDirtyBox) {
+    let halo = data.halo as i32;
+    let grown = LightDirtyBox {
+        min: dirty.min.map(|v| v - halo),
+        max: dirty.max.map(|v| v + halo),
+    };
+    if light_box_voxels(&grown) == 0 {
+        return;
+    }
+    let size = data.chunk_size as i32;
+    let first = grown.min.map(|v| floor_div(v, size));
+    let last = grown.max.map(|v| floor_div(v - 1, size));
+    for x in first[0]..=last[0] {
+        for y in first[1]..=last[1] {
+            for z in first[2]..=last[2] {
+                let chunk = ChunkPos::new(x, y, z);
+                let bounds = chunk_bounds(chunk, data.chunk_size);
+                let clipped = LightDirtyBox {
+                    min: [0, 1, 2].map(|axis| grown.min[axis].max(bounds.min[axis])),
+                    max: [0, 1, 2].map(|axis| grown.max[axis].min(bounds.max[axis])),
+                };
+                data.chunks
+                    .entry(chunk)
+                    .and_modify(|existing| *existing = union_light_boxes(existing, &clipped))
+                    .or_insert(clipped);
+            }
+        }
+    }
+    let center = dirty.min.map(|v| floor_div(v, size));
+    data.edited_chunks
+        .insert(ChunkPos::new(center[0], center[1], center[2]));
+    data.stats.edits_recorded += 1;
+}
+
+/// Mark the voxels around one edited block dirty
+pub fn record_light_edit(data: &mut LightDirtyData, pos: VoxelPos) {
+    record_light_edit_box(
+        data,
+        LightDirtyBox {
+            min: [pos.x, pos.y, pos.z],
+            max: [pos.x + 1, pos.y + 1, pos.z + 1],
+        },
+    );
+}
+
+/// Mark a whole chunk dirty (e.g. after a bulk fill or a fresh upload)
+pub fn mark_chunk_light_dirty(data: &mut LightDirtyData, chunk: ChunkPos) {
+    record_light_edit_box(data, chunk_bounds(chunk, data.chunk_size));
+}
+
+/// Whether any edit waits for a relight
+pub fn has_light_dirty(data: &LightDirtyData) -> bool {
+    !data.chunks.is_empty()
+}
+
+/// Merge touching boxes while their union holds at most `max_waste` times
+/// the voxels of both, until no pair qualifies
+pub fn merge_light_dirty_boxes(
+    mut boxes: Vec<LightDirtyBox>,
+    max_waste: f32,
+) -> Vec<LightDirtyBox> {
+    let mut merged = true;
+    while merged {
+        merged = false;
+        'outer: for i in 0..boxes.len() {
+            for j in (i + 1)..boxes.len() {
+                if !light_boxes_touch(&boxes[i], &boxes[j]) {
+                    continue;
+                }
+                let union = union_light_boxes(&boxes[i], &boxes[j]);
+                // Overlapping boxes count their shared voxels twice, which
+                // only makes merging them easier
+                let parts = light_box_voxels(&boxes[i]) + light_box_voxels(&boxes[j]);
+                if light_box_voxels(&union) as f64 <= parts as f64 * f64::from(max_waste) {
+                    boxes[i] = union;
+                    boxes.swap_remove(j);
+                    merged = true;
+                    break 'outer;
+                }
+            }
+        }
+    }
+    boxes
+}
+
+/// Split a box into slabs of at most `max_voxels` (along z, then y rows)
+/// so each fits one dispatch
+pub fn split_light_box(dirty: &LightDirtyBox, max_voxels: u64) -> Vec<LightDirtyBox> {
+    let [sx, sy, _] = [0, 1, 2].map(|axis| (dirty.max[axis] - dirty.min[axis]).max(0) as u64);
+    let layer = sx * sy;
+    if layer == 0 || light_box_voxels(dirty) <= max_voxels {
+        return vec![*dirty];
+    }
+    if layer > max_voxels {
+        let rows = (max_voxels / sx.max(1)).max(1) as i32;
+        let mut slabs = Vec::new();
+        for z in dirty.min[2]..dirty.max[2] {
+            let mut y = dirty.min[1];
+            while y < dirty.max[1] {
+                let top = (y + rows).min(dirty.max[1]);
+                slabs.push(LightDirtyBox {
+                    min: [dirty.min[0], y, z],
+                    max: [dirty.max[0], top, z + 1],
+                });
+                y = top;
+            }
+        }
+        return slabs;
+    }
+    let depth = (max_voxels / layer) as i32;
+    let mut slabs = Vec::new();
+    let mut z = dirty.min[2];
+    while z < dirty.max[2] {
+        let top = (z + depth).min(dirty.max[2]);
+        slabs.push(LightDirtyBox {
+            min: [dirty.min[0], dirty.min[1], z],
+            max: [dirty.max[0], dirty.max[1], top],
+        });
+        z = top;
+    }
+    slabs
+}
+
+/// Take the dirty boxes as merged dispatch regions and clear the tracker
+pub fn take_light_dirty_regions(data: &mut LightDirtyData) -> Vec<LightDirtyBox> {
+    if data.chunks.is_empty() {
+        return Vec::new();
+    }
+    let mut boxes: Vec<LightDirtyBox> = data.chunks.drain().map(|(_, dirty)| dirty).collect();
+    // Deterministic merge order regardless of hash order
+    boxes.sort_by_key(|dirty| (dirty.min, dirty.max));
+    let regions = merge_light_dirty_boxes(boxes, LIGHT_DIRTY_MERGE_WASTE);
+
+    let mut neighbourhood = HashSet::new();
+    for chunk in data.edited_chunks.drain() {
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    neighbourhood.insert(ChunkPos::new(chunk.x + dx, chunk.y + dy, chunk.z + dz));
+                }
+            }
+        }
+    }
+    let stats = &mut data.stats;
+    stats.regions_dispatched += regions.len() as u64;
+    stats.voxels_dispatched += regions.iter().map(light_box_voxels).sum::<u64>();
+    stats.full_relight_voxels += neighbourhood.len() as u64 * u64::from(data.chunk_size).pow(3);
+    regions
+}
+
+/// How many times fewer voxels incremental relights touched than whole-chunk
+/// relights would have (1.0 before any relight)
+pub fn light_dirty_speedup(stats: &LightDirtyStats) -> f32 {
+    if stats.voxels_dispatched == 0 {
+        return 1.0;
+    }
+    stats.full_relight_voxels as f32 / stats.voxels_dispatched as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_edit_dirties_a_small_box() {
+        let mut data = create_light_dirty(32, 2);
+        record_light_edit(&mut data, VoxelPos::new(10, 10, 10));
+        record_light_edit(&mut data, VoxelPos::new(12, 10, 10));
+        assert_eq!(data.chunks.len(), 1);
+        let regions = take_light_dirty_regions(&mut data);
+        assert_eq!(
+            regions,
+            vec![LightDirtyBox {
+                min: [8, 8, 8],
+                max: [15, 13, 13]
+            }]
+        );
+        assert!(!has_light_dirty(&data));
+        assert_eq!(data.stats.voxels_dispatched, 7 * 5 * 5);
+        assert!(light_dirty_speedup(&data.stats) > 1000.0);
+    }
+
+    #[test]
+    fn test_edit_on_chunk_border_merges_across_chunks() {
+        let mut data = create_light_dirty(32, 1);
+        record_light_edit(&mut data, VoxelPos::new(31, 5, 5));
+        // Halo spills into the next chunk along x
+        assert_eq!(data.chunks.len(), 2);
+        let regions = take_light_dirty_regions(&mut data);
+        assert_eq!(
+            regions,
+            vec![LightDirtyBox {
+                min: [30, 4, 4],
+                max: [33, 7, 7]
+            }]
+        );
+
+        // Edits in one chunk share its min/max box; boxes of different
+        // chunks that do not touch stay separate regions
+        record_light_edit(&mut data, VoxelPos::new(1, 1, 1));
+        record_light_edit(&mut data, VoxelPos::new(25, 25, 25));
+        assert_eq!(take_light_dirty_regions(&mut data).len(), 1);
+        record_light_edit(&mut data, VoxelPos::new(1, 1, 1));
+        record_light_edit(&mut data, VoxelPos::new(40, 1, 1));
+        assert_eq!(take_light_dirty_regions(&mut data).len(), 2);
+    }
+
+    #[test]
+    fn test_split_keeps_every_voxel() {
+        let dirty = LightDirtyBox {
+            min: [0, 0, 0],
+            max: [10, 10, 10],
+        };
+        for max_voxels in [1000, 250, 37, 5] {
+            let slabs = split_light_box(&dirty, max_voxels);
+            assert!(slabs
+                .iter()
+                .all(|slab| light_box_voxels(slab) <= max_voxels.max(10)));
+            assert_eq!(slabs.iter().map(light_box_voxels).sum::<u64>(), 1000);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Light Query Data - Pure DOP
+//!
+//! Gameplay questions about light ("is this spot dark and sheltered?")
+//! answered on the CPU from the shadow cache, without GPU readbacks on the
+//! query path. Positions whose chunk is not cached yet answer `None` and
+//! queue a readback, so spawn logic simply retries them on a later tick.
+//!
+//! NO METHODS - just data.
+
+use super::LightLevel;
+use crate::world::core::VoxelPos;
+
+/// Sky exposure a spawn rule requires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkyExposure {
+    Any,
+    /// Nothing blocks skylight above the position
+    Open,
+    /// Something blocks skylight above the position
+    Sheltered,
+}
+
+/// Light conditions a spawn position must meet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpawnLightRule {
+    pub max_block_light: u8,
+    pub max_sky_light: u8,
+    pub sky: SkyExposure,
+}
+
+/// Light at one voxel; `None` fields are not known yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LightSample {
+    pub position: VoxelPos,
+    pub light: Option<LightLevel>,
+    pub sky_visible: Option<bool>,
+}
@@ -0,0 +1,212 @@
+//! Light Query Operations - Pure DOP functions
+//!
+//! Light comes from the shadow cache's copy of the GPU light buffers, sky
+//! visibility from its column heightmaps. After a block edit the cached light
+//! of that chunk is stale until the next readback: `get_light_at` still
+//! returns it, and `is_sky_visible` falls back to the heightmap.
+
+use super::light_query_data::{LightSample, SkyExposure, SpawnLightRule};
+use super::LightLevel;
+use crate::constants::lighting::{
+    DEFAULT_SPAWN_MAX_BLOCK_LIGHT, DEFAULT_SPAWN_MAX_SKY_LIGHT, MAX_LIGHT_LEVEL,
+};
+use crate::world::core::VoxelPos;
+use crate::world::storage::{
+    shadow_column_height, shadow_get_light, unpack_voxel_light, ShadowCacheData, ShadowLightLookup,
+};
+
+/// Dark and sheltered: no block light, dim sky light, roof overhead
+pub fn default_spawn_light_rule() -> SpawnLightRule {
+    SpawnLightRule {
+        max_block_light: DEFAULT_SPAWN_MAX_BLOCK_LIGHT,
+        max_sky_light: DEFAULT_SPAWN_MAX_SKY_LIGHT,
+        sky: SkyExposure::Sheltered,
+    }
+}
+
+/// Block and sky light at a voxel (`None` until its chunk is cached)
+pub fn get_light_at(cache: &mut ShadowCacheData, pos: VoxelPos) -> Option<LightLevel> {
+    match shadow_get_light(cache, pos) {
+        ShadowLightLookup::Hit { light, .. } => {
+            let (block, sky) = unpack_voxel_light(light);
+            Some(LightLevel::new(sky, block))
+        }
+        ShadowLightLookup::Miss => None,
+    }
+}
+
+/// Whether nothing blocks skylight above a voxel (`None` while unknown)
+pub fn is_sky_visible(cache: &mut ShadowCacheData, pos: VoxelPos) -> Option<bool> {
+    if shadow_column_height(cache, pos.x, pos.z).is_some_and(|height| height > pos.y) {
+        return Some(false);
+    }
+
+    match shadow_get_light(cache, pos) {
+        ShadowLightLookup::Hit { light, stale } => {
+            let (_, sky) = unpack_voxel_light(light);
+            if sky >= MAX_LIGHT_LEVEL {
+                Some(true)
+            } else if stale {
+                // Roof may have just been removed; wait for the relit chunk
+                None
+            } else {
+                Some(false)
+            }
+        }
+        ShadowLightLookup::Miss => None,
+    }
+}
+
+/// Light and sky visibility of one voxel
+pub fn sample_light(cache: &mut ShadowCacheData, pos: VoxelPos) -> LightSample {
+    LightSample {
+        position: pos,
+        light: get_light_at(cache, pos),
+        sky_visible: is_sky_visible(cache, pos),
+    }
+}
+
+/// `get_light_at` for many voxels
+pub fn get_light_batch(
+    cache: &mut ShadowCacheData,
+    positions: &[VoxelPos],
+) -> Vec<Option<LightLevel>> {
+    positions
+        .iter()
+        .map(|pos| get_light_at(cache, *pos))
+        .collect()
+}
+
+/// `is_sky_visible` for many voxels
+pub fn is_sky_visible_batch(
+    cache: &mut ShadowCacheData,
+    positions: &[VoxelPos],
+) -> Vec<Option<bool>> {
+    positions
+        .iter()
+        .map(|pos| is_sky_visible(cache, *pos))
+        .collect()
+}
+
+/// `sample_light` for many voxels
+pub fn sample_light_batch(cache: &mut ShadowCacheData, positions: &[VoxelPos]) -> Vec<LightSample> {
+    positions
+        .iter()
+        .map(|pos| sample_light(cache, *pos))
+        .collect()
+}
+
+/// Whether a sample meets a spawn rule (`None` while the data it needs is
+/// not cached)
+pub fn spawn_light_allows(rule: &SpawnLightRule, sample: &LightSample) -> Option<bool> {
+    let light = sample.light?;
+    if light.block > rule.max_block_light || light.sky > rule.max_sky_light {
+        return Some(false);
+    }
+    match rule.sky {
+        SkyExposure::Any => Some(true),
+        SkyExposure::Open => sample.sky_visible,
+        SkyExposure::Sheltered => sample.sky_visible.map(|visible| !visible),
+    }
+}
+
+/// Positions that meet a spawn rule. Positions not cached yet are left out
+/// (their readbacks are queued, so they can pass on a later call).
+pub fn filter_spawn_positions(
+    cache: &mut ShadowCacheData,
+    positions: &[VoxelPos],
+    rule: &SpawnLightRule,
+) -> Vec<VoxelPos> {
+    positions
+        .iter()
+        .copied()
+        .filter(|pos| spawn_light_allows(rule, &sample_light(cache, *pos)) == Some(true))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::compute::ModificationCommand;
+    use crate::world::core::{BlockId, ChunkPos, DEFAULT_CHUNK_LAYOUT};
+    use crate::world::storage::{
+        apply_modifications_to_shadow, create_shadow_cache, insert_shadow_chunk, VoxelData,
+    };
+
+    /// Chunk open to the sky except for a stone roof at y = 10 over x < 4
+    fn roofed_chunk() -> Vec<VoxelData> {
+        let size = DEFAULT_CHUNK_LAYOUT.size;
+        let mut voxels = Vec::with_capacity(DEFAULT_CHUNK_LAYOUT.voxels_per_chunk as usize);
+        for _z in 0..size {
+            for y in 0..size {
+                for x in 0..size {
+                    let voxel = if x < 4 && y == 10 {
+                        VoxelData::new(BlockId::STONE.0, 0, 0, 0)
+                    } else if x < 4 && y < 10 {
+                        VoxelData::new(0, if x == 0 { 12 } else { 0 }, 3, 0)
+                    } else {
+                        VoxelData::new(0, 0, 15, 0)
+                    };
+                    voxels.push(voxel);
+                }
+            }
+        }
+        voxels
+    }
+
+    #[test]
+    fn test_light_and_sky_queries() {
+        let mut cache = create_shadow_cache(4, DEFAULT_CHUNK_LAYOUT);
+        let under_roof = VoxelPos::new(2, 5, 2);
+        let open = VoxelPos::new(8, 5, 2);
+
+        assert_eq!(get_light_at(&mut cache, under_roof), None);
+        assert!(cache.pending.contains(&ChunkPos::new(0, 0, 0)));
+
+        insert_shadow_chunk(&mut cache, ChunkPos::new(0, 0, 0), &roofed_chunk());
+        assert_eq!(
+            get_light_at(&mut cache, under_roof),
+            Some(LightLevel::new(3, 0))
+        );
+        assert_eq!(
+            get_light_batch(&mut cache, &[VoxelPos::new(0, 5, 2), open]),
+            vec![Some(LightLevel::new(3, 12)), Some(LightLevel::new(15, 0))]
+        );
+        assert_eq!(
+            is_sky_visible_batch(&mut cache, &[under_roof, open]),
+            vec![Some(false), Some(true)]
+        );
+
+        let rule = default_spawn_light_rule();
+        let candidates = [
+            under_roof,
+            VoxelPos::new(0, 5, 2),
+            open,
+            VoxelPos::new(200, 5, 2),
+        ];
+        assert_eq!(
+            filter_spawn_positions(&mut cache, &candidates, &rule),
+            vec![under_roof]
+        );
+    }
+
+    #[test]
+    fn test_heightmap_follows_block_edits() {
+        let mut cache = create_shadow_cache(4, DEFAULT_CHUNK_LAYOUT);
+        insert_shadow_chunk(&mut cache, ChunkPos::new(0, 0, 0), &roofed_chunk());
+        let open = VoxelPos::new(8, 5, 2);
+        let under_roof = VoxelPos::new(2, 5, 2);
+
+        // Placing a roof block hides the sky at once, before any relight
+        apply_modifications_to_shadow(
+            &mut cache,
+            &[ModificationCommand::set_block(8, 12, 2, BlockId::STONE.0)],
+        );
+        assert_eq!(is_sky_visible(&mut cache, open), Some(false));
+
+        // Breaking the old roof leaves the stale sky light undecided
+        apply_modifications_to_shadow(&mut cache, &[ModificationCommand::break_block(2, 10, 2)]);
+        assert_eq!(is_sky_visible(&mut cache, under_roof), None);
+        assert!(cache.pending.contains(&ChunkPos::new(0, 0, 0)));
+    }
+}
@@ -0,0 +1,106 @@
+//! Light Reference Data - Pure DOP
+//!
+//! CPU reference for block and sky light propagation, used by tests to
+//! check the GPU lighting kernels. A fixture is a small volume of opaque
+//! and transparent voxels with light emitters; both the CPU reference and
+//! the GPU kernel light it with the same rules, and the harness diffs the
+//! results voxel by voxel. Operations live in light_reference_operations.rs.
+//!
+//! Rules (levels 0-15):
+//! - Block light: a transparent voxel takes the brighter of its own
+//!   emission and its brightest face neighbour minus `LIGHT_FALLOFF`.
+//!   Opaque voxels keep only their own emission.
+//! - Sky light: with the sky open, full skylight enters the top layer and
+//!   travels straight down without loss; every other step loses
+//!   `LIGHT_FALLOFF`. Opaque voxels have none.
+//! - Nothing outside the fixture volume is lit.
+//!
+//! NO METHODS - just data.
+
+use super::LightType;
+
+/// Volume to light
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LightFixture {
+    pub name: String,
+    /// Voxels per axis
+    pub size: [u32; 3],
+    /// Per voxel, x fastest, then y, then z
+    pub opaque: Vec<bool>,
+    /// Block light each voxel emits
+    pub emission: Vec<u8>,
+    /// Skylight enters through the top face
+    pub sky_open: bool,
+}
+
+/// Lit fixture; levels are stored like `opaque`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LightField {
+    pub size: [u32; 3],
+    pub block: Vec<u8>,
+    pub sky: Vec<u8>,
+}
+
+/// How far the GPU may stray from the reference
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LightTolerance {
+    /// Level difference per voxel and channel that still counts as a match
+    pub max_level_delta: u8,
+    /// Voxels beyond `max_level_delta` that still count as a pass
+    pub max_mismatched_voxels: usize,
+}
+
+/// One voxel channel that differs by more than the tolerance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LightMismatch {
+    /// Position inside the fixture (x, y, z)
+    pub local: [u32; 3],
+    pub channel: LightType,
+    /// CPU reference level
+    pub expected: u8,
+    /// GPU level
+    pub actual: u8,
+}
+
+/// Voxel by voxel comparison of a GPU result with the reference
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LightFieldDiff {
+    /// Voxels compared (each has a block and a sky channel)
+    pub compared_voxels: usize,
+    /// Voxels with any difference at all
+    pub differing_voxels: usize,
+    /// Voxels with a channel beyond `max_level_delta`
+    pub mismatched_voxels: usize,
+    /// Largest level difference on either channel
+    pub max_block_delta: u8,
+    pub max_sky_delta: u8,
+    /// First few mismatches, in storage order
+    pub mismatches: Vec<LightMismatch>,
+    /// GPU result has a different size; nothing was compared
+    pub size_mismatch: bool,
+}
+
+/// Result of checking one fixture
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LightFixtureOutcome {
+    Matched,
+    /// Differs, but no more than the tolerance allows
+    WithinTolerance(LightFieldDiff),
+    Regressed(LightFieldDiff),
+    /// The GPU path could not run the fixture
+    Failed(String),
+}
+
+/// Outcome for one fixture
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LightFixtureResult {
+    pub fixture: String,
+    pub outcome: LightFixtureOutcome,
+}
+
+/// Outcome of a harness run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LightHarnessReport {
+    pub tolerance: LightTolerance,
+    pub results: Vec<LightFixtureResult>,
+}
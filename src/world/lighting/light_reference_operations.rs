@@ -0,0 +1,459 @@
+//! Light Reference Operations - Pure DOP functions
+//!
+//! Build lighting fixtures, light them on the CPU, and diff a GPU result
+//! against the reference. `run_light_harness` takes the GPU path as a
+//! closure (see `GpuLightFixtureRunner`), so the comparison itself runs
+//! without a device.
+
+use super::light_reference_data::{
+    LightField, LightFieldDiff, LightFixture, LightFixtureOutcome, LightFixtureResult,
+    LightHarnessReport, LightMismatch, LightTolerance,
+};
+use super::LightType;
+use crate::constants::lighting::{
+    LIGHT_FALLOFF, LIGHT_FIXTURE_CHUNK_SIZE, LIGHT_MAX_MISMATCHES_ENV_VAR, LIGHT_TOLERANCE_ENV_VAR,
+    MAX_LIGHT_LEVEL, MAX_REPORTED_LIGHT_MISMATCHES,
+};
+use crate::world::core::BlockId;
+use crate::world::data_types::ChunkData;
+use std::collections::VecDeque;
+
+/// Voxels whose light still has to spread
+type LightQueue = VecDeque<[u32; 3]>;
+
+/// Face neighbours; index 3 steps down
+const FACE_OFFSETS: [[i32; 3]; 6] = [
+    [1, 0, 0],
+    [-1, 0, 0],
+    [0, 1, 0],
+    [0, -1, 0],
+    [0, 0, 1],
+    [0, 0, -1],
+];
+
+/// Empty, unlit fixture
+pub fn create_light_fixture(name: &str, size: [u32; 3], sky_open: bool) -> LightFixture {
+    let voxels = (size[0] * size[1] * size[2]) as usize;
+    LightFixture {
+        name: name.to_string(),
+        size,
+        opaque: vec![false; voxels],
+        emission: vec![0; voxels],
+        sky_open,
+    }
+}
+
+/// Storage index of a position inside a volume (x fastest, then y, then z)
+pub fn light_fixture_index(size: [u32; 3], local: [u32; 3]) -> usize {
+    (local[0] + local[1] * size[0] + local[2] * size[0] * size[1]) as usize
+}
+
+/// Make the voxels in `min..max` opaque (or open them again); clamped to
+/// the fixture
+pub fn set_fixture_box(fixture: &mut LightFixture, min: [u32; 3], max: [u32; 3], opaque: bool) {
+    let size = fixture.size;
+    for z in min[2]..max[2].min(size[2]) {
+        for y in min[1]..max[1].min(size[1]) {
+            for x in min[0]..max[0].min(size[0]) {
+                fixture.opaque[light_fixture_index(size, [x, y, z])] = opaque;
+            }
+        }
+    }
+}
+
+/// Place a light source; levels above `MAX_LIGHT_LEVEL` are clamped
+pub fn set_fixture_emitter(fixture: &mut LightFixture, local: [u32; 3], level: u8) {
+    if local
+        .iter()
+        .zip(&fixture.size)
+        .all(|(axis, size)| axis < size)
+    {
+        fixture.emission[light_fixture_index(fixture.size, local)] = level.min(MAX_LIGHT_LEVEL);
+    }
+}
+
+/// Fixture from a chunk's blocks, with opacity and emission looked up per
+/// block (e.g. from the block registry)
+pub fn light_fixture_from_chunk(
+    name: &str,
+    chunk: &ChunkData,
+    is_opaque: impl Fn(BlockId) -> bool,
+    emission: impl Fn(BlockId) -> u8,
+    sky_open: bool,
+) -> LightFixture {
+    let edge = (chunk.blocks.len() as f64).cbrt().round() as u32;
+    LightFixture {
+        name: name.to_string(),
+        size: [edge; 3],
+        opaque: chunk.blocks.iter().map(|&block| is_opaque(block)).collect(),
+        emission: chunk
+            .blocks
+            .iter()
+            .map(|&block| emission(block).min(MAX_LIGHT_LEVEL))
+            .collect(),
+        sky_open,
+    }
+}
+
+/// Fixtures covering falloff, occlusion, skylight under overhangs, sealed
+/// rooms and a chunk-sized cave system
+pub fn default_light_fixtures() -> Vec<LightFixture> {
+    let mut open_field = create_light_fixture("open_field_torch", [17, 8, 17], true);
+    set_fixture_box(&mut open_field, [0, 0, 0], [17, 1, 17], true);
+    set_fixture_emitter(&mut open_field, [8, 1, 8], 14);
+
+    let mut wall = create_light_fixture("wall_with_doorway", [24, 6, 9], false);
+    set_fixture_box(&mut wall, [12, 0, 0], [13, 6, 9], true);
+    set_fixture_box(&mut wall, [12, 0, 4], [13, 2, 5], false);
+    set_fixture_emitter(&mut wall, [4, 1, 4], 15);
+
+    let mut overhang = create_light_fixture("overhang", [20, 10, 6], true);
+    set_fixture_box(&mut overhang, [0, 0, 0], [20, 1, 6], true);
+    set_fixture_box(&mut overhang, [0, 5, 0], [12, 6, 6], true);
+
+    let mut room = create_light_fixture("sealed_room", [12, 12, 12], true);
+    set_fixture_box(&mut room, [2, 2, 2], [10, 10, 10], true);
+    set_fixture_box(&mut room, [3, 3, 3], [9, 9, 9], false);
+    set_fixture_emitter(&mut room, [5, 3, 5], 12);
+    // Glowing block set into the wall still lights the room
+    set_fixture_emitter(&mut room, [8, 8, 9], 9);
+
+    let size = LIGHT_FIXTURE_CHUNK_SIZE;
+    let mut caves = create_light_fixture("chunk_caves", [size; 3], true);
+    for z in 0..size {
+        for y in 0..size {
+            for x in 0..size {
+                // Cheap deterministic hash: rock with tunnels and pockets
+                let hash = (x.wrapping_mul(73_856_093)
+                    ^ y.wrapping_mul(19_349_663)
+                    ^ z.wrapping_mul(83_492_791))
+                    % 7;
+                let tunnel = (x + z) % 9 < 2 || y % 11 == 5;
+                let opaque = y < size - 6 && !tunnel && hash != 0;
+                caves.opaque[light_fixture_index([size; 3], [x, y, z])] = opaque;
+            }
+        }
+    }
+    for (i, level) in [15, 13, 10, 7].into_iter().enumerate() {
+        let offset = 3 + i as u32 * 7;
+        set_fixture_emitter(&mut caves, [offset, 5, size - 1 - offset], level);
+    }
+
+    vec![open_field, wall, overhang, room, caves]
+}
+
+/// Light a fixture on the CPU (breadth-first flood fill from every source)
+pub fn propagate_light_reference(fixture: &LightFixture) -> LightField {
+    let size = fixture.size;
+    let voxels = fixture.opaque.len();
+    let mut field = LightField {
+        size,
+        block: fixture.emission.clone(),
+        sky: vec![0; voxels],
+    };
+
+    let mut queue = LightQueue::new();
+    for_each_voxel(size, |local| {
+        if fixture.emission[light_fixture_index(size, local)] > 0 {
+            queue.push_back(local);
+        }
+    });
+    flood_light(fixture, &mut field.block, &mut queue, false);
+
+    if fixture.sky_open && size[1] > 0 {
+        let top = size[1] - 1;
+        for z in 0..size[2] {
+            for x in 0..size[0] {
+                let index = light_fixture_index(size, [x, top, z]);
+                if !fixture.opaque[index] {
+                    field.sky[index] = MAX_LIGHT_LEVEL;
+                    queue.push_back([x, top, z]);
+                }
+            }
+        }
+    }
+    flood_light(fixture, &mut field.sky, &mut queue, true);
+    field
+}
+
+fn for_each_voxel(size: [u32; 3], mut visit: impl FnMut([u32; 3])) {
+    for z in 0..size[2] {
+        for y in 0..size[1] {
+            for x in 0..size[0] {
+                visit([x, y, z]);
+            }
+        }
+    }
+}
+
+/// Spread `levels` from the queued voxels into transparent neighbours.
+/// Skylight at full strength travels down without loss.
+fn flood_light(fixture: &LightFixture, levels: &mut [u8], queue: &mut LightQueue, sky: bool) {
+    let size = fixture.size;
+    while let Some(local) = queue.pop_front() {
+        let level = levels[light_fixture_index(size, local)];
+        for (face, offset) in FACE_OFFSETS.iter().enumerate() {
+            let neighbour = [0, 1, 2].map(|axis| local[axis] as i32 + offset[axis]);
+            if (0..3).any(|axis| neighbour[axis] < 0 || neighbour[axis] >= size[axis] as i32) {
+                continue;
+            }
+            let neighbour = neighbour.map(|axis| axis as u32);
+            let index = light_fixture_index(size, neighbour);
+            if fixture.opaque[index] {
+                continue;
+            }
+            let downward = face == 3;
+            let spread = if sky && downward && level == MAX_LIGHT_LEVEL {
+                MAX_LIGHT_LEVEL
+            } else {
+                level.saturating_sub(LIGHT_FALLOFF)
+            };
+            if spread > levels[index] {
+                levels[index] = spread;
+                queue.push_back(neighbour);
+            }
+        }
+    }
+}
+
+/// Compare `actual` (GPU) with `expected` (reference) voxel by voxel
+pub fn diff_light_fields(
+    expected: &LightField,
+    actual: &LightField,
+    tolerance: &LightTolerance,
+) -> LightFieldDiff {
+    let mut diff = LightFieldDiff::default();
+    let voxels = expected.block.len();
+    if expected.size != actual.size || actual.block.len() != voxels || actual.sky.len() != voxels {
+        diff.size_mismatch = true;
+        return diff;
+    }
+
+    diff.compared_voxels = voxels;
+    let size = expected.size;
+    for_each_voxel(size, |local| {
+        let index = light_fixture_index(size, local);
+        let channels = [
+            (LightType::Block, expected.block[index], actual.block[index]),
+            (LightType::Sky, expected.sky[index], actual.sky[index]),
+        ];
+        let mut differs = false;
+        let mut mismatched = false;
+        for (channel, want, got) in channels {
+            let delta = want.abs_diff(got);
+            match channel {
+                LightType::Block => diff.max_block_delta = diff.max_block_delta.max(delta),
+                LightType::Sky => diff.max_sky_delta = diff.max_sky_delta.max(delta),
+            }
+            differs |= delta > 0;
+            if delta > tolerance.max_level_delta {
+                mismatched = true;
+                if diff.mismatches.len() < MAX_REPORTED_LIGHT_MISMATCHES {
+                    diff.mismatches.push(LightMismatch {
+                        local,
+                        channel,
+                        expected: want,
+                        actual: got,
+                    });
+                }
+            }
+        }
+        diff.differing_voxels += differs as usize;
+        diff.mismatched_voxels += mismatched as usize;
+    });
+    diff
+}
+
+/// Tolerance from `HEARTH_LIGHT_TOLERANCE` (levels) and
+/// `HEARTH_LIGHT_MAX_MISMATCHES` (voxels); exact by default
+pub fn light_tolerance_from_env() -> LightTolerance {
+    let read = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .unwrap_or(0)
+    };
+    LightTolerance {
+        max_level_delta: read(LIGHT_TOLERANCE_ENV_VAR).min(u8::MAX as usize) as u8,
+        max_mismatched_voxels: read(LIGHT_MAX_MISMATCHES_ENV_VAR),
+    }
+}
+
+/// Light every fixture with the reference and with `gpu`, and diff them
+pub fn run_light_harness(
+    fixtures: &[LightFixture],
+    tolerance: &LightTolerance,
+    mut gpu: impl FnMut(&LightFixture) -> Result<LightField, String>,
+) -> LightHarnessReport {
+    let results = fixtures
+        .iter()
+        .map(|fixture| {
+            let outcome = match gpu(fixture) {
+                Err(e) => LightFixtureOutcome::Failed(e),
+                Ok(actual) => {
+                    let expected = propagate_light_reference(fixture);
+                    let diff = diff_light_fields(&expected, &actual, tolerance);
+                    if diff.size_mismatch
+                        || diff.mismatched_voxels > tolerance.max_mismatched_voxels
+                    {
+                        LightFixtureOutcome::Regressed(diff)
+                    } else if diff.differing_voxels > 0 {
+                        LightFixtureOutcome::WithinTolerance(diff)
+                    } else {
+                        LightFixtureOutcome::Matched
+                    }
+                }
+            };
+            LightFixtureResult {
+                fixture: fixture.name.clone(),
+                outcome,
+            }
+        })
+        .collect();
+    LightHarnessReport {
+        tolerance: *tolerance,
+        results,
+    }
+}
+
+/// True when no fixture regressed or failed
+pub fn light_harness_passed(report: &LightHarnessReport) -> bool {
+    report.results.iter().all(|result| {
+        matches!(
+            result.outcome,
+            LightFixtureOutcome::Matched | LightFixtureOutcome::WithinTolerance(_)
+        )
+    })
+}
+
+/// One line per fixture, with the first mismatches of regressed ones
+pub fn format_light_report(report: &LightHarnessReport) -> String {
+    let mut summary = format!(
+        "Lighting harness (tolerance: {} levels, {} voxels)\n",
+        report.tolerance.max_level_delta, report.tolerance.max_mismatched_voxels
+    );
+    let deltas = |diff: &LightFieldDiff| {
+        format!(
+            "{} of {} voxels differ, max delta block {} sky {}",
+            diff.differing_voxels, diff.compared_voxels, diff.max_block_delta, diff.max_sky_delta
+        )
+    };
+    for result in &report.results {
+        let name = &result.fixture;
+        match &result.outcome {
+            LightFixtureOutcome::Matched => summary.push_str(&format!("{}: matched\n", name)),
+            LightFixtureOutcome::WithinTolerance(diff) => {
+                summary.push_str(&format!("{}: within tolerance, {}\n", name, deltas(diff)));
+            }
+            LightFixtureOutcome::Failed(e) => {
+                summary.push_str(&format!("{}: GPU run failed: {}\n", name, e));
+            }
+            LightFixtureOutcome::Regressed(diff) if diff.size_mismatch => {
+                summary.push_str(&format!("{}: GPU result has the wrong size\n", name));
+            }
+            LightFixtureOutcome::Regressed(diff) => {
+                summary.push_str(&format!(
+                    "{}: REGRESSED, {} voxels outside tolerance, {}\n",
+                    name,
+                    diff.mismatched_voxels,
+                    deltas(diff)
+                ));
+                for mismatch in &diff.mismatches {
+                    summary.push_str(&format!(
+                        "    {:?} light at {:?}: expected {}, got {}\n",
+                        mismatch.channel, mismatch.local, mismatch.expected, mismatch.actual
+                    ));
+                }
+            }
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_light_falls_off_and_stops_at_walls() {
+        let fixtures = default_light_fixtures();
+        let level = |field: &LightField, local: [u32; 3], sky: bool| {
+            let index = light_fixture_index(field.size, local);
+            if sky {
+                field.sky[index]
+            } else {
+                field.block[index]
+            }
+        };
+
+        let field = propagate_light_reference(&fixtures[0]);
+        assert_eq!(level(&field, [8, 1, 8], false), 14);
+        assert_eq!(level(&field, [11, 1, 8], false), 11);
+        assert_eq!(level(&field, [8, 0, 8], false), 0, "floor is opaque");
+        assert_eq!(
+            level(&field, [0, 1, 0], true),
+            15,
+            "open sky reaches the floor"
+        );
+
+        // The torch reaches the far side only through the doorway
+        let field = propagate_light_reference(&fixtures[1]);
+        assert_eq!(level(&field, [12, 1, 4], false), 7);
+        assert_eq!(level(&field, [13, 1, 4], false), 6);
+        assert_eq!(level(&field, [13, 4, 0], false), 0);
+
+        // Skylight under the overhang fades with distance from its edge
+        let field = propagate_light_reference(&fixtures[2]);
+        assert_eq!(level(&field, [12, 1, 3], true), 15);
+        assert_eq!(level(&field, [11, 1, 3], true), 14);
+        assert_eq!(level(&field, [8, 1, 3], true), 11);
+        assert_eq!(level(&field, [5, 7, 3], true), 15, "above the roof");
+
+        let field = propagate_light_reference(&fixtures[3]);
+        assert_eq!(level(&field, [5, 5, 5], true), 0, "sealed from the sky");
+        assert_eq!(
+            level(&field, [8, 8, 8], false),
+            8,
+            "lit by the glowing wall"
+        );
+        assert_eq!(level(&field, [5, 3, 5], false), 12);
+    }
+
+    #[test]
+    fn test_harness_reports_tolerance_and_regressions() {
+        let fixtures = default_light_fixtures();
+        let reference = propagate_light_reference(&fixtures[0]);
+        let mut dimmed = reference.clone();
+        dimmed.block[light_fixture_index(reference.size, [9, 1, 8])] -= 1;
+        dimmed.sky[light_fixture_index(reference.size, [0, 5, 0])] = 12;
+
+        let exact = LightTolerance::default();
+        let diff = diff_light_fields(&reference, &dimmed, &exact);
+        assert_eq!((diff.differing_voxels, diff.mismatched_voxels), (2, 2));
+        assert_eq!((diff.max_block_delta, diff.max_sky_delta), (1, 3));
+        // Storage order: the sky voxel comes first
+        assert_eq!(diff.mismatches[0].channel, LightType::Sky);
+
+        let report = run_light_harness(&fixtures[..1], &exact, |_| Ok(dimmed.clone()));
+        assert!(!light_harness_passed(&report));
+        assert!(format_light_report(&report).contains("REGRESSED, 2 voxels"));
+
+        let loose = LightTolerance {
+            max_level_delta: 1,
+            max_mismatched_voxels: 1,
+        };
+        let report = run_light_harness(&fixtures[..1], &loose, |_| Ok(dimmed.clone()));
+        assert!(light_harness_passed(&report));
+        assert!(matches!(
+            report.results[0].outcome,
+            LightFixtureOutcome::WithinTolerance(_)
+        ));
+
+        let report = run_light_harness(&fixtures, &exact, |fixture| {
+            Ok(propagate_light_reference(fixture))
+        });
+        assert!(light_harness_passed(&report));
+        let report = run_light_harness(&fixtures[..1], &exact, |_| Err("no device".to_string()));
+        assert!(!light_harness_passed(&report));
+    }
+}
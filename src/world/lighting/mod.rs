@@ -3,6 +3,12 @@
 //! Complete lighting system migrated from CPU to GPU for optimal performance.
 //! Provides time-of-day, light propagation, and skylight calculations.
 
+mod light_dirty_data;
+mod light_dirty_operations;
+mod light_query_data;
+mod light_query_operations;
+mod light_reference_data;
+mod light_reference_operations;
 mod skylight;
 mod time_of_day;
 
@@ -12,7 +18,27 @@ use parking_lot::RwLock;
 use std::sync::Arc;
 use std::time::Duration;
 
-pub use skylight::SkylightCalculator;
+pub use light_dirty_data::{LightDirtyBox, LightDirtyData, LightDirtyStats};
+pub use light_dirty_operations::{
+    create_light_dirty, has_light_dirty, light_box_voxels, light_boxes_touch,
+    light_dirty_speedup, mark_chunk_light_dirty, merge_light_dirty_boxes, record_light_edit,
+    record_light_edit_box, split_light_box, take_light_dirty_regions, union_light_boxes,
+};
+pub use light_query_data::{LightSample, SkyExposure, SpawnLightRule};
+pub use light_query_operations::{
+    default_spawn_light_rule, filter_spawn_positions, get_light_at, get_light_batch,
+    is_sky_visible, is_sky_visible_batch, sample_light, sample_light_batch, spawn_light_allows,
+};
+pub use light_reference_data::{
+    LightField, LightFieldDiff, LightFixture, LightFixtureOutcome, LightFixtureResult,
+    LightHarnessReport, LightMismatch, LightTolerance,
+};
+pub use light_reference_operations::{
+    create_light_fixture, default_light_fixtures, diff_light_fields, format_light_report,
+    light_fixture_from_chunk, light_fixture_index, light_harness_passed, light_tolerance_from_env,
+    propagate_light_reference, run_light_harness, set_fixture_box, set_fixture_emitter,
+};
+pub use skylight::{block_light_emission, blocks_skylight, SkylightCalculator};
 pub use time_of_day::*;
 
 /// Types of light in the game
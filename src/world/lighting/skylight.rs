@@ -4,6 +4,7 @@
 //! compatible with the GPU-first architecture.
 //! All functions are pure DOP - take data, return results.
 
+use crate::constants::lighting::MAX_LIGHT_LEVEL;
 use crate::world::core::{BlockId, VoxelPos};
 use crate::world::{world_operations, data_types::WorldData};
 
@@ -17,11 +18,14 @@ impl SkylightCalculator {
     /// This recalculates skylight propagation when blocks change.
     /// In the GPU-first architecture, this would typically be done on GPU.
     pub fn update_column(world: &WorldData, x: i32, _y: i32, z: i32, chunk_size: u32) {
-        // Start from the top of the world
-        let mut current_light = 15u8; // Full skylight at top
+        // Everything above the surface is open sky at full light
+        let Some(surface) = world_operations::get_surface_height(world, x, z) else {
+            return;
+        };
+        let mut current_light = 15u8;
 
-        // Scan down the column
-        for y in (0..256).rev() {
+        // Scan down the column from the surface
+        for y in (0..=surface).rev() {
             let pos = VoxelPos::new(x, y, z);
             let block = world_operations::get_block(world, pos, chunk_size);
 
@@ -58,6 +62,20 @@ impl SkylightCalculator {
     }
 }
 
+/// Whether a block stops skylight from reaching the voxels below it
+pub fn blocks_skylight(block_id: BlockId) -> bool {
+    block_id != BlockId::AIR && !is_transparent(block_id)
+}
+
+/// Block light a block emits (0 for blocks that do not glow)
+pub fn block_light_emission(block_id: BlockId) -> u8 {
+    match block_id {
+        BlockId::GLOWSTONE | BlockId::LAVA => MAX_LIGHT_LEVEL,
+        BlockId::TORCH => MAX_LIGHT_LEVEL - 1,
+        _ => 0,
+    }
+}
+
 /// Helper function to check if a block is transparent for skylight
 /// Pure function - no world reference needed
 fn is_transparent(block_id: BlockId) -> bool {
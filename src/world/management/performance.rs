@@ -1,5 +1,6 @@
 //! Performance monitoring for unified world system
 
+use crate::world::compute::ChunkVerificationReport;
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
@@ -21,6 +22,13 @@ pub struct GenerationStats {
     pub backend: String,
     pub cache_hits: u64,
     pub cache_misses: u64,
+    /// Chunks checked by post-generation verification
+    pub chunks_verified: u64,
+    /// Chunks verification flagged as (nearly) empty
+    pub suspicious_chunks: u64,
+    pub chunks_regenerated: u64,
+    /// Suspicious chunks left as is after the last regeneration attempt
+    pub chunks_abandoned: u64,
 }
 
 /// Storage performance statistics
@@ -91,6 +99,15 @@ impl PerformanceMonitor {
         self.update_generation_stats();
     }
 
+    /// Accumulate the outcome of a chunk verification poll
+    pub fn record_chunk_verification(&mut self, report: &ChunkVerificationReport) {
+        let stats = &mut self.metrics.generation_stats;
+        stats.chunks_verified += report.chunks_verified;
+        stats.suspicious_chunks += report.suspicious_chunks;
+        stats.chunks_regenerated += report.chunks_regenerated;
+        stats.chunks_abandoned += report.chunks_abandoned;
+    }
+
     /// Record a compute pass time
     pub fn record_compute_time(&mut self, duration: Duration) {
         self.compute_times.push_back(duration);
@@ -206,6 +223,7 @@ impl PerformanceMonitor {
              ========================\n\
              Uptime: {:.2}s\n\
              Generation: {:.2}ms avg, {:.2}ms peak ({})\n\
+             Verification: {} checked, {} suspicious, {} regenerated, {} abandoned\n\
              Compute: {:.2}ms avg, {} passes\n\
              Memory: {:.1}MB / {:.1}MB peak\n\
              Chunks: {} loaded\n\
@@ -214,6 +232,10 @@ impl PerformanceMonitor {
             self.metrics.generation_stats.avg_generation_time_ms,
             self.metrics.generation_stats.peak_generation_time_ms,
             self.metrics.generation_stats.backend,
+            self.metrics.generation_stats.chunks_verified,
+            self.metrics.generation_stats.suspicious_chunks,
+            self.metrics.generation_stats.chunks_regenerated,
+            self.metrics.generation_stats.chunks_abandoned,
             self.metrics.compute_stats.avg_compute_time_ms,
             self.metrics.compute_stats.compute_passes,
             self.metrics.storage_stats.memory_usage_mb,
@@ -263,6 +285,10 @@ impl Default for GenerationStats {
             backend: "Unknown".to_string(),
             cache_hits: 0,
             cache_misses: 0,
+            chunks_verified: 0,
+            suspicious_chunks: 0,
+            chunks_regenerated: 0,
+            chunks_abandoned: 0,
         }
     }
 }
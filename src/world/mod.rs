@@ -29,14 +29,16 @@ pub mod generation;
 pub mod interfaces;
 pub mod lighting;
 pub mod management;
+pub mod season_data;
+pub mod season_operations;
 pub mod storage;
 pub mod weather_manager;
 pub mod world_operations;
 
 // Re-export core types for convenience
 pub use core::{
-    BlockFace, BlockId, BlockRegistry, ChunkPos, PhysicsProperties, Ray, RaycastHit,
-    RenderData, VoxelPos,
+    create_chunk_layout, BlockFace, BlockId, BlockRegistry, ChunkLayout, ChunkLayoutError,
+    ChunkPos, PhysicsProperties, Ray, RaycastHit, RenderData, VoxelPos, DEFAULT_CHUNK_LAYOUT,
 };
 
 // Re-export storage systems
@@ -44,6 +46,11 @@ pub use storage::{
     GpuChunk,
     GpuChunkManager,
     GpuChunkStats,
+    LightingStorageMode,
+    ShadowCacheData,
+    ShadowLookup,
+    SparseChunk,
+    SparseStorageStats,
     TempChunk,
     VoxelData,
     // GPU-first storage
@@ -102,22 +109,42 @@ pub use world_operations::{
     get_block, set_block, raycast, is_chunk_loaded, load_chunk, unload_chunk,
     get_chunks_in_radius, get_loaded_chunks, WorldModification,
     voxel_to_chunk, chunk_to_world, get_local_position,
-    get_world_size, get_world_seed, get_world_tick, get_active_chunk_count,
+    insert_chunk, insert_generated_chunk, get_world_size, get_world_seed, get_world_tick,
+    get_active_chunk_count,
+    get_world_chunk_layout, get_world_chunk_size,
     set_blocks_batch, get_blocks_batch, log_world_stats, validate_world_data,
+    normalize_region, region_size, copy_region, paste_region, fill_region,
+    get_surface_height, compute_chunk_column_tops, apply_chunk_column_tops,
+    rebuild_chunk_heightmap,
 };
+pub use data_types::{ColumnHeights, RegionBlocks, RegionPasteStats};
 
 // Re-export block system
 pub use blocks::register_basic_blocks;
 
 // Re-export lighting system
 pub use lighting::{
-    DayNightCycleData, LightLevel, LightType, LightUpdate, LightingStats, SkylightCalculator,
-    TimeOfDayData,
+    default_spawn_light_rule, filter_spawn_positions, get_light_at, get_light_batch,
+    is_sky_visible, is_sky_visible_batch, sample_light, sample_light_batch, spawn_light_allows,
+    DayNightCycleData, LightLevel, LightSample, LightType, LightUpdate, LightingStats,
+    SkyExposure, SkylightCalculator, SpawnLightRule, TimeOfDayData,
 };
 
 // Re-export weather system
 pub use weather_manager::{WeatherManager, WeatherZone};
 
+// Re-export season system
+pub use season_data::{
+    SavedWorldTime, Season, SeasonChange, SeasonConfig, SeasonData, SeasonParams, SeasonSnapshot,
+    SEASONS, SEASON_COUNT,
+};
+pub use season_operations::{
+    advance_world_days, apply_season_to_processes, apply_season_to_terrain_params,
+    blend_season_params, create_season_data, current_season_params, day_of_season,
+    default_season_config, load_world_time, pick_seasonal_weather, previous_season,
+    save_world_time, season_for_day, season_progress, season_snapshot, update_season_clock,
+};
+
 /// Helper function to convert voxel position to chunk position
 /// Following DOP principles - pure function that transforms data
 pub fn voxel_to_chunk_pos(voxel_pos: VoxelPos, chunk_size: u32) -> ChunkPos {
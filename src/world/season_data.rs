@@ -0,0 +1,100 @@
+//! Season Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Seasons count whole days of the day/night cycle: every
+//! `days_per_season` days the world moves to the next season. Each season
+//! has a profile of biome parameters (snow cover, grass and foliage tints,
+//! temperature, weather odds, crop growth rate); the parameters in effect
+//! fade from the previous season's profile over the first days of a
+//! season. Operations live in season_operations.rs.
+
+use crate::constants::seasons::WEATHER_TYPE_COUNT;
+use serde::{Deserialize, Serialize};
+
+/// Number of seasons in a year
+pub const SEASON_COUNT: usize = 4;
+
+/// Season of the world, in yearly order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Season {
+    Spring = 0,
+    Summer = 1,
+    Autumn = 2,
+    Winter = 3,
+}
+
+/// Every season in yearly order
+pub const SEASONS: [Season; SEASON_COUNT] = [
+    Season::Spring,
+    Season::Summer,
+    Season::Autumn,
+    Season::Winter,
+];
+
+/// Biome parameters of one season
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeasonParams {
+    /// Share of exposed surface covered by snow (0.0 - 1.0)
+    pub snow_cover: f32,
+    /// Multiplied into the biome grass color
+    pub grass_tint: [f32; 3],
+    /// Multiplied into the biome foliage color
+    pub foliage_tint: [f32; 3],
+    /// Added to the biome temperature (Celsius)
+    pub temperature_offset: f32,
+    /// Relative odds of each weather type, indexed by the
+    /// `constants::weather::WEATHER_*` values
+    pub weather_weights: [f32; WEATHER_TYPE_COUNT],
+    /// Speed of `ProcessCategory::Growth` processes (1.0 = normal)
+    pub growth_rate: f32,
+}
+
+/// Length of seasons and the profile of each
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeasonConfig {
+    pub days_per_season: u32,
+    /// Days at the start of a season over which its profile fades in
+    pub blend_days: f32,
+    /// Indexed by `Season`
+    pub profiles: [SeasonParams; SEASON_COUNT],
+}
+
+/// World clock and season state
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeasonData {
+    pub config: SeasonConfig,
+    /// Whole days since the world was created
+    pub day: u64,
+    /// Time of day at the last update (hours)
+    pub hours: f32,
+    pub season: Season,
+}
+
+/// Season transition reported by a clock update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeasonChange {
+    pub from: Season,
+    pub to: Season,
+    /// Day the new season started on
+    pub day: u64,
+}
+
+/// Season state as games query it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeasonSnapshot {
+    pub season: Season,
+    pub day: u64,
+    /// Day within the season, from 0
+    pub day_of_season: u32,
+    /// How far through the season the world is (0.0 - 1.0)
+    pub progress: f32,
+    /// Parameters in effect, blended across the transition
+    pub params: SeasonParams,
+}
+
+/// World time as written to a save
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedWorldTime {
+    pub day: u64,
+    pub hours: f32,
+}
@@ -0,0 +1,315 @@
+//! Season Operations - Pure functions over SeasonData
+//!
+//! `update_season_clock` counts days as the day/night cycle wraps past
+//! midnight and reports a `SeasonChange` when a new season starts. The
+//! blended `SeasonParams` are then applied where each parameter matters:
+//! terrain params for snow cover and temperature, the biome color map for
+//! tints (`renderer::apply_season_tint`), the process manager for crop
+//! growth, and `pick_seasonal_weather` for the game's weather rolls.
+
+use super::lighting::TimeOfDayData;
+use super::season_data::{
+    SavedWorldTime, Season, SeasonChange, SeasonConfig, SeasonData, SeasonParams, SeasonSnapshot,
+    SEASONS, SEASON_COUNT,
+};
+use crate::constants::blocks::SNOW;
+use crate::constants::seasons::{DAYS_PER_SEASON, MIN_SNOW_COVER, SEASON_BLEND_DAYS};
+use crate::constants::weather::WEATHER_CLEAR;
+use crate::gpu::types::terrain::{BlockDistribution, TerrainParams};
+use crate::process::{ProcessCategory, ProcessManager};
+
+/// Hours in one turn of the day/night cycle
+const HOURS_PER_DAY: f32 = 24.0;
+
+/// Default length and a temperate profile for each season
+pub fn default_season_config() -> SeasonConfig {
+    // Weights: clear, rain, snow, fog, storm, hail, sandstorm, blizzard
+    let season =
+        |snow_cover, grass_tint, foliage_tint, temperature_offset, weather_weights, growth_rate| {
+            SeasonParams {
+                snow_cover,
+                grass_tint,
+                foliage_tint,
+                temperature_offset,
+                weather_weights,
+                growth_rate,
+            }
+        };
+    SeasonConfig {
+        days_per_season: DAYS_PER_SEASON,
+        blend_days: SEASON_BLEND_DAYS,
+        profiles: [
+            season(
+                0.0,
+                [0.95, 1.05, 0.9],
+                [0.95, 1.05, 0.95],
+                0.0,
+                [5.0, 3.0, 0.2, 1.5, 1.0, 0.3, 0.2, 0.0],
+                1.25,
+            ),
+            season(
+                0.0,
+                [1.0, 1.0, 1.0],
+                [1.0, 1.0, 1.0],
+                6.0,
+                [6.0, 1.5, 0.0, 0.5, 2.0, 0.5, 0.5, 0.0],
+                1.0,
+            ),
+            season(
+                0.0,
+                [1.1, 0.9, 0.65],
+                [2.6, 1.0, 0.6],
+                -2.0,
+                [4.0, 3.0, 0.5, 2.0, 1.0, 0.3, 0.2, 0.0],
+                0.6,
+            ),
+            season(
+                0.8,
+                [0.8, 0.8, 0.85],
+                [0.7, 0.7, 0.75],
+                -12.0,
+                [4.0, 0.5, 3.0, 1.5, 0.5, 0.2, 0.1, 1.0],
+                0.1,
+            ),
+        ],
+    }
+}
+
+/// Start the world clock at day 0, midnight
+pub fn create_season_data(config: SeasonConfig) -> SeasonData {
+    SeasonData {
+        config,
+        day: 0,
+        hours: 0.0,
+        season: season_for_day(&config, 0),
+    }
+}
+
+/// Season a world day falls in
+pub fn season_for_day(config: &SeasonConfig, day: u64) -> Season {
+    let index = day / config.days_per_season.max(1) as u64 % SEASON_COUNT as u64;
+    SEASONS[index as usize]
+}
+
+/// Season before `season` in the year
+pub fn previous_season(season: Season) -> Season {
+    SEASONS[(season as usize + SEASON_COUNT - 1) % SEASON_COUNT]
+}
+
+/// Follow the day/night cycle: a time of day earlier than the last one
+/// means the cycle wrapped past midnight into a new day
+pub fn update_season_clock(data: &mut SeasonData, time: &TimeOfDayData) -> Option<SeasonChange> {
+    let wrapped = time.hours < data.hours;
+    data.hours = time.hours;
+    if wrapped {
+        advance_world_days(data, 1)
+    } else {
+        None
+    }
+}
+
+/// Skip whole days (sleeping, time commands); reports the season the
+/// world ends up in if it changed
+pub fn advance_world_days(data: &mut SeasonData, days: u64) -> Option<SeasonChange> {
+    data.day = data.day.saturating_add(days);
+    let season = season_for_day(&data.config, data.day);
+    if season == data.season {
+        return None;
+    }
+    let change = SeasonChange {
+        from: data.season,
+        to: season,
+        day: data.day - day_of_season(data) as u64,
+    };
+    data.season = season;
+    Some(change)
+}
+
+/// Day within the current season, from 0
+pub fn day_of_season(data: &SeasonData) -> u32 {
+    (data.day % data.config.days_per_season.max(1) as u64) as u32
+}
+
+/// How far through the current season the world is (0.0 - 1.0)
+pub fn season_progress(data: &SeasonData) -> f32 {
+    let days = day_of_season(data) as f32 + data.hours / HOURS_PER_DAY;
+    (days / data.config.days_per_season.max(1) as f32).min(1.0)
+}
+
+/// Parameters in effect: the current season's profile, faded in from the
+/// previous season's over the first `blend_days` of the season
+pub fn current_season_params(data: &SeasonData) -> SeasonParams {
+    let profiles = &data.config.profiles;
+    let current = profiles[data.season as usize];
+    if data.config.blend_days <= 0.0 {
+        return current;
+    }
+    let days = day_of_season(data) as f32 + data.hours / HOURS_PER_DAY;
+    let t = days / data.config.blend_days;
+    if t >= 1.0 {
+        return current;
+    }
+    blend_season_params(
+        &profiles[previous_season(data.season) as usize],
+        &current,
+        t,
+    )
+}
+
+/// Interpolate every parameter from `from` (t = 0) to `to` (t = 1)
+pub fn blend_season_params(from: &SeasonParams, to: &SeasonParams, t: f32) -> SeasonParams {
+    let lerp = |a: f32, b: f32| a + (b - a) * t;
+    SeasonParams {
+        snow_cover: lerp(from.snow_cover, to.snow_cover),
+        grass_tint: std::array::from_fn(|i| lerp(from.grass_tint[i], to.grass_tint[i])),
+        foliage_tint: std::array::from_fn(|i| lerp(from.foliage_tint[i], to.foliage_tint[i])),
+        temperature_offset: lerp(from.temperature_offset, to.temperature_offset),
+        weather_weights: std::array::from_fn(|i| {
+            lerp(from.weather_weights[i], to.weather_weights[i])
+        }),
+        growth_rate: lerp(from.growth_rate, to.growth_rate),
+    }
+}
+
+/// Season state for gateway queries
+pub fn season_snapshot(data: &SeasonData) -> SeasonSnapshot {
+    SeasonSnapshot {
+        season: data.season,
+        day: data.day,
+        day_of_season: day_of_season(data),
+        progress: season_progress(data),
+        params: current_season_params(data),
+    }
+}
+
+/// Weather type (`constants::weather::WEATHER_*`) for a uniform `roll` in
+/// 0.0..1.0, weighted by the season's odds
+pub fn pick_seasonal_weather(params: &SeasonParams, roll: f32) -> u32 {
+    let total: f32 = params.weather_weights.iter().map(|w| w.max(0.0)).sum();
+    if total <= 0.0 {
+        return WEATHER_CLEAR;
+    }
+    let mut remaining = roll.clamp(0.0, 1.0) * total;
+    let mut last = WEATHER_CLEAR;
+    for (weather, weight) in params.weather_weights.iter().enumerate() {
+        if *weight <= 0.0 {
+            continue;
+        }
+        last = weather as u32;
+        if remaining < *weight {
+            return last;
+        }
+        remaining -= weight;
+    }
+    last
+}
+
+/// Shift a chunk's temperature and cover its surface with snow; apply
+/// after `WeatherManager::apply_weather_to_params`
+pub fn apply_season_to_terrain_params(params: &mut TerrainParams, season: &SeasonParams) {
+    params.set_temperature_celsius(params.temperature_celsius() + season.temperature_offset);
+    if season.snow_cover >= MIN_SNOW_COVER {
+        params.add_distribution(BlockDistribution {
+            block_id: SNOW as u32,
+            min_height: params.sea_level as i32 + 1,
+            max_height: i32::MAX,
+            probability: season.snow_cover.min(1.0),
+            noise_threshold: 0.3,
+            _padding: [0; 3],
+        });
+    }
+}
+
+/// Set how fast crops grow in the process system
+pub fn apply_season_to_processes(processes: &mut ProcessManager, season: &SeasonParams) {
+    processes.set_category_rate(ProcessCategory::Growth, season.growth_rate);
+}
+
+/// World clock for saving
+pub fn save_world_time(data: &SeasonData) -> SavedWorldTime {
+    SavedWorldTime {
+        day: data.day,
+        hours: data.hours,
+    }
+}
+
+/// Restore a saved world clock without reporting a season change; returns
+/// the time of day to put back into the game's day/night cycle
+pub fn load_world_time(data: &mut SeasonData, saved: &SavedWorldTime) -> TimeOfDayData {
+    data.day = saved.day;
+    data.hours = saved.hours.rem_euclid(HOURS_PER_DAY);
+    data.season = season_for_day(&data.config, data.day);
+    TimeOfDayData { hours: data.hours }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::seasons::WEATHER_TYPE_COUNT;
+
+    fn test_config() -> SeasonConfig {
+        SeasonConfig {
+            days_per_season: 2,
+            blend_days: 1.0,
+            ..default_season_config()
+        }
+    }
+
+    #[test]
+    fn seasons_change_as_the_day_cycle_wraps() {
+        let mut data = create_season_data(test_config());
+        let time = |hours| TimeOfDayData { hours };
+
+        assert_eq!(update_season_clock(&mut data, &time(20.0)), None);
+        assert_eq!(update_season_clock(&mut data, &time(2.0)), None);
+        assert_eq!(data.day, 1);
+
+        update_season_clock(&mut data, &time(23.0));
+        let change = update_season_clock(&mut data, &time(0.5));
+        assert_eq!(
+            change,
+            Some(SeasonChange {
+                from: Season::Spring,
+                to: Season::Summer,
+                day: 2,
+            })
+        );
+
+        // Half a day into summer is halfway through the fade from spring
+        update_season_clock(&mut data, &time(12.0));
+        let params = current_season_params(&data);
+        let profiles = data.config.profiles;
+        let expected = (profiles[0].growth_rate + profiles[1].growth_rate) / 2.0;
+        assert!((params.growth_rate - expected).abs() < 1e-5);
+
+        let change = advance_world_days(&mut data, 5);
+        assert_eq!(change.map(|c| (c.to, c.day)), Some((Season::Winter, 6)));
+        assert_eq!(season_snapshot(&data).day_of_season, 1);
+        assert_eq!(
+            current_season_params(&data),
+            profiles[Season::Winter as usize]
+        );
+    }
+
+    #[test]
+    fn world_time_round_trips_and_weather_follows_weights() {
+        let mut data = create_season_data(test_config());
+        advance_world_days(&mut data, 5);
+        update_season_clock(&mut data, &TimeOfDayData { hours: 18.0 });
+        let saved = save_world_time(&data);
+
+        let mut loaded = create_season_data(test_config());
+        let time = load_world_time(&mut loaded, &saved);
+        assert_eq!(time.hours, 18.0);
+        assert_eq!(loaded, data);
+
+        let mut params = current_season_params(&data);
+        params.weather_weights = [0.0; WEATHER_TYPE_COUNT];
+        assert_eq!(pick_seasonal_weather(&params, 0.7), WEATHER_CLEAR);
+        params.weather_weights[1] = 1.0;
+        params.weather_weights[2] = 3.0;
+        assert_eq!(pick_seasonal_weather(&params, 0.2), 1);
+        assert_eq!(pick_seasonal_weather(&params, 0.3), 2);
+        assert_eq!(pick_seasonal_weather(&params, 1.0), 2);
+    }
+}
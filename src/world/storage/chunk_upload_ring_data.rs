@@ -0,0 +1,85 @@
+//! Chunk Upload Ring Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Queued chunk uploads are packed into one contiguous region of a GPU
+//! staging buffer with a single `write_buffer` per frame, then copied into
+//! their world buffer slots. Chunks landing in adjacent slots share one
+//! copy. A bytes-per-frame budget spreads bulk loads over several frames.
+//! All transformations happen in chunk_upload_ring_operations.rs
+
+use crate::memory::GpuAllocationGuard;
+use crate::world::core::ChunkPos;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Staging ring settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkUploadConfig {
+    /// Size of the GPU staging ring
+    pub ring_size_bytes: u64,
+    /// Bytes flushed per frame; at least one chunk always goes out
+    pub bytes_per_frame: u64,
+}
+
+/// Upload counters for the profiler
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChunkUploadStats {
+    /// Chunks copied to the GPU since creation
+    pub chunks_uploaded: u64,
+    pub bytes_uploaded: u64,
+    /// `copy_buffer_to_buffer` calls issued (fewer than chunks when coalesced)
+    pub copy_operations: u64,
+    /// Frames that flushed at least one chunk
+    pub flushes: u64,
+    /// Chunks uploaded by the latest flush
+    pub last_flush_chunks: u32,
+    pub last_flush_bytes: u64,
+    pub last_flush_copies: u32,
+    /// CPU time spent packing and writing the latest batch
+    pub last_flush_cpu_ms: f32,
+    /// Smoothed upload rate between flushes (MB/s)
+    pub bandwidth_mb_per_sec: f32,
+    /// Chunks still waiting for a later frame
+    pub pending_chunks: u32,
+    pub pending_bytes: u64,
+}
+
+/// One contiguous staging-to-slot copy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadCopyRun {
+    pub src_offset: u64,
+    pub dst_offset: u64,
+    pub size: u64,
+}
+
+/// Chunk bytes waiting for a flush
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingChunkUpload {
+    pub chunk_pos: ChunkPos,
+    pub dst_offset: u64,
+    pub data: Vec<u8>,
+}
+
+/// Staging ring and queue of chunk uploads waiting for a flush
+pub struct ChunkUploadRingData {
+    pub staging_buffer: wgpu::Buffer,
+    /// Record of the staging buffer in the GPU allocation tracker
+    pub staging_allocation: GpuAllocationGuard,
+    pub ring_size: u64,
+    /// Bytes flushed per frame, at most `ring_size`
+    pub bytes_per_frame: u64,
+    /// Next free byte in the ring
+    pub head: u64,
+    pub pending: VecDeque<PendingChunkUpload>,
+    pub pending_bytes: u64,
+    /// Reused packing buffer for a frame's batch
+    pub scratch: Vec<u8>,
+    pub stats: ChunkUploadStats,
+    pub last_flush: Option<Instant>,
+}
+
+impl Default for ChunkUploadConfig {
+    fn default() -> Self {
+        super::chunk_upload_ring_operations::default_chunk_upload_config()
+    }
+}
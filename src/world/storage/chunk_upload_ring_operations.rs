@@ -0,0 +1,265 @@
+//! Chunk Upload Ring Operations - Pure DOP Functions
+//!
+//! Functions that create the staging ring, queue and cancel chunk uploads
+//! and flush them to the world buffer within the frame budget.
+
+use super::chunk_upload_ring_data::{
+    ChunkUploadConfig, ChunkUploadRingData, ChunkUploadStats, PendingChunkUpload, UploadCopyRun,
+};
+use crate::constants::chunk_upload;
+use crate::memory::{create_tracked_buffer, GpuOwnerGuard};
+use crate::world::core::ChunkPos;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Ring size and frame budget from the engine constants
+pub fn default_chunk_upload_config() -> ChunkUploadConfig {
+    ChunkUploadConfig {
+        ring_size_bytes: chunk_upload::RING_SIZE_BYTES,
+        bytes_per_frame: chunk_upload::BYTES_PER_FRAME,
+    }
+}
+
+/// Create the staging ring; it grows to fit at least one `max_upload_bytes` upload
+pub fn create_chunk_upload_ring(
+    device: &wgpu::Device,
+    owner: &GpuOwnerGuard,
+    config: ChunkUploadConfig,
+    max_upload_bytes: u64,
+) -> ChunkUploadRingData {
+    let ring_size = config
+        .ring_size_bytes
+        .max(max_upload_bytes)
+        .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
+    let (staging_buffer, staging_allocation) = create_tracked_buffer(
+        device,
+        owner,
+        &wgpu::BufferDescriptor {
+            label: Some("Chunk Upload Staging Ring"),
+            size: ring_size,
+            usage: wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
+
+    log::info!(
+        "[CHUNK_UPLOAD] Staging ring: {} MB, {} MB per frame",
+        ring_size / (1024 * 1024),
+        config.bytes_per_frame / (1024 * 1024)
+    );
+
+    ChunkUploadRingData {
+        staging_buffer,
+        staging_allocation,
+        ring_size,
+        bytes_per_frame: config.bytes_per_frame.min(ring_size),
+        head: 0,
+        pending: VecDeque::new(),
+        pending_bytes: 0,
+        scratch: Vec::new(),
+        stats: ChunkUploadStats::default(),
+        last_flush: None,
+    }
+}
+
+/// Change the per-frame budget (clamped to the ring size)
+pub fn set_chunk_upload_bytes_per_frame(ring: &mut ChunkUploadRingData, bytes_per_frame: u64) {
+    ring.bytes_per_frame = bytes_per_frame.min(ring.ring_size);
+}
+
+/// Queue bytes for `dst_offset`; replaces a pending upload of the same chunk
+pub fn queue_ring_chunk_upload(
+    ring: &mut ChunkUploadRingData,
+    chunk_pos: ChunkPos,
+    dst_offset: u64,
+    data: Vec<u8>,
+) {
+    if let Some(existing) = ring.pending.iter_mut().find(|p| p.chunk_pos == chunk_pos) {
+        ring.pending_bytes = ring.pending_bytes - existing.data.len() as u64 + data.len() as u64;
+        existing.dst_offset = dst_offset;
+        existing.data = data;
+        return;
+    }
+    ring.pending_bytes += data.len() as u64;
+    ring.pending.push_back(PendingChunkUpload {
+        chunk_pos,
+        dst_offset,
+        data,
+    });
+}
+
+/// Drop a pending upload (e.g. the chunk was unloaded before its flush)
+pub fn cancel_ring_chunk_upload(ring: &mut ChunkUploadRingData, chunk_pos: ChunkPos) -> bool {
+    let Some(index) = ring.pending.iter().position(|p| p.chunk_pos == chunk_pos) else {
+        return false;
+    };
+    if let Some(removed) = ring.pending.remove(index) {
+        ring.pending_bytes -= removed.data.len() as u64;
+    }
+    true
+}
+
+/// Merge copies whose source and destination ranges both continue the
+/// previous copy. Input is in staging order.
+pub fn coalesce_upload_copies(copies: &[UploadCopyRun]) -> Vec<UploadCopyRun> {
+    let mut runs: Vec<UploadCopyRun> = Vec::with_capacity(copies.len());
+    for copy in copies {
+        match runs.last_mut() {
+            Some(run)
+                if run.src_offset + run.size == copy.src_offset
+                    && run.dst_offset + run.size == copy.dst_offset =>
+            {
+                run.size += copy.size;
+            }
+            _ => runs.push(*copy),
+        }
+    }
+    runs
+}
+
+/// Upload up to the frame budget: one staging write, then coalesced copies
+/// into `dst`. Returns the positions of the chunks now on the GPU.
+pub fn flush_chunk_upload_ring(
+    ring: &mut ChunkUploadRingData,
+    queue: &wgpu::Queue,
+    encoder: &mut wgpu::CommandEncoder,
+    dst: &wgpu::Buffer,
+) -> Vec<ChunkPos> {
+    let start = Instant::now();
+    ring.scratch.clear();
+    let mut copies = Vec::new();
+    let mut uploaded = Vec::new();
+
+    while let Some(next) = ring.pending.front() {
+        let size = next.data.len() as u64;
+        let batch = ring.scratch.len() as u64;
+        // The first chunk always fits: the ring holds at least one
+        if batch > 0 && (batch + size > ring.bytes_per_frame || batch + size > ring.ring_size) {
+            break;
+        }
+        let Some(upload) = ring.pending.pop_front() else {
+            break;
+        };
+        ring.pending_bytes -= size;
+        copies.push(UploadCopyRun {
+            src_offset: batch,
+            dst_offset: upload.dst_offset,
+            size,
+        });
+        ring.scratch.extend_from_slice(&upload.data);
+        uploaded.push(upload.chunk_pos);
+    }
+
+    let batch_size = ring.scratch.len() as u64;
+    if batch_size > 0 {
+        if ring.head + batch_size > ring.ring_size {
+            ring.head = 0;
+        }
+        let base = ring.head;
+        queue.write_buffer(&ring.staging_buffer, base, &ring.scratch);
+
+        let runs = coalesce_upload_copies(&copies);
+        for run in &runs {
+            encoder.copy_buffer_to_buffer(
+                &ring.staging_buffer,
+                base + run.src_offset,
+                dst,
+                run.dst_offset,
+                run.size,
+            );
+        }
+        ring.head = (base + batch_size).next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
+
+        record_upload_flush(
+            ring,
+            uploaded.len() as u32,
+            batch_size,
+            runs.len() as u64,
+            start,
+        );
+    }
+
+    ring.stats.pending_chunks = ring.pending.len() as u32;
+    ring.stats.pending_bytes = ring.pending_bytes;
+    uploaded
+}
+
+fn record_upload_flush(
+    ring: &mut ChunkUploadRingData,
+    chunks: u32,
+    bytes: u64,
+    copies: u64,
+    start: Instant,
+) {
+    let now = Instant::now();
+    let stats = &mut ring.stats;
+    if let Some(previous) = ring.last_flush {
+        let seconds = now.duration_since(previous).as_secs_f64();
+        if seconds > 0.0 {
+            let rate = bytes as f64 / seconds / (1024.0 * 1024.0);
+            let smoothed = stats.bandwidth_mb_per_sec as f64;
+            stats.bandwidth_mb_per_sec = if stats.flushes <= 1 {
+                rate as f32
+            } else {
+                (smoothed + (rate - smoothed) * chunk_upload::BANDWIDTH_SMOOTHING) as f32
+            };
+        }
+    }
+    ring.last_flush = Some(now);
+
+    stats.chunks_uploaded += chunks as u64;
+    stats.bytes_uploaded += bytes;
+    stats.copy_operations += copies;
+    stats.flushes += 1;
+    stats.last_flush_chunks = chunks;
+    stats.last_flush_bytes = bytes;
+    stats.last_flush_copies = copies as u32;
+    stats.last_flush_cpu_ms = start.elapsed().as_secs_f32() * 1000.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjacent_slots_share_a_copy() {
+        let copies = [
+            UploadCopyRun {
+                src_offset: 0,
+                dst_offset: 400,
+                size: 100,
+            },
+            UploadCopyRun {
+                src_offset: 100,
+                dst_offset: 500,
+                size: 100,
+            },
+            UploadCopyRun {
+                src_offset: 200,
+                dst_offset: 0,
+                size: 100,
+            },
+            UploadCopyRun {
+                src_offset: 300,
+                dst_offset: 100,
+                size: 100,
+            },
+        ];
+        let runs = coalesce_upload_copies(&copies);
+        assert_eq!(
+            runs,
+            vec![
+                UploadCopyRun {
+                    src_offset: 0,
+                    dst_offset: 400,
+                    size: 200
+                },
+                UploadCopyRun {
+                    src_offset: 200,
+                    dst_offset: 0,
+                    size: 200
+                },
+            ]
+        );
+    }
+}
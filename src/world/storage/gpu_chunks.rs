@@ -1,7 +1,9 @@
 use crate::constants::core::VOXELS_PER_CHUNK;
+use crate::memory::{
+    create_tracked_buffer_init, register_global_gpu_owner, GpuAllocationGuard, GpuOwnerGuard,
+};
 use crate::world::core::ChunkPos;
 use crate::world::storage::{Chunk, TempChunk};
-use wgpu::util::DeviceExt;
 
 /// GPU-resident chunk data for efficient GPU processing
 pub struct GpuChunk {
@@ -12,6 +14,7 @@ pub struct GpuChunk {
     block_buffer: wgpu::Buffer,
     light_buffer: wgpu::Buffer,
     metadata_buffer: wgpu::Buffer,
+    gpu_allocations: Vec<GpuAllocationGuard>,
 
     // Bind group for compute shaders
     bind_group: Option<wgpu::BindGroup>,
@@ -22,25 +25,33 @@ pub struct GpuChunk {
 
 impl GpuChunk {
     /// Create a new GPU chunk from temporary chunk data
-    pub fn new(device: &wgpu::Device, chunk: &TempChunk) -> Self {
+    pub fn new(device: &wgpu::Device, owner: &GpuOwnerGuard, chunk: &TempChunk) -> Self {
         let size = chunk.size();
         let block_count = (size * size * size) as usize;
 
         // Create block buffer
         let blocks = chunk.blocks();
-        let block_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("GPU Chunk Block Buffer"),
-            contents: bytemuck::cast_slice(&blocks),
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-        });
+        let (block_buffer, block_allocation) = create_tracked_buffer_init(
+            device,
+            owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("GPU Chunk Block Buffer"),
+                contents: bytemuck::cast_slice(blocks),
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            },
+        );
 
         // Create light buffer (placeholder for now)
         let light_data = vec![0u8; block_count * 2]; // 2 bytes per block for light
-        let light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("GPU Chunk Light Buffer"),
-            contents: &light_data,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-        });
+        let (light_buffer, light_allocation) = create_tracked_buffer_init(
+            device,
+            owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("GPU Chunk Light Buffer"),
+                contents: &light_data,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            },
+        );
 
         // Create metadata buffer
         let metadata = ChunkMetadata {
@@ -55,11 +66,15 @@ impl GpuChunk {
             flags: 0,
             _padding: 0,
         };
-        let metadata_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("GPU Chunk Metadata Buffer"),
-            contents: bytemuck::bytes_of(&metadata),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
+        let (metadata_buffer, metadata_allocation) = create_tracked_buffer_init(
+            device,
+            owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("GPU Chunk Metadata Buffer"),
+                contents: bytemuck::bytes_of(&metadata),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
 
         Self {
             position: chunk.position().clone(),
@@ -67,31 +82,40 @@ impl GpuChunk {
             block_buffer,
             light_buffer,
             metadata_buffer,
+            gpu_allocations: vec![block_allocation, light_allocation, metadata_allocation],
             bind_group: None,
             dirty: false,
         }
     }
 
     /// Create a new GPU chunk from ChunkData
-    pub fn new_from_chunk(device: &wgpu::Device, chunk: &Chunk) -> Self {
+    pub fn new_from_chunk(device: &wgpu::Device, owner: &GpuOwnerGuard, chunk: &Chunk) -> Self {
         let blocks = chunk.blocks();
         let block_count = blocks.len();
         let size = (block_count as f64).cbrt() as u32; // Derive size from block count
 
         // Create block buffer
-        let block_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("GPU Chunk Block Buffer"),
-            contents: bytemuck::cast_slice(blocks),
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-        });
+        let (block_buffer, block_allocation) = create_tracked_buffer_init(
+            device,
+            owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("GPU Chunk Block Buffer"),
+                contents: bytemuck::cast_slice(blocks),
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            },
+        );
 
         // Create light buffer (placeholder for now)
         let light_data = vec![0u8; block_count * 2]; // 2 bytes per block for light
-        let light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("GPU Chunk Light Buffer"),
-            contents: &light_data,
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
-        });
+        let (light_buffer, light_allocation) = create_tracked_buffer_init(
+            device,
+            owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("GPU Chunk Light Buffer"),
+                contents: &light_data,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            },
+        );
 
         // Create metadata buffer
         let pos = chunk.position();
@@ -102,11 +126,15 @@ impl GpuChunk {
             flags: 0,
             _padding: 0,
         };
-        let metadata_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("GPU Chunk Metadata Buffer"),
-            contents: bytemuck::bytes_of(&metadata),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
+        let (metadata_buffer, metadata_allocation) = create_tracked_buffer_init(
+            device,
+            owner,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("GPU Chunk Metadata Buffer"),
+                contents: bytemuck::bytes_of(&metadata),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
 
         Self {
             position: pos,
@@ -114,6 +142,7 @@ impl GpuChunk {
             block_buffer,
             light_buffer,
             metadata_buffer,
+            gpu_allocations: vec![block_allocation, light_allocation, metadata_allocation],
             bind_group: None,
             dirty: false,
         }
@@ -187,6 +216,7 @@ struct ChunkMetadata {
 pub struct GpuChunkManager {
     chunks: std::collections::HashMap<ChunkPos, GpuChunk>,
     bind_group_layout: wgpu::BindGroupLayout,
+    gpu_owner: GpuOwnerGuard,
 }
 
 impl GpuChunkManager {
@@ -234,6 +264,7 @@ impl GpuChunkManager {
         Self {
             chunks: std::collections::HashMap::new(),
             bind_group_layout,
+            gpu_owner: register_global_gpu_owner("gpu_chunks"),
         }
     }
 
@@ -246,7 +277,7 @@ impl GpuChunkManager {
             gpu_chunk.update(queue, chunk);
         } else {
             // Create new GPU chunk
-            let mut gpu_chunk = GpuChunk::new_from_chunk(device, chunk);
+            let mut gpu_chunk = GpuChunk::new_from_chunk(device, &self.gpu_owner, chunk);
             gpu_chunk.create_bind_group(device, &self.bind_group_layout);
             self.chunks.insert(pos, gpu_chunk);
         }
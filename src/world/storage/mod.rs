@@ -3,19 +3,72 @@
 //! This module provides GPU-resident world storage,
 //! following the GPU-first architecture principle.
 
+mod chunk_upload_ring_data;
+mod chunk_upload_ring_operations;
 mod gpu_chunks;
+mod region_readback;
+mod shadow_cache;
 mod temp_chunk;
+mod voxel_inspector;
+mod voxel_palette;
 mod world_buffer;
 
 // Type alias for compatibility
 pub use crate::world::data_types::ChunkData as Chunk;
 
 // GPU-first storage (primary)
-pub use world_buffer::{VoxelData, WorldBuffer, WorldBufferDescriptor};
+pub use world_buffer::{
+    detect_uniform_chunk, merge_voxel_light, pack_voxel_light, unpack_voxel_light,
+    LightingStorageMode, SparseChunk, SparseStorageStats, VoxelData, WorldBuffer,
+    WorldBufferDescriptor,
+};
+
+// Batched chunk uploads
+pub use chunk_upload_ring_data::{
+    ChunkUploadConfig, ChunkUploadRingData, ChunkUploadStats, PendingChunkUpload, UploadCopyRun,
+};
+pub use chunk_upload_ring_operations::{
+    cancel_ring_chunk_upload, coalesce_upload_copies, create_chunk_upload_ring,
+    default_chunk_upload_config, flush_chunk_upload_ring, queue_ring_chunk_upload,
+    set_chunk_upload_bytes_per_frame,
+};
+
+// Sub-chunk readback of voxel regions
+pub use region_readback::{
+    clamp_voxel_region, decode_region_readback, poll_region_readback, region_contains,
+    region_copy_runs, region_voxel_at, region_voxel_count, request_region_readback,
+    voxel_regions_for_bounds, wait_region_readback, RegionCopyRun, RegionReadbackBatch,
+    RegionReadbackStatus, RegionSource, RegionVoxels, VoxelRegion,
+};
 
 // GPU chunk management
 pub use gpu_chunks::{GpuChunk, GpuChunkManager, GpuChunkStats};
 
+// CPU shadow cache for readback-free block queries
+pub use shadow_cache::{
+    apply_modifications_to_shadow, create_shadow_cache, evict_shadow_columns,
+    insert_shadow_chunk, insert_shadow_region, invalidate_shadow_chunk, poll_shadow_readbacks,
+    queue_shadow_region, request_shadow_readbacks, request_shadow_region_readbacks,
+    shadow_cache_hit_rate, shadow_column_height, shadow_contains_chunk, shadow_get_block,
+    shadow_get_block_or, shadow_get_light, ShadowCacheData, ShadowCacheStats, ShadowLightLookup,
+    ShadowLookup, ShadowPartialChunk, ShadowRegionReadback, SharedShadowCache, SHADOW_NO_HEIGHT,
+};
+
+// Debug readback of voxels around a position
+pub use voxel_inspector::{
+    create_voxel_inspector, decode_voxel_inspection, format_inspected_voxel,
+    inspected_chunk_ranges, inspected_voxel_at, inspection_lines, parse_inspect_command,
+    poll_voxel_inspections, request_voxel_inspection, InspectChunkRange, InspectChunkSource,
+    InspectIndexRange, InspectLightRange, InspectReadback, InspectedVoxel, InspectedVoxelSource,
+    VoxelInspectRequest, VoxelInspection, VoxelInspectorData,
+};
+
+// 8-bit palette voxel format
+pub use voxel_palette::{
+    depalettize_chunk, palette_index_words, palette_slot_bytes, palette_voxel, palettize_chunk,
+    PaletteStorageStats, PalettizedChunk, VoxelStorageFormat,
+};
+
 // Temporary chunk for GPU data transfer only
 pub use temp_chunk::TempChunk;
 
@@ -32,7 +85,13 @@ impl UnifiedStorage {
         device: std::sync::Arc<wgpu::Device>,
         descriptor: &WorldBufferDescriptor,
     ) -> Result<Self, StorageError> {
+        crate::game::begin_gateway_load_phase(
+            crate::game::LoadPhase::WorldBufferAlloc,
+            0,
+            "Allocating world buffer",
+        );
         let world_buffer = WorldBuffer::new(device.clone(), descriptor);
+        crate::game::complete_gateway_load_phase(crate::game::LoadPhase::WorldBufferAlloc);
         Ok(UnifiedStorage {
             world_buffer: std::sync::Arc::new(std::sync::Mutex::new(world_buffer)),
             device,
@@ -59,4 +118,13 @@ pub enum StorageError {
 
     #[error("Backend mismatch: operation requires {required} but storage is {actual}")]
     BackendMismatch { required: String, actual: String },
+
+    #[error("Invalid light data: expected {expected} voxels, got {actual}")]
+    InvalidLightData { expected: usize, actual: usize },
+
+    #[error("GPU readback failed: {message}")]
+    ReadbackFailed { message: String },
 }
+
+/// Result type for storage operations
+pub type StorageResult<T> = Result<T, StorageError>;
@@ -0,0 +1,454 @@
+//! Sub-chunk readback of voxel regions
+//!
+//! `WorldBuffer::read_chunk` copies a whole chunk to the CPU even when the
+//! caller needs a handful of voxels. Here a region is a box of local voxel
+//! coordinates inside one chunk; only its x rows are copied, adjacent rows
+//! are merged into one copy, and every region of a batch shares a single
+//! staging buffer and submission.
+//!
+//! Voxels come back x fastest, then y, then z, relative to the region's
+//! minimum corner. Sparse chunks are answered from their descriptor and
+//! chunks without a slot read as air, without touching the GPU.
+
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+
+use crate::memory::{create_tracked_buffer, GpuAllocationGuard};
+use crate::world::core::{layout_voxel_index, ChunkLayout, ChunkPos, VoxelPos};
+
+use super::world_buffer::{VoxelData, WorldBuffer};
+use super::{StorageError, StorageResult};
+
+type MapResultReceiver = Receiver<Result<(), wgpu::BufferAsyncError>>;
+
+/// Box of voxels inside one chunk, bounds inclusive, in local coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoxelRegion {
+    pub chunk_pos: ChunkPos,
+    pub min: [u32; 3],
+    pub max: [u32; 3],
+}
+
+/// Contiguous voxels copied for a region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionCopyRun {
+    /// Voxel index inside the chunk slot
+    pub first_voxel: u32,
+    pub voxel_count: u32,
+}
+
+/// Voxels read for one region
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionVoxels {
+    pub region: VoxelRegion,
+    pub voxels: Vec<VoxelData>,
+}
+
+/// Where a region's voxels come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionSource {
+    /// Copied into the staging buffer starting at `staging_offset` bytes
+    Gpu {
+        staging_offset: u64,
+        runs: Vec<RegionCopyRun>,
+    },
+    /// Whole region holds one voxel (sparse chunk, or air without a slot)
+    Filled(VoxelData),
+}
+
+/// Progress of an asynchronous region readback
+#[derive(Debug)]
+pub enum RegionReadbackStatus {
+    Pending,
+    Ready(Vec<RegionVoxels>),
+    Failed(StorageError),
+}
+
+/// One submitted batch of region reads
+pub struct RegionReadbackBatch {
+    pub regions: Vec<VoxelRegion>,
+    pub sources: Vec<RegionSource>,
+    /// Bytes copied from the GPU for the whole batch
+    pub byte_size: u64,
+    buffer: Option<wgpu::Buffer>,
+    allocation: Option<GpuAllocationGuard>,
+    receiver: Option<MapResultReceiver>,
+}
+
+/// Clamp a region to the chunk and order its corners
+pub fn clamp_voxel_region(layout: ChunkLayout, region: VoxelRegion) -> VoxelRegion {
+    let last = layout.size.saturating_sub(1);
+    let mut min = [0; 3];
+    let mut max = [0; 3];
+    for axis in 0..3 {
+        let a = region.min[axis].min(last);
+        let b = region.max[axis].min(last);
+        min[axis] = a.min(b);
+        max[axis] = a.max(b);
+    }
+    VoxelRegion {
+        chunk_pos: region.chunk_pos,
+        min,
+        max,
+    }
+}
+
+/// Number of voxels in a region
+pub fn region_voxel_count(region: &VoxelRegion) -> u32 {
+    (0..3)
+        .map(|axis| region.max[axis].saturating_sub(region.min[axis]) + 1)
+        .product()
+}
+
+/// Split an inclusive world-space box into per-chunk regions
+pub fn voxel_regions_for_bounds(
+    layout: ChunkLayout,
+    min: VoxelPos,
+    max: VoxelPos,
+) -> Vec<VoxelRegion> {
+    let lo = VoxelPos::new(min.x.min(max.x), min.y.min(max.y), min.z.min(max.z));
+    let hi = VoxelPos::new(min.x.max(max.x), min.y.max(max.y), min.z.max(max.z));
+    let chunk_min = lo.to_chunk_pos(layout.size);
+    let chunk_max = hi.to_chunk_pos(layout.size);
+    let size = layout.size as i32;
+
+    let mut regions = Vec::new();
+    for cz in chunk_min.z..=chunk_max.z {
+        for cy in chunk_min.y..=chunk_max.y {
+            for cx in chunk_min.x..=chunk_max.x {
+                let origin = [cx * size, cy * size, cz * size];
+                let lo = [lo.x, lo.y, lo.z];
+                let hi = [hi.x, hi.y, hi.z];
+                let mut region = VoxelRegion {
+                    chunk_pos: ChunkPos::new(cx, cy, cz),
+                    min: [0; 3],
+                    max: [0; 3],
+                };
+                for axis in 0..3 {
+                    region.min[axis] = (lo[axis] - origin[axis]).clamp(0, size - 1) as u32;
+                    region.max[axis] = (hi[axis] - origin[axis]).clamp(0, size - 1) as u32;
+                }
+                regions.push(region);
+            }
+        }
+    }
+    regions
+}
+
+/// Whether a region contains a world voxel
+pub fn region_contains(layout: ChunkLayout, region: &VoxelRegion, pos: VoxelPos) -> bool {
+    if pos.to_chunk_pos(layout.size) != region.chunk_pos {
+        return false;
+    }
+    let (x, y, z) = pos.to_local_pos(layout.size);
+    [x, y, z]
+        .iter()
+        .enumerate()
+        .all(|(axis, v)| (region.min[axis]..=region.max[axis]).contains(v))
+}
+
+/// Copies needed for a region: one per x row, merged where rows are adjacent
+/// in the slot (full-width rows, full planes)
+pub fn region_copy_runs(layout: ChunkLayout, region: &VoxelRegion) -> Vec<RegionCopyRun> {
+    let row_len = region.max[0] - region.min[0] + 1;
+    let mut runs: Vec<RegionCopyRun> = Vec::new();
+
+    for z in region.min[2]..=region.max[2] {
+        for y in region.min[1]..=region.max[1] {
+            let first_voxel = layout_voxel_index(layout, region.min[0], y, z);
+            match runs.last_mut() {
+                Some(run) if run.first_voxel + run.voxel_count == first_voxel => {
+                    run.voxel_count += row_len;
+                }
+                _ => runs.push(RegionCopyRun {
+                    first_voxel,
+                    voxel_count: row_len,
+                }),
+            }
+        }
+    }
+    runs
+}
+
+/// Record and submit the copies for a batch of regions, then start mapping
+/// the staging buffer. Regions are clamped to the chunk first.
+pub fn request_region_readback(
+    world_buffer: &WorldBuffer,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    regions: &[VoxelRegion],
+) -> RegionReadbackBatch {
+    let layout = world_buffer.chunk_layout();
+    let voxel_bytes = std::mem::size_of::<VoxelData>() as u64;
+    let regions: Vec<VoxelRegion> = regions
+        .iter()
+        .map(|region| clamp_voxel_region(layout, *region))
+        .collect();
+
+    let mut sources = Vec::with_capacity(regions.len());
+    let mut byte_size = 0;
+    for region in &regions {
+        if let Some(sparse) = world_buffer.sparse_chunk(region.chunk_pos) {
+            sources.push(RegionSource::Filled(sparse.voxel));
+        } else if world_buffer.existing_chunk_slot(region.chunk_pos).is_none() {
+            sources.push(RegionSource::Filled(VoxelData::AIR));
+        } else {
+            sources.push(RegionSource::Gpu {
+                staging_offset: byte_size,
+                runs: region_copy_runs(layout, region),
+            });
+            byte_size += region_voxel_count(region) as u64 * voxel_bytes;
+        }
+    }
+
+    let mut batch = RegionReadbackBatch {
+        regions,
+        sources,
+        byte_size,
+        buffer: None,
+        allocation: None,
+        receiver: None,
+    };
+    if byte_size == 0 {
+        return batch;
+    }
+
+    let (buffer, allocation) = create_tracked_buffer(
+        device,
+        world_buffer.gpu_owner(),
+        &wgpu::BufferDescriptor {
+            label: Some("Region Readback Staging"),
+            size: byte_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        },
+    );
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Region Readback"),
+    });
+
+    for (region, source) in batch.regions.iter().zip(&batch.sources) {
+        let RegionSource::Gpu {
+            staging_offset,
+            runs,
+        } = source
+        else {
+            continue;
+        };
+        let Some(slot) = world_buffer.existing_chunk_slot(region.chunk_pos) else {
+            continue;
+        };
+        let slot_offset = world_buffer.slot_offset(slot);
+        let mut destination = *staging_offset;
+        for run in runs {
+            let size = run.voxel_count as u64 * voxel_bytes;
+            encoder.copy_buffer_to_buffer(
+                world_buffer.voxel_buffer(),
+                slot_offset + run.first_voxel as u64 * voxel_bytes,
+                &buffer,
+                destination,
+                size,
+            );
+            destination += size;
+        }
+    }
+
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let (sender, receiver) = channel();
+    buffer
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+    batch.buffer = Some(buffer);
+    batch.allocation = Some(allocation);
+    batch.receiver = Some(receiver);
+
+    log::debug!(
+        "[REGION_READBACK] Requested {} regions ({} bytes)",
+        batch.regions.len(),
+        byte_size
+    );
+    batch
+}
+
+/// Split mapped staging bytes back into per-region voxels
+pub fn decode_region_readback(
+    regions: &[VoxelRegion],
+    sources: &[RegionSource],
+    staging: &[VoxelData],
+) -> Vec<RegionVoxels> {
+    let voxel_bytes = std::mem::size_of::<VoxelData>() as u64;
+    regions
+        .iter()
+        .zip(sources)
+        .map(|(region, source)| {
+            let count = region_voxel_count(region) as usize;
+            let voxels = match source {
+                RegionSource::Filled(voxel) => vec![*voxel; count],
+                RegionSource::Gpu { staging_offset, .. } => {
+                    let start = (*staging_offset / voxel_bytes) as usize;
+                    staging
+                        .get(start..start + count)
+                        .map(|voxels| voxels.to_vec())
+                        .unwrap_or_else(|| vec![VoxelData::AIR; count])
+                }
+            };
+            RegionVoxels {
+                region: *region,
+                voxels,
+            }
+        })
+        .collect()
+}
+
+fn finish_region_readback(batch: &mut RegionReadbackBatch) -> Vec<RegionVoxels> {
+    let staging: Vec<VoxelData> = match batch.buffer.take() {
+        Some(buffer) => {
+            let voxels = {
+                let mapped = buffer.slice(..).get_mapped_range();
+                bytemuck::cast_slice(&mapped).to_vec()
+            };
+            buffer.unmap();
+            voxels
+        }
+        None => Vec::new(),
+    };
+    batch.allocation = None;
+    batch.receiver = None;
+    decode_region_readback(&batch.regions, &batch.sources, &staging)
+}
+
+/// Check a batch without blocking
+pub fn poll_region_readback(
+    batch: &mut RegionReadbackBatch,
+    device: &wgpu::Device,
+) -> RegionReadbackStatus {
+    let Some(receiver) = &batch.receiver else {
+        return RegionReadbackStatus::Ready(finish_region_readback(batch));
+    };
+
+    device.poll(wgpu::Maintain::Poll);
+    match receiver.try_recv() {
+        Ok(Ok(())) => RegionReadbackStatus::Ready(finish_region_readback(batch)),
+        Ok(Err(e)) => RegionReadbackStatus::Failed(StorageError::ReadbackFailed {
+            message: format!("Buffer mapping failed: {:?}", e),
+        }),
+        Err(TryRecvError::Empty) => RegionReadbackStatus::Pending,
+        Err(TryRecvError::Disconnected) => {
+            RegionReadbackStatus::Failed(StorageError::ReadbackFailed {
+                message: "Failed to receive mapping result".to_string(),
+            })
+        }
+    }
+}
+
+/// Block until a batch is mapped
+pub fn wait_region_readback(
+    mut batch: RegionReadbackBatch,
+    device: &wgpu::Device,
+) -> StorageResult<Vec<RegionVoxels>> {
+    if let Some(receiver) = &batch.receiver {
+        device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|_| StorageError::ReadbackFailed {
+                message: "Failed to receive mapping result".to_string(),
+            })?
+            .map_err(|e| StorageError::ReadbackFailed {
+                message: format!("Buffer mapping failed: {:?}", e),
+            })?;
+    }
+    Ok(finish_region_readback(&mut batch))
+}
+
+/// Voxel at local chunk coordinates, if the region covers them
+pub fn region_voxel_at(read: &RegionVoxels, local: [u32; 3]) -> Option<VoxelData> {
+    let region = &read.region;
+    if (0..3).any(|axis| !(region.min[axis]..=region.max[axis]).contains(&local[axis])) {
+        return None;
+    }
+    let width = region.max[0] - region.min[0] + 1;
+    let height = region.max[1] - region.min[1] + 1;
+    let index = (local[0] - region.min[0])
+        + (local[1] - region.min[1]) * width
+        + (local[2] - region.min[2]) * width * height;
+    read.voxels.get(index as usize).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::core::create_chunk_layout;
+
+    fn region(min: [u32; 3], max: [u32; 3]) -> VoxelRegion {
+        VoxelRegion {
+            chunk_pos: ChunkPos::new(0, 0, 0),
+            min,
+            max,
+        }
+    }
+
+    #[test]
+    fn test_copy_runs_merge_adjacent_rows() {
+        let layout = create_chunk_layout(32).expect("layout");
+
+        // Partial rows stay separate
+        let runs = region_copy_runs(layout, &region([2, 3, 4], [3, 4, 4]));
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].first_voxel, layout_voxel_index(layout, 2, 3, 4));
+        assert_eq!(runs[0].voxel_count, 2);
+
+        // Full-width rows merge into one run per plane, full planes into one run
+        assert_eq!(
+            region_copy_runs(layout, &region([0, 2, 1], [31, 5, 2])).len(),
+            2
+        );
+        let full = region_copy_runs(layout, &region([0, 0, 3], [31, 31, 5]));
+        assert_eq!(full.len(), 1);
+        assert_eq!(full[0].voxel_count, 32 * 32 * 3);
+    }
+
+    #[test]
+    fn test_decode_orders_region_voxels() {
+        let layout = create_chunk_layout(32).expect("layout");
+        let read_region = region([1, 1, 1], [2, 2, 2]);
+        let runs = region_copy_runs(layout, &read_region);
+
+        // Simulate the staging buffer: runs copied back to back
+        let mut staging = vec![VoxelData::new(99, 0, 0, 0)];
+        for run in &runs {
+            for index in run.first_voxel..run.first_voxel + run.voxel_count {
+                staging.push(VoxelData::new(index as u16, 0, 0, 0));
+            }
+        }
+        let sources = [RegionSource::Gpu {
+            staging_offset: std::mem::size_of::<VoxelData>() as u64,
+            runs,
+        }];
+        let reads = decode_region_readback(&[read_region], &sources, &staging);
+
+        let voxel = region_voxel_at(&reads[0], [2, 1, 2]).expect("covered");
+        assert_eq!(voxel.block_id() as u32, layout_voxel_index(layout, 2, 1, 2));
+        assert!(region_voxel_at(&reads[0], [0, 1, 1]).is_none());
+    }
+
+    #[test]
+    fn test_bounds_split_across_chunks() {
+        let layout = create_chunk_layout(32).expect("layout");
+        let regions =
+            voxel_regions_for_bounds(layout, VoxelPos::new(30, 0, -1), VoxelPos::new(33, 1, 0));
+        assert_eq!(regions.len(), 4);
+        assert!(regions.iter().all(|r| region_voxel_count(r) == 2 * 2));
+        assert!(region_contains(
+            layout,
+            &regions[0],
+            VoxelPos::new(31, 1, -1)
+        ));
+        assert!(!region_contains(
+            layout,
+            &regions[0],
+            VoxelPos::new(32, 1, -1)
+        ));
+    }
+}
@@ -0,0 +1,888 @@
+//! CPU shadow cache for readback-free block queries
+//!
+//! The GPU WorldBuffer is authoritative for voxel data. Gameplay code
+//! (raycasts, collision) still needs cheap per-voxel lookups, so this cache
+//! keeps a bounded set of recently accessed chunk columns on the CPU.
+//!
+//! - Misses are recorded as pending readbacks and resolved asynchronously
+//!   via [`request_shadow_readbacks`] / [`poll_shadow_readbacks`].
+//! - Modifications submitted to the GPU are mirrored with
+//!   [`apply_modifications_to_shadow`] so cached data never goes stale;
+//!   a `ChunkModifier` given the cache with `with_shadow_cache` does this
+//!   itself. Whole-chunk uploads and clears call [`invalidate_shadow_chunk`].
+//! - Columns are evicted least-recently-used once `max_columns` is reached.
+//! - Callers that only need a small box (physics around a body) queue it with
+//!   [`queue_shadow_region`]; [`request_shadow_region_readbacks`] reads all
+//!   queued boxes in one batch instead of whole chunks, and the voxels are
+//!   kept as a partial overlay until the full chunk arrives.
+//! - Cached chunks also keep the block and sky light read back with them, and
+//!   each column keeps a heightmap of its highest skylight-blocking voxels,
+//!   so light queries (`world::lighting::get_light_at`) need no GPU access.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+
+use crate::constants::region_readback::{SHADOW_PARTIAL_VOXEL_LIMIT, SHADOW_REGION_BATCH};
+use crate::memory::{create_tracked_buffer, GpuAllocationGuard};
+use crate::world::compute::ModificationCommand;
+use crate::world::core::{layout_voxel_index, BlockId, ChunkLayout, ChunkPos, VoxelPos};
+use crate::world::lighting::blocks_skylight;
+
+use super::region_readback::{
+    poll_region_readback, region_contains, request_region_readback, voxel_regions_for_bounds,
+    RegionReadbackBatch, RegionReadbackStatus, RegionVoxels, VoxelRegion,
+};
+use super::world_buffer::{pack_voxel_light, VoxelData, WorldBuffer};
+
+/// Key for a vertical column of chunks
+pub type ShadowColumnKey = (i32, i32);
+
+/// A single cached chunk inside a column
+#[derive(Debug, Clone)]
+pub struct ShadowChunk {
+    pub blocks: Vec<BlockId>,
+    /// Block and sky light per voxel (`pack_voxel_light` layout)
+    pub light: Vec<u16>,
+    /// Blocks changed since the light was read back
+    pub light_stale: bool,
+    /// Bumped on every modification so in-flight readbacks can be discarded
+    pub version: u64,
+}
+
+/// Heightmap value of a column position with no skylight-blocking voxel
+pub const SHADOW_NO_HEIGHT: i32 = i32::MIN;
+
+/// A vertical column of cached chunks
+#[derive(Debug, Clone, Default)]
+pub struct ShadowColumn {
+    pub chunks: HashMap<i32, ShadowChunk>,
+    /// World Y of the highest skylight-blocking voxel in the cached chunks,
+    /// per local `x + z * size` (`SHADOW_NO_HEIGHT` if none)
+    pub heightmap: Vec<i32>,
+    pub last_access: u64,
+}
+
+/// Voxels of an uncached chunk known from region readbacks, by local index
+#[derive(Debug, Clone, Default)]
+pub struct ShadowPartialChunk {
+    pub blocks: HashMap<u32, BlockId>,
+    /// Light of the same voxels (`pack_voxel_light` layout)
+    pub light: HashMap<u32, u16>,
+}
+
+/// Result of a shadow cache lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowLookup {
+    /// Block is cached and up to date
+    Hit(BlockId),
+    /// Chunk is not cached; a readback has been queued
+    Miss,
+}
+
+/// Result of a shadow cache light lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowLightLookup {
+    /// Light is cached (`pack_voxel_light` layout). `stale` when blocks
+    /// changed since the readback; a fresh readback has been queued.
+    Hit { light: u16, stale: bool },
+    /// Chunk is not cached; a readback has been queued
+    Miss,
+}
+
+/// Shadow cache statistics
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShadowCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub invalidations: u64,
+    pub readbacks_completed: u64,
+    pub readbacks_discarded: u64,
+    pub region_readbacks_completed: u64,
+    /// Bytes copied by region readbacks
+    pub region_bytes_read: u64,
+}
+
+type MapResultReceiver = Receiver<Result<(), wgpu::BufferAsyncError>>;
+
+/// In-flight asynchronous readback of one chunk
+pub struct ShadowReadback {
+    pub chunk_pos: ChunkPos,
+    /// Chunk version when the copy was recorded
+    pub version: u64,
+    buffer: wgpu::Buffer,
+    allocation: GpuAllocationGuard,
+    receiver: MapResultReceiver,
+}
+
+/// In-flight batch of region readbacks
+pub struct ShadowRegionReadback {
+    pub batch: RegionReadbackBatch,
+    /// Chunk version of each region when the copy was recorded
+    pub versions: Vec<u64>,
+}
+
+/// Bounded CPU-side cache of chunk columns
+pub struct ShadowCacheData {
+    pub columns: HashMap<ShadowColumnKey, ShadowColumn>,
+    pub max_columns: usize,
+    pub chunk_layout: ChunkLayout,
+    /// Chunks that missed and still need a readback
+    pub pending: HashSet<ChunkPos>,
+    /// Per-chunk modification version, kept even when the chunk is not cached
+    pub versions: HashMap<ChunkPos, u64>,
+    pub in_flight: Vec<ShadowReadback>,
+    /// Partial chunks filled by region readbacks
+    pub partial: HashMap<ChunkPos, ShadowPartialChunk>,
+    pub partial_voxels: usize,
+    /// Regions waiting for `request_shadow_region_readbacks`
+    pub pending_regions: Vec<VoxelRegion>,
+    pub region_in_flight: Vec<ShadowRegionReadback>,
+    pub access_tick: u64,
+    pub stats: ShadowCacheStats,
+}
+
+/// Shadow cache shared by the chunk modifier, the engine and the gateway
+pub type SharedShadowCache = Arc<Mutex<ShadowCacheData>>;
+
+/// Create a shadow cache holding at most `max_columns` chunk columns
+pub fn create_shadow_cache(max_columns: usize, chunk_layout: ChunkLayout) -> ShadowCacheData {
+    ShadowCacheData {
+        columns: HashMap::new(),
+        max_columns: max_columns.max(1),
+        chunk_layout,
+        pending: HashSet::new(),
+        versions: HashMap::new(),
+        in_flight: Vec::new(),
+        partial: HashMap::new(),
+        partial_voxels: 0,
+        pending_regions: Vec::new(),
+        region_in_flight: Vec::new(),
+        access_tick: 0,
+        stats: ShadowCacheStats::default(),
+    }
+}
+
+fn column_key(chunk_pos: ChunkPos) -> ShadowColumnKey {
+    (chunk_pos.x, chunk_pos.z)
+}
+
+fn local_index(pos: VoxelPos, chunk_size: u32) -> usize {
+    let (x, y, z) = pos.to_local_pos(chunk_size);
+    let cs = chunk_size as usize;
+    x as usize + y as usize * cs + z as usize * cs * cs
+}
+
+fn chunk_version(cache: &ShadowCacheData, chunk_pos: ChunkPos) -> u64 {
+    cache.versions.get(&chunk_pos).copied().unwrap_or(0)
+}
+
+/// Look up a block, queueing a readback on miss
+pub fn shadow_get_block(cache: &mut ShadowCacheData, pos: VoxelPos) -> ShadowLookup {
+    let chunk_pos = pos.to_chunk_pos(cache.chunk_layout.size);
+    let index = local_index(pos, cache.chunk_layout.size);
+
+    cache.access_tick += 1;
+    let tick = cache.access_tick;
+
+    let cached = cache
+        .columns
+        .get_mut(&column_key(chunk_pos))
+        .and_then(|column| {
+            column.last_access = tick;
+            column.chunks.get(&chunk_pos.y)
+        })
+        .and_then(|chunk| chunk.blocks.get(index).copied());
+
+    match cached {
+        Some(block) => {
+            cache.stats.hits += 1;
+            ShadowLookup::Hit(block)
+        }
+        None => {
+            let partial = cache
+                .partial
+                .get(&chunk_pos)
+                .and_then(|chunk| chunk.blocks.get(&(index as u32)).copied());
+            if let Some(block) = partial {
+                cache.stats.hits += 1;
+                return ShadowLookup::Hit(block);
+            }
+
+            cache.stats.misses += 1;
+            // A queued region will answer this; don't pull in the whole chunk
+            if !region_requested(cache, pos) {
+                cache.pending.insert(chunk_pos);
+            }
+            ShadowLookup::Miss
+        }
+    }
+}
+
+fn region_requested(cache: &ShadowCacheData, pos: VoxelPos) -> bool {
+    let layout = cache.chunk_layout;
+    cache
+        .pending_regions
+        .iter()
+        .chain(
+            cache
+                .region_in_flight
+                .iter()
+                .flat_map(|r| r.batch.regions.iter()),
+        )
+        .any(|region| region_contains(layout, region, pos))
+}
+
+/// Look up the light of a voxel, queueing a readback on miss or when the
+/// cached light is stale
+pub fn shadow_get_light(cache: &mut ShadowCacheData, pos: VoxelPos) -> ShadowLightLookup {
+    let chunk_pos = pos.to_chunk_pos(cache.chunk_layout.size);
+    let index = local_index(pos, cache.chunk_layout.size);
+
+    cache.access_tick += 1;
+    let tick = cache.access_tick;
+
+    let cached = cache
+        .columns
+        .get_mut(&column_key(chunk_pos))
+        .and_then(|column| {
+            column.last_access = tick;
+            column.chunks.get(&chunk_pos.y)
+        })
+        .and_then(|chunk| Some((*chunk.light.get(index)?, chunk.light_stale)));
+
+    if let Some((light, stale)) = cached {
+        cache.stats.hits += 1;
+        if stale {
+            cache.pending.insert(chunk_pos);
+        }
+        return ShadowLightLookup::Hit { light, stale };
+    }
+
+    let partial = cache
+        .partial
+        .get(&chunk_pos)
+        .and_then(|chunk| chunk.light.get(&(index as u32)).copied());
+    if let Some(light) = partial {
+        cache.stats.hits += 1;
+        return ShadowLightLookup::Hit {
+            light,
+            stale: false,
+        };
+    }
+
+    cache.stats.misses += 1;
+    if !region_requested(cache, pos) {
+        cache.pending.insert(chunk_pos);
+    }
+    ShadowLightLookup::Miss
+}
+
+/// World Y of the highest skylight-blocking voxel cached in the column of
+/// `(x, z)`: `SHADOW_NO_HEIGHT` when the cached chunks have none, `None`
+/// when no chunk of the column is cached
+pub fn shadow_column_height(cache: &ShadowCacheData, x: i32, z: i32) -> Option<i32> {
+    let chunk_size = cache.chunk_layout.size;
+    let pos = VoxelPos::new(x, 0, z);
+    let chunk_pos = pos.to_chunk_pos(chunk_size);
+    let (local_x, _, local_z) = pos.to_local_pos(chunk_size);
+    cache
+        .columns
+        .get(&column_key(chunk_pos))
+        .and_then(|column| {
+            column
+                .heightmap
+                .get((local_x + local_z * chunk_size) as usize)
+                .copied()
+        })
+}
+
+/// Look up a block, returning `fallback` on miss
+pub fn shadow_get_block_or(
+    cache: &mut ShadowCacheData,
+    pos: VoxelPos,
+    fallback: BlockId,
+) -> BlockId {
+    match shadow_get_block(cache, pos) {
+        ShadowLookup::Hit(block) => block,
+        ShadowLookup::Miss => fallback,
+    }
+}
+
+/// Check whether a chunk is currently cached
+pub fn shadow_contains_chunk(cache: &ShadowCacheData, chunk_pos: ChunkPos) -> bool {
+    cache
+        .columns
+        .get(&column_key(chunk_pos))
+        .is_some_and(|column| column.chunks.contains_key(&chunk_pos.y))
+}
+
+/// Store readback results for a chunk, evicting old columns if needed
+pub fn insert_shadow_chunk(cache: &mut ShadowCacheData, chunk_pos: ChunkPos, voxels: &[VoxelData]) {
+    let expected = cache.chunk_layout.voxels_per_chunk;
+    if voxels.len() != expected as usize {
+        log::warn!(
+            "[SHADOW_CACHE] Ignoring readback for {:?}: expected {} voxels, got {}",
+            chunk_pos,
+            expected,
+            voxels.len()
+        );
+        return;
+    }
+
+    let key = column_key(chunk_pos);
+    if !cache.columns.contains_key(&key) {
+        evict_shadow_columns(cache, cache.max_columns.saturating_sub(1));
+    }
+
+    cache.access_tick += 1;
+    let tick = cache.access_tick;
+    let version = chunk_version(cache, chunk_pos);
+
+    let chunk_size = cache.chunk_layout.size;
+    let column = cache.columns.entry(key).or_default();
+    column.last_access = tick;
+    column.chunks.insert(
+        chunk_pos.y,
+        ShadowChunk {
+            blocks: voxels.iter().map(|v| BlockId(v.block_id())).collect(),
+            light: voxels
+                .iter()
+                .map(|v| pack_voxel_light(v.light_level(), v.sky_light_level()))
+                .collect(),
+            light_stale: false,
+            version,
+        },
+    );
+    rebuild_column_heightmap(column, chunk_size);
+    cache.pending.remove(&chunk_pos);
+    remove_partial_chunk(cache, chunk_pos);
+}
+
+fn voxel_height(chunk: &ShadowChunk, chunk_y: i32, chunk_size: u32, x: u32, z: u32) -> i32 {
+    let cs = chunk_size as usize;
+    (0..chunk_size)
+        .rev()
+        .find(|&y| {
+            let index = x as usize + y as usize * cs + z as usize * cs * cs;
+            chunk
+                .blocks
+                .get(index)
+                .is_some_and(|block| blocks_skylight(*block))
+        })
+        .map_or(SHADOW_NO_HEIGHT, |y| chunk_y * chunk_size as i32 + y as i32)
+}
+
+fn column_height_at(column: &ShadowColumn, chunk_size: u32, x: u32, z: u32) -> i32 {
+    let mut chunk_ys: Vec<i32> = column.chunks.keys().copied().collect();
+    chunk_ys.sort_unstable_by(|a, b| b.cmp(a));
+    chunk_ys
+        .into_iter()
+        .filter_map(|chunk_y| {
+            let chunk = column.chunks.get(&chunk_y)?;
+            let height = voxel_height(chunk, chunk_y, chunk_size, x, z);
+            (height != SHADOW_NO_HEIGHT).then_some(height)
+        })
+        .next()
+        .unwrap_or(SHADOW_NO_HEIGHT)
+}
+
+/// Recompute a column's heightmap from its cached chunks
+fn rebuild_column_heightmap(column: &mut ShadowColumn, chunk_size: u32) {
+    let mut heightmap = Vec::with_capacity((chunk_size * chunk_size) as usize);
+    for z in 0..chunk_size {
+        for x in 0..chunk_size {
+            heightmap.push(column_height_at(column, chunk_size, x, z));
+        }
+    }
+    column.heightmap = heightmap;
+}
+
+/// Keep a column's heightmap current after one voxel changed
+fn update_column_height(column: &mut ShadowColumn, chunk_size: u32, pos: VoxelPos, block: BlockId) {
+    let (x, _, z) = pos.to_local_pos(chunk_size);
+    let index = (x + z * chunk_size) as usize;
+    let Some(&height) = column.heightmap.get(index) else {
+        return;
+    };
+    if blocks_skylight(block) {
+        if pos.y > height {
+            column.heightmap[index] = pos.y;
+        }
+    } else if pos.y == height {
+        column.heightmap[index] = column_height_at(column, chunk_size, x, z);
+    }
+}
+
+fn remove_partial_chunk(cache: &mut ShadowCacheData, chunk_pos: ChunkPos) {
+    if let Some(partial) = cache.partial.remove(&chunk_pos) {
+        cache.partial_voxels = cache.partial_voxels.saturating_sub(partial.blocks.len());
+    }
+}
+
+/// Store the voxels of a region readback as a partial chunk
+///
+/// Ignored when the full chunk is already cached. All partial chunks are
+/// dropped once they hold more than `SHADOW_PARTIAL_VOXEL_LIMIT` voxels.
+pub fn insert_shadow_region(cache: &mut ShadowCacheData, read: &RegionVoxels) {
+    let region = read.region;
+    if shadow_contains_chunk(cache, region.chunk_pos) {
+        return;
+    }
+    if cache.partial_voxels + read.voxels.len() > SHADOW_PARTIAL_VOXEL_LIMIT {
+        cache.partial.clear();
+        cache.partial_voxels = 0;
+    }
+
+    let layout = cache.chunk_layout;
+    let partial = cache.partial.entry(region.chunk_pos).or_default();
+    let before = partial.blocks.len();
+    let mut voxels = read.voxels.iter();
+    for z in region.min[2]..=region.max[2] {
+        for y in region.min[1]..=region.max[1] {
+            for x in region.min[0]..=region.max[0] {
+                let Some(voxel) = voxels.next() else {
+                    break;
+                };
+                let index = layout_voxel_index(layout, x, y, z);
+                partial.blocks.insert(index, BlockId(voxel.block_id()));
+                partial.light.insert(
+                    index,
+                    pack_voxel_light(voxel.light_level(), voxel.sky_light_level()),
+                );
+            }
+        }
+    }
+    cache.partial_voxels += partial.blocks.len() - before;
+}
+
+/// Evict least-recently-used columns until at most `target` remain
+pub fn evict_shadow_columns(cache: &mut ShadowCacheData, target: usize) {
+    while cache.columns.len() > target {
+        let oldest = cache
+            .columns
+            .iter()
+            .min_by_key(|(_, column)| column.last_access)
+            .map(|(key, _)| *key);
+
+        match oldest {
+            Some(key) => {
+                cache.columns.remove(&key);
+                cache.stats.evictions += 1;
+            }
+            None => break,
+        }
+    }
+}
+
+/// Drop a cached chunk so the next query triggers a fresh readback
+pub fn invalidate_shadow_chunk(cache: &mut ShadowCacheData, chunk_pos: ChunkPos) {
+    *cache.versions.entry(chunk_pos).or_insert(0) += 1;
+    remove_partial_chunk(cache, chunk_pos);
+
+    let key = column_key(chunk_pos);
+    let chunk_size = cache.chunk_layout.size;
+    if let Some(column) = cache.columns.get_mut(&key) {
+        if column.chunks.remove(&chunk_pos.y).is_some() {
+            cache.stats.invalidations += 1;
+            rebuild_column_heightmap(column, chunk_size);
+        }
+        if column.chunks.is_empty() {
+            cache.columns.remove(&key);
+        }
+    }
+}
+
+/// Mirror queued GPU modifications into the cache
+///
+/// Set and break commands are written through so cached chunks stay valid;
+/// their light is only marked stale, since the GPU relights it later.
+/// Explosions invalidate every chunk touched by their radius.
+pub fn apply_modifications_to_shadow(
+    cache: &mut ShadowCacheData,
+    commands: &[ModificationCommand],
+) {
+    let chunk_size = cache.chunk_layout.size;
+
+    for cmd in commands {
+        let [x, y, z] = cmd.position;
+        match cmd.mod_type {
+            0 | 1 => {
+                let pos = VoxelPos::new(x, y, z);
+                let chunk_pos = pos.to_chunk_pos(chunk_size);
+                let index = local_index(pos, chunk_size);
+
+                *cache.versions.entry(chunk_pos).or_insert(0) += 1;
+                let version = chunk_version(cache, chunk_pos);
+
+                let new_block = BlockId(cmd.block_id as u16);
+                if let Some(column) = cache.columns.get_mut(&column_key(chunk_pos)) {
+                    if let Some(chunk) = column.chunks.get_mut(&chunk_pos.y) {
+                        if let Some(block) = chunk.blocks.get_mut(index) {
+                            *block = new_block;
+                        }
+                        chunk.light_stale = true;
+                        chunk.version = version;
+                        update_column_height(column, chunk_size, pos, new_block);
+                    }
+                }
+                if let Some(block) = cache
+                    .partial
+                    .get_mut(&chunk_pos)
+                    .and_then(|partial| partial.blocks.get_mut(&(index as u32)))
+                {
+                    *block = new_block;
+                }
+            }
+            2 => {
+                let radius = cmd.radius.max(0.0).ceil() as i32;
+                let min =
+                    VoxelPos::new(x - radius, y - radius, z - radius).to_chunk_pos(chunk_size);
+                let max =
+                    VoxelPos::new(x + radius, y + radius, z + radius).to_chunk_pos(chunk_size);
+
+                for cx in min.x..=max.x {
+                    for cy in min.y..=max.y {
+                        for cz in min.z..=max.z {
+                            invalidate_shadow_chunk(cache, ChunkPos::new(cx, cy, cz));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Record async readbacks for up to `budget` pending chunks
+///
+/// Sparse chunks are resolved from their descriptor and palette chunks are
+/// promoted to a full slot first. Chunks not in the WorldBuffer yet stay
+/// pending until their upload invalidates them and a query asks again.
+pub fn request_shadow_readbacks(
+    cache: &mut ShadowCacheData,
+    world_buffer: &WorldBuffer,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    budget: usize,
+) -> usize {
+    if cache.pending.is_empty() || budget == 0 {
+        return 0;
+    }
+
+    let in_flight: HashSet<ChunkPos> = cache.in_flight.iter().map(|r| r.chunk_pos).collect();
+    let batch: Vec<ChunkPos> = cache
+        .pending
+        .iter()
+        .filter(|pos| !in_flight.contains(pos))
+        .filter(|pos| {
+            world_buffer.existing_chunk_slot(**pos).is_some()
+                || world_buffer.is_chunk_sparse(**pos)
+                || world_buffer.is_chunk_palettized(**pos)
+        })
+        .take(budget)
+        .copied()
+        .collect();
+
+    if batch.is_empty() {
+        return 0;
+    }
+
+    let chunk_bytes = world_buffer.slot_size();
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Shadow Cache Readback"),
+    });
+    let mut recorded = Vec::new();
+
+    // Decoded into `encoder` ahead of the copies; a decode batch that is
+    // full leaves the rest pending for the next call
+    let palettized: Vec<ChunkPos> = batch
+        .iter()
+        .filter(|pos| world_buffer.is_chunk_palettized(**pos))
+        .copied()
+        .collect();
+    world_buffer.promote_palette_chunks(queue, &mut encoder, &palettized);
+
+    for chunk_pos in batch {
+        if let Some(sparse) = world_buffer.sparse_chunk(chunk_pos) {
+            let voxels = vec![sparse.voxel; cache.chunk_layout.voxels_per_chunk as usize];
+            insert_shadow_chunk(cache, chunk_pos, &voxels);
+            continue;
+        }
+        let Some(slot) = world_buffer.existing_chunk_slot(chunk_pos) else {
+            continue;
+        };
+
+        let (buffer, allocation) = create_tracked_buffer(
+            device,
+            world_buffer.gpu_owner(),
+            &wgpu::BufferDescriptor {
+                label: Some("Shadow Cache Staging"),
+                size: chunk_bytes,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
+        encoder.copy_buffer_to_buffer(
+            world_buffer.voxel_buffer(),
+            world_buffer.slot_offset(slot),
+            &buffer,
+            0,
+            chunk_bytes,
+        );
+        recorded.push((chunk_pos, buffer, allocation));
+    }
+
+    if recorded.is_empty() {
+        return 0;
+    }
+
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let count = recorded.len();
+    for (chunk_pos, buffer, allocation) in recorded {
+        let (sender, receiver) = channel();
+        buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        cache.in_flight.push(ShadowReadback {
+            chunk_pos,
+            version: chunk_version(cache, chunk_pos),
+            buffer,
+            allocation,
+            receiver,
+        });
+    }
+
+    count
+}
+
+/// Queue the voxels of an inclusive world-space box for a region readback
+///
+/// Chunks that are already cached are skipped.
+pub fn queue_shadow_region(cache: &mut ShadowCacheData, min: VoxelPos, max: VoxelPos) {
+    for region in voxel_regions_for_bounds(cache.chunk_layout, min, max) {
+        if !shadow_contains_chunk(cache, region.chunk_pos)
+            && !cache.pending_regions.contains(&region)
+        {
+            cache.pending_regions.push(region);
+        }
+    }
+}
+
+/// Read up to `SHADOW_REGION_BATCH` queued regions in one batch
+pub fn request_shadow_region_readbacks(
+    cache: &mut ShadowCacheData,
+    world_buffer: &WorldBuffer,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+) -> usize {
+    let count = cache.pending_regions.len().min(SHADOW_REGION_BATCH);
+    if count == 0 {
+        return 0;
+    }
+
+    let regions: Vec<VoxelRegion> = cache.pending_regions.drain(..count).collect();
+    let versions = regions
+        .iter()
+        .map(|region| chunk_version(cache, region.chunk_pos))
+        .collect();
+    let batch = request_region_readback(world_buffer, device, queue, &regions);
+    cache.stats.region_bytes_read += batch.byte_size;
+    cache
+        .region_in_flight
+        .push(ShadowRegionReadback { batch, versions });
+    count
+}
+
+fn poll_shadow_region_readbacks(cache: &mut ShadowCacheData, device: &wgpu::Device) -> usize {
+    let mut completed = 0;
+    let mut still_pending = Vec::new();
+
+    for mut readback in std::mem::take(&mut cache.region_in_flight) {
+        match poll_region_readback(&mut readback.batch, device) {
+            RegionReadbackStatus::Pending => still_pending.push(readback),
+            RegionReadbackStatus::Ready(reads) => {
+                for (read, version) in reads.iter().zip(&readback.versions) {
+                    if *version != chunk_version(cache, read.region.chunk_pos) {
+                        cache.stats.readbacks_discarded += 1;
+                        cache.pending_regions.push(read.region);
+                        continue;
+                    }
+                    insert_shadow_region(cache, read);
+                    cache.stats.region_readbacks_completed += 1;
+                    completed += 1;
+                }
+            }
+            RegionReadbackStatus::Failed(e) => {
+                log::warn!("[SHADOW_CACHE] Region readback failed: {}", e);
+                cache.stats.readbacks_discarded += readback.batch.regions.len() as u64;
+            }
+        }
+    }
+
+    cache.region_in_flight = still_pending;
+    completed
+}
+
+/// Collect finished readbacks without blocking
+///
+/// Readbacks whose chunk was modified after the copy was recorded are
+/// discarded and the chunk (or region) is re-queued.
+pub fn poll_shadow_readbacks(cache: &mut ShadowCacheData, device: &wgpu::Device) -> usize {
+    let mut completed = 0;
+    if !cache.region_in_flight.is_empty() {
+        completed += poll_shadow_region_readbacks(cache, device);
+    }
+    if cache.in_flight.is_empty() {
+        return completed;
+    }
+
+    device.poll(wgpu::Maintain::Poll);
+
+    let mut still_pending = Vec::new();
+
+    for readback in std::mem::take(&mut cache.in_flight) {
+        match readback.receiver.try_recv() {
+            Ok(Ok(())) => {
+                if readback.version != chunk_version(cache, readback.chunk_pos) {
+                    cache.stats.readbacks_discarded += 1;
+                    readback.buffer.unmap();
+                    cache.pending.insert(readback.chunk_pos);
+                    continue;
+                }
+
+                let voxels: Vec<VoxelData> = {
+                    let mapped = readback.buffer.slice(..).get_mapped_range();
+                    bytemuck::cast_slice(&mapped).to_vec()
+                };
+                readback.buffer.unmap();
+
+                insert_shadow_chunk(cache, readback.chunk_pos, &voxels);
+                cache.stats.readbacks_completed += 1;
+                completed += 1;
+            }
+            Ok(Err(e)) => {
+                log::warn!(
+                    "[SHADOW_CACHE] Readback mapping failed for {:?}: {:?}",
+                    readback.chunk_pos,
+                    e
+                );
+                cache.stats.readbacks_discarded += 1;
+            }
+            Err(TryRecvError::Empty) => still_pending.push(readback),
+            Err(TryRecvError::Disconnected) => {
+                cache.stats.readbacks_discarded += 1;
+            }
+        }
+    }
+
+    cache.in_flight = still_pending;
+    completed
+}
+
+/// Hit rate over the lifetime of the cache
+pub fn shadow_cache_hit_rate(cache: &ShadowCacheData) -> f32 {
+    let total = cache.stats.hits + cache.stats.misses;
+    if total == 0 {
+        0.0
+    } else {
+        cache.stats.hits as f32 / total as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::core::DEFAULT_CHUNK_LAYOUT;
+
+    fn filled_chunk(block: u16) -> Vec<VoxelData> {
+        vec![VoxelData::new(block, 0, 0, 0); DEFAULT_CHUNK_LAYOUT.voxels_per_chunk as usize]
+    }
+
+    #[test]
+    fn test_miss_then_hit() {
+        let mut cache = create_shadow_cache(4, DEFAULT_CHUNK_LAYOUT);
+        let pos = VoxelPos::new(3, 4, 5);
+
+        assert_eq!(shadow_get_block(&mut cache, pos), ShadowLookup::Miss);
+        assert!(cache.pending.contains(&ChunkPos::new(0, 0, 0)));
+
+        insert_shadow_chunk(&mut cache, ChunkPos::new(0, 0, 0), &filled_chunk(7));
+        assert!(cache.pending.is_empty());
+        assert_eq!(
+            shadow_get_block(&mut cache, pos),
+            ShadowLookup::Hit(BlockId(7))
+        );
+    }
+
+    #[test]
+    fn test_modifications_write_through_and_invalidate() {
+        let mut cache = create_shadow_cache(4, DEFAULT_CHUNK_LAYOUT);
+        insert_shadow_chunk(&mut cache, ChunkPos::new(0, 0, 0), &filled_chunk(1));
+
+        apply_modifications_to_shadow(&mut cache, &[ModificationCommand::break_block(2, 2, 2)]);
+        assert_eq!(
+            shadow_get_block(&mut cache, VoxelPos::new(2, 2, 2)),
+            ShadowLookup::Hit(BlockId::AIR)
+        );
+
+        apply_modifications_to_shadow(&mut cache, &[ModificationCommand::explode(10, 10, 10, 3.0)]);
+        assert!(!shadow_contains_chunk(&cache, ChunkPos::new(0, 0, 0)));
+    }
+
+    #[test]
+    fn test_lru_eviction_bounds_columns() {
+        let mut cache = create_shadow_cache(2, DEFAULT_CHUNK_LAYOUT);
+        insert_shadow_chunk(&mut cache, ChunkPos::new(0, 0, 0), &filled_chunk(1));
+        insert_shadow_chunk(&mut cache, ChunkPos::new(1, 0, 0), &filled_chunk(1));
+
+        // Touch the first column so the second becomes least recently used
+        let _ = shadow_get_block(&mut cache, VoxelPos::new(0, 0, 0));
+        insert_shadow_chunk(&mut cache, ChunkPos::new(2, 0, 0), &filled_chunk(1));
+
+        assert_eq!(cache.columns.len(), 2);
+        assert!(shadow_contains_chunk(&cache, ChunkPos::new(0, 0, 0)));
+        assert!(!shadow_contains_chunk(&cache, ChunkPos::new(1, 0, 0)));
+        assert_eq!(cache.stats.evictions, 1);
+    }
+
+    #[test]
+    fn test_region_overlay_answers_without_chunk_readback() {
+        let mut cache = create_shadow_cache(4, DEFAULT_CHUNK_LAYOUT);
+        queue_shadow_region(&mut cache, VoxelPos::new(1, 1, 1), VoxelPos::new(2, 2, 2));
+        assert_eq!(cache.pending_regions.len(), 1);
+
+        // Covered by the queued region, so no full chunk readback is queued
+        assert_eq!(
+            shadow_get_block(&mut cache, VoxelPos::new(2, 1, 1)),
+            ShadowLookup::Miss
+        );
+        assert!(cache.pending.is_empty());
+
+        let region = cache.pending_regions.remove(0);
+        let read = RegionVoxels {
+            region,
+            voxels: vec![VoxelData::new(5, 0, 0, 0); 8],
+        };
+        insert_shadow_region(&mut cache, &read);
+        assert_eq!(
+            shadow_get_block(&mut cache, VoxelPos::new(2, 1, 1)),
+            ShadowLookup::Hit(BlockId(5))
+        );
+
+        apply_modifications_to_shadow(&mut cache, &[ModificationCommand::break_block(2, 1, 1)]);
+        assert_eq!(
+            shadow_get_block(&mut cache, VoxelPos::new(2, 1, 1)),
+            ShadowLookup::Hit(BlockId::AIR)
+        );
+
+        // The full chunk replaces the overlay
+        insert_shadow_chunk(&mut cache, ChunkPos::new(0, 0, 0), &filled_chunk(1));
+        assert!(cache.partial.is_empty());
+        assert_eq!(cache.partial_voxels, 0);
+    }
+}
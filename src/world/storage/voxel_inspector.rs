@@ -0,0 +1,633 @@
+//! Voxel inspector: debug readback of world buffer contents
+//!
+//! `inspect x y z [radius]` reads back the cube of voxels around a position
+//! asynchronously and decodes each `VoxelData` (block, light, sky light,
+//! metadata) together with where it lives on the GPU (slot and byte offset),
+//! so generation and lighting bugs can be checked against the buffer rather
+//! than logs.
+//!
+//! - [`request_voxel_inspection`] records one small copy per overlapped chunk
+//!   (only the index range the cube touches) into a single staging buffer.
+//! - [`poll_voxel_inspections`] decodes finished readbacks without blocking;
+//!   the newest result is kept for the debug UI ([`inspection_lines`]).
+//! - Sparse chunks are answered from their descriptor and chunks without a
+//!   slot are reported as not resident.
+
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+
+use crate::constants::voxel_inspector::{DEFAULT_RADIUS, MAX_RADIUS};
+use crate::memory::{create_tracked_buffer, GpuAllocationGuard};
+use crate::world::core::{ChunkPos, VoxelPos};
+
+use super::world_buffer::{unpack_voxel_light, VoxelData, WorldBuffer};
+
+/// Cube of voxels to inspect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoxelInspectRequest {
+    pub center: VoxelPos,
+    /// Voxels on each side of the center (0 = just the center)
+    pub radius: u32,
+}
+
+/// Where an inspected voxel's value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectedVoxelSource {
+    /// Read back from the chunk's world buffer slot
+    Resident { slot: u32, byte_offset: u64 },
+    /// Uniform chunk stored as a sparse descriptor (no slot)
+    Sparse,
+    /// Chunk has neither a slot nor a descriptor
+    NotResident,
+}
+
+/// One decoded voxel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InspectedVoxel {
+    pub position: VoxelPos,
+    pub chunk: ChunkPos,
+    /// Flat index inside the chunk
+    pub local_index: u32,
+    pub source: InspectedVoxelSource,
+    pub voxel: VoxelData,
+    /// Block and sky light; from the dedicated light buffer when there is one
+    pub block_light: u8,
+    pub sky_light: u8,
+}
+
+/// Decoded result of one request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoxelInspection {
+    pub id: u64,
+    pub request: VoxelInspectRequest,
+    /// x fastest, then y, then z
+    pub voxels: Vec<InspectedVoxel>,
+}
+
+/// Copy of one chunk's index range into the staging buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InspectChunkRange {
+    pub chunk: ChunkPos,
+    pub slot: u32,
+    pub slot_byte_offset: u64,
+    /// First chunk-local index copied
+    pub first_index: u32,
+    pub voxel_offset: u64,
+    /// Light copy when the world has a dedicated light buffer
+    pub light: Option<InspectLightRange>,
+}
+
+/// Copy of one chunk's light values into the staging buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InspectLightRange {
+    /// First chunk-local index copied (even, for copy alignment)
+    pub first_index: u32,
+    pub staging_offset: u64,
+}
+
+/// Chunk-local index range a request needs from one chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InspectIndexRange {
+    pub chunk: ChunkPos,
+    pub first_index: u32,
+    /// Inclusive
+    pub last_index: u32,
+}
+
+/// How each chunk overlapped by a request is answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectChunkSource {
+    Resident(InspectChunkRange),
+    Sparse { chunk: ChunkPos, voxel: VoxelData },
+    NotResident { chunk: ChunkPos },
+}
+
+type MapResultReceiver = Receiver<Result<(), wgpu::BufferAsyncError>>;
+
+/// In-flight readback of one request
+pub struct InspectReadback {
+    pub id: u64,
+    pub request: VoxelInspectRequest,
+    pub chunks: Vec<InspectChunkSource>,
+    buffer: Option<wgpu::Buffer>,
+    allocation: Option<GpuAllocationGuard>,
+    receiver: Option<MapResultReceiver>,
+}
+
+/// Inspector state
+pub struct VoxelInspectorData {
+    pub in_flight: Vec<InspectReadback>,
+    /// Newest completed inspection
+    pub latest: Option<VoxelInspection>,
+    pub next_id: u64,
+    pub chunk_size: u32,
+    /// Whether light comes from a dedicated buffer
+    pub dedicated_light: bool,
+}
+
+/// Create an inspector for a world buffer's layout
+pub fn create_voxel_inspector(world_buffer: &WorldBuffer) -> VoxelInspectorData {
+    VoxelInspectorData {
+        in_flight: Vec::new(),
+        latest: None,
+        next_id: 1,
+        chunk_size: world_buffer.chunk_layout().size,
+        dedicated_light: world_buffer.light_buffer().is_some(),
+    }
+}
+
+/// Parse `inspect <x> <y> <z> [radius]`
+pub fn parse_inspect_command(command: &str) -> Result<VoxelInspectRequest, String> {
+    let mut parts = command.split_whitespace();
+    if parts.next() != Some("inspect") {
+        return Err(format!("Unknown inspector command: {}", command));
+    }
+    let args: Vec<&str> = parts.collect();
+    if args.len() != 3 && args.len() != 4 {
+        return Err("Usage: inspect <x> <y> <z> [radius]".to_string());
+    }
+
+    let mut coords = [0i32; 3];
+    for (coord, arg) in coords.iter_mut().zip(&args) {
+        *coord = arg
+            .parse()
+            .map_err(|_| format!("Invalid coordinate: {}", arg))?;
+    }
+    let radius = match args.get(3) {
+        Some(arg) => arg
+            .parse::<u32>()
+            .map_err(|_| format!("Invalid radius: {}", arg))?,
+        None => DEFAULT_RADIUS,
+    };
+    if radius > MAX_RADIUS {
+        return Err(format!(
+            "Radius {} exceeds the maximum of {}",
+            radius, MAX_RADIUS
+        ));
+    }
+
+    Ok(VoxelInspectRequest {
+        center: VoxelPos::new(coords[0], coords[1], coords[2]),
+        radius,
+    })
+}
+
+fn request_bounds(request: &VoxelInspectRequest) -> (VoxelPos, VoxelPos) {
+    let r = request.radius as i32;
+    let c = request.center;
+    (
+        VoxelPos::new(c.x - r, c.y - r, c.z - r),
+        VoxelPos::new(c.x + r, c.y + r, c.z + r),
+    )
+}
+
+fn local_index(position: VoxelPos, chunk_size: u32) -> u32 {
+    let (x, y, z) = position.to_local_pos(chunk_size);
+    x + y * chunk_size + z * chunk_size * chunk_size
+}
+
+/// Chunks overlapped by a request with the local index range each one needs
+pub fn inspected_chunk_ranges(
+    request: &VoxelInspectRequest,
+    chunk_size: u32,
+) -> Vec<InspectIndexRange> {
+    let (min, max) = request_bounds(request);
+    let (min_chunk, max_chunk) = (min.to_chunk_pos(chunk_size), max.to_chunk_pos(chunk_size));
+    let size = chunk_size as i32;
+    let mut ranges = Vec::new();
+
+    for cz in min_chunk.z..=max_chunk.z {
+        for cy in min_chunk.y..=max_chunk.y {
+            for cx in min_chunk.x..=max_chunk.x {
+                let chunk = ChunkPos::new(cx, cy, cz);
+                let lo = VoxelPos::new(
+                    min.x.max(cx * size),
+                    min.y.max(cy * size),
+                    min.z.max(cz * size),
+                );
+                let hi = VoxelPos::new(
+                    max.x.min(cx * size + size - 1),
+                    max.y.min(cy * size + size - 1),
+                    max.z.min(cz * size + size - 1),
+                );
+                ranges.push(InspectIndexRange {
+                    chunk,
+                    first_index: local_index(lo, chunk_size),
+                    last_index: local_index(hi, chunk_size),
+                });
+            }
+        }
+    }
+    ranges
+}
+
+/// Record the readback for a request. Returns its id.
+pub fn request_voxel_inspection(
+    inspector: &mut VoxelInspectorData,
+    world_buffer: &WorldBuffer,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    request: VoxelInspectRequest,
+) -> u64 {
+    let id = inspector.next_id;
+    inspector.next_id += 1;
+
+    let voxel_bytes = std::mem::size_of::<VoxelData>() as u64;
+    let mut chunks = Vec::new();
+    let mut copies = Vec::new();
+    let mut staging_size = 0u64;
+
+    for range in inspected_chunk_ranges(&request, inspector.chunk_size) {
+        let (chunk, first, last) = (range.chunk, range.first_index, range.last_index);
+        if let Some(sparse) = world_buffer.sparse_chunk(chunk) {
+            chunks.push(InspectChunkSource::Sparse {
+                chunk,
+                voxel: sparse.voxel,
+            });
+            continue;
+        }
+        let Some(slot) = world_buffer.existing_chunk_slot(chunk) else {
+            chunks.push(InspectChunkSource::NotResident { chunk });
+            continue;
+        };
+
+        let slot_byte_offset = world_buffer.slot_offset(slot);
+        let voxel_offset = staging_size;
+        let voxel_size = (last - first + 1) as u64 * voxel_bytes;
+        copies.push((
+            false,
+            slot_byte_offset + first as u64 * voxel_bytes,
+            voxel_offset,
+            voxel_size,
+        ));
+        staging_size += voxel_size;
+
+        // u16 per voxel; widen to even indices so the copy stays 4-byte aligned
+        let light = world_buffer.light_buffer().map(|_| {
+            let light_first = first & !1;
+            let light_count = (last - light_first + 2) & !1;
+            let offset = staging_size;
+            copies.push((
+                true,
+                world_buffer.light_slot_offset(slot) + light_first as u64 * 2,
+                offset,
+                light_count as u64 * 2,
+            ));
+            staging_size += light_count as u64 * 2;
+            InspectLightRange {
+                first_index: light_first,
+                staging_offset: offset,
+            }
+        });
+
+        chunks.push(InspectChunkSource::Resident(InspectChunkRange {
+            chunk,
+            slot,
+            slot_byte_offset,
+            first_index: first,
+            voxel_offset,
+            light,
+        }));
+    }
+
+    let mut readback = InspectReadback {
+        id,
+        request,
+        chunks,
+        buffer: None,
+        allocation: None,
+        receiver: None,
+    };
+
+    if staging_size > 0 {
+        let (buffer, allocation) = create_tracked_buffer(
+            device,
+            world_buffer.gpu_owner(),
+            &wgpu::BufferDescriptor {
+                label: Some("Voxel Inspector Staging"),
+                size: staging_size,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Voxel Inspector Readback"),
+        });
+        for (is_light, src_offset, dst_offset, size) in copies {
+            let source = match (is_light, world_buffer.light_buffer()) {
+                (true, Some(light_buffer)) => light_buffer,
+                _ => world_buffer.voxel_buffer(),
+            };
+            encoder.copy_buffer_to_buffer(source, src_offset, &buffer, dst_offset, size);
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let (sender, receiver) = channel();
+        buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        readback.buffer = Some(buffer);
+        readback.allocation = Some(allocation);
+        readback.receiver = Some(receiver);
+    }
+
+    log::debug!(
+        "[VOXEL_INSPECTOR] Inspection {} of {:?} (radius {}): {} chunks, {} staging bytes",
+        id,
+        request.center,
+        request.radius,
+        readback.chunks.len(),
+        staging_size
+    );
+    inspector.in_flight.push(readback);
+    id
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let slice = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let slice = bytes.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([slice[0], slice[1]]))
+}
+
+/// Decode the staging bytes of a finished readback
+pub fn decode_voxel_inspection(
+    id: u64,
+    request: VoxelInspectRequest,
+    chunks: &[InspectChunkSource],
+    staging: &[u8],
+    chunk_size: u32,
+) -> VoxelInspection {
+    let (min, max) = request_bounds(&request);
+    let mut voxels = Vec::new();
+
+    for z in min.z..=max.z {
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let position = VoxelPos::new(x, y, z);
+                let chunk = position.to_chunk_pos(chunk_size);
+                let local_index = local_index(position, chunk_size);
+                let source = chunks.iter().find(|c| match c {
+                    InspectChunkSource::Resident(range) => range.chunk == chunk,
+                    InspectChunkSource::Sparse { chunk: c, .. }
+                    | InspectChunkSource::NotResident { chunk: c } => *c == chunk,
+                });
+
+                let (source, voxel, light) = match source {
+                    Some(InspectChunkSource::Resident(range)) => {
+                        let relative = (local_index - range.first_index) as u64;
+                        let raw = read_u32(staging, (range.voxel_offset + relative * 4) as usize)
+                            .unwrap_or(0);
+                        let light = range.light.and_then(|light| {
+                            let relative = (local_index - light.first_index) as u64;
+                            read_u16(staging, (light.staging_offset + relative * 2) as usize)
+                        });
+                        (
+                            InspectedVoxelSource::Resident {
+                                slot: range.slot,
+                                byte_offset: range.slot_byte_offset + local_index as u64 * 4,
+                            },
+                            VoxelData(raw),
+                            light,
+                        )
+                    }
+                    Some(InspectChunkSource::Sparse { voxel, .. }) => {
+                        (InspectedVoxelSource::Sparse, *voxel, None)
+                    }
+                    _ => (InspectedVoxelSource::NotResident, VoxelData::AIR, None),
+                };
+
+                let (block_light, sky_light) = match light {
+                    Some(packed) => unpack_voxel_light(packed),
+                    None => (voxel.light_level(), voxel.sky_light_level()),
+                };
+                voxels.push(InspectedVoxel {
+                    position,
+                    chunk,
+                    local_index,
+                    source,
+                    voxel,
+                    block_light,
+                    sky_light,
+                });
+            }
+        }
+    }
+
+    VoxelInspection {
+        id,
+        request,
+        voxels,
+    }
+}
+
+/// Decode finished readbacks without blocking. Returns how many completed;
+/// the newest is in `inspector.latest`.
+pub fn poll_voxel_inspections(inspector: &mut VoxelInspectorData, device: &wgpu::Device) -> usize {
+    if inspector.in_flight.is_empty() {
+        return 0;
+    }
+
+    device.poll(wgpu::Maintain::Poll);
+
+    let mut completed = 0;
+    let mut still_pending = Vec::new();
+
+    for readback in std::mem::take(&mut inspector.in_flight) {
+        let staging = match (&readback.buffer, &readback.receiver) {
+            (Some(buffer), Some(receiver)) => match receiver.try_recv() {
+                Ok(Ok(())) => {
+                    let bytes = buffer.slice(..).get_mapped_range().to_vec();
+                    buffer.unmap();
+                    bytes
+                }
+                Ok(Err(e)) => {
+                    log::warn!(
+                        "[VOXEL_INSPECTOR] Readback mapping failed for inspection {}: {:?}",
+                        readback.id,
+                        e
+                    );
+                    continue;
+                }
+                Err(TryRecvError::Empty) => {
+                    still_pending.push(readback);
+                    continue;
+                }
+                Err(TryRecvError::Disconnected) => continue,
+            },
+            // Nothing resident: answered without a GPU copy
+            _ => Vec::new(),
+        };
+
+        let inspection = decode_voxel_inspection(
+            readback.id,
+            readback.request,
+            &readback.chunks,
+            &staging,
+            inspector.chunk_size,
+        );
+        if inspector
+            .latest
+            .as_ref()
+            .is_none_or(|latest| latest.id < inspection.id)
+        {
+            inspector.latest = Some(inspection);
+        }
+        completed += 1;
+    }
+
+    inspector.in_flight = still_pending;
+    completed
+}
+
+/// Inspected voxel at a position, if it is part of the inspection
+pub fn inspected_voxel_at(
+    inspection: &VoxelInspection,
+    position: VoxelPos,
+) -> Option<&InspectedVoxel> {
+    inspection.voxels.iter().find(|v| v.position == position)
+}
+
+/// One debug line per voxel
+pub fn format_inspected_voxel(voxel: &InspectedVoxel) -> String {
+    let location = match voxel.source {
+        InspectedVoxelSource::Resident { slot, byte_offset } => {
+            format!("slot {} @0x{:08x}", slot, byte_offset)
+        }
+        InspectedVoxelSource::Sparse => "sparse".to_string(),
+        InspectedVoxelSource::NotResident => "not resident".to_string(),
+    };
+    format!(
+        "({}, {}, {}) chunk ({}, {}, {}) idx {} {}: block {} light {} sky {} meta {} raw 0x{:08x}",
+        voxel.position.x,
+        voxel.position.y,
+        voxel.position.z,
+        voxel.chunk.x,
+        voxel.chunk.y,
+        voxel.chunk.z,
+        voxel.local_index,
+        location,
+        voxel.voxel.block_id(),
+        voxel.block_light,
+        voxel.sky_light,
+        voxel.voxel.metadata(),
+        voxel.voxel.0
+    )
+}
+
+/// Lines for the debug UI or console, center voxel first
+pub fn inspection_lines(inspection: &VoxelInspection) -> Vec<String> {
+    let center = inspection.request.center;
+    let mut lines = vec![format!(
+        "Inspection {} at ({}, {}, {}) radius {}: {} voxels",
+        inspection.id,
+        center.x,
+        center.y,
+        center.z,
+        inspection.request.radius,
+        inspection.voxels.len()
+    )];
+    if let Some(voxel) = inspected_voxel_at(inspection, center) {
+        lines.push(format_inspected_voxel(voxel));
+    }
+    lines.extend(
+        inspection
+            .voxels
+            .iter()
+            .filter(|v| v.position != center)
+            .map(format_inspected_voxel),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_inspect_command() {
+        assert_eq!(
+            parse_inspect_command("inspect 10 -64 3"),
+            Ok(VoxelInspectRequest {
+                center: VoxelPos::new(10, -64, 3),
+                radius: DEFAULT_RADIUS,
+            })
+        );
+        assert_eq!(
+            parse_inspect_command("inspect 0 0 0 2").map(|r| r.radius),
+            Ok(2)
+        );
+        assert!(parse_inspect_command("inspect 0 0").is_err());
+        assert!(parse_inspect_command("inspect 0 0 0 99").is_err());
+    }
+
+    #[test]
+    fn test_decode_spans_resident_and_missing_chunks() {
+        let chunk_size = 4;
+        // x from -1 to 1 crosses from chunk -1 into chunk 0
+        let request = VoxelInspectRequest {
+            center: VoxelPos::new(0, 1, 1),
+            radius: 0,
+        };
+        let ranges = inspected_chunk_ranges(&request, chunk_size);
+        assert_eq!(
+            ranges,
+            vec![InspectIndexRange {
+                chunk: ChunkPos::new(0, 0, 0),
+                first_index: 20,
+                last_index: 20
+            }]
+        );
+
+        let wide = VoxelInspectRequest {
+            radius: 1,
+            ..request
+        };
+        let ranges = inspected_chunk_ranges(&wide, chunk_size);
+        assert_eq!(ranges.len(), 2);
+        let InspectIndexRange {
+            chunk,
+            first_index: first,
+            last_index: last,
+        } = ranges[1];
+        assert_eq!((chunk, first, last), (ChunkPos::new(0, 0, 0), 0, 41));
+
+        // Chunk 0 resident in slot 2; the staging copy starts at `first`
+        let mut staging = vec![0u8; (last - first + 1) as usize * 4];
+        let center_index = 20usize;
+        staging[center_index * 4..center_index * 4 + 4]
+            .copy_from_slice(&VoxelData::new(7, 3, 15, 2).0.to_le_bytes());
+        let chunks = [
+            InspectChunkSource::NotResident {
+                chunk: ChunkPos::new(-1, 0, 0),
+            },
+            InspectChunkSource::Resident(InspectChunkRange {
+                chunk,
+                slot: 2,
+                slot_byte_offset: 1000,
+                first_index: first,
+                voxel_offset: 0,
+                light: None,
+            }),
+        ];
+
+        let inspection = decode_voxel_inspection(1, wide, &chunks, &staging, chunk_size);
+        assert_eq!(inspection.voxels.len(), 27);
+        let center = inspected_voxel_at(&inspection, request.center).expect("center");
+        assert_eq!(center.voxel.block_id(), 7);
+        assert_eq!((center.block_light, center.sky_light), (3, 15));
+        assert_eq!(
+            center.source,
+            InspectedVoxelSource::Resident {
+                slot: 2,
+                byte_offset: 1080
+            }
+        );
+        let outside = inspected_voxel_at(&inspection, VoxelPos::new(-1, 1, 1)).expect("outside");
+        assert_eq!(outside.source, InspectedVoxelSource::NotResident);
+    }
+}
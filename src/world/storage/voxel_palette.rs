@@ -0,0 +1,353 @@
+//! Palettized voxel storage
+//!
+//! Most chunks use only a handful of distinct voxel values. In the palette
+//! tier a chunk stores each distinct packed voxel once in a per-chunk
+//! palette (up to 256 entries) and one 8-bit palette index per voxel, four
+//! indices per u32 word: a quarter of the 32-bit format plus 1 KB of palette.
+//! Chunks with more distinct values stay in the 32-bit format.
+//!
+//! The layout must match `palette_voxel` in wgsl_includes/voxel_palette.wgsl.
+
+use super::world_buffer::VoxelData;
+use crate::constants::voxel_palette::{
+    PALETTE_DECODE_MAX_BATCH, PALETTE_INDICES_PER_WORD, PALETTE_MAX_ENTRIES,
+};
+use crate::memory::{create_tracked_buffer, GpuAllocationGuard, GpuOwnerGuard};
+use crate::world::core::ChunkPos;
+use bytemuck::{Pod, Zeroable};
+use std::collections::HashMap;
+
+/// How chunk voxels are stored in a WorldBuffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VoxelStorageFormat {
+    /// Every resident chunk uses 4 bytes per voxel
+    #[default]
+    Full,
+    /// Chunks with at most 256 distinct voxels use a palette and 8-bit
+    /// indices; the rest fall back to the 32-bit format
+    Palette8,
+}
+
+/// A chunk in palette form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PalettizedChunk {
+    /// Distinct packed voxels, in order of first appearance
+    pub palette: Vec<VoxelData>,
+    /// Palette indices, four per word, voxel `i` in byte `i % 4` of word `i / 4`
+    pub indices: Vec<u32>,
+}
+
+/// Occupancy of the palette tier
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PaletteStorageStats {
+    /// Chunks stored in palette form
+    pub palette_chunks: u32,
+    /// Palette slots available in total
+    pub palette_capacity: u32,
+    /// Uploads that fell back to the 32-bit format because the chunk had
+    /// too many distinct voxels or the palette tier was full
+    pub fallback_uploads: u64,
+    /// GPU memory saved compared to storing the palette chunks at 32 bits
+    pub bytes_saved: u64,
+}
+
+/// Palette slot and the full slot it is decoded into
+pub(super) type PaletteDecodeSlots = (u32, u32);
+
+/// u32 words of palette indices per chunk
+pub fn palette_index_words(voxels_per_chunk: usize) -> usize {
+    voxels_per_chunk.div_ceil(PALETTE_INDICES_PER_WORD)
+}
+
+/// Bytes one palette slot occupies on the GPU (indices plus palette)
+pub fn palette_slot_bytes(voxels_per_chunk: usize) -> u64 {
+    ((palette_index_words(voxels_per_chunk) + PALETTE_MAX_ENTRIES) * 4) as u64
+}
+
+/// Palette form of a chunk, or None when it has more than 256 distinct voxels
+pub fn palettize_chunk(voxels: &[VoxelData]) -> Option<PalettizedChunk> {
+    let mut palette = Vec::new();
+    let mut lookup: HashMap<u32, u32> = HashMap::new();
+    let mut indices = vec![0u32; palette_index_words(voxels.len())];
+
+    for (i, voxel) in voxels.iter().enumerate() {
+        let index = match lookup.get(&voxel.0) {
+            Some(index) => *index,
+            None => {
+                if palette.len() == PALETTE_MAX_ENTRIES {
+                    return None;
+                }
+                let index = palette.len() as u32;
+                palette.push(*voxel);
+                lookup.insert(voxel.0, index);
+                index
+            }
+        };
+        indices[i / PALETTE_INDICES_PER_WORD] |= index << ((i % PALETTE_INDICES_PER_WORD) * 8);
+    }
+
+    Some(PalettizedChunk { palette, indices })
+}
+
+/// Voxel `index` of a palettized chunk
+pub fn palette_voxel(chunk: &PalettizedChunk, index: usize) -> VoxelData {
+    let word = chunk
+        .indices
+        .get(index / PALETTE_INDICES_PER_WORD)
+        .copied()
+        .unwrap_or(0);
+    let entry = (word >> ((index % PALETTE_INDICES_PER_WORD) * 8)) & 0xFF;
+    chunk
+        .palette
+        .get(entry as usize)
+        .copied()
+        .unwrap_or(VoxelData::AIR)
+}
+
+/// Expand a palettized chunk back into `voxel_count` 32-bit voxels
+pub fn depalettize_chunk(chunk: &PalettizedChunk, voxel_count: usize) -> Vec<VoxelData> {
+    (0..voxel_count)
+        .map(|index| palette_voxel(chunk, index))
+        .collect()
+}
+
+const DECODE_WORKGROUP_SIZE: u32 = 256;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct DecodeJob {
+    palette_slot: u32,
+    dense_slot: u32,
+    _padding: [u32; 2],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct DecodeParams {
+    job_count: u32,
+    _padding: [u32; 3],
+}
+
+/// Palette slot bookkeeping
+#[derive(Default)]
+pub(super) struct PaletteSlots {
+    pub slots: HashMap<ChunkPos, u32>,
+    pub free: Vec<u32>,
+    /// Released slots a recorded decode pass may still read; they move to
+    /// `draining` at the next chunk upload flush
+    pub retired: Vec<u32>,
+    /// Slots retired before the previous flush; they return to `free` at the
+    /// next one, once the frame that recorded their decode was submitted
+    pub draining: Vec<u32>,
+    pub fallback_uploads: u64,
+}
+
+/// GPU buffers, decode pipeline and slots of a WorldBuffer's palette tier
+pub(super) struct PaletteTier {
+    pub capacity: u32,
+    pub index_words: u64,
+    pub slots: std::sync::Mutex<PaletteSlots>,
+    decode_pipeline: wgpu::ComputePipeline,
+    decode_bind_group: wgpu::BindGroup,
+    job_buffer: wgpu::Buffer,
+    params_buffer: wgpu::Buffer,
+    gpu_allocations: Vec<GpuAllocationGuard>,
+}
+
+impl PaletteTier {
+    pub fn new(
+        device: &wgpu::Device,
+        owner: &GpuOwnerGuard,
+        voxel_buffer: &wgpu::Buffer,
+        index_buffer: &wgpu::Buffer,
+        palette_buffer: &wgpu::Buffer,
+        capacity: u32,
+        voxels_per_chunk: u32,
+    ) -> Self {
+        let shader = match crate::gpu::automation::create_gpu_shader(
+            device,
+            "palette_decode",
+            include_str!("../../shaders/compute/palette_decode.wgsl"),
+        ) {
+            Ok(shader) => shader,
+            Err(e) => {
+                log::error!("Failed to create palette decode shader: {}", e);
+                panic!("Failed to create palette decode shader: {}", e);
+            }
+        };
+
+        let bind_group_layout = crate::create_bind_group_layout!(
+            device,
+            "Palette Decode Bind Group Layout",
+            0 => buffer(storage),       // World voxels
+            1 => buffer(storage_read),  // Palette indices
+            2 => buffer(storage_read),  // Palettes
+            3 => buffer(storage_read),  // Jobs
+            4 => buffer(uniform)        // Params
+        );
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Palette Decode Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let decode_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Palette Decode Pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader.module,
+            entry_point: "decode_palette_chunks",
+        });
+
+        let (job_buffer, job_allocation) = create_tracked_buffer(
+            device,
+            owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Palette Decode Job Buffer"),
+                size: (PALETTE_DECODE_MAX_BATCH * std::mem::size_of::<DecodeJob>()) as u64,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
+
+        let (params_buffer, params_allocation) = create_tracked_buffer(
+            device,
+            owner,
+            &wgpu::BufferDescriptor {
+                label: Some("Palette Decode Params Buffer"),
+                size: std::mem::size_of::<DecodeParams>() as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
+
+        let decode_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Palette Decode Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: voxel_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: index_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: palette_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: job_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        Self {
+            capacity,
+            index_words: palette_index_words(voxels_per_chunk as usize) as u64,
+            slots: std::sync::Mutex::new(PaletteSlots {
+                free: (0..capacity).rev().collect(),
+                ..Default::default()
+            }),
+            decode_pipeline,
+            decode_bind_group,
+            job_buffer,
+            params_buffer,
+            gpu_allocations: vec![job_allocation, params_allocation],
+        }
+    }
+
+    pub fn lock_slots(&self) -> std::sync::MutexGuard<'_, PaletteSlots> {
+        match self.slots.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                log::warn!("[WORLD_BUFFER] palette slots mutex was poisoned, recovering");
+                poisoned.into_inner()
+            }
+        }
+    }
+
+    /// Byte offset of a palette slot in the index buffer
+    pub fn index_offset(&self, slot: u32) -> u64 {
+        slot as u64 * self.index_words * 4
+    }
+
+    /// Byte offset of a palette slot in the palette buffer
+    pub fn palette_offset(&self, slot: u32) -> u64 {
+        slot as u64 * PALETTE_MAX_ENTRIES as u64 * 4
+    }
+
+    /// Record a pass expanding palette slots into full slots.
+    /// The job list is written with `write_buffer`, so record at most one
+    /// decode per submitted command buffer.
+    pub fn encode_decode(
+        &self,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        jobs: &[PaletteDecodeSlots],
+        voxels_per_chunk: u32,
+    ) {
+        let jobs: Vec<DecodeJob> = jobs
+            .iter()
+            .take(PALETTE_DECODE_MAX_BATCH)
+            .map(|&(palette_slot, dense_slot)| DecodeJob {
+                palette_slot,
+                dense_slot,
+                _padding: [0; 2],
+            })
+            .collect();
+        if jobs.is_empty() {
+            return;
+        }
+        queue.write_buffer(&self.job_buffer, 0, bytemuck::cast_slice(&jobs));
+        let params = DecodeParams {
+            job_count: jobs.len() as u32,
+            _padding: [0; 3],
+        };
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Palette Decode Pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.decode_pipeline);
+        pass.set_bind_group(0, &self.decode_bind_group, &[]);
+        pass.dispatch_workgroups(
+            voxels_per_chunk.div_ceil(DECODE_WORKGROUP_SIZE),
+            jobs.len() as u32,
+            1,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_round_trip_and_overflow() {
+        let voxels: Vec<VoxelData> = (0..1000u32)
+            .map(|i| VoxelData::new((i % 7) as u16, (i % 3) as u8, 15, 0))
+            .collect();
+        let chunk = palettize_chunk(&voxels).expect("21 distinct voxels fit");
+        assert_eq!(chunk.palette.len(), 21);
+        assert_eq!(chunk.indices.len(), 250);
+        assert_eq!(depalettize_chunk(&chunk, voxels.len()), voxels);
+
+        // 256 distinct values still fit, 257 overflow to the 32-bit format
+        let full: Vec<VoxelData> = (0..256u16).map(|id| VoxelData::new(id, 0, 0, 0)).collect();
+        let chunk = palettize_chunk(&full).expect("256 distinct voxels fit");
+        assert_eq!(palette_voxel(&chunk, 255), VoxelData::new(255, 0, 0, 0));
+        let overflow: Vec<VoxelData> = (0..257u16).map(|id| VoxelData::new(id, 0, 0, 0)).collect();
+        assert!(palettize_chunk(&overflow).is_none());
+
+        // A 32³ chunk takes a quarter of the 32-bit size plus its palette
+        assert_eq!(palette_slot_bytes(32 * 32 * 32), 32 * 32 * 32 + 1024);
+    }
+}
@@ -1,9 +1,23 @@
-use crate::constants::core::{CHUNK_SIZE, MAX_WORLD_SIZE, VOXELS_PER_CHUNK};
+use crate::constants::core::MAX_WORLD_SIZE;
 use crate::constants::buffer_layouts::*;
-use crate::gpu::buffer_layouts::{bindings, calculations, layouts, usage};
+use crate::gpu::buffer_layouts::{bindings, layouts, usage};
 use crate::constants::gpu_limits;
+use crate::constants::voxel_palette::{DENSE_FALLBACK_DIVISOR, PALETTE_MAX_ENTRIES};
 use crate::morton::morton_encode;
-use crate::world::core::ChunkPos;
+use crate::world::core::{layout_chunk_bytes, ChunkLayout, ChunkPos};
+use crate::world::storage::{StorageError, StorageResult};
+use super::chunk_upload_ring_data::{ChunkUploadConfig, ChunkUploadRingData, ChunkUploadStats};
+use super::chunk_upload_ring_operations::{
+    cancel_ring_chunk_upload, create_chunk_upload_ring, flush_chunk_upload_ring,
+    queue_ring_chunk_upload, set_chunk_upload_bytes_per_frame,
+};
+use super::region_readback::{
+    request_region_readback, wait_region_readback, RegionVoxels, VoxelRegion,
+};
+use super::voxel_palette::{
+    depalettize_chunk, palette_slot_bytes, palettize_chunk, PaletteStorageStats, PaletteTier,
+    PalettizedChunk, VoxelStorageFormat,
+};
 use bytemuck::{Pod, Zeroable};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -17,7 +31,7 @@ use std::time::Instant;
 /// - Bits 24-27: Metadata (flags, rotation, etc)
 /// - Bits 28-31: Reserved
 #[repr(C)]
-#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
 pub struct VoxelData(pub u32);
 
 impl VoxelData {
@@ -53,6 +67,15 @@ impl VoxelData {
     }
 }
 
+/// Where per-voxel light levels are stored on the GPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightingStorageMode {
+    /// Light lives in the 4-bit fields of VoxelData (low memory, 16 levels)
+    Packed,
+    /// Light lives in a separate buffer with 8-bit block and 8-bit sky light per voxel
+    Dedicated,
+}
+
 /// Descriptor for creating a WorldBuffer
 pub struct WorldBufferDescriptor {
     /// View distance in chunks (determines buffer size)
@@ -61,6 +84,17 @@ pub struct WorldBufferDescriptor {
     pub enable_atomics: bool,
     /// Enable readback for debugging
     pub enable_readback: bool,
+    /// Light storage layout (use Packed to save memory on small GPUs)
+    pub lighting_mode: LightingStorageMode,
+    /// Keep uniform chunks (all air, all stone, ...) as descriptors instead of full slots
+    pub enable_sparse_chunks: bool,
+    /// Chunk dimensions of the world stored in this buffer
+    pub chunk_layout: ChunkLayout,
+    /// Staging ring and per-frame budget for batched chunk uploads
+    pub chunk_upload: ChunkUploadConfig,
+    /// 32-bit voxels everywhere, or 8-bit palette indices where a chunk
+    /// has at most 256 distinct voxels (about 4x the chunks per GB)
+    pub voxel_format: VoxelStorageFormat,
 }
 
 impl Default for WorldBufferDescriptor {
@@ -70,10 +104,77 @@ impl Default for WorldBufferDescriptor {
             view_distance: recommended_view_distance(256), // Conservative default for 256MB GPUs
             enable_atomics: true,
             enable_readback: cfg!(debug_assertions),
+            lighting_mode: LightingStorageMode::Dedicated,
+            enable_sparse_chunks: true,
+            chunk_layout: ChunkLayout::default(),
+            chunk_upload: ChunkUploadConfig::default(),
+            voxel_format: VoxelStorageFormat::Full,
         }
     }
 }
 
+/// Descriptor for a chunk whose voxels are all identical
+///
+/// Sparse chunks do not occupy a slot in the voxel buffer. They are promoted
+/// to a full slot on demand, e.g. right before the chunk is modified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SparseChunk {
+    /// Value of every voxel in the chunk
+    pub voxel: VoxelData,
+}
+
+type SparseChunkMap = HashMap<ChunkPos, SparseChunk>;
+
+/// Palettized chunk waiting for `flush_chunk_uploads`
+type PendingPaletteUpload = (ChunkPos, PalettizedChunk);
+
+/// Occupancy of the dense and sparse storage tiers
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SparseStorageStats {
+    /// Chunks holding a full voxel buffer slot
+    pub resident_chunks: u32,
+    /// Chunks stored as uniform descriptors
+    pub sparse_chunks: u32,
+    /// GPU memory not allocated thanks to the sparse tier
+    pub bytes_saved: u64,
+}
+
+/// Return the common voxel if every voxel in the chunk is identical
+pub fn detect_uniform_chunk(voxels: &[VoxelData]) -> Option<VoxelData> {
+    let first = *voxels.first()?;
+    voxels
+        .iter()
+        .all(|voxel| voxel.0 == first.0)
+        .then_some(first)
+}
+
+/// Pack block and sky light into the 16-bit dedicated lighting format
+/// Layout: bits 0-7 block light, bits 8-15 sky light
+#[inline]
+pub fn pack_voxel_light(block_light: u8, sky_light: u8) -> u16 {
+    (block_light as u16) | ((sky_light as u16) << 8)
+}
+
+/// Unpack the 16-bit dedicated lighting format into (block_light, sky_light)
+#[inline]
+pub fn unpack_voxel_light(packed: u16) -> (u8, u8) {
+    ((packed & 0xFF) as u8, (packed >> 8) as u8)
+}
+
+/// Write saved light (pack_voxel_light layout) into the 4-bit light fields
+/// of packed voxels; levels above 15 are clamped
+pub fn merge_voxel_light(voxels: &mut [VoxelData], light: &[u16]) {
+    for (voxel, packed) in voxels.iter_mut().zip(light) {
+        let (block_light, sky_light) = unpack_voxel_light(*packed);
+        *voxel = VoxelData::new(
+            voxel.block_id(),
+            block_light.min(15),
+            sky_light.min(15),
+            voxel.metadata(),
+        );
+    }
+}
+
 /// GPU-resident world buffer containing all voxel data
 pub struct WorldBuffer {
     device: Arc<wgpu::Device>,
@@ -84,6 +185,10 @@ pub struct WorldBuffer {
     /// Chunk metadata buffer (loaded/generated flags, timestamps, etc)
     metadata_buffer: wgpu::Buffer,
 
+    /// Dedicated light buffer (None when lighting is packed into VoxelData)
+    light_buffer: Option<wgpu::Buffer>,
+    lighting_mode: LightingStorageMode,
+
     /// Staging buffer for CPU->GPU uploads (if needed)
     staging_buffer: Option<wgpu::Buffer>,
 
@@ -103,21 +208,74 @@ pub struct WorldBuffer {
     /// Next available slot (simple round-robin allocation)
     /// Protected by same mutex as chunk_slots
     next_slot: Arc<Mutex<u32>>,
+
+    /// Uniform chunks stored as descriptors instead of slots
+    sparse_chunks: Arc<Mutex<SparseChunkMap>>,
+    enable_sparse_chunks: bool,
+
+    /// Chunk dimensions and the per-slot byte sizes derived from them
+    chunk_layout: ChunkLayout,
+    slot_size: u64,
+    light_slot_size: u64,
+
+    /// Batched uploads waiting for `flush_chunk_uploads`
+    upload_ring: ChunkUploadRingData,
+
+    /// Palette tier: 8-bit indices and per-chunk palettes (placeholder
+    /// buffers when the format is Full, so bind groups look the same)
+    voxel_format: VoxelStorageFormat,
+    palette_index_buffer: wgpu::Buffer,
+    palette_buffer: wgpu::Buffer,
+    palette: Option<PaletteTier>,
+    /// Batched palette uploads waiting for `flush_chunk_uploads`
+    pending_palette_uploads: Vec<PendingPaletteUpload>,
+
+    /// Voxel, metadata and palette buffers plus the optional light and staging ones
+    gpu_allocations: Vec<crate::memory::GpuAllocationGuard>,
+    gpu_owner: crate::memory::GpuOwnerGuard,
 }
 
 impl WorldBuffer {
+    /// Panics if the view distance exceeds GPU limits or `desc.chunk_layout`
+    /// differs from the layout installed with `set_gpu_chunk_layout`
     pub fn new(device: Arc<wgpu::Device>, desc: &WorldBufferDescriptor) -> Self {
         let view_distance = desc.view_distance;
+        let chunk_layout = desc.chunk_layout;
+        let slot_size = layout_chunk_bytes(chunk_layout, VOXEL_DATA_SIZE);
+        let light_slot_size = layout_chunk_bytes(chunk_layout, LIGHT_DATA_SIZE);
+
+        // Shaders get CHUNK_SIZE from the GPU registry; a different size there
+        // would index this buffer with the wrong stride
+        let shader_layout = crate::gpu::automation::gpu_chunk_layout();
+        if shader_layout != chunk_layout {
+            panic!(
+                "WorldBuffer: chunk size {} does not match shader chunk size {}; call set_gpu_chunk_layout first",
+                chunk_layout.size,
+                shader_layout.size
+            );
+        }
 
         // Calculate maximum chunks based on view distance
         // Use sphere approximation: chunks within view_distance radius
         // Conservative estimate: (2 * view_distance + 1)³ to ensure we have enough space
         let diameter = 2 * view_distance + 1;
-        let max_chunks = diameter * diameter * diameter;
+        let view_chunks = diameter * diameter * diameter;
+
+        // With the palette tier most chunks live there; full slots only take
+        // palette overflow and chunks being generated or edited
+        let max_chunks = match desc.voxel_format {
+            VoxelStorageFormat::Full => view_chunks,
+            VoxelStorageFormat::Palette8 => (view_chunks / DENSE_FALLBACK_DIVISOR).max(1),
+        };
 
-        // Safety check: prevent massive allocations
-        let memory_mb = (max_chunks as u64 * CHUNK_BUFFER_SLOT_SIZE) / (1024 * 1024);
-        let memory_bytes = max_chunks as u64 * CHUNK_BUFFER_SLOT_SIZE;
+        // Safety check: prevent massive allocations. The binding limit holds
+        // per buffer; the total also counts the dedicated lighting buffer
+        let memory_bytes = max_chunks as u64 * slot_size;
+        let light_bytes = match desc.lighting_mode {
+            LightingStorageMode::Dedicated => max_chunks as u64 * light_slot_size,
+            LightingStorageMode::Packed => 0,
+        };
+        let memory_mb = (memory_bytes + light_bytes) / (1024 * 1024);
 
         // GPU binding limit check
         if memory_bytes > gpu_limits::MAX_BUFFER_BINDING_SIZE {
@@ -125,7 +283,7 @@ impl WorldBuffer {
                        view_distance, memory_mb, gpu_limits::MAX_BUFFER_BINDING_SIZE / (1024 * 1024));
 
             // Calculate maximum safe view distance
-            let max_safe_chunks = gpu_limits::MAX_BUFFER_BINDING_SIZE / CHUNK_BUFFER_SLOT_SIZE;
+            let max_safe_chunks = gpu_limits::MAX_BUFFER_BINDING_SIZE / slot_size;
             let max_safe_diameter = (max_safe_chunks as f64).powf(1.0 / 3.0).floor() as u32;
             let max_safe_view_distance = (max_safe_diameter - 1) / 2;
 
@@ -145,8 +303,8 @@ impl WorldBuffer {
             memory_mb
         );
 
-        let total_voxels = max_chunks as u64 * VOXELS_PER_CHUNK as u64;
-        let buffer_size = max_chunks as u64 * CHUNK_BUFFER_SLOT_SIZE;
+        let total_voxels = max_chunks as u64 * chunk_layout.voxels_per_chunk as u64;
+        let buffer_size = max_chunks as u64 * slot_size;
 
         // Main voxel buffer
         let usage = if desc.enable_readback {
@@ -155,7 +313,16 @@ impl WorldBuffer {
             usage::STORAGE
         };
 
-        let voxel_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let gpu_owner = crate::memory::register_global_gpu_owner("world");
+        let mut gpu_allocations = Vec::new();
+        let mut create_buffer = |descriptor: &wgpu::BufferDescriptor| {
+            let (buffer, guard) =
+                crate::memory::create_tracked_buffer(&device, &gpu_owner, descriptor);
+            gpu_allocations.push(guard);
+            buffer
+        };
+
+        let voxel_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("World Voxel Buffer"),
             size: buffer_size,
             usage,
@@ -164,18 +331,36 @@ impl WorldBuffer {
 
         // Chunk metadata buffer
         let metadata_size = max_chunks as u64 * CHUNK_METADATA_SIZE;
-        let metadata_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let metadata_buffer = create_buffer(&wgpu::BufferDescriptor {
             label: Some("Chunk Metadata Buffer"),
             size: metadata_size,
             usage: usage::STORAGE,
             mapped_at_creation: false,
         });
 
+        // Dedicated lighting buffer, sized per slot like the voxel buffer
+        let light_buffer = match desc.lighting_mode {
+            LightingStorageMode::Dedicated => {
+                let light_size = max_chunks as u64 * light_slot_size;
+                log::info!(
+                    "WorldBuffer: allocating dedicated lighting buffer ({} MB)",
+                    light_size / (1024 * 1024)
+                );
+                Some(create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("World Light Buffer"),
+                    size: light_size,
+                    usage,
+                    mapped_at_creation: false,
+                }))
+            }
+            LightingStorageMode::Packed => None,
+        };
+
         // Optional staging buffer for uploads
         let staging_buffer = if desc.enable_readback {
-            Some(device.create_buffer(&wgpu::BufferDescriptor {
+            Some(create_buffer(&wgpu::BufferDescriptor {
                 label: Some("World Staging Buffer"),
-                size: CHUNK_BUFFER_SLOT_SIZE,
+                size: slot_size,
                 usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
                 mapped_at_creation: false,
             }))
@@ -183,45 +368,141 @@ impl WorldBuffer {
             None
         };
 
+        // Palette tier sized for the whole view volume
+        let palette_capacity = match desc.voxel_format {
+            VoxelStorageFormat::Full => 0,
+            VoxelStorageFormat::Palette8 => view_chunks,
+        };
+        let palette_bytes = palette_slot_bytes(chunk_layout.voxels_per_chunk as usize);
+        let palette_index_size =
+            palette_bytes - (PALETTE_MAX_ENTRIES * std::mem::size_of::<VoxelData>()) as u64;
+        if palette_capacity as u64 * palette_index_size > gpu_limits::MAX_BUFFER_BINDING_SIZE {
+            panic!(
+                "WorldBuffer: view_distance {} needs {} MB of palette indices (limit: {} MB)",
+                view_distance,
+                palette_capacity as u64 * palette_index_size / (1024 * 1024),
+                gpu_limits::MAX_BUFFER_BINDING_SIZE / (1024 * 1024)
+            );
+        }
+        if palette_capacity > 0 {
+            log::info!(
+                "WorldBuffer: palette tier of {} chunks ({} MB), {} full slots for overflow",
+                palette_capacity,
+                palette_capacity as u64 * palette_bytes / (1024 * 1024),
+                max_chunks
+            );
+        }
+        let palette_index_buffer = create_buffer(&wgpu::BufferDescriptor {
+            label: Some("World Palette Index Buffer"),
+            size: (palette_capacity as u64 * palette_index_size).max(4),
+            usage: usage::STORAGE_READ,
+            mapped_at_creation: false,
+        });
+        let palette_buffer = create_buffer(&wgpu::BufferDescriptor {
+            label: Some("World Palette Buffer"),
+            size: (palette_capacity as u64 * (palette_bytes - palette_index_size)).max(4),
+            usage: usage::STORAGE_READ,
+            mapped_at_creation: false,
+        });
+
         // Create bind group layout using centralized definitions
-        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: Some("World Buffer Bind Group Layout"),
-            entries: &[
-                layouts::storage_buffer_entry(
-                    bindings::world::VOXEL_BUFFER,
-                    false,
+        let mut layout_entries = vec![
+            layouts::storage_buffer_entry(
+                bindings::world::VOXEL_BUFFER,
+                false,
+                wgpu::ShaderStages::COMPUTE
+                    | wgpu::ShaderStages::VERTEX
+                    | wgpu::ShaderStages::FRAGMENT,
+            ),
+            layouts::storage_buffer_entry(
+                bindings::world::METADATA_BUFFER,
+                false,
+                wgpu::ShaderStages::COMPUTE,
+            ),
+        ];
+        if light_buffer.is_some() {
+            layout_entries.push(layouts::storage_buffer_entry(
+                bindings::world::LIGHT_BUFFER,
+                false,
+                wgpu::ShaderStages::COMPUTE
+                    | wgpu::ShaderStages::VERTEX
+                    | wgpu::ShaderStages::FRAGMENT,
+            ));
+        }
+        if palette_capacity > 0 {
+            for binding in [
+                bindings::world::PALETTE_INDEX_BUFFER,
+                bindings::world::PALETTE_BUFFER,
+            ] {
+                layout_entries.push(layouts::storage_buffer_entry(
+                    binding,
+                    true,
                     wgpu::ShaderStages::COMPUTE
                         | wgpu::ShaderStages::VERTEX
                         | wgpu::ShaderStages::FRAGMENT,
-                ),
-                layouts::storage_buffer_entry(
-                    bindings::world::METADATA_BUFFER,
-                    false,
-                    wgpu::ShaderStages::COMPUTE,
-                ),
-            ],
+                ));
+            }
+        }
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("World Buffer Bind Group Layout"),
+            entries: &layout_entries,
         });
 
         // Create bind group
+        let mut group_entries = vec![
+            wgpu::BindGroupEntry {
+                binding: bindings::world::VOXEL_BUFFER,
+                resource: voxel_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: bindings::world::METADATA_BUFFER,
+                resource: metadata_buffer.as_entire_binding(),
+            },
+        ];
+        if let Some(buffer) = &light_buffer {
+            group_entries.push(wgpu::BindGroupEntry {
+                binding: bindings::world::LIGHT_BUFFER,
+                resource: buffer.as_entire_binding(),
+            });
+        }
+        if palette_capacity > 0 {
+            group_entries.push(wgpu::BindGroupEntry {
+                binding: bindings::world::PALETTE_INDEX_BUFFER,
+                resource: palette_index_buffer.as_entire_binding(),
+            });
+            group_entries.push(wgpu::BindGroupEntry {
+                binding: bindings::world::PALETTE_BUFFER,
+                resource: palette_buffer.as_entire_binding(),
+            });
+        }
+
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("World Buffer Bind Group"),
             layout: &bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: bindings::world::VOXEL_BUFFER,
-                    resource: voxel_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: bindings::world::METADATA_BUFFER,
-                    resource: metadata_buffer.as_entire_binding(),
-                },
-            ],
+            entries: &group_entries,
+        });
+
+        let upload_ring =
+            create_chunk_upload_ring(&device, &gpu_owner, desc.chunk_upload, slot_size);
+        let palette = (palette_capacity > 0).then(|| {
+            PaletteTier::new(
+                &device,
+                &gpu_owner,
+                &voxel_buffer,
+                &palette_index_buffer,
+                &palette_buffer,
+                palette_capacity,
+                chunk_layout.voxels_per_chunk,
+            )
         });
 
         Self {
             device,
             voxel_buffer,
             metadata_buffer,
+            light_buffer,
+            lighting_mode: desc.lighting_mode,
             staging_buffer,
             bind_group,
             bind_group_layout,
@@ -230,6 +511,19 @@ impl WorldBuffer {
             total_voxels,
             chunk_slots: Arc::new(Mutex::new(HashMap::new())),
             next_slot: Arc::new(Mutex::new(0)),
+            sparse_chunks: Arc::new(Mutex::new(HashMap::new())),
+            enable_sparse_chunks: desc.enable_sparse_chunks,
+            chunk_layout,
+            slot_size,
+            light_slot_size,
+            upload_ring,
+            voxel_format: desc.voxel_format,
+            palette_index_buffer,
+            palette_buffer,
+            palette,
+            pending_palette_uploads: Vec::new(),
+            gpu_allocations,
+            gpu_owner,
         }
     }
 
@@ -248,11 +542,41 @@ impl WorldBuffer {
         &self.voxel_buffer
     }
 
+    /// Get the allocation tracker owner for readback staging buffers
+    pub fn gpu_owner(&self) -> &crate::memory::GpuOwnerGuard {
+        &self.gpu_owner
+    }
+
     /// Get the metadata buffer (for custom bind groups)
     pub fn metadata_buffer(&self) -> &wgpu::Buffer {
         &self.metadata_buffer
     }
 
+    /// Get the dedicated light buffer (None in packed lighting mode)
+    pub fn light_buffer(&self) -> Option<&wgpu::Buffer> {
+        self.light_buffer.as_ref()
+    }
+
+    /// Get the light storage layout this buffer was created with
+    pub fn lighting_mode(&self) -> LightingStorageMode {
+        self.lighting_mode
+    }
+
+    /// Get the voxel format this buffer was created with
+    pub fn voxel_format(&self) -> VoxelStorageFormat {
+        self.voxel_format
+    }
+
+    /// Get the palette index buffer (a 4-byte placeholder in Full format)
+    pub fn palette_index_buffer(&self) -> &wgpu::Buffer {
+        &self.palette_index_buffer
+    }
+
+    /// Get the per-chunk palette buffer (a 4-byte placeholder in Full format)
+    pub fn palette_buffer(&self) -> &wgpu::Buffer {
+        &self.palette_buffer
+    }
+
     /// Get the view distance
     pub fn view_distance(&self) -> u32 {
         self.view_distance
@@ -265,7 +589,23 @@ impl WorldBuffer {
 
     /// Get or allocate a buffer slot for a chunk position
     /// CRITICAL: Prevents slot collisions that cause GPU readback failures
-    pub fn get_chunk_slot(&mut self, chunk_pos: ChunkPos) -> u32 {
+    ///
+    /// A sparse chunk loses its descriptor here since the caller is about to
+    /// overwrite the slot; use `promote_sparse_chunk` to keep its contents.
+    pub fn get_chunk_slot(&self, chunk_pos: ChunkPos) -> u32 {
+        if self.lock_sparse_chunks().remove(&chunk_pos).is_some() {
+            log::debug!(
+                "[WORLD_BUFFER] Dropping sparse descriptor for chunk {:?} (slot requested)",
+                chunk_pos
+            );
+        }
+        if self.remove_palette_chunk(chunk_pos) {
+            log::debug!(
+                "[WORLD_BUFFER] Retiring palette slot of chunk {:?} (slot requested)",
+                chunk_pos
+            );
+        }
+
         log::debug!("[WORLD_BUFFER::get_chunk_slot] Called for chunk {:?}", chunk_pos);
         // Lock both mutexes to ensure thread safety
         let mut chunk_slots = match self.chunk_slots.lock() {
@@ -341,30 +681,365 @@ impl WorldBuffer {
         }
     }
 
+    /// Look up the slot of an already-resident chunk without allocating one
+    pub fn existing_chunk_slot(&self, chunk_pos: ChunkPos) -> Option<u32> {
+        match self.chunk_slots.lock() {
+            Ok(slots) => slots.get(&chunk_pos).copied(),
+            Err(poisoned) => poisoned.into_inner().get(&chunk_pos).copied(),
+        }
+    }
+
+    fn lock_sparse_chunks(&self) -> std::sync::MutexGuard<'_, SparseChunkMap> {
+        match self.sparse_chunks.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                log::warn!("[WORLD_BUFFER] sparse_chunks mutex was poisoned, recovering");
+                poisoned.into_inner()
+            }
+        }
+    }
+
+    /// Whether uniform chunks are kept in the sparse tier
+    pub fn sparse_chunks_enabled(&self) -> bool {
+        self.enable_sparse_chunks
+    }
+
+    /// Store a uniform chunk as a descriptor, releasing its slot if it had one.
+    /// Returns false (and stores nothing) when the sparse tier is disabled.
+    pub fn store_sparse_chunk(&self, chunk_pos: ChunkPos, voxel: VoxelData) -> bool {
+        if !self.enable_sparse_chunks {
+            return false;
+        }
+
+        let released = match self.chunk_slots.lock() {
+            Ok(mut slots) => slots.remove(&chunk_pos),
+            Err(poisoned) => poisoned.into_inner().remove(&chunk_pos),
+        };
+        if let Some(slot) = released {
+            log::debug!(
+                "[WORLD_BUFFER] Released slot {} of uniform chunk {:?}",
+                slot,
+                chunk_pos
+            );
+        }
+        self.remove_palette_chunk(chunk_pos);
+
+        self.lock_sparse_chunks().insert(chunk_pos, SparseChunk { voxel });
+        true
+    }
+
+    /// Get the descriptor of a sparse chunk
+    pub fn sparse_chunk(&self, chunk_pos: ChunkPos) -> Option<SparseChunk> {
+        self.lock_sparse_chunks().get(&chunk_pos).copied()
+    }
+
+    /// Check whether a chunk lives in the sparse tier
+    pub fn is_chunk_sparse(&self, chunk_pos: ChunkPos) -> bool {
+        self.lock_sparse_chunks().contains_key(&chunk_pos)
+    }
+
+    /// Forget a sparse chunk (e.g. when it is unloaded)
+    pub fn remove_sparse_chunk(&self, chunk_pos: ChunkPos) -> bool {
+        self.lock_sparse_chunks().remove(&chunk_pos).is_some()
+    }
+
+    /// Move a sparse chunk into a full slot, filling it with its uniform voxel.
+    /// Returns the slot, or None if the chunk was not sparse.
+    ///
+    /// The fill is queued with `write_buffer`, so it lands before any command
+    /// buffer submitted afterwards (e.g. a modification pass).
+    pub fn promote_sparse_chunk(&self, queue: &wgpu::Queue, chunk_pos: ChunkPos) -> Option<u32> {
+        let sparse = self.sparse_chunk(chunk_pos)?;
+        let slot = self.get_chunk_slot(chunk_pos);
+
+        let voxels = vec![sparse.voxel; self.chunk_layout.voxels_per_chunk as usize];
+        queue.write_buffer(
+            &self.voxel_buffer,
+            self.slot_offset(slot),
+            bytemuck::cast_slice(&voxels),
+        );
+
+        if let Some(light_buffer) = &self.light_buffer {
+            let light =
+                pack_voxel_light(sparse.voxel.light_level(), sparse.voxel.sky_light_level());
+            let light_data = vec![light; self.chunk_layout.voxels_per_chunk as usize];
+            queue.write_buffer(
+                light_buffer,
+                self.light_slot_offset(slot),
+                bytemuck::cast_slice(&light_data),
+            );
+        }
+
+        log::debug!(
+            "[WORLD_BUFFER] Promoted sparse chunk {:?} (block {}) to slot {}",
+            chunk_pos,
+            sparse.voxel.block_id(),
+            slot
+        );
+        Some(slot)
+    }
+
+    /// Palette slot of a palettized chunk
+    pub fn palette_chunk_slot(&self, chunk_pos: ChunkPos) -> Option<u32> {
+        let palette = self.palette.as_ref()?;
+        palette.lock_slots().slots.get(&chunk_pos).copied()
+    }
+
+    /// Check whether a chunk lives in the palette tier
+    pub fn is_chunk_palettized(&self, chunk_pos: ChunkPos) -> bool {
+        self.palette_chunk_slot(chunk_pos).is_some()
+    }
+
+    /// Release the palette slot of a chunk. The slot is reused only after two
+    /// `flush_chunk_uploads` calls, so a decode recorded before still reads it.
+    pub fn remove_palette_chunk(&self, chunk_pos: ChunkPos) -> bool {
+        let Some(palette) = &self.palette else {
+            return false;
+        };
+        let mut slots = palette.lock_slots();
+        match slots.slots.remove(&chunk_pos) {
+            Some(slot) => {
+                slots.retired.push(slot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget an unloaded chunk: its full slot, sparse descriptor and
+    /// palette slot become free for other chunks
+    pub fn release_chunk_slot(&mut self, chunk_pos: ChunkPos) -> bool {
+        self.cancel_queued_upload(chunk_pos);
+        let sparse = self.lock_sparse_chunks().remove(&chunk_pos).is_some();
+        let palette = self.remove_palette_chunk(chunk_pos);
+        let full = match self.chunk_slots.lock() {
+            Ok(mut slots) => slots.remove(&chunk_pos).is_some(),
+            Err(poisoned) => poisoned.into_inner().remove(&chunk_pos).is_some(),
+        };
+        sparse || palette || full
+    }
+
+    /// Palette slot for a chunk about to be written, releasing its full slot
+    /// and sparse descriptor. None when the palette tier is off or full.
+    fn allocate_palette_slot(&self, chunk_pos: ChunkPos) -> Option<u32> {
+        let palette = self.palette.as_ref()?;
+        let slot = {
+            let mut slots = palette.lock_slots();
+            match slots.slots.get(&chunk_pos) {
+                Some(slot) => *slot,
+                None => {
+                    let Some(slot) = slots.free.pop() else {
+                        slots.fallback_uploads += 1;
+                        return None;
+                    };
+                    slots.slots.insert(chunk_pos, slot);
+                    slot
+                }
+            }
+        };
+
+        self.lock_sparse_chunks().remove(&chunk_pos);
+        match self.chunk_slots.lock() {
+            Ok(mut chunk_slots) => chunk_slots.remove(&chunk_pos),
+            Err(poisoned) => poisoned.into_inner().remove(&chunk_pos),
+        };
+        Some(slot)
+    }
+
+    /// Palette form of a chunk for the palette tier, counting overflow
+    fn palettize_for_tier(&self, voxels: &[VoxelData]) -> Option<PalettizedChunk> {
+        let palette = self.palette.as_ref()?;
+        let chunk = palettize_chunk(voxels);
+        if chunk.is_none() {
+            palette.lock_slots().fallback_uploads += 1;
+        }
+        chunk
+    }
+
+    fn write_palette_chunk(&self, queue: &wgpu::Queue, slot: u32, chunk: &PalettizedChunk) {
+        let Some(palette) = &self.palette else {
+            return;
+        };
+        queue.write_buffer(
+            &self.palette_index_buffer,
+            palette.index_offset(slot),
+            bytemuck::cast_slice(&chunk.indices),
+        );
+        queue.write_buffer(
+            &self.palette_buffer,
+            palette.palette_offset(slot),
+            bytemuck::cast_slice(&chunk.palette),
+        );
+    }
+
+    /// Store a chunk in the palette tier. Returns false (and stores nothing)
+    /// when the format is Full, the chunk has more than 256 distinct voxels
+    /// or the palette tier is full; the caller then uses a full slot.
+    pub fn store_palette_chunk(
+        &self,
+        queue: &wgpu::Queue,
+        chunk_pos: ChunkPos,
+        voxels: &[VoxelData],
+    ) -> bool {
+        let Some(chunk) = self.palettize_for_tier(voxels) else {
+            return false;
+        };
+        let Some(slot) = self.allocate_palette_slot(chunk_pos) else {
+            return false;
+        };
+        self.write_palette_chunk(queue, slot, &chunk);
+        log::debug!(
+            "[WORLD_BUFFER] Chunk {:?} stored in palette slot {} ({} entries)",
+            chunk_pos,
+            slot,
+            chunk.palette.len()
+        );
+        true
+    }
+
+    /// Move palettized chunks into full slots, decoding them on the GPU into
+    /// `encoder` before anything recorded after (e.g. a modification pass).
+    /// Returns the chunks promoted; chunks beyond `PALETTE_DECODE_MAX_BATCH`
+    /// wait for the next call.
+    pub fn promote_palette_chunks(
+        &self,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        chunk_positions: &[ChunkPos],
+    ) -> Vec<ChunkPos> {
+        let Some(palette) = &self.palette else {
+            return Vec::new();
+        };
+
+        let mut promoted = Vec::new();
+        let mut jobs = Vec::new();
+        for &chunk_pos in chunk_positions {
+            if jobs.len() == crate::constants::voxel_palette::PALETTE_DECODE_MAX_BATCH {
+                break;
+            }
+            let Some(palette_slot) = self.palette_chunk_slot(chunk_pos) else {
+                continue;
+            };
+            // get_chunk_slot retires the palette slot, keeping its data intact
+            let dense_slot = self.get_chunk_slot(chunk_pos);
+            jobs.push((palette_slot, dense_slot));
+            promoted.push(chunk_pos);
+        }
+
+        palette.encode_decode(queue, encoder, &jobs, self.chunk_layout.voxels_per_chunk);
+        if !promoted.is_empty() {
+            log::debug!(
+                "[WORLD_BUFFER] Promoted {} palette chunks to full slots",
+                promoted.len()
+            );
+        }
+        promoted
+    }
+
+    /// Occupancy of the palette tier
+    pub fn palette_storage_stats(&self) -> PaletteStorageStats {
+        let Some(palette) = &self.palette else {
+            return PaletteStorageStats::default();
+        };
+        let slots = palette.lock_slots();
+        let palette_chunks = slots.slots.len() as u32;
+        let palette_bytes = palette_slot_bytes(self.chunk_layout.voxels_per_chunk as usize);
+        PaletteStorageStats {
+            palette_chunks,
+            palette_capacity: palette.capacity,
+            fallback_uploads: slots.fallback_uploads,
+            bytes_saved: palette_chunks as u64 * self.slot_size.saturating_sub(palette_bytes),
+        }
+    }
+
+    /// Occupancy of the dense and sparse tiers
+    pub fn sparse_storage_stats(&self) -> SparseStorageStats {
+        let resident_chunks = match self.chunk_slots.lock() {
+            Ok(slots) => slots.len(),
+            Err(poisoned) => poisoned.into_inner().len(),
+        } as u32;
+        let sparse_chunks = self.lock_sparse_chunks().len() as u32;
+        let light_slot_size = if self.light_buffer.is_some() {
+            self.light_slot_size
+        } else {
+            0
+        };
+
+        SparseStorageStats {
+            resident_chunks,
+            sparse_chunks,
+            bytes_saved: sparse_chunks as u64 * (self.slot_size + light_slot_size),
+        }
+    }
+
     /// Calculate buffer offset for a chunk slot
     pub fn slot_offset(&self, slot: u32) -> u64 {
-        calculations::chunk_slot_offset(slot)
+        slot as u64 * self.slot_size
+    }
+
+    /// Calculate offset for a chunk slot in the dedicated lighting buffer
+    pub fn light_slot_offset(&self, slot: u32) -> u64 {
+        slot as u64 * self.light_slot_size
+    }
+
+    /// Chunk dimensions of the world stored in this buffer
+    pub fn chunk_layout(&self) -> ChunkLayout {
+        self.chunk_layout
+    }
+
+    /// Bytes occupied by one chunk slot in the voxel buffer
+    pub fn slot_size(&self) -> u64 {
+        self.slot_size
     }
 
     /// Upload a single chunk from CPU (migration path)
     pub fn upload_chunk(&mut self, queue: &wgpu::Queue, chunk_pos: ChunkPos, voxels: &[VoxelData]) {
+        self.write_chunk(queue, chunk_pos, voxels, true);
+    }
+
+    /// Upload a chunk, into the palette tier if `allow_palette` and it fits
+    fn write_chunk(
+        &mut self,
+        queue: &wgpu::Queue,
+        chunk_pos: ChunkPos,
+        voxels: &[VoxelData],
+        allow_palette: bool,
+    ) {
         let start = Instant::now();
 
         assert_eq!(
             voxels.len(),
-            VOXELS_PER_CHUNK as usize,
+            self.chunk_layout.voxels_per_chunk as usize,
             "[WORLD_BUFFER] Invalid voxel count for chunk {:?}: expected {}, got {}",
             chunk_pos,
-            VOXELS_PER_CHUNK,
+            self.chunk_layout.voxels_per_chunk,
             voxels.len()
         );
 
-        log::info!(
+        crate::debug_rate_limited!(
             "[WORLD_BUFFER] Uploading chunk {:?} to GPU ({} voxels)",
             chunk_pos,
             voxels.len()
         );
 
+        // A direct upload supersedes any batched one still waiting
+        self.cancel_queued_upload(chunk_pos);
+
+        if let Some(voxel) = detect_uniform_chunk(voxels) {
+            if self.store_sparse_chunk(chunk_pos, voxel) {
+                log::debug!(
+                    "[WORLD_BUFFER] Chunk {:?} is uniform (block {}), stored as sparse descriptor",
+                    chunk_pos,
+                    voxel.block_id()
+                );
+                return;
+            }
+        }
+
+        if allow_palette && self.store_palette_chunk(queue, chunk_pos, voxels) {
+            return;
+        }
+
         // Count non-air voxels for diagnostics
         let non_air_count = voxels.iter().filter(|v| v.block_id() != 0).count();
         let fill_percentage = (non_air_count as f64 / voxels.len() as f64) * 100.0;
@@ -398,7 +1073,7 @@ impl WorldBuffer {
             0.0
         };
 
-        log::info!(
+        crate::info_rate_limited!(
             "[WORLD_BUFFER] Chunk {:?} upload completed: {:.2}ms total, {:.1} MB/s bandwidth",
             chunk_pos,
             total_duration.as_secs_f64() * 1000.0,
@@ -406,15 +1081,191 @@ impl WorldBuffer {
         );
     }
 
+    /// Queue a chunk for the next `flush_chunk_uploads` instead of writing it
+    /// immediately; bulk loads go out in large batched copies
+    pub fn queue_chunk_upload(&mut self, chunk_pos: ChunkPos, voxels: &[VoxelData]) {
+        assert_eq!(
+            voxels.len(),
+            self.chunk_layout.voxels_per_chunk as usize,
+            "[WORLD_BUFFER] Invalid voxel count for chunk {:?}: expected {}, got {}",
+            chunk_pos,
+            self.chunk_layout.voxels_per_chunk,
+            voxels.len()
+        );
+
+        if let Some(voxel) = detect_uniform_chunk(voxels) {
+            if self.store_sparse_chunk(chunk_pos, voxel) {
+                self.cancel_queued_upload(chunk_pos);
+                return;
+            }
+        }
+
+        // The palette slot is taken now; its contents are written at the flush
+        if let Some(chunk) = self.palettize_for_tier(voxels) {
+            if self.allocate_palette_slot(chunk_pos).is_some() {
+                self.cancel_queued_upload(chunk_pos);
+                self.pending_palette_uploads.push((chunk_pos, chunk));
+                return;
+            }
+        }
+
+        self.pending_palette_uploads.retain(|(pos, _)| *pos != chunk_pos);
+        let slot = self.get_chunk_slot(chunk_pos);
+        let offset = self.slot_offset(slot);
+        queue_ring_chunk_upload(
+            &mut self.upload_ring,
+            chunk_pos,
+            offset,
+            bytemuck::cast_slice(voxels).to_vec(),
+        );
+    }
+
+    /// Drop a batched upload of a chunk that is about to be overwritten
+    fn cancel_queued_upload(&mut self, chunk_pos: ChunkPos) {
+        cancel_ring_chunk_upload(&mut self.upload_ring, chunk_pos);
+        self.pending_palette_uploads.retain(|(pos, _)| *pos != chunk_pos);
+    }
+
+    /// Copy queued chunks into their slots, up to the per-frame budget.
+    /// Call once per submitted encoder; returns the chunks now on the GPU.
+    pub fn flush_chunk_uploads(
+        &mut self,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Vec<ChunkPos> {
+        let mut uploaded =
+            flush_chunk_upload_ring(&mut self.upload_ring, queue, encoder, &self.voxel_buffer);
+
+        if let Some(palette) = &self.palette {
+            let mut slots = palette.lock_slots();
+            let retired = std::mem::take(&mut slots.retired);
+            let drained = std::mem::replace(&mut slots.draining, retired);
+            slots.free.extend(drained);
+        }
+        for (chunk_pos, chunk) in std::mem::take(&mut self.pending_palette_uploads) {
+            // Skipped when the chunk moved to another tier since it was queued
+            if let Some(slot) = self.palette_chunk_slot(chunk_pos) {
+                self.write_palette_chunk(queue, slot, &chunk);
+                uploaded.push(chunk_pos);
+            }
+        }
+
+        if !uploaded.is_empty() {
+            let stats = self.upload_ring.stats;
+            crate::debug_rate_limited!(
+                "[WORLD_BUFFER] Flushed {} chunks ({} KB) in {} copies, {} pending, {:.1} MB/s",
+                stats.last_flush_chunks,
+                stats.last_flush_bytes / 1024,
+                stats.last_flush_copies,
+                stats.pending_chunks,
+                stats.bandwidth_mb_per_sec
+            );
+        }
+        uploaded
+    }
+
+    /// True while batched uploads are waiting for a flush
+    pub fn has_pending_chunk_uploads(&self) -> bool {
+        !self.upload_ring.pending.is_empty() || !self.pending_palette_uploads.is_empty()
+    }
+
+    /// Change how many bytes of chunk data are flushed per frame
+    pub fn set_chunk_upload_budget(&mut self, bytes_per_frame: u64) {
+        set_chunk_upload_bytes_per_frame(&mut self.upload_ring, bytes_per_frame);
+    }
+
+    /// Upload counters and bandwidth for the profiler
+    pub fn chunk_upload_stats(&self) -> ChunkUploadStats {
+        self.upload_ring.stats
+    }
+
+    /// Upload light levels for a single chunk into the dedicated lighting buffer
+    /// Values use the pack_voxel_light layout, one u16 per voxel
+    pub fn upload_chunk_light(
+        &mut self,
+        queue: &wgpu::Queue,
+        chunk_pos: ChunkPos,
+        light: &[u16],
+    ) -> Result<(), StorageError> {
+        if light.len() != self.chunk_layout.voxels_per_chunk as usize {
+            return Err(StorageError::InvalidLightData {
+                expected: self.chunk_layout.voxels_per_chunk as usize,
+                actual: light.len(),
+            });
+        }
+
+        // Palette chunks have no light slot; upload them with their light
+        if self.is_chunk_palettized(chunk_pos) {
+            return Err(StorageError::BackendMismatch {
+                required: "full voxel slot".to_string(),
+                actual: "palette slot".to_string(),
+            });
+        }
+
+        // Light for a sparse chunk needs its voxels resident too
+        let slot = self
+            .promote_sparse_chunk(queue, chunk_pos)
+            .unwrap_or_else(|| self.get_chunk_slot(chunk_pos));
+        let light_buffer = self.light_buffer.as_ref().ok_or_else(|| StorageError::BackendMismatch {
+            required: "dedicated lighting buffer".to_string(),
+            actual: "packed lighting".to_string(),
+        })?;
+
+        queue.write_buffer(
+            light_buffer,
+            self.light_slot_offset(slot),
+            bytemuck::cast_slice(light),
+        );
+
+        log::debug!(
+            "[WORLD_BUFFER] Uploaded light data for chunk {:?} to slot {}",
+            chunk_pos,
+            slot
+        );
+        Ok(())
+    }
+
+    /// Upload a chunk together with previously baked light, so it does not
+    /// need a lighting pass. Light goes to whichever storage the mode uses.
+    pub fn upload_chunk_with_light(
+        &mut self,
+        queue: &wgpu::Queue,
+        chunk_pos: ChunkPos,
+        voxels: &[VoxelData],
+        light: &[u16],
+    ) -> Result<(), StorageError> {
+        if light.len() != self.chunk_layout.voxels_per_chunk as usize {
+            return Err(StorageError::InvalidLightData {
+                expected: self.chunk_layout.voxels_per_chunk as usize,
+                actual: light.len(),
+            });
+        }
+
+        match self.lighting_mode {
+            LightingStorageMode::Dedicated => {
+                // Light needs a full slot next to the voxels
+                self.write_chunk(queue, chunk_pos, voxels, false);
+                self.upload_chunk_light(queue, chunk_pos, light)
+            }
+            LightingStorageMode::Packed => {
+                let mut lit = voxels.to_vec();
+                merge_voxel_light(&mut lit, light);
+                self.upload_chunk(queue, chunk_pos, &lit);
+                Ok(())
+            }
+        }
+    }
+
     /// Clear a chunk to air
     pub fn clear_chunk(&mut self, encoder: &mut wgpu::CommandEncoder, chunk_pos: ChunkPos) {
         let start = Instant::now();
 
         log::debug!("[WORLD_BUFFER] Clearing chunk {:?} to air", chunk_pos);
+        self.cancel_queued_upload(chunk_pos);
 
         let slot = self.get_chunk_slot(chunk_pos);
         let offset = self.slot_offset(slot);
-        let size = CHUNK_BUFFER_SLOT_SIZE;
+        let size = self.slot_size;
 
         log::debug!(
             "[WORLD_BUFFER] Clear operation: slot {}, offset {} bytes, size {} bytes",
@@ -425,6 +1276,15 @@ impl WorldBuffer {
 
         encoder.clear_buffer(&self.voxel_buffer, offset, Some(size));
 
+        // Stale light from the previous occupant of this slot must not leak into the new chunk
+        if let Some(light_buffer) = &self.light_buffer {
+            encoder.clear_buffer(
+                light_buffer,
+                self.light_slot_offset(slot),
+                Some(self.light_slot_size),
+            );
+        }
+
         let duration = start.elapsed();
         log::debug!(
             "[WORLD_BUFFER] Chunk {:?} clear operation queued in {:.1}μs",
@@ -458,6 +1318,15 @@ impl WorldBuffer {
             chunk_pos
         );
 
+        // Sparse chunks are answered from their descriptor without touching the GPU
+        if let Some(sparse) = self.sparse_chunk(chunk_pos) {
+            return Ok(vec![sparse.voxel; self.chunk_layout.voxels_per_chunk as usize]);
+        }
+
+        if let Some(slot) = self.palette_chunk_slot(chunk_pos) {
+            return Ok(self.read_palette_chunk(device, queue, slot)?);
+        }
+
         // Check staging buffer exists
         if self.staging_buffer.is_none() {
             let error_msg = "WorldBuffer readback not enabled - missing staging buffer";
@@ -468,7 +1337,7 @@ impl WorldBuffer {
         // Get chunk slot and calculate source offset
         let slot = self.get_chunk_slot(chunk_pos);
         let source_offset = self.slot_offset(slot);
-        let chunk_size_bytes = VOXELS_PER_CHUNK as u64 * std::mem::size_of::<VoxelData>() as u64;
+        let chunk_size_bytes = self.slot_size;
 
         // Get staging buffer reference after mutable operations
         let staging_buffer = match self.staging_buffer.as_ref() {
@@ -592,6 +1461,194 @@ impl WorldBuffer {
         Ok(result)
     }
 
+    /// Read a palettized chunk back and expand it to 32-bit voxels
+    fn read_palette_chunk(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        slot: u32,
+    ) -> StorageResult<Vec<VoxelData>> {
+        let Some(palette) = &self.palette else {
+            return Err(StorageError::ReadbackFailed {
+                message: "WorldBuffer has no palette tier".to_string(),
+            });
+        };
+        if self.staging_buffer.is_none() {
+            return Err(StorageError::ReadbackFailed {
+                message: "WorldBuffer readback not enabled - missing staging buffer".to_string(),
+            });
+        }
+
+        let index_bytes = palette.index_words * 4;
+        let palette_bytes = (PALETTE_MAX_ENTRIES * std::mem::size_of::<VoxelData>()) as u64;
+        let (staging, _staging_allocation) = crate::memory::create_tracked_buffer(
+            device,
+            &self.gpu_owner,
+            &wgpu::BufferDescriptor {
+                label: Some("WorldBuffer Palette Readback"),
+                size: index_bytes + palette_bytes,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            },
+        );
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("WorldBuffer Palette Readback"),
+        });
+        encoder.copy_buffer_to_buffer(
+            &self.palette_index_buffer,
+            palette.index_offset(slot),
+            &staging,
+            0,
+            index_bytes,
+        );
+        encoder.copy_buffer_to_buffer(
+            &self.palette_buffer,
+            palette.palette_offset(slot),
+            &staging,
+            index_bytes,
+            palette_bytes,
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let buffer_slice = staging.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|_| StorageError::ReadbackFailed {
+                message: "Failed to receive mapping result".to_string(),
+            })?
+            .map_err(|e| StorageError::ReadbackFailed {
+                message: format!("Buffer mapping failed: {:?}", e),
+            })?;
+
+        let mapped = buffer_slice.get_mapped_range();
+        let (indices, entries) = mapped.split_at(index_bytes as usize);
+        let chunk = PalettizedChunk {
+            palette: bytemuck::cast_slice(entries).to_vec(),
+            indices: bytemuck::cast_slice(indices).to_vec(),
+        };
+        drop(mapped);
+        staging.unmap();
+
+        Ok(depalettize_chunk(
+            &chunk,
+            self.chunk_layout.voxels_per_chunk as usize,
+        ))
+    }
+
+    /// Read a chunk's light levels (pack_voxel_light layout) for baking into a save
+    /// Blocks like read_chunk; needs readback enabled
+    pub fn read_chunk_light(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        chunk_pos: ChunkPos,
+    ) -> StorageResult<Vec<u16>> {
+        let dedicated = self.light_buffer.is_some() && self.sparse_chunk(chunk_pos).is_none();
+        if !dedicated {
+            // Packed light (and sparse chunks) come back with the voxels
+            let voxels = self
+                .read_chunk(device, queue, chunk_pos)
+                .map_err(|e| StorageError::ReadbackFailed {
+                    message: e.to_string(),
+                })?;
+            return Ok(voxels
+                .iter()
+                .map(|v| pack_voxel_light(v.light_level(), v.sky_light_level()))
+                .collect());
+        }
+
+        let slot = self.get_chunk_slot(chunk_pos);
+        let (Some(light_buffer), Some(staging_buffer)) =
+            (self.light_buffer.as_ref(), self.staging_buffer.as_ref())
+        else {
+            return Err(StorageError::ReadbackFailed {
+                message: "WorldBuffer readback not enabled - missing staging buffer".to_string(),
+            });
+        };
+
+        // Light slots are half the size of voxel slots, so the staging buffer fits one
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("WorldBuffer Light Readback"),
+        });
+        encoder.copy_buffer_to_buffer(
+            light_buffer,
+            self.light_slot_offset(slot),
+            staging_buffer,
+            0,
+            self.light_slot_size,
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let buffer_slice = staging_buffer.slice(..self.light_slot_size);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|_| StorageError::ReadbackFailed {
+                message: "Failed to receive mapping result".to_string(),
+            })?
+            .map_err(|e| StorageError::ReadbackFailed {
+                message: format!("Buffer mapping failed: {:?}", e),
+            })?;
+
+        let mapped_data = buffer_slice.get_mapped_range();
+        let light: Vec<u16> = bytemuck::cast_slice(&mapped_data)
+            [..self.chunk_layout.voxels_per_chunk as usize]
+            .to_vec();
+        drop(mapped_data);
+        staging_buffer.unmap();
+
+        log::debug!(
+            "[WORLD_BUFFER] Read light data for chunk {:?} from slot {}",
+            chunk_pos,
+            slot
+        );
+        Ok(light)
+    }
+
+    /// Read only the voxels between two local corners of a chunk (inclusive)
+    /// Copies just the needed rows instead of the full chunk; blocking
+    pub fn read_voxels(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        chunk_pos: ChunkPos,
+        local_min: [u32; 3],
+        local_max: [u32; 3],
+    ) -> StorageResult<Vec<VoxelData>> {
+        let region = VoxelRegion {
+            chunk_pos,
+            min: local_min,
+            max: local_max,
+        };
+        let mut reads = self.read_voxel_regions(device, queue, &[region])?;
+        Ok(reads.pop().map(|read| read.voxels).unwrap_or_default())
+    }
+
+    /// Read several regions with one staging buffer and one submission; blocking
+    pub fn read_voxel_regions(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        regions: &[VoxelRegion],
+    ) -> StorageResult<Vec<RegionVoxels>> {
+        let batch = request_region_readback(self, device, queue, regions);
+        log::debug!(
+            "[WORLD_BUFFER] Region readback of {} regions: {} bytes",
+            regions.len(),
+            batch.byte_size
+        );
+        wait_region_readback(batch, device)
+    }
+
     /// Read chunk data from GPU buffer (blocking)
     /// This is an alias for read_chunk for backward compatibility
     pub fn read_chunk_blocking(
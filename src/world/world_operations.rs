@@ -5,10 +5,17 @@
 //!
 //! This is what GAMES call directly to interact with the world.
 
-use super::core::{BlockId, ChunkPos, Ray, RaycastHit, VoxelPos, BlockFace};
-use super::data_types::WorldData;
+use super::core::{
+    chunk_layout_for_size, layout_voxel_index, chunk_origin_voxel, column_local_index, column_to_chunk,
+    voxel_chunk_index, voxel_to_chunk_pos, voxel_to_local, world_point_to_voxel, BlockFace,
+    BlockId, ChunkLayout, ChunkPos, Ray, RaycastHit, VoxelPos,
+};
+use super::data_types::{ChunkData, ColumnHeights, RegionBlocks, RegionPasteStats, WorldData};
 use super::error::WorldError;
+use super::storage::TempChunk;
+use crate::constants::terrain::NO_SURFACE_HEIGHT;
 use cgmath::{InnerSpace, Point3};
+use std::collections::HashSet;
 
 // ============================================================================
 // BLOCK OPERATIONS
@@ -24,23 +31,12 @@ use cgmath::{InnerSpace, Point3};
 /// # Returns
 /// BlockId at that position, or AIR if out of bounds
 pub fn get_block(world: &WorldData, pos: VoxelPos, chunk_size: u32) -> BlockId {
-    // Calculate chunk position
-    let chunk_size_i32 = chunk_size as i32;
-    let chunk_pos = ChunkPos {
-        x: pos.x.div_euclid(chunk_size_i32),
-        y: pos.y.div_euclid(chunk_size_i32),
-        z: pos.z.div_euclid(chunk_size_i32),
-    };
+    // Chunk and index in its flat array
+    let (chunk_pos, index) = voxel_chunk_index(chunk_layout_for_size(chunk_size), pos);
 
     // Find chunk in world data
     if let Some(chunk) = world.chunks.iter().find(|c| c.position == chunk_pos) {
-        // Calculate local position within chunk
-        let local_x = pos.x.rem_euclid(chunk_size_i32) as u32;
-        let local_y = pos.y.rem_euclid(chunk_size_i32) as u32;
-        let local_z = pos.z.rem_euclid(chunk_size_i32) as u32;
-
-        // Calculate index in flat array
-        let index = (local_x + local_y * chunk_size + local_z * chunk_size * chunk_size) as usize;
+        let index = index as usize;
 
         if index < chunk.blocks.len() {
             chunk.blocks[index]
@@ -68,27 +64,25 @@ pub fn set_block(
     block_id: BlockId,
     chunk_size: u32,
 ) -> Result<WorldModification, WorldError> {
-    // Calculate chunk position
-    let chunk_size_i32 = chunk_size as i32;
-    let chunk_pos = ChunkPos {
-        x: pos.x.div_euclid(chunk_size_i32),
-        y: pos.y.div_euclid(chunk_size_i32),
-        z: pos.z.div_euclid(chunk_size_i32),
-    };
+    // Chunk and index in its flat array
+    let layout = chunk_layout_for_size(chunk_size);
+    let (chunk_pos, index) = voxel_chunk_index(layout, pos);
 
     // Find chunk in world data
     if let Some(chunk) = world.chunks.iter_mut().find(|c| c.position == chunk_pos) {
-        // Calculate local position within chunk
-        let local_x = pos.x.rem_euclid(chunk_size_i32) as u32;
-        let local_y = pos.y.rem_euclid(chunk_size_i32) as u32;
-        let local_z = pos.z.rem_euclid(chunk_size_i32) as u32;
-
-        // Calculate index in flat array
-        let index = (local_x + local_y * chunk_size + local_z * chunk_size * chunk_size) as usize;
+        let [local_x, _, local_z] = voxel_to_local(layout, pos);
+        let index = index as usize;
 
         if index < chunk.blocks.len() {
             let old_block = chunk.blocks[index];
             chunk.blocks[index] = block_id;
+            // Metadata belongs to the old block
+            chunk.block_metadata.remove(&(index as u32));
+
+            if (old_block == BlockId::AIR) != (block_id == BlockId::AIR) {
+                let top = chunk_column_top(chunk, local_x, local_z, chunk_size);
+                set_column_section_top(world, chunk_pos, local_x, local_z, top, chunk_size);
+            }
 
             Ok(WorldModification {
                 position: pos,
@@ -104,6 +98,39 @@ pub fn set_block(
     }
 }
 
+/// Get block metadata at position (0 if none or out of bounds)
+pub fn get_block_metadata(world: &WorldData, pos: VoxelPos, chunk_size: u32) -> u8 {
+    let chunk_pos = voxel_to_chunk(pos, chunk_size);
+    let Some(chunk) = world.chunks.iter().find(|c| c.position == chunk_pos) else {
+        return 0;
+    };
+    let (local_x, local_y, local_z) = get_local_position(pos, chunk_size);
+    let index = local_x + local_y * chunk_size + local_z * chunk_size * chunk_size;
+    chunk.block_metadata.get(&index).copied().unwrap_or(0)
+}
+
+/// Set a block together with its metadata (e.g. orientation)
+pub fn set_block_with_metadata(
+    world: &mut WorldData,
+    pos: VoxelPos,
+    block_id: BlockId,
+    metadata: u8,
+    chunk_size: u32,
+) -> Result<WorldModification, WorldError> {
+    let modification = set_block(world, pos, block_id, chunk_size)?;
+
+    if metadata != 0 {
+        let chunk_pos = voxel_to_chunk(pos, chunk_size);
+        if let Some(chunk) = world.chunks.iter_mut().find(|c| c.position == chunk_pos) {
+            let (local_x, local_y, local_z) = get_local_position(pos, chunk_size);
+            let index = local_x + local_y * chunk_size + local_z * chunk_size * chunk_size;
+            chunk.block_metadata.insert(index, metadata);
+        }
+    }
+
+    Ok(modification)
+}
+
 /// World modification record
 #[derive(Clone, Copy, Debug)]
 pub struct WorldModification {
@@ -145,11 +172,7 @@ pub fn raycast(
         );
 
         // Convert to voxel position
-        let voxel_pos = VoxelPos {
-            x: point.x.floor() as i32,
-            y: point.y.floor() as i32,
-            z: point.z.floor() as i32,
-        };
+        let voxel_pos = world_point_to_voxel([point.x, point.y, point.z]);
 
         // Check block at this position
         let block = get_block(world, voxel_pos, chunk_size);
@@ -211,6 +234,13 @@ pub fn load_chunk(
     chunk_pos: ChunkPos,
     chunk_size: u32,
 ) -> Result<(), WorldError> {
+    if chunk_size != world.chunk_layout.size {
+        return Err(WorldError::OperationFailed(format!(
+            "Chunk size {} does not match world chunk size {}",
+            chunk_size, world.chunk_layout.size
+        )));
+    }
+
     // Check if already loaded
     if world.active_chunks.contains(&chunk_pos) {
         return Ok(());
@@ -221,11 +251,12 @@ pub fn load_chunk(
 
     if !chunk_exists {
         // Create new empty chunk
-        use super::data_types::{ChunkData, ChunkMetadata};
+        use super::data_types::ChunkMetadata;
         let blocks_per_chunk = (chunk_size * chunk_size * chunk_size) as usize;
         let new_chunk = ChunkData {
             position: chunk_pos,
             blocks: vec![BlockId::AIR; blocks_per_chunk],
+            block_metadata: std::collections::HashMap::new(),
             flags: ChunkMetadata::default(),
             last_modified: world.tick,
         };
@@ -238,6 +269,61 @@ pub fn load_chunk(
     Ok(())
 }
 
+/// Store a generated chunk (replacing any stored copy), mark it active and
+/// rebuild its heightmap section. Generated chunks are y-major
+/// (`TempChunk::blocks`); the world stores them x-major.
+pub fn insert_generated_chunk(world: &mut WorldData, chunk: &TempChunk) -> Result<(), WorldError> {
+    let size = world.chunk_layout.size;
+    if chunk.size != size || chunk.blocks.len() != world.chunk_layout.voxels_per_chunk as usize {
+        return Err(WorldError::OperationFailed(format!(
+            "Generated chunk size {} does not match world chunk size {}",
+            chunk.size, size
+        )));
+    }
+
+    let mut blocks = vec![BlockId::AIR; chunk.blocks.len()];
+    for (index, block) in chunk.blocks.iter().enumerate() {
+        let index = index as u32;
+        let (x, z, y) = (index % size, (index / size) % size, index / (size * size));
+        blocks[layout_voxel_index(world.chunk_layout, x, y, z) as usize] = *block;
+    }
+
+    use super::data_types::ChunkMetadata;
+    let data = ChunkData {
+        position: chunk.position,
+        flags: ChunkMetadata {
+            is_generated: true,
+            is_empty: blocks.iter().all(|block| *block == BlockId::AIR),
+            ..ChunkMetadata::default()
+        },
+        blocks,
+        block_metadata: std::collections::HashMap::new(),
+        last_modified: world.tick,
+    };
+    insert_chunk(world, data)
+}
+
+/// Store a chunk (e.g. loaded from a save), replacing any stored copy, mark
+/// it active and rebuild its heightmap section
+pub fn insert_chunk(world: &mut WorldData, chunk: ChunkData) -> Result<(), WorldError> {
+    let size = world.chunk_layout.size;
+    if chunk.blocks.len() != world.chunk_layout.voxels_per_chunk as usize {
+        return Err(WorldError::OperationFailed(format!(
+            "Chunk {:?} has {} blocks, expected {}",
+            chunk.position,
+            chunk.blocks.len(),
+            world.chunk_layout.voxels_per_chunk
+        )));
+    }
+    let position = chunk.position;
+    match world.chunks.iter_mut().find(|c| c.position == position) {
+        Some(stored) => *stored = chunk,
+        None => world.chunks.push(chunk),
+    }
+    world.active_chunks.insert(position);
+    rebuild_chunk_heightmap(world, position, size)
+}
+
 /// Unload a chunk (mark as inactive)
 pub fn unload_chunk(world: &mut WorldData, chunk_pos: ChunkPos) -> Result<(), WorldError> {
     world.active_chunks.remove(&chunk_pos);
@@ -297,6 +383,16 @@ pub fn get_world_tick(world: &WorldData) -> u64 {
     world.tick
 }
 
+/// Get world chunk layout
+pub fn get_world_chunk_layout(world: &WorldData) -> ChunkLayout {
+    world.chunk_layout
+}
+
+/// Get world chunk size (the `chunk_size` argument the other operations expect)
+pub fn get_world_chunk_size(world: &WorldData) -> u32 {
+    world.chunk_layout.size
+}
+
 /// Get active chunk count
 pub fn get_active_chunk_count(world: &WorldData) -> usize {
     world.active_chunks.len()
@@ -330,38 +426,315 @@ pub fn get_blocks_batch(
         .collect()
 }
 
+// ============================================================================
+// REGION OPERATIONS (bulk fill / copy / paste)
+// ============================================================================
+
+/// Order two corners into (min, max), both inclusive
+pub fn normalize_region(a: VoxelPos, b: VoxelPos) -> (VoxelPos, VoxelPos) {
+    (
+        VoxelPos::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+        VoxelPos::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+    )
+}
+
+/// Size of the inclusive region between two corners
+pub fn region_size(a: VoxelPos, b: VoxelPos) -> [u32; 3] {
+    let (min, max) = normalize_region(a, b);
+    [
+        (max.x - min.x + 1) as u32,
+        (max.y - min.y + 1) as u32,
+        (max.z - min.z + 1) as u32,
+    ]
+}
+
+/// Visit every position of a region, resolving each chunk once per run of
+/// blocks that share it. `visit` gets the chunk index (None if not loaded),
+/// the block index within the chunk and the region-relative index.
+fn for_each_region_block<F>(
+    world: &WorldData,
+    min: VoxelPos,
+    size: [u32; 3],
+    chunk_size: u32,
+    mut visit: F,
+) where
+    F: FnMut(Option<usize>, usize, usize),
+{
+    let mut cached_chunk: Option<ChunkPos> = None;
+    let mut cached_index: Option<usize> = None;
+    let mut region_index = 0;
+    for z in 0..size[2] as i32 {
+        for y in 0..size[1] as i32 {
+            for x in 0..size[0] as i32 {
+                let pos = VoxelPos::new(min.x + x, min.y + y, min.z + z);
+                let chunk_pos = voxel_to_chunk(pos, chunk_size);
+                if cached_chunk != Some(chunk_pos) {
+                    cached_chunk = Some(chunk_pos);
+                    cached_index = world.chunks.iter().position(|c| c.position == chunk_pos);
+                }
+
+                let (local_x, local_y, local_z) = get_local_position(pos, chunk_size);
+                let block_index =
+                    (local_x + local_y * chunk_size + local_z * chunk_size * chunk_size) as usize;
+                visit(cached_index, block_index, region_index);
+                region_index += 1;
+            }
+        }
+    }
+}
+
+/// Copy blocks and metadata from the inclusive region between two corners.
+/// Unloaded chunks read as air.
+pub fn copy_region(world: &WorldData, a: VoxelPos, b: VoxelPos, chunk_size: u32) -> RegionBlocks {
+    let (min, _) = normalize_region(a, b);
+    let size = region_size(a, b);
+    let volume = size.iter().map(|s| *s as usize).product();
+    let mut blocks = vec![BlockId::AIR; volume];
+    let mut metadata = vec![0u8; volume];
+
+    for_each_region_block(world, min, size, chunk_size, |chunk_index, block_index, index| {
+        if let Some(chunk) = chunk_index.map(|i| &world.chunks[i]) {
+            blocks[index] = chunk.blocks.get(block_index).copied().unwrap_or(BlockId::AIR);
+            metadata[index] = chunk
+                .block_metadata
+                .get(&(block_index as u32))
+                .copied()
+                .unwrap_or(0);
+        }
+    });
+
+    RegionBlocks {
+        size,
+        blocks,
+        metadata,
+    }
+}
+
+/// Write `region` with its minimum corner at `anchor`.
+/// Blocks in unloaded chunks are skipped and counted.
+pub fn paste_region(
+    world: &mut WorldData,
+    region: &RegionBlocks,
+    anchor: VoxelPos,
+    chunk_size: u32,
+) -> RegionPasteStats {
+    let mut writes = Vec::with_capacity(region.blocks.len());
+    let mut stats = RegionPasteStats::default();
+    for_each_region_block(world, anchor, region.size, chunk_size, |chunk_index, block_index, index| {
+        match chunk_index {
+            Some(chunk_index) => writes.push((chunk_index, block_index, index)),
+            None => stats.unloaded += 1,
+        }
+    });
+
+    let tick = world.tick;
+    let mut touched_columns = HashSet::new();
+    for (chunk_index, block_index, index) in writes {
+        let chunk = &mut world.chunks[chunk_index];
+        let Some(block) = chunk.blocks.get_mut(block_index) else {
+            continue;
+        };
+        *block = region.blocks[index];
+
+        let metadata = region.metadata.get(index).copied().unwrap_or(0);
+        if metadata == 0 {
+            chunk.block_metadata.remove(&(block_index as u32));
+        } else {
+            chunk.block_metadata.insert(block_index as u32, metadata);
+        }
+        chunk.flags.is_dirty = true;
+        chunk.flags.needs_lighting_update = true;
+        chunk.last_modified = tick;
+        touched_columns.insert((chunk_index, block_index as u32 % (chunk_size * chunk_size)));
+        stats.placed += 1;
+    }
+
+    for (chunk_index, column) in touched_columns {
+        let chunk = &world.chunks[chunk_index];
+        let (local_x, local_z) = (column % chunk_size, column / chunk_size);
+        let top = chunk_column_top(chunk, local_x, local_z, chunk_size);
+        let chunk_pos = chunk.position;
+        set_column_section_top(world, chunk_pos, local_x, local_z, top, chunk_size);
+    }
+
+    stats
+}
+
+/// Fill the inclusive region between two corners with one block
+pub fn fill_region(
+    world: &mut WorldData,
+    a: VoxelPos,
+    b: VoxelPos,
+    block_id: BlockId,
+    chunk_size: u32,
+) -> RegionPasteStats {
+    let (min, _) = normalize_region(a, b);
+    let size = region_size(a, b);
+    let volume = size.iter().map(|s| *s as usize).product();
+    let region = RegionBlocks {
+        size,
+        blocks: vec![block_id; volume],
+        metadata: Vec::new(),
+    };
+    paste_region(world, &region, min, chunk_size)
+}
+
+// ============================================================================
+// SURFACE HEIGHTMAP
+// ============================================================================
+
+/// Highest non-air local y + 1 of one column of a chunk (0 = empty)
+fn chunk_column_top(chunk: &ChunkData, local_x: u32, local_z: u32, chunk_size: u32) -> u32 {
+    (0..chunk_size)
+        .rev()
+        .find(|&y| {
+            let index = (local_x + y * chunk_size + local_z * chunk_size * chunk_size) as usize;
+            chunk.blocks.get(index).is_some_and(|block| *block != BlockId::AIR)
+        })
+        .map_or(0, |y| y + 1)
+}
+
+/// Per-column tops of a chunk's blocks, indexed `x + z * chunk_size`
+/// (CPU reference of the GPU heightmap pass)
+pub fn compute_chunk_column_tops(chunk: &ChunkData, chunk_size: u32) -> Vec<u32> {
+    let mut tops = Vec::with_capacity((chunk_size * chunk_size) as usize);
+    for local_z in 0..chunk_size {
+        for local_x in 0..chunk_size {
+            tops.push(chunk_column_top(chunk, local_x, local_z, chunk_size));
+        }
+    }
+    tops
+}
+
+/// Surface of one column from its sections, highest chunk first
+fn column_surface(heights: &ColumnHeights, column: usize, chunk_size: u32) -> i32 {
+    heights
+        .sections
+        .iter()
+        .rev()
+        .find_map(|(chunk_y, tops)| match tops.get(column) {
+            Some(&top) if top > 0 => Some(chunk_y * chunk_size as i32 + top as i32 - 1),
+            _ => None,
+        })
+        .unwrap_or(NO_SURFACE_HEIGHT)
+}
+
+fn column_heights_entry(
+    world: &mut WorldData,
+    chunk_pos: ChunkPos,
+    chunk_size: u32,
+) -> &mut ColumnHeights {
+    let columns = (chunk_size * chunk_size) as usize;
+    world
+        .column_heights
+        .entry((chunk_pos.x, chunk_pos.z))
+        .or_insert_with(|| ColumnHeights {
+            surface: vec![NO_SURFACE_HEIGHT; columns],
+            sections: Default::default(),
+        })
+}
+
+/// Update one column of one chunk section and the column's surface
+fn set_column_section_top(
+    world: &mut WorldData,
+    chunk_pos: ChunkPos,
+    local_x: u32,
+    local_z: u32,
+    top: u32,
+    chunk_size: u32,
+) {
+    let columns = (chunk_size * chunk_size) as usize;
+    let column = (local_x + local_z * chunk_size) as usize;
+    let heights = column_heights_entry(world, chunk_pos, chunk_size);
+    let section = heights
+        .sections
+        .entry(chunk_pos.y)
+        .or_insert_with(|| vec![0; columns]);
+    if section.get(column) == Some(&top) {
+        return;
+    }
+    if let Some(slot) = section.get_mut(column) {
+        *slot = top;
+    }
+    let surface = column_surface(heights, column, chunk_size);
+    if let Some(slot) = heights.surface.get_mut(column) {
+        *slot = surface;
+    }
+}
+
+/// Replace a whole chunk section of the heightmap, e.g. with tops read
+/// back from the GPU heightmap pass after generation
+pub fn apply_chunk_column_tops(
+    world: &mut WorldData,
+    chunk_pos: ChunkPos,
+    tops: Vec<u32>,
+    chunk_size: u32,
+) -> Result<(), WorldError> {
+    let columns = (chunk_size * chunk_size) as usize;
+    if tops.len() != columns {
+        return Err(WorldError::OperationFailed(format!(
+            "Heightmap section has {} columns, expected {}",
+            tops.len(),
+            columns
+        )));
+    }
+
+    let heights = column_heights_entry(world, chunk_pos, chunk_size);
+    heights.sections.insert(chunk_pos.y, tops);
+    for column in 0..columns {
+        heights.surface[column] = column_surface(heights, column, chunk_size);
+    }
+    Ok(())
+}
+
+/// Recompute a chunk's heightmap section from its blocks (after its blocks
+/// were written directly, e.g. by generation or loading)
+pub fn rebuild_chunk_heightmap(
+    world: &mut WorldData,
+    chunk_pos: ChunkPos,
+    chunk_size: u32,
+) -> Result<(), WorldError> {
+    let chunk = world
+        .chunks
+        .iter()
+        .find(|c| c.position == chunk_pos)
+        .ok_or(WorldError::ChunkNotLoaded)?;
+    let tops = compute_chunk_column_tops(chunk, chunk_size);
+    apply_chunk_column_tops(world, chunk_pos, tops, chunk_size)
+}
+
+/// World y of the highest non-air block in column (x, z), or None if no
+/// loaded chunk of the column holds a block. Constant time; kept current
+/// by `set_block`, region edits and `apply_chunk_column_tops`.
+pub fn get_surface_height(world: &WorldData, x: i32, z: i32) -> Option<i32> {
+    let layout = world.chunk_layout;
+    let heights = world.column_heights.get(&column_to_chunk(layout, x, z))?;
+    let column = column_local_index(layout, x, z);
+    heights
+        .surface
+        .get(column)
+        .copied()
+        .filter(|height| *height != NO_SURFACE_HEIGHT)
+}
+
 // ============================================================================
 // UTILITIES
 // ============================================================================
 
 /// Convert voxel position to chunk position
 pub fn voxel_to_chunk(pos: VoxelPos, chunk_size: u32) -> ChunkPos {
-    let chunk_size_i32 = chunk_size as i32;
-    ChunkPos {
-        x: pos.x.div_euclid(chunk_size_i32),
-        y: pos.y.div_euclid(chunk_size_i32),
-        z: pos.z.div_euclid(chunk_size_i32),
-    }
+    voxel_to_chunk_pos(chunk_layout_for_size(chunk_size), pos)
 }
 
 /// Convert chunk position to world position (chunk corner)
 pub fn chunk_to_world(chunk_pos: ChunkPos, chunk_size: u32) -> VoxelPos {
-    let chunk_size_i32 = chunk_size as i32;
-    VoxelPos {
-        x: chunk_pos.x * chunk_size_i32,
-        y: chunk_pos.y * chunk_size_i32,
-        z: chunk_pos.z * chunk_size_i32,
-    }
+    chunk_origin_voxel(chunk_layout_for_size(chunk_size), chunk_pos)
 }
 
 /// Get local position within chunk (0 to chunk_size-1)
 pub fn get_local_position(pos: VoxelPos, chunk_size: u32) -> (u32, u32, u32) {
-    let chunk_size_i32 = chunk_size as i32;
-    (
-        pos.x.rem_euclid(chunk_size_i32) as u32,
-        pos.y.rem_euclid(chunk_size_i32) as u32,
-        pos.z.rem_euclid(chunk_size_i32) as u32,
-    )
+    let [x, y, z] = voxel_to_local(chunk_layout_for_size(chunk_size), pos);
+    (x, y, z)
 }
 
 // ============================================================================
@@ -376,11 +749,19 @@ pub fn log_world_stats(world: &WorldData) {
     log::info!("  Tick: {}", world.tick);
     log::info!("  Active chunks: {}", world.active_chunks.len());
     log::info!("  Chunk capacity: {}", world.chunk_capacity);
+    log::info!("  Chunk size: {}", world.chunk_layout.size);
 }
 
 /// Validate world data integrity
 pub fn validate_world_data(world: &WorldData, chunk_size: u32) -> Result<(), String> {
-    let expected_blocks_per_chunk = (chunk_size * chunk_size * chunk_size) as usize;
+    if chunk_size != world.chunk_layout.size {
+        return Err(format!(
+            "Chunk size {} does not match world chunk size {}",
+            chunk_size, world.chunk_layout.size
+        ));
+    }
+
+    let expected_blocks_per_chunk = world.chunk_layout.voxels_per_chunk as usize;
 
     for chunk in &world.chunks {
         if chunk.blocks.len() != expected_blocks_per_chunk {
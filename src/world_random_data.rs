@@ -0,0 +1,63 @@
+//! World Random Data - Pure DOP
+//!
+//! NO METHODS. Just data.
+//! Stream derivation and generation live in world_random_operations.rs
+//!
+//! Every random decision an engine system makes comes from a named stream
+//! (terrain, loot, weather, ai, ...) derived from the world seed, so a
+//! world replays the same way from the same seed and saved stream states.
+//! Parallel consumers take forks keyed by tick and consumer instead of
+//! sharing a stream, which keeps their results independent of scheduling.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// xoshiro256** generator state; implements `rand::RngCore`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorldRng {
+    pub state: [u64; 4],
+}
+
+/// Named streams of one world
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldRandomData {
+    pub world_seed: u64,
+    /// Simulation tick forks are derived from
+    pub tick: u64,
+    /// Streams drawn from so far, by name
+    pub streams: BTreeMap<String, WorldRng>,
+}
+
+/// Stream state as saved with the world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedRngStream {
+    pub name: String,
+    pub state: [u64; 4],
+}
+
+/// Contents of the world's random state file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedWorldRandom {
+    pub version: u32,
+    pub world_seed: u64,
+    pub tick: u64,
+    pub streams: Vec<SavedRngStream>,
+}
+
+/// World random state errors
+#[derive(Debug, thiserror::Error)]
+pub enum WorldRandomError {
+    #[error("World random state serialization failed: {0}")]
+    Serialization(String),
+
+    #[error("World random state deserialization failed: {0}")]
+    Deserialization(String),
+
+    #[error("World random state I/O failed: {0}")]
+    Io(String),
+
+    #[error("Saved random state belongs to seed {saved}, not {expected}")]
+    SeedMismatch { expected: u64, saved: u64 },
+}
+
+pub type WorldRandomResult<T> = Result<T, WorldRandomError>;
@@ -0,0 +1,263 @@
+//! World Random Operations - Pure DOP functions
+//!
+//! Sequential consumers draw from `world_stream(random, LOOT_STREAM)`;
+//! work spread over threads takes `fork_world_stream(random, name, key)`
+//! with a key naming the consumer (e.g. `position_fork_key` of a chunk),
+//! which is derived from the seed, stream, tick and key alone and leaves
+//! the stream untouched. Call `advance_world_random_tick` once per fixed
+//! tick and save the states with the world. Engine systems must not use
+//! `rand::thread_rng` or `rand::random` (clippy rejects both).
+
+use crate::constants::persistence_constants::{WORLD_RANDOM_FILE, WORLD_RANDOM_FORMAT_VERSION};
+use crate::persistence::{read_save_file, write_save_file, SaveCipherData};
+use crate::world_random_data::{
+    SavedRngStream, SavedWorldRandom, WorldRandomData, WorldRandomError, WorldRandomResult,
+    WorldRng,
+};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const SPLITMIX_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// SplitMix64 finalizer
+fn splitmix_mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Next value of a SplitMix64 sequence
+pub fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(SPLITMIX_GAMMA);
+    splitmix_mix(*state)
+}
+
+/// Stable 64-bit key of a stream name (FNV-1a)
+pub fn stream_name_key(name: &str) -> u64 {
+    name.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3)
+    })
+}
+
+/// Fork key of a voxel or chunk position
+pub fn position_fork_key(x: i32, y: i32, z: i32) -> u64 {
+    let mut state = (x as u32 as u64) | ((z as u32 as u64) << 32);
+    splitmix64(&mut state) ^ splitmix_mix(y as u32 as u64)
+}
+
+/// Generator whose state is expanded from `seed` with SplitMix64
+pub fn seed_world_rng(seed: u64) -> WorldRng {
+    let mut splitmix = seed;
+    WorldRng {
+        state: [
+            splitmix64(&mut splitmix),
+            splitmix64(&mut splitmix),
+            splitmix64(&mut splitmix),
+            splitmix64(&mut splitmix),
+        ],
+    }
+}
+
+/// Next 64 random bits (xoshiro256**)
+pub fn next_world_rng_u64(rng: &mut WorldRng) -> u64 {
+    let s = &mut rng.state;
+    let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+    let t = s[1] << 17;
+    s[2] ^= s[0];
+    s[3] ^= s[1];
+    s[1] ^= s[2];
+    s[0] ^= s[3];
+    s[2] ^= t;
+    s[3] = s[3].rotate_left(45);
+    result
+}
+
+impl rand::RngCore for WorldRng {
+    fn next_u32(&mut self) -> u32 {
+        (next_world_rng_u64(self) >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        next_world_rng_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = next_world_rng_u64(self).to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Seed of stream `name` in a world
+fn stream_seed(world_seed: u64, name: &str) -> u64 {
+    splitmix_mix(splitmix_mix(world_seed) ^ stream_name_key(name))
+}
+
+/// Streams for a world, at tick 0
+pub fn create_world_random(world_seed: u64) -> WorldRandomData {
+    WorldRandomData {
+        world_seed,
+        tick: 0,
+        streams: BTreeMap::new(),
+    }
+}
+
+/// Stream `name`, created from the world seed the first time it is used
+pub fn world_stream<'a>(random: &'a mut WorldRandomData, name: &str) -> &'a mut WorldRng {
+    let world_seed = random.world_seed;
+    random
+        .streams
+        .entry(name.to_string())
+        .or_insert_with(|| seed_world_rng(stream_seed(world_seed, name)))
+}
+
+/// Independent generator for one consumer of stream `name` this tick;
+/// the same seed, stream, tick and key always give the same generator
+pub fn fork_world_stream(random: &WorldRandomData, name: &str, key: u64) -> WorldRng {
+    let tick_seed = splitmix_mix(stream_seed(random.world_seed, name) ^ random.tick);
+    seed_world_rng(splitmix_mix(tick_seed.wrapping_add(SPLITMIX_GAMMA) ^ key))
+}
+
+/// Move to `tick`; forks taken afterwards differ from the last tick's
+pub fn advance_world_random_tick(random: &mut WorldRandomData, tick: u64) {
+    random.tick = tick;
+}
+
+/// Encode the stream states
+pub fn serialize_world_random(random: &WorldRandomData) -> WorldRandomResult<Vec<u8>> {
+    let saved = SavedWorldRandom {
+        version: WORLD_RANDOM_FORMAT_VERSION,
+        world_seed: random.world_seed,
+        tick: random.tick,
+        streams: random
+            .streams
+            .iter()
+            .map(|(name, rng)| SavedRngStream {
+                name: name.clone(),
+                state: rng.state,
+            })
+            .collect(),
+    };
+    bincode::serialize(&saved).map_err(|e| WorldRandomError::Serialization(e.to_string()))
+}
+
+/// Decode stream states saved by `serialize_world_random`
+pub fn deserialize_world_random(bytes: &[u8]) -> WorldRandomResult<WorldRandomData> {
+    let saved: SavedWorldRandom = bincode::deserialize(bytes)
+        .map_err(|e| WorldRandomError::Deserialization(e.to_string()))?;
+    if saved.version > WORLD_RANDOM_FORMAT_VERSION {
+        return Err(WorldRandomError::Deserialization(format!(
+            "Unsupported world random version {}",
+            saved.version
+        )));
+    }
+    Ok(WorldRandomData {
+        world_seed: saved.world_seed,
+        tick: saved.tick,
+        streams: saved
+            .streams
+            .into_iter()
+            .map(|stream| {
+                (
+                    stream.name,
+                    WorldRng {
+                        state: stream.state,
+                    },
+                )
+            })
+            .collect(),
+    })
+}
+
+/// Write the stream states into a world slot directory
+pub fn save_world_random(
+    random: &WorldRandomData,
+    world_dir: &Path,
+    cipher: Option<&SaveCipherData>,
+) -> WorldRandomResult<()> {
+    let bytes = serialize_world_random(random)?;
+    write_save_file(cipher, &world_dir.join(WORLD_RANDOM_FILE), &bytes)
+        .map_err(|e| WorldRandomError::Io(e.to_string()))
+}
+
+/// Read the stream states of a world slot directory; worlds saved without
+/// them start fresh from `world_seed`
+pub fn load_world_random(
+    world_dir: &Path,
+    world_seed: u64,
+    cipher: Option<&SaveCipherData>,
+) -> WorldRandomResult<WorldRandomData> {
+    let path = world_dir.join(WORLD_RANDOM_FILE);
+    if !path.exists() {
+        return Ok(create_world_random(world_seed));
+    }
+    let bytes = read_save_file(cipher, &path).map_err(|e| WorldRandomError::Io(e.to_string()))?;
+    let random = deserialize_world_random(&bytes)?;
+    if random.world_seed != world_seed {
+        return Err(WorldRandomError::SeedMismatch {
+            expected: world_seed,
+            saved: random.world_seed,
+        });
+    }
+    Ok(random)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::world_random::{LOOT_STREAM, TERRAIN_STREAM};
+    use rand::Rng;
+
+    #[test]
+    fn test_streams_are_reproducible_and_independent() {
+        let mut a = create_world_random(42);
+        let mut b = create_world_random(42);
+
+        // Drawing from one stream does not shift another
+        let _: u64 = world_stream(&mut a, TERRAIN_STREAM).gen();
+        let loot_a: [u32; 4] = world_stream(&mut a, LOOT_STREAM).gen();
+        let loot_b: [u32; 4] = world_stream(&mut b, LOOT_STREAM).gen();
+        assert_eq!(loot_a, loot_b);
+        assert_ne!(
+            next_world_rng_u64(world_stream(&mut b, TERRAIN_STREAM)),
+            next_world_rng_u64(world_stream(&mut b, LOOT_STREAM))
+        );
+
+        // Forks depend on tick and key only
+        let key = position_fork_key(3, -1, 7);
+        let fork = fork_world_stream(&a, TERRAIN_STREAM, key);
+        assert_eq!(fork, fork_world_stream(&b, TERRAIN_STREAM, key));
+        assert_ne!(
+            fork,
+            fork_world_stream(&a, TERRAIN_STREAM, position_fork_key(3, -1, 8))
+        );
+        advance_world_random_tick(&mut a, 1);
+        assert_ne!(fork, fork_world_stream(&a, TERRAIN_STREAM, key));
+
+        // Saved states continue where they left off
+        let dir = tempfile::tempdir().expect("temp dir");
+        save_world_random(&a, dir.path(), None).expect("save");
+        let mut restored = load_world_random(dir.path(), 42, None).expect("load");
+        assert_eq!(restored, a);
+        assert_eq!(
+            next_world_rng_u64(world_stream(&mut restored, LOOT_STREAM)),
+            next_world_rng_u64(world_stream(&mut a, LOOT_STREAM))
+        );
+        assert!(matches!(
+            load_world_random(dir.path(), 7, None),
+            Err(WorldRandomError::SeedMismatch { saved: 42, .. })
+        ));
+        assert_eq!(
+            load_world_random(&dir.path().join("none"), 7, None)
+                .expect("fresh")
+                .tick,
+            0
+        );
+    }
+}
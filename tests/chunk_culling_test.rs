@@ -0,0 +1,68 @@
+//! GPU chunk culling keeps the chunk draws in front of the camera and drops
+//! the ones behind it or cave culled
+
+use cgmath::{EuclideanSpace, Point3};
+use hearth_engine::camera::{
+    build_projection_matrix, build_view_matrix, calculate_forward_vector, init_camera,
+};
+use hearth_engine::engine_gpu_world_operations::chunk_draw_metadata;
+use hearth_engine::gpu::buffer_layouts::DrawMetadata;
+use hearth_engine::renderer::gpu_culling::{ChunkCulling, GpuCamera};
+use hearth_engine::world::core::{chunk_layout_for_size, ChunkPos};
+
+mod common;
+
+fn read_u32(device: &wgpu::Device, queue: &wgpu::Queue, buffer: &wgpu::Buffer) -> u32 {
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Chunk Culling Test Readback"),
+        size: 4,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&Default::default());
+    encoder.copy_buffer_to_buffer(buffer, 0, &readback, 0, 4);
+    queue.submit(std::iter::once(encoder.finish()));
+    readback.slice(..).map_async(wgpu::MapMode::Read, |_| {});
+    device.poll(wgpu::Maintain::Wait);
+    let count = bytemuck::cast_slice::<u8, u32>(&readback.slice(..).get_mapped_range())[0];
+    readback.unmap();
+    count
+}
+
+#[test]
+fn test_chunks_behind_the_camera_or_cave_culled_get_no_draw() {
+    let Some((device, queue)) = common::create_test_device() else {
+        eprintln!("No GPU adapter, skipping chunk culling test");
+        return;
+    };
+    let mut culling = ChunkCulling::new(&device).expect("chunk culling shader");
+    let camera = init_camera(Point3::new(25.0, 25.0, 25.0), 0.0, 0.0);
+    let gpu_camera = GpuCamera::from_matrices(
+        &build_view_matrix(&camera),
+        &build_projection_matrix(&camera),
+        camera.position.to_vec(),
+    );
+
+    // Three chunks ahead of the camera and one behind it; the farthest
+    // ahead was not reached by cave culling
+    let forward = calculate_forward_vector(0.0, 0.0);
+    let step = |n: f32| {
+        ChunkPos::new(
+            (forward.x * n).round() as i32,
+            0,
+            (forward.z * n).round() as i32,
+        )
+    };
+    let layout = chunk_layout_for_size(50);
+    let mut draws: Vec<DrawMetadata> = [step(2.0), step(3.0), step(4.0), step(-3.0)]
+        .iter()
+        .enumerate()
+        .map(|(i, pos)| chunk_draw_metadata(layout, *pos, i as u32))
+        .collect();
+    draws[2].flags &= !DrawMetadata::FLAG_VISIBLE;
+
+    let mut encoder = device.create_command_encoder(&Default::default());
+    culling.encode(&device, &mut encoder, &gpu_camera, &draws);
+    queue.submit(std::iter::once(encoder.finish()));
+    assert_eq!(read_u32(&device, &queue, culling.draw_count_buffer()), 2);
+}
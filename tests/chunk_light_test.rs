@@ -0,0 +1,114 @@
+//! Chunk light propagation writes the world buffer's lighting storage:
+//! the dedicated lighting buffer, or the voxel light bits in packed mode
+
+use hearth_engine::gpu::buffer_layouts::{
+    calculations, CHUNK_BUFFER_SLOT_SIZE, CHUNK_LIGHT_SLOT_SIZE,
+};
+use hearth_engine::world::compute::GpuChunkLight;
+use hearth_engine::world::core::{BlockId, ChunkPos};
+use hearth_engine::world::storage::{
+    pack_voxel_light, unpack_voxel_light, LightingStorageMode, VoxelData, WorldBuffer,
+    WorldBufferDescriptor,
+};
+
+mod common;
+
+/// Stone below this height, air above
+const GROUND: i32 = 10;
+
+/// Ground with a glowstone buried in it next to a sealed two-voxel pocket
+fn lit_chunk_voxels(size: i32) -> Vec<VoxelData> {
+    let mut voxels = Vec::with_capacity((size * size * size) as usize);
+    for z in 0..size {
+        for y in 0..size {
+            for x in 0..size {
+                let block = match (x, y, z) {
+                    (5, 5, 5) => BlockId::GLOWSTONE,
+                    (6, 5, 5) | (7, 5, 5) => BlockId::AIR,
+                    _ if y < GROUND => BlockId::STONE,
+                    _ => BlockId::AIR,
+                };
+                voxels.push(VoxelData::new(block.0, 0, 0, 0));
+            }
+        }
+    }
+    voxels
+}
+
+/// Light a single chunk and return (block, sky) per voxel
+fn light_chunk(lighting_mode: LightingStorageMode) -> Option<(i32, Vec<(u8, u8)>)> {
+    let (device, queue) = common::create_world_buffer_device("Chunk Light Test Device")?;
+    let mut world_buffer = WorldBuffer::new(
+        device.clone(),
+        &WorldBufferDescriptor {
+            view_distance: 1,
+            enable_readback: true,
+            enable_sparse_chunks: false,
+            lighting_mode,
+            ..Default::default()
+        },
+    );
+    let size = world_buffer.chunk_layout().size as i32;
+    let chunk = ChunkPos::new(0, 0, 0);
+    world_buffer.upload_chunk(&queue, chunk, &lit_chunk_voxels(size));
+
+    let chunk_light = GpuChunkLight::new(device.clone()).expect("chunk light shader");
+    let mut encoder = device.create_command_encoder(&Default::default());
+    let lit = chunk_light.encode_chunk_light(&mut encoder, &world_buffer, &[chunk]);
+    queue.submit(std::iter::once(encoder.finish()));
+    assert_eq!(lit, vec![chunk]);
+
+    let light = world_buffer
+        .read_chunk_light(&device, &queue, chunk)
+        .expect("read light")
+        .into_iter()
+        .map(unpack_voxel_light)
+        .collect();
+    Some((size, light))
+}
+
+fn assert_chunk_lit(size: i32, light: &[(u8, u8)]) {
+    let at = |x: i32, y: i32, z: i32| light[(x + y * size + z * size * size) as usize];
+    // Open sky reaches the ground without falloff
+    assert_eq!(at(20, size - 1, 20), (0, 15));
+    assert_eq!(at(20, GROUND, 20), (0, 15));
+    // Stone is dark; the glowstone keeps its own emission
+    assert_eq!(at(20, GROUND - 1, 20), (0, 0));
+    assert_eq!(at(5, 5, 5), (15, 0));
+    // Block light fades one level per voxel through the sealed pocket
+    assert_eq!(at(6, 5, 5), (14, 0));
+    assert_eq!(at(7, 5, 5), (13, 0));
+}
+
+#[test]
+fn test_chunk_light_fills_dedicated_light_buffer() {
+    let Some((size, light)) = light_chunk(LightingStorageMode::Dedicated) else {
+        eprintln!("No suitable GPU adapter, skipping chunk light test");
+        return;
+    };
+    assert_chunk_lit(size, &light);
+}
+
+#[test]
+fn test_chunk_light_fills_packed_voxel_light() {
+    let Some((size, light)) = light_chunk(LightingStorageMode::Packed) else {
+        eprintln!("No suitable GPU adapter, skipping chunk light test");
+        return;
+    };
+    assert_chunk_lit(size, &light);
+}
+
+#[test]
+fn test_light_slot_layout() {
+    // Dedicated light slots are half the size of voxel slots and stay copy-aligned
+    assert_eq!(CHUNK_LIGHT_SLOT_SIZE * 2, CHUNK_BUFFER_SLOT_SIZE);
+    assert_eq!(CHUNK_LIGHT_SLOT_SIZE % wgpu::COPY_BUFFER_ALIGNMENT, 0);
+    assert_eq!(
+        calculations::chunk_light_slot_offset(3),
+        3 * CHUNK_LIGHT_SLOT_SIZE
+    );
+
+    let packed = pack_voxel_light(200, 17);
+    assert_eq!(unpack_voxel_light(packed), (200, 17));
+    assert_eq!(unpack_voxel_light(pack_voxel_light(255, 255)), (255, 255));
+}
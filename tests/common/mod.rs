@@ -0,0 +1,124 @@
+//! Setup shared by the integration tests
+// Each test crate uses only some of the helpers
+#![allow(dead_code)]
+
+use hearth_engine::renderer::{attach_renderer_to_texture, Renderer};
+use std::sync::Arc;
+
+/// Width and height of `render_texture`
+pub const TARGET_SIZE: u32 = 64;
+
+/// Device with the default features and limits, or None without an
+/// adapter
+pub fn create_test_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&Default::default()))?;
+    pollster::block_on(adapter.request_device(&Default::default(), None)).ok()
+}
+
+/// Device able to hold a `WorldBuffer`, or None when no adapter supports
+/// it. The world buffer layout exposes voxels to vertex shaders
+/// read-write, so the adapter needs vertex-writable storage.
+pub fn create_world_buffer_device(label: &str) -> Option<(Arc<wgpu::Device>, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&Default::default()))?;
+    let features = wgpu::Features::VERTEX_WRITABLE_STORAGE;
+    if !adapter.features().contains(features) {
+        return None;
+    }
+    let (device, queue) = pollster::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: Some(label),
+            required_features: features,
+            required_limits: wgpu::Limits::default(),
+        },
+        None,
+    ))
+    .ok()?;
+    Some((Arc::new(device), queue))
+}
+
+/// Renderer drawing into a `render_texture`, or None without an adapter
+pub fn offscreen_renderer() -> Option<Renderer> {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&Default::default()))?;
+    // The engine's GPU world needs VERTEX_WRITABLE_STORAGE; without it the
+    // engine runs with CPU-only chunks
+    let (device, queue) = pollster::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: Some("Offscreen Test Device"),
+            required_features: adapter.features() & wgpu::Features::VERTEX_WRITABLE_STORAGE,
+            required_limits: wgpu::Limits::default(),
+        },
+        None,
+    ))
+    .expect("Failed to create device");
+    let device = Arc::new(device);
+    let queue = Arc::new(queue);
+
+    let texture = render_texture(&device);
+    Some(attach_renderer_to_texture(texture, device, queue).expect("attach"))
+}
+
+/// `TARGET_SIZE` square Rgba8 target that can be rendered to and read back
+pub fn render_texture(device: &wgpu::Device) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Offscreen Test Target"),
+        size: wgpu::Extent3d {
+            width: TARGET_SIZE,
+            height: TARGET_SIZE,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
+
+/// RGBA of the center pixel of a `render_texture`
+pub fn center_pixel(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+) -> [u8; 4] {
+    let bytes_per_row = TARGET_SIZE * 4;
+    let staging = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Offscreen Test Readback"),
+        size: u64::from(bytes_per_row * TARGET_SIZE),
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &staging,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(TARGET_SIZE),
+            },
+        },
+        texture.size(),
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = staging.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| {});
+    device.poll(wgpu::Maintain::Wait);
+    let offset = ((TARGET_SIZE / 2 * TARGET_SIZE + TARGET_SIZE / 2) * 4) as usize;
+    let pixel = {
+        let bytes = slice.get_mapped_range();
+        [
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ]
+    };
+    staging.unmap();
+    pixel
+}
@@ -0,0 +1,106 @@
+//! Simulated GPU device loss: the renderer must notice the loss, skip frames
+//! while the device is gone, move to a new device and restore shadowed buffers
+
+use hearth_engine::renderer::{
+    attach_renderer_to_texture, create_buffer_shadow, default_sky_config, device_lost,
+    enable_renderer_placement_preview, enable_renderer_sky, poll_device_recovery,
+    render_embedded_frame, shadow_buffer, write_buffer_shadow,
+};
+use std::sync::Arc;
+
+fn read_buffer(device: &wgpu::Device, queue: &wgpu::Queue, buffer: &wgpu::Buffer) -> Vec<u8> {
+    let staging = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Recovery Test Readback"),
+        size: buffer.size(),
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    encoder.copy_buffer_to_buffer(buffer, 0, &staging, 0, buffer.size());
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = staging.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| {});
+    device.poll(wgpu::Maintain::Wait);
+    let bytes = slice.get_mapped_range().to_vec();
+    staging.unmap();
+    bytes
+}
+
+#[test]
+fn test_renderer_recovers_from_device_loss() {
+    let instance = wgpu::Instance::default();
+    let Some(adapter) = pollster::block_on(instance.request_adapter(&Default::default())) else {
+        eprintln!("No GPU adapter, skipping device recovery test");
+        return;
+    };
+    let (device, queue) = pollster::block_on(adapter.request_device(&Default::default(), None))
+        .expect("Failed to create device");
+    let device = Arc::new(device);
+    let queue = Arc::new(queue);
+
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Recovery Test Target"),
+        size: wgpu::Extent3d {
+            width: 64,
+            height: 64,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let mut renderer =
+        attach_renderer_to_texture(texture, device.clone(), queue.clone()).expect("attach");
+    enable_renderer_sky(&mut renderer, default_sky_config()).expect("sky");
+    enable_renderer_placement_preview(&mut renderer).expect("preview");
+    assert_eq!(render_embedded_frame(&mut renderer), Ok(true));
+
+    let shadow = create_buffer_shadow(
+        &mut renderer.recovery.shadows,
+        &device,
+        "Recovery Test Buffer",
+        wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        &[1, 2, 3, 4, 5, 6, 7, 8],
+    );
+    assert!(write_buffer_shadow(
+        &mut renderer.recovery.shadows,
+        &queue,
+        shadow,
+        4,
+        &[9, 9, 9, 9]
+    ));
+    assert!(!write_buffer_shadow(
+        &mut renderer.recovery.shadows,
+        &queue,
+        shadow,
+        6,
+        &[0; 4]
+    ));
+
+    // Simulate a GPU reset
+    device.destroy();
+    device.poll(wgpu::Maintain::Poll);
+    assert!(device_lost(&renderer.recovery.signal));
+    assert_eq!(render_embedded_frame(&mut renderer), Ok(false));
+
+    assert_eq!(poll_device_recovery(&mut renderer, &instance), Ok(true));
+    assert!(!Arc::ptr_eq(&renderer.device, &device));
+    assert!(!device_lost(&renderer.recovery.signal));
+    assert_eq!(renderer.recovery.stats.losses, 1);
+    assert_eq!(renderer.recovery.stats.recoveries, 1);
+    assert_eq!(renderer.recovery.stats.restored_bytes, 8);
+    assert!(renderer.sky.is_some() && renderer.placement_preview.is_some());
+
+    // Frames render again and the buffer holds its last written contents
+    assert_eq!(render_embedded_frame(&mut renderer), Ok(true));
+    let buffer = shadow_buffer(&renderer.recovery.shadows, shadow).expect("shadow");
+    assert_eq!(
+        read_buffer(&renderer.device, &renderer.queue, buffer),
+        vec![1, 2, 3, 4, 9, 9, 9, 9]
+    );
+    assert_eq!(poll_device_recovery(&mut renderer, &instance), Ok(false));
+}
@@ -0,0 +1,1150 @@
+//! Headless embedded mode: an engine attached to an offscreen texture runs
+//! its frames (pacing, input, adaptive quality and rendering) without a
+//! window or event loop
+
+use hearth_engine::audio::AudioSourceId;
+use hearth_engine::camera::init_camera_with_spawn;
+use hearth_engine::constants::engine_world::SAVE_CHUNK_DIRECTORY;
+use hearth_engine::constants::network_constants::FIRST_GAME_PACKET_TYPE;
+use hearth_engine::constants::persistence_constants::PIPELINE_CACHE_DIR;
+use hearth_engine::constants::{far_terrain, terrain::DRY_SEA_LEVEL};
+use hearth_engine::engine_buffers::{PhysicsFlags, AABB as BufferAABB};
+use hearth_engine::gpu::automation::{
+    BindingAccess, CustomPassBinding, CustomPassDescriptor, CustomPassDispatch, CustomPassResource,
+    CustomPassStage,
+};
+use hearth_engine::network::{self, LockstepInput, LockstepSimulation, LockstepTick, SendPriority};
+use hearth_engine::persistence::chunk_save_path;
+use hearth_engine::physics::PlayerEntityEvent;
+use hearth_engine::renderer::{
+    pipeline_cache_key, pipeline_cache_path, PipelineCacheStatus, GHOST_MAX_VERTEX_COUNT,
+};
+use hearth_engine::world::blocks::block_data::BlockProperties;
+use hearth_engine::world::blocks::{register_block_orientation, BlockModel, OrientationMode};
+use hearth_engine::world::core::{BlockId, PhysicsProperties, RenderData, VoxelPos};
+use hearth_engine::world::generation::{
+    default_superflat_config, FarSurface, SuperflatConfig, SuperflatLayer, WorldPreset,
+};
+use hearth_engine::world::storage::{inspected_voxel_at, InspectedVoxelSource};
+use hearth_engine::world::voxel_to_chunk_pos;
+use hearth_engine::world::world_operations::get_block;
+use hearth_engine::world_random_data::WorldRandomData;
+use hearth_engine::world_random_operations::create_world_random;
+use hearth_engine::{Engine, EngineConfig, WorldGeneratorType};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use winit::event::WindowEvent;
+
+mod common;
+
+/// Run frames until every chunk in the view distance came back from the
+/// generation jobs on the thread pool
+fn frame_until_streamed(engine: &mut Engine) {
+    engine.frame(&[]);
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while engine.world_stats().chunks_pending > 0 && Instant::now() < deadline {
+        engine.frame(&[]);
+    }
+}
+
+#[test]
+fn test_embedded_engine_renders_frames_headless() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping embedded engine test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+
+    let first = engine.frame(&[]);
+    assert_eq!(first.frame_number, 0);
+    assert_eq!(first.delta_time, 0.0);
+    assert!(first.rendered, "first frame: {:?}", first.error);
+    assert_eq!(first.target_size, (64, 64));
+
+    let second = engine.frame(&[WindowEvent::CloseRequested]);
+    assert_eq!(second.frame_number, 1);
+    assert!(second.rendered, "second frame: {:?}", second.error);
+    assert!(second.exit_requested);
+    assert!(engine.frame_pacing_stats().frames >= 2);
+}
+
+#[test]
+fn test_feature_toggle_applies_at_the_next_frame() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping feature flag test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 1,
+        vsync: false,
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    let cloud_shadows = |engine: &mut Engine| {
+        engine
+            .renderer_mut()
+            .and_then(|renderer| renderer.clouds.as_ref())
+            .map(|clouds| clouds.shadows_enabled)
+    };
+    engine.frame(&[]);
+    assert_eq!(cloud_shadows(&mut engine), Some(true));
+
+    // Queued by the console, applied (with its hook) by the frame
+    engine.run_console_command("feature off shadows");
+    assert_eq!(cloud_shadows(&mut engine), Some(true));
+    engine.frame(&[]);
+    assert_eq!(cloud_shadows(&mut engine), Some(false));
+    let metrics = engine.buffers().read().metrics.feature_flags.clone();
+    assert!(!metrics.enabled.iter().any(|flag| flag == "shadows"));
+    assert!(metrics.changes_applied >= 1);
+
+    engine.run_console_command("feature on shadows");
+    engine.frame(&[]);
+    assert_eq!(cloud_shadows(&mut engine), Some(true));
+}
+
+#[test]
+fn test_preset_generator_type_streams_superflat_chunks() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping superflat preset test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+            default_superflat_config(),
+        )),
+        ..EngineConfig::default()
+    };
+    let chunk_size = config.chunk_size;
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+
+    frame_until_streamed(&mut engine);
+    assert!(engine.world_stats().chunks_generated > 0);
+    let block = |y| get_block(engine.world(), VoxelPos::new(5, y, -3), chunk_size);
+    assert_eq!(block(0), BlockId::BEDROCK);
+    assert_eq!(block(20), BlockId::STONE);
+    assert_eq!(block(44), BlockId::GRASS);
+    assert_eq!(block(46), BlockId::AIR);
+}
+
+#[test]
+fn test_loaded_chunks_are_meshed_from_the_frame_arena() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping GPU world test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+            default_superflat_config(),
+        )),
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+
+    // Meshing in the last frame is published when the next one starts
+    frame_until_streamed(&mut engine);
+    engine.frame(&[]);
+    let Some(stats) = engine.gpu_world_stats() else {
+        eprintln!("No VERTEX_WRITABLE_STORAGE, skipping GPU world test");
+        return;
+    };
+    assert!(stats.chunks_uploaded > 0);
+    assert!(stats.chunks_meshed > 0);
+    let arena = engine.buffers().read().metrics.frame_arena;
+    assert!(
+        arena.allocations >= 2,
+        "mesh requests and tint data: {:?}",
+        arena
+    );
+    let uploads = engine.buffers().read().metrics.chunk_uploads;
+    assert!(uploads.chunks_uploaded > 0);
+    assert!(uploads.flushes > 0);
+
+    // Edits of uploaded chunks go through the chunk modifier, along with
+    // the decorations placed since the last sync
+    let decorated = engine.decoration_stats().blocks_placed;
+    engine
+        .set_block(VoxelPos::new(1, 1, 1), BlockId::STONE, 0)
+        .expect("set block");
+    engine.frame(&[]);
+    let edited = engine.gpu_world_stats().expect("gpu world");
+    assert_eq!(edited.blocks_modified, decorated + 1);
+}
+
+#[test]
+fn test_registered_custom_pass_is_encoded_after_uploads() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping custom pass test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    if engine.gpu_world_stats().is_none() {
+        eprintln!("No VERTEX_WRITABLE_STORAGE, skipping custom pass test");
+        return;
+    }
+
+    let source = "@group(0) @binding(0) var<storage, read> world: array<u32>;\n\
+                  @group(0) @binding(1) var<storage, read_write> seen: array<atomic<u32>>;\n\
+                  @compute @workgroup_size(1) fn count_chunks() {\n\
+                      atomicAdd(&seen[0], 1u + (world[0] & 0u));\n\
+                  }";
+    engine
+        .register_custom_pass(CustomPassDescriptor {
+            name: "count_chunks".to_string(),
+            wgsl_source: source.to_string(),
+            entry_point: "count_chunks".to_string(),
+            stage: CustomPassStage::PostGeneration,
+            bindings: vec![
+                CustomPassBinding {
+                    binding: 0,
+                    access: BindingAccess::ReadOnly,
+                    resource: CustomPassResource::WorldVoxels,
+                },
+                CustomPassBinding {
+                    binding: 1,
+                    access: BindingAccess::ReadWrite,
+                    resource: CustomPassResource::Owned { size: 4 },
+                },
+            ],
+            dispatch: CustomPassDispatch::PerChunk,
+        })
+        .expect("register custom pass");
+
+    frame_until_streamed(&mut engine);
+    let stats = engine.gpu_world_stats().expect("gpu world");
+    assert!(stats.chunks_uploaded > 0);
+    assert!(stats.custom_passes_encoded >= 1, "{:?}", stats);
+}
+
+#[test]
+fn test_chunks_sealed_below_the_ground_are_cave_culled() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping cave culling test");
+        return;
+    };
+    // Solid from bedrock up to y = 70: the camera chunk (y = 2) looks down
+    // onto the ground of chunk y = 1, which seals off chunk y = 0
+    let layer = |block, thickness| SuperflatLayer { block, thickness };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(SuperflatConfig {
+            layers: vec![layer(BlockId::BEDROCK, 1), layer(BlockId::STONE, 70)],
+            ..default_superflat_config()
+        })),
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    if engine.gpu_world_stats().is_none() {
+        eprintln!("No VERTEX_WRITABLE_STORAGE, skipping cave culling test");
+        return;
+    }
+    engine.set_camera(&init_camera_with_spawn(cgmath::Point3::new(
+        25.0, 120.0, 25.0,
+    )));
+
+    // Connectivity is read back a few frames after the chunks are uploaded
+    let mut stats = engine.gpu_world_stats().expect("gpu world");
+    for _ in 0..60 {
+        engine.frame(&[]);
+        stats = engine.gpu_world_stats().expect("gpu world");
+        if stats.chunks_cave_culled > 0 {
+            break;
+        }
+    }
+    assert!(stats.chunks_meshed > 0);
+    assert!(stats.chunks_cave_culled > 0, "{:?}", stats);
+}
+
+#[test]
+fn test_chunks_next_to_a_finer_band_are_meshed_with_skirts() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping LOD stitching test");
+        return;
+    };
+    // Ground at y = 71, inside chunk y = 1 like the camera
+    let layer = |block, thickness| SuperflatLayer { block, thickness };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(SuperflatConfig {
+            layers: vec![layer(BlockId::BEDROCK, 1), layer(BlockId::STONE, 70)],
+            ..default_superflat_config()
+        })),
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    if engine.gpu_world_stats().is_none() {
+        eprintln!("No VERTEX_WRITABLE_STORAGE, skipping LOD stitching test");
+        return;
+    }
+    engine.set_camera(&init_camera_with_spawn(cgmath::Point3::new(
+        25.0, 80.0, 25.0,
+    )));
+
+    // Ground chunks around the camera chunk fall in the coarser band and
+    // border its full-detail ground
+    let mut stats = engine.gpu_world_stats().expect("gpu world");
+    for _ in 0..60 {
+        engine.frame(&[]);
+        stats = engine.gpu_world_stats().expect("gpu world");
+        if stats.chunks_stitched > 0 {
+            break;
+        }
+    }
+    assert!(stats.chunks_stitched > 0, "{:?}", stats);
+}
+
+#[test]
+fn test_chunks_that_drew_are_decorated() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping decoration test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+            default_superflat_config(),
+        )),
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    if engine.gpu_world_stats().is_none() {
+        eprintln!("No VERTEX_WRITABLE_STORAGE, skipping decoration test");
+        return;
+    }
+    engine.set_camera(&init_camera_with_spawn(cgmath::Point3::new(
+        25.0, 60.0, 25.0,
+    )));
+
+    // Chunks join the queue once meshed and are decorated after the delay
+    let started = std::time::Instant::now();
+    while engine.decoration_stats().chunks_finished == 0
+        && started.elapsed() < std::time::Duration::from_secs(20)
+    {
+        engine.frame(&[]);
+    }
+    let stats = engine.decoration_stats();
+    assert!(stats.chunks_finished > 0, "{:?}", stats);
+    assert!(stats.blocks_placed > 0, "{:?}", stats);
+}
+
+#[test]
+fn test_props_stream_around_the_camera() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping prop streaming test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+            default_superflat_config(),
+        )),
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    if engine.gpu_world_stats().is_none() {
+        eprintln!("No VERTEX_WRITABLE_STORAGE, skipping prop streaming test");
+        return;
+    }
+    engine.set_camera(&init_camera_with_spawn(cgmath::Point3::new(
+        25.0, 60.0, 25.0,
+    )));
+
+    // Cells over chunks that were not loaded yet regenerate once they are
+    let mut stats = engine.gpu_world_stats().expect("gpu world");
+    for _ in 0..30 {
+        engine.frame(&[]);
+        stats = engine.gpu_world_stats().expect("gpu world");
+        if stats.prop_instances > 0 {
+            break;
+        }
+    }
+    assert!(stats.prop_instances > 0, "{:?}", stats);
+}
+
+#[test]
+fn test_smooth_terrain_follows_edits() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping smooth terrain test");
+        return;
+    };
+    let superflat = default_superflat_config();
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        smooth_terrain: true,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(superflat.clone())),
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    if engine.gpu_world_stats().is_none() {
+        eprintln!("No VERTEX_WRITABLE_STORAGE, skipping smooth terrain test");
+        return;
+    }
+    engine.set_camera(&init_camera_with_spawn(cgmath::Point3::new(
+        25.0, 60.0, 25.0,
+    )));
+
+    // The grass and dirt of the loaded chunks get a smooth surface
+    let mut stats = engine.gpu_world_stats().expect("gpu world");
+    for _ in 0..60 {
+        engine.frame(&[]);
+        stats = engine.gpu_world_stats().expect("gpu world");
+        if stats.smooth_chunks > 0 {
+            break;
+        }
+    }
+    assert!(stats.smooth_chunks > 0, "{:?}", stats);
+
+    // Once the queue and the decorations settle, digging out the grass
+    // rebuilds the field of its chunk
+    let mut settled = 0;
+    for _ in 0..200 {
+        engine.frame(&[]);
+        let next = engine.gpu_world_stats().expect("gpu world");
+        settled = if next == stats { settled + 1 } else { 0 };
+        stats = next;
+        if settled == 5 {
+            break;
+        }
+    }
+    assert_eq!(settled, 5, "{:?}", stats);
+    let grass_y = superflat
+        .layers
+        .iter()
+        .map(|layer| layer.thickness as i32)
+        .sum::<i32>()
+        - 1;
+    engine
+        .set_block(VoxelPos::new(25, grass_y, 25), BlockId::AIR, 0)
+        .expect("set block");
+    engine.frame(&[]);
+    let edited = engine.gpu_world_stats().expect("gpu world");
+    assert!(
+        edited.smooth_chunks_tessellated > stats.smooth_chunks_tessellated,
+        "{:?}",
+        edited
+    );
+}
+
+#[test]
+fn test_entities_are_banded_around_the_camera_each_frame() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping entity LOD test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    engine.set_camera(&init_camera_with_spawn(cgmath::Point3::new(0.0, 60.0, 0.0)));
+    {
+        let mut buffers = engine.buffers().write();
+        let positions = vec![[4.0, 60.0, 0.0], [3000.0, 60.0, 0.0]];
+        buffers.transforms.previous_positions = positions.clone();
+        buffers.transforms.current_positions = positions;
+        buffers.transforms.previous_rotations = vec![[0.0, 0.0, 0.0, 1.0]; 2];
+        buffers.transforms.current_rotations = vec![[0.0, 0.0, 0.0, 1.0]; 2];
+    }
+
+    // One entity next to the camera, one past the impostor band
+    engine.frame(&[]);
+    let lod = engine.buffers().read().metrics.entity_lod;
+    assert_eq!(lod.band_counts, [1, 0, 0, 1]);
+    let renderer = engine.renderer_mut().expect("renderer");
+    assert_eq!(renderer.entity_lod.draws.len(), 1);
+}
+
+#[test]
+fn test_far_terrain_ring_starts_at_the_render_distance() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping far terrain test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        ..EngineConfig::default()
+    };
+    let inner_radius = (config.render_distance * config.chunk_size) as f32;
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    engine.set_camera(&init_camera_with_spawn(cgmath::Point3::new(
+        25.0, 60.0, 25.0,
+    )));
+
+    // The heightmap is generated around the camera and the ring drawn in
+    // the multisampled main pass
+    let result = engine.frame(&[]);
+    assert!(result.rendered, "{:?}", result.error);
+    let renderer = engine.renderer_mut().expect("renderer");
+    let far_terrain = renderer.far_terrain.as_ref().expect("far terrain");
+    assert_eq!(far_terrain.config.inner_radius, inner_radius);
+    assert!(far_terrain.heightmap_center.is_some());
+    // The reference terrain has no water to flood the horizon with
+    assert_eq!(far_terrain.uniform.sea_level, DRY_SEA_LEVEL);
+    assert_eq!(
+        far_terrain.uniform.surface_mode,
+        far_terrain::SURFACE_REFERENCE
+    );
+}
+
+#[test]
+fn test_far_terrain_ring_follows_the_preset_surface() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping far terrain preset test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+            default_superflat_config(),
+        )),
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    engine.set_camera(&init_camera_with_spawn(cgmath::Point3::new(
+        25.0, 60.0, 25.0,
+    )));
+
+    // The horizon is the superflat ground, not the reference hills
+    engine.frame(&[]);
+    let renderer = engine.renderer_mut().expect("renderer");
+    let far_terrain = renderer.far_terrain.as_ref().expect("far terrain");
+    assert!(far_terrain.heightmap_center.is_some());
+    assert_eq!(far_terrain.uniform.surface_mode, far_terrain::SURFACE_FLAT);
+    assert_eq!(far_terrain.uniform.flat_height, 44.0);
+    assert_eq!(
+        far_terrain.generated_surface,
+        FarSurface::Flat { height: 44 }
+    );
+}
+
+#[test]
+fn test_block_atlas_streams_in_from_the_frame() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping texture streaming test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    engine.set_camera(&init_camera_with_spawn(cgmath::Point3::new(
+        25.0, 60.0, 25.0,
+    )));
+
+    // The atlas uploads its coarsest mips in the first frame; the GPU
+    // allocation tracker counts them in the memory the monitor budgets
+    engine.frame(&[]);
+    engine.frame(&[]);
+    let metrics = engine.buffers().read().metrics.clone();
+    assert_eq!(metrics.texture_streaming.textures, 1);
+    assert!(metrics.texture_streaming.resident_bytes > 0);
+    assert!(metrics.gpu_memory_usage >= metrics.texture_streaming.resident_bytes);
+    assert!(metrics.gpu_allocations.allocations > 0);
+}
+
+#[test]
+fn test_startup_pipelines_are_timed_and_the_cache_saved_on_drop() {
+    let Some(mut renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping pipeline cache test");
+        return;
+    };
+    // A texture host knows its adapter only if it says so
+    let instance = wgpu::Instance::default();
+    let adapter_info = pollster::block_on(instance.request_adapter(&Default::default()))
+        .map(|adapter| adapter.get_info())
+        .expect("adapter");
+    renderer.adapter_info = Some(adapter_info.clone());
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+
+    engine.frame(&[]);
+    let stats = engine.buffers().read().metrics.pipeline_cache;
+    assert_ne!(stats.status, PipelineCacheStatus::Cold);
+    assert!(stats.pipelines_created > 0);
+
+    drop(engine);
+    let path = pipeline_cache_path(
+        &pipeline_cache_key(&adapter_info),
+        std::path::Path::new(PIPELINE_CACHE_DIR),
+    );
+    assert!(path.exists(), "{}", path.display());
+}
+
+#[test]
+fn test_held_light_is_previewed_where_it_would_be_placed() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping light preview test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+            default_superflat_config(),
+        )),
+        ..EngineConfig::default()
+    };
+    let chunk_size = config.chunk_size;
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    let lantern = engine.register_block(
+        "test:lantern",
+        BlockProperties {
+            id: BlockId::AIR,
+            name: "lantern".to_string(),
+            is_solid: true,
+            is_transparent: false,
+            transparent: false,
+            light_emission: 15,
+            physics_enabled: true,
+            physics: PhysicsProperties {
+                solid: true,
+                density: 1000.0,
+                friction: 0.6,
+                restitution: 0.0,
+                speed_multiplier: 1.0,
+            },
+            render_data: RenderData {
+                color: [1.0, 0.8, 0.5],
+                texture_id: 0,
+                light_emission: 15,
+            },
+            hardness: 0.5,
+            flammable: false,
+            blast_resistance: 1.0,
+        },
+    );
+    engine.set_held_block(Some(lantern));
+    let mut camera = init_camera_with_spawn(cgmath::Point3::new(5.5, 60.0, -3.5));
+    camera.pitch_radians = -1.5;
+    engine.set_camera(&camera);
+
+    // The preview follows once the ground below the camera is loaded
+    let mut previewed = None;
+    for _ in 0..100 {
+        let result = engine.frame(&[]);
+        assert!(result.rendered, "{:?}", result.error);
+        let renderer = engine.renderer_mut().expect("renderer");
+        let preview = renderer.light_preview.as_ref().expect("light preview");
+        previewed = preview.previewed;
+        if previewed.is_some() {
+            break;
+        }
+    }
+    let previewed = previewed.expect("the held light is previewed");
+    assert_eq!(previewed.emission, 15);
+    let target = previewed.target;
+    assert_eq!(get_block(engine.world(), target, chunk_size), BlockId::AIR);
+    let below = VoxelPos::new(target.x, target.y - 1, target.z);
+    assert_ne!(get_block(engine.world(), below, chunk_size), BlockId::AIR);
+
+    // Blocks that emit nothing have no preview
+    engine.set_held_block(Some(BlockId::STONE));
+    engine.frame(&[]);
+    let renderer = engine.renderer_mut().expect("renderer");
+    assert!(renderer
+        .light_preview
+        .as_ref()
+        .expect("light preview")
+        .previewed
+        .is_none());
+}
+
+#[test]
+fn test_held_block_ghost_takes_its_model() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping placement preview test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+            default_superflat_config(),
+        )),
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    register_block_orientation(
+        engine.block_schemas_mut(),
+        BlockId::COBBLESTONE,
+        OrientationMode::Stairs,
+    )
+    .expect("stairs");
+    engine.set_held_block(Some(BlockId::COBBLESTONE));
+    let mut camera = init_camera_with_spawn(cgmath::Point3::new(5.5, 60.0, -3.5));
+    camera.pitch_radians = -1.5;
+    engine.set_camera(&camera);
+    frame_until_streamed(&mut engine);
+    engine.frame(&[]);
+
+    let renderer = engine.renderer_mut().expect("renderer");
+    let ghost = renderer
+        .placement_preview
+        .as_ref()
+        .expect("placement preview");
+    let preview = ghost.preview.expect("the held block is previewed");
+    assert_eq!(preview.block, BlockId::COBBLESTONE);
+    assert_eq!(preview.model, BlockModel::Stairs);
+    assert_eq!(ghost.vertex_count as usize, GHOST_MAX_VERTEX_COUNT);
+}
+
+#[test]
+fn test_inspect_command_reads_back_voxels_from_the_gpu_world() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping voxel inspector test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+            default_superflat_config(),
+        )),
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    engine.set_camera(&init_camera_with_spawn(cgmath::Point3::new(
+        5.0, 60.0, -3.0,
+    )));
+    assert!(engine
+        .run_console_command("inspect 5 44")
+        .starts_with("Usage"));
+    assert!(engine
+        .run_console_command("teleport 0 0 0")
+        .starts_with("Unknown command"));
+
+    // Asked again until the grass chunk has been uploaded
+    let center = VoxelPos::new(5, 44, -3);
+    let mut grass = None;
+    for frame in 0..200 {
+        if frame % 10 == 0 {
+            let output = engine.run_console_command("inspect 5 44 -3 1");
+            assert!(output.contains("requested"), "{}", output);
+        }
+        engine.frame(&[]);
+        grass = engine
+            .voxel_inspection()
+            .and_then(|inspection| inspected_voxel_at(inspection, center))
+            .filter(|voxel| voxel.source != InspectedVoxelSource::NotResident)
+            .copied();
+        if grass.is_some() {
+            break;
+        }
+    }
+    let grass = grass.expect("the inspection reads back a resident voxel");
+    assert_eq!(grass.voxel.block_id(), BlockId::GRASS.0);
+    assert_eq!(
+        engine.voxel_inspection().expect("inspection").voxels.len(),
+        27
+    );
+}
+
+#[test]
+fn test_spectator_path_plays_from_the_console() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping spectator test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    // Without a camera there is nothing to start from
+    engine.run_console_command("spectator free");
+    assert!(engine.spectator().is_none());
+
+    // Keyframes are taken from the engine camera
+    let start = init_camera_with_spawn(cgmath::Point3::new(0.0, 60.0, 0.0));
+    let end = init_camera_with_spawn(cgmath::Point3::new(40.0, 60.0, 0.0));
+    engine.set_camera(&start);
+    engine.run_console_command("spectator path new flyby");
+    engine.run_console_command("spectator path key flyby 0");
+    engine.set_camera(&end);
+    engine.run_console_command("spectator path key flyby 600");
+    engine.set_camera(&start);
+    engine.run_console_command("spectator path play flyby");
+    assert!(engine.spectator().is_some());
+
+    engine.frame(&[]);
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    engine.frame(&[]);
+    let x = engine.camera().expect("camera").position.x;
+    assert!(x > 0.0 && x < 40.0, "camera at x = {}", x);
+
+    // Handing the camera back leaves it where playback stopped
+    engine.run_console_command("spectator off");
+    assert!(engine.spectator().is_none());
+    engine.frame(&[]);
+    assert_eq!(engine.camera().expect("camera").position.x, x);
+}
+
+#[test]
+fn test_player_moves_against_physics_entities() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping player collision test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    {
+        let mut buffers = engine.buffers().write();
+        let physics = &mut buffers.physics;
+        // A platform moving along x and a crate on top of it, far above
+        // the generated ground
+        physics.positions = vec![[0.0, 396.0, 0.0], [30.0, 408.0, 0.0]];
+        physics.velocities = vec![[10.0, 0.0, 0.0], [0.0; 3]];
+        physics.accelerations = vec![[0.0; 3]; 2];
+        physics.aabbs = vec![
+            BufferAABB {
+                min: [-100.0, 392.0, -100.0],
+                max: [100.0, 400.0, 100.0],
+            },
+            BufferAABB {
+                min: [26.0, 400.0, -4.0],
+                max: [34.0, 416.0, 4.0],
+            },
+        ];
+        physics.flags = vec![
+            PhysicsFlags {
+                is_kinematic: true,
+                is_dynamic: false,
+                ..PhysicsFlags::default()
+            },
+            PhysicsFlags::default(),
+        ];
+        physics.entity_count = 2;
+    }
+    engine.place_player([0.0, 409.0, 0.0]);
+
+    let step = engine.move_player([0.0; 3], 0.1);
+    assert!(step
+        .events
+        .contains(&PlayerEntityEvent::Landed { entity: 0 }));
+    assert_eq!(engine.player().ground_entity, Some(0));
+
+    // Riding the platform carries the player one voxel per tick
+    engine.move_player([0.0; 3], 0.1);
+    assert!((engine.player().position[0] - 1.0).abs() < 1e-3);
+
+    // Walking into the crate stops the player at its face
+    let step = engine.move_player([40.0, 0.0, 0.0], 0.1);
+    assert!((engine.player().position[0] - 22.0).abs() < 0.05);
+    assert!(step
+        .events
+        .iter()
+        .any(|event| matches!(event, PlayerEntityEvent::Blocked { entity: 1, .. })));
+
+    // Overlapping it pushes player and crate apart by mass
+    engine.place_player([23.0, 409.0, 0.0]);
+    let step = engine.move_player([0.0; 3], 0.1);
+    assert_eq!(step.pushes.len(), 1);
+    let crate_x = engine.buffers().read().physics.positions[1][0];
+    assert!(crate_x > 30.0, "crate at x = {}", crate_x);
+}
+
+#[test]
+fn test_audio_sources_are_muffled_by_the_loaded_world() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping audio occlusion test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+            default_superflat_config(),
+        )),
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    engine.set_camera(&init_camera_with_spawn(cgmath::Point3::new(
+        5.5, 50.5, -3.5,
+    )));
+    let buried = AudioSourceId(1);
+    let open = AudioSourceId(2);
+    engine.set_audio_source(buried, [5.5, 30.5, -3.5]);
+    engine.set_audio_source(open, [12.5, 50.5, -3.5]);
+
+    // The buried source is heard through the ground once it has loaded
+    frame_until_streamed(&mut engine);
+    for _ in 0..50 {
+        engine.frame(&[]);
+        if engine.audio_occlusion(buried).is_some_and(|o| o.gain < 1.0) {
+            break;
+        }
+    }
+    let buried_occlusion = engine.audio_occlusion(buried).expect("buried source");
+    assert!(buried_occlusion.blocked_voxels > 0);
+    assert!(
+        buried_occlusion.low_pass_cutoff_hz < engine.audio_propagation_mut().config.open_cutoff_hz
+    );
+    assert_eq!(engine.audio_occlusion(open).expect("open source").gain, 1.0);
+
+    // A wall placed between them muffles the cached open path
+    engine
+        .set_block(VoxelPos::new(9, 50, -4), BlockId::STONE, 0)
+        .expect("place wall");
+    engine.frame(&[]);
+    assert!(engine.audio_occlusion(open).expect("open source").gain < 1.0);
+
+    engine.remove_audio_source(buried);
+    assert!(engine.audio_occlusion(buried).is_none());
+}
+
+#[test]
+fn test_packet_capture_records_the_engine_network_session() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping packet capture test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = dir.path().join("session.hpcap");
+    network::add_network_connection(
+        engine.network_mut(),
+        network::create_connection(1, network::BandwidthConfig::default()),
+    );
+
+    let started = engine.run_console_command(&format!("netcap start {}", path.display()));
+    assert!(started.starts_with("Capturing"), "{}", started);
+    let chat = FIRST_GAME_PACKET_TYPE;
+    network::send_packet(
+        engine.network_mut(),
+        1,
+        SendPriority::BlockUpdate,
+        chat,
+        vec![7; 24],
+    )
+    .expect("queued");
+    network::receive_packet(engine.network_mut(), 1, chat, b"chat".to_vec()).expect("received");
+    // The frame releases the queued packet for the transport
+    engine.frame(&[]);
+    assert_eq!(network::take_sent_packets(engine.network_mut()).len(), 1);
+
+    let dump = engine.run_console_command("netcap dump");
+    assert!(dump.contains("2 packets"), "{}", dump);
+    assert_eq!(
+        engine.run_console_command("netcap stop"),
+        "Captured 2 packets"
+    );
+    // Sent and received chat share one packet type, one row per direction
+    let dump = engine.run_console_command("netcap dump");
+    assert_eq!(
+        dump.matches(&format!("type_{}", chat)).count(),
+        2,
+        "{}",
+        dump
+    );
+}
+
+/// Folds every tick's inputs into a counter; the peer's copy goes wrong
+/// once at `diverge_at`
+struct CounterSimulation {
+    state: u64,
+    diverge_at: Option<u64>,
+}
+
+impl LockstepSimulation for CounterSimulation {
+    fn sample_input(&mut self) -> LockstepInput {
+        vec![self.state as u8]
+    }
+
+    fn simulate(&mut self, tick: &LockstepTick, _random: &mut WorldRandomData) {
+        for (player, input) in &tick.inputs {
+            self.state = self.state.rotate_left(5) ^ u64::from(*player) ^ input.len() as u64;
+        }
+        if self.diverge_at == Some(tick.tick) {
+            self.state ^= 1;
+        }
+    }
+
+    fn encode_state(&self) -> Vec<u8> {
+        self.state.to_le_bytes().to_vec()
+    }
+
+    fn load_state(&mut self, state: &[u8]) {
+        self.state = u64::from_le_bytes(state.try_into().expect("8 byte state"));
+    }
+}
+
+fn counter_session(local: u8, diverge_at: Option<u64>) -> network::LockstepSessionData {
+    let config = network::LockstepConfig {
+        hash_interval_ticks: 10,
+        ..network::default_lockstep_config()
+    };
+    let lockstep = network::create_lockstep(config, local, 0, &[0, 1]).expect("lockstep");
+    let remote = 1 - local;
+    network::create_lockstep_session(
+        lockstep,
+        BTreeMap::from([(remote, 1)]),
+        create_world_random(7),
+        Box::new(CounterSimulation {
+            state: 0,
+            diverge_at,
+        }),
+    )
+    .expect("session")
+}
+
+/// Hand every packet `from` released to `to`, as a transport would
+fn deliver_packets(from: &mut network::NetworkData, to: &mut network::NetworkData) {
+    for sent in network::take_sent_packets(from) {
+        network::receive_packet(
+            to,
+            sent.connection_id,
+            sent.packet.priority as u16,
+            sent.packet.payload,
+        )
+        .expect("delivered");
+    }
+}
+
+#[test]
+fn test_lockstep_runs_on_the_engine_fixed_tick_and_resyncs_peers() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping lockstep test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    network::add_network_connection(
+        engine.network_mut(),
+        network::create_connection(1, network::BandwidthConfig::default()),
+    );
+    engine.start_lockstep(counter_session(0, None));
+
+    // The peer runs without an engine and goes wrong at tick 3
+    let mut peer_network = network::create_network();
+    network::add_network_connection(
+        &mut peer_network,
+        network::create_connection(1, network::BandwidthConfig::default()),
+    );
+    let mut peer = counter_session(1, Some(3));
+
+    let host_ticks = |engine: &Engine| engine.lockstep().expect("host").lockstep.stats.ticks;
+    for _ in 0..3000 {
+        engine.frame(&[]);
+        deliver_packets(engine.network_mut(), &mut peer_network);
+        network::tick_lockstep_session(&mut peer, &mut peer_network).expect("peer tick");
+        network::flush_network(&mut peer_network, 1.0 / 60.0);
+        deliver_packets(&mut peer_network, engine.network_mut());
+        if host_ticks(&engine) >= 45 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(2));
+    }
+    assert!(
+        host_ticks(&engine) >= 45,
+        "host ran {} ticks",
+        host_ticks(&engine)
+    );
+
+    // The divergence was caught once; the hashes compared after the
+    // resync all matched
+    let host = &engine.lockstep().expect("host").lockstep;
+    assert_eq!(host.stats.desyncs, 1);
+    assert_eq!(host.stats.resyncs, 1);
+    assert!(host.stats.hashes_checked >= 4, "{:?}", host.stats);
+    assert_eq!(peer.lockstep.stats.resyncs, 1);
+    assert!(!matches!(
+        peer.lockstep.status,
+        network::LockstepStatus::Desynced { .. }
+    ));
+}
+
+#[test]
+fn test_saved_chunks_come_back_with_their_light() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping saved light test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+            default_superflat_config(),
+        )),
+        ..EngineConfig::default()
+    };
+    let chunk_size = config.chunk_size;
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    let dir = tempfile::tempdir().expect("temp dir");
+    engine.open_world_save(dir.path()).expect("world save");
+    let home = init_camera_with_spawn(cgmath::Point3::new(5.5, 60.0, 5.5));
+    engine.set_camera(&home);
+    frame_until_streamed(&mut engine);
+    let edit = VoxelPos::new(4, 60, 4);
+    engine.set_block(edit, BlockId::STONE, 0).expect("edit");
+    // The edited chunk is relit before it leaves
+    for _ in 0..10 {
+        engine.frame(&[]);
+    }
+    if engine.gpu_world_stats().map_or(0, |stats| stats.chunks_lit) == 0 {
+        eprintln!("No GPU chunk lighting, skipping saved light test");
+        return;
+    }
+
+    engine.set_camera(&init_camera_with_spawn(cgmath::Point3::new(
+        (chunk_size * 20) as f32,
+        60.0,
+        5.5,
+    )));
+    frame_until_streamed(&mut engine);
+    let chunk = voxel_to_chunk_pos(edit, chunk_size);
+    let path = chunk_save_path(&dir.path().join(SAVE_CHUNK_DIRECTORY), chunk);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !path.exists() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(path.exists());
+
+    engine.set_camera(&home);
+    frame_until_streamed(&mut engine);
+    engine.frame(&[]);
+    let stats = engine.gpu_world_stats().expect("gpu world");
+    assert!(stats.chunks_light_restored > 0, "{:?}", stats);
+    assert_eq!(get_block(engine.world(), edit, chunk_size), BlockId::STONE);
+}
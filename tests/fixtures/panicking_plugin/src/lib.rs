@@ -0,0 +1,54 @@
+//! Test plugin whose `on_tick` queues an event and then panics
+
+use hearth_plugin_api::{
+    declare_plugin, plugin_str, PluginBlock, PluginEvent, PluginHostApi, PluginStr, PluginVersion,
+    PLUGIN_EVENT_CUSTOM, PLUGIN_NO_PLAYER, PLUGIN_OK,
+};
+
+fn register_block(host: &PluginHostApi) -> i32 {
+    let block = PluginBlock {
+        id: 201,
+        name: plugin_str("fixture_block"),
+        is_solid: 1,
+        is_transparent: 0,
+        is_liquid: 0,
+        can_interact: 0,
+        light_emission: 0,
+        hardness: 1.0,
+        friction: 0.6,
+        restitution: 0.0,
+        speed_multiplier: 1.0,
+    };
+    (host.register_block)(host.context, &block)
+}
+
+fn world_start(_host: &PluginHostApi, _seed: u32) -> i32 {
+    PLUGIN_OK
+}
+
+fn panic_on_tick(host: &PluginHostApi, _tick: u64, _delta_seconds: f32) -> i32 {
+    let event = PluginEvent {
+        kind: PLUGIN_EVENT_CUSTOM,
+        player_id: PLUGIN_NO_PLAYER,
+        position: [0; 3],
+        world_position: [0.0; 3],
+        id: 0,
+        metadata: 0,
+        count: 0,
+        name: plugin_str("before_panic"),
+        data: PluginStr {
+            ptr: std::ptr::null(),
+            len: 0,
+        },
+    };
+    (host.queue_event)(host.context, &event);
+    panic!("fixture plugin bug");
+}
+
+declare_plugin! {
+    name: "panicking_fixture",
+    version: PluginVersion { major: 1, minor: 0, patch: 0 },
+    on_load: register_block,
+    on_world_start: world_start,
+    on_tick: panic_on_tick,
+}
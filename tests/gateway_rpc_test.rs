@@ -0,0 +1,76 @@
+//! Gateway RPC requests are answered by the engine frame. Kept apart from
+//! the other engine tests: the RPC queue is global, and any engine's frame
+//! would answer it from its own world
+
+use hearth_engine::game::{
+    gateway_request, init_gateway_rpc, poll_gateway_response, shutdown_gateway_rpc, GatewayRequest,
+    GatewayResponse, GatewayRpcConfig, GatewayRpcError,
+};
+use hearth_engine::world::core::{BlockId, VoxelPos};
+use hearth_engine::world::generation::{default_superflat_config, WorldPreset};
+use hearth_engine::world::world_operations::get_block;
+use hearth_engine::{Engine, EngineConfig, WorldGeneratorType};
+
+mod common;
+
+#[test]
+fn test_engine_frame_answers_gateway_requests() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping gateway RPC test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+            default_superflat_config(),
+        )),
+        ..EngineConfig::default()
+    };
+    let chunk_size = config.chunk_size;
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    let ground = VoxelPos::new(2, 44, 2);
+    for _ in 0..100 {
+        engine.frame(&[]);
+        if get_block(engine.world(), ground, chunk_size) != BlockId::AIR {
+            break;
+        }
+    }
+
+    init_gateway_rpc(GatewayRpcConfig {
+        max_in_flight: 2,
+        default_timeout_ms: 60_000,
+        max_served_per_frame: 8,
+    });
+    let query = gateway_request(GatewayRequest::QueryBlockInfo { position: ground }, None)
+        .expect("query submitted");
+    let stale = gateway_request(GatewayRequest::QueryBlockInfo { position: ground }, Some(0))
+        .expect("stale query submitted");
+    // Both are still in flight
+    assert!(matches!(
+        gateway_request(GatewayRequest::QueryBlockInfo { position: ground }, None),
+        Err(GatewayRpcError::Backpressure { in_flight: 2 })
+    ));
+    assert!(poll_gateway_response(query).is_none());
+
+    engine.frame(&[]);
+    match poll_gateway_response(query) {
+        Some(Ok(GatewayResponse::BlockInfo {
+            position,
+            block_id,
+            chunk_loaded,
+            ..
+        })) => {
+            assert_eq!(position, ground);
+            assert_eq!(block_id, get_block(engine.world(), ground, chunk_size));
+            assert_ne!(block_id, BlockId::AIR);
+            assert!(chunk_loaded);
+        }
+        other => panic!("unexpected answer {:?}", other),
+    }
+    assert_eq!(
+        poll_gateway_response(stale),
+        Some(Err(GatewayRpcError::TimedOut { timeout_ms: 0 }))
+    );
+    shutdown_gateway_rpc();
+}
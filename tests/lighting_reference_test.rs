@@ -0,0 +1,45 @@
+//! Light the lighting fixtures on the GPU and diff them voxel by voxel
+//! against the CPU reference.
+//!
+//! Set HEARTH_LIGHT_TOLERANCE=<levels> to accept small level differences
+//! per voxel, and HEARTH_LIGHT_MAX_MISMATCHES=<voxels> to accept a few
+//! voxels beyond that. Skipped when no GPU adapter is available.
+
+use hearth_engine::world::compute::GpuLightFixtureRunner;
+use hearth_engine::world::lighting::{
+    default_light_fixtures, format_light_report, light_harness_passed, light_tolerance_from_env,
+    run_light_harness,
+};
+use std::sync::Arc;
+
+#[test]
+fn test_gpu_lighting_matches_cpu_reference() {
+    let instance = wgpu::Instance::default();
+    let Some(adapter) = pollster::block_on(instance.request_adapter(&Default::default())) else {
+        eprintln!("No GPU adapter, skipping the lighting harness");
+        return;
+    };
+    let (device, queue) = pollster::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: Some("Lighting Harness Device"),
+            required_features: wgpu::Features::empty(),
+            required_limits: wgpu::Limits::default(),
+        },
+        None,
+    ))
+    .expect("device");
+    let runner = GpuLightFixtureRunner::new(Arc::new(device), Arc::new(queue))
+        .expect("light fixture runner");
+
+    let report = run_light_harness(
+        &default_light_fixtures(),
+        &light_tolerance_from_env(),
+        |fixture| runner.light_fixture(fixture),
+    );
+    println!("{}", format_light_report(&report));
+    assert!(
+        light_harness_passed(&report),
+        "GPU lighting drifted from the CPU reference:\n{}",
+        format_light_report(&report)
+    );
+}
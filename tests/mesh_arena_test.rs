@@ -0,0 +1,105 @@
+//! Mesh arena defragmentation moves live meshes down over the holes and
+//! patches their indirect draws in the same submission
+
+use hearth_engine::gpu::buffer_layouts::IndirectDrawIndexedCommand;
+use hearth_engine::renderer::gpu_meshing::{
+    allocate_mesh, create_mesh_arena, create_mesh_index_arena, defragment_mesh_arena, free_mesh,
+    DefragBudget, MeshArena,
+};
+use hearth_engine::world::core::ChunkPos;
+use std::time::Duration;
+
+mod common;
+
+const COMMAND_SIZE: u64 = std::mem::size_of::<IndirectDrawIndexedCommand>() as u64;
+
+fn read_buffer(device: &wgpu::Device, queue: &wgpu::Queue, buffer: &wgpu::Buffer) -> Vec<u8> {
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Mesh Arena Test Readback"),
+        size: buffer.size(),
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&Default::default());
+    encoder.copy_buffer_to_buffer(buffer, 0, &readback, 0, buffer.size());
+    queue.submit(std::iter::once(encoder.finish()));
+    readback.slice(..).map_async(wgpu::MapMode::Read, |_| {});
+    device.poll(wgpu::Maintain::Wait);
+    let bytes = readback.slice(..).get_mapped_range().to_vec();
+    readback.unmap();
+    bytes
+}
+
+/// Three meshes of `sizes` bytes filled with 1, 2 and 3; the middle one is
+/// freed and the arena compacted. Returns the arena bytes and draws after.
+fn compact_three_meshes(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    mut arena: MeshArena,
+    sizes: [u64; 3],
+) -> (MeshArena, Vec<u8>, Vec<IndirectDrawIndexedCommand>) {
+    let chunks = [
+        ChunkPos::new(0, 0, 0),
+        ChunkPos::new(1, 0, 0),
+        ChunkPos::new(2, 0, 0),
+    ];
+    for (draw, (chunk, size)) in chunks.iter().zip(sizes).enumerate() {
+        let offset = allocate_mesh(&mut arena, *chunk, size, draw as u32).expect("allocate");
+        queue.write_buffer(&arena.buffer, offset, &vec![draw as u8 + 1; size as usize]);
+    }
+    assert!(free_mesh(&mut arena, chunks[1]));
+    assert!(arena.stats.fragmentation > 0.0);
+
+    let draws = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Mesh Arena Test Draws"),
+        size: 3 * COMMAND_SIZE,
+        usage: wgpu::BufferUsages::INDIRECT
+            | wgpu::BufferUsages::COPY_SRC
+            | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&Default::default());
+    let budget = DefragBudget {
+        max_bytes: u64::MAX,
+        max_time: Duration::from_secs(1),
+    };
+    let step = defragment_mesh_arena(&mut arena, &mut encoder, queue, &draws, budget);
+    queue.submit(std::iter::once(encoder.finish()));
+    assert_eq!(step.meshes_moved, 1);
+    assert!(step.complete);
+    assert_eq!(arena.stats.fragmentation, 0.0);
+
+    let bytes = read_buffer(device, queue, &arena.buffer);
+    let commands = bytemuck::cast_slice(&read_buffer(device, queue, &draws)).to_vec();
+    (arena, bytes, commands)
+}
+
+#[test]
+fn test_vertex_arena_defrag_moves_meshes_and_patches_base_vertex() {
+    let Some((device, queue)) = common::create_test_device() else {
+        eprintln!("No GPU adapter, skipping mesh arena test");
+        return;
+    };
+    let arena = create_mesh_arena(&device, 4096, 64);
+    let (_, bytes, commands) = compact_three_meshes(&device, &queue, arena, [128, 256, 192]);
+
+    // The last mesh slid down to vertex 2, right after the first one
+    assert!(bytes[..128].iter().all(|byte| *byte == 1));
+    assert!(bytes[128..320].iter().all(|byte| *byte == 3));
+    assert_eq!(commands[2].base_vertex, 2);
+    assert_eq!(commands[2].first_index, 0);
+}
+
+#[test]
+fn test_index_arena_defrag_patches_first_index() {
+    let Some((device, queue)) = common::create_test_device() else {
+        eprintln!("No GPU adapter, skipping mesh arena test");
+        return;
+    };
+    let arena = create_mesh_index_arena(&device, 4096);
+    let (_, bytes, commands) = compact_three_meshes(&device, &queue, arena, [24, 12, 36]);
+
+    assert!(bytes[24..60].iter().all(|byte| *byte == 3));
+    assert_eq!(commands[2].first_index, 6);
+    assert_eq!(commands[2].base_vertex, 0);
+}
@@ -0,0 +1,141 @@
+//! Native plugins run from the engine frame: the plugin directory is loaded
+//! at startup, `on_tick` runs every frame and what plugins queue reaches
+//! the game gateway, through the same claim checks as player edits. Kept
+//! apart from the other engine tests because the gateway is global.
+
+use hearth_engine::game::{
+    get_metrics, init_gateway, is_gateway_initialized, run_gateway_claim_command,
+    with_gateway_claims, BlockAction,
+};
+use hearth_engine::plugin::{
+    plugin_hook, plugin_str, register_static_plugin, PluginDescriptor, PluginEvent, PluginHostApi,
+    PluginState, PluginStr, PluginVersion, ENGINE_PLUGIN_VERSION, PLUGIN_ABI_VERSION,
+};
+use hearth_engine::world::core::{BlockId, VoxelPos};
+use hearth_engine::world::world_operations::get_block;
+use hearth_engine::{Engine, EngineConfig};
+use hearth_plugin_api::{PLUGIN_EVENT_BLOCK_PLACE, PLUGIN_EVENT_CUSTOM, PLUGIN_NO_PLAYER};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+mod common;
+
+static TICKS: AtomicU64 = AtomicU64::new(0);
+
+fn queue_tick_event(host: &PluginHostApi, tick: u64, _dt: f32) -> i32 {
+    TICKS.fetch_add(1, Ordering::Relaxed);
+    let bytes = tick.to_le_bytes();
+    let event = PluginEvent {
+        kind: PLUGIN_EVENT_CUSTOM,
+        player_id: PLUGIN_NO_PLAYER,
+        position: [0; 3],
+        world_position: [0.0; 3],
+        id: 0,
+        metadata: 0,
+        count: 0,
+        name: plugin_str("tick"),
+        data: PluginStr {
+            ptr: bytes.as_ptr(),
+            len: bytes.len(),
+        },
+    };
+    (host.queue_event)(host.context, &event)
+}
+
+/// Places glass for player 5 inside the claim the test creates
+fn place_for_player(host: &PluginHostApi, _tick: u64, _dt: f32) -> i32 {
+    let event = PluginEvent {
+        kind: PLUGIN_EVENT_BLOCK_PLACE,
+        player_id: 5,
+        position: [2, 44, 2],
+        world_position: [0.0; 3],
+        id: BlockId::GLASS.0,
+        metadata: 0,
+        count: 0,
+        name: plugin_str(""),
+        data: PluginStr {
+            ptr: std::ptr::null(),
+            len: 0,
+        },
+    };
+    (host.queue_event)(host.context, &event)
+}
+
+fn panic_on_tick(_host: &PluginHostApi, _tick: u64, _dt: f32) -> i32 {
+    panic!("plugin bug");
+}
+
+fn descriptor(name: &'static str) -> PluginDescriptor {
+    PluginDescriptor {
+        abi_version: PLUGIN_ABI_VERSION,
+        name: plugin_str(name),
+        version: PluginVersion {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        },
+        engine_version: ENGINE_PLUGIN_VERSION,
+        on_load: None,
+        on_world_start: None,
+        on_tick: None,
+        on_unload: None,
+    }
+}
+
+#[test]
+fn test_engine_frame_ticks_plugins_into_the_gateway() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping plugin test");
+        return;
+    };
+    // A directory without libraries loads no plugins
+    let plugin_dir = tempfile::tempdir().expect("temp dir");
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        plugin_dir: Some(plugin_dir.path().to_path_buf()),
+        ..EngineConfig::default()
+    };
+    let chunk_size = config.chunk_size;
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    assert!(engine.plugins().plugins.is_empty());
+
+    init_gateway();
+    assert!(is_gateway_initialized());
+    let ticking = PluginDescriptor {
+        on_tick: Some(plugin_hook!(on_tick, queue_tick_event)),
+        ..descriptor("ticking")
+    };
+    let faulty = PluginDescriptor {
+        on_tick: Some(plugin_hook!(on_tick, panic_on_tick)),
+        ..descriptor("faulty")
+    };
+    register_static_plugin(engine.plugins_mut(), ticking).expect("compatible plugin");
+    register_static_plugin(engine.plugins_mut(), faulty).expect("compatible plugin");
+
+    engine.frame(&[]);
+    engine.frame(&[]);
+
+    // The panicking plugin is disabled; the other keeps ticking and its
+    // events are processed by the gateway in the same frame
+    assert_eq!(TICKS.load(Ordering::Relaxed), 2);
+    let plugins = &engine.plugins().plugins;
+    assert!(matches!(plugins[1].state, PluginState::Failed(_)));
+    assert_eq!(plugins[0].state, PluginState::Active);
+    assert!(engine.plugins().outbox.events.is_empty());
+    assert_eq!(get_metrics().events_processed, 2);
+
+    // A plugin editing for a player is held to that player's claims
+    let created = run_gateway_claim_command(None, "claim create keep 0 40 0 8 48 8 player:1");
+    assert!(matches!(created, Some(Ok(_))), "{:?}", created);
+    let position = VoxelPos::new(2, 44, 2);
+    let before = get_block(engine.world(), position, chunk_size);
+    let builder = PluginDescriptor {
+        on_tick: Some(plugin_hook!(on_tick, place_for_player)),
+        ..descriptor("builder")
+    };
+    register_static_plugin(engine.plugins_mut(), builder).expect("compatible plugin");
+    engine.frame(&[]);
+    let denied = with_gateway_claims(|claims| claims.stats.denied[BlockAction::Place as usize]);
+    assert_eq!(denied, Some(1));
+    assert_eq!(get_block(engine.world(), position, chunk_size), before);
+}
@@ -0,0 +1,60 @@
+//! A plugin built as its own dynamic library, whose hook panics, is
+//! disabled instead of taking the engine down. The fixture crate is built
+//! with the same cargo into the test's temporary target directory.
+
+use hearth_engine::plugin::{
+    create_plugin_host, drain_plugin_outbox, find_plugin, load_plugin, plugins_tick,
+    plugins_world_start, unload_plugins, PluginError, PluginState,
+};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn build_fixture_plugin() -> PathBuf {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("panicking_plugin")
+        .join("Cargo.toml");
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("plugin_fixtures");
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--quiet", "--offline", "--manifest-path"])
+        .arg(&manifest)
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .expect("Failed to run cargo for the fixture plugin");
+    assert!(status.success(), "Fixture plugin failed to build");
+    target_dir.join("debug").join(format!(
+        "{}panicking_plugin.{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_EXTENSION
+    ))
+}
+
+#[test]
+fn test_panicking_library_plugin_is_disabled() {
+    let library = build_fixture_plugin();
+    let mut host = create_plugin_host();
+    load_plugin(&mut host, &library).expect("fixture plugin loads");
+    assert!(plugins_world_start(&mut host, 7).is_empty());
+    let loaded = drain_plugin_outbox(&mut host);
+    assert_eq!(loaded.blocks.len(), 1);
+    assert_eq!(loaded.blocks[0].name, "fixture_block");
+
+    // The panic stays inside the library and comes back as an error code;
+    // the event queued before it is dropped
+    let failures = plugins_tick(&mut host, 1, 0.05);
+    assert!(matches!(
+        &failures[..],
+        [PluginError::HookFailed { hook, .. }] if hook == "on_tick"
+    ));
+    assert!(matches!(
+        find_plugin(&host, "panicking_fixture").map(|plugin| &plugin.state),
+        Some(PluginState::Failed(_))
+    ));
+    assert!(drain_plugin_outbox(&mut host).events.is_empty());
+
+    // A disabled plugin is not called again
+    assert!(plugins_tick(&mut host, 2, 0.05).is_empty());
+    assert!(unload_plugins(&mut host).is_empty());
+}
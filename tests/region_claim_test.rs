@@ -0,0 +1,162 @@
+//! Player block edits queued on the gateway are checked against the region
+//! claims and action cooldowns and applied by the engine frame. Kept apart
+//! from the other engine tests because the gateway is global.
+
+use hearth_engine::camera::{calculate_forward_vector_from_camera, init_camera_with_spawn};
+use hearth_engine::game::{
+    init_gateway, place_oriented_block_dop, query_action_cooldown, queue_event,
+    queue_limited_event, run_gateway_claim_command, with_gateway_action_limits,
+    with_gateway_claims, BlockAction, GameEvent,
+};
+use hearth_engine::world::blocks::{
+    compute_placement_metadata, create_metadata_schema_registry, register_block_orientation,
+    OrientationMode,
+};
+use hearth_engine::world::core::{BlockFace, BlockId, RaycastHit, VoxelPos};
+use hearth_engine::world::generation::{default_superflat_config, WorldPreset};
+use hearth_engine::world::world_operations::{get_block, get_block_metadata};
+use hearth_engine::{Engine, EngineConfig, WorldGeneratorType};
+use std::time::Duration;
+
+mod common;
+
+fn player_break(player_id: u32, position: VoxelPos) -> GameEvent {
+    GameEvent::BlockBreak {
+        position,
+        block_id: BlockId::AIR,
+        player_id: Some(player_id),
+    }
+}
+
+fn player_place(player_id: u32, position: VoxelPos) -> GameEvent {
+    GameEvent::BlockPlace {
+        position,
+        block_id: BlockId::GLASS,
+        metadata: 0,
+        player_id: Some(player_id),
+    }
+}
+
+#[test]
+fn test_engine_applies_only_the_edits_claims_and_cooldowns_allow() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping region claim test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        world_generator_type: WorldGeneratorType::Preset(WorldPreset::Superflat(
+            default_superflat_config(),
+        )),
+        ..EngineConfig::default()
+    };
+    let chunk_size = config.chunk_size;
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    let ground = VoxelPos::new(2, 44, 2);
+    for _ in 0..100 {
+        engine.frame(&[]);
+        if get_block(engine.world(), ground, chunk_size) != BlockId::AIR {
+            break;
+        }
+    }
+    let before = get_block(engine.world(), ground, chunk_size);
+    assert_ne!(before, BlockId::AIR);
+
+    init_gateway();
+    let created = run_gateway_claim_command(None, "claim create spawn 0 40 0 8 48 8 player:1");
+    assert!(matches!(created, Some(Ok(_))), "{:?}", created);
+
+    // A non-member's break is refused and leaves the world as it was
+    queue_event(player_break(2, ground));
+    engine.frame(&[]);
+    assert_eq!(get_block(engine.world(), ground, chunk_size), before);
+    let denied = with_gateway_claims(|claims| claims.stats.denied[BlockAction::Break as usize]);
+    assert_eq!(denied, Some(1));
+
+    // The owner's goes through
+    queue_event(player_break(1, ground));
+    engine.frame(&[]);
+    assert_eq!(get_block(engine.world(), ground, chunk_size), BlockId::AIR);
+
+    // Outside any claim anyone may place, as fast as the cooldown allows:
+    // the burst goes through, the next one waits
+    let row: Vec<VoxelPos> = (0..3).map(|x| VoxelPos::new(20 + x, 45, 20)).collect();
+    let last = get_block(engine.world(), row[2], chunk_size);
+    assert_ne!(last, BlockId::GLASS);
+    assert!(queue_limited_event(player_place(2, row[0])));
+    assert!(queue_limited_event(player_place(2, row[1])));
+    assert!(!queue_limited_event(player_place(2, row[2])));
+    assert!(query_action_cooldown(2, BlockAction::Place) > 0);
+    engine.frame(&[]);
+    assert_eq!(
+        get_block(engine.world(), row[0], chunk_size),
+        BlockId::GLASS
+    );
+    assert_eq!(
+        get_block(engine.world(), row[1], chunk_size),
+        BlockId::GLASS
+    );
+    assert_eq!(get_block(engine.world(), row[2], chunk_size), last);
+    let rejected =
+        with_gateway_action_limits(|limits| limits.stats.rejected[BlockAction::Place as usize]);
+    assert_eq!(rejected, Some(1));
+
+    // The gateway clock runs with the frames
+    for _ in 0..2 {
+        std::thread::sleep(Duration::from_millis(150));
+        engine.frame(&[]);
+    }
+    assert_eq!(query_action_cooldown(2, BlockAction::Place), 0);
+    queue_event(player_place(2, row[2]));
+    engine.frame(&[]);
+    assert_eq!(
+        get_block(engine.world(), row[2], chunk_size),
+        BlockId::GLASS
+    );
+
+    // Oriented placement goes through the same checks, its facing in the
+    // queued edit's metadata
+    let mut schemas = create_metadata_schema_registry();
+    register_block_orientation(
+        &mut schemas,
+        BlockId::COBBLESTONE,
+        OrientationMode::Horizontal,
+    )
+    .expect("orientation");
+    // Back into the hole the owner broke
+    let camera = init_camera_with_spawn(cgmath::Point3::new(2.5, 50.0, -1.5));
+    let hit = RaycastHit {
+        position: VoxelPos::new(ground.x, ground.y - 1, ground.z),
+        face: BlockFace::Top,
+        distance: 6.0,
+        block: before,
+    };
+    let target = ground;
+    let place = |engine: &Engine, player_id| {
+        place_oriented_block_dop(
+            engine.world(),
+            &schemas,
+            &camera,
+            &hit,
+            BlockId::COBBLESTONE,
+            player_id,
+            chunk_size,
+        )
+    };
+    assert!(!place(&engine, 2));
+    engine.frame(&[]);
+    assert_eq!(get_block(engine.world(), target, chunk_size), BlockId::AIR);
+    assert!(place(&engine, 1));
+    assert_eq!(get_block(engine.world(), target, chunk_size), BlockId::AIR);
+    engine.frame(&[]);
+    assert_eq!(
+        get_block(engine.world(), target, chunk_size),
+        BlockId::COBBLESTONE
+    );
+    let forward = calculate_forward_vector_from_camera(&camera);
+    assert_eq!(
+        get_block_metadata(engine.world(), target, chunk_size),
+        compute_placement_metadata(&schemas, BlockId::COBBLESTONE, forward, BlockFace::Top)
+    );
+}
@@ -0,0 +1,81 @@
+//! Render scale: below native resolution the scene is drawn into a smaller
+//! offscreen texture in every anti-aliasing mode and upscaled into the
+//! target, which ends up showing the same sky
+
+use cgmath::Point3;
+use hearth_engine::camera::init_camera;
+use hearth_engine::renderer::{
+    attach_renderer_to_texture, default_sky_config, enable_renderer_sky, render_embedded_frame,
+    set_renderer_anti_aliasing, set_renderer_render_scale, update_renderer_sky, AntiAliasingMode,
+    RenderTarget,
+};
+use hearth_engine::world::lighting::noon_time;
+use hearth_engine::world::WeatherData;
+use std::sync::Arc;
+
+mod common;
+
+#[test]
+fn test_reduced_render_scale_upscales_in_every_mode() {
+    let instance = wgpu::Instance::default();
+    let Some(adapter) = pollster::block_on(instance.request_adapter(&Default::default())) else {
+        eprintln!("No GPU adapter, skipping render scale test");
+        return;
+    };
+    let (device, queue) = pollster::block_on(adapter.request_device(&Default::default(), None))
+        .expect("Failed to create device");
+    let device = Arc::new(device);
+    let queue = Arc::new(queue);
+
+    let mut renderer = attach_renderer_to_texture(
+        common::render_texture(&device),
+        device.clone(),
+        queue.clone(),
+    )
+    .expect("attach");
+    enable_renderer_sky(&mut renderer, default_sky_config()).expect("sky");
+    let camera = init_camera(Point3::new(0.0, 80.0, 0.0), 0.0, 0.0);
+    update_renderer_sky(&mut renderer, &noon_time(), &WeatherData::clear(), &camera);
+    let target_pixel = |renderer: &hearth_engine::renderer::Renderer| match &renderer.target {
+        RenderTarget::Texture { texture, .. } => common::center_pixel(&device, &queue, texture),
+        _ => unreachable!("attached to a texture"),
+    };
+
+    assert_eq!(render_embedded_frame(&mut renderer), Ok(true));
+    let native = target_pixel(&renderer);
+
+    let modes = [
+        AntiAliasingMode::Off,
+        AntiAliasingMode::Msaa { samples: 4 },
+        AntiAliasingMode::Fxaa,
+        AntiAliasingMode::Taa,
+    ];
+    for mode in modes {
+        set_renderer_anti_aliasing(&mut renderer, mode).expect("anti-aliasing");
+        set_renderer_render_scale(&mut renderer, 0.5);
+        assert_eq!(render_embedded_frame(&mut renderer), Ok(true), "{:?}", mode);
+
+        let targets = renderer.anti_aliasing.targets.as_ref().expect("targets");
+        assert_eq!(
+            (targets.width, targets.height),
+            (common::TARGET_SIZE, common::TARGET_SIZE)
+        );
+        assert_eq!(
+            (targets.scene_width, targets.scene_height),
+            (common::TARGET_SIZE / 2, common::TARGET_SIZE / 2)
+        );
+        assert!(targets.scene.is_some(), "{:?} draws offscreen", mode);
+
+        let scaled = target_pixel(&renderer);
+        for channel in 0..3 {
+            assert!(
+                native[channel].abs_diff(scaled[channel]) <= 8,
+                "{:?}: {:?} vs native {:?}",
+                mode,
+                scaled,
+                native
+            );
+        }
+        set_renderer_render_scale(&mut renderer, 1.0);
+    }
+}
@@ -0,0 +1,52 @@
+//! Seasons counted on the gateway clock reach the engine's world: a new
+//! season sets how fast its crops grow. Kept apart from the other engine
+//! tests because the gateway is global.
+
+use hearth_engine::game::{init_gateway, with_gateway_seasons};
+use hearth_engine::process::ProcessCategory;
+use hearth_engine::world::SEASON_COUNT;
+use hearth_engine::{Engine, EngineConfig};
+
+mod common;
+
+#[test]
+fn test_new_season_sets_the_world_growth_rate() {
+    let Some(renderer) = common::offscreen_renderer() else {
+        eprintln!("No GPU adapter, skipping season engine test");
+        return;
+    };
+    let config = EngineConfig {
+        render_distance: 2,
+        vsync: false,
+        ..EngineConfig::default()
+    };
+    let mut engine = Engine::new_embedded(config, renderer).expect("embedded engine");
+    init_gateway();
+    let config = with_gateway_seasons(|seasons| seasons.config).expect("gateway");
+
+    // Days within the season change nothing
+    assert!(engine.advance_days(1).is_none());
+    assert_eq!(
+        engine
+            .processes_mut()
+            .category_rate(ProcessCategory::Growth),
+        1.0
+    );
+
+    // Every season sets its own rate; at least one of them is not normal
+    let mut rates = Vec::new();
+    for _ in 0..SEASON_COUNT {
+        let change = engine
+            .advance_days(config.days_per_season as u64)
+            .expect("new season");
+        let growth = config.profiles[change.to as usize].growth_rate;
+        assert_eq!(
+            engine
+                .processes_mut()
+                .category_rate(ProcessCategory::Growth),
+            growth
+        );
+        rates.push(growth);
+    }
+    assert!(rates.iter().any(|&rate| rate != 1.0));
+}
@@ -0,0 +1,121 @@
+//! Secondary views: a view renders the sky from its own camera into its own
+//! texture, honours its interval, yields to the per-frame budget and
+//! survives a device loss
+
+use cgmath::Point3;
+use hearth_engine::camera::init_camera;
+use hearth_engine::renderer::{
+    add_secondary_view, attach_renderer_to_texture, default_secondary_view_config,
+    default_sky_config, enable_renderer_sky, poll_device_recovery, render_embedded_frame,
+    secondary_view, secondary_view_texture, set_secondary_view_budget, set_secondary_view_interval,
+    update_renderer_sky, SecondaryViewBudget,
+};
+use hearth_engine::world::lighting::noon_time;
+use hearth_engine::world::WeatherData;
+use std::sync::Arc;
+
+mod common;
+
+#[test]
+fn test_secondary_view_renders_from_its_camera() {
+    let instance = wgpu::Instance::default();
+    let Some(adapter) = pollster::block_on(instance.request_adapter(&Default::default())) else {
+        eprintln!("No GPU adapter, skipping secondary view test");
+        return;
+    };
+    let (device, queue) = pollster::block_on(adapter.request_device(&Default::default(), None))
+        .expect("Failed to create device");
+    let device = Arc::new(device);
+    let queue = Arc::new(queue);
+
+    let mut renderer = attach_renderer_to_texture(
+        common::render_texture(&device),
+        device.clone(),
+        queue.clone(),
+    )
+    .expect("attach");
+    enable_renderer_sky(&mut renderer, default_sky_config()).expect("sky");
+    let main_camera = init_camera(Point3::new(0.0, 80.0, 0.0), 0.0, 0.0);
+    update_renderer_sky(
+        &mut renderer,
+        &noon_time(),
+        &WeatherData::clear(),
+        &main_camera,
+    );
+
+    // Looking straight up sees the zenith, the main camera the horizon
+    let up_camera = init_camera(Point3::new(0.0, 80.0, 0.0), 0.0, 1.5);
+    let up = add_secondary_view(
+        &mut renderer,
+        up_camera,
+        default_secondary_view_config(common::TARGET_SIZE, common::TARGET_SIZE),
+    )
+    .expect("view");
+    assert!(add_secondary_view(
+        &mut renderer,
+        up_camera,
+        default_secondary_view_config(0, 8)
+    )
+    .is_err());
+
+    assert_eq!(render_embedded_frame(&mut renderer), Ok(true));
+    assert_eq!(renderer.secondary_views.stats.rendered_last_frame, 1);
+    assert!(secondary_view_texture(&renderer, up).is_some());
+    let view_pixel = common::center_pixel(
+        &device,
+        &queue,
+        &secondary_view(&renderer, up).expect("view").texture,
+    );
+    let main_texture = match &renderer.target {
+        hearth_engine::renderer::RenderTarget::Texture { texture, .. } => texture,
+        _ => unreachable!("attached to a texture"),
+    };
+    let main_pixel = common::center_pixel(&device, &queue, main_texture);
+    assert!(
+        view_pixel[0] < main_pixel[0],
+        "zenith {:?} should be darker red than horizon {:?}",
+        view_pixel,
+        main_pixel
+    );
+
+    // Every third frame only
+    assert!(set_secondary_view_interval(&mut renderer, up, 3));
+    let mut rendered = 0;
+    for _ in 0..6 {
+        assert_eq!(render_embedded_frame(&mut renderer), Ok(true));
+        rendered += renderer.secondary_views.stats.rendered_last_frame;
+    }
+    assert_eq!(rendered, 2);
+
+    // A second view over the pixel budget waits for a later frame
+    assert!(set_secondary_view_interval(&mut renderer, up, 1));
+    let second = add_secondary_view(
+        &mut renderer,
+        main_camera,
+        default_secondary_view_config(common::TARGET_SIZE, common::TARGET_SIZE),
+    )
+    .expect("view");
+    set_secondary_view_budget(
+        &mut renderer,
+        SecondaryViewBudget {
+            max_views_per_frame: 2,
+            pixel_budget: u64::from(common::TARGET_SIZE * common::TARGET_SIZE),
+            target_frame_ms: 16.7,
+        },
+    );
+    assert_eq!(render_embedded_frame(&mut renderer), Ok(true));
+    let stats = renderer.secondary_views.stats;
+    assert_eq!(
+        (stats.rendered_last_frame, stats.deferred_last_frame),
+        (1, 1)
+    );
+    assert!(secondary_view(&renderer, second).is_some());
+
+    // Views come back on the new device
+    device.destroy();
+    device.poll(wgpu::Maintain::Poll);
+    assert_eq!(poll_device_recovery(&mut renderer, &instance), Ok(true));
+    assert_eq!(render_embedded_frame(&mut renderer), Ok(true));
+    assert_eq!(renderer.secondary_views.stats.rendered_last_frame, 1);
+    assert!(secondary_view(&renderer, up).is_some_and(|view| view.sky.is_some()));
+}
@@ -0,0 +1,247 @@
+//! Sparse tier: uniform chunks are stored as descriptors without a slot,
+//! read back without touching the GPU, and get a full slot on the first
+//! non-uniform write
+
+use hearth_engine::world::compute::{ChunkModifier, ModificationCommand};
+use hearth_engine::world::core::{BlockId, ChunkPos, VoxelPos};
+use hearth_engine::world::storage::{
+    create_shadow_cache, detect_uniform_chunk, request_shadow_readbacks, shadow_get_block,
+    ShadowLookup, VoxelData, WorldBuffer, WorldBufferDescriptor,
+};
+use std::sync::{Arc, Mutex};
+
+mod common;
+
+fn create_world_buffer(device: &Arc<wgpu::Device>, enable_sparse_chunks: bool) -> WorldBuffer {
+    WorldBuffer::new(
+        device.clone(),
+        &WorldBufferDescriptor {
+            view_distance: 1,
+            enable_readback: true,
+            enable_sparse_chunks,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn test_detect_uniform_chunk() {
+    let stone = VoxelData::new(1, 0, 15, 0);
+    assert_eq!(detect_uniform_chunk(&[]), None);
+    assert_eq!(detect_uniform_chunk(&[stone; 64]), Some(stone));
+    assert_eq!(
+        detect_uniform_chunk(&[VoxelData::AIR; 64]),
+        Some(VoxelData::AIR)
+    );
+
+    // Any difference disqualifies the chunk, including light and metadata
+    let mut voxels = vec![stone; 64];
+    voxels[63] = VoxelData::new(1, 0, 14, 0);
+    assert_eq!(detect_uniform_chunk(&voxels), None);
+    voxels[63] = VoxelData::new(1, 0, 15, 2);
+    assert_eq!(detect_uniform_chunk(&voxels), None);
+    voxels[63] = VoxelData::new(2, 0, 15, 0);
+    assert_eq!(detect_uniform_chunk(&voxels), None);
+}
+
+#[test]
+fn test_store_sparse_chunk_and_stats() {
+    let Some((device, queue)) = common::create_world_buffer_device("Sparse Chunk Test Device")
+    else {
+        eprintln!("No suitable GPU adapter, skipping sparse chunk test");
+        return;
+    };
+    let mut world_buffer = create_world_buffer(&device, true);
+    let empty = world_buffer.sparse_storage_stats();
+    assert_eq!(
+        (
+            empty.resident_chunks,
+            empty.sparse_chunks,
+            empty.bytes_saved
+        ),
+        (0, 0, 0)
+    );
+
+    // Storing a chunk that had a slot releases the slot
+    let dense = ChunkPos::new(1, 0, 0);
+    world_buffer.get_chunk_slot(dense);
+    assert_eq!(world_buffer.sparse_storage_stats().resident_chunks, 1);
+    let stone = VoxelData::new(1, 0, 15, 0);
+    assert!(world_buffer.store_sparse_chunk(dense, stone));
+    assert_eq!(world_buffer.existing_chunk_slot(dense), None);
+    assert_eq!(
+        world_buffer.sparse_chunk(dense).map(|chunk| chunk.voxel),
+        Some(stone)
+    );
+
+    let air = ChunkPos::new(0, 5, 0);
+    assert!(world_buffer.store_sparse_chunk(air, VoxelData::AIR));
+    let stats = world_buffer.sparse_storage_stats();
+    assert_eq!(stats.resident_chunks, 0);
+    assert_eq!(stats.sparse_chunks, 2);
+    let light_slot_size = match world_buffer.light_buffer() {
+        Some(_) => world_buffer.light_slot_offset(1),
+        None => 0,
+    };
+    assert_eq!(
+        stats.bytes_saved,
+        2 * (world_buffer.slot_size() + light_slot_size)
+    );
+
+    // Readback is answered from the descriptor
+    let voxel_count = world_buffer.chunk_layout().voxels_per_chunk as usize;
+    assert_eq!(
+        world_buffer
+            .read_chunk(&device, &queue, dense)
+            .expect("read"),
+        vec![stone; voxel_count]
+    );
+
+    // Requesting a slot drops the descriptor: the caller overwrites the slot
+    world_buffer.get_chunk_slot(air);
+    assert!(!world_buffer.is_chunk_sparse(air));
+    let stats = world_buffer.sparse_storage_stats();
+    assert_eq!((stats.resident_chunks, stats.sparse_chunks), (1, 1));
+
+    assert!(world_buffer.remove_sparse_chunk(dense));
+    assert!(!world_buffer.remove_sparse_chunk(dense));
+
+    // With the tier disabled nothing is stored and uploads take a slot
+    let mut dense_only = create_world_buffer(&device, false);
+    assert!(!dense_only.store_sparse_chunk(air, VoxelData::AIR));
+    dense_only.upload_chunk(&queue, air, &vec![VoxelData::AIR; voxel_count]);
+    assert!(dense_only.existing_chunk_slot(air).is_some());
+    assert_eq!(dense_only.sparse_storage_stats().sparse_chunks, 0);
+}
+
+#[test]
+fn test_first_non_uniform_write_promotes_sparse_chunk() {
+    let Some((device, queue)) = common::create_world_buffer_device("Sparse Chunk Test Device")
+    else {
+        eprintln!("No suitable GPU adapter, skipping sparse chunk test");
+        return;
+    };
+    let mut world_buffer = create_world_buffer(&device, true);
+    let voxel_count = world_buffer.chunk_layout().voxels_per_chunk as usize;
+    let size = world_buffer.chunk_layout().size as i32;
+
+    // Uniform uploads go to the sparse tier
+    let chunk = ChunkPos::new(0, 0, 0);
+    let stone = VoxelData::new(1, 0, 15, 0);
+    world_buffer.upload_chunk(&queue, chunk, &vec![stone; voxel_count]);
+    assert!(world_buffer.is_chunk_sparse(chunk));
+    assert_eq!(world_buffer.existing_chunk_slot(chunk), None);
+
+    let modifier = ChunkModifier::new(device.clone());
+    let mut encoder = device.create_command_encoder(&Default::default());
+    modifier.apply_modifications(
+        &mut encoder,
+        &queue,
+        &world_buffer,
+        &[ModificationCommand::break_block(1, 2, 3)],
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    assert!(!world_buffer.is_chunk_sparse(chunk));
+    assert!(world_buffer.existing_chunk_slot(chunk).is_some());
+    let stats = world_buffer.sparse_storage_stats();
+    assert_eq!((stats.resident_chunks, stats.sparse_chunks), (1, 0));
+
+    // The slot holds the uniform fill plus the edit
+    let voxels = world_buffer
+        .read_chunk(&device, &queue, chunk)
+        .expect("read");
+    let edited = (1 + 2 * size + 3 * size * size) as usize;
+    assert_eq!(voxels[edited].block_id(), 0);
+    assert_eq!(
+        voxels.iter().filter(|voxel| voxel.block_id() == 1).count(),
+        voxel_count - 1
+    );
+
+    // Explosions are split per resident chunk; the centre voxel always goes
+    let neighbour = ChunkPos::new(1, 0, 0);
+    world_buffer.upload_chunk(&queue, neighbour, &vec![stone; voxel_count]);
+    let mut encoder = device.create_command_encoder(&Default::default());
+    modifier.apply_modifications(
+        &mut encoder,
+        &queue,
+        &world_buffer,
+        &[ModificationCommand::explode(size - 1, 5, 5, 3.0)],
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+    let voxels = world_buffer
+        .read_chunk(&device, &queue, chunk)
+        .expect("read");
+    let centre = (size - 1 + 5 * size + 5 * size * size) as usize;
+    assert_eq!(voxels[centre].block_id(), 0);
+    assert!(!world_buffer.is_chunk_sparse(neighbour));
+    let neighbour_voxels = world_buffer
+        .read_chunk(&device, &queue, neighbour)
+        .expect("read");
+    assert!(neighbour_voxels.iter().any(|voxel| voxel.block_id() == 0));
+
+    // Chunks outside the commands stay sparse
+    let untouched = ChunkPos::new(0, 1, 0);
+    world_buffer.upload_chunk(&queue, untouched, &vec![VoxelData::AIR; voxel_count]);
+    let mut encoder = device.create_command_encoder(&Default::default());
+    modifier.apply_modifications(
+        &mut encoder,
+        &queue,
+        &world_buffer,
+        &[ModificationCommand::set_block(0, 0, 0, 2)],
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+    assert!(world_buffer.is_chunk_sparse(untouched));
+}
+
+#[test]
+fn test_shadow_cache_follows_sparse_chunks_and_modifier_edits() {
+    let Some((device, queue)) = common::create_world_buffer_device("Sparse Chunk Test Device")
+    else {
+        eprintln!("No suitable GPU adapter, skipping sparse chunk test");
+        return;
+    };
+    let mut world_buffer = create_world_buffer(&device, true);
+    let layout = world_buffer.chunk_layout();
+    let voxel_count = layout.voxels_per_chunk as usize;
+    let shadow = Arc::new(Mutex::new(create_shadow_cache(4, layout)));
+
+    let chunk = ChunkPos::new(0, 0, 0);
+    let stone = VoxelData::new(1, 0, 15, 0);
+    world_buffer.upload_chunk(&queue, chunk, &vec![stone; voxel_count]);
+    let unloaded = VoxelPos::new(0, layout.size as i32, 0);
+    {
+        let mut cache = shadow.lock().expect("shadow cache");
+        assert_eq!(
+            shadow_get_block(&mut cache, VoxelPos::new(1, 2, 3)),
+            ShadowLookup::Miss
+        );
+        assert_eq!(shadow_get_block(&mut cache, unloaded), ShadowLookup::Miss);
+        request_shadow_readbacks(&mut cache, &world_buffer, &device, &queue, 8);
+
+        // The sparse chunk resolves from its descriptor, not as air
+        assert_eq!(
+            shadow_get_block(&mut cache, VoxelPos::new(1, 2, 3)),
+            ShadowLookup::Hit(BlockId(1))
+        );
+        // A chunk that was never uploaded waits for its upload
+        assert!(cache.pending.contains(&unloaded.to_chunk_pos(layout.size)));
+        assert_eq!(shadow_get_block(&mut cache, unloaded), ShadowLookup::Miss);
+    }
+
+    let modifier = ChunkModifier::new(device.clone()).with_shadow_cache(shadow.clone());
+    let mut encoder = device.create_command_encoder(&Default::default());
+    modifier.apply_modifications(
+        &mut encoder,
+        &queue,
+        &world_buffer,
+        &[ModificationCommand::break_block(1, 2, 3)],
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let mut cache = shadow.lock().expect("shadow cache");
+    assert_eq!(
+        shadow_get_block(&mut cache, VoxelPos::new(1, 2, 3)),
+        ShadowLookup::Hit(BlockId::AIR)
+    );
+}
@@ -0,0 +1,95 @@
+//! Palette voxel format: chunks with few distinct voxels go to the palette
+//! tier, overflow falls back to 32-bit slots, and both read back unchanged
+//! (palette chunks also after the GPU decode into a full slot)
+
+use hearth_engine::world::core::ChunkPos;
+use hearth_engine::world::storage::{
+    VoxelData, VoxelStorageFormat, WorldBuffer, WorldBufferDescriptor,
+};
+
+mod common;
+
+fn patterned_chunk(voxel_count: usize, distinct: usize) -> Vec<VoxelData> {
+    (0..voxel_count)
+        .map(|i| VoxelData::new((i % distinct) as u16 + 1, 0, 15, 0))
+        .collect()
+}
+
+#[test]
+fn test_palette_tier_round_trip_and_fallback() {
+    let Some((device, queue)) = common::create_world_buffer_device("Voxel Palette Test Device")
+    else {
+        eprintln!("No suitable GPU adapter, skipping voxel palette test");
+        return;
+    };
+
+    let mut world_buffer = WorldBuffer::new(
+        device.clone(),
+        &WorldBufferDescriptor {
+            view_distance: 1,
+            enable_readback: true,
+            voxel_format: VoxelStorageFormat::Palette8,
+            ..Default::default()
+        },
+    );
+    let voxel_count = world_buffer.chunk_layout().voxels_per_chunk as usize;
+
+    let small = ChunkPos::new(0, 0, 0);
+    let small_voxels = patterned_chunk(voxel_count, 5);
+    world_buffer.upload_chunk(&queue, small, &small_voxels);
+    assert!(world_buffer.is_chunk_palettized(small));
+    assert_eq!(world_buffer.existing_chunk_slot(small), None);
+
+    let noisy = ChunkPos::new(1, 0, 0);
+    let noisy_voxels = patterned_chunk(voxel_count, 300);
+    world_buffer.upload_chunk(&queue, noisy, &noisy_voxels);
+    assert!(!world_buffer.is_chunk_palettized(noisy));
+    assert!(world_buffer.existing_chunk_slot(noisy).is_some());
+
+    let queued = ChunkPos::new(0, 1, 0);
+    let queued_voxels = patterned_chunk(voxel_count, 200);
+    world_buffer.queue_chunk_upload(queued, &queued_voxels);
+    let mut encoder = device.create_command_encoder(&Default::default());
+    assert!(world_buffer
+        .flush_chunk_uploads(&queue, &mut encoder)
+        .contains(&queued));
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let stats = world_buffer.palette_storage_stats();
+    assert_eq!(stats.palette_chunks, 2);
+    assert_eq!(stats.fallback_uploads, 1);
+    assert!(stats.bytes_saved > 0);
+
+    assert_eq!(
+        world_buffer
+            .read_chunk(&device, &queue, small)
+            .expect("read"),
+        small_voxels
+    );
+    assert_eq!(
+        world_buffer
+            .read_chunk(&device, &queue, noisy)
+            .expect("read"),
+        noisy_voxels
+    );
+    assert_eq!(
+        world_buffer
+            .read_chunk(&device, &queue, queued)
+            .expect("read"),
+        queued_voxels
+    );
+
+    // Edits need a full slot; the decode pass expands the palette chunk
+    let mut encoder = device.create_command_encoder(&Default::default());
+    let promoted = world_buffer.promote_palette_chunks(&queue, &mut encoder, &[small, noisy]);
+    queue.submit(std::iter::once(encoder.finish()));
+    assert_eq!(promoted, vec![small]);
+    assert!(!world_buffer.is_chunk_palettized(small));
+    assert!(world_buffer.existing_chunk_slot(small).is_some());
+    assert_eq!(
+        world_buffer
+            .read_chunk(&device, &queue, small)
+            .expect("read"),
+        small_voxels
+    );
+}
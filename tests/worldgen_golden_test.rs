@@ -0,0 +1,29 @@
+//! Regenerate the world generation fixtures and diff them against the
+//! golden data in tests/golden/worldgen.
+//!
+//! After an intentional terrain change, rerun with HEARTH_BLESS_GOLDEN=1 to
+//! rewrite the golden files, or HEARTH_GOLDEN_TOLERANCE=<voxels> to accept
+//! a small amount of drift per fixture.
+
+use hearth_engine::world::generation::{
+    default_golden_fixtures, format_golden_report, generate_reference_chunk,
+    golden_config_from_env, golden_report_passed, verify_golden_fixtures,
+};
+use std::path::Path;
+
+#[test]
+fn test_reference_terrain_matches_golden_data() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/worldgen");
+    let config = golden_config_from_env();
+
+    let report = verify_golden_fixtures(&dir, &default_golden_fixtures(), &config, |fixture| {
+        generate_reference_chunk(fixture.chunk_pos, fixture.chunk_size)
+    })
+    .expect("golden data should be readable");
+
+    assert!(
+        golden_report_passed(&report),
+        "world generation drifted from the golden data:\n{}",
+        format_golden_report(&report)
+    );
+}